@@ -49,6 +49,7 @@ import com.zeroclaw.android.model.RefreshCommand
 import com.zeroclaw.android.service.CostBridge
 import com.zeroclaw.android.service.CronBridge
 import com.zeroclaw.android.service.DaemonServiceBridge
+import com.zeroclaw.android.service.DeviceContextBridge
 import com.zeroclaw.android.service.EventBridge
 import com.zeroclaw.android.service.HealthBridge
 import com.zeroclaw.android.service.MemoryBridge
@@ -151,6 +152,10 @@ class ZeroClawApplication :
     lateinit var eventBridge: EventBridge
         private set
 
+    /** Provider of notifications and the foreground app for the agent's device tools. */
+    lateinit var deviceContextBridge: DeviceContextBridge
+        private set
+
     /** Bridge for cron job CRUD FFI calls. */
     lateinit var cronBridge: CronBridge
         private set
@@ -240,6 +245,8 @@ class ZeroClawApplication :
         memoryBridge = MemoryBridge()
         eventBridge = EventBridge(activityRepository, ioScope)
         daemonBridge.eventBridge = eventBridge
+        deviceContextBridge = DeviceContextBridge(this)
+        daemonBridge.deviceContextBridge = deviceContextBridge
 
         sessionLockManager = SessionLockManager(settingsRepository.settings, ioScope)
         ProcessLifecycleOwner.get().lifecycle.addObserver(sessionLockManager)
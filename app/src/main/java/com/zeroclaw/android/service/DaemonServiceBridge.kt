@@ -66,6 +66,15 @@ class DaemonServiceBridge(
      */
     var eventBridge: EventBridge? = null
 
+    /**
+     * Optional [DeviceContextBridge] exposing notifications and the foreground
+     * app to the agent.
+     *
+     * Set from [ZeroClawApplication.onCreate] alongside [eventBridge] and
+     * registered and unregistered with it.
+     */
+    var deviceContextBridge: DeviceContextBridge? = null
+
     init {
         require(dataDir.isNotEmpty()) { "dataDir must not be empty" }
     }
@@ -255,6 +264,7 @@ class DaemonServiceBridge(
             _serviceState.value = ServiceState.RUNNING
             _restartRequired.value = false
             eventBridge?.register()
+            deviceContextBridge?.register()
         } catch (e: FfiException) {
             if (isDaemonAlreadyRunning(e)) {
                 Log.i(TAG, "Daemon already running, syncing state to RUNNING")
@@ -262,6 +272,7 @@ class DaemonServiceBridge(
                 _serviceState.value = ServiceState.RUNNING
                 _restartRequired.value = false
                 eventBridge?.register()
+                deviceContextBridge?.register()
                 return
             }
             _lastError.value = e.errorDetail()
@@ -320,6 +331,7 @@ class DaemonServiceBridge(
     suspend fun stop() {
         _serviceState.value = ServiceState.STOPPING
         eventBridge?.unregister()
+        deviceContextBridge?.unregister()
         try {
             withContext(ioDispatcher) { stopDaemon() }
             _lastError.value = null
@@ -0,0 +1,133 @@
+/*
+ * Copyright 2026 PhoneClaw Community
+ *
+ * Licensed under the MIT License. See LICENSE in the project root.
+ */
+
+package com.zeroclaw.android.service
+
+import android.app.Notification
+import android.app.usage.UsageEvents
+import android.app.usage.UsageStatsManager
+import android.content.Context
+import android.content.pm.PackageManager
+import android.service.notification.StatusBarNotification
+import android.util.Log
+import com.zeroclaw.ffi.FfiDeviceContext
+import com.zeroclaw.ffi.FfiForegroundApp
+import com.zeroclaw.ffi.FfiNotification
+
+/**
+ * Device context provider for the agent's Android tools.
+ *
+ * Implements [FfiDeviceContext] so it can be registered with the native daemon
+ * via [com.zeroclaw.ffi.registerDeviceContext]. Notifications come from
+ * [DeviceNotificationListener] and the foreground app from [UsageStatsManager];
+ * each returns nothing until the user grants notification or usage access.
+ *
+ * Callbacks run on a native background thread while a tool call executes,
+ * so they only read system state and never block on the main thread.
+ *
+ * @param context Application context for system services and app labels.
+ * @param notifications Source of the posted notifications; defaults to the
+ *   bound [DeviceNotificationListener].
+ */
+class DeviceContextBridge(
+    private val context: Context,
+    private val notifications: () -> List<StatusBarNotification> =
+        DeviceNotificationListener::activeSnapshot,
+) : FfiDeviceContext {
+    /**
+     * Called by the native layer for the `android_notifications` tool.
+     *
+     * @return Posted notifications, newest first; empty without notification access.
+     */
+    override fun listNotifications(): List<FfiNotification> =
+        notifications()
+            .sortedByDescending { it.postTime }
+            .map { sbn ->
+                val extras = sbn.notification.extras
+                FfiNotification(
+                    packageName = sbn.packageName,
+                    appLabel = appLabel(sbn.packageName),
+                    title = extras?.getCharSequence(Notification.EXTRA_TITLE)?.toString().orEmpty(),
+                    text = extras?.getCharSequence(Notification.EXTRA_TEXT)?.toString().orEmpty(),
+                    postedAtMs = sbn.postTime,
+                )
+            }
+
+    /**
+     * Called by the native layer for the `android_foreground_app` tool.
+     *
+     * Uses the most recent move-to-foreground event of the last
+     * [FOREGROUND_LOOKBACK_MS] milliseconds.
+     *
+     * @return The foreground app, or `null` without usage access or recent activity.
+     */
+    @Suppress("TooGenericExceptionCaught")
+    override fun currentApp(): FfiForegroundApp? {
+        val usageStats =
+            context.getSystemService(Context.USAGE_STATS_SERVICE) as? UsageStatsManager
+                ?: return null
+        val now = System.currentTimeMillis()
+        val events =
+            try {
+                usageStats.queryEvents(now - FOREGROUND_LOOKBACK_MS, now)
+            } catch (e: Exception) {
+                Log.w(TAG, "Failed to query usage events: ${e.message}")
+                return null
+            } ?: return null
+        val event = UsageEvents.Event()
+        var latest: FfiForegroundApp? = null
+        while (events.hasNextEvent()) {
+            events.getNextEvent(event)
+            @Suppress("DEPRECATION")
+            if (event.eventType == UsageEvents.Event.MOVE_TO_FOREGROUND) {
+                latest =
+                    FfiForegroundApp(
+                        packageName = event.packageName,
+                        appLabel = appLabel(event.packageName),
+                        activity = event.className.orEmpty(),
+                    )
+            }
+        }
+        return latest
+    }
+
+    /**
+     * Registers this bridge as the device context provider with the native daemon.
+     *
+     * Only one provider may be registered at a time; calling this replaces any
+     * previously registered provider.
+     */
+    fun register() {
+        com.zeroclaw.ffi.registerDeviceContext(this)
+    }
+
+    /**
+     * Removes the device context provider from the native daemon.
+     *
+     * Device tools in running sessions report the context as unavailable afterwards.
+     */
+    fun unregister() {
+        com.zeroclaw.ffi.unregisterDeviceContext()
+    }
+
+    private fun appLabel(packageName: String): String =
+        try {
+            val info = context.packageManager.getApplicationInfo(packageName, 0)
+            context.packageManager.getApplicationLabel(info).toString()
+        } catch (
+            @Suppress("SwallowedException") e: PackageManager.NameNotFoundException,
+        ) {
+            ""
+        }
+
+    /** Constants for [DeviceContextBridge]. */
+    companion object {
+        private const val TAG = "DeviceContextBridge"
+
+        /** How far back usage events are searched for the foreground app. */
+        private const val FOREGROUND_LOOKBACK_MS = 10 * 60 * 1000L
+    }
+}
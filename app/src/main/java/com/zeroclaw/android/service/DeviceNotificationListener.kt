@@ -0,0 +1,61 @@
+/*
+ * Copyright 2026 PhoneClaw Community
+ *
+ * Licensed under the MIT License. See LICENSE in the project root.
+ */
+
+package com.zeroclaw.android.service
+
+import android.service.notification.NotificationListenerService
+import android.service.notification.StatusBarNotification
+import android.util.Log
+
+/**
+ * Notification listener backing the agent's `android_notifications` tool.
+ *
+ * The system binds this service once the user grants notification access
+ * in Settings. While bound, [DeviceContextBridge] reads the posted
+ * notifications through [activeSnapshot]; when access is revoked the
+ * service is unbound and the snapshot becomes empty.
+ */
+class DeviceNotificationListener : NotificationListenerService() {
+    override fun onListenerConnected() {
+        connected = this
+    }
+
+    override fun onListenerDisconnected() {
+        if (connected === this) connected = null
+    }
+
+    override fun onDestroy() {
+        if (connected === this) connected = null
+        super.onDestroy()
+    }
+
+    /** Accessors for the currently bound listener. */
+    companion object {
+        private const val TAG = "DeviceNotifListener"
+
+        @Volatile
+        private var connected: DeviceNotificationListener? = null
+
+        /**
+         * Returns the notifications currently posted, or an empty list when
+         * notification access has not been granted.
+         *
+         * Safe to call from any thread.
+         *
+         * @return Posted notifications in the order the system reports them.
+         */
+        @Suppress("TooGenericExceptionCaught")
+        fun activeSnapshot(): List<StatusBarNotification> {
+            val listener = connected ?: return emptyList()
+            return try {
+                listener.activeNotifications?.toList().orEmpty()
+            } catch (e: Exception) {
+                Log.w(TAG, "Failed to read active notifications: ${e.message}")
+                emptyList()
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+/*
+ * Copyright 2026 PhoneClaw Community
+ *
+ * Licensed under the MIT License. See LICENSE in the project root.
+ */
+
+package com.zeroclaw.android.service
+
+import android.app.Notification
+import android.content.Context
+import android.content.pm.PackageManager
+import android.service.notification.StatusBarNotification
+import io.mockk.every
+import io.mockk.mockk
+import io.mockk.mockkStatic
+import io.mockk.unmockkAll
+import io.mockk.verify
+import org.junit.jupiter.api.AfterEach
+import org.junit.jupiter.api.Assertions.assertEquals
+import org.junit.jupiter.api.Assertions.assertNull
+import org.junit.jupiter.api.Assertions.assertTrue
+import org.junit.jupiter.api.BeforeEach
+import org.junit.jupiter.api.DisplayName
+import org.junit.jupiter.api.Test
+
+/**
+ * Unit tests for [DeviceContextBridge].
+ *
+ * Mocks the static UniFFI-generated functions and the Android system
+ * services so that tests run without the native library or a device.
+ */
+@DisplayName("DeviceContextBridge")
+class DeviceContextBridgeTest {
+    private lateinit var context: Context
+
+    /** Mocks FFI statics and a context without usage access or app labels. */
+    @BeforeEach
+    fun setUp() {
+        mockkStatic("com.zeroclaw.ffi.Zeroclaw_androidKt")
+        val packageManager = mockk<PackageManager>()
+        every { packageManager.getApplicationInfo(any<String>(), any<Int>()) } throws
+            PackageManager.NameNotFoundException()
+        context = mockk()
+        every { context.packageManager } returns packageManager
+        every { context.getSystemService(Context.USAGE_STATS_SERVICE) } returns null
+    }
+
+    /** Tears down all mocks after each test. */
+    @AfterEach
+    fun tearDown() {
+        unmockkAll()
+    }
+
+    private fun posted(
+        packageName: String,
+        postTime: Long,
+    ): StatusBarNotification =
+        mockk {
+            every { this@mockk.packageName } returns packageName
+            every { this@mockk.postTime } returns postTime
+            every { notification } returns Notification()
+        }
+
+    @Test
+    @DisplayName("listNotifications returns posted notifications newest first")
+    fun `listNotifications returns posted notifications newest first`() {
+        val bridge =
+            DeviceContextBridge(context) {
+                listOf(posted("com.example.mail", 1_000L), posted("com.whatsapp", 2_000L))
+            }
+
+        val notifications = bridge.listNotifications()
+
+        assertEquals(listOf("com.whatsapp", "com.example.mail"), notifications.map { it.packageName })
+        assertEquals(2_000L, notifications.first().postedAtMs)
+        assertEquals("", notifications.first().appLabel)
+    }
+
+    @Test
+    @DisplayName("listNotifications is empty without notification access")
+    fun `listNotifications is empty without notification access`() {
+        val bridge = DeviceContextBridge(context) { emptyList() }
+
+        assertTrue(bridge.listNotifications().isEmpty())
+    }
+
+    @Test
+    @DisplayName("currentApp is null without usage access")
+    fun `currentApp is null without usage access`() {
+        val bridge = DeviceContextBridge(context) { emptyList() }
+
+        assertNull(bridge.currentApp())
+    }
+
+    @Test
+    @DisplayName("register calls FFI registerDeviceContext")
+    fun `register calls FFI registerDeviceContext`() {
+        val bridge = DeviceContextBridge(context) { emptyList() }
+        every { com.zeroclaw.ffi.registerDeviceContext(any()) } returns Unit
+
+        bridge.register()
+
+        verify { com.zeroclaw.ffi.registerDeviceContext(bridge) }
+    }
+
+    @Test
+    @DisplayName("unregister calls FFI unregisterDeviceContext")
+    fun `unregister calls FFI unregisterDeviceContext`() {
+        val bridge = DeviceContextBridge(context) { emptyList() }
+        every { com.zeroclaw.ffi.unregisterDeviceContext() } returns Unit
+
+        bridge.unregister()
+
+        verify { com.zeroclaw.ffi.unregisterDeviceContext() }
+    }
+}
@@ -0,0 +1,430 @@
+/*
+ * Copyright 2026 ZeroClaw Community
+ *
+ * Licensed under the MIT License. See LICENSE in the project root.
+ */
+
+//! Device context bridge exposing notifications and the foreground app.
+//!
+//! Kotlin registers an [`FfiDeviceContext`] implementation backed by the
+//! app's `NotificationListenerService` and `UsageStatsManager`. While a
+//! provider is registered, new agent sessions include
+//! [`AndroidNotificationsTool`] and [`AndroidForegroundTool`] so the agent
+//! can answer questions like "what did I just get a message about?".
+//!
+//! Both callbacks are read-only. The tools look up the provider on every
+//! call, so unregistering it (e.g. when the user revokes notification
+//! access) takes effect without restarting the session.
+
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use async_trait::async_trait;
+use zeroclaw::tools::{Tool, ToolResult};
+
+use crate::error::FfiError;
+
+/// Default number of notifications returned by [`AndroidNotificationsTool`].
+const DEFAULT_NOTIFICATION_LIMIT: usize = 10;
+
+/// Hard cap on notifications returned in a single tool call.
+const MAX_NOTIFICATION_LIMIT: usize = 50;
+
+/// Maximum characters of notification body text included per entry.
+const MAX_NOTIFICATION_TEXT_CHARS: usize = 280;
+
+/// A notification currently shown in the Android status bar.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiNotification {
+    /// Package that posted the notification (e.g. `"com.whatsapp"`).
+    pub package_name: String,
+    /// Human-readable app label, or empty string if unknown.
+    pub app_label: String,
+    /// Notification title (`EXTRA_TITLE`), or empty string.
+    pub title: String,
+    /// Notification body (`EXTRA_TEXT`), or empty string.
+    pub text: String,
+    /// Post time in epoch milliseconds.
+    pub posted_at_ms: i64,
+}
+
+/// The app currently in the foreground.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FfiForegroundApp {
+    /// Package name of the foreground app.
+    pub package_name: String,
+    /// Human-readable app label, or empty string if unknown.
+    pub app_label: String,
+    /// Fully qualified activity class name, or empty string if unknown.
+    pub activity: String,
+}
+
+/// Callback interface that Kotlin implements to expose device context.
+///
+/// Methods are invoked from a Rust background thread while a tool call
+/// is executing, so implementations must be thread-safe and should return
+/// quickly.
+#[uniffi::export(callback_interface)]
+pub trait FfiDeviceContext: Send + Sync {
+    /// Returns the notifications currently posted, newest first.
+    ///
+    /// Returns an empty list if notification access has not been granted.
+    fn list_notifications(&self) -> Vec<FfiNotification>;
+
+    /// Returns the foreground app, or `None` if usage access has not been
+    /// granted or the foreground app cannot be determined.
+    fn current_app(&self) -> Option<FfiForegroundApp>;
+}
+
+/// Global device context provider slot.
+static PROVIDER: OnceLock<Mutex<Option<Arc<dyn FfiDeviceContext>>>> = OnceLock::new();
+
+/// Returns a reference to the provider mutex, initialising on first access.
+fn provider_slot() -> &'static Mutex<Option<Arc<dyn FfiDeviceContext>>> {
+    PROVIDER.get_or_init(|| Mutex::new(None))
+}
+
+/// Returns a clone of the registered provider, if any.
+///
+/// The [`Arc`] is cloned outside the lock so the foreign callback is never
+/// invoked while the mutex is held.
+fn current_provider() -> Option<Arc<dyn FfiDeviceContext>> {
+    provider_slot()
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(Arc::clone))
+}
+
+/// Returns whether a device context provider is currently registered.
+pub(crate) fn is_registered() -> bool {
+    current_provider().is_some()
+}
+
+/// Registers the Kotlin-side device context provider.
+///
+/// A new provider replaces the previous one. Takes effect for sessions
+/// started after this call.
+pub(crate) fn register_device_context_inner(
+    provider: Arc<dyn FfiDeviceContext>,
+) -> Result<(), FfiError> {
+    let mut slot = provider_slot()
+        .lock()
+        .map_err(|_| FfiError::StateCorrupted {
+            detail: "device context mutex poisoned".into(),
+        })?;
+    *slot = Some(provider);
+    Ok(())
+}
+
+/// Unregisters the current device context provider.
+///
+/// Device tools in running sessions report the context as unavailable.
+pub(crate) fn unregister_device_context_inner() -> Result<(), FfiError> {
+    let mut slot = provider_slot()
+        .lock()
+        .map_err(|_| FfiError::StateCorrupted {
+            detail: "device context mutex poisoned".into(),
+        })?;
+    *slot = None;
+    Ok(())
+}
+
+/// Builds the failure result returned when no provider is registered.
+fn unavailable(what: &str) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(format!(
+            "{what} is unavailable: the app has not granted device context access"
+        )),
+    }
+}
+
+/// Truncates `text` to at most `max_chars` characters, appending an ellipsis.
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}...", &text[..idx]),
+        None => text.to_string(),
+    }
+}
+
+/// Formats a notification as a single line for the LLM.
+fn format_notification(out: &mut String, n: &FfiNotification) {
+    let app = if n.app_label.is_empty() {
+        n.package_name.as_str()
+    } else {
+        n.app_label.as_str()
+    };
+    let posted = chrono::DateTime::from_timestamp_millis(n.posted_at_ms)
+        .map_or_else(|| "unknown time".to_string(), |t| t.to_rfc3339());
+    let _ = write!(out, "- [{posted}] {app} ({})", n.package_name);
+    if !n.title.is_empty() {
+        let _ = write!(out, ": {}", n.title);
+    }
+    if !n.text.is_empty() {
+        let _ = write!(
+            out,
+            " — {}",
+            truncate_chars(&n.text, MAX_NOTIFICATION_TEXT_CHARS)
+        );
+    }
+    out.push('\n');
+}
+
+/// Lists notifications currently shown on the device.
+pub(crate) struct AndroidNotificationsTool;
+
+#[async_trait]
+impl Tool for AndroidNotificationsTool {
+    fn name(&self) -> &'static str {
+        "android_notifications"
+    }
+
+    fn description(&self) -> &'static str {
+        "List notifications currently shown on the user's phone, newest \
+         first. Optionally filter by app package name."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "package": {
+                    "type": "string",
+                    "description": "Only return notifications from this package (e.g. com.whatsapp)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum notifications to return (default: 10, max: 50)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let Some(provider) = current_provider() else {
+            return Ok(unavailable("Notification list"));
+        };
+
+        let package = args
+            .get("package")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|p| !p.is_empty());
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(DEFAULT_NOTIFICATION_LIMIT, |v| v as usize)
+            .clamp(1, MAX_NOTIFICATION_LIMIT);
+
+        let notifications = tokio::task::spawn_blocking(move || provider.list_notifications())
+            .await
+            .map_err(|e| anyhow::anyhow!("Notification callback failed: {e}"))?;
+
+        let matching: Vec<&FfiNotification> = notifications
+            .iter()
+            .filter(|n| package.is_none_or(|p| n.package_name == p))
+            .take(limit)
+            .collect();
+
+        if matching.is_empty() {
+            let output = match package {
+                Some(p) => format!("No notifications from {p}."),
+                None => "No notifications.".to_string(),
+            };
+            return Ok(ToolResult {
+                success: true,
+                output,
+                error: None,
+            });
+        }
+
+        let mut output = format!("{} notifications:\n", matching.len());
+        for n in matching {
+            format_notification(&mut output, n);
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+/// Reports which app is currently in the foreground.
+pub(crate) struct AndroidForegroundTool;
+
+#[async_trait]
+impl Tool for AndroidForegroundTool {
+    fn name(&self) -> &'static str {
+        "android_foreground_app"
+    }
+
+    fn description(&self) -> &'static str {
+        "Return the app the user currently has open on their phone \
+         (package name, label, and activity)."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {}
+        })
+    }
+
+    async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let Some(provider) = current_provider() else {
+            return Ok(unavailable("Foreground app"));
+        };
+
+        let app = tokio::task::spawn_blocking(move || provider.current_app())
+            .await
+            .map_err(|e| anyhow::anyhow!("Foreground app callback failed: {e}"))?;
+
+        let output = match app {
+            Some(app) => {
+                let mut out = if app.app_label.is_empty() {
+                    app.package_name.clone()
+                } else {
+                    format!("{} ({})", app.app_label, app.package_name)
+                };
+                if !app.activity.is_empty() {
+                    let _ = write!(out, ", activity {}", app.activity);
+                }
+                out
+            }
+            None => "The foreground app could not be determined.".to_string(),
+        };
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serialises tests that mutate the process-global provider slot.
+    static SLOT_LOCK: Mutex<()> = Mutex::new(());
+
+    /// A canned device context standing in for the Kotlin bridge.
+    struct MockDeviceContext {
+        /// Notifications returned by `list_notifications`.
+        notifications: Vec<FfiNotification>,
+        /// App returned by `current_app`.
+        app: Option<FfiForegroundApp>,
+    }
+
+    impl FfiDeviceContext for MockDeviceContext {
+        fn list_notifications(&self) -> Vec<FfiNotification> {
+            self.notifications.clone()
+        }
+
+        fn current_app(&self) -> Option<FfiForegroundApp> {
+            self.app.clone()
+        }
+    }
+
+    fn notification(package: &str, title: &str, text: &str) -> FfiNotification {
+        FfiNotification {
+            package_name: package.into(),
+            app_label: String::new(),
+            title: title.into(),
+            text: text.into(),
+            posted_at_ms: 1_767_225_600_000,
+        }
+    }
+
+    fn run<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_tools_report_unavailable_without_provider() {
+        let _guard = SLOT_LOCK.lock().unwrap();
+        unregister_device_context_inner().unwrap();
+        assert!(!is_registered());
+
+        let result = run(AndroidNotificationsTool.execute(serde_json::json!({}))).unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("unavailable"));
+
+        let result = run(AndroidForegroundTool.execute(serde_json::json!({}))).unwrap();
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_notifications_filter_and_limit() {
+        let _guard = SLOT_LOCK.lock().unwrap();
+        register_device_context_inner(Arc::new(MockDeviceContext {
+            notifications: vec![
+                notification("com.whatsapp", "Alice", "Dinner at 8?"),
+                notification("com.google.android.gm", "Invoice", "Your invoice is ready"),
+                notification("com.whatsapp", "Bob", "On my way"),
+            ],
+            app: None,
+        }))
+        .unwrap();
+
+        let result = run(AndroidNotificationsTool.execute(serde_json::json!({
+            "package": "com.whatsapp",
+            "limit": 1
+        })))
+        .unwrap();
+        assert!(result.success);
+        assert!(result.output.starts_with("1 notifications:"));
+        assert!(
+            result
+                .output
+                .contains("com.whatsapp): Alice — Dinner at 8?")
+        );
+        assert!(!result.output.contains("Bob"));
+        assert!(!result.output.contains("Invoice"));
+
+        let result = run(AndroidNotificationsTool.execute(serde_json::json!({
+            "package": "org.example.none"
+        })))
+        .unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "No notifications from org.example.none.");
+
+        unregister_device_context_inner().unwrap();
+    }
+
+    #[test]
+    fn test_foreground_app_formats_label_and_activity() {
+        let _guard = SLOT_LOCK.lock().unwrap();
+        register_device_context_inner(Arc::new(MockDeviceContext {
+            notifications: Vec::new(),
+            app: Some(FfiForegroundApp {
+                package_name: "com.spotify.music".into(),
+                app_label: "Spotify".into(),
+                activity: "com.spotify.music.MainActivity".into(),
+            }),
+        }))
+        .unwrap();
+        assert!(is_registered());
+
+        let result = run(AndroidForegroundTool.execute(serde_json::json!({}))).unwrap();
+        assert!(result.success);
+        assert_eq!(
+            result.output,
+            "Spotify (com.spotify.music), activity com.spotify.music.MainActivity"
+        );
+
+        unregister_device_context_inner().unwrap();
+    }
+
+    #[test]
+    fn test_truncate_chars_is_char_safe() {
+        assert_eq!(truncate_chars("héllo", 2), "hé...");
+        assert_eq!(truncate_chars("short", 10), "short");
+    }
+}
@@ -17,6 +17,7 @@ uniffi::setup_scaffolding!();
 mod auth_profiles;
 mod cost;
 mod cron;
+mod device_context;
 mod error;
 mod estop;
 mod events;
@@ -601,6 +602,49 @@ pub fn unregister_event_listener() -> Result<(), FfiError> {
     })
 }
 
+/// Registers the Kotlin-side device context provider.
+///
+/// While registered, agent sessions started afterwards expose the
+/// `android_notifications` and `android_foreground_app` tools. A new
+/// provider replaces the previous one.
+///
+/// # Errors
+///
+/// Returns [`FfiError::StateCorrupted`] if internal state is poisoned, or
+/// [`FfiError::InternalPanic`] if native code panics.
+#[uniffi::export]
+pub fn register_device_context(
+    provider: Box<dyn device_context::FfiDeviceContext>,
+) -> Result<(), FfiError> {
+    let provider: Arc<dyn device_context::FfiDeviceContext> = Arc::from(provider);
+    catch_unwind(AssertUnwindSafe(|| {
+        device_context::register_device_context_inner(provider)
+    }))
+    .unwrap_or_else(|e| {
+        Err(FfiError::InternalPanic {
+            detail: panic_detail(&e),
+        })
+    })
+}
+
+/// Unregisters the device context provider.
+///
+/// Device tools in running sessions report the context as unavailable
+/// until a provider is registered again.
+///
+/// # Errors
+///
+/// Returns [`FfiError::StateCorrupted`] if internal state is poisoned, or
+/// [`FfiError::InternalPanic`] if native code panics.
+#[uniffi::export]
+pub fn unregister_device_context() -> Result<(), FfiError> {
+    catch_unwind(device_context::unregister_device_context_inner).unwrap_or_else(|e| {
+        Err(FfiError::InternalPanic {
+            detail: panic_detail(&e),
+        })
+    })
+}
+
 /// Returns the most recent events as a JSON array.
 ///
 /// Events are ordered chronologically (oldest first). The `limit`
@@ -38,6 +38,7 @@ use zeroclaw::memory::{Memory, MemoryCategory};
 use zeroclaw::providers::{ChatMessage, ChatRequest, Provider};
 use zeroclaw::tools::{Tool, ToolResult, ToolSpec};
 
+use crate::device_context;
 use crate::error::FfiError;
 use crate::runtime::{clone_daemon_config, clone_daemon_memory};
 use crate::url_helpers;
@@ -774,6 +775,11 @@ fn build_tools_registry(config: &zeroclaw::Config, memory: Arc<dyn Memory>) -> V
         }));
     }
 
+    if device_context::is_registered() {
+        tools.push(Box::new(device_context::AndroidNotificationsTool));
+        tools.push(Box::new(device_context::AndroidForegroundTool));
+    }
+
     if tools.len() > MAX_SESSION_TOOLS {
         tracing::warn!(
             total = tools.len(),
@@ -0,0 +1,185 @@
+//! Source citations for web tool results.
+//!
+//! `web_search_tool` and `web_fetch` register every URL they return with the
+//! current turn (see [`super::turn::cite_source`]) and label it with a short
+//! id, `S1`, `S2`, …, that stays the same for that URL for the rest of the
+//! turn. The tool output tells the model to cite it as `[S1]`. Before the
+//! reply is delivered, the ids that actually appear in it are listed with
+//! their URLs in a trailing "Sources" section, so links survive tool-output
+//! truncation and users can see what came from where.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::sync::OnceLock;
+
+/// One web source seen during a turn.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Source {
+    /// Short id the model cites, e.g. `S1`.
+    pub id: String,
+    pub url: String,
+    /// Page or result title; empty when unknown.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title: String,
+}
+
+/// Turn-scoped id assignment. The same URL always gets the same id.
+#[derive(Debug, Default)]
+pub struct SourceRegistry {
+    sources: Vec<Source>,
+}
+
+impl SourceRegistry {
+    /// Id for `url`, assigning the next one on first sight. A title given
+    /// later fills in one that was unknown.
+    pub fn cite(&mut self, url: &str, title: &str) -> String {
+        let url = url.trim();
+        let title = title.trim();
+        if let Some(source) = self.sources.iter_mut().find(|s| s.url == url) {
+            if source.title.is_empty() {
+                source.title = title.to_string();
+            }
+            return source.id.clone();
+        }
+        let id = format!("S{}", self.sources.len() + 1);
+        self.sources.push(Source {
+            id: id.clone(),
+            url: url.to_string(),
+            title: title.to_string(),
+        });
+        id
+    }
+
+    pub fn sources(&self) -> &[Source] {
+        &self.sources
+    }
+}
+
+/// Instruction appended to tool output next to an id.
+pub fn cite_instruction(id: &str) -> String {
+    format!("cite as [{id}]")
+}
+
+fn citation_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\[(S\d+)\]").unwrap())
+}
+
+/// Sources whose id appears as `[S<n>]` in `text`, in id order.
+pub fn cited_in<'a>(sources: &'a [Source], text: &str) -> Vec<&'a Source> {
+    let cited: HashSet<&str> = citation_pattern()
+        .captures_iter(text)
+        .filter_map(|caps| caps.get(1))
+        .map(|id| id.as_str())
+        .collect();
+    sources
+        .iter()
+        .filter(|source| cited.contains(source.id.as_str()))
+        .collect()
+}
+
+/// `reply` followed by a "Sources" section listing `cited`. With `links`,
+/// entries are Markdown links for the channel formatter to convert;
+/// otherwise titles are followed by the bare URL.
+pub fn append_sources(reply: &str, cited: &[&Source], links: bool) -> String {
+    if cited.is_empty() {
+        return reply.to_string();
+    }
+    let mut out = format!("{}\n\nSources:", reply.trim_end());
+    for source in cited {
+        let title = if source.title.is_empty() {
+            source.url.as_str()
+        } else {
+            source.title.as_str()
+        };
+        // Keep titles from closing the link text early.
+        let title = title.replace(['[', ']'], "");
+        let entry = if links {
+            // Escaped so link parsers don't end the URL at its first `)`.
+            let url = source.url.replace('(', "%28").replace(')', "%29");
+            format!("[{title}]({url})")
+        } else if source.title.is_empty() {
+            source.url.clone()
+        } else {
+            format!("{title} {}", source.url)
+        };
+        let _ = write!(out, "\n[{}] {entry}", source.id);
+    }
+    out
+}
+
+/// Whether replies on `channel` can carry real hyperlinks. Channels whose
+/// formatter flattens links get bare URLs instead.
+pub fn channel_renders_links(channel: &str) -> bool {
+    crate::channels::formatting::formatter_for_channel(channel)
+        .is_none_or(|formatter| formatter.renders_links())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> SourceRegistry {
+        let mut registry = SourceRegistry::default();
+        registry.cite("https://a.example/one", "One");
+        registry.cite("https://b.example/two", "");
+        registry.cite("https://c.example/three_(draft)", "Three [draft]");
+        registry
+    }
+
+    #[test]
+    fn ids_are_sequential_and_stable_per_url() {
+        let mut registry = SourceRegistry::default();
+        assert_eq!(registry.cite("https://a.example", "A"), "S1");
+        assert_eq!(registry.cite("https://b.example", ""), "S2");
+        assert_eq!(registry.cite(" https://a.example ", "Other"), "S1");
+        assert_eq!(registry.cite("https://b.example", "B"), "S2");
+        assert_eq!(registry.sources()[0].title, "A");
+        assert_eq!(registry.sources()[1].title, "B");
+    }
+
+    #[test]
+    fn only_sources_cited_in_text_are_listed() {
+        let registry = registry();
+        let cited = cited_in(
+            registry.sources(),
+            "Per [S3] and [S1], again [S1]; [S9] and S2 are not sources.",
+        );
+        let ids: Vec<_> = cited.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, ["S1", "S3"]);
+        assert!(cited_in(registry.sources(), "no citations").is_empty());
+    }
+
+    #[test]
+    fn sources_section_uses_links_or_bare_urls() {
+        let registry = registry();
+        let cited = cited_in(registry.sources(), "[S1] [S2] [S3]");
+
+        assert_eq!(
+            append_sources("Answer.\n", &cited, true),
+            "Answer.\n\nSources:\n\
+             [S1] [One](https://a.example/one)\n\
+             [S2] [https://b.example/two](https://b.example/two)\n\
+             [S3] [Three draft](https://c.example/three_%28draft%29)"
+        );
+        assert_eq!(
+            append_sources("Answer.", &cited, false),
+            "Answer.\n\nSources:\n\
+             [S1] One https://a.example/one\n\
+             [S2] https://b.example/two\n\
+             [S3] Three draft https://c.example/three_(draft)"
+        );
+        assert_eq!(append_sources("Answer.", &[], true), "Answer.");
+    }
+
+    #[test]
+    fn link_support_follows_the_channel_formatter() {
+        assert!(channel_renders_links("slack"));
+        assert!(channel_renders_links("discord"));
+        assert!(channel_renders_links("telegram"));
+        assert!(!channel_renders_links("signal"));
+        assert!(!channel_renders_links("whatsapp"));
+    }
+}
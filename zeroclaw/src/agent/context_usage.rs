@@ -0,0 +1,289 @@
+//! Context-window utilization.
+//!
+//! Each provider call of a turn is measured as a [`ContextSample`]: the
+//! estimated prompt size ([`packing::estimate_tokens`]), the input tokens
+//! the provider reported, and the model's context window (`agent.
+//! context_window`, or [`known_context_window`]). The turn keeps its
+//! largest sample (see [`crate::agent::turn::record_context`]); at the end
+//! of a channel turn it is counted into a utilization bucket, stored as the
+//! session's latest utilization and audited when above
+//! [`HIGH_UTILIZATION_PCT`].
+//!
+//! [`packing::estimate_tokens`]: crate::agent::packing::estimate_tokens
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Turns above this share of the context window are audited.
+pub const HIGH_UTILIZATION_PCT: f64 = 95.0;
+
+/// Context windows of common models, matched by prefix of the model name
+/// without its provider prefix (`anthropic/claude-…` → `claude-…`). More
+/// specific prefixes come first.
+const KNOWN_CONTEXT_WINDOWS: &[(&str, u64)] = &[
+    ("claude-", 200_000),
+    ("gpt-5", 400_000),
+    ("gpt-4.1", 1_047_576),
+    ("gpt-4o", 128_000),
+    ("gpt-4-turbo", 128_000),
+    ("o1", 200_000),
+    ("o3", 200_000),
+    ("o4-mini", 200_000),
+    ("gemini-1.5-pro", 2_097_152),
+    ("gemini-", 1_048_576),
+    ("deepseek-", 128_000),
+    ("mistral-large", 128_000),
+    ("llama-3.1", 128_000),
+    ("llama3.1", 128_000),
+    ("llama-3.3", 128_000),
+    ("llama3.3", 128_000),
+    ("qwen2.5", 32_768),
+];
+
+/// Context window of `model` from [`KNOWN_CONTEXT_WINDOWS`].
+pub fn known_context_window(model: &str) -> Option<u64> {
+    let name = model
+        .rsplit('/')
+        .next()
+        .unwrap_or(model)
+        .to_ascii_lowercase();
+    KNOWN_CONTEXT_WINDOWS
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map(|(_, window)| *window)
+}
+
+/// Context window used for `model`: `configured` when non-zero, else the
+/// built-in table.
+pub fn context_window(model: &str, configured: usize) -> Option<u64> {
+    if configured > 0 {
+        return u64::try_from(configured).ok();
+    }
+    known_context_window(model)
+}
+
+/// Prompt size of one provider call against the model's context window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextSample {
+    /// Estimated prompt tokens.
+    pub estimated_tokens: u64,
+    /// Input tokens reported by the provider, when it reported usage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<u64>,
+    /// Context window of the model.
+    pub window: u64,
+}
+
+impl ContextSample {
+    /// Prompt size: the provider's count when known, else the estimate.
+    pub fn tokens(&self) -> u64 {
+        self.input_tokens.unwrap_or(self.estimated_tokens)
+    }
+
+    /// Share of the window used, in percent.
+    pub fn utilization_pct(&self) -> f64 {
+        if self.window == 0 {
+            return 0.0;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let pct = self.tokens() as f64 * 100.0 / self.window as f64;
+        pct
+    }
+}
+
+/// Utilization bucket of a turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bucket {
+    Under50,
+    From50To80,
+    From80To95,
+    Over95,
+}
+
+impl Bucket {
+    pub fn of(pct: f64) -> Self {
+        if pct < 50.0 {
+            Self::Under50
+        } else if pct < 80.0 {
+            Self::From50To80
+        } else if pct <= HIGH_UTILIZATION_PCT {
+            Self::From80To95
+        } else {
+            Self::Over95
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Self::Under50 => 0,
+            Self::From50To80 => 1,
+            Self::From80To95 => 2,
+            Self::Over95 => 3,
+        }
+    }
+}
+
+/// Utilization counters; the process-wide instance is [`METRICS`].
+pub struct ContextMetrics {
+    turns_by_bucket: [AtomicU64; 4],
+    calls_with_usage: AtomicU64,
+    estimated_tokens: AtomicU64,
+    reported_tokens: AtomicU64,
+}
+
+pub static METRICS: ContextMetrics = ContextMetrics::new();
+
+impl ContextMetrics {
+    pub const fn new() -> Self {
+        Self {
+            turns_by_bucket: [
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+            ],
+            calls_with_usage: AtomicU64::new(0),
+            estimated_tokens: AtomicU64::new(0),
+            reported_tokens: AtomicU64::new(0),
+        }
+    }
+
+    /// Compare the estimate of one provider call with the reported count.
+    /// Calls without reported usage are not counted.
+    pub fn record_call(&self, sample: &ContextSample) {
+        let Some(reported) = sample.input_tokens else {
+            return;
+        };
+        self.calls_with_usage.fetch_add(1, Ordering::Relaxed);
+        self.estimated_tokens
+            .fetch_add(sample.estimated_tokens, Ordering::Relaxed);
+        self.reported_tokens.fetch_add(reported, Ordering::Relaxed);
+    }
+
+    /// Count a finished turn by its peak utilization; returns its bucket.
+    pub fn record_turn(&self, sample: &ContextSample) -> Bucket {
+        let bucket = Bucket::of(sample.utilization_pct());
+        self.turns_by_bucket[bucket.index()].fetch_add(1, Ordering::Relaxed);
+        bucket
+    }
+
+    pub fn snapshot_json(&self) -> serde_json::Value {
+        let turns = |bucket: Bucket| self.turns_by_bucket[bucket.index()].load(Ordering::Relaxed);
+        let estimated = self.estimated_tokens.load(Ordering::Relaxed);
+        let reported = self.reported_tokens.load(Ordering::Relaxed);
+        #[allow(clippy::cast_precision_loss)]
+        let estimate_ratio = (estimated > 0).then(|| reported as f64 / estimated as f64);
+        serde_json::json!({
+            "turns_by_utilization": {
+                "under_50": turns(Bucket::Under50),
+                "50_to_80": turns(Bucket::From50To80),
+                "80_to_95": turns(Bucket::From80To95),
+                "over_95": turns(Bucket::Over95),
+            },
+            "calls_with_usage": self.calls_with_usage.load(Ordering::Relaxed),
+            "estimated_input_tokens": estimated,
+            "reported_input_tokens": reported,
+            // Reported / estimated; above 1 means the estimate runs low.
+            "estimate_ratio": estimate_ratio,
+        })
+    }
+}
+
+impl Default for ContextMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// [`METRICS`] as JSON, reported by `/api/monitor/metrics`.
+pub fn snapshot_json() -> serde_json::Value {
+    METRICS.snapshot_json()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(estimated: u64, reported: Option<u64>, window: u64) -> ContextSample {
+        ContextSample {
+            estimated_tokens: estimated,
+            input_tokens: reported,
+            window,
+        }
+    }
+
+    #[test]
+    fn windows_come_from_config_or_the_model_table() {
+        assert_eq!(
+            known_context_window("anthropic/claude-sonnet-4"),
+            Some(200_000)
+        );
+        assert_eq!(known_context_window("gpt-4o-mini"), Some(128_000));
+        assert_eq!(known_context_window("gemini-1.5-pro-002"), Some(2_097_152));
+        assert_eq!(
+            known_context_window("google/gemini-2.5-flash"),
+            Some(1_048_576)
+        );
+        assert_eq!(known_context_window("my-local-model"), None);
+        assert_eq!(context_window("my-local-model", 8_192), Some(8_192));
+        assert_eq!(context_window("claude-3-haiku", 50_000), Some(50_000));
+    }
+
+    #[test]
+    fn reported_tokens_take_precedence_over_the_estimate() {
+        let estimated_only = sample(40_000, None, 100_000);
+        assert_eq!(estimated_only.tokens(), 40_000);
+        assert!((estimated_only.utilization_pct() - 40.0).abs() < f64::EPSILON);
+
+        let reported = sample(40_000, Some(52_000), 100_000);
+        assert_eq!(reported.tokens(), 52_000);
+        assert!((reported.utilization_pct() - 52.0).abs() < f64::EPSILON);
+
+        let metrics = ContextMetrics::new();
+        metrics.record_call(&estimated_only);
+        metrics.record_call(&reported);
+        metrics.record_call(&sample(10_000, Some(13_000), 100_000));
+        let snapshot = metrics.snapshot_json();
+        assert_eq!(snapshot["calls_with_usage"], 2);
+        assert_eq!(snapshot["estimated_input_tokens"], 50_000);
+        assert_eq!(snapshot["reported_input_tokens"], 65_000);
+        assert_eq!(snapshot["estimate_ratio"], 1.3);
+    }
+
+    #[test]
+    fn turns_are_counted_by_utilization_bucket() {
+        let metrics = ContextMetrics::new();
+        assert_eq!(metrics.record_turn(&sample(10, None, 100)), Bucket::Under50);
+        assert_eq!(
+            metrics.record_turn(&sample(50, None, 100)),
+            Bucket::From50To80
+        );
+        assert_eq!(
+            metrics.record_turn(&sample(79, None, 100)),
+            Bucket::From50To80
+        );
+        assert_eq!(
+            metrics.record_turn(&sample(80, None, 100)),
+            Bucket::From80To95
+        );
+        assert_eq!(
+            metrics.record_turn(&sample(95, None, 100)),
+            Bucket::From80To95
+        );
+        assert_eq!(
+            metrics.record_turn(&sample(90, Some(99), 100)),
+            Bucket::Over95
+        );
+        assert_eq!(
+            metrics.record_turn(&sample(0, Some(150), 100)),
+            Bucket::Over95
+        );
+
+        let turns = &metrics.snapshot_json()["turns_by_utilization"];
+        assert_eq!(turns["under_50"], 1);
+        assert_eq!(turns["50_to_80"], 2);
+        assert_eq!(turns["80_to_95"], 2);
+        assert_eq!(turns["over_95"], 2);
+        assert!(metrics.snapshot_json()["estimate_ratio"].is_null());
+    }
+}
@@ -1,3 +1,8 @@
+use crate::agent::context_usage;
+use crate::agent::packing::{self, PackingLimits};
+use crate::agent::progress::{self, TurnPhase, TurnProgress};
+use crate::agent::tool_repair::{self, ArgumentsStatus, MalformedCallTracker};
+use crate::approval::confirm::{ConfirmationGate, Resolution};
 use crate::approval::{ApprovalManager, ApprovalRequest, ApprovalResponse};
 use crate::config::Config;
 use crate::memory::{self, Memory, MemoryCategory};
@@ -7,8 +12,9 @@ use crate::providers::{
     self, ChatMessage, ChatRequest, Provider, ProviderCapabilityError, ToolCall,
 };
 use crate::runtime;
+use crate::security::redaction::{self, RedactionPath, Redactor};
 use crate::security::SecurityPolicy;
-use crate::tools::{self, Tool};
+use crate::tools::{self, execute_tool, Tool};
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
 use regex::{Regex, RegexSet};
@@ -163,7 +169,7 @@ fn trim_history(history: &mut Vec<ChatMessage>, max_history: usize) {
     history.drain(start..start + to_remove);
 }
 
-fn build_compaction_transcript(messages: &[ChatMessage]) -> String {
+pub(crate) fn build_compaction_transcript(messages: &[ChatMessage]) -> String {
     let mut transcript = String::new();
     for msg in messages {
         let role = msg.role.to_uppercase();
@@ -214,7 +220,38 @@ async fn auto_compact_history(
     let compact_end = start + compact_count;
     let to_compact: Vec<ChatMessage> = history[start..compact_end].to_vec();
     let transcript = build_compaction_transcript(&to_compact);
+    let summary = summarize_compaction_transcript(provider, model, &transcript).await;
+    apply_compaction_summary(history, start, compact_end, &summary);
+
+    Ok(true)
+}
+
+/// Apply `[agent.channel_overrides.<channel>]` to a run started on behalf of
+/// `channel` (e.g. `cron`, `heartbeat`): append its prompt suffix and use its
+/// temperature. Runs without a channel keep the caller's values.
+fn apply_channel_override(
+    config: &Config,
+    channel: Option<&str>,
+    system_prompt: String,
+    temperature: f64,
+) -> (String, f64) {
+    match channel.and_then(|name| config.agent.channel_override(name)) {
+        Some(override_) => (
+            override_.system_prompt(&system_prompt),
+            override_.temperature.unwrap_or(temperature),
+        ),
+        None => (system_prompt, temperature),
+    }
+}
 
+/// Ask `provider` for a bullet-point summary of `transcript`, falling back to
+/// a plain truncation when the call fails. Shared with stored-session
+/// compaction ([`crate::sessions::compaction`]).
+pub(crate) async fn summarize_compaction_transcript(
+    provider: &dyn Provider,
+    model: &str,
+    transcript: &str,
+) -> String {
     let summarizer_system = "You are a conversation compaction engine. Summarize older chat history into concise context for future turns. Preserve: user preferences, commitments, decisions, unresolved tasks, key facts. Omit: filler, repeated chit-chat, verbose tool logs. Output plain text bullet points only.";
 
     let summarizer_user = format!(
@@ -227,13 +264,10 @@ async fn auto_compact_history(
         .await
         .unwrap_or_else(|_| {
             // Fallback to deterministic local truncation when summarization fails.
-            truncate_with_ellipsis(&transcript, COMPACTION_MAX_SUMMARY_CHARS)
+            truncate_with_ellipsis(transcript, COMPACTION_MAX_SUMMARY_CHARS)
         });
 
-    let summary = truncate_with_ellipsis(&summary_raw, COMPACTION_MAX_SUMMARY_CHARS);
-    apply_compaction_summary(history, start, compact_end, &summary);
-
-    Ok(true)
+    truncate_with_ellipsis(&summary_raw, COMPACTION_MAX_SUMMARY_CHARS)
 }
 
 /// Build context preamble by searching memory for relevant entries.
@@ -311,15 +345,9 @@ fn build_hardware_context(
     context
 }
 
-/// Find a tool by name in the registry.
-fn find_tool<'a>(tools: &'a [Box<dyn Tool>], name: &str) -> Option<&'a dyn Tool> {
-    tools.iter().find(|t| t.name() == name).map(|t| t.as_ref())
-}
-
 fn parse_arguments_value(raw: Option<&serde_json::Value>) -> serde_json::Value {
     match raw {
-        Some(serde_json::Value::String(s)) => serde_json::from_str::<serde_json::Value>(s)
-            .unwrap_or_else(|_| serde_json::Value::Object(serde_json::Map::new())),
+        Some(serde_json::Value::String(s)) => tool_repair::parse_arguments(s).0,
         Some(value) => value.clone(),
         None => serde_json::Value::Object(serde_json::Map::new()),
     }
@@ -1120,7 +1148,8 @@ fn default_param_for_tool(tool: &str) -> &'static str {
         // All file tools default to "path"
         "file_read" | "fileread" | "readfile" | "read_file" | "file" | "file_write"
         | "filewrite" | "writefile" | "write_file" | "file_edit" | "fileedit" | "editfile"
-        | "edit_file" | "file_list" | "filelist" | "listfiles" | "list_files" => "path",
+        | "edit_file" | "file_list" | "filelist" | "listfiles" | "list_files" | "list_dir"
+        | "listdir" => "path",
         // Memory recall and forget both default to "query"
         "memory_recall" | "memoryrecall" | "recall" | "memrecall" | "memory_forget"
         | "memoryforget" | "forget" | "memforget" => "query",
@@ -1726,16 +1755,24 @@ fn detect_tool_call_parse_issue(response: &str, parsed_calls: &[ParsedToolCall])
     }
 }
 
-fn parse_structured_tool_calls(tool_calls: &[ToolCall]) -> Vec<ParsedToolCall> {
+/// Convert native tool calls, repairing near-JSON arguments. The returned
+/// statuses are index-aligned with the calls so the loop can report repairs
+/// and refuse to run calls whose arguments could not be decoded.
+fn parse_structured_tool_calls(
+    tool_calls: &[ToolCall],
+) -> (Vec<ParsedToolCall>, Vec<tool_repair::ArgumentsStatus>) {
     tool_calls
         .iter()
-        .map(|call| ParsedToolCall {
-            name: call.name.clone(),
-            arguments: serde_json::from_str::<serde_json::Value>(&call.arguments)
-                .unwrap_or_else(|_| serde_json::Value::Object(serde_json::Map::new())),
-            tool_call_id: Some(call.id.clone()),
+        .map(|call| {
+            let (arguments, status) = tool_repair::parse_arguments(&call.arguments);
+            let parsed = ParsedToolCall {
+                name: call.name.clone(),
+                arguments,
+                tool_call_id: Some(call.id.clone()),
+            };
+            (parsed, status)
         })
-        .collect()
+        .unzip()
 }
 
 /// Build assistant history entry in JSON format for native tool-call APIs.
@@ -1858,6 +1895,24 @@ pub(crate) fn is_tool_loop_cancelled(err: &anyhow::Error) -> bool {
     err.chain().any(|source| source.is::<ToolLoopCancelled>())
 }
 
+/// The tool loop used up its iteration budget without a final answer.
+#[derive(Debug)]
+pub(crate) struct ToolLoopExhausted {
+    pub max_iterations: usize,
+}
+
+impl std::fmt::Display for ToolLoopExhausted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Agent exceeded maximum tool iterations ({})",
+            self.max_iterations
+        )
+    }
+}
+
+impl std::error::Error for ToolLoopExhausted {}
+
 /// Execute a single turn of the agent loop: send messages, parse tool calls,
 /// execute tools, and loop until the LLM produces a final text response.
 /// When `silent` is true, suppresses stdout (for channel use).
@@ -1873,6 +1928,7 @@ pub(crate) async fn agent_turn(
     silent: bool,
     multimodal_config: &crate::config::MultimodalConfig,
     max_tool_iterations: usize,
+    context_packing: &PackingLimits,
 ) -> Result<String> {
     run_tool_call_loop(
         provider,
@@ -1890,7 +1946,9 @@ pub(crate) async fn agent_turn(
         None,
         None,
         None,
+        None,
         &[],
+        context_packing,
     )
     .await
 }
@@ -1899,81 +1957,86 @@ async fn execute_one_tool(
     call_name: &str,
     call_arguments: serde_json::Value,
     tools_registry: &[Box<dyn Tool>],
+    excluded_tools: &[String],
     observer: &dyn Observer,
     cancellation_token: Option<&CancellationToken>,
 ) -> Result<ToolExecutionOutcome> {
-    observer.record_event(&ObserverEvent::ToolCallStart {
-        tool: call_name.to_string(),
-    });
-    let start = Instant::now();
-
-    let Some(tool) = find_tool(tools_registry, call_name) else {
-        let reason = format!("Unknown tool: {call_name}");
-        let duration = start.elapsed();
-        observer.record_event(&ObserverEvent::ToolCall {
-            tool: call_name.to_string(),
-            duration,
-            success: false,
-        });
-        return Ok(ToolExecutionOutcome {
-            output: reason.clone(),
-            success: false,
-            error_reason: Some(scrub_credentials(&reason)),
-            duration,
-        });
-    };
-
-    let tool_future = tool.execute(call_arguments);
-    let tool_result = if let Some(token) = cancellation_token {
+    let tool_future = execute_tool(
+        tools_registry,
+        excluded_tools,
+        call_name,
+        call_arguments,
+        observer,
+    );
+    let outcome = if let Some(token) = cancellation_token {
         tokio::select! {
             () = token.cancelled() => return Err(ToolLoopCancelled.into()),
-            result = tool_future => result,
+            outcome = tool_future => outcome,
         }
     } else {
         tool_future.await
     };
 
-    match tool_result {
-        Ok(r) => {
-            let duration = start.elapsed();
-            observer.record_event(&ObserverEvent::ToolCall {
-                tool: call_name.to_string(),
-                duration,
-                success: r.success,
-            });
-            if r.success {
-                Ok(ToolExecutionOutcome {
-                    output: scrub_credentials(&r.output),
-                    success: true,
-                    error_reason: None,
-                    duration,
-                })
-            } else {
-                let reason = r.error.unwrap_or(r.output);
-                Ok(ToolExecutionOutcome {
-                    output: format!("Error: {reason}"),
-                    success: false,
-                    error_reason: Some(scrub_credentials(&reason)),
-                    duration,
-                })
-            }
-        }
-        Err(e) => {
-            let duration = start.elapsed();
-            observer.record_event(&ObserverEvent::ToolCall {
-                tool: call_name.to_string(),
-                duration,
-                success: false,
-            });
-            let reason = format!("Error executing {call_name}: {e}");
-            Ok(ToolExecutionOutcome {
-                output: reason.clone(),
-                success: false,
-                error_reason: Some(scrub_credentials(&reason)),
-                duration,
-            })
+    Ok(ToolExecutionOutcome {
+        output: redaction::apply(
+            RedactionPath::ToolResult,
+            scrub_credentials(&outcome.output),
+        ),
+        success: outcome.success,
+        error_reason: outcome.error_reason.as_deref().map(scrub_credentials),
+        duration: outcome.duration,
+    })
+}
+
+/// Settle a call held for confirmation with the newest message of `sender`
+/// (see [`crate::approval::confirm`]); messages from other participants
+/// leave it held. A confirmed call runs now; the outcome is appended to that
+/// message so the model continues from it.
+async fn settle_held_call(
+    gate: &ConfirmationGate,
+    session_key: &str,
+    sender: &str,
+    channel_name: &str,
+    history: &mut [ChatMessage],
+    tools_registry: &[Box<dyn Tool>],
+    excluded_tools: &[String],
+    observer: &dyn Observer,
+    cancellation_token: Option<&CancellationToken>,
+) -> Result<()> {
+    let Some(last) = history.last_mut().filter(|message| message.role == "user") else {
+        return Ok(());
+    };
+    let Some(resolution) = gate.resolve(session_key, sender, channel_name, &last.content) else {
+        return Ok(());
+    };
+    let note = match resolution {
+        Resolution::Approved(call) => {
+            super::turn::record_tool(&call.tool_name);
+            let outcome = execute_one_tool(
+                &call.tool_name,
+                call.arguments,
+                tools_registry,
+                excluded_tools,
+                observer,
+                cancellation_token,
+            )
+            .await?;
+            format!(
+                "[The user confirmed the held {0} call; it ran]\n<tool_result name=\"{0}\">\n{1}\n</tool_result>",
+                call.tool_name, outcome.output
+            )
         }
-    }
+        Resolution::Denied(call) => format!(
+            "[The user did not confirm the held {} call; it was cancelled and did not run]",
+            call.tool_name
+        ),
+        Resolution::Expired(call) => format!(
+            "[The held {} call expired before the user confirmed it; it did not run]",
+            call.tool_name
+        ),
+    };
+    last.content = format!("{}\n\n{note}", last.content);
+    Ok(())
 }
 
 struct ToolExecutionOutcome {
@@ -2005,6 +2068,7 @@ fn should_execute_tools_in_parallel(
 async fn execute_tools_parallel(
     tool_calls: &[ParsedToolCall],
     tools_registry: &[Box<dyn Tool>],
+    excluded_tools: &[String],
     observer: &dyn Observer,
     cancellation_token: Option<&CancellationToken>,
 ) -> Result<Vec<ToolExecutionOutcome>> {
@@ -2015,6 +2079,7 @@ async fn execute_tools_parallel(
                 &call.name,
                 call.arguments.clone(),
                 tools_registry,
+                excluded_tools,
                 observer,
                 cancellation_token,
             )
@@ -2028,6 +2093,7 @@ async fn execute_tools_parallel(
 async fn execute_tools_sequential(
     tool_calls: &[ParsedToolCall],
     tools_registry: &[Box<dyn Tool>],
+    excluded_tools: &[String],
     observer: &dyn Observer,
     cancellation_token: Option<&CancellationToken>,
 ) -> Result<Vec<ToolExecutionOutcome>> {
@@ -2039,6 +2105,7 @@ async fn execute_tools_sequential(
                 &call.name,
                 call.arguments.clone(),
                 tools_registry,
+                excluded_tools,
                 observer,
                 cancellation_token,
             )
@@ -2079,8 +2146,10 @@ pub(crate) async fn run_tool_call_loop(
     max_tool_iterations: usize,
     cancellation_token: Option<CancellationToken>,
     on_delta: Option<tokio::sync::mpsc::Sender<String>>,
+    on_progress: Option<tokio::sync::mpsc::Sender<TurnProgress>>,
     hooks: Option<&crate::hooks::HookRunner>,
     excluded_tools: &[String],
+    context_packing: &PackingLimits,
 ) -> Result<String> {
     let max_iterations = if max_tool_iterations == 0 {
         DEFAULT_MAX_TOOL_ITERATIONS
@@ -2094,10 +2163,45 @@ pub(crate) async fn run_tool_call_loop(
         .map(|tool| tool.spec())
         .collect();
     let use_native_tools = provider.supports_native_tools() && !tool_specs.is_empty();
-    let turn_id = Uuid::new_v4().to_string();
+    let offered_tool_names: Vec<&str> = tool_specs.iter().map(|spec| spec.name.as_str()).collect();
+    let offered_tools: Arc<[String]> = offered_tool_names
+        .iter()
+        .map(|name| (*name).to_string())
+        .collect();
+    let turn_id = super::turn::current_turn_id().unwrap_or_else(|| Uuid::new_v4().to_string());
     let mut seen_tool_signatures: HashSet<(String, String)> = HashSet::new();
+    let mut malformed_calls = MalformedCallTracker::default();
+    progress::emit(on_progress.as_ref(), TurnPhase::Started);
+
+    // Calls are held on the session's own channel turn. Sub-tasks and
+    // delegation running inside it cannot ask the user, so they refuse the
+    // gated tools instead (`nested_gate`).
+    let session_gate = crate::sessions::current_session()
+        .and_then(|session| crate::approval::confirm::installed().map(|gate| (gate, session)));
+    let (confirmation, nested_gate) = match session_gate {
+        Some((gate, session)) if session.channel == channel_name => {
+            (Some((gate, session.session_key, session.sender)), None)
+        }
+        Some((gate, _)) => (None, Some(gate)),
+        None => (None, None),
+    };
+    if let Some((gate, session_key, sender)) = &confirmation {
+        settle_held_call(
+            gate,
+            session_key,
+            sender,
+            channel_name,
+            history,
+            tools_registry,
+            excluded_tools,
+            observer,
+            cancellation_token.as_ref(),
+        )
+        .await?;
+    }
 
     for iteration in 0..max_iterations {
+        crate::health::record_agent_activity();
         if cancellation_token
             .as_ref()
             .is_some_and(CancellationToken::is_cancelled)
@@ -2117,8 +2221,23 @@ pub(crate) async fn run_tool_call_loop(
             .into());
         }
 
-        let prepared_messages =
-            multimodal::prepare_messages_for_provider(history, multimodal_config).await?;
+        let (packed_history, packing_stats) = packing::pack_history(history, context_packing);
+        if !packing_stats.is_noop() {
+            tracing::debug!(
+                iteration = iteration + 1,
+                truncated_results = packing_stats.truncated_results,
+                dropped_messages = packing_stats.dropped_messages,
+                "Packed tool-loop context before provider call"
+            );
+            observer.record_event(&ObserverEvent::ContextPacked {
+                truncated_results: packing_stats.truncated_results,
+                dropped_messages: packing_stats.dropped_messages,
+            });
+        }
+
+        let mut prepared_messages =
+            multimodal::prepare_messages_for_provider(&packed_history, multimodal_config).await?;
+        let mut sent_tokens = packing::estimate_tokens(&packed_history);
 
         // ── Progress: LLM thinking ────────────────────────────
         if let Some(ref tx) = on_delta {
@@ -2135,6 +2254,7 @@ pub(crate) async fn run_tool_call_loop(
             model: model.to_string(),
             messages_count: history.len(),
         });
+        super::turn::record_iteration();
         runtime_trace::record_event(
             "llm_request",
             Some(channel_name),
@@ -2164,156 +2284,227 @@ pub(crate) async fn run_tool_call_loop(
             None
         };
 
-        let chat_future = provider.chat(
-            ChatRequest {
-                messages: &prepared_messages.messages,
-                tools: request_tools,
-            },
-            model,
-            temperature,
-        );
+        // A context-window rejection gets one retry with emergency packing;
+        // any other failure (or a second rejection) is reported as-is.
+        let mut emergency_packed = false;
+        let chat_result = loop {
+            let chat_future = provider.chat(
+                ChatRequest {
+                    messages: &prepared_messages.messages,
+                    tools: request_tools,
+                },
+                model,
+                temperature,
+            );
+
+            let result = if let Some(token) = cancellation_token.as_ref() {
+                tokio::select! {
+                    () = token.cancelled() => return Err(ToolLoopCancelled.into()),
+                    result = chat_future => result,
+                }
+            } else {
+                chat_future.await
+            };
 
-        let chat_result = if let Some(token) = cancellation_token.as_ref() {
-            tokio::select! {
-                () = token.cancelled() => return Err(ToolLoopCancelled.into()),
-                result = chat_future => result,
+            match result {
+                Err(e)
+                    if !emergency_packed
+                        && crate::providers::reliable::is_context_window_exceeded(&e) =>
+                {
+                    emergency_packed = true;
+                    let limits = context_packing.emergency(&packed_history);
+                    let (repacked, stats) = packing::pack_history(history, &limits);
+                    if packing::estimate_tokens(&repacked)
+                        >= packing::estimate_tokens(&packed_history)
+                    {
+                        break Err(e);
+                    }
+                    tracing::warn!(
+                        iteration = iteration + 1,
+                        truncated_results = stats.truncated_results,
+                        dropped_messages = stats.dropped_messages,
+                        "Provider rejected request as too long; retrying with emergency packing"
+                    );
+                    observer.record_event(&ObserverEvent::ContextPacked {
+                        truncated_results: stats.truncated_results,
+                        dropped_messages: stats.dropped_messages,
+                    });
+                    sent_tokens = packing::estimate_tokens(&repacked);
+                    prepared_messages =
+                        multimodal::prepare_messages_for_provider(&repacked, multimodal_config)
+                            .await?;
+                }
+                other => break other,
             }
-        } else {
-            chat_future.await
         };
 
-        let (response_text, parsed_text, tool_calls, assistant_history_content, native_tool_calls) =
-            match chat_result {
-                Ok(resp) => {
-                    let (resp_input_tokens, resp_output_tokens) = resp
-                        .usage
-                        .as_ref()
-                        .map(|u| (u.input_tokens, u.output_tokens))
-                        .unwrap_or((None, None));
-
-                    observer.record_event(&ObserverEvent::LlmResponse {
-                        provider: provider_name.to_string(),
-                        model: model.to_string(),
-                        duration: llm_started_at.elapsed(),
-                        success: true,
-                        error_message: None,
+        let (
+            response_text,
+            parsed_text,
+            tool_calls,
+            argument_statuses,
+            assistant_history_content,
+            native_tool_calls,
+        ) = match chat_result {
+            Ok(resp) => {
+                let (resp_input_tokens, resp_output_tokens) = resp
+                    .usage
+                    .as_ref()
+                    .map(|u| (u.input_tokens, u.output_tokens))
+                    .unwrap_or((None, None));
+                super::turn::record_usage(resp_input_tokens, resp_output_tokens);
+                if let Some(window) =
+                    context_usage::context_window(model, context_packing.context_window)
+                {
+                    let sample = context_usage::ContextSample {
+                        estimated_tokens: u64::try_from(sent_tokens).unwrap_or(u64::MAX),
                         input_tokens: resp_input_tokens,
-                        output_tokens: resp_output_tokens,
-                    });
-
-                    let response_text = resp.text_or_empty().to_string();
-                    // First try native structured tool calls (OpenAI-format).
-                    // Fall back to text-based parsing (XML tags, markdown blocks,
-                    // GLM format) only if the provider returned no native calls —
-                    // this ensures we support both native and prompt-guided models.
-                    let mut calls = parse_structured_tool_calls(&resp.tool_calls);
-                    let mut parsed_text = String::new();
-
-                    if calls.is_empty() {
-                        let (fallback_text, fallback_calls) = parse_tool_calls(&response_text);
-                        if !fallback_text.is_empty() {
-                            parsed_text = fallback_text;
-                        }
-                        calls = fallback_calls;
-                    }
+                        window,
+                    };
+                    context_usage::METRICS.record_call(&sample);
+                    super::turn::record_context(sample);
+                }
+                // Sub-tasks stop once their token budget is spent; without
+                // provider usage the exchange is estimated.
+                let exchange_tokens = match (resp_input_tokens, resp_output_tokens) {
+                    (None, None) => u64::try_from(
+                        packing::estimate_tokens(&packed_history)
+                            + resp.text_or_empty().chars().count().div_ceil(4),
+                    )
+                    .unwrap_or(u64::MAX),
+                    (input, output) => input.unwrap_or(0) + output.unwrap_or(0),
+                };
+                super::subtask::charge_tokens(exchange_tokens)?;
+
+                let llm_latency = llm_started_at.elapsed();
+                super::turn::METRICS.record_llm_call(llm_latency);
+                observer.record_event(&ObserverEvent::LlmResponse {
+                    provider: provider_name.to_string(),
+                    model: model.to_string(),
+                    duration: llm_latency,
+                    success: true,
+                    error_message: None,
+                    input_tokens: resp_input_tokens,
+                    output_tokens: resp_output_tokens,
+                });
 
-                    if let Some(parse_issue) = detect_tool_call_parse_issue(&response_text, &calls)
-                    {
-                        runtime_trace::record_event(
-                            "tool_call_parse_issue",
-                            Some(channel_name),
-                            Some(provider_name),
-                            Some(model),
-                            Some(&turn_id),
-                            Some(false),
-                            Some(&parse_issue),
-                            serde_json::json!({
-                                "iteration": iteration + 1,
-                                "response_excerpt": truncate_with_ellipsis(
-                                    &scrub_credentials(&response_text),
-                                    600
-                                ),
-                            }),
-                        );
+                let response_text = resp.text_or_empty().to_string();
+                // First try native structured tool calls (OpenAI-format).
+                // Fall back to text-based parsing (XML tags, markdown blocks,
+                // GLM format) only if the provider returned no native calls —
+                // this ensures we support both native and prompt-guided models.
+                let (mut calls, mut argument_statuses) =
+                    parse_structured_tool_calls(&resp.tool_calls);
+                let mut parsed_text = String::new();
+
+                if calls.is_empty() {
+                    let (fallback_text, fallback_calls) = parse_tool_calls(&response_text);
+                    if !fallback_text.is_empty() {
+                        parsed_text = fallback_text;
                     }
+                    calls = fallback_calls;
+                    argument_statuses = vec![ArgumentsStatus::Valid; calls.len()];
+                }
 
+                if let Some(parse_issue) = detect_tool_call_parse_issue(&response_text, &calls) {
                     runtime_trace::record_event(
-                        "llm_response",
+                        "tool_call_parse_issue",
                         Some(channel_name),
                         Some(provider_name),
                         Some(model),
                         Some(&turn_id),
-                        Some(true),
-                        None,
+                        Some(false),
+                        Some(&parse_issue),
                         serde_json::json!({
                             "iteration": iteration + 1,
-                            "duration_ms": llm_started_at.elapsed().as_millis(),
-                            "input_tokens": resp_input_tokens,
-                            "output_tokens": resp_output_tokens,
-                            "raw_response": scrub_credentials(&response_text),
-                            "native_tool_calls": resp.tool_calls.len(),
-                            "parsed_tool_calls": calls.len(),
+                            "response_excerpt": truncate_with_ellipsis(
+                                &scrub_credentials(&response_text),
+                                600
+                            ),
                         }),
                     );
+                }
 
-                    // Preserve native tool call IDs in assistant history so role=tool
-                    // follow-up messages can reference the exact call id.
-                    let reasoning_content = resp.reasoning_content.clone();
-                    let assistant_history_content = if resp.tool_calls.is_empty() {
-                        if use_native_tools {
-                            build_native_assistant_history_from_parsed_calls(
-                                &response_text,
-                                &calls,
-                                reasoning_content.as_deref(),
-                            )
-                            .unwrap_or_else(|| response_text.clone())
-                        } else {
-                            response_text.clone()
-                        }
-                    } else {
-                        build_native_assistant_history(
+                runtime_trace::record_event(
+                    "llm_response",
+                    Some(channel_name),
+                    Some(provider_name),
+                    Some(model),
+                    Some(&turn_id),
+                    Some(true),
+                    None,
+                    serde_json::json!({
+                        "iteration": iteration + 1,
+                        "duration_ms": llm_started_at.elapsed().as_millis(),
+                        "input_tokens": resp_input_tokens,
+                        "output_tokens": resp_output_tokens,
+                        "raw_response": scrub_credentials(&response_text),
+                        "native_tool_calls": resp.tool_calls.len(),
+                        "parsed_tool_calls": calls.len(),
+                    }),
+                );
+
+                // Preserve native tool call IDs in assistant history so role=tool
+                // follow-up messages can reference the exact call id.
+                let reasoning_content = resp.reasoning_content.clone();
+                let assistant_history_content = if resp.tool_calls.is_empty() {
+                    if use_native_tools {
+                        build_native_assistant_history_from_parsed_calls(
                             &response_text,
-                            &resp.tool_calls,
+                            &calls,
                             reasoning_content.as_deref(),
                         )
-                    };
-
-                    let native_calls = resp.tool_calls;
-                    (
-                        response_text,
-                        parsed_text,
-                        calls,
-                        assistant_history_content,
-                        native_calls,
+                        .unwrap_or_else(|| response_text.clone())
+                    } else {
+                        response_text.clone()
+                    }
+                } else {
+                    build_native_assistant_history(
+                        &response_text,
+                        &resp.tool_calls,
+                        reasoning_content.as_deref(),
                     )
-                }
-                Err(e) => {
-                    let safe_error = crate::providers::sanitize_api_error(&e.to_string());
-                    observer.record_event(&ObserverEvent::LlmResponse {
-                        provider: provider_name.to_string(),
-                        model: model.to_string(),
-                        duration: llm_started_at.elapsed(),
-                        success: false,
-                        error_message: Some(safe_error.clone()),
-                        input_tokens: None,
-                        output_tokens: None,
-                    });
-                    runtime_trace::record_event(
-                        "llm_response",
-                        Some(channel_name),
-                        Some(provider_name),
-                        Some(model),
-                        Some(&turn_id),
-                        Some(false),
-                        Some(&safe_error),
-                        serde_json::json!({
-                            "iteration": iteration + 1,
-                            "duration_ms": llm_started_at.elapsed().as_millis(),
-                        }),
-                    );
-                    return Err(e);
-                }
-            };
+                };
+
+                let native_calls = resp.tool_calls;
+                (
+                    response_text,
+                    parsed_text,
+                    calls,
+                    argument_statuses,
+                    assistant_history_content,
+                    native_calls,
+                )
+            }
+            Err(e) => {
+                let safe_error = crate::providers::sanitize_api_error(&e.to_string());
+                let llm_latency = llm_started_at.elapsed();
+                super::turn::METRICS.record_llm_call(llm_latency);
+                observer.record_event(&ObserverEvent::LlmResponse {
+                    provider: provider_name.to_string(),
+                    model: model.to_string(),
+                    duration: llm_latency,
+                    success: false,
+                    error_message: Some(safe_error.clone()),
+                    input_tokens: None,
+                    output_tokens: None,
+                });
+                runtime_trace::record_event(
+                    "llm_response",
+                    Some(channel_name),
+                    Some(provider_name),
+                    Some(model),
+                    Some(&turn_id),
+                    Some(false),
+                    Some(&safe_error),
+                    serde_json::json!({
+                        "iteration": iteration + 1,
+                        "duration_ms": llm_started_at.elapsed().as_millis(),
+                    }),
+                );
+                return Err(e);
+            }
+        };
 
         let display_text = if parsed_text.is_empty() {
             response_text.clone()
@@ -2349,6 +2540,7 @@ pub(crate) async fn run_tool_call_loop(
                 }),
             );
             // No tool calls — this is the final response.
+            progress::emit(on_progress.as_ref(), TurnPhase::Composing);
             // If a streaming sender is provided, relay the text in small chunks
             // so the channel can progressively update the draft message.
             if let Some(ref tx) = on_delta {
@@ -2397,8 +2589,97 @@ pub(crate) async fn run_tool_call_loop(
         let allow_parallel_execution = should_execute_tools_in_parallel(&tool_calls, approval);
         let mut executable_indices: Vec<usize> = Vec::new();
         let mut executable_calls: Vec<ParsedToolCall> = Vec::new();
+        let mut retry_cutoff: Option<(String, String)> = None;
+        let mut held_prompt: Option<String> = None;
 
         for (idx, call) in tool_calls.iter().enumerate() {
+            // ── Malformed or hallucinated calls ─────────────
+            let raw_arguments = native_tool_calls.get(idx).map_or_else(
+                || call.arguments.to_string(),
+                |native| native.arguments.clone(),
+            );
+            let is_known_tool = tools_registry.iter().any(|tool| tool.name() == call.name);
+            let malformed = if is_known_tool {
+                match &argument_statuses[idx] {
+                    ArgumentsStatus::Valid => None,
+                    ArgumentsStatus::Repaired => {
+                        runtime_trace::record_event(
+                            "tool_call_arguments_repaired",
+                            Some(channel_name),
+                            Some(provider_name),
+                            Some(model),
+                            Some(&turn_id),
+                            Some(true),
+                            None,
+                            serde_json::json!({
+                                "iteration": iteration + 1,
+                                "tool": call.name,
+                                "raw_arguments": scrub_credentials(&raw_arguments),
+                            }),
+                        );
+                        None
+                    }
+                    ArgumentsStatus::Invalid(error) => {
+                        let message = format!(
+                            "Arguments for '{}' are not valid JSON ({error}). Resend the call with \
+                             a single JSON object matching the tool's parameter schema.",
+                            call.name
+                        );
+                        runtime_trace::record_event(
+                            "tool_call_arguments_invalid",
+                            Some(channel_name),
+                            Some(provider_name),
+                            Some(model),
+                            Some(&turn_id),
+                            Some(false),
+                            Some(error),
+                            serde_json::json!({
+                                "iteration": iteration + 1,
+                                "tool": call.name,
+                                "raw_arguments": scrub_credentials(&raw_arguments),
+                            }),
+                        );
+                        Some(message)
+                    }
+                }
+            } else {
+                let suggestions = tool_repair::suggest_tool_names(&call.name, &offered_tool_names);
+                let message = tool_repair::unknown_tool_message(&call.name, &offered_tool_names);
+                runtime_trace::record_event(
+                    "tool_call_unknown_tool",
+                    Some(channel_name),
+                    Some(provider_name),
+                    Some(model),
+                    Some(&turn_id),
+                    Some(false),
+                    Some(&format!("Unknown tool: {}", call.name)),
+                    serde_json::json!({
+                        "iteration": iteration + 1,
+                        "tool": call.name,
+                        "suggestions": suggestions,
+                    }),
+                );
+                Some(message)
+            };
+
+            if let Some(message) = malformed {
+                if malformed_calls.record_failure(&call.name) && retry_cutoff.is_none() {
+                    retry_cutoff = Some((call.name.clone(), raw_arguments));
+                }
+                ordered_results[idx] = Some((
+                    call.name.clone(),
+                    call.tool_call_id.clone(),
+                    ToolExecutionOutcome {
+                        output: message.clone(),
+                        success: false,
+                        error_reason: Some(message),
+                        duration: Duration::ZERO,
+                    },
+                ));
+                continue;
+            }
+            malformed_calls.record_success(&call.name);
+
             // ── Hook: before_tool_call (modifying) ──────────
             let mut tool_name = call.name.clone();
             let mut tool_args = call.arguments.clone();
@@ -2491,10 +2772,14 @@ pub(crate) async fn run_tool_call_loop(
                 }
             }
 
-            let signature = tool_call_signature(&tool_name, &tool_args);
-            if !seen_tool_signatures.insert(signature) {
-                let duplicate = format!(
-                    "Skipped duplicate tool call '{tool_name}' with identical arguments in this turn."
+            // ── Channel confirmation ─────────────────────────
+            if nested_gate
+                .as_ref()
+                .is_some_and(|gate| gate.requires_confirmation(&tool_name))
+            {
+                let refused = format!(
+                    "Not run: '{tool_name}' needs the user's confirmation, which is only \
+                     available in the main conversation, not in a sub-task or delegated run."
                 );
                 runtime_trace::record_event(
                     "tool_call_result",
@@ -2503,27 +2788,102 @@ pub(crate) async fn run_tool_call_loop(
                     Some(model),
                     Some(&turn_id),
                     Some(false),
-                    Some(&duplicate),
+                    Some(&refused),
                     serde_json::json!({
                         "iteration": iteration + 1,
                         "tool": tool_name.clone(),
                         "arguments": scrub_credentials(&tool_args.to_string()),
-                        "deduplicated": true,
                     }),
                 );
                 ordered_results[idx] = Some((
                     tool_name.clone(),
                     call.tool_call_id.clone(),
                     ToolExecutionOutcome {
-                        output: duplicate.clone(),
+                        output: refused.clone(),
                         success: false,
-                        error_reason: Some(duplicate),
+                        error_reason: Some(refused),
                         duration: Duration::ZERO,
                     },
                 ));
                 continue;
             }
+            if let Some((gate, session_key, sender)) = &confirmation {
+                if gate.requires_confirmation(&tool_name) {
+                    let held = if held_prompt.is_some() {
+                        format!(
+                            "Not run: another action is already waiting for the user's \
+                             confirmation. Call '{tool_name}' again after they answer."
+                        )
+                    } else {
+                        let pending =
+                            gate.hold(session_key, sender, channel_name, &tool_name, &tool_args);
+                        held_prompt = Some(gate.describe(&pending));
+                        "Held for the user's confirmation; it runs only if their next message \
+                         confirms it."
+                            .to_string()
+                    };
+                    runtime_trace::record_event(
+                        "tool_call_held",
+                        Some(channel_name),
+                        Some(provider_name),
+                        Some(model),
+                        Some(&turn_id),
+                        Some(false),
+                        Some(&held),
+                        serde_json::json!({
+                            "iteration": iteration + 1,
+                            "tool": tool_name.clone(),
+                            "arguments": scrub_credentials(&tool_args.to_string()),
+                        }),
+                    );
+                    ordered_results[idx] = Some((
+                        tool_name.clone(),
+                        call.tool_call_id.clone(),
+                        ToolExecutionOutcome {
+                            output: held,
+                            success: false,
+                            error_reason: None,
+                            duration: Duration::ZERO,
+                        },
+                    ));
+                    continue;
+                }
+            }
 
+            let signature = tool_call_signature(&tool_name, &tool_args);
+            if !seen_tool_signatures.insert(signature) {
+                let duplicate = format!(
+                    "Skipped duplicate tool call '{tool_name}' with identical arguments in this turn."
+                );
+                runtime_trace::record_event(
+                    "tool_call_result",
+                    Some(channel_name),
+                    Some(provider_name),
+                    Some(model),
+                    Some(&turn_id),
+                    Some(false),
+                    Some(&duplicate),
+                    serde_json::json!({
+                        "iteration": iteration + 1,
+                        "tool": tool_name.clone(),
+                        "arguments": scrub_credentials(&tool_args.to_string()),
+                        "deduplicated": true,
+                    }),
+                );
+                ordered_results[idx] = Some((
+                    tool_name.clone(),
+                    call.tool_call_id.clone(),
+                    ToolExecutionOutcome {
+                        output: duplicate.clone(),
+                        success: false,
+                        error_reason: Some(duplicate),
+                        duration: Duration::ZERO,
+                    },
+                ));
+                continue;
+            }
+
+            super::turn::record_tool(&tool_name);
             runtime_trace::record_event(
                 "tool_call_start",
                 Some(channel_name),
@@ -2550,6 +2910,12 @@ pub(crate) async fn run_tool_call_loop(
                 tracing::debug!(tool = %tool_name, "Sending progress start to draft");
                 let _ = tx.send(progress).await;
             }
+            progress::emit(
+                on_progress.as_ref(),
+                TurnPhase::ToolStarted {
+                    tool: tool_name.clone(),
+                },
+            );
 
             executable_indices.push(idx);
             executable_calls.push(ParsedToolCall {
@@ -2560,19 +2926,27 @@ pub(crate) async fn run_tool_call_loop(
         }
 
         let executed_outcomes = if allow_parallel_execution && executable_calls.len() > 1 {
-            execute_tools_parallel(
-                &executable_calls,
-                tools_registry,
-                observer,
-                cancellation_token.as_ref(),
+            super::subtask::with_offered_tools(
+                Arc::clone(&offered_tools),
+                execute_tools_parallel(
+                    &executable_calls,
+                    tools_registry,
+                    excluded_tools,
+                    observer,
+                    cancellation_token.as_ref(),
+                ),
             )
             .await?
         } else {
-            execute_tools_sequential(
-                &executable_calls,
-                tools_registry,
-                observer,
-                cancellation_token.as_ref(),
+            super::subtask::with_offered_tools(
+                Arc::clone(&offered_tools),
+                execute_tools_sequential(
+                    &executable_calls,
+                    tools_registry,
+                    excluded_tools,
+                    observer,
+                    cancellation_token.as_ref(),
+                ),
             )
             .await?
         };
@@ -2669,6 +3043,53 @@ pub(crate) async fn run_tool_call_loop(
                 history.push(ChatMessage::tool(tool_msg.to_string()));
             }
         }
+
+        // Images produced by tools (screenshots) go to vision providers as
+        // image content on the next call; others keep the text description.
+        if provider.supports_vision() {
+            let (max_images, _) = multimodal_config.effective_limits();
+            let room = max_images.saturating_sub(multimodal::count_image_markers(history));
+            let markers: Vec<String> = individual_results
+                .iter()
+                .filter_map(|(_, output)| multimodal::tool_output_image(output))
+                .take(room)
+                .map(|path| format!("[IMAGE:{path}]"))
+                .collect();
+            if !markers.is_empty() {
+                history.push(ChatMessage::user(format!(
+                    "[Tool images]\n{}",
+                    markers.join("\n")
+                )));
+            }
+        }
+
+        // A held call ends the turn; the user's reply decides whether it runs.
+        if let Some(prompt) = held_prompt {
+            progress::emit(on_progress.as_ref(), TurnPhase::Composing);
+            history.push(ChatMessage::assistant(prompt.clone()));
+            return Ok(prompt);
+        }
+
+        if let Some((tool, raw_arguments)) = retry_cutoff {
+            let message = tool_repair::cutoff_message(&tool, &scrub_credentials(&raw_arguments));
+            runtime_trace::record_event(
+                "tool_call_retry_cutoff",
+                Some(channel_name),
+                Some(provider_name),
+                Some(model),
+                Some(&turn_id),
+                Some(false),
+                Some(&message),
+                serde_json::json!({
+                    "iteration": iteration + 1,
+                    "tool": tool,
+                    "failures": malformed_calls.failures(&tool),
+                }),
+            );
+            progress::emit(on_progress.as_ref(), TurnPhase::Composing);
+            history.push(ChatMessage::assistant(message.clone()));
+            return Ok(message);
+        }
     }
 
     runtime_trace::record_event(
@@ -2683,7 +3104,52 @@ pub(crate) async fn run_tool_call_loop(
             "max_iterations": max_iterations,
         }),
     );
-    anyhow::bail!("Agent exceeded maximum tool iterations ({max_iterations})")
+    Err(ToolLoopExhausted { max_iterations }.into())
+}
+
+/// Settings of a [`run_turn`].
+pub(crate) struct HeadlessTurn<'a> {
+    pub provider_name: &'a str,
+    pub model: &'a str,
+    pub temperature: f64,
+    /// Channel the run is attributed to in traces (e.g. `delegate`).
+    pub channel_name: &'a str,
+    pub multimodal_config: &'a crate::config::MultimodalConfig,
+    pub max_iterations: usize,
+}
+
+/// Run a turn over `history` with no approvals, streaming drafts, progress
+/// events or hooks, so nothing reaches a channel while it runs. Shared by
+/// agentic delegation and sub-tasks ([`super::subtask`]); callers must not
+/// hand it tools that need approval, since nobody is asked here.
+pub(crate) async fn run_turn(
+    provider: &dyn Provider,
+    history: &mut Vec<ChatMessage>,
+    tools_registry: &[Box<dyn Tool>],
+    observer: &dyn Observer,
+    turn: &HeadlessTurn<'_>,
+) -> Result<String> {
+    run_tool_call_loop(
+        provider,
+        history,
+        tools_registry,
+        observer,
+        turn.provider_name,
+        turn.model,
+        turn.temperature,
+        true,
+        None,
+        turn.channel_name,
+        turn.multimodal_config,
+        turn.max_iterations,
+        None,
+        None,
+        None,
+        None,
+        &[],
+        &PackingLimits::default(),
+    )
+    .await
 }
 
 /// Build the tool instruction block for the system prompt so the LLM knows
@@ -2731,6 +3197,7 @@ pub async fn run(
     temperature: f64,
     peripheral_overrides: Vec<String>,
     interactive: bool,
+    channel: Option<&str>,
 ) -> Result<String> {
     // ── Wire up agnostic subsystems ──────────────────────────────
     let base_observer = observability::create_observer(&config.observability);
@@ -2741,6 +3208,7 @@ pub async fn run(
         &config.autonomy,
         &config.workspace_dir,
     ));
+    redaction::install(Redactor::from_config(&config));
 
     // ── Memory (the brain) ────────────────────────────────────────
     let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage(
@@ -2808,6 +3276,11 @@ pub async fn run(
         zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
         secrets_encrypt: config.secrets.encrypt,
         reasoning_enabled: config.runtime.reasoning_enabled,
+        openrouter: config.openrouter.clone(),
+        response_cache: providers::cache::ProviderCache::from_config(
+            &config,
+            Some(Arc::clone(&observer)),
+        ),
     };
 
     let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
@@ -2856,6 +3329,10 @@ pub async fn run(
             "file_read",
             "Read file contents. Use when: inspecting project files, configs, logs. Don't use when: a targeted search is enough.",
         ),
+        (
+            "list_dir",
+            "List directory entries, optionally recursive with a glob filter and size/mtime metadata. Use when: exploring project layout or locating files. Don't use when: you already know the exact path.",
+        ),
         (
             "file_write",
             "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
@@ -2893,7 +3370,7 @@ pub async fn run(
     tool_descs.push(("cron_runs", "Show recent run history for a cron job."));
     tool_descs.push((
         "screenshot",
-        "Capture a screenshot of the current screen. Returns file path and base64-encoded PNG. Use when: visual verification, UI inspection, debugging displays.",
+        "Capture a screenshot of the current screen. Returns a downscaled image attachment (path, size, dimensions); vision models also see the image. Use when: visual verification, UI inspection, debugging displays.",
     ));
     tool_descs.push((
         "image_info",
@@ -2976,6 +3453,8 @@ pub async fn run(
     if !native_tools {
         system_prompt.push_str(&build_tool_instructions(&tools_registry));
     }
+    let (system_prompt, temperature) =
+        apply_channel_override(&config, channel, system_prompt, temperature);
 
     // ── Approval manager (supervised mode) ───────────────────────
     let approval_manager = if interactive {
@@ -2983,7 +3462,7 @@ pub async fn run(
     } else {
         None
     };
-    let channel_name = if interactive { "cli" } else { "daemon" };
+    let channel_name = channel.unwrap_or(if interactive { "cli" } else { "daemon" });
 
     // ── Execute ──────────────────────────────────────────────────
     let start = Instant::now();
@@ -3036,7 +3515,9 @@ pub async fn run(
             None,
             None,
             None,
+            None,
             &[],
+            &PackingLimits::from_config(&config.agent),
         )
         .await?;
         final_output = response.clone();
@@ -3158,7 +3639,9 @@ pub async fn run(
                 None,
                 None,
                 None,
+                None,
                 &[],
+                &PackingLimits::from_config(&config.agent),
             )
             .await
             {
@@ -3266,6 +3749,11 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
         secrets_encrypt: config.secrets.encrypt,
         reasoning_enabled: config.runtime.reasoning_enabled,
+        openrouter: config.openrouter.clone(),
+        response_cache: providers::cache::ProviderCache::from_config(
+            &config,
+            Some(Arc::clone(&observer)),
+        ),
     };
     let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
         provider_name,
@@ -3390,6 +3878,7 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         true,
         &config.multimodal,
         config.agent.max_tool_iterations,
+        &PackingLimits::from_config(&config.agent),
     )
     .await
 }
@@ -3521,6 +4010,11 @@ mod tests {
             self.capabilities.native_tool_calling = true;
             self
         }
+
+        fn with_vision(mut self) -> Self {
+            self.capabilities.vision = true;
+            self
+        }
     }
 
     #[async_trait]
@@ -3555,6 +4049,94 @@ mod tests {
         }
     }
 
+    /// Rejects any request carrying a message longer than `max_chars` with a
+    /// typed context-window error.
+    struct ContextLimitedProvider {
+        max_chars: usize,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Provider for ContextLimitedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            anyhow::bail!("chat_with_system should not be used in context limit tests");
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if request
+                .messages
+                .iter()
+                .any(|m| m.content.chars().count() > self.max_chars)
+            {
+                return Err(crate::providers::ProviderError::ContextTooLong {
+                    message: "maximum context length exceeded".to_string(),
+                }
+                .into());
+            }
+            Ok(ChatResponse {
+                text: Some("done".to_string()),
+                tool_calls: Vec::new(),
+                usage: None,
+                reasoning_content: None,
+            })
+        }
+    }
+
+    fn history_with_large_old_tool_result() -> Vec<ChatMessage> {
+        vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("read the log"),
+            ChatMessage::assistant("<tool_call>{}</tool_call>"),
+            ChatMessage::user(format!("[Tool results]\n{}", "x".repeat(50_000))),
+            ChatMessage::assistant("the log is long"),
+            ChatMessage::user("summarize it"),
+        ]
+    }
+
+    async fn run_context_limited_loop(
+        provider: &ContextLimitedProvider,
+        history: &mut Vec<ChatMessage>,
+    ) -> anyhow::Result<String> {
+        let unpacked = PackingLimits {
+            tool_result_chars: 0,
+            max_context_tokens: 0,
+            context_window: 0,
+        };
+        run_tool_call_loop(
+            provider,
+            history,
+            &[],
+            &NoopObserver,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "cli",
+            &crate::config::MultimodalConfig::default(),
+            2,
+            None,
+            None,
+            None,
+            None,
+            &[],
+            &unpacked,
+        )
+        .await
+    }
+
     struct CountingTool {
         name: String,
         invocations: Arc<AtomicUsize>,
@@ -3702,7 +4284,9 @@ mod tests {
             None,
             None,
             None,
+            None,
             &[],
+            &PackingLimits::default(),
         )
         .await
         .expect_err("provider without vision support should fail");
@@ -3730,6 +4314,7 @@ mod tests {
             max_images: 4,
             max_image_size_mb: 1,
             allow_remote_fetch: false,
+            ..crate::config::MultimodalConfig::default()
         };
 
         let err = run_tool_call_loop(
@@ -3748,7 +4333,9 @@ mod tests {
             None,
             None,
             None,
+            None,
             &[],
+            &PackingLimits::default(),
         )
         .await
         .expect_err("oversized payload must fail");
@@ -3788,7 +4375,9 @@ mod tests {
             None,
             None,
             None,
+            None,
             &[],
+            &PackingLimits::default(),
         )
         .await
         .expect("valid multimodal payload should pass");
@@ -3797,6 +4386,55 @@ mod tests {
         assert_eq!(calls.load(Ordering::SeqCst), 1);
     }
 
+    #[tokio::test]
+    async fn run_tool_call_loop_refuses_calls_to_excluded_tools() {
+        // The model names the tool even though it was not offered.
+        let provider = ScriptedProvider::from_text_responses(vec![
+            r#"<tool_call>
+{"name":"count_tool","arguments":{"value":"X"}}
+</tool_call>"#,
+            "done",
+        ]);
+        let invocations = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(CountingTool::new(
+            "count_tool",
+            Arc::clone(&invocations),
+        ))];
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("count something"),
+        ];
+
+        let result = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &NoopObserver,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "telegram",
+            &crate::config::MultimodalConfig::default(),
+            4,
+            None,
+            None,
+            None,
+            None,
+            &["count_tool".to_string()],
+            &PackingLimits::default(),
+        )
+        .await
+        .expect("loop should finish after the refused call");
+
+        assert_eq!(result, "done");
+        assert_eq!(invocations.load(Ordering::SeqCst), 0);
+        assert!(history
+            .iter()
+            .any(|msg| msg.content.contains("Tool 'count_tool' is not available")));
+    }
+
     #[test]
     fn should_execute_tools_in_parallel_returns_false_for_single_call() {
         let calls = vec![ParsedToolCall {
@@ -3857,6 +4495,42 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn run_tool_call_loop_retries_once_with_emergency_packing() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = ContextLimitedProvider {
+            max_chars: 10_000,
+            calls: Arc::clone(&calls),
+        };
+        let mut history = history_with_large_old_tool_result();
+
+        let result = run_context_limited_loop(&provider, &mut history)
+            .await
+            .expect("emergency packing should let the retry through");
+
+        assert_eq!(result, "done");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        // Packing only shapes the request; the loop history keeps the output.
+        assert!(history[3].content.len() > 50_000);
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_reports_second_context_rejection() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = ContextLimitedProvider {
+            max_chars: 5,
+            calls: Arc::clone(&calls),
+        };
+        let mut history = history_with_large_old_tool_result();
+
+        let err = run_context_limited_loop(&provider, &mut history)
+            .await
+            .expect_err("a request that cannot shrink enough should fail");
+
+        assert!(crate::providers::reliable::is_context_window_exceeded(&err));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
     #[tokio::test]
     async fn run_tool_call_loop_executes_multiple_tools_with_ordered_results() {
         let provider = ScriptedProvider::from_text_responses(vec![
@@ -3914,7 +4588,9 @@ mod tests {
             None,
             None,
             None,
+            None,
             &[],
+            &PackingLimits::default(),
         )
         .await
         .expect("parallel execution should complete");
@@ -3943,6 +4619,94 @@ mod tests {
         );
     }
 
+    struct ImageTool {
+        path: std::path::PathBuf,
+    }
+
+    #[async_trait]
+    impl Tool for ImageTool {
+        fn name(&self) -> &str {
+            "snap"
+        }
+
+        fn description(&self) -> &str {
+            "Returns an image description like the screenshot tool"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+
+        async fn execute(
+            &self,
+            _args: serde_json::Value,
+        ) -> anyhow::Result<crate::tools::ToolResult> {
+            Ok(crate::tools::ToolResult {
+                success: true,
+                output: serde_json::json!({"image_path": self.path, "width": 1}).to_string(),
+                error: None,
+            })
+        }
+    }
+
+    async fn run_image_tool_loop(provider: &ScriptedProvider) -> Vec<ChatMessage> {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("shot.png");
+        std::fs::write(&path, [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n']).unwrap();
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(ImageTool { path })];
+        let mut history = vec![ChatMessage::user("what is on screen?")];
+
+        let result = run_tool_call_loop(
+            provider,
+            &mut history,
+            &tools_registry,
+            &NoopObserver,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "cli",
+            &crate::config::MultimodalConfig::default(),
+            3,
+            None,
+            None,
+            None,
+            None,
+            &[],
+            &PackingLimits::default(),
+        )
+        .await
+        .expect("tool image flow should succeed");
+        assert_eq!(result, "done");
+        history
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_attaches_tool_images_for_vision_providers() {
+        let responses = vec![
+            "<tool_call>\n{\"name\":\"snap\",\"arguments\":{}}\n</tool_call>",
+            "done",
+        ];
+
+        let history = run_image_tool_loop(
+            &ScriptedProvider::from_text_responses(responses.clone()).with_vision(),
+        )
+        .await;
+        let images = history
+            .iter()
+            .find(|msg| msg.role == "user" && msg.content.starts_with("[Tool images]"))
+            .expect("vision provider should receive the tool image");
+        assert!(images.content.contains("[IMAGE:"));
+        assert!(images.content.contains("shot.png"));
+
+        let history = run_image_tool_loop(&ScriptedProvider::from_text_responses(responses)).await;
+        assert!(history
+            .iter()
+            .all(|msg| !msg.content.starts_with("[Tool images]")));
+        assert_eq!(crate::multimodal::count_image_markers(&history), 0);
+    }
+
     #[tokio::test]
     async fn run_tool_call_loop_deduplicates_repeated_tool_calls() {
         let provider = ScriptedProvider::from_text_responses(vec![
@@ -3983,7 +4747,9 @@ mod tests {
             None,
             None,
             None,
+            None,
             &[],
+            &PackingLimits::default(),
         )
         .await
         .expect("loop should finish after deduplicating repeated calls");
@@ -4003,6 +4769,208 @@ mod tests {
         assert!(tool_results.content.contains("Skipped duplicate tool call"));
     }
 
+    /// One channel turn of `telegram_confirm_loop` against scripted replies.
+    async fn confirm_turn(
+        history: &mut Vec<ChatMessage>,
+        tools_registry: &[Box<dyn Tool>],
+        responses: Vec<&str>,
+    ) -> String {
+        let provider = ScriptedProvider::from_text_responses(responses);
+        let ctx = crate::sessions::SessionContext {
+            session_key: "telegram_confirm_loop".into(),
+            channel: "telegram".into(),
+            reply_target: "confirm_loop".into(),
+            thread_ts: None,
+            sender: "confirm_loop".into(),
+        };
+        crate::sessions::with_session(
+            ctx,
+            run_tool_call_loop(
+                &provider,
+                history,
+                tools_registry,
+                &NoopObserver,
+                "mock-provider",
+                "mock-model",
+                0.0,
+                true,
+                None,
+                "telegram",
+                &crate::config::MultimodalConfig::default(),
+                4,
+                None,
+                None,
+                None,
+                None,
+                &[],
+                &PackingLimits::default(),
+            ),
+        )
+        .await
+        .expect("turn should finish")
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_holds_flagged_tools_until_the_user_confirms() {
+        use crate::approval::confirm::{self, CONFIRMATION_TTL};
+
+        let _installed = confirm::TEST_INSTALL_LOCK.lock().await;
+        let tmp = tempfile::TempDir::new().unwrap();
+        confirm::install(Some(ConfirmationGate::new(
+            &["guarded_tool".into()],
+            CONFIRMATION_TTL,
+            tmp.path(),
+        )));
+        let guarded = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![
+            Box::new(CountingTool::new("guarded_tool", Arc::clone(&guarded))),
+            Box::new(CountingTool::new("count_tool", Arc::clone(&counted))),
+        ];
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("clean up"),
+        ];
+        let prompt = confirm_turn(
+            &mut history,
+            &tools_registry,
+            vec![
+                r#"<tool_call>
+{"name":"guarded_tool","arguments":{"value":"A"}}
+</tool_call>
+<tool_call>
+{"name":"count_tool","arguments":{"value":"B"}}
+</tool_call>"#,
+            ],
+        )
+        .await;
+        assert!(
+            prompt.starts_with("Waiting for your confirmation"),
+            "{prompt}"
+        );
+        assert_eq!(guarded.load(Ordering::SeqCst), 0);
+        assert_eq!(
+            counted.load(Ordering::SeqCst),
+            1,
+            "unflagged tools still run"
+        );
+
+        history.push(ChatMessage::user("yes"));
+        assert_eq!(
+            confirm_turn(&mut history, &tools_registry, vec!["done"]).await,
+            "done"
+        );
+        assert_eq!(guarded.load(Ordering::SeqCst), 1);
+        let confirmed = history.iter().rev().find(|msg| msg.role == "user").unwrap();
+        assert!(
+            confirmed.content.contains("counted:A"),
+            "{}",
+            confirmed.content
+        );
+
+        history.push(ChatMessage::user("again"));
+        confirm_turn(
+            &mut history,
+            &tools_registry,
+            vec![
+                r#"<tool_call>
+{"name":"guarded_tool","arguments":{"value":"C"}}
+</tool_call>"#,
+            ],
+        )
+        .await;
+        history.push(ChatMessage::user("no, leave it"));
+        assert_eq!(
+            confirm_turn(&mut history, &tools_registry, vec!["ok"]).await,
+            "ok"
+        );
+        assert_eq!(
+            guarded.load(Ordering::SeqCst),
+            1,
+            "denied call must not run"
+        );
+        let denied = history.iter().rev().find(|msg| msg.role == "user").unwrap();
+        assert!(denied.content.contains("did not run"), "{}", denied.content);
+        confirm::install(None);
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_emits_progress_in_turn_order() {
+        let provider = ScriptedProvider::from_text_responses(vec![
+            r#"<tool_call>
+{"name":"count_a","arguments":{"value":"1"}}
+</tool_call>"#,
+            r#"<tool_call>
+{"name":"count_b","arguments":{"value":"2"}}
+</tool_call>"#,
+            "done",
+        ]);
+        let invocations = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![
+            Box::new(CountingTool::new("count_a", Arc::clone(&invocations))),
+            Box::new(CountingTool::new("count_b", Arc::clone(&invocations))),
+        ];
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("run tool calls"),
+        ];
+        let observer = NoopObserver;
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::channel(16);
+
+        let result = crate::sessions::with_session(
+            crate::sessions::SessionContext {
+                session_key: "telegram_alice".into(),
+                channel: "telegram".into(),
+                reply_target: "42".into(),
+                thread_ts: None,
+                sender: "alice".into(),
+            },
+            run_tool_call_loop(
+                &provider,
+                &mut history,
+                &tools_registry,
+                &observer,
+                "mock-provider",
+                "mock-model",
+                0.0,
+                true,
+                None,
+                "telegram",
+                &crate::config::MultimodalConfig::default(),
+                4,
+                None,
+                None,
+                Some(progress_tx),
+                None,
+                &[],
+                &PackingLimits::default(),
+            ),
+        )
+        .await
+        .expect("loop should finish");
+        assert_eq!(result, "done");
+        assert_eq!(invocations.load(Ordering::SeqCst), 2);
+
+        let mut phases = Vec::new();
+        while let Some(event) = progress_rx.recv().await {
+            assert_eq!(event.session_key.as_deref(), Some("telegram_alice"));
+            phases.push(event.phase);
+        }
+        assert_eq!(
+            phases,
+            vec![
+                TurnPhase::Started,
+                TurnPhase::ToolStarted {
+                    tool: "count_a".into()
+                },
+                TurnPhase::ToolStarted {
+                    tool: "count_b".into()
+                },
+                TurnPhase::Composing,
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn run_tool_call_loop_native_mode_preserves_fallback_tool_call_ids() {
         let provider = ScriptedProvider::from_text_responses(vec![
@@ -4039,7 +5007,9 @@ mod tests {
             None,
             None,
             None,
+            None,
             &[],
+            &PackingLimits::default(),
         )
         .await
         .expect("native fallback id flow should complete");
@@ -4060,6 +5030,127 @@ mod tests {
         );
     }
 
+    fn native_tool_call_response(name: &str, arguments: &str) -> ChatResponse {
+        ChatResponse {
+            text: Some(String::new()),
+            tool_calls: vec![ToolCall {
+                id: format!("call_{name}"),
+                name: name.to_string(),
+                arguments: arguments.to_string(),
+            }],
+            usage: None,
+            reasoning_content: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_repairs_near_json_native_arguments() {
+        let provider = ScriptedProvider {
+            responses: Arc::new(Mutex::new(VecDeque::from([
+                native_tool_call_response("count_tool", "```json\n{'value': 'A',}\n```"),
+                ChatResponse {
+                    text: Some("done".into()),
+                    tool_calls: Vec::new(),
+                    usage: None,
+                    reasoning_content: None,
+                },
+            ]))),
+            capabilities: ProviderCapabilities::default(),
+        }
+        .with_native_tool_support();
+        let invocations = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(CountingTool::new(
+            "count_tool",
+            Arc::clone(&invocations),
+        ))];
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("run tool calls"),
+        ];
+
+        let result = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &NoopObserver,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "cli",
+            &crate::config::MultimodalConfig::default(),
+            4,
+            None,
+            None,
+            None,
+            None,
+            &[],
+            &PackingLimits::default(),
+        )
+        .await
+        .expect("repaired call should run");
+
+        assert_eq!(result, "done");
+        assert_eq!(invocations.load(Ordering::SeqCst), 1);
+        assert!(history
+            .iter()
+            .any(|msg| msg.role == "tool" && msg.content.contains("counted:A")));
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_stops_after_repeated_unknown_tool_calls() {
+        let provider = ScriptedProvider {
+            responses: Arc::new(Mutex::new(VecDeque::from([
+                native_tool_call_response("count_tol", r#"{"value": "A"}"#),
+                native_tool_call_response("count_tol", r#"{"value": "B"}"#),
+                native_tool_call_response("count_tol", r#"{"value": "C"}"#),
+            ]))),
+            capabilities: ProviderCapabilities::default(),
+        }
+        .with_native_tool_support();
+        let invocations = Arc::new(AtomicUsize::new(0));
+        let tools_registry: Vec<Box<dyn Tool>> = vec![Box::new(CountingTool::new(
+            "count_tool",
+            Arc::clone(&invocations),
+        ))];
+        let mut history = vec![
+            ChatMessage::system("test-system"),
+            ChatMessage::user("run tool calls"),
+        ];
+
+        let result = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools_registry,
+            &NoopObserver,
+            "mock-provider",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "cli",
+            &crate::config::MultimodalConfig::default(),
+            10,
+            None,
+            None,
+            None,
+            None,
+            &[],
+            &PackingLimits::default(),
+        )
+        .await
+        .expect("loop should stop with a user-facing message");
+
+        assert_eq!(invocations.load(Ordering::SeqCst), 0);
+        assert!(result.contains("`count_tol`"), "{result}");
+        assert!(result.contains(r#"{"value": "B"}"#), "{result}");
+        assert!(history
+            .iter()
+            .any(|msg| msg.role == "tool" && msg.content.contains("Did you mean 'count_tool'?")));
+        assert_eq!(history.last().unwrap().content, result);
+    }
+
     #[test]
     fn parse_tool_calls_extracts_single_call() {
         let response = r#"Let me check that.
@@ -4673,6 +5764,42 @@ Tail"#;
         assert_eq!(history.len(), 3);
     }
 
+    #[test]
+    fn scheduled_runs_get_their_channel_fragment() {
+        let mut config = Config::default();
+        config.agent.channel_overrides.insert(
+            "cron".into(),
+            crate::config::ChannelOverrideConfig {
+                system_prompt_suffix: Some("Report results; do not address the user.".into()),
+                temperature: Some(0.1),
+                ..crate::config::ChannelOverrideConfig::default()
+            },
+        );
+        config.agent.channel_overrides.insert(
+            "heartbeat".into(),
+            crate::config::ChannelOverrideConfig {
+                system_prompt_suffix: Some("This is a periodic check.".into()),
+                ..crate::config::ChannelOverrideConfig::default()
+            },
+        );
+
+        let (prompt, temperature) =
+            apply_channel_override(&config, Some("cron"), "base".into(), 0.7);
+        assert!(prompt
+            .ends_with("## Channel Instructions\n\nReport results; do not address the user.\n"));
+        assert_eq!(temperature, 0.1);
+
+        let (prompt, temperature) =
+            apply_channel_override(&config, Some("heartbeat"), "base".into(), 0.7);
+        assert!(prompt.contains("This is a periodic check."));
+        assert!(!prompt.contains("do not address the user"));
+        assert_eq!(temperature, 0.7);
+
+        let (prompt, temperature) = apply_channel_override(&config, None, "base".into(), 0.9);
+        assert_eq!(prompt, "base");
+        assert_eq!(temperature, 0.9);
+    }
+
     #[test]
     fn build_compaction_transcript_formats_roles() {
         let messages = vec![
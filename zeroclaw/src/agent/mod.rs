@@ -1,10 +1,17 @@
 #[allow(clippy::module_inception)]
 pub mod agent;
+pub mod citations;
 pub mod classifier;
+pub mod context_usage;
 pub mod dispatcher;
 pub mod loop_;
 pub mod memory_loader;
+pub mod packing;
+pub mod progress;
 pub mod prompt;
+pub mod subtask;
+pub mod tool_repair;
+pub mod turn;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,365 @@
+//! Context packing for the tool-call loop.
+//!
+//! Every loop iteration re-sends the whole conversation, so one large tool
+//! output is paid for again on each later iteration and can push the request
+//! past the model's context window. Before each provider call,
+//! `run_tool_call_loop` runs the history through [`pack_history`]:
+//!
+//! 1. Tool results from before the latest iteration are cut to
+//!    [`PackingLimits::tool_result_chars`]; the latest results stay verbatim.
+//! 2. If the estimated token count still exceeds
+//!    [`PackingLimits::max_context_tokens`], whole tool exchanges (the
+//!    assistant tool-call message plus its results) are dropped, oldest first.
+//!    System messages, the user's request and the latest exchange are never
+//!    dropped.
+//!
+//! Packing only affects what is sent; the loop's own history keeps the full
+//! tool output.
+//!
+//! When the provider still rejects a request as too long, the loop repacks
+//! once with [`PackingLimits::emergency`] and retries.
+
+use crate::config::AgentConfig;
+use crate::providers::ChatMessage;
+
+/// Prefix of the user message carrying tool results in prompt-guided mode.
+const PROMPT_TOOL_RESULTS_PREFIX: &str = "[Tool results]";
+/// Rough per-message overhead (role, separators) in tokens.
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+/// Per-result cap used after the provider rejected a request as too long.
+const EMERGENCY_TOOL_RESULT_CHARS: usize = 1_000;
+
+/// Caps applied by [`pack_history`]; `0` disables the respective step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackingLimits {
+    /// Characters kept from each tool result older than the latest iteration.
+    pub tool_result_chars: usize,
+    /// Estimated token ceiling for one provider request.
+    pub max_context_tokens: usize,
+    /// Configured context window of the model (`agent.context_window`);
+    /// `0` looks the model up. Not used for packing; carried to the loop for
+    /// [`context_usage`](super::context_usage) reporting.
+    pub context_window: usize,
+}
+
+impl PackingLimits {
+    pub fn from_config(config: &AgentConfig) -> Self {
+        Self {
+            tool_result_chars: config.tool_result_pack_chars,
+            max_context_tokens: config.max_context_tokens,
+            context_window: config.context_window,
+        }
+    }
+
+    /// Tighter limits after the provider rejected `rejected` as exceeding
+    /// its context window: results capped harder and a ceiling of half the
+    /// rejected request's estimated size.
+    pub fn emergency(&self, rejected: &[ChatMessage]) -> Self {
+        let tool_result_chars = match self.tool_result_chars {
+            0 => EMERGENCY_TOOL_RESULT_CHARS,
+            chars => chars.min(EMERGENCY_TOOL_RESULT_CHARS),
+        };
+        Self {
+            tool_result_chars,
+            max_context_tokens: (estimate_tokens(rejected) / 2).max(1),
+            context_window: self.context_window,
+        }
+    }
+}
+
+impl Default for PackingLimits {
+    fn default() -> Self {
+        Self::from_config(&AgentConfig::default())
+    }
+}
+
+/// What [`pack_history`] changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PackingStats {
+    /// Tool results that were cut to the per-result cap.
+    pub truncated_results: usize,
+    /// Messages dropped to fit the token ceiling.
+    pub dropped_messages: usize,
+}
+
+impl PackingStats {
+    pub fn is_noop(&self) -> bool {
+        self.truncated_results == 0 && self.dropped_messages == 0
+    }
+}
+
+/// Approximate token count of `messages` (about four characters per token).
+pub fn estimate_tokens(messages: &[ChatMessage]) -> usize {
+    messages
+        .iter()
+        .map(|message| message.content.chars().count().div_ceil(4) + MESSAGE_OVERHEAD_TOKENS)
+        .sum()
+}
+
+/// Build the message list to send for the next provider call.
+pub fn pack_history(
+    history: &[ChatMessage],
+    limits: &PackingLimits,
+) -> (Vec<ChatMessage>, PackingStats) {
+    let mut stats = PackingStats::default();
+    let mut messages = history.to_vec();
+
+    // Results after the last assistant message belong to the iteration that
+    // just ran and are what the model is about to read; keep them intact.
+    let latest_assistant = messages.iter().rposition(|m| m.role == "assistant");
+    if limits.tool_result_chars > 0 {
+        if let Some(boundary) = latest_assistant {
+            for message in &mut messages[..boundary] {
+                if is_tool_result(message)
+                    && truncate_tool_result(message, limits.tool_result_chars)
+                {
+                    stats.truncated_results += 1;
+                }
+            }
+        }
+    }
+
+    if limits.max_context_tokens > 0 {
+        let mut tokens = estimate_tokens(&messages);
+        while tokens > limits.max_context_tokens {
+            let Some(range) = oldest_droppable_exchange(&messages) else {
+                break;
+            };
+            tokens -= estimate_tokens(&messages[range.clone()]);
+            stats.dropped_messages += range.len();
+            messages.drain(range);
+        }
+    }
+
+    (messages, stats)
+}
+
+fn is_tool_result(message: &ChatMessage) -> bool {
+    message.role == "tool"
+        || (message.role == "user" && message.content.starts_with(PROMPT_TOOL_RESULTS_PREFIX))
+}
+
+/// Cut a tool result to `max_chars`; returns whether anything was removed.
+///
+/// Native results are JSON (`{"tool_call_id", "content"}`); only the content
+/// is cut so the call id stays valid.
+fn truncate_tool_result(message: &mut ChatMessage, max_chars: usize) -> bool {
+    if message.role == "tool" {
+        if let Ok(mut value) = serde_json::from_str::<serde_json::Value>(&message.content) {
+            if let Some(content) = value.get("content").and_then(serde_json::Value::as_str) {
+                let Some(cut) = truncate_with_note(content, max_chars) else {
+                    return false;
+                };
+                value["content"] = serde_json::Value::String(cut);
+                message.content = value.to_string();
+                return true;
+            }
+        }
+    }
+    match truncate_with_note(&message.content, max_chars) {
+        Some(cut) => {
+            message.content = cut;
+            true
+        }
+        None => false,
+    }
+}
+
+fn truncate_with_note(text: &str, max_chars: usize) -> Option<String> {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return None;
+    }
+    let kept: String = text.chars().take(max_chars).collect();
+    Some(format!(
+        "{kept}\n[truncated {} of {total} chars from an earlier tool result; re-run the tool if the full output is needed]",
+        total - max_chars
+    ))
+}
+
+/// Index range of the oldest tool exchange (an assistant message plus the
+/// tool results that follow it). The newest exchange is never returned, and
+/// user and system messages are never part of an exchange.
+fn oldest_droppable_exchange(messages: &[ChatMessage]) -> Option<std::ops::Range<usize>> {
+    let exchanges: Vec<std::ops::Range<usize>> = (0..messages.len())
+        .filter(|&start| messages[start].role == "assistant")
+        .filter_map(|start| {
+            let end = messages[start + 1..]
+                .iter()
+                .position(|m| !is_tool_result(m))
+                .map_or(messages.len(), |offset| start + 1 + offset);
+            (end > start + 1).then_some(start..end)
+        })
+        .collect();
+    // The newest exchange holds the results the model is working from.
+    if exchanges.len() < 2 {
+        return None;
+    }
+    exchanges.into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn native_result(id: &str, content: &str) -> ChatMessage {
+        ChatMessage::tool(serde_json::json!({ "tool_call_id": id, "content": content }).to_string())
+    }
+
+    fn tool_call(id: &str) -> ChatMessage {
+        ChatMessage::assistant(
+            serde_json::json!({ "content": "", "tool_calls": [{ "id": id, "name": "shell", "arguments": "{}" }] })
+                .to_string(),
+        )
+    }
+
+    fn contents(messages: &[ChatMessage]) -> Vec<(&str, &str)> {
+        messages
+            .iter()
+            .map(|m| (m.role.as_str(), m.content.as_str()))
+            .collect()
+    }
+
+    fn result_content(message: &ChatMessage) -> String {
+        let value: serde_json::Value = serde_json::from_str(&message.content).unwrap();
+        value["content"].as_str().unwrap().to_string()
+    }
+
+    /// system, user, then `rounds` tool exchanges with `size`-char outputs.
+    fn oversized_conversation(rounds: usize, size: usize) -> Vec<ChatMessage> {
+        let mut history = vec![
+            ChatMessage::system("system prompt"),
+            ChatMessage::user("analyze the logs"),
+        ];
+        for round in 0..rounds {
+            let id = format!("call_{round}");
+            history.push(tool_call(&id));
+            history.push(native_result(&id, &"x".repeat(size)));
+        }
+        history
+    }
+
+    #[test]
+    fn older_results_are_capped_and_latest_kept_verbatim() {
+        let history = oversized_conversation(3, 64 * 1024);
+        let limits = PackingLimits {
+            tool_result_chars: 2048,
+            max_context_tokens: 0,
+            context_window: 0,
+        };
+
+        let (packed, stats) = pack_history(&history, &limits);
+
+        assert_eq!(packed.len(), history.len());
+        assert_eq!(stats.truncated_results, 2);
+        assert_eq!(stats.dropped_messages, 0);
+        for older in [&packed[3], &packed[5]] {
+            let content = result_content(older);
+            assert!(content.starts_with(&"x".repeat(2048)));
+            assert!(content.contains("[truncated 63488 of 65536 chars"));
+            assert!(content.chars().count() < 2048 + 200);
+        }
+        assert_eq!(packed[7].content, history[7].content);
+        // Call ids survive truncation.
+        let value: serde_json::Value = serde_json::from_str(&packed[3].content).unwrap();
+        assert_eq!(value["tool_call_id"], "call_0");
+    }
+
+    #[test]
+    fn oldest_exchanges_are_dropped_to_fit_the_ceiling() {
+        let history = oversized_conversation(5, 8_000);
+        let limits = PackingLimits {
+            tool_result_chars: 0,
+            max_context_tokens: 5_000,
+            context_window: 0,
+        };
+
+        let (packed, stats) = pack_history(&history, &limits);
+
+        assert!(estimate_tokens(&packed) <= 5_000);
+        assert_eq!(stats.dropped_messages, 6);
+        assert_eq!(packed[0].role, "system");
+        assert_eq!(packed[1].content, "analyze the logs");
+        let ids: Vec<String> = packed[2..]
+            .iter()
+            .filter(|m| m.role == "tool")
+            .map(|m| {
+                serde_json::from_str::<serde_json::Value>(&m.content).unwrap()["tool_call_id"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(ids, vec!["call_3", "call_4"]);
+        // Every remaining result still follows its tool-call message.
+        for (i, message) in packed.iter().enumerate() {
+            if message.role == "tool" {
+                assert_eq!(packed[i - 1].role, "assistant");
+            }
+        }
+    }
+
+    #[test]
+    fn never_drops_system_request_or_latest_exchange() {
+        let history = oversized_conversation(1, 100_000);
+        let limits = PackingLimits {
+            tool_result_chars: 1_000,
+            max_context_tokens: 10,
+            context_window: 0,
+        };
+
+        let (packed, stats) = pack_history(&history, &limits);
+
+        assert_eq!(contents(&packed), contents(&history));
+        assert!(stats.is_noop());
+    }
+
+    #[test]
+    fn prompt_mode_results_are_capped_and_small_history_untouched() {
+        let history = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user("do it"),
+            ChatMessage::assistant("<tool_call>{}</tool_call>"),
+            ChatMessage::user(format!("[Tool results]\n{}", "y".repeat(5_000))),
+            ChatMessage::assistant("<tool_call>{}</tool_call>"),
+            ChatMessage::user("[Tool results]\nshort"),
+        ];
+        let (packed, stats) = pack_history(&history, &PackingLimits::default());
+        assert_eq!(stats.truncated_results, 1);
+        assert!(packed[3].content.starts_with("[Tool results]\ny"));
+        assert!(packed[3].content.contains("[truncated "));
+        assert_eq!(packed[5].content, history[5].content);
+
+        let small = oversized_conversation(2, 10);
+        let (packed, stats) = pack_history(&small, &PackingLimits::default());
+        assert_eq!(contents(&packed), contents(&small));
+        assert!(stats.is_noop());
+    }
+
+    #[test]
+    fn emergency_limits_shrink_a_rejected_request() {
+        let history = oversized_conversation(4, 6_000);
+        let (sent, _) = pack_history(&history, &PackingLimits::default());
+        let limits = PackingLimits::default().emergency(&sent);
+        assert!(limits.tool_result_chars <= EMERGENCY_TOOL_RESULT_CHARS);
+        assert_eq!(limits.max_context_tokens, estimate_tokens(&sent) / 2);
+
+        let (repacked, stats) = pack_history(&history, &limits);
+        assert!(!stats.is_noop());
+        assert!(estimate_tokens(&repacked) < estimate_tokens(&sent));
+        assert_eq!(
+            repacked.last().unwrap().content,
+            history.last().unwrap().content
+        );
+
+        let disabled = PackingLimits {
+            tool_result_chars: 0,
+            max_context_tokens: 0,
+            context_window: 0,
+        };
+        assert_eq!(
+            disabled.emergency(&sent).tool_result_chars,
+            EMERGENCY_TOOL_RESULT_CHARS
+        );
+    }
+}
@@ -0,0 +1,47 @@
+//! Structured turn progress for channel adapters.
+//!
+//! `run_tool_call_loop` publishes [`TurnProgress`] events so a channel can
+//! keep the user informed during long multi-tool turns (typing indicators,
+//! an editable "running shell…" status message). Unlike the `on_delta`
+//! draft stream these events carry no text to display verbatim; adapters
+//! decide how to render them.
+
+use tokio::sync::mpsc;
+
+/// Where an agent turn currently is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TurnPhase {
+    /// The turn started; the first LLM request is about to be sent.
+    Started,
+    /// A tool is about to be executed.
+    ToolStarted { tool: String },
+    /// The LLM produced its final answer and the reply is being composed.
+    Composing,
+}
+
+/// A progress event for one turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TurnProgress {
+    /// History key of the conversation, when the turn runs inside a channel session.
+    pub session_key: Option<String>,
+    pub phase: TurnPhase,
+}
+
+/// Capacity of the per-turn progress channel. Progress is best-effort: when
+/// the adapter falls behind, further events are dropped instead of stalling
+/// the turn.
+pub const PROGRESS_CHANNEL_CAPACITY: usize = 32;
+
+pub(crate) fn emit(tx: Option<&mpsc::Sender<TurnProgress>>, phase: TurnPhase) {
+    if let Some(tx) = tx {
+        let _ = tx.try_send(TurnProgress {
+            session_key: crate::sessions::current_session().map(|ctx| ctx.session_key),
+            phase,
+        });
+    }
+}
+
+/// Status line shown while a tool runs, e.g. `⏳ running shell…`.
+pub fn status_text(tool: &str) -> String {
+    format!("\u{23f3} running {tool}\u{2026}")
+}
@@ -105,10 +105,13 @@ impl PromptSection for IdentitySection {
             "USER.md",
             "HEARTBEAT.md",
             "BOOTSTRAP.md",
-            "MEMORY.md",
         ] {
             inject_workspace_file(&mut prompt, ctx.workspace_dir, file);
         }
+        match crate::memory::notes::prompt_digest(ctx.workspace_dir) {
+            Some(digest) => prompt.push_str(&digest),
+            None => inject_workspace_file(&mut prompt, ctx.workspace_dir, "MEMORY.md"),
+        }
 
         Ok(prompt)
     }
@@ -352,6 +355,7 @@ mod tests {
                 args: std::collections::HashMap::new(),
             }],
             prompts: vec!["Run smoke tests before deploy.".into()],
+            permissions: crate::skills::SkillPermissions::default(),
             location: None,
         }];
 
@@ -390,6 +394,7 @@ mod tests {
                 args: std::collections::HashMap::new(),
             }],
             prompts: vec!["Run smoke tests before deploy.".into()],
+            permissions: crate::skills::SkillPermissions::default(),
             location: Some(Path::new("/tmp/workspace/skills/deploy/SKILL.md").to_path_buf()),
         }];
 
@@ -450,6 +455,7 @@ mod tests {
                 args: std::collections::HashMap::new(),
             }],
             prompts: vec!["Use <tool_call> and & keep output \"safe\"".into()],
+            permissions: crate::skills::SkillPermissions::default(),
             location: None,
         }];
         let ctx = PromptContext {
@@ -0,0 +1,163 @@
+//! Scoped sub-tasks spawned by the `spawn_subtask` tool.
+//!
+//! A sub-task is a nested [`run_turn`] with its own history, seeded from a
+//! task description the parent model writes, a tool allowlist narrowed from
+//! the parent's, and its own iteration cap and token budget. Only its final
+//! answer goes back to the parent, as the tool output; it never streams
+//! drafts or progress to the channel.
+//!
+//! It runs inside the parent turn, so its LLM calls and tools are counted in
+//! the parent's [`TurnSummary`](super::turn::TurnSummary). Log lines carry a
+//! `subtask` span and audit events written meanwhile carry its
+//! `subtask_id` next to the parent `turn_id`. Sub-tasks cannot spawn
+//! sub-tasks (depth 1).
+
+use super::loop_::{run_turn, HeadlessTurn};
+use crate::observability::NoopObserver;
+use crate::providers::{ChatMessage, Provider};
+use crate::tools::Tool;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tracing::Instrument;
+
+tokio::task_local! {
+    static CURRENT_SUBTASK: Arc<SubtaskState>;
+    static OFFERED_TOOLS: Arc<[String]>;
+}
+
+const SUBTASK_SYSTEM_PROMPT: &str = "You are working on one sub-task for another assistant. \
+Use the tools you have to complete the task below, then reply with the result only: \
+your reply is handed back verbatim and nobody else reads it.";
+
+#[derive(Debug)]
+struct SubtaskState {
+    id: String,
+    max_tokens: u64,
+    used_tokens: AtomicU64,
+}
+
+/// The sub-task spent its token budget before producing an answer.
+#[derive(Debug)]
+pub struct SubtaskBudgetExceeded {
+    pub used_tokens: u64,
+    pub max_tokens: u64,
+}
+
+impl std::fmt::Display for SubtaskBudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "sub-task token budget exhausted ({} of {} tokens used)",
+            self.used_tokens, self.max_tokens
+        )
+    }
+}
+
+impl std::error::Error for SubtaskBudgetExceeded {}
+
+/// Id of the sub-task being executed, if any.
+pub fn current_subtask_id() -> Option<String> {
+    CURRENT_SUBTASK.try_with(|state| state.id.clone()).ok()
+}
+
+/// Charge the tokens of one LLM exchange to the current sub-task. Fails once
+/// the budget is spent; a no-op outside a sub-task.
+pub(crate) fn charge_tokens(tokens: u64) -> Result<(), SubtaskBudgetExceeded> {
+    CURRENT_SUBTASK
+        .try_with(|state| {
+            let used_tokens = state.used_tokens.fetch_add(tokens, Ordering::Relaxed) + tokens;
+            if used_tokens > state.max_tokens {
+                Err(SubtaskBudgetExceeded {
+                    used_tokens,
+                    max_tokens: state.max_tokens,
+                })
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap_or(Ok(()))
+}
+
+/// Run tool executions in `fut` with `names` as the tools the calling loop
+/// offers the model, the widest allowlist a sub-task may ask for.
+pub(crate) async fn with_offered_tools<F: Future>(names: Arc<[String]>, fut: F) -> F::Output {
+    OFFERED_TOOLS.scope(names, fut).await
+}
+
+/// Tools offered to the model by the loop executing the current tool call.
+pub fn offered_tools() -> Option<Arc<[String]>> {
+    OFFERED_TOOLS.try_with(Arc::clone).ok()
+}
+
+/// Outcome of [`run_subtask`].
+#[derive(Debug)]
+pub struct SubtaskRun {
+    pub id: String,
+    pub tokens_used: u64,
+    /// Final answer, or why the sub-task stopped without one.
+    pub result: anyhow::Result<String>,
+}
+
+/// Run `task` as a sub-task with `tools`, stopping after `turn.max_iterations`
+/// LLM calls or once `max_tokens` are used.
+pub(crate) async fn run_subtask(
+    provider: &dyn Provider,
+    tools: &[Box<dyn Tool>],
+    turn: &HeadlessTurn<'_>,
+    task: &str,
+    max_tokens: u64,
+) -> SubtaskRun {
+    let state = Arc::new(SubtaskState {
+        id: uuid::Uuid::new_v4().to_string(),
+        max_tokens,
+        used_tokens: AtomicU64::new(0),
+    });
+    let span = tracing::info_span!("subtask", subtask_id = %state.id);
+    let mut history = vec![
+        ChatMessage::system(SUBTASK_SYSTEM_PROMPT),
+        ChatMessage::user(task.trim()),
+    ];
+
+    let result = Box::pin(
+        CURRENT_SUBTASK
+            .scope(
+                Arc::clone(&state),
+                run_turn(provider, &mut history, tools, &NoopObserver, turn),
+            )
+            .instrument(span),
+    )
+    .await;
+
+    SubtaskRun {
+        id: state.id.clone(),
+        tokens_used: state.used_tokens.load(Ordering::Relaxed),
+        result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn charge_tokens_only_applies_inside_a_subtask() {
+        assert!(charge_tokens(u64::MAX / 2).is_ok());
+        assert!(current_subtask_id().is_none());
+
+        let state = Arc::new(SubtaskState {
+            id: "sub-1".into(),
+            max_tokens: 100,
+            used_tokens: AtomicU64::new(0),
+        });
+        CURRENT_SUBTASK
+            .scope(state, async {
+                assert_eq!(current_subtask_id().as_deref(), Some("sub-1"));
+                assert!(charge_tokens(60).is_ok());
+                let exceeded = charge_tokens(60).unwrap_err();
+                assert_eq!(exceeded.used_tokens, 120);
+                assert_eq!(exceeded.max_tokens, 100);
+            })
+            .await;
+    }
+}
@@ -0,0 +1,344 @@
+//! Recovery from malformed or hallucinated tool calls.
+//!
+//! Models sometimes send tool arguments that are almost JSON (wrapped in a
+//! markdown fence, single-quoted, with trailing commas) or call a tool that
+//! does not exist. Feeding a bare error back usually makes them repeat the
+//! mistake until the loop runs out of iterations. `run_tool_call_loop` uses
+//! this module to:
+//!
+//! 1. repair near-JSON arguments ([`repair_json_arguments`]) before giving up,
+//! 2. answer unknown tool names with ranked suggestions and the list of valid
+//!    names ([`unknown_tool_message`]), and
+//! 3. stop retrying a tool after [`MALFORMED_CALL_LIMIT`] consecutive
+//!    malformed calls in one turn ([`MalformedCallTracker`]).
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Consecutive malformed calls to one tool, within a turn, before the loop
+/// stops and reports back to the user.
+pub const MALFORMED_CALL_LIMIT: u32 = 2;
+
+/// Suggestions offered for an unknown tool name.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// How native tool-call arguments were decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgumentsStatus {
+    /// Valid JSON as sent.
+    Valid,
+    /// Only parsed after [`repair_json_arguments`].
+    Repaired,
+    /// Not recoverable; carries the original parse error.
+    Invalid(String),
+}
+
+/// Decode raw tool-call arguments, falling back to a lenient repair pass.
+/// Empty input is treated as an empty object, as is unrecoverable input
+/// (reported as [`ArgumentsStatus::Invalid`]).
+pub fn parse_arguments(raw: &str) -> (serde_json::Value, ArgumentsStatus) {
+    let empty = || serde_json::Value::Object(serde_json::Map::new());
+    if raw.trim().is_empty() {
+        return (empty(), ArgumentsStatus::Valid);
+    }
+    match serde_json::from_str::<serde_json::Value>(raw) {
+        Ok(value) => (value, ArgumentsStatus::Valid),
+        Err(e) => match repair_json_arguments(raw) {
+            Some(value) => (value, ArgumentsStatus::Repaired),
+            None => (empty(), ArgumentsStatus::Invalid(e.to_string())),
+        },
+    }
+}
+
+/// Best-effort fix of near-JSON arguments: strips a surrounding markdown
+/// fence, converts single-quoted strings to double-quoted ones and removes
+/// trailing commas. Returns `None` unless the result is a JSON object.
+pub fn repair_json_arguments(raw: &str) -> Option<serde_json::Value> {
+    let unfenced = strip_markdown_fence(raw.trim());
+    let requoted = replace_single_quotes(unfenced);
+    let cleaned = remove_trailing_commas(&requoted);
+    serde_json::from_str::<serde_json::Value>(cleaned.trim())
+        .ok()
+        .filter(serde_json::Value::is_object)
+}
+
+fn strip_markdown_fence(input: &str) -> &str {
+    let Some(rest) = input.strip_prefix("```") else {
+        return input;
+    };
+    // Drop the info string (```json) up to the end of the first line.
+    let body = rest.split_once('\n').map_or(rest, |(_, body)| body);
+    body.trim_end().strip_suffix("```").unwrap_or(body).trim()
+}
+
+/// Rewrite `'...'` string literals as `"..."`, escaping embedded double
+/// quotes. Apostrophes inside double-quoted strings are left alone.
+fn replace_single_quotes(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut in_double = false;
+    let mut in_single = false;
+    let mut escaped = false;
+
+    for ch in input.chars() {
+        if escaped {
+            // `\'` is not a valid JSON escape; keep the quote itself.
+            if !(in_single && ch == '\'') {
+                out.push('\\');
+            }
+            out.push(ch);
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' if in_double || in_single => escaped = true,
+            '"' if in_single => out.push_str("\\\""),
+            '"' => {
+                in_double = !in_double;
+                out.push(ch);
+            }
+            '\'' if !in_double => {
+                in_single = !in_single;
+                out.push('"');
+            }
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+/// Drop commas directly followed (ignoring whitespace) by `}` or `]`,
+/// outside string literals.
+fn remove_trailing_commas(input: &str) -> String {
+    let chars: Vec<char> = input.chars().collect();
+    let mut out = String::with_capacity(input.len());
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, &ch) in chars.iter().enumerate() {
+        if in_string {
+            out.push(ch);
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        if ch == '"' {
+            in_string = true;
+        } else if ch == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}' | ']')) {
+                continue;
+            }
+        }
+        out.push(ch);
+    }
+    out
+}
+
+/// Levenshtein distance over characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut current);
+    }
+    prev[b.len()]
+}
+
+/// Registered tool names close to `name`, best match first.
+///
+/// Names are compared case-insensitively with `-`/space treated as `_`. A
+/// candidate qualifies when its edit distance is at most a third of the
+/// longer name (minimum 2), or when one name contains the other.
+pub fn suggest_tool_names<'a>(name: &str, candidates: &[&'a str]) -> Vec<&'a str> {
+    let normalize = |s: &str| s.trim().to_lowercase().replace(['-', ' '], "_");
+    let wanted = normalize(name);
+    if wanted.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ranked: Vec<(usize, &'a str)> = candidates
+        .iter()
+        .filter_map(|&candidate| {
+            let normalized = normalize(candidate);
+            let distance = edit_distance(&wanted, &normalized);
+            let budget = (wanted.chars().count().max(normalized.chars().count()) / 3).max(2);
+            let contains = normalized.contains(&wanted) || wanted.contains(&normalized);
+            (distance <= budget || contains).then_some((distance, candidate))
+        })
+        .collect();
+    ranked.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)));
+    ranked
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Error fed back to the model for a call to a tool that does not exist.
+pub fn unknown_tool_message(name: &str, candidates: &[&str]) -> String {
+    let mut message = format!("Unknown tool: {name}.");
+    let suggestions = suggest_tool_names(name, candidates);
+    if !suggestions.is_empty() {
+        let quoted: Vec<String> = suggestions.iter().map(|s| format!("'{s}'")).collect();
+        let _ = write!(message, " Did you mean {}?", quoted.join(" or "));
+    }
+    let mut valid = candidates.to_vec();
+    valid.sort_unstable();
+    let _ = write!(
+        message,
+        "\nValid tool names: {}.\nCall one of these exactly as listed.",
+        valid.join(", ")
+    );
+    message
+}
+
+/// Reply sent to the user when the loop gives up on `tool` for this turn.
+pub fn cutoff_message(tool: &str, raw_arguments: &str) -> String {
+    let attempted = crate::util::truncate_with_ellipsis(raw_arguments.trim(), 300);
+    let attempted = if attempted.is_empty() {
+        "(no arguments)".to_string()
+    } else {
+        attempted
+    };
+    format!(
+        "I stopped after {MALFORMED_CALL_LIMIT} failed attempts to call the `{tool}` tool: \
+         the call was malformed each time.\nWhat I was trying to run: `{tool}` with {attempted}\n\
+         You can rephrase the request or ask me to try a different approach."
+    )
+}
+
+/// Per-turn count of consecutive malformed calls, keyed by tool name.
+#[derive(Debug, Default)]
+pub struct MalformedCallTracker {
+    failures: HashMap<String, u32>,
+}
+
+impl MalformedCallTracker {
+    /// Record a malformed call; returns `true` once `tool` reaches
+    /// [`MALFORMED_CALL_LIMIT`] and should not be retried this turn.
+    pub fn record_failure(&mut self, tool: &str) -> bool {
+        let count = self.failures.entry(tool.to_string()).or_insert(0);
+        *count += 1;
+        *count >= MALFORMED_CALL_LIMIT
+    }
+
+    /// A well-formed call resets the streak for `tool`.
+    pub fn record_success(&mut self, tool: &str) {
+        self.failures.remove(tool);
+    }
+
+    pub fn failures(&self, tool: &str) -> u32 {
+        self.failures.get(tool).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn repair_handles_fences_single_quotes_and_trailing_commas() {
+        assert_eq!(
+            repair_json_arguments("```json\n{\"command\": \"ls\",}\n```"),
+            Some(json!({"command": "ls"}))
+        );
+        assert_eq!(
+            repair_json_arguments("{'path': 'notes/today.md', 'tags': ['a', 'b',],}"),
+            Some(json!({"path": "notes/today.md", "tags": ["a", "b"]}))
+        );
+        assert_eq!(
+            repair_json_arguments(r#"{'text': 'she said "hi"', "note": "it's fine"}"#),
+            Some(json!({"text": "she said \"hi\"", "note": "it's fine"}))
+        );
+        // Commas inside strings are data, not trailing commas.
+        assert_eq!(
+            repair_json_arguments(r#"{"pattern": "a,}",}"#),
+            Some(json!({"pattern": "a,}"}))
+        );
+    }
+
+    #[test]
+    fn repair_gives_up_on_non_objects_and_garbage() {
+        assert_eq!(repair_json_arguments("[1, 2,]"), None);
+        assert_eq!(repair_json_arguments("command=ls"), None);
+        assert_eq!(repair_json_arguments("{\"command\": "), None);
+    }
+
+    #[test]
+    fn parse_arguments_reports_which_path_was_taken() {
+        assert_eq!(
+            parse_arguments(r#"{"a": 1}"#),
+            (json!({"a": 1}), ArgumentsStatus::Valid)
+        );
+        assert_eq!(parse_arguments("  "), (json!({}), ArgumentsStatus::Valid));
+        assert_eq!(
+            parse_arguments("{'a': 1,}"),
+            (json!({"a": 1}), ArgumentsStatus::Repaired)
+        );
+        let (value, status) = parse_arguments("{not json");
+        assert_eq!(value, json!({}));
+        assert!(matches!(status, ArgumentsStatus::Invalid(_)));
+    }
+
+    #[test]
+    fn suggestions_rank_closest_names_first() {
+        let tools = [
+            "file_read",
+            "file_write",
+            "file_edit",
+            "shell",
+            "memory_recall",
+            "web_search",
+        ];
+        assert_eq!(suggest_tool_names("file_reed", &tools), vec!["file_read"]);
+        assert_eq!(suggest_tool_names("file", &tools).len(), 3);
+        assert_eq!(suggest_tool_names("Shell", &tools), vec!["shell"]);
+        assert_eq!(suggest_tool_names("web-search", &tools), vec!["web_search"]);
+        assert_eq!(suggest_tool_names("recall", &tools), vec!["memory_recall"]);
+        assert!(suggest_tool_names("launch_rocket", &tools).is_empty());
+    }
+
+    #[test]
+    fn unknown_tool_message_lists_suggestions_and_valid_names() {
+        let message = unknown_tool_message("shel", &["shell", "file_read"]);
+        assert!(message.starts_with("Unknown tool: shel. Did you mean 'shell'?"));
+        assert!(message.contains("Valid tool names: file_read, shell."));
+
+        let message = unknown_tool_message("teleport", &["shell"]);
+        assert!(!message.contains("Did you mean"));
+        assert!(message.contains("Valid tool names: shell."));
+    }
+
+    #[test]
+    fn cutoff_message_names_tool_and_attempted_arguments() {
+        let message = cutoff_message("file_read", "{'path': notes}");
+        assert!(message.contains("`file_read`"));
+        assert!(message.contains("{'path': notes}"));
+        assert!(cutoff_message("shell", " ").contains("(no arguments)"));
+    }
+
+    #[test]
+    fn tracker_cuts_off_after_consecutive_failures_per_tool() {
+        let mut tracker = MalformedCallTracker::default();
+        assert!(!tracker.record_failure("shell"));
+        tracker.record_success("shell");
+        assert_eq!(tracker.failures("shell"), 0);
+
+        assert!(!tracker.record_failure("shell"));
+        assert!(!tracker.record_failure("file_read"));
+        assert!(tracker.record_failure("shell"));
+        assert_eq!(tracker.failures("file_read"), 1);
+    }
+}
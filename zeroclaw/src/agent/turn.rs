@@ -0,0 +1,377 @@
+//! Turn-level correlation.
+//!
+//! Each channel turn (one inbound message through to the delivered reply)
+//! gets a UUID `turn_id`. The turn runs inside a `turn` tracing span carrying
+//! the id, so every log line written while handling it (LLM calls, tools,
+//! adapter delivery) can be grepped by it. Audit events written during the
+//! turn are stamped with it, `run_tool_call_loop` uses it for its runtime
+//! trace events, and the persisted reply records it in its metadata.
+//!
+//! A [`TurnRecorder`] scoped with [`with_turn`] also collects iterations,
+//! tools and token usage; [`TurnRecorder::summary`] turns them into the
+//! `turn_summary` audit event read by `GET /api/monitor/turns`. It also
+//! holds the web sources cited during the turn (see [`super::citations`]).
+//!
+//! Process-wide totals (turns started and how they ended, iterations, LLM
+//! latency) are kept in [`METRICS`] for `GET /api/monitor/metrics`.
+
+use super::citations::{Source, SourceRegistry};
+use super::context_usage::ContextSample;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+tokio::task_local! {
+    static CURRENT_TURN: Arc<TurnRecorder>;
+}
+
+/// How a turn ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnOutcome {
+    Completed,
+    Error,
+    MaxIterations,
+    Cancelled,
+}
+
+/// Per-turn summary recorded in the audit log as a `turn_summary` event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnSummary {
+    pub turn_id: String,
+    /// Conversation history key, e.g. `telegram_alice`.
+    pub session: String,
+    pub iterations: u32,
+    /// Tools executed, in order (repeats included).
+    pub tools_used: Vec<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub duration_ms: u64,
+    pub outcome: TurnOutcome,
+    /// Largest prompt of the turn against the model's context window; unset
+    /// when the window is unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<ContextSample>,
+}
+
+#[derive(Debug, Default)]
+struct TurnStats {
+    iterations: u32,
+    tools_used: Vec<String>,
+    input_tokens: u64,
+    output_tokens: u64,
+    outcome: Option<TurnOutcome>,
+    context: Option<ContextSample>,
+    sources: SourceRegistry,
+}
+
+/// Identity and running totals of one turn.
+#[derive(Debug)]
+pub struct TurnRecorder {
+    id: String,
+    session: String,
+    started_at: Instant,
+    stats: Mutex<TurnStats>,
+}
+
+impl TurnRecorder {
+    pub fn new(session: impl Into<String>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            session: session.into(),
+            started_at: Instant::now(),
+            stats: Mutex::new(TurnStats::default()),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Summary of the turn so far; `None` until an outcome was set (turns
+    /// answered without the model, e.g. runtime commands, have none).
+    pub fn summary(&self) -> Option<TurnSummary> {
+        let stats = self.stats.lock();
+        Some(TurnSummary {
+            turn_id: self.id.clone(),
+            session: self.session.clone(),
+            iterations: stats.iterations,
+            tools_used: stats.tools_used.clone(),
+            input_tokens: stats.input_tokens,
+            output_tokens: stats.output_tokens,
+            duration_ms: u64::try_from(self.started_at.elapsed().as_millis()).unwrap_or(u64::MAX),
+            outcome: stats.outcome?,
+            context: stats.context,
+        })
+    }
+}
+
+/// Run `fut` as part of `turn`.
+pub async fn with_turn<F: Future>(turn: Arc<TurnRecorder>, fut: F) -> F::Output {
+    CURRENT_TURN.scope(turn, fut).await
+}
+
+/// Id of the turn being executed, if any.
+pub fn current_turn_id() -> Option<String> {
+    CURRENT_TURN.try_with(|turn| turn.id.clone()).ok()
+}
+
+fn update(apply: impl FnOnce(&mut TurnStats)) {
+    let _ = CURRENT_TURN.try_with(|turn| apply(&mut turn.stats.lock()));
+}
+
+/// Count an LLM request of the current turn.
+pub fn record_iteration() {
+    update(|stats| stats.iterations += 1);
+}
+
+/// Add provider-reported token usage to the current turn.
+pub fn record_usage(input_tokens: Option<u64>, output_tokens: Option<u64>) {
+    update(|stats| {
+        stats.input_tokens += input_tokens.unwrap_or(0);
+        stats.output_tokens += output_tokens.unwrap_or(0);
+    });
+}
+
+/// Note the context size of a provider call of the current turn; the turn
+/// keeps its largest.
+pub fn record_context(sample: ContextSample) {
+    update(|stats| {
+        if stats
+            .context
+            .is_none_or(|peak| sample.tokens() >= peak.tokens())
+        {
+            stats.context = Some(sample);
+        }
+    });
+}
+
+/// Note a tool executed by the current turn.
+pub fn record_tool(name: &str) {
+    update(|stats| stats.tools_used.push(name.to_string()));
+}
+
+/// Set how the current turn ended.
+pub fn record_outcome(outcome: TurnOutcome) {
+    update(|stats| stats.outcome = Some(outcome));
+}
+
+/// Register a web source with the current turn and return its citation id,
+/// e.g. `S1`; `None` outside a turn.
+pub fn cite_source(url: &str, title: &str) -> Option<String> {
+    CURRENT_TURN
+        .try_with(|turn| turn.stats.lock().sources.cite(url, title))
+        .ok()
+}
+
+/// Web sources registered by the current turn so far, in id order.
+pub fn current_sources() -> Vec<Source> {
+    CURRENT_TURN
+        .try_with(|turn| turn.stats.lock().sources.sources().to_vec())
+        .unwrap_or_default()
+}
+
+/// Turn and LLM call counters; the process-wide instance is [`METRICS`].
+pub struct TurnMetrics {
+    started: AtomicU64,
+    completed: AtomicU64,
+    failed: AtomicU64,
+    max_iterations: AtomicU64,
+    cancelled: AtomicU64,
+    /// Iterations of finished turns.
+    iterations: AtomicU64,
+    llm_calls: AtomicU64,
+    llm_latency_ms: AtomicU64,
+    llm_latency_max_ms: AtomicU64,
+}
+
+pub static METRICS: TurnMetrics = TurnMetrics::new();
+
+impl TurnMetrics {
+    pub const fn new() -> Self {
+        Self {
+            started: AtomicU64::new(0),
+            completed: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+            max_iterations: AtomicU64::new(0),
+            cancelled: AtomicU64::new(0),
+            iterations: AtomicU64::new(0),
+            llm_calls: AtomicU64::new(0),
+            llm_latency_ms: AtomicU64::new(0),
+            llm_latency_max_ms: AtomicU64::new(0),
+        }
+    }
+
+    /// Count a turn handed to the model.
+    pub fn record_started(&self) {
+        self.started.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a finished turn by its outcome.
+    pub fn record_finished(&self, summary: &TurnSummary) {
+        let counter = match summary.outcome {
+            TurnOutcome::Completed => &self.completed,
+            TurnOutcome::Error => &self.failed,
+            TurnOutcome::MaxIterations => &self.max_iterations,
+            TurnOutcome::Cancelled => &self.cancelled,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        self.iterations
+            .fetch_add(u64::from(summary.iterations), Ordering::Relaxed);
+    }
+
+    /// Count one provider call and how long it took, successful or not.
+    pub fn record_llm_call(&self, latency: Duration) {
+        let ms = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+        self.llm_calls.fetch_add(1, Ordering::Relaxed);
+        self.llm_latency_ms.fetch_add(ms, Ordering::Relaxed);
+        self.llm_latency_max_ms.fetch_max(ms, Ordering::Relaxed);
+    }
+
+    pub fn snapshot_json(&self) -> serde_json::Value {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        let finished = load(&self.completed)
+            + load(&self.failed)
+            + load(&self.max_iterations)
+            + load(&self.cancelled);
+        let llm_calls = load(&self.llm_calls);
+        #[allow(clippy::cast_precision_loss)]
+        let iterations_per_turn =
+            (finished > 0).then(|| load(&self.iterations) as f64 / finished as f64);
+        serde_json::json!({
+            "started": load(&self.started),
+            "completed": load(&self.completed),
+            "failed": load(&self.failed),
+            "max_iterations": load(&self.max_iterations),
+            "cancelled": load(&self.cancelled),
+            "iterations_per_turn": iterations_per_turn,
+            "llm_calls": llm_calls,
+            "llm_latency_avg_ms": (llm_calls > 0).then(|| load(&self.llm_latency_ms) / llm_calls),
+            "llm_latency_max_ms": load(&self.llm_latency_max_ms),
+        })
+    }
+}
+
+impl Default for TurnMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// [`METRICS`] as JSON, reported by `/api/monitor/metrics`.
+pub fn snapshot_json() -> serde_json::Value {
+    METRICS.snapshot_json()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn recorder_collects_stats_inside_scope_only() {
+        record_iteration();
+        assert!(current_turn_id().is_none());
+
+        let turn = Arc::new(TurnRecorder::new("telegram_alice"));
+        with_turn(Arc::clone(&turn), async {
+            assert_eq!(current_turn_id().as_deref(), Some(turn.id()));
+            record_iteration();
+            record_usage(Some(120), Some(30));
+            record_context(ContextSample {
+                estimated_tokens: 100,
+                input_tokens: Some(120),
+                window: 1_000,
+            });
+            record_tool("shell");
+            record_iteration();
+            record_usage(Some(200), None);
+            record_context(ContextSample {
+                estimated_tokens: 110,
+                input_tokens: None,
+                window: 1_000,
+            });
+        })
+        .await;
+        assert!(turn.summary().is_none());
+
+        with_turn(Arc::clone(&turn), async {
+            record_outcome(TurnOutcome::Completed);
+        })
+        .await;
+        let summary = turn.summary().unwrap();
+        assert_eq!(summary.session, "telegram_alice");
+        assert_eq!(summary.iterations, 2);
+        assert_eq!(summary.tools_used, ["shell"]);
+        assert_eq!((summary.input_tokens, summary.output_tokens), (320, 30));
+        assert_eq!(summary.outcome, TurnOutcome::Completed);
+        // The larger prompt is kept, by reported count over estimate.
+        assert_eq!(summary.context.map(|c| c.tokens()), Some(120));
+    }
+
+    #[test]
+    fn metrics_count_outcomes_iterations_and_llm_latency() {
+        let metrics = TurnMetrics::new();
+        let finished = |outcome, iterations| TurnSummary {
+            turn_id: "t".into(),
+            session: "telegram_alice".into(),
+            iterations,
+            tools_used: Vec::new(),
+            input_tokens: 0,
+            output_tokens: 0,
+            duration_ms: 0,
+            outcome,
+            context: None,
+        };
+        assert!(metrics.snapshot_json()["iterations_per_turn"].is_null());
+
+        for _ in 0..4 {
+            metrics.record_started();
+        }
+        metrics.record_finished(&finished(TurnOutcome::Completed, 1));
+        metrics.record_finished(&finished(TurnOutcome::Completed, 3));
+        metrics.record_finished(&finished(TurnOutcome::Error, 1));
+        metrics.record_finished(&finished(TurnOutcome::MaxIterations, 7));
+        metrics.record_llm_call(Duration::from_millis(100));
+        metrics.record_llm_call(Duration::from_millis(300));
+
+        let snapshot = metrics.snapshot_json();
+        assert_eq!(snapshot["started"], 4);
+        assert_eq!(snapshot["completed"], 2);
+        assert_eq!(snapshot["failed"], 1);
+        assert_eq!(snapshot["max_iterations"], 1);
+        assert_eq!(snapshot["cancelled"], 0);
+        assert_eq!(snapshot["iterations_per_turn"], 3.0);
+        assert_eq!(snapshot["llm_calls"], 2);
+        assert_eq!(snapshot["llm_latency_avg_ms"], 200);
+        assert_eq!(snapshot["llm_latency_max_ms"], 300);
+    }
+
+    #[tokio::test]
+    async fn sources_get_ids_across_tool_calls_of_one_turn() {
+        assert!(cite_source("https://a.example", "A").is_none());
+
+        let turn = Arc::new(TurnRecorder::new("telegram_alice"));
+        with_turn(Arc::clone(&turn), async {
+            assert_eq!(cite_source("https://a.example", "A").as_deref(), Some("S1"));
+            assert_eq!(cite_source("https://b.example", "B").as_deref(), Some("S2"));
+        })
+        .await;
+        with_turn(Arc::clone(&turn), async {
+            assert_eq!(cite_source("https://b.example", "").as_deref(), Some("S2"));
+            assert_eq!(cite_source("https://c.example", "C").as_deref(), Some("S3"));
+            let ids: Vec<_> = current_sources().into_iter().map(|s| s.id).collect();
+            assert_eq!(ids, ["S1", "S2", "S3"]);
+        })
+        .await;
+
+        // A new turn starts again from S1.
+        with_turn(Arc::new(TurnRecorder::new("telegram_alice")), async {
+            assert!(current_sources().is_empty());
+            assert_eq!(cite_source("https://c.example", "C").as_deref(), Some("S1"));
+        })
+        .await;
+    }
+}
@@ -0,0 +1,476 @@
+//! Channel confirmation for destructive tool calls.
+//!
+//! With `[security.sandbox] require_confirmation` on, a call to one of the
+//! `confirm_tools` during a channel turn is not executed. The agent loop
+//! holds it here, keyed by session, and ends the turn with a message that
+//! describes the exact action (command, or file path with a diff preview)
+//! and a short token. The next message from the sender who triggered the
+//! call decides: a reply containing the token or a plain "yes" runs the held
+//! call and the turn continues with its result; anything else cancels it.
+//! Other participants of a shared session cannot settle it. Held calls
+//! expire after [`CONFIRMATION_TTL`] and live in memory only, so a restart
+//! drops them.
+//! Every hold and decision is written to the audit log.
+
+use super::summarize_args;
+use crate::config::Config;
+use crate::security::{AuditEvent, AuditEventType, AuditLogger, SecurityPolicy};
+use parking_lot::{Mutex, RwLock};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long a held call waits for the user's confirmation.
+pub const CONFIRMATION_TTL: Duration = Duration::from_secs(10 * 60);
+/// Changed lines shown in a `file_write` diff preview.
+const DIFF_PREVIEW_LINES: usize = 20;
+/// Replies that confirm a held call without repeating its token.
+const AFFIRMATIVES: &[&str] = &["yes", "y", "ok", "okay", "confirm", "approve", "go ahead"];
+
+static INSTALLED: RwLock<Option<Arc<ConfirmationGate>>> = RwLock::new(None);
+
+/// A tool call waiting for the user's confirmation.
+#[derive(Debug, Clone)]
+pub struct PendingCall {
+    pub token: String,
+    /// Channel sender whose turn made the call; only they can confirm it.
+    pub sender: String,
+    pub tool_name: String,
+    pub arguments: serde_json::Value,
+    held_at: Instant,
+}
+
+/// What the user's reply did to a held call.
+#[derive(Debug, Clone)]
+pub enum Resolution {
+    /// Confirmed in time; the call should run now.
+    Approved(PendingCall),
+    /// Any other reply; the call is dropped.
+    Denied(PendingCall),
+    /// The reply came after [`CONFIRMATION_TTL`]; the call is dropped.
+    Expired(PendingCall),
+}
+
+impl Resolution {
+    fn decision(&self) -> &'static str {
+        match self {
+            Self::Approved(_) => "approved",
+            Self::Denied(_) => "denied",
+            Self::Expired(_) => "expired",
+        }
+    }
+
+    pub fn call(&self) -> &PendingCall {
+        match self {
+            Self::Approved(call) | Self::Denied(call) | Self::Expired(call) => call,
+        }
+    }
+}
+
+/// Which tools need confirmation, and the calls currently held.
+pub struct ConfirmationGate {
+    tools: HashSet<String>,
+    ttl: Duration,
+    /// Decides which paths a `file_write` diff preview may read.
+    security: SecurityPolicy,
+    pending: Mutex<HashMap<String, PendingCall>>,
+    audit: Option<Arc<AuditLogger>>,
+}
+
+impl ConfirmationGate {
+    pub fn new(tools: &[String], ttl: Duration, workspace_dir: &Path) -> Self {
+        Self {
+            tools: tools.iter().cloned().collect(),
+            ttl,
+            security: SecurityPolicy {
+                workspace_dir: workspace_dir.to_path_buf(),
+                ..SecurityPolicy::default()
+            },
+            pending: Mutex::new(HashMap::new()),
+            audit: None,
+        }
+    }
+
+    /// Gate for `[security.sandbox]`, or `None` when confirmation is off.
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let sandbox = &config.security.sandbox;
+        if !sandbox.require_confirmation || sandbox.confirm_tools.is_empty() {
+            return None;
+        }
+        let gate = Self {
+            security: SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir),
+            ..Self::new(
+                &sandbox.confirm_tools,
+                CONFIRMATION_TTL,
+                &config.workspace_dir,
+            )
+        };
+        let audit = config.config_path.parent().and_then(|zeroclaw_dir| {
+            AuditLogger::new(config.security.audit.clone(), zeroclaw_dir.to_path_buf()).ok()
+        });
+        Some(match audit {
+            Some(audit) => gate.with_audit(Arc::new(audit)),
+            None => gate,
+        })
+    }
+
+    #[must_use]
+    pub fn with_audit(mut self, audit: Arc<AuditLogger>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    pub fn requires_confirmation(&self, tool_name: &str) -> bool {
+        self.tools.contains(tool_name)
+    }
+
+    /// Hold a call `sender` triggered in `session_key`, replacing any call
+    /// already held there.
+    pub fn hold(
+        &self,
+        session_key: &str,
+        sender: &str,
+        channel: &str,
+        tool_name: &str,
+        arguments: &serde_json::Value,
+    ) -> PendingCall {
+        let call = PendingCall {
+            token: new_token(),
+            sender: sender.to_string(),
+            tool_name: tool_name.to_string(),
+            arguments: arguments.clone(),
+            held_at: Instant::now(),
+        };
+        self.pending
+            .lock()
+            .insert(session_key.to_string(), call.clone());
+        self.audit_decision(channel, session_key, &call, "held");
+        call
+    }
+
+    /// Whether a call is held for `session_key`, expired or not.
+    pub fn has_pending(&self, session_key: &str) -> bool {
+        self.pending.lock().contains_key(session_key)
+    }
+
+    /// Settle the call held for `session_key` with `sender`'s `reply`.
+    /// `None` when nothing is held, or when the call was triggered by someone
+    /// else; it then stays held for its own sender.
+    pub fn resolve(
+        &self,
+        session_key: &str,
+        sender: &str,
+        channel: &str,
+        reply: &str,
+    ) -> Option<Resolution> {
+        let call = {
+            let mut pending = self.pending.lock();
+            if pending.get(session_key)?.sender != sender {
+                return None;
+            }
+            pending.remove(session_key)?
+        };
+        let resolution = if call.held_at.elapsed() >= self.ttl {
+            Resolution::Expired(call)
+        } else if is_affirmative(reply, &call.token) {
+            Resolution::Approved(call)
+        } else {
+            Resolution::Denied(call)
+        };
+        self.audit_decision(
+            channel,
+            session_key,
+            resolution.call(),
+            resolution.decision(),
+        );
+        Some(resolution)
+    }
+
+    /// Message sent to the user for a held call.
+    pub fn describe(&self, call: &PendingCall) -> String {
+        let action = match call.tool_name.as_str() {
+            "shell" => {
+                let command = call
+                    .arguments
+                    .get("command")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or_default();
+                format!("run this command:\n{command}")
+            }
+            "file_write" => {
+                let path = call
+                    .arguments
+                    .get("path")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or_default();
+                let content = call
+                    .arguments
+                    .get("content")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or_default();
+                match self.previewable_path(path) {
+                    Some(resolved) => {
+                        let current = std::fs::read_to_string(resolved).ok();
+                        let preview = crate::util::diff_preview(
+                            current.as_deref(),
+                            content,
+                            DIFF_PREVIEW_LINES,
+                        );
+                        match current {
+                            Some(_) => format!("overwrite {path}:\n{preview}"),
+                            None => format!("create {path}:\n{preview}"),
+                        }
+                    }
+                    None => format!("write {path} (no preview: the path is not allowed)"),
+                }
+            }
+            other => format!("call {other} with {}", summarize_args(&call.arguments)),
+        };
+        format!(
+            "Waiting for your confirmation. I want to {action}\n\nReply \"yes\" or {} within {} \
+             minutes to go ahead; any other reply cancels it.",
+            call.token,
+            self.ttl.as_secs() / 60
+        )
+    }
+
+    /// Where a `file_write` to `path` would land, when the security policy
+    /// lets tools read it. A file that does not exist yet is checked through
+    /// its parent directory.
+    fn previewable_path(&self, path: &str) -> Option<PathBuf> {
+        if !self.security.is_path_allowed(path) {
+            return None;
+        }
+        let full = self.security.workspace_dir.join(path);
+        let resolved = match full.canonicalize() {
+            Ok(resolved) => resolved,
+            Err(_) => full.parent()?.canonicalize().ok()?.join(full.file_name()?),
+        };
+        self.security
+            .validate_path(&resolved, None)
+            .is_ok()
+            .then_some(resolved)
+    }
+
+    fn audit_decision(&self, channel: &str, session_key: &str, call: &PendingCall, decision: &str) {
+        let Some(audit) = &self.audit else {
+            return;
+        };
+        let event = AuditEvent::new(AuditEventType::ToolConfirmation)
+            .with_actor(channel.to_string(), Some(session_key.to_string()), None)
+            .with_action(
+                format!(
+                    "{decision}: {} ({})",
+                    call.tool_name,
+                    summarize_args(&call.arguments)
+                ),
+                "high".into(),
+                decision == "approved",
+                matches!(decision, "held" | "approved"),
+            );
+        if let Err(e) = audit.log(&event) {
+            tracing::warn!("Failed to write tool confirmation audit event: {e}");
+        }
+    }
+}
+
+/// Six lowercase hex characters.
+fn new_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()[..6].to_string()
+}
+
+/// Whether `reply` confirms the call held under `token`: it contains the
+/// token, or is a bare affirmative. A `[name] ` prefix (added to messages in
+/// shared sessions) and trailing punctuation are ignored.
+pub fn is_affirmative(reply: &str, token: &str) -> bool {
+    let reply = reply.trim().to_lowercase();
+    if reply.contains(&token.to_lowercase()) {
+        return true;
+    }
+    let reply = match reply
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once("] "))
+    {
+        Some((_, text)) => text,
+        None => reply.as_str(),
+    };
+    let reply = reply.trim_end_matches(|c: char| c.is_ascii_punctuation() || c.is_whitespace());
+    AFFIRMATIVES.contains(&reply)
+}
+
+/// Use `gate` for channel turns from now on; `None` turns confirmation off.
+pub fn install(gate: Option<ConfirmationGate>) {
+    *INSTALLED.write() = gate.map(Arc::new);
+}
+
+pub fn installed() -> Option<Arc<ConfirmationGate>> {
+    INSTALLED.read().clone()
+}
+
+/// Serializes tests that install a gate, since it is process-wide.
+#[cfg(test)]
+pub(crate) static TEST_INSTALL_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn gate(ttl: Duration, workspace: &Path) -> ConfirmationGate {
+        ConfirmationGate::new(&["shell".into(), "file_write".into()], ttl, workspace)
+    }
+
+    #[test]
+    fn held_call_is_approved_once_by_token_or_yes() {
+        let tmp = TempDir::new().unwrap();
+        let gate = gate(CONFIRMATION_TTL, tmp.path());
+        assert!(gate.requires_confirmation("shell"));
+        assert!(!gate.requires_confirmation("file_read"));
+
+        let call = gate.hold(
+            "telegram_alice",
+            "alice",
+            "telegram",
+            "shell",
+            &json!({"command": "ls"}),
+        );
+        assert!(gate.has_pending("telegram_alice"));
+        let reply = format!("sure, {} please", call.token.to_uppercase());
+        let resolution = gate
+            .resolve("telegram_alice", "alice", "telegram", &reply)
+            .unwrap();
+        assert!(matches!(resolution, Resolution::Approved(ref c) if c.tool_name == "shell"));
+        assert!(gate
+            .resolve("telegram_alice", "alice", "telegram", "yes")
+            .is_none());
+
+        gate.hold(
+            "telegram_alice",
+            "alice",
+            "telegram",
+            "shell",
+            &json!({"command": "ls"}),
+        );
+        assert!(matches!(
+            gate.resolve("telegram_alice", "alice", "telegram", "[Alice] Yes!"),
+            Some(Resolution::Approved(_))
+        ));
+    }
+
+    #[test]
+    fn any_other_reply_denies_and_late_replies_expire() {
+        let tmp = TempDir::new().unwrap();
+        let gate = gate(CONFIRMATION_TTL, tmp.path());
+        gate.hold(
+            "slack_bob",
+            "bob",
+            "slack",
+            "shell",
+            &json!({"command": "rm -r build"}),
+        );
+        assert!(matches!(
+            gate.resolve(
+                "slack_bob",
+                "bob",
+                "slack",
+                "yes, but what does it delete first?"
+            ),
+            Some(Resolution::Denied(_))
+        ));
+        assert!(!gate.has_pending("slack_bob"));
+
+        let expired = self::gate(Duration::ZERO, tmp.path());
+        expired.hold(
+            "slack_bob",
+            "bob",
+            "slack",
+            "shell",
+            &json!({"command": "ls"}),
+        );
+        assert!(matches!(
+            expired.resolve("slack_bob", "bob", "slack", "yes"),
+            Some(Resolution::Expired(_))
+        ));
+        assert!(!expired.has_pending("slack_bob"));
+    }
+
+    #[test]
+    fn file_write_description_previews_the_diff() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("notes.txt"), "one\ntwo\nthree\n").unwrap();
+        let gate = gate(CONFIRMATION_TTL, tmp.path());
+
+        let call = gate.hold(
+            "cli_me",
+            "me",
+            "cli",
+            "file_write",
+            &json!({"path": "notes.txt", "content": "one\n2\nthree\n"}),
+        );
+        let message = gate.describe(&call);
+        assert!(message.contains("overwrite notes.txt"), "{message}");
+        assert!(message.contains("- two\n+ 2"), "{message}");
+        assert!(!message.contains("- one"), "{message}");
+        assert!(message.contains(&call.token));
+
+        let created = gate.hold(
+            "cli_me",
+            "me",
+            "cli",
+            "file_write",
+            &json!({"path": "new.txt", "content": "hello"}),
+        );
+        assert!(gate.describe(&created).contains("create new.txt:\n+ hello"));
+    }
+
+    #[test]
+    fn only_the_requesting_sender_can_confirm() {
+        let tmp = TempDir::new().unwrap();
+        let gate = gate(CONFIRMATION_TTL, tmp.path());
+        let call = gate.hold(
+            "telegram_group",
+            "alice",
+            "telegram",
+            "shell",
+            &json!({"command": "rm -r build"}),
+        );
+        assert!(gate
+            .resolve("telegram_group", "bob", "telegram", "[Bob] yes")
+            .is_none());
+        assert!(gate
+            .resolve("telegram_group", "bob", "telegram", &call.token)
+            .is_none());
+        assert!(gate.has_pending("telegram_group"));
+        assert!(matches!(
+            gate.resolve("telegram_group", "alice", "telegram", "[Alice] yes"),
+            Some(Resolution::Approved(_))
+        ));
+    }
+
+    #[test]
+    fn file_write_description_skips_preview_of_disallowed_paths() {
+        let tmp = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        let secret = outside.path().join("secret.txt");
+        std::fs::write(&secret, "api_key=hunter2\n").unwrap();
+        let gate = gate(CONFIRMATION_TTL, tmp.path());
+
+        for path in [
+            secret.to_string_lossy().into_owned(),
+            "../secret.txt".into(),
+        ] {
+            let call = gate.hold(
+                "cli_me",
+                "me",
+                "cli",
+                "file_write",
+                &json!({"path": path, "content": "x"}),
+            );
+            let message = gate.describe(&call);
+            assert!(message.contains("no preview"), "{message}");
+            assert!(!message.contains("hunter2"), "{message}");
+        }
+    }
+}
@@ -11,6 +11,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::io::{self, BufRead, Write};
 
+pub mod confirm;
+
 // ── Types ────────────────────────────────────────────────────────
 
 /// A request to approve a tool call before execution.
@@ -0,0 +1,338 @@
+//! Files uploaded through `POST /api/attachment` and referenced from messages
+//! as `attachment://{id}`.
+//!
+//! Each attachment is stored under `{workspace}/attachments/` as the raw bytes
+//! (`{id}`) plus a metadata sidecar (`{id}.json`). Attachments expire after
+//! `[gateway] attachment_retention_hours`; expired files are treated as
+//! missing and purged on the next upload.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// URI prefix used to reference an uploaded attachment.
+pub const ATTACHMENT_URI_PREFIX: &str = "attachment://";
+
+/// Largest accepted upload (10 MiB).
+pub const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+
+/// Metadata for one uploaded file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: String,
+    pub filename: String,
+    pub mime_type: String,
+    pub size_bytes: u64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Attachment {
+    pub fn uri(&self) -> String {
+        format!("{ATTACHMENT_URI_PREFIX}{}", self.id)
+    }
+
+    /// Whether `read_attachment` can return the content as text.
+    pub fn is_text(&self) -> bool {
+        is_text_mime(&self.mime_type)
+    }
+}
+
+/// MIME types whose content is readable as UTF-8 text.
+pub fn is_text_mime(mime_type: &str) -> bool {
+    let essence = mime_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    essence.starts_with("text/")
+        || essence.ends_with("+json")
+        || essence.ends_with("+xml")
+        || matches!(
+            essence.as_str(),
+            "application/json"
+                | "application/xml"
+                | "application/yaml"
+                | "application/x-yaml"
+                | "application/toml"
+                | "application/javascript"
+                | "application/x-sh"
+                | "application/csv"
+        )
+}
+
+/// Extract the ID from `attachment://{id}` or a bare ID. IDs are UUIDs, so
+/// anything else (path separators in particular) is rejected.
+pub fn parse_attachment_id(reference: &str) -> Option<&str> {
+    let reference = reference.trim();
+    let id = reference
+        .strip_prefix(ATTACHMENT_URI_PREFIX)
+        .unwrap_or(reference);
+    let valid =
+        !id.is_empty() && id.len() <= 64 && id.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+    valid.then_some(id)
+}
+
+/// Keep only the final path component of a client-supplied filename.
+fn sanitize_filename(filename: &str) -> String {
+    let name = filename
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(255)
+        .collect::<String>();
+    let name = name.trim();
+    if name.is_empty() || name == "." || name == ".." {
+        "attachment".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Attachment files under one workspace.
+pub struct AttachmentStore {
+    dir: PathBuf,
+    retention: chrono::Duration,
+}
+
+impl AttachmentStore {
+    pub fn new(workspace_dir: &Path, retention_hours: u64) -> Self {
+        let hours = i64::try_from(retention_hours.max(1)).unwrap_or(i64::MAX / 3_600_000);
+        Self {
+            dir: workspace_dir.join("attachments"),
+            retention: chrono::Duration::hours(hours),
+        }
+    }
+
+    /// Where the bytes of attachment `id` are stored.
+    pub fn data_path(&self, id: &str) -> PathBuf {
+        self.dir.join(id)
+    }
+
+    fn meta_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+
+    /// Store `data` and return its metadata. Expired attachments are purged
+    /// first.
+    pub fn save(&self, filename: &str, mime_type: &str, data: &[u8]) -> Result<Attachment> {
+        if data.len() > MAX_ATTACHMENT_BYTES {
+            anyhow::bail!(
+                "attachment is {} bytes; the limit is {MAX_ATTACHMENT_BYTES}",
+                data.len()
+            );
+        }
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("failed to create {}", self.dir.display()))?;
+        if let Err(e) = self.purge_expired() {
+            tracing::warn!("Attachment cleanup failed: {e:#}");
+        }
+
+        let created_at = Utc::now();
+        let mime_type = mime_type.trim();
+        let attachment = Attachment {
+            id: uuid::Uuid::new_v4().to_string(),
+            filename: sanitize_filename(filename),
+            mime_type: if mime_type.is_empty() {
+                "application/octet-stream".to_string()
+            } else {
+                mime_type.to_string()
+            },
+            size_bytes: data.len() as u64,
+            created_at,
+            expires_at: created_at + self.retention,
+        };
+        std::fs::write(self.data_path(&attachment.id), data)
+            .context("failed to write attachment")?;
+        std::fs::write(
+            self.meta_path(&attachment.id),
+            serde_json::to_vec_pretty(&attachment)?,
+        )
+        .context("failed to write attachment metadata")?;
+        Ok(attachment)
+    }
+
+    /// Metadata for `reference` (`attachment://{id}` or a bare ID), or
+    /// `None` if it does not exist or has expired.
+    pub fn get(&self, reference: &str) -> Result<Option<Attachment>> {
+        let Some(id) = parse_attachment_id(reference) else {
+            return Ok(None);
+        };
+        let raw = match std::fs::read(self.meta_path(id)) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).context("failed to read attachment metadata"),
+        };
+        let attachment: Attachment =
+            serde_json::from_slice(&raw).context("corrupt attachment metadata")?;
+        Ok((attachment.expires_at > Utc::now()).then_some(attachment))
+    }
+
+    /// Metadata and content for `reference`.
+    pub fn read(&self, reference: &str) -> Result<Option<(Attachment, Vec<u8>)>> {
+        let Some(attachment) = self.get(reference)? else {
+            return Ok(None);
+        };
+        let data = std::fs::read(self.data_path(&attachment.id))
+            .with_context(|| format!("attachment {} has no content", attachment.id))?;
+        Ok(Some((attachment, data)))
+    }
+
+    /// Look up every reference, failing on the first unknown or expired one.
+    pub fn resolve(&self, references: &[String]) -> Result<Vec<Attachment>> {
+        references
+            .iter()
+            .map(|reference| {
+                self.get(reference)?
+                    .with_context(|| format!("unknown or expired attachment: {reference}"))
+            })
+            .collect()
+    }
+
+    /// Delete expired attachments; returns how many were removed.
+    pub fn purge_expired(&self) -> Result<usize> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let now = Utc::now();
+        let mut removed = 0;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let expired = std::fs::read(&path)
+                .ok()
+                .and_then(|raw| serde_json::from_slice::<Attachment>(&raw).ok())
+                .is_none_or(|attachment| attachment.expires_at <= now);
+            if expired {
+                let _ = std::fs::remove_file(path.with_extension(""));
+                std::fs::remove_file(&path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// Message section telling the model which files came with the message.
+pub fn describe_attachments(attachments: &[Attachment]) -> String {
+    if attachments.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from("[Attachments]\n");
+    for attachment in attachments {
+        let _ = write!(
+            out,
+            "- {} ({}, {} bytes) {}",
+            attachment.filename,
+            attachment.mime_type,
+            attachment.size_bytes,
+            attachment.uri()
+        );
+        if attachment.is_text() {
+            let _ = writeln!(
+                out,
+                " — read it with the read_attachment tool (id \"{}\")",
+                attachment.id
+            );
+        } else {
+            out.push_str(" — binary file, not readable as text\n");
+        }
+    }
+    out
+}
+
+/// `message` followed by the description of `attachments`.
+pub fn with_attachments(message: &str, attachments: &[Attachment]) -> String {
+    if attachments.is_empty() {
+        return message.to_string();
+    }
+    format!("{message}\n\n{}", describe_attachments(attachments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn save_and_read_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let store = AttachmentStore::new(tmp.path(), 24);
+        let saved = store
+            .save("../../etc/notes.txt", "text/plain", b"hello")
+            .unwrap();
+        assert_eq!(saved.filename, "notes.txt");
+        assert_eq!(saved.size_bytes, 5);
+        assert_eq!(
+            saved.expires_at - saved.created_at,
+            chrono::Duration::hours(24)
+        );
+
+        let (meta, data) = store.read(&saved.uri()).unwrap().unwrap();
+        assert_eq!(meta, saved);
+        assert_eq!(data, b"hello");
+        assert!(store.get("attachment://../secrets").unwrap().is_none());
+        assert!(store
+            .get("00000000-0000-0000-0000-000000000000")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn expired_attachments_are_hidden_and_purged() {
+        let tmp = TempDir::new().unwrap();
+        let store = AttachmentStore::new(tmp.path(), 24);
+        let saved = store.save("a.txt", "text/plain", b"a").unwrap();
+
+        let mut expired = saved.clone();
+        expired.expires_at = Utc::now() - chrono::Duration::seconds(1);
+        std::fs::write(
+            store.meta_path(&saved.id),
+            serde_json::to_vec(&expired).unwrap(),
+        )
+        .unwrap();
+        assert!(store.get(&saved.id).unwrap().is_none());
+
+        let err = store.resolve(&[saved.uri()]).unwrap_err().to_string();
+        assert!(err.contains("unknown or expired attachment"), "{err}");
+
+        assert_eq!(store.purge_expired().unwrap(), 1);
+        assert!(!store.data_path(&saved.id).exists());
+    }
+
+    #[test]
+    fn description_covers_text_and_binary() {
+        let tmp = TempDir::new().unwrap();
+        let store = AttachmentStore::new(tmp.path(), 1);
+        let text = store.save("notes.md", "text/markdown", b"# hi").unwrap();
+        let image = store.save("cat.png", "image/png", &[0x89, 0x50]).unwrap();
+
+        let described = with_attachments("see files", &[text.clone(), image]);
+        assert!(described.starts_with("see files\n\n[Attachments]\n"));
+        assert!(described.contains(&format!(
+            "- notes.md (text/markdown, 4 bytes) attachment://{} — read it with the read_attachment tool",
+            text.id
+        )));
+        assert!(described.contains("cat.png (image/png, 2 bytes)"));
+        assert!(described.contains("binary file"));
+        assert_eq!(with_attachments("plain", &[]), "plain");
+    }
+
+    #[test]
+    fn text_mime_detection() {
+        assert!(is_text_mime("text/plain; charset=utf-8"));
+        assert!(is_text_mime("application/json"));
+        assert!(is_text_mime("application/vnd.api+json"));
+        assert!(!is_text_mime("image/png"));
+        assert!(!is_text_mime("application/pdf"));
+    }
+}
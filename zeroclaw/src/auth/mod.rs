@@ -1,5 +1,6 @@
 pub mod anthropic_token;
 pub mod gemini_oauth;
+pub mod google_service_account;
 pub mod oauth_common;
 pub mod openai_oauth;
 pub mod profiles;
@@ -0,0 +1,458 @@
+//! Workspace backup archives (`zeroclaw backup export|import`).
+//!
+//! An archive is a gzip-compressed tar holding `manifest.json` followed by
+//! workspace-relative files under `workspace/`:
+//!
+//! - `sessions/sessions.db` — channel conversation history
+//! - `cron/jobs.db` — scheduled jobs and run history
+//! - `memory/` — memory backend files (`brain.db`, markdown notes, ...)
+//! - `skills/` — installed skills
+//! - top-level `*.md` workspace files (`MEMORY.md`, `SOUL.md`, ...)
+//!
+//! SQLite databases are snapshotted with `VACUUM INTO`, so exporting while
+//! the daemon is running yields a consistent copy. Paths are stored relative
+//! to the workspace, so an archive can be restored into a workspace at a
+//! different location. `config.toml` and the secret key are deliberately not
+//! included: credentials stay on the machine that owns them.
+
+use crate::config::Config;
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::path::{Component, Path, PathBuf};
+
+/// Archive layout version. Bump when the layout changes incompatibly.
+pub const BACKUP_SCHEMA_VERSION: u32 = 1;
+
+const MANIFEST_NAME: &str = "manifest.json";
+const WORKSPACE_PREFIX: &str = "workspace";
+
+/// Workspace-relative SQLite databases included in every backup.
+const DATABASES: &[&str] = &["sessions/sessions.db", "cron/jobs.db"];
+
+/// Workspace-relative directories included recursively.
+const DIRECTORIES: &[&str] = &["memory", "skills"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BackupManifest {
+    pub schema_version: u32,
+    pub crate_version: String,
+    pub created_at: String,
+    /// Workspace-relative paths of every file in the archive.
+    pub files: Vec<String>,
+}
+
+/// Handle `zeroclaw backup <subcommand>` CLI commands.
+pub fn handle_command(command: crate::BackupCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::BackupCommands::Export { file } => {
+            let manifest = export_workspace(&config.workspace_dir, &file)?;
+            println!(
+                "Exported {} file(s) from {} to {}",
+                manifest.files.len(),
+                config.workspace_dir.display(),
+                file.display()
+            );
+            Ok(())
+        }
+        crate::BackupCommands::Import { file, force } => {
+            let manifest = import_workspace(&config.workspace_dir, &file, force)?;
+            println!(
+                "Imported {} file(s) into {} (backup created {} by zeroclaw {})",
+                manifest.files.len(),
+                config.workspace_dir.display(),
+                manifest.created_at,
+                manifest.crate_version
+            );
+            println!("Restart the daemon or gateway to pick up the restored data.");
+            Ok(())
+        }
+    }
+}
+
+/// Write a backup of `workspace_dir` to `dest` (`.tar.gz`).
+pub fn export_workspace(workspace_dir: &Path, dest: &Path) -> Result<BackupManifest> {
+    let files = collect_files(workspace_dir)?;
+    let manifest = BackupManifest {
+        schema_version: BACKUP_SCHEMA_VERSION,
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        files: files.iter().map(|rel| rel_to_string(rel)).collect(),
+    };
+
+    if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let out = File::create(dest)
+        .with_context(|| format!("Failed to create backup file {}", dest.display()))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(out, Compression::default()));
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    archive.append_data(&mut header, MANIFEST_NAME, manifest_json.as_slice())?;
+
+    for rel in &files {
+        let source = workspace_dir.join(rel);
+        let name = Path::new(WORKSPACE_PREFIX).join(rel);
+        if is_sqlite_file(rel) {
+            let snapshot = snapshot_path(dest, rel);
+            let result = snapshot_database(&source, &snapshot).and_then(|()| {
+                archive
+                    .append_path_with_name(&snapshot, &name)
+                    .map_err(Into::into)
+            });
+            let _ = fs::remove_file(&snapshot);
+            result.with_context(|| format!("Failed to back up {}", source.display()))?;
+        } else {
+            archive
+                .append_path_with_name(&source, &name)
+                .with_context(|| format!("Failed to back up {}", source.display()))?;
+        }
+    }
+
+    archive
+        .into_inner()
+        .context("Failed to finish backup archive")?
+        .finish()
+        .context("Failed to finish backup archive")?;
+    Ok(manifest)
+}
+
+/// Restore a backup into `workspace_dir`.
+///
+/// Refuses to overwrite existing sessions, cron jobs, memory or skills unless
+/// `force` is set. Run this while the daemon is stopped.
+pub fn import_workspace(
+    workspace_dir: &Path,
+    archive: &Path,
+    force: bool,
+) -> Result<BackupManifest> {
+    let manifest = read_manifest(archive)?;
+    if manifest.schema_version > BACKUP_SCHEMA_VERSION {
+        bail!(
+            "Backup schema version {} is newer than supported version {BACKUP_SCHEMA_VERSION} \
+             (created by zeroclaw {}); upgrade zeroclaw to import it",
+            manifest.schema_version,
+            manifest.crate_version
+        );
+    }
+
+    if !force {
+        let existing = existing_data(workspace_dir);
+        if !existing.is_empty() {
+            bail!(
+                "Workspace {} already has data ({}); pass --force to overwrite it",
+                workspace_dir.display(),
+                existing.join(", ")
+            );
+        }
+    }
+
+    fs::create_dir_all(workspace_dir)
+        .with_context(|| format!("Failed to create {}", workspace_dir.display()))?;
+    let mut tar = open_archive(archive)?;
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let Ok(rel) = path.strip_prefix(WORKSPACE_PREFIX) else {
+            continue;
+        };
+        if !rel
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            bail!("Backup contains an unsafe path: {}", path.display());
+        }
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let target = workspace_dir.join(rel);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        if is_sqlite_file(rel) {
+            // A leftover WAL from the replaced database would be replayed
+            // on top of the restored one.
+            for suffix in ["-wal", "-shm"] {
+                let mut sidecar = target.clone().into_os_string();
+                sidecar.push(suffix);
+                let _ = fs::remove_file(PathBuf::from(sidecar));
+            }
+        }
+        entry
+            .unpack(&target)
+            .with_context(|| format!("Failed to restore {}", target.display()))?;
+    }
+
+    Ok(manifest)
+}
+
+/// Read only the manifest of a backup archive.
+pub fn read_manifest(archive: &Path) -> Result<BackupManifest> {
+    let mut tar = open_archive(archive)?;
+    let mut entries = tar.entries()?;
+    let Some(first) = entries.next() else {
+        bail!("{} is an empty archive", archive.display());
+    };
+    let first = first?;
+    if first.path()?.as_ref() != Path::new(MANIFEST_NAME) {
+        bail!(
+            "{} is not a zeroclaw backup (missing {MANIFEST_NAME})",
+            archive.display()
+        );
+    }
+    serde_json::from_reader(first).context("Backup manifest is not valid JSON")
+}
+
+fn open_archive(archive: &Path) -> Result<tar::Archive<GzDecoder<File>>> {
+    let file = File::open(archive)
+        .with_context(|| format!("Failed to open backup {}", archive.display()))?;
+    Ok(tar::Archive::new(GzDecoder::new(file)))
+}
+
+/// Workspace-relative files to back up, in archive order.
+fn collect_files(workspace_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = DATABASES
+        .iter()
+        .map(PathBuf::from)
+        .filter(|rel| workspace_dir.join(rel).is_file())
+        .collect();
+
+    for dir in DIRECTORIES {
+        collect_dir(workspace_dir, Path::new(dir), &mut files)?;
+    }
+
+    if let Ok(entries) = fs::read_dir(workspace_dir) {
+        let mut markdown: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+            .map(|entry| PathBuf::from(entry.file_name()))
+            .filter(|name| name.extension().is_some_and(|ext| ext == "md"))
+            .collect();
+        markdown.sort();
+        files.extend(markdown);
+    }
+    Ok(files)
+}
+
+fn collect_dir(workspace_dir: &Path, rel: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let dir = workspace_dir.join(rel);
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    let mut entries: Vec<_> = fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(Result::ok)
+        .collect();
+    entries.sort_by_key(fs::DirEntry::file_name);
+    for entry in entries {
+        let child = rel.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_dir(workspace_dir, &child, files)?;
+        } else if file_type.is_file() && !is_sqlite_sidecar(&child) {
+            files.push(child);
+        }
+    }
+    Ok(())
+}
+
+fn existing_data(workspace_dir: &Path) -> Vec<String> {
+    let mut existing: Vec<String> = DATABASES
+        .iter()
+        .filter(|rel| workspace_dir.join(rel).is_file())
+        .map(ToString::to_string)
+        .collect();
+    for dir in DIRECTORIES {
+        let non_empty =
+            fs::read_dir(workspace_dir.join(dir)).is_ok_and(|mut entries| entries.next().is_some());
+        if non_empty {
+            existing.push(format!("{dir}/"));
+        }
+    }
+    existing
+}
+
+fn is_sqlite_file(rel: &Path) -> bool {
+    rel.extension().is_some_and(|ext| ext == "db")
+}
+
+fn is_sqlite_sidecar(rel: &Path) -> bool {
+    let name = rel.to_string_lossy();
+    name.ends_with(".db-wal") || name.ends_with(".db-shm") || name.ends_with(".db-journal")
+}
+
+fn snapshot_path(dest: &Path, rel: &Path) -> PathBuf {
+    let stem = rel_to_string(rel).replace('/', "_");
+    let dir = dest
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    dir.join(format!(".{stem}.{}.snapshot", uuid::Uuid::new_v4()))
+}
+
+/// Consistent copy of a live SQLite database (WAL contents included).
+fn snapshot_database(source: &Path, snapshot: &Path) -> Result<()> {
+    let conn = rusqlite::Connection::open_with_flags(
+        source,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))?;
+    conn.execute("VACUUM INTO ?1", [snapshot.to_string_lossy().as_ref()])?;
+    Ok(())
+}
+
+fn rel_to_string(rel: &Path) -> String {
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cron;
+    use crate::sessions::SqliteSessionStore;
+    use tempfile::TempDir;
+
+    fn config_for(workspace_dir: &Path) -> Config {
+        let config = Config {
+            workspace_dir: workspace_dir.to_path_buf(),
+            config_path: workspace_dir.join("config.toml"),
+            ..Config::default()
+        };
+        fs::create_dir_all(&config.workspace_dir).unwrap();
+        config
+    }
+
+    fn populate(config: &Config) -> String {
+        let store = SqliteSessionStore::open(&config.workspace_dir).unwrap();
+        store
+            .append_message("telegram_alice", "user", "hello from the phone")
+            .unwrap();
+        store
+            .append_message("telegram_alice", "assistant", "hi alice")
+            .unwrap();
+        let job = cron::add_job(config, "0 9 * * *", "echo morning").unwrap();
+        fs::create_dir_all(config.workspace_dir.join("skills/weather")).unwrap();
+        fs::write(
+            config.workspace_dir.join("skills/weather/SKILL.md"),
+            "# Weather",
+        )
+        .unwrap();
+        fs::write(config.workspace_dir.join("MEMORY.md"), "- likes tea").unwrap();
+        job.id
+    }
+
+    #[test]
+    fn export_wipe_import_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let config = config_for(&tmp.path().join("phone"));
+        let job_id = populate(&config);
+        let archive = tmp.path().join("backups/zeroclaw.tar.gz");
+
+        let manifest = export_workspace(&config.workspace_dir, &archive).unwrap();
+        assert_eq!(manifest.schema_version, BACKUP_SCHEMA_VERSION);
+        assert!(manifest.files.contains(&"sessions/sessions.db".to_string()));
+        assert!(manifest.files.contains(&"cron/jobs.db".to_string()));
+        assert!(manifest
+            .files
+            .contains(&"skills/weather/SKILL.md".to_string()));
+        assert!(manifest.files.iter().all(|f| !f.ends_with("-wal")));
+
+        fs::remove_dir_all(&config.workspace_dir).unwrap();
+        // Restore into a workspace at a different location.
+        let laptop = config_for(&tmp.path().join("laptop"));
+        import_workspace(&laptop.workspace_dir, &archive, false).unwrap();
+
+        let store = SqliteSessionStore::open(&laptop.workspace_dir).unwrap();
+        let history = store.load_history("telegram_alice", None).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].content, "hello from the phone");
+        let jobs = cron::list_jobs(&laptop).unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].id, job_id);
+        assert_eq!(
+            fs::read_to_string(laptop.workspace_dir.join("MEMORY.md")).unwrap(),
+            "- likes tea"
+        );
+        assert!(laptop
+            .workspace_dir
+            .join("skills/weather/SKILL.md")
+            .is_file());
+    }
+
+    #[test]
+    fn import_refuses_to_clobber_without_force() {
+        let tmp = TempDir::new().unwrap();
+        let config = config_for(&tmp.path().join("ws"));
+        populate(&config);
+        let archive = tmp.path().join("backup.tar.gz");
+        export_workspace(&config.workspace_dir, &archive).unwrap();
+
+        let err = import_workspace(&config.workspace_dir, &archive, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("--force"), "{err}");
+        assert!(err.contains("sessions/sessions.db"), "{err}");
+
+        import_workspace(&config.workspace_dir, &archive, true).unwrap();
+        assert_eq!(cron::list_jobs(&config).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn import_rejects_newer_schema_and_foreign_archives() {
+        let tmp = TempDir::new().unwrap();
+        let archive = tmp.path().join("future.tar.gz");
+        let manifest = serde_json::to_vec(&BackupManifest {
+            schema_version: BACKUP_SCHEMA_VERSION + 1,
+            crate_version: "99.0.0".into(),
+            created_at: "2030-01-01T00:00:00Z".into(),
+            files: Vec::new(),
+        })
+        .unwrap();
+        let mut builder = tar::Builder::new(GzEncoder::new(
+            File::create(&archive).unwrap(),
+            Compression::fast(),
+        ));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, MANIFEST_NAME, manifest.as_slice())
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let err = import_workspace(&tmp.path().join("ws"), &archive, true)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("newer than supported"), "{err}");
+
+        let foreign = tmp.path().join("foreign.tar.gz");
+        let mut builder = tar::Builder::new(GzEncoder::new(
+            File::create(&foreign).unwrap(),
+            Compression::fast(),
+        ));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(2);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "notes.txt", &b"hi"[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+        let err = read_manifest(&foreign).unwrap_err().to_string();
+        assert!(err.contains("not a zeroclaw backup"), "{err}");
+    }
+}
@@ -1,5 +1,6 @@
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
+use std::collections::HashMap;
 use tokio::io::{self, AsyncBufReadExt, BufReader};
 use uuid::Uuid;
 
@@ -48,6 +49,8 @@ impl Channel for CliChannel {
                     .unwrap_or_default()
                     .as_secs(),
                 thread_ts: None,
+                sender_name: None,
+                metadata: HashMap::new(),
             };
 
             if tx.send(msg).await.is_err() {
@@ -76,6 +79,7 @@ mod tests {
                 recipient: "user".into(),
                 subject: None,
                 thread_ts: None,
+                silent: false,
             })
             .await;
         assert!(result.is_ok());
@@ -90,6 +94,7 @@ mod tests {
                 recipient: String::new(),
                 subject: None,
                 thread_ts: None,
+                silent: false,
             })
             .await;
         assert!(result.is_ok());
@@ -111,6 +116,8 @@ mod tests {
             channel: "cli".into(),
             timestamp: 1_234_567_890,
             thread_ts: None,
+            sender_name: None,
+            metadata: HashMap::new(),
         };
         assert_eq!(msg.id, "test-id");
         assert_eq!(msg.sender, "user");
@@ -130,6 +137,8 @@ mod tests {
             channel: "ch".into(),
             timestamp: 0,
             thread_ts: None,
+            sender_name: None,
+            metadata: HashMap::new(),
         };
         let cloned = msg.clone();
         assert_eq!(cloned.id, msg.id);
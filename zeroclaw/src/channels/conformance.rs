@@ -0,0 +1,211 @@
+//! Test support for channel adapters.
+//!
+//! [`check_outbound_contract`] runs the same outbound checks against any
+//! adapter pointed at a mock platform API: a send without a recipient fails
+//! before any request, a rejected request is not retried, transient failures
+//! are retried [`MAX_SEND_RETRIES`] times and then reported to the caller
+//! (which queues the reply), `runtime.adapter_max_inflight` bounds
+//! concurrent sends, and sends to the same recipient run one at a time. [`LoopbackChannel`] is an adapter without a platform:
+//! whatever the runtime sends through it comes back as an inbound message,
+//! for end-to-end tests of the dispatch loop without network.
+
+use super::send_retry::MAX_SEND_RETRIES;
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use wiremock::matchers::any;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Delay of each mocked send when checking `max_inflight`.
+const SLOW_SEND: Duration = Duration::from_millis(150);
+
+/// Echoes outbound messages back as inbound messages from the recipient.
+pub struct LoopbackChannel {
+    name: String,
+    echo: mpsc::Sender<ChannelMessage>,
+    sent: Mutex<Vec<SendMessage>>,
+}
+
+impl LoopbackChannel {
+    /// A channel called `name` and the receiver its echoes arrive on.
+    pub fn new(name: &str) -> (Self, mpsc::Receiver<ChannelMessage>) {
+        let (echo, rx) = mpsc::channel(64);
+        let channel = Self {
+            name: name.to_string(),
+            echo,
+            sent: Mutex::new(Vec::new()),
+        };
+        (channel, rx)
+    }
+
+    /// Everything sent through the channel so far.
+    pub fn sent(&self) -> Vec<SendMessage> {
+        self.sent.lock().clone()
+    }
+}
+
+#[async_trait]
+impl Channel for LoopbackChannel {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let recipient = super::send_retry::require_recipient(&self.name, message)?;
+        self.sent.lock().push(message.clone());
+        let count = self.sent.lock().len();
+        self.echo
+            .send(ChannelMessage {
+                id: format!("loopback-{count}"),
+                sender: recipient.to_string(),
+                reply_target: recipient.to_string(),
+                content: message.content.clone(),
+                channel: self.name.clone(),
+                timestamp: chrono::Utc::now().timestamp().unsigned_abs(),
+                thread_ts: message.thread_ts.clone(),
+                sender_name: None,
+                metadata: HashMap::new(),
+            })
+            .await
+            .map_err(|_| anyhow::anyhow!("loopback receiver dropped"))
+    }
+
+    async fn listen(&self, _tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Check an adapter's outbound path against a mock platform API.
+///
+/// `make(api_base, max_inflight)` builds the adapter with its API base URL
+/// replaced and `with_outbound_limits(max_inflight, 0)`; `recipients` are
+/// three distinct valid targets for it.
+pub async fn check_outbound_contract<F>(make: F, recipients: [&str; 3])
+where
+    F: Fn(&str, usize) -> Arc<dyn Channel>,
+{
+    let recipient = recipients[0];
+    let name = make("http://127.0.0.1:9", 1).name().to_string();
+
+    let server = MockServer::start().await;
+    let channel = make(&server.uri(), 1);
+    for blank in ["", "  "] {
+        let result = channel.send(&SendMessage::new("hello", blank)).await;
+        assert!(result.is_err(), "{name}: sent without a recipient");
+    }
+    assert_eq!(
+        requests(&server).await,
+        0,
+        "{name}: called the API without a recipient"
+    );
+
+    let server = mock_api(ResponseTemplate::new(400)).await;
+    let result = make(&server.uri(), 1)
+        .send(&SendMessage::new("hello", recipient))
+        .await;
+    assert!(result.is_err(), "{name}: rejected request reported as sent");
+    assert_eq!(
+        requests(&server).await,
+        1,
+        "{name}: retried a rejected request"
+    );
+
+    let server = mock_api(ResponseTemplate::new(503).insert_header("Retry-After", "0")).await;
+    let result = make(&server.uri(), 1)
+        .send(&SendMessage::new("hello", recipient))
+        .await;
+    assert!(
+        result.is_err(),
+        "{name}: failure after retries was not reported"
+    );
+    assert_eq!(
+        requests(&server).await,
+        1 + MAX_SEND_RETRIES as usize,
+        "{name}: transient failures not retried {MAX_SEND_RETRIES} times"
+    );
+
+    let same = [recipient; 3];
+    for (max_inflight, targets, bounded) in [
+        (1, recipients, true),
+        (3, recipients, false),
+        (3, same, true),
+    ] {
+        let server = mock_api(ok_response().set_delay(SLOW_SEND)).await;
+        let channel = make(&server.uri(), max_inflight);
+        let started = Instant::now();
+        let mut sends = tokio::task::JoinSet::new();
+        for (i, target) in targets.into_iter().enumerate() {
+            let channel = Arc::clone(&channel);
+            let message = SendMessage::new(format!("message {i}"), target);
+            sends.spawn(async move { channel.send(&message).await });
+        }
+        while let Some(result) = sends.join_next().await {
+            result
+                .unwrap()
+                .unwrap_or_else(|e| panic!("{name}: send failed: {e:#}"));
+        }
+        let serialized = started.elapsed() >= SLOW_SEND * 3;
+        assert_eq!(
+            serialized,
+            bounded,
+            "{name}: max_inflight {max_inflight} not respected or same-recipient sends \
+             overlapped ({:?} for 3 sends to {targets:?})",
+            started.elapsed()
+        );
+    }
+}
+
+/// A success body accepted by every adapter (`ok` for Slack, ids for Graph).
+fn ok_response() -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "ok": true,
+        "messages": [{ "id": "wamid.1" }],
+    }))
+}
+
+async fn mock_api(response: ResponseTemplate) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(response)
+        .mount(&server)
+        .await;
+    server
+}
+
+async fn requests(server: &MockServer) -> usize {
+    server
+        .received_requests()
+        .await
+        .map_or(0, |requests| requests.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn loopback_echoes_sends_as_inbound_messages() {
+        let (channel, mut echoes) = LoopbackChannel::new("loopback");
+        channel
+            .send(&SendMessage::new("ping", "alice").in_thread(Some("t1".into())))
+            .await
+            .unwrap();
+        let echo = echoes.recv().await.unwrap();
+        assert_eq!(echo.channel, "loopback");
+        assert_eq!(echo.sender, "alice");
+        assert_eq!(echo.content, "ping");
+        assert_eq!(echo.thread_ts.as_deref(), Some("t1"));
+        assert_eq!(channel.sent().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn loopback_conforms_to_missing_recipient_check() {
+        let (channel, _echoes) = LoopbackChannel::new("loopback");
+        assert!(channel.send(&SendMessage::new("ping", " ")).await.is_err());
+        assert!(channel.sent().is_empty());
+    }
+}
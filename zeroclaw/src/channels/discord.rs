@@ -1,3 +1,7 @@
+use super::discord_commands::{self, SlashCommand};
+use super::formatting::{ChannelFormatter, DiscordFormatter};
+use super::send_retry::{retry_after_header, send_chunks_with_retry, OutboundLimiter, SendError};
+use super::splitting::split_message;
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
@@ -6,6 +10,7 @@ use reqwest::multipart::{Form, Part};
 use serde_json::json;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
@@ -16,9 +21,19 @@ pub struct DiscordChannel {
     allowed_users: Vec<String>,
     listen_to_bots: bool,
     mention_only: bool,
+    /// With `mention_only`, guild messages starting with this are processed too.
+    command_prefix: Option<String>,
     typing_handles: Mutex<HashMap<String, tokio::task::JoinHandle<()>>>,
+    /// Config used by slash command handlers; `None` disables the commands.
+    command_config: Option<Arc<crate::config::Config>>,
+    command_users: Vec<String>,
+    /// REST API base for sending and editing messages; replaced in tests.
+    api_base: String,
+    outbound: OutboundLimiter,
 }
 
+const DISCORD_API_BASE: &str = "https://discord.com/api/v10";
+
 impl DiscordChannel {
     pub fn new(
         bot_token: String,
@@ -33,10 +48,49 @@ impl DiscordChannel {
             allowed_users,
             listen_to_bots,
             mention_only,
+            command_prefix: None,
             typing_handles: Mutex::new(HashMap::new()),
+            command_config: None,
+            command_users: Vec::new(),
+            api_base: DISCORD_API_BASE.to_string(),
+            outbound: OutboundLimiter::default(),
         }
     }
 
+    /// Send and edit messages through another API base (e.g. a mock server).
+    pub fn with_api_base(mut self, api_base: String) -> Self {
+        self.api_base = api_base.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Bound concurrent sends (`runtime.adapter_max_inflight`) and set the
+    /// retry jitter (`runtime.adapter_retry_jitter_ms`).
+    pub fn with_outbound_limits(mut self, max_inflight: usize, retry_jitter_ms: u64) -> Self {
+        self.outbound = OutboundLimiter::new(max_inflight, retry_jitter_ms);
+        self
+    }
+
+    /// Also accept `mention_only` guild messages that start with `prefix`.
+    pub fn with_command_prefix(mut self, prefix: Option<String>) -> Self {
+        self.command_prefix = prefix.filter(|p| !p.trim().is_empty());
+        self
+    }
+
+    /// Register `/claw_*` slash commands on startup and serve them for the
+    /// given Discord user IDs. Commands are skipped when `command_users` is
+    /// empty.
+    pub fn with_slash_commands(
+        mut self,
+        config: Arc<crate::config::Config>,
+        command_users: Vec<String>,
+    ) -> Self {
+        if !command_users.is_empty() {
+            self.command_config = Some(config);
+            self.command_users = command_users;
+        }
+        self
+    }
+
     fn http_client(&self) -> reqwest::Client {
         crate::config::build_runtime_proxy_client("channel.discord")
     }
@@ -53,6 +107,102 @@ impl DiscordChannel {
         let part = token.split('.').next()?;
         base64_decode(part)
     }
+
+    /// Overwrite the application's slash commands. Guild-scoped when a guild
+    /// is configured (visible immediately), global otherwise.
+    fn spawn_command_registration(&self, application_id: &str) {
+        if self.command_config.is_none() {
+            return;
+        }
+        let url = match self.guild_id {
+            Some(ref gid) => format!(
+                "https://discord.com/api/v10/applications/{application_id}/guilds/{gid}/commands"
+            ),
+            None => format!("https://discord.com/api/v10/applications/{application_id}/commands"),
+        };
+        let client = self.http_client();
+        let token = self.bot_token.clone();
+        tokio::spawn(async move {
+            let result = client
+                .put(&url)
+                .header("Authorization", format!("Bot {token}"))
+                .json(&discord_commands::command_definitions())
+                .send()
+                .await;
+            match result {
+                Ok(resp) if resp.status().is_success() => {
+                    tracing::info!("Discord: registered slash commands");
+                }
+                Ok(resp) => {
+                    let status = resp.status();
+                    let err = resp.text().await.unwrap_or_default();
+                    tracing::warn!("Discord: slash command registration failed ({status}): {err}");
+                }
+                Err(err) => tracing::warn!("Discord: slash command registration failed: {err}"),
+            }
+        });
+    }
+
+    /// Answer an application command interaction with an ephemeral reply.
+    fn spawn_interaction_reply(&self, interaction: &serde_json::Value) {
+        let Some(config) = self.command_config.clone() else {
+            return;
+        };
+        if !discord_commands::is_application_command(interaction) {
+            return;
+        }
+        let (Some(id), Some(token)) = (
+            interaction.get("id").and_then(serde_json::Value::as_str),
+            interaction.get("token").and_then(serde_json::Value::as_str),
+        ) else {
+            return;
+        };
+        let url = format!("https://discord.com/api/v10/interactions/{id}/{token}/callback");
+        let user_id = discord_commands::interaction_user_id(interaction).unwrap_or("");
+        let channel_id = interaction
+            .get("channel_id")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("")
+            .to_string();
+        let guild_mismatch = match (
+            self.guild_id.as_deref(),
+            interaction
+                .get("guild_id")
+                .and_then(serde_json::Value::as_str),
+        ) {
+            (Some(expected), Some(actual)) => expected != actual,
+            _ => false,
+        };
+
+        let command = if guild_mismatch
+            || !discord_commands::is_command_user_allowed(&self.command_users, user_id)
+        {
+            tracing::warn!("Discord: rejecting slash command from unauthorized user: {user_id}");
+            Err("You are not allowed to use this command.".to_string())
+        } else {
+            interaction
+                .get("data")
+                .ok_or_else(|| "Missing command data.".to_string())
+                .and_then(SlashCommand::parse)
+        };
+
+        let client = self.http_client();
+        tokio::spawn(async move {
+            let content = match command {
+                Ok(command) => discord_commands::execute(config, command, channel_id).await,
+                Err(err) => err,
+            };
+            if let Err(err) = client
+                .post(&url)
+                .json(&discord_commands::ephemeral_reply(&content))
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+            {
+                tracing::warn!("Discord: failed to answer slash command: {err}");
+            }
+        });
+    }
 }
 
 /// Process Discord message attachments and return a string to append to the
@@ -230,17 +380,22 @@ fn with_inline_attachment_urls(
     lines.join("\n")
 }
 
+/// `id` of the message a successful create/edit call returned.
+async fn sent_message_id(resp: reqwest::Response) -> Option<String> {
+    let body: serde_json::Value = resp.json().await.ok()?;
+    body.get("id")?.as_str().map(String::from)
+}
+
 async fn send_discord_message_json(
     client: &reqwest::Client,
     bot_token: &str,
-    recipient: &str,
+    url: &str,
     content: &str,
-) -> anyhow::Result<()> {
-    let url = format!("https://discord.com/api/v10/channels/{recipient}/messages");
+) -> Result<Option<String>, SendError> {
     let body = json!({ "content": content });
 
     let resp = client
-        .post(&url)
+        .post(url)
         .header("Authorization", format!("Bot {bot_token}"))
         .json(&body)
         .send()
@@ -248,33 +403,36 @@ async fn send_discord_message_json(
 
     if !resp.status().is_success() {
         let status = resp.status();
+        let retry_after = retry_after_header(resp.headers());
         let err = resp
             .text()
             .await
             .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
-        anyhow::bail!("Discord send message failed ({status}): {err}");
+        return Err(SendError::from_status(
+            status,
+            retry_after,
+            anyhow::anyhow!("Discord send message failed ({status}): {err}"),
+        ));
     }
 
-    Ok(())
+    Ok(sent_message_id(resp).await)
 }
 
 async fn send_discord_message_with_files(
     client: &reqwest::Client,
     bot_token: &str,
-    recipient: &str,
+    url: &str,
     content: &str,
     files: &[PathBuf],
-) -> anyhow::Result<()> {
-    let url = format!("https://discord.com/api/v10/channels/{recipient}/messages");
-
+) -> Result<Option<String>, SendError> {
     let mut form = Form::new().text("payload_json", json!({ "content": content }).to_string());
 
     for (idx, path) in files.iter().enumerate() {
         let bytes = tokio::fs::read(path).await.map_err(|error| {
-            anyhow::anyhow!(
+            SendError::fatal(anyhow::anyhow!(
                 "Discord attachment read failed for '{}': {error}",
                 path.display()
-            )
+            ))
         })?;
         let filename = path
             .file_name()
@@ -288,7 +446,7 @@ async fn send_discord_message_with_files(
     }
 
     let resp = client
-        .post(&url)
+        .post(url)
         .header("Authorization", format!("Bot {bot_token}"))
         .multipart(form)
         .send()
@@ -296,14 +454,19 @@ async fn send_discord_message_with_files(
 
     if !resp.status().is_success() {
         let status = resp.status();
+        let retry_after = retry_after_header(resp.headers());
         let err = resp
             .text()
             .await
             .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
-        anyhow::bail!("Discord send message with files failed ({status}): {err}");
+        return Err(SendError::from_status(
+            status,
+            retry_after,
+            anyhow::anyhow!("Discord send message with files failed ({status}): {err}"),
+        ));
     }
 
-    Ok(())
+    Ok(sent_message_id(resp).await)
 }
 
 const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
@@ -315,51 +478,8 @@ const DISCORD_MAX_MESSAGE_LENGTH: usize = 2000;
 const DISCORD_ACK_REACTIONS: &[&str] = &["⚡️", "🦀", "🙌", "💪", "👌", "👀", "👣"];
 
 /// Split a message into chunks that respect Discord's 2000-character limit.
-/// Tries to split at word boundaries when possible.
 fn split_message_for_discord(message: &str) -> Vec<String> {
-    if message.chars().count() <= DISCORD_MAX_MESSAGE_LENGTH {
-        return vec![message.to_string()];
-    }
-
-    let mut chunks = Vec::new();
-    let mut remaining = message;
-
-    while !remaining.is_empty() {
-        // Find the byte offset for the 2000th character boundary.
-        // If there are fewer than 2000 chars left, we can emit the tail directly.
-        let hard_split = remaining
-            .char_indices()
-            .nth(DISCORD_MAX_MESSAGE_LENGTH)
-            .map_or(remaining.len(), |(idx, _)| idx);
-
-        let chunk_end = if hard_split == remaining.len() {
-            hard_split
-        } else {
-            // Try to find a good break point (newline, then space)
-            let search_area = &remaining[..hard_split];
-
-            // Prefer splitting at newline
-            if let Some(pos) = search_area.rfind('\n') {
-                // Don't split if the newline is too close to the end
-                if search_area[..pos].chars().count() >= DISCORD_MAX_MESSAGE_LENGTH / 2 {
-                    pos + 1
-                } else {
-                    // Try space as fallback
-                    search_area.rfind(' ').map_or(hard_split, |space| space + 1)
-                }
-            } else if let Some(pos) = search_area.rfind(' ') {
-                pos + 1
-            } else {
-                // Hard split at the limit
-                hard_split
-            }
-        };
-
-        chunks.push(remaining[..chunk_end].to_string());
-        remaining = &remaining[chunk_end..];
-    }
-
-    chunks
+    split_message(message, DISCORD_MAX_MESSAGE_LENGTH)
 }
 
 fn pick_uniform_index(len: usize) -> usize {
@@ -413,22 +533,44 @@ fn contains_bot_mention(content: &str, bot_user_id: &str) -> bool {
     content.contains(&tags[0]) || content.contains(&tags[1])
 }
 
-fn normalize_incoming_content(
-    content: &str,
+/// What `normalize_incoming_content` needs to know about an inbound message.
+struct MentionGate<'a> {
     mention_only: bool,
-    bot_user_id: &str,
-) -> Option<String> {
+    bot_user_id: &'a str,
+    /// DMs carry no `guild_id` and are never gated.
+    is_dm: bool,
+    /// The message replies to one of the bot's messages.
+    replied_to_bot: bool,
+    command_prefix: Option<&'a str>,
+}
+
+/// Return the content to forward, or `None` to ignore the message.
+///
+/// With `mention_only`, guild messages must start with the command prefix,
+/// mention the bot, or reply to it, and bot mentions are stripped. The
+/// prefix is always stripped.
+fn normalize_incoming_content(content: &str, gate: &MentionGate<'_>) -> Option<String> {
     if content.is_empty() {
         return None;
     }
 
-    if mention_only && !contains_bot_mention(content, bot_user_id) {
+    let prefixed = gate
+        .command_prefix
+        .and_then(|prefix| content.trim_start().strip_prefix(prefix));
+    let body = prefixed.unwrap_or(content);
+
+    if gate.mention_only
+        && !gate.is_dm
+        && prefixed.is_none()
+        && !gate.replied_to_bot
+        && !contains_bot_mention(content, gate.bot_user_id)
+    {
         return None;
     }
 
-    let mut normalized = content.to_string();
-    if mention_only {
-        for tag in mention_tags(bot_user_id) {
+    let mut normalized = body.to_string();
+    if gate.mention_only {
+        for tag in mention_tags(gate.bot_user_id) {
             normalized = normalized.replace(&tag, " ");
         }
     }
@@ -486,6 +628,13 @@ impl Channel for DiscordChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        self.send_with_id(message).await.map(drop)
+    }
+
+    /// Returns the id of the last chunk sent.
+    async fn send_with_id(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        let _permit = self.outbound.acquire_for(&message.recipient).await;
+
         let raw_content = super::strip_tool_call_tags(&message.content);
         let (cleaned_content, parsed_attachments) = parse_attachment_markers(&raw_content);
         let (mut local_files, remote_urls, unresolved_markers) =
@@ -507,31 +656,81 @@ impl Channel for DiscordChannel {
             local_files.truncate(10);
         }
 
+        let cleaned_content = DiscordFormatter.format(&cleaned_content);
         let content =
             with_inline_attachment_urls(&cleaned_content, &remote_urls, &unresolved_markers);
         let chunks = split_message_for_discord(&content);
         let client = self.http_client();
+        let local_files = &local_files;
+        let url = format!("{}/channels/{}/messages", self.api_base, message.recipient);
+        let last_message_id = Mutex::new(None);
+
+        send_chunks_with_retry(
+            "Discord send message",
+            chunks.len(),
+            std::time::Duration::from_millis(500),
+            self.outbound.retry_jitter_ms(),
+            |i| {
+                let (client, url, last_message_id) = (&client, &url, &last_message_id);
+                let chunk = &chunks[i];
+                async move {
+                    let sent = if i == 0 && !local_files.is_empty() {
+                        send_discord_message_with_files(
+                            client,
+                            &self.bot_token,
+                            url,
+                            chunk,
+                            local_files,
+                        )
+                        .await?
+                    } else {
+                        send_discord_message_json(client, &self.bot_token, url, chunk).await?
+                    };
+                    *last_message_id.lock() = sent;
+                    Ok(())
+                }
+            },
+        )
+        .await?;
+        Ok(last_message_id.into_inner())
+    }
 
-        for (i, chunk) in chunks.iter().enumerate() {
-            if i == 0 && !local_files.is_empty() {
-                send_discord_message_with_files(
-                    &client,
-                    &self.bot_token,
-                    &message.recipient,
-                    chunk,
-                    &local_files,
-                )
-                .await?;
-            } else {
-                send_discord_message_json(&client, &self.bot_token, &message.recipient, chunk)
-                    .await?;
-            }
+    fn supports_message_edits(&self) -> bool {
+        true
+    }
 
-            if i < chunks.len() - 1 {
-                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-            }
+    async fn edit_message(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let content = DiscordFormatter.format(&super::strip_tool_call_tags(text));
+        if content.chars().count() > DISCORD_MAX_MESSAGE_LENGTH
+            || !parse_attachment_markers(&content).1.is_empty()
+        {
+            anyhow::bail!("Discord cannot edit message {message_id} into this content");
+        }
+        let _permit = self.outbound.acquire().await;
+        let raw_id = message_id.strip_prefix("discord_").unwrap_or(message_id);
+        let resp = self
+            .http_client()
+            .patch(format!(
+                "{}/channels/{recipient}/messages/{raw_id}",
+                self.api_base
+            ))
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .json(&json!({ "content": content }))
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            anyhow::bail!("Discord edit message failed ({status}): {err}");
         }
-
         Ok(())
     }
 
@@ -659,10 +858,27 @@ impl Channel for DiscordChannel {
                         _ => {}
                     }
 
-                    // Only handle MESSAGE_CREATE (opcode 0, type "MESSAGE_CREATE")
+                    // Dispatch events (opcode 0): READY registers slash commands,
+                    // INTERACTION_CREATE answers them, MESSAGE_CREATE feeds the agent.
                     let event_type = event.get("t").and_then(|t| t.as_str()).unwrap_or("");
-                    if event_type != "MESSAGE_CREATE" {
-                        continue;
+                    match event_type {
+                        "READY" => {
+                            if let Some(app_id) = event
+                                .pointer("/d/application/id")
+                                .and_then(serde_json::Value::as_str)
+                            {
+                                self.spawn_command_registration(app_id);
+                            }
+                            continue;
+                        }
+                        "INTERACTION_CREATE" => {
+                            if let Some(d) = event.get("d") {
+                                self.spawn_interaction_reply(d);
+                            }
+                            continue;
+                        }
+                        "MESSAGE_CREATE" => {}
+                        _ => continue,
                     }
 
                     let Some(d) = event.get("d") else {
@@ -698,9 +914,19 @@ impl Channel for DiscordChannel {
                     }
 
                     let content = d.get("content").and_then(|c| c.as_str()).unwrap_or("");
-                    let Some(clean_content) =
-                        normalize_incoming_content(content, self.mention_only, &bot_user_id)
-                    else {
+                    let gate = MentionGate {
+                        mention_only: self.mention_only,
+                        bot_user_id: &bot_user_id,
+                        is_dm: d.get("guild_id").is_none(),
+                        replied_to_bot: d
+                            .get("referenced_message")
+                            .and_then(|m| m.get("author"))
+                            .and_then(|a| a.get("id"))
+                            .and_then(serde_json::Value::as_str)
+                            .is_some_and(|id| id == bot_user_id),
+                        command_prefix: self.command_prefix.as_deref(),
+                    };
+                    let Some(clean_content) = normalize_incoming_content(content, &gate) else {
                         continue;
                     };
 
@@ -771,6 +997,8 @@ impl Channel for DiscordChannel {
                             .unwrap_or_default()
                             .as_secs(),
                         thread_ts: None,
+                        sender_name: None,
+                        metadata: HashMap::new(),
                     };
 
                     if tx.send(channel_msg).await.is_err() {
@@ -886,6 +1114,49 @@ impl Channel for DiscordChannel {
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn sent_message_id_is_returned_and_used_for_edits() {
+        use wiremock::matchers::{body_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/channels/42/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "1111" })))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/channels/42/messages/1111"))
+            .and(body_json(json!({ "content": "Meeting is at 3pm" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "1111" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/channels/42/messages/2222"))
+            .respond_with(
+                ResponseTemplate::new(404).set_body_json(json!({ "message": "Unknown Message" })),
+            )
+            .mount(&server)
+            .await;
+
+        let ch = DiscordChannel::new("fake".into(), None, vec![], false, false)
+            .with_api_base(server.uri());
+        assert!(ch.supports_message_edits());
+        let id = ch
+            .send_with_id(&SendMessage::new("Meeting is at 2pm", "42"))
+            .await
+            .unwrap()
+            .expect("Discord returns the id of the created message");
+        assert_eq!(id, "1111");
+
+        ch.edit_message("42", &id, "Meeting is at 3pm")
+            .await
+            .unwrap();
+        let err = ch.edit_message("42", "2222", "too late").await.unwrap_err();
+        assert!(err.to_string().contains("404"));
+    }
+
     #[test]
     fn discord_channel_name() {
         let ch = DiscordChannel::new("fake".into(), None, vec![], false, false);
@@ -996,24 +1267,75 @@ mod tests {
         assert!(!contains_bot_mention("hi <@99999>", "12345"));
     }
 
+    fn guild_gate() -> MentionGate<'static> {
+        MentionGate {
+            mention_only: true,
+            bot_user_id: "12345",
+            is_dm: false,
+            replied_to_bot: false,
+            command_prefix: Some("!claw"),
+        }
+    }
+
     #[test]
     fn normalize_incoming_content_requires_mention_when_enabled() {
-        let cleaned = normalize_incoming_content("hello there", true, "12345");
+        let cleaned = normalize_incoming_content("hello there", &guild_gate());
         assert!(cleaned.is_none());
     }
 
     #[test]
     fn normalize_incoming_content_strips_mentions_and_trims() {
-        let cleaned = normalize_incoming_content("  <@!12345> run status  ", true, "12345");
+        let cleaned = normalize_incoming_content("  <@!12345> run status  ", &guild_gate());
         assert_eq!(cleaned.as_deref(), Some("run status"));
     }
 
     #[test]
     fn normalize_incoming_content_rejects_empty_after_strip() {
-        let cleaned = normalize_incoming_content("<@12345>", true, "12345");
+        let cleaned = normalize_incoming_content("<@12345>", &guild_gate());
         assert!(cleaned.is_none());
     }
 
+    #[test]
+    fn mention_gate_lets_dms_through() {
+        let gate = MentionGate {
+            is_dm: true,
+            ..guild_gate()
+        };
+        let cleaned = normalize_incoming_content("hello there", &gate);
+        assert_eq!(cleaned.as_deref(), Some("hello there"));
+    }
+
+    #[test]
+    fn mention_gate_accepts_reply_to_bot() {
+        let gate = MentionGate {
+            replied_to_bot: true,
+            ..guild_gate()
+        };
+        let cleaned = normalize_incoming_content("yes, do it", &gate);
+        assert_eq!(cleaned.as_deref(), Some("yes, do it"));
+    }
+
+    #[test]
+    fn mention_gate_accepts_and_strips_command_prefix() {
+        let cleaned = normalize_incoming_content("  !claw what's the weather", &guild_gate());
+        assert_eq!(cleaned.as_deref(), Some("what's the weather"));
+        assert!(normalize_incoming_content("say !claw later", &guild_gate()).is_none());
+    }
+
+    #[test]
+    fn mention_gate_off_forwards_everything() {
+        let gate = MentionGate {
+            mention_only: false,
+            ..guild_gate()
+        };
+        let cleaned = normalize_incoming_content(" <@12345> hello there ", &gate);
+        assert_eq!(cleaned.as_deref(), Some("<@12345> hello there"));
+        assert_eq!(
+            normalize_incoming_content("chatter", &gate).as_deref(),
+            Some("chatter")
+        );
+    }
+
     // Message splitting tests
 
     #[test]
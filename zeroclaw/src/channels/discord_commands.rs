@@ -0,0 +1,474 @@
+//! Discord application (slash) commands for cron management and status.
+//!
+//! Commands are registered over REST when the gateway reports `READY` and
+//! arrive back as `INTERACTION_CREATE` events. Replies are ephemeral, so only
+//! the invoking user sees them.
+
+use crate::config::Config;
+use crate::cron::{self, DeliveryConfig, Schedule, SessionTarget};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// Shortest interval accepted by `/claw_cron_add`.
+pub const MIN_EVERY_SECS: u64 = 60;
+
+/// Discord caps interaction reply content at 2000 characters.
+const MAX_REPLY_CHARS: usize = 1900;
+
+/// Interaction type for application commands.
+const INTERACTION_APPLICATION_COMMAND: u64 = 2;
+
+/// Interaction callback type `CHANNEL_MESSAGE_WITH_SOURCE`.
+const CALLBACK_CHANNEL_MESSAGE: u64 = 4;
+
+/// Message flag that hides a reply from everyone but the invoker.
+const FLAG_EPHEMERAL: u64 = 1 << 6;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlashCommand {
+    Status,
+    CronList,
+    CronAdd { message: String, every_secs: u64 },
+    CronRemove { id: String },
+}
+
+/// Command definitions for the bulk-overwrite endpoint
+/// (`PUT /applications/{app_id}/commands`).
+pub fn command_definitions() -> Value {
+    // Option types: 3 = STRING, 4 = INTEGER.
+    json!([
+        {
+            "name": "claw_status",
+            "type": 1,
+            "description": "Show ZeroClaw runtime status"
+        },
+        {
+            "name": "claw_cron_list",
+            "type": 1,
+            "description": "List scheduled cron jobs"
+        },
+        {
+            "name": "claw_cron_add",
+            "type": 1,
+            "description": "Schedule a recurring agent prompt",
+            "options": [
+                {
+                    "name": "message",
+                    "type": 3,
+                    "description": "Prompt to run on each tick",
+                    "required": true
+                },
+                {
+                    "name": "every",
+                    "type": 4,
+                    "description": "Interval in seconds",
+                    "required": true,
+                    "min_value": MIN_EVERY_SECS
+                }
+            ]
+        },
+        {
+            "name": "claw_cron_remove",
+            "type": 1,
+            "description": "Remove a scheduled cron job",
+            "options": [
+                {
+                    "name": "id",
+                    "type": 3,
+                    "description": "Job id from /claw_cron_list",
+                    "required": true
+                }
+            ]
+        }
+    ])
+}
+
+/// Check if a Discord user ID may run slash commands.
+/// Empty list means deny everyone; `"*"` means allow everyone.
+pub fn is_command_user_allowed(command_users: &[String], user_id: &str) -> bool {
+    !user_id.is_empty() && command_users.iter().any(|u| u == "*" || u == user_id)
+}
+
+/// Invoking user of an interaction: `member.user` in guilds, `user` in DMs.
+pub fn interaction_user_id(interaction: &Value) -> Option<&str> {
+    interaction
+        .get("member")
+        .and_then(|m| m.get("user"))
+        .or_else(|| interaction.get("user"))
+        .and_then(|u| u.get("id"))
+        .and_then(Value::as_str)
+}
+
+/// Whether an `INTERACTION_CREATE` payload is an application command.
+pub fn is_application_command(interaction: &Value) -> bool {
+    interaction.get("type").and_then(Value::as_u64) == Some(INTERACTION_APPLICATION_COMMAND)
+}
+
+/// Convert an `every:<seconds>` argument into a cron schedule.
+pub fn every_schedule(every_secs: u64) -> Result<Schedule, String> {
+    if every_secs < MIN_EVERY_SECS {
+        return Err(format!("`every` must be at least {MIN_EVERY_SECS} seconds"));
+    }
+    let every_ms = every_secs
+        .checked_mul(1000)
+        .ok_or_else(|| "`every` is too large".to_string())?;
+    Ok(Schedule::Every { every_ms })
+}
+
+fn option<'a>(data: &'a Value, name: &str) -> Option<&'a Value> {
+    data.get("options")
+        .and_then(Value::as_array)?
+        .iter()
+        .find(|o| o.get("name").and_then(Value::as_str) == Some(name))
+        .and_then(|o| o.get("value"))
+}
+
+fn string_option(data: &Value, name: &str) -> Result<String, String> {
+    option(data, name)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(ToString::to_string)
+        .ok_or_else(|| format!("missing `{name}`"))
+}
+
+impl SlashCommand {
+    /// Parse the `data` object of an application command interaction.
+    pub fn parse(data: &Value) -> Result<Self, String> {
+        let name = data.get("name").and_then(Value::as_str).unwrap_or("");
+        match name {
+            "claw_status" => Ok(Self::Status),
+            "claw_cron_list" => Ok(Self::CronList),
+            "claw_cron_add" => {
+                let message = string_option(data, "message")?;
+                let every_secs = option(data, "every")
+                    .and_then(Value::as_u64)
+                    .ok_or_else(|| "`every` must be a positive number of seconds".to_string())?;
+                every_schedule(every_secs)?;
+                Ok(Self::CronAdd {
+                    message,
+                    every_secs,
+                })
+            }
+            "claw_cron_remove" => Ok(Self::CronRemove {
+                id: string_option(data, "id")?,
+            }),
+            other => Err(format!("unknown command `{other}`")),
+        }
+    }
+}
+
+/// Ephemeral `CHANNEL_MESSAGE_WITH_SOURCE` callback body.
+pub fn ephemeral_reply(content: &str) -> Value {
+    json!({
+        "type": CALLBACK_CHANNEL_MESSAGE,
+        "data": {
+            "content": crate::util::truncate_with_ellipsis(content, MAX_REPLY_CHARS),
+            "flags": FLAG_EPHEMERAL
+        }
+    })
+}
+
+fn format_uptime(secs: u64) -> String {
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    let (hours, rem) = (rem / 3600, rem % 3600);
+    let minutes = rem / 60;
+    if days > 0 {
+        format!("{days}d {hours}h {minutes}m")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m {}s", rem % 60)
+    }
+}
+
+fn describe_schedule(schedule: &Schedule) -> String {
+    match schedule {
+        Schedule::Cron { expr, tz: Some(tz) } => format!("cron `{expr}` ({tz})"),
+        Schedule::Cron { expr, tz: None } => format!("cron `{expr}`"),
+        Schedule::At {
+            at,
+            original: Some(original),
+        } => format!("once at {} ({original})", at.to_rfc3339()),
+        Schedule::At { at, original: None } => format!("once at {}", at.to_rfc3339()),
+        Schedule::Every { every_ms } => format!("every {}s", every_ms / 1000),
+    }
+}
+
+fn status_text(config: &Config) -> String {
+    let health = crate::health::snapshot();
+    let unhealthy: Vec<&str> = health
+        .components
+        .iter()
+        .filter(|(_, c)| c.status != "ok")
+        .map(|(name, _)| name.as_str())
+        .collect();
+    let components = if unhealthy.is_empty() {
+        format!("{} ok", health.components.len())
+    } else {
+        format!("degraded: {}", unhealthy.join(", "))
+    };
+    let jobs = cron::list_jobs(config)
+        .map_or_else(|e| format!("unavailable ({e})"), |j| j.len().to_string());
+    format!(
+        "**ZeroClaw status**\nuptime: {}\ncomponents: {components}\ncron jobs: {jobs}",
+        format_uptime(health.uptime_seconds)
+    )
+}
+
+fn cron_list_text(config: &Config) -> anyhow::Result<String> {
+    let jobs = cron::list_jobs(config)?;
+    if jobs.is_empty() {
+        return Ok("No scheduled jobs.".into());
+    }
+    let mut lines = vec![format!("**Scheduled jobs ({})**", jobs.len())];
+    for job in jobs {
+        let what = job.prompt.as_deref().unwrap_or(&job.command);
+        lines.push(format!(
+            "`{}` {} next {} {}",
+            job.id,
+            describe_schedule(&job.schedule),
+            job.next_run.format("%Y-%m-%d %H:%M UTC"),
+            crate::util::truncate_with_ellipsis(what, 80)
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn execute_blocking(config: &Config, command: SlashCommand, channel_id: &str) -> String {
+    match command {
+        SlashCommand::Status => status_text(config),
+        SlashCommand::CronList => {
+            cron_list_text(config).unwrap_or_else(|e| format!("Failed to list jobs: {e}"))
+        }
+        SlashCommand::CronAdd {
+            message,
+            every_secs,
+        } => {
+            let schedule = match every_schedule(every_secs) {
+                Ok(s) => s,
+                Err(e) => return e,
+            };
+            let delivery = DeliveryConfig {
+                mode: "announce".into(),
+                channel: Some("discord".into()),
+                to: Some(channel_id.to_string()),
+                best_effort: true,
+                session_key: None,
+            };
+            match cron::add_agent_job(
+                config,
+                None,
+                schedule,
+                &message,
+                SessionTarget::Isolated,
+                None,
+                Some(delivery),
+                false,
+            ) {
+                Ok(job) => format!(
+                    "Added job `{}` ({}), next run {}",
+                    job.id,
+                    describe_schedule(&job.schedule),
+                    job.next_run.format("%Y-%m-%d %H:%M UTC")
+                ),
+                Err(e) => format!("Failed to add job: {e}"),
+            }
+        }
+        SlashCommand::CronRemove { id } => match cron::remove_job(config, &id) {
+            Ok(()) => format!("Removed job `{id}`"),
+            Err(e) => format!("Failed to remove job: {e}"),
+        },
+    }
+}
+
+/// Run a parsed command and return the reply text. Cron storage is
+/// synchronous SQLite, so the work happens on the blocking pool.
+pub async fn execute(config: Arc<Config>, command: SlashCommand, channel_id: String) -> String {
+    tokio::task::spawn_blocking(move || execute_blocking(&config, command, &channel_id))
+        .await
+        .unwrap_or_else(|e| format!("Command failed: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(name: &str, options: Value) -> Value {
+        json!({ "id": "1", "name": name, "type": 1, "options": options })
+    }
+
+    #[test]
+    fn parses_cron_add_into_every_schedule() {
+        let cmd = SlashCommand::parse(&data(
+            "claw_cron_add",
+            json!([
+                { "name": "message", "type": 3, "value": "  check the inbox " },
+                { "name": "every", "type": 4, "value": 3600 }
+            ]),
+        ))
+        .unwrap();
+        assert_eq!(
+            cmd,
+            SlashCommand::CronAdd {
+                message: "check the inbox".into(),
+                every_secs: 3600
+            }
+        );
+        assert_eq!(
+            every_schedule(3600).unwrap(),
+            Schedule::Every {
+                every_ms: 3_600_000
+            }
+        );
+    }
+
+    #[test]
+    fn cron_add_rejects_short_or_missing_interval() {
+        let short = data(
+            "claw_cron_add",
+            json!([
+                { "name": "message", "value": "hi" },
+                { "name": "every", "value": 5 }
+            ]),
+        );
+        assert!(SlashCommand::parse(&short)
+            .unwrap_err()
+            .contains("at least"));
+
+        let missing = data(
+            "claw_cron_add",
+            json!([{ "name": "message", "value": "hi" }]),
+        );
+        assert!(SlashCommand::parse(&missing).is_err());
+
+        let negative = data(
+            "claw_cron_add",
+            json!([
+                { "name": "message", "value": "hi" },
+                { "name": "every", "value": -60 }
+            ]),
+        );
+        assert!(SlashCommand::parse(&negative).is_err());
+        assert!(every_schedule(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn cron_add_rejects_blank_message() {
+        let blank = data(
+            "claw_cron_add",
+            json!([
+                { "name": "message", "value": "   " },
+                { "name": "every", "value": 60 }
+            ]),
+        );
+        assert_eq!(
+            SlashCommand::parse(&blank).unwrap_err(),
+            "missing `message`"
+        );
+    }
+
+    #[test]
+    fn parses_remove_status_and_list() {
+        assert_eq!(
+            SlashCommand::parse(&data(
+                "claw_cron_remove",
+                json!([{ "name": "id", "value": "abc" }])
+            ))
+            .unwrap(),
+            SlashCommand::CronRemove { id: "abc".into() }
+        );
+        assert_eq!(
+            SlashCommand::parse(&json!({ "name": "claw_status" })).unwrap(),
+            SlashCommand::Status
+        );
+        assert_eq!(
+            SlashCommand::parse(&json!({ "name": "claw_cron_list" })).unwrap(),
+            SlashCommand::CronList
+        );
+        assert!(SlashCommand::parse(&json!({ "name": "other" })).is_err());
+    }
+
+    #[test]
+    fn definitions_cover_every_parsed_command() {
+        let defs = command_definitions();
+        let names: Vec<&str> = defs
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|d| d["name"].as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "claw_status",
+                "claw_cron_list",
+                "claw_cron_add",
+                "claw_cron_remove"
+            ]
+        );
+    }
+
+    #[test]
+    fn permission_gate_denies_by_default() {
+        assert!(!is_command_user_allowed(&[], "123"));
+        let users = vec!["123".to_string()];
+        assert!(is_command_user_allowed(&users, "123"));
+        assert!(!is_command_user_allowed(&users, "1234"));
+        assert!(!is_command_user_allowed(&users, ""));
+        assert!(is_command_user_allowed(&["*".to_string()], "999"));
+    }
+
+    #[test]
+    fn interaction_user_prefers_guild_member() {
+        let guild = json!({ "member": { "user": { "id": "1" } }, "user": { "id": "2" } });
+        assert_eq!(interaction_user_id(&guild), Some("1"));
+        let dm = json!({ "user": { "id": "2" } });
+        assert_eq!(interaction_user_id(&dm), Some("2"));
+        assert_eq!(interaction_user_id(&json!({})), None);
+    }
+
+    #[test]
+    fn reply_is_ephemeral_and_bounded() {
+        let reply = ephemeral_reply(&"x".repeat(5000));
+        assert_eq!(reply["type"], 4);
+        assert_eq!(reply["data"]["flags"], 64);
+        assert!(reply["data"]["content"].as_str().unwrap().chars().count() <= 2000);
+    }
+
+    #[test]
+    fn cron_add_targets_invoking_channel() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        };
+        std::fs::create_dir_all(&config.workspace_dir).unwrap();
+
+        let reply = execute_blocking(
+            &config,
+            SlashCommand::CronAdd {
+                message: "ping".into(),
+                every_secs: 120,
+            },
+            "chan-9",
+        );
+        assert!(reply.starts_with("Added job"), "{reply}");
+
+        let jobs = cron::list_jobs(&config).unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].delivery.channel.as_deref(), Some("discord"));
+        assert_eq!(jobs[0].delivery.to.as_deref(), Some("chan-9"));
+
+        let reply = execute_blocking(
+            &config,
+            SlashCommand::CronRemove {
+                id: jobs[0].id.clone(),
+            },
+            "chan-9",
+        );
+        assert!(reply.starts_with("Removed job"), "{reply}");
+        assert!(cron::list_jobs(&config).unwrap().is_empty());
+    }
+}
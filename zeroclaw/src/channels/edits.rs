@@ -0,0 +1,170 @@
+//! Replacing replies that were already delivered.
+//!
+//! Channels that can edit messages ([`Channel::supports_message_edits`])
+//! get corrections in place instead of a second message: the progress
+//! status of a turn is edited into its reply, and a correction the agent
+//! makes with the `edit_reply` tool replaces the earlier reply. A tool runs
+//! inside the turn without access to the channel, so it records an
+//! [`OutboundEdit`] with [`note_outbound_edit`] and the runtime applies the
+//! session's edits ([`take_outbound_edits`]) once the model call ends. When
+//! the channel cannot edit or the platform refuses (message too old or
+//! deleted), [`edit_or_send`] sends the text as a new message instead.
+
+use super::traits::{Channel, SendMessage};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// A correction of a delivered reply, waiting for the channel runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboundEdit {
+    /// Persisted assistant turn the correction replaces.
+    pub row_id: i64,
+    /// Platform id of the delivered reply (`<channel>_message_id`).
+    pub message_id: String,
+    pub new_content: String,
+}
+
+fn pending() -> &'static Mutex<HashMap<String, Vec<OutboundEdit>>> {
+    static PENDING: OnceLock<Mutex<HashMap<String, Vec<OutboundEdit>>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Record a correction of a reply in session `key` for the channel runtime
+/// to deliver.
+pub fn note_outbound_edit(key: &str, edit: OutboundEdit) {
+    pending()
+        .lock()
+        .entry(key.to_string())
+        .or_default()
+        .push(edit);
+}
+
+/// Take the corrections noted for `key` since the last call, oldest first.
+pub fn take_outbound_edits(key: &str) -> Vec<OutboundEdit> {
+    pending().lock().remove(key).unwrap_or_default()
+}
+
+/// Show `reply` in place of message `message_id` when the channel supports
+/// edits, otherwise (or when the edit fails) send it as a new message.
+/// Returns the platform id of the message now showing the text.
+pub async fn edit_or_send(
+    channel: &dyn Channel,
+    reply: &SendMessage,
+    message_id: &str,
+) -> anyhow::Result<Option<String>> {
+    if channel.supports_message_edits() {
+        match channel
+            .edit_message(&reply.recipient, message_id, &reply.content)
+            .await
+        {
+            Ok(()) => return Ok(Some(message_id.to_string())),
+            Err(e) => tracing::info!(
+                "Editing message {message_id} on {} failed; sending a new message: {e}",
+                channel.name()
+            ),
+        }
+    }
+    channel.send_with_id(reply).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Edits succeed only for message "1"; sends get ids "new-<n>".
+    #[derive(Default)]
+    struct EditingChannel {
+        edits_supported: bool,
+        ops: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Channel for EditingChannel {
+        fn name(&self) -> &str {
+            "editing"
+        }
+
+        async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+            self.send_with_id(message).await.map(drop)
+        }
+
+        async fn send_with_id(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+            let mut ops = self.ops.lock();
+            ops.push(format!("send:{}", message.content));
+            Ok(Some(format!("new-{}", ops.len())))
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<super::super::traits::ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn supports_message_edits(&self) -> bool {
+            self.edits_supported
+        }
+
+        async fn edit_message(
+            &self,
+            _recipient: &str,
+            message_id: &str,
+            text: &str,
+        ) -> anyhow::Result<()> {
+            self.ops.lock().push(format!("edit:{message_id}:{text}"));
+            anyhow::ensure!(message_id == "1", "message to edit not found");
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn edits_in_place_and_falls_back_to_a_new_message() {
+        let channel = EditingChannel {
+            edits_supported: true,
+            ..EditingChannel::default()
+        };
+        let reply = SendMessage::new("fixed", "chat");
+
+        assert_eq!(
+            edit_or_send(&channel, &reply, "1").await.unwrap(),
+            Some("1".into())
+        );
+        assert_eq!(
+            edit_or_send(&channel, &reply, "2").await.unwrap(),
+            Some("new-3".into())
+        );
+        assert_eq!(
+            *channel.ops.lock(),
+            vec!["edit:1:fixed", "edit:2:fixed", "send:fixed"]
+        );
+    }
+
+    #[tokio::test]
+    async fn sends_without_trying_when_the_channel_cannot_edit() {
+        let channel = EditingChannel::default();
+        let reply = SendMessage::new("fixed", "chat");
+        assert_eq!(
+            edit_or_send(&channel, &reply, "1").await.unwrap(),
+            Some("new-1".into())
+        );
+        assert_eq!(*channel.ops.lock(), vec!["send:fixed"]);
+    }
+
+    #[test]
+    fn edits_are_taken_once_per_session_in_order() {
+        let edit = |row_id| OutboundEdit {
+            row_id,
+            message_id: row_id.to_string(),
+            new_content: "fixed".into(),
+        };
+        note_outbound_edit("edits-a", edit(1));
+        note_outbound_edit("edits-a", edit(2));
+        note_outbound_edit("edits-b", edit(3));
+
+        assert_eq!(take_outbound_edits("edits-a"), vec![edit(1), edit(2)]);
+        assert!(take_outbound_edits("edits-a").is_empty());
+        assert_eq!(take_outbound_edits("edits-b"), vec![edit(3)]);
+    }
+}
@@ -15,14 +15,15 @@ use async_imap::Session;
 use async_trait::async_trait;
 use futures_util::TryStreamExt;
 use lettre::message::SinglePart;
-use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::authentication::{Credentials, Mechanism};
 use lettre::{Message, SmtpTransport, Transport};
 use mail_parser::{MessageParser, MimeHeaders};
 use rustls::{ClientConfig, RootCertStore};
 use rustls_pki_types::DnsName;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::net::TcpStream;
@@ -57,7 +58,12 @@ pub struct EmailConfig {
     /// Email username for authentication
     pub username: String,
     /// Email password for authentication
+    #[serde(default)]
     pub password: String,
+    /// OAuth2 access token. When set, IMAP and SMTP authenticate with XOAUTH2
+    /// instead of `password` (Gmail, Microsoft 365).
+    #[serde(default)]
+    pub oauth_token: Option<String>,
     /// From address for outgoing emails
     pub from_address: String,
     /// IDLE timeout in seconds before re-establishing connection (default: 1740 = 29 minutes)
@@ -105,6 +111,7 @@ impl Default for EmailConfig {
             smtp_tls: true,
             username: String::new(),
             password: String::new(),
+            oauth_token: None,
             from_address: String::new(),
             idle_timeout_secs: default_idle_timeout(),
             allowed_senders: Vec::new(),
@@ -114,10 +121,63 @@ impl Default for EmailConfig {
 
 type ImapSession = Session<TlsStream<TcpStream>>;
 
+/// SASL XOAUTH2 initial response for IMAP `AUTHENTICATE XOAUTH2`.
+struct XOAuth2 {
+    user: String,
+    access_token: String,
+}
+
+impl async_imap::Authenticator for XOAuth2 {
+    type Response = String;
+
+    fn process(&mut self, _challenge: &[u8]) -> Self::Response {
+        format!(
+            "user={}\x01auth=Bearer {}\x01\x01",
+            self.user, self.access_token
+        )
+    }
+}
+
+/// Last inbound message from a sender, used to thread the reply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EmailThread {
+    subject: String,
+    message_id: String,
+    /// `References` of the inbound message (ids without angle brackets)
+    references: Vec<String>,
+}
+
+impl EmailThread {
+    fn reply_subject(&self) -> String {
+        if self.subject.len() >= 3 && self.subject[..3].eq_ignore_ascii_case("re:") {
+            self.subject.clone()
+        } else {
+            format!("Re: {}", self.subject)
+        }
+    }
+
+    fn in_reply_to(&self) -> String {
+        format!("<{}>", self.message_id)
+    }
+
+    /// RFC 5322: the parent's `References` followed by the parent's id.
+    fn references(&self) -> String {
+        self.references
+            .iter()
+            .chain(std::iter::once(&self.message_id))
+            .map(|id| format!("<{id}>"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
 /// Email channel — IMAP IDLE for instant push notifications, SMTP for outbound
 pub struct EmailChannel {
     pub config: EmailConfig,
     seen_messages: Arc<Mutex<HashSet<String>>>,
+    /// Conversation state per sender (lowercased address)
+    threads: Arc<Mutex<HashMap<String, EmailThread>>>,
+    workspace_dir: Option<PathBuf>,
 }
 
 impl EmailChannel {
@@ -125,9 +185,17 @@ impl EmailChannel {
         Self {
             config,
             seen_messages: Arc::new(Mutex::new(HashSet::new())),
+            threads: Arc::new(Mutex::new(HashMap::new())),
+            workspace_dir: None,
         }
     }
 
+    /// Directory where inbound attachments are saved (`{dir}/email_files/`).
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
     /// Check if a sender email is in the allowlist
     pub fn is_sender_allowed(&self, email: &str) -> bool {
         if self.config.allowed_senders.is_empty() {
@@ -173,6 +241,45 @@ impl EmailChannel {
         normalized
     }
 
+    /// Remove quoted history from a reply so only the new text reaches the agent.
+    ///
+    /// Cuts at the first reply header (`On … wrote:`, `-----Original Message-----`,
+    /// Outlook's underscore rule) or signature delimiter, and drops `>` quoted
+    /// lines. Falls back to the original text when nothing would be left.
+    pub fn strip_quoted_history(text: &str) -> String {
+        let lines: Vec<&str> = text.lines().collect();
+        let mut kept: Vec<&str> = Vec::new();
+        for (idx, line) in lines.iter().enumerate() {
+            let trimmed = line.trim();
+            let is_attribution = trimmed.ends_with("wrote:")
+                && (trimmed.starts_with("On ")
+                    || idx
+                        .checked_sub(1)
+                        .is_some_and(|prev| lines[prev].trim().starts_with("On ")));
+            let is_separator = (trimmed.starts_with("-----")
+                && trimmed.to_ascii_lowercase().contains("original message"))
+                || (trimmed.len() >= 10 && trimmed.chars().all(|c| c == '_'))
+                || *line == "-- ";
+            if is_attribution || is_separator {
+                // A wrapped attribution starts on the previous line.
+                if is_attribution && !trimmed.starts_with("On ") {
+                    kept.pop();
+                }
+                break;
+            }
+            if trimmed.starts_with('>') {
+                continue;
+            }
+            kept.push(line);
+        }
+        let stripped = kept.join("\n").trim().to_string();
+        if stripped.is_empty() {
+            text.trim().to_string()
+        } else {
+            stripped
+        }
+    }
+
     /// Extract the sender address from a parsed email
     fn extract_sender(parsed: &mail_parser::Message) -> String {
         parsed
@@ -228,10 +335,21 @@ impl EmailChannel {
         let client = async_imap::Client::new(stream);
 
         // Login
-        let session = client
-            .login(&self.config.username, &self.config.password)
-            .await
-            .map_err(|(e, _)| anyhow!("IMAP login failed: {}", e))?;
+        let session = if let Some(token) = &self.config.oauth_token {
+            let auth = XOAuth2 {
+                user: self.config.username.clone(),
+                access_token: token.clone(),
+            };
+            client
+                .authenticate("XOAUTH2", auth)
+                .await
+                .map_err(|(e, _)| anyhow!("IMAP XOAUTH2 authentication failed: {}", e))?
+        } else {
+            client
+                .login(&self.config.username, &self.config.password)
+                .await
+                .map_err(|(e, _)| anyhow!("IMAP login failed: {}", e))?
+        };
 
         debug!("IMAP login successful");
         Ok(session)
@@ -260,50 +378,8 @@ impl EmailChannel {
 
         for msg in messages {
             let uid = msg.uid.unwrap_or(0);
-            if let Some(body) = msg.body() {
-                if let Some(parsed) = MessageParser::default().parse(body) {
-                    let sender = Self::extract_sender(&parsed);
-                    let subject = parsed.subject().unwrap_or("(no subject)").to_string();
-                    let body_text = Self::extract_text(&parsed);
-                    let content = format!("Subject: {}\n\n{}", subject, body_text);
-                    let msg_id = parsed
-                        .message_id()
-                        .map(|s| s.to_string())
-                        .unwrap_or_else(|| format!("gen-{}", Uuid::new_v4()));
-
-                    #[allow(clippy::cast_sign_loss)]
-                    let ts = parsed
-                        .date()
-                        .map(|d| {
-                            let naive = chrono::NaiveDate::from_ymd_opt(
-                                d.year as i32,
-                                u32::from(d.month),
-                                u32::from(d.day),
-                            )
-                            .and_then(|date| {
-                                date.and_hms_opt(
-                                    u32::from(d.hour),
-                                    u32::from(d.minute),
-                                    u32::from(d.second),
-                                )
-                            });
-                            naive.map_or(0, |n| n.and_utc().timestamp() as u64)
-                        })
-                        .unwrap_or_else(|| {
-                            SystemTime::now()
-                                .duration_since(UNIX_EPOCH)
-                                .map(|d| d.as_secs())
-                                .unwrap_or(0)
-                        });
-
-                    results.push(ParsedEmail {
-                        _uid: uid,
-                        msg_id,
-                        sender,
-                        content,
-                        timestamp: ts,
-                    });
-                }
+            if let Some(email) = msg.body().and_then(|body| Self::parse_email(uid, body)) {
+                results.push(email);
             }
         }
 
@@ -319,6 +395,107 @@ impl EmailChannel {
         Ok(results)
     }
 
+    /// Parse a raw RFC 822 message into the fields the channel needs.
+    fn parse_email(uid: u32, raw: &[u8]) -> Option<ParsedEmail> {
+        let parsed = MessageParser::default().parse(raw)?;
+        let sender = Self::extract_sender(&parsed);
+        let subject = parsed.subject().unwrap_or("(no subject)").to_string();
+        let body_text = Self::strip_quoted_history(&Self::extract_text(&parsed));
+        let content = format!("Subject: {}\n\n{}", subject, body_text);
+        let msg_id = parsed
+            .message_id()
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| format!("gen-{}", Uuid::new_v4()));
+        let references = parsed
+            .references()
+            .as_text_list()
+            .map(|ids| ids.iter().map(|id| id.to_string()).collect())
+            .unwrap_or_default();
+        let attachments = parsed
+            .attachments()
+            .filter(|part| !part.contents().is_empty())
+            .map(|part| EmailAttachment {
+                name: MimeHeaders::attachment_name(part)
+                    .unwrap_or("attachment")
+                    .to_string(),
+                is_image: MimeHeaders::content_type(part)
+                    .map_or(false, |ct| ct.ctype().eq_ignore_ascii_case("image")),
+                data: part.contents().to_vec(),
+            })
+            .collect();
+
+        #[allow(clippy::cast_sign_loss)]
+        let ts = parsed
+            .date()
+            .map(|d| {
+                let naive = chrono::NaiveDate::from_ymd_opt(
+                    d.year as i32,
+                    u32::from(d.month),
+                    u32::from(d.day),
+                )
+                .and_then(|date| {
+                    date.and_hms_opt(u32::from(d.hour), u32::from(d.minute), u32::from(d.second))
+                });
+                naive.map_or(0, |n| n.and_utc().timestamp() as u64)
+            })
+            .unwrap_or_else(|| {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0)
+            });
+
+        Some(ParsedEmail {
+            uid,
+            msg_id,
+            sender,
+            subject,
+            references,
+            content,
+            attachments,
+            timestamp: ts,
+        })
+    }
+
+    /// Save attachments to `{workspace}/email_files/` and return the content
+    /// markers for them: `[IMAGE:/path]` for images, `[Document: name] /path`
+    /// otherwise, matching the other channels' attachment handling.
+    async fn save_attachments(&self, uid: u32, attachments: &[EmailAttachment]) -> Vec<String> {
+        if attachments.is_empty() {
+            return Vec::new();
+        }
+        let Some(workspace) = self.workspace_dir.as_ref() else {
+            warn!("Cannot save email attachments: workspace_dir not configured");
+            return Vec::new();
+        };
+        let save_dir = workspace.join("email_files");
+        if let Err(e) = tokio::fs::create_dir_all(&save_dir).await {
+            warn!("Failed to create email_files directory: {e}");
+            return Vec::new();
+        }
+
+        let mut markers = Vec::new();
+        for attachment in attachments {
+            // Never trust the sender's path components.
+            let base = Path::new(&attachment.name)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("attachment");
+            let local_filename = format!("{uid}_{base}");
+            let local_path = save_dir.join(&local_filename);
+            if let Err(e) = tokio::fs::write(&local_path, &attachment.data).await {
+                warn!("Failed to save attachment to {}: {e}", local_path.display());
+                continue;
+            }
+            markers.push(if attachment.is_image {
+                format!("[IMAGE:{}]", local_path.display())
+            } else {
+                format!("[Document: {}] {}", base, local_path.display())
+            });
+        }
+        markers
+    }
+
     /// Run the IDLE loop, returning when a new message arrives or timeout
     /// Note: IDLE consumes the session and returns it via done()
     async fn wait_for_changes(
@@ -452,14 +629,31 @@ impl EmailChannel {
                 continue;
             }
 
+            self.threads.lock().await.insert(
+                email.sender.to_lowercase(),
+                EmailThread {
+                    subject: email.subject,
+                    message_id: email.msg_id.clone(),
+                    references: email.references,
+                },
+            );
+
+            let mut content = email.content;
+            for marker in self.save_attachments(email.uid, &email.attachments).await {
+                content.push_str("\n\n");
+                content.push_str(&marker);
+            }
+
             let msg = ChannelMessage {
                 id: email.msg_id,
                 reply_target: email.sender.clone(),
                 sender: email.sender,
-                content: email.content,
+                content,
                 channel: "email".to_string(),
                 timestamp: email.timestamp,
                 thread_ts: None,
+                sender_name: None,
+                metadata: HashMap::new(),
             };
 
             if tx.send(msg).await.is_err() {
@@ -472,31 +666,47 @@ impl EmailChannel {
     }
 
     fn create_smtp_transport(&self) -> Result<SmtpTransport> {
-        let creds = Credentials::new(self.config.username.clone(), self.config.password.clone());
-        let transport = if self.config.smtp_tls {
+        let secret = self
+            .config
+            .oauth_token
+            .clone()
+            .unwrap_or_else(|| self.config.password.clone());
+        let creds = Credentials::new(self.config.username.clone(), secret);
+        let builder = if self.config.smtp_tls {
             SmtpTransport::relay(&self.config.smtp_host)?
-                .port(self.config.smtp_port)
-                .credentials(creds)
-                .build()
         } else {
             SmtpTransport::builder_dangerous(&self.config.smtp_host)
-                .port(self.config.smtp_port)
-                .credentials(creds)
-                .build()
+        }
+        .port(self.config.smtp_port)
+        .credentials(creds);
+        let builder = if self.config.oauth_token.is_some() {
+            builder.authentication(vec![Mechanism::Xoauth2])
+        } else {
+            builder
         };
-        Ok(transport)
+        Ok(builder.build())
     }
 }
 
 /// Internal struct for parsed email data
 struct ParsedEmail {
-    _uid: u32,
+    uid: u32,
     msg_id: String,
     sender: String,
+    subject: String,
+    references: Vec<String>,
     content: String,
+    attachments: Vec<EmailAttachment>,
     timestamp: u64,
 }
 
+/// Attachment of an inbound email, not yet written to disk
+struct EmailAttachment {
+    name: String,
+    is_image: bool,
+    data: Vec<u8>,
+}
+
 /// Result from waiting on IDLE
 enum IdleWaitResult {
     NewMail,
@@ -511,24 +721,46 @@ impl Channel for EmailChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> Result<()> {
-        // Use explicit subject if provided, otherwise fall back to legacy parsing or default
-        let (subject, body) = if let Some(ref subj) = message.subject {
-            (subj.as_str(), message.content.as_str())
+        // Use explicit subject if provided, otherwise fall back to legacy parsing,
+        // then to a reply in the sender's thread, then to a default
+        let thread = self
+            .threads
+            .lock()
+            .await
+            .get(&message.recipient.to_lowercase())
+            .cloned();
+        let (subject, body, thread) = if let Some(ref subj) = message.subject {
+            (subj.clone(), message.content.as_str(), None)
         } else if message.content.starts_with("Subject: ") {
             if let Some(pos) = message.content.find('\n') {
-                (&message.content[9..pos], message.content[pos + 1..].trim())
+                (
+                    message.content[9..pos].to_string(),
+                    message.content[pos + 1..].trim(),
+                    None,
+                )
             } else {
-                ("ZeroClaw Message", message.content.as_str())
+                ("ZeroClaw Message".into(), message.content.as_str(), None)
             }
+        } else if let Some(thread) = thread {
+            (
+                thread.reply_subject(),
+                message.content.as_str(),
+                Some(thread),
+            )
         } else {
-            ("ZeroClaw Message", message.content.as_str())
+            ("ZeroClaw Message".into(), message.content.as_str(), None)
         };
 
-        let email = Message::builder()
+        let mut builder = Message::builder()
             .from(self.config.from_address.parse()?)
             .to(message.recipient.parse()?)
-            .subject(subject)
-            .singlepart(SinglePart::plain(body.to_string()))?;
+            .subject(subject);
+        if let Some(thread) = thread {
+            builder = builder
+                .in_reply_to(thread.in_reply_to())
+                .references(thread.references());
+        }
+        let email = builder.singlepart(SinglePart::plain(body.to_string()))?;
 
         let transport = self.create_smtp_transport()?;
         transport.send(&email)?;
@@ -632,6 +864,7 @@ mod tests {
             smtp_tls: true,
             username: "user@example.com".to_string(),
             password: "pass123".to_string(),
+            oauth_token: None,
             from_address: "bot@example.com".to_string(),
             idle_timeout_secs: 1200,
             allowed_senders: vec!["allowed@example.com".to_string()],
@@ -652,6 +885,7 @@ mod tests {
             smtp_tls: true,
             username: "user@test.com".to_string(),
             password: "secret".to_string(),
+            oauth_token: None,
             from_address: "bot@test.com".to_string(),
             idle_timeout_secs: 1740,
             allowed_senders: vec!["*".to_string()],
@@ -897,6 +1131,7 @@ mod tests {
             smtp_tls: true,
             username: "user@example.com".to_string(),
             password: "password123".to_string(),
+            oauth_token: None,
             from_address: "bot@example.com".to_string(),
             idle_timeout_secs: 1740,
             allowed_senders: vec!["allowed@example.com".to_string()],
@@ -974,4 +1209,176 @@ mod tests {
         let debug_str = format!("{:?}", config);
         assert!(debug_str.contains("imap.debug.com"));
     }
+
+    // ── Inbound parsing fixtures ────────────────────────────────
+
+    const PLAIN_REPLY: &str = "From: Alice <alice@example.com>\r
+To: bot@example.com\r
+Subject: Re: Weekly report\r
+Message-ID: <reply-2@example.com>\r
+In-Reply-To: <bot-1@example.com>\r
+References: <root-0@example.com> <bot-1@example.com>\r
+Date: Tue, 13 Oct 2026 09:30:00 +0000\r
+Content-Type: text/plain; charset=utf-8\r
+\r
+Sounds good, ship it.\r
+\r
+On Mon, Oct 12, 2026 at 5:00 PM ZeroClaw <bot@example.com>\r
+wrote:\r
+> Here is the weekly report.\r
+> Anything to add?\r
+";
+
+    const MULTIPART_ALTERNATIVE: &str = "From: bob@example.com\r
+To: bot@example.com\r
+Subject: Lunch\r
+Message-ID: <alt-1@example.com>\r
+MIME-Version: 1.0\r
+Content-Type: multipart/alternative; boundary=\"b1\"\r
+\r
+--b1\r
+Content-Type: text/plain; charset=utf-8\r
+\r
+Plain body wins\r
+--b1\r
+Content-Type: text/html; charset=utf-8\r
+\r
+<p>HTML body</p>\r
+--b1--\r
+";
+
+    const HTML_ONLY: &str = "From: carol@example.com\r
+To: bot@example.com\r
+Subject: Status\r
+Message-ID: <html-1@example.com>\r
+MIME-Version: 1.0\r
+Content-Type: text/html; charset=utf-8\r
+\r
+<html><body><p>Deploy <b>finished</b></p></body></html>\r
+";
+
+    const WITH_ATTACHMENT: &str = "From: dave@example.com\r
+To: bot@example.com\r
+Subject: Invoice\r
+Message-ID: <att-1@example.com>\r
+MIME-Version: 1.0\r
+Content-Type: multipart/mixed; boundary=\"m1\"\r
+\r
+--m1\r
+Content-Type: text/plain; charset=utf-8\r
+\r
+See attached.\r
+--m1\r
+Content-Type: application/pdf; name=\"../../invoice.pdf\"\r
+Content-Disposition: attachment; filename=\"../../invoice.pdf\"\r
+Content-Transfer-Encoding: base64\r
+\r
+JVBERi0xLjQ=\r
+--m1--\r
+";
+
+    #[test]
+    fn parse_plain_reply_strips_quote_and_keeps_thread_headers() {
+        let email = EmailChannel::parse_email(7, PLAIN_REPLY.as_bytes()).unwrap();
+        assert_eq!(email.sender, "alice@example.com");
+        assert_eq!(email.subject, "Re: Weekly report");
+        assert_eq!(email.msg_id, "reply-2@example.com");
+        assert_eq!(
+            email.references,
+            vec!["root-0@example.com", "bot-1@example.com"]
+        );
+        assert_eq!(
+            email.content,
+            "Subject: Re: Weekly report\n\nSounds good, ship it."
+        );
+        assert!(email.attachments.is_empty());
+    }
+
+    #[test]
+    fn parse_multipart_alternative_prefers_plain_text() {
+        let email = EmailChannel::parse_email(1, MULTIPART_ALTERNATIVE.as_bytes()).unwrap();
+        assert_eq!(email.content, "Subject: Lunch\n\nPlain body wins");
+        assert!(email.references.is_empty());
+        assert!(email.attachments.is_empty());
+    }
+
+    #[test]
+    fn parse_html_only_message_yields_text() {
+        let email = EmailChannel::parse_email(1, HTML_ONLY.as_bytes()).unwrap();
+        assert!(email.content.starts_with("Subject: Status\n\n"));
+        assert!(email.content.contains("Deploy"));
+        assert!(email.content.contains("finished"));
+        assert!(!email.content.contains("<b>"));
+    }
+
+    #[tokio::test]
+    async fn attachments_are_saved_under_workspace_with_markers() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let email = EmailChannel::parse_email(42, WITH_ATTACHMENT.as_bytes()).unwrap();
+        assert_eq!(email.content, "Subject: Invoice\n\nSee attached.");
+        assert_eq!(email.attachments.len(), 1);
+
+        let channel =
+            EmailChannel::new(EmailConfig::default()).with_workspace_dir(tmp.path().into());
+        let markers = channel.save_attachments(42, &email.attachments).await;
+        let saved = tmp.path().join("email_files").join("42_invoice.pdf");
+        assert_eq!(
+            markers,
+            vec![format!("[Document: invoice.pdf] {}", saved.display())]
+        );
+        assert_eq!(std::fs::read(&saved).unwrap(), b"%PDF-1.4");
+    }
+
+    #[test]
+    fn strip_quoted_history_handles_common_reply_formats() {
+        assert_eq!(
+            EmailChannel::strip_quoted_history(
+                "Yes please.\n\n-----Original Message-----\nFrom: bot\nDo you want it?"
+            ),
+            "Yes please."
+        );
+        assert_eq!(
+            EmailChannel::strip_quoted_history("Thanks!\n-- \nAlice\nACME Corp"),
+            "Thanks!"
+        );
+        assert_eq!(
+            EmailChannel::strip_quoted_history("> only a quote"),
+            "> only a quote"
+        );
+    }
+
+    #[test]
+    fn email_thread_builds_reply_headers() {
+        let thread = EmailThread {
+            subject: "Weekly report".into(),
+            message_id: "reply-2@example.com".into(),
+            references: vec!["root-0@example.com".into()],
+        };
+        assert_eq!(thread.reply_subject(), "Re: Weekly report");
+        assert_eq!(thread.in_reply_to(), "<reply-2@example.com>");
+        assert_eq!(
+            thread.references(),
+            "<root-0@example.com> <reply-2@example.com>"
+        );
+
+        let already_reply = EmailThread {
+            subject: "RE: Weekly report".into(),
+            ..thread
+        };
+        assert_eq!(already_reply.reply_subject(), "RE: Weekly report");
+    }
+
+    #[test]
+    fn oauth_token_allows_missing_password() {
+        let json = r#"{
+            "imap_host": "imap.gmail.com",
+            "smtp_host": "smtp.gmail.com",
+            "username": "bot@gmail.com",
+            "oauth_token": "ya29.token",
+            "from_address": "bot@gmail.com"
+        }"#;
+        let config: EmailConfig = serde_json::from_str(json).unwrap();
+        assert!(config.password.is_empty());
+        assert_eq!(config.oauth_token.as_deref(), Some("ya29.token"));
+    }
 }
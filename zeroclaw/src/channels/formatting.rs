@@ -0,0 +1,705 @@
+//! Outbound Markdown → platform dialect conversion.
+//!
+//! The agent produces CommonMark-ish Markdown. Each messaging platform renders
+//! a different subset (Telegram HTML, Slack `mrkdwn`, WhatsApp's
+//! `*bold*`/`_italic_`, plain SMS-style text), so adapters run outbound
+//! content through a [`ChannelFormatter`] before sending. The Markdown is
+//! parsed with `pulldown-cmark`; fenced code blocks are preserved verbatim and
+//! the blank lines between blocks are kept as written.
+
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+/// Converts agent Markdown into a platform-specific dialect.
+///
+/// Implementors describe how each construct is rendered; the provided
+/// [`format`](ChannelFormatter::format) method parses the Markdown and walks
+/// the resulting blocks and inlines.
+pub trait ChannelFormatter: Send + Sync {
+    /// Escape plain text for the target dialect.
+    fn escape(&self, text: &str) -> String {
+        text.to_string()
+    }
+
+    fn bold(&self, inner: &str) -> String;
+
+    fn italic(&self, inner: &str) -> String;
+
+    fn strike(&self, inner: &str) -> String;
+
+    /// Inline code. `code` is raw (unescaped) text.
+    fn inline_code(&self, code: &str) -> String {
+        format!("`{code}`")
+    }
+
+    /// Link with already-formatted `text` and a raw `url`.
+    fn link(&self, text: &str, url: &str) -> String {
+        format!("{text} ({url})")
+    }
+
+    /// Whether [`link`](ChannelFormatter::link) produces a real hyperlink
+    /// rather than flattening it to text.
+    fn renders_links(&self) -> bool {
+        false
+    }
+
+    /// Heading line (`# Title`). `text` is already formatted.
+    fn heading(&self, _level: usize, text: &str) -> String {
+        self.bold(text)
+    }
+
+    /// Fenced code block. `code` is raw text without the fences.
+    fn code_block(&self, _language: &str, code: &str) -> String {
+        format!("```\n{code}\n```")
+    }
+
+    /// Block quote around already-formatted `text`.
+    fn quote(&self, text: &str) -> String {
+        text.lines()
+            .map(|line| format!("> {line}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Marker put before each unordered list item.
+    fn bullet(&self) -> &'static str {
+        "•"
+    }
+
+    /// Convert a full Markdown message into this dialect.
+    fn format(&self, markdown: &str) -> String {
+        render(self, markdown)
+    }
+}
+
+/// Telegram HTML (`parse_mode: "HTML"`): `<b>`, `<i>`, `<s>`, `<code>`,
+/// `<pre>`, `<a href>` and `<blockquote>`, with every other `<`, `>`, `&` and
+/// quote escaped.
+pub struct TelegramFormatter;
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+impl ChannelFormatter for TelegramFormatter {
+    fn escape(&self, text: &str) -> String {
+        escape_html(text)
+    }
+
+    fn bold(&self, inner: &str) -> String {
+        format!("<b>{inner}</b>")
+    }
+
+    fn italic(&self, inner: &str) -> String {
+        format!("<i>{inner}</i>")
+    }
+
+    fn strike(&self, inner: &str) -> String {
+        format!("<s>{inner}</s>")
+    }
+
+    fn inline_code(&self, code: &str) -> String {
+        format!("<code>{}</code>", escape_html(code))
+    }
+
+    fn link(&self, text: &str, url: &str) -> String {
+        format!("<a href=\"{}\">{text}</a>", escape_html(url))
+    }
+
+    fn renders_links(&self) -> bool {
+        true
+    }
+
+    /// Telegram rejects `class` attributes, so the language is dropped.
+    fn code_block(&self, _language: &str, code: &str) -> String {
+        format!("<pre><code>{}</code></pre>", escape_html(code))
+    }
+
+    fn quote(&self, text: &str) -> String {
+        format!("<blockquote>{text}</blockquote>")
+    }
+}
+
+/// Slack `mrkdwn`: `*bold*`, `_italic_`, `~strike~`, `<url|text>`.
+///
+/// Slack's own control sequences in the agent's text (`<@U123>` mentions,
+/// `<!here>`, `<#C123|general>`, `<https://…|label>` links) are passed through
+/// untouched rather than escaped.
+pub struct SlackFormatter;
+
+/// `<@user>`, `<#channel>`, `<!special>` and `<scheme:…>` sequences.
+static SLACK_CONTROL_SEQUENCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<(?:[@#!][^<>\s|]+|(?:https?|mailto|tel):[^<>\s|]+)(?:\|[^<>\n]*)?>").unwrap()
+});
+
+impl ChannelFormatter for SlackFormatter {
+    fn escape(&self, text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    fn bold(&self, inner: &str) -> String {
+        format!("*{inner}*")
+    }
+
+    fn italic(&self, inner: &str) -> String {
+        format!("_{inner}_")
+    }
+
+    fn strike(&self, inner: &str) -> String {
+        format!("~{inner}~")
+    }
+
+    fn link(&self, text: &str, url: &str) -> String {
+        format!("<{url}|{text}>")
+    }
+
+    fn renders_links(&self) -> bool {
+        true
+    }
+
+    fn format(&self, markdown: &str) -> String {
+        let spans: Vec<Range<usize>> = SLACK_CONTROL_SEQUENCE
+            .find_iter(markdown)
+            .map(|m| m.range())
+            .collect();
+        render_preserving(self, markdown, &spans)
+    }
+}
+
+/// WhatsApp: `*bold*`, `_italic_`, `~strike~`, ```` ``` ```` monospace blocks.
+pub struct WhatsAppFormatter;
+
+impl ChannelFormatter for WhatsAppFormatter {
+    fn bold(&self, inner: &str) -> String {
+        format!("*{inner}*")
+    }
+
+    fn italic(&self, inner: &str) -> String {
+        format!("_{inner}_")
+    }
+
+    fn strike(&self, inner: &str) -> String {
+        format!("~{inner}~")
+    }
+}
+
+/// Discord renders standard Markdown; only headings beyond level 3 and
+/// language-less fences need normalizing.
+pub struct DiscordFormatter;
+
+impl ChannelFormatter for DiscordFormatter {
+    fn bold(&self, inner: &str) -> String {
+        format!("**{inner}**")
+    }
+
+    fn italic(&self, inner: &str) -> String {
+        format!("*{inner}*")
+    }
+
+    fn strike(&self, inner: &str) -> String {
+        format!("~~{inner}~~")
+    }
+
+    fn link(&self, text: &str, url: &str) -> String {
+        format!("[{text}]({url})")
+    }
+
+    fn renders_links(&self) -> bool {
+        true
+    }
+
+    fn heading(&self, level: usize, text: &str) -> String {
+        if level <= 3 {
+            format!("{} {text}", "#".repeat(level))
+        } else {
+            self.bold(text)
+        }
+    }
+
+    fn code_block(&self, language: &str, code: &str) -> String {
+        format!("```{language}\n{code}\n```")
+    }
+
+    fn bullet(&self) -> &'static str {
+        "-"
+    }
+}
+
+/// Plain text for channels without rich formatting (SMS-style bridges, IRC).
+pub struct PlainTextFormatter;
+
+impl ChannelFormatter for PlainTextFormatter {
+    fn bold(&self, inner: &str) -> String {
+        inner.to_string()
+    }
+
+    fn italic(&self, inner: &str) -> String {
+        inner.to_string()
+    }
+
+    fn strike(&self, inner: &str) -> String {
+        inner.to_string()
+    }
+
+    fn inline_code(&self, code: &str) -> String {
+        code.to_string()
+    }
+
+    fn heading(&self, _level: usize, text: &str) -> String {
+        text.to_string()
+    }
+
+    fn code_block(&self, _language: &str, code: &str) -> String {
+        code.to_string()
+    }
+}
+
+/// Look up the formatter for a channel name, if the channel needs one.
+/// Telegram is left out: it converts to HTML at send time so it can fall
+/// back to plain text when Telegram rejects the markup.
+pub fn formatter_for_channel(channel: &str) -> Option<&'static dyn ChannelFormatter> {
+    match channel {
+        "slack" => Some(&SlackFormatter),
+        "whatsapp" | "wati" => Some(&WhatsAppFormatter),
+        "discord" => Some(&DiscordFormatter),
+        "irc" | "linq" | "signal" | "imessage" => Some(&PlainTextFormatter),
+        _ => None,
+    }
+}
+
+/// Opens and closes the placeholder standing in for a preserved span.
+const PLACEHOLDER_OPEN: char = '\u{E000}';
+const PLACEHOLDER_CLOSE: char = '\u{E001}';
+
+/// Render `markdown`, copying the byte ranges in `spans` (sorted, disjoint)
+/// to the output verbatim. Each span is swapped for a placeholder Markdown
+/// treats as plain text, and restored after rendering.
+fn render_preserving<F: ChannelFormatter + ?Sized>(
+    formatter: &F,
+    markdown: &str,
+    spans: &[Range<usize>],
+) -> String {
+    if spans.is_empty() {
+        return render(formatter, markdown);
+    }
+    let mut masked = String::with_capacity(markdown.len());
+    let mut last = 0;
+    for (index, span) in spans.iter().enumerate() {
+        masked.push_str(&markdown[last..span.start]);
+        masked.push(PLACEHOLDER_OPEN);
+        masked.push_str(&index.to_string());
+        masked.push(PLACEHOLDER_CLOSE);
+        last = span.end;
+    }
+    masked.push_str(&markdown[last..]);
+
+    let rendered = render(formatter, &masked);
+    let mut out = String::with_capacity(rendered.len());
+    let mut rest = rendered.as_str();
+    while let Some(open) = rest.find(PLACEHOLDER_OPEN) {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + PLACEHOLDER_OPEN.len_utf8()..];
+        let restored = after.find(PLACEHOLDER_CLOSE).and_then(|close| {
+            let span = spans.get(after[..close].parse::<usize>().ok()?)?;
+            Some((&markdown[span.clone()], close))
+        });
+        match restored {
+            Some((original, close)) => {
+                out.push_str(original);
+                rest = &after[close + PLACEHOLDER_CLOSE.len_utf8()..];
+            }
+            None => {
+                out.push(PLACEHOLDER_OPEN);
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn is_web_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// Walks `pulldown-cmark` events, building output in a stack of buffers: one
+/// per construct whose rendering wraps its content (emphasis, links,
+/// headings, code blocks, quotes).
+struct Renderer<'f, F: ?Sized> {
+    formatter: &'f F,
+    buffers: Vec<String>,
+    /// Next number of each open list; `None` for unordered lists.
+    lists: Vec<Option<u64>>,
+    /// Destinations of the open links and images.
+    links: Vec<String>,
+    /// Language of the open code block.
+    code_language: Option<String>,
+    /// A list item marker was just written; its first block joins the marker
+    /// line.
+    item_open: bool,
+}
+
+impl<F: ChannelFormatter + ?Sized> Renderer<'_, F> {
+    fn current(&mut self) -> &mut String {
+        self.buffers
+            .last_mut()
+            .expect("renderer keeps a root buffer")
+    }
+
+    fn write(&mut self, text: &str) {
+        self.item_open = false;
+        self.current().push_str(text);
+    }
+
+    fn open(&mut self) {
+        self.buffers.push(String::new());
+    }
+
+    fn close(&mut self) -> String {
+        if self.buffers.len() > 1 {
+            self.buffers.pop().unwrap_or_default()
+        } else {
+            String::new()
+        }
+    }
+
+    /// Separate a block starting at byte `start` from the content before it,
+    /// keeping up to one blank line from the source.
+    fn start_block(&mut self, markdown: &str, start: usize) {
+        if std::mem::take(&mut self.item_open) {
+            return;
+        }
+        let before = &markdown[..start];
+        let gap = &before[before.trim_end().len()..];
+        let wanted = gap.matches('\n').count().clamp(1, 2);
+        let current = self.current();
+        if current.is_empty() {
+            return;
+        }
+        let present = current.len() - current.trim_end_matches('\n').len();
+        for _ in present..wanted {
+            current.push('\n');
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'_>, markdown: &str, start: usize) {
+        match tag {
+            Tag::Paragraph | Tag::HtmlBlock => {
+                self.start_block(markdown, start);
+                if matches!(tag, Tag::HtmlBlock) {
+                    self.open();
+                }
+            }
+            Tag::Heading { .. } | Tag::BlockQuote(_) => {
+                self.start_block(markdown, start);
+                self.open();
+            }
+            Tag::CodeBlock(kind) => {
+                self.start_block(markdown, start);
+                self.code_language = Some(match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                });
+                self.open();
+            }
+            Tag::List(first) => self.lists.push(first),
+            Tag::Item => {
+                self.start_block(markdown, start);
+                let depth = self.lists.len().saturating_sub(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        let marker = format!("{number}.");
+                        *number += 1;
+                        marker
+                    }
+                    _ => self.formatter.bullet().to_string(),
+                };
+                let indent = "  ".repeat(depth);
+                self.write(&format!("{indent}{marker} "));
+                self.item_open = true;
+            }
+            Tag::Emphasis | Tag::Strong | Tag::Strikethrough => self.open(),
+            Tag::Link { dest_url, .. } | Tag::Image { dest_url, .. } => {
+                self.links.push(dest_url.to_string());
+                self.open();
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Heading(level) => {
+                let text = self.close();
+                let rendered = self.formatter.heading(level as usize, &text);
+                self.write(&rendered);
+            }
+            TagEnd::BlockQuote(_) => {
+                let text = self.close();
+                let rendered = self.formatter.quote(text.trim_end_matches('\n'));
+                self.write(&rendered);
+            }
+            TagEnd::HtmlBlock => {
+                let html = self.close();
+                self.write(html.trim_end_matches('\n'));
+            }
+            TagEnd::CodeBlock => {
+                let code = self.close();
+                let code = code.strip_suffix('\n').unwrap_or(&code);
+                let language = self.code_language.take().unwrap_or_default();
+                let rendered = self.formatter.code_block(&language, code);
+                self.write(&rendered);
+            }
+            TagEnd::List(_) => {
+                self.lists.pop();
+            }
+            TagEnd::Emphasis => {
+                let inner = self.close();
+                let rendered = self.formatter.italic(&inner);
+                self.write(&rendered);
+            }
+            TagEnd::Strong => {
+                let inner = self.close();
+                let rendered = self.formatter.bold(&inner);
+                self.write(&rendered);
+            }
+            TagEnd::Strikethrough => {
+                let inner = self.close();
+                let rendered = self.formatter.strike(&inner);
+                self.write(&rendered);
+            }
+            TagEnd::Link | TagEnd::Image => {
+                let text = self.close();
+                let url = self.links.pop().unwrap_or_default();
+                let escaped_url = self.formatter.escape(&url);
+                let rendered = if text == escaped_url && !self.formatter.renders_links() {
+                    text
+                } else if is_web_url(&url) {
+                    self.formatter.link(&text, &url)
+                } else {
+                    // Leave non-web links (`javascript:`, relative paths) as
+                    // written instead of making them clickable.
+                    format!("[{text}]({escaped_url})")
+                };
+                self.write(&rendered);
+            }
+            _ => {}
+        }
+    }
+
+    fn event(&mut self, event: Event<'_>, markdown: &str, start: usize) {
+        match event {
+            Event::Start(tag) => self.start(tag, markdown, start),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) if self.code_language.is_some() => self.write(&text),
+            Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                let escaped = self.formatter.escape(&text);
+                self.write(&escaped);
+            }
+            Event::Code(code) => {
+                let rendered = self.formatter.inline_code(&code);
+                self.write(&rendered);
+            }
+            Event::SoftBreak | Event::HardBreak => self.write("\n"),
+            Event::Rule => {
+                self.start_block(markdown, start);
+                self.write("---");
+            }
+            Event::TaskListMarker(done) => self.write(if done { "[x] " } else { "[ ] " }),
+            Event::FootnoteReference(name) => {
+                let escaped = self.formatter.escape(&format!("[^{name}]"));
+                self.write(&escaped);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn render<F: ChannelFormatter + ?Sized>(formatter: &F, markdown: &str) -> String {
+    let mut renderer = Renderer {
+        formatter,
+        buffers: vec![String::new()],
+        lists: Vec::new(),
+        links: Vec::new(),
+        code_language: None,
+        item_open: false,
+    };
+    let options = Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    for (event, range) in Parser::new_ext(markdown, options).into_offset_iter() {
+        renderer.event(event, markdown, range.start);
+    }
+    while renderer.buffers.len() > 1 {
+        let orphan = renderer.close();
+        renderer.write(&orphan);
+    }
+    renderer.buffers.pop().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slack_converts_bold_italic_strike_and_links() {
+        let out = SlackFormatter
+            .format("**Deploy** is *done* ~~maybe~~, see [docs](https://example.com/a?b=1)");
+        assert_eq!(
+            out,
+            "*Deploy* is _done_ ~maybe~, see <https://example.com/a?b=1|docs>"
+        );
+    }
+
+    #[test]
+    fn slack_escapes_angle_brackets_and_ampersands_outside_code() {
+        let out = SlackFormatter.format("a < b && c > d `x<y`");
+        assert_eq!(out, "a &lt; b &amp;&amp; c &gt; d `x<y`");
+    }
+
+    #[test]
+    fn nested_formatting_inside_bold_is_converted() {
+        let out = SlackFormatter.format("**bold with _italic_ inside**");
+        assert_eq!(out, "*bold with _italic_ inside*");
+    }
+
+    #[test]
+    fn underscores_inside_words_are_not_italic() {
+        let out = WhatsAppFormatter.format("call snake_case_name now");
+        assert_eq!(out, "call snake_case_name now");
+    }
+
+    #[test]
+    fn headings_become_bold_lines() {
+        assert_eq!(WhatsAppFormatter.format("## Summary"), "*Summary*");
+        assert_eq!(DiscordFormatter.format("## Summary"), "## Summary");
+        assert_eq!(DiscordFormatter.format("#### Deep"), "**Deep**");
+        assert_eq!(PlainTextFormatter.format("# Title"), "Title");
+    }
+
+    #[test]
+    fn code_blocks_are_preserved_verbatim() {
+        let input = "Run:\n```bash\necho **not bold** <tag>\n```\nDone";
+        let out = SlackFormatter.format(input);
+        assert_eq!(out, "Run:\n```\necho **not bold** <tag>\n```\nDone");
+
+        let discord = DiscordFormatter.format(input);
+        assert_eq!(discord, "Run:\n```bash\necho **not bold** <tag>\n```\nDone");
+    }
+
+    #[test]
+    fn unterminated_code_block_is_closed() {
+        let out = WhatsAppFormatter.format("```\nlet x = 1;");
+        assert_eq!(out, "```\nlet x = 1;\n```");
+    }
+
+    #[test]
+    fn long_code_block_survives_formatting_unchanged() {
+        let body = "x *= 2; // keep *stars*\n".repeat(200);
+        let input = format!("```rust\n{}```", body);
+        let out = SlackFormatter.format(&input);
+        assert!(out.starts_with("```\n"));
+        assert!(out.ends_with("\n```"));
+        assert!(out.contains("x *= 2; // keep *stars*"));
+        assert_eq!(out.matches("keep *stars*").count(), 200);
+    }
+
+    #[test]
+    fn plain_text_strips_markup_and_inlines_links() {
+        let out = PlainTextFormatter.format("**Hi** see [site](https://a.b) and `cmd`");
+        assert_eq!(out, "Hi see site (https://a.b) and cmd");
+    }
+
+    #[test]
+    fn non_http_links_are_left_alone() {
+        let out = SlackFormatter.format("[x](javascript:alert(1))");
+        assert_eq!(out, "[x](javascript:alert(1))");
+    }
+
+    #[test]
+    fn multibyte_text_is_handled() {
+        let out = WhatsAppFormatter.format("**héllo** 🎉 _wörld_");
+        assert_eq!(out, "*héllo* 🎉 _wörld_");
+    }
+
+    #[test]
+    fn formatter_lookup_by_channel_name() {
+        assert!(formatter_for_channel("slack").is_some());
+        assert!(formatter_for_channel("whatsapp").is_some());
+        assert!(formatter_for_channel("telegram").is_none());
+    }
+
+    #[test]
+    fn slack_control_sequences_pass_through() {
+        let out = SlackFormatter.format(
+            "<@U123> and <!here>: see <https://example.com/a?b=1|the *docs*> in <#C1|general>, a < b",
+        );
+        assert_eq!(
+            out,
+            "<@U123> and <!here>: see <https://example.com/a?b=1|the *docs*> in <#C1|general>, a &lt; b"
+        );
+        assert_eq!(SlackFormatter.format("**hi <@U1>**"), "*hi <@U1>*");
+    }
+
+    #[test]
+    fn list_bullets_are_not_emphasis() {
+        let input = "* first\n* second has *stress*\n  1. nested";
+        assert_eq!(
+            SlackFormatter.format(input),
+            "• first\n• second has _stress_\n  1. nested"
+        );
+        assert_eq!(DiscordFormatter.format("* one\n* two"), "- one\n- two");
+    }
+
+    #[test]
+    fn blank_lines_between_blocks_are_kept() {
+        let out = WhatsAppFormatter.format("Intro\n\n- a\n- b\n\nOutro");
+        assert_eq!(out, "Intro\n\n• a\n• b\n\nOutro");
+    }
+
+    #[test]
+    fn telegram_renders_html_and_escapes_special_characters() {
+        let out = TelegramFormatter.format(
+            "# Plan\n**bold _and italic_** ~~gone~~ `a<b && \"c\"` [it's](https://x.y/?a=1&b=2) 5 > 3",
+        );
+        assert_eq!(
+            out,
+            "<b>Plan</b>\n<b>bold <i>and italic</i></b> <s>gone</s> <code>a&lt;b &amp;&amp; &quot;c&quot;</code> \
+             <a href=\"https://x.y/?a=1&amp;b=2\">it&#39;s</a> 5 &gt; 3"
+        );
+        assert_eq!(
+            TelegramFormatter.format("```html\n<b>&</b>\n```\n> quoted"),
+            "<pre><code>&lt;b&gt;&amp;&lt;/b&gt;</code></pre>\n<blockquote>quoted</blockquote>"
+        );
+    }
+
+    #[test]
+    fn formatted_code_blocks_survive_message_splitting() {
+        let body = "let a = b * c; // *not* emphasis <tag>\n".repeat(60);
+        let input = format!("Intro **text**\n```rust\n{body}```\nDone");
+        let formatted = DiscordFormatter.format(&input);
+        let chunks = super::super::splitting::split_message(&formatted, 500);
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 500);
+            assert_eq!(chunk.matches("```").count() % 2, 0, "unbalanced: {chunk}");
+        }
+        assert!(chunks[0].starts_with("Intro **text**\n```rust\n"));
+        assert!(chunks[1].starts_with("```rust\n"));
+        assert!(chunks.last().unwrap().ends_with("```\nDone"));
+        let lines = chunks
+            .iter()
+            .flat_map(|chunk| chunk.lines())
+            .filter(|line| line.starts_with("let a"))
+            .count();
+        assert_eq!(lines, 60);
+    }
+}
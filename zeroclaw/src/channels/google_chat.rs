@@ -0,0 +1,1116 @@
+use super::send_retry::{retry_after_header, send_with_retry, OutboundLimiter, SendError};
+use super::traits::ChannelMessage;
+use crate::auth::google_service_account::{ServiceAccountKey, ServiceAccountTokens};
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Issuer of the bearer tokens Google Chat attaches to app requests.
+pub const GOOGLE_CHAT_ISSUER: &str = "chat@system.gserviceaccount.com";
+
+const GOOGLE_CHAT_JWKS_URL: &str =
+    "https://www.googleapis.com/service_accounts/v1/jwk/chat@system.gserviceaccount.com";
+
+/// Google rotates these keys daily; an hour keeps us well inside that window.
+const JWKS_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// Unknown `kid`s trigger a refetch, but never more often than this.
+const JWKS_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Allowed clock skew when checking `exp` / `iat`.
+const CLOCK_SKEW_SECS: u64 = 60;
+
+const CHAT_API_BASE: &str = "https://chat.googleapis.com/v1";
+
+/// OAuth scope a Chat app needs to post messages as itself.
+const CHAT_BOT_SCOPE: &str = "https://www.googleapis.com/auth/chat.bot";
+
+/// Characters of the reply kept as a card's notification text.
+const CARD_FALLBACK_CHARS: usize = 200;
+
+/// Source of the public keys Google Chat signs its bearer tokens with.
+///
+/// The production implementation is [`GoogleJwksKeys`]; tests inject their
+/// own keys through [`GoogleChatChannel::with_signing_keys`].
+#[async_trait]
+pub trait ChatSigningKeys: Send + Sync {
+    /// Verify an RS256 `signature` over `message` with the key named `kid`.
+    async fn verify_signature(
+        &self,
+        kid: &str,
+        message: &[u8],
+        signature: &[u8],
+    ) -> anyhow::Result<()>;
+}
+
+#[derive(Clone)]
+struct RsaJwk {
+    n: Vec<u8>,
+    e: Vec<u8>,
+}
+
+#[derive(Default)]
+struct JwksCache {
+    keys: HashMap<String, RsaJwk>,
+    fetched_at: Option<Instant>,
+}
+
+/// Google's published JWKS for `chat@system.gserviceaccount.com`, cached.
+pub struct GoogleJwksKeys {
+    client: reqwest::Client,
+    url: String,
+    cache: tokio::sync::Mutex<JwksCache>,
+}
+
+impl GoogleJwksKeys {
+    pub fn new() -> Self {
+        Self {
+            client: crate::config::build_runtime_proxy_client("channel.google_chat"),
+            url: GOOGLE_CHAT_JWKS_URL.to_string(),
+            cache: tokio::sync::Mutex::new(JwksCache::default()),
+        }
+    }
+
+    async fn key(&self, kid: &str) -> anyhow::Result<RsaJwk> {
+        let mut cache = self.cache.lock().await;
+        let age = cache.fetched_at.map(|at| at.elapsed());
+
+        let fresh = age.is_some_and(|age| age < JWKS_CACHE_TTL);
+        if fresh {
+            if let Some(key) = cache.keys.get(kid) {
+                return Ok(key.clone());
+            }
+        }
+
+        let may_refresh = age.is_none_or(|age| age >= JWKS_MIN_REFRESH_INTERVAL);
+        if !fresh || may_refresh {
+            let body: serde_json::Value = self
+                .client
+                .get(&self.url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            cache.keys = parse_jwks(&body);
+            cache.fetched_at = Some(Instant::now());
+        }
+
+        cache
+            .keys
+            .get(kid)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("unknown signing key id: {kid}"))
+    }
+}
+
+impl Default for GoogleJwksKeys {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ChatSigningKeys for GoogleJwksKeys {
+    async fn verify_signature(
+        &self,
+        kid: &str,
+        message: &[u8],
+        signature: &[u8],
+    ) -> anyhow::Result<()> {
+        let key = self.key(kid).await?;
+        ring::signature::RsaPublicKeyComponents {
+            n: key.n.as_slice(),
+            e: key.e.as_slice(),
+        }
+        .verify(
+            &ring::signature::RSA_PKCS1_2048_8192_SHA256,
+            message,
+            signature,
+        )
+        .map_err(|_| anyhow::anyhow!("bad token signature"))
+    }
+}
+
+/// Extract RSA keys from a JWKS document, keyed by `kid`.
+fn parse_jwks(body: &serde_json::Value) -> HashMap<String, RsaJwk> {
+    let mut keys = HashMap::new();
+    let Some(entries) = body.get("keys").and_then(|v| v.as_array()) else {
+        return keys;
+    };
+
+    for entry in entries {
+        if entry.get("kty").and_then(|v| v.as_str()) != Some("RSA") {
+            continue;
+        }
+        let field = |name: &str| {
+            entry
+                .get(name)
+                .and_then(|v| v.as_str())
+                .and_then(|s| URL_SAFE_NO_PAD.decode(s.trim_end_matches('=')).ok())
+        };
+        let (Some(kid), Some(n), Some(e)) = (
+            entry.get("kid").and_then(|v| v.as_str()),
+            field("n"),
+            field("e"),
+        ) else {
+            continue;
+        };
+        keys.insert(kid.to_string(), RsaJwk { n, e });
+    }
+    keys
+}
+
+fn decode_segment(segment: &str) -> anyhow::Result<Vec<u8>> {
+    URL_SAFE_NO_PAD
+        .decode(segment.trim_end_matches('='))
+        .map_err(|_| anyhow::anyhow!("malformed token"))
+}
+
+/// Verify a Google Chat bearer token: RS256 signature by one of `keys`,
+/// issuer [`GOOGLE_CHAT_ISSUER`], audience `audience` (the app's project
+/// number), and not expired at `now_secs`.
+pub async fn verify_chat_bearer_token(
+    token: &str,
+    audience: &str,
+    keys: &dyn ChatSigningKeys,
+    now_secs: u64,
+) -> anyhow::Result<()> {
+    let mut parts = token.split('.');
+    let (Some(header_b64), Some(claims_b64), Some(signature_b64), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        anyhow::bail!("malformed token");
+    };
+
+    let header: serde_json::Value = serde_json::from_slice(&decode_segment(header_b64)?)?;
+    if header.get("alg").and_then(|v| v.as_str()) != Some("RS256") {
+        anyhow::bail!("unsupported token algorithm");
+    }
+    let kid = header
+        .get("kid")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("token has no key id"))?;
+
+    let signing_input = &token[..header_b64.len() + 1 + claims_b64.len()];
+    keys.verify_signature(
+        kid,
+        signing_input.as_bytes(),
+        &decode_segment(signature_b64)?,
+    )
+    .await?;
+
+    let claims: serde_json::Value = serde_json::from_slice(&decode_segment(claims_b64)?)?;
+
+    if claims.get("iss").and_then(|v| v.as_str()) != Some(GOOGLE_CHAT_ISSUER) {
+        anyhow::bail!("unexpected token issuer");
+    }
+
+    let audience_matches = match claims.get("aud") {
+        Some(serde_json::Value::String(aud)) => aud == audience,
+        Some(serde_json::Value::Array(auds)) => auds.iter().any(|a| a.as_str() == Some(audience)),
+        _ => false,
+    };
+    if !audience_matches {
+        anyhow::bail!("unexpected token audience");
+    }
+
+    let exp = claims
+        .get("exp")
+        .and_then(serde_json::Value::as_u64)
+        .ok_or_else(|| anyhow::anyhow!("token has no expiry"))?;
+    if exp + CLOCK_SKEW_SECS < now_secs {
+        anyhow::bail!("token expired");
+    }
+    if let Some(iat) = claims.get("iat").and_then(serde_json::Value::as_u64) {
+        if iat > now_secs + CLOCK_SKEW_SECS {
+            anyhow::bail!("token issued in the future");
+        }
+    }
+
+    Ok(())
+}
+
+/// Outbound side of the Chat REST API (`spaces.messages.create`).
+///
+/// The production implementation is [`HttpChatApi`]; tests record the
+/// messages instead.
+#[async_trait]
+pub trait ChatApi: Send + Sync {
+    /// Create `message` in `space` (`spaces/AAA`). A `thread.name` in the
+    /// message makes it a reply in that thread. One attempt; the caller
+    /// retries errors classified as transient.
+    async fn create_message(
+        &self,
+        space: &str,
+        message: &serde_json::Value,
+    ) -> Result<(), SendError>;
+}
+
+/// [`ChatApi`] authenticated as the app's service account.
+pub struct HttpChatApi {
+    client: reqwest::Client,
+    tokens: ServiceAccountTokens,
+}
+
+impl HttpChatApi {
+    /// `service_account_json` is the key JSON itself or a path to the key file.
+    pub fn from_service_account(service_account_json: &str) -> anyhow::Result<Self> {
+        let key = ServiceAccountKey::load(service_account_json)?;
+        let client = crate::config::build_runtime_proxy_client("channel.google_chat");
+        Ok(Self {
+            tokens: ServiceAccountTokens::new(key, CHAT_BOT_SCOPE, client.clone()),
+            client,
+        })
+    }
+}
+
+#[async_trait]
+impl ChatApi for HttpChatApi {
+    async fn create_message(
+        &self,
+        space: &str,
+        message: &serde_json::Value,
+    ) -> Result<(), SendError> {
+        let token = self
+            .tokens
+            .access_token()
+            .await
+            .map_err(|e| SendError::transient(e, None))?;
+        let mut request = self
+            .client
+            .post(format!("{CHAT_API_BASE}/{space}/messages"))
+            .bearer_auth(token)
+            .json(message);
+        if message.pointer("/thread/name").is_some() {
+            request =
+                request.query(&[("messageReplyOption", "REPLY_MESSAGE_FALLBACK_TO_NEW_THREAD")]);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let retry_after = retry_after_header(response.headers());
+            let body = response.text().await.unwrap_or_default();
+            return Err(SendError::from_status(
+                status,
+                retry_after,
+                anyhow::anyhow!(
+                    "Google Chat API error ({status}): {}",
+                    crate::util::truncate_with_ellipsis(&body, 300)
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Google Chat app.
+///
+/// Google Chat POSTs interaction events to the gateway endpoint
+/// `/google-chat`. Without a [`ChatApi`] the reply goes back synchronously
+/// in the HTTP response; with one (service-account mode) the event is acked
+/// at once and the reply is posted through the Chat REST API, so long turns
+/// are not cut off by Chat's response deadline.
+pub struct GoogleChatChannel {
+    project_number: String,
+    allowed_users: Vec<String>,
+    keys: Arc<dyn ChatSigningKeys>,
+    api: Option<Arc<dyn ChatApi>>,
+    outbound: OutboundLimiter,
+}
+
+impl GoogleChatChannel {
+    pub fn new(project_number: String, allowed_users: Vec<String>) -> Self {
+        Self::with_signing_keys(
+            project_number,
+            allowed_users,
+            Arc::new(GoogleJwksKeys::new()),
+        )
+    }
+
+    pub fn with_signing_keys(
+        project_number: String,
+        allowed_users: Vec<String>,
+        keys: Arc<dyn ChatSigningKeys>,
+    ) -> Self {
+        Self {
+            project_number: project_number.trim().to_string(),
+            allowed_users,
+            keys,
+            api: None,
+            outbound: OutboundLimiter::default(),
+        }
+    }
+
+    /// Post replies through the Chat REST API instead of the webhook response.
+    pub fn with_chat_api(mut self, api: Arc<dyn ChatApi>) -> Self {
+        self.api = Some(api);
+        self
+    }
+
+    /// Bound concurrent API sends (`runtime.adapter_max_inflight`) and set the
+    /// retry jitter (`runtime.adapter_retry_jitter_ms`).
+    pub fn with_outbound_limits(mut self, max_inflight: usize, retry_jitter_ms: u64) -> Self {
+        self.outbound = OutboundLimiter::new(max_inflight, retry_jitter_ms);
+        self
+    }
+
+    /// Whether replies go through the Chat REST API.
+    pub fn replies_via_api(&self) -> bool {
+        self.api.is_some()
+    }
+
+    /// Post `text` to the space (and thread) named by a
+    /// [`session_key`](Self::session_key). Needs service-account mode.
+    pub async fn send_to_session(&self, session_key: &str, text: &str) -> anyhow::Result<()> {
+        let Some(api) = &self.api else {
+            anyhow::bail!("Google Chat has no service account configured");
+        };
+        let (space, thread) = Self::parse_session_key(session_key)
+            .ok_or_else(|| anyhow::anyhow!("not a Google Chat session key: {session_key}"))?;
+        let mut message = message_body(text);
+        if let Some(thread) = thread {
+            message["thread"] = serde_json::json!({ "name": thread });
+        }
+        let _permit = self.outbound.acquire_for(session_key).await;
+        send_with_retry(
+            "Google Chat create message",
+            self.outbound.retry_jitter_ms(),
+            || api.create_message(&space, &message),
+        )
+        .await
+    }
+
+    /// Inverse of [`session_key`](Self::session_key): the space resource
+    /// name and, for threaded sessions, the thread resource name.
+    pub fn parse_session_key(session_key: &str) -> Option<(String, Option<String>)> {
+        let rest = session_key.strip_prefix("google_chat:")?;
+        let (space, thread) = match rest.split_once(':') {
+            Some((space, thread)) => (space, Some(thread)),
+            None => (rest, None),
+        };
+        if space.is_empty() {
+            return None;
+        }
+        let space_name = format!("spaces/{space}");
+        let thread_name = thread
+            .filter(|thread| !thread.is_empty())
+            .map(|thread| format!("{space_name}/threads/{thread}"));
+        Some((space_name, thread_name))
+    }
+
+    /// Verify the `Authorization: Bearer <jwt>` header Google Chat sends.
+    pub async fn verify_authorization(&self, authorization: &str) -> anyhow::Result<()> {
+        let token = authorization
+            .trim()
+            .strip_prefix("Bearer ")
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("missing bearer token"))?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        verify_chat_bearer_token(token, &self.project_number, self.keys.as_ref(), now).await
+    }
+
+    fn is_user_allowed(&self, ids: &[&str]) -> bool {
+        self.allowed_users
+            .iter()
+            .any(|allowed| allowed == "*" || ids.iter().any(|id| allowed.eq_ignore_ascii_case(id)))
+    }
+
+    /// Session key for a parsed message: `google_chat:{space}:{thread}`, or
+    /// `google_chat:{space}` for unthreaded messages.
+    ///
+    /// Resource names are shortened to their ids, so
+    /// `spaces/AAA/threads/BBB` becomes `google_chat:AAA:BBB`.
+    pub fn session_key(msg: &ChannelMessage) -> String {
+        let space = msg.reply_target.rsplit('/').next().unwrap_or_default();
+        match msg.thread_ts.as_deref() {
+            Some(thread) => format!(
+                "google_chat:{space}:{}",
+                thread.rsplit('/').next().unwrap_or_default()
+            ),
+            None => format!("google_chat:{space}"),
+        }
+    }
+
+    /// Parse a Google Chat webhook payload into channel messages.
+    ///
+    /// Accepts the Chat app event envelope (`type`, `message.sender`,
+    /// `message.argumentText`, `message.thread.name`, `space.name`) and
+    /// falls back to a simplified `{space, thread, sender, text}` shape
+    /// for relays that pre-flatten events.
+    pub fn parse_webhook_payload(&self, payload: &serde_json::Value) -> Vec<ChannelMessage> {
+        let is_envelope = payload
+            .get("type")
+            .is_some_and(serde_json::Value::is_string)
+            && payload
+                .get("message")
+                .is_some_and(serde_json::Value::is_object);
+        if is_envelope {
+            self.parse_event_envelope(payload)
+        } else {
+            self.parse_simplified_payload(payload)
+        }
+    }
+
+    fn parse_event_envelope(&self, payload: &serde_json::Value) -> Vec<ChannelMessage> {
+        let str_at = |value: &serde_json::Value, pointer: &str| {
+            value
+                .pointer(pointer)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+
+        let event_type = str_at(payload, "/type").unwrap_or_default();
+        if event_type != "MESSAGE" {
+            tracing::debug!("Google Chat: skipping non-message event: {event_type}");
+            return Vec::new();
+        }
+
+        let Some(message) = payload.get("message") else {
+            return Vec::new();
+        };
+
+        if str_at(message, "/sender/type").as_deref() == Some("BOT") {
+            tracing::debug!("Google Chat: skipping bot-originated message");
+            return Vec::new();
+        }
+
+        let Some(space) = str_at(message, "/space/name").or_else(|| str_at(payload, "/space/name"))
+        else {
+            tracing::warn!("Google Chat: missing space.name in event");
+            return Vec::new();
+        };
+
+        let email = str_at(message, "/sender/email");
+        let user_name = str_at(message, "/sender/name");
+        let Some(sender) = email.clone().or_else(|| user_name.clone()) else {
+            tracing::warn!("Google Chat: missing message.sender in event");
+            return Vec::new();
+        };
+
+        let ids: Vec<&str> = [email.as_deref(), user_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect();
+        if !self.is_user_allowed(&ids) {
+            tracing::warn!(
+                "Google Chat: ignoring message from unauthorized user: {sender}. \
+                Add to channels_config.google_chat.allowed_users in config.toml."
+            );
+            return Vec::new();
+        }
+
+        // argumentText has the leading @mention stripped; plain text is the
+        // fallback for direct messages where Chat omits it.
+        let Some(content) = str_at(message, "/argumentText").or_else(|| str_at(message, "/text"))
+        else {
+            return Vec::new();
+        };
+
+        let timestamp = str_at(message, "/createTime")
+            .or_else(|| str_at(payload, "/eventTime"))
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(&t).ok())
+            .and_then(|t| u64::try_from(t.timestamp()).ok())
+            .unwrap_or_else(now_unix_secs);
+
+        vec![ChannelMessage {
+            id: str_at(message, "/name").unwrap_or_else(|| Uuid::new_v4().to_string()),
+            sender,
+            reply_target: space,
+            content,
+            channel: "google_chat".to_string(),
+            timestamp,
+            thread_ts: str_at(message, "/thread/name"),
+            sender_name: None,
+            metadata: HashMap::new(),
+        }]
+    }
+
+    fn parse_simplified_payload(&self, payload: &serde_json::Value) -> Vec<ChannelMessage> {
+        let field = |name: &str| {
+            payload
+                .get(name)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+        };
+
+        let (Some(space), Some(sender), Some(text)) =
+            (field("space"), field("sender"), field("text"))
+        else {
+            tracing::debug!("Google Chat: payload matches neither event envelope nor simple shape");
+            return Vec::new();
+        };
+
+        if !self.is_user_allowed(&[sender]) {
+            tracing::warn!("Google Chat: ignoring message from unauthorized user: {sender}");
+            return Vec::new();
+        }
+
+        let space = if space.starts_with("spaces/") {
+            space.to_string()
+        } else {
+            format!("spaces/{space}")
+        };
+        let thread_ts = field("thread").map(|thread| {
+            if thread.starts_with("spaces/") {
+                thread.to_string()
+            } else {
+                format!("{space}/threads/{thread}")
+            }
+        });
+
+        vec![ChannelMessage {
+            id: field("message_id").map_or_else(|| Uuid::new_v4().to_string(), str::to_string),
+            sender: sender.to_string(),
+            reply_target: space,
+            content: text.to_string(),
+            channel: "google_chat".to_string(),
+            timestamp: now_unix_secs(),
+            thread_ts,
+            sender_name: None,
+            metadata: HashMap::new(),
+        }]
+    }
+}
+
+/// A Chat message for `text`. Plain replies are sent as `text`; replies with
+/// markdown tables or fenced code become a `cardsV2` card, one section per
+/// block, since Chat's text formatting would flatten them.
+pub fn message_body(text: &str) -> serde_json::Value {
+    let blocks = markdown_blocks(text);
+    if blocks.iter().all(|block| matches!(block, Block::Text(_))) {
+        return serde_json::json!({ "text": text });
+    }
+
+    let sections: Vec<_> = blocks.iter().map(Block::to_section).collect();
+    serde_json::json!({
+        "fallbackText": crate::util::truncate_with_ellipsis(text, CARD_FALLBACK_CHARS),
+        "cardsV2": [{
+            "cardId": "reply",
+            "card": { "sections": sections },
+        }],
+    })
+}
+
+#[derive(Debug, PartialEq)]
+enum Block {
+    Text(String),
+    Code {
+        language: String,
+        body: String,
+    },
+    Table {
+        header: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+}
+
+/// Card text is a small HTML subset; escape everything and keep newlines.
+fn card_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\n', "<br>")
+}
+
+impl Block {
+    fn to_section(&self) -> serde_json::Value {
+        match self {
+            Self::Text(text) => serde_json::json!({
+                "widgets": [{ "textParagraph": { "text": card_text(text) } }],
+            }),
+            Self::Code { language, body } => serde_json::json!({
+                "header": if language.is_empty() { "Code" } else { language.as_str() },
+                "widgets": [{
+                    "decoratedText": { "text": card_text(body), "wrapText": true },
+                }],
+            }),
+            Self::Table { header, rows } => {
+                let widgets: Vec<_> = rows
+                    .iter()
+                    .map(|row| {
+                        let fields: Vec<String> = header
+                            .iter()
+                            .zip(row)
+                            .skip(1)
+                            .map(|(name, value)| {
+                                format!("<b>{}</b>: {}", card_text(name), card_text(value))
+                            })
+                            .collect();
+                        let first = row.first().map(|cell| card_text(cell)).unwrap_or_default();
+                        if fields.is_empty() {
+                            serde_json::json!({ "decoratedText": { "text": first } })
+                        } else {
+                            serde_json::json!({
+                                "decoratedText": {
+                                    "topLabel": first,
+                                    "text": fields.join("<br>"),
+                                    "wrapText": true,
+                                },
+                            })
+                        }
+                    })
+                    .collect();
+                serde_json::json!({ "widgets": widgets })
+            }
+        }
+    }
+}
+
+fn table_cells(line: &str) -> Vec<String> {
+    line.trim()
+        .trim_matches('|')
+        .split('|')
+        .map(|cell| cell.trim().to_string())
+        .collect()
+}
+
+fn is_table_separator(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with('|')
+        && line.contains('-')
+        && line.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '))
+}
+
+/// Split markdown into text, fenced-code and table blocks.
+fn markdown_blocks(text: &str) -> Vec<Block> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    fn flush(paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>) {
+        let text = paragraph.join("\n").trim().to_string();
+        if !text.is_empty() {
+            blocks.push(Block::Text(text));
+        }
+        paragraph.clear();
+    }
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if let Some(language) = line.trim_start().strip_prefix("```") {
+            flush(&mut paragraph, &mut blocks);
+            let end = lines[i + 1..]
+                .iter()
+                .position(|l| l.trim_start().starts_with("```"))
+                .map_or(lines.len(), |offset| i + 1 + offset);
+            blocks.push(Block::Code {
+                language: language.trim().to_string(),
+                body: lines[i + 1..end].join("\n"),
+            });
+            i = end + 1;
+            continue;
+        }
+        if line.trim_start().starts_with('|')
+            && lines
+                .get(i + 1)
+                .is_some_and(|next| is_table_separator(next))
+        {
+            flush(&mut paragraph, &mut blocks);
+            let header = table_cells(line);
+            let mut rows = Vec::new();
+            i += 2;
+            while i < lines.len() && lines[i].trim_start().starts_with('|') {
+                rows.push(table_cells(lines[i]));
+                i += 1;
+            }
+            blocks.push(Block::Table { header, rows });
+            continue;
+        }
+        paragraph.push(line);
+        i += 1;
+    }
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn now_unix_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    const FIXTURE_EVENT: &str = include_str!("../../tests/fixtures/google_chat_message_event.json");
+    const PROJECT_NUMBER: &str = "123456789012";
+
+    /// Stand-in for Google's RSA keys: "signs" with HMAC-SHA256 under a
+    /// per-test random secret so tokens can be minted without a private key.
+    struct TestKeys {
+        kid: &'static str,
+        secret: [u8; 32],
+    }
+
+    impl TestKeys {
+        fn new() -> Self {
+            Self {
+                kid: "test-key",
+                secret: rand::random(),
+            }
+        }
+
+        fn sign(&self, message: &[u8]) -> Vec<u8> {
+            let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).unwrap();
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+
+        fn token(&self, claims: &serde_json::Value) -> String {
+            let header = serde_json::json!({"alg": "RS256", "kid": self.kid, "typ": "JWT"});
+            let signing_input = format!(
+                "{}.{}",
+                URL_SAFE_NO_PAD.encode(header.to_string()),
+                URL_SAFE_NO_PAD.encode(claims.to_string())
+            );
+            let signature = URL_SAFE_NO_PAD.encode(self.sign(signing_input.as_bytes()));
+            format!("{signing_input}.{signature}")
+        }
+    }
+
+    #[async_trait]
+    impl ChatSigningKeys for TestKeys {
+        async fn verify_signature(
+            &self,
+            kid: &str,
+            message: &[u8],
+            signature: &[u8],
+        ) -> anyhow::Result<()> {
+            anyhow::ensure!(kid == self.kid, "unknown signing key id: {kid}");
+            let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).unwrap();
+            mac.update(message);
+            mac.verify_slice(signature)
+                .map_err(|_| anyhow::anyhow!("bad token signature"))
+        }
+    }
+
+    fn claims(aud: &str, exp: u64) -> serde_json::Value {
+        serde_json::json!({
+            "iss": GOOGLE_CHAT_ISSUER,
+            "aud": aud,
+            "iat": exp - 3600,
+            "exp": exp,
+        })
+    }
+
+    fn channel_with(keys: Arc<TestKeys>, allowed: &[&str]) -> GoogleChatChannel {
+        GoogleChatChannel::with_signing_keys(
+            PROJECT_NUMBER.into(),
+            allowed.iter().map(|s| (*s).to_string()).collect(),
+            keys,
+        )
+    }
+
+    #[test]
+    fn parses_genuine_message_event_fixture() {
+        let channel = channel_with(Arc::new(TestKeys::new()), &["ada@example.com"]);
+        let payload: serde_json::Value = serde_json::from_str(FIXTURE_EVENT).unwrap();
+
+        let messages = channel.parse_webhook_payload(&payload);
+        assert_eq!(messages.len(), 1);
+        let msg = &messages[0];
+        assert_eq!(msg.channel, "google_chat");
+        assert_eq!(msg.sender, "ada@example.com");
+        assert_eq!(msg.content, "what's on my calendar tomorrow?");
+        assert_eq!(msg.reply_target, "spaces/AAAAx9yZ1Kc");
+        assert_eq!(
+            msg.id,
+            "spaces/AAAAx9yZ1Kc/messages/Qm8xWk3Jd7E.Qm8xWk3Jd7E"
+        );
+        assert_eq!(
+            msg.thread_ts.as_deref(),
+            Some("spaces/AAAAx9yZ1Kc/threads/Qm8xWk3Jd7E")
+        );
+        assert_eq!(msg.timestamp, 1_772_442_927);
+        assert_eq!(
+            GoogleChatChannel::session_key(msg),
+            "google_chat:AAAAx9yZ1Kc:Qm8xWk3Jd7E"
+        );
+    }
+
+    #[test]
+    fn envelope_respects_allowlist_and_skips_bots_and_other_events() {
+        let keys = Arc::new(TestKeys::new());
+        let mut payload: serde_json::Value = serde_json::from_str(FIXTURE_EVENT).unwrap();
+
+        let denied = channel_with(Arc::clone(&keys), &["someone@example.com"]);
+        assert!(denied.parse_webhook_payload(&payload).is_empty());
+
+        // Allowlist also matches the users/{id} resource name.
+        let by_id = channel_with(Arc::clone(&keys), &["users/104857302948573928475"]);
+        assert_eq!(by_id.parse_webhook_payload(&payload).len(), 1);
+
+        let open = channel_with(Arc::clone(&keys), &["*"]);
+        payload["message"]["sender"]["type"] = "BOT".into();
+        assert!(open.parse_webhook_payload(&payload).is_empty());
+
+        payload["message"]["sender"]["type"] = "HUMAN".into();
+        payload["type"] = "ADDED_TO_SPACE".into();
+        assert!(open.parse_webhook_payload(&payload).is_empty());
+    }
+
+    #[test]
+    fn falls_back_to_simplified_shape() {
+        let channel = channel_with(Arc::new(TestKeys::new()), &["*"]);
+        let payload = serde_json::json!({
+            "space": "AAAA",
+            "thread": "T1",
+            "sender": "bob@example.com",
+            "text": "hello"
+        });
+
+        let messages = channel.parse_webhook_payload(&payload);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].reply_target, "spaces/AAAA");
+        assert_eq!(
+            messages[0].thread_ts.as_deref(),
+            Some("spaces/AAAA/threads/T1")
+        );
+        assert_eq!(
+            GoogleChatChannel::session_key(&messages[0]),
+            "google_chat:AAAA:T1"
+        );
+
+        assert!(channel
+            .parse_webhook_payload(&serde_json::json!({"text": "no space"}))
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn accepts_valid_bearer_token() {
+        let keys = Arc::new(TestKeys::new());
+        let channel = channel_with(Arc::clone(&keys), &["*"]);
+        let token = keys.token(&claims(PROJECT_NUMBER, now_unix_secs() + 600));
+
+        channel
+            .verify_authorization(&format!("Bearer {token}"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_audience_expired_and_tampered_tokens() {
+        let keys = Arc::new(TestKeys::new());
+        let now = 1_772_442_927;
+
+        let bad_aud = keys.token(&claims("999999999999", now + 600));
+        let err = verify_chat_bearer_token(&bad_aud, PROJECT_NUMBER, keys.as_ref(), now)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("audience"));
+
+        let expired = keys.token(&claims(PROJECT_NUMBER, now - 3600));
+        let err = verify_chat_bearer_token(&expired, PROJECT_NUMBER, keys.as_ref(), now)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("expired"));
+
+        let mut wrong_issuer = claims(PROJECT_NUMBER, now + 600);
+        wrong_issuer["iss"] = "attacker@example.com".into();
+        let token = keys.token(&wrong_issuer);
+        assert!(
+            verify_chat_bearer_token(&token, PROJECT_NUMBER, keys.as_ref(), now)
+                .await
+                .is_err()
+        );
+
+        // Signed by a different key than the one the verifier trusts.
+        let forged = TestKeys::new().token(&claims(PROJECT_NUMBER, now + 600));
+        let err = verify_chat_bearer_token(&forged, PROJECT_NUMBER, keys.as_ref(), now)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("signature"));
+
+        let channel = channel_with(keys, &["*"]);
+        assert!(channel.verify_authorization("").await.is_err());
+        assert!(channel
+            .verify_authorization("Bearer not-a-jwt")
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn parse_jwks_extracts_rsa_keys() {
+        let body = serde_json::json!({
+            "keys": [
+                {"kty": "RSA", "kid": "k1", "n": "AQAB", "e": "AQAB", "alg": "RS256"},
+                {"kty": "EC", "kid": "k2", "x": "AQAB", "y": "AQAB"},
+                {"kty": "RSA", "kid": "k3", "n": "!!!", "e": "AQAB"}
+            ]
+        });
+        let keys = parse_jwks(&body);
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys["k1"].e, vec![1, 0, 1]);
+    }
+
+    #[derive(Default)]
+    struct RecordingApi {
+        sent: tokio::sync::Mutex<Vec<(String, serde_json::Value)>>,
+        /// Attempts answered with a transient error before accepting.
+        failures: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ChatApi for RecordingApi {
+        async fn create_message(
+            &self,
+            space: &str,
+            message: &serde_json::Value,
+        ) -> Result<(), SendError> {
+            let failures = &self.failures;
+            if failures.load(std::sync::atomic::Ordering::SeqCst) > 0 {
+                failures.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+                return Err(SendError::transient(
+                    anyhow::anyhow!("503"),
+                    Some(Duration::ZERO),
+                ));
+            }
+            self.sent
+                .lock()
+                .await
+                .push((space.to_string(), message.clone()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn service_account_mode_posts_to_the_session_space_and_thread() {
+        let webhook_only = channel_with(Arc::new(TestKeys::new()), &["*"]);
+        assert!(!webhook_only.replies_via_api());
+        assert!(webhook_only
+            .send_to_session("google_chat:AAA", "hi")
+            .await
+            .is_err());
+
+        let api = Arc::new(RecordingApi::default());
+        let channel = channel_with(Arc::new(TestKeys::new()), &["*"]).with_chat_api(api.clone());
+        assert!(channel.replies_via_api());
+        channel
+            .send_to_session("google_chat:AAA:BBB", "threaded")
+            .await
+            .unwrap();
+        channel
+            .send_to_session("google_chat:AAA", "top level")
+            .await
+            .unwrap();
+        assert!(channel.send_to_session("slack_bob", "x").await.is_err());
+
+        let sent = api.sent.lock().await;
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[0].0, "spaces/AAA");
+        assert_eq!(sent[0].1["thread"]["name"], "spaces/AAA/threads/BBB");
+        assert_eq!(sent[0].1["text"], "threaded");
+        assert!(sent[1].1.get("thread").is_none());
+    }
+
+    #[tokio::test]
+    async fn api_sends_retry_transient_failures() {
+        let api = Arc::new(RecordingApi::default());
+        api.failures.store(2, std::sync::atomic::Ordering::SeqCst);
+        let channel = channel_with(Arc::new(TestKeys::new()), &["*"])
+            .with_chat_api(api.clone())
+            .with_outbound_limits(1, 0);
+        channel
+            .send_to_session("google_chat:AAA", "eventually")
+            .await
+            .unwrap();
+        assert_eq!(api.sent.lock().await.len(), 1);
+    }
+
+    #[test]
+    fn session_keys_round_trip_to_resource_names() {
+        let msg = ChannelMessage {
+            id: "m".into(),
+            sender: "u".into(),
+            reply_target: "spaces/AAA".into(),
+            content: "hi".into(),
+            channel: "google_chat".into(),
+            timestamp: 0,
+            thread_ts: Some("spaces/AAA/threads/BBB".into()),
+            sender_name: None,
+            metadata: HashMap::new(),
+        };
+        let key = GoogleChatChannel::session_key(&msg);
+        assert_eq!(
+            GoogleChatChannel::parse_session_key(&key),
+            Some((
+                "spaces/AAA".to_string(),
+                Some("spaces/AAA/threads/BBB".to_string())
+            ))
+        );
+        assert_eq!(
+            GoogleChatChannel::parse_session_key("google_chat:AAA"),
+            Some(("spaces/AAA".to_string(), None))
+        );
+        assert_eq!(GoogleChatChannel::parse_session_key("google_chat:"), None);
+    }
+
+    #[test]
+    fn plain_replies_stay_plain_text() {
+        let body = message_body("Sure — **done**.\n\nAnything else?");
+        assert_eq!(
+            body,
+            serde_json::json!({"text": "Sure — **done**.\n\nAnything else?"})
+        );
+    }
+
+    #[test]
+    fn tables_and_code_become_card_sections() {
+        let reply = "Here are the results:\n\n\
+                     | Host | Status | Latency |\n\
+                     |------|:------:|--------:|\n\
+                     | api  | up     | 12ms    |\n\
+                     | db   | <down> | -       |\n\n\
+                     Restart with:\n\
+                     ```bash\n\
+                     systemctl restart db\n\
+                     ```";
+        let body = message_body(reply);
+        assert!(body.get("text").is_none());
+        assert!(body["fallbackText"]
+            .as_str()
+            .unwrap()
+            .starts_with("Here are the results:"));
+
+        let sections = body["cardsV2"][0]["card"]["sections"].as_array().unwrap();
+        assert_eq!(sections.len(), 4);
+        assert_eq!(
+            sections[0]["widgets"][0]["textParagraph"]["text"],
+            "Here are the results:"
+        );
+
+        let rows = sections[1]["widgets"].as_array().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["decoratedText"]["topLabel"], "api");
+        assert_eq!(
+            rows[0]["decoratedText"]["text"],
+            "<b>Status</b>: up<br><b>Latency</b>: 12ms"
+        );
+        assert_eq!(
+            rows[1]["decoratedText"]["text"],
+            "<b>Status</b>: &lt;down&gt;<br><b>Latency</b>: -"
+        );
+
+        assert_eq!(sections[3]["header"], "bash");
+        assert_eq!(
+            sections[3]["widgets"][0]["decoratedText"]["text"],
+            "systemctl restart db"
+        );
+    }
+}
@@ -1,7 +1,9 @@
+use crate::channels::formatting::{ChannelFormatter, PlainTextFormatter};
 use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use directories::UserDirs;
 use rusqlite::{Connection, OpenFlags};
+use std::collections::HashMap;
 use std::path::Path;
 use tokio::sync::mpsc;
 
@@ -105,7 +107,7 @@ impl Channel for IMessageChannel {
 
         // SECURITY: Escape both message AND target to prevent AppleScript injection
         // See: CWE-78 (OS Command Injection)
-        let escaped_msg = escape_applescript(&message.content);
+        let escaped_msg = escape_applescript(&PlainTextFormatter.format(&message.content));
         let escaped_target = escape_applescript(&message.recipient);
 
         let script = format!(
@@ -232,6 +234,8 @@ end tell"#
                                 .unwrap_or_default()
                                 .as_secs(),
                             thread_ts: None,
+                            sender_name: None,
+                            metadata: HashMap::new(),
                         };
 
                         if tx.send(msg).await.is_err() {
@@ -0,0 +1,430 @@
+//! Size and flood limits for inbound channel messages.
+//!
+//! The dispatch loop passes every inbound message through an
+//! [`InboundGuard`] before it becomes a turn:
+//!
+//! - a message longer than `max_message_bytes` is cut at a character
+//!   boundary and annotated; the full text is saved under `inbound/` in the
+//!   workspace so the agent can read it with its file tools;
+//! - messages from one sender arriving within `coalesce_window_ms` of the
+//!   previous one are held and combined into a single turn;
+//! - a sender over `max_messages_per_minute` gets one notice, and the rest
+//!   of their messages are dropped until the rate falls below the cap.
+
+use crate::channels::traits::ChannelMessage;
+use crate::config::InboundGuardConfig;
+use anyhow::{Context, Result};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Workspace directory holding the full text of truncated messages.
+pub const INBOUND_ARTIFACT_DIR: &str = "inbound";
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+static TRUNCATED: AtomicU64 = AtomicU64::new(0);
+static COALESCED: AtomicU64 = AtomicU64::new(0);
+static THROTTLED: AtomicU64 = AtomicU64::new(0);
+static DROPPED: AtomicU64 = AtomicU64::new(0);
+
+/// Counters since startup, reported by `/api/monitor/metrics`.
+pub fn snapshot_json() -> serde_json::Value {
+    serde_json::json!({
+        "truncated": TRUNCATED.load(Ordering::Relaxed),
+        "coalesced": COALESCED.load(Ordering::Relaxed),
+        "throttled": THROTTLED.load(Ordering::Relaxed),
+        "dropped": DROPPED.load(Ordering::Relaxed),
+    })
+}
+
+/// Save the full text of `msg` under [`INBOUND_ARTIFACT_DIR`]. Returns the
+/// path relative to the workspace.
+async fn save_original(workspace_dir: &Path, msg: &ChannelMessage) -> Result<String> {
+    let dir = workspace_dir.join(INBOUND_ARTIFACT_DIR);
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .with_context(|| format!("Failed to create {}", dir.display()))?;
+    let channel: String = msg
+        .channel
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect();
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    let file_name = format!("{channel}-{}-{}.txt", msg.timestamp, &id[..8]);
+    let path = dir.join(&file_name);
+    tokio::fs::write(&path, &msg.content)
+        .await
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(format!("{INBOUND_ARTIFACT_DIR}/{file_name}"))
+}
+
+/// Key messages are coalesced and rate limited by.
+fn sender_key(msg: &ChannelMessage) -> String {
+    format!(
+        "{}_{}_{}_{}",
+        msg.channel,
+        msg.reply_target,
+        msg.sender,
+        msg.thread_ts.as_deref().unwrap_or_default()
+    )
+}
+
+/// What to do with an inbound message.
+#[derive(Debug)]
+pub enum Admission {
+    /// Start a turn now.
+    Dispatch(ChannelMessage),
+    /// Held to be combined with the messages that follow; released by
+    /// [`InboundGuard::flush_due`].
+    Held,
+    /// The sender just went over the cap: reply with the flood notice.
+    Throttled(ChannelMessage),
+    /// The sender is over the cap and was already notified.
+    Dropped(ChannelMessage),
+}
+
+struct Pending {
+    message: ChannelMessage,
+    flush_at: Instant,
+}
+
+#[derive(Default)]
+struct SenderState {
+    recent: VecDeque<Instant>,
+    notified: bool,
+    last_dispatch: Option<Instant>,
+    pending: Option<Pending>,
+}
+
+impl SenderState {
+    fn is_idle(&self, now: Instant, window: Duration) -> bool {
+        self.pending.is_none()
+            && self
+                .recent
+                .back()
+                .is_none_or(|at| now.duration_since(*at) >= RATE_WINDOW)
+            && self
+                .last_dispatch
+                .is_none_or(|at| now.duration_since(at) >= window)
+    }
+}
+
+/// Per-dispatch-loop inbound limits. See the module docs.
+pub struct InboundGuard {
+    max_message_bytes: usize,
+    window: Duration,
+    max_per_minute: usize,
+    notice: String,
+    senders: HashMap<String, SenderState>,
+}
+
+impl InboundGuard {
+    pub fn new(config: &InboundGuardConfig) -> Self {
+        Self {
+            max_message_bytes: config.max_message_bytes,
+            window: Duration::from_millis(config.coalesce_window_ms),
+            max_per_minute: usize::try_from(config.max_messages_per_minute).unwrap_or(usize::MAX),
+            notice: config.flood_notice.clone(),
+            senders: HashMap::new(),
+        }
+    }
+
+    /// A guard that lets everything through unchanged.
+    pub fn disabled() -> Self {
+        Self::new(&InboundGuardConfig {
+            max_message_bytes: 0,
+            coalesce_window_ms: 0,
+            max_messages_per_minute: 0,
+            flood_notice: String::new(),
+        })
+    }
+
+    /// Reply sent with [`Admission::Throttled`].
+    pub fn notice(&self) -> &str {
+        &self.notice
+    }
+
+    /// Rate limit `msg` and hold it for coalescing if its sender sent
+    /// another message within the window.
+    pub fn admit(&mut self, msg: ChannelMessage, now: Instant) -> Admission {
+        let window = self.window;
+        self.senders.retain(|_, state| !state.is_idle(now, window));
+
+        let state = self.senders.entry(sender_key(&msg)).or_default();
+        while state
+            .recent
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= RATE_WINDOW)
+        {
+            state.recent.pop_front();
+        }
+
+        if self.max_per_minute > 0 && state.recent.len() >= self.max_per_minute {
+            if state.notified {
+                DROPPED.fetch_add(1, Ordering::Relaxed);
+                return Admission::Dropped(msg);
+            }
+            state.notified = true;
+            THROTTLED.fetch_add(1, Ordering::Relaxed);
+            return Admission::Throttled(msg);
+        }
+        state.notified = false;
+        if self.max_per_minute > 0 {
+            state.recent.push_back(now);
+        }
+
+        if window.is_zero() {
+            return Admission::Dispatch(msg);
+        }
+        if let Some(pending) = &mut state.pending {
+            merge_into(&mut pending.message, msg);
+            pending.flush_at = now + window;
+            COALESCED.fetch_add(1, Ordering::Relaxed);
+            return Admission::Held;
+        }
+        if state
+            .last_dispatch
+            .is_some_and(|at| now.duration_since(at) < window)
+        {
+            state.pending = Some(Pending {
+                message: msg,
+                flush_at: now + window,
+            });
+            return Admission::Held;
+        }
+        state.last_dispatch = Some(now);
+        Admission::Dispatch(msg)
+    }
+
+    /// When the next held message is due.
+    pub fn next_flush(&self) -> Option<Instant> {
+        self.senders
+            .values()
+            .filter_map(|state| state.pending.as_ref().map(|p| p.flush_at))
+            .min()
+    }
+
+    /// Held messages whose window has passed, each combined into one.
+    pub fn flush_due(&mut self, now: Instant) -> Vec<ChannelMessage> {
+        let mut due: Vec<(Instant, ChannelMessage)> = Vec::new();
+        for state in self.senders.values_mut() {
+            if state.pending.as_ref().is_some_and(|p| p.flush_at <= now) {
+                if let Some(pending) = state.pending.take() {
+                    state.last_dispatch = Some(now);
+                    due.push((pending.flush_at, pending.message));
+                }
+            }
+        }
+        due.sort_by_key(|(flush_at, _)| *flush_at);
+        due.into_iter().map(|(_, message)| message).collect()
+    }
+
+    /// Every held message, for shutdown.
+    pub fn flush_all(&mut self) -> Vec<ChannelMessage> {
+        self.senders
+            .values_mut()
+            .filter_map(|state| state.pending.take().map(|p| p.message))
+            .collect()
+    }
+
+    /// Truncate `msg` to `max_message_bytes`, saving the full text under
+    /// [`INBOUND_ARTIFACT_DIR`] and noting where in the message.
+    pub async fn limit_size(
+        &self,
+        mut msg: ChannelMessage,
+        workspace_dir: &Path,
+    ) -> ChannelMessage {
+        let max_bytes = self.max_message_bytes;
+        if max_bytes == 0 || msg.content.len() <= max_bytes {
+            return msg;
+        }
+        let total = msg.content.len();
+        let note = match save_original(workspace_dir, &msg).await {
+            Ok(path) => format!(
+                "[Message truncated: it was {total} bytes, over the {max_bytes}-byte limit. \
+                 The full text is saved at `{path}`; read it with file_read if you need the rest.]"
+            ),
+            Err(e) => {
+                tracing::warn!("Failed to save oversized inbound message: {e:#}");
+                format!(
+                    "[Message truncated: it was {total} bytes, over the {max_bytes}-byte limit.]"
+                )
+            }
+        };
+        let kept = crate::util::truncate_at_char_boundary(&msg.content, max_bytes).to_string();
+        msg.content = format!("{kept}\n\n{note}");
+        TRUNCATED.fetch_add(1, Ordering::Relaxed);
+        tracing::info!(
+            channel = %msg.channel,
+            sender = %msg.sender,
+            bytes = total,
+            "Truncated oversized inbound message"
+        );
+        msg
+    }
+}
+
+/// Append `next` to `into`: the combined message keeps the first message's
+/// routing and takes the latest id and timestamp.
+fn merge_into(into: &mut ChannelMessage, next: ChannelMessage) {
+    into.content.push_str("\n\n");
+    into.content.push_str(&next.content);
+    into.id = next.id;
+    into.timestamp = next.timestamp;
+    into.metadata.extend(next.metadata);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: id.into(),
+            sender: "alice".into(),
+            reply_target: "chat-1".into(),
+            content: content.into(),
+            channel: "telegram".into(),
+            timestamp: 1_700_000_000,
+            thread_ts: None,
+            sender_name: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn guard(max_bytes: usize, window_ms: u64, per_minute: u32) -> InboundGuard {
+        InboundGuard::new(&InboundGuardConfig {
+            max_message_bytes: max_bytes,
+            coalesce_window_ms: window_ms,
+            max_messages_per_minute: per_minute,
+            ..InboundGuardConfig::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn truncation_respects_multibyte_boundaries() {
+        let tmp = tempfile::tempdir().unwrap();
+        let original = "🦀".repeat(100);
+        let limited = guard(101, 0, 0)
+            .limit_size(message("1", &original), tmp.path())
+            .await;
+        let (kept, note) = limited.content.split_once("\n\n").unwrap();
+        assert_eq!(kept, "🦀".repeat(25));
+        assert!(note.contains("it was 400 bytes, over the 101-byte limit"));
+
+        let saved = std::fs::read_dir(tmp.path().join(INBOUND_ARTIFACT_DIR))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        assert!(note.contains(&format!(
+            "inbound/{}",
+            saved.file_name().unwrap().to_string_lossy()
+        )));
+        assert_eq!(std::fs::read_to_string(saved).unwrap(), original);
+    }
+
+    #[tokio::test]
+    async fn messages_within_limit_are_untouched() {
+        let tmp = tempfile::tempdir().unwrap();
+        let msg = guard(16, 0, 0)
+            .limit_size(message("1", "hello"), tmp.path())
+            .await;
+        assert_eq!(msg.content, "hello");
+        assert!(!tmp.path().join(INBOUND_ARTIFACT_DIR).exists());
+    }
+
+    #[test]
+    fn bursts_are_coalesced_into_one_turn() {
+        let mut guard = guard(0, 3_000, 0);
+        let start = Instant::now();
+
+        assert!(matches!(
+            guard.admit(message("1", "first"), start),
+            Admission::Dispatch(m) if m.content == "first"
+        ));
+        assert!(matches!(
+            guard.admit(message("2", "second"), start + Duration::from_secs(1)),
+            Admission::Held
+        ));
+        assert!(matches!(
+            guard.admit(message("3", "third"), start + Duration::from_secs(2)),
+            Admission::Held
+        ));
+        // Each held message pushes the flush back by a full window.
+        assert_eq!(guard.next_flush(), Some(start + Duration::from_secs(5)));
+        assert!(guard.flush_due(start + Duration::from_secs(4)).is_empty());
+
+        let flushed = guard.flush_due(start + Duration::from_secs(5));
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].content, "second\n\nthird");
+        assert_eq!(flushed[0].id, "3");
+        assert_eq!(guard.next_flush(), None);
+
+        // A message after a quiet window starts a turn immediately.
+        let mut other = message("4", "later");
+        other.sender = "bob".into();
+        assert!(matches!(
+            guard.admit(other, start + Duration::from_secs(5)),
+            Admission::Dispatch(_)
+        ));
+        assert!(matches!(
+            guard.admit(message("5", "much later"), start + Duration::from_secs(20)),
+            Admission::Dispatch(_)
+        ));
+    }
+
+    #[test]
+    fn senders_over_the_cap_are_notified_once_then_dropped() {
+        let mut guard = guard(0, 0, 3);
+        let start = Instant::now();
+        for i in 0..3 {
+            assert!(matches!(
+                guard.admit(message(&i.to_string(), "hi"), start),
+                Admission::Dispatch(_)
+            ));
+        }
+        assert!(matches!(
+            guard.admit(message("4", "hi"), start),
+            Admission::Throttled(_)
+        ));
+        assert!(matches!(
+            guard.admit(message("5", "hi"), start + Duration::from_secs(30)),
+            Admission::Dropped(_)
+        ));
+
+        let mut other = message("6", "hi");
+        other.sender = "bob".into();
+        assert!(matches!(guard.admit(other, start), Admission::Dispatch(_)));
+
+        // A minute later the sender is accepted again.
+        assert!(matches!(
+            guard.admit(message("7", "hi"), start + Duration::from_secs(60)),
+            Admission::Dispatch(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn disabled_guard_passes_everything_through() {
+        let mut guard = InboundGuard::disabled();
+        let now = Instant::now();
+        for i in 0..50 {
+            assert!(matches!(
+                guard.admit(message(&i.to_string(), "hi"), now),
+                Admission::Dispatch(_)
+            ));
+        }
+        let tmp = tempfile::tempdir().unwrap();
+        let long = "x".repeat(100_000);
+        assert_eq!(
+            guard
+                .limit_size(message("1", &long), tmp.path())
+                .await
+                .content,
+            long
+        );
+    }
+}
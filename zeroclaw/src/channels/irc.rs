@@ -1,5 +1,7 @@
+use crate::channels::formatting::{ChannelFormatter, PlainTextFormatter};
 use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
@@ -360,7 +362,8 @@ impl Channel for IrcChannel {
         // 512 - sender prefix (~64 bytes for :nick!user@host) - "PRIVMSG " - target - " :" - "\r\n"
         let overhead = SENDER_PREFIX_RESERVE + 10 + message.recipient.len() + 2;
         let max_payload = 512_usize.saturating_sub(overhead);
-        let chunks = split_message(&message.content, max_payload);
+        let content = PlainTextFormatter.format(&message.content);
+        let chunks = split_message(&content, max_payload);
 
         for chunk in chunks {
             Self::send_raw(writer, &format!("PRIVMSG {} :{chunk}", message.recipient)).await?;
@@ -580,6 +583,8 @@ impl Channel for IrcChannel {
                             .unwrap_or_default()
                             .as_secs(),
                         thread_ts: None,
+                        sender_name: None,
+                        metadata: HashMap::new(),
                     };
 
                     if tx.send(channel_msg).await.is_err() {
@@ -1,5 +1,7 @@
+use super::formatting::{ChannelFormatter, PlainTextFormatter};
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 /// Linq channel — uses the Linq Partner V3 API for iMessage, RCS, and SMS.
@@ -213,6 +215,8 @@ impl LinqChannel {
             channel: "linq".to_string(),
             timestamp,
             thread_ts: None,
+            sender_name: None,
+            metadata: HashMap::new(),
         });
 
         messages
@@ -229,12 +233,13 @@ impl Channel for LinqChannel {
         // If reply_target looks like a chat_id, send to existing chat.
         // Otherwise create a new chat with the recipient phone number.
         let recipient = &message.recipient;
+        let content = PlainTextFormatter.format(&message.content);
 
         let body = serde_json::json!({
             "message": {
                 "parts": [{
                     "type": "text",
-                    "value": message.content
+                    "value": content
                 }]
             }
         });
@@ -263,7 +268,7 @@ impl Channel for LinqChannel {
                 "message": {
                     "parts": [{
                         "type": "text",
-                        "value": message.content
+                        "value": content
                     }]
                 }
             });
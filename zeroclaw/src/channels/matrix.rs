@@ -645,6 +645,8 @@ impl Channel for MatrixChannel {
                         .unwrap_or_default()
                         .as_secs(),
                     thread_ts: None,
+                    sender_name: None,
+                    metadata: HashMap::new(),
                 };
 
                 let _ = tx.send(msg).await;
@@ -2,6 +2,7 @@ use super::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 /// Nextcloud Talk channel in webhook mode.
@@ -168,6 +169,8 @@ impl NextcloudTalkChannel {
             channel: "nextcloud_talk".to_string(),
             timestamp,
             thread_ts: None,
+            sender_name: None,
+            metadata: HashMap::new(),
         });
 
         messages
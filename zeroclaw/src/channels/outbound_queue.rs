@@ -0,0 +1,484 @@
+//! Persistent queue for outbound replies that could not be delivered.
+//!
+//! When a channel send fails (token revoked, network down) the reply is
+//! stored in `state/outbound_queue.db` instead of being dropped. A background
+//! drainer retries due rows with capped exponential backoff until the message
+//! is delivered or its TTL expires. A row is deleted as soon as a send
+//! succeeds, so every queued message is delivered at most once.
+//!
+//! rusqlite calls block, so async callers go through [`blocking`]. The
+//! channel runtime registers its queue so the gateway's channel health
+//! endpoint reads the same handle rather than opening the database again.
+
+use super::traits::{Channel, SendMessage};
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// First retry delay; doubles per attempt up to the configured cap.
+const BASE_RETRY_SECS: u64 = 5;
+/// Rows fetched per drain pass.
+const DRAIN_BATCH: usize = 50;
+/// How often the drainer looks for due rows.
+pub const DRAIN_INTERVAL: Duration = Duration::from_secs(5);
+const LAST_ERROR_MAX_CHARS: usize = 500;
+
+/// A reply waiting to be re-sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedOutbound {
+    pub id: String,
+    pub channel: String,
+    pub session_key: String,
+    pub message: SendMessage,
+    pub attempts: u32,
+    pub created_at: i64,
+    pub next_retry_at: i64,
+}
+
+/// Retry policy for the drainer, taken from `[reliability]`.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Give up on a message this long after it was first queued.
+    pub ttl: Duration,
+    /// Upper bound on the delay between two attempts.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(reliability: &crate::config::ReliabilityConfig) -> Self {
+        Self {
+            ttl: Duration::from_secs(reliability.outbound_queue_ttl_secs),
+            max_backoff: Duration::from_secs(reliability.outbound_retry_max_backoff_secs.max(1)),
+        }
+    }
+
+    /// Delay before the next attempt once `attempts` sends have failed.
+    fn delay_after(&self, attempts: u32) -> Duration {
+        let exp = attempts.saturating_sub(1).min(20);
+        Duration::from_secs(BASE_RETRY_SECS.saturating_mul(1 << exp)).min(self.max_backoff)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DrainStats {
+    pub delivered: usize,
+    pub failed: usize,
+    pub expired: usize,
+}
+
+pub struct OutboundQueue {
+    conn: Mutex<Connection>,
+}
+
+pub fn queue_db_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("state").join("outbound_queue.db")
+}
+
+fn now_unix() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn duration_secs(d: Duration) -> i64 {
+    i64::try_from(d.as_secs()).unwrap_or(i64::MAX)
+}
+
+impl OutboundQueue {
+    pub fn open(workspace_dir: &Path) -> Result<Self> {
+        let db_path = queue_db_path(workspace_dir);
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create state directory: {}", parent.display())
+            })?;
+        }
+        let conn = Connection::open(&db_path)
+            .with_context(|| format!("Failed to open outbound queue: {}", db_path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS outbound_queue (
+                id            TEXT PRIMARY KEY,
+                channel       TEXT NOT NULL,
+                session_key   TEXT NOT NULL,
+                recipient     TEXT NOT NULL,
+                subject       TEXT,
+                thread_ts     TEXT,
+                content       TEXT NOT NULL,
+                attempts      INTEGER NOT NULL DEFAULT 0,
+                created_at    INTEGER NOT NULL,
+                next_retry_at INTEGER NOT NULL,
+                last_error    TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_outbound_queue_next_retry
+                ON outbound_queue(next_retry_at);",
+        )
+        .context("Failed to initialize outbound queue schema")?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Store a message whose first send just failed. Returns the row id.
+    pub fn enqueue(
+        &self,
+        channel: &str,
+        session_key: &str,
+        message: &SendMessage,
+        error: &str,
+        policy: &RetryPolicy,
+    ) -> Result<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = now_unix();
+        let next = now + duration_secs(policy.delay_after(1));
+        self.conn.lock().execute(
+            "INSERT INTO outbound_queue
+                (id, channel, session_key, recipient, subject, thread_ts, content,
+                 attempts, created_at, next_retry_at, last_error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 1, ?8, ?9, ?10)",
+            params![
+                id,
+                channel,
+                session_key,
+                message.recipient,
+                message.subject,
+                message.thread_ts,
+                message.content,
+                now,
+                next,
+                truncate_error(error),
+            ],
+        )?;
+        Ok(id)
+    }
+
+    /// Rows whose `next_retry_at` has passed, oldest first.
+    pub fn due(&self, now: i64, limit: usize) -> Result<Vec<QueuedOutbound>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, channel, session_key, recipient, subject, thread_ts, content,
+                    attempts, created_at, next_retry_at
+             FROM outbound_queue
+             WHERE next_retry_at <= ?1
+             ORDER BY created_at ASC
+             LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(
+            params![now, i64::try_from(limit).unwrap_or(i64::MAX)],
+            |row| {
+                Ok(QueuedOutbound {
+                    id: row.get(0)?,
+                    channel: row.get(1)?,
+                    session_key: row.get(2)?,
+                    message: SendMessage {
+                        recipient: row.get(3)?,
+                        subject: row.get(4)?,
+                        thread_ts: row.get(5)?,
+                        content: row.get(6)?,
+                        silent: false,
+                    },
+                    attempts: row.get(7)?,
+                    created_at: row.get(8)?,
+                    next_retry_at: row.get(9)?,
+                })
+            },
+        )?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(Into::into)
+    }
+
+    pub fn mark_delivered(&self, id: &str) -> Result<()> {
+        self.conn
+            .lock()
+            .execute("DELETE FROM outbound_queue WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    fn mark_failed(&self, item: &QueuedOutbound, error: &str, policy: &RetryPolicy) -> Result<()> {
+        let attempts = item.attempts.saturating_add(1);
+        let next = now_unix() + duration_secs(policy.delay_after(attempts));
+        self.conn.lock().execute(
+            "UPDATE outbound_queue
+             SET attempts = ?2, next_retry_at = ?3, last_error = ?4
+             WHERE id = ?1",
+            params![item.id, attempts, next, truncate_error(error)],
+        )?;
+        Ok(())
+    }
+
+    /// Delete rows queued longer than `ttl` ago. Returns how many were dropped.
+    pub fn drop_expired(&self, now: i64, ttl: Duration) -> Result<usize> {
+        let cutoff = now.saturating_sub(duration_secs(ttl));
+        let dropped = self.conn.lock().execute(
+            "DELETE FROM outbound_queue WHERE created_at < ?1",
+            params![cutoff],
+        )?;
+        Ok(dropped)
+    }
+
+    /// Number of queued messages per channel.
+    pub fn counts_by_channel(&self) -> Result<BTreeMap<String, u64>> {
+        let conn = self.conn.lock();
+        let mut stmt =
+            conn.prepare("SELECT channel, COUNT(*) FROM outbound_queue GROUP BY channel")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, u64>(1)?))
+        })?;
+        rows.collect::<rusqlite::Result<BTreeMap<_, _>>>()
+            .map_err(Into::into)
+    }
+}
+
+fn truncate_error(error: &str) -> String {
+    crate::util::truncate_with_ellipsis(error, LAST_ERROR_MAX_CHARS)
+}
+
+/// Queues opened by running channel runtimes, keyed by workspace.
+fn active_queues() -> &'static Mutex<HashMap<PathBuf, Arc<OutboundQueue>>> {
+    static QUEUES: OnceLock<Mutex<HashMap<PathBuf, Arc<OutboundQueue>>>> = OnceLock::new();
+    QUEUES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Share the queue of a workspace's channel runtime. Called once by
+/// `start_channels`.
+pub fn register_queue(workspace_dir: &Path, queue: Arc<OutboundQueue>) {
+    active_queues()
+        .lock()
+        .insert(workspace_dir.to_path_buf(), queue);
+}
+
+/// Queue registered for `workspace_dir`, if channels are running.
+pub fn queue_for(workspace_dir: &Path) -> Option<Arc<OutboundQueue>> {
+    active_queues().lock().get(workspace_dir).cloned()
+}
+
+/// Run `f` against `queue` on the blocking thread pool.
+pub async fn blocking<T, F>(queue: Arc<OutboundQueue>, f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&OutboundQueue) -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || f(&queue)).await?
+}
+
+/// One pass over the queue: expire old rows, then retry everything due.
+pub(crate) async fn drain_once(
+    queue: &Arc<OutboundQueue>,
+    channels: &HashMap<String, Arc<dyn Channel>>,
+    policy: &RetryPolicy,
+) -> Result<DrainStats> {
+    let now = now_unix();
+    let ttl = policy.ttl;
+    let (expired, due) = blocking(Arc::clone(queue), move |queue| {
+        Ok((queue.drop_expired(now, ttl)?, queue.due(now, DRAIN_BATCH)?))
+    })
+    .await?;
+    let mut stats = DrainStats {
+        expired,
+        ..DrainStats::default()
+    };
+    if stats.expired > 0 {
+        tracing::warn!(
+            "Dropped {} outbound message(s) that could not be delivered within {}s",
+            stats.expired,
+            policy.ttl.as_secs()
+        );
+    }
+
+    for item in due {
+        let result = match channels.get(&item.channel) {
+            Some(channel) => channel.send(&item.message).await,
+            None => Err(anyhow::anyhow!("channel '{}' is not running", item.channel)),
+        };
+        match result {
+            Ok(()) => {
+                let id = item.id.clone();
+                blocking(Arc::clone(queue), move |queue| queue.mark_delivered(&id)).await?;
+                stats.delivered += 1;
+                tracing::info!(
+                    channel = %item.channel,
+                    session = %item.session_key,
+                    attempts = item.attempts + 1,
+                    "Delivered queued outbound message"
+                );
+            }
+            Err(e) => {
+                let (error, policy) = (e.to_string(), *policy);
+                blocking(Arc::clone(queue), move |queue| {
+                    queue.mark_failed(&item, &error, &policy)
+                })
+                .await?;
+                stats.failed += 1;
+            }
+        }
+    }
+    Ok(stats)
+}
+
+/// Retry queued messages every [`DRAIN_INTERVAL`] until aborted.
+pub(crate) fn spawn_drainer(
+    queue: Arc<OutboundQueue>,
+    channels: Arc<HashMap<String, Arc<dyn Channel>>>,
+    policy: RetryPolicy,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(DRAIN_INTERVAL);
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            tick.tick().await;
+            if let Err(e) = drain_once(&queue, &channels, &policy).await {
+                tracing::warn!("Outbound queue drain failed: {e}");
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::ChannelMessage;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    /// Fails the first `fail_times` sends, then records deliveries.
+    struct FlakyChannel {
+        fail_times: usize,
+        calls: AtomicUsize,
+        delivered: parking_lot::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Channel for FlakyChannel {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn send(&self, message: &SendMessage) -> Result<()> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.fail_times {
+                anyhow::bail!("network unreachable");
+            }
+            self.delivered.lock().push(message.content.clone());
+            Ok(())
+        }
+
+        async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            ttl: Duration::from_secs(3600),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+
+    fn force_due(queue: &OutboundQueue) {
+        queue
+            .conn
+            .lock()
+            .execute("UPDATE outbound_queue SET next_retry_at = 0", [])
+            .unwrap();
+    }
+
+    #[test]
+    fn backoff_doubles_and_is_capped() {
+        let p = policy();
+        assert_eq!(p.delay_after(1), Duration::from_secs(5));
+        assert_eq!(p.delay_after(2), Duration::from_secs(10));
+        assert_eq!(p.delay_after(4), Duration::from_secs(40));
+        assert_eq!(p.delay_after(5), Duration::from_secs(60));
+        assert_eq!(p.delay_after(100), Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn message_is_delivered_exactly_once_after_channel_recovers() {
+        let tmp = TempDir::new().unwrap();
+        let queue = Arc::new(OutboundQueue::open(tmp.path()).unwrap());
+        let flaky = Arc::new(FlakyChannel {
+            fail_times: 2,
+            calls: AtomicUsize::new(0),
+            delivered: parking_lot::Mutex::new(Vec::new()),
+        });
+        let mut channels: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        channels.insert("flaky".into(), flaky.clone());
+
+        let msg = SendMessage::new("hello", "chat-1").in_thread(Some("t1".into()));
+        queue
+            .enqueue("flaky", "flaky_alice", &msg, "initial failure", &policy())
+            .unwrap();
+        assert_eq!(queue.counts_by_channel().unwrap()["flaky"], 1);
+
+        // Not due yet: nothing is attempted.
+        let stats = drain_once(&queue, &channels, &policy()).await.unwrap();
+        assert_eq!(stats, DrainStats::default());
+
+        force_due(&queue);
+        let stats = drain_once(&queue, &channels, &policy()).await.unwrap();
+        assert_eq!(stats.failed, 1);
+        let row = &queue.due(i64::MAX, 10).unwrap()[0];
+        assert_eq!(row.attempts, 2);
+        assert!(row.next_retry_at > now_unix());
+
+        force_due(&queue);
+        drain_once(&queue, &channels, &policy()).await.unwrap();
+        force_due(&queue);
+        let stats = drain_once(&queue, &channels, &policy()).await.unwrap();
+        assert_eq!(stats.delivered, 1);
+
+        force_due(&queue);
+        drain_once(&queue, &channels, &policy()).await.unwrap();
+        assert_eq!(*flaky.delivered.lock(), vec!["hello".to_string()]);
+        assert!(queue.counts_by_channel().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn registered_queue_is_shared_by_workspace() {
+        let tmp = TempDir::new().unwrap();
+        assert!(queue_for(tmp.path()).is_none());
+
+        let queue = Arc::new(OutboundQueue::open(tmp.path()).unwrap());
+        register_queue(tmp.path(), Arc::clone(&queue));
+        let msg = SendMessage::new("queued", "chat-3");
+        queue
+            .enqueue("slack", "slack_carol", &msg, "timeout", &policy())
+            .unwrap();
+
+        let shared = queue_for(tmp.path()).unwrap();
+        assert!(Arc::ptr_eq(&shared, &queue));
+        let counts = blocking(shared, |queue| queue.counts_by_channel())
+            .await
+            .unwrap();
+        assert_eq!(counts["slack"], 1);
+    }
+
+    #[tokio::test]
+    async fn queue_survives_reopen_and_expires_after_ttl() {
+        let tmp = TempDir::new().unwrap();
+        let msg = SendMessage::new("later", "chat-2");
+        OutboundQueue::open(tmp.path())
+            .unwrap()
+            .enqueue("telegram", "telegram_bob", &msg, "401", &policy())
+            .unwrap();
+
+        let queue = Arc::new(OutboundQueue::open(tmp.path()).unwrap());
+        let rows = queue.due(i64::MAX, 10).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].message, msg);
+
+        // Channel is not running: the row stays queued until it expires.
+        force_due(&queue);
+        let stats = drain_once(&queue, &HashMap::new(), &policy())
+            .await
+            .unwrap();
+        assert_eq!(stats.failed, 1);
+
+        let expired = RetryPolicy {
+            ttl: Duration::ZERO,
+            ..policy()
+        };
+        assert_eq!(queue.drop_expired(now_unix() + 1, expired.ttl).unwrap(), 1);
+        assert!(queue.counts_by_channel().unwrap().is_empty());
+    }
+}
@@ -2,7 +2,7 @@ use super::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
 use serde_json::json;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tokio_tungstenite::tungstenite::Message;
@@ -451,7 +451,11 @@ impl Channel for QQChannel {
                             let chat_id = format!("user:{user_openid}");
 
                             let channel_msg = ChannelMessage {
-                                id: Uuid::new_v4().to_string(),
+                                id: if msg_id.is_empty() {
+                                    Uuid::new_v4().to_string()
+                                } else {
+                                    format!("qq_{msg_id}")
+                                },
                                 sender: user_openid.to_string(),
                                 reply_target: chat_id,
                                 content,
@@ -461,6 +465,8 @@ impl Channel for QQChannel {
                                     .unwrap_or_default()
                                     .as_secs(),
                                 thread_ts: None,
+                                sender_name: None,
+                                metadata: HashMap::new(),
                             };
 
                             if tx.send(channel_msg).await.is_err() {
@@ -489,7 +495,11 @@ impl Channel for QQChannel {
                             let chat_id = format!("group:{group_openid}");
 
                             let channel_msg = ChannelMessage {
-                                id: Uuid::new_v4().to_string(),
+                                id: if msg_id.is_empty() {
+                                    Uuid::new_v4().to_string()
+                                } else {
+                                    format!("qq_{msg_id}")
+                                },
                                 sender: author_id.to_string(),
                                 reply_target: chat_id,
                                 content,
@@ -499,6 +509,8 @@ impl Channel for QQChannel {
                                     .unwrap_or_default()
                                     .as_secs(),
                                 thread_ts: None,
+                                sender_name: None,
+                                metadata: HashMap::new(),
                             };
 
                             if tx.send(channel_msg).await.is_err() {
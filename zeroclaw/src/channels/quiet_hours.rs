@@ -0,0 +1,571 @@
+//! Quiet hours for agent-initiated messages.
+//!
+//! Cron and heartbeat deliveries are screened against `[quiet_hours]` for
+//! their channel before they are sent. Inside the window a message is
+//! deferred, dropped or sent without a notification. Deferred messages are
+//! stored in `state/deferred_outbound.db` with a release time at the end of
+//! the window; the scheduler releases them, combining every message held
+//! for the same channel and recipient into one. Replies to user messages
+//! are never held back.
+
+use crate::config::{Config, QuietHoursBehavior, QuietHoursConfig};
+use crate::cron::at::ScheduleTz;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDateTime, NaiveTime, Utc};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+/// Header of a released batch.
+const BATCH_HEADER: &str = "While you were away:";
+const BATCH_SEPARATOR: &str = "\n\n---\n\n";
+
+/// Why a message is being sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageOrigin {
+    /// A reply to a user message; never held back.
+    UserReply,
+    /// Sent by the operator through the gateway; never held back.
+    Operator,
+    Cron,
+    Heartbeat,
+}
+
+impl MessageOrigin {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::UserReply => "user_reply",
+            Self::Operator => "operator",
+            Self::Cron => "cron",
+            Self::Heartbeat => "heartbeat",
+        }
+    }
+
+    pub fn is_agent_initiated(self) -> bool {
+        !matches!(self, Self::UserReply | Self::Operator)
+    }
+}
+
+/// Parse a `HH:MM` wall time.
+pub fn parse_wall_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// A daily wall-clock window; `end` before `start` wraps past midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietWindow {
+    /// Whether wall time `t` is inside the window. `start == end` is empty.
+    pub fn contains(&self, t: NaiveTime) -> bool {
+        if self.start <= self.end {
+            t >= self.start && t < self.end
+        } else {
+            t >= self.start || t < self.end
+        }
+    }
+
+    /// The next time the window ends after `at`.
+    pub fn end_after(&self, at: NaiveDateTime) -> NaiveDateTime {
+        let end_today = at.date().and_time(self.end);
+        if end_today > at {
+            end_today
+        } else {
+            end_today + Duration::days(1)
+        }
+    }
+}
+
+/// The quiet-hours window and behavior for `channel`, or `None` when quiet
+/// hours are off for it.
+pub fn policy_for(
+    config: &QuietHoursConfig,
+    channel: &str,
+) -> Option<(QuietWindow, QuietHoursBehavior)> {
+    let channel_override = config.channels.get(&channel.to_ascii_lowercase());
+    let enabled = channel_override
+        .and_then(|o| o.enabled)
+        .unwrap_or(config.enabled);
+    if !enabled {
+        return None;
+    }
+    let start = channel_override
+        .and_then(|o| o.start.as_deref())
+        .unwrap_or(&config.start);
+    let end = channel_override
+        .and_then(|o| o.end.as_deref())
+        .unwrap_or(&config.end);
+    let behavior = channel_override
+        .and_then(|o| o.behavior)
+        .unwrap_or(config.behavior);
+    Some((
+        QuietWindow {
+            start: parse_wall_time(start)?,
+            end: parse_wall_time(end)?,
+        },
+        behavior,
+    ))
+}
+
+/// What to do with a message right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Send,
+    SendSilent,
+    Defer { release_at: DateTime<Utc> },
+    Drop,
+}
+
+/// Apply the quiet-hours policy for `channel` to a message from `origin`
+/// sent at `now`.
+pub fn decide(
+    config: &Config,
+    channel: &str,
+    origin: MessageOrigin,
+    now: DateTime<Utc>,
+) -> Decision {
+    if !origin.is_agent_initiated() {
+        return Decision::Send;
+    }
+    let Some((window, behavior)) = policy_for(&config.quiet_hours, channel) else {
+        return Decision::Send;
+    };
+    let tz = ScheduleTz::from_config(config.timezone.as_deref()).unwrap_or(ScheduleTz::Local);
+    let local = tz.local_datetime(now);
+    if !window.contains(local.time()) {
+        return Decision::Send;
+    }
+    match behavior {
+        QuietHoursBehavior::Drop => Decision::Drop,
+        QuietHoursBehavior::SendSilent => Decision::SendSilent,
+        QuietHoursBehavior::Defer => {
+            let end = window.end_after(local);
+            // An end skipped by a DST jump resolves an hour later.
+            let release_at = tz
+                .to_utc(end)
+                .or_else(|_| tz.to_utc(end + Duration::hours(1)))
+                .unwrap_or(now + Duration::hours(1));
+            Decision::Defer { release_at }
+        }
+    }
+}
+
+/// Outcome of [`screen`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Screened {
+    /// Send now, without a notification when `silent`.
+    Send { silent: bool },
+    /// Deferred or dropped; nothing to send now.
+    Held,
+}
+
+/// Screen a message to `recipient` on `channel` at `now`: deferred
+/// messages are stored for release, dropped ones are logged.
+pub fn screen(
+    config: &Config,
+    channel: &str,
+    recipient: &str,
+    content: &str,
+    origin: MessageOrigin,
+    now: DateTime<Utc>,
+) -> Result<Screened> {
+    match decide(config, channel, origin, now) {
+        Decision::Send => Ok(Screened::Send { silent: false }),
+        Decision::SendSilent => Ok(Screened::Send { silent: true }),
+        Decision::Drop => {
+            tracing::info!(
+                channel,
+                origin = origin.as_str(),
+                "Dropped a message sent during quiet hours"
+            );
+            Ok(Screened::Held)
+        }
+        Decision::Defer { release_at } => {
+            DeferredOutbox::open(&config.workspace_dir)?
+                .defer(channel, recipient, content, origin, release_at)?;
+            tracing::info!(
+                channel,
+                origin = origin.as_str(),
+                release_at = %release_at,
+                "Deferred a message sent during quiet hours"
+            );
+            Ok(Screened::Held)
+        }
+    }
+}
+
+/// Messages held for one channel and recipient.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeferredBatch {
+    pub channel: String,
+    pub recipient: String,
+    pub ids: Vec<String>,
+    /// Oldest first.
+    pub messages: Vec<String>,
+}
+
+impl DeferredBatch {
+    /// The batch as one message.
+    pub fn combined(&self) -> String {
+        format!("{BATCH_HEADER}\n\n{}", self.messages.join(BATCH_SEPARATOR))
+    }
+}
+
+pub fn deferred_db_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("state").join("deferred_outbound.db")
+}
+
+/// Messages deferred by quiet hours, waiting for their release time.
+pub struct DeferredOutbox {
+    conn: Mutex<Connection>,
+}
+
+impl DeferredOutbox {
+    pub fn open(workspace_dir: &Path) -> Result<Self> {
+        let db_path = deferred_db_path(workspace_dir);
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create state directory: {}", parent.display())
+            })?;
+        }
+        let conn = Connection::open(&db_path)
+            .with_context(|| format!("Failed to open deferred outbox: {}", db_path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS deferred_outbound (
+                id         TEXT PRIMARY KEY,
+                channel    TEXT NOT NULL,
+                recipient  TEXT NOT NULL,
+                content    TEXT NOT NULL,
+                origin     TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                release_at INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_deferred_outbound_release
+                ON deferred_outbound(release_at);",
+        )
+        .context("Failed to initialize deferred outbox schema")?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Hold `content` until `release_at`. Returns the row id.
+    pub fn defer(
+        &self,
+        channel: &str,
+        recipient: &str,
+        content: &str,
+        origin: MessageOrigin,
+        release_at: DateTime<Utc>,
+    ) -> Result<String> {
+        let id = uuid::Uuid::new_v4().to_string();
+        self.conn.lock().execute(
+            "INSERT INTO deferred_outbound
+                (id, channel, recipient, content, origin, created_at, release_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                id,
+                channel,
+                recipient,
+                content,
+                origin.as_str(),
+                Utc::now().timestamp_micros(),
+                release_at.timestamp(),
+            ],
+        )?;
+        Ok(id)
+    }
+
+    /// Released messages, one batch per channel and recipient.
+    pub fn due(&self, now: DateTime<Utc>) -> Result<Vec<DeferredBatch>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, channel, recipient, content
+             FROM deferred_outbound
+             WHERE release_at <= ?1
+             ORDER BY channel, recipient, created_at ASC, rowid ASC",
+        )?;
+        let rows = stmt.query_map(params![now.timestamp()], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?;
+
+        let mut batches: Vec<DeferredBatch> = Vec::new();
+        for row in rows {
+            let (id, channel, recipient, content) = row?;
+            match batches.last_mut() {
+                Some(batch) if batch.channel == channel && batch.recipient == recipient => {
+                    batch.ids.push(id);
+                    batch.messages.push(content);
+                }
+                _ => batches.push(DeferredBatch {
+                    channel,
+                    recipient,
+                    ids: vec![id],
+                    messages: vec![content],
+                }),
+            }
+        }
+        Ok(batches)
+    }
+
+    pub fn remove(&self, ids: &[String]) -> Result<()> {
+        let conn = self.conn.lock();
+        for id in ids {
+            conn.execute("DELETE FROM deferred_outbound WHERE id = ?1", params![id])?;
+        }
+        Ok(())
+    }
+}
+
+/// Send every batch released by `now` with `send(channel, recipient,
+/// combined)`. Sent batches are removed; failed ones stay for the next
+/// pass. Returns how many batches were sent.
+pub async fn release_due<F, Fut>(
+    outbox: &DeferredOutbox,
+    now: DateTime<Utc>,
+    send: F,
+) -> Result<usize>
+where
+    F: Fn(String, String, String) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let mut sent = 0;
+    for batch in outbox.due(now)? {
+        match send(
+            batch.channel.clone(),
+            batch.recipient.clone(),
+            batch.combined(),
+        )
+        .await
+        {
+            Ok(()) => {
+                outbox.remove(&batch.ids)?;
+                sent += 1;
+                tracing::info!(
+                    channel = %batch.channel,
+                    messages = batch.messages.len(),
+                    "Released messages deferred by quiet hours"
+                );
+            }
+            Err(e) => {
+                tracing::warn!(
+                    channel = %batch.channel,
+                    "Failed to release deferred messages: {e}"
+                );
+            }
+        }
+    }
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::QuietHoursOverride;
+    use chrono::TimeZone;
+    use std::sync::Arc;
+
+    fn time(value: &str) -> NaiveTime {
+        parse_wall_time(value).unwrap()
+    }
+
+    fn at(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 10, h, m, 0).unwrap()
+    }
+
+    fn quiet_config(workspace: &Path) -> Config {
+        let mut config = Config::default();
+        config.workspace_dir = workspace.to_path_buf();
+        config.timezone = Some("UTC".into());
+        config.quiet_hours.enabled = true;
+        config
+    }
+
+    #[test]
+    fn window_wraps_past_midnight() {
+        let window = QuietWindow {
+            start: time("22:00"),
+            end: time("07:00"),
+        };
+        assert!(window.contains(time("22:00")));
+        assert!(window.contains(time("23:59")));
+        assert!(window.contains(time("00:00")));
+        assert!(window.contains(time("06:59")));
+        assert!(!window.contains(time("07:00")));
+        assert!(!window.contains(time("12:00")));
+        assert!(!window.contains(time("21:59")));
+
+        let late = at(23, 30).naive_utc();
+        assert_eq!(
+            window.end_after(late),
+            at(7, 0).naive_utc() + Duration::days(1)
+        );
+        let early = at(3, 0).naive_utc();
+        assert_eq!(window.end_after(early), at(7, 0).naive_utc());
+    }
+
+    #[test]
+    fn same_day_window_and_empty_window() {
+        let window = QuietWindow {
+            start: time("13:00"),
+            end: time("15:00"),
+        };
+        assert!(window.contains(time("14:00")));
+        assert!(!window.contains(time("15:00")));
+        assert!(!window.contains(time("12:59")));
+        assert_eq!(
+            window.end_after(at(14, 0).naive_utc()),
+            at(15, 0).naive_utc()
+        );
+
+        let empty = QuietWindow {
+            start: time("09:00"),
+            end: time("09:00"),
+        };
+        assert!(!empty.contains(time("09:00")));
+    }
+
+    #[test]
+    fn channel_overrides_fall_back_to_the_global_policy() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = quiet_config(tmp.path());
+        config.quiet_hours.channels.insert(
+            "telegram".into(),
+            QuietHoursOverride {
+                behavior: Some(QuietHoursBehavior::SendSilent),
+                ..QuietHoursOverride::default()
+            },
+        );
+        config.quiet_hours.channels.insert(
+            "slack".into(),
+            QuietHoursOverride {
+                enabled: Some(false),
+                ..QuietHoursOverride::default()
+            },
+        );
+
+        let night = at(23, 0);
+        assert_eq!(
+            decide(&config, "telegram", MessageOrigin::Cron, night),
+            Decision::SendSilent
+        );
+        assert_eq!(
+            decide(&config, "slack", MessageOrigin::Cron, night),
+            Decision::Send
+        );
+        assert_eq!(
+            decide(&config, "discord", MessageOrigin::Heartbeat, night),
+            Decision::Defer {
+                release_at: at(7, 0) + Duration::days(1)
+            }
+        );
+        assert_eq!(
+            decide(&config, "discord", MessageOrigin::Cron, at(12, 0)),
+            Decision::Send
+        );
+    }
+
+    #[test]
+    fn user_replies_are_never_held() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = quiet_config(tmp.path());
+        config.quiet_hours.behavior = QuietHoursBehavior::Drop;
+        let night = at(2, 0);
+        assert_eq!(
+            decide(&config, "telegram", MessageOrigin::Cron, night),
+            Decision::Drop
+        );
+        assert_eq!(
+            screen(
+                &config,
+                "telegram",
+                "42",
+                "hi",
+                MessageOrigin::UserReply,
+                night
+            )
+            .unwrap(),
+            Screened::Send { silent: false }
+        );
+    }
+
+    #[tokio::test]
+    async fn deferred_messages_are_released_as_one_batch() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = quiet_config(tmp.path());
+        let night = at(23, 0);
+        for content in ["Backup finished", "Disk at 91%"] {
+            let screened = screen(
+                &config,
+                "telegram",
+                "42",
+                content,
+                MessageOrigin::Cron,
+                night,
+            )
+            .unwrap();
+            assert_eq!(screened, Screened::Held);
+        }
+        screen(
+            &config,
+            "discord",
+            "chan",
+            "Report ready",
+            MessageOrigin::Heartbeat,
+            night,
+        )
+        .unwrap();
+
+        let outbox = DeferredOutbox::open(tmp.path()).unwrap();
+        assert!(outbox.due(at(23, 30)).unwrap().is_empty());
+
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let morning = at(7, 0) + Duration::days(1);
+        let released = release_due(&outbox, morning, |channel, recipient, content| {
+            let sent = Arc::clone(&sent);
+            async move {
+                sent.lock().push((channel, recipient, content));
+                Ok(())
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(released, 2);
+        let sent = sent.lock();
+        assert_eq!(sent[0].0, "discord");
+        assert_eq!(sent[1].0, "telegram");
+        assert_eq!(sent[1].1, "42");
+        assert_eq!(
+            sent[1].2,
+            "While you were away:\n\nBackup finished\n\n---\n\nDisk at 91%"
+        );
+        assert!(outbox.due(morning).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_releases_stay_queued() {
+        let tmp = tempfile::tempdir().unwrap();
+        let outbox = DeferredOutbox::open(tmp.path()).unwrap();
+        outbox
+            .defer("telegram", "42", "hello", MessageOrigin::Cron, at(7, 0))
+            .unwrap();
+
+        let released = release_due(&outbox, at(8, 0), |_, _, _| async {
+            anyhow::bail!("network down")
+        })
+        .await
+        .unwrap();
+        assert_eq!(released, 0);
+        assert_eq!(outbox.due(at(8, 0)).unwrap().len(), 1);
+    }
+}
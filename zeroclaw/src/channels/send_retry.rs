@@ -0,0 +1,497 @@
+//! Retry and concurrency control for outbound adapter sends.
+//!
+//! Adapters run each platform API call through [`send_with_retry`]: rate
+//! limits (429), server errors and network failures are retried with
+//! exponential backoff plus random jitter, honoring the platform's
+//! `Retry-After` hint, while other client errors fail immediately. Multi-chunk
+//! replies go through [`send_chunks_with_retry`], which retries each chunk on
+//! its own so a transient failure resumes at the failed chunk instead of
+//! dropping the rest. [`OutboundLimiter`] bounds how many sends one adapter
+//! runs at once across chats (`runtime.adapter_max_inflight`) and keeps
+//! sends to the same chat in order, retries included, and
+//! [`require_recipient`] refuses a send that has no target before any call.
+
+use super::traits::SendMessage;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{OwnedMutexGuard, Semaphore, SemaphorePermit};
+
+/// Retries after the first failed attempt.
+pub const MAX_SEND_RETRIES: u32 = 3;
+/// Default for `runtime.adapter_max_inflight`.
+pub const DEFAULT_ADAPTER_MAX_INFLIGHT: usize = 4;
+/// Default for `runtime.adapter_retry_jitter_ms`.
+pub const DEFAULT_ADAPTER_RETRY_JITTER_MS: u64 = 250;
+
+/// Targets an adapter keeps ordered at once. Lanes are dropped as soon as
+/// they go idle, so this is only reached with this many chats mid-send;
+/// beyond it, sends to further targets are not ordered.
+const MAX_ORDERED_TARGETS: usize = 1024;
+
+const BASE_BACKOFF_MS: u64 = 500;
+/// Cap for both the exponential backoff and platform `Retry-After` hints.
+const MAX_BACKOFF_MS: u64 = 30_000;
+
+/// A failed send attempt, classified for retry.
+#[derive(Debug)]
+pub struct SendError {
+    error: anyhow::Error,
+    retryable: bool,
+    retry_after: Option<Duration>,
+}
+
+impl SendError {
+    /// A failure that will not go away by retrying (bad request, auth).
+    pub fn fatal(error: impl Into<anyhow::Error>) -> Self {
+        Self {
+            error: error.into(),
+            retryable: false,
+            retry_after: None,
+        }
+    }
+
+    /// A failure worth retrying, optionally after a platform-provided delay.
+    pub fn transient(error: impl Into<anyhow::Error>, retry_after: Option<Duration>) -> Self {
+        Self {
+            error: error.into(),
+            retryable: true,
+            retry_after,
+        }
+    }
+
+    /// Classify an HTTP error response: 429 and 5xx are retryable.
+    pub fn from_status(
+        status: reqwest::StatusCode,
+        retry_after: Option<Duration>,
+        error: impl Into<anyhow::Error>,
+    ) -> Self {
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            Self::transient(error, retry_after)
+        } else {
+            Self::fatal(error)
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        self.retryable
+    }
+}
+
+impl From<reqwest::Error> for SendError {
+    /// Connection failures and timeouts are transient.
+    fn from(error: reqwest::Error) -> Self {
+        Self::transient(error, None)
+    }
+}
+
+/// Parse a `Retry-After` header given in (possibly fractional) seconds.
+pub fn retry_after_header(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let secs: f64 = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    (secs.is_finite() && secs >= 0.0).then(|| Duration::from_secs_f64(secs))
+}
+
+/// Delay before retry number `retry` (0-based).
+///
+/// A platform `Retry-After` hint is used as given (capped); otherwise the
+/// delay doubles from 500 ms. Up to `jitter_ms` of random jitter is added so
+/// adapters retrying at the same time do not hit the API in lockstep.
+pub fn backoff_delay(retry: u32, retry_after: Option<Duration>, jitter_ms: u64) -> Duration {
+    let base_ms = match retry_after {
+        Some(hint) => u64::try_from(hint.as_millis()).unwrap_or(u64::MAX),
+        None => BASE_BACKOFF_MS.saturating_mul(1_u64 << retry.min(16)),
+    }
+    .min(MAX_BACKOFF_MS);
+    let jitter = if jitter_ms == 0 {
+        0
+    } else {
+        rand::random::<u64>() % (jitter_ms + 1)
+    };
+    Duration::from_millis(base_ms + jitter)
+}
+
+/// Run `attempt` until it succeeds, fails permanently, or runs out of retries.
+pub async fn send_with_retry<F, Fut>(
+    what: &str,
+    jitter_ms: u64,
+    mut attempt: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<(), SendError>>,
+{
+    let mut retry = 0;
+    loop {
+        match attempt().await {
+            Ok(()) => return Ok(()),
+            Err(err) if err.retryable && retry < MAX_SEND_RETRIES => {
+                let delay = backoff_delay(retry, err.retry_after, jitter_ms);
+                tracing::warn!(
+                    "{what} failed (retry {}/{MAX_SEND_RETRIES} in {}ms): {:#}",
+                    retry + 1,
+                    delay.as_millis(),
+                    err.error
+                );
+                tokio::time::sleep(delay).await;
+                retry += 1;
+            }
+            Err(err) => return Err(err.error),
+        }
+    }
+}
+
+/// Send `count` chunks in order, retrying each one independently.
+///
+/// `send_chunk(index)` performs one attempt for chunk `index`. Chunks that
+/// were delivered are never re-sent; `gap` is slept between chunks.
+pub async fn send_chunks_with_retry<F, Fut>(
+    what: &str,
+    count: usize,
+    gap: Duration,
+    jitter_ms: u64,
+    mut send_chunk: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = Result<(), SendError>>,
+{
+    for index in 0..count {
+        send_with_retry(what, jitter_ms, || send_chunk(index)).await?;
+        if index + 1 < count && !gap.is_zero() {
+            tokio::time::sleep(gap).await;
+        }
+    }
+    Ok(())
+}
+
+/// The trimmed recipient of `message`, or an error (and a warning) when it
+/// has none, so a reply without a target is reported instead of being sent
+/// to nowhere or dropped silently.
+pub fn require_recipient<'a>(channel: &str, message: &'a SendMessage) -> anyhow::Result<&'a str> {
+    let recipient = message.recipient.trim();
+    if recipient.is_empty() {
+        tracing::warn!(channel, "Dropping outbound message with no recipient");
+        anyhow::bail!("{channel} send has no recipient");
+    }
+    Ok(recipient)
+}
+
+/// One FIFO lane per target; a send holds its target's lane until it is
+/// done, so a retrying message cannot be overtaken by the next one.
+type Lanes = Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>;
+
+/// Bounds concurrent outbound sends of one adapter, keeps sends to the same
+/// target in order and carries its retry jitter.
+#[derive(Debug, Clone)]
+pub struct OutboundLimiter {
+    permits: Arc<Semaphore>,
+    lanes: Lanes,
+    retry_jitter_ms: u64,
+}
+
+impl OutboundLimiter {
+    pub fn new(max_inflight: usize, retry_jitter_ms: u64) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_inflight.max(1))),
+            lanes: Lanes::default(),
+            retry_jitter_ms,
+        }
+    }
+
+    /// Wait for a send slot; the slot is released when the permit drops.
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        self.permits
+            .acquire()
+            .await
+            .expect("outbound semaphore is never closed")
+    }
+
+    /// Wait until earlier sends to `target` are finished, then for a send
+    /// slot. Hold the permit for the whole send, retries included. Sends to
+    /// other targets still run concurrently up to `max_inflight`.
+    pub async fn acquire_for(&self, target: &str) -> OutboundPermit<'_> {
+        // Queue on the lane before taking a slot, so waiting for a busy
+        // chat never holds up other chats.
+        let lane = match self.lane(target) {
+            Some(lane) => Some(LaneGuard {
+                guard: Some(lane.lock_owned().await),
+                target: target.to_string(),
+                lanes: Arc::clone(&self.lanes),
+            }),
+            None => None,
+        };
+        OutboundPermit {
+            _slot: self.acquire().await,
+            _lane: lane,
+        }
+    }
+
+    fn lane(&self, target: &str) -> Option<Arc<tokio::sync::Mutex<()>>> {
+        let mut lanes = self.lanes.lock();
+        if let Some(lane) = lanes.get(target) {
+            return Some(Arc::clone(lane));
+        }
+        if lanes.len() >= MAX_ORDERED_TARGETS {
+            // Lanes abandoned by cancelled sends are only found here.
+            lanes.retain(|_, lane| Arc::strong_count(lane) > 1);
+            if lanes.len() >= MAX_ORDERED_TARGETS {
+                tracing::debug!(target, "Outbound ordering table full; sending unordered");
+                return None;
+            }
+        }
+        let lane = Arc::new(tokio::sync::Mutex::new(()));
+        lanes.insert(target.to_string(), Arc::clone(&lane));
+        Some(lane)
+    }
+
+    pub fn retry_jitter_ms(&self) -> u64 {
+        self.retry_jitter_ms
+    }
+}
+
+/// A send slot plus the target's lane, from [`OutboundLimiter::acquire_for`].
+pub struct OutboundPermit<'a> {
+    _slot: SemaphorePermit<'a>,
+    _lane: Option<LaneGuard>,
+}
+
+struct LaneGuard {
+    guard: Option<OwnedMutexGuard<()>>,
+    target: String,
+    lanes: Lanes,
+}
+
+impl Drop for LaneGuard {
+    /// Release the lane and drop it from the map when nobody is waiting.
+    fn drop(&mut self) {
+        drop(self.guard.take());
+        let mut lanes = self.lanes.lock();
+        if lanes
+            .get(&self.target)
+            .is_some_and(|lane| Arc::strong_count(lane) == 1)
+        {
+            lanes.remove(&self.target);
+        }
+    }
+}
+
+impl Default for OutboundLimiter {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_ADAPTER_MAX_INFLIGHT,
+            DEFAULT_ADAPTER_RETRY_JITTER_MS,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn rate_limited() -> SendError {
+        SendError::from_status(
+            reqwest::StatusCode::TOO_MANY_REQUESTS,
+            Some(Duration::ZERO),
+            anyhow::anyhow!("429"),
+        )
+    }
+
+    #[test]
+    fn backoff_doubles_honors_retry_after_and_caps() {
+        assert_eq!(backoff_delay(0, None, 0), Duration::from_millis(500));
+        assert_eq!(backoff_delay(2, None, 0), Duration::from_secs(2));
+        assert_eq!(backoff_delay(30, None, 0), Duration::from_secs(30));
+        assert_eq!(
+            backoff_delay(0, Some(Duration::from_secs(7)), 0),
+            Duration::from_secs(7)
+        );
+        assert_eq!(
+            backoff_delay(0, Some(Duration::from_secs(3600)), 0),
+            Duration::from_secs(30)
+        );
+        let jittered = backoff_delay(0, None, 100);
+        assert!(jittered >= Duration::from_millis(500) && jittered <= Duration::from_millis(600));
+    }
+
+    #[test]
+    fn classifies_statuses_and_parses_retry_after_header() {
+        let err = |status| SendError::from_status(status, None, anyhow::anyhow!("x"));
+        assert!(err(reqwest::StatusCode::TOO_MANY_REQUESTS).is_retryable());
+        assert!(err(reqwest::StatusCode::BAD_GATEWAY).is_retryable());
+        assert!(!err(reqwest::StatusCode::BAD_REQUEST).is_retryable());
+        assert!(!err(reqwest::StatusCode::FORBIDDEN).is_retryable());
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(retry_after_header(&headers), None);
+        headers.insert(reqwest::header::RETRY_AFTER, "1.5".parse().unwrap());
+        assert_eq!(
+            retry_after_header(&headers),
+            Some(Duration::from_millis(1500))
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_transient_failures_until_success() {
+        let calls = AtomicUsize::new(0);
+        send_with_retry("test", 0, || async {
+            if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(rate_limited())
+            } else {
+                Ok(())
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries_and_on_fatal_errors() {
+        let calls = AtomicUsize::new(0);
+        let err = send_with_retry("test", 0, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(rate_limited())
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "429");
+        assert_eq!(calls.load(Ordering::SeqCst), 1 + MAX_SEND_RETRIES as usize);
+
+        let calls = AtomicUsize::new(0);
+        send_with_retry("test", 0, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(SendError::fatal(anyhow::anyhow!("400")))
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn chunk_sequence_resumes_at_the_failed_chunk() {
+        let attempts = Mutex::new(Vec::new());
+        let failed_once = AtomicUsize::new(0);
+        send_chunks_with_retry("test", 3, Duration::ZERO, 0, |index| {
+            attempts.lock().push(index);
+            let fail = index == 1 && failed_once.fetch_add(1, Ordering::SeqCst) == 0;
+            async move {
+                if fail {
+                    Err(rate_limited())
+                } else {
+                    Ok(())
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(*attempts.lock(), vec![0, 1, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn limiter_bounds_concurrent_sends() {
+        let limiter = OutboundLimiter::new(2, 0);
+        let first = limiter.acquire().await;
+        let _second = limiter.acquire().await;
+        assert!(
+            tokio::time::timeout(Duration::from_millis(20), limiter.acquire())
+                .await
+                .is_err()
+        );
+        drop(first);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(20), limiter.acquire())
+                .await
+                .is_ok()
+        );
+    }
+
+    /// Send `label` to `target` through `limiter`, failing the first attempt
+    /// of `A` with a rate limit.
+    async fn send_ordered(
+        limiter: &OutboundLimiter,
+        target: &str,
+        label: &'static str,
+        delivered: &Mutex<Vec<&'static str>>,
+        attempts: &AtomicUsize,
+    ) {
+        let _permit = limiter.acquire_for(target).await;
+        send_with_retry("test", 0, || async {
+            if label == "A" && attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(SendError::transient(
+                    anyhow::anyhow!("429"),
+                    Some(Duration::from_millis(100)),
+                ));
+            }
+            delivered.lock().push(label);
+            Ok(())
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn retried_send_is_not_overtaken_on_the_same_target() {
+        let limiter = OutboundLimiter::new(4, 0);
+        let delivered = Mutex::new(Vec::new());
+        let attempts = AtomicUsize::new(0);
+
+        let a = send_ordered(&limiter, "chat-1", "A", &delivered, &attempts);
+        let b = async {
+            // Queued after A's first, failed attempt.
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            send_ordered(&limiter, "chat-1", "B", &delivered, &attempts).await;
+        };
+        let c = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            send_ordered(&limiter, "chat-2", "C", &delivered, &attempts).await;
+        };
+        tokio::join!(a, b, c);
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        // C (another chat) overtakes the retrying A; B never does.
+        assert_eq!(*delivered.lock(), vec!["C", "A", "B"]);
+        assert!(
+            limiter.lanes.lock().is_empty(),
+            "idle lanes were not reaped"
+        );
+    }
+
+    #[tokio::test]
+    async fn waiting_on_a_busy_target_does_not_take_a_slot() {
+        let limiter = OutboundLimiter::new(2, 0);
+        let busy = limiter.acquire_for("chat-1").await;
+        let queued = limiter.acquire_for("chat-1");
+        tokio::pin!(queued);
+        assert!(tokio::time::timeout(Duration::from_millis(20), &mut queued)
+            .await
+            .is_err());
+        // The second slot is still free for another chat.
+        let other = tokio::time::timeout(Duration::from_millis(20), limiter.acquire_for("chat-2"))
+            .await
+            .expect("queued send held a slot");
+        drop((busy, other));
+        let _next = queued.await;
+        assert_eq!(limiter.lanes.lock().len(), 1);
+    }
+
+    #[test]
+    fn lane_table_is_bounded() {
+        let limiter = OutboundLimiter::new(1, 0);
+        let held: Vec<_> = (0..MAX_ORDERED_TARGETS)
+            .map(|i| limiter.lane(&format!("chat-{i}")).unwrap())
+            .collect();
+        assert!(limiter.lane("one-more").is_none());
+        drop(held);
+        assert!(limiter.lane("one-more").is_some());
+        assert_eq!(limiter.lanes.lock().len(), 1);
+    }
+}
@@ -1,8 +1,10 @@
+use crate::channels::formatting::{ChannelFormatter, PlainTextFormatter};
 use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use futures_util::StreamExt;
 use reqwest::Client;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use uuid::Uuid;
@@ -266,6 +268,8 @@ impl SignalChannel {
             channel: "signal".to_string(),
             timestamp: timestamp / 1000, // millis → secs
             thread_ts: None,
+            sender_name: None,
+            metadata: HashMap::new(),
         })
     }
 }
@@ -277,15 +281,16 @@ impl Channel for SignalChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let content = PlainTextFormatter.format(&message.content);
         let params = match Self::parse_recipient_target(&message.recipient) {
             RecipientTarget::Direct(number) => serde_json::json!({
                 "recipient": [number],
-                "message": &message.content,
+                "message": &content,
                 "account": &self.account,
             }),
             RecipientTarget::Group(group_id) => serde_json::json!({
                 "groupId": group_id,
-                "message": &message.content,
+                "message": &content,
                 "account": &self.account,
             }),
         };
@@ -1,13 +1,73 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::formatting::{ChannelFormatter, SlackFormatter};
+use super::send_retry::{
+    require_recipient, retry_after_header, send_chunks_with_retry, OutboundLimiter, SendError,
+};
+use super::splitting::split_message;
+use super::traits::{Channel, ChannelMessage, SendMessage, THREAD_PARENT_METADATA_KEY};
 use async_trait::async_trait;
+use parking_lot::Mutex;
 use std::collections::HashMap;
+use std::future::Future;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
+/// Slack truncates `chat.postMessage` text beyond 40 000 characters.
+const SLACK_MAX_MESSAGE_LENGTH: usize = 40_000;
+const SLACK_API_BASE: &str = "https://slack.com/api";
+
 /// Slack channel — polls conversations.history via Web API
 pub struct SlackChannel {
     bot_token: String,
     channel_id: Option<String>,
     allowed_users: Vec<String>,
+    /// Post an editable "⏳ running <tool>…" message while a turn runs tools.
+    progress_messages: bool,
+    /// `users.info` results by user id; `None` caches a failed lookup.
+    user_names: Mutex<HashMap<String, Option<String>>>,
+    /// Thread root text by `channel:thread_ts`; `None` caches a failed fetch.
+    thread_parents: Mutex<HashMap<String, Option<String>>>,
+    api_base: String,
+    outbound: OutboundLimiter,
+}
+
+/// Cap on cached `users.info` lookups; the cache is cleared when full.
+const USER_NAME_CACHE_CAP: usize = 512;
+/// Cap on cached thread root messages; the cache is cleared when full.
+const THREAD_PARENT_CACHE_CAP: usize = 512;
+
+/// Split an outbound target of the form `channel` or `channel:thread_ts`
+/// (e.g. `C123:1712345678.000100`), the latter replying in that thread.
+pub(crate) fn parse_delivery_target(target: &str) -> anyhow::Result<(&str, Option<&str>)> {
+    let (channel, thread_ts) = match target.split_once(':') {
+        Some((channel, ts)) => (channel, Some(ts)),
+        None => (target, None),
+    };
+    if channel.is_empty() || channel.chars().any(char::is_whitespace) {
+        anyhow::bail!("invalid Slack target '{target}': expected 'channel' or 'channel:thread_ts'");
+    }
+    if let Some(ts) = thread_ts {
+        if !is_message_ts(ts) {
+            anyhow::bail!(
+                "invalid Slack thread_ts '{ts}' in target '{target}': expected a message \
+                 timestamp such as 1712345678.000100"
+            );
+        }
+    }
+    Ok((channel, thread_ts))
+}
+
+/// Build the `channel[:thread_ts]` target accepted by [`parse_delivery_target`].
+pub(crate) fn delivery_target(channel: &str, thread_ts: Option<&str>) -> String {
+    match thread_ts {
+        Some(ts) => format!("{channel}:{ts}"),
+        None => channel.to_string(),
+    }
+}
+
+/// Slack message timestamps are `<seconds>.<micros>`, both all digits.
+fn is_message_ts(ts: &str) -> bool {
+    let all_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    ts.split_once('.')
+        .is_some_and(|(secs, micros)| all_digits(secs) && all_digits(micros))
 }
 
 impl SlackChannel {
@@ -16,13 +76,116 @@ impl SlackChannel {
             bot_token,
             channel_id,
             allowed_users,
+            progress_messages: false,
+            user_names: Mutex::new(HashMap::new()),
+            thread_parents: Mutex::new(HashMap::new()),
+            api_base: SLACK_API_BASE.to_string(),
+            outbound: OutboundLimiter::default(),
+        }
+    }
+
+    /// Override the Web API base URL. Useful for testing.
+    pub fn with_api_base(mut self, api_base: String) -> Self {
+        self.api_base = api_base.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Bound concurrent sends (`runtime.adapter_max_inflight`) and set the
+    /// retry jitter (`runtime.adapter_retry_jitter_ms`).
+    pub fn with_outbound_limits(mut self, max_inflight: usize, retry_jitter_ms: u64) -> Self {
+        self.outbound = OutboundLimiter::new(max_inflight, retry_jitter_ms);
+        self
+    }
+
+    /// Post a transient tool status message during long turns.
+    pub fn with_progress_messages(mut self, enabled: bool) -> Self {
+        self.progress_messages = enabled;
+        self
+    }
+
+    /// Call a `chat.*` Web API method and return the parsed response,
+    /// failing on HTTP errors and on `"ok": false`.
+    async fn chat_api(
+        &self,
+        method: &str,
+        body: &serde_json::Value,
+    ) -> anyhow::Result<serde_json::Value> {
+        let resp = self
+            .http_client()
+            .post(format!("{}/{method}", self.api_base))
+            .bearer_auth(&self.bot_token)
+            .json(body)
+            .send()
+            .await?;
+        let status = resp.status();
+        let text = resp.text().await.unwrap_or_default();
+        if !status.is_success() {
+            anyhow::bail!("Slack {method} failed ({status}): {text}");
+        }
+        let parsed: serde_json::Value = serde_json::from_str(&text).unwrap_or_default();
+        if parsed.get("ok") == Some(&serde_json::Value::Bool(false)) {
+            let err = parsed
+                .get("error")
+                .and_then(|e| e.as_str())
+                .unwrap_or("unknown");
+            anyhow::bail!("Slack {method} failed: {err}");
         }
+        Ok(parsed)
     }
 
     fn http_client(&self) -> reqwest::Client {
         crate::config::build_runtime_proxy_client("channel.slack")
     }
 
+    /// One `chat.postMessage` attempt, returning the `ts` of the posted
+    /// message. Slack reports most app-level errors with a 200 and
+    /// `"ok": false`; those are not retried.
+    async fn post_message_once(
+        &self,
+        channel: &str,
+        text: &str,
+        thread_ts: Option<&str>,
+    ) -> Result<Option<String>, SendError> {
+        let mut body = serde_json::json!({ "channel": channel, "text": text });
+        if let Some(ts) = thread_ts {
+            body["thread_ts"] = serde_json::json!(ts);
+        }
+        let resp = self
+            .http_client()
+            .post(format!("{}/chat.postMessage", self.api_base))
+            .bearer_auth(&self.bot_token)
+            .json(&body)
+            .send()
+            .await?;
+        let status = resp.status();
+        let retry_after = retry_after_header(resp.headers());
+        let body = resp
+            .text()
+            .await
+            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+        if !status.is_success() {
+            return Err(SendError::from_status(
+                status,
+                retry_after,
+                anyhow::anyhow!("Slack chat.postMessage failed ({status}): {body}"),
+            ));
+        }
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+        if parsed.get("ok") == Some(&serde_json::Value::Bool(false)) {
+            let err = parsed
+                .get("error")
+                .and_then(|e| e.as_str())
+                .unwrap_or("unknown");
+            return Err(SendError::fatal(anyhow::anyhow!(
+                "Slack chat.postMessage failed: {err}"
+            )));
+        }
+        Ok(parsed
+            .get("ts")
+            .and_then(|ts| ts.as_str())
+            .map(String::from))
+    }
+
     /// Check if a Slack user ID is in the allowlist.
     /// Empty list means deny everyone until explicitly configured.
     /// `"*"` means allow everyone.
@@ -34,7 +197,7 @@ impl SlackChannel {
     async fn get_bot_user_id(&self) -> Option<String> {
         let resp: serde_json::Value = self
             .http_client()
-            .get("https://slack.com/api/auth.test")
+            .get(format!("{}/auth.test", self.api_base))
             .bearer_auth(&self.bot_token)
             .send()
             .await
@@ -48,6 +211,132 @@ impl SlackChannel {
             .map(String::from)
     }
 
+    /// Display name for a user id via `users.info`, cached per process.
+    /// Lookup failures (e.g. a token without `users:read`) are cached too.
+    async fn user_display_name(&self, user_id: &str) -> Option<String> {
+        if let Some(cached) = self.user_names.lock().get(user_id) {
+            return cached.clone();
+        }
+
+        let name = match self
+            .http_client()
+            .get(format!("{}/users.info", self.api_base))
+            .bearer_auth(&self.bot_token)
+            .query(&[("user", user_id)])
+            .send()
+            .await
+        {
+            Ok(resp) => resp
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|info| Self::display_name_from_user_info(&info)),
+            Err(e) => {
+                tracing::debug!("Slack users.info failed for {user_id}: {e}");
+                None
+            }
+        };
+
+        let mut cache = self.user_names.lock();
+        if cache.len() >= USER_NAME_CACHE_CAP {
+            cache.clear();
+        }
+        cache.insert(user_id.to_string(), name.clone());
+        name
+    }
+
+    /// Text of the root message of thread `thread_ts`, cached per process.
+    async fn thread_parent_text(&self, channel_id: &str, thread_ts: &str) -> Option<String> {
+        self.cached_thread_parent(channel_id, thread_ts, || {
+            self.fetch_thread_parent(channel_id, thread_ts)
+        })
+        .await
+    }
+
+    async fn cached_thread_parent<F, Fut>(
+        &self,
+        channel_id: &str,
+        thread_ts: &str,
+        fetch: F,
+    ) -> Option<String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Option<String>>,
+    {
+        let key = delivery_target(channel_id, Some(thread_ts));
+        if let Some(cached) = self.thread_parents.lock().get(&key) {
+            return cached.clone();
+        }
+
+        let text = fetch().await;
+
+        let mut cache = self.thread_parents.lock();
+        if cache.len() >= THREAD_PARENT_CACHE_CAP {
+            cache.clear();
+        }
+        cache.insert(key, text.clone());
+        text
+    }
+
+    /// Root message of a thread via `conversations.replies`.
+    async fn fetch_thread_parent(&self, channel_id: &str, thread_ts: &str) -> Option<String> {
+        let resp = match self
+            .http_client()
+            .get(format!("{}/conversations.replies", self.api_base))
+            .bearer_auth(&self.bot_token)
+            .query(&[("channel", channel_id), ("ts", thread_ts), ("limit", "1")])
+            .send()
+            .await
+        {
+            Ok(resp) => resp,
+            Err(e) => {
+                tracing::debug!("Slack conversations.replies failed for {channel_id}: {e}");
+                return None;
+            }
+        };
+        let data: serde_json::Value = resp.json().await.ok()?;
+        if data.get("ok") == Some(&serde_json::Value::Bool(false)) {
+            let err = data
+                .get("error")
+                .and_then(|e| e.as_str())
+                .unwrap_or("unknown");
+            tracing::debug!("Slack conversations.replies error for {channel_id}: {err}");
+            return None;
+        }
+        Self::thread_parent_from_replies(&data, thread_ts)
+    }
+
+    /// The message whose `ts` is the thread root; Slack lists it first.
+    fn thread_parent_from_replies(data: &serde_json::Value, thread_ts: &str) -> Option<String> {
+        data.get("messages")?
+            .as_array()?
+            .iter()
+            .find(|m| m.get("ts").and_then(|t| t.as_str()) == Some(thread_ts))?
+            .get("text")?
+            .as_str()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string)
+    }
+
+    /// Prefer the profile display name, then the real name, then the handle.
+    fn display_name_from_user_info(info: &serde_json::Value) -> Option<String> {
+        let user = info.get("user")?;
+        let profile = user.get("profile");
+        [
+            profile.and_then(|p| p.get("display_name")),
+            profile.and_then(|p| p.get("real_name")),
+            user.get("real_name"),
+            user.get("name"),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(serde_json::Value::as_str)
+        .map(str::trim)
+        .find(|name| !name.is_empty())
+        .map(str::to_string)
+    }
+
     /// Resolve the thread identifier for inbound Slack messages.
     /// Replies carry `thread_ts` (root thread id); top-level messages only have `ts`.
     fn inbound_thread_ts(msg: &serde_json::Value, ts: &str) -> Option<String> {
@@ -114,7 +403,7 @@ impl SlackChannel {
 
             let resp = self
                 .http_client()
-                .get("https://slack.com/api/conversations.list")
+                .get(format!("{}/conversations.list", self.api_base))
                 .bearer_auth(&self.bot_token)
                 .query(&query_params)
                 .send()
@@ -185,44 +474,94 @@ impl Channel for SlackChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        let mut body = serde_json::json!({
-            "channel": message.recipient,
-            "text": message.content
-        });
+        self.send_with_id(message).await.map(drop)
+    }
 
-        if let Some(ref ts) = message.thread_ts {
-            body["thread_ts"] = serde_json::json!(ts);
+    /// Returns the `ts` of the last chunk posted.
+    async fn send_with_id(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        let recipient = require_recipient("slack", message)?;
+        let _permit = self.outbound.acquire_for(recipient).await;
+        let text = SlackFormatter.format(&message.content);
+        let chunks = split_message(&text, SLACK_MAX_MESSAGE_LENGTH);
+        let last_ts = Mutex::new(None);
+        send_chunks_with_retry(
+            "Slack chat.postMessage",
+            chunks.len(),
+            Duration::ZERO,
+            self.outbound.retry_jitter_ms(),
+            |index| {
+                let (chunks, last_ts) = (&chunks, &last_ts);
+                async move {
+                    let ts = self
+                        .post_message_once(recipient, &chunks[index], message.thread_ts.as_deref())
+                        .await?;
+                    *last_ts.lock() = ts;
+                    Ok(())
+                }
+            },
+        )
+        .await?;
+        Ok(last_ts.into_inner())
+    }
+
+    fn supports_message_edits(&self) -> bool {
+        true
+    }
+
+    async fn edit_message(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let text = SlackFormatter.format(text);
+        if text.chars().count() > SLACK_MAX_MESSAGE_LENGTH {
+            anyhow::bail!("Slack cannot edit message {message_id} into a split reply");
         }
+        let _permit = self.outbound.acquire().await;
+        self.chat_api(
+            "chat.update",
+            &serde_json::json!({ "channel": recipient, "ts": message_id, "text": text }),
+        )
+        .await
+        .map(|_| ())
+    }
+
+    fn progress_messages_enabled(&self) -> bool {
+        self.progress_messages
+    }
 
+    async fn send_status(&self, recipient: &str, text: &str) -> anyhow::Result<Option<String>> {
         let resp = self
-            .http_client()
-            .post("https://slack.com/api/chat.postMessage")
-            .bearer_auth(&self.bot_token)
-            .json(&body)
-            .send()
+            .chat_api(
+                "chat.postMessage",
+                &serde_json::json!({ "channel": recipient, "text": text }),
+            )
             .await?;
+        Ok(resp.get("ts").and_then(|ts| ts.as_str()).map(String::from))
+    }
 
-        let status = resp.status();
-        let body = resp
-            .text()
-            .await
-            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
-
-        if !status.is_success() {
-            anyhow::bail!("Slack chat.postMessage failed ({status}): {body}");
-        }
-
-        // Slack returns 200 for most app-level errors; check JSON "ok" field
-        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
-        if parsed.get("ok") == Some(&serde_json::Value::Bool(false)) {
-            let err = parsed
-                .get("error")
-                .and_then(|e| e.as_str())
-                .unwrap_or("unknown");
-            anyhow::bail!("Slack chat.postMessage failed: {err}");
-        }
+    async fn update_status(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.chat_api(
+            "chat.update",
+            &serde_json::json!({ "channel": recipient, "ts": message_id, "text": text }),
+        )
+        .await
+        .map(|_| ())
+    }
 
-        Ok(())
+    async fn delete_status(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
+        self.chat_api(
+            "chat.delete",
+            &serde_json::json!({ "channel": recipient, "ts": message_id }),
+        )
+        .await
+        .map(|_| ())
     }
 
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
@@ -294,7 +633,7 @@ impl Channel for SlackChannel {
 
                 let resp = match self
                     .http_client()
-                    .get("https://slack.com/api/conversations.history")
+                    .get(format!("{}/conversations.history", self.api_base))
                     .bearer_auth(&self.bot_token)
                     .query(&params)
                     .send()
@@ -357,6 +696,19 @@ impl Channel for SlackChannel {
                         }
 
                         last_ts_by_channel.insert(channel_id.clone(), ts.to_string());
+                        let sender_name = self.user_display_name(user).await;
+                        let mut metadata = HashMap::new();
+                        if let Some(parent_ts) = msg
+                            .get("thread_ts")
+                            .and_then(|t| t.as_str())
+                            .filter(|parent_ts| *parent_ts != ts)
+                        {
+                            if let Some(parent) =
+                                self.thread_parent_text(&channel_id, parent_ts).await
+                            {
+                                metadata.insert(THREAD_PARENT_METADATA_KEY.to_string(), parent);
+                            }
+                        }
 
                         let channel_msg = ChannelMessage {
                             id: format!("slack_{channel_id}_{ts}"),
@@ -369,6 +721,8 @@ impl Channel for SlackChannel {
                                 .unwrap_or_default()
                                 .as_secs(),
                             thread_ts: Self::inbound_thread_ts(msg, ts),
+                            sender_name,
+                            metadata,
                         };
 
                         if tx.send(channel_msg).await.is_err() {
@@ -382,7 +736,7 @@ impl Channel for SlackChannel {
 
     async fn health_check(&self) -> bool {
         self.http_client()
-            .get("https://slack.com/api/auth.test")
+            .get(format!("{}/auth.test", self.api_base))
             .bearer_auth(&self.bot_token)
             .send()
             .await
@@ -394,6 +748,8 @@ impl Channel for SlackChannel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::channels::conformance::check_outbound_contract;
+    use std::sync::Arc;
 
     #[test]
     fn slack_channel_name() {
@@ -401,6 +757,77 @@ mod tests {
         assert_eq!(ch.name(), "slack");
     }
 
+    #[tokio::test]
+    async fn outbound_path_conforms_to_adapter_contract() {
+        check_outbound_contract(
+            |api_base, max_inflight| {
+                Arc::new(
+                    SlackChannel::new("xoxb-fake".into(), None, vec![])
+                        .with_api_base(api_base.into())
+                        .with_outbound_limits(max_inflight, 0),
+                )
+            },
+            ["C12345", "C23456", "C34567"],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn sent_ts_is_returned_and_used_for_edits() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/chat.postMessage"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "ok": true, "ts": "1700000000.000100" })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/chat.update"))
+            .and(body_partial_json(serde_json::json!({
+                "channel": "C12345",
+                "ts": "1700000000.000100",
+                "text": "Meeting is at 3pm",
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "ok": true })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/chat.update"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(
+                    serde_json::json!({ "ok": false, "error": "message_not_found" }),
+                ),
+            )
+            .mount(&server)
+            .await;
+
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec![]).with_api_base(server.uri());
+        assert!(ch.supports_message_edits());
+        let ts = ch
+            .send_with_id(&SendMessage::new("Meeting is at 2pm", "C12345"))
+            .await
+            .unwrap()
+            .expect("Slack returns the ts of the posted message");
+        assert_eq!(ts, "1700000000.000100");
+
+        ch.edit_message("C12345", &ts, "Meeting is at 3pm")
+            .await
+            .unwrap();
+        let err = ch
+            .edit_message("C12345", "1600000000.000100", "too late")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("message_not_found"));
+    }
+
     #[test]
     fn slack_channel_with_channel_id() {
         let ch = SlackChannel::new("xoxb-fake".into(), Some("C12345".into()), vec![]);
@@ -561,6 +988,38 @@ mod tests {
         assert_eq!(thread_ts, None);
     }
 
+    #[test]
+    fn display_name_from_user_info_prefers_profile_display_name() {
+        let info = serde_json::json!({
+            "ok": true,
+            "user": {
+                "name": "alice.w",
+                "real_name": "Alice Walker",
+                "profile": { "display_name": "Alice", "real_name": "Alice Walker" }
+            }
+        });
+        assert_eq!(
+            SlackChannel::display_name_from_user_info(&info).as_deref(),
+            Some("Alice")
+        );
+
+        let blank_display = serde_json::json!({
+            "user": { "name": "bob", "profile": { "display_name": " ", "real_name": "Bob Stone" } }
+        });
+        assert_eq!(
+            SlackChannel::display_name_from_user_info(&blank_display).as_deref(),
+            Some("Bob Stone")
+        );
+
+        let handle_only = serde_json::json!({ "user": { "name": "carol" } });
+        assert_eq!(
+            SlackChannel::display_name_from_user_info(&handle_only).as_deref(),
+            Some("carol")
+        );
+        let error = serde_json::json!({ "ok": false, "error": "missing_scope" });
+        assert_eq!(SlackChannel::display_name_from_user_info(&error), None);
+    }
+
     #[test]
     fn ensure_poll_cursor_bootstraps_new_channel() {
         let mut cursors = HashMap::new();
@@ -582,4 +1041,77 @@ mod tests {
             Some("1700000000.000001")
         );
     }
+
+    #[test]
+    fn delivery_target_round_trips_and_rejects_malformed_thread_ts() {
+        let target = delivery_target("C123", Some("1712345678.000100"));
+        assert_eq!(target, "C123:1712345678.000100");
+        assert_eq!(
+            parse_delivery_target(&target).unwrap(),
+            ("C123", Some("1712345678.000100"))
+        );
+        assert_eq!(delivery_target("C123", None), "C123");
+        assert_eq!(parse_delivery_target("C123").unwrap(), ("C123", None));
+
+        for bad in [
+            "",
+            ":1712345678.000100",
+            "C123:",
+            "C123:1712345678",
+            "C123:abc.def",
+            "C123:1712345678.000100:x",
+            "C 123",
+        ] {
+            assert!(
+                parse_delivery_target(bad).is_err(),
+                "{bad:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn thread_parent_from_replies_picks_the_root_message() {
+        let data = serde_json::json!({
+            "ok": true,
+            "messages": [
+                {"ts": "123.001", "text": " Deploy failed on main "},
+                {"ts": "123.002", "thread_ts": "123.001", "text": "looking"}
+            ]
+        });
+        assert_eq!(
+            SlackChannel::thread_parent_from_replies(&data, "123.001").as_deref(),
+            Some("Deploy failed on main")
+        );
+        assert!(SlackChannel::thread_parent_from_replies(&data, "999.000").is_none());
+    }
+
+    #[tokio::test]
+    async fn thread_parent_is_fetched_once_per_thread() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec![]);
+        let fetches = std::sync::atomic::AtomicUsize::new(0);
+        let fetch = |text: Option<&str>| {
+            fetches.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let text = text.map(str::to_string);
+            async move { text }
+        };
+
+        for _ in 0..2 {
+            let parent = ch
+                .cached_thread_parent("C1", "123.001", || fetch(Some("root text")))
+                .await;
+            assert_eq!(parent.as_deref(), Some("root text"));
+        }
+        for _ in 0..2 {
+            let parent = ch
+                .cached_thread_parent("C1", "123.002", || fetch(None))
+                .await;
+            assert!(parent.is_none());
+        }
+        let other_channel = ch
+            .cached_thread_parent("C2", "123.001", || fetch(Some("other")))
+            .await;
+        assert_eq!(other_channel.as_deref(), Some("other"));
+
+        assert_eq!(fetches.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
 }
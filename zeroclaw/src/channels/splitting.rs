@@ -0,0 +1,223 @@
+//! Splitting of long outbound messages into platform-sized chunks.
+//!
+//! Every platform caps the length of a single text message (Discord 2000,
+//! Telegram 4096, WhatsApp 4096, Slack 40 000 characters) and rejects longer
+//! payloads outright. Adapters run outbound text through [`split_message`]
+//! with their own limit and send the chunks in order. Limits are counted in
+//! characters, which is how these APIs measure them.
+
+const FENCE: &str = "```";
+/// Worst-case length of the fence appended to close a block: `"\n```"`.
+const CLOSE_FENCE_LEN: usize = 4;
+
+/// Split `content` into chunks of at most `max_len` characters.
+///
+/// Breaks after a newline in the second half of the window when possible,
+/// then after a space, and only then mid-word (always on a char boundary).
+/// A chunk that would end inside a fenced code block is closed with a fence
+/// and the block is re-opened, info string included, at the start of the
+/// next chunk, so every chunk renders on its own.
+pub fn split_message(content: &str, max_len: usize) -> Vec<String> {
+    split_pieces(content, max_len)
+        .iter()
+        .map(Piece::render)
+        .collect()
+}
+
+/// A slice of the original text plus the fence lines added around it.
+struct Piece<'a> {
+    reopen: Option<&'a str>,
+    body: &'a str,
+    close: bool,
+}
+
+impl Piece<'_> {
+    fn render(&self) -> String {
+        let mut out = String::with_capacity(self.body.len() + 16);
+        if let Some(fence) = self.reopen {
+            out.push_str(fence);
+            out.push('\n');
+        }
+        out.push_str(self.body);
+        if self.close {
+            if !self.body.ends_with('\n') {
+                out.push('\n');
+            }
+            out.push_str(FENCE);
+        }
+        out
+    }
+}
+
+fn split_pieces(content: &str, max_len: usize) -> Vec<Piece<'_>> {
+    let max_len = max_len.max(1);
+    if content.chars().count() <= max_len {
+        return vec![Piece {
+            reopen: None,
+            body: content,
+            close: false,
+        }];
+    }
+
+    let mut pieces = Vec::new();
+    let mut rest = content;
+    let mut open: Option<&str> = None;
+
+    while !rest.is_empty() {
+        // Skip re-opening when the fence line would leave no room for text.
+        let reopen = open.filter(|fence| fence.chars().count() + 1 + CLOSE_FENCE_LEN < max_len);
+        let budget = max_len - reopen.map_or(0, |fence| fence.chars().count() + 1);
+
+        if rest.chars().count() <= budget {
+            pieces.push(Piece {
+                reopen,
+                body: rest,
+                close: false,
+            });
+            break;
+        }
+
+        let mut end = break_point(rest, budget);
+        let mut state = fence_state(open, &rest[..end]);
+        if state.is_some() && budget > CLOSE_FENCE_LEN {
+            end = break_point(rest, budget - CLOSE_FENCE_LEN);
+            state = fence_state(open, &rest[..end]);
+        }
+        let body = &rest[..end];
+        let close = state.is_some() && body.chars().count() + CLOSE_FENCE_LEN <= budget;
+
+        pieces.push(Piece {
+            reopen,
+            body,
+            close,
+        });
+        open = state;
+        rest = &rest[end..];
+    }
+
+    pieces
+}
+
+/// Byte offset at which to end a chunk of at most `budget` characters.
+/// Always > 0 for non-empty `text` and `budget >= 1`.
+fn break_point(text: &str, budget: usize) -> usize {
+    let hard_split = text
+        .char_indices()
+        .nth(budget)
+        .map_or(text.len(), |(idx, _)| idx);
+    if hard_split == text.len() {
+        return hard_split;
+    }
+
+    let search_area = &text[..hard_split];
+    if let Some(pos) = search_area.rfind('\n') {
+        // A newline too close to the start would waste most of the chunk.
+        if search_area[..pos].chars().count() >= budget / 2 {
+            return pos + 1;
+        }
+    }
+    search_area.rfind(' ').map_or(hard_split, |pos| pos + 1)
+}
+
+/// The fence line of the code block still open after `text`, given the
+/// block open before it.
+fn fence_state<'a>(mut open: Option<&'a str>, text: &'a str) -> Option<&'a str> {
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with(FENCE) {
+            open = if open.is_some() { None } else { Some(line) };
+        }
+    }
+    open
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic xorshift so failures are reproducible.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            usize::try_from(self.next() % n as u64).unwrap()
+        }
+    }
+
+    fn random_text(rng: &mut Rng, tokens: &[&str]) -> String {
+        let len = rng.below(400);
+        (0..len).map(|_| tokens[rng.below(tokens.len())]).collect()
+    }
+
+    #[test]
+    fn short_message_is_a_single_chunk() {
+        assert_eq!(split_message("", 10), vec![""]);
+        assert_eq!(split_message("hello", 5), vec!["hello"]);
+    }
+
+    #[test]
+    fn prefers_newline_then_space_then_hard_split() {
+        assert_eq!(
+            split_message("aaaaaa\nbbb ccc", 10),
+            vec!["aaaaaa\n", "bbb ccc"]
+        );
+        assert_eq!(split_message("aa bbbbbbbbb", 10), vec!["aa ", "bbbbbbbbb"]);
+        assert_eq!(split_message("🦀🦀🦀🦀🦀", 2), vec!["🦀🦀", "🦀🦀", "🦀"]);
+    }
+
+    #[test]
+    fn code_fence_is_closed_and_reopened_across_chunks() {
+        let content = format!("Intro\n```rust\n{}```\nAfter", "let x = 1;\n".repeat(8));
+        let chunks = split_message(&content, 60);
+        assert!(chunks.len() > 1);
+        assert!(chunks[0].ends_with("\n```"), "{:?}", chunks[0]);
+        assert!(chunks[1].starts_with("```rust\n"), "{:?}", chunks[1]);
+        for chunk in &chunks {
+            assert!(chunk.chars().count() <= 60, "{chunk:?}");
+            assert!(fence_state(None, chunk).is_none(), "{chunk:?}");
+        }
+    }
+
+    #[test]
+    fn random_plain_text_round_trips_within_limit() {
+        let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+        let tokens = ["a", "bb", "word ", " ", "\n", "é", "🦀", "line\n"];
+        for _ in 0..500 {
+            let content = random_text(&mut rng, &tokens);
+            let max_len = 1 + rng.below(120);
+            let chunks = split_message(&content, max_len);
+            assert_eq!(chunks.concat(), content, "max_len={max_len}");
+            for chunk in &chunks {
+                assert!(chunk.chars().count() <= max_len, "max_len={max_len}");
+            }
+        }
+    }
+
+    #[test]
+    fn random_fenced_text_keeps_content_and_balances_fences() {
+        let mut rng = Rng(0xD1B5_4A32_D192_ED03);
+        let tokens = ["a", "word ", "\n", "🦀", "```rust\n", "```\n", "x = 1;\n"];
+        for _ in 0..500 {
+            let content = random_text(&mut rng, &tokens);
+            let max_len = 30 + rng.below(150);
+            let pieces = split_pieces(&content, max_len);
+            let bodies: String = pieces.iter().map(|piece| piece.body).collect();
+            assert_eq!(bodies, content, "max_len={max_len}");
+
+            let chunks = split_message(&content, max_len);
+            for chunk in &chunks {
+                assert!(chunk.chars().count() <= max_len, "max_len={max_len}");
+            }
+            for chunk in &chunks[..chunks.len() - 1] {
+                assert!(fence_state(None, chunk).is_none(), "{chunk:?}");
+            }
+        }
+    }
+}
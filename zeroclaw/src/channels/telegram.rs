@@ -1,3 +1,6 @@
+use super::formatting::{ChannelFormatter, TelegramFormatter};
+use super::send_retry::{send_chunks_with_retry, OutboundLimiter, SendError};
+use super::splitting::split_message;
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use crate::config::{Config, StreamMode};
 use crate::security::pairing::PairingGuard;
@@ -6,6 +9,7 @@ use async_trait::async_trait;
 use directories::UserDirs;
 use parking_lot::Mutex;
 use reqwest::multipart::{Form, Part};
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
@@ -37,59 +41,24 @@ enum IncomingAttachmentKind {
 const TELEGRAM_BIND_COMMAND: &str = "/bind";
 
 /// Split a message into chunks that respect Telegram's 4096 character limit.
-/// Tries to split at word boundaries when possible, and handles continuation.
 /// The effective per-chunk limit is reduced to leave room for continuation markers.
 fn split_message_for_telegram(message: &str) -> Vec<String> {
     if message.chars().count() <= TELEGRAM_MAX_MESSAGE_LENGTH {
         return vec![message.to_string()];
     }
+    split_message(
+        message,
+        TELEGRAM_MAX_MESSAGE_LENGTH - TELEGRAM_CONTINUATION_OVERHEAD,
+    )
+}
 
-    let mut chunks = Vec::new();
-    let mut remaining = message;
-    let chunk_limit = TELEGRAM_MAX_MESSAGE_LENGTH - TELEGRAM_CONTINUATION_OVERHEAD;
-
-    while !remaining.is_empty() {
-        // If the remainder fits within the full limit, take it all (last chunk
-        // or single chunk — continuation overhead is at most 14 chars).
-        if remaining.chars().count() <= TELEGRAM_MAX_MESSAGE_LENGTH {
-            chunks.push(remaining.to_string());
-            break;
-        }
-
-        // Find the byte offset for the Nth character boundary.
-        let hard_split = remaining
-            .char_indices()
-            .nth(chunk_limit)
-            .map_or(remaining.len(), |(idx, _)| idx);
-
-        let chunk_end = if hard_split == remaining.len() {
-            hard_split
-        } else {
-            // Try to find a good break point (newline, then space)
-            let search_area = &remaining[..hard_split];
-
-            // Prefer splitting at newline
-            if let Some(pos) = search_area.rfind('\n') {
-                // Don't split if the newline is too close to the start
-                if search_area[..pos].chars().count() >= chunk_limit / 2 {
-                    pos + 1
-                } else {
-                    // Try space as fallback
-                    search_area.rfind(' ').unwrap_or(hard_split) + 1
-                }
-            } else if let Some(pos) = search_area.rfind(' ') {
-                pos + 1
-            } else {
-                // Hard split at character boundary
-                hard_split
-            }
-        };
-
-        chunks.push(remaining[..chunk_end].to_string());
-        remaining = &remaining[chunk_end..];
-    }
-
-    chunks
+/// `parameters.retry_after` (seconds) from a Telegram error response.
+fn telegram_retry_after(body: &str) -> Option<Duration> {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()?
+        .pointer("/parameters/retry_after")?
+        .as_u64()
+        .map(Duration::from_secs)
 }
 
 fn pick_uniform_index(len: usize) -> usize {
@@ -304,6 +273,8 @@ pub struct TelegramChannel {
     draft_update_interval_ms: u64,
     last_draft_edit: Mutex<std::collections::HashMap<String, std::time::Instant>>,
     mention_only: bool,
+    /// With `mention_only`, group messages starting with this are processed too.
+    command_prefix: Option<String>,
     bot_username: Mutex<Option<String>>,
     /// Base URL for the Telegram Bot API. Defaults to `https://api.telegram.org`.
     /// Override for local Bot API servers or testing.
@@ -311,6 +282,9 @@ pub struct TelegramChannel {
     transcription: Option<crate::config::TranscriptionConfig>,
     voice_transcriptions: Mutex<std::collections::HashMap<String, String>>,
     workspace_dir: Option<std::path::PathBuf>,
+    /// Post an editable "⏳ running <tool>…" message while a turn runs tools.
+    progress_messages: bool,
+    outbound: OutboundLimiter,
 }
 
 impl TelegramChannel {
@@ -337,14 +311,30 @@ impl TelegramChannel {
             last_draft_edit: Mutex::new(std::collections::HashMap::new()),
             typing_handle: Mutex::new(None),
             mention_only,
+            command_prefix: None,
             bot_username: Mutex::new(None),
             api_base: "https://api.telegram.org".to_string(),
             transcription: None,
             voice_transcriptions: Mutex::new(std::collections::HashMap::new()),
             workspace_dir: None,
+            progress_messages: false,
+            outbound: OutboundLimiter::default(),
         }
     }
 
+    /// Bound concurrent sends (`runtime.adapter_max_inflight`) and set the
+    /// retry jitter (`runtime.adapter_retry_jitter_ms`).
+    pub fn with_outbound_limits(mut self, max_inflight: usize, retry_jitter_ms: u64) -> Self {
+        self.outbound = OutboundLimiter::new(max_inflight, retry_jitter_ms);
+        self
+    }
+
+    /// Post a transient tool status message during long turns.
+    pub fn with_progress_messages(mut self, enabled: bool) -> Self {
+        self.progress_messages = enabled;
+        self
+    }
+
     /// Configure workspace directory for saving downloaded attachments.
     pub fn with_workspace_dir(mut self, dir: std::path::PathBuf) -> Self {
         self.workspace_dir = Some(dir);
@@ -362,6 +352,12 @@ impl TelegramChannel {
         self
     }
 
+    /// Also accept `mention_only` group messages that start with `prefix`.
+    pub fn with_command_prefix(mut self, prefix: Option<String>) -> Self {
+        self.command_prefix = prefix.filter(|p| !p.trim().is_empty());
+        self
+    }
+
     /// Override the Telegram Bot API base URL.
     /// Useful for local Bot API servers or testing.
     pub fn with_api_base(mut self, api_base: String) -> Self {
@@ -519,6 +515,51 @@ impl TelegramChannel {
         format!("{}/bot{}/{method}", self.api_base, self.bot_token)
     }
 
+    /// Replace the text of message `id` with `text`, formatted as HTML and
+    /// retried as plain text when Telegram rejects the markup. Returns
+    /// whether Telegram accepted either edit.
+    async fn edit_message_text(&self, chat_id: &str, id: i64, text: &str) -> anyhow::Result<bool> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+            "message_id": id,
+            "text": Self::markdown_to_telegram_html(text),
+            "parse_mode": "HTML",
+        });
+
+        let resp = self
+            .client
+            .post(self.api_url("editMessageText"))
+            .json(&body)
+            .send()
+            .await?;
+
+        if resp.status().is_success() {
+            return Ok(true);
+        }
+
+        // Markdown failed — retry without parse_mode
+        let plain_body = serde_json::json!({
+            "chat_id": chat_id,
+            "message_id": id,
+            "text": text,
+        });
+
+        let resp = self
+            .client
+            .post(self.api_url("editMessageText"))
+            .json(&plain_body)
+            .send()
+            .await?;
+
+        if resp.status().is_success() {
+            return Ok(true);
+        }
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        tracing::debug!("Telegram editMessageText failed ({status}): {body}");
+        Ok(false)
+    }
+
     async fn fetch_bot_username(&self) -> anyhow::Result<String> {
         let resp = self.http_client().get(self.api_url("getMe")).send().await?;
 
@@ -628,6 +669,51 @@ impl TelegramChannel {
         (!normalized.is_empty()).then_some(normalized)
     }
 
+    /// Content to forward for a gated group message, or `None` when it isn't
+    /// addressed to the bot. Addressed means a leading `command_prefix`, an
+    /// @-mention, or a reply to one of the bot's messages; the prefix and
+    /// mentions are stripped. The result may be empty (e.g. a bare reply).
+    fn addressed_group_content(
+        text: &str,
+        bot_username: &str,
+        replied_to_bot: bool,
+        command_prefix: Option<&str>,
+    ) -> Option<String> {
+        let prefixed = command_prefix.and_then(|prefix| text.trim_start().strip_prefix(prefix));
+        let body = match prefixed {
+            Some(rest) => rest,
+            None if replied_to_bot || Self::contains_bot_mention(text, bot_username) => text,
+            None => return None,
+        };
+        Some(Self::normalize_incoming_content(body, bot_username).unwrap_or_default())
+    }
+
+    fn is_reply_to_bot(message: &serde_json::Value, bot_username: &str) -> bool {
+        message
+            .get("reply_to_message")
+            .and_then(|reply| reply.get("from"))
+            .and_then(|from| from.get("username"))
+            .and_then(serde_json::Value::as_str)
+            .is_some_and(|username| username.eq_ignore_ascii_case(bot_username))
+    }
+
+    /// Apply `mention_only` gating. Direct messages and ungated channels pass
+    /// `text` through unchanged; group messages go through
+    /// [`Self::addressed_group_content`]. Until the bot username is known,
+    /// gated group messages are dropped.
+    fn gate_message(&self, message: &serde_json::Value, text: &str) -> Option<String> {
+        if !self.mention_only || !Self::is_group_message(message) {
+            return Some(text.to_string());
+        }
+        let bot_username = self.bot_username.lock().clone()?;
+        Self::addressed_group_content(
+            text,
+            &bot_username,
+            Self::is_reply_to_bot(message, &bot_username),
+            self.command_prefix.as_deref(),
+        )
+    }
+
     fn is_group_message(message: &serde_json::Value) -> bool {
         message
             .get("chat")
@@ -905,26 +991,17 @@ Allowlist Telegram username (without '@') or numeric user ID.",
     /// Attempt to parse a Telegram update as a document/photo attachment.
     ///
     /// Downloads the file to `{workspace_dir}/telegram_files/` and returns a
-    /// `ChannelMessage` with the local file path. Returns `None` if the message
-    /// is not an attachment, workspace_dir is not configured, or the file exceeds
-    /// size limits.
+    /// `ChannelMessage` with the local file path. Documents that cannot be
+    /// downloaded (too large, no workspace_dir, download failure) still produce
+    /// a message with a placeholder entry; their `telegram_file_id` metadata
+    /// lets a later step fetch them. Returns `None` if the message is not an
+    /// attachment or is a photo that could not be downloaded.
     async fn try_parse_attachment_message(
         &self,
         update: &serde_json::Value,
     ) -> Option<ChannelMessage> {
         let message = update.get("message")?;
-        let attachment = Self::parse_attachment_metadata(message)?;
-
-        // Check file size limit
-        if let Some(size) = attachment.file_size {
-            if size > TELEGRAM_MAX_FILE_DOWNLOAD_BYTES {
-                tracing::info!(
-                    "Skipping attachment: file size {size} bytes exceeds {} MB limit",
-                    TELEGRAM_MAX_FILE_DOWNLOAD_BYTES / (1024 * 1024)
-                );
-                return None;
-            }
-        }
+        let mut attachment = Self::parse_attachment_metadata(message)?;
 
         let (username, sender_id, sender_identity) = Self::extract_sender_info(message);
 
@@ -937,6 +1014,10 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             return None;
         }
 
+        // Gate on the caption before downloading anything.
+        let caption = attachment.caption.take().unwrap_or_default();
+        attachment.caption = Some(self.gate_message(message, &caption)?);
+
         let chat_id = message
             .get("chat")
             .and_then(|chat| chat.get("id"))
@@ -959,56 +1040,29 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             chat_id.clone()
         };
 
-        // Ensure workspace directory is configured
-        let workspace = self.workspace_dir.as_ref().or_else(|| {
-            tracing::warn!("Cannot save attachment: workspace_dir not configured");
-            None
-        })?;
-
-        let save_dir = workspace.join("telegram_files");
-        if let Err(e) = tokio::fs::create_dir_all(&save_dir).await {
-            tracing::warn!("Failed to create telegram_files directory: {e}");
-            return None;
-        }
-
-        // Download file from Telegram
-        let tg_file_path = match self.get_file_path(&attachment.file_id).await {
-            Ok(p) => p,
-            Err(e) => {
-                tracing::warn!("Failed to get attachment file path: {e}");
-                return None;
-            }
-        };
-
-        let file_data = match self.download_file(&tg_file_path).await {
-            Ok(d) => d,
-            Err(e) => {
-                tracing::warn!("Failed to download attachment: {e}");
-                return None;
-            }
-        };
-
-        // Determine local filename
-        let local_filename = match &attachment.file_name {
-            Some(name) => name.clone(),
-            None => {
-                // For photos, derive extension from Telegram file path
-                let ext = tg_file_path.rsplit('.').next().unwrap_or("jpg");
-                format!("photo_{chat_id}_{message_id}.{ext}")
-            }
-        };
-
-        let local_path = save_dir.join(&local_filename);
-        if let Err(e) = tokio::fs::write(&local_path, &file_data).await {
-            tracing::warn!("Failed to save attachment to {}: {e}", local_path.display());
-            return None;
-        }
+        let mut metadata = Self::message_metadata(message);
 
         // Build message content.
         // Photos with image extensions use [IMAGE:] marker so the multimodal
         // pipeline validates vision capability. Non-image files always get
         // [Document:] format regardless of Telegram's classification.
-        let mut content = format_attachment_content(attachment.kind, &local_filename, &local_path);
+        let mut content = match self
+            .download_attachment(&attachment, &chat_id, message_id)
+            .await
+        {
+            Ok((local_filename, local_path)) => {
+                metadata.insert("file_path".into(), local_path.display().to_string());
+                format_attachment_content(attachment.kind, &local_filename, &local_path)
+            }
+            Err(reason) => {
+                tracing::warn!("Telegram attachment not downloaded: {reason}");
+                if attachment.kind != IncomingAttachmentKind::Document {
+                    return None;
+                }
+                let name = attachment.file_name.as_deref().unwrap_or("document");
+                format!("[Document: {name}] (not downloaded: {reason})")
+            }
+        };
         if let Some(caption) = &attachment.caption {
             if !caption.is_empty() {
                 use std::fmt::Write;
@@ -1032,9 +1086,65 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 .unwrap_or_default()
                 .as_secs(),
             thread_ts: thread_id,
+            sender_name: Self::sender_full_name(message),
+            metadata,
         })
     }
 
+    /// Save an incoming attachment under `{workspace_dir}/telegram_files/`.
+    /// Returns the local file name and path, or why it was not downloaded.
+    async fn download_attachment(
+        &self,
+        attachment: &IncomingAttachment,
+        chat_id: &str,
+        message_id: i64,
+    ) -> Result<(String, std::path::PathBuf), String> {
+        if let Some(size) = attachment.file_size {
+            if size > TELEGRAM_MAX_FILE_DOWNLOAD_BYTES {
+                return Err(format!(
+                    "file size {size} bytes exceeds {} MB limit",
+                    TELEGRAM_MAX_FILE_DOWNLOAD_BYTES / (1024 * 1024)
+                ));
+            }
+        }
+
+        let workspace = self
+            .workspace_dir
+            .as_ref()
+            .ok_or_else(|| "workspace_dir not configured".to_string())?;
+
+        let save_dir = workspace.join("telegram_files");
+        tokio::fs::create_dir_all(&save_dir)
+            .await
+            .map_err(|e| format!("failed to create telegram_files directory: {e}"))?;
+
+        // Download file from Telegram
+        let tg_file_path = self
+            .get_file_path(&attachment.file_id)
+            .await
+            .map_err(|e| format!("failed to get file path: {e}"))?;
+        let file_data = self
+            .download_file(&tg_file_path)
+            .await
+            .map_err(|e| format!("download failed: {e}"))?;
+
+        // Determine local filename
+        let local_filename = match &attachment.file_name {
+            Some(name) => name.clone(),
+            None => {
+                // For photos, derive extension from Telegram file path
+                let ext = tg_file_path.rsplit('.').next().unwrap_or("jpg");
+                format!("photo_{chat_id}_{message_id}.{ext}")
+            }
+        };
+
+        let local_path = save_dir.join(&local_filename);
+        tokio::fs::write(&local_path, &file_data)
+            .await
+            .map_err(|e| format!("failed to save to {}: {e}", local_path.display()))?;
+        Ok((local_filename, local_path))
+    }
+
     /// Attempt to parse a Telegram update as a voice message and transcribe it.
     ///
     /// Returns `None` if the message is not a voice message, transcription is disabled,
@@ -1064,6 +1174,13 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             return None;
         }
 
+        // Voice notes carry no mention; in gated groups they must reply to the bot.
+        let caption = message
+            .get("caption")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default();
+        self.gate_message(message, caption)?;
+
         let chat_id = message
             .get("chat")
             .and_then(|chat| chat.get("id"))
@@ -1149,6 +1266,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 .unwrap_or_default()
                 .as_secs(),
             thread_ts: thread_id,
+            sender_name: Self::sender_full_name(message),
+            metadata: Self::message_metadata(message),
         })
     }
 
@@ -1173,6 +1292,76 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         (username, sender_id, sender_identity)
     }
 
+    /// `first_name last_name` of the sender, when Telegram provides a name.
+    fn sender_full_name(message: &serde_json::Value) -> Option<String> {
+        let from = message.get("from")?;
+        let full_name = ["first_name", "last_name"]
+            .iter()
+            .filter_map(|field| from.get(field).and_then(serde_json::Value::as_str))
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        (!full_name.is_empty()).then_some(full_name)
+    }
+
+    /// Metadata recorded with an inbound message: the Telegram message id,
+    /// chat type and (for groups and channels) chat title, plus the file
+    /// reference of any media so it can be fetched later.
+    fn message_metadata(message: &serde_json::Value) -> HashMap<String, String> {
+        let mut metadata = HashMap::new();
+        if let Some(id) = message
+            .get("message_id")
+            .and_then(serde_json::Value::as_i64)
+        {
+            metadata.insert("telegram_message_id".into(), id.to_string());
+        }
+        let chat = message.get("chat");
+        for (field, key) in [
+            ("type", "telegram_chat_type"),
+            ("title", "telegram_chat_title"),
+        ] {
+            if let Some(value) = chat
+                .and_then(|chat| chat.get(field))
+                .and_then(serde_json::Value::as_str)
+            {
+                metadata.insert(key.into(), value.to_string());
+            }
+        }
+
+        // Photos come as several sizes; the last is the largest.
+        let media = [
+            "document",
+            "audio",
+            "voice",
+            "video",
+            "video_note",
+            "sticker",
+        ]
+        .into_iter()
+        .find_map(|kind| message.get(kind).map(|file| (kind, file)))
+        .or_else(|| {
+            message
+                .get("photo")
+                .and_then(serde_json::Value::as_array)
+                .and_then(|sizes| sizes.last())
+                .map(|file| ("photo", file))
+        });
+        if let Some((kind, file)) = media {
+            if let Some(file_id) = file.get("file_id").and_then(serde_json::Value::as_str) {
+                metadata.insert("telegram_file_id".into(), file_id.to_string());
+                metadata.insert("telegram_file_kind".into(), kind.to_string());
+            }
+            if let Some(name) = file.get("file_name").and_then(serde_json::Value::as_str) {
+                metadata.insert("file_name".into(), name.to_string());
+            }
+            if let Some(size) = file.get("file_size").and_then(serde_json::Value::as_u64) {
+                metadata.insert("file_size".into(), size.to_string());
+            }
+        }
+        metadata
+    }
+
     /// Extract reply context from a Telegram `reply_to_message`, if present.
     fn extract_reply_context(&self, message: &serde_json::Value) -> Option<String> {
         let reply = message.get("reply_to_message")?;
@@ -1244,17 +1433,9 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             return None;
         }
 
-        let is_group = Self::is_group_message(message);
-        if self.mention_only && is_group {
-            let bot_username = self.bot_username.lock();
-            if let Some(ref bot_username) = *bot_username {
-                if !Self::contains_bot_mention(&text, bot_username) {
-                    return None;
-                }
-            } else {
-                return None;
-            }
-        }
+        let content = self
+            .gate_message(message, text)
+            .filter(|content| !content.is_empty())?;
 
         let chat_id = message
             .get("chat")
@@ -1280,14 +1461,6 @@ Allowlist Telegram username (without '@') or numeric user ID.",
             chat_id.clone()
         };
 
-        let content = if self.mention_only && is_group {
-            let bot_username = self.bot_username.lock();
-            let bot_username = bot_username.as_ref()?;
-            Self::normalize_incoming_content(&text, bot_username)?
-        } else {
-            text.to_string()
-        };
-
         let content = if let Some(quote) = self.extract_reply_context(message) {
             format!("{quote}\n\n{content}")
         } else {
@@ -1305,6 +1478,8 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                 .unwrap_or_default()
                 .as_secs(),
             thread_ts: thread_id,
+            sender_name: Self::sender_full_name(message),
+            metadata: Self::message_metadata(message),
         })
     }
 
@@ -1354,164 +1529,9 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         Ok(format!("data:image/jpeg;base64,{}", b64))
     }
 
-    /// Convert Markdown to Telegram HTML format.
-    /// Telegram HTML supports: <b>, <i>, <u>, <s>, <code>, <pre>, <a href="...">
-    /// This mirrors OpenClaw's markdownToTelegramHtml approach.
+    /// Convert Markdown to Telegram HTML (`parse_mode: "HTML"`).
     fn markdown_to_telegram_html(text: &str) -> String {
-        let lines: Vec<&str> = text.split('\n').collect();
-        let mut result_lines: Vec<String> = Vec::new();
-
-        for line in &lines {
-            let trimmed_line = line.trim_start();
-            if trimmed_line.starts_with("```") {
-                // Preserve fence lines so the second-pass block parser can consume them
-                // without interference from inline backtick handling.
-                result_lines.push(trimmed_line.to_string());
-                continue;
-            }
-
-            let mut line_out = String::new();
-
-            // Handle code blocks (``` ... ```) - handled at text level below
-            // Handle headers: ## Title → <b>Title</b>
-            let stripped = line.trim_start_matches('#');
-            let header_level = line.len() - stripped.len();
-            if header_level > 0 && line.starts_with('#') && stripped.starts_with(' ') {
-                let title = Self::escape_html(stripped.trim());
-                result_lines.push(format!("<b>{title}</b>"));
-                continue;
-            }
-
-            // Inline formatting
-            let mut i = 0;
-            let bytes = line.as_bytes();
-            let len = bytes.len();
-            while i < len {
-                // Bold: **text** or __text__
-                if i + 1 < len && bytes[i] == b'*' && bytes[i + 1] == b'*' {
-                    if let Some(end) = line[i + 2..].find("**") {
-                        let inner = Self::escape_html(&line[i + 2..i + 2 + end]);
-                        line_out.push_str(&format!("<b>{inner}</b>"));
-                        i += 4 + end;
-                        continue;
-                    }
-                }
-                if i + 1 < len && bytes[i] == b'_' && bytes[i + 1] == b'_' {
-                    if let Some(end) = line[i + 2..].find("__") {
-                        let inner = Self::escape_html(&line[i + 2..i + 2 + end]);
-                        line_out.push_str(&format!("<b>{inner}</b>"));
-                        i += 4 + end;
-                        continue;
-                    }
-                }
-                // Italic: *text* or _text_ (single)
-                if bytes[i] == b'*' && (i == 0 || bytes[i - 1] != b'*') {
-                    if let Some(end) = line[i + 1..].find('*') {
-                        if end > 0 {
-                            let inner = Self::escape_html(&line[i + 1..i + 1 + end]);
-                            line_out.push_str(&format!("<i>{inner}</i>"));
-                            i += 2 + end;
-                            continue;
-                        }
-                    }
-                }
-                // Inline code: `code`
-                if bytes[i] == b'`' && (i == 0 || bytes[i - 1] != b'`') {
-                    if let Some(end) = line[i + 1..].find('`') {
-                        let inner = Self::escape_html(&line[i + 1..i + 1 + end]);
-                        line_out.push_str(&format!("<code>{inner}</code>"));
-                        i += 2 + end;
-                        continue;
-                    }
-                }
-                // Markdown link: [text](url)
-                if bytes[i] == b'[' {
-                    if let Some(bracket_end) = line[i + 1..].find(']') {
-                        let text_part = &line[i + 1..i + 1 + bracket_end];
-                        let after_bracket = i + 1 + bracket_end + 1; // position after ']'
-                        if after_bracket < len && bytes[after_bracket] == b'(' {
-                            if let Some(paren_end) = line[after_bracket + 1..].find(')') {
-                                let url = &line[after_bracket + 1..after_bracket + 1 + paren_end];
-                                if url.starts_with("http://") || url.starts_with("https://") {
-                                    let text_html = Self::escape_html(text_part);
-                                    let url_html = Self::escape_html(url);
-                                    line_out.push_str(&format!(
-                                        "<a href=\"{url_html}\">{text_html}</a>"
-                                    ));
-                                    i = after_bracket + 1 + paren_end + 1;
-                                    continue;
-                                }
-                            }
-                        }
-                    }
-                }
-                // Strikethrough: ~~text~~
-                if i + 1 < len && bytes[i] == b'~' && bytes[i + 1] == b'~' {
-                    if let Some(end) = line[i + 2..].find("~~") {
-                        let inner = Self::escape_html(&line[i + 2..i + 2 + end]);
-                        line_out.push_str(&format!("<s>{inner}</s>"));
-                        i += 4 + end;
-                        continue;
-                    }
-                }
-                // Default: escape HTML entities
-                let ch = line[i..].chars().next().unwrap();
-                match ch {
-                    '<' => line_out.push_str("&lt;"),
-                    '>' => line_out.push_str("&gt;"),
-                    '&' => line_out.push_str("&amp;"),
-                    '"' => line_out.push_str("&quot;"),
-                    '\'' => line_out.push_str("&#39;"),
-                    _ => line_out.push(ch),
-                }
-                i += ch.len_utf8();
-            }
-            result_lines.push(line_out);
-        }
-
-        // Second pass: handle ``` code blocks across lines
-        let joined = result_lines.join("\n");
-        let mut final_out = String::with_capacity(joined.len());
-        let mut in_code_block = false;
-        let mut code_buf = String::new();
-
-        for line in joined.split('\n') {
-            let trimmed = line.trim();
-            if trimmed.starts_with("```") {
-                if !in_code_block {
-                    in_code_block = true;
-                    code_buf.clear();
-                } else {
-                    in_code_block = false;
-                    let escaped = code_buf.trim_end_matches('\n');
-                    // Telegram HTML parse mode supports <pre> and <code>, but not class attributes.
-                    final_out.push_str(&format!("<pre><code>{escaped}</code></pre>\n"));
-                    code_buf.clear();
-                }
-            } else if in_code_block {
-                code_buf.push_str(line);
-                code_buf.push('\n');
-            } else {
-                final_out.push_str(line);
-                final_out.push('\n');
-            }
-        }
-        if in_code_block && !code_buf.is_empty() {
-            final_out.push_str(&format!(
-                "<pre><code>{}</code></pre>\n",
-                code_buf.trim_end()
-            ));
-        }
-
-        final_out.trim_end_matches('\n').to_string()
-    }
-
-    fn escape_html(s: &str) -> String {
-        s.replace('&', "&amp;")
-            .replace('<', "&lt;")
-            .replace('>', "&gt;")
-            .replace('"', "&quot;")
-            .replace('\'', "&#39;")
+        TelegramFormatter.format(text)
     }
 
     async fn send_text_chunks(
@@ -1519,88 +1539,146 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         message: &str,
         chat_id: &str,
         thread_id: Option<&str>,
-    ) -> anyhow::Result<()> {
+        silent: bool,
+    ) -> anyhow::Result<Option<String>> {
         let chunks = split_message_for_telegram(message);
-
-        for (index, chunk) in chunks.iter().enumerate() {
-            let text = if chunks.len() > 1 {
-                if index == 0 {
-                    format!("{chunk}\n\n(continues...)")
-                } else if index == chunks.len() - 1 {
-                    format!("(continued)\n\n{chunk}")
+        let texts: Vec<String> = chunks
+            .iter()
+            .enumerate()
+            .map(|(index, chunk)| {
+                if chunks.len() > 1 {
+                    if index == 0 {
+                        format!("{chunk}\n\n(continues...)")
+                    } else if index == chunks.len() - 1 {
+                        format!("(continued)\n\n{chunk}")
+                    } else {
+                        format!("(continued)\n\n{chunk}\n\n(continues...)")
+                    }
                 } else {
-                    format!("(continued)\n\n{chunk}\n\n(continues...)")
+                    chunk.to_string()
                 }
-            } else {
-                chunk.to_string()
-            };
+            })
+            .collect();
+
+        let last_message_id = Mutex::new(None);
+        send_chunks_with_retry(
+            "Telegram sendMessage",
+            texts.len(),
+            Duration::from_millis(100),
+            self.outbound.retry_jitter_ms(),
+            |index| {
+                let (texts, last_message_id) = (&texts, &last_message_id);
+                async move {
+                    let sent = self
+                        .send_text_once(&texts[index], chat_id, thread_id, silent)
+                        .await?;
+                    *last_message_id.lock() = sent;
+                    Ok(())
+                }
+            },
+        )
+        .await?;
+        Ok(last_message_id.into_inner())
+    }
 
-            let mut markdown_body = serde_json::json!({
-                "chat_id": chat_id,
-                "text": Self::markdown_to_telegram_html(&text),
-                "parse_mode": "HTML"
-            });
+    /// `result.message_id` of a successful send call, as a string.
+    fn sent_message_id(response: &serde_json::Value) -> Option<String> {
+        response
+            .get("result")
+            .and_then(|result| result.get("message_id"))
+            .and_then(serde_json::Value::as_i64)
+            .map(|id| id.to_string())
+    }
 
-            // Add message_thread_id for forum topic support
-            if let Some(tid) = thread_id {
-                markdown_body["message_thread_id"] = serde_json::Value::String(tid.to_string());
-            }
+    /// One `sendMessage` attempt: HTML first, plain text if Telegram rejects the markup.
+    /// Returns the id Telegram assigned to the message.
+    async fn send_text_once(
+        &self,
+        text: &str,
+        chat_id: &str,
+        thread_id: Option<&str>,
+        silent: bool,
+    ) -> Result<Option<String>, SendError> {
+        let mut markdown_body = serde_json::json!({
+            "chat_id": chat_id,
+            "text": Self::markdown_to_telegram_html(text),
+            "parse_mode": "HTML"
+        });
 
-            let markdown_resp = self
-                .http_client()
-                .post(self.api_url("sendMessage"))
-                .json(&markdown_body)
-                .send()
-                .await?;
+        // Add message_thread_id for forum topic support
+        if let Some(tid) = thread_id {
+            markdown_body["message_thread_id"] = serde_json::Value::String(tid.to_string());
+        }
+        if silent {
+            markdown_body["disable_notification"] = serde_json::Value::Bool(true);
+        }
 
-            if markdown_resp.status().is_success() {
-                if index < chunks.len() - 1 {
-                    tokio::time::sleep(Duration::from_millis(100)).await;
-                }
-                continue;
-            }
+        let markdown_resp = self
+            .http_client()
+            .post(self.api_url("sendMessage"))
+            .json(&markdown_body)
+            .send()
+            .await?;
 
-            let markdown_status = markdown_resp.status();
-            let markdown_err = markdown_resp.text().await.unwrap_or_default();
-            tracing::warn!(
-                status = ?markdown_status,
-                "Telegram sendMessage with Markdown failed; retrying without parse_mode"
-            );
+        if markdown_resp.status().is_success() {
+            let body = markdown_resp.json().await.unwrap_or_default();
+            return Ok(Self::sent_message_id(&body));
+        }
 
-            let mut plain_body = serde_json::json!({
-                "chat_id": chat_id,
-                "text": text,
-            });
+        let markdown_status = markdown_resp.status();
+        let markdown_err = markdown_resp.text().await.unwrap_or_default();
+        if markdown_status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            || markdown_status.is_server_error()
+        {
+            // Rate limits and outages are not markup problems; retry as is.
+            return Err(SendError::from_status(
+                markdown_status,
+                telegram_retry_after(&markdown_err),
+                anyhow::anyhow!("Telegram sendMessage failed ({markdown_status}): {markdown_err}"),
+            ));
+        }
+        tracing::warn!(
+            status = ?markdown_status,
+            "Telegram sendMessage with Markdown failed; retrying without parse_mode"
+        );
 
-            // Add message_thread_id for forum topic support
-            if let Some(tid) = thread_id {
-                plain_body["message_thread_id"] = serde_json::Value::String(tid.to_string());
-            }
-            let plain_resp = self
-                .http_client()
-                .post(self.api_url("sendMessage"))
-                .json(&plain_body)
-                .send()
-                .await?;
+        let mut plain_body = serde_json::json!({
+            "chat_id": chat_id,
+            "text": text,
+        });
 
-            if !plain_resp.status().is_success() {
-                let plain_status = plain_resp.status();
-                let plain_err = plain_resp.text().await.unwrap_or_default();
-                anyhow::bail!(
+        // Add message_thread_id for forum topic support
+        if let Some(tid) = thread_id {
+            plain_body["message_thread_id"] = serde_json::Value::String(tid.to_string());
+        }
+        if silent {
+            plain_body["disable_notification"] = serde_json::Value::Bool(true);
+        }
+        let plain_resp = self
+            .http_client()
+            .post(self.api_url("sendMessage"))
+            .json(&plain_body)
+            .send()
+            .await?;
+
+        if !plain_resp.status().is_success() {
+            let plain_status = plain_resp.status();
+            let plain_err = plain_resp.text().await.unwrap_or_default();
+            return Err(SendError::from_status(
+                plain_status,
+                telegram_retry_after(&plain_err),
+                anyhow::anyhow!(
                     "Telegram sendMessage failed (markdown {}: {}; plain {}: {})",
                     markdown_status,
                     markdown_err,
                     plain_status,
                     plain_err
-                );
-            }
-
-            if index < chunks.len() - 1 {
-                tokio::time::sleep(Duration::from_millis(100)).await;
-            }
+                ),
+            ));
         }
 
-        Ok(())
+        let body = plain_resp.json().await.unwrap_or_default();
+        Ok(Self::sent_message_id(&body))
     }
 
     async fn send_media_by_url(
@@ -1690,7 +1768,7 @@ Allowlist Telegram username (without '@') or numeric user ID.",
                     TelegramAttachmentKind::Voice => "Voice",
                 };
                 let fallback_text = format!("{kind_label}: {target}");
-                self.send_text_chunks(&fallback_text, chat_id, thread_id)
+                self.send_text_chunks(&fallback_text, chat_id, thread_id, false)
                     .await?;
             }
 
@@ -2303,7 +2381,7 @@ impl Channel for TelegramChannel {
 
             // Send text without markers
             if !text_without_markers.is_empty() {
-                self.send_text_chunks(&text_without_markers, &chat_id, thread_id.as_deref())
+                self.send_text_chunks(&text_without_markers, &chat_id, thread_id.as_deref(), false)
                     .await?;
             }
 
@@ -2332,67 +2410,103 @@ impl Channel for TelegramChannel {
 
             // Fall back to chunked send
             return self
-                .send_text_chunks(text, &chat_id, thread_id.as_deref())
-                .await;
+                .send_text_chunks(text, &chat_id, thread_id.as_deref(), false)
+                .await
+                .map(drop);
         }
 
         let Some(id) = msg_id else {
             return self
-                .send_text_chunks(text, &chat_id, thread_id.as_deref())
-                .await;
+                .send_text_chunks(text, &chat_id, thread_id.as_deref(), false)
+                .await
+                .map(drop);
         };
 
-        // Try editing with HTML formatting
-        let body = serde_json::json!({
+        if self.edit_message_text(&chat_id, id, text).await? {
+            return Ok(());
+        }
+
+        // Edit failed entirely — fall back to new message
+        tracing::warn!("Telegram finalize_draft edit failed; falling back to sendMessage");
+        self.send_text_chunks(text, &chat_id, thread_id.as_deref(), false)
+            .await
+            .map(drop)
+    }
+
+    async fn cancel_draft(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
+        let (chat_id, _) = Self::parse_reply_target(recipient);
+        self.last_draft_edit.lock().remove(&chat_id);
+        self.delete_status(recipient, message_id).await
+    }
+
+    fn progress_messages_enabled(&self) -> bool {
+        self.progress_messages
+    }
+
+    async fn send_status(&self, recipient: &str, text: &str) -> anyhow::Result<Option<String>> {
+        let (chat_id, thread_id) = Self::parse_reply_target(recipient);
+        let mut body = serde_json::json!({
             "chat_id": chat_id,
-            "message_id": id,
-            "text": Self::markdown_to_telegram_html(text),
-            "parse_mode": "HTML",
+            "text": text,
+            "disable_notification": true,
         });
+        if let Some(tid) = thread_id {
+            body["message_thread_id"] = serde_json::Value::String(tid.to_string());
+        }
 
         let resp = self
             .client
-            .post(self.api_url("editMessageText"))
+            .post(self.api_url("sendMessage"))
             .json(&body)
             .send()
             .await?;
-
-        if resp.status().is_success() {
-            return Ok(());
+        if !resp.status().is_success() {
+            let err = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Telegram sendMessage (status) failed: {err}");
         }
 
-        // Markdown failed — retry without parse_mode
-        let plain_body = serde_json::json!({
-            "chat_id": chat_id,
-            "message_id": id,
-            "text": text,
-        });
+        let resp_json: serde_json::Value = resp.json().await?;
+        Ok(resp_json
+            .get("result")
+            .and_then(|r| r.get("message_id"))
+            .and_then(serde_json::Value::as_i64)
+            .map(|id| id.to_string()))
+    }
 
+    async fn update_status(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let (chat_id, _) = Self::parse_reply_target(recipient);
+        let Ok(message_id) = message_id.parse::<i64>() else {
+            return Ok(());
+        };
         let resp = self
             .client
             .post(self.api_url("editMessageText"))
-            .json(&plain_body)
+            .json(&serde_json::json!({
+                "chat_id": chat_id,
+                "message_id": message_id,
+                "text": text,
+            }))
             .send()
             .await?;
-
-        if resp.status().is_success() {
-            return Ok(());
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            tracing::debug!("Telegram editMessageText (status) failed ({status}): {body}");
         }
-
-        // Edit failed entirely — fall back to new message
-        tracing::warn!("Telegram finalize_draft edit failed; falling back to sendMessage");
-        self.send_text_chunks(text, &chat_id, thread_id.as_deref())
-            .await
+        Ok(())
     }
 
-    async fn cancel_draft(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
+    async fn delete_status(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
         let (chat_id, _) = Self::parse_reply_target(recipient);
-        self.last_draft_edit.lock().remove(&chat_id);
-
         let message_id = match message_id.parse::<i64>() {
             Ok(id) => id,
             Err(e) => {
-                tracing::debug!("Invalid Telegram draft message_id '{message_id}': {e}");
+                tracing::debug!("Invalid Telegram message_id '{message_id}': {e}");
                 return Ok(());
             }
         };
@@ -2416,7 +2530,41 @@ impl Channel for TelegramChannel {
         Ok(())
     }
 
+    fn supports_message_edits(&self) -> bool {
+        true
+    }
+
+    async fn edit_message(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let text = strip_tool_call_tags(text);
+        let (chat_id, _) = Self::parse_reply_target(recipient);
+        let id = message_id
+            .parse::<i64>()
+            .map_err(|e| anyhow::anyhow!("Invalid Telegram message_id '{message_id}': {e}"))?;
+        // editMessageText can neither add attachments nor split long text.
+        if text.len() > TELEGRAM_MAX_MESSAGE_LENGTH || !parse_attachment_markers(&text).1.is_empty()
+        {
+            anyhow::bail!("Telegram cannot edit message {id} into this content");
+        }
+        if !self.edit_message_text(&chat_id, id, &text).await? {
+            anyhow::bail!("Telegram refused to edit message {id}");
+        }
+        Ok(())
+    }
+
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        self.send_with_id(message).await.map(drop)
+    }
+
+    /// Returns the id of the last text message sent; attachments sent
+    /// without accompanying text report none.
+    async fn send_with_id(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        let _permit = self.outbound.acquire_for(&message.recipient).await;
+
         // Strip tool_call tags before processing to prevent Markdown parsing failures
         let content = strip_tool_call_tags(&message.content);
 
@@ -2429,8 +2577,10 @@ impl Channel for TelegramChannel {
         let (text_without_markers, attachments) = parse_attachment_markers(&content);
 
         if !attachments.is_empty() {
+            let mut sent_id = None;
             if !text_without_markers.is_empty() {
-                self.send_text_chunks(&text_without_markers, chat_id, thread_id)
+                sent_id = self
+                    .send_text_chunks(&text_without_markers, chat_id, thread_id, message.silent)
                     .await?;
             }
 
@@ -2438,16 +2588,17 @@ impl Channel for TelegramChannel {
                 self.send_attachment(chat_id, thread_id, attachment).await?;
             }
 
-            return Ok(());
+            return Ok(sent_id);
         }
 
         if let Some(attachment) = parse_path_only_attachment(&content) {
             self.send_attachment(chat_id, thread_id, &attachment)
                 .await?;
-            return Ok(());
+            return Ok(None);
         }
 
-        self.send_text_chunks(&content, chat_id, thread_id).await
+        self.send_text_chunks(&content, chat_id, thread_id, message.silent)
+            .await
     }
 
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
@@ -3113,6 +3264,131 @@ mod tests {
         assert_eq!(msg.id, "telegram_-100200300_42");
     }
 
+    #[test]
+    fn parse_update_message_records_message_and_group_metadata() {
+        let ch = TelegramChannel::new("token".into(), vec!["*".into()], false);
+        let update = serde_json::json!({
+            "update_id": 4,
+            "message": {
+                "message_id": 42,
+                "text": "hello group",
+                "from": { "id": 555, "username": "alice" },
+                "chat": { "id": -100_200_300, "type": "supergroup", "title": "Ops" }
+            }
+        });
+
+        let msg = ch.parse_update_message(&update).unwrap();
+
+        assert_eq!(
+            msg.metadata,
+            HashMap::from([
+                ("telegram_message_id".to_string(), "42".to_string()),
+                ("telegram_chat_type".to_string(), "supergroup".to_string()),
+                ("telegram_chat_title".to_string(), "Ops".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn message_metadata_records_media_file_references() {
+        let document = serde_json::json!({
+            "message_id": 7,
+            "chat": { "id": 123, "type": "private" },
+            "document": { "file_id": "BQAD-doc", "file_name": "report.pdf", "file_size": 2048 },
+            "caption": "Q3 numbers"
+        });
+        let metadata = TelegramChannel::message_metadata(&document);
+        assert_eq!(metadata["telegram_file_id"], "BQAD-doc");
+        assert_eq!(metadata["telegram_file_kind"], "document");
+        assert_eq!(metadata["file_name"], "report.pdf");
+        assert_eq!(metadata["file_size"], "2048");
+        assert_eq!(metadata["telegram_chat_type"], "private");
+        assert!(!metadata.contains_key("telegram_chat_title"));
+
+        let photo = serde_json::json!({
+            "message_id": 8,
+            "chat": { "id": 123, "type": "private" },
+            "photo": [
+                { "file_id": "small", "file_size": 100 },
+                { "file_id": "large", "file_size": 9000 }
+            ]
+        });
+        let metadata = TelegramChannel::message_metadata(&photo);
+        assert_eq!(metadata["telegram_file_id"], "large");
+        assert_eq!(metadata["telegram_file_kind"], "photo");
+        assert_eq!(metadata["file_size"], "9000");
+
+        let voice = serde_json::json!({
+            "message_id": 9,
+            "chat": { "id": 123, "type": "private" },
+            "voice": { "file_id": "AwAD-voice", "duration": 3 }
+        });
+        let metadata = TelegramChannel::message_metadata(&voice);
+        assert_eq!(metadata["telegram_file_kind"], "voice");
+        assert!(!metadata.contains_key("file_size"));
+    }
+
+    #[tokio::test]
+    async fn undownloadable_document_becomes_placeholder_with_file_reference() {
+        let ch = TelegramChannel::new("token".into(), vec!["*".into()], false);
+        let update = serde_json::json!({
+            "update_id": 5,
+            "message": {
+                "message_id": 43,
+                "from": { "id": 555, "username": "alice" },
+                "chat": { "id": 123, "type": "private" },
+                "document": {
+                    "file_id": "BQAD-big",
+                    "file_name": "dump.tar",
+                    "file_size": TELEGRAM_MAX_FILE_DOWNLOAD_BYTES + 1
+                },
+                "caption": "logs attached"
+            }
+        });
+
+        let msg = ch.try_parse_attachment_message(&update).await.unwrap();
+
+        assert!(msg
+            .content
+            .starts_with("[Document: dump.tar] (not downloaded: file size"));
+        assert!(msg.content.ends_with("\n\nlogs attached"));
+        assert_eq!(msg.metadata["telegram_file_id"], "BQAD-big");
+        assert_eq!(msg.metadata["telegram_message_id"], "43");
+        assert!(!msg.metadata.contains_key("file_path"));
+    }
+
+    #[tokio::test]
+    async fn undownloadable_photo_is_still_dropped() {
+        let ch = TelegramChannel::new("token".into(), vec!["*".into()], false);
+        let update = serde_json::json!({
+            "update_id": 6,
+            "message": {
+                "message_id": 44,
+                "from": { "id": 555, "username": "alice" },
+                "chat": { "id": 123, "type": "private" },
+                "photo": [{ "file_id": "big", "file_size": TELEGRAM_MAX_FILE_DOWNLOAD_BYTES + 1 }]
+            }
+        });
+
+        assert!(ch.try_parse_attachment_message(&update).await.is_none());
+    }
+
+    #[test]
+    fn sent_message_id_reads_send_result() {
+        let response = serde_json::json!({
+            "ok": true,
+            "result": { "message_id": 314, "chat": { "id": 123 } }
+        });
+        assert_eq!(
+            TelegramChannel::sent_message_id(&response).as_deref(),
+            Some("314")
+        );
+        assert_eq!(
+            TelegramChannel::sent_message_id(&serde_json::json!({"ok": true})),
+            None
+        );
+    }
+
     // ── File sending API URL tests ──────────────────────────────────
 
     #[test]
@@ -3740,6 +4016,87 @@ mod tests {
         assert!(ch.parse_update_message(&empty_update).is_none());
     }
 
+    fn gated_channel() -> TelegramChannel {
+        let ch = TelegramChannel::new("token".into(), vec!["*".into()], true)
+            .with_command_prefix(Some("!claw".into()));
+        *ch.bot_username.lock() = Some("mybot".to_string());
+        ch
+    }
+
+    fn text_update(chat_type: &str, text: &str, reply_from: Option<&str>) -> serde_json::Value {
+        let mut message = serde_json::json!({
+            "message_id": 50,
+            "text": text,
+            "from": { "id": 555, "username": "alice" },
+            "chat": { "id": -100_200_300, "type": chat_type }
+        });
+        if let Some(from) = reply_from {
+            message["reply_to_message"] = serde_json::json!({
+                "message_id": 49,
+                "text": "earlier",
+                "from": { "id": 1, "username": from, "is_bot": true }
+            });
+        }
+        serde_json::json!({ "update_id": 20, "message": message })
+    }
+
+    #[test]
+    fn mention_gate_private_chat_always_passes() {
+        let ch = gated_channel();
+        let parsed = ch
+            .parse_update_message(&text_update("private", "hello", None))
+            .expect("DMs are never gated");
+        assert_eq!(parsed.content, "hello");
+    }
+
+    #[test]
+    fn mention_gate_group_without_trigger_is_ignored() {
+        let ch = gated_channel();
+        assert!(ch
+            .parse_update_message(&text_update("supergroup", "lunch?", None))
+            .is_none());
+        assert!(ch
+            .parse_update_message(&text_update("group", "hi", Some("someone_else")))
+            .is_none());
+    }
+
+    #[test]
+    fn mention_gate_group_reply_to_bot_passes() {
+        let ch = gated_channel();
+        let parsed = ch
+            .parse_update_message(&text_update("group", "yes please", Some("MyBot")))
+            .expect("reply to bot should pass");
+        assert!(parsed.content.ends_with("yes please"));
+        assert!(parsed.content.starts_with("> @MyBot:"));
+    }
+
+    #[test]
+    fn mention_gate_group_command_prefix_is_stripped() {
+        let ch = gated_channel();
+        let parsed = ch
+            .parse_update_message(&text_update("group", "!claw  weather  today", None))
+            .expect("prefix should pass");
+        assert_eq!(parsed.content, "weather today");
+    }
+
+    #[test]
+    fn mention_gate_disabled_forwards_group_chatter() {
+        let ch = TelegramChannel::new("token".into(), vec!["*".into()], false);
+        let parsed = ch
+            .parse_update_message(&text_update("group", "lunch?", None))
+            .expect("ungated group message");
+        assert_eq!(parsed.content, "lunch?");
+    }
+
+    #[test]
+    fn addressed_group_content_allows_empty_reply_for_media() {
+        assert_eq!(
+            TelegramChannel::addressed_group_content("", "mybot", true, None).as_deref(),
+            Some("")
+        );
+        assert!(TelegramChannel::addressed_group_content("", "mybot", false, None).is_none());
+    }
+
     #[test]
     fn telegram_is_group_message_detects_groups() {
         let group_msg = serde_json::json!({
@@ -3937,6 +4294,24 @@ mod tests {
         assert_eq!(identity, "42");
     }
 
+    #[test]
+    fn sender_full_name_joins_first_and_last_name() {
+        let full = serde_json::json!({
+            "from": { "id": 1, "first_name": "Alice", "last_name": "Walker" }
+        });
+        assert_eq!(
+            TelegramChannel::sender_full_name(&full).as_deref(),
+            Some("Alice Walker")
+        );
+        let first_only = serde_json::json!({ "from": { "id": 2, "first_name": "Bob" } });
+        assert_eq!(
+            TelegramChannel::sender_full_name(&first_only).as_deref(),
+            Some("Bob")
+        );
+        let nameless = serde_json::json!({ "from": { "id": 3, "first_name": " " } });
+        assert_eq!(TelegramChannel::sender_full_name(&nameless), None);
+    }
+
     // ─────────────────────────────────────────────────────────────────────
     // extract_reply_context tests
     // ─────────────────────────────────────────────────────────────────────
@@ -4601,6 +4976,6 @@ mod tests {
 
         // The combination of marker_count > 0 && !supports_vision() means
         // the agent loop will return ProviderCapabilityError before calling
-        // the provider, and the channel will send "⚠️ Error: ..." to the user.
+        // the provider, and the channel will send the unsupported-input reply to the user.
     }
 }
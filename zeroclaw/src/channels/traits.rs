@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use std::collections::HashMap;
 
 /// A message received from or sent to a channel
 #[derive(Debug, Clone)]
@@ -12,16 +13,29 @@ pub struct ChannelMessage {
     /// Platform thread identifier (e.g. Slack `ts`, Discord thread ID).
     /// When set, replies should be posted as threaded responses.
     pub thread_ts: Option<String>,
+    /// Human-readable sender name when the platform supplies one; recorded
+    /// in the contacts table so group conversations can be attributed.
+    pub sender_name: Option<String>,
+    /// Platform details of the message (ids, chat info, media file
+    /// references), keyed `<channel>_<field>` or generic names such as
+    /// `file_name`. Persisted with the user turn.
+    pub metadata: HashMap<String, String>,
 }
 
+/// [`ChannelMessage::metadata`] key holding the text of the thread root a
+/// threaded message replies to, when the adapter could resolve it.
+pub const THREAD_PARENT_METADATA_KEY: &str = "thread_parent";
+
 /// Message to send through a channel
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SendMessage {
     pub content: String,
     pub recipient: String,
     pub subject: Option<String>,
     /// Platform thread identifier for threaded replies (e.g. Slack `thread_ts`).
     pub thread_ts: Option<String>,
+    /// Deliver without a notification where the platform supports it.
+    pub silent: bool,
 }
 
 impl SendMessage {
@@ -32,6 +46,7 @@ impl SendMessage {
             recipient: recipient.into(),
             subject: None,
             thread_ts: None,
+            silent: false,
         }
     }
 
@@ -46,6 +61,7 @@ impl SendMessage {
             recipient: recipient.into(),
             subject: Some(subject.into()),
             thread_ts: None,
+            silent: false,
         }
     }
 
@@ -54,6 +70,12 @@ impl SendMessage {
         self.thread_ts = thread_ts;
         self
     }
+
+    /// Deliver without a notification (e.g. during quiet hours).
+    pub fn silent(mut self, silent: bool) -> Self {
+        self.silent = silent;
+        self
+    }
 }
 
 /// Core channel trait — implement for any messaging platform
@@ -65,6 +87,13 @@ pub trait Channel: Send + Sync {
     /// Send a message through this channel
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()>;
 
+    /// Send a message and return the platform id of the delivered message
+    /// (the last one when the content was split), when the platform reports
+    /// it. The runtime records the id on the persisted assistant turn.
+    async fn send_with_id(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        self.send(message).await.map(|()| None)
+    }
+
     /// Start listening for incoming messages (long-running)
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()>;
 
@@ -119,6 +148,52 @@ pub trait Channel: Send + Sync {
         Ok(())
     }
 
+    /// Whether the channel posts a transient status message (e.g. "⏳ running shell…")
+    /// while a turn runs tools. Opt-in per channel config.
+    fn progress_messages_enabled(&self) -> bool {
+        false
+    }
+
+    /// Post a transient status message. Returns a platform message ID for later edits.
+    async fn send_status(&self, _recipient: &str, _text: &str) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Replace the text of a status message sent with [`Channel::send_status`].
+    async fn update_status(
+        &self,
+        _recipient: &str,
+        _message_id: &str,
+        _text: &str,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Remove a status message once the real reply is about to be delivered.
+    async fn delete_status(&self, _recipient: &str, _message_id: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Whether [`Channel::edit_message`] can replace the text of a message
+    /// this channel delivered. The runtime then edits the progress status
+    /// into the final reply and applies corrections in place.
+    fn supports_message_edits(&self) -> bool {
+        false
+    }
+
+    /// Replace the text of a delivered message, identified by the id
+    /// [`Channel::send_with_id`] or [`Channel::send_status`] returned. Fails
+    /// when the platform refuses (message too old, deleted, text too long);
+    /// callers then send a new message instead.
+    async fn edit_message(
+        &self,
+        _recipient: &str,
+        _message_id: &str,
+        _text: &str,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("{} does not support editing messages", self.name())
+    }
+
     /// Add a reaction (emoji) to a message.
     ///
     /// `channel_id` is the platform channel/conversation identifier (e.g. Discord channel ID).
@@ -172,6 +247,8 @@ mod tests {
                 channel: "dummy".into(),
                 timestamp: 123,
                 thread_ts: None,
+                sender_name: None,
+                metadata: HashMap::new(),
             })
             .await
             .map_err(|e| anyhow::anyhow!(e.to_string()))
@@ -188,6 +265,8 @@ mod tests {
             channel: "dummy".into(),
             timestamp: 999,
             thread_ts: None,
+            sender_name: None,
+            metadata: HashMap::new(),
         };
 
         let cloned = message.clone();
@@ -206,6 +285,15 @@ mod tests {
         assert!(channel.health_check().await);
         assert!(channel.start_typing("bob").await.is_ok());
         assert!(channel.stop_typing("bob").await.is_ok());
+        assert!(!channel.progress_messages_enabled());
+        assert!(channel
+            .send_status("bob", "working")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(channel.delete_status("bob", "1").await.is_ok());
+        assert!(!channel.supports_message_edits());
+        assert!(channel.edit_message("bob", "1", "fixed").await.is_err());
         assert!(channel
             .send(&SendMessage::new("hello", "bob"))
             .await
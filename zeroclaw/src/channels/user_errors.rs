@@ -0,0 +1,354 @@
+//! User-facing replies for failed channel turns.
+//!
+//! Provider errors carry status codes, JSON bodies and endpoint details that
+//! must not reach chat users. A failed turn is classified into a
+//! [`UserErrorKind`] and answered with the configured template for that kind
+//! (`[channels_config.error_messages]`) plus a short reference id. The full
+//! error is logged and written to the audit log under the same id, so an
+//! operator can find it from a user's screenshot.
+
+use crate::config::{Config, ErrorMessagesConfig};
+use crate::providers::{self, ProviderCapabilityError, ProviderError};
+use crate::security::{AuditEvent, AuditEventType, AuditLogger};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Length of the reference id shown to users.
+const REFERENCE_LEN: usize = 8;
+
+/// Broad class of a failed turn; selects the reply template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserErrorKind {
+    RateLimited,
+    Auth,
+    Network,
+    ContextTooLong,
+    SandboxViolation,
+    MaxIterations,
+    UnsupportedInput,
+    TimedOut,
+    Internal,
+}
+
+impl UserErrorKind {
+    /// Classify a tool-loop error. Typed errors are matched first; errors
+    /// flattened to text (e.g. the reliable provider's attempt summary) are
+    /// recognized by status codes and wording.
+    pub fn classify(err: &anyhow::Error) -> Self {
+        if err
+            .chain()
+            .any(|source| source.is::<crate::agent::loop_::ToolLoopExhausted>())
+        {
+            return Self::MaxIterations;
+        }
+        match err.downcast_ref::<ProviderError>() {
+            Some(ProviderError::ContextTooLong { .. }) => return Self::ContextTooLong,
+            Some(ProviderError::Config { .. }) => return Self::Auth,
+            Some(ProviderError::Timeout { .. }) => return Self::Network,
+            None => {}
+        }
+        if err.downcast_ref::<ProviderCapabilityError>().is_some() {
+            return Self::UnsupportedInput;
+        }
+        if providers::reliable::is_context_window_exceeded(err) {
+            return Self::ContextTooLong;
+        }
+        if let Some(reqwest_err) = err.downcast_ref::<reqwest::Error>() {
+            match reqwest_err.status().map(|status| status.as_u16()) {
+                Some(429) => return Self::RateLimited,
+                Some(401 | 403) => return Self::Auth,
+                None if reqwest_err.is_connect()
+                    || reqwest_err.is_timeout()
+                    || reqwest_err.is_request() =>
+                {
+                    return Self::Network;
+                }
+                _ => {}
+            }
+        }
+
+        let text = format!("{err:#}");
+        let lower = text.to_lowercase();
+        let mentions = |hints: &[&str]| hints.iter().any(|hint| lower.contains(hint));
+        if has_status_code(&text, 429)
+            || mentions(&["rate limit", "rate_limited", "too many requests", "quota"])
+        {
+            Self::RateLimited
+        } else if has_status_code(&text, 401)
+            || has_status_code(&text, 403)
+            || mentions(&[
+                "unauthorized",
+                "forbidden",
+                "api key",
+                "authentication failed",
+            ])
+        {
+            Self::Auth
+        } else if mentions(&[
+            "security policy",
+            "action blocked",
+            "blocked potentially dangerous",
+            "blocked local/private host",
+            "sandbox",
+        ]) {
+            Self::SandboxViolation
+        } else if mentions(&[
+            "error sending request",
+            "connection refused",
+            "connection reset",
+            "dns error",
+            "timed out",
+            "network is unreachable",
+        ]) {
+            Self::Network
+        } else {
+            Self::Internal
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RateLimited => "rate_limited",
+            Self::Auth => "auth",
+            Self::Network => "network",
+            Self::ContextTooLong => "context_too_long",
+            Self::SandboxViolation => "sandbox_violation",
+            Self::MaxIterations => "max_iterations",
+            Self::UnsupportedInput => "unsupported_input",
+            Self::TimedOut => "timed_out",
+            Self::Internal => "internal",
+        }
+    }
+}
+
+/// Whether `text` contains `code` as a standalone number.
+fn has_status_code(text: &str, code: u16) -> bool {
+    text.split(|c: char| !c.is_ascii_digit())
+        .any(|word| word.parse::<u16>() == Ok(code))
+}
+
+/// Where and how a turn failed; `detail` is the operator-facing error.
+#[derive(Debug, Clone, Copy)]
+pub struct FailedTurn<'a> {
+    pub channel: &'a str,
+    pub sender: &'a str,
+    pub detail: &'a str,
+    pub elapsed: Duration,
+}
+
+/// Reply for a failed turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserFacingError {
+    pub kind: UserErrorKind,
+    pub reference: String,
+    /// Template text followed by the reference id.
+    pub text: String,
+}
+
+/// Turns classified failures into replies and records their details.
+#[derive(Default)]
+pub struct ErrorPresenter {
+    messages: ErrorMessagesConfig,
+    audit: Option<Arc<AuditLogger>>,
+}
+
+impl ErrorPresenter {
+    pub fn new(messages: ErrorMessagesConfig, audit: Option<Arc<AuditLogger>>) -> Self {
+        Self { messages, audit }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        let audit = config.config_path.parent().and_then(|zeroclaw_dir| {
+            AuditLogger::new(config.security.audit.clone(), zeroclaw_dir.to_path_buf())
+                .ok()
+                .map(Arc::new)
+        });
+        Self::new(config.channels_config.error_messages.clone(), audit)
+    }
+
+    /// Audit logger failures are recorded in, when auditing is configured.
+    pub fn audit(&self) -> Option<&Arc<AuditLogger>> {
+        self.audit.as_ref()
+    }
+
+    fn template(&self, kind: UserErrorKind) -> &str {
+        let messages = &self.messages;
+        match kind {
+            UserErrorKind::RateLimited => &messages.rate_limited,
+            UserErrorKind::Auth => &messages.auth,
+            UserErrorKind::Network => &messages.network,
+            UserErrorKind::ContextTooLong => &messages.context_too_long,
+            UserErrorKind::SandboxViolation => &messages.sandbox_violation,
+            UserErrorKind::MaxIterations => &messages.max_iterations,
+            UserErrorKind::UnsupportedInput => &messages.unsupported_input,
+            UserErrorKind::TimedOut => &messages.timed_out,
+            UserErrorKind::Internal => &messages.internal,
+        }
+    }
+
+    /// Build the reply for `turn` and log its detail under a new reference id.
+    pub fn present(&self, kind: UserErrorKind, turn: &FailedTurn<'_>) -> UserFacingError {
+        let reference = uuid::Uuid::new_v4().simple().to_string()[..REFERENCE_LEN].to_string();
+        let detail = providers::sanitize_api_error(turn.detail);
+        tracing::warn!(
+            reference = %reference,
+            kind = kind.as_str(),
+            channel = turn.channel,
+            "Turn failed: {detail}"
+        );
+        if let Some(audit) = &self.audit {
+            let duration_ms = u64::try_from(turn.elapsed.as_millis()).unwrap_or(u64::MAX);
+            let event = AuditEvent::new(AuditEventType::ChannelError)
+                .with_actor(
+                    turn.channel.to_string(),
+                    Some(turn.sender.to_string()),
+                    None,
+                )
+                .with_action(kind.as_str().to_string(), "low".into(), false, false)
+                .with_result(false, None, duration_ms, Some(detail))
+                .with_reference(reference.clone());
+            if let Err(e) = audit.log(&event) {
+                tracing::warn!("Failed to write channel error audit event: {e}");
+            }
+        }
+
+        UserFacingError {
+            kind,
+            text: format!("{} (ref: {reference})", self.template(kind)),
+            reference,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::loop_::ToolLoopExhausted;
+    use crate::config::AuditConfig;
+    use crate::security::audit::{audit_log_path, read_recent_events, AuditQuery};
+
+    fn turn(detail: &str) -> FailedTurn<'_> {
+        FailedTurn {
+            channel: "telegram",
+            sender: "alice",
+            detail,
+            elapsed: Duration::from_millis(1200),
+        }
+    }
+
+    #[test]
+    fn classifies_typed_errors() {
+        let context: anyhow::Error = ProviderError::ContextTooLong {
+            message: "too long".into(),
+        }
+        .into();
+        let config: anyhow::Error = ProviderError::Config {
+            message: "OpenAI API error (401 Unauthorized)".into(),
+        }
+        .into();
+        let vision: anyhow::Error = ProviderCapabilityError {
+            provider: "ollama".into(),
+            capability: "vision".into(),
+            message: "no vision".into(),
+        }
+        .into();
+        let exhausted: anyhow::Error = ToolLoopExhausted { max_iterations: 3 }.into();
+
+        assert_eq!(
+            UserErrorKind::classify(&context),
+            UserErrorKind::ContextTooLong
+        );
+        assert_eq!(UserErrorKind::classify(&config), UserErrorKind::Auth);
+        assert_eq!(
+            UserErrorKind::classify(&vision),
+            UserErrorKind::UnsupportedInput
+        );
+        assert_eq!(
+            UserErrorKind::classify(&exhausted.context("channel turn")),
+            UserErrorKind::MaxIterations
+        );
+    }
+
+    #[test]
+    fn classifies_flattened_provider_and_tool_errors() {
+        let cases = [
+            (
+                "All providers/models failed. Attempts:\nprovider=openai model=gpt-4o attempt 3/3: rate_limited; error=OpenAI API error (429 Too Many Requests): {\"error\":{}}",
+                UserErrorKind::RateLimited,
+            ),
+            (
+                "All providers/models failed. Attempts:\nprovider=openai model=gpt-4o attempt 1/3: non_retryable; error=OpenAI API error (401 Unauthorized): Incorrect API key",
+                UserErrorKind::Auth,
+            ),
+            (
+                "error sending request for url (https://api.openai.com/v1/chat/completions)",
+                UserErrorKind::Network,
+            ),
+            (
+                "Command blocked by security policy: rm -rf /",
+                UserErrorKind::SandboxViolation,
+            ),
+            (
+                "prompt is too long: 210000 tokens > 200000 maximum",
+                UserErrorKind::ContextTooLong,
+            ),
+            ("response contained 4010 tokens", UserErrorKind::Internal),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(
+                UserErrorKind::classify(&anyhow::anyhow!(message)),
+                expected,
+                "{message}"
+            );
+        }
+    }
+
+    #[test]
+    fn reply_hides_detail_and_audit_entry_carries_reference() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let audit_config = AuditConfig {
+            enabled: true,
+            ..AuditConfig::default()
+        };
+        let audit = AuditLogger::new(audit_config.clone(), tmp.path().to_path_buf()).unwrap();
+        let presenter = ErrorPresenter::new(ErrorMessagesConfig::default(), Some(Arc::new(audit)));
+        let detail = r#"OpenAI API error (429 Too Many Requests): {"error":{"message":"Rate limit reached for org-abc"}}"#;
+
+        let reply = presenter.present(UserErrorKind::RateLimited, &turn(detail));
+
+        assert_eq!(reply.reference.len(), REFERENCE_LEN);
+        assert!(reply
+            .text
+            .starts_with(&ErrorMessagesConfig::default().rate_limited));
+        assert!(reply.text.ends_with(&format!("(ref: {})", reply.reference)));
+        assert!(!reply.text.contains("429"));
+        assert!(!reply.text.contains("org-abc"));
+
+        let events = read_recent_events(
+            &audit_log_path(&audit_config, tmp.path()),
+            &AuditQuery {
+                limit: 10,
+                event_type: Some("channel_error".into()),
+                session: Some("alice".into()),
+            },
+        );
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["reference"], reply.reference.as_str());
+        assert_eq!(events[0]["action"]["command"], "rate_limited");
+        let logged = events[0]["result"]["error"].as_str().unwrap();
+        assert!(logged.contains("Rate limit reached for org-abc"));
+    }
+
+    #[test]
+    fn configured_templates_replace_defaults() {
+        let presenter = ErrorPresenter::new(
+            ErrorMessagesConfig {
+                max_iterations: "Zu viele Schritte.".into(),
+                ..ErrorMessagesConfig::default()
+            },
+            None,
+        );
+        let reply = presenter.present(UserErrorKind::MaxIterations, &turn("exhausted"));
+        assert!(reply.text.starts_with("Zu viele Schritte. (ref: "));
+    }
+}
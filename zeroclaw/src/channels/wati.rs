@@ -1,7 +1,12 @@
+use super::splitting::split_message;
 use super::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
+use std::collections::HashMap;
 use uuid::Uuid;
 
+/// WATI relays to WhatsApp, which caps text messages at 4096 characters.
+const WATI_MAX_MESSAGE_LENGTH: usize = 4096;
+
 /// WATI WhatsApp Business API channel.
 ///
 /// This channel operates in webhook mode (push-based) rather than polling.
@@ -163,6 +168,8 @@ impl WatiChannel {
             channel: "wati".to_string(),
             timestamp,
             thread_ts: None,
+            sender_name: None,
+            metadata: HashMap::new(),
         });
 
         messages
@@ -177,28 +184,29 @@ impl Channel for WatiChannel {
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
         let target = self.build_target(&message.recipient);
-
-        let body = serde_json::json!({
-            "target": target,
-            "text": message.content
-        });
-
         let url = format!("{}/api/ext/v3/conversations/messages/text", self.api_url);
 
-        let resp = self
-            .client
-            .post(&url)
-            .bearer_auth(&self.api_token)
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
+        for chunk in split_message(&message.content, WATI_MAX_MESSAGE_LENGTH) {
+            let body = serde_json::json!({
+                "target": target,
+                "text": chunk
+            });
 
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let error_body = resp.text().await.unwrap_or_default();
-            tracing::error!("WATI send failed: {status} — {error_body}");
-            anyhow::bail!("WATI API error: {status}");
+            let resp = self
+                .client
+                .post(&url)
+                .bearer_auth(&self.api_token)
+                .header("Content-Type", "application/json")
+                .json(&body)
+                .send()
+                .await?;
+
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let error_body = resp.text().await.unwrap_or_default();
+                tracing::error!("WATI send failed: {status} — {error_body}");
+                anyhow::bail!("WATI API error: {status}");
+            }
         }
 
         Ok(())
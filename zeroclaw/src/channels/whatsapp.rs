@@ -1,15 +1,276 @@
+use super::formatting::{ChannelFormatter, WhatsAppFormatter};
+use super::send_retry::{
+    require_recipient, retry_after_header, send_chunks_with_retry, OutboundLimiter, SendError,
+};
+use super::splitting::split_message;
 use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::whatsapp_delivery::{within_session_window, DeliveryStatusStore};
+use crate::config::WhatsAppTemplateConfig;
+use anyhow::Context;
 use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 use uuid::Uuid;
 
+const WHATSAPP_GRAPH_API_BASE: &str = "https://graph.facebook.com/v18.0";
+/// Largest inbound media file downloaded from the Graph API (WhatsApp caps
+/// audio and images at 16 MB; documents can be larger and are skipped).
+const WHATSAPP_MAX_MEDIA_DOWNLOAD_BYTES: u64 = 20 * 1024 * 1024;
+/// The Cloud API rejects text message bodies longer than 4096 characters.
+const WHATSAPP_MAX_MESSAGE_LENGTH: usize = 4096;
+/// Template body parameters are limited to 1024 characters.
+const WHATSAPP_MAX_TEMPLATE_PARAMETER_LENGTH: usize = 1024;
+/// Health component that carries sends rejected by the 24-hour window rule.
+const WHATSAPP_DELIVERY_COMPONENT: &str = "whatsapp_delivery";
+
+/// Delivery receipt from the `statuses` array of a webhook payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhatsAppStatus {
+    /// Outbound message id (`wamid.…`) the receipt refers to.
+    pub message_id: String,
+    pub recipient: String,
+    /// `sent`, `delivered`, `read` or `failed`.
+    pub status: String,
+    pub timestamp: u64,
+    /// First error title for `failed` receipts.
+    pub error: Option<String>,
+}
+
+/// Everything a webhook payload carried, after replay filtering.
+#[derive(Debug, Default)]
+pub struct WhatsAppWebhookBatch {
+    pub messages: Vec<ChannelMessage>,
+    pub statuses: Vec<WhatsAppStatus>,
+    /// Messages and statuses dropped because their timestamp is older than
+    /// the configured event age.
+    pub skipped_stale: usize,
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Meta sends timestamps as decimal strings; accept bare numbers too.
+fn parse_timestamp(value: Option<&serde_json::Value>) -> Option<u64> {
+    let value = value?;
+    value
+        .as_str()
+        .and_then(|t| t.parse::<u64>().ok())
+        .or_else(|| value.as_u64())
+}
+
+/// Iterate the `value` objects of every `entry[].changes[]` in a payload.
+fn change_values(payload: &serde_json::Value) -> impl Iterator<Item = &serde_json::Value> {
+    payload
+        .get("entry")
+        .and_then(|e| e.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.get("changes").and_then(|c| c.as_array()))
+        .flatten()
+        .filter_map(|change| change.get("value"))
+}
+
+/// Kind of media attached to an inbound `WhatsApp` message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IncomingMediaKind {
+    Image,
+    Audio,
+    Voice,
+    Document,
+}
+
+impl IncomingMediaKind {
+    /// Content used when the media cannot be downloaded and there is no caption.
+    fn placeholder(self, filename: Option<&str>) -> String {
+        match (self, filename) {
+            (Self::Image, _) => "[image received]".to_string(),
+            (Self::Audio, _) => "[audio received]".to_string(),
+            (Self::Voice, _) => "[voice message received]".to_string(),
+            (Self::Document, Some(name)) => format!("[document received: {name}]"),
+            (Self::Document, None) => "[document received]".to_string(),
+        }
+    }
+
+    fn accepts_mime(self, mime: &str) -> bool {
+        match self {
+            Self::Image => mime.starts_with("image/"),
+            Self::Audio | Self::Voice => mime.starts_with("audio/"),
+            Self::Document => !mime.is_empty(),
+        }
+    }
+}
+
+/// Media reference parsed from a webhook message, resolved later through the
+/// Graph API media endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IncomingMedia {
+    media_id: String,
+    kind: IncomingMediaKind,
+    mime_type: Option<String>,
+    filename: Option<String>,
+    caption: Option<String>,
+}
+
+/// Extract the user-facing content and any media reference from one webhook
+/// message. Returns `None` for message types the agent does not handle.
+fn parse_message_body(msg: &serde_json::Value) -> Option<(String, Option<IncomingMedia>)> {
+    if let Some(text_obj) = msg.get("text") {
+        let body = text_obj.get("body").and_then(|b| b.as_str()).unwrap_or("");
+        return Some((body.to_string(), None));
+    }
+
+    if let Some(location) = msg.get("location") {
+        let latitude = location
+            .get("latitude")
+            .and_then(serde_json::Value::as_f64)?;
+        let longitude = location
+            .get("longitude")
+            .and_then(serde_json::Value::as_f64)?;
+        let mut content = format!("shared location: {latitude},{longitude}");
+        let label = ["name", "address"]
+            .iter()
+            .filter_map(|key| location.get(*key).and_then(|v| v.as_str()))
+            .filter(|v| !v.trim().is_empty())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !label.is_empty() {
+            content = format!("{content} ({label})");
+        }
+        return Some((content, None));
+    }
+
+    let (key, kind) = match msg.get("type").and_then(|t| t.as_str()) {
+        Some("image") => ("image", IncomingMediaKind::Image),
+        Some("document") => ("document", IncomingMediaKind::Document),
+        Some("voice") => ("voice", IncomingMediaKind::Voice),
+        Some("audio") => ("audio", IncomingMediaKind::Audio),
+        _ => return None,
+    };
+    let object = msg.get(key)?;
+    let media_id = object.get("id").and_then(|v| v.as_str())?.to_string();
+    // Push-to-talk notes arrive as `audio` with `voice: true`.
+    let kind = if kind == IncomingMediaKind::Audio
+        && object.get("voice").and_then(serde_json::Value::as_bool) == Some(true)
+    {
+        IncomingMediaKind::Voice
+    } else {
+        kind
+    };
+    let field = |name: &str| {
+        object
+            .get(name)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(String::from)
+    };
+    let media = IncomingMedia {
+        media_id,
+        kind,
+        mime_type: field("mime_type"),
+        filename: field("filename"),
+        caption: field("caption"),
+    };
+    let content = media
+        .caption
+        .clone()
+        .unwrap_or_else(|| kind.placeholder(media.filename.as_deref()));
+    Some((content, Some(media)))
+}
+
+/// File extension for a downloaded media file.
+fn media_extension(mime_type: Option<&str>, filename: Option<&str>) -> String {
+    let from_mime = match mime_type.map(|m| m.split(';').next().unwrap_or(m).trim()) {
+        Some("image/jpeg") => Some("jpg"),
+        Some("image/png") => Some("png"),
+        Some("image/webp") => Some("webp"),
+        Some("image/gif") => Some("gif"),
+        Some("audio/ogg") => Some("ogg"),
+        Some("audio/mpeg") => Some("mp3"),
+        Some("audio/mp4") => Some("m4a"),
+        Some("audio/aac") => Some("aac"),
+        Some("audio/amr") => Some("amr"),
+        Some("application/pdf") => Some("pdf"),
+        Some("text/plain") => Some("txt"),
+        _ => None,
+    };
+    if let Some(ext) = from_mime {
+        return ext.to_string();
+    }
+    filename
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .filter(|ext| {
+            !ext.is_empty() && ext.len() <= 8 && ext.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+        .unwrap_or_else(|| "bin".to_string())
+}
+
 /// `WhatsApp` channel — uses `WhatsApp` Business Cloud API
 ///
 /// This channel operates in webhook mode (push-based) rather than polling.
 /// Messages are received via the gateway's `/whatsapp` webhook endpoint.
 /// The `listen` method here is a no-op placeholder; actual message handling
 /// happens in the gateway when Meta sends webhook events.
+///
+/// Plain http is allowed only to the loopback interface (a local proxy or a
+/// test server), where the token never crosses the network.
+/// Cloud API payload for a template send. Body parameters substitute
+/// `{text}` and `{subject}` from the message; Meta rejects newlines and tabs
+/// inside parameters, so whitespace runs are collapsed to single spaces.
+fn template_payload(
+    to: &str,
+    template: &WhatsAppTemplateConfig,
+    message: &SendMessage,
+) -> serde_json::Value {
+    let text = WhatsAppFormatter.format(&message.content);
+    let subject = message.subject.as_deref().unwrap_or_default();
+    let parameters: Vec<serde_json::Value> = template
+        .body_parameters
+        .iter()
+        .map(|param| {
+            let value = param.replace("{text}", &text).replace("{subject}", subject);
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            serde_json::json!({
+                "type": "text",
+                "text": crate::util::truncate_with_ellipsis(
+                    &value,
+                    WHATSAPP_MAX_TEMPLATE_PARAMETER_LENGTH - 3,
+                ),
+            })
+        })
+        .collect();
+
+    let mut components = Vec::new();
+    if !parameters.is_empty() {
+        components.push(serde_json::json!({
+            "type": "body",
+            "parameters": parameters,
+        }));
+    }
+    serde_json::json!({
+        "messaging_product": "whatsapp",
+        "recipient_type": "individual",
+        "to": to,
+        "type": "template",
+        "template": {
+            "name": template.name,
+            "language": { "code": template.language },
+            "components": components,
+        }
+    })
+}
+
 fn ensure_https(url: &str) -> anyhow::Result<()> {
-    if !url.starts_with("https://") {
+    let loopback = ["http://127.0.0.1:", "http://localhost:", "http://[::1]:"]
+        .iter()
+        .any(|prefix| url.starts_with(prefix));
+    if !url.starts_with("https://") && !loopback {
         anyhow::bail!(
             "Refusing to transmit sensitive data over non-HTTPS URL: URL scheme must be https"
         );
@@ -27,6 +288,13 @@ pub struct WhatsAppChannel {
     endpoint_id: String,
     verify_token: String,
     allowed_numbers: Vec<String>,
+    api_base: String,
+    workspace_dir: Option<PathBuf>,
+    transcription: Option<crate::config::TranscriptionConfig>,
+    max_event_age_secs: u64,
+    outbound: OutboundLimiter,
+    session_window: Option<Arc<DeliveryStatusStore>>,
+    template: Option<WhatsAppTemplateConfig>,
 }
 
 impl WhatsAppChannel {
@@ -41,13 +309,164 @@ impl WhatsAppChannel {
             endpoint_id,
             verify_token,
             allowed_numbers,
+            api_base: WHATSAPP_GRAPH_API_BASE.to_string(),
+            workspace_dir: None,
+            transcription: None,
+            max_event_age_secs: 0,
+            outbound: OutboundLimiter::default(),
+            session_window: None,
+            template: None,
+        }
+    }
+
+    /// Track each sender's last inbound message in `store` and apply the
+    /// 24-hour customer service window to outbound sends. Without a store
+    /// every send is attempted as free-form text.
+    pub fn with_session_window(mut self, store: Arc<DeliveryStatusStore>) -> Self {
+        self.session_window = Some(store);
+        self
+    }
+
+    /// Template sent instead of text to recipients outside the window.
+    pub fn with_template(mut self, template: Option<WhatsAppTemplateConfig>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Whether free-form text may be sent to `to` right now. Unknown when no
+    /// store is configured or it cannot be read, in which case text is tried.
+    fn in_session_window(&self, to: &str) -> bool {
+        let Some(ref store) = self.session_window else {
+            return true;
+        };
+        match store.last_inbound(to) {
+            Ok(last_inbound) => within_session_window(last_inbound, unix_now()),
+            Err(e) => {
+                tracing::warn!("WhatsApp: cannot read session window for {to}: {e:#}");
+                true
+            }
         }
     }
 
+    /// Bound concurrent sends (`runtime.adapter_max_inflight`) and set the
+    /// retry jitter (`runtime.adapter_retry_jitter_ms`).
+    pub fn with_outbound_limits(mut self, max_inflight: usize, retry_jitter_ms: u64) -> Self {
+        self.outbound = OutboundLimiter::new(max_inflight, retry_jitter_ms);
+        self
+    }
+
+    /// Drop webhook messages and statuses whose timestamp is older than
+    /// `secs`, so a captured (validly signed) payload cannot be replayed
+    /// later. `0` disables the check.
+    pub fn with_max_event_age_secs(mut self, secs: u64) -> Self {
+        self.max_event_age_secs = secs;
+        self
+    }
+
+    fn is_stale(&self, timestamp: u64, now: u64) -> bool {
+        self.max_event_age_secs > 0 && now.saturating_sub(timestamp) > self.max_event_age_secs
+    }
+
+    /// Override the Graph API base URL (including the version segment).
+    /// Useful for testing.
+    pub fn with_api_base(mut self, api_base: String) -> Self {
+        self.api_base = api_base.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Configure workspace directory for saving downloaded media.
+    pub fn with_workspace_dir(mut self, dir: PathBuf) -> Self {
+        self.workspace_dir = Some(dir);
+        self
+    }
+
+    /// Configure voice transcription for audio and voice messages.
+    pub fn with_transcription(mut self, config: crate::config::TranscriptionConfig) -> Self {
+        if config.enabled {
+            self.transcription = Some(config);
+        }
+        self
+    }
+
     fn http_client(&self) -> reqwest::Client {
         crate::config::build_runtime_proxy_client("channel.whatsapp")
     }
 
+    /// One Cloud API text message attempt.
+    async fn send_text_once(&self, url: &str, to: &str, body: &str) -> Result<(), SendError> {
+        let payload = serde_json::json!({
+            "messaging_product": "whatsapp",
+            "recipient_type": "individual",
+            "to": to,
+            "type": "text",
+            "text": {
+                "preview_url": false,
+                "body": body
+            }
+        });
+        self.post_message_once(url, &payload).await
+    }
+
+    /// One Cloud API `/messages` request.
+    async fn post_message_once(
+        &self,
+        url: &str,
+        payload: &serde_json::Value,
+    ) -> Result<(), SendError> {
+        let resp = self
+            .http_client()
+            .post(url)
+            .bearer_auth(&self.access_token)
+            .header("Content-Type", "application/json")
+            .json(payload)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let retry_after = retry_after_header(resp.headers());
+            let error_body = resp.text().await.unwrap_or_default();
+            tracing::error!("WhatsApp send failed: {status} — {error_body}");
+            return Err(SendError::from_status(
+                status,
+                retry_after,
+                anyhow::anyhow!("WhatsApp API error: {status}"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Send `message` as the configured template because `to` is outside
+    /// the 24-hour window. Without a template the send fails and the
+    /// rejection is reported on the `whatsapp_delivery` health component.
+    async fn send_outside_window(
+        &self,
+        url: &str,
+        to: &str,
+        message: &SendMessage,
+    ) -> anyhow::Result<()> {
+        let Some(ref template) = self.template else {
+            let reason = format!(
+                "WhatsApp: {to} has not sent a message in the last 24 hours, so Meta only \
+                 accepts template messages; configure [channels_config.whatsapp.template] \
+                 to reach recipients outside the customer service window"
+            );
+            tracing::error!("{reason}");
+            crate::health::mark_component_error(WHATSAPP_DELIVERY_COMPONENT, &reason);
+            anyhow::bail!(reason);
+        };
+
+        let payload = template_payload(to, template, message);
+        send_chunks_with_retry(
+            "WhatsApp send template",
+            1,
+            std::time::Duration::ZERO,
+            self.outbound.retry_jitter_ms(),
+            |_| self.post_message_once(url, &payload),
+        )
+        .await
+    }
+
     /// Check if a phone number is allowed (E.164 format: +1234567890)
     fn is_number_allowed(&self, phone: &str) -> bool {
         self.allowed_numbers.iter().any(|n| n == "*" || n == phone)
@@ -58,8 +477,102 @@ impl WhatsAppChannel {
         &self.verify_token
     }
 
-    /// Parse an incoming webhook payload from Meta and extract messages
+    /// Parse an incoming webhook payload from Meta and extract messages.
+    ///
+    /// Media messages carry their caption (or a placeholder such as
+    /// `[image received]`); use [`Self::receive_webhook`] to also
+    /// download the media.
     pub fn parse_webhook_payload(&self, payload: &serde_json::Value) -> Vec<ChannelMessage> {
+        self.parse_inbound(payload)
+            .into_iter()
+            .map(|(message, _)| message)
+            .collect()
+    }
+
+    /// Parse messages and delivery receipts from a webhook payload, dropping
+    /// stale events before any media is downloaded. Images and documents are
+    /// saved under `{workspace}/whatsapp_files/` and referenced with the same
+    /// markers Telegram attachments use, and audio is transcribed when
+    /// transcription is configured. Media that cannot be fetched keeps its
+    /// caption or placeholder so the message is never dropped.
+    pub async fn receive_webhook(&self, payload: &serde_json::Value) -> WhatsAppWebhookBatch {
+        let now = unix_now();
+        let mut batch = WhatsAppWebhookBatch::default();
+
+        for status in self.parse_statuses(payload) {
+            if self.is_stale(status.timestamp, now) {
+                batch.skipped_stale += 1;
+            } else {
+                batch.statuses.push(status);
+            }
+        }
+
+        for (mut message, media) in self.parse_inbound(payload) {
+            if self.is_stale(message.timestamp, now) {
+                tracing::warn!(
+                    "WhatsApp: skipping stale message from {} (timestamp {})",
+                    message.sender,
+                    message.timestamp
+                );
+                batch.skipped_stale += 1;
+                continue;
+            }
+            if let Some(ref store) = self.session_window {
+                if let Err(e) = store.record_inbound(&message.sender, message.timestamp) {
+                    tracing::warn!("WhatsApp: failed to record session window: {e:#}");
+                }
+            }
+            if let Some(media) = media {
+                match self.resolve_media(&media).await {
+                    Ok(content) => message.content = content,
+                    Err(e) => tracing::warn!(
+                        "WhatsApp: keeping placeholder for media {}: {e:#}",
+                        media.media_id
+                    ),
+                }
+            }
+            batch.messages.push(message);
+        }
+
+        batch
+    }
+
+    /// Extract delivery receipts (`value.statuses[]`) from a webhook payload.
+    pub fn parse_statuses(&self, payload: &serde_json::Value) -> Vec<WhatsAppStatus> {
+        change_values(payload)
+            .filter_map(|value| value.get("statuses").and_then(|s| s.as_array()))
+            .flatten()
+            .filter_map(|status| {
+                let message_id = status.get("id").and_then(|v| v.as_str())?;
+                let state = status.get("status").and_then(|v| v.as_str())?;
+                Some(WhatsAppStatus {
+                    message_id: message_id.to_string(),
+                    recipient: status
+                        .get("recipient_id")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    status: state.to_string(),
+                    timestamp: parse_timestamp(status.get("timestamp")).unwrap_or_else(unix_now),
+                    error: status
+                        .get("errors")
+                        .and_then(|e| e.as_array())
+                        .and_then(|errors| errors.first())
+                        .and_then(|err| {
+                            err.get("title")
+                                .or_else(|| err.get("message"))
+                                .and_then(|v| v.as_str())
+                        })
+                        .map(ToString::to_string),
+                })
+            })
+            .collect()
+    }
+
+    fn parse_inbound(
+        &self,
+        payload: &serde_json::Value,
+    ) -> Vec<(ChannelMessage, Option<IncomingMedia>)> {
         let mut messages = Vec::new();
 
         // WhatsApp Cloud API webhook structure:
@@ -82,6 +595,18 @@ impl WhatsAppChannel {
                 let Some(msgs) = value.get("messages").and_then(|m| m.as_array()) else {
                     continue;
                 };
+                let profile_name = |wa_id: &str| {
+                    value
+                        .get("contacts")
+                        .and_then(|c| c.as_array())?
+                        .iter()
+                        .find(|c| c.get("wa_id").and_then(|id| id.as_str()) == Some(wa_id))?
+                        .pointer("/profile/name")
+                        .and_then(|n| n.as_str())
+                        .map(str::trim)
+                        .filter(|n| !n.is_empty())
+                        .map(str::to_string)
+                };
 
                 for msg in msgs {
                     // Get sender phone number
@@ -105,16 +630,8 @@ impl WhatsAppChannel {
                         continue;
                     }
 
-                    // Extract text content (support text messages only for now)
-                    let content = if let Some(text_obj) = msg.get("text") {
-                        text_obj
-                            .get("body")
-                            .and_then(|b| b.as_str())
-                            .unwrap_or("")
-                            .to_string()
-                    } else {
-                        // Could be image, audio, etc. — skip for now
-                        tracing::debug!("WhatsApp: skipping non-text message from {from}");
+                    let Some((content, media)) = parse_message_body(msg) else {
+                        tracing::debug!("WhatsApp: skipping unsupported message from {from}");
                         continue;
                     };
 
@@ -122,33 +639,147 @@ impl WhatsAppChannel {
                         continue;
                     }
 
-                    // Get timestamp
-                    let timestamp = msg
-                        .get("timestamp")
-                        .and_then(|t| t.as_str())
-                        .and_then(|t| t.parse::<u64>().ok())
-                        .unwrap_or_else(|| {
-                            std::time::SystemTime::now()
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .unwrap_or_default()
-                                .as_secs()
-                        });
-
-                    messages.push(ChannelMessage {
-                        id: Uuid::new_v4().to_string(),
+                    let timestamp = parse_timestamp(msg.get("timestamp")).unwrap_or_else(unix_now);
+
+                    let message = ChannelMessage {
+                        id: msg
+                            .get("id")
+                            .and_then(|id| id.as_str())
+                            .filter(|id| !id.is_empty())
+                            .map_or_else(|| Uuid::new_v4().to_string(), str::to_string),
                         reply_target: normalized_from.clone(),
                         sender: normalized_from,
                         content,
                         channel: "whatsapp".to_string(),
                         timestamp,
                         thread_ts: None,
-                    });
+                        sender_name: profile_name(from),
+                        metadata: HashMap::new(),
+                    };
+                    messages.push((message, media));
                 }
             }
         }
 
         messages
     }
+
+    /// Download `media` and build the message content for it.
+    async fn resolve_media(&self, media: &IncomingMedia) -> anyhow::Result<String> {
+        let (data, mime_type) = self.download_media(&media.media_id).await?;
+        let mime_type = mime_type
+            .or_else(|| media.mime_type.clone())
+            .unwrap_or_default();
+        if !media.kind.accepts_mime(&mime_type) {
+            anyhow::bail!("unexpected MIME type '{mime_type}' for {:?}", media.kind);
+        }
+
+        let ext = media_extension(Some(&mime_type), media.filename.as_deref());
+        let safe_id: String = media
+            .media_id
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+            .collect();
+        let local_filename = format!("whatsapp_{safe_id}.{ext}");
+
+        if matches!(
+            media.kind,
+            IncomingMediaKind::Audio | IncomingMediaKind::Voice
+        ) {
+            if let Some(config) = self.transcription.as_ref() {
+                match super::transcription::transcribe_audio(data.clone(), &local_filename, config)
+                    .await
+                {
+                    Ok(text) if !text.trim().is_empty() => {
+                        return Ok(format!("[Voice] {}", text.trim()));
+                    }
+                    Ok(_) => tracing::info!("WhatsApp: transcription returned empty text"),
+                    Err(e) => tracing::warn!("WhatsApp voice transcription failed: {e:#}"),
+                }
+            }
+        }
+
+        let workspace = self
+            .workspace_dir
+            .as_ref()
+            .context("workspace_dir not configured")?;
+        let save_dir = workspace.join("whatsapp_files");
+        tokio::fs::create_dir_all(&save_dir).await?;
+        let local_path = save_dir.join(&local_filename);
+        tokio::fs::write(&local_path, &data)
+            .await
+            .with_context(|| format!("failed to save media to {}", local_path.display()))?;
+
+        let mut content = match media.kind {
+            IncomingMediaKind::Image => format!("[IMAGE:{}]", local_path.display()),
+            IncomingMediaKind::Document => format!(
+                "[Document: {}] {}",
+                media.filename.as_deref().unwrap_or(&local_filename),
+                local_path.display()
+            ),
+            IncomingMediaKind::Audio | IncomingMediaKind::Voice => {
+                format!("[Audio] {}", local_path.display())
+            }
+        };
+        if let Some(caption) = &media.caption {
+            content.push_str("\n\n");
+            content.push_str(caption);
+        }
+        Ok(content)
+    }
+
+    /// Fetch a media object: `GET /{media_id}` returns a short-lived URL that
+    /// is then downloaded with the same bearer token.
+    async fn download_media(&self, media_id: &str) -> anyhow::Result<(Vec<u8>, Option<String>)> {
+        let client = self.http_client();
+        let meta_url = format!("{}/{media_id}", self.api_base);
+        let resp = client
+            .get(&meta_url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .context("failed to look up WhatsApp media")?;
+        if !resp.status().is_success() {
+            anyhow::bail!("WhatsApp media lookup failed: {}", resp.status());
+        }
+        let meta: serde_json::Value = resp.json().await?;
+        let url = meta
+            .get("url")
+            .and_then(|u| u.as_str())
+            .context("media lookup response has no url")?;
+        if let Some(size) = meta.get("file_size").and_then(serde_json::Value::as_u64) {
+            if size > WHATSAPP_MAX_MEDIA_DOWNLOAD_BYTES {
+                anyhow::bail!(
+                    "media size {size} bytes exceeds {} MB limit",
+                    WHATSAPP_MAX_MEDIA_DOWNLOAD_BYTES / (1024 * 1024)
+                );
+            }
+        }
+        // The token is sent along, so only follow HTTPS URLs (or the
+        // configured API host itself).
+        if !url.starts_with(&self.api_base) {
+            ensure_https(url)?;
+        }
+
+        let resp = client
+            .get(url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .context("failed to download WhatsApp media")?;
+        if !resp.status().is_success() {
+            anyhow::bail!("WhatsApp media download failed: {}", resp.status());
+        }
+        let data = resp.bytes().await?;
+        if data.len() as u64 > WHATSAPP_MAX_MEDIA_DOWNLOAD_BYTES {
+            anyhow::bail!("media exceeds download limit");
+        }
+        let mime_type = meta
+            .get("mime_type")
+            .and_then(|m| m.as_str())
+            .map(String::from);
+        Ok((data.to_vec(), mime_type))
+    }
 }
 
 #[async_trait]
@@ -159,47 +790,28 @@ impl Channel for WhatsAppChannel {
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
         // WhatsApp Cloud API: POST to /v18.0/{phone_number_id}/messages
-        let url = format!(
-            "https://graph.facebook.com/v18.0/{}/messages",
-            self.endpoint_id
-        );
+        let url = format!("{}/{}/messages", self.api_base, self.endpoint_id);
 
         // Normalize recipient (remove leading + if present for API)
-        let to = message
-            .recipient
-            .strip_prefix('+')
-            .unwrap_or(&message.recipient);
-
-        let body = serde_json::json!({
-            "messaging_product": "whatsapp",
-            "recipient_type": "individual",
-            "to": to,
-            "type": "text",
-            "text": {
-                "preview_url": false,
-                "body": message.content
-            }
-        });
+        let recipient = require_recipient("whatsapp", message)?;
+        let to = recipient.strip_prefix('+').unwrap_or(recipient);
 
         ensure_https(&url)?;
 
-        let resp = self
-            .http_client()
-            .post(&url)
-            .bearer_auth(&self.access_token)
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
-
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let error_body = resp.text().await.unwrap_or_default();
-            tracing::error!("WhatsApp send failed: {status} — {error_body}");
-            anyhow::bail!("WhatsApp API error: {status}");
+        let _permit = self.outbound.acquire_for(to).await;
+        if !self.in_session_window(to) {
+            return self.send_outside_window(&url, to, message).await;
         }
-
-        Ok(())
+        let text = WhatsAppFormatter.format(&message.content);
+        let chunks = split_message(&text, WHATSAPP_MAX_MESSAGE_LENGTH);
+        send_chunks_with_retry(
+            "WhatsApp send message",
+            chunks.len(),
+            std::time::Duration::ZERO,
+            self.outbound.retry_jitter_ms(),
+            |index| self.send_text_once(&url, to, &chunks[index]),
+        )
+        .await
     }
 
     async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
@@ -219,7 +831,7 @@ impl Channel for WhatsAppChannel {
 
     async fn health_check(&self) -> bool {
         // Check if we can reach the WhatsApp API
-        let url = format!("https://graph.facebook.com/v18.0/{}", self.endpoint_id);
+        let url = format!("{}/{}", self.api_base, self.endpoint_id);
 
         if ensure_https(&url).is_err() {
             return false;
@@ -248,6 +860,158 @@ mod tests {
         )
     }
 
+    #[tokio::test]
+    async fn outbound_path_conforms_to_adapter_contract() {
+        crate::channels::conformance::check_outbound_contract(
+            |api_base, max_inflight| {
+                std::sync::Arc::new(
+                    make_channel()
+                        .with_api_base(api_base.into())
+                        .with_outbound_limits(max_inflight, 0),
+                )
+            },
+            ["+1234567890", "+1234567891", "+1234567892"],
+        )
+        .await;
+    }
+
+    #[test]
+    fn template_payload_fills_body_parameters() {
+        let template = WhatsAppTemplateConfig {
+            name: "reminder".into(),
+            language: "de".into(),
+            body_parameters: vec!["{subject}".into(), "{text}".into(), "fixed".into()],
+        };
+        let message =
+            SendMessage::with_subject("Water the plants\n\ttoday", "15551234567", "Reminder");
+
+        let payload = template_payload("15551234567", &template, &message);
+        assert_eq!(payload["type"], "template");
+        assert_eq!(payload["to"], "15551234567");
+        assert!(payload.get("text").is_none());
+        assert_eq!(payload["template"]["name"], "reminder");
+        assert_eq!(payload["template"]["language"]["code"], "de");
+        let params = &payload["template"]["components"][0]["parameters"];
+        assert_eq!(payload["template"]["components"][0]["type"], "body");
+        assert_eq!(params[0]["text"], "Reminder");
+        assert_eq!(params[1]["text"], "Water the plants today");
+        assert_eq!(params[2]["text"], "fixed");
+
+        let long = SendMessage::new("x".repeat(5000), "15551234567");
+        let payload = template_payload("15551234567", &template, &long);
+        let text = payload["template"]["components"][0]["parameters"][1]["text"]
+            .as_str()
+            .unwrap();
+        assert_eq!(text.chars().count(), WHATSAPP_MAX_TEMPLATE_PARAMETER_LENGTH);
+
+        let no_params = WhatsAppTemplateConfig {
+            body_parameters: Vec::new(),
+            ..template
+        };
+        let payload = template_payload("15551234567", &no_params, &long);
+        assert_eq!(payload["template"]["components"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn send_outside_window_without_template_reports_failure() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let store = Arc::new(DeliveryStatusStore::open(tmp.path()).unwrap());
+        let ch = make_channel()
+            .with_api_base("https://graph.invalid".into())
+            .with_session_window(store);
+
+        let err = ch
+            .send(&SendMessage::new("Daily reminder", "+1234567890"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("24 hours"), "{err}");
+        let health = crate::health::snapshot();
+        let component = &health.components[WHATSAPP_DELIVERY_COMPONENT];
+        assert_eq!(component.status, "error");
+        assert!(component
+            .last_error
+            .as_deref()
+            .is_some_and(|e| e.contains("1234567890")));
+    }
+
+    #[tokio::test]
+    async fn inbound_webhook_messages_open_the_session_window() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let store = Arc::new(DeliveryStatusStore::open(tmp.path()).unwrap());
+        let ch = make_channel().with_session_window(Arc::clone(&store));
+        let now = unix_now();
+        let payload = serde_json::json!({
+            "entry": [{
+                "changes": [{
+                    "value": {
+                        "messages": [{
+                            "from": "1234567890",
+                            "id": "wamid.window",
+                            "timestamp": now.to_string(),
+                            "type": "text",
+                            "text": { "body": "hi" }
+                        }]
+                    }
+                }]
+            }]
+        });
+
+        assert!(!ch.in_session_window("1234567890"));
+        let batch = ch.receive_webhook(&payload).await;
+        assert_eq!(batch.messages.len(), 1);
+        assert_eq!(store.last_inbound("1234567890").unwrap(), Some(now));
+        assert!(ch.in_session_window("1234567890"));
+        assert!(make_channel().in_session_window("1234567890"));
+    }
+
+    #[test]
+    fn https_is_required_except_on_loopback() {
+        assert!(ensure_https("https://graph.facebook.com/v18.0/1/messages").is_ok());
+        assert!(ensure_https("http://127.0.0.1:8080/1/messages").is_ok());
+        assert!(ensure_https("http://graph.facebook.com/v18.0/1/messages").is_err());
+        assert!(ensure_https("http://127.0.0.1.evil.example/1/messages").is_err());
+    }
+
+    #[test]
+    fn whatsapp_parse_statuses_extracts_receipts_and_errors() {
+        let ch = make_channel();
+        let payload = serde_json::json!({
+            "entry": [{
+                "changes": [{
+                    "value": {
+                        "statuses": [
+                            { "id": "wamid.1", "status": "delivered", "timestamp": "1700000000", "recipient_id": "15551234567" },
+                            { "id": "wamid.2", "status": "failed", "timestamp": 1_700_000_001,
+                              "errors": [{ "code": 131_047, "title": "Re-engagement message" }] },
+                            { "status": "read" }
+                        ]
+                    }
+                }]
+            }]
+        });
+        let statuses = ch.parse_statuses(&payload);
+        assert_eq!(statuses.len(), 2);
+        assert_eq!(statuses[0].message_id, "wamid.1");
+        assert_eq!(statuses[0].timestamp, 1_700_000_000);
+        assert_eq!(statuses[0].recipient, "15551234567");
+        assert_eq!(statuses[1].status, "failed");
+        assert_eq!(statuses[1].timestamp, 1_700_000_001);
+        assert_eq!(statuses[1].error.as_deref(), Some("Re-engagement message"));
+        assert!(ch.parse_webhook_payload(&payload).is_empty());
+    }
+
+    #[test]
+    fn whatsapp_event_age_check_is_opt_in() {
+        let ch = make_channel();
+        assert!(!ch.is_stale(0, 1_700_000_000));
+
+        let ch = make_channel().with_max_event_age_secs(300);
+        assert!(!ch.is_stale(1_700_000_000 - 300, 1_700_000_000));
+        assert!(ch.is_stale(1_700_000_000 - 301, 1_700_000_000));
+        // Clock skew into the future is not treated as stale.
+        assert!(!ch.is_stale(1_700_000_100, 1_700_000_000));
+    }
+
     #[test]
     fn whatsapp_channel_name() {
         let ch = make_channel();
@@ -323,6 +1087,34 @@ mod tests {
         assert_eq!(msgs[0].content, "Hello ZeroClaw!");
         assert_eq!(msgs[0].channel, "whatsapp");
         assert_eq!(msgs[0].timestamp, 1_699_999_999);
+        assert_eq!(msgs[0].sender_name, None);
+    }
+
+    #[test]
+    fn whatsapp_parse_attaches_contact_profile_name() {
+        let ch = make_channel();
+        let payload = serde_json::json!({
+            "entry": [{
+                "changes": [{
+                    "value": {
+                        "contacts": [
+                            { "wa_id": "1999", "profile": { "name": "Someone Else" } },
+                            { "wa_id": "1234567890", "profile": { "name": "Alice" } }
+                        ],
+                        "messages": [{
+                            "from": "1234567890",
+                            "id": "wamid.named",
+                            "type": "text",
+                            "text": { "body": "hi" }
+                        }]
+                    }
+                }]
+            }]
+        });
+
+        let msgs = ch.parse_webhook_payload(&payload);
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].sender_name.as_deref(), Some("Alice"));
     }
 
     #[test]
@@ -349,7 +1141,7 @@ mod tests {
     }
 
     #[test]
-    fn whatsapp_parse_non_text_message_skipped() {
+    fn whatsapp_parse_image_message_uses_placeholder() {
         let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
         let payload = serde_json::json!({
             "entry": [{
@@ -367,7 +1159,8 @@ mod tests {
         });
 
         let msgs = ch.parse_webhook_payload(&payload);
-        assert!(msgs.is_empty(), "Non-text messages should be skipped");
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].content, "[image received]");
     }
 
     #[test]
@@ -736,7 +1529,7 @@ mod tests {
     }
 
     #[test]
-    fn whatsapp_parse_audio_message_skipped() {
+    fn whatsapp_parse_audio_message_uses_placeholder() {
         let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
         let payload = serde_json::json!({
             "entry": [{
@@ -753,7 +1546,8 @@ mod tests {
             }]
         });
         let msgs = ch.parse_webhook_payload(&payload);
-        assert!(msgs.is_empty());
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].content, "[audio received]");
     }
 
     #[test]
@@ -778,7 +1572,7 @@ mod tests {
     }
 
     #[test]
-    fn whatsapp_parse_document_message_skipped() {
+    fn whatsapp_parse_document_message_uses_placeholder() {
         let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
         let payload = serde_json::json!({
             "entry": [{
@@ -795,7 +1589,8 @@ mod tests {
             }]
         });
         let msgs = ch.parse_webhook_payload(&payload);
-        assert!(msgs.is_empty());
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].content, "[document received: file.pdf]");
     }
 
     #[test]
@@ -820,7 +1615,7 @@ mod tests {
     }
 
     #[test]
-    fn whatsapp_parse_location_message_skipped() {
+    fn whatsapp_parse_location_message_as_text() {
         let ch = WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()]);
         let payload = serde_json::json!({
             "entry": [{
@@ -837,7 +1632,8 @@ mod tests {
             }]
         });
         let msgs = ch.parse_webhook_payload(&payload);
-        assert!(msgs.is_empty());
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].content, "shared location: 40.7128,-74.006");
     }
 
     #[test]
@@ -1135,4 +1931,43 @@ mod tests {
             "<script>alert('xss')</script> & \"quotes\" 'apostrophe'"
         );
     }
+
+    #[test]
+    fn whatsapp_parse_media_body_variants() {
+        let (content, media) = parse_message_body(&serde_json::json!({
+            "type": "image",
+            "image": { "id": "img1", "mime_type": "image/jpeg", "caption": "my receipt" }
+        }))
+        .unwrap();
+        assert_eq!(content, "my receipt");
+        let media = media.unwrap();
+        assert_eq!(media.kind, IncomingMediaKind::Image);
+        assert_eq!(media.mime_type.as_deref(), Some("image/jpeg"));
+
+        let (content, media) = parse_message_body(&serde_json::json!({
+            "type": "audio",
+            "audio": { "id": "ptt1", "mime_type": "audio/ogg; codecs=opus", "voice": true }
+        }))
+        .unwrap();
+        assert_eq!(content, "[voice message received]");
+        assert_eq!(media.unwrap().kind, IncomingMediaKind::Voice);
+
+        let (content, media) = parse_message_body(&serde_json::json!({
+            "type": "location",
+            "location": { "latitude": 51.5, "longitude": -0.12, "name": "Office" }
+        }))
+        .unwrap();
+        assert_eq!(content, "shared location: 51.5,-0.12 (Office)");
+        assert!(media.is_none());
+    }
+
+    #[test]
+    fn whatsapp_media_extension_prefers_mime_type() {
+        assert_eq!(media_extension(Some("audio/ogg; codecs=opus"), None), "ogg");
+        assert_eq!(media_extension(None, Some("Report.DOCX")), "docx");
+        assert_eq!(
+            media_extension(Some("application/x-unknown"), Some("../../x")),
+            "bin"
+        );
+    }
 }
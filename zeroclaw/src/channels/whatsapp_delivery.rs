@@ -0,0 +1,223 @@
+//! Delivery status of outbound `WhatsApp` Cloud API messages.
+//!
+//! Meta reports `sent` / `delivered` / `read` / `failed` receipts through the
+//! `statuses` array of the same webhook that carries inbound messages. The
+//! latest state per message id is kept in `state/whatsapp_delivery.db`.
+//! Receipts can arrive out of order, so a status never moves backwards
+//! (a late `delivered` does not overwrite `read`).
+//!
+//! The same database keeps the time of each sender's latest inbound message,
+//! which decides whether free-form text may still be sent to them (see
+//! [`within_session_window`]).
+
+use super::whatsapp::WhatsAppStatus;
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+
+pub struct DeliveryStatusStore {
+    conn: Mutex<Connection>,
+}
+
+pub fn delivery_db_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("state").join("whatsapp_delivery.db")
+}
+
+/// Meta only accepts free-form messages within 24 hours of the user's last
+/// message (the customer service window); outside it, templates are required.
+pub const SESSION_WINDOW_SECS: u64 = 24 * 60 * 60;
+
+/// Whether a message sent at `now` falls inside the customer service window
+/// opened by the recipient's last inbound message.
+pub fn within_session_window(last_inbound: Option<u64>, now: u64) -> bool {
+    last_inbound.is_some_and(|at| now.saturating_sub(at) < SESSION_WINDOW_SECS)
+}
+
+/// Recipients are stored without the leading `+` the inbound path adds.
+fn normalize_number(number: &str) -> &str {
+    number.strip_prefix('+').unwrap_or(number)
+}
+
+/// Ordering used to ignore receipts that arrive after a later state.
+fn status_rank(status: &str) -> i64 {
+    match status {
+        "sent" => 1,
+        "delivered" => 2,
+        "read" => 3,
+        "failed" => 4,
+        _ => 0,
+    }
+}
+
+impl DeliveryStatusStore {
+    pub fn open(workspace_dir: &Path) -> Result<Self> {
+        let db_path = delivery_db_path(workspace_dir);
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create state directory: {}", parent.display())
+            })?;
+        }
+        let conn = Connection::open(&db_path)
+            .with_context(|| format!("Failed to open delivery status db: {}", db_path.display()))?;
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA busy_timeout = 5000;
+             CREATE TABLE IF NOT EXISTS delivery_status (
+                message_id  TEXT PRIMARY KEY,
+                recipient   TEXT NOT NULL,
+                status      TEXT NOT NULL,
+                status_rank INTEGER NOT NULL,
+                timestamp   INTEGER NOT NULL,
+                error       TEXT
+            );
+             CREATE TABLE IF NOT EXISTS inbound_window (
+                number       TEXT PRIMARY KEY,
+                last_inbound INTEGER NOT NULL
+            );",
+        )
+        .context("Failed to initialize delivery status schema")?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Store a receipt. Returns `false` when an equal or later status is
+    /// already recorded for the message.
+    pub fn record(&self, status: &WhatsAppStatus) -> Result<bool> {
+        let timestamp = i64::try_from(status.timestamp).unwrap_or(i64::MAX);
+        let changed = self.conn.lock().execute(
+            "INSERT INTO delivery_status (message_id, recipient, status, status_rank, timestamp, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(message_id) DO UPDATE SET
+                status = excluded.status,
+                status_rank = excluded.status_rank,
+                timestamp = excluded.timestamp,
+                error = excluded.error
+             WHERE excluded.status_rank > delivery_status.status_rank",
+            params![
+                status.message_id,
+                status.recipient,
+                status.status,
+                status_rank(&status.status),
+                timestamp,
+                status.error,
+            ],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Latest recorded status for an outbound message id.
+    pub fn get(&self, message_id: &str) -> Result<Option<WhatsAppStatus>> {
+        self.conn
+            .lock()
+            .query_row(
+                "SELECT message_id, recipient, status, timestamp, error
+                 FROM delivery_status WHERE message_id = ?1",
+                params![message_id],
+                |row| {
+                    let timestamp: i64 = row.get(3)?;
+                    Ok(WhatsAppStatus {
+                        message_id: row.get(0)?,
+                        recipient: row.get(1)?,
+                        status: row.get(2)?,
+                        timestamp: u64::try_from(timestamp).unwrap_or(0),
+                        error: row.get(4)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(Into::into)
+    }
+
+    /// Note an inbound message from `sender`. An older timestamp never
+    /// replaces a newer one.
+    pub fn record_inbound(&self, sender: &str, timestamp: u64) -> Result<()> {
+        let timestamp = i64::try_from(timestamp).unwrap_or(i64::MAX);
+        self.conn.lock().execute(
+            "INSERT INTO inbound_window (number, last_inbound) VALUES (?1, ?2)
+             ON CONFLICT(number) DO UPDATE SET last_inbound = excluded.last_inbound
+             WHERE excluded.last_inbound > inbound_window.last_inbound",
+            params![normalize_number(sender), timestamp],
+        )?;
+        Ok(())
+    }
+
+    /// Time of the latest inbound message from `number`, if any.
+    pub fn last_inbound(&self, number: &str) -> Result<Option<u64>> {
+        let timestamp: Option<i64> = self
+            .conn
+            .lock()
+            .query_row(
+                "SELECT last_inbound FROM inbound_window WHERE number = ?1",
+                params![normalize_number(number)],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(timestamp.map(|t| u64::try_from(t).unwrap_or(0)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn receipt(status: &str, timestamp: u64) -> WhatsAppStatus {
+        WhatsAppStatus {
+            message_id: "wamid.abc".into(),
+            recipient: "15551234567".into(),
+            status: status.into(),
+            timestamp,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn status_only_moves_forward() {
+        let tmp = TempDir::new().unwrap();
+        let store = DeliveryStatusStore::open(tmp.path()).unwrap();
+
+        assert!(store.record(&receipt("sent", 1)).unwrap());
+        assert!(store.record(&receipt("read", 3)).unwrap());
+        assert!(!store.record(&receipt("delivered", 2)).unwrap());
+        assert!(!store.record(&receipt("read", 4)).unwrap());
+
+        let stored = store.get("wamid.abc").unwrap().unwrap();
+        assert_eq!(stored.status, "read");
+        assert_eq!(stored.timestamp, 3);
+        assert!(store.get("wamid.missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn failed_receipt_keeps_error() {
+        let tmp = TempDir::new().unwrap();
+        let store = DeliveryStatusStore::open(tmp.path()).unwrap();
+        store.record(&receipt("sent", 1)).unwrap();
+        let mut failed = receipt("failed", 2);
+        failed.error = Some("Message undeliverable".into());
+        assert!(store.record(&failed).unwrap());
+
+        let reopened = DeliveryStatusStore::open(tmp.path()).unwrap();
+        let stored = reopened.get("wamid.abc").unwrap().unwrap();
+        assert_eq!(stored.status, "failed");
+        assert_eq!(stored.error.as_deref(), Some("Message undeliverable"));
+    }
+
+    #[test]
+    fn session_window_follows_latest_inbound_message() {
+        let tmp = TempDir::new().unwrap();
+        let store = DeliveryStatusStore::open(tmp.path()).unwrap();
+        assert_eq!(store.last_inbound("15551234567").unwrap(), None);
+        assert!(!within_session_window(None, 1_000));
+
+        store.record_inbound("+15551234567", 1_000).unwrap();
+        store.record_inbound("15551234567", 500).unwrap();
+        let last = store.last_inbound("+15551234567").unwrap();
+        assert_eq!(last, Some(1_000));
+
+        assert!(within_session_window(last, 1_000));
+        assert!(within_session_window(last, 1_000 + SESSION_WINDOW_SECS - 1));
+        assert!(!within_session_window(last, 1_000 + SESSION_WINDOW_SECS));
+    }
+}
@@ -0,0 +1,575 @@
+//! Semantic config checks for `zeroclaw config check` and server startup.
+//!
+//! [`Config::validate`] rejects values that cannot be parsed into a working
+//! runtime; this module additionally catches settings that load fine but
+//! misbehave later (a WhatsApp token without a phone number id, a public
+//! gateway without pairing, ...). Each finding names the offending key and
+//! says how to fix it.
+
+use super::Config;
+use crate::security::pairing::is_public_bind;
+use anyhow::Result;
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+
+/// Webhook secrets shorter than this are easy to brute-force.
+const MIN_WEBHOOK_SECRET_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    /// Startup is refused.
+    Error,
+    /// Startup continues; the issue is logged.
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigIssue {
+    pub severity: IssueSeverity,
+    /// Dotted config key the issue is about (e.g. `channels_config.whatsapp.phone_number_id`).
+    pub path: String,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn error(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Error,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Warning,
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == IssueSeverity::Error
+    }
+}
+
+impl Config {
+    /// Collect every config problem instead of stopping at the first one.
+    pub fn check(&self) -> Vec<ConfigIssue> {
+        let mut issues = Vec::new();
+        if let Err(e) = self.validate() {
+            issues.push(ConfigIssue::error("config", format!("{e:#}")));
+        }
+        check_provider(self, &mut issues);
+        check_gateway(self, &mut issues);
+        check_channels(self, &mut issues);
+        check_workspace(&self.workspace_dir, &mut issues);
+        issues
+    }
+}
+
+fn provider_error(name: &str) -> Option<String> {
+    crate::providers::create_provider(name, None)
+        .err()
+        .map(|e| {
+            e.to_string()
+                .lines()
+                .next()
+                .unwrap_or("unknown provider")
+                .to_string()
+        })
+}
+
+fn check_provider(config: &Config, issues: &mut Vec<ConfigIssue>) {
+    if !(0.0..=2.0).contains(&config.default_temperature) {
+        issues.push(ConfigIssue::error(
+            "default_temperature",
+            format!(
+                "{} is out of range; use a value between 0.0 and 2.0",
+                config.default_temperature
+            ),
+        ));
+    }
+
+    // Credentials may still come from the environment or an auth profile at
+    // runtime, so a provider that cannot be built here is only a warning.
+    match config.default_provider.as_deref() {
+        Some(provider) => {
+            if let Some(reason) = provider_error(provider) {
+                issues.push(ConfigIssue::warning(
+                    "default_provider",
+                    format!("\"{provider}\" is not a usable provider: {reason}. Run `zeroclaw providers` for the list."),
+                ));
+            }
+        }
+        None => issues.push(ConfigIssue::error(
+            "default_provider",
+            "no provider configured; set default_provider (e.g. \"openrouter\")",
+        )),
+    }
+
+    for (i, route) in config.model_routes.iter().enumerate() {
+        if let Some(reason) = provider_error(&route.provider) {
+            issues.push(ConfigIssue::warning(
+                format!("model_routes[{i}].provider"),
+                format!(
+                    "route \"{}\" references provider \"{}\", which is not configured: {reason}",
+                    route.hint, route.provider
+                ),
+            ));
+        }
+    }
+
+    for fallback in &config.reliability.fallback_providers {
+        if let Some(reason) = provider_error(fallback) {
+            issues.push(ConfigIssue::warning(
+                "reliability.fallback_providers",
+                format!("fallback provider \"{fallback}\" will be skipped: {reason}"),
+            ));
+        }
+    }
+}
+
+fn check_gateway(config: &Config, issues: &mut Vec<ConfigIssue>) {
+    let gateway = &config.gateway;
+    if is_public_bind(&gateway.host) && !gateway.require_pairing {
+        issues.push(ConfigIssue::error(
+            "gateway.require_pairing",
+            format!(
+                "gateway binds {} with pairing disabled, so anyone who can reach it can drive the agent; set require_pairing = true or bind 127.0.0.1",
+                gateway.host
+            ),
+        ));
+    }
+
+    if let Some(secret) = config
+        .channels_config
+        .webhook
+        .as_ref()
+        .and_then(|webhook| webhook.secret.as_deref())
+        .map(str::trim)
+        .filter(|secret| !secret.is_empty())
+    {
+        if secret.chars().count() < MIN_WEBHOOK_SECRET_LEN {
+            issues.push(ConfigIssue::warning(
+                "channels_config.webhook.secret",
+                format!(
+                    "secret is shorter than {MIN_WEBHOOK_SECRET_LEN} characters; use a longer random value"
+                ),
+            ));
+        }
+    }
+}
+
+fn is_blank(value: Option<&str>) -> bool {
+    value.map_or(true, |v| v.trim().is_empty())
+}
+
+fn check_channels(config: &Config, issues: &mut Vec<ConfigIssue>) {
+    let channels = &config.channels_config;
+
+    if let Some(wa) = channels.whatsapp.as_ref() {
+        let path = "channels_config.whatsapp";
+        let has_token = !is_blank(wa.access_token.as_deref());
+        let has_phone = !is_blank(wa.phone_number_id.as_deref());
+        if has_token && !has_phone && wa.session_path.is_none() {
+            issues.push(ConfigIssue::error(
+                format!("{path}.phone_number_id"),
+                "access_token is set but phone_number_id is missing; Cloud API sends would fail. Copy it from Meta Business → WhatsApp → API Setup",
+            ));
+        }
+        if has_phone && !has_token {
+            issues.push(ConfigIssue::error(
+                format!("{path}.access_token"),
+                "phone_number_id is set but access_token is missing",
+            ));
+        }
+        if has_phone && is_blank(wa.verify_token.as_deref()) {
+            issues.push(ConfigIssue::error(
+                format!("{path}.verify_token"),
+                "Cloud API mode needs verify_token for the GET /whatsapp webhook handshake",
+            ));
+        }
+        if wa.is_cloud_config()
+            && is_blank(wa.app_secret.as_deref())
+            && is_blank(
+                std::env::var("ZEROCLAW_WHATSAPP_APP_SECRET")
+                    .ok()
+                    .as_deref(),
+            )
+        {
+            issues.push(ConfigIssue::warning(
+                format!("{path}.app_secret"),
+                "no app_secret; inbound webhook signatures (X-Hub-Signature-256) are not verified",
+            ));
+        }
+        if wa.is_ambiguous_config() {
+            issues.push(ConfigIssue::warning(
+                path,
+                "both phone_number_id and session_path are set; Cloud API mode wins and session_path is ignored",
+            ));
+        }
+    }
+
+    if let Some(linq) = channels.linq.as_ref() {
+        if linq.api_token.trim().is_empty() {
+            issues.push(ConfigIssue::error(
+                "channels_config.linq.api_token",
+                "api_token is empty",
+            ));
+        }
+        if is_blank(linq.signing_secret.as_deref())
+            && is_blank(
+                std::env::var("ZEROCLAW_LINQ_SIGNING_SECRET")
+                    .ok()
+                    .as_deref(),
+            )
+        {
+            issues.push(ConfigIssue::warning(
+                "channels_config.linq.signing_secret",
+                "no signing_secret; inbound webhook signatures are not verified",
+            ));
+        }
+    }
+
+    if let Some(wati) = channels.wati.as_ref() {
+        if wati.api_token.trim().is_empty() {
+            issues.push(ConfigIssue::error(
+                "channels_config.wati.api_token",
+                "api_token is empty",
+            ));
+        }
+    }
+
+    if let Some(nc) = channels.nextcloud_talk.as_ref() {
+        match reqwest::Url::parse(nc.base_url.trim()) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            _ => issues.push(ConfigIssue::error(
+                "channels_config.nextcloud_talk.base_url",
+                format!(
+                    "\"{}\" is not an http(s) URL (e.g. https://cloud.example.com)",
+                    nc.base_url
+                ),
+            )),
+        }
+        if is_blank(nc.webhook_secret.as_deref())
+            && is_blank(
+                std::env::var("ZEROCLAW_NEXTCLOUD_TALK_WEBHOOK_SECRET")
+                    .ok()
+                    .as_deref(),
+            )
+        {
+            issues.push(ConfigIssue::warning(
+                "channels_config.nextcloud_talk.webhook_secret",
+                "no webhook_secret; inbound webhook signatures are not verified",
+            ));
+        }
+    }
+
+    if let Some(gc) = channels.google_chat.as_ref() {
+        if gc.project_number.trim().is_empty() {
+            issues.push(ConfigIssue::error(
+                "channels_config.google_chat.project_number",
+                "project_number is required to verify Google Chat bearer tokens",
+            ));
+        }
+        if let Some(key) = gc.service_account_json.as_deref() {
+            if let Err(e) = crate::auth::google_service_account::ServiceAccountKey::load(key) {
+                issues.push(ConfigIssue::error(
+                    "channels_config.google_chat.service_account_json",
+                    format!("{e:#}"),
+                ));
+            }
+        }
+    }
+}
+
+fn check_workspace(workspace_dir: &Path, issues: &mut Vec<ConfigIssue>) {
+    if !workspace_dir.is_dir() {
+        issues.push(ConfigIssue::error(
+            "workspace_dir",
+            format!(
+                "{} does not exist or is not a directory",
+                workspace_dir.display()
+            ),
+        ));
+        return;
+    }
+
+    let probe = workspace_dir.join(format!(".zeroclaw_config_check_{}", std::process::id()));
+    let writable = std::fs::File::create(&probe).and_then(|mut file| file.write_all(b"probe"));
+    let _ = std::fs::remove_file(&probe);
+    if let Err(e) = writable {
+        issues.push(ConfigIssue::error(
+            "workspace_dir",
+            format!("{} is not writable: {e}", workspace_dir.display()),
+        ));
+    }
+}
+
+/// Human-readable report used by `zeroclaw config check`.
+pub fn render_report(issues: &[ConfigIssue]) -> String {
+    use std::fmt::Write as _;
+
+    let mut out = String::new();
+    if issues.is_empty() {
+        out.push_str("✅ Configuration looks good.\n");
+        return out;
+    }
+    for issue in issues {
+        let icon = if issue.is_error() { "❌" } else { "⚠️ " };
+        let _ = writeln!(out, "{icon} {}: {}", issue.path, issue.message);
+    }
+    let errors = issues.iter().filter(|i| i.is_error()).count();
+    let _ = writeln!(
+        out,
+        "\nSummary: {errors} error(s), {} warning(s)",
+        issues.len() - errors
+    );
+    out
+}
+
+/// Startup gate for the gateway and daemon: log warnings, refuse to start on
+/// errors. `host` and `port` are the address actually bound, which CLI flags
+/// may override, so the gateway checks run against them.
+pub fn ensure_startable(config: &Config, host: &str, port: u16) -> Result<()> {
+    let mut bound = config.clone();
+    bound.gateway.host = host.to_string();
+    bound.gateway.port = port;
+    let issues = bound.check();
+    for issue in issues.iter().filter(|i| !i.is_error()) {
+        tracing::warn!("config: {}: {}", issue.path, issue.message);
+    }
+    let errors: Vec<String> = issues
+        .iter()
+        .filter(|i| i.is_error())
+        .map(|i| format!("  - {}: {}", i.path, i.message))
+        .collect();
+    if !errors.is_empty() {
+        anyhow::bail!(
+            "Refusing to start: {} config error(s):\n{}\nRun `zeroclaw config check` for details.",
+            errors.len(),
+            errors.join("\n")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::{
+        GoogleChatConfig, LinqConfig, ModelRouteConfig, NextcloudTalkConfig, WebhookConfig,
+        WhatsAppConfig,
+    };
+    use tempfile::TempDir;
+
+    fn fixture(tmp: &TempDir) -> Config {
+        Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        }
+    }
+
+    fn paths(issues: &[ConfigIssue], severity: IssueSeverity) -> Vec<String> {
+        issues
+            .iter()
+            .filter(|i| i.severity == severity)
+            .map(|i| i.path.clone())
+            .collect()
+    }
+
+    fn whatsapp(token: Option<&str>, phone: Option<&str>) -> WhatsAppConfig {
+        WhatsAppConfig {
+            access_token: token.map(Into::into),
+            phone_number_id: phone.map(Into::into),
+            verify_token: Some("verify".into()),
+            app_secret: Some("app-secret".into()),
+            session_path: None,
+            pair_phone: None,
+            pair_code: None,
+            allowed_numbers: vec!["*".into()],
+            max_event_age_secs: 3600,
+            template: None,
+        }
+    }
+
+    #[test]
+    fn default_config_in_writable_workspace_is_clean() {
+        let tmp = TempDir::new().unwrap();
+        let issues = fixture(&tmp).check();
+        assert!(issues.is_empty(), "{issues:?}");
+        assert_eq!(render_report(&issues), "✅ Configuration looks good.\n");
+        assert!(ensure_startable(&fixture(&tmp), "127.0.0.1", 42617).is_ok());
+    }
+
+    #[test]
+    fn whatsapp_token_without_phone_number_id_is_error() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = fixture(&tmp);
+        config.channels_config.whatsapp = Some(whatsapp(Some("token"), None));
+        let issues = config.check();
+        assert_eq!(
+            paths(&issues, IssueSeverity::Error),
+            vec!["channels_config.whatsapp.phone_number_id"]
+        );
+
+        config.channels_config.whatsapp = Some(whatsapp(None, Some("123")));
+        assert_eq!(
+            paths(&config.check(), IssueSeverity::Error),
+            vec!["channels_config.whatsapp.access_token"]
+        );
+    }
+
+    #[test]
+    fn whatsapp_cloud_without_app_secret_warns() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = fixture(&tmp);
+        let mut wa = whatsapp(Some("token"), Some("123"));
+        wa.app_secret = None;
+        config.channels_config.whatsapp = Some(wa);
+        if std::env::var("ZEROCLAW_WHATSAPP_APP_SECRET").is_ok() {
+            return;
+        }
+        let issues = config.check();
+        assert!(paths(&issues, IssueSeverity::Error).is_empty());
+        assert_eq!(
+            paths(&issues, IssueSeverity::Warning),
+            vec!["channels_config.whatsapp.app_secret"]
+        );
+    }
+
+    #[test]
+    fn unknown_default_provider_and_route_provider_are_warnings() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = fixture(&tmp);
+        config.default_provider = Some("definitely-not-a-provider".into());
+        config.model_routes = vec![ModelRouteConfig {
+            hint: "fast".into(),
+            provider: "also-not-a-provider".into(),
+            model: "m".into(),
+            api_key: None,
+        }];
+        let issues = config.check();
+        assert!(paths(&issues, IssueSeverity::Error).is_empty());
+        assert_eq!(
+            paths(&issues, IssueSeverity::Warning),
+            vec!["default_provider", "model_routes[0].provider"]
+        );
+        assert!(ensure_startable(&config, "127.0.0.1", 42617).is_ok());
+    }
+
+    #[test]
+    fn temperature_out_of_range_is_error() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = fixture(&tmp);
+        config.default_temperature = 3.5;
+        assert_eq!(
+            paths(&config.check(), IssueSeverity::Error),
+            vec!["default_temperature"]
+        );
+    }
+
+    #[test]
+    fn public_bind_without_pairing_is_error() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = fixture(&tmp);
+        config.gateway.host = "0.0.0.0".into();
+        config.gateway.require_pairing = false;
+        assert_eq!(
+            paths(&config.check(), IssueSeverity::Error),
+            vec!["gateway.require_pairing"]
+        );
+
+        config.gateway.require_pairing = true;
+        assert!(config.check().is_empty());
+    }
+
+    #[test]
+    fn startup_checks_the_bound_address_not_the_configured_one() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = fixture(&tmp);
+        config.gateway.host = "127.0.0.1".into();
+        config.gateway.require_pairing = false;
+
+        let err = ensure_startable(&config, "0.0.0.0", 8080)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("gateway.require_pairing"));
+        assert!(err.contains("0.0.0.0"));
+
+        config.gateway.host = "0.0.0.0".into();
+        assert!(ensure_startable(&config, "127.0.0.1", 8080).is_ok());
+    }
+
+    #[test]
+    fn short_webhook_secret_warns() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = fixture(&tmp);
+        config.channels_config.webhook = Some(WebhookConfig {
+            port: 8080,
+            secret: Some("short".into()),
+        });
+        assert_eq!(
+            paths(&config.check(), IssueSeverity::Warning),
+            vec!["channels_config.webhook.secret"]
+        );
+    }
+
+    #[test]
+    fn webhook_channels_report_missing_credentials() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = fixture(&tmp);
+        config.channels_config.linq = Some(LinqConfig {
+            api_token: String::new(),
+            from_phone: "+15550001111".into(),
+            signing_secret: Some("signing".into()),
+            allowed_senders: vec![],
+        });
+        config.channels_config.nextcloud_talk = Some(NextcloudTalkConfig {
+            base_url: "cloud.example.com".into(),
+            app_token: "token".into(),
+            webhook_secret: Some("secret".into()),
+            allowed_users: vec![],
+        });
+        config.channels_config.google_chat = Some(GoogleChatConfig {
+            project_number: " ".into(),
+            allowed_users: vec![],
+            service_account_json: None,
+        });
+        assert_eq!(
+            paths(&config.check(), IssueSeverity::Error),
+            vec![
+                "channels_config.linq.api_token",
+                "channels_config.nextcloud_talk.base_url",
+                "channels_config.google_chat.project_number",
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_workspace_is_error_and_blocks_startup() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = fixture(&tmp);
+        config.workspace_dir = tmp.path().join("missing");
+        let issues = config.check();
+        assert_eq!(paths(&issues, IssueSeverity::Error), vec!["workspace_dir"]);
+
+        let err = ensure_startable(&config, "127.0.0.1", 42617)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Refusing to start: 1 config error(s)"));
+        assert!(render_report(&issues).contains("Summary: 1 error(s), 0 warning(s)"));
+    }
+
+    #[test]
+    fn issues_serialize_with_lowercase_severity() {
+        let issue = ConfigIssue::warning("a.b", "msg");
+        assert_eq!(
+            serde_json::to_value(&issue).unwrap(),
+            serde_json::json!({"severity": "warning", "path": "a.b", "message": "msg"})
+        );
+    }
+}
@@ -1,4 +1,6 @@
+pub mod check;
 pub mod schema;
+pub mod secret_refs;
 pub mod traits;
 
 #[allow(unused_imports)]
@@ -6,17 +8,22 @@ pub use schema::{
     apply_runtime_proxy_to_builder, build_runtime_proxy_client,
     build_runtime_proxy_client_with_timeouts, runtime_proxy_config, set_runtime_proxy_config,
     AgentConfig, AuditConfig, AutonomyConfig, BrowserComputerUseConfig, BrowserConfig,
-    BuiltinHooksConfig, ChannelsConfig, ClassificationRule, ComposioConfig, Config, CostConfig,
-    CronConfig, DelegateAgentConfig, DiscordConfig, DockerRuntimeConfig, EmbeddingRouteConfig,
-    EstopConfig, FeishuConfig, GatewayConfig, HardwareConfig, HardwareTransport, HeartbeatConfig,
-    HooksConfig, HttpRequestConfig, IMessageConfig, IdentityConfig, LarkConfig, MatrixConfig,
-    MemoryConfig, ModelRouteConfig, MultimodalConfig, NextcloudTalkConfig, ObservabilityConfig,
-    OtpConfig, OtpMethod, PeripheralBoardConfig, PeripheralsConfig, ProxyConfig, ProxyScope,
-    QdrantConfig, QueryClassificationConfig, ReliabilityConfig, ResourceLimitsConfig,
-    RuntimeConfig, SandboxBackend, SandboxConfig, SchedulerConfig, SecretsConfig, SecurityConfig,
-    SkillsConfig, SkillsPromptInjectionMode, SlackConfig, StorageConfig, StorageProviderConfig,
-    StorageProviderSection, StreamMode, TelegramConfig, TranscriptionConfig, TunnelConfig,
-    WebFetchConfig, WebSearchConfig, WebhookConfig,
+    BuiltinHooksConfig, ChannelOverrideConfig, ChannelsConfig, ClassificationRule,
+    CommandFilterMode, ComposioConfig, Config, CostConfig, CronConfig, DelegateAgentConfig,
+    DiscordConfig, DockerRuntimeConfig, EmbeddingRouteConfig, ErrorMessagesConfig, EstopConfig,
+    FeishuConfig, GatewayConfig, GatewayLogStreamConfig, GatewayTlsConfig, GoogleChatConfig,
+    GoogleSheetsConfig, HardwareConfig, HardwareTransport, HeartbeatConfig, HooksConfig,
+    HttpRequestConfig, IMessageConfig, IdentityConfig, InboundGuardConfig, LarkConfig,
+    MatrixConfig, MemoryConfig, ModelRouteConfig, MultimodalConfig, NextcloudTalkConfig,
+    ObservabilityConfig, OpenRouterConfig, OtpConfig, OtpMethod, PeripheralBoardConfig,
+    PeripheralsConfig, ProxyConfig, ProxyScope, QdrantConfig, QueryClassificationConfig,
+    QuietHoursBehavior, QuietHoursConfig, QuietHoursOverride, RedactionConfig, RedactionMode,
+    ReliabilityConfig, ResourceLimitsConfig, RuntimeConfig, SandboxBackend, SandboxConfig,
+    SchedulerConfig, SecretsConfig, SecurityConfig, SessionCompactionConfig, SkillsConfig,
+    SkillsPromptInjectionMode, SlackConfig, StorageConfig, StorageProviderConfig,
+    StorageProviderSection, StreamMode, SubtaskConfig, TelegramConfig, TranscriptionConfig,
+    TunnelConfig, WebConfig, WebFetchConfig, WebSearchConfig, WebhookConfig,
+    WhatsAppTemplateConfig,
 };
 
 pub fn name_and_presence<T: traits::ChannelConfig>(channel: &Option<T>) -> (&'static str, bool) {
@@ -45,6 +52,8 @@ mod tests {
             draft_update_interval_ms: 1000,
             interrupt_on_new_message: false,
             mention_only: false,
+            command_prefix: None,
+            progress_messages: false,
         };
 
         let discord = DiscordConfig {
@@ -53,6 +62,8 @@ mod tests {
             allowed_users: vec![],
             listen_to_bots: false,
             mention_only: false,
+            command_prefix: None,
+            command_users: vec![],
         };
 
         let lark = LarkConfig {
@@ -25,6 +25,7 @@ const SUPPORTED_PROXY_SERVICE_KEYS: &[&str] = &[
     "channel.dingtalk",
     "channel.discord",
     "channel.feishu",
+    "channel.google_chat",
     "channel.lark",
     "channel.matrix",
     "channel.mattermost",
@@ -62,7 +63,10 @@ static RUNTIME_PROXY_CLIENT_CACHE: OnceLock<RwLock<HashMap<String, reqwest::Clie
 /// Top-level ZeroClaw configuration, loaded from `config.toml`.
 ///
 /// Resolution order: `ZEROCLAW_WORKSPACE` env → `active_workspace.toml` marker → `~/.zeroclaw/config.toml`.
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+///
+/// String values may be written as `"env:VAR_NAME"` or `"file:/path"`; see
+/// [`super::secret_refs`]. `Debug` output redacts credentials.
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Config {
     /// Workspace directory - computed from home, not serialized
     #[serde(skip)]
@@ -70,6 +74,10 @@ pub struct Config {
     /// Path to config.toml - computed from home, not serialized
     #[serde(skip)]
     pub config_path: PathBuf,
+    /// `env:` / `file:` references resolved at load, restored on save
+    #[serde(skip)]
+    #[schemars(skip)]
+    pub secret_refs: Vec<super::secret_refs::SecretRef>,
     /// API key for the selected provider. Overridden by `ZEROCLAW_API_KEY` or `API_KEY` env vars.
     pub api_key: Option<String>,
     /// Base URL override for provider API (e.g. "http://10.0.0.1:11434" for remote Ollama)
@@ -106,6 +114,10 @@ pub struct Config {
     #[serde(default)]
     pub reliability: ReliabilityConfig,
 
+    /// OpenRouter attribution headers and model routing (`[openrouter]`).
+    #[serde(default)]
+    pub openrouter: OpenRouterConfig,
+
     /// Scheduler configuration for periodic task execution (`[scheduler]`).
     #[serde(default)]
     pub scheduler: SchedulerConfig,
@@ -138,6 +150,15 @@ pub struct Config {
     #[serde(default)]
     pub cron: CronConfig,
 
+    /// Quiet hours for agent-initiated messages (`[quiet_hours]`).
+    #[serde(default)]
+    pub quiet_hours: QuietHoursConfig,
+
+    /// IANA timezone (e.g. `Europe/Lisbon`) used to interpret wall-clock
+    /// times such as "tomorrow 9am". Defaults to the host's local timezone.
+    #[serde(default)]
+    pub timezone: Option<String>,
+
     /// Channel configurations: Telegram, Discord, Slack, etc. (`[channels_config]`).
     #[serde(default)]
     pub channels_config: ChannelsConfig,
@@ -146,6 +167,10 @@ pub struct Config {
     #[serde(default)]
     pub memory: MemoryConfig,
 
+    /// Background summarize-and-trim of idle channel sessions (`[session_compaction]`).
+    #[serde(default)]
+    pub session_compaction: SessionCompactionConfig,
+
     /// Persistent storage provider configuration (`[storage]`).
     #[serde(default)]
     pub storage: StorageConfig,
@@ -162,6 +187,10 @@ pub struct Config {
     #[serde(default)]
     pub composio: ComposioConfig,
 
+    /// Google Sheets memory tool (`[google_sheets]`). Unset: tool not registered.
+    #[serde(default)]
+    pub google_sheets: Option<GoogleSheetsConfig>,
+
     /// Secrets encryption configuration (`[secrets]`).
     #[serde(default)]
     pub secrets: SecretsConfig,
@@ -186,6 +215,10 @@ pub struct Config {
     #[serde(default)]
     pub web_search: WebSearchConfig,
 
+    /// Web dashboard configuration (`[web]`).
+    #[serde(default)]
+    pub web: WebConfig,
+
     /// Proxy configuration for outbound HTTP/HTTPS/SOCKS5 traffic (`[proxy]`).
     #[serde(default)]
     pub proxy: ProxyConfig,
@@ -219,6 +252,76 @@ pub struct Config {
     pub transcription: TranscriptionConfig,
 }
 
+/// Key fragments whose string values are treated as credentials in `Debug` output.
+const SECRET_KEY_MARKERS: &[&str] = &[
+    "key",
+    "token",
+    "secret",
+    "password",
+    "passwd",
+    "credential",
+    "db_url",
+];
+
+fn redact_secret_values(value: &mut toml::Value) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, child) in table.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if SECRET_KEY_MARKERS.iter().any(|marker| key.contains(marker)) {
+                    redact_strings(child);
+                } else {
+                    redact_secret_values(child);
+                }
+            }
+        }
+        toml::Value::Array(items) => items.iter_mut().for_each(redact_secret_values),
+        _ => {}
+    }
+}
+
+fn redact_strings(value: &mut toml::Value) {
+    match value {
+        toml::Value::String(s) if !s.is_empty() => *s = "[REDACTED]".to_string(),
+        toml::Value::Table(table) => table.iter_mut().for_each(|(_, v)| redact_strings(v)),
+        toml::Value::Array(items) => items.iter_mut().for_each(redact_strings),
+        _ => {}
+    }
+}
+
+/// Prints TOML values inline (`"x"`, `{ a = 1 }`) rather than as `toml::Value` variants.
+struct TomlDisplay<'a>(&'a toml::Value);
+
+impl std::fmt::Debug for TomlDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.0, f)
+    }
+}
+
+impl std::fmt::Debug for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut out = f.debug_struct("Config");
+        out.field("workspace_dir", &self.workspace_dir)
+            .field("config_path", &self.config_path);
+        let Ok(mut value) = toml::Value::try_from(self) else {
+            return out.finish_non_exhaustive();
+        };
+        redact_secret_values(&mut value);
+        // Values that came from env:/file: references are secrets regardless of key name.
+        for secret_ref in &self.secret_refs {
+            if let Some(node) = super::secret_refs::lookup_mut(&mut value, &secret_ref.path) {
+                redact_strings(node);
+            }
+        }
+        if let toml::Value::Table(table) = &value {
+            for (key, child) in table {
+                out.field(key, &TomlDisplay(child));
+            }
+        }
+        out.finish()
+    }
+}
+
 /// Named provider profile definition compatible with Codex app-server style config.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ModelProviderConfig {
@@ -411,6 +514,111 @@ pub struct AgentConfig {
     /// Tool dispatch strategy (e.g. `"auto"`). Default: `"auto"`.
     #[serde(default = "default_agent_tool_dispatcher")]
     pub tool_dispatcher: String,
+    /// Characters kept from each tool result of an earlier loop iteration
+    /// when the conversation is re-sent to the provider. `0` disables.
+    /// Default: `2048`.
+    #[serde(default = "default_agent_tool_result_pack_chars")]
+    pub tool_result_pack_chars: usize,
+    /// Estimated token ceiling for one provider request during a tool loop;
+    /// the oldest tool exchanges are dropped beyond it. Set it below the
+    /// model's context window. `0` disables. Default: `100000`.
+    #[serde(default = "default_agent_max_context_tokens")]
+    pub max_context_tokens: usize,
+    /// Context window of the model in tokens, used to report context
+    /// utilization per turn. `0` uses a built-in table of common models.
+    #[serde(default)]
+    pub context_window: usize,
+    /// Per-channel prompt and sampling overrides, keyed by channel name
+    /// (`telegram`, `whatsapp`, `discord`, ...; `cron` and `heartbeat` for
+    /// scheduled runs): `[agent.channel_overrides.whatsapp]`.
+    ///
+    /// Precedence for a turn: session settings, then the channel override,
+    /// then the global defaults.
+    #[serde(default)]
+    pub channel_overrides: HashMap<String, ChannelOverrideConfig>,
+    /// Limits of sub-tasks started with the `spawn_subtask` tool
+    /// (`[agent.subtasks]`).
+    #[serde(default)]
+    pub subtasks: SubtaskConfig,
+}
+
+/// Sub-task limits (`[agent.subtasks]`). Budgets the model asks for are
+/// clamped to these.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SubtaskConfig {
+    /// Register the `spawn_subtask` tool. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// LLM calls one sub-task may make. Default: `6`.
+    #[serde(default = "default_subtask_max_iterations")]
+    pub max_iterations: usize,
+    /// Tokens (input + output, estimated when the provider reports none)
+    /// one sub-task may use. Default: `40000`.
+    #[serde(default = "default_subtask_max_tokens")]
+    pub max_tokens: u64,
+    /// Wall-clock limit of one sub-task in seconds. Default: `300`.
+    #[serde(default = "default_subtask_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_subtask_max_iterations() -> usize {
+    6
+}
+
+fn default_subtask_max_tokens() -> u64 {
+    40_000
+}
+
+fn default_subtask_timeout_secs() -> u64 {
+    300
+}
+
+impl Default for SubtaskConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_iterations: default_subtask_max_iterations(),
+            max_tokens: default_subtask_max_tokens(),
+            timeout_secs: default_subtask_timeout_secs(),
+        }
+    }
+}
+
+/// Overrides applied to every turn on one channel (`[agent.channel_overrides.<channel>]`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ChannelOverrideConfig {
+    /// Appended to the system prompt for this channel.
+    #[serde(default)]
+    pub system_prompt_suffix: Option<String>,
+    /// Output token cap. Recorded for providers that accept one; the agent
+    /// loop does not forward it yet.
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// Sampling temperature for this channel.
+    #[serde(default)]
+    pub temperature: Option<f64>,
+}
+
+impl AgentConfig {
+    /// Override configured for `channel`, if any.
+    pub fn channel_override(&self, channel: &str) -> Option<&ChannelOverrideConfig> {
+        self.channel_overrides.get(channel)
+    }
+}
+
+impl ChannelOverrideConfig {
+    /// `base` with `system_prompt_suffix` appended as a channel section.
+    pub fn system_prompt(&self, base: &str) -> String {
+        match self
+            .system_prompt_suffix
+            .as_deref()
+            .map(str::trim)
+            .filter(|suffix| !suffix.is_empty())
+        {
+            Some(suffix) => format!("{base}\n\n## Channel Instructions\n\n{suffix}\n"),
+            None => base.to_string(),
+        }
+    }
 }
 
 fn default_agent_max_tool_iterations() -> usize {
@@ -425,6 +633,14 @@ fn default_agent_tool_dispatcher() -> String {
     "auto".into()
 }
 
+fn default_agent_tool_result_pack_chars() -> usize {
+    2048
+}
+
+fn default_agent_max_context_tokens() -> usize {
+    100_000
+}
+
 impl Default for AgentConfig {
     fn default() -> Self {
         Self {
@@ -433,6 +649,11 @@ impl Default for AgentConfig {
             max_history_messages: default_agent_max_history_messages(),
             parallel_tools: false,
             tool_dispatcher: default_agent_tool_dispatcher(),
+            tool_result_pack_chars: default_agent_tool_result_pack_chars(),
+            max_context_tokens: default_agent_max_context_tokens(),
+            context_window: 0,
+            channel_overrides: HashMap::new(),
+            subtasks: SubtaskConfig::default(),
         }
     }
 }
@@ -471,6 +692,30 @@ pub struct SkillsConfig {
     /// `full` preserves legacy behavior. `compact` keeps context small and loads skills on demand.
     #[serde(default)]
     pub prompt_injection_mode: SkillsPromptInjectionMode,
+    /// Watch the workspace skills directory and rebuild the channel system prompt
+    /// when skills are added, changed, or removed. Default: `false`.
+    #[serde(default)]
+    pub hot_reload: bool,
+    /// Polling interval for the skills watcher, in seconds. Default: `10`.
+    #[serde(default = "default_skills_hot_reload_interval_secs")]
+    pub hot_reload_interval_secs: u64,
+    /// Hard cap, in seconds, on the shell timeout a skill may request through
+    /// `permissions.max_exec_timeout`. Default: `600`.
+    #[serde(default = "default_skills_max_exec_timeout_secs")]
+    pub max_exec_timeout_secs: u64,
+    /// Allow skills to request `fs_scope = "system"` (file access outside the
+    /// workspace). When `false` such requests fall back to the workspace scope.
+    /// Default: `false`.
+    #[serde(default)]
+    pub allow_system_fs_scope: bool,
+}
+
+fn default_skills_hot_reload_interval_secs() -> u64 {
+    10
+}
+
+fn default_skills_max_exec_timeout_secs() -> u64 {
+    600
 }
 
 impl Default for SkillsConfig {
@@ -479,6 +724,10 @@ impl Default for SkillsConfig {
             open_skills_enabled: false,
             open_skills_dir: None,
             prompt_injection_mode: SkillsPromptInjectionMode::default(),
+            hot_reload: false,
+            hot_reload_interval_secs: default_skills_hot_reload_interval_secs(),
+            max_exec_timeout_secs: default_skills_max_exec_timeout_secs(),
+            allow_system_fs_scope: false,
         }
     }
 }
@@ -495,12 +744,26 @@ pub struct MultimodalConfig {
     /// Allow fetching remote image URLs (http/https). Disabled by default.
     #[serde(default)]
     pub allow_remote_fetch: bool,
+    /// Longest edge, in pixels, that `screenshot` downscales captures to.
+    #[serde(default = "default_multimodal_screenshot_max_dimension")]
+    pub screenshot_max_dimension: u32,
+    /// JPEG quality (1-100) used when re-encoding screenshots.
+    #[serde(default = "default_multimodal_screenshot_jpeg_quality")]
+    pub screenshot_jpeg_quality: u8,
 }
 
 fn default_multimodal_max_images() -> usize {
     4
 }
 
+fn default_multimodal_screenshot_max_dimension() -> u32 {
+    768
+}
+
+fn default_multimodal_screenshot_jpeg_quality() -> u8 {
+    80
+}
+
 fn default_multimodal_max_image_size_mb() -> usize {
     5
 }
@@ -520,6 +783,8 @@ impl Default for MultimodalConfig {
             max_images: default_multimodal_max_images(),
             max_image_size_mb: default_multimodal_max_image_size_mb(),
             allow_remote_fetch: false,
+            screenshot_max_dimension: default_multimodal_screenshot_max_dimension(),
+            screenshot_jpeg_quality: default_multimodal_screenshot_jpeg_quality(),
         }
     }
 }
@@ -759,6 +1024,7 @@ impl Default for PeripheralBoardConfig {
 ///
 /// Controls the HTTP gateway for webhook and pairing endpoints.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct GatewayConfig {
     /// Gateway port (default: 42617)
     #[serde(default = "default_gateway_port")]
@@ -784,6 +1050,11 @@ pub struct GatewayConfig {
     #[serde(default = "default_webhook_rate_limit")]
     pub webhook_rate_limit_per_minute: u32,
 
+    /// Max inbound platform messages per minute per channel + sender
+    /// (`/whatsapp`, `/linq`, `/wati`, `/nextcloud-talk`). `0` disables the limit.
+    #[serde(default = "default_inbound_rate_limit")]
+    pub inbound_rate_limit_per_minute: u32,
+
     /// Trust proxy-forwarded client IP headers (`X-Forwarded-For`, `X-Real-IP`).
     /// Disabled by default; enable only behind a trusted reverse proxy.
     #[serde(default)]
@@ -800,6 +1071,79 @@ pub struct GatewayConfig {
     /// Maximum distinct idempotency keys retained in memory.
     #[serde(default = "default_gateway_idempotency_max_keys")]
     pub idempotency_max_keys: usize,
+
+    /// Agent turns the gateway runs concurrently across all inbound endpoints.
+    #[serde(default = "default_gateway_max_concurrent_turns")]
+    pub max_concurrent_turns: usize,
+
+    /// Inbound messages allowed to wait for a free turn. Beyond this,
+    /// `/webhook` answers `503` and platform webhooks send a busy reply.
+    #[serde(default = "default_gateway_inbound_queue_capacity")]
+    pub inbound_queue_capacity: usize,
+
+    /// Serve HTTPS with this certificate instead of plaintext HTTP.
+    #[serde(default)]
+    pub tls: Option<GatewayTlsConfig>,
+
+    /// Allow a non-loopback bind with pairing disabled, leaving every
+    /// endpoint unauthenticated on the network (default: false).
+    #[serde(default)]
+    pub allow_insecure: bool,
+
+    /// Hours an upload from `POST /api/attachment` stays readable.
+    #[serde(default = "default_gateway_attachment_retention_hours")]
+    pub attachment_retention_hours: u64,
+
+    /// Forward runtime log records to `/api/events` (`[gateway.log_stream]`).
+    #[serde(default)]
+    pub log_stream: GatewayLogStreamConfig,
+}
+
+/// Live log forwarding onto the gateway event stream (`[gateway.log_stream]`).
+///
+/// WARN and ERROR records are published as `system_log` events; INFO
+/// records are forwarded only for the listed targets.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct GatewayLogStreamConfig {
+    /// Publish log records as `system_log` events. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Target prefixes whose INFO records are also forwarded.
+    #[serde(default = "default_log_stream_info_targets")]
+    pub info_targets: Vec<String>,
+    /// Records published per second; the rest are dropped and counted.
+    #[serde(default = "default_log_stream_max_per_second")]
+    pub max_per_second: u32,
+}
+
+fn default_log_stream_info_targets() -> Vec<String> {
+    vec!["audit".into()]
+}
+
+fn default_log_stream_max_per_second() -> u32 {
+    20
+}
+
+impl Default for GatewayLogStreamConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            info_targets: default_log_stream_info_targets(),
+            max_per_second: default_log_stream_max_per_second(),
+        }
+    }
+}
+
+/// TLS termination for the gateway (`[gateway.tls]`).
+///
+/// PEM files are re-read when they change on disk, so renewed certificates
+/// are picked up without a restart.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct GatewayTlsConfig {
+    /// PEM certificate chain, leaf first.
+    pub cert_path: String,
+    /// PEM private key (PKCS#8, PKCS#1 or SEC1).
+    pub key_path: String,
 }
 
 fn default_gateway_port() -> u16 {
@@ -818,6 +1162,10 @@ fn default_webhook_rate_limit() -> u32 {
     60
 }
 
+fn default_inbound_rate_limit() -> u32 {
+    30
+}
+
 fn default_idempotency_ttl_secs() -> u64 {
     300
 }
@@ -830,6 +1178,18 @@ fn default_gateway_idempotency_max_keys() -> usize {
     10_000
 }
 
+fn default_gateway_max_concurrent_turns() -> usize {
+    4
+}
+
+fn default_gateway_inbound_queue_capacity() -> usize {
+    32
+}
+
+fn default_gateway_attachment_retention_hours() -> u64 {
+    24
+}
+
 fn default_true() -> bool {
     true
 }
@@ -844,10 +1204,17 @@ impl Default for GatewayConfig {
             paired_tokens: Vec::new(),
             pair_rate_limit_per_minute: default_pair_rate_limit(),
             webhook_rate_limit_per_minute: default_webhook_rate_limit(),
+            inbound_rate_limit_per_minute: default_inbound_rate_limit(),
             trust_forwarded_headers: false,
             rate_limit_max_keys: default_gateway_rate_limit_max_keys(),
             idempotency_ttl_secs: default_idempotency_ttl_secs(),
             idempotency_max_keys: default_gateway_idempotency_max_keys(),
+            max_concurrent_turns: default_gateway_max_concurrent_turns(),
+            inbound_queue_capacity: default_gateway_inbound_queue_capacity(),
+            tls: None,
+            allow_insecure: false,
+            attachment_retention_hours: default_gateway_attachment_retention_hours(),
+            log_stream: GatewayLogStreamConfig::default(),
         }
     }
 }
@@ -884,6 +1251,30 @@ impl Default for ComposioConfig {
     }
 }
 
+// ── Google Sheets (structured memory) ───────────────────────────
+
+/// Google Sheets memory configuration (`[google_sheets]` section).
+///
+/// Registers the `sheets_memory` tool, which appends, reads and searches rows
+/// in one spreadsheet the user can also edit from the Sheets UI.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GoogleSheetsConfig {
+    /// Spreadsheet ID (the long key in the spreadsheet URL)
+    pub spreadsheet_id: String,
+    /// OAuth access token with the `spreadsheets` scope (stored encrypted when
+    /// secrets.encrypt = true). Falls back to `GOOGLE_SHEETS_ACCESS_TOKEN`.
+    #[serde(default)]
+    pub access_token: Option<String>,
+    /// Client-side cap on reads and on writes per minute, matching Google's
+    /// default per-user quota. `0` disables the cap.
+    #[serde(default = "default_sheets_requests_per_minute")]
+    pub requests_per_minute: u32,
+}
+
+fn default_sheets_requests_per_minute() -> u32 {
+    60
+}
+
 // ── Secrets (encrypted credential store) ────────────────────────
 
 /// Secrets encryption configuration (`[secrets]` section).
@@ -1093,6 +1484,35 @@ impl Default for WebFetchConfig {
     }
 }
 
+// ── Web dashboard ────────────────────────────────────────────────
+
+/// Web dashboard configuration (`[web]` section).
+///
+/// The gateway serves the dashboard bundled into the binary. Setting
+/// `dashboard_dir` serves a separately built dashboard from disk instead.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebConfig {
+    /// Directory containing a built dashboard (`index.html`, `assets/`),
+    /// served read-only at `/`, `/assets/*` and `/_app/*`. Relative paths
+    /// resolve from the workspace. Unset: the bundled dashboard is served.
+    #[serde(default)]
+    pub dashboard_dir: Option<String>,
+    /// Add a sanitized `content_html` rendering of assistant replies to
+    /// `/ws/events` and `/ws/chat` messages (default: true). Raw `content`
+    /// is always sent unchanged.
+    #[serde(default = "default_true")]
+    pub sanitize_output: bool,
+}
+
+impl Default for WebConfig {
+    fn default() -> Self {
+        Self {
+            dashboard_dir: None,
+            sanitize_output: true,
+        }
+    }
+}
+
 // ── Web search ───────────────────────────────────────────────────
 
 /// Web search tool configuration (`[web_search]` section).
@@ -1113,6 +1533,10 @@ pub struct WebSearchConfig {
     /// Request timeout in seconds
     #[serde(default = "default_web_search_timeout_secs")]
     pub timeout_secs: u64,
+    /// Searches allowed per minute, so a runaway tool loop cannot exhaust
+    /// the provider quota
+    #[serde(default = "default_web_search_max_requests_per_minute")]
+    pub max_requests_per_minute: u32,
 }
 
 fn default_web_search_provider() -> String {
@@ -1127,6 +1551,10 @@ fn default_web_search_timeout_secs() -> u64 {
     15
 }
 
+fn default_web_search_max_requests_per_minute() -> u32 {
+    30
+}
+
 impl Default for WebSearchConfig {
     fn default() -> Self {
         Self {
@@ -1135,6 +1563,7 @@ impl Default for WebSearchConfig {
             brave_api_key: None,
             max_results: default_web_search_max_results(),
             timeout_secs: default_web_search_timeout_secs(),
+            max_requests_per_minute: default_web_search_max_requests_per_minute(),
         }
     }
 }
@@ -1729,6 +2158,16 @@ pub struct MemoryConfig {
     /// For sqlite backend: prune conversation rows older than this many days
     #[serde(default = "default_conversation_retention_days")]
     pub conversation_retention_days: u32,
+    /// Delete channel session turns (`sessions/sessions.db`) older than this
+    /// many days. The newest `agent.max_history_messages` turns of every
+    /// session are always kept. Default: `0` (keep forever).
+    #[serde(default)]
+    pub session_retention_days: u32,
+    /// Let `sessions_search`, `memory_search`, `sessions_list` and
+    /// `sessions_history` read every stored channel session. By default a
+    /// channel turn only sees its own conversation. Default: `false`.
+    #[serde(default)]
+    pub cross_session_search: bool,
     /// Embedding provider: "none" | "openai" | "custom:URL"
     #[serde(default = "default_embedding_provider")]
     pub embedding_provider: String,
@@ -1766,6 +2205,10 @@ pub struct MemoryConfig {
     /// Max number of cached responses before LRU eviction (default: 5000)
     #[serde(default = "default_response_cache_max")]
     pub response_cache_max_entries: usize,
+    /// Provider calls hotter than this, or carrying tool definitions, bypass
+    /// the response cache (default: 0.3)
+    #[serde(default = "default_response_cache_max_temperature")]
+    pub response_cache_max_temperature: f64,
 
     // ── Memory Snapshot (soul backup to Markdown) ─────────────
     /// Enable periodic export of core memories to MEMORY_SNAPSHOT.md
@@ -1833,6 +2276,9 @@ fn default_response_cache_ttl() -> u32 {
 fn default_response_cache_max() -> usize {
     5_000
 }
+fn default_response_cache_max_temperature() -> f64 {
+    0.3
+}
 
 impl Default for MemoryConfig {
     fn default() -> Self {
@@ -1843,6 +2289,8 @@ impl Default for MemoryConfig {
             archive_after_days: default_archive_after_days(),
             purge_after_days: default_purge_after_days(),
             conversation_retention_days: default_conversation_retention_days(),
+            session_retention_days: 0,
+            cross_session_search: false,
             embedding_provider: default_embedding_provider(),
             embedding_model: default_embedding_model(),
             embedding_dimensions: default_embedding_dims(),
@@ -1854,6 +2302,7 @@ impl Default for MemoryConfig {
             response_cache_enabled: false,
             response_cache_ttl_minutes: default_response_cache_ttl(),
             response_cache_max_entries: default_response_cache_max(),
+            response_cache_max_temperature: default_response_cache_max_temperature(),
             snapshot_enabled: false,
             snapshot_on_hygiene: false,
             auto_hydrate: true,
@@ -1863,6 +2312,73 @@ impl Default for MemoryConfig {
     }
 }
 
+// ── Session compaction ───────────────────────────────────────────
+
+/// Background compaction of stored channel sessions (`[session_compaction]`).
+///
+/// Sessions that grew past `message_threshold` turns and have been idle for
+/// `idle_hours` get their older turns folded into the stored session summary
+/// and removed, keeping the newest `keep_recent` turns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SessionCompactionConfig {
+    /// Run the background task. Default: `true`.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Seconds between passes. Default: `3600`.
+    #[serde(default = "default_session_compaction_interval_secs")]
+    pub interval_secs: u64,
+    /// Only sessions with more turns than this are compacted. Default: `200`.
+    #[serde(default = "default_session_compaction_message_threshold")]
+    pub message_threshold: usize,
+    /// Only sessions without a new turn for this many hours are compacted. Default: `24`.
+    #[serde(default = "default_session_compaction_idle_hours")]
+    pub idle_hours: u64,
+    /// Turns kept verbatim per compacted session. Default: `50`.
+    #[serde(default = "default_session_compaction_keep_recent")]
+    pub keep_recent: usize,
+    /// Summarize removed turns with the default model. When `false`, turns
+    /// are dropped without an LLM call. Default: `true`.
+    #[serde(default = "default_true")]
+    pub summarize: bool,
+    /// Sessions summarized at once (1–2) to bound LLM spend. Default: `1`.
+    #[serde(default = "default_session_compaction_concurrency")]
+    pub concurrency: usize,
+}
+
+fn default_session_compaction_interval_secs() -> u64 {
+    3600
+}
+
+fn default_session_compaction_message_threshold() -> usize {
+    200
+}
+
+fn default_session_compaction_idle_hours() -> u64 {
+    24
+}
+
+fn default_session_compaction_keep_recent() -> usize {
+    50
+}
+
+fn default_session_compaction_concurrency() -> usize {
+    1
+}
+
+impl Default for SessionCompactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: default_session_compaction_interval_secs(),
+            message_threshold: default_session_compaction_message_threshold(),
+            idle_hours: default_session_compaction_idle_hours(),
+            keep_recent: default_session_compaction_keep_recent(),
+            summarize: true,
+            concurrency: default_session_compaction_concurrency(),
+        }
+    }
+}
+
 // ── Observability ─────────────────────────────────────────────────
 
 /// Observability backend configuration (`[observability]` section).
@@ -2102,6 +2618,14 @@ pub struct RuntimeConfig {
     /// - `Some(false)`: disable reasoning/thinking when supported
     #[serde(default)]
     pub reasoning_enabled: Option<bool>,
+
+    /// Maximum concurrent outbound sends per channel adapter (Telegram, Discord).
+    #[serde(default = "default_adapter_max_inflight")]
+    pub adapter_max_inflight: usize,
+
+    /// Upper bound of the random jitter added to outbound send retry backoff.
+    #[serde(default = "default_adapter_retry_jitter_ms")]
+    pub adapter_retry_jitter_ms: u64,
 }
 
 /// Docker runtime configuration (`[runtime.docker]` section).
@@ -2140,6 +2664,14 @@ fn default_runtime_kind() -> String {
     "native".into()
 }
 
+fn default_adapter_max_inflight() -> usize {
+    crate::channels::send_retry::DEFAULT_ADAPTER_MAX_INFLIGHT
+}
+
+fn default_adapter_retry_jitter_ms() -> u64 {
+    crate::channels::send_retry::DEFAULT_ADAPTER_RETRY_JITTER_MS
+}
+
 fn default_docker_image() -> String {
     "alpine:3.20".into()
 }
@@ -2176,6 +2708,55 @@ impl Default for RuntimeConfig {
             kind: default_runtime_kind(),
             docker: DockerRuntimeConfig::default(),
             reasoning_enabled: None,
+            adapter_max_inflight: default_adapter_max_inflight(),
+            adapter_retry_jitter_ms: default_adapter_retry_jitter_ms(),
+        }
+    }
+}
+
+// ── OpenRouter ───────────────────────────────────────────────────
+
+/// OpenRouter-specific settings (`[openrouter]` section).
+///
+/// Only used when the provider is `openrouter`. Fallback models and provider
+/// preferences are sent with each request and resolved by OpenRouter itself,
+/// independently of `[reliability]` fallbacks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct OpenRouterConfig {
+    /// `HTTP-Referer` header identifying the app; some models reject requests without it.
+    #[serde(default = "default_openrouter_http_referer")]
+    pub http_referer: String,
+    /// `X-Title` header shown in OpenRouter usage dashboards.
+    #[serde(default = "default_openrouter_x_title")]
+    pub x_title: String,
+    /// Models OpenRouter tries, in order, when the requested model is
+    /// unavailable (sent as the `models` routing array).
+    #[serde(default)]
+    pub fallback_models: Vec<String>,
+    /// Upstream providers to prefer, in order (e.g. `["anthropic", "together"]`).
+    #[serde(default)]
+    pub provider_order: Vec<String>,
+    /// Whether OpenRouter may use providers outside `provider_order`. Unset: OpenRouter default.
+    #[serde(default)]
+    pub allow_fallbacks: Option<bool>,
+}
+
+fn default_openrouter_http_referer() -> String {
+    "https://github.com/theonlyhennygod/zeroclaw".into()
+}
+
+fn default_openrouter_x_title() -> String {
+    "ZeroClaw".into()
+}
+
+impl Default for OpenRouterConfig {
+    fn default() -> Self {
+        Self {
+            http_referer: default_openrouter_http_referer(),
+            x_title: default_openrouter_x_title(),
+            fallback_models: Vec::new(),
+            provider_order: Vec::new(),
+            allow_fallbacks: None,
         }
     }
 }
@@ -2193,6 +2774,10 @@ pub struct ReliabilityConfig {
     /// Base backoff (ms) for provider retry delay.
     #[serde(default = "default_provider_backoff_ms")]
     pub provider_backoff_ms: u64,
+    /// Seconds one provider request may take before it is abandoned and
+    /// retried like a network failure. `0` disables. Default: `120`.
+    #[serde(default = "default_provider_timeout_secs")]
+    pub provider_timeout_secs: u64,
     /// Fallback provider chain (e.g. `["anthropic", "openai"]`).
     #[serde(default)]
     pub fallback_providers: Vec<String>,
@@ -2216,6 +2801,13 @@ pub struct ReliabilityConfig {
     /// Max retries for cron job execution attempts.
     #[serde(default = "default_scheduler_retries")]
     pub scheduler_retries: u32,
+    /// How long a failed channel reply stays in the outbound queue before it
+    /// is dropped. Default: 6 hours.
+    #[serde(default = "default_outbound_queue_ttl_secs")]
+    pub outbound_queue_ttl_secs: u64,
+    /// Max delay between retries of a queued channel reply.
+    #[serde(default = "default_outbound_retry_max_backoff_secs")]
+    pub outbound_retry_max_backoff_secs: u64,
 }
 
 fn default_provider_retries() -> u32 {
@@ -2226,6 +2818,10 @@ fn default_provider_backoff_ms() -> u64 {
     500
 }
 
+fn default_provider_timeout_secs() -> u64 {
+    120
+}
+
 fn default_channel_backoff_secs() -> u64 {
     2
 }
@@ -2242,11 +2838,20 @@ fn default_scheduler_retries() -> u32 {
     2
 }
 
+fn default_outbound_queue_ttl_secs() -> u64 {
+    6 * 60 * 60
+}
+
+fn default_outbound_retry_max_backoff_secs() -> u64 {
+    300
+}
+
 impl Default for ReliabilityConfig {
     fn default() -> Self {
         Self {
             provider_retries: default_provider_retries(),
             provider_backoff_ms: default_provider_backoff_ms(),
+            provider_timeout_secs: default_provider_timeout_secs(),
             fallback_providers: Vec::new(),
             api_keys: Vec::new(),
             model_fallbacks: std::collections::HashMap::new(),
@@ -2254,6 +2859,8 @@ impl Default for ReliabilityConfig {
             channel_max_backoff_secs: default_channel_backoff_max_secs(),
             scheduler_poll_secs: default_scheduler_poll_secs(),
             scheduler_retries: default_scheduler_retries(),
+            outbound_queue_ttl_secs: default_outbound_queue_ttl_secs(),
+            outbound_retry_max_backoff_secs: default_outbound_retry_max_backoff_secs(),
         }
     }
 }
@@ -2450,6 +3057,77 @@ impl Default for CronConfig {
     }
 }
 
+// ── Quiet hours ─────────────────────────────────────────────────
+
+/// What happens to an agent-initiated message sent during quiet hours.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuietHoursBehavior {
+    /// Hold the message until quiet hours end, batched per recipient.
+    #[default]
+    Defer,
+    /// Discard the message.
+    Drop,
+    /// Send it without a notification where the channel supports that
+    /// (Telegram); elsewhere it is sent normally.
+    SendSilent,
+}
+
+/// Quiet hours for cron and heartbeat deliveries (`[quiet_hours]` section).
+/// Replies to user messages are never held back.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct QuietHoursConfig {
+    /// Enable quiet hours. Default: `false`.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Start of quiet hours, `HH:MM` wall time in `timezone`. Default: `"22:00"`.
+    #[serde(default = "default_quiet_hours_start")]
+    pub start: String,
+    /// End of quiet hours, `HH:MM`; earlier than `start` wraps past midnight.
+    /// Default: `"07:00"`.
+    #[serde(default = "default_quiet_hours_end")]
+    pub end: String,
+    /// What to do with messages sent during quiet hours. Default: `defer`.
+    #[serde(default)]
+    pub behavior: QuietHoursBehavior,
+    /// Per-channel overrides, keyed by channel name (`[quiet_hours.channels.telegram]`).
+    #[serde(default)]
+    pub channels: HashMap<String, QuietHoursOverride>,
+}
+
+fn default_quiet_hours_start() -> String {
+    "22:00".into()
+}
+
+fn default_quiet_hours_end() -> String {
+    "07:00".into()
+}
+
+impl Default for QuietHoursConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: default_quiet_hours_start(),
+            end: default_quiet_hours_end(),
+            behavior: QuietHoursBehavior::default(),
+            channels: HashMap::new(),
+        }
+    }
+}
+
+/// Per-channel quiet hours; unset fields fall back to `[quiet_hours]`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct QuietHoursOverride {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub start: Option<String>,
+    #[serde(default)]
+    pub end: Option<String>,
+    #[serde(default)]
+    pub behavior: Option<QuietHoursBehavior>,
+}
+
 // ── Tunnel ──────────────────────────────────────────────────────
 
 /// Tunnel configuration for exposing the gateway publicly (`[tunnel]` section).
@@ -2574,6 +3252,9 @@ pub struct ChannelsConfig {
     pub wati: Option<WatiConfig>,
     /// Nextcloud Talk bot channel configuration.
     pub nextcloud_talk: Option<NextcloudTalkConfig>,
+    /// Google Chat app configuration (gateway webhook).
+    #[serde(default)]
+    pub google_chat: Option<GoogleChatConfig>,
     /// Email channel configuration.
     pub email: Option<crate::channels::email_channel::EmailConfig>,
     /// IRC channel configuration.
@@ -2596,8 +3277,179 @@ pub struct ChannelsConfig {
     /// Default: 300s for on-device LLMs (Ollama) which are slower than cloud APIs.
     #[serde(default = "default_channel_message_timeout_secs")]
     pub message_timeout_secs: u64,
-}
-
+    /// When true, a newer message from the same sender in the same chat
+    /// cancels the in-flight turn on every channel (Telegram alone:
+    /// `[channels_config.telegram] interrupt_on_new_message`). The cancelled
+    /// turn sends nothing; tools it already started are noted in the
+    /// session for the next turn.
+    #[serde(default)]
+    pub interrupt_on_new_message: bool,
+    /// How long an inbound message id is remembered so adapter replays
+    /// (reconnects, redeliveries) are skipped instead of answered twice.
+    /// `0` disables deduplication. Default: 600s.
+    #[serde(default = "default_channel_inbound_dedup_ttl_secs")]
+    pub inbound_dedup_ttl_secs: u64,
+    /// Maximum inbound message ids remembered; the oldest is evicted first.
+    #[serde(default = "default_channel_inbound_dedup_max_keys")]
+    pub inbound_dedup_max_keys: usize,
+    /// Replies sent to users when a turn fails.
+    #[serde(default)]
+    pub error_messages: ErrorMessagesConfig,
+    /// Size and flood limits applied to inbound messages.
+    #[serde(default)]
+    pub inbound_guard: InboundGuardConfig,
+}
+
+fn default_channel_inbound_dedup_ttl_secs() -> u64 {
+    600
+}
+
+fn default_channel_inbound_dedup_max_keys() -> usize {
+    10_000
+}
+
+/// Inbound size and flood limits (`[channels_config.inbound_guard]`).
+///
+/// Oversized messages are truncated before they reach the agent; the full
+/// text is saved under `inbound/` in the workspace. Messages from one
+/// sender that arrive in quick succession are combined into one turn, and
+/// a sender over the per-minute cap gets one notice while the rest is
+/// dropped.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct InboundGuardConfig {
+    /// Longest inbound message passed to the agent, in bytes. `0` disables
+    /// truncation. Default: 16384.
+    #[serde(default = "default_inbound_max_message_bytes")]
+    pub max_message_bytes: usize,
+    /// Messages from the same sender arriving within this many milliseconds
+    /// of the previous one are combined into one turn. `0` disables
+    /// coalescing. Default: 3000.
+    #[serde(default = "default_inbound_coalesce_window_ms")]
+    pub coalesce_window_ms: u64,
+    /// Messages accepted per sender per minute. `0` disables the cap.
+    /// Default: 20.
+    #[serde(default = "default_inbound_max_messages_per_minute")]
+    pub max_messages_per_minute: u32,
+    /// Reply sent once when a sender exceeds the cap.
+    #[serde(default = "default_inbound_flood_notice")]
+    pub flood_notice: String,
+}
+
+fn default_inbound_max_message_bytes() -> usize {
+    16 * 1024
+}
+
+fn default_inbound_coalesce_window_ms() -> u64 {
+    3_000
+}
+
+fn default_inbound_max_messages_per_minute() -> u32 {
+    20
+}
+
+fn default_inbound_flood_notice() -> String {
+    "⚠️ You're sending messages faster than I can handle. Please slow down; messages sent in the next minute will be ignored.".into()
+}
+
+impl Default for InboundGuardConfig {
+    fn default() -> Self {
+        Self {
+            max_message_bytes: default_inbound_max_message_bytes(),
+            coalesce_window_ms: default_inbound_coalesce_window_ms(),
+            max_messages_per_minute: default_inbound_max_messages_per_minute(),
+            flood_notice: default_inbound_flood_notice(),
+        }
+    }
+}
+
+/// Replies sent to chat users when a turn fails
+/// (`[channels_config.error_messages]`).
+///
+/// Each reply is followed by a short reference id that is also written to
+/// the audit log with the full error; raw provider errors are never sent.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct ErrorMessagesConfig {
+    /// The provider is rate limiting or out of quota.
+    #[serde(default = "default_error_message_rate_limited")]
+    pub rate_limited: String,
+    /// The provider rejected the credentials or configuration.
+    #[serde(default = "default_error_message_auth")]
+    pub auth: String,
+    /// The provider could not be reached.
+    #[serde(default = "default_error_message_network")]
+    pub network: String,
+    /// The conversation no longer fits the model's context window.
+    #[serde(default = "default_error_message_context_too_long")]
+    pub context_too_long: String,
+    /// The security policy blocked the request.
+    #[serde(default = "default_error_message_sandbox_violation")]
+    pub sandbox_violation: String,
+    /// The tool loop hit `max_tool_iterations`.
+    #[serde(default = "default_error_message_max_iterations")]
+    pub max_iterations: String,
+    /// The model cannot handle the input (e.g. images without vision).
+    #[serde(default = "default_error_message_unsupported_input")]
+    pub unsupported_input: String,
+    /// The turn exceeded the message timeout.
+    #[serde(default = "default_error_message_timed_out")]
+    pub timed_out: String,
+    /// Anything else.
+    #[serde(default = "default_error_message_internal")]
+    pub internal: String,
+}
+
+fn default_error_message_rate_limited() -> String {
+    "⚠️ The AI service is busy right now. Please try again in a minute.".into()
+}
+
+fn default_error_message_auth() -> String {
+    "⚠️ I can't reach the AI service because of a configuration problem. Please let the operator know.".into()
+}
+
+fn default_error_message_network() -> String {
+    "⚠️ I couldn't reach the AI service. Please try again shortly.".into()
+}
+
+fn default_error_message_context_too_long() -> String {
+    "⚠️ This conversation got too long for the model. Please resend your last message.".into()
+}
+
+fn default_error_message_sandbox_violation() -> String {
+    "⚠️ That request was blocked by the security policy.".into()
+}
+
+fn default_error_message_max_iterations() -> String {
+    "⚠️ I couldn't finish this within the allowed number of steps. Try splitting it into smaller requests.".into()
+}
+
+fn default_error_message_unsupported_input() -> String {
+    "⚠️ The current model can't process this kind of input, such as images. Switch models with /models and try again.".into()
+}
+
+fn default_error_message_timed_out() -> String {
+    "⚠️ Request timed out while waiting for the model. Please try again.".into()
+}
+
+fn default_error_message_internal() -> String {
+    "⚠️ Something went wrong while handling your message. Please try again.".into()
+}
+
+impl Default for ErrorMessagesConfig {
+    fn default() -> Self {
+        Self {
+            rate_limited: default_error_message_rate_limited(),
+            auth: default_error_message_auth(),
+            network: default_error_message_network(),
+            context_too_long: default_error_message_context_too_long(),
+            sandbox_violation: default_error_message_sandbox_violation(),
+            max_iterations: default_error_message_max_iterations(),
+            unsupported_input: default_error_message_unsupported_input(),
+            timed_out: default_error_message_timed_out(),
+            internal: default_error_message_internal(),
+        }
+    }
+}
+
 impl ChannelsConfig {
     /// get channels' metadata and `.is_some()`, except webhook
     #[rustfmt::skip]
@@ -2688,6 +3540,10 @@ impl ChannelsConfig {
             Box::new(ConfigWrapper::new(&self.webhook)),
             self.webhook.is_some(),
         ));
+        ret.push((
+            Box::new(ConfigWrapper::new(&self.google_chat)),
+            self.google_chat.is_some(),
+        ));
         ret
     }
 }
@@ -2712,6 +3568,7 @@ impl Default for ChannelsConfig {
             linq: None,
             wati: None,
             nextcloud_talk: None,
+            google_chat: None,
             email: None,
             irc: None,
             lark: None,
@@ -2721,6 +3578,11 @@ impl Default for ChannelsConfig {
             nostr: None,
             clawdtalk: None,
             message_timeout_secs: default_channel_message_timeout_secs(),
+            interrupt_on_new_message: false,
+            inbound_dedup_ttl_secs: default_channel_inbound_dedup_ttl_secs(),
+            inbound_dedup_max_keys: default_channel_inbound_dedup_max_keys(),
+            error_messages: ErrorMessagesConfig::default(),
+            inbound_guard: InboundGuardConfig::default(),
         }
     }
 }
@@ -2757,10 +3619,19 @@ pub struct TelegramConfig {
     /// cancels the in-flight request and starts a fresh response with preserved history.
     #[serde(default)]
     pub interrupt_on_new_message: bool,
-    /// When true, only respond to messages that @-mention the bot in groups.
+    /// When true, group messages are only processed when they @-mention the
+    /// bot, reply to one of its messages, or start with `command_prefix`.
     /// Direct messages are always processed.
     #[serde(default)]
     pub mention_only: bool,
+    /// With `mention_only`, group messages starting with this prefix (e.g.
+    /// `"!claw"`) are also processed, with the prefix stripped.
+    #[serde(default)]
+    pub command_prefix: Option<String>,
+    /// Post a "⏳ running <tool>…" status message during multi-tool turns,
+    /// edited as tools change and deleted when the reply arrives.
+    #[serde(default)]
+    pub progress_messages: bool,
 }
 
 impl ChannelConfig for TelegramConfig {
@@ -2786,10 +3657,19 @@ pub struct DiscordConfig {
     /// The bot still ignores its own messages to prevent feedback loops.
     #[serde(default)]
     pub listen_to_bots: bool,
-    /// When true, only respond to messages that @-mention the bot.
-    /// Other messages in the guild are silently ignored.
+    /// When true, guild messages are only processed when they @-mention the
+    /// bot, reply to one of its messages, or start with `command_prefix`.
+    /// Direct messages are always processed.
     #[serde(default)]
     pub mention_only: bool,
+    /// With `mention_only`, guild messages starting with this prefix (e.g.
+    /// `"!claw"`) are also processed, with the prefix stripped.
+    #[serde(default)]
+    pub command_prefix: Option<String>,
+    /// Discord user IDs allowed to run `/claw_*` slash commands (cron
+    /// management and status). Empty = commands are not registered.
+    #[serde(default)]
+    pub command_users: Vec<String>,
 }
 
 impl ChannelConfig for DiscordConfig {
@@ -2814,6 +3694,10 @@ pub struct SlackConfig {
     /// Allowed Slack user IDs. Empty = deny all.
     #[serde(default)]
     pub allowed_users: Vec<String>,
+    /// Post a "⏳ running <tool>…" status message during multi-tool turns,
+    /// edited as tools change and deleted when the reply arrives.
+    #[serde(default)]
+    pub progress_messages: bool,
 }
 
 impl ChannelConfig for SlackConfig {
@@ -2986,6 +3870,52 @@ pub struct WhatsAppConfig {
     /// Allowed phone numbers (E.164 format: +1234567890) or "*" for all
     #[serde(default)]
     pub allowed_numbers: Vec<String>,
+    /// Ignore webhook messages and delivery receipts older than this many
+    /// seconds, so a captured signed payload cannot be replayed. Keep it
+    /// above the gateway downtime you want Meta's retries to cover.
+    /// `0` disables the check. Default: `3600`. Only used in Cloud API mode
+    #[serde(default = "default_whatsapp_max_event_age_secs")]
+    pub max_event_age_secs: u64,
+    /// Approved message template sent instead of free-form text when the
+    /// recipient has not written in the last 24 hours (Meta rejects text
+    /// outside that window). Only used in Cloud API mode
+    #[serde(default)]
+    pub template: Option<WhatsAppTemplateConfig>,
+}
+
+fn default_whatsapp_max_event_age_secs() -> u64 {
+    3600
+}
+
+/// Message template used for `WhatsApp` sends outside the 24-hour customer
+/// service window.
+///
+/// ```toml
+/// [channels_config.whatsapp.template]
+/// name = "reminder"
+/// language = "en_US"
+/// body_parameters = ["{text}"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct WhatsAppTemplateConfig {
+    /// Template name as approved in Meta Business Manager.
+    pub name: String,
+    /// Template language code. Default: `"en_US"`.
+    #[serde(default = "default_whatsapp_template_language")]
+    pub language: String,
+    /// Values for the template's body placeholders, in order. `{text}` is
+    /// replaced with the outbound message text and `{subject}` with its
+    /// subject; anything else is sent literally. Default: `["{text}"]`.
+    #[serde(default = "default_whatsapp_template_body_parameters")]
+    pub body_parameters: Vec<String>,
+}
+
+fn default_whatsapp_template_language() -> String {
+    "en_US".into()
+}
+
+fn default_whatsapp_template_body_parameters() -> Vec<String> {
+    vec!["{text}".into()]
 }
 
 impl ChannelConfig for WhatsAppConfig {
@@ -3075,6 +4005,32 @@ impl ChannelConfig for NextcloudTalkConfig {
     }
 }
 
+/// Google Chat app configuration (events POSTed to the gateway `/google-chat` endpoint).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GoogleChatConfig {
+    /// Google Cloud project number of the Chat app. Incoming bearer tokens
+    /// must carry this as their audience.
+    pub project_number: String,
+    /// Allowed sender emails or `users/{id}` names (`[]` = deny all, `"*"` = allow all).
+    #[serde(default)]
+    pub allowed_users: Vec<String>,
+    /// Service-account key for the Chat REST API: the key JSON itself or a
+    /// path to the key file. When set, events are acked immediately and
+    /// replies are posted with `spaces.messages.create` (threaded when the
+    /// event was); otherwise replies go back in the webhook response.
+    #[serde(default)]
+    pub service_account_json: Option<String>,
+}
+
+impl ChannelConfig for GoogleChatConfig {
+    fn name() -> &'static str {
+        "Google Chat"
+    }
+    fn desc() -> &'static str {
+        "Google Workspace Chat app"
+    }
+}
+
 impl WhatsAppConfig {
     /// Detect which backend to use based on config fields.
     /// Returns "cloud" if phone_number_id is set, "web" if session_path is set.
@@ -3260,6 +4216,10 @@ pub struct SecurityConfig {
     /// Emergency-stop state machine configuration.
     #[serde(default)]
     pub estop: EstopConfig,
+
+    /// Credential redaction for replies and tool results.
+    #[serde(default)]
+    pub redaction: RedactionConfig,
 }
 
 /// OTP validation strategy.
@@ -3371,6 +4331,32 @@ impl Default for EstopConfig {
     }
 }
 
+/// What to do when content matches a credential pattern or a loaded secret.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RedactionMode {
+    /// Replace each match with `[REDACTED:<kind>]`.
+    #[default]
+    Redact,
+    /// Leave the content as-is; still count, log and audit the match.
+    WarnOnly,
+    /// Skip the scan.
+    Off,
+}
+
+/// Credential redaction configuration (`[security.redaction]`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RedactionConfig {
+    /// Replies just before they are sent to a channel.
+    #[serde(default)]
+    pub outbound: RedactionMode,
+
+    /// Tool results before they are fed back to the provider.
+    #[serde(default)]
+    pub tool_results: RedactionMode,
+}
+
 /// Sandbox configuration for OS-level isolation
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SandboxConfig {
@@ -3385,6 +4371,122 @@ pub struct SandboxConfig {
     /// Custom Firejail arguments (when backend = firejail)
     #[serde(default)]
     pub firejail_args: Vec<String>,
+
+    /// Register the `run_code` tool. Its interpreters (`python3`, `node`)
+    /// must also be listed in `autonomy.allowed_commands`
+    #[serde(default)]
+    pub code_enabled: bool,
+
+    /// `run_code` tool: seconds before the snippet's process group is killed
+    #[serde(default = "default_code_timeout_secs")]
+    pub code_timeout_secs: u64,
+
+    /// `run_code` tool: maximum bytes kept from each of stdout and stderr
+    #[serde(default = "default_code_max_output_bytes")]
+    pub code_max_output_bytes: usize,
+
+    /// `run_code` tool: data segment limit in MB (0 = unlimited)
+    #[serde(default = "default_code_max_memory_mb")]
+    pub code_max_memory_mb: u64,
+
+    /// `shell` tool: what happens when a command matches a blocked rule
+    #[serde(default)]
+    pub command_filter: CommandFilterMode,
+
+    /// `shell` tool: blocked command shapes, matched per command word and
+    /// arguments (`"rm -rf /"`); `|` separates pipeline stages (`"curl | sh"`)
+    #[serde(default = "default_blocked_patterns")]
+    pub blocked_patterns: Vec<String>,
+
+    /// `shell` tool: regexes matched against each tokenized pipeline
+    #[serde(default = "default_blocked_regexes")]
+    pub blocked_regexes: Vec<String>,
+
+    /// `file_write` tool: commit each successful write to the workspace git
+    /// repository (created on first use) so edits can be reverted
+    #[serde(default)]
+    pub auto_commit_writes: bool,
+
+    /// Tools whose output beyond `artifact_preview_bytes` is saved to
+    /// `.artifacts/` in the workspace instead of being cut off
+    #[serde(default = "default_artifact_tools")]
+    pub artifact_tools: Vec<String>,
+
+    /// Bytes of an artifact-backed tool's output returned inline
+    #[serde(default = "default_artifact_preview_bytes")]
+    pub artifact_preview_bytes: usize,
+
+    /// Size cap of `.artifacts/` in MB; least recently used files go first
+    #[serde(default = "default_artifact_max_total_mb")]
+    pub artifact_max_total_mb: u64,
+
+    /// Hold calls to `confirm_tools` made during channel turns until the
+    /// user confirms them with a reply
+    #[serde(default)]
+    pub require_confirmation: bool,
+
+    /// Tools that wait for confirmation when `require_confirmation` is on
+    #[serde(default = "default_confirm_tools")]
+    pub confirm_tools: Vec<String>,
+}
+
+fn default_code_timeout_secs() -> u64 {
+    20
+}
+
+fn default_code_max_output_bytes() -> usize {
+    65_536
+}
+
+fn default_code_max_memory_mb() -> u64 {
+    512
+}
+
+fn default_artifact_tools() -> Vec<String> {
+    vec!["shell".into(), "web_fetch".into()]
+}
+
+fn default_confirm_tools() -> Vec<String> {
+    vec!["shell".into(), "file_write".into()]
+}
+
+fn default_artifact_preview_bytes() -> usize {
+    65_536
+}
+
+fn default_artifact_max_total_mb() -> u64 {
+    256
+}
+
+fn default_blocked_patterns() -> Vec<String> {
+    [
+        "rm -rf /",
+        "rm -rf /*",
+        "rm -rf ~",
+        "mkfs*",
+        "shutdown",
+        "reboot",
+        "halt",
+        "poweroff",
+        "killall",
+        "curl | sh",
+        "curl | bash",
+        "curl | python*",
+        "wget | sh",
+        "wget | bash",
+        "wget | python*",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_blocked_regexes() -> Vec<String> {
+    vec![
+        // `bash -c "$(curl …)"`, `sh <(wget …)`
+        r"^(ba|z|da)?sh (-c .*|<\()\b(curl|wget)\b".to_string(),
+        r"^eval .*\b(curl|wget)\b".to_string(),
+    ]
 }
 
 impl Default for SandboxConfig {
@@ -3393,10 +4495,37 @@ impl Default for SandboxConfig {
             enabled: None, // Auto-detect
             backend: SandboxBackend::Auto,
             firejail_args: Vec::new(),
+            code_enabled: false,
+            code_timeout_secs: default_code_timeout_secs(),
+            code_max_output_bytes: default_code_max_output_bytes(),
+            code_max_memory_mb: default_code_max_memory_mb(),
+            command_filter: CommandFilterMode::default(),
+            blocked_patterns: default_blocked_patterns(),
+            blocked_regexes: default_blocked_regexes(),
+            auto_commit_writes: false,
+            artifact_tools: default_artifact_tools(),
+            artifact_preview_bytes: default_artifact_preview_bytes(),
+            artifact_max_total_mb: default_artifact_max_total_mb(),
+            require_confirmation: false,
+            confirm_tools: default_confirm_tools(),
         }
     }
 }
 
+/// Enforcement of `[security.sandbox]` blocked command rules
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandFilterMode {
+    /// Refuse matching commands (default)
+    #[default]
+    Block,
+    /// Run matching commands but log a warning and an audit event
+    #[serde(alias = "warn-and-audit", alias = "warn_and_audit")]
+    Warn,
+    /// Skip the blocked rules entirely
+    Off,
+}
+
 /// Sandbox backend selection
 #[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 #[serde(rename_all = "lowercase")]
@@ -3590,6 +4719,7 @@ impl Default for Config {
         Self {
             workspace_dir: zeroclaw_dir.join("workspace"),
             config_path: zeroclaw_dir.join("config.toml"),
+            secret_refs: Vec::new(),
             api_key: None,
             api_url: None,
             default_provider: Some("openrouter".to_string()),
@@ -3601,6 +4731,8 @@ impl Default for Config {
             security: SecurityConfig::default(),
             runtime: RuntimeConfig::default(),
             reliability: ReliabilityConfig::default(),
+            openrouter: OpenRouterConfig::default(),
+            session_compaction: SessionCompactionConfig::default(),
             scheduler: SchedulerConfig::default(),
             agent: AgentConfig::default(),
             skills: SkillsConfig::default(),
@@ -3608,18 +4740,22 @@ impl Default for Config {
             embedding_routes: Vec::new(),
             heartbeat: HeartbeatConfig::default(),
             cron: CronConfig::default(),
+            quiet_hours: QuietHoursConfig::default(),
+            timezone: None,
             channels_config: ChannelsConfig::default(),
             memory: MemoryConfig::default(),
             storage: StorageConfig::default(),
             tunnel: TunnelConfig::default(),
             gateway: GatewayConfig::default(),
             composio: ComposioConfig::default(),
+            google_sheets: None,
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             http_request: HttpRequestConfig::default(),
             multimodal: MultimodalConfig::default(),
             web_fetch: WebFetchConfig::default(),
             web_search: WebSearchConfig::default(),
+            web: WebConfig::default(),
             proxy: ProxyConfig::default(),
             identity: IdentityConfig::default(),
             cost: CostConfig::default(),
@@ -3921,7 +5057,9 @@ fn encrypt_optional_secret(
     field_name: &str,
 ) -> Result<()> {
     if let Some(raw) = value.clone() {
-        if !crate::security::SecretStore::is_encrypted(&raw) {
+        if !crate::security::SecretStore::is_encrypted(&raw)
+            && !super::secret_refs::is_reference(&raw)
+        {
             *value = Some(
                 store
                     .encrypt(&raw)
@@ -3937,7 +5075,9 @@ fn encrypt_secret(
     value: &mut String,
     field_name: &str,
 ) -> Result<()> {
-    if !crate::security::SecretStore::is_encrypted(value) {
+    if !crate::security::SecretStore::is_encrypted(value)
+        && !super::secret_refs::is_reference(value)
+    {
         *value = store
             .encrypt(value)
             .with_context(|| format!("Failed to encrypt {field_name}"))?;
@@ -4039,20 +5179,7 @@ impl Config {
                 }
             }
 
-            let contents = fs::read_to_string(&config_path)
-                .await
-                .context("Failed to read config file")?;
-
-            // Track ignored/unknown config keys to warn users about silent misconfigurations
-            // (e.g., using [providers.ollama] which doesn't exist instead of top-level api_url)
-            let mut ignored_paths: Vec<String> = Vec::new();
-            let mut config: Config = serde_ignored::deserialize(
-                toml::de::Deserializer::parse(&contents).context("Failed to parse config file")?,
-                |path| {
-                    ignored_paths.push(path.to_string());
-                },
-            )
-            .context("Failed to deserialize config file")?;
+            let (config, ignored_paths) = Self::read_from_file(&config_path, workspace_dir).await?;
 
             // Warn about each unknown config key
             for path in ignored_paths {
@@ -4061,48 +5188,6 @@ impl Config {
                     path
                 );
             }
-            // Set computed paths that are skipped during serialization
-            config.config_path = config_path.clone();
-            config.workspace_dir = workspace_dir;
-            let store = crate::security::SecretStore::new(&zeroclaw_dir, config.secrets.encrypt);
-            decrypt_optional_secret(&store, &mut config.api_key, "config.api_key")?;
-            decrypt_optional_secret(
-                &store,
-                &mut config.composio.api_key,
-                "config.composio.api_key",
-            )?;
-
-            decrypt_optional_secret(
-                &store,
-                &mut config.browser.computer_use.api_key,
-                "config.browser.computer_use.api_key",
-            )?;
-
-            decrypt_optional_secret(
-                &store,
-                &mut config.web_search.brave_api_key,
-                "config.web_search.brave_api_key",
-            )?;
-
-            decrypt_optional_secret(
-                &store,
-                &mut config.storage.provider.config.db_url,
-                "config.storage.provider.config.db_url",
-            )?;
-
-            for agent in config.agents.values_mut() {
-                decrypt_optional_secret(&store, &mut agent.api_key, "config.agents.*.api_key")?;
-            }
-
-            if let Some(ref mut ns) = config.channels_config.nostr {
-                decrypt_secret(
-                    &store,
-                    &mut ns.private_key,
-                    "config.channels_config.nostr.private_key",
-                )?;
-            }
-
-            config.apply_env_overrides();
             config.validate()?;
             tracing::info!(
                 path = %config.config_path.display(),
@@ -4138,6 +5223,44 @@ impl Config {
         }
     }
 
+    /// Read the config file at `config_path`: resolve `env:`/`file:`
+    /// references, decrypt secrets and apply env overrides. Not validated.
+    /// Also returns the unknown keys that were ignored (typos, deprecated
+    /// options).
+    pub async fn read_from_file(
+        config_path: &Path,
+        workspace_dir: PathBuf,
+    ) -> Result<(Self, Vec<String>)> {
+        let zeroclaw_dir = config_path
+            .parent()
+            .context("Config path has no parent directory")?;
+        let contents = fs::read_to_string(config_path)
+            .await
+            .context("Failed to read config file")?;
+
+        // Track ignored/unknown config keys to warn users about silent misconfigurations
+        // (e.g., using [providers.ollama] which doesn't exist instead of top-level api_url)
+        let mut ignored_paths: Vec<String> = Vec::new();
+        let mut raw: toml::Value =
+            toml::from_str(&contents).context("Failed to parse config file")?;
+        let secret_refs = super::secret_refs::resolve_secret_refs(&mut raw, zeroclaw_dir)
+            .context("Failed to resolve env:/file: reference in config file")?;
+        let mut config: Config = serde_ignored::deserialize(raw, |path| {
+            ignored_paths.push(path.to_string());
+        })
+        .context("Failed to deserialize config file")?;
+        config.secret_refs = secret_refs;
+
+        // Set computed paths that are skipped during serialization
+        config.config_path = config_path.to_path_buf();
+        config.workspace_dir = workspace_dir;
+        let store = crate::security::SecretStore::new(zeroclaw_dir, config.secrets.encrypt);
+        config.decrypt_secrets(&store)?;
+
+        config.apply_env_overrides();
+        Ok((config, ignored_paths))
+    }
+
     fn lookup_model_provider_profile(
         &self,
         provider_name: &str,
@@ -4231,6 +5354,80 @@ impl Config {
         if self.gateway.host.trim().is_empty() {
             anyhow::bail!("gateway.host must not be empty");
         }
+        if self.gateway.attachment_retention_hours == 0 {
+            anyhow::bail!("gateway.attachment_retention_hours must be greater than 0");
+        }
+        if self.multimodal.screenshot_max_dimension < 64 {
+            anyhow::bail!("multimodal.screenshot_max_dimension must be at least 64");
+        }
+        if !(1..=100).contains(&self.multimodal.screenshot_jpeg_quality) {
+            anyhow::bail!("multimodal.screenshot_jpeg_quality must be between 1 and 100");
+        }
+        if let Some(tls) = &self.gateway.tls {
+            if tls.cert_path.trim().is_empty() {
+                anyhow::bail!("gateway.tls.cert_path must not be empty");
+            }
+            if tls.key_path.trim().is_empty() {
+                anyhow::bail!("gateway.tls.key_path must not be empty");
+            }
+        }
+
+        // Quiet hours
+        let quiet_times = [
+            (
+                "quiet_hours.start".to_string(),
+                Some(&self.quiet_hours.start),
+            ),
+            ("quiet_hours.end".to_string(), Some(&self.quiet_hours.end)),
+        ]
+        .into_iter()
+        .chain(self.quiet_hours.channels.iter().flat_map(|(channel, o)| {
+            [
+                (
+                    format!("quiet_hours.channels.{channel}.start"),
+                    o.start.as_ref(),
+                ),
+                (
+                    format!("quiet_hours.channels.{channel}.end"),
+                    o.end.as_ref(),
+                ),
+            ]
+        }));
+        for (field, value) in quiet_times {
+            if let Some(value) = value {
+                if crate::channels::quiet_hours::parse_wall_time(value).is_none() {
+                    anyhow::bail!("{field} must be a HH:MM time, got {value:?}");
+                }
+            }
+        }
+
+        // Channel overrides
+        for (channel, override_) in &self.agent.channel_overrides {
+            if let Some(temperature) = override_.temperature {
+                if !(0.0..=2.0).contains(&temperature) {
+                    anyhow::bail!(
+                        "agent.channel_overrides.{channel}.temperature must be between 0 and 2"
+                    );
+                }
+            }
+            if override_.max_tokens == Some(0) {
+                anyhow::bail!("agent.channel_overrides.{channel}.max_tokens must be at least 1");
+            }
+        }
+
+        // Session compaction
+        let compaction = &self.session_compaction;
+        if compaction.interval_secs == 0 {
+            anyhow::bail!("session_compaction.interval_secs must be greater than 0");
+        }
+        if !(1..=2).contains(&compaction.concurrency) {
+            anyhow::bail!("session_compaction.concurrency must be 1 or 2");
+        }
+        if compaction.keep_recent >= compaction.message_threshold {
+            anyhow::bail!(
+                "session_compaction.keep_recent must be smaller than session_compaction.message_threshold"
+            );
+        }
 
         // Autonomy
         if self.autonomy.max_actions_per_hour == 0 {
@@ -4280,6 +5477,26 @@ impl Config {
         if self.security.estop.state_file.trim().is_empty() {
             anyhow::bail!("security.estop.state_file must not be empty");
         }
+        for (i, pattern) in self.security.sandbox.blocked_patterns.iter().enumerate() {
+            if pattern.trim().is_empty() {
+                anyhow::bail!("security.sandbox.blocked_patterns[{i}] must not be empty");
+            }
+        }
+        for (i, pattern) in self.security.sandbox.blocked_regexes.iter().enumerate() {
+            if let Err(e) = regex::Regex::new(pattern) {
+                anyhow::bail!("security.sandbox.blocked_regexes[{i}] is not a valid regex: {e}");
+            }
+        }
+
+        // Response cache
+        if self.memory.response_cache_enabled {
+            let max_temperature = self.memory.response_cache_max_temperature;
+            if !max_temperature.is_finite() || max_temperature < 0.0 {
+                anyhow::bail!(
+                    "memory.response_cache_max_temperature must be a non-negative number"
+                );
+            }
+        }
 
         // Scheduler
         if self.scheduler.max_concurrent == 0 {
@@ -4583,102 +5800,468 @@ impl Config {
                 }
             }
         }
-
-        // Storage provider key (optional backend override): ZEROCLAW_STORAGE_PROVIDER
-        if let Ok(provider) = std::env::var("ZEROCLAW_STORAGE_PROVIDER") {
-            let provider = provider.trim();
-            if !provider.is_empty() {
-                self.storage.provider.config.provider = provider.to_string();
-            }
+
+        // Storage provider key (optional backend override): ZEROCLAW_STORAGE_PROVIDER
+        if let Ok(provider) = std::env::var("ZEROCLAW_STORAGE_PROVIDER") {
+            let provider = provider.trim();
+            if !provider.is_empty() {
+                self.storage.provider.config.provider = provider.to_string();
+            }
+        }
+
+        // Storage connection URL (for remote backends): ZEROCLAW_STORAGE_DB_URL
+        if let Ok(db_url) = std::env::var("ZEROCLAW_STORAGE_DB_URL") {
+            let db_url = db_url.trim();
+            if !db_url.is_empty() {
+                self.storage.provider.config.db_url = Some(db_url.to_string());
+            }
+        }
+
+        // Storage connect timeout: ZEROCLAW_STORAGE_CONNECT_TIMEOUT_SECS
+        if let Ok(timeout_secs) = std::env::var("ZEROCLAW_STORAGE_CONNECT_TIMEOUT_SECS") {
+            if let Ok(timeout_secs) = timeout_secs.parse::<u64>() {
+                if timeout_secs > 0 {
+                    self.storage.provider.config.connect_timeout_secs = Some(timeout_secs);
+                }
+            }
+        }
+        // Proxy enabled flag: ZEROCLAW_PROXY_ENABLED
+        let explicit_proxy_enabled = std::env::var("ZEROCLAW_PROXY_ENABLED")
+            .ok()
+            .as_deref()
+            .and_then(parse_proxy_enabled);
+        if let Some(enabled) = explicit_proxy_enabled {
+            self.proxy.enabled = enabled;
+        }
+
+        // Proxy URLs: ZEROCLAW_* wins, then generic *PROXY vars.
+        let mut proxy_url_overridden = false;
+        if let Ok(proxy_url) =
+            std::env::var("ZEROCLAW_HTTP_PROXY").or_else(|_| std::env::var("HTTP_PROXY"))
+        {
+            self.proxy.http_proxy = normalize_proxy_url_option(Some(&proxy_url));
+            proxy_url_overridden = true;
+        }
+        if let Ok(proxy_url) =
+            std::env::var("ZEROCLAW_HTTPS_PROXY").or_else(|_| std::env::var("HTTPS_PROXY"))
+        {
+            self.proxy.https_proxy = normalize_proxy_url_option(Some(&proxy_url));
+            proxy_url_overridden = true;
+        }
+        if let Ok(proxy_url) =
+            std::env::var("ZEROCLAW_ALL_PROXY").or_else(|_| std::env::var("ALL_PROXY"))
+        {
+            self.proxy.all_proxy = normalize_proxy_url_option(Some(&proxy_url));
+            proxy_url_overridden = true;
+        }
+        if let Ok(no_proxy) =
+            std::env::var("ZEROCLAW_NO_PROXY").or_else(|_| std::env::var("NO_PROXY"))
+        {
+            self.proxy.no_proxy = normalize_no_proxy_list(vec![no_proxy]);
+        }
+
+        if explicit_proxy_enabled.is_none()
+            && proxy_url_overridden
+            && self.proxy.has_any_proxy_url()
+        {
+            self.proxy.enabled = true;
+        }
+
+        // Proxy scope and service selectors.
+        if let Ok(scope_raw) = std::env::var("ZEROCLAW_PROXY_SCOPE") {
+            if let Some(scope) = parse_proxy_scope(&scope_raw) {
+                self.proxy.scope = scope;
+            } else {
+                tracing::warn!(
+                    scope = %scope_raw,
+                    "Ignoring invalid ZEROCLAW_PROXY_SCOPE (valid: environment|zeroclaw|services)"
+                );
+            }
+        }
+
+        if let Ok(services_raw) = std::env::var("ZEROCLAW_PROXY_SERVICES") {
+            self.proxy.services = normalize_service_list(vec![services_raw]);
+        }
+
+        if let Err(error) = self.proxy.validate() {
+            tracing::warn!("Invalid proxy configuration ignored: {error}");
+            self.proxy.enabled = false;
+        }
+
+        if self.proxy.enabled && self.proxy.scope == ProxyScope::Environment {
+            self.proxy.apply_to_process_env();
+        }
+
+        set_runtime_proxy_config(self.proxy.clone());
+    }
+
+    /// Credentials currently loaded, as `(kind, value)` pairs, for exact-value
+    /// redaction of outbound content. Empty values are skipped.
+    pub fn loaded_secrets(&self) -> Vec<(&'static str, &str)> {
+        let mut secrets: Vec<(&'static str, Option<&str>)> = vec![
+            ("provider_api_key", self.api_key.as_deref()),
+            ("api_key", self.composio.api_key.as_deref()),
+            ("api_key", self.browser.computer_use.api_key.as_deref()),
+            ("api_key", self.web_search.brave_api_key.as_deref()),
+            ("api_key", self.memory.qdrant.api_key.as_deref()),
+            (
+                "database_url",
+                self.storage.provider.config.db_url.as_deref(),
+            ),
+            (
+                "access_token",
+                self.google_sheets
+                    .as_ref()
+                    .and_then(|sheets| sheets.access_token.as_deref()),
+            ),
+        ];
+        for key in &self.reliability.api_keys {
+            secrets.push(("provider_api_key", Some(key)));
+        }
+        for agent in self.agents.values() {
+            secrets.push(("provider_api_key", agent.api_key.as_deref()));
+        }
+        for route in &self.model_routes {
+            secrets.push(("provider_api_key", route.api_key.as_deref()));
+        }
+        for route in &self.embedding_routes {
+            secrets.push(("provider_api_key", route.api_key.as_deref()));
+        }
+        if let Some(cf) = &self.tunnel.cloudflare {
+            secrets.push(("tunnel_token", Some(&cf.token)));
+        }
+        if let Some(ngrok) = &self.tunnel.ngrok {
+            secrets.push(("tunnel_token", Some(&ngrok.auth_token)));
+        }
+
+        let channels = &self.channels_config;
+        if let Some(tg) = &channels.telegram {
+            secrets.push(("bot_token", Some(&tg.bot_token)));
+        }
+        if let Some(dc) = &channels.discord {
+            secrets.push(("bot_token", Some(&dc.bot_token)));
+        }
+        if let Some(sl) = &channels.slack {
+            secrets.push(("bot_token", Some(&sl.bot_token)));
+            secrets.push(("bot_token", sl.app_token.as_deref()));
+        }
+        if let Some(mm) = &channels.mattermost {
+            secrets.push(("bot_token", Some(&mm.bot_token)));
+        }
+        if let Some(wh) = &channels.webhook {
+            secrets.push(("signing_secret", wh.secret.as_deref()));
+        }
+        if let Some(mx) = &channels.matrix {
+            secrets.push(("access_token", Some(&mx.access_token)));
+        }
+        if let Some(wa) = &channels.whatsapp {
+            secrets.push(("access_token", wa.access_token.as_deref()));
+            secrets.push(("signing_secret", wa.verify_token.as_deref()));
+            secrets.push(("app_secret", wa.app_secret.as_deref()));
+        }
+        if let Some(linq) = &channels.linq {
+            secrets.push(("access_token", Some(&linq.api_token)));
+            secrets.push(("signing_secret", linq.signing_secret.as_deref()));
+        }
+        if let Some(wati) = &channels.wati {
+            secrets.push(("access_token", Some(&wati.api_token)));
+        }
+        if let Some(nc) = &channels.nextcloud_talk {
+            secrets.push(("bot_token", Some(&nc.app_token)));
+            secrets.push(("signing_secret", nc.webhook_secret.as_deref()));
+        }
+        let lark_apps = [
+            channels
+                .lark
+                .as_ref()
+                .map(|l| (&l.app_secret, &l.encrypt_key, &l.verification_token)),
+            channels
+                .feishu
+                .as_ref()
+                .map(|f| (&f.app_secret, &f.encrypt_key, &f.verification_token)),
+        ];
+        for (app_secret, encrypt_key, verification_token) in lark_apps.into_iter().flatten() {
+            secrets.push(("app_secret", Some(app_secret)));
+            secrets.push(("signing_secret", encrypt_key.as_deref()));
+            secrets.push(("signing_secret", verification_token.as_deref()));
+        }
+        if let Some(dt) = &channels.dingtalk {
+            secrets.push(("app_secret", Some(&dt.client_secret)));
+        }
+        if let Some(qq) = &channels.qq {
+            secrets.push(("app_secret", Some(&qq.app_secret)));
+        }
+        if let Some(ns) = &channels.nostr {
+            secrets.push(("private_key", Some(&ns.private_key)));
+        }
+
+        secrets
+            .into_iter()
+            .filter_map(|(kind, value)| {
+                let value = value?.trim();
+                (!value.is_empty()).then_some((kind, value))
+            })
+            .collect()
+    }
+
+    /// Credential fields stored encrypted on disk, with their config paths.
+    fn secret_fields_mut(&mut self) -> Vec<(&'static str, SecretField<'_>)> {
+        use SecretField::{Optional, Required};
+
+        let mut fields = vec![
+            ("config.api_key", Optional(&mut self.api_key)),
+            (
+                "config.composio.api_key",
+                Optional(&mut self.composio.api_key),
+            ),
+            (
+                "config.browser.computer_use.api_key",
+                Optional(&mut self.browser.computer_use.api_key),
+            ),
+            (
+                "config.web_search.brave_api_key",
+                Optional(&mut self.web_search.brave_api_key),
+            ),
+            (
+                "config.storage.provider.config.db_url",
+                Optional(&mut self.storage.provider.config.db_url),
+            ),
+        ];
+        if let Some(sheets) = &mut self.google_sheets {
+            fields.push((
+                "config.google_sheets.access_token",
+                Optional(&mut sheets.access_token),
+            ));
+        }
+        for agent in self.agents.values_mut() {
+            fields.push(("config.agents.*.api_key", Optional(&mut agent.api_key)));
+        }
+
+        let channels = &mut self.channels_config;
+        if let Some(tg) = &mut channels.telegram {
+            fields.push((
+                "config.channels_config.telegram.bot_token",
+                Required(&mut tg.bot_token),
+            ));
+        }
+        if let Some(dc) = &mut channels.discord {
+            fields.push((
+                "config.channels_config.discord.bot_token",
+                Required(&mut dc.bot_token),
+            ));
+        }
+        if let Some(sl) = &mut channels.slack {
+            fields.push((
+                "config.channels_config.slack.bot_token",
+                Required(&mut sl.bot_token),
+            ));
+            fields.push((
+                "config.channels_config.slack.app_token",
+                Optional(&mut sl.app_token),
+            ));
+        }
+        if let Some(mm) = &mut channels.mattermost {
+            fields.push((
+                "config.channels_config.mattermost.bot_token",
+                Required(&mut mm.bot_token),
+            ));
+        }
+        if let Some(wh) = &mut channels.webhook {
+            fields.push((
+                "config.channels_config.webhook.secret",
+                Optional(&mut wh.secret),
+            ));
+        }
+        if let Some(mx) = &mut channels.matrix {
+            fields.push((
+                "config.channels_config.matrix.access_token",
+                Required(&mut mx.access_token),
+            ));
+        }
+        if let Some(wa) = &mut channels.whatsapp {
+            fields.push((
+                "config.channels_config.whatsapp.access_token",
+                Optional(&mut wa.access_token),
+            ));
+            fields.push((
+                "config.channels_config.whatsapp.verify_token",
+                Optional(&mut wa.verify_token),
+            ));
+            fields.push((
+                "config.channels_config.whatsapp.app_secret",
+                Optional(&mut wa.app_secret),
+            ));
+        }
+        if let Some(linq) = &mut channels.linq {
+            fields.push((
+                "config.channels_config.linq.api_token",
+                Required(&mut linq.api_token),
+            ));
+            fields.push((
+                "config.channels_config.linq.signing_secret",
+                Optional(&mut linq.signing_secret),
+            ));
+        }
+        if let Some(wati) = &mut channels.wati {
+            fields.push((
+                "config.channels_config.wati.api_token",
+                Required(&mut wati.api_token),
+            ));
         }
-
-        // Storage connection URL (for remote backends): ZEROCLAW_STORAGE_DB_URL
-        if let Ok(db_url) = std::env::var("ZEROCLAW_STORAGE_DB_URL") {
-            let db_url = db_url.trim();
-            if !db_url.is_empty() {
-                self.storage.provider.config.db_url = Some(db_url.to_string());
-            }
+        if let Some(nc) = &mut channels.nextcloud_talk {
+            fields.push((
+                "config.channels_config.nextcloud_talk.app_token",
+                Required(&mut nc.app_token),
+            ));
+            fields.push((
+                "config.channels_config.nextcloud_talk.webhook_secret",
+                Optional(&mut nc.webhook_secret),
+            ));
         }
-
-        // Storage connect timeout: ZEROCLAW_STORAGE_CONNECT_TIMEOUT_SECS
-        if let Ok(timeout_secs) = std::env::var("ZEROCLAW_STORAGE_CONNECT_TIMEOUT_SECS") {
-            if let Ok(timeout_secs) = timeout_secs.parse::<u64>() {
-                if timeout_secs > 0 {
-                    self.storage.provider.config.connect_timeout_secs = Some(timeout_secs);
-                }
-            }
+        if let Some(email) = &mut channels.email {
+            fields.push((
+                "config.channels_config.email.password",
+                Required(&mut email.password),
+            ));
         }
-        // Proxy enabled flag: ZEROCLAW_PROXY_ENABLED
-        let explicit_proxy_enabled = std::env::var("ZEROCLAW_PROXY_ENABLED")
-            .ok()
-            .as_deref()
-            .and_then(parse_proxy_enabled);
-        if let Some(enabled) = explicit_proxy_enabled {
-            self.proxy.enabled = enabled;
+        if let Some(irc) = &mut channels.irc {
+            fields.push((
+                "config.channels_config.irc.server_password",
+                Optional(&mut irc.server_password),
+            ));
+            fields.push((
+                "config.channels_config.irc.nickserv_password",
+                Optional(&mut irc.nickserv_password),
+            ));
+            fields.push((
+                "config.channels_config.irc.sasl_password",
+                Optional(&mut irc.sasl_password),
+            ));
         }
-
-        // Proxy URLs: ZEROCLAW_* wins, then generic *PROXY vars.
-        let mut proxy_url_overridden = false;
-        if let Ok(proxy_url) =
-            std::env::var("ZEROCLAW_HTTP_PROXY").or_else(|_| std::env::var("HTTP_PROXY"))
-        {
-            self.proxy.http_proxy = normalize_proxy_url_option(Some(&proxy_url));
-            proxy_url_overridden = true;
+        if let Some(lark) = &mut channels.lark {
+            fields.push((
+                "config.channels_config.lark.app_secret",
+                Required(&mut lark.app_secret),
+            ));
+            fields.push((
+                "config.channels_config.lark.encrypt_key",
+                Optional(&mut lark.encrypt_key),
+            ));
+            fields.push((
+                "config.channels_config.lark.verification_token",
+                Optional(&mut lark.verification_token),
+            ));
         }
-        if let Ok(proxy_url) =
-            std::env::var("ZEROCLAW_HTTPS_PROXY").or_else(|_| std::env::var("HTTPS_PROXY"))
-        {
-            self.proxy.https_proxy = normalize_proxy_url_option(Some(&proxy_url));
-            proxy_url_overridden = true;
+        if let Some(feishu) = &mut channels.feishu {
+            fields.push((
+                "config.channels_config.feishu.app_secret",
+                Required(&mut feishu.app_secret),
+            ));
+            fields.push((
+                "config.channels_config.feishu.encrypt_key",
+                Optional(&mut feishu.encrypt_key),
+            ));
+            fields.push((
+                "config.channels_config.feishu.verification_token",
+                Optional(&mut feishu.verification_token),
+            ));
         }
-        if let Ok(proxy_url) =
-            std::env::var("ZEROCLAW_ALL_PROXY").or_else(|_| std::env::var("ALL_PROXY"))
-        {
-            self.proxy.all_proxy = normalize_proxy_url_option(Some(&proxy_url));
-            proxy_url_overridden = true;
+        if let Some(dt) = &mut channels.dingtalk {
+            fields.push((
+                "config.channels_config.dingtalk.client_secret",
+                Required(&mut dt.client_secret),
+            ));
         }
-        if let Ok(no_proxy) =
-            std::env::var("ZEROCLAW_NO_PROXY").or_else(|_| std::env::var("NO_PROXY"))
-        {
-            self.proxy.no_proxy = normalize_no_proxy_list(vec![no_proxy]);
+        if let Some(qq) = &mut channels.qq {
+            fields.push((
+                "config.channels_config.qq.app_secret",
+                Required(&mut qq.app_secret),
+            ));
         }
-
-        if explicit_proxy_enabled.is_none()
-            && proxy_url_overridden
-            && self.proxy.has_any_proxy_url()
-        {
-            self.proxy.enabled = true;
+        if let Some(ns) = &mut channels.nostr {
+            fields.push((
+                "config.channels_config.nostr.private_key",
+                Required(&mut ns.private_key),
+            ));
         }
-
-        // Proxy scope and service selectors.
-        if let Ok(scope_raw) = std::env::var("ZEROCLAW_PROXY_SCOPE") {
-            if let Some(scope) = parse_proxy_scope(&scope_raw) {
-                self.proxy.scope = scope;
-            } else {
-                tracing::warn!(
-                    scope = %scope_raw,
-                    "Ignoring invalid ZEROCLAW_PROXY_SCOPE (valid: environment|zeroclaw|services)"
-                );
-            }
+        if let Some(ct) = &mut channels.clawdtalk {
+            fields.push((
+                "config.channels_config.clawdtalk.api_key",
+                Required(&mut ct.api_key),
+            ));
+            fields.push((
+                "config.channels_config.clawdtalk.webhook_secret",
+                Optional(&mut ct.webhook_secret),
+            ));
         }
+        fields
+    }
 
-        if let Ok(services_raw) = std::env::var("ZEROCLAW_PROXY_SERVICES") {
-            self.proxy.services = normalize_service_list(vec![services_raw]);
+    /// Decrypt credential fields in place (values without an `enc` prefix are left as-is).
+    fn decrypt_secrets(&mut self, store: &crate::security::SecretStore) -> Result<()> {
+        for (name, field) in self.secret_fields_mut() {
+            match field {
+                SecretField::Required(value) => decrypt_secret(store, value, name)?,
+                SecretField::Optional(value) => decrypt_optional_secret(store, value, name)?,
+            }
         }
+        Ok(())
+    }
 
-        if let Err(error) = self.proxy.validate() {
-            tracing::warn!("Invalid proxy configuration ignored: {error}");
-            self.proxy.enabled = false;
+    /// Encrypt credential fields in place (already-encrypted values and
+    /// `env:`/`file:` references are left as-is). Returns how many fields
+    /// hold an encrypted value afterwards.
+    fn encrypt_secrets(&mut self, store: &crate::security::SecretStore) -> Result<usize> {
+        let mut encrypted = 0;
+        for (name, field) in self.secret_fields_mut() {
+            let value = match field {
+                SecretField::Required(value) => {
+                    encrypt_secret(store, value, name)?;
+                    Some(value.as_str())
+                }
+                SecretField::Optional(value) => {
+                    encrypt_optional_secret(store, value, name)?;
+                    value.as_deref()
+                }
+            };
+            if value.is_some_and(crate::security::SecretStore::is_encrypted) {
+                encrypted += 1;
+            }
         }
+        Ok(encrypted)
+    }
 
-        if self.proxy.enabled && self.proxy.scope == ProxyScope::Environment {
-            self.proxy.apply_to_process_env();
-        }
+    /// Re-encrypt the credentials stored in `config.toml` with the current
+    /// secret store — passphrase-derived (`enc3:`) when
+    /// `ZEROCLAW_SECRETS_PASSPHRASE` is set. Works from the file itself so env
+    /// overrides are not persisted and `env:`/`file:` references stay as written.
+    /// Returns the number of encrypted values in the rewritten file.
+    pub async fn encrypt_secrets_on_disk(&self) -> Result<usize> {
+        let zeroclaw_dir = self
+            .config_path
+            .parent()
+            .context("Config path must have a parent directory")?;
+        let contents = fs::read_to_string(&self.config_path)
+            .await
+            .with_context(|| format!("Failed to read {}", self.config_path.display()))?;
+        let mut on_disk: Config =
+            toml::from_str(&contents).context("Failed to parse config file")?;
+        on_disk.config_path = self.config_path.clone();
+        on_disk.workspace_dir = self.workspace_dir.clone();
+        on_disk.secrets.encrypt = true;
 
-        set_runtime_proxy_config(self.proxy.clone());
+        let store = crate::security::SecretStore::new(zeroclaw_dir, true);
+        on_disk.decrypt_secrets(&store)?;
+        on_disk.write_to_disk().await
     }
 
     pub async fn save(&self) -> Result<()> {
+        self.write_to_disk().await.map(drop)
+    }
+
+    /// Write the config atomically, returning how many credential fields
+    /// were stored encrypted.
+    async fn write_to_disk(&self) -> Result<usize> {
         // Encrypt secrets before serialization
         let mut config_to_save = self.clone();
         let zeroclaw_dir = self
@@ -4687,45 +6270,17 @@ impl Config {
             .context("Config path must have a parent directory")?;
         let store = crate::security::SecretStore::new(zeroclaw_dir, self.secrets.encrypt);
 
-        encrypt_optional_secret(&store, &mut config_to_save.api_key, "config.api_key")?;
-        encrypt_optional_secret(
-            &store,
-            &mut config_to_save.composio.api_key,
-            "config.composio.api_key",
-        )?;
-
-        encrypt_optional_secret(
-            &store,
-            &mut config_to_save.browser.computer_use.api_key,
-            "config.browser.computer_use.api_key",
-        )?;
-
-        encrypt_optional_secret(
-            &store,
-            &mut config_to_save.web_search.brave_api_key,
-            "config.web_search.brave_api_key",
-        )?;
-
-        encrypt_optional_secret(
-            &store,
-            &mut config_to_save.storage.provider.config.db_url,
-            "config.storage.provider.config.db_url",
-        )?;
-
-        for agent in config_to_save.agents.values_mut() {
-            encrypt_optional_secret(&store, &mut agent.api_key, "config.agents.*.api_key")?;
-        }
-
-        if let Some(ref mut ns) = config_to_save.channels_config.nostr {
-            encrypt_secret(
-                &store,
-                &mut ns.private_key,
-                "config.channels_config.nostr.private_key",
-            )?;
-        }
+        let encrypted = config_to_save.encrypt_secrets(&store)?;
 
-        let toml_str =
-            toml::to_string_pretty(&config_to_save).context("Failed to serialize config")?;
+        let toml_str = if self.secret_refs.is_empty() {
+            toml::to_string_pretty(&config_to_save).context("Failed to serialize config")?
+        } else {
+            let current = toml::Value::try_from(self).context("Failed to serialize config")?;
+            let mut saved =
+                toml::Value::try_from(&config_to_save).context("Failed to serialize config")?;
+            super::secret_refs::restore_secret_refs(&mut saved, &current, &self.secret_refs);
+            toml::to_string_pretty(&saved).context("Failed to serialize config")?
+        };
 
         let parent_dir = self
             .config_path
@@ -4810,10 +6365,16 @@ impl Config {
             let _ = fs::remove_file(&backup_path).await;
         }
 
-        Ok(())
+        Ok(encrypted)
     }
 }
 
+/// A credential field of [`Config`], for encrypting and decrypting in place.
+enum SecretField<'a> {
+    Required(&'a mut String),
+    Optional(&'a mut Option<String>),
+}
+
 async fn sync_directory(path: &Path) -> Result<()> {
     #[cfg(unix)]
     {
@@ -4864,6 +6425,7 @@ mod tests {
         assert!((c.default_temperature - 0.7).abs() < f64::EPSILON);
         assert!(c.api_key.is_none());
         assert!(!c.skills.open_skills_enabled);
+        assert!(!c.skills.hot_reload);
         assert_eq!(
             c.skills.prompt_injection_mode,
             SkillsPromptInjectionMode::Full
@@ -5066,6 +6628,7 @@ default_temperature = 0.7
         let config = Config {
             workspace_dir: PathBuf::from("/tmp/test/workspace"),
             config_path: PathBuf::from("/tmp/test/config.toml"),
+            secret_refs: Vec::new(),
             api_key: Some("sk-test-key".into()),
             api_url: None,
             default_provider: Some("openrouter".into()),
@@ -5097,6 +6660,8 @@ default_temperature = 0.7
                 ..RuntimeConfig::default()
             },
             reliability: ReliabilityConfig::default(),
+            openrouter: OpenRouterConfig::default(),
+            session_compaction: SessionCompactionConfig::default(),
             scheduler: SchedulerConfig::default(),
             skills: SkillsConfig::default(),
             model_routes: Vec::new(),
@@ -5110,6 +6675,8 @@ default_temperature = 0.7
                 to: Some("123456".into()),
             },
             cron: CronConfig::default(),
+            quiet_hours: QuietHoursConfig::default(),
+            timezone: None,
             channels_config: ChannelsConfig {
                 cli: true,
                 telegram: Some(TelegramConfig {
@@ -5119,6 +6686,8 @@ default_temperature = 0.7
                     draft_update_interval_ms: default_draft_update_interval_ms(),
                     interrupt_on_new_message: false,
                     mention_only: false,
+                    command_prefix: None,
+                    progress_messages: false,
                 }),
                 discord: None,
                 slack: None,
@@ -5131,6 +6700,7 @@ default_temperature = 0.7
                 linq: None,
                 wati: None,
                 nextcloud_talk: None,
+                google_chat: None,
                 email: None,
                 irc: None,
                 lark: None,
@@ -5140,18 +6710,25 @@ default_temperature = 0.7
                 nostr: None,
                 clawdtalk: None,
                 message_timeout_secs: 300,
+                interrupt_on_new_message: false,
+                inbound_dedup_ttl_secs: 600,
+                inbound_dedup_max_keys: 10_000,
+                error_messages: ErrorMessagesConfig::default(),
+                inbound_guard: InboundGuardConfig::default(),
             },
             memory: MemoryConfig::default(),
             storage: StorageConfig::default(),
             tunnel: TunnelConfig::default(),
             gateway: GatewayConfig::default(),
             composio: ComposioConfig::default(),
+            google_sheets: None,
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             http_request: HttpRequestConfig::default(),
             multimodal: MultimodalConfig::default(),
             web_fetch: WebFetchConfig::default(),
             web_search: WebSearchConfig::default(),
+            web: WebConfig::default(),
             proxy: ProxyConfig::default(),
             agent: AgentConfig::default(),
             identity: IdentityConfig::default(),
@@ -5304,6 +6881,7 @@ tool_dispatcher = "xml"
         let config = Config {
             workspace_dir: dir.join("workspace"),
             config_path: config_path.clone(),
+            secret_refs: Vec::new(),
             api_key: Some("sk-roundtrip".into()),
             api_url: None,
             default_provider: Some("openrouter".into()),
@@ -5315,6 +6893,8 @@ tool_dispatcher = "xml"
             security: SecurityConfig::default(),
             runtime: RuntimeConfig::default(),
             reliability: ReliabilityConfig::default(),
+            openrouter: OpenRouterConfig::default(),
+            session_compaction: SessionCompactionConfig::default(),
             scheduler: SchedulerConfig::default(),
             skills: SkillsConfig::default(),
             model_routes: Vec::new(),
@@ -5322,18 +6902,22 @@ tool_dispatcher = "xml"
             query_classification: QueryClassificationConfig::default(),
             heartbeat: HeartbeatConfig::default(),
             cron: CronConfig::default(),
+            quiet_hours: QuietHoursConfig::default(),
+            timezone: None,
             channels_config: ChannelsConfig::default(),
             memory: MemoryConfig::default(),
             storage: StorageConfig::default(),
             tunnel: TunnelConfig::default(),
             gateway: GatewayConfig::default(),
             composio: ComposioConfig::default(),
+            google_sheets: None,
             secrets: SecretsConfig::default(),
             browser: BrowserConfig::default(),
             http_request: HttpRequestConfig::default(),
             multimodal: MultimodalConfig::default(),
             web_fetch: WebFetchConfig::default(),
             web_search: WebSearchConfig::default(),
+            web: WebConfig::default(),
             proxy: ProxyConfig::default(),
             agent: AgentConfig::default(),
             identity: IdentityConfig::default(),
@@ -5490,6 +7074,8 @@ tool_dispatcher = "xml"
             draft_update_interval_ms: 500,
             interrupt_on_new_message: true,
             mention_only: false,
+            command_prefix: None,
+            progress_messages: false,
         };
         let json = serde_json::to_string(&tc).unwrap();
         let parsed: TelegramConfig = serde_json::from_str(&json).unwrap();
@@ -5517,6 +7103,8 @@ tool_dispatcher = "xml"
             allowed_users: vec![],
             listen_to_bots: false,
             mention_only: false,
+            command_prefix: None,
+            command_users: vec![],
         };
         let json = serde_json::to_string(&dc).unwrap();
         let parsed: DiscordConfig = serde_json::from_str(&json).unwrap();
@@ -5532,6 +7120,8 @@ tool_dispatcher = "xml"
             allowed_users: vec![],
             listen_to_bots: false,
             mention_only: false,
+            command_prefix: None,
+            command_users: vec![],
         };
         let json = serde_json::to_string(&dc).unwrap();
         let parsed: DiscordConfig = serde_json::from_str(&json).unwrap();
@@ -5695,6 +7285,7 @@ allowed_users = ["@ops:matrix.org"]
             linq: None,
             wati: None,
             nextcloud_talk: None,
+            google_chat: None,
             email: None,
             irc: None,
             lark: None,
@@ -5704,6 +7295,11 @@ allowed_users = ["@ops:matrix.org"]
             nostr: None,
             clawdtalk: None,
             message_timeout_secs: 300,
+            interrupt_on_new_message: false,
+            inbound_dedup_ttl_secs: 600,
+            inbound_dedup_max_keys: 10_000,
+            error_messages: ErrorMessagesConfig::default(),
+            inbound_guard: InboundGuardConfig::default(),
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -5801,6 +7397,8 @@ channel_id = "C123"
             pair_phone: None,
             pair_code: None,
             allowed_numbers: vec!["+1234567890".into(), "+9876543210".into()],
+            max_event_age_secs: 3600,
+            template: None,
         };
         let json = serde_json::to_string(&wc).unwrap();
         let parsed: WhatsAppConfig = serde_json::from_str(&json).unwrap();
@@ -5821,6 +7419,8 @@ channel_id = "C123"
             pair_phone: None,
             pair_code: None,
             allowed_numbers: vec!["+1".into()],
+            max_event_age_secs: 3600,
+            template: None,
         };
         let toml_str = toml::to_string(&wc).unwrap();
         let parsed: WhatsAppConfig = toml::from_str(&toml_str).unwrap();
@@ -5846,6 +7446,8 @@ channel_id = "C123"
             pair_phone: None,
             pair_code: None,
             allowed_numbers: vec!["*".into()],
+            max_event_age_secs: 3600,
+            template: None,
         };
         let toml_str = toml::to_string(&wc).unwrap();
         let parsed: WhatsAppConfig = toml::from_str(&toml_str).unwrap();
@@ -5863,6 +7465,8 @@ channel_id = "C123"
             pair_phone: None,
             pair_code: None,
             allowed_numbers: vec!["+1".into()],
+            max_event_age_secs: 3600,
+            template: None,
         };
         assert!(wc.is_ambiguous_config());
         assert_eq!(wc.backend_type(), "cloud");
@@ -5879,6 +7483,8 @@ channel_id = "C123"
             pair_phone: None,
             pair_code: None,
             allowed_numbers: vec![],
+            max_event_age_secs: 3600,
+            template: None,
         };
         assert!(!wc.is_ambiguous_config());
         assert_eq!(wc.backend_type(), "web");
@@ -5905,10 +7511,13 @@ channel_id = "C123"
                 pair_phone: None,
                 pair_code: None,
                 allowed_numbers: vec!["+1".into()],
+                max_event_age_secs: 3600,
+                template: None,
             }),
             linq: None,
             wati: None,
             nextcloud_talk: None,
+            google_chat: None,
             email: None,
             irc: None,
             lark: None,
@@ -5918,6 +7527,11 @@ channel_id = "C123"
             nostr: None,
             clawdtalk: None,
             message_timeout_secs: 300,
+            interrupt_on_new_message: false,
+            inbound_dedup_ttl_secs: 600,
+            inbound_dedup_max_keys: 10_000,
+            error_messages: ErrorMessagesConfig::default(),
+            inbound_guard: InboundGuardConfig::default(),
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -5967,6 +7581,7 @@ channel_id = "C123"
         );
         assert_eq!(g.pair_rate_limit_per_minute, 10);
         assert_eq!(g.webhook_rate_limit_per_minute, 60);
+        assert_eq!(g.inbound_rate_limit_per_minute, 30);
         assert!(!g.trust_forwarded_headers);
         assert_eq!(g.rate_limit_max_keys, 10_000);
         assert_eq!(g.idempotency_ttl_secs, 300);
@@ -5998,10 +7613,24 @@ channel_id = "C123"
             paired_tokens: vec!["zc_test_token".into()],
             pair_rate_limit_per_minute: 12,
             webhook_rate_limit_per_minute: 80,
+            inbound_rate_limit_per_minute: 20,
             trust_forwarded_headers: true,
             rate_limit_max_keys: 2048,
             idempotency_ttl_secs: 600,
             idempotency_max_keys: 4096,
+            max_concurrent_turns: 2,
+            inbound_queue_capacity: 8,
+            tls: Some(GatewayTlsConfig {
+                cert_path: "/etc/zeroclaw/cert.pem".into(),
+                key_path: "/etc/zeroclaw/key.pem".into(),
+            }),
+            allow_insecure: false,
+            attachment_retention_hours: 6,
+            log_stream: GatewayLogStreamConfig {
+                enabled: true,
+                info_targets: vec!["audit".into(), "zeroclaw::tools".into()],
+                max_per_second: 5,
+            },
         };
         let toml_str = toml::to_string(&g).unwrap();
         let parsed: GatewayConfig = toml::from_str(&toml_str).unwrap();
@@ -6010,10 +7639,101 @@ channel_id = "C123"
         assert_eq!(parsed.paired_tokens, vec!["zc_test_token"]);
         assert_eq!(parsed.pair_rate_limit_per_minute, 12);
         assert_eq!(parsed.webhook_rate_limit_per_minute, 80);
+        assert_eq!(parsed.inbound_rate_limit_per_minute, 20);
         assert!(parsed.trust_forwarded_headers);
         assert_eq!(parsed.rate_limit_max_keys, 2048);
         assert_eq!(parsed.idempotency_ttl_secs, 600);
         assert_eq!(parsed.idempotency_max_keys, 4096);
+        assert_eq!(parsed.max_concurrent_turns, 2);
+        assert_eq!(parsed.inbound_queue_capacity, 8);
+        assert_eq!(parsed.tls, g.tls);
+        assert!(!parsed.allow_insecure);
+        assert_eq!(parsed.attachment_retention_hours, 6);
+        assert_eq!(parsed.log_stream, g.log_stream);
+    }
+
+    #[test]
+    async fn agent_channel_overrides_parse_and_validate() {
+        let raw = r#"
+workspace_dir = "/tmp/ws"
+config_path = "/tmp/config.toml"
+default_temperature = 0.7
+
+[agent.channel_overrides.whatsapp]
+system_prompt_suffix = "Keep replies short and emoji-light."
+max_tokens = 400
+
+[agent.channel_overrides.cron]
+system_prompt_suffix = "Do not address the user."
+temperature = 0.2
+"#;
+        let parsed: Config = toml::from_str(raw).unwrap();
+        let whatsapp = parsed.agent.channel_override("whatsapp").unwrap();
+        assert_eq!(whatsapp.max_tokens, Some(400));
+        assert_eq!(whatsapp.temperature, None);
+        assert_eq!(
+            parsed.agent.channel_override("cron").unwrap().temperature,
+            Some(0.2)
+        );
+        assert!(parsed.agent.channel_override("discord").is_none());
+        parsed.validate().unwrap();
+
+        let mut hot = parsed.clone();
+        hot.agent
+            .channel_overrides
+            .get_mut("cron")
+            .unwrap()
+            .temperature = Some(3.0);
+        let err = hot.validate().unwrap_err().to_string();
+        assert!(
+            err.contains("agent.channel_overrides.cron.temperature"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    async fn gateway_tls_section_parses_and_validates() {
+        let with_tls = r#"
+workspace_dir = "/tmp/ws"
+config_path = "/tmp/config.toml"
+default_temperature = 0.7
+
+[gateway]
+host = "0.0.0.0"
+allow_public_bind = true
+
+[gateway.tls]
+cert_path = "/data/certs/gateway.pem"
+key_path = "/data/certs/gateway.key"
+"#;
+        let parsed: Config = toml::from_str(with_tls).unwrap();
+        let tls = parsed
+            .gateway
+            .tls
+            .as_ref()
+            .expect("tls section should parse");
+        assert_eq!(tls.cert_path, "/data/certs/gateway.pem");
+        assert_eq!(tls.key_path, "/data/certs/gateway.key");
+        assert!(!parsed.gateway.allow_insecure);
+        parsed.validate().unwrap();
+
+        let mut missing_key = parsed.clone();
+        missing_key.gateway.tls.as_mut().unwrap().key_path = " ".into();
+        let err = missing_key.validate().unwrap_err().to_string();
+        assert!(err.contains("gateway.tls.key_path"), "{err}");
+
+        let incomplete = with_tls.replace("key_path = \"/data/certs/gateway.key\"\n", "");
+        assert!(toml::from_str::<Config>(&incomplete).is_err());
+
+        let plain: Config = toml::from_str(
+            r#"
+workspace_dir = "/tmp/ws"
+config_path = "/tmp/config.toml"
+default_temperature = 0.7
+"#,
+        )
+        .unwrap();
+        assert!(plain.gateway.tls.is_none());
     }
 
     #[test]
@@ -6591,6 +8311,27 @@ requires_openai_auth = true
             .contains("wire_api must be one of: responses, chat_completions"));
     }
 
+    #[test]
+    async fn validate_rejects_malformed_quiet_hours() {
+        let mut config = Config::default();
+        config.quiet_hours.channels.insert(
+            "telegram".to_string(),
+            QuietHoursOverride {
+                end: Some("7am".to_string()),
+                ..QuietHoursOverride::default()
+            },
+        );
+
+        let error = config.validate().expect_err("expected validation failure");
+        assert!(error
+            .to_string()
+            .contains("quiet_hours.channels.telegram.end must be a HH:MM time"));
+
+        config.quiet_hours.channels.clear();
+        config.quiet_hours.start = "23:30".to_string();
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     async fn env_override_model_fallback() {
         let _env_guard = env_override_lock().await;
@@ -6750,6 +8491,147 @@ requires_openai_auth = true
         let _ = fs::remove_dir_all(temp_home).await;
     }
 
+    #[test]
+    async fn load_or_init_resolves_secret_refs_and_save_keeps_them() {
+        let _env_guard = env_override_lock().await;
+        let temp_home =
+            std::env::temp_dir().join(format!("zeroclaw_test_home_{}", uuid::Uuid::new_v4()));
+        let workspace_dir = temp_home.join("profile-refs");
+        fs::create_dir_all(&workspace_dir).await.unwrap();
+        fs::write(workspace_dir.join("brave.key"), "brave-from-file\n")
+            .await
+            .unwrap();
+        fs::write(
+            workspace_dir.join("config.toml"),
+            "default_temperature = 0.7\napi_key = \"env:ZEROCLAW_TEST_REF_API_KEY\"\n\
+             [web_search]\nbrave_api_key = \"file:brave.key\"\n",
+        )
+        .await
+        .unwrap();
+
+        let original_home = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &temp_home);
+        std::env::set_var("ZEROCLAW_WORKSPACE", &workspace_dir);
+        std::env::set_var("ZEROCLAW_TEST_REF_API_KEY", "sk-from-env-ref");
+
+        let config = Config::load_or_init().await.unwrap();
+        assert_eq!(config.api_key.as_deref(), Some("sk-from-env-ref"));
+        assert_eq!(
+            config.web_search.brave_api_key.as_deref(),
+            Some("brave-from-file")
+        );
+
+        let debug = format!("{config:?}");
+        assert!(!debug.contains("sk-from-env-ref"), "{debug}");
+        assert!(!debug.contains("brave-from-file"), "{debug}");
+        assert!(debug.contains("[REDACTED]"));
+
+        config.save().await.unwrap();
+        let saved = fs::read_to_string(workspace_dir.join("config.toml"))
+            .await
+            .unwrap();
+        assert!(saved.contains("env:ZEROCLAW_TEST_REF_API_KEY"), "{saved}");
+        assert!(saved.contains("file:brave.key"), "{saved}");
+        assert!(!saved.contains("sk-from-env-ref"));
+
+        std::env::remove_var("ZEROCLAW_TEST_REF_API_KEY");
+        let err = Config::load_or_init().await.unwrap_err();
+        assert!(
+            format!("{err:#}").contains("ZEROCLAW_TEST_REF_API_KEY is not set"),
+            "{err:#}"
+        );
+
+        std::env::remove_var("ZEROCLAW_WORKSPACE");
+        if let Some(home) = original_home {
+            std::env::set_var("HOME", home);
+        } else {
+            std::env::remove_var("HOME");
+        }
+        let _ = fs::remove_dir_all(temp_home).await;
+    }
+
+    #[test]
+    async fn encrypt_secrets_on_disk_keeps_references() {
+        let tmp = TempDir::new().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            // Env overrides applied at load must not end up in the file.
+            api_key: Some("sk-from-env-override".into()),
+            ..Config::default()
+        };
+        fs::write(
+            &config.config_path,
+            "default_temperature = 0.7\napi_key = \"sk-plain-on-disk\"\n[composio]\napi_key = \"env:COMPOSIO_KEY\"\n\
+             [secrets]\nencrypt = false\n",
+        )
+        .await
+        .unwrap();
+
+        let encrypted = config.encrypt_secrets_on_disk().await.unwrap();
+        assert_eq!(encrypted, 1);
+
+        let saved = fs::read_to_string(&config.config_path).await.unwrap();
+        let stored: Config = toml::from_str(&saved).unwrap();
+        assert!(stored.secrets.encrypt);
+        assert_eq!(stored.composio.api_key.as_deref(), Some("env:COMPOSIO_KEY"));
+        let api_key = stored.api_key.unwrap();
+        assert!(api_key.starts_with("enc"));
+        let store = crate::security::SecretStore::new(tmp.path(), true);
+        assert_eq!(store.decrypt(&api_key).unwrap(), "sk-plain-on-disk");
+    }
+
+    #[test]
+    async fn channel_tokens_are_encrypted_on_save() {
+        let tmp = TempDir::new().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        };
+        fs::write(
+            &config.config_path,
+            "default_temperature = 0.7\n\
+             [channels_config]\ncli = true\n\
+             [channels_config.telegram]\nbot_token = \"123:tg-token\"\nallowed_users = []\n\
+             [channels_config.webhook]\nport = 8080\nsecret = \"hook-secret\"\n\
+             [secrets]\nencrypt = false\n",
+        )
+        .await
+        .unwrap();
+
+        let encrypted = config.encrypt_secrets_on_disk().await.unwrap();
+        assert_eq!(encrypted, 2);
+
+        let saved = fs::read_to_string(&config.config_path).await.unwrap();
+        assert!(!saved.contains("123:tg-token"));
+        assert!(!saved.contains("hook-secret"));
+
+        let mut stored: Config = toml::from_str(&saved).unwrap();
+        let store = crate::security::SecretStore::new(tmp.path(), true);
+        stored.decrypt_secrets(&store).unwrap();
+        let channels = &stored.channels_config;
+        assert_eq!(
+            channels.telegram.as_ref().unwrap().bot_token,
+            "123:tg-token"
+        );
+        assert_eq!(
+            channels.webhook.as_ref().unwrap().secret.as_deref(),
+            Some("hook-secret")
+        );
+    }
+
+    #[test]
+    async fn config_debug_redacts_credentials() {
+        let mut config = Config::default();
+        config.api_key = Some("sk-plain-debug".into());
+        config.composio.api_key = Some("composio-debug".into());
+        let debug = format!("{config:?}");
+        assert!(!debug.contains("sk-plain-debug"));
+        assert!(!debug.contains("composio-debug"));
+        assert!(debug.contains("default_temperature"));
+    }
+
     #[test]
     async fn load_or_init_workspace_suffix_uses_legacy_config_layout() {
         let _env_guard = env_override_lock().await;
@@ -7644,4 +9526,30 @@ require_otp_to_resume = true
             .expect_err("expected ttl validation failure");
         assert!(err.to_string().contains("token_ttl_secs"));
     }
+
+    #[test]
+    async fn sandbox_command_filter_parses_and_validates_regexes() {
+        let parsed: Config = toml::from_str(
+            r#"
+default_temperature = 0.7
+
+[security.sandbox]
+command_filter = "warn-and-audit"
+blocked_patterns = ["git push --force"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.security.sandbox.command_filter,
+            CommandFilterMode::Warn
+        );
+        assert_eq!(parsed.security.sandbox.blocked_patterns.len(), 1);
+        assert!(!parsed.security.sandbox.blocked_regexes.is_empty());
+        parsed.validate().unwrap();
+
+        let mut config = Config::default();
+        config.security.sandbox.blocked_regexes = vec!["(unclosed".into()];
+        let err = config.validate().expect_err("expected invalid regex");
+        assert!(err.to_string().contains("blocked_regexes[0]"));
+    }
 }
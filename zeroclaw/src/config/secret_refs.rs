@@ -0,0 +1,255 @@
+//! `env:` / `file:` indirection for config values.
+//!
+//! Any string in `config.toml` may be written as `"env:VAR_NAME"` or
+//! `"file:/path/to/secret"` instead of a literal. References are resolved
+//! once at load time, before deserialization, so the rest of the code only
+//! ever sees plain values. The references are remembered and written back on
+//! save, so `Config::save` never turns an indirection into a literal secret.
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+/// A config value that was loaded through an `env:` or `file:` reference.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretRef {
+    /// Location in the TOML tree (table keys, array indices as numbers).
+    pub path: Vec<String>,
+    /// The reference as written in the file, e.g. `env:OPENAI_API_KEY`.
+    pub reference: String,
+    resolved: String,
+}
+
+impl std::fmt::Debug for SecretRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretRef")
+            .field("path", &self.dotted_path())
+            .field("reference", &self.reference)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SecretRef {
+    pub fn dotted_path(&self) -> String {
+        self.path.join(".")
+    }
+
+    /// The value the reference resolved to at load time.
+    pub fn resolved(&self) -> &str {
+        &self.resolved
+    }
+}
+
+/// Whether a string is an `env:` / `file:` reference rather than a literal.
+/// `file://` URLs are left alone.
+pub fn is_reference(raw: &str) -> bool {
+    raw.starts_with("env:") || (raw.starts_with("file:") && !raw.starts_with("file://"))
+}
+
+/// Resolve a single reference. `base_dir` anchors relative `file:` paths.
+pub fn resolve_reference(raw: &str, field: &str, base_dir: &Path) -> Result<String> {
+    if let Some(name) = raw.strip_prefix("env:") {
+        let name = name.trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            bail!("{field}: invalid environment variable name in \"{raw}\"");
+        }
+        return match std::env::var(name) {
+            Ok(value) if !value.is_empty() => Ok(value),
+            Ok(_) => bail!("{field}: environment variable {name} is set but empty"),
+            Err(std::env::VarError::NotPresent) => {
+                bail!("{field}: environment variable {name} is not set")
+            }
+            Err(std::env::VarError::NotUnicode(_)) => {
+                bail!("{field}: environment variable {name} is not valid UTF-8")
+            }
+        };
+    }
+
+    if let Some(path) = raw.strip_prefix("file:") {
+        let expanded = shellexpand::tilde(path.trim()).into_owned();
+        if expanded.is_empty() {
+            bail!("{field}: empty path in \"{raw}\"");
+        }
+        let path = base_dir.join(expanded);
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("{field}: failed to read secret file {}", path.display()))?;
+        // Secret files usually end with a newline (`echo token > file`).
+        let value = contents.trim_end_matches(['\r', '\n']);
+        if value.is_empty() {
+            bail!("{field}: secret file {} is empty", path.display());
+        }
+        return Ok(value.to_string());
+    }
+
+    Ok(raw.to_string())
+}
+
+/// Replace every reference in `value` with what it points to.
+pub fn resolve_secret_refs(value: &mut toml::Value, base_dir: &Path) -> Result<Vec<SecretRef>> {
+    let mut refs = Vec::new();
+    let mut path = Vec::new();
+    resolve_in(value, base_dir, &mut path, &mut refs)?;
+    Ok(refs)
+}
+
+fn resolve_in(
+    value: &mut toml::Value,
+    base_dir: &Path,
+    path: &mut Vec<String>,
+    refs: &mut Vec<SecretRef>,
+) -> Result<()> {
+    match value {
+        toml::Value::String(raw) if is_reference(raw) => {
+            let field = format!("config.{}", path.join("."));
+            let resolved = resolve_reference(raw, &field, base_dir)?;
+            refs.push(SecretRef {
+                path: path.clone(),
+                reference: std::mem::replace(raw, resolved.clone()),
+                resolved,
+            });
+        }
+        toml::Value::Table(table) => {
+            for (key, child) in table.iter_mut() {
+                path.push(key.clone());
+                resolve_in(child, base_dir, path, refs)?;
+                path.pop();
+            }
+        }
+        toml::Value::Array(items) => {
+            for (index, child) in items.iter_mut().enumerate() {
+                path.push(index.to_string());
+                resolve_in(child, base_dir, path, refs)?;
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn lookup<'a>(value: &'a toml::Value, path: &[String]) -> Option<&'a toml::Value> {
+    path.iter().try_fold(value, |node, segment| match node {
+        toml::Value::Table(table) => table.get(segment),
+        toml::Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+pub(crate) fn lookup_mut<'a>(
+    value: &'a mut toml::Value,
+    path: &[String],
+) -> Option<&'a mut toml::Value> {
+    path.iter().try_fold(value, |node, segment| match node {
+        toml::Value::Table(table) => table.get_mut(segment),
+        toml::Value::Array(items) => items.get_mut(segment.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+/// Put references back into a serialized config before it is written.
+///
+/// `current` is the unencrypted in-memory config; a reference is only
+/// restored while its field still holds the value it resolved to, so a value
+/// changed at runtime (e.g. via onboarding) is saved as the new literal.
+pub fn restore_secret_refs(saved: &mut toml::Value, current: &toml::Value, refs: &[SecretRef]) {
+    for secret_ref in refs {
+        let unchanged = lookup(current, &secret_ref.path).and_then(toml::Value::as_str)
+            == Some(secret_ref.resolved.as_str());
+        if !unchanged {
+            continue;
+        }
+        if let Some(slot) = lookup_mut(saved, &secret_ref.path) {
+            *slot = toml::Value::String(secret_ref.reference.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn parse(raw: &str) -> toml::Value {
+        toml::from_str(raw).unwrap()
+    }
+
+    #[test]
+    fn resolves_env_and_file_references() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("bot_token"), "123:abc\n").unwrap();
+        std::env::set_var("ZEROCLAW_TEST_SECRET_REF_KEY", "sk-from-env");
+
+        let mut value = parse(
+            r#"
+api_key = "env:ZEROCLAW_TEST_SECRET_REF_KEY"
+default_model = "gpt-4o"
+[channels_config.telegram]
+bot_token = "file:bot_token"
+allowed_users = ["file://not-a-ref"]
+"#,
+        );
+        let refs = resolve_secret_refs(&mut value, tmp.path()).unwrap();
+
+        assert_eq!(value["api_key"].as_str(), Some("sk-from-env"));
+        assert_eq!(
+            value["channels_config"]["telegram"]["bot_token"].as_str(),
+            Some("123:abc")
+        );
+        assert_eq!(
+            value["channels_config"]["telegram"]["allowed_users"][0].as_str(),
+            Some("file://not-a-ref")
+        );
+        assert_eq!(refs.len(), 2);
+        assert!(refs
+            .iter()
+            .any(|r| r.dotted_path() == "channels_config.telegram.bot_token"
+                && r.reference == "file:bot_token"));
+        std::env::remove_var("ZEROCLAW_TEST_SECRET_REF_KEY");
+    }
+
+    #[test]
+    fn missing_env_var_or_file_names_the_field() {
+        let tmp = TempDir::new().unwrap();
+        let mut value = parse(r#"api_key = "env:ZEROCLAW_TEST_SECRET_REF_MISSING""#);
+        let err = resolve_secret_refs(&mut value, tmp.path())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("config.api_key"), "{err}");
+        assert!(
+            err.contains("ZEROCLAW_TEST_SECRET_REF_MISSING is not set"),
+            "{err}"
+        );
+
+        let mut value = parse(
+            r#"[channels_config.discord]
+bot_token = "file:/nonexistent/zeroclaw/token""#,
+        );
+        let err = format!(
+            "{:#}",
+            resolve_secret_refs(&mut value, tmp.path()).unwrap_err()
+        );
+        assert!(
+            err.contains("config.channels_config.discord.bot_token"),
+            "{err}"
+        );
+        assert!(err.contains("/nonexistent/zeroclaw/token"), "{err}");
+    }
+
+    #[test]
+    fn restore_keeps_unchanged_references_only() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("a"), "secret-a").unwrap();
+        std::fs::write(tmp.path().join("b"), "secret-b").unwrap();
+        let mut loaded = parse("api_key = \"file:a\"\n[composio]\napi_key = \"file:b\"\n");
+        let refs = resolve_secret_refs(&mut loaded, tmp.path()).unwrap();
+
+        let mut current = loaded.clone();
+        current["composio"]["api_key"] = toml::Value::String("rotated".into());
+        let mut saved = current.clone();
+        saved["api_key"] = toml::Value::String("enc2:deadbeef".into());
+        restore_secret_refs(&mut saved, &current, &refs);
+
+        assert_eq!(saved["api_key"].as_str(), Some("file:a"));
+        assert_eq!(saved["composio"]["api_key"].as_str(), Some("rotated"));
+        assert!(!format!("{refs:?}").contains("secret-a"));
+    }
+}
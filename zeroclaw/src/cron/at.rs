@@ -0,0 +1,407 @@
+//! Parsing of one-shot `at` times.
+//!
+//! Accepts RFC3339 timestamps plus a small set of natural expressions so the
+//! model does not have to compute epoch values itself:
+//!
+//! - `in 2 hours`, `in 30m`, `in an hour`
+//! - `tomorrow 9am`, `today at 18:30`, `tonight`
+//! - `monday 14:00`, `next friday at noon`
+//! - `2026-03-01 09:00` (local wall time in the configured timezone)
+//! - `9pm` (next occurrence)
+//!
+//! Wall-clock expressions are resolved in the configured timezone
+//! (`timezone` in config.toml), falling back to the system local zone.
+
+use anyhow::{bail, Context, Result};
+use chrono::{
+    DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+    Weekday,
+};
+use std::str::FromStr;
+
+/// A timezone from config, or the host's local zone when unset.
+#[derive(Debug, Clone, Copy)]
+pub enum ScheduleTz {
+    Named(chrono_tz::Tz),
+    Local,
+}
+
+impl ScheduleTz {
+    pub fn from_config(name: Option<&str>) -> Result<Self> {
+        match name.map(str::trim).filter(|n| !n.is_empty()) {
+            Some(name) => chrono_tz::Tz::from_str(name)
+                .map(Self::Named)
+                .map_err(|_| anyhow::anyhow!("Invalid IANA timezone in config: {name}")),
+            None => Ok(Self::Local),
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Self::Named(tz) => tz.name().to_string(),
+            Self::Local => iana_time_zone_hint().unwrap_or_else(|| "local".to_string()),
+        }
+    }
+
+    fn local_date(self, now: DateTime<Utc>) -> NaiveDate {
+        match self {
+            Self::Named(tz) => now.with_timezone(&tz).date_naive(),
+            Self::Local => now.with_timezone(&Local).date_naive(),
+        }
+    }
+
+    /// Wall time of a UTC instant in this zone.
+    pub fn local_datetime(self, at: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Self::Named(tz) => at.with_timezone(&tz).naive_local(),
+            Self::Local => at.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    /// Wall time to UTC. Times skipped by a DST jump are rejected; repeated
+    /// ones resolve to the earlier instant.
+    pub fn to_utc(self, local: NaiveDateTime) -> Result<DateTime<Utc>> {
+        let resolved = match self {
+            Self::Named(tz) => tz
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc)),
+            Self::Local => Local
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|dt| dt.with_timezone(&Utc)),
+        };
+        resolved.with_context(|| format!("{local} does not exist in {}", self.name()))
+    }
+
+    pub fn weekday(self, at: DateTime<Utc>) -> Weekday {
+        self.local_date(at).weekday()
+    }
+
+    /// Format a UTC instant as wall time in this zone.
+    pub fn format(self, at: DateTime<Utc>) -> String {
+        match self {
+            Self::Named(tz) => at
+                .with_timezone(&tz)
+                .format("%Y-%m-%d %H:%M %Z")
+                .to_string(),
+            Self::Local => at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M %Z")
+                .to_string(),
+        }
+    }
+}
+
+/// Best-effort IANA name of the host zone, from `TZ` or `/etc/localtime`.
+fn iana_time_zone_hint() -> Option<String> {
+    if let Ok(tz) = std::env::var("TZ") {
+        let tz = tz.trim_start_matches(':').trim();
+        if chrono_tz::Tz::from_str(tz).is_ok() {
+            return Some(tz.to_string());
+        }
+    }
+    let target = std::fs::read_link("/etc/localtime").ok()?;
+    let target = target.to_string_lossy();
+    let (_, name) = target.split_once("zoneinfo/")?;
+    chrono_tz::Tz::from_str(name).ok().map(|_| name.to_string())
+}
+
+/// Build a [`Schedule`](crate::cron::Schedule) from tool/API JSON. `at`
+/// schedules may carry any expression [`parse_at`] understands; the original
+/// text is kept on the schedule unless it was already RFC3339.
+pub fn schedule_from_json(
+    value: &serde_json::Value,
+    tz: ScheduleTz,
+    now: DateTime<Utc>,
+) -> Result<crate::cron::Schedule> {
+    let is_at = value.get("kind").and_then(serde_json::Value::as_str) == Some("at");
+    match value.get("at").and_then(serde_json::Value::as_str) {
+        Some(raw) if is_at => {
+            let at = parse_at(raw, tz, now)?;
+            let original = DateTime::parse_from_rfc3339(raw.trim())
+                .is_err()
+                .then(|| raw.trim().to_string());
+            Ok(crate::cron::Schedule::At { at, original })
+        }
+        _ => serde_json::from_value(value.clone()).map_err(Into::into),
+    }
+}
+
+/// Resolve `input` to a UTC instant strictly after `now`.
+pub fn parse_at(input: &str, tz: ScheduleTz, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let raw = input.trim();
+    if raw.is_empty() {
+        bail!("'at' time is empty");
+    }
+
+    let at = if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        dt.with_timezone(&Utc)
+    } else {
+        parse_natural(&raw.to_lowercase(), tz, now).with_context(|| {
+            format!(
+                "Could not understand 'at' time '{raw}'. Use RFC3339 (2026-03-01T09:00:00Z), \
+                 'in 2 hours', 'tomorrow 9am', 'friday 14:00' or 'YYYY-MM-DD HH:MM'"
+            )
+        })?
+    };
+
+    if at <= now {
+        bail!(
+            "'at' time '{raw}' resolves to {}, which is in the past (now is {})",
+            tz.format(at),
+            tz.format(now)
+        );
+    }
+    Ok(at)
+}
+
+fn parse_natural(input: &str, tz: ScheduleTz, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let words: Vec<&str> = input
+        .split_whitespace()
+        .filter(|w| !matches!(*w, "at" | "on"))
+        .collect();
+
+    if let Some(rest) = words.strip_prefix(&["in"]) {
+        return Ok(now + parse_relative(rest)?);
+    }
+
+    if let Some(local) = parse_naive_datetime(input) {
+        return tz.to_utc(local);
+    }
+
+    let today = tz.local_date(now);
+    let (date, time_words): (Option<NaiveDate>, &[&str]) = match words.as_slice() {
+        ["tonight"] => (Some(today), &["8pm"][..]),
+        ["today" | "tonight", rest @ ..] => (Some(today), rest),
+        ["tomorrow", rest @ ..] => (today.succ_opt(), rest),
+        ["next", day, rest @ ..] => (Some(next_weekday(today, parse_weekday(day)?, true)), rest),
+        [day, rest @ ..] if parse_weekday(day).is_ok() => {
+            (Some(next_weekday(today, parse_weekday(day)?, false)), rest)
+        }
+        rest => (None, rest),
+    };
+
+    let time = match time_words {
+        [] if date.is_some() => NaiveTime::from_hms_opt(9, 0, 0).unwrap_or_default(),
+        [] => bail!("missing time"),
+        words => parse_time(&words.concat())?,
+    };
+
+    match date {
+        Some(date) => tz.to_utc(date.and_time(time)),
+        None => {
+            // Bare time: today if still ahead, otherwise tomorrow.
+            let candidate = tz.to_utc(today.and_time(time))?;
+            if candidate > now {
+                Ok(candidate)
+            } else {
+                let tomorrow = today.succ_opt().context("date overflow")?;
+                tz.to_utc(tomorrow.and_time(time))
+            }
+        }
+    }
+}
+
+fn parse_relative(words: &[&str]) -> Result<Duration> {
+    // Accept "2 hours", "2h", "2hours" and "an hour".
+    let (amount, unit) = match words {
+        [amount, unit] => ((*amount).to_string(), (*unit).to_string()),
+        [single] => {
+            let split = single
+                .find(|c: char| !c.is_ascii_digit())
+                .context("missing unit")?;
+            (single[..split].to_string(), single[split..].to_string())
+        }
+        _ => bail!("expected 'in <number> <unit>'"),
+    };
+    let amount: i64 = match amount.as_str() {
+        "a" | "an" | "one" => 1,
+        n => n.parse().with_context(|| format!("invalid number '{n}'"))?,
+    };
+    if amount <= 0 {
+        bail!("relative time must be positive");
+    }
+    let unit = unit.trim_end_matches('s');
+    let duration = match unit {
+        "sec" | "second" | "" => Duration::try_seconds(amount),
+        "m" | "min" | "minute" => Duration::try_minutes(amount),
+        "h" | "hr" | "hour" => Duration::try_hours(amount),
+        "d" | "day" => Duration::try_days(amount),
+        "w" | "week" => Duration::try_weeks(amount),
+        other => bail!("unknown time unit '{other}'"),
+    };
+    duration.context("relative time is too large")
+}
+
+fn parse_naive_datetime(input: &str) -> Option<NaiveDateTime> {
+    const FORMATS: [&str; 4] = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dt%H:%M:%S",
+        "%Y-%m-%dt%H:%M",
+    ];
+    let input = input.replace(" at ", " ");
+    FORMATS
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(&input, fmt).ok())
+}
+
+fn parse_weekday(word: &str) -> Result<Weekday> {
+    let day = match word {
+        "mon" | "monday" => Weekday::Mon,
+        "tue" | "tues" | "tuesday" => Weekday::Tue,
+        "wed" | "wednesday" => Weekday::Wed,
+        "thu" | "thurs" | "thursday" => Weekday::Thu,
+        "fri" | "friday" => Weekday::Fri,
+        "sat" | "saturday" => Weekday::Sat,
+        "sun" | "sunday" => Weekday::Sun,
+        other => bail!("unknown day '{other}'"),
+    };
+    Ok(day)
+}
+
+/// Next date falling on `day`. Today counts unless `skip_today` is set
+/// ("next monday" said on a Monday means a week later).
+fn next_weekday(today: NaiveDate, day: Weekday, skip_today: bool) -> NaiveDate {
+    let ahead = (7 + day.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+    let ahead = if ahead == 0 && skip_today { 7 } else { ahead };
+    today + Duration::days(i64::from(ahead))
+}
+
+/// `9am`, `9:30pm`, `14:00`, `noon`, `midnight`.
+fn parse_time(word: &str) -> Result<NaiveTime> {
+    match word {
+        "noon" => return Ok(NaiveTime::from_hms_opt(12, 0, 0).unwrap_or_default()),
+        "midnight" => return Ok(NaiveTime::MIN),
+        _ => {}
+    }
+
+    let (clock, meridiem) = if let Some(clock) = word.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = word.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (word, None)
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>()?, m.parse::<u32>()?),
+        None => (clock.parse::<u32>()?, 0),
+    };
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => bail!("invalid 12-hour time '{word}'"),
+        Some(true) => hour % 12 + 12,
+        Some(false) => hour % 12,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0).with_context(|| format!("invalid time '{word}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lisbon() -> ScheduleTz {
+        ScheduleTz::from_config(Some("Europe/Lisbon")).unwrap()
+    }
+
+    fn new_york() -> ScheduleTz {
+        ScheduleTz::from_config(Some("America/New_York")).unwrap()
+    }
+
+    // Wednesday 2026-03-04 15:30 UTC (10:30 in New York, 15:30 in Lisbon).
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 4, 15, 30, 0).unwrap()
+    }
+
+    fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn rfc3339_is_taken_as_is() {
+        let at = parse_at("2026-03-05T08:00:00+01:00", new_york(), now()).unwrap();
+        assert_eq!(at, utc(2026, 3, 5, 7, 0));
+    }
+
+    #[test]
+    fn relative_expressions() {
+        assert_eq!(
+            parse_at("in 2 hours", lisbon(), now()).unwrap(),
+            now() + Duration::hours(2)
+        );
+        assert_eq!(
+            parse_at("in 30m", lisbon(), now()).unwrap(),
+            now() + Duration::minutes(30)
+        );
+        assert_eq!(
+            parse_at("In an hour", lisbon(), now()).unwrap(),
+            now() + Duration::hours(1)
+        );
+        assert!(parse_at("in 3 fortnights", lisbon(), now()).is_err());
+    }
+
+    #[test]
+    fn wall_clock_expressions_use_configured_timezone() {
+        // 9am New York (EST, UTC-5) on Thursday.
+        assert_eq!(
+            parse_at("tomorrow 9am", new_york(), now()).unwrap(),
+            utc(2026, 3, 5, 14, 0)
+        );
+        assert_eq!(
+            parse_at("tomorrow 9am", lisbon(), now()).unwrap(),
+            utc(2026, 3, 5, 9, 0)
+        );
+        assert_eq!(
+            parse_at("today at 18:30", lisbon(), now()).unwrap(),
+            utc(2026, 3, 4, 18, 30)
+        );
+        assert_eq!(
+            parse_at("2026-03-10 07:15", new_york(), now()).unwrap(),
+            // DST starts 2026-03-08 in New York: EDT is UTC-4.
+            utc(2026, 3, 10, 11, 15)
+        );
+    }
+
+    #[test]
+    fn weekdays_and_bare_times() {
+        // "friday" from a Wednesday is two days ahead; default time is 9am.
+        assert_eq!(
+            parse_at("friday", lisbon(), now()).unwrap(),
+            utc(2026, 3, 6, 9, 0)
+        );
+        assert_eq!(
+            parse_at("next wednesday at noon", lisbon(), now()).unwrap(),
+            utc(2026, 3, 11, 12, 0)
+        );
+        // 3pm Lisbon already passed today, so it rolls to tomorrow.
+        assert_eq!(
+            parse_at("3pm", lisbon(), now()).unwrap(),
+            utc(2026, 3, 5, 15, 0)
+        );
+        assert_eq!(
+            parse_at("9:45pm", lisbon(), now()).unwrap(),
+            utc(2026, 3, 4, 21, 45)
+        );
+    }
+
+    #[test]
+    fn past_times_are_rejected_with_clear_error() {
+        let err = parse_at("2026-03-01T00:00:00Z", lisbon(), now())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("in the past"), "{err}");
+        assert!(err.contains("2026-03-04 15:30"), "{err}");
+
+        let err = parse_at("today 8am", lisbon(), now()).unwrap_err();
+        assert!(err.to_string().contains("in the past"));
+    }
+
+    #[test]
+    fn gibberish_and_bad_timezone_are_errors() {
+        let err = parse_at("whenever you like", lisbon(), now()).unwrap_err();
+        assert!(err.to_string().contains("Could not understand"));
+        assert!(parse_at("13pm", lisbon(), now()).is_err());
+        assert!(ScheduleTz::from_config(Some("Mars/Olympus")).is_err());
+    }
+}
@@ -2,6 +2,7 @@ use crate::config::Config;
 use crate::security::SecurityPolicy;
 use anyhow::{bail, Result};
 
+pub mod at;
 mod schedule;
 mod store;
 mod types;
@@ -15,9 +16,11 @@ pub use schedule::{
 #[allow(unused_imports)]
 pub use store::{
     add_agent_job, add_job, add_shell_job, due_jobs, get_job, list_jobs, list_runs,
-    record_last_run, record_run, remove_job, reschedule_after_run, update_job,
+    record_last_run, record_missed_runs, record_run, remove_job, reschedule_after_run, update_job,
+};
+pub use types::{
+    CatchUp, CronJob, CronJobPatch, CronRun, DeliveryConfig, JobType, Schedule, SessionTarget,
 };
-pub use types::{CronJob, CronJobPatch, CronRun, DeliveryConfig, JobType, Schedule, SessionTarget};
 
 #[allow(clippy::needless_pass_by_value)]
 pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<()> {
@@ -31,20 +34,37 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
                 return Ok(());
             }
 
+            let tz = at::ScheduleTz::from_config(config.timezone.as_deref())?;
+            let now = chrono::Utc::now();
             println!("🕒 Scheduled jobs ({}):", jobs.len());
             for job in jobs {
                 let last_run = job
                     .last_run
-                    .map_or_else(|| "never".into(), |d| d.to_rfc3339());
+                    .map_or_else(|| "never".into(), |d| describe_time(tz, d, now));
                 let last_status = job.last_status.unwrap_or_else(|| "n/a".into());
+                let schedule = match &job.schedule {
+                    Schedule::At {
+                        original: Some(original),
+                        ..
+                    } => format!("At({original:?})"),
+                    other => format!("{other:?}"),
+                };
+                let next_run = if job.enabled {
+                    describe_time(tz, job.next_run, now)
+                } else {
+                    "paused".into()
+                };
                 println!(
-                    "- {} | {:?} | next={} | last={} ({})",
-                    job.id,
-                    job.schedule,
-                    job.next_run.to_rfc3339(),
-                    last_run,
-                    last_status,
+                    "- {} | {} | next={} | last={} ({})",
+                    job.id, schedule, next_run, last_run, last_status,
                 );
+                if job.missed_runs > 0 {
+                    println!(
+                        "    missed: {} (catch-up: {})",
+                        job.missed_runs,
+                        job.catch_up.as_str()
+                    );
+                }
                 if !job.command.is_empty() {
                     println!("    cmd: {}", job.command);
                 }
@@ -54,6 +74,31 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
             }
             Ok(())
         }
+        crate::CronCommands::Next { n } => {
+            let jobs = list_jobs(config)?;
+            let runs = upcoming_runs(&jobs, n);
+            if runs.is_empty() {
+                println!("No upcoming runs (no enabled scheduled tasks).");
+                return Ok(());
+            }
+            let tz = at::ScheduleTz::from_config(config.timezone.as_deref())?;
+            let now = chrono::Utc::now();
+            println!("⏭️  Next {} run(s):", runs.len());
+            for (at, job) in runs {
+                let label = job
+                    .name
+                    .as_deref()
+                    .or(job.prompt.as_deref())
+                    .unwrap_or(&job.command);
+                println!(
+                    "  {}  {}  {}",
+                    describe_time(tz, at, now),
+                    job.id,
+                    crate::util::truncate_with_ellipsis(label, 60)
+                );
+            }
+            Ok(())
+        }
         crate::CronCommands::Add {
             expression,
             tz,
@@ -70,14 +115,23 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
             println!("  Cmd : {}", job.command);
             Ok(())
         }
-        crate::CronCommands::AddAt { at, command } => {
-            let at = chrono::DateTime::parse_from_rfc3339(&at)
-                .map_err(|e| anyhow::anyhow!("Invalid RFC3339 timestamp for --at: {e}"))?
-                .with_timezone(&chrono::Utc);
-            let schedule = Schedule::At { at };
-            let job = add_shell_job(config, None, schedule, &command)?;
+        crate::CronCommands::AddAt { at, command, keep } => {
+            let tz = at::ScheduleTz::from_config(config.timezone.as_deref())?;
+            let schedule = at::schedule_from_json(
+                &serde_json::json!({"kind": "at", "at": at}),
+                tz,
+                chrono::Utc::now(),
+            )?;
+            let mut job = add_shell_job(config, None, schedule, &command)?;
+            if keep {
+                job = keep_after_run(config, &job.id)?;
+            }
             println!("✅ Added one-shot cron job {}", job.id);
-            println!("  At  : {}", job.next_run.to_rfc3339());
+            println!(
+                "  At  : {} ({})",
+                job.next_run.to_rfc3339(),
+                tz.format(job.next_run)
+            );
             println!("  Cmd : {}", job.command);
             Ok(())
         }
@@ -90,8 +144,15 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
             println!("  Cmd      : {}", job.command);
             Ok(())
         }
-        crate::CronCommands::Once { delay, command } => {
-            let job = add_once(config, &delay, &command)?;
+        crate::CronCommands::Once {
+            delay,
+            command,
+            keep,
+        } => {
+            let mut job = add_once(config, &delay, &command)?;
+            if keep {
+                job = keep_after_run(config, &job.id)?;
+            }
             println!("✅ Added one-shot cron job {}", job.id);
             println!("  At  : {}", job.next_run.to_rfc3339());
             println!("  Cmd : {}", job.command);
@@ -103,10 +164,23 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
             tz,
             command,
             name,
+            catch_up,
         } => {
-            if expression.is_none() && tz.is_none() && command.is_none() && name.is_none() {
-                bail!("At least one of --expression, --tz, --command, or --name must be provided");
+            if expression.is_none()
+                && tz.is_none()
+                && command.is_none()
+                && name.is_none()
+                && catch_up.is_none()
+            {
+                bail!("At least one of --expression, --tz, --command, --name, or --catch-up must be provided");
             }
+            let catch_up = catch_up
+                .map(|raw| {
+                    CatchUp::parse(&raw).ok_or_else(|| {
+                        anyhow::anyhow!("Invalid --catch-up '{raw}'; expected run-once or skip")
+                    })
+                })
+                .transpose()?;
 
             // Merge expression/tz with the existing schedule so that
             // --tz alone updates the timezone and --expression alone
@@ -139,6 +213,7 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
                 schedule,
                 command,
                 name,
+                catch_up,
                 ..CronJobPatch::default()
             };
 
@@ -174,10 +249,82 @@ pub fn add_once_at(
     at: chrono::DateTime<chrono::Utc>,
     command: &str,
 ) -> Result<CronJob> {
-    let schedule = Schedule::At { at };
+    let schedule = Schedule::At { at, original: None };
     add_shell_job(config, None, schedule, command)
 }
 
+/// Keep a one-shot job after it fires (paused, with its run history)
+/// instead of deleting it.
+pub fn keep_after_run(config: &Config, id: &str) -> Result<CronJob> {
+    update_job(
+        config,
+        id,
+        CronJobPatch {
+            delete_after_run: Some(false),
+            ..CronJobPatch::default()
+        },
+    )
+}
+
+/// The next `n` firings across enabled jobs, soonest first. Recurring jobs
+/// are projected forward from their stored `next_run`, so one job can fill
+/// several slots; one-shot jobs appear once.
+pub fn upcoming_runs(jobs: &[CronJob], n: usize) -> Vec<(chrono::DateTime<chrono::Utc>, &CronJob)> {
+    let mut runs = Vec::new();
+    for job in jobs.iter().filter(|job| job.enabled) {
+        let mut at = job.next_run;
+        for _ in 0..n {
+            runs.push((at, job));
+            if matches!(job.schedule, Schedule::At { .. }) {
+                break;
+            }
+            match next_run_for_schedule(&job.schedule, at) {
+                Ok(next) if next > at => at = next,
+                _ => break,
+            }
+        }
+    }
+    runs.sort_by_key(|(at, _)| *at);
+    runs.truncate(n);
+    runs
+}
+
+/// `at` as wall time in `tz` plus how far it is from `now`, e.g.
+/// `2025-01-15 14:00 CET (in 2h 5m)`.
+pub fn describe_time(
+    tz: at::ScheduleTz,
+    at: chrono::DateTime<chrono::Utc>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> String {
+    format!("{} ({})", tz.format(at), relative_time(at, now))
+}
+
+/// Coarse distance between `at` and `now`: `in 5m`, `in 2h 5m`,
+/// `3d 4h ago`, or `now` within a minute.
+pub fn relative_time(
+    at: chrono::DateTime<chrono::Utc>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> String {
+    let delta = at - now;
+    let minutes = delta.num_minutes().unsigned_abs();
+    if minutes == 0 {
+        return "now".into();
+    }
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    let span = match (days, hours) {
+        (0, 0) => format!("{minutes}m"),
+        (0, _) if minutes == 0 => format!("{hours}h"),
+        (0, _) => format!("{hours}h {minutes}m"),
+        (_, 0) => format!("{days}d"),
+        _ => format!("{days}d {hours}h"),
+    };
+    if delta > chrono::Duration::zero() {
+        format!("in {span}")
+    } else {
+        format!("{span} ago")
+    }
+}
+
 pub fn pause_job(config: &Config, id: &str) -> Result<CronJob> {
     update_job(
         config,
@@ -249,6 +396,87 @@ mod tests {
         .unwrap()
     }
 
+    #[test]
+    fn keep_flag_retains_one_shot_jobs() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let add = |keep| {
+            handle_command(
+                crate::CronCommands::Once {
+                    delay: "2h".into(),
+                    command: format!("echo keep={keep}"),
+                    keep,
+                },
+                &config,
+            )
+            .unwrap();
+        };
+        add(false);
+        add(true);
+
+        let jobs = list_jobs(&config).unwrap();
+        let by_command = |cmd: &str| jobs.iter().find(|job| job.command == cmd).unwrap();
+        assert!(by_command("echo keep=false").delete_after_run);
+        assert!(!by_command("echo keep=true").delete_after_run);
+    }
+
+    #[test]
+    fn upcoming_runs_project_recurring_jobs_and_skip_paused_ones() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let now = chrono::Utc::now();
+        let hourly = add_shell_job(
+            &config,
+            None,
+            Schedule::Every {
+                every_ms: 3_600_000,
+            },
+            "echo hourly",
+        )
+        .unwrap();
+        let once = add_once_at(&config, now + chrono::Duration::minutes(90), "echo once").unwrap();
+        let paused = make_job(&config, "*/5 * * * *", None, "echo paused");
+        pause_job(&config, &paused.id).unwrap();
+
+        let jobs = list_jobs(&config).unwrap();
+        let runs = upcoming_runs(&jobs, 4);
+        let ids: Vec<&str> = runs.iter().map(|(_, job)| job.id.as_str()).collect();
+        assert_eq!(ids, [&hourly.id, &once.id, &hourly.id, &hourly.id]);
+        assert_eq!(runs[0].0, hourly.next_run);
+        assert_eq!(runs[2].0 - runs[0].0, chrono::Duration::hours(1));
+        assert_eq!(runs[3].0 - runs[2].0, chrono::Duration::hours(1));
+        assert!(runs.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+
+        // A one-shot job is never projected twice.
+        let only_once: Vec<_> = jobs
+            .iter()
+            .filter(|job| job.id == once.id)
+            .cloned()
+            .collect();
+        assert_eq!(upcoming_runs(&only_once, 5).len(), 1);
+    }
+
+    #[test]
+    fn relative_and_local_times_are_formatted() {
+        let now = chrono::DateTime::parse_from_rfc3339("2025-01-15T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let at = |minutes| now + chrono::Duration::minutes(minutes);
+        assert_eq!(relative_time(at(0), now), "now");
+        assert_eq!(relative_time(at(5), now), "in 5m");
+        assert_eq!(relative_time(at(120), now), "in 2h");
+        assert_eq!(relative_time(at(125), now), "in 2h 5m");
+        assert_eq!(relative_time(at(3 * 1440 + 4 * 60 + 7), now), "in 3d 4h");
+        assert_eq!(relative_time(at(-1440), now), "1d ago");
+        assert_eq!(relative_time(at(-90), now), "1h 30m ago");
+
+        let tz = at::ScheduleTz::from_config(Some("Europe/Berlin")).unwrap();
+        assert_eq!(
+            describe_time(tz, at(125), now),
+            "2025-01-15 15:05 CET (in 2h 5m)"
+        );
+    }
+
     fn run_update(
         config: &Config,
         id: &str,
@@ -264,6 +492,7 @@ mod tests {
                 tz: tz.map(Into::into),
                 command: command.map(Into::into),
                 name: name.map(Into::into),
+                catch_up: None,
             },
             config,
         )
@@ -378,6 +607,31 @@ mod tests {
         assert_eq!(updated.expression, "*/5 * * * *");
     }
 
+    #[test]
+    fn update_sets_catch_up_policy() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let job = make_job(&config, "*/5 * * * *", None, "echo test");
+        assert_eq!(job.catch_up, CatchUp::RunOnce);
+
+        let update = |raw: &str| {
+            handle_command(
+                crate::CronCommands::Update {
+                    id: job.id.clone(),
+                    expression: None,
+                    tz: None,
+                    command: None,
+                    name: None,
+                    catch_up: Some(raw.into()),
+                },
+                &config,
+            )
+        };
+        update("skip").unwrap();
+        assert_eq!(get_job(&config, &job.id).unwrap().catch_up, CatchUp::Skip);
+        assert!(update("sometimes").is_err());
+    }
+
     #[test]
     fn update_no_flags_fails() {
         let tmp = TempDir::new().unwrap();
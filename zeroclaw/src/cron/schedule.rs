@@ -25,7 +25,7 @@ pub fn next_run_for_schedule(schedule: &Schedule, from: DateTime<Utc>) -> Result
                     .ok_or_else(|| anyhow::anyhow!("No future occurrence for expression: {expr}"))
             }
         }
-        Schedule::At { at } => Ok(*at),
+        Schedule::At { at, .. } => Ok(*at),
         Schedule::Every { every_ms } => {
             if *every_ms == 0 {
                 anyhow::bail!("Invalid schedule: every_ms must be > 0");
@@ -45,7 +45,7 @@ pub fn validate_schedule(schedule: &Schedule, now: DateTime<Utc>) -> Result<()>
             let _ = next_run_for_schedule(schedule, now)?;
             Ok(())
         }
-        Schedule::At { at } => {
+        Schedule::At { at, .. } => {
             if *at <= now {
                 anyhow::bail!("Invalid schedule: 'at' must be in the future");
             }
@@ -95,7 +95,7 @@ mod tests {
         assert!(next > now);
 
         let at = now + ChronoDuration::minutes(10);
-        let at_schedule = Schedule::At { at };
+        let at_schedule = Schedule::At { at, original: None };
         let next_at = next_run_for_schedule(&at_schedule, now).unwrap();
         assert_eq!(next_at, at);
     }
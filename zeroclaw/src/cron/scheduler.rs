@@ -1,11 +1,14 @@
+use crate::channels::quiet_hours::{self, DeferredOutbox, MessageOrigin, Screened};
 use crate::channels::{
     Channel, DiscordChannel, MattermostChannel, SendMessage, SlackChannel, TelegramChannel,
 };
 use crate::config::Config;
 use crate::cron::{
-    due_jobs, next_run_for_schedule, record_last_run, record_run, remove_job, reschedule_after_run,
-    update_job, CronJob, CronJobPatch, DeliveryConfig, JobType, Schedule, SessionTarget,
+    due_jobs, next_run_for_schedule, record_last_run, record_missed_runs, record_run, remove_job,
+    reschedule_after_run, update_job, CatchUp, CronJob, CronJobPatch, DeliveryConfig, JobType,
+    Schedule, SessionTarget,
 };
+use crate::security::redaction::{self, RedactionPath};
 use crate::security::SecurityPolicy;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
@@ -18,6 +21,44 @@ use tokio::time::{self, Duration};
 const MIN_POLL_SECONDS: u64 = 5;
 const SHELL_JOB_TIMEOUT_SECS: u64 = 120;
 const SCHEDULER_COMPONENT: &str = "scheduler";
+/// Recent turns of the originating session included in a follow-up prompt.
+const FOLLOWUP_CONTEXT_TURNS: usize = 10;
+const FOLLOWUP_CONTEXT_TURN_CHARS: usize = 500;
+/// Upper bound when counting missed slots (keeps a tiny `every_ms` after a
+/// long outage from looping for ages).
+const MAX_COUNTED_MISSED_SLOTS: u64 = 10_000;
+
+/// Channels [`deliver_announcement`] can send to.
+pub(crate) const ANNOUNCE_CHANNELS: &[&str] = &["telegram", "discord", "slack", "mattermost"];
+
+/// Whether `channel` (one of [`ANNOUNCE_CHANNELS`]) is configured.
+pub(crate) fn announce_channel_configured(config: &Config, channel: &str) -> bool {
+    let channels = &config.channels_config;
+    match channel {
+        "telegram" => channels.telegram.is_some(),
+        "discord" => channels.discord.is_some(),
+        "slack" => channels.slack.is_some(),
+        "mattermost" => channels.mattermost.is_some(),
+        _ => false,
+    }
+}
+
+/// Recipient used when none is given: the channel the bot is restricted to
+/// on Slack and Mattermost. Telegram and Discord always need one.
+pub(crate) fn default_announce_target(config: &Config, channel: &str) -> Option<String> {
+    let channel_id = match channel {
+        "slack" => config.channels_config.slack.as_ref()?.channel_id.as_deref(),
+        "mattermost" => config
+            .channels_config
+            .mattermost
+            .as_ref()?
+            .channel_id
+            .as_deref(),
+        _ => None,
+    }?;
+    let channel_id = channel_id.trim();
+    (!channel_id.is_empty() && channel_id != "*").then(|| channel_id.to_string())
+}
 
 pub async fn run(config: Config) -> Result<()> {
     let poll_secs = config.reliability.scheduler_poll_secs.max(MIN_POLL_SECONDS);
@@ -44,8 +85,74 @@ pub async fn run(config: Config) -> Result<()> {
             }
         };
 
+        let jobs = apply_catch_up(&config, jobs, Utc::now());
         process_due_jobs(&config, &security, jobs, SCHEDULER_COMPONENT).await;
+
+        if let Err(e) = release_deferred(&config).await {
+            tracing::warn!("Failed to release deferred messages: {e}");
+        }
+    }
+}
+
+/// Scheduled slots after `job.next_run` that have also passed by `now`.
+///
+/// Non-zero means the job is overdue by more than one interval, i.e. runs
+/// were missed rather than merely picked up late by the poll loop.
+fn missed_slots(job: &CronJob, now: DateTime<Utc>) -> u64 {
+    if matches!(job.schedule, Schedule::At { .. }) {
+        return 0;
     }
+    let mut missed = 0;
+    let mut slot = job.next_run;
+    while missed < MAX_COUNTED_MISSED_SLOTS {
+        match next_run_for_schedule(&job.schedule, slot) {
+            Ok(next) if next <= now && next > slot => {
+                missed += 1;
+                slot = next;
+            }
+            _ => break,
+        }
+    }
+    missed
+}
+
+/// Record missed slots for overdue jobs and apply each job's catch-up policy.
+/// Returns the jobs that should run now.
+fn apply_catch_up(config: &Config, jobs: Vec<CronJob>, now: DateTime<Utc>) -> Vec<CronJob> {
+    jobs.into_iter()
+        .filter_map(|mut job| {
+            let missed = missed_slots(&job, now);
+            if missed == 0 {
+                return Some(job);
+            }
+            match job.catch_up {
+                CatchUp::RunOnce => {
+                    tracing::info!(
+                        "Cron job '{}' missed {missed} run(s); running once to catch up",
+                        job.id
+                    );
+                    if let Err(e) = record_missed_runs(config, &job.id, missed, None) {
+                        tracing::warn!("Failed to record missed cron runs: {e}");
+                    }
+                    job.missed_runs += missed;
+                    Some(job)
+                }
+                CatchUp::Skip => {
+                    // The overdue slot itself is skipped too.
+                    let next_run = next_run_for_schedule(&job.schedule, now).ok();
+                    tracing::info!(
+                        "Cron job '{}' missed {} run(s); skipping to the next slot",
+                        job.id,
+                        missed + 1
+                    );
+                    if let Err(e) = record_missed_runs(config, &job.id, missed + 1, next_run) {
+                        tracing::warn!("Failed to record missed cron runs: {e}");
+                    }
+                    None
+                }
+            }
+        })
+        .collect()
 }
 
 pub async fn execute_job_now(config: &Config, job: &CronJob) -> (bool, String) {
@@ -98,18 +205,21 @@ async fn process_due_jobs(
     crate::health::mark_component_ok(component);
 
     let max_concurrent = config.scheduler.max_concurrent.max(1);
-    let mut in_flight =
-        stream::iter(
-            jobs.into_iter().map(|job| {
-                let config = config.clone();
-                let security = Arc::clone(security);
-                let component = component.to_owned();
-                async move {
-                    execute_and_persist_job(&config, security.as_ref(), &job, &component).await
-                }
-            }),
-        )
-        .buffer_unordered(max_concurrent);
+    let mut in_flight = stream::iter(jobs.into_iter().map(|job| {
+        let config = config.clone();
+        let security = Arc::clone(security);
+        let component = component.to_owned();
+        async move {
+            Box::pin(execute_and_persist_job(
+                &config,
+                security.as_ref(),
+                &job,
+                &component,
+            ))
+            .await
+        }
+    }))
+    .buffer_unordered(max_concurrent);
 
     while let Some((job_id, success, output)) = in_flight.next().await {
         if !success {
@@ -161,13 +271,16 @@ async fn run_agent_job(
         );
     }
     let name = job.name.clone().unwrap_or_else(|| "cron-job".to_string());
-    let prompt = job.prompt.clone().unwrap_or_default();
+    let mut prompt = job.prompt.clone().unwrap_or_default();
+    if let Some(session_key) = job.delivery.session_key.as_deref() {
+        prompt = followup_prompt(config, session_key, &prompt).await;
+    }
     let prefixed_prompt = format!("[cron:{} {name}] {prompt}", job.id);
     let model_override = job.model.clone();
 
     let run_result = match job.session_target {
         SessionTarget::Main | SessionTarget::Isolated => {
-            crate::agent::run(
+            Box::pin(crate::agent::run(
                 config.clone(),
                 Some(prefixed_prompt),
                 None,
@@ -175,7 +288,8 @@ async fn run_agent_job(
                 config.default_temperature,
                 vec![],
                 false,
-            )
+                Some("cron"),
+            ))
             .await
         }
     };
@@ -203,10 +317,13 @@ async fn persist_job_result(
 ) -> bool {
     let duration_ms = (finished_at - started_at).num_milliseconds();
 
-    if let Err(e) = deliver_if_configured(config, job, output).await {
-        if job.delivery.best_effort {
+    match deliver_if_configured(config, job, output).await {
+        Ok(()) if success => record_followup_turn(config, job, output).await,
+        Ok(()) => {}
+        Err(e) if job.delivery.best_effort => {
             tracing::warn!("Cron delivery failed (best_effort): {e}");
-        } else {
+        }
+        Err(e) => {
             success = false;
             tracing::warn!("Cron delivery failed: {e}");
         }
@@ -222,13 +339,15 @@ async fn persist_job_result(
         duration_ms,
     );
 
-    if is_one_shot_auto_delete(job) {
-        if success {
+    if matches!(job.schedule, Schedule::At { .. }) {
+        if success && job.delete_after_run {
             if let Err(e) = remove_job(config, &job.id) {
                 tracing::warn!("Failed to remove one-shot cron job after success: {e}");
             }
         } else {
-            let _ = record_last_run(config, &job.id, finished_at, false, output);
+            // Failed or kept (`--keep`): nothing is left to run, so pause the
+            // job rather than let its past `next_run` fire it on every poll.
+            let _ = record_last_run(config, &job.id, finished_at, success, output);
             if let Err(e) = update_job(
                 config,
                 &job.id,
@@ -237,7 +356,7 @@ async fn persist_job_result(
                     ..CronJobPatch::default()
                 },
             ) {
-                tracing::warn!("Failed to disable failed one-shot cron job: {e}");
+                tracing::warn!("Failed to disable finished one-shot cron job: {e}");
             }
         }
         return success;
@@ -250,8 +369,56 @@ async fn persist_job_result(
     success
 }
 
-fn is_one_shot_auto_delete(job: &CronJob) -> bool {
-    job.delete_after_run && matches!(job.schedule, Schedule::At { .. })
+/// Prefix a follow-up prompt with the tail of the conversation that
+/// scheduled it, so the agent knows what it promised to check.
+async fn followup_prompt(config: &Config, session_key: &str, prompt: &str) -> String {
+    let (workspace_dir, key) = (config.workspace_dir.clone(), session_key.to_string());
+    let history =
+        tokio::task::spawn_blocking(move || match crate::sessions::store_for(&workspace_dir) {
+            Some(store) => store.load_history(&key, Some(FOLLOWUP_CONTEXT_TURNS)),
+            None => crate::sessions::SqliteSessionStore::open_read_only(&workspace_dir)
+                .and_then(|store| store.load_history(&key, Some(FOLLOWUP_CONTEXT_TURNS))),
+        })
+        .await
+        .ok()
+        .and_then(Result::ok)
+        .unwrap_or_default();
+
+    if history.is_empty() {
+        return format!("Follow-up you scheduled earlier in this conversation: {prompt}");
+    }
+    let transcript = history
+        .iter()
+        .map(|turn| {
+            format!(
+                "{}: {}",
+                turn.role,
+                crate::util::truncate_with_ellipsis(&turn.content, FOLLOWUP_CONTEXT_TURN_CHARS)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "Follow-up you scheduled earlier in this conversation.\n\
+         Recent conversation:\n{transcript}\n\n\
+         Follow-up task: {prompt}"
+    )
+}
+
+/// Append the delivered output of a session-bound job to that session's
+/// history, so it reads as part of the original conversation.
+async fn record_followup_turn(config: &Config, job: &CronJob, output: &str) {
+    let Some(session_key) = job.delivery.session_key.clone() else {
+        return;
+    };
+    let (key, output) = (session_key.clone(), output.to_string());
+    let result = crate::sessions::blocking_writable(&config.workspace_dir, move |store| {
+        store.append_message(&key, "assistant", &output)
+    })
+    .await;
+    if let Err(e) = result {
+        tracing::warn!("Failed to record follow-up in session {session_key}: {e}");
+    }
 }
 
 fn warn_if_high_frequency_agent_job(job: &CronJob) {
@@ -296,14 +463,44 @@ async fn deliver_if_configured(config: &Config, job: &CronJob, output: &str) ->
         .as_deref()
         .ok_or_else(|| anyhow::anyhow!("delivery.to is required for announce mode"))?;
 
-    deliver_announcement(config, channel, target, output).await
+    deliver_announcement(config, channel, target, output, MessageOrigin::Cron).await
 }
 
+/// Send an agent-initiated message, subject to quiet hours for `channel`.
 pub(crate) async fn deliver_announcement(
     config: &Config,
     channel: &str,
     target: &str,
     output: &str,
+    origin: MessageOrigin,
+) -> Result<()> {
+    let output = redaction::apply(RedactionPath::Outbound, output.to_string());
+    match quiet_hours::screen(config, channel, target, &output, origin, Utc::now())? {
+        Screened::Send { silent } => {
+            send_announcement(config, channel, target, &output, silent).await
+        }
+        Screened::Held => Ok(()),
+    }
+}
+
+/// Send messages held by quiet hours whose window has ended.
+async fn release_deferred(config: &Config) -> Result<usize> {
+    if !quiet_hours::deferred_db_path(&config.workspace_dir).exists() {
+        return Ok(0);
+    }
+    let outbox = DeferredOutbox::open(&config.workspace_dir)?;
+    quiet_hours::release_due(&outbox, Utc::now(), |channel, target, content| async move {
+        send_announcement(config, &channel, &target, &content, false).await
+    })
+    .await
+}
+
+async fn send_announcement(
+    config: &Config,
+    channel: &str,
+    target: &str,
+    output: &str,
+    silent: bool,
 ) -> Result<()> {
     match channel.to_ascii_lowercase().as_str() {
         "telegram" => {
@@ -316,8 +513,14 @@ pub(crate) async fn deliver_announcement(
                 tg.bot_token.clone(),
                 tg.allowed_users.clone(),
                 tg.mention_only,
+            )
+            .with_outbound_limits(
+                config.runtime.adapter_max_inflight,
+                config.runtime.adapter_retry_jitter_ms,
             );
-            channel.send(&SendMessage::new(output, target)).await?;
+            channel
+                .send(&SendMessage::new(output, target).silent(silent))
+                .await?;
         }
         "discord" => {
             let dc = config
@@ -331,8 +534,14 @@ pub(crate) async fn deliver_announcement(
                 dc.allowed_users.clone(),
                 dc.listen_to_bots,
                 dc.mention_only,
+            )
+            .with_outbound_limits(
+                config.runtime.adapter_max_inflight,
+                config.runtime.adapter_retry_jitter_ms,
             );
-            channel.send(&SendMessage::new(output, target)).await?;
+            channel
+                .send(&SendMessage::new(output, target).silent(silent))
+                .await?;
         }
         "slack" => {
             let sl = config
@@ -345,7 +554,14 @@ pub(crate) async fn deliver_announcement(
                 sl.channel_id.clone(),
                 sl.allowed_users.clone(),
             );
-            channel.send(&SendMessage::new(output, target)).await?;
+            let (channel_id, thread_ts) = crate::channels::slack::parse_delivery_target(target)?;
+            channel
+                .send(
+                    &SendMessage::new(output, channel_id)
+                        .in_thread(thread_ts.map(String::from))
+                        .silent(silent),
+                )
+                .await?;
         }
         "mattermost" => {
             let mm = config
@@ -361,8 +577,11 @@ pub(crate) async fn deliver_announcement(
                 mm.thread_replies.unwrap_or(true),
                 mm.mention_only.unwrap_or(false),
             );
-            channel.send(&SendMessage::new(output, target)).await?;
+            channel
+                .send(&SendMessage::new(output, target).silent(silent))
+                .await?;
         }
+        // Keep ANNOUNCE_CHANNELS in sync when adding a channel here.
         other => anyhow::bail!("unsupported delivery channel: {other}"),
     }
 
@@ -504,6 +723,9 @@ mod tests {
             last_run: None,
             last_status: None,
             last_output: None,
+            last_error: None,
+            missed_runs: 0,
+            catch_up: crate::cron::CatchUp::RunOnce,
         }
     }
 
@@ -750,6 +972,68 @@ mod tests {
         assert!(output.contains("rate limit exceeded"));
     }
 
+    #[tokio::test]
+    async fn downtime_gap_counts_missed_runs_and_applies_catch_up() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp).await;
+        let every_minute = || crate::cron::Schedule::Every { every_ms: 60_000 };
+        let run_once = cron::add_shell_job(&config, None, every_minute(), "echo a").unwrap();
+        let skip = cron::add_shell_job(&config, None, every_minute(), "echo b").unwrap();
+        cron::update_job(
+            &config,
+            &skip.id,
+            CronJobPatch {
+                catch_up: Some(CatchUp::Skip),
+                ..CronJobPatch::default()
+            },
+        )
+        .unwrap();
+        let late = cron::add_shell_job(&config, None, every_minute(), "echo c").unwrap();
+
+        // Process down for five and a half minutes: five more slots passed
+        // after the one each job was waiting for.
+        let now = Utc::now();
+        let gap_start = now - ChronoDuration::seconds(330);
+        for id in [&run_once.id, &skip.id] {
+            record_missed_runs(&config, id, 0, Some(gap_start)).unwrap();
+        }
+        // Merely picked up late by the poll loop: not a missed run.
+        record_missed_runs(
+            &config,
+            &late.id,
+            0,
+            Some(now - ChronoDuration::seconds(30)),
+        )
+        .unwrap();
+
+        let due = cron::due_jobs(&config, now).unwrap();
+        assert_eq!(due.len(), 3);
+        let runnable = apply_catch_up(&config, due, now);
+
+        let mut ids: Vec<&str> = runnable.iter().map(|job| job.id.as_str()).collect();
+        ids.sort_unstable();
+        let mut expected = vec![run_once.id.as_str(), late.id.as_str()];
+        expected.sort_unstable();
+        assert_eq!(ids, expected);
+
+        assert_eq!(cron::get_job(&config, &run_once.id).unwrap().missed_runs, 5);
+        assert_eq!(cron::get_job(&config, &late.id).unwrap().missed_runs, 0);
+        let skipped = cron::get_job(&config, &skip.id).unwrap();
+        assert_eq!(skipped.missed_runs, 6);
+        assert!(skipped.next_run > now);
+    }
+
+    #[test]
+    fn one_shot_jobs_never_count_missed_slots() {
+        let mut job = test_job("echo once");
+        job.schedule = crate::cron::Schedule::At {
+            at: Utc::now() - ChronoDuration::hours(3),
+            original: None,
+        };
+        job.next_run = Utc::now() - ChronoDuration::hours(3);
+        assert_eq!(missed_slots(&job, Utc::now()), 0);
+    }
+
     #[tokio::test]
     async fn process_due_jobs_marks_component_ok_even_when_idle() {
         let tmp = TempDir::new().unwrap();
@@ -814,7 +1098,7 @@ mod tests {
         let job = cron::add_agent_job(
             &config,
             Some("one-shot".into()),
-            crate::cron::Schedule::At { at },
+            crate::cron::Schedule::At { at, original: None },
             "Hello",
             SessionTarget::Isolated,
             None,
@@ -831,6 +1115,92 @@ mod tests {
         assert!(lookup.is_err());
     }
 
+    fn followup_job(config: &Config, session_key: &str) -> CronJob {
+        cron::add_agent_job(
+            config,
+            Some("follow-up".into()),
+            crate::cron::Schedule::At {
+                at: Utc::now() + ChronoDuration::minutes(20),
+                original: Some("in 20 minutes".into()),
+            },
+            "check the build",
+            SessionTarget::Isolated,
+            None,
+            Some(DeliveryConfig {
+                mode: "none".into(),
+                channel: None,
+                to: None,
+                best_effort: true,
+                session_key: Some(session_key.into()),
+            }),
+            true,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn persist_job_result_records_followup_in_original_session() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp).await;
+        let job = followup_job(&config, "telegram_alice");
+        assert_eq!(
+            cron::get_job(&config, &job.id)
+                .unwrap()
+                .delivery
+                .session_key
+                .as_deref(),
+            Some("telegram_alice")
+        );
+        let started = Utc::now();
+
+        let success =
+            persist_job_result(&config, &job, true, "Build is green", started, started).await;
+        assert!(success);
+
+        let store = crate::sessions::SqliteSessionStore::open(&config.workspace_dir).unwrap();
+        let history = store.load_history("telegram_alice", None).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].role, "assistant");
+        assert_eq!(history[0].content, "Build is green");
+        assert!(!store.session_exists("cron:telegram").unwrap());
+    }
+
+    #[tokio::test]
+    async fn persist_job_result_failed_followup_is_not_recorded() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp).await;
+        let job = followup_job(&config, "telegram_alice");
+        let started = Utc::now();
+
+        let success = persist_job_result(&config, &job, false, "boom", started, started).await;
+        assert!(!success);
+
+        let store = crate::sessions::SqliteSessionStore::open(&config.workspace_dir).unwrap();
+        assert!(!store.session_exists("telegram_alice").unwrap());
+    }
+
+    #[tokio::test]
+    async fn followup_prompt_includes_recent_session_turns() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp).await;
+        assert_eq!(
+            followup_prompt(&config, "telegram_alice", "check the build").await,
+            "Follow-up you scheduled earlier in this conversation: check the build"
+        );
+
+        let store = crate::sessions::SqliteSessionStore::open(&config.workspace_dir).unwrap();
+        store
+            .append_message("telegram_alice", "user", "check CI again in 20 minutes")
+            .unwrap();
+        store
+            .append_message("telegram_bob", "user", "unrelated")
+            .unwrap();
+        let prompt = followup_prompt(&config, "telegram_alice", "check the build").await;
+        assert!(prompt.contains("user: check CI again in 20 minutes"));
+        assert!(!prompt.contains("unrelated"));
+        assert!(prompt.ends_with("Follow-up task: check the build"));
+    }
+
     #[tokio::test]
     async fn persist_job_result_failure_disables_one_shot() {
         let tmp = TempDir::new().unwrap();
@@ -839,7 +1209,7 @@ mod tests {
         let job = cron::add_agent_job(
             &config,
             Some("one-shot".into()),
-            crate::cron::Schedule::At { at },
+            crate::cron::Schedule::At { at, original: None },
             "Hello",
             SessionTarget::Isolated,
             None,
@@ -909,6 +1279,7 @@ mod tests {
                 channel: Some("telegram".into()),
                 to: Some("123456".into()),
                 best_effort: false,
+                session_key: None,
             }),
             false,
         )
@@ -947,6 +1318,7 @@ mod tests {
                 channel: Some("telegram".into()),
                 to: Some("123456".into()),
                 best_effort: true,
+                session_key: None,
             }),
             false,
         )
@@ -974,7 +1346,7 @@ mod tests {
         let job = cron::add_agent_job(
             &config,
             Some("at-no-autodelete".into()),
-            crate::cron::Schedule::At { at },
+            crate::cron::Schedule::At { at, original: None },
             "Hello",
             SessionTarget::Isolated,
             None,
@@ -989,9 +1361,12 @@ mod tests {
         let success = persist_job_result(&config, &job, true, "ok", started, finished).await;
         assert!(success);
 
+        // Kept for its history, but expired: it must not fire again.
         let updated = cron::get_job(&config, &job.id).unwrap();
-        assert!(updated.enabled);
+        assert!(!updated.enabled);
         assert_eq!(updated.last_status.as_deref(), Some("ok"));
+        let later = Utc::now() + ChronoDuration::hours(1);
+        assert!(cron::due_jobs(&config, later).unwrap().is_empty());
     }
 
     #[tokio::test]
@@ -1007,6 +1382,7 @@ mod tests {
             channel: Some("invalid".into()),
             to: Some("target".into()),
             best_effort: true,
+            session_key: None,
         };
         let err = deliver_if_configured(&config, &job, "x").await.unwrap_err();
         assert!(err.to_string().contains("unsupported delivery channel"));
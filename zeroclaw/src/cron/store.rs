@@ -1,12 +1,13 @@
 use crate::config::Config;
 use crate::cron::{
-    next_run_for_schedule, schedule_cron_expression, validate_schedule, CronJob, CronJobPatch,
-    CronRun, DeliveryConfig, JobType, Schedule, SessionTarget,
+    next_run_for_schedule, schedule_cron_expression, validate_schedule, CatchUp, CronJob,
+    CronJobPatch, CronRun, DeliveryConfig, JobType, Schedule, SessionTarget,
 };
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use rusqlite::types::{FromSqlResult, ValueRef};
 use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 const MAX_CRON_OUTPUT_BYTES: usize = 16 * 1024;
@@ -42,7 +43,7 @@ pub fn add_shell_job(
 
     let delete_after_run = matches!(schedule, Schedule::At { .. });
 
-    with_connection(config, |conn| {
+    with_job_change(config, |conn| {
         conn.execute(
             "INSERT INTO cron_jobs (
                 id, expression, command, schedule, job_type, prompt, name, session_target, model,
@@ -85,8 +86,9 @@ pub fn add_agent_job(
     let expression = schedule_cron_expression(&schedule).unwrap_or_default();
     let schedule_json = serde_json::to_string(&schedule)?;
     let delivery = delivery.unwrap_or_default();
+    validate_delivery(&delivery)?;
 
-    with_connection(config, |conn| {
+    with_job_change(config, |conn| {
         conn.execute(
             "INSERT INTO cron_jobs (
                 id, expression, command, schedule, job_type, prompt, name, session_target, model,
@@ -117,7 +119,8 @@ pub fn list_jobs(config: &Config) -> Result<Vec<CronJob>> {
     with_connection(config, |conn| {
         let mut stmt = conn.prepare(
             "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
-                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output
+                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output,
+                    last_error, missed_runs, catch_up
              FROM cron_jobs ORDER BY next_run ASC",
         )?;
 
@@ -135,7 +138,8 @@ pub fn get_job(config: &Config, job_id: &str) -> Result<CronJob> {
     with_connection(config, |conn| {
         let mut stmt = conn.prepare(
             "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
-                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output
+                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output,
+                    last_error, missed_runs, catch_up
              FROM cron_jobs WHERE id = ?1",
         )?;
 
@@ -149,7 +153,7 @@ pub fn get_job(config: &Config, job_id: &str) -> Result<CronJob> {
 }
 
 pub fn remove_job(config: &Config, id: &str) -> Result<()> {
-    let changed = with_connection(config, |conn| {
+    let changed = with_job_change(config, |conn| {
         conn.execute("DELETE FROM cron_jobs WHERE id = ?1", params![id])
             .context("Failed to delete cron job")
     })?;
@@ -168,7 +172,8 @@ pub fn due_jobs(config: &Config, now: DateTime<Utc>) -> Result<Vec<CronJob>> {
     with_connection(config, |conn| {
         let mut stmt = conn.prepare(
             "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
-                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output
+                    enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output,
+                    last_error, missed_runs, catch_up
              FROM cron_jobs
              WHERE enabled = 1 AND next_run <= ?1
              ORDER BY next_run ASC
@@ -208,6 +213,7 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
         job.enabled = enabled;
     }
     if let Some(delivery) = patch.delivery {
+        validate_delivery(&delivery)?;
         job.delivery = delivery;
     }
     if let Some(model) = patch.model {
@@ -219,23 +225,26 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
     if let Some(delete_after_run) = patch.delete_after_run {
         job.delete_after_run = delete_after_run;
     }
+    if let Some(catch_up) = patch.catch_up {
+        job.catch_up = catch_up;
+    }
 
     if schedule_changed {
         job.next_run = next_run_for_schedule(&job.schedule, Utc::now())?;
     }
 
-    with_connection(config, |conn| {
+    with_job_change(config, |conn| {
         conn.execute(
             "UPDATE cron_jobs
              SET expression = ?1, command = ?2, schedule = ?3, job_type = ?4, prompt = ?5, name = ?6,
                  session_target = ?7, model = ?8, enabled = ?9, delivery = ?10, delete_after_run = ?11,
-                 next_run = ?12
-             WHERE id = ?13",
+                 next_run = ?12, catch_up = ?13
+             WHERE id = ?14",
             params![
                 job.expression,
                 job.command,
                 serde_json::to_string(&job.schedule)?,
-                <JobType as Into<&str>>::into(job.job_type).to_string(),
+                <JobType as Into<&str>>::into(job.job_type.clone()).to_string(),
                 job.prompt,
                 job.name,
                 job.session_target.as_str(),
@@ -244,6 +253,7 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
                 serde_json::to_string(&job.delivery)?,
                 if job.delete_after_run { 1 } else { 0 },
                 job.next_run.to_rfc3339(),
+                job.catch_up.as_str(),
                 job.id,
             ],
         )
@@ -266,7 +276,8 @@ pub fn record_last_run(
     with_connection(config, |conn| {
         conn.execute(
             "UPDATE cron_jobs
-             SET last_run = ?1, last_status = ?2, last_output = ?3
+             SET last_run = ?1, last_status = ?2, last_output = ?3,
+                 last_error = CASE WHEN ?2 = 'error' THEN ?3 ELSE last_error END
              WHERE id = ?4",
             params![finished_at.to_rfc3339(), status, bounded_output, job_id],
         )
@@ -289,7 +300,8 @@ pub fn reschedule_after_run(
     with_connection(config, |conn| {
         conn.execute(
             "UPDATE cron_jobs
-             SET next_run = ?1, last_run = ?2, last_status = ?3, last_output = ?4
+             SET next_run = ?1, last_run = ?2, last_status = ?3, last_output = ?4,
+                 last_error = CASE WHEN ?3 = 'error' THEN ?4 ELSE last_error END
              WHERE id = ?5",
             params![
                 next_run.to_rfc3339(),
@@ -304,6 +316,27 @@ pub fn reschedule_after_run(
     })
 }
 
+/// Add `missed` to the job's missed-run counter and, when given, move its
+/// next run (used when overdue slots are skipped).
+pub fn record_missed_runs(
+    config: &Config,
+    job_id: &str,
+    missed: u64,
+    next_run: Option<DateTime<Utc>>,
+) -> Result<()> {
+    let missed = i64::try_from(missed).unwrap_or(i64::MAX);
+    with_connection(config, |conn| {
+        conn.execute(
+            "UPDATE cron_jobs
+             SET missed_runs = missed_runs + ?1, next_run = COALESCE(?2, next_run)
+             WHERE id = ?3",
+            params![missed, next_run.map(|at| at.to_rfc3339()), job_id],
+        )
+        .context("Failed to record missed cron runs")?;
+        Ok(())
+    })
+}
+
 pub fn record_run(
     config: &Config,
     job_id: &str,
@@ -354,6 +387,20 @@ pub fn record_run(
     })
 }
 
+/// Reject announce targets the channel could never deliver to, so a bad
+/// Slack `channel:thread_ts` fails when the job is saved, not when it fires.
+fn validate_delivery(delivery: &DeliveryConfig) -> Result<()> {
+    if !delivery.mode.eq_ignore_ascii_case("announce") {
+        return Ok(());
+    }
+    if let (Some(channel), Some(to)) = (delivery.channel.as_deref(), delivery.to.as_deref()) {
+        if channel.eq_ignore_ascii_case("slack") {
+            crate::channels::slack::parse_delivery_target(to)?;
+        }
+    }
+    Ok(())
+}
+
 fn truncate_cron_output(output: &str) -> String {
     if output.len() <= MAX_CRON_OUTPUT_BYTES {
         return output.to_string();
@@ -450,6 +497,13 @@ fn map_cron_job_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CronJob> {
         },
         last_status: row.get(15)?,
         last_output: row.get(16)?,
+        last_error: row.get(17)?,
+        missed_runs: u64::try_from(row.get::<_, i64>(18)?).unwrap_or_default(),
+        catch_up: row
+            .get::<_, Option<String>>(19)?
+            .as_deref()
+            .and_then(CatchUp::parse)
+            .unwrap_or_default(),
     })
 }
 
@@ -513,14 +567,122 @@ fn add_column_if_missing(conn: &Connection, name: &str, sql_type: &str) -> Resul
     }
 }
 
-fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
-    let db_path = config.workspace_dir.join("cron").join("jobs.db");
+/// Schema version recorded in `PRAGMA user_version`; bump it and add a step
+/// to [`migrate_schema`] when the tables change.
+const CRON_SCHEMA_VERSION: i64 = 1;
+
+fn cron_db_path(config: &Config) -> PathBuf {
+    config.workspace_dir.join("cron").join("jobs.db")
+}
+
+/// Last good copy of the store, refreshed after every change to a job.
+fn backup_path(db_path: &Path) -> PathBuf {
+    db_path.with_extension("db.bak")
+}
+
+/// Where a store SQLite reports as corrupt is moved, e.g.
+/// `jobs.db.corrupt-20260101T120000Z`.
+fn quarantine_path(db_path: &Path, at: DateTime<Utc>) -> PathBuf {
+    db_path.with_extension(format!("db.corrupt-{}", at.format("%Y%m%dT%H%M%SZ")))
+}
+
+fn with_connection<T>(config: &Config, f: impl Fn(&Connection) -> Result<T>) -> Result<T> {
+    let db_path = cron_db_path(config);
     if let Some(parent) = db_path.parent() {
         std::fs::create_dir_all(parent)
             .with_context(|| format!("Failed to create cron directory: {}", parent.display()))?;
     }
 
-    let conn = Connection::open(&db_path)
+    match open_store(&db_path).and_then(|conn| f(&conn)) {
+        Err(e) if is_corruption(&e) => {
+            recover_corrupt_store(&db_path, &e)?;
+            open_store(&db_path).and_then(|conn| f(&conn))
+        }
+        result => result,
+    }
+}
+
+/// [`with_connection`] for changes to job definitions: on success the store
+/// is also copied to the backup that [`recover_corrupt_store`] restores.
+fn with_job_change<T>(config: &Config, f: impl Fn(&Connection) -> Result<T>) -> Result<T> {
+    let db_path = cron_db_path(config);
+    with_connection(config, |conn| {
+        let value = f(conn)?;
+        if let Err(e) = refresh_backup(conn, &db_path) {
+            tracing::warn!("Failed to back up cron DB {}: {e:#}", db_path.display());
+        }
+        Ok(value)
+    })
+}
+
+/// Write a consistent copy of the store next to it and rename it over the
+/// previous backup, so a crash never leaves a half-written backup.
+fn refresh_backup(conn: &Connection, db_path: &Path) -> Result<()> {
+    let backup = backup_path(db_path);
+    let tmp = backup.with_extension("bak.tmp");
+    let _ = std::fs::remove_file(&tmp);
+    conn.execute("VACUUM INTO ?1", params![tmp.to_string_lossy()])
+        .context("Failed to write cron DB backup")?;
+    std::fs::File::open(&tmp)?.sync_all()?;
+    std::fs::rename(&tmp, &backup)?;
+    #[cfg(unix)]
+    if let Some(parent) = backup.parent() {
+        std::fs::File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+fn is_corruption(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<rusqlite::Error>(),
+            Some(rusqlite::Error::SqliteFailure(e, _))
+                if matches!(
+                    e.code,
+                    rusqlite::ErrorCode::DatabaseCorrupt | rusqlite::ErrorCode::NotADatabase
+                )
+        )
+    })
+}
+
+/// Move a corrupt store aside (never delete it: it may still hold jobs worth
+/// salvaging by hand) and restore the last backup in its place, if any.
+fn recover_corrupt_store(db_path: &Path, err: &anyhow::Error) -> Result<()> {
+    let quarantine = quarantine_path(db_path, Utc::now());
+    std::fs::rename(db_path, &quarantine).with_context(|| {
+        format!(
+            "Cron DB {} is corrupt ({err:#}) and could not be moved aside",
+            db_path.display()
+        )
+    })?;
+    let journal = db_path.with_extension("db-journal");
+    if journal.exists() {
+        let _ = std::fs::rename(&journal, quarantine.with_extension("journal"));
+    }
+
+    let backup = backup_path(db_path);
+    if backup.exists() {
+        std::fs::copy(&backup, db_path)
+            .with_context(|| format!("Failed to restore cron DB from {}", backup.display()))?;
+        tracing::error!(
+            "Cron DB {} is corrupt ({err:#}); moved it to {} and restored the backup from {}",
+            db_path.display(),
+            quarantine.display(),
+            backup.display()
+        );
+    } else {
+        tracing::error!(
+            "Cron DB {} is corrupt ({err:#}); moved it to {} and started an empty one \
+             (no backup found)",
+            db_path.display(),
+            quarantine.display()
+        );
+    }
+    Ok(())
+}
+
+fn open_store(db_path: &Path) -> Result<Connection> {
+    let conn = Connection::open(db_path)
         .with_context(|| format!("Failed to open cron DB: {}", db_path.display()))?;
 
     conn.execute_batch(
@@ -542,7 +704,10 @@ fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>)
             next_run         TEXT NOT NULL,
             last_run         TEXT,
             last_status      TEXT,
-            last_output      TEXT
+            last_output      TEXT,
+            last_error       TEXT,
+            missed_runs      INTEGER NOT NULL DEFAULT 0,
+            catch_up         TEXT NOT NULL DEFAULT 'run_once'
         );
         CREATE INDEX IF NOT EXISTS idx_cron_jobs_next_run ON cron_jobs(next_run);
 
@@ -562,17 +727,38 @@ fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>)
     )
     .context("Failed to initialize cron schema")?;
 
-    add_column_if_missing(&conn, "schedule", "TEXT")?;
-    add_column_if_missing(&conn, "job_type", "TEXT NOT NULL DEFAULT 'shell'")?;
-    add_column_if_missing(&conn, "prompt", "TEXT")?;
-    add_column_if_missing(&conn, "name", "TEXT")?;
-    add_column_if_missing(&conn, "session_target", "TEXT NOT NULL DEFAULT 'isolated'")?;
-    add_column_if_missing(&conn, "model", "TEXT")?;
-    add_column_if_missing(&conn, "enabled", "INTEGER NOT NULL DEFAULT 1")?;
-    add_column_if_missing(&conn, "delivery", "TEXT")?;
-    add_column_if_missing(&conn, "delete_after_run", "INTEGER NOT NULL DEFAULT 0")?;
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version < CRON_SCHEMA_VERSION {
+        migrate_schema(&conn, version)?;
+        conn.execute_batch(&format!("PRAGMA user_version = {CRON_SCHEMA_VERSION}"))?;
+    } else if version > CRON_SCHEMA_VERSION {
+        tracing::warn!(
+            "Cron DB {} has schema version {version}, newer than {CRON_SCHEMA_VERSION}; \
+             columns this version does not know are left untouched",
+            db_path.display()
+        );
+    }
+    Ok(conn)
+}
 
-    f(&conn)
+/// Bring a store at schema `from` up to [`CRON_SCHEMA_VERSION`].
+fn migrate_schema(conn: &Connection, from: i64) -> Result<()> {
+    if from < 1 {
+        // Stores created before versioning may lack columns added since.
+        add_column_if_missing(conn, "schedule", "TEXT")?;
+        add_column_if_missing(conn, "job_type", "TEXT NOT NULL DEFAULT 'shell'")?;
+        add_column_if_missing(conn, "prompt", "TEXT")?;
+        add_column_if_missing(conn, "name", "TEXT")?;
+        add_column_if_missing(conn, "session_target", "TEXT NOT NULL DEFAULT 'isolated'")?;
+        add_column_if_missing(conn, "model", "TEXT")?;
+        add_column_if_missing(conn, "enabled", "INTEGER NOT NULL DEFAULT 1")?;
+        add_column_if_missing(conn, "delivery", "TEXT")?;
+        add_column_if_missing(conn, "delete_after_run", "INTEGER NOT NULL DEFAULT 0")?;
+        add_column_if_missing(conn, "last_error", "TEXT")?;
+        add_column_if_missing(conn, "missed_runs", "INTEGER NOT NULL DEFAULT 0")?;
+        add_column_if_missing(conn, "catch_up", "TEXT NOT NULL DEFAULT 'run_once'")?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -613,6 +799,7 @@ mod tests {
             None,
             Schedule::At {
                 at: Utc::now() + ChronoDuration::minutes(10),
+                original: None,
             },
             "echo once",
         )
@@ -629,6 +816,43 @@ mod tests {
         assert!(!recurring.delete_after_run);
     }
 
+    #[test]
+    fn agent_job_rejects_malformed_slack_thread_target() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let slack_delivery = |to: &str| DeliveryConfig {
+            mode: "announce".into(),
+            channel: Some("slack".into()),
+            to: Some(to.into()),
+            best_effort: true,
+            session_key: None,
+        };
+        let add = |delivery| {
+            add_agent_job(
+                &config,
+                None,
+                Schedule::Every { every_ms: 60_000 },
+                "report",
+                SessionTarget::Isolated,
+                None,
+                Some(delivery),
+                false,
+            )
+        };
+
+        let err = add(slack_delivery("C123:yesterday")).unwrap_err();
+        assert!(err.to_string().contains("thread_ts"), "{err}");
+
+        let job = add(slack_delivery("C123:1712345678.000100")).unwrap();
+        assert_eq!(job.delivery.to.as_deref(), Some("C123:1712345678.000100"));
+
+        let patch = CronJobPatch {
+            delivery: Some(slack_delivery("C123:")),
+            ..CronJobPatch::default()
+        };
+        assert!(update_job(&config, &job.id, patch).is_err());
+    }
+
     #[test]
     fn add_list_remove_roundtrip() {
         let tmp = TempDir::new().unwrap();
@@ -784,6 +1008,87 @@ mod tests {
         assert!(matches!(job.schedule, Schedule::Cron { .. }));
     }
 
+    fn quarantined_files(config: &Config) -> Vec<PathBuf> {
+        std::fs::read_dir(config.workspace_dir.join("cron"))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.to_string_lossy().contains(".db.corrupt-"))
+            .collect()
+    }
+
+    #[test]
+    fn truncated_store_is_quarantined_and_restored_from_backup() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        add_job(&config, "*/5 * * * *", "echo one").unwrap();
+        add_job(&config, "0 9 * * *", "echo two").unwrap();
+        let db_path = cron_db_path(&config);
+        assert!(backup_path(&db_path).exists());
+
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(&db_path)
+            .unwrap();
+        file.set_len(4096 + 100).unwrap();
+        drop(file);
+
+        let mut commands: Vec<_> = list_jobs(&config)
+            .unwrap()
+            .into_iter()
+            .map(|job| job.command)
+            .collect();
+        commands.sort();
+        assert_eq!(commands, ["echo one", "echo two"]);
+        let quarantined = quarantined_files(&config);
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(
+            std::fs::metadata(&quarantined[0]).unwrap().len(),
+            4096 + 100
+        );
+    }
+
+    #[test]
+    fn corrupt_store_without_backup_is_kept_aside_not_overwritten() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let db_path = cron_db_path(&config);
+        std::fs::create_dir_all(db_path.parent().unwrap()).unwrap();
+        std::fs::write(&db_path, vec![0xAB; 8192]).unwrap();
+
+        assert!(list_jobs(&config).unwrap().is_empty());
+        let quarantined = quarantined_files(&config);
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(std::fs::read(&quarantined[0]).unwrap(), vec![0xAB; 8192]);
+        add_job(&config, "*/5 * * * *", "echo fresh").unwrap();
+        assert_eq!(list_jobs(&config).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn quarantine_name_carries_utc_timestamp() {
+        let at = DateTime::parse_from_rfc3339("2026-03-04T05:06:07Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            quarantine_path(Path::new("/w/cron/jobs.db"), at),
+            Path::new("/w/cron/jobs.db.corrupt-20260304T050607Z")
+        );
+        assert_eq!(
+            backup_path(Path::new("/w/cron/jobs.db")),
+            Path::new("/w/cron/jobs.db.bak")
+        );
+    }
+
+    #[test]
+    fn store_records_schema_version() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let version: i64 = with_connection(&config, |conn| {
+            Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+        })
+        .unwrap();
+        assert_eq!(version, CRON_SCHEMA_VERSION);
+    }
+
     #[test]
     fn record_and_prune_runs() {
         let tmp = TempDir::new().unwrap();
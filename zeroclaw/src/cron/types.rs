@@ -58,6 +58,35 @@ impl SessionTarget {
     }
 }
 
+/// What the scheduler does when a job is overdue by more than one interval
+/// (the process was down or the machine asleep when slots came due).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CatchUp {
+    /// Run once for the whole gap, then continue on schedule.
+    #[default]
+    RunOnce,
+    /// Skip the missed slots and wait for the next one.
+    Skip,
+}
+
+impl CatchUp {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::RunOnce => "run_once",
+            Self::Skip => "skip",
+        }
+    }
+
+    pub(crate) fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "run_once" => Some(Self::RunOnce),
+            "skip" => Some(Self::Skip),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "kind", rename_all = "lowercase")]
 pub enum Schedule {
@@ -68,6 +97,10 @@ pub enum Schedule {
     },
     At {
         at: DateTime<Utc>,
+        /// The expression the user or model gave (e.g. "tomorrow 9am"),
+        /// kept for display next to the resolved time.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        original: Option<String>,
     },
     Every {
         every_ms: u64,
@@ -84,6 +117,10 @@ pub struct DeliveryConfig {
     pub to: Option<String>,
     #[serde(default = "default_true")]
     pub best_effort: bool,
+    /// Conversation that scheduled the job (follow-ups). The delivered
+    /// output is recorded in this session's history.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_key: Option<String>,
 }
 
 impl Default for DeliveryConfig {
@@ -93,6 +130,7 @@ impl Default for DeliveryConfig {
             channel: None,
             to: None,
             best_effort: true,
+            session_key: None,
         }
     }
 }
@@ -120,6 +158,11 @@ pub struct CronJob {
     pub last_run: Option<DateTime<Utc>>,
     pub last_status: Option<String>,
     pub last_output: Option<String>,
+    /// Output of the most recent failed run; kept after later successes.
+    pub last_error: Option<String>,
+    /// Scheduled slots that passed without a run, summed over the job's life.
+    pub missed_runs: u64,
+    pub catch_up: CatchUp,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -144,11 +187,12 @@ pub struct CronJobPatch {
     pub model: Option<String>,
     pub session_target: Option<SessionTarget>,
     pub delete_after_run: Option<bool>,
+    pub catch_up: Option<CatchUp>,
 }
 
 #[cfg(test)]
 mod tests {
-    use super::JobType;
+    use super::{CatchUp, JobType};
 
     #[test]
     fn job_type_try_from_accepts_known_values_case_insensitive() {
@@ -163,4 +207,15 @@ mod tests {
         assert!(JobType::try_from("").is_err());
         assert!(JobType::try_from("unknown").is_err());
     }
+
+    #[test]
+    fn catch_up_parses_cli_and_stored_forms() {
+        assert_eq!(CatchUp::parse("skip"), Some(CatchUp::Skip));
+        assert_eq!(CatchUp::parse("run-once"), Some(CatchUp::RunOnce));
+        assert_eq!(
+            CatchUp::parse(CatchUp::RunOnce.as_str()),
+            Some(CatchUp::RunOnce)
+        );
+        assert_eq!(CatchUp::parse("later"), None);
+    }
 }
@@ -0,0 +1,329 @@
+//! Single-instance guard for `zeroclaw daemon` and `zeroclaw gateway`.
+//!
+//! Two layers keep a second instance from starting half-way:
+//!
+//! - [`InstanceLock`] writes `zeroclaw.pid` next to `config.toml`. A lock
+//!   whose pid is no longer running is treated as stale and replaced.
+//! - [`bind_gateway_listener`] claims the gateway port before any other
+//!   component starts. If the port is taken, it asks the current holder's
+//!   `/health` whether it is ZeroClaw and reports that instance's pid,
+//!   version and uptime, or tells the user to pick another port.
+
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const LOCK_FILE_NAME: &str = "zeroclaw.pid";
+
+/// A lock file that is still empty after this long belongs to a process
+/// that died between creating and writing it.
+const EMPTY_LOCK_GRACE: Duration = Duration::from_secs(5);
+
+const HEALTH_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What an existing lock file says about its owner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LockState {
+    /// Held by another process that is still running.
+    Held(u32),
+    /// Just created by another process that has not written its pid yet.
+    Acquiring,
+    /// Left behind by a process that is gone; safe to replace.
+    Stale,
+}
+
+fn classify_lock(
+    contents: &str,
+    age: Duration,
+    own_pid: u32,
+    alive: impl Fn(u32) -> bool,
+) -> LockState {
+    let contents = contents.trim();
+    match contents.parse::<u32>() {
+        Ok(pid) if pid != own_pid && alive(pid) => LockState::Held(pid),
+        Err(_) if contents.is_empty() && age < EMPTY_LOCK_GRACE => LockState::Acquiring,
+        Ok(_) | Err(_) => LockState::Stale,
+    }
+}
+
+/// Exclusive claim on a config directory, released on drop.
+#[derive(Debug)]
+pub struct InstanceLock {
+    path: PathBuf,
+    pid: u32,
+}
+
+impl InstanceLock {
+    pub fn path_for(config_dir: &Path) -> PathBuf {
+        config_dir.join(LOCK_FILE_NAME)
+    }
+
+    /// Claim `config_dir` for this process, replacing a stale lock.
+    pub fn acquire(config_dir: &Path) -> Result<Self> {
+        Self::acquire_with(config_dir, std::process::id(), process_alive)
+    }
+
+    fn acquire_with(config_dir: &Path, pid: u32, alive: impl Fn(u32) -> bool) -> Result<Self> {
+        std::fs::create_dir_all(config_dir)
+            .with_context(|| format!("failed to create {}", config_dir.display()))?;
+        let path = Self::path_for(config_dir);
+
+        // One retry: the first pass may only clear a stale lock.
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    writeln!(file, "{pid}")
+                        .with_context(|| format!("failed to write {}", path.display()))?;
+                    return Ok(Self { path, pid });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+                Err(e) => {
+                    return Err(e).with_context(|| format!("failed to create {}", path.display()))
+                }
+            }
+
+            let contents = std::fs::read_to_string(&path).unwrap_or_default();
+            let age = std::fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .unwrap_or_default();
+            match classify_lock(&contents, age, pid, &alive) {
+                LockState::Held(holder) => anyhow::bail!(
+                    "Another ZeroClaw instance (pid {holder}) is already running with this config \
+                     directory (lock: {}). Stop it first; if pid {holder} is not ZeroClaw, delete \
+                     the lock file.",
+                    path.display()
+                ),
+                LockState::Acquiring => anyhow::bail!(
+                    "Another ZeroClaw instance is starting with this config directory (lock: {}).",
+                    path.display()
+                ),
+                LockState::Stale => {
+                    tracing::warn!("Replacing stale instance lock {}", path.display());
+                    match std::fs::remove_file(&path) {
+                        Ok(()) => {}
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                        Err(e) => {
+                            return Err(e).with_context(|| {
+                                format!("failed to remove stale lock {}", path.display())
+                            })
+                        }
+                    }
+                }
+            }
+        }
+        anyhow::bail!(
+            "Could not acquire instance lock {}; another instance raced for it",
+            path.display()
+        )
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        // Never remove a lock that a newer instance took over.
+        let ours = std::fs::read_to_string(&self.path)
+            .is_ok_and(|contents| contents.trim().parse::<u32>() == Ok(self.pid));
+        if ours {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = i32::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: signal 0 only checks that the pid exists and may be signalled.
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Without a cheap liveness check, assume the holder is alive; the error
+/// message tells the user how to clear a leftover lock.
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+/// Bind the gateway port, explaining an address-in-use failure.
+pub async fn bind_gateway_listener(host: &str, port: u16) -> Result<tokio::net::TcpListener> {
+    let addr: SocketAddr = format!("{host}:{port}")
+        .parse()
+        .with_context(|| format!("invalid gateway address {host}:{port}"))?;
+    match tokio::net::TcpListener::bind(addr).await {
+        Ok(listener) => Ok(listener),
+        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+            let health = probe_health(addr).await;
+            anyhow::bail!(describe_port_conflict(port, health.as_ref()))
+        }
+        Err(e) => Err(e).with_context(|| format!("failed to bind gateway to {addr}")),
+    }
+}
+
+/// `/health` of whatever listens on `addr`, if it answers with JSON over
+/// plain HTTP or (for `[gateway.tls]` instances) HTTPS.
+async fn probe_health(addr: SocketAddr) -> Option<serde_json::Value> {
+    let mut target = addr;
+    if target.ip().is_unspecified() {
+        target.set_ip(if target.is_ipv4() {
+            Ipv4Addr::LOCALHOST.into()
+        } else {
+            Ipv6Addr::LOCALHOST.into()
+        });
+    }
+    // Only reading the public health endpoint, so any certificate will do.
+    let client = reqwest::Client::builder()
+        .timeout(HEALTH_PROBE_TIMEOUT)
+        .danger_accept_invalid_certs(true)
+        .build()
+        .ok()?;
+    for scheme in ["http", "https"] {
+        let Ok(response) = client
+            .get(format!("{scheme}://{target}/health"))
+            .send()
+            .await
+        else {
+            continue;
+        };
+        if let Ok(body) = response.json().await {
+            return Some(body);
+        }
+    }
+    None
+}
+
+/// Error shown when the gateway port is taken. `health` is the holder's
+/// `/health` body, when it answered.
+fn describe_port_conflict(port: u16, health: Option<&serde_json::Value>) -> String {
+    let runtime = health
+        .filter(|h| h.get("status").and_then(serde_json::Value::as_str) == Some("ok"))
+        .and_then(|h| h.get("runtime").map(|runtime| (h, runtime)));
+    let Some((health, runtime)) = runtime else {
+        return format!(
+            "Port {port} is already in use by another program. Set [gateway] port in \
+             config.toml or pass --port to use a different one."
+        );
+    };
+
+    let mut details = Vec::new();
+    if let Some(pid) = runtime.get("pid").and_then(serde_json::Value::as_u64) {
+        details.push(format!("pid {pid}"));
+    }
+    if let Some(version) = health.get("version").and_then(serde_json::Value::as_str) {
+        details.push(format!("version {version}"));
+    }
+    if let Some(uptime) = runtime
+        .get("uptime_seconds")
+        .and_then(serde_json::Value::as_u64)
+    {
+        details.push(format!("up {}", crate::health::format_uptime(uptime)));
+    }
+    let details = if details.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", details.join(", "))
+    };
+    format!(
+        "ZeroClaw is already running on port {port}{details}. Stop it first, or check it with \
+         `zeroclaw status --live`."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const FRESH: Duration = Duration::from_secs(1);
+    const OLD: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn classify_lock_detects_live_stale_and_partial_locks() {
+        let alive = |pid: u32| pid == 100;
+        assert_eq!(classify_lock("100\n", OLD, 1, alive), LockState::Held(100));
+        assert_eq!(classify_lock("200\n", FRESH, 1, alive), LockState::Stale);
+        // Our own pid left behind (e.g. pid reuse after a crash) is stale.
+        assert_eq!(classify_lock("100", OLD, 100, alive), LockState::Stale);
+        assert_eq!(classify_lock("", FRESH, 1, alive), LockState::Acquiring);
+        assert_eq!(classify_lock("  ", OLD, 1, alive), LockState::Stale);
+        assert_eq!(classify_lock("garbage", FRESH, 1, alive), LockState::Stale);
+    }
+
+    #[test]
+    fn acquire_refuses_live_holder_and_replaces_stale_lock() {
+        let tmp = TempDir::new().unwrap();
+        let path = InstanceLock::path_for(tmp.path());
+
+        let first = InstanceLock::acquire_with(tmp.path(), 100, |_| true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap().trim(), "100");
+
+        let err = InstanceLock::acquire_with(tmp.path(), 200, |pid| pid == 100).unwrap_err();
+        assert!(err.to_string().contains("pid 100"), "{err}");
+
+        // Holder died without cleaning up: the next instance takes over.
+        std::mem::forget(first);
+        let second = InstanceLock::acquire_with(tmp.path(), 200, |_| false).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap().trim(), "200");
+        drop(second);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn drop_leaves_a_lock_taken_over_by_another_instance() {
+        let tmp = TempDir::new().unwrap();
+        let path = InstanceLock::path_for(tmp.path());
+        let lock = InstanceLock::acquire_with(tmp.path(), 100, |_| false).unwrap();
+        std::fs::write(&path, "300\n").unwrap();
+        drop(lock);
+        assert_eq!(std::fs::read_to_string(&path).unwrap().trim(), "300");
+    }
+
+    #[test]
+    fn live_process_keeps_its_lock() {
+        let tmp = TempDir::new().unwrap();
+        let _lock = InstanceLock::acquire(tmp.path()).unwrap();
+        assert!(process_alive(std::process::id()));
+
+        let err = InstanceLock::acquire_with(tmp.path(), u32::MAX, process_alive).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(&format!("pid {}", std::process::id())));
+    }
+
+    #[test]
+    fn port_conflict_message_distinguishes_zeroclaw_from_foreign_process() {
+        let health = json!({
+            "status": "ok",
+            "version": "0.1.0",
+            "runtime": { "pid": 4242, "uptime_seconds": 3_720 }
+        });
+        let ours = describe_port_conflict(42617, Some(&health));
+        assert!(ours.starts_with("ZeroClaw is already running on port 42617"));
+        assert!(ours.contains("pid 4242, version 0.1.0, up 1h 2m"), "{ours}");
+
+        let foreign = describe_port_conflict(8080, Some(&json!({"status": "ok"})));
+        assert!(foreign.contains("in use by another program"));
+        assert!(foreign.contains("[gateway] port"));
+        assert_eq!(describe_port_conflict(8080, None), foreign);
+    }
+
+    #[tokio::test]
+    async fn bind_reports_a_taken_port() {
+        let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = taken.local_addr().unwrap().port();
+
+        let err = bind_gateway_listener("127.0.0.1", port).await.unwrap_err();
+        assert!(err.to_string().contains(&format!("Port {port}")), "{err}");
+
+        drop(taken);
+        let listener = bind_gateway_listener("127.0.0.1", port).await.unwrap();
+        assert_eq!(listener.local_addr().unwrap().port(), port);
+    }
+}
@@ -1,3 +1,5 @@
+pub mod instance;
+
 use crate::config::Config;
 use anyhow::Result;
 use chrono::Utc;
@@ -8,12 +10,59 @@ use tokio::time::Duration;
 
 const STATUS_FLUSH_SECONDS: u64 = 5;
 
-pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
-    let initial_backoff = config.reliability.channel_initial_backoff_secs.max(1);
-    let max_backoff = config
-        .reliability
-        .channel_max_backoff_secs
-        .max(initial_backoff);
+/// A component that stays up this long is considered healthy again and its
+/// next failure restarts from the initial backoff.
+const HEALTHY_RESET_SECONDS: u64 = 300;
+
+/// How a supervised component is restarted after it fails or exits.
+#[derive(Debug, Clone, Copy)]
+struct RestartPolicy {
+    initial_backoff_secs: u64,
+    max_backoff_secs: u64,
+    /// Consecutive restarts allowed before the daemon gives up.
+    max_restarts: Option<u32>,
+    healthy_reset: Duration,
+}
+
+impl RestartPolicy {
+    fn from_config(config: &Config, max_restarts: Option<u32>) -> Self {
+        let initial_backoff_secs = config.reliability.channel_initial_backoff_secs.max(1);
+        Self {
+            initial_backoff_secs,
+            max_backoff_secs: config
+                .reliability
+                .channel_max_backoff_secs
+                .max(initial_backoff_secs),
+            max_restarts,
+            healthy_reset: Duration::from_secs(HEALTHY_RESET_SECONDS),
+        }
+    }
+
+    /// Delay before restart number `attempt` (1-based) of a run of
+    /// consecutive failures: the initial backoff, doubled per attempt and
+    /// capped at the max backoff.
+    fn backoff_secs(&self, attempt: u32) -> u64 {
+        let initial = self.initial_backoff_secs.max(1);
+        let doublings = attempt.saturating_sub(1).min(63);
+        initial
+            .saturating_mul(1_u64 << doublings)
+            .min(self.max_backoff_secs.max(initial))
+    }
+}
+
+/// Run the daemon. With `max_restarts`, a component that has to be restarted
+/// more than that many times in a row stops the daemon with an error.
+pub async fn run(config: Config, host: String, port: u16, max_restarts: Option<u32>) -> Result<()> {
+    // Claim the gateway port before anything else starts, so a second
+    // instance exits here instead of running channels without a gateway.
+    crate::gateway::check_bind_policy(&host, &config)?;
+    let listener = instance::bind_gateway_listener(&host, port).await?;
+    // Restarts rebind the port the first bind got (matters for port 0).
+    let port = listener.local_addr()?.port();
+    let prebound = std::sync::Arc::new(parking_lot::Mutex::new(Some(listener)));
+
+    let policy = RestartPolicy::from_config(&config, max_restarts);
+    let (give_up_tx, mut give_up_rx) = tokio::sync::mpsc::unbounded_channel();
 
     crate::health::mark_component_ok("daemon");
 
@@ -30,12 +79,20 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         let gateway_host = host.clone();
         handles.push(spawn_component_supervisor(
             "gateway",
-            initial_backoff,
-            max_backoff,
+            policy,
+            give_up_tx.clone(),
             move || {
                 let cfg = gateway_cfg.clone();
                 let host = gateway_host.clone();
-                async move { crate::gateway::run_gateway(&host, port, cfg).await }
+                let listener = prebound.lock().take();
+                async move {
+                    match listener {
+                        Some(listener) => {
+                            crate::gateway::run_gateway_with_listener(&host, listener, cfg).await
+                        }
+                        None => Box::pin(crate::gateway::run_gateway(&host, port, cfg)).await,
+                    }
+                }
             },
         ));
     }
@@ -45,11 +102,11 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
             let channels_cfg = config.clone();
             handles.push(spawn_component_supervisor(
                 "channels",
-                initial_backoff,
-                max_backoff,
+                policy,
+                give_up_tx.clone(),
                 move || {
                     let cfg = channels_cfg.clone();
-                    async move { crate::channels::start_channels(cfg).await }
+                    async move { Box::pin(crate::channels::start_channels(cfg)).await }
                 },
             ));
         } else {
@@ -62,8 +119,8 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         let heartbeat_cfg = config.clone();
         handles.push(spawn_component_supervisor(
             "heartbeat",
-            initial_backoff,
-            max_backoff,
+            policy,
+            give_up_tx.clone(),
             move || {
                 let cfg = heartbeat_cfg.clone();
                 async move { Box::pin(run_heartbeat_worker(cfg)).await }
@@ -75,8 +132,8 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         let scheduler_cfg = config.clone();
         handles.push(spawn_component_supervisor(
             "scheduler",
-            initial_backoff,
-            max_backoff,
+            policy,
+            give_up_tx.clone(),
             move || {
                 let cfg = scheduler_cfg.clone();
                 async move { crate::cron::scheduler::run(cfg).await }
@@ -92,8 +149,21 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
     println!("   Components: gateway, channels, heartbeat, scheduler");
     println!("   Ctrl+C to stop");
 
-    tokio::signal::ctrl_c().await?;
-    crate::health::mark_component_error("daemon", "shutdown requested");
+    drop(give_up_tx);
+    let outcome = tokio::select! {
+        signal = tokio::signal::ctrl_c() => {
+            crate::health::mark_component_error("daemon", "shutdown requested");
+            signal.map_err(anyhow::Error::from)
+        }
+        Some(name) = give_up_rx.recv() => {
+            let message = format!(
+                "component '{name}' exceeded --max-restarts ({})",
+                max_restarts.unwrap_or_default()
+            );
+            crate::health::mark_component_error("daemon", &message);
+            Err(anyhow::anyhow!(message))
+        }
+    };
 
     for handle in &handles {
         handle.abort();
@@ -102,7 +172,7 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         let _ = handle.await;
     }
 
-    Ok(())
+    outcome
 }
 
 pub fn state_file_path(config: &Config) -> PathBuf {
@@ -138,8 +208,8 @@ fn spawn_state_writer(config: Config) -> JoinHandle<()> {
 
 fn spawn_component_supervisor<F, Fut>(
     name: &'static str,
-    initial_backoff_secs: u64,
-    max_backoff_secs: u64,
+    policy: RestartPolicy,
+    give_up: tokio::sync::mpsc::UnboundedSender<&'static str>,
     mut run_component: F,
 ) -> JoinHandle<()>
 where
@@ -147,17 +217,15 @@ where
     Fut: Future<Output = Result<()>> + Send + 'static,
 {
     tokio::spawn(async move {
-        let mut backoff = initial_backoff_secs.max(1);
-        let max_backoff = max_backoff_secs.max(backoff);
+        let mut attempt: u32 = 0;
 
         loop {
             crate::health::mark_component_ok(name);
+            let started = tokio::time::Instant::now();
             match run_component().await {
                 Ok(()) => {
                     crate::health::mark_component_error(name, "component exited unexpectedly");
                     tracing::warn!("Daemon component '{name}' exited unexpectedly");
-                    // Clean exit — reset backoff since the component ran successfully
-                    backoff = initial_backoff_secs.max(1);
                 }
                 Err(e) => {
                     crate::health::mark_component_error(name, e.to_string());
@@ -165,10 +233,22 @@ where
                 }
             }
 
+            // A component that stayed up long enough starts a fresh run of
+            // failures, so one crash after days of uptime restarts quickly.
+            if started.elapsed() >= policy.healthy_reset {
+                attempt = 0;
+            }
+            attempt = attempt.saturating_add(1);
+            if policy.max_restarts.is_some_and(|max| attempt > max) {
+                tracing::error!(
+                    "Daemon component '{name}' failed {attempt} times in a row; giving up"
+                );
+                let _ = give_up.send(name);
+                return;
+            }
+
             crate::health::bump_component_restart(name);
-            tokio::time::sleep(Duration::from_secs(backoff)).await;
-            // Double backoff AFTER sleeping so first error uses initial_backoff
-            backoff = backoff.saturating_mul(2).min(max_backoff);
+            tokio::time::sleep(Duration::from_secs(policy.backoff_secs(attempt))).await;
         }
     })
 }
@@ -183,6 +263,18 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
     );
     let delivery = heartbeat_delivery_target(&config)?;
 
+    let heartbeats_dir = config
+        .workspace_dir
+        .join(crate::heartbeat::tasks::HEARTBEATS_DIR);
+    if heartbeats_dir.is_dir() {
+        return Box::pin(run_heartbeat_files(
+            &config,
+            &heartbeats_dir,
+            delivery.as_ref(),
+        ))
+        .await;
+    }
+
     let interval_mins = config.heartbeat.interval_minutes.max(5);
     let mut interval = tokio::time::interval(Duration::from_secs(u64::from(interval_mins) * 60));
 
@@ -197,47 +289,134 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
 
         for task in tasks {
             let prompt = format!("[Heartbeat Task] {task}");
-            let temp = config.default_temperature;
-            match crate::agent::run(
-                config.clone(),
-                Some(prompt),
-                None,
-                None,
-                temp,
-                vec![],
-                false,
-            )
-            .await
-            {
-                Ok(output) => {
-                    crate::health::mark_component_ok("heartbeat");
-                    let announcement = if output.trim().is_empty() {
-                        "heartbeat task executed".to_string()
-                    } else {
-                        output
-                    };
-                    if let Some((channel, target)) = &delivery {
-                        if let Err(e) = crate::cron::scheduler::deliver_announcement(
-                            &config,
-                            channel,
-                            target,
-                            &announcement,
-                        )
-                        .await
-                        {
-                            crate::health::mark_component_error(
-                                "heartbeat",
-                                format!("delivery failed: {e}"),
-                            );
-                            tracing::warn!("Heartbeat delivery failed: {e}");
-                        }
-                    }
+            run_heartbeat_prompt(&config, prompt, delivery.as_ref()).await;
+        }
+    }
+}
+
+/// How often `heartbeats/` is rescanned for due files.
+const HEARTBEAT_FILES_POLL_SECS: u64 = 60;
+
+/// Per-file heartbeat mode: each due `heartbeats/*.md` file runs in its own
+/// `heartbeat:<stem>` session, and run times persist across restarts.
+async fn run_heartbeat_files(
+    config: &Config,
+    dir: &std::path::Path,
+    delivery: Option<&(String, String)>,
+) -> Result<()> {
+    use crate::heartbeat::tasks::{scan_heartbeat_dir, HeartbeatState};
+
+    let default_interval_secs = u64::from(config.heartbeat.interval_minutes.max(5)) * 60;
+    let mut state = HeartbeatState::load(dir);
+    tracing::info!("💓 Heartbeat started: per-file tasks in {}", dir.display());
+
+    loop {
+        let files = scan_heartbeat_dir(dir, default_interval_secs)
+            .await?
+            .unwrap_or_default();
+        let due: Vec<_> = state
+            .due(&files, Utc::now().timestamp())
+            .into_iter()
+            .cloned()
+            .collect();
+
+        for file in due {
+            if let Some(prompt) = file.prompt() {
+                let session_key = file.session_key();
+                let session = crate::sessions::SessionContext {
+                    session_key: session_key.clone(),
+                    channel: "heartbeat".into(),
+                    reply_target: delivery.map(|(_, to)| to.clone()).unwrap_or_default(),
+                    thread_ts: None,
+                    sender: String::new(),
+                };
+                let output = Box::pin(crate::sessions::with_session(
+                    session,
+                    run_heartbeat_prompt(config, prompt.clone(), delivery),
+                ))
+                .await;
+                if let Some(output) = output {
+                    record_heartbeat_turns(config, &session_key, &prompt, &output).await;
                 }
-                Err(e) => {
-                    crate::health::mark_component_error("heartbeat", e.to_string());
-                    tracing::warn!("Heartbeat task failed: {e}");
+            }
+            state.mark_run(&file.stem, Utc::now().timestamp(), &files);
+            if let Err(e) = state.save(dir) {
+                tracing::warn!("Failed to save heartbeat state: {e}");
+            }
+        }
+
+        let wait = state
+            .secs_until_next_due(&files, Utc::now().timestamp())
+            .unwrap_or(HEARTBEAT_FILES_POLL_SECS)
+            .clamp(1, HEARTBEAT_FILES_POLL_SECS);
+        tokio::time::sleep(Duration::from_secs(wait)).await;
+    }
+}
+
+/// Keep a heartbeat file's runs in its own session history.
+async fn record_heartbeat_turns(config: &Config, session_key: &str, prompt: &str, output: &str) {
+    let key = session_key.to_string();
+    let (prompt, output) = (prompt.to_string(), output.to_string());
+    let result = crate::sessions::blocking_writable(&config.workspace_dir, move |store| {
+        store.append_message(&key, "user", &prompt)?;
+        store.append_message(&key, "assistant", &output)
+    })
+    .await;
+    if let Err(e) = result {
+        tracing::warn!("Failed to record heartbeat turn in session {session_key}: {e}");
+    }
+}
+
+/// Run one heartbeat prompt through the agent and deliver the output.
+/// Returns the output, or `None` when the agent run failed.
+async fn run_heartbeat_prompt(
+    config: &Config,
+    prompt: String,
+    delivery: Option<&(String, String)>,
+) -> Option<String> {
+    let temp = config.default_temperature;
+    match Box::pin(crate::agent::run(
+        config.clone(),
+        Some(prompt),
+        None,
+        None,
+        temp,
+        vec![],
+        false,
+        Some("heartbeat"),
+    ))
+    .await
+    {
+        Ok(output) => {
+            crate::health::mark_component_ok("heartbeat");
+            let announcement = if output.trim().is_empty() {
+                "heartbeat task executed".to_string()
+            } else {
+                output
+            };
+            if let Some((channel, target)) = delivery {
+                if let Err(e) = crate::cron::scheduler::deliver_announcement(
+                    config,
+                    channel,
+                    target,
+                    &announcement,
+                    crate::channels::quiet_hours::MessageOrigin::Heartbeat,
+                )
+                .await
+                {
+                    crate::health::mark_component_error(
+                        "heartbeat",
+                        format!("delivery failed: {e}"),
+                    );
+                    tracing::warn!("Heartbeat delivery failed: {e}");
                 }
             }
+            Some(announcement)
+        }
+        Err(e) => {
+            crate::health::mark_component_error("heartbeat", e.to_string());
+            tracing::warn!("Heartbeat task failed: {e}");
+            None
         }
     }
 }
@@ -341,6 +520,26 @@ mod tests {
         config
     }
 
+    #[tokio::test]
+    async fn run_fails_fast_before_starting_components_when_port_is_taken() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = taken.local_addr().unwrap().port();
+
+        // Without the bind-first check this would start the supervisors and
+        // never return.
+        let err = tokio::time::timeout(
+            Duration::from_secs(30),
+            run(config.clone(), "127.0.0.1".into(), port, None),
+        )
+        .await
+        .expect("daemon should exit instead of running headless")
+        .unwrap_err();
+        assert!(err.to_string().contains(&format!("Port {port}")), "{err}");
+        assert!(!state_file_path(&config).exists());
+    }
+
     #[test]
     fn state_file_path_uses_config_directory() {
         let tmp = TempDir::new().unwrap();
@@ -350,11 +549,22 @@ mod tests {
         assert_eq!(path, tmp.path().join("daemon_state.json"));
     }
 
+    fn test_policy(max_restarts: Option<u32>) -> RestartPolicy {
+        RestartPolicy {
+            initial_backoff_secs: 1,
+            max_backoff_secs: 1,
+            max_restarts,
+            healthy_reset: Duration::from_secs(HEALTHY_RESET_SECONDS),
+        }
+    }
+
     #[tokio::test]
     async fn supervisor_marks_error_and_restart_on_failure() {
-        let handle = spawn_component_supervisor("daemon-test-fail", 1, 1, || async {
-            anyhow::bail!("boom")
-        });
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let handle =
+            spawn_component_supervisor("daemon-test-fail", test_policy(None), tx, || async {
+                anyhow::bail!("boom")
+            });
 
         tokio::time::sleep(Duration::from_millis(50)).await;
         handle.abort();
@@ -372,7 +582,11 @@ mod tests {
 
     #[tokio::test]
     async fn supervisor_marks_unexpected_exit_as_error() {
-        let handle = spawn_component_supervisor("daemon-test-exit", 1, 1, || async { Ok(()) });
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let handle =
+            spawn_component_supervisor("daemon-test-exit", test_policy(None), tx, || async {
+                Ok(())
+            });
 
         tokio::time::sleep(Duration::from_millis(50)).await;
         handle.abort();
@@ -388,6 +602,42 @@ mod tests {
             .contains("component exited unexpectedly"));
     }
 
+    #[tokio::test]
+    async fn supervisor_gives_up_after_max_restarts() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let runs = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let counter = std::sync::Arc::clone(&runs);
+        let handle = spawn_component_supervisor(
+            "daemon-test-give-up",
+            test_policy(Some(1)),
+            tx,
+            move || {
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async { anyhow::bail!("crash at startup") }
+            },
+        );
+
+        assert_eq!(rx.recv().await, Some("daemon-test-give-up"));
+        handle.await.unwrap();
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn backoff_doubles_per_attempt_up_to_the_cap() {
+        let policy = RestartPolicy {
+            initial_backoff_secs: 2,
+            max_backoff_secs: 60,
+            max_restarts: None,
+            healthy_reset: Duration::from_secs(HEALTHY_RESET_SECONDS),
+        };
+        let delays: Vec<u64> = (1..=7)
+            .map(|attempt| policy.backoff_secs(attempt))
+            .collect();
+        assert_eq!(delays, vec![2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(policy.backoff_secs(u32::MAX), 60);
+        assert_eq!(policy.backoff_secs(0), 2);
+    }
+
     #[test]
     fn detects_no_supervised_channels() {
         let config = Config::default();
@@ -404,6 +654,8 @@ mod tests {
             draft_update_interval_ms: 1000,
             interrupt_on_new_message: false,
             mention_only: false,
+            command_prefix: None,
+            progress_messages: false,
         });
         assert!(has_supervised_channels(&config));
     }
@@ -536,6 +788,8 @@ mod tests {
             draft_update_interval_ms: 1000,
             interrupt_on_new_message: false,
             mention_only: false,
+            command_prefix: None,
+            progress_messages: false,
         });
 
         let target = heartbeat_delivery_target(&config).unwrap();
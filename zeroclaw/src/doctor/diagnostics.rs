@@ -0,0 +1,482 @@
+//! On-demand self-diagnostics the agent and dashboard can act on.
+//!
+//! Where `zeroclaw doctor` inspects local state and `zeroclaw verify` runs
+//! live checks one by one from the CLI, [`DiagnosticsService`] runs cheap
+//! live probes from inside a running gateway: provider ping, channel
+//! credential checks (Telegram `getMe`, Slack `auth.test`, ...), a HEAD
+//! request against the public URL, free disk space in the workspace and
+//! SQLite writability. Probes are the [`VerifyCheck`]s `verify` uses plus a
+//! few local ones, run concurrently with a tight timeout. Results are
+//! cached for [`CACHE_TTL`] so repeated questions do not hit every API
+//! again; each failure carries a suggested fix.
+//!
+//! Served by `GET /api/monitor/diagnostics` and the `run_diagnostics` tool.
+
+use super::verify::{self, CheckReport, CheckStatus, VerifyCheck};
+use crate::config::Config;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Budget for a single probe.
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long a report is served from cache.
+pub const CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Free space below which the disk probe fails.
+const MIN_FREE_DISK_MB: u64 = 100;
+
+/// Outcome of one probe, with a suggested fix when it failed.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Finding {
+    pub target: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+impl From<CheckReport> for Finding {
+    fn from(report: CheckReport) -> Self {
+        let suggestion =
+            (report.status == CheckStatus::Fail).then(|| suggested_fix(&report.target));
+        Self {
+            target: report.target,
+            status: report.status,
+            detail: report.detail,
+            suggestion,
+        }
+    }
+}
+
+/// A diagnostics run.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DiagnosticsReport {
+    pub checked_at: DateTime<Utc>,
+    /// `true` when served from an earlier run.
+    pub cached: bool,
+    pub healthy: bool,
+    pub findings: Vec<Finding>,
+}
+
+impl DiagnosticsReport {
+    /// Plain-text rendering: the `verify` table followed by suggested fixes.
+    pub fn render(&self) -> String {
+        let reports: Vec<CheckReport> = self
+            .findings
+            .iter()
+            .map(|finding| CheckReport {
+                target: finding.target.clone(),
+                status: finding.status,
+                detail: finding.detail.clone(),
+            })
+            .collect();
+        let mut out = format!(
+            "Diagnostics checked at {}{}:\n{}",
+            self.checked_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            if self.cached { " (cached)" } else { "" },
+            verify::format_table(&reports)
+        );
+        let fixes: Vec<&Finding> = self
+            .findings
+            .iter()
+            .filter(|finding| finding.suggestion.is_some())
+            .collect();
+        if fixes.is_empty() {
+            out.push_str("\nEverything checked is healthy.");
+        } else {
+            out.push_str("\nSuggested fixes:");
+            for finding in fixes {
+                let _ = write!(
+                    out,
+                    "\n- {}: {}",
+                    finding.target,
+                    finding.suggestion.as_deref().unwrap_or_default()
+                );
+            }
+        }
+        out
+    }
+}
+
+fn suggested_fix(target: &str) -> String {
+    let fix = match target.split_once(':') {
+        Some(("provider", _)) => {
+            "Check the provider's API key, model name and account credit; `zeroclaw verify \
+             --provider` shows the full error."
+        }
+        Some(("channel", "telegram")) => {
+            "Telegram rejected the bot token: create a new one with @BotFather and update \
+             channels_config.telegram.bot_token."
+        }
+        Some(("channel", "slack")) => {
+            "Slack auth.test failed: reinstall the app or rotate the bot token (xoxb-…) in \
+             channels_config.slack."
+        }
+        Some(("channel", _)) => {
+            "The channel's credentials or network were rejected: re-check its section in \
+             channels_config and run `zeroclaw verify --channel <name>`."
+        }
+        Some(("url", _)) => {
+            "The public URL did not answer: make sure the tunnel is running and points at the \
+             gateway port."
+        }
+        Some(("disk", _)) => {
+            "Free space in the workspace volume: prune old sessions (`zeroclaw sessions trim`) \
+             or remove large files under .artifacts/."
+        }
+        Some(("sqlite", _)) => {
+            "The database is not writable: check file permissions and that no other process \
+             holds a long write lock; `zeroclaw doctor` reports workspace problems."
+        }
+        _ => "Run `zeroclaw doctor` and `zeroclaw verify` for details.",
+    };
+    fix.to_string()
+}
+
+/// Fails when the volume holding the workspace is almost full.
+pub struct DiskSpaceCheck {
+    pub workspace_dir: PathBuf,
+}
+
+#[async_trait]
+impl VerifyCheck for DiskSpaceCheck {
+    fn target(&self) -> String {
+        "disk:workspace".into()
+    }
+
+    async fn run(&self) -> Result<String> {
+        let workspace_dir = self.workspace_dir.clone();
+        let available =
+            tokio::task::spawn_blocking(move || super::disk_available_mb(&workspace_dir))
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("could not read free space (df unavailable)"))?;
+        if available < MIN_FREE_DISK_MB {
+            anyhow::bail!("only {available} MB free");
+        }
+        Ok(format!("{available} MB free"))
+    }
+}
+
+/// Takes and releases a write lock on a SQLite database.
+pub struct SqliteWritableCheck {
+    pub name: &'static str,
+    pub path: PathBuf,
+}
+
+#[async_trait]
+impl VerifyCheck for SqliteWritableCheck {
+    fn target(&self) -> String {
+        format!("sqlite:{}", self.name)
+    }
+
+    async fn run(&self) -> Result<String> {
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            if !path.exists() {
+                return Ok("not created yet".to_string());
+            }
+            let conn = rusqlite::Connection::open(&path)?;
+            conn.busy_timeout(Duration::from_secs(2))?;
+            conn.execute_batch("BEGIN IMMEDIATE; ROLLBACK;")?;
+            Ok("writable".to_string())
+        })
+        .await?
+    }
+}
+
+/// Sends a HEAD request to a URL that should reach this gateway.
+pub struct UrlHeadCheck {
+    pub url: String,
+}
+
+#[async_trait]
+impl VerifyCheck for UrlHeadCheck {
+    fn target(&self) -> String {
+        format!("url:{}", self.url)
+    }
+
+    async fn run(&self) -> Result<String> {
+        let response = crate::config::build_runtime_proxy_client("diagnostics")
+            .head(&self.url)
+            .timeout(PROBE_TIMEOUT)
+            .send()
+            .await?;
+        let status = response.status();
+        if status.is_server_error() {
+            anyhow::bail!("answered HTTP {status}");
+        }
+        Ok(format!("answered HTTP {status}"))
+    }
+}
+
+/// Probes for everything configured in `config`, plus reports for probes
+/// that could not even be built.
+async fn configured_checks(config: &Config) -> (Vec<Box<dyn VerifyCheck>>, Vec<CheckReport>) {
+    let mut checks: Vec<Box<dyn VerifyCheck>> = Vec::new();
+    let mut reports = Vec::new();
+
+    match verify::provider_check(config).await {
+        Ok(check) => checks.push(check),
+        Err(e) => reports.push(verify::failed(
+            format!(
+                "provider:{}",
+                crate::channels::resolved_default_provider(config)
+            ),
+            super::format_error_chain(&e),
+        )),
+    }
+    match crate::channels::health_check_channels(config).await {
+        Ok(channels) => checks.extend(channels.into_iter().map(|(name, channel)| {
+            Box::new(verify::ChannelCheck { name, channel }) as Box<dyn VerifyCheck>
+        })),
+        Err(e) => reports.push(verify::failed("channel:*", super::format_error_chain(&e))),
+    }
+
+    let tunnel = &config.tunnel;
+    let public_urls = [
+        tunnel.custom.as_ref().and_then(|c| c.health_url.clone()),
+        tunnel
+            .ngrok
+            .as_ref()
+            .and_then(|n| n.domain.as_ref())
+            .map(|domain| format!("https://{domain}/health")),
+    ];
+    checks.extend(
+        public_urls
+            .into_iter()
+            .flatten()
+            .map(|url| Box::new(UrlHeadCheck { url }) as Box<dyn VerifyCheck>),
+    );
+
+    let workspace_dir = &config.workspace_dir;
+    checks.push(Box::new(DiskSpaceCheck {
+        workspace_dir: workspace_dir.clone(),
+    }));
+    for (name, path) in [
+        (
+            "sessions",
+            crate::sessions::SqliteSessionStore::db_path(workspace_dir),
+        ),
+        ("cron", workspace_dir.join("cron").join("jobs.db")),
+        ("memory", workspace_dir.join("memory").join("brain.db")),
+    ] {
+        checks.push(Box::new(SqliteWritableCheck { name, path }));
+    }
+
+    (checks, reports)
+}
+
+/// Runs probes on demand and caches the latest report.
+pub struct DiagnosticsService {
+    ttl: Duration,
+    probe_timeout: Duration,
+    /// Held across a run, so concurrent callers share one run.
+    latest: tokio::sync::Mutex<Option<(Instant, DiagnosticsReport)>>,
+}
+
+impl DiagnosticsService {
+    pub fn new(ttl: Duration, probe_timeout: Duration) -> Self {
+        Self {
+            ttl,
+            probe_timeout,
+            latest: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Report for `config`: the cached one while fresh, unless `refresh`.
+    pub async fn report(&self, config: &Config, refresh: bool) -> DiagnosticsReport {
+        self.report_with(refresh, || configured_checks(config))
+            .await
+    }
+
+    async fn report_with<F, Fut>(&self, refresh: bool, checks: F) -> DiagnosticsReport
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = (Vec<Box<dyn VerifyCheck>>, Vec<CheckReport>)>,
+    {
+        let mut latest = self.latest.lock().await;
+        if let Some((at, report)) = latest.as_ref() {
+            if !refresh && at.elapsed() < self.ttl {
+                return DiagnosticsReport {
+                    cached: true,
+                    ..report.clone()
+                };
+            }
+        }
+
+        let (checks, mut reports) = checks().await;
+        let mut results = verify::run_checks_concurrently(&checks, self.probe_timeout).await;
+        results.append(&mut reports);
+        let findings: Vec<Finding> = results.into_iter().map(Finding::from).collect();
+        let report = DiagnosticsReport {
+            checked_at: Utc::now(),
+            cached: false,
+            healthy: findings.iter().all(|f| f.status != CheckStatus::Fail),
+            findings,
+        };
+        *latest = Some((Instant::now(), report.clone()));
+        report
+    }
+}
+
+/// The process-wide service behind the API route and the tool.
+pub fn shared() -> &'static DiagnosticsService {
+    static SERVICE: OnceLock<DiagnosticsService> = OnceLock::new();
+    SERVICE.get_or_init(|| DiagnosticsService::new(CACHE_TTL, PROBE_TIMEOUT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct MockProbe {
+        target: &'static str,
+        outcome: std::result::Result<&'static str, &'static str>,
+        delay: Duration,
+        runs: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl VerifyCheck for MockProbe {
+        fn target(&self) -> String {
+            self.target.into()
+        }
+
+        async fn run(&self) -> Result<String> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            self.outcome
+                .map(str::to_string)
+                .map_err(|e| anyhow::anyhow!(e))
+        }
+    }
+
+    fn probes(runs: &Arc<AtomicUsize>) -> (Vec<Box<dyn VerifyCheck>>, Vec<CheckReport>) {
+        let probe = |target, outcome, delay| {
+            Box::new(MockProbe {
+                target,
+                outcome,
+                delay,
+                runs: Arc::clone(runs),
+            }) as Box<dyn VerifyCheck>
+        };
+        (
+            vec![
+                probe(
+                    "provider:mock",
+                    Ok("model answered in 3ms"),
+                    Duration::from_millis(150),
+                ),
+                probe(
+                    "channel:telegram",
+                    Err("401 Unauthorized"),
+                    Duration::from_millis(150),
+                ),
+                probe("disk:workspace", Ok("5120 MB free"), Duration::ZERO),
+                probe(
+                    "url:https://example.test/health",
+                    Ok("never"),
+                    Duration::from_secs(60),
+                ),
+            ],
+            vec![verify::failed("channel:*", "failed to build channels")],
+        )
+    }
+
+    #[tokio::test]
+    async fn partial_failures_are_reported_with_fixes() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let service = DiagnosticsService::new(CACHE_TTL, Duration::from_millis(200));
+
+        let started = Instant::now();
+        let report = service.report_with(false, || async { probes(&runs) }).await;
+        // Concurrent: one run lasts about as long as the timed-out probe.
+        assert!(started.elapsed() < Duration::from_millis(450));
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+
+        assert!(!report.healthy);
+        assert!(!report.cached);
+        let status = |target: &str| {
+            report
+                .findings
+                .iter()
+                .find(|f| f.target == target)
+                .map(|f| (f.status, f.suggestion.is_some()))
+                .unwrap()
+        };
+        assert_eq!(status("provider:mock"), (CheckStatus::Pass, false));
+        assert_eq!(status("channel:telegram"), (CheckStatus::Fail, true));
+        assert_eq!(status("disk:workspace"), (CheckStatus::Pass, false));
+        assert_eq!(
+            status("url:https://example.test/health"),
+            (CheckStatus::Fail, true)
+        );
+        assert_eq!(status("channel:*"), (CheckStatus::Fail, true));
+
+        let text = report.render();
+        assert!(text.contains("401 Unauthorized"));
+        assert!(text.contains("@BotFather"));
+        assert!(text.contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn cached_report_is_reused_until_it_expires() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let healthy = |runs: &Arc<AtomicUsize>| {
+            let runs = Arc::clone(runs);
+            || async move {
+                let probe: Box<dyn VerifyCheck> = Box::new(MockProbe {
+                    target: "sqlite:sessions",
+                    outcome: Ok("writable"),
+                    delay: Duration::ZERO,
+                    runs,
+                });
+                (vec![probe], Vec::new())
+            }
+        };
+        let service = DiagnosticsService::new(Duration::from_millis(100), PROBE_TIMEOUT);
+
+        let first = service.report_with(false, healthy(&runs)).await;
+        assert!(first.healthy);
+        let second = service.report_with(false, healthy(&runs)).await;
+        assert!(second.cached);
+        assert_eq!(second.checked_at, first.checked_at);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(second.render().contains("Everything checked is healthy"));
+
+        let refreshed = service.report_with(true, healthy(&runs)).await;
+        assert!(!refreshed.cached);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let expired = service.report_with(false, healthy(&runs)).await;
+        assert!(!expired.cached);
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn sqlite_probe_reports_writable_and_missing_databases() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("probe.db");
+        let check = SqliteWritableCheck {
+            name: "probe",
+            path: path.clone(),
+        };
+        assert_eq!(check.run().await.unwrap(), "not created yet");
+
+        rusqlite::Connection::open(&path)
+            .unwrap()
+            .execute_batch("CREATE TABLE t (x INTEGER);")
+            .unwrap();
+        assert_eq!(check.run().await.unwrap(), "writable");
+    }
+}
@@ -1,3 +1,6 @@
+pub mod diagnostics;
+pub mod verify;
+
 use crate::config::Config;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
@@ -0,0 +1,470 @@
+//! `zeroclaw verify`: live, non-destructive checks that the configured
+//! provider, channels and integrations actually answer.
+//!
+//! Every check is a [`VerifyCheck`] wrapping the client the runtime already
+//! uses (provider, channel `health_check`, web search, Sheets transport), so
+//! tests can substitute mocks for the network.
+
+use crate::channels::traits::Channel;
+use crate::config::Config;
+use crate::memory::sheets::SheetsClient;
+use crate::providers::{self, Provider};
+use crate::tools::{Tool, WebSearchTool};
+use anyhow::Result;
+use async_trait::async_trait;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Budget for a single check.
+const CHECK_TIMEOUT_SECS: u64 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    Skipped,
+}
+
+impl CheckStatus {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Pass => "✅ pass",
+            Self::Fail => "❌ fail",
+            Self::Skipped => "➖ skipped",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckReport {
+    pub target: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+/// One live check. `run` returns a short detail on success.
+#[async_trait]
+pub trait VerifyCheck: Send + Sync {
+    fn target(&self) -> String;
+    async fn run(&self) -> Result<String>;
+}
+
+/// Sends a one-word prompt to the provider and reports latency and model.
+pub struct ProviderPing {
+    pub provider_name: String,
+    pub model: String,
+    pub provider: Arc<dyn Provider>,
+}
+
+#[async_trait]
+impl VerifyCheck for ProviderPing {
+    fn target(&self) -> String {
+        format!("provider:{}", self.provider_name)
+    }
+
+    async fn run(&self) -> Result<String> {
+        let started = Instant::now();
+        self.provider
+            .chat_with_system(
+                Some("Reply with the single word: pong"),
+                "ping",
+                &self.model,
+                0.0,
+            )
+            .await?;
+        Ok(format!(
+            "model {} answered in {}ms",
+            self.model,
+            started.elapsed().as_millis()
+        ))
+    }
+}
+
+/// Runs the channel's credential check (Telegram `getMe`, Slack
+/// `auth.test`, Discord `users/@me`, WhatsApp phone number info, ...).
+pub struct ChannelCheck {
+    pub name: &'static str,
+    pub channel: Arc<dyn Channel>,
+}
+
+#[async_trait]
+impl VerifyCheck for ChannelCheck {
+    fn target(&self) -> String {
+        format!("channel:{}", self.name.to_ascii_lowercase())
+    }
+
+    async fn run(&self) -> Result<String> {
+        if self.channel.health_check().await {
+            Ok("credentials accepted".into())
+        } else {
+            anyhow::bail!("health check failed (auth/config/network)")
+        }
+    }
+}
+
+/// Runs a one-result web search through the configured provider.
+pub struct WebSearchCheck {
+    pub tool: WebSearchTool,
+}
+
+#[async_trait]
+impl VerifyCheck for WebSearchCheck {
+    fn target(&self) -> String {
+        "web_search:brave".into()
+    }
+
+    async fn run(&self) -> Result<String> {
+        let result = self
+            .tool
+            .execute(serde_json::json!({ "query": "zeroclaw" }))
+            .await?;
+        if result.success {
+            Ok("search returned".into())
+        } else {
+            anyhow::bail!(result.error.unwrap_or_else(|| "search failed".into()))
+        }
+    }
+}
+
+/// Fetches the spreadsheet's metadata.
+pub struct SheetsCheck {
+    pub client: SheetsClient,
+}
+
+#[async_trait]
+impl VerifyCheck for SheetsCheck {
+    fn target(&self) -> String {
+        "google_sheets".into()
+    }
+
+    async fn run(&self) -> Result<String> {
+        let title = self.client.spreadsheet_title().await?;
+        Ok(format!("spreadsheet '{title}' readable"))
+    }
+}
+
+pub(super) fn skipped(target: impl Into<String>, reason: impl Into<String>) -> CheckReport {
+    CheckReport {
+        target: target.into(),
+        status: CheckStatus::Skipped,
+        detail: reason.into(),
+    }
+}
+
+pub(super) fn failed(target: impl Into<String>, reason: impl Into<String>) -> CheckReport {
+    CheckReport {
+        target: target.into(),
+        status: CheckStatus::Fail,
+        detail: reason.into(),
+    }
+}
+
+async fn run_check(check: &dyn VerifyCheck, timeout: Duration) -> CheckReport {
+    let target = check.target();
+    match tokio::time::timeout(timeout, check.run()).await {
+        Ok(Ok(detail)) => CheckReport {
+            target,
+            status: CheckStatus::Pass,
+            detail,
+        },
+        Ok(Err(e)) => failed(target, super::format_error_chain(&e)),
+        Err(_) => failed(target, format!("timed out after {}s", timeout.as_secs())),
+    }
+}
+
+/// Run `checks` one after another, each within `timeout`.
+pub async fn run_checks(checks: &[Box<dyn VerifyCheck>], timeout: Duration) -> Vec<CheckReport> {
+    let mut reports = Vec::with_capacity(checks.len());
+    for check in checks {
+        reports.push(run_check(check.as_ref(), timeout).await);
+    }
+    reports
+}
+
+/// Run `checks` at the same time, each within `timeout`; reports keep the
+/// order of `checks`.
+pub async fn run_checks_concurrently(
+    checks: &[Box<dyn VerifyCheck>],
+    timeout: Duration,
+) -> Vec<CheckReport> {
+    futures_util::future::join_all(
+        checks
+            .iter()
+            .map(|check| run_check(check.as_ref(), timeout)),
+    )
+    .await
+}
+
+/// Render reports as an aligned table.
+pub fn format_table(reports: &[CheckReport]) -> String {
+    let width = reports
+        .iter()
+        .map(|r| r.target.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("CHECK".len());
+    let mut out = format!("  {:<width$}  {:<10}  DETAIL\n", "CHECK", "STATUS");
+    for report in reports {
+        let _ = writeln!(
+            out,
+            "  {:<width$}  {:<10}  {}",
+            report.target,
+            report.status.label(),
+            super::truncate_for_display(&report.detail, 120)
+        );
+    }
+    out
+}
+
+pub(super) async fn provider_check(config: &Config) -> Result<Box<dyn VerifyCheck>> {
+    let provider_name = crate::channels::resolved_default_provider(config);
+    let model = crate::channels::resolved_default_model(config);
+    let options = providers::ProviderRuntimeOptions {
+        auth_profile_override: None,
+        provider_api_url: config.api_url.clone(),
+        zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
+        secrets_encrypt: config.secrets.encrypt,
+        reasoning_enabled: config.runtime.reasoning_enabled,
+        openrouter: config.openrouter.clone(),
+        response_cache: None,
+    };
+    // Probe the primary provider itself: no retries, no fallbacks.
+    let reliability = crate::config::ReliabilityConfig {
+        provider_retries: 0,
+        fallback_providers: Vec::new(),
+        model_fallbacks: std::collections::HashMap::new(),
+        ..config.reliability.clone()
+    };
+    let (api_key, api_url, name) = (
+        config.api_key.clone(),
+        config.api_url.clone(),
+        provider_name.clone(),
+    );
+    let provider = tokio::task::spawn_blocking(move || {
+        providers::create_resilient_provider_with_options(
+            &name,
+            api_key.as_deref(),
+            api_url.as_deref(),
+            &reliability,
+            &options,
+        )
+    })
+    .await??;
+    Ok(Box::new(ProviderPing {
+        provider_name,
+        model,
+        provider: Arc::from(provider),
+    }))
+}
+
+/// Run the checks selected by `channel` / `provider_only` and print a table.
+/// With neither filter everything configured is checked. Fails when any
+/// configured check fails.
+pub async fn run(config: &Config, channel: Option<&str>, provider_only: bool) -> Result<()> {
+    let check_all = channel.is_none() && !provider_only;
+    let mut checks: Vec<Box<dyn VerifyCheck>> = Vec::new();
+    let mut reports = Vec::new();
+
+    if check_all || provider_only {
+        match provider_check(config).await {
+            Ok(check) => checks.push(check),
+            Err(e) => reports.push(failed(
+                format!(
+                    "provider:{}",
+                    crate::channels::resolved_default_provider(config)
+                ),
+                super::format_error_chain(&e),
+            )),
+        }
+    }
+
+    if check_all || channel.is_some() {
+        let mut matched = false;
+        for (name, configured) in crate::channels::health_check_channels(config).await? {
+            if channel.is_some_and(|wanted| !wanted.eq_ignore_ascii_case(name)) {
+                continue;
+            }
+            matched = true;
+            checks.push(Box::new(ChannelCheck {
+                name,
+                channel: configured,
+            }));
+        }
+        if let Some(wanted) = channel {
+            if !matched && !wanted.eq_ignore_ascii_case("webhook") {
+                anyhow::bail!("channel '{wanted}' is not configured");
+            }
+        }
+        if config.channels_config.webhook.is_some()
+            && channel.is_none_or(|wanted| wanted.eq_ignore_ascii_case("webhook"))
+        {
+            reports.push(skipped(
+                "channel:webhook",
+                "inbound only; check `zeroclaw gateway` then GET /health",
+            ));
+        }
+    }
+
+    if check_all {
+        let search = &config.web_search;
+        if search.enabled && search.provider.trim().eq_ignore_ascii_case("brave") {
+            checks.push(Box::new(WebSearchCheck {
+                tool: WebSearchTool::new(
+                    search.provider.clone(),
+                    search.brave_api_key.clone(),
+                    1,
+                    search.timeout_secs,
+                ),
+            }));
+        } else {
+            reports.push(skipped("web_search:brave", "Brave search not configured"));
+        }
+
+        match &config.google_sheets {
+            Some(sheets) => match SheetsClient::from_config(sheets) {
+                Ok(client) => checks.push(Box::new(SheetsCheck { client })),
+                Err(e) => reports.push(failed("google_sheets", e.to_string())),
+            },
+            None => reports.push(skipped("google_sheets", "not configured")),
+        }
+    }
+
+    let mut results = run_checks(&checks, Duration::from_secs(CHECK_TIMEOUT_SECS)).await;
+    results.extend(reports);
+
+    println!("🔎 ZeroClaw Verify");
+    println!();
+    print!("{}", format_table(&results));
+
+    let failures = results
+        .iter()
+        .filter(|r| r.status == CheckStatus::Fail)
+        .count();
+    println!();
+    if failures > 0 {
+        anyhow::bail!("{failures} check(s) failed");
+    }
+    println!("  All configured checks passed.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::sheets::{SheetsError, SheetsRequest, SheetsTransport};
+    use serde_json::{json, Value};
+
+    struct FixedProvider {
+        reply: Option<&'static str>,
+    }
+
+    #[async_trait]
+    impl Provider for FixedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            assert_eq!(message, "ping");
+            self.reply
+                .map(str::to_string)
+                .ok_or_else(|| anyhow::anyhow!("401 invalid api key"))
+        }
+    }
+
+    struct FixedSheets(Result<Value, u16>);
+
+    #[async_trait]
+    impl SheetsTransport for FixedSheets {
+        async fn send(&self, request: SheetsRequest) -> Result<Value, SheetsError> {
+            assert!(request.path.is_empty());
+            self.0.clone().map_err(|status| SheetsError::Api {
+                status,
+                message: "The caller does not have permission".into(),
+            })
+        }
+    }
+
+    struct HangingCheck;
+
+    #[async_trait]
+    impl VerifyCheck for HangingCheck {
+        fn target(&self) -> String {
+            "channel:slow".into()
+        }
+
+        async fn run(&self) -> Result<String> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok("never".into())
+        }
+    }
+
+    fn ping(reply: Option<&'static str>) -> Box<dyn VerifyCheck> {
+        Box::new(ProviderPing {
+            provider_name: "mock".into(),
+            model: "mock-model".into(),
+            provider: Arc::new(FixedProvider { reply }),
+        })
+    }
+
+    fn sheets(response: Result<Value, u16>) -> Box<dyn VerifyCheck> {
+        Box::new(SheetsCheck {
+            client: SheetsClient::new(Arc::new(FixedSheets(response)), 60),
+        })
+    }
+
+    #[tokio::test]
+    async fn run_checks_reports_pass_fail_and_timeout() {
+        let checks = vec![
+            ping(Some("pong")),
+            ping(None),
+            sheets(Ok(json!({"properties": {"title": "Agent log"}}))),
+            sheets(Err(403)),
+            Box::new(HangingCheck) as Box<dyn VerifyCheck>,
+        ];
+
+        let reports = run_checks(&checks, Duration::from_millis(50)).await;
+
+        let statuses: Vec<CheckStatus> = reports.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                CheckStatus::Pass,
+                CheckStatus::Fail,
+                CheckStatus::Pass,
+                CheckStatus::Fail,
+                CheckStatus::Fail,
+            ]
+        );
+        assert_eq!(reports[0].target, "provider:mock");
+        assert!(reports[0]
+            .detail
+            .starts_with("model mock-model answered in"));
+        assert!(reports[1].detail.contains("invalid api key"));
+        assert_eq!(reports[2].detail, "spreadsheet 'Agent log' readable");
+        assert!(reports[3].detail.contains("403"));
+        assert!(reports[4].detail.contains("timed out"));
+    }
+
+    #[test]
+    fn format_table_aligns_columns() {
+        let table = format_table(&[
+            CheckReport {
+                target: "provider:openrouter".into(),
+                status: CheckStatus::Pass,
+                detail: "ok".into(),
+            },
+            skipped("google_sheets", "not configured"),
+        ]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].trim_start().starts_with("CHECK"));
+        assert!(lines[2].contains("➖ skipped"));
+        let detail_column = |line: &str| line.find("  ok").or_else(|| line.find("  not"));
+        assert_eq!(detail_column(lines[1]), detail_column(lines[2]));
+    }
+}
@@ -46,6 +46,11 @@ fn require_auth(
 
 // ── Query parameters ─────────────────────────────────────────────
 
+#[derive(Deserialize)]
+pub struct AttachmentUploadQuery {
+    pub filename: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct MemoryQuery {
     pub query: Option<String>,
@@ -59,6 +64,61 @@ pub struct MemoryStoreBody {
     pub category: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct SessionSearchQuery {
+    pub q: String,
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct SessionExportQuery {
+    pub format: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ReloadQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Deserialize)]
+pub struct SessionLinkBody {
+    pub session_key: String,
+    pub canonical_key: String,
+}
+
+#[derive(Deserialize)]
+pub struct DiagnosticsQuery {
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+#[derive(Deserialize)]
+pub struct SessionSummariesQuery {
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct SessionMessagesQuery {
+    pub limit: Option<usize>,
+    /// `html` adds a sanitized `content_html` to every message.
+    pub render: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AuditLogQuery {
+    pub limit: Option<usize>,
+    #[serde(rename = "type")]
+    pub event_type: Option<String>,
+    pub session: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct TurnsQuery {
+    pub limit: Option<usize>,
+    pub session: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct CronAddBody {
     pub name: Option<String>,
@@ -66,6 +126,17 @@ pub struct CronAddBody {
     pub command: String,
 }
 
+#[derive(Deserialize)]
+pub struct ChannelSendBody {
+    pub channel: String,
+    /// Recipient in the channel's delivery format (`chat_id[:thread_id]`,
+    /// `channel[:thread_ts]`, …). Optional on Slack and Mattermost when the
+    /// bot is restricted to one channel.
+    #[serde(default)]
+    pub to: Option<String>,
+    pub text: String,
+}
+
 // ── Handlers ────────────────────────────────────────────────────
 
 /// GET /api/status — system status overview
@@ -81,16 +152,31 @@ pub async fn handle_api_status(
     let health = crate::health::snapshot();
 
     let mut channels = serde_json::Map::new();
+    let mut configured_adapters = 0;
 
     for (channel, present) in config.channels_config.channels() {
+        configured_adapters += usize::from(present);
         channels.insert(channel.name().to_string(), serde_json::Value::Bool(present));
     }
+    let running_adapters = health.running_channels();
 
     let body = serde_json::json!({
         "provider": config.default_provider,
         "model": state.model,
         "temperature": state.temperature,
-        "uptime_seconds": health.uptime_seconds,
+        "started_at": state.started_at.to_rfc3339(),
+        "uptime_seconds": crate::health::uptime_seconds(state.started_at, chrono::Utc::now()),
+        "agent_loop_alive": crate::health::agent_loop_alive(),
+        "agent_last_activity": crate::health::agent_last_activity()
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+            .map(|ts| ts.to_rfc3339()),
+        "adapters": {
+            "configured": configured_adapters,
+            "running": running_adapters.len(),
+            "running_names": running_adapters,
+        },
+        "config_path": config.config_path,
+        "workspace_dir": config.workspace_dir,
         "gateway_port": config.gateway.port,
         "locale": "en",
         "memory_backend": state.mem.name(),
@@ -181,6 +267,66 @@ pub async fn handle_api_config_put(
     Json(serde_json::json!({"status": "ok"})).into_response()
 }
 
+/// PUT /api/control/reload — validate the config file on disk and apply it
+///
+/// Responds 422 with the validation issues when the file does not load;
+/// otherwise with the changes (secrets masked) and whether the reload task
+/// has `applied` them or they are still `queued`. `?dry_run=true` stops
+/// after validation.
+pub async fn handle_api_control_reload(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<ReloadQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let current = state.config.lock().clone();
+    let candidate = match super::reload::load_candidate(&current).await {
+        Ok(candidate) => candidate,
+        Err(issues) => {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(serde_json::json!({"status": "invalid", "issues": issues})),
+            )
+                .into_response();
+        }
+    };
+    let changes = super::reload::diff_configs(&current, &candidate.config);
+
+    if params.dry_run {
+        return Json(serde_json::json!({
+            "status": "valid",
+            "dry_run": true,
+            "changes": changes,
+            "unknown_keys": candidate.unknown_keys,
+        }))
+        .into_response();
+    }
+
+    let Some(reload) = &state.reload else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({"error": "Config reload is not available"})),
+        )
+            .into_response();
+    };
+    match Box::pin(reload.apply(candidate.config)).await {
+        Ok(outcome) => Json(serde_json::json!({
+            "status": outcome.as_str(),
+            "changes": changes,
+            "unknown_keys": candidate.unknown_keys,
+        }))
+        .into_response(),
+        Err(e) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({"error": format!("Config reload failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
 /// GET /api/tools — list registered tool specs
 pub async fn handle_api_tools(
     State(state): State<AppState>,
@@ -297,6 +443,97 @@ pub async fn handle_api_cron_delete(
     }
 }
 
+/// GET /api/cron/jobs — cron jobs with their run state (last result, last
+/// error, missed runs, next run)
+pub async fn handle_api_cron_jobs(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let config = state.config.lock().clone();
+    match crate::cron::list_jobs(&config) {
+        Ok(jobs) => {
+            let jobs_json: Vec<serde_json::Value> = jobs
+                .iter()
+                .map(|job| {
+                    serde_json::json!({
+                        "id": job.id,
+                        "name": job.name,
+                        "job_type": <crate::cron::JobType as Into<&str>>::into(job.job_type.clone()),
+                        "schedule": job.schedule,
+                        "command": job.command,
+                        "prompt": job.prompt,
+                        "enabled": job.enabled,
+                        "catch_up": job.catch_up,
+                        "next_run": job.next_run.to_rfc3339(),
+                        "last_run": job.last_run.map(|t| t.to_rfc3339()),
+                        "last_status": job.last_status,
+                        "last_error": job.last_error,
+                        "missed_runs": job.missed_runs,
+                    })
+                })
+                .collect();
+            Json(serde_json::json!({"jobs": jobs_json})).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Failed to list cron jobs: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// POST /api/cron/jobs/:id/run — run a job now, outside its schedule
+///
+/// Runs once under the configured security policy and records the run like
+/// `cron_run` does; the job's schedule is left unchanged.
+pub async fn handle_api_cron_run(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let config = state.config.lock().clone();
+    let Ok(job) = crate::cron::get_job(&config, &id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("Cron job '{id}' not found")})),
+        )
+            .into_response();
+    };
+
+    let started_at = chrono::Utc::now();
+    let (success, output) = Box::pin(crate::cron::scheduler::execute_job_now(&config, &job)).await;
+    let finished_at = chrono::Utc::now();
+    let duration_ms = (finished_at - started_at).num_milliseconds();
+    let status = if success { "ok" } else { "error" };
+
+    let _ = crate::cron::record_run(
+        &config,
+        &job.id,
+        started_at,
+        finished_at,
+        status,
+        Some(&output),
+        duration_ms,
+    );
+    let _ = crate::cron::record_last_run(&config, &job.id, finished_at, success, &output);
+
+    Json(serde_json::json!({
+        "job_id": job.id,
+        "status": status,
+        "duration_ms": duration_ms,
+        "output": output,
+    }))
+    .into_response()
+}
+
 /// GET /api/integrations — list all integrations with status
 pub async fn handle_api_integrations(
     State(state): State<AppState>,
@@ -361,6 +598,24 @@ pub async fn handle_api_doctor(
     .into_response()
 }
 
+/// GET /api/monitor/diagnostics — run live health probes (cached for a
+/// minute unless `refresh=true`)
+pub async fn handle_api_monitor_diagnostics(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<DiagnosticsQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let config = state.config.lock().clone();
+    let report = crate::doctor::diagnostics::shared()
+        .report(&config, params.refresh)
+        .await;
+    Json(report).into_response()
+}
+
 /// GET /api/memory — list or search memory entries
 pub async fn handle_api_memory_list(
     State(state): State<AppState>,
@@ -394,65 +649,691 @@ pub async fn handle_api_memory_list(
             Ok(entries) => Json(serde_json::json!({"entries": entries})).into_response(),
             Err(e) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": format!("Memory list failed: {e}")})),
+                Json(serde_json::json!({"error": format!("Memory list failed: {e}")})),
+            )
+                .into_response(),
+        }
+    }
+}
+
+/// POST /api/memory — store a memory entry
+pub async fn handle_api_memory_store(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<MemoryStoreBody>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let category = body
+        .category
+        .as_deref()
+        .map(|cat| match cat {
+            "core" => crate::memory::MemoryCategory::Core,
+            "daily" => crate::memory::MemoryCategory::Daily,
+            "conversation" => crate::memory::MemoryCategory::Conversation,
+            other => crate::memory::MemoryCategory::Custom(other.to_string()),
+        })
+        .unwrap_or(crate::memory::MemoryCategory::Core);
+
+    match state
+        .mem
+        .store(&body.key, &body.content, category, None)
+        .await
+    {
+        Ok(()) => Json(serde_json::json!({"status": "ok"})).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Memory store failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// DELETE /api/memory/:key — delete a memory entry
+pub async fn handle_api_memory_delete(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(key): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    match state.mem.forget(&key).await {
+        Ok(deleted) => {
+            Json(serde_json::json!({"status": "ok", "deleted": deleted})).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Memory forget failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// Open the channel sessions store read-only; `None` when nothing has been recorded yet.
+fn open_session_store(
+    state: &AppState,
+) -> anyhow::Result<Option<std::sync::Arc<crate::sessions::SqliteSessionStore>>> {
+    let workspace_dir = state.config.lock().workspace_dir.clone();
+    if !crate::sessions::SqliteSessionStore::db_path(&workspace_dir).exists() {
+        return Ok(None);
+    }
+    crate::sessions::SqliteSessionStore::open_read_only(&workspace_dir)
+        .map(|store| Some(std::sync::Arc::new(store)))
+}
+
+/// GET /api/sessions — recorded channel sessions, most recent first
+pub async fn handle_api_sessions_list(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let store = match open_session_store(&state) {
+        Ok(Some(store)) => store,
+        Ok(None) => return Json(serde_json::json!({"sessions": []})).into_response(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Failed to open sessions: {e}")})),
+            )
+                .into_response()
+        }
+    };
+
+    match crate::sessions::blocking(store, |store| store.list_sessions()).await {
+        Ok(sessions) => Json(serde_json::json!({"sessions": sessions})).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Session list failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/sessions/search?q=... — full-text search over session messages
+pub async fn handle_api_sessions_search(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<SessionSearchQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let store = match open_session_store(&state) {
+        Ok(Some(store)) => store,
+        Ok(None) => return Json(serde_json::json!({"results": []})).into_response(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Failed to open sessions: {e}")})),
+            )
+                .into_response()
+        }
+    };
+
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let query = params.q;
+    match crate::sessions::blocking(store, move |store| store.search_messages(&query, limit)).await
+    {
+        Ok(results) => Json(serde_json::json!({"results": results})).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Session search failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/sessions/:session_key/export?format=markdown|json — the whole
+/// session, streamed as it is rendered
+pub async fn handle_api_session_export(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(session_key): Path<String>,
+    Query(params): Query<SessionExportQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let format = match params
+        .format
+        .as_deref()
+        .unwrap_or("markdown")
+        .parse::<crate::sessions::export::ExportFormat>()
+    {
+        Ok(format) => format,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+                .into_response()
+        }
+    };
+
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("No session found for key: {session_key}")})),
+        )
+            .into_response()
+    };
+    let store = match open_session_store(&state) {
+        Ok(Some(store)) => store,
+        Ok(None) => return not_found(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Failed to open sessions: {e}")})),
+            )
+                .into_response()
+        }
+    };
+    let key = session_key.clone();
+    match crate::sessions::blocking(std::sync::Arc::clone(&store), move |store| {
+        store.session_exists(&key)
+    })
+    .await
+    {
+        Ok(true) => {}
+        Ok(false) => return not_found(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Session lookup failed: {e}")})),
+            )
+                .into_response()
+        }
+    }
+
+    let filename = format!(
+        "{}.{}",
+        session_key
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_') {
+                c
+            } else {
+                '_'
+            })
+            .collect::<String>(),
+        format.extension()
+    );
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<String, std::io::Error>>(16);
+    let key = session_key.clone();
+    tokio::task::spawn_blocking(move || {
+        let exported = store.export_session(&key, format, |chunk| {
+            tx.blocking_send(Ok(chunk.to_string()))
+                .map_err(|_| anyhow::anyhow!("export client disconnected"))
+        });
+        if let Err(e) = exported {
+            tracing::warn!("session export for {key} stopped: {e}");
+            let _ = tx.blocking_send(Err(std::io::Error::other(e.to_string())));
+        }
+    });
+
+    (
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        axum::body::Body::from_stream(tokio_stream::wrappers::ReceiverStream::new(rx)),
+    )
+        .into_response()
+}
+
+/// GET /api/sessions/:session_key/summaries?limit=N — every summary
+/// compaction recorded for the session, newest first
+pub async fn handle_api_session_summaries(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(session_key): Path<String>,
+    Query(params): Query<SessionSummariesQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("No session found for key: {session_key}")})),
+        )
+            .into_response()
+    };
+    let store = match open_session_store(&state) {
+        Ok(Some(store)) => store,
+        Ok(None) => return not_found(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Failed to open sessions: {e}")})),
+            )
+                .into_response()
+        }
+    };
+    let key = session_key.clone();
+    match crate::sessions::blocking(std::sync::Arc::clone(&store), move |store| {
+        store.session_exists(&key)
+    })
+    .await
+    {
+        Ok(true) => {}
+        Ok(false) => return not_found(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Session lookup failed: {e}")})),
+            )
+                .into_response()
+        }
+    }
+
+    let limit = params.limit.unwrap_or(50).clamp(1, 500);
+    let key = session_key.clone();
+    match crate::sessions::blocking(store, move |store| store.summary_history(&key, Some(limit)))
+        .await
+    {
+        Ok(summaries) => Json(serde_json::json!({
+            "session_key": session_key,
+            "summaries": summaries,
+        }))
+        .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Summary lookup failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/sessions/:session_key/messages?limit=N&render=html — recent
+/// history, oldest first. `render=html` adds sanitized HTML beside the raw
+/// content for browser clients.
+pub async fn handle_api_session_messages(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(session_key): Path<String>,
+    Query(params): Query<SessionMessagesQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let render_html = match params.render.as_deref() {
+        None | Some("raw") => false,
+        Some("html") => true,
+        Some(other) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({
+                    "error": format!("Unknown render '{other}' (expected html or raw)")
+                })),
+            )
+                .into_response()
+        }
+    };
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("No session found for key: {session_key}")})),
+        )
+            .into_response()
+    };
+    let store = match open_session_store(&state) {
+        Ok(Some(store)) => store,
+        Ok(None) => return not_found(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Failed to open sessions: {e}")})),
+            )
+                .into_response()
+        }
+    };
+    let key = session_key.clone();
+    match crate::sessions::blocking(std::sync::Arc::clone(&store), move |store| {
+        store.session_exists(&key)
+    })
+    .await
+    {
+        Ok(true) => {}
+        Ok(false) => return not_found(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Session lookup failed: {e}")})),
+            )
+                .into_response()
+        }
+    }
+
+    let limit = params.limit.unwrap_or(50).clamp(1, 500);
+    let key = session_key.clone();
+    let history =
+        match crate::sessions::blocking(store, move |store| store.load_history(&key, Some(limit)))
+            .await
+        {
+            Ok(history) => history,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({"error": format!("History lookup failed: {e}")})),
+                )
+                    .into_response()
+            }
+        };
+    let messages: Vec<serde_json::Value> = history
+        .into_iter()
+        .map(|message| {
+            let mut entry = serde_json::json!({
+                "role": message.role().as_str(),
+                "content": message.content,
+                "created_at": message.created_at,
+            });
+            if render_html {
+                super::sanitize::add_content_html(&mut entry);
+            }
+            entry
+        })
+        .collect();
+    Json(serde_json::json!({
+        "session_key": session_key,
+        "messages": messages,
+    }))
+    .into_response()
+}
+
+/// PATCH /api/sessions/:session_key/settings — change per-session overrides.
+/// Fields left out keep their value; `null` clears one.
+pub async fn handle_api_session_settings_patch(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(session_key): Path<String>,
+    Json(patch): Json<crate::sessions::SessionSettingsPatch>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let config = state.config.lock().clone();
+    let store = match crate::sessions::store_for(&config.workspace_dir) {
+        Some(store) => Ok(store),
+        None => crate::sessions::SqliteSessionStore::open(&config.workspace_dir)
+            .map(std::sync::Arc::new),
+    };
+    let store = match store {
+        Ok(store) => store,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Failed to open sessions: {e}")})),
+            )
+                .into_response()
+        }
+    };
+
+    let key = session_key.clone();
+    let mut settings =
+        match crate::sessions::blocking(std::sync::Arc::clone(&store), move |store| {
+            store.settings(&key)
+        })
+        .await
+        {
+            Ok(settings) => settings,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({"error": format!("Failed to load settings: {e}")})),
+                )
+                    .into_response()
+            }
+        };
+    settings.apply(patch);
+    if let Err(e) = settings.validate(&config).await {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": format!("{e:#}")})),
+        )
+            .into_response();
+    }
+
+    let key = session_key.clone();
+    match crate::sessions::blocking(store, move |store| {
+        store.set_settings(&key, &settings)?;
+        store.settings(&key)
+    })
+    .await
+    {
+        Ok(saved) => Json(serde_json::json!({
+            "status": "ok",
+            "session_key": session_key,
+            "settings": saved,
+        }))
+        .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Failed to save settings: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// Session store for writes: the channel runtime's when it is running, else
+/// opened read-write; `None` when nothing has been recorded yet.
+fn writable_session_store(
+    state: &AppState,
+) -> anyhow::Result<Option<std::sync::Arc<crate::sessions::SqliteSessionStore>>> {
+    let workspace_dir = state.config.lock().workspace_dir.clone();
+    if let Some(store) = crate::sessions::store_for(&workspace_dir) {
+        return Ok(Some(store));
+    }
+    if !crate::sessions::SqliteSessionStore::db_path(&workspace_dir).exists() {
+        return Ok(None);
+    }
+    crate::sessions::SqliteSessionStore::open(&workspace_dir)
+        .map(|store| Some(std::sync::Arc::new(store)))
+}
+
+/// DELETE /api/sessions/:session_key — remove a session with its messages,
+/// pins, settings and summaries
+pub async fn handle_api_session_delete(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(session_key): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("No session found for key: {session_key}")})),
+        )
+            .into_response()
+    };
+    let store = match writable_session_store(&state) {
+        Ok(Some(store)) => store,
+        Ok(None) => return not_found(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Failed to open sessions: {e}")})),
+            )
+                .into_response()
+        }
+    };
+
+    let key = session_key.clone();
+    match crate::sessions::blocking(store, move |store| store.delete_session(&key)).await {
+        Ok(true) => {
+            crate::sessions::redact::note_forgotten(
+                &session_key,
+                crate::sessions::redact::Forgotten::Session,
+            );
+            Json(serde_json::json!({"status": "ok", "session_key": session_key})).into_response()
+        }
+        Ok(false) => not_found(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Session delete failed: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// POST /api/sessions/link — make `session_key` continue the conversation of
+/// `canonical_key`, so both channels share one history
+pub async fn handle_api_sessions_link(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<SessionLinkBody>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let workspace_dir = state.config.lock().workspace_dir.clone();
+    let store = match crate::sessions::store_for(&workspace_dir) {
+        Some(store) => Ok(store),
+        None => crate::sessions::SqliteSessionStore::open(&workspace_dir).map(std::sync::Arc::new),
+    };
+    let store = match store {
+        Ok(store) => store,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Failed to open sessions: {e}")})),
             )
-                .into_response(),
+                .into_response()
         }
+    };
+
+    let (key, canonical) = (body.session_key.clone(), body.canonical_key);
+    match crate::sessions::blocking(store, move |store| store.link_sessions(&key, &canonical)).await
+    {
+        Ok(canonical_key) => Json(serde_json::json!({
+            "status": "ok",
+            "session_key": body.session_key,
+            "canonical_key": canonical_key,
+        }))
+        .into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": format!("{e:#}")})),
+        )
+            .into_response(),
     }
 }
 
-/// POST /api/memory — store a memory entry
-pub async fn handle_api_memory_store(
+/// DELETE /api/sessions/:session_key/link — undo the links of a session:
+/// its own link when it is an alias, all links to it when it is canonical
+pub async fn handle_api_session_unlink(
     State(state): State<AppState>,
     headers: HeaderMap,
-    Json(body): Json<MemoryStoreBody>,
+    Path(session_key): Path<String>,
 ) -> impl IntoResponse {
     if let Err(e) = require_auth(&state, &headers) {
         return e.into_response();
     }
 
-    let category = body
-        .category
-        .as_deref()
-        .map(|cat| match cat {
-            "core" => crate::memory::MemoryCategory::Core,
-            "daily" => crate::memory::MemoryCategory::Daily,
-            "conversation" => crate::memory::MemoryCategory::Conversation,
-            other => crate::memory::MemoryCategory::Custom(other.to_string()),
-        })
-        .unwrap_or(crate::memory::MemoryCategory::Core);
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(
+                serde_json::json!({"error": format!("No links found for session: {session_key}")}),
+            ),
+        )
+            .into_response()
+    };
+    let store = match writable_session_store(&state) {
+        Ok(Some(store)) => store,
+        Ok(None) => return not_found(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Failed to open sessions: {e}")})),
+            )
+                .into_response()
+        }
+    };
 
-    match state
-        .mem
-        .store(&body.key, &body.content, category, None)
-        .await
-    {
-        Ok(()) => Json(serde_json::json!({"status": "ok"})).into_response(),
+    let key = session_key.clone();
+    match crate::sessions::blocking(store, move |store| store.unlink_session(&key)).await {
+        Ok(0) => not_found(),
+        Ok(removed) => Json(serde_json::json!({
+            "status": "ok",
+            "session_key": session_key,
+            "removed": removed,
+        }))
+        .into_response(),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": format!("Memory store failed: {e}")})),
+            Json(serde_json::json!({"error": format!("Session unlink failed: {e}")})),
         )
             .into_response(),
     }
 }
 
-/// DELETE /api/memory/:key — delete a memory entry
-pub async fn handle_api_memory_delete(
+/// POST /api/messages/:id/redact — replace a stored message with
+/// `[redacted]`, keeping its place in the conversation
+pub async fn handle_api_message_redact(
     State(state): State<AppState>,
     headers: HeaderMap,
-    Path(key): Path<String>,
+    Path(id): Path<i64>,
 ) -> impl IntoResponse {
     if let Err(e) = require_auth(&state, &headers) {
         return e.into_response();
     }
 
-    match state.mem.forget(&key).await {
-        Ok(deleted) => {
-            Json(serde_json::json!({"status": "ok", "deleted": deleted})).into_response()
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("No message found with id: {id}")})),
+        )
+            .into_response()
+    };
+    let store = match writable_session_store(&state) {
+        Ok(Some(store)) => store,
+        Ok(None) => return not_found(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Failed to open sessions: {e}")})),
+            )
+                .into_response()
         }
+    };
+
+    match crate::sessions::blocking(store, move |store| store.redact_message(id)).await {
+        Ok(Some(redacted)) => {
+            crate::sessions::redact::note_forgotten(
+                &redacted.session_key,
+                crate::sessions::redact::Forgotten::Messages(vec![redacted.content]),
+            );
+            Json(serde_json::json!({
+                "status": "ok",
+                "id": redacted.id,
+                "session_key": redacted.session_key,
+                "role": redacted.role,
+            }))
+            .into_response()
+        }
+        Ok(None) => not_found(),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({"error": format!("Memory forget failed: {e}")})),
+            Json(serde_json::json!({"error": format!("Message redaction failed: {e}")})),
         )
             .into_response(),
     }
@@ -515,11 +1396,304 @@ pub async fn handle_api_health(
     }
 
     let snapshot = crate::health::snapshot();
-    Json(serde_json::json!({"health": snapshot})).into_response()
+    Json(serde_json::json!({
+        "health": snapshot,
+        "rate_limited": state.rate_limiter.rejection_counts(),
+    }))
+    .into_response()
+}
+
+/// GET /api/ui/config — what the dashboard needs before pairing.
+///
+/// Public like `/health`: channel names and flags only, never tokens or
+/// other config values.
+pub async fn handle_api_ui_config(State(state): State<AppState>) -> impl IntoResponse {
+    let config = state.config.lock();
+    let channels: Vec<serde_json::Value> = config
+        .channels_config
+        .channels()
+        .into_iter()
+        .map(|(channel, configured)| {
+            serde_json::json!({ "name": channel.name(), "configured": configured })
+        })
+        .collect();
+    let custom_dashboard = config
+        .web
+        .dashboard_dir
+        .as_deref()
+        .is_some_and(|dir| !dir.trim().is_empty());
+
+    Json(serde_json::json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "auth_required": state.pairing.require_pairing(),
+        "paired": state.pairing.is_paired(),
+        "channels": channels,
+        "dashboard": if custom_dashboard { "directory" } else { "embedded" },
+    }))
+}
+
+/// GET /api/channels/health — per-channel listener health and queued replies
+pub async fn handle_api_channels_health(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let workspace_dir = state.config.lock().workspace_dir.clone();
+    let queued = match crate::channels::outbound_queue::queue_for(&workspace_dir) {
+        Some(queue) => {
+            crate::channels::outbound_queue::blocking(queue, |queue| queue.counts_by_channel())
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Failed to read outbound queue: {e}");
+                    std::collections::BTreeMap::new()
+                })
+        }
+        None => std::collections::BTreeMap::new(),
+    };
+
+    let mut channels = serde_json::Map::new();
+    for (component, health) in crate::health::snapshot().components {
+        if let Some(name) = component.strip_prefix("channel:") {
+            channels.insert(
+                name.to_string(),
+                serde_json::json!({
+                    "status": health.status,
+                    "last_ok": health.last_ok,
+                    "last_error": health.last_error,
+                    "restart_count": health.restart_count,
+                    "queued_outbound": queued.get(name).copied().unwrap_or(0),
+                }),
+            );
+        }
+    }
+    // Channels that are down may still have replies waiting.
+    for (name, count) in &queued {
+        channels
+            .entry(name.clone())
+            .or_insert_with(|| serde_json::json!({"status": "unknown", "queued_outbound": count}));
+    }
+
+    Json(serde_json::json!({
+        "channels": channels,
+        "queued_outbound_total": queued.values().sum::<u64>(),
+    }))
+    .into_response()
+}
+
+/// POST /api/channels/send — deliver operator text to a channel as the
+/// assistant, without an agent turn. The message is recorded in the
+/// recipient's session so the agent knows it was sent.
+pub async fn handle_api_channels_send(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<ChannelSendBody>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let bad_request = |error: String| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": error })),
+        )
+            .into_response()
+    };
+    let config = state.config.lock().clone();
+    let channel = body.channel.trim().to_ascii_lowercase();
+    if !crate::cron::scheduler::ANNOUNCE_CHANNELS.contains(&channel.as_str()) {
+        return bad_request(format!(
+            "Unsupported channel '{}'; expected one of: {}",
+            body.channel,
+            crate::cron::scheduler::ANNOUNCE_CHANNELS.join(", ")
+        ));
+    }
+    if !crate::cron::scheduler::announce_channel_configured(&config, &channel) {
+        return bad_request(format!("Channel '{channel}' is not configured"));
+    }
+    if body.text.trim().is_empty() {
+        return bad_request("'text' must not be empty".into());
+    }
+    let Some(to) = body
+        .to
+        .map(|to| to.trim().to_string())
+        .filter(|to| !to.is_empty())
+        .or_else(|| crate::cron::scheduler::default_announce_target(&config, &channel))
+    else {
+        return bad_request(format!("'to' is required for {channel}"));
+    };
+    if channel == "slack" {
+        if let Err(e) = crate::channels::slack::parse_delivery_target(&to) {
+            return bad_request(e.to_string());
+        }
+    }
+
+    let session_key = crate::channels::delivery_session_key(&channel, &to);
+    if let Err(e) = Box::pin(crate::cron::scheduler::deliver_announcement(
+        &config,
+        &channel,
+        &to,
+        &body.text,
+        crate::channels::quiet_hours::MessageOrigin::Operator,
+    ))
+    .await
+    {
+        return (
+            StatusCode::BAD_GATEWAY,
+            Json(serde_json::json!({"error": format!("Delivery failed: {e}")})),
+        )
+            .into_response();
+    }
+
+    let recorded = record_operator_message(&config.workspace_dir, &session_key, &body.text)
+        .await
+        .map_err(|e| tracing::warn!("Failed to record operator message in {session_key}: {e}"))
+        .is_ok();
+    Json(serde_json::json!({
+        "status": "sent",
+        "channel": channel,
+        "to": to,
+        "session_key": session_key,
+        "recorded": recorded,
+    }))
+    .into_response()
+}
+
+/// Append `text` to `session_key` as an assistant turn marked as sent by
+/// the operator.
+async fn record_operator_message(
+    workspace_dir: &std::path::Path,
+    session_key: &str,
+    text: &str,
+) -> anyhow::Result<()> {
+    let metadata =
+        std::collections::HashMap::from([("source".to_string(), "operator".to_string())]);
+    let (key, text) = (session_key.to_string(), text.to_string());
+    crate::sessions::blocking_writable(workspace_dir, move |store| {
+        store.append_message_with_metadata(&key, "assistant", &text, None, &metadata)
+    })
+    .await?;
+    Ok(())
+}
+
+/// GET /api/monitor/metrics — per-tool call metrics, gateway queue state,
+/// session compaction and turn totals
+pub async fn handle_api_monitor_metrics(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    Json(serde_json::json!({
+        "uptime_seconds": crate::health::snapshot().uptime_seconds,
+        "queue": {
+            "depth": state.inbound_queue.depth(),
+            "max_depth": state.inbound_queue.max_depth(),
+            "accepted": state.inbound_queue.accepted(),
+            "rejected": state.inbound_queue.rejected(),
+        },
+        "tools": crate::tools::metrics::snapshot_json(),
+        "session_compaction": crate::sessions::compaction::snapshot_json(),
+        "inbound_dedup": {
+            "duplicates_skipped": crate::channels::duplicate_inbound_skipped(),
+        },
+        "inbound_guard": crate::channels::inbound_guard::snapshot_json(),
+        "redaction": crate::security::redaction::snapshot_json(),
+        "context_utilization": crate::agent::context_usage::snapshot_json(),
+        "turns": crate::agent::turn::snapshot_json(),
+    }))
+    .into_response()
+}
+
+/// GET /api/monitor/audit — recent audit log entries, newest first
+pub async fn handle_api_monitor_audit(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<AuditLogQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let log_path = audit_log_path(&state);
+    let query = crate::security::audit::AuditQuery {
+        limit: params.limit.unwrap_or(100).clamp(1, 1000),
+        event_type: params.event_type.filter(|t| !t.is_empty()),
+        session: params.session.filter(|s| !s.is_empty()),
+    };
+
+    match tokio::task::spawn_blocking(move || {
+        crate::security::audit::read_recent_events(&log_path, &query)
+    })
+    .await
+    {
+        Ok(entries) => Json(serde_json::json!({"entries": entries})).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Failed to read audit log: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/monitor/turns — recent channel turn summaries, newest first
+pub async fn handle_api_monitor_turns(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<TurnsQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let log_path = audit_log_path(&state);
+    let query = crate::security::audit::AuditQuery {
+        limit: params.limit.unwrap_or(20).clamp(1, 200),
+        event_type: Some("turn_summary".into()),
+        session: params.session.filter(|s| !s.is_empty()),
+    };
+
+    match tokio::task::spawn_blocking(move || {
+        crate::security::audit::read_recent_events(&log_path, &query)
+    })
+    .await
+    {
+        Ok(events) => {
+            let turns: Vec<serde_json::Value> = events
+                .into_iter()
+                .filter_map(|event| {
+                    let mut turn = event.get("turn")?.clone();
+                    turn["timestamp"] = event.get("timestamp")?.clone();
+                    Some(turn)
+                })
+                .collect();
+            Json(serde_json::json!({"turns": turns})).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Failed to read audit log: {e}")})),
+        )
+            .into_response(),
+    }
 }
 
 // ── Helpers ─────────────────────────────────────────────────────
 
+fn audit_log_path(state: &AppState) -> std::path::PathBuf {
+    let config = state.config.lock();
+    let zeroclaw_dir = config
+        .config_path
+        .parent()
+        .map(std::path::Path::to_path_buf)
+        .unwrap_or_default();
+    crate::security::audit::audit_log_path(&config.security.audit, &zeroclaw_dir)
+}
+
 fn is_masked_secret(value: &str) -> bool {
     value == MASKED_SECRET
 }
@@ -692,7 +1866,7 @@ fn restore_embedding_route_api_keys(
     }
 }
 
-fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Config {
+pub(super) fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Config {
     let mut masked = config.clone();
 
     mask_optional_secret(&mut masked.api_key);
@@ -785,6 +1959,7 @@ fn mask_sensitive_fields(config: &crate::config::Config) -> crate::config::Confi
     }
     if let Some(email) = masked.channels_config.email.as_mut() {
         mask_required_secret(&mut email.password);
+        mask_optional_secret(&mut email.oauth_token);
     }
     masked
 }
@@ -971,6 +2146,7 @@ fn restore_masked_sensitive_fields(
         current.channels_config.email.as_ref(),
     ) {
         restore_required_secret(&mut incoming_ch.password, &current_ch.password);
+        restore_optional_secret(&mut incoming_ch.oauth_token, &current_ch.oauth_token);
     }
 }
 
@@ -988,6 +2164,561 @@ fn hydrate_config_for_save(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use http_body_util::BodyExt;
+
+    #[tokio::test]
+    async fn status_reports_uptime_liveness_and_adapter_counts() {
+        let state = AppState {
+            started_at: chrono::Utc::now() - chrono::Duration::seconds(90),
+            ..crate::gateway::test_support::test_state()
+        };
+
+        let response = handle_api_status(State(state), HeaderMap::new())
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+
+        assert!(body["uptime_seconds"].as_u64().unwrap() >= 90);
+        assert!(body["started_at"].as_str().is_some());
+        assert!(body["agent_loop_alive"].is_boolean());
+        assert_eq!(body["adapters"]["configured"], 0);
+        assert!(body["adapters"]["running_names"].is_array());
+        assert!(body["config_path"].is_string());
+        assert!(body["workspace_dir"].is_string());
+    }
+
+    #[tokio::test]
+    async fn ui_config_lists_channels_without_secrets() {
+        let state = crate::gateway::test_support::test_state();
+        state.config.lock().channels_config.telegram = Some(crate::config::TelegramConfig {
+            bot_token: "123:secret-bot-token".into(),
+            ..serde_json::from_value(serde_json::json!({
+                "bot_token": "x",
+                "allowed_users": []
+            }))
+            .unwrap()
+        });
+
+        let response = handle_api_ui_config(State(state)).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+
+        assert!(body["auth_required"].is_boolean());
+        assert_eq!(body["dashboard"], "embedded");
+        let telegram = body["channels"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|channel| channel["name"] == "Telegram")
+            .unwrap();
+        assert_eq!(telegram["configured"], true);
+        assert!(!String::from_utf8_lossy(&bytes).contains("secret-bot-token"));
+    }
+
+    #[tokio::test]
+    async fn cron_run_now_runs_once_and_jobs_report_state() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::gateway::test_support::test_state();
+        let config = {
+            let mut config = state.config.lock();
+            config.workspace_dir = tmp.path().to_path_buf();
+            config.clone()
+        };
+        let job = crate::cron::add_shell_job(
+            &config,
+            Some("nightly".into()),
+            crate::cron::Schedule::Cron {
+                expr: "0 3 * * *".into(),
+                tz: None,
+            },
+            "echo run-now",
+        )
+        .unwrap();
+
+        let call = |request: Request<Body>| {
+            let router = crate::gateway::build_router(state.clone());
+            async move {
+                let response = router.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = response.into_body().collect().await.unwrap().to_bytes();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+                )
+            }
+        };
+
+        let (status, body) = call(
+            Request::post(format!("/api/cron/jobs/{}/run", job.id))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ok");
+        assert!(body["output"].as_str().unwrap().contains("run-now"));
+        assert_eq!(
+            crate::cron::list_runs(&config, &job.id, 10).unwrap().len(),
+            1
+        );
+        // Manual runs leave the schedule alone.
+        assert_eq!(
+            crate::cron::get_job(&config, &job.id).unwrap().next_run,
+            job.next_run
+        );
+
+        let (status, body) =
+            call(Request::get("/api/cron/jobs").body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::OK);
+        let listed = &body["jobs"][0];
+        assert_eq!(listed["id"], job.id.as_str());
+        assert_eq!(listed["last_status"], "ok");
+        assert_eq!(listed["missed_runs"], 0);
+        assert_eq!(listed["catch_up"], "run_once");
+        assert!(listed["last_error"].is_null());
+
+        let (status, _) = call(
+            Request::post("/api/cron/jobs/missing/run")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn session_settings_patch_persists_and_rejects_invalid_values() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::gateway::test_support::test_state();
+        state.config.lock().workspace_dir = tmp.path().to_path_buf();
+
+        let call = |body: serde_json::Value| {
+            let router = crate::gateway::build_router(state.clone());
+            async move {
+                let request = Request::patch("/api/sessions/telegram_alice/settings")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap();
+                let response = router.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = response.into_body().collect().await.unwrap().to_bytes();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+                )
+            }
+        };
+
+        let (status, body) = call(serde_json::json!({
+            "temperature": 0.4,
+            "system_prompt_extra": "Be verbose."
+        }))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["settings"]["temperature"], 0.4);
+
+        let (status, body) = call(serde_json::json!({"temperature": 2.5})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("between 0 and 2"));
+
+        let (status, body) = call(serde_json::json!({"system_prompt_extra": null})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["settings"]["temperature"], 0.4);
+        assert!(body["settings"]["system_prompt_extra"].is_null());
+
+        let stored = crate::sessions::SqliteSessionStore::open(tmp.path())
+            .unwrap()
+            .settings("telegram_alice")
+            .unwrap();
+        assert_eq!(stored.temperature, Some(0.4));
+        assert!(stored.system_prompt_extra.is_none());
+    }
+
+    #[tokio::test]
+    async fn sessions_link_and_unlink_through_the_api() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::gateway::test_support::test_state();
+        state.config.lock().workspace_dir = tmp.path().to_path_buf();
+
+        let call = |request: Request<Body>| {
+            let router = crate::gateway::build_router(state.clone());
+            async move {
+                let response = router.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = response.into_body().collect().await.unwrap().to_bytes();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+                )
+            }
+        };
+        let link = |session_key: &str, canonical_key: &str| {
+            Request::post("/api/sessions/link")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    serde_json::json!({
+                        "session_key": session_key,
+                        "canonical_key": canonical_key,
+                    })
+                    .to_string(),
+                ))
+                .unwrap()
+        };
+        let unlink = || {
+            Request::delete("/api/sessions/webchat_alice/link")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let (status, body) = call(link("webchat_alice", "whatsapp_alice")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["canonical_key"], "whatsapp_alice");
+        let store = crate::sessions::SqliteSessionStore::open(tmp.path()).unwrap();
+        assert_eq!(
+            store.canonical_key("webchat_alice").unwrap(),
+            "whatsapp_alice"
+        );
+
+        let (status, _) = call(link("whatsapp_alice", "webchat_alice")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, body) = call(unlink()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["removed"], 1);
+        assert_eq!(
+            store.canonical_key("webchat_alice").unwrap(),
+            "webchat_alice"
+        );
+        let (status, _) = call(unlink()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn session_messages_render_sanitized_html_on_request() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::gateway::test_support::test_state();
+        state.config.lock().workspace_dir = tmp.path().to_path_buf();
+        let store = crate::sessions::SqliteSessionStore::open(tmp.path()).unwrap();
+        store
+            .append_message("webchat_eve", "user", "summarise this page")
+            .unwrap();
+        store
+            .append_message(
+                "webchat_eve",
+                "assistant",
+                "**Done** <img src=x onerror=alert(1)> [more](javascript:alert(2))",
+            )
+            .unwrap();
+
+        let call = |uri: &'static str| {
+            let router = crate::gateway::build_router(state.clone());
+            async move {
+                let response = router
+                    .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                let status = response.status();
+                let bytes = response.into_body().collect().await.unwrap().to_bytes();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+                )
+            }
+        };
+
+        let (status, body) = call("/api/sessions/webchat_eve/messages").await;
+        assert_eq!(status, StatusCode::OK);
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1]["role"], "assistant");
+        assert!(messages[1]["content"]
+            .as_str()
+            .unwrap()
+            .contains("onerror=alert(1)"));
+        assert!(messages[1].get("content_html").is_none());
+
+        let (_, body) = call("/api/sessions/webchat_eve/messages?render=html&limit=1").await;
+        let messages = body["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 1);
+        let html = messages[0]["content_html"].as_str().unwrap();
+        assert!(html.contains("<strong>Done</strong>"));
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("javascript:"));
+
+        let (status, _) = call("/api/sessions/webchat_eve/messages?render=svg").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = call("/api/sessions/nobody/messages").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn session_summaries_are_listed_newest_first() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::gateway::test_support::test_state();
+        state.config.lock().workspace_dir = tmp.path().to_path_buf();
+        let store = crate::sessions::SqliteSessionStore::open(tmp.path()).unwrap();
+        store.append_message("slack_bob", "user", "hi").unwrap();
+        store.set_summary("slack_bob", "- greeted").unwrap();
+        store
+            .append_message("slack_bob", "user", "ship it")
+            .unwrap();
+        store
+            .set_summary("slack_bob", "- greeted\n- asked to ship")
+            .unwrap();
+
+        let call = |uri: &'static str| {
+            let router = crate::gateway::build_router(state.clone());
+            async move {
+                let response = router
+                    .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                let status = response.status();
+                let bytes = response.into_body().collect().await.unwrap().to_bytes();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+                )
+            }
+        };
+
+        let (status, body) = call("/api/sessions/slack_bob/summaries").await;
+        assert_eq!(status, StatusCode::OK);
+        let summaries = body["summaries"].as_array().unwrap();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0]["summary"], "- greeted\n- asked to ship");
+        assert_eq!(summaries[0]["message_count_at"], 2);
+        assert_eq!(summaries[1]["summary"], "- greeted");
+        assert_eq!(summaries[1]["message_count_at"], 1);
+
+        let (_, body) = call("/api/sessions/slack_bob/summaries?limit=1").await;
+        assert_eq!(body["summaries"].as_array().unwrap().len(), 1);
+
+        let (status, _) = call("/api/sessions/missing/summaries").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn control_reload_validates_before_applying() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let config_path = tmp.path().join("config.toml");
+        let mut running = crate::config::Config::default();
+        running.config_path = config_path.clone();
+        running.workspace_dir = tmp.path().join("workspace");
+        running.default_model = Some("old-model".into());
+        let config_state = std::sync::Arc::new(parking_lot::Mutex::new(running.clone()));
+        let state = AppState {
+            config: std::sync::Arc::clone(&config_state),
+            reload: Some(crate::gateway::reload::ReloadHandle::spawn(
+                std::sync::Arc::clone(&config_state),
+            )),
+            ..crate::gateway::test_support::test_state()
+        };
+        let call = |uri: &'static str| {
+            let router = crate::gateway::build_router(state.clone());
+            async move {
+                let response = router
+                    .oneshot(Request::put(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                let status = response.status();
+                let bytes = response.into_body().collect().await.unwrap().to_bytes();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+                )
+            }
+        };
+
+        let mut invalid = running.clone();
+        invalid.gateway.attachment_retention_hours = 0;
+        std::fs::write(&config_path, toml::to_string(&invalid).unwrap()).unwrap();
+        let (status, body) = call("/api/control/reload?dry_run=true").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["status"], "invalid");
+        assert!(body["issues"][0]
+            .as_str()
+            .unwrap()
+            .contains("attachment_retention_hours"));
+
+        let mut candidate = running.clone();
+        candidate.default_model = Some("new-model".into());
+        std::fs::write(&config_path, toml::to_string(&candidate).unwrap()).unwrap();
+        let (status, body) = call("/api/control/reload?dry_run=true").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "valid");
+        assert_eq!(body["changes"]["model"]["new"], "new-model");
+        assert_eq!(
+            config_state.lock().default_model.as_deref(),
+            Some("old-model")
+        );
+
+        let (status, body) = call("/api/control/reload").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "applied");
+        assert_eq!(body["changes"]["model"]["old"], "old-model");
+        assert_eq!(
+            config_state.lock().default_model.as_deref(),
+            Some("new-model")
+        );
+    }
+
+    #[tokio::test]
+    async fn channels_send_delivers_and_records_the_message() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v4/posts"))
+            .and(body_partial_json(serde_json::json!({
+                "channel_id": "town",
+                "root_id": "root1",
+                "message": "Backup finished",
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({"id": "p1"})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = crate::config::Config::default();
+        config.workspace_dir = tmp.path().to_path_buf();
+        config.channels_config.mattermost = Some(crate::config::schema::MattermostConfig {
+            url: server.uri(),
+            bot_token: "mm-token".into(),
+            channel_id: Some("town".into()),
+            allowed_users: vec![],
+            thread_replies: None,
+            mention_only: None,
+        });
+        config.channels_config.telegram = Some(crate::config::TelegramConfig {
+            bot_token: "123:tg".into(),
+            allowed_users: vec![],
+            stream_mode: crate::config::StreamMode::default(),
+            draft_update_interval_ms: 1000,
+            interrupt_on_new_message: false,
+            mention_only: false,
+            command_prefix: None,
+            progress_messages: false,
+        });
+        let state = AppState {
+            config: std::sync::Arc::new(parking_lot::Mutex::new(config)),
+            ..crate::gateway::test_support::test_state()
+        };
+        let send = |body: serde_json::Value| {
+            let router = crate::gateway::build_router(state.clone());
+            async move {
+                let response = router
+                    .oneshot(
+                        Request::post("/api/channels/send")
+                            .header("content-type", "application/json")
+                            .body(Body::from(body.to_string()))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = response.status();
+                let bytes = response.into_body().collect().await.unwrap().to_bytes();
+                (
+                    status,
+                    serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+                )
+            }
+        };
+
+        let (status, body) = send(serde_json::json!({
+            "channel": "mattermost",
+            "to": "town:root1",
+            "text": "Backup finished",
+        }))
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["session_key"], "mattermost_root1_town");
+        assert_eq!(body["recorded"], true);
+
+        let store = crate::sessions::SqliteSessionStore::open(tmp.path()).unwrap();
+        let history = store.load_history("mattermost_root1_town", None).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].role, "assistant");
+        assert_eq!(history[0].content, "Backup finished");
+
+        for (request, error) in [
+            (
+                serde_json::json!({"channel": "irc", "to": "#ops", "text": "hi"}),
+                "Unsupported channel",
+            ),
+            (
+                serde_json::json!({"channel": "discord", "to": "1", "text": "hi"}),
+                "not configured",
+            ),
+            (
+                serde_json::json!({"channel": "telegram", "text": "hi"}),
+                "'to' is required",
+            ),
+            (
+                serde_json::json!({"channel": "mattermost", "to": "town", "text": "  "}),
+                "must not be empty",
+            ),
+        ] {
+            let (status, body) = send(request).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert!(body["error"].as_str().unwrap().contains(error), "{body}");
+        }
+    }
+
+    #[tokio::test]
+    async fn cron_job_routes_require_auth_when_pairing_enabled() {
+        let state = AppState {
+            pairing: std::sync::Arc::new(crate::security::pairing::PairingGuard::new(
+                true,
+                &["zc_valid".to_string()],
+            )),
+            ..crate::gateway::test_support::test_state()
+        };
+        let response = Box::pin(handle_api_cron_run(
+            State(state.clone()),
+            HeaderMap::new(),
+            Path("x".into()),
+        ))
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = handle_api_cron_jobs(State(state), HeaderMap::new())
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
 
     #[test]
     fn masking_keeps_toml_valid_and_preserves_api_keys_type() {
@@ -1023,6 +2754,7 @@ mod tests {
             smtp_tls: true,
             username: "agent@example.com".to_string(),
             password: "email-password-secret".to_string(),
+            oauth_token: None,
             from_address: "agent@example.com".to_string(),
             idle_timeout_secs: 1740,
             allowed_senders: vec!["*".to_string()],
@@ -1156,6 +2888,7 @@ mod tests {
             smtp_tls: true,
             username: "agent@example.com".to_string(),
             password: "email-password-real".to_string(),
+            oauth_token: None,
             from_address: "agent@example.com".to_string(),
             idle_timeout_secs: 1740,
             allowed_senders: vec!["*".to_string()],
@@ -1394,3 +3127,63 @@ mod tests {
             .all(|route| route.api_key.as_deref() != Some(MASKED_SECRET)));
     }
 }
+
+/// POST /api/attachment?filename=… — store the raw request body as an
+/// attachment. `Content-Type` gives its MIME type; the returned
+/// `attachment://{id}` URI (or the id) can be passed in `/webhook`
+/// `attachment_ids`.
+pub async fn handle_api_attachment_upload(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<AttachmentUploadQuery>,
+    body: axum::body::Bytes,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+    if body.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Attachment body is empty"})),
+        )
+            .into_response();
+    }
+
+    let mime_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("application/octet-stream");
+    let filename = params.filename.as_deref().unwrap_or("attachment");
+    let (workspace_dir, retention_hours) = {
+        let config = state.config.lock();
+        (
+            config.workspace_dir.clone(),
+            config.gateway.attachment_retention_hours,
+        )
+    };
+    let store = crate::attachments::AttachmentStore::new(&workspace_dir, retention_hours);
+
+    match store.save(filename, mime_type, &body) {
+        Ok(attachment) => (
+            StatusCode::CREATED,
+            Json(serde_json::json!({
+                "id": attachment.id,
+                "uri": attachment.uri(),
+                "filename": attachment.filename,
+                "mime_type": attachment.mime_type,
+                "size_bytes": attachment.size_bytes,
+                "expires_at": attachment.expires_at.to_rfc3339(),
+                "message": format!(
+                    "Stored for {retention_hours}h; expires at {}. Reference it in attachment_ids before then.",
+                    attachment.expires_at.to_rfc3339()
+                ),
+            })),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Attachment upload failed: {e:#}")})),
+        )
+            .into_response(),
+    }
+}
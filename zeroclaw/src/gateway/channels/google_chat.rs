@@ -0,0 +1,213 @@
+//! Google Chat app interaction events.
+
+use crate::channels::google_chat::message_body;
+use crate::channels::traits::ChannelMessage;
+use crate::channels::GoogleChatChannel;
+use crate::gateway::queue::BUSY_REPLY;
+use crate::gateway::rate_limit::retain_inbound_within_limit;
+use crate::gateway::{run_gateway_chat_with_tools, ApiError, AppState};
+use crate::memory::MemoryCategory;
+use crate::util::truncate_with_ellipsis;
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json},
+};
+use std::sync::Arc;
+
+fn google_chat_memory_key(msg: &ChannelMessage) -> String {
+    format!("{}_{}", GoogleChatChannel::session_key(msg), msg.id)
+}
+
+/// POST /google-chat — Google Chat app interaction events
+///
+/// Without a service account the agent runs inline and its answer is the
+/// response body ([`message_body`]); with one the event is acked with `{}`
+/// and the reply is posted through the Chat API.
+pub async fn handle_google_chat_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(ref google_chat) = state.google_chat else {
+        return ApiError::not_configured("Google Chat").into_response();
+    };
+
+    // ── Security: Verify the Google-signed bearer token ──
+    let authorization = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if let Err(e) = google_chat.verify_authorization(authorization).await {
+        tracing::warn!("Google Chat webhook token verification failed: {e}");
+        return ApiError::auth_invalid("Invalid bearer token").into_response();
+    }
+
+    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&body) else {
+        return ApiError::payload_invalid("Invalid JSON payload").into_response();
+    };
+
+    // Chat redelivers events it did not see answered; message.name is stable.
+    let messages: Vec<_> = google_chat
+        .parse_webhook_payload(&payload)
+        .into_iter()
+        .filter(|msg| {
+            let is_new = state
+                .idempotency_store
+                .record_if_new(&format!("google_chat:{}", msg.id));
+            if !is_new {
+                tracing::info!("Google Chat: dropping duplicate delivery of {}", msg.id);
+            }
+            is_new
+        })
+        .collect();
+
+    // An empty object is a valid "no reply" for Chat; anything else must be
+    // a Message, so rate-limited and non-message events are acked with `{}`.
+    let messages = retain_inbound_within_limit(&state.rate_limiter, "google_chat", messages);
+    let Some(msg) = messages.into_iter().next() else {
+        return (StatusCode::OK, Json(serde_json::json!({}))).into_response();
+    };
+
+    let Ok(slot) = state.inbound_queue.try_enqueue() else {
+        return (
+            StatusCode::OK,
+            Json(serde_json::json!({"text": BUSY_REPLY})),
+        )
+            .into_response();
+    };
+
+    let session_key = GoogleChatChannel::session_key(&msg);
+    tracing::info!(
+        "Google Chat message from {} in {session_key}: {}",
+        msg.sender,
+        truncate_with_ellipsis(&msg.content, 50)
+    );
+
+    if state.auto_save {
+        let key = google_chat_memory_key(&msg);
+        let _ = state
+            .mem
+            .store(&key, &msg.content, MemoryCategory::Conversation, None)
+            .await;
+    }
+
+    let workspace_dir = state.config.lock().workspace_dir.clone();
+    let sessions = crate::sessions::store_for(&workspace_dir);
+    if let Some(ref store) = sessions {
+        let (key, content) = (session_key.clone(), msg.content.clone());
+        let appended = crate::sessions::blocking(Arc::clone(store), move |store| {
+            store.append_message(&key, "user", &content)
+        })
+        .await;
+        if let Err(e) = appended {
+            tracing::debug!("Failed to persist Google Chat turn for {session_key}: {e}");
+        }
+    }
+
+    // Service-account mode: ack now and post the reply through the Chat API,
+    // so a long turn is not cut off by Chat's response deadline.
+    if google_chat.replies_via_api() {
+        let google_chat = Arc::clone(google_chat);
+        let state = state.clone();
+        tokio::spawn(async move {
+            let reply = slot
+                .run(run_turn(
+                    &state,
+                    sessions.as_ref(),
+                    &session_key,
+                    &msg.content,
+                ))
+                .await;
+            if let Err(e) = google_chat.send_to_session(&session_key, &reply).await {
+                tracing::error!("Google Chat: failed to post reply to {session_key}: {e:#}");
+            }
+        });
+        return (StatusCode::OK, Json(serde_json::json!({}))).into_response();
+    }
+
+    let reply = slot
+        .run(run_turn(
+            &state,
+            sessions.as_ref(),
+            &session_key,
+            &msg.content,
+        ))
+        .await;
+    (StatusCode::OK, Json(message_body(&reply))).into_response()
+}
+
+/// Run the agent for one message and persist its reply; failures become a
+/// user-facing apology.
+async fn run_turn(
+    state: &AppState,
+    sessions: Option<&Arc<crate::sessions::SqliteSessionStore>>,
+    session_key: &str,
+    content: &str,
+) -> String {
+    match run_gateway_chat_with_tools(state, content).await {
+        Ok(response) => {
+            if let Some(store) = sessions {
+                let (key, reply) = (session_key.to_string(), response.clone());
+                let appended = crate::sessions::blocking(Arc::clone(store), move |store| {
+                    store.append_message(&key, "assistant", &reply)
+                })
+                .await;
+                if let Err(e) = appended {
+                    tracing::debug!("Failed to persist Google Chat reply for {session_key}: {e}");
+                }
+            }
+            response
+        }
+        Err(e) => {
+            tracing::error!("LLM error for Google Chat message: {e:#}");
+            "Sorry, I couldn't process your message right now.".to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateway::test_support::{test_state, MockProvider};
+    use crate::providers::Provider;
+    use axum::http::HeaderValue;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn google_chat_webhook_rejects_missing_or_malformed_bearer_token() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+
+        let state = AppState {
+            provider,
+            google_chat: Some(Arc::new(GoogleChatChannel::new(
+                "123456789012".into(),
+                vec!["*".into()],
+            ))),
+            ..test_state()
+        };
+        let body = Bytes::from_static(
+            br#"{"type":"MESSAGE","space":{"name":"spaces/A"},"message":{"name":"spaces/A/messages/1","sender":{"name":"users/1"},"text":"hi"}}"#,
+        );
+
+        let response =
+            handle_google_chat_webhook(State(state.clone()), HeaderMap::new(), body.clone())
+                .await
+                .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer not-a-jwt"),
+        );
+        let response = handle_google_chat_webhook(State(state), headers, body)
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,133 @@
+//! Linq webhook (iMessage/RCS/SMS).
+
+use crate::channels::traits::ChannelMessage;
+use crate::channels::{Channel, SendMessage};
+use crate::gateway::queue::BUSY_REPLY;
+use crate::gateway::rate_limit::{inbound_rate_limited_ack, retain_inbound_within_limit};
+use crate::gateway::{run_gateway_chat_with_tools, ApiError, AppState};
+use crate::memory::MemoryCategory;
+use crate::util::truncate_with_ellipsis;
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json},
+};
+
+fn linq_memory_key(msg: &ChannelMessage) -> String {
+    format!("linq_{}_{}", msg.sender, msg.id)
+}
+
+/// POST /linq — incoming message webhook (iMessage/RCS/SMS via Linq)
+pub async fn handle_linq_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(ref linq) = state.linq else {
+        return ApiError::not_configured("Linq").into_response();
+    };
+
+    let body_str = String::from_utf8_lossy(&body);
+
+    // ── Security: Verify X-Webhook-Signature if signing_secret is configured ──
+    if let Some(ref signing_secret) = state.linq_signing_secret {
+        let timestamp = headers
+            .get("X-Webhook-Timestamp")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+
+        let signature = headers
+            .get("X-Webhook-Signature")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+
+        if !crate::channels::linq::verify_linq_signature(
+            signing_secret,
+            &body_str,
+            timestamp,
+            signature,
+        ) {
+            tracing::warn!(
+                "Linq webhook signature verification failed (signature: {})",
+                if signature.is_empty() {
+                    "missing"
+                } else {
+                    "invalid"
+                }
+            );
+            return ApiError::signature_invalid().into_response();
+        }
+    }
+
+    // Parse JSON body
+    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&body) else {
+        return ApiError::payload_invalid("Invalid JSON payload").into_response();
+    };
+
+    // Parse messages from the webhook payload
+    let messages = linq.parse_webhook_payload(&payload);
+
+    if messages.is_empty() {
+        // Acknowledge the webhook even if no messages (could be status/delivery events)
+        return (StatusCode::OK, Json(serde_json::json!({"status": "ok"}))).into_response();
+    }
+
+    let messages = retain_inbound_within_limit(&state.rate_limiter, "linq", messages);
+    if messages.is_empty() {
+        return inbound_rate_limited_ack().into_response();
+    }
+
+    // Process each message
+    for msg in &messages {
+        tracing::info!(
+            "Linq message from {}: {}",
+            msg.sender,
+            truncate_with_ellipsis(&msg.content, 50)
+        );
+
+        let Ok(slot) = state.inbound_queue.try_enqueue() else {
+            let _ = linq
+                .send(&SendMessage::new(BUSY_REPLY, &msg.reply_target))
+                .await;
+            continue;
+        };
+
+        // Auto-save to memory
+        if state.auto_save {
+            let key = linq_memory_key(msg);
+            let _ = state
+                .mem
+                .store(&key, &msg.content, MemoryCategory::Conversation, None)
+                .await;
+        }
+
+        // Call the LLM
+        match slot
+            .run(run_gateway_chat_with_tools(&state, &msg.content))
+            .await
+        {
+            Ok(response) => {
+                // Send reply via Linq
+                if let Err(e) = linq
+                    .send(&SendMessage::new(response, &msg.reply_target))
+                    .await
+                {
+                    tracing::error!("Failed to send Linq reply: {e}");
+                }
+            }
+            Err(e) => {
+                tracing::error!("LLM error for Linq message: {e:#}");
+                let _ = linq
+                    .send(&SendMessage::new(
+                        "Sorry, I couldn't process your message right now.",
+                        &msg.reply_target,
+                    ))
+                    .await;
+            }
+        }
+    }
+
+    // Acknowledge the webhook
+    (StatusCode::OK, Json(serde_json::json!({"status": "ok"}))).into_response()
+}
@@ -0,0 +1,10 @@
+//! Platform webhook handlers, one module per channel.
+//!
+//! Each handler verifies the platform's signature or token, applies the
+//! per channel + sender inbound limit, and answers through the shared agent.
+
+pub mod google_chat;
+pub mod linq;
+pub mod nextcloud_talk;
+pub mod wati;
+pub mod whatsapp;
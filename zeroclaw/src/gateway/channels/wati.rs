@@ -0,0 +1,120 @@
+//! WATI (`WhatsApp` Business) webhook verification and inbound messages.
+
+use crate::channels::traits::ChannelMessage;
+use crate::channels::{Channel, SendMessage};
+use crate::gateway::queue::BUSY_REPLY;
+use crate::gateway::rate_limit::{inbound_rate_limited_ack, retain_inbound_within_limit};
+use crate::gateway::{run_gateway_chat_with_tools, ApiError, AppState};
+use crate::memory::MemoryCategory;
+use crate::util::truncate_with_ellipsis;
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+
+fn wati_memory_key(msg: &ChannelMessage) -> String {
+    format!("wati_{}_{}", msg.sender, msg.id)
+}
+
+/// GET /wati — WATI webhook verification (echoes hub.challenge)
+pub async fn handle_wati_verify(
+    State(state): State<AppState>,
+    Query(params): Query<WatiVerifyQuery>,
+) -> impl IntoResponse {
+    if state.wati.is_none() {
+        return ApiError::not_configured("WATI").into_response();
+    }
+
+    // WATI may use Meta-style webhook verification; echo the challenge
+    if let Some(challenge) = params.challenge {
+        tracing::info!("WATI webhook verified successfully");
+        return (StatusCode::OK, challenge).into_response();
+    }
+
+    ApiError::payload_invalid("Missing hub.challenge").into_response()
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct WatiVerifyQuery {
+    #[serde(rename = "hub.challenge")]
+    pub challenge: Option<String>,
+}
+
+/// POST /wati — incoming WATI WhatsApp message webhook
+pub async fn handle_wati_webhook(State(state): State<AppState>, body: Bytes) -> impl IntoResponse {
+    let Some(ref wati) = state.wati else {
+        return ApiError::not_configured("WATI").into_response();
+    };
+
+    // Parse JSON body
+    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&body) else {
+        return ApiError::payload_invalid("Invalid JSON payload").into_response();
+    };
+
+    // Parse messages from the webhook payload
+    let messages = wati.parse_webhook_payload(&payload);
+
+    if messages.is_empty() {
+        return (StatusCode::OK, Json(serde_json::json!({"status": "ok"}))).into_response();
+    }
+
+    let messages = retain_inbound_within_limit(&state.rate_limiter, "wati", messages);
+    if messages.is_empty() {
+        return inbound_rate_limited_ack().into_response();
+    }
+
+    // Process each message
+    for msg in &messages {
+        tracing::info!(
+            "WATI message from {}: {}",
+            msg.sender,
+            truncate_with_ellipsis(&msg.content, 50)
+        );
+
+        let Ok(slot) = state.inbound_queue.try_enqueue() else {
+            let _ = wati
+                .send(&SendMessage::new(BUSY_REPLY, &msg.reply_target))
+                .await;
+            continue;
+        };
+
+        // Auto-save to memory
+        if state.auto_save {
+            let key = wati_memory_key(msg);
+            let _ = state
+                .mem
+                .store(&key, &msg.content, MemoryCategory::Conversation, None)
+                .await;
+        }
+
+        // Call the LLM
+        match slot
+            .run(run_gateway_chat_with_tools(&state, &msg.content))
+            .await
+        {
+            Ok(response) => {
+                // Send reply via WATI
+                if let Err(e) = wati
+                    .send(&SendMessage::new(response, &msg.reply_target))
+                    .await
+                {
+                    tracing::error!("Failed to send WATI reply: {e}");
+                }
+            }
+            Err(e) => {
+                tracing::error!("LLM error for WATI message: {e:#}");
+                let _ = wati
+                    .send(&SendMessage::new(
+                        "Sorry, I couldn't process your message right now.",
+                        &msg.reply_target,
+                    ))
+                    .await;
+            }
+        }
+    }
+
+    // Acknowledge the webhook
+    (StatusCode::OK, Json(serde_json::json!({"status": "ok"}))).into_response()
+}
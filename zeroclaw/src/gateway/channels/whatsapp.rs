@@ -0,0 +1,594 @@
+//! `WhatsApp` Cloud API webhook: Meta verification handshake and inbound messages.
+
+use crate::channels::traits::ChannelMessage;
+use crate::channels::whatsapp::WhatsAppStatus;
+use crate::channels::{Channel, SendMessage};
+use crate::gateway::queue::BUSY_REPLY;
+use crate::gateway::rate_limit::retain_inbound_within_limit;
+use crate::gateway::{run_gateway_chat_with_tools, ApiError, AppState, ErrorCode};
+use crate::memory::MemoryCategory;
+use crate::security::pairing::constant_time_eq;
+use crate::util::truncate_with_ellipsis;
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json},
+};
+
+fn whatsapp_memory_key(msg: &ChannelMessage) -> String {
+    format!("whatsapp_{}_{}", msg.sender, msg.id)
+}
+
+/// `WhatsApp` verification query params
+#[derive(serde::Deserialize)]
+pub struct WhatsAppVerifyQuery {
+    #[serde(rename = "hub.mode")]
+    pub mode: Option<String>,
+    #[serde(rename = "hub.verify_token")]
+    pub verify_token: Option<String>,
+    #[serde(rename = "hub.challenge")]
+    pub challenge: Option<String>,
+}
+
+/// GET /whatsapp — Meta webhook verification
+pub async fn handle_whatsapp_verify(
+    State(state): State<AppState>,
+    Query(params): Query<WhatsAppVerifyQuery>,
+) -> impl IntoResponse {
+    let Some(ref wa) = state.whatsapp else {
+        return ApiError::not_configured("WhatsApp").into_response();
+    };
+
+    // Verify the token matches (constant-time comparison to prevent timing attacks)
+    let token_matches = params
+        .verify_token
+        .as_deref()
+        .is_some_and(|t| constant_time_eq(t, wa.verify_token()));
+    if params.mode.as_deref() == Some("subscribe") && token_matches {
+        if let Some(ch) = params.challenge {
+            tracing::info!("WhatsApp webhook verified successfully");
+            return (StatusCode::OK, ch).into_response();
+        }
+        return ApiError::payload_invalid("Missing hub.challenge").into_response();
+    }
+
+    tracing::warn!("WhatsApp webhook verification failed — token mismatch");
+    ApiError::new(
+        StatusCode::FORBIDDEN,
+        ErrorCode::AuthInvalid,
+        "Verify token mismatch",
+    )
+    .into_response()
+}
+
+/// Verify `WhatsApp` webhook signature (`X-Hub-Signature-256`).
+/// Returns true if the signature is valid, false otherwise.
+/// See: <https://developers.facebook.com/docs/graph-api/webhooks/getting-started#verification-requests>
+pub fn verify_whatsapp_signature(app_secret: &str, body: &[u8], signature_header: &str) -> bool {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    // Signature format: "sha256=<hex_signature>"
+    let Some(hex_sig) = signature_header.strip_prefix("sha256=") else {
+        return false;
+    };
+
+    // Decode hex signature
+    let Ok(expected) = hex::decode(hex_sig) else {
+        return false;
+    };
+
+    // Compute HMAC-SHA256
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(app_secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+
+    // Constant-time comparison
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// POST /whatsapp — incoming message webhook
+pub async fn handle_whatsapp_message(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(ref wa) = state.whatsapp else {
+        return ApiError::not_configured("WhatsApp").into_response();
+    };
+
+    // ── Security: Verify X-Hub-Signature-256 if app_secret is configured ──
+    if let Some(ref app_secret) = state.whatsapp_app_secret {
+        let signature = headers
+            .get("X-Hub-Signature-256")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+
+        if !verify_whatsapp_signature(app_secret, &body, signature) {
+            tracing::warn!(
+                "WhatsApp webhook signature verification failed (signature: {})",
+                if signature.is_empty() {
+                    "missing"
+                } else {
+                    "invalid"
+                }
+            );
+            return ApiError::signature_invalid().into_response();
+        }
+    }
+
+    // Parse JSON body
+    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&body) else {
+        return ApiError::payload_invalid("Invalid JSON payload").into_response();
+    };
+
+    // Parse messages and delivery receipts, dropping replayed (stale) events
+    let batch = wa.receive_webhook(&payload).await;
+    let statuses_processed = record_statuses(&state, &batch.statuses).await;
+    let ack = |status: &str, messages_accepted: usize| {
+        (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "status": status,
+                "messages_accepted": messages_accepted,
+                "statuses_processed": statuses_processed,
+                "skipped_stale": batch.skipped_stale,
+            })),
+        )
+            .into_response()
+    };
+
+    if batch.messages.is_empty() {
+        return ack("ok", 0);
+    }
+
+    let messages = retain_inbound_within_limit(&state.rate_limiter, "whatsapp", batch.messages);
+    if messages.is_empty() {
+        return ack("rate_limited", 0);
+    }
+
+    // Process each message
+    let mut messages_accepted = 0;
+    for msg in &messages {
+        tracing::info!(
+            "WhatsApp message from {}: {}",
+            msg.sender,
+            truncate_with_ellipsis(&msg.content, 50)
+        );
+
+        let Ok(slot) = state.inbound_queue.try_enqueue() else {
+            let _ = wa
+                .send(&SendMessage::new(BUSY_REPLY, &msg.reply_target))
+                .await;
+            continue;
+        };
+        messages_accepted += 1;
+
+        // Auto-save to memory
+        if state.auto_save {
+            let key = whatsapp_memory_key(msg);
+            let _ = state
+                .mem
+                .store(&key, &msg.content, MemoryCategory::Conversation, None)
+                .await;
+        }
+
+        match slot
+            .run(run_gateway_chat_with_tools(&state, &msg.content))
+            .await
+        {
+            Ok(response) => {
+                // Send reply via WhatsApp
+                if let Err(e) = wa
+                    .send(&SendMessage::new(response, &msg.reply_target))
+                    .await
+                {
+                    tracing::error!("Failed to send WhatsApp reply: {e}");
+                }
+            }
+            Err(e) => {
+                tracing::error!("LLM error for WhatsApp message: {e:#}");
+                let _ = wa
+                    .send(&SendMessage::new(
+                        "Sorry, I couldn't process your message right now.",
+                        &msg.reply_target,
+                    ))
+                    .await;
+            }
+        }
+    }
+
+    ack("ok", messages_accepted)
+}
+
+/// Persist delivery receipts on the blocking pool. Returns how many were
+/// handled (stored, or already superseded by a later status).
+async fn record_statuses(state: &AppState, statuses: &[WhatsAppStatus]) -> usize {
+    for status in statuses {
+        tracing::debug!(
+            "WhatsApp message {} to {} is {}",
+            status.message_id,
+            status.recipient,
+            status.status
+        );
+        if let Some(ref error) = status.error {
+            tracing::warn!("WhatsApp message {} failed: {error}", status.message_id);
+        }
+    }
+    let store = state.whatsapp_delivery.clone();
+    if let Some(store) = store.filter(|_| !statuses.is_empty()) {
+        let statuses = statuses.to_vec();
+        let stored = tokio::task::spawn_blocking(move || {
+            for status in &statuses {
+                if let Err(e) = store.record(status) {
+                    tracing::warn!("Failed to store WhatsApp delivery status: {e:#}");
+                }
+            }
+        })
+        .await;
+        if let Err(e) = stored {
+            tracing::warn!("WhatsApp delivery status task failed: {e}");
+        }
+    }
+    statuses.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::whatsapp_delivery::DeliveryStatusStore;
+    use crate::channels::WhatsAppChannel;
+    use crate::gateway::test_support::{generate_test_secret, test_state, MockProvider};
+    use crate::providers::Provider;
+    use http_body_util::BodyExt;
+    use std::collections::HashMap;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
+    fn unix_now() -> u64 {
+        chrono::Utc::now().timestamp().unsigned_abs()
+    }
+
+    fn wa_state(
+        provider: Arc<MockProvider>,
+        delivery: Option<Arc<DeliveryStatusStore>>,
+    ) -> AppState {
+        let provider: Arc<dyn Provider> = provider;
+        AppState {
+            provider,
+            whatsapp: Some(Arc::new(
+                WhatsAppChannel::new(
+                    "access-token".into(),
+                    "123456".into(),
+                    "verify-me".into(),
+                    vec!["*".into()],
+                )
+                // Plain http is rejected before any request is made.
+                .with_api_base("http://graph.invalid".into())
+                .with_max_event_age_secs(3600),
+            )),
+            whatsapp_delivery: delivery,
+            ..test_state()
+        }
+    }
+
+    fn status_json(id: &str, status: &str, ts: u64) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "status": status,
+            "timestamp": ts.to_string(),
+            "recipient_id": "15551234567"
+        })
+    }
+
+    fn message_json(ts: u64) -> serde_json::Value {
+        serde_json::json!({
+            "from": "15551234567",
+            "id": "wamid.in",
+            "timestamp": ts.to_string(),
+            "type": "text",
+            "text": { "body": "hello" }
+        })
+    }
+
+    fn payload(value: serde_json::Value) -> Bytes {
+        Bytes::from(
+            serde_json::json!({
+                "object": "whatsapp_business_account",
+                "entry": [{ "id": "1", "changes": [{ "field": "messages", "value": value }] }]
+            })
+            .to_string(),
+        )
+    }
+
+    async fn post(state: AppState, body: Bytes) -> (StatusCode, serde_json::Value) {
+        let response = handle_whatsapp_message(State(state), HeaderMap::new(), body)
+            .await
+            .into_response();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn statuses_only_payload_records_receipts() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let delivery = Arc::new(DeliveryStatusStore::open(tmp.path()).unwrap());
+        let provider = Arc::new(MockProvider::default());
+        let now = unix_now();
+        let body = payload(serde_json::json!({
+            "messaging_product": "whatsapp",
+            "statuses": [
+                status_json("wamid.out1", "delivered", now - 5),
+                status_json("wamid.out1", "read", now),
+                status_json("wamid.out2", "sent", now),
+            ]
+        }));
+
+        let (status, ack) = post(wa_state(provider.clone(), Some(delivery.clone())), body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ack["status"], "ok");
+        assert_eq!(ack["messages_accepted"], 0);
+        assert_eq!(ack["statuses_processed"], 3);
+        assert_eq!(ack["skipped_stale"], 0);
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 0);
+        assert_eq!(delivery.get("wamid.out1").unwrap().unwrap().status, "read");
+        assert_eq!(delivery.get("wamid.out2").unwrap().unwrap().status, "sent");
+    }
+
+    #[tokio::test]
+    async fn mixed_payload_accepts_messages_and_statuses() {
+        let provider = Arc::new(MockProvider::default());
+        let now = unix_now();
+        let body = payload(serde_json::json!({
+            "messages": [message_json(now)],
+            "statuses": [status_json("wamid.out1", "delivered", now)]
+        }));
+
+        let (status, ack) = post(wa_state(provider, None), body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ack["messages_accepted"], 1);
+        assert_eq!(ack["statuses_processed"], 1);
+        assert_eq!(ack["skipped_stale"], 0);
+    }
+
+    #[tokio::test]
+    async fn stale_events_are_skipped() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let delivery = Arc::new(DeliveryStatusStore::open(tmp.path()).unwrap());
+        let provider = Arc::new(MockProvider::default());
+        let old = unix_now() - 7200;
+        let body = payload(serde_json::json!({
+            "messages": [message_json(old)],
+            "statuses": [status_json("wamid.out1", "read", old)]
+        }));
+
+        let (status, ack) = post(wa_state(provider.clone(), Some(delivery.clone())), body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ack["messages_accepted"], 0);
+        assert_eq!(ack["statuses_processed"], 0);
+        assert_eq!(ack["skipped_stale"], 2);
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 0);
+        assert!(delivery.get("wamid.out1").unwrap().is_none());
+    }
+
+    #[test]
+    fn whatsapp_query_fields_are_optional() {
+        let q = WhatsAppVerifyQuery {
+            mode: None,
+            verify_token: None,
+            challenge: None,
+        };
+        assert!(q.mode.is_none());
+    }
+
+    #[test]
+    fn whatsapp_memory_key_includes_sender_and_message_id() {
+        let msg = ChannelMessage {
+            id: "wamid-123".into(),
+            sender: "+1234567890".into(),
+            reply_target: "+1234567890".into(),
+            content: "hello".into(),
+            channel: "whatsapp".into(),
+            timestamp: 1,
+            thread_ts: None,
+            sender_name: None,
+            metadata: HashMap::new(),
+        };
+
+        let key = whatsapp_memory_key(&msg);
+        assert_eq!(key, "whatsapp_+1234567890_wamid-123");
+    }
+
+    fn compute_whatsapp_signature_hex(secret: &str, body: &[u8]) -> String {
+        use hmac::{Hmac, Mac};
+        use sha2::Sha256;
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn compute_whatsapp_signature_header(secret: &str, body: &[u8]) -> String {
+        format!("sha256={}", compute_whatsapp_signature_hex(secret, body))
+    }
+
+    #[test]
+    fn whatsapp_signature_valid() {
+        let app_secret = generate_test_secret();
+        let body = b"test body content";
+
+        let signature_header = compute_whatsapp_signature_header(&app_secret, body);
+
+        assert!(verify_whatsapp_signature(
+            &app_secret,
+            body,
+            &signature_header
+        ));
+    }
+
+    #[test]
+    fn whatsapp_signature_invalid_wrong_secret() {
+        let app_secret = generate_test_secret();
+        let wrong_secret = generate_test_secret();
+        let body = b"test body content";
+
+        let signature_header = compute_whatsapp_signature_header(&wrong_secret, body);
+
+        assert!(!verify_whatsapp_signature(
+            &app_secret,
+            body,
+            &signature_header
+        ));
+    }
+
+    #[test]
+    fn whatsapp_signature_invalid_wrong_body() {
+        let app_secret = generate_test_secret();
+        let original_body = b"original body";
+        let tampered_body = b"tampered body";
+
+        let signature_header = compute_whatsapp_signature_header(&app_secret, original_body);
+
+        // Verify with tampered body should fail
+        assert!(!verify_whatsapp_signature(
+            &app_secret,
+            tampered_body,
+            &signature_header
+        ));
+    }
+
+    #[test]
+    fn whatsapp_signature_missing_prefix() {
+        let app_secret = generate_test_secret();
+        let body = b"test body";
+
+        // Signature without "sha256=" prefix
+        let signature_header = "abc123def456";
+
+        assert!(!verify_whatsapp_signature(
+            &app_secret,
+            body,
+            signature_header
+        ));
+    }
+
+    #[test]
+    fn whatsapp_signature_empty_header() {
+        let app_secret = generate_test_secret();
+        let body = b"test body";
+
+        assert!(!verify_whatsapp_signature(&app_secret, body, ""));
+    }
+
+    #[test]
+    fn whatsapp_signature_invalid_hex() {
+        let app_secret = generate_test_secret();
+        let body = b"test body";
+
+        // Invalid hex characters
+        let signature_header = "sha256=not_valid_hex_zzz";
+
+        assert!(!verify_whatsapp_signature(
+            &app_secret,
+            body,
+            signature_header
+        ));
+    }
+
+    #[test]
+    fn whatsapp_signature_empty_body() {
+        let app_secret = generate_test_secret();
+        let body = b"";
+
+        let signature_header = compute_whatsapp_signature_header(&app_secret, body);
+
+        assert!(verify_whatsapp_signature(
+            &app_secret,
+            body,
+            &signature_header
+        ));
+    }
+
+    #[test]
+    fn whatsapp_signature_unicode_body() {
+        let app_secret = generate_test_secret();
+        let body = "Hello 🦀 World".as_bytes();
+
+        let signature_header = compute_whatsapp_signature_header(&app_secret, body);
+
+        assert!(verify_whatsapp_signature(
+            &app_secret,
+            body,
+            &signature_header
+        ));
+    }
+
+    #[test]
+    fn whatsapp_signature_json_payload() {
+        let app_secret = generate_test_secret();
+        let body = br#"{"entry":[{"changes":[{"value":{"messages":[{"from":"1234567890","text":{"body":"Hello"}}]}}]}]}"#;
+
+        let signature_header = compute_whatsapp_signature_header(&app_secret, body);
+
+        assert!(verify_whatsapp_signature(
+            &app_secret,
+            body,
+            &signature_header
+        ));
+    }
+
+    #[test]
+    fn whatsapp_signature_case_sensitive_prefix() {
+        let app_secret = generate_test_secret();
+        let body = b"test body";
+
+        let hex_sig = compute_whatsapp_signature_hex(&app_secret, body);
+
+        // Wrong case prefix should fail
+        let wrong_prefix = format!("SHA256={hex_sig}");
+        assert!(!verify_whatsapp_signature(&app_secret, body, &wrong_prefix));
+
+        // Correct prefix should pass
+        let correct_prefix = format!("sha256={hex_sig}");
+        assert!(verify_whatsapp_signature(
+            &app_secret,
+            body,
+            &correct_prefix
+        ));
+    }
+
+    #[test]
+    fn whatsapp_signature_truncated_hex() {
+        let app_secret = generate_test_secret();
+        let body = b"test body";
+
+        let hex_sig = compute_whatsapp_signature_hex(&app_secret, body);
+        let truncated = &hex_sig[..32]; // Only half the signature
+        let signature_header = format!("sha256={truncated}");
+
+        assert!(!verify_whatsapp_signature(
+            &app_secret,
+            body,
+            &signature_header
+        ));
+    }
+
+    #[test]
+    fn whatsapp_signature_extra_bytes() {
+        let app_secret = generate_test_secret();
+        let body = b"test body";
+
+        let hex_sig = compute_whatsapp_signature_hex(&app_secret, body);
+        let extended = format!("{hex_sig}deadbeef");
+        let signature_header = format!("sha256={extended}");
+
+        assert!(!verify_whatsapp_signature(
+            &app_secret,
+            body,
+            &signature_header
+        ));
+    }
+}
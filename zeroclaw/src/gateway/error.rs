@@ -0,0 +1,212 @@
+//! Structured error bodies and request ids for gateway endpoints.
+//!
+//! Every request gets a UUID from [`assign_request_id`]. It is returned in the
+//! `X-Request-Id` header, attached to the request's `tracing` span (so every
+//! log line written while handling it carries `request_id=…`) and included in
+//! [`ApiError`] bodies:
+//!
+//! ```json
+//! {"error":"signature_invalid","message":"Invalid signature","request_id":"…"}
+//! ```
+//!
+//! Integrators can quote the id from a rejected delivery and operators can
+//! find the matching log lines. The `error` codes are stable (see
+//! [`ErrorCode`]); messages are for humans and may change. Success responses
+//! keep their endpoint-specific shapes.
+
+use axum::{
+    extract::Request,
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Response header carrying the request id.
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Id of the gateway request being handled, if any.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Run `fut` as part of request `request_id`, e.g. work a handler spawned
+/// that outlives the request.
+pub async fn with_request_id<F: std::future::Future>(request_id: String, fut: F) -> F::Output {
+    REQUEST_ID.scope(request_id, fut).await
+}
+
+/// Middleware: give each request a fresh id, scope the handler with it and a
+/// `request` span, and echo it in `X-Request-Id`.
+pub async fn assign_request_id(request: Request, next: Next) -> Response {
+    let request_id = Uuid::new_v4().to_string();
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+    let mut response = REQUEST_ID
+        .scope(request_id.clone(), next.run(request).instrument(span))
+        .await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+/// Stable machine-readable error codes.
+///
+/// Duplicate deliveries are not errors: `/webhook` acknowledges a repeated
+/// `X-Idempotency-Key` with `200 {"status": "duplicate"}` so clients stop
+/// retrying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Missing or invalid bearer token, webhook secret or pairing code.
+    AuthInvalid,
+    /// Platform webhook signature did not verify.
+    SignatureInvalid,
+    /// Body is not valid JSON or lacks required fields.
+    PayloadInvalid,
+    /// Too many requests from this client; see `retry_after`.
+    RateLimited,
+    /// The endpoint's channel is not configured.
+    NotConfigured,
+    /// Inbound queue is full; see `retry_after`.
+    Busy,
+    /// The model provider failed to answer.
+    UpstreamFailed,
+}
+
+/// JSON error response; see the module docs for the body shape.
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    status: StatusCode,
+    code: ErrorCode,
+    message: String,
+    retry_after: Option<u64>,
+    extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+            retry_after: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+
+    pub fn auth_invalid(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, ErrorCode::AuthInvalid, message)
+    }
+
+    pub fn signature_invalid() -> Self {
+        Self::new(
+            StatusCode::UNAUTHORIZED,
+            ErrorCode::SignatureInvalid,
+            "Invalid signature",
+        )
+    }
+
+    pub fn payload_invalid(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, ErrorCode::PayloadInvalid, message)
+    }
+
+    pub fn not_configured(channel: &str) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            ErrorCode::NotConfigured,
+            format!("{channel} not configured"),
+        )
+    }
+
+    /// Adds a `Retry-After` header and a matching `retry_after` field.
+    #[must_use]
+    pub fn with_retry_after(mut self, secs: u64) -> Self {
+        self.retry_after = Some(secs);
+        self
+    }
+
+    /// Adds an endpoint-specific field to the body.
+    #[must_use]
+    pub fn with_field(mut self, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        self.extra.insert(key.to_string(), value.into());
+        self
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        // Handlers called outside the router (tests) still get an id.
+        let request_id = current_request_id().unwrap_or_else(|| Uuid::new_v4().to_string());
+        let mut body = self.extra;
+        body.insert("error".into(), serde_json::json!(self.code));
+        body.insert("message".into(), self.message.into());
+        body.insert("request_id".into(), request_id.into());
+        if let Some(secs) = self.retry_after {
+            body.insert("retry_after".into(), secs.into());
+        }
+
+        let mut response = (self.status, Json(body)).into_response();
+        if let Some(secs) = self.retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+
+    async fn body_json(response: Response) -> serde_json::Value {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn error_body_carries_code_message_and_scoped_request_id() {
+        let response = REQUEST_ID
+            .scope("req-1".to_string(), async {
+                ApiError::payload_invalid("Invalid JSON payload").into_response()
+            })
+            .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_json(response).await,
+            serde_json::json!({
+                "error": "payload_invalid",
+                "message": "Invalid JSON payload",
+                "request_id": "req-1",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn retry_after_sets_header_and_field() {
+        let response = ApiError::new(StatusCode::SERVICE_UNAVAILABLE, ErrorCode::Busy, "busy")
+            .with_retry_after(10)
+            .with_field("max_pending", 4)
+            .into_response();
+
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "10");
+        let body = body_json(response).await;
+        assert_eq!(body["error"], "busy");
+        assert_eq!(body["retry_after"], 10);
+        assert_eq!(body["max_pending"], 4);
+        assert_eq!(body["request_id"].as_str().unwrap().len(), 36);
+    }
+}
@@ -0,0 +1,292 @@
+//! Live log forwarding onto the gateway event stream.
+//!
+//! [`LogStreamLayer`] is installed into the process-wide `tracing` subscriber
+//! at startup and does nothing until the gateway calls [`install`] with its
+//! broadcast sender (`[gateway.log_stream] enabled = true`). From then on,
+//! WARN/ERROR records — and INFO records for the configured targets — are
+//! published to `/api/events` as `system_log` events:
+//!
+//! ```json
+//! {"type":"system_log","level":"warn","target":"zeroclaw::channels","message":"...","timestamp":"..."}
+//! ```
+//!
+//! A per-second cap protects the event bus from log storms. Records over the
+//! cap are dropped, and the next published record carries a `dropped` count.
+
+use crate::config::GatewayLogStreamConfig;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use tokio::sync::broadcast;
+use tracing::field::{Field, Visit};
+use tracing::Level;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+type SharedSink = Arc<RwLock<Option<LogSink>>>;
+
+fn global_sink() -> &'static SharedSink {
+    static SINK: OnceLock<SharedSink> = OnceLock::new();
+    SINK.get_or_init(|| Arc::new(RwLock::new(None)))
+}
+
+/// Start (or stop, when disabled) forwarding log records to `tx`.
+pub fn install(tx: broadcast::Sender<serde_json::Value>, config: &GatewayLogStreamConfig) {
+    let sink = config.enabled.then(|| LogSink::new(tx, config));
+    *global_sink().write().unwrap_or_else(|e| e.into_inner()) = sink;
+}
+
+/// Fixed one-second window counter.
+#[derive(Debug)]
+pub(crate) struct LogRateLimiter {
+    max_per_second: u32,
+    window: Mutex<RateWindow>,
+}
+
+#[derive(Debug, Default)]
+struct RateWindow {
+    second: i64,
+    sent: u32,
+    dropped: u64,
+}
+
+impl LogRateLimiter {
+    pub(crate) fn new(max_per_second: u32) -> Self {
+        Self {
+            max_per_second,
+            window: Mutex::new(RateWindow::default()),
+        }
+    }
+
+    /// Admit one record at `now` (unix seconds). Returns the number of
+    /// records dropped since the last admitted one, or `None` when the
+    /// current second is already full.
+    pub(crate) fn admit(&self, now: i64) -> Option<u64> {
+        let mut window = self.window.lock().unwrap_or_else(|e| e.into_inner());
+        if window.second != now {
+            window.second = now;
+            window.sent = 0;
+        }
+        if window.sent >= self.max_per_second {
+            window.dropped += 1;
+            return None;
+        }
+        window.sent += 1;
+        Some(std::mem::take(&mut window.dropped))
+    }
+}
+
+#[derive(Debug)]
+struct LogSink {
+    tx: broadcast::Sender<serde_json::Value>,
+    info_targets: Vec<String>,
+    limiter: LogRateLimiter,
+}
+
+impl LogSink {
+    fn new(tx: broadcast::Sender<serde_json::Value>, config: &GatewayLogStreamConfig) -> Self {
+        Self {
+            tx,
+            info_targets: config.info_targets.clone(),
+            limiter: LogRateLimiter::new(config.max_per_second),
+        }
+    }
+
+    fn accepts(&self, level: Level, target: &str) -> bool {
+        level <= Level::WARN
+            || (level == Level::INFO
+                && self
+                    .info_targets
+                    .iter()
+                    .any(|prefix| target_matches(target, prefix)))
+    }
+}
+
+/// `prefix` matches the target itself or any `prefix::` child module.
+fn target_matches(target: &str, prefix: &str) -> bool {
+    target
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// Collects the `message` field plus any other fields as `key=value`.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={value}", field.name());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={value:?}", field.name());
+        }
+    }
+}
+
+/// `tracing` layer that publishes selected records as `system_log` events.
+pub struct LogStreamLayer {
+    sink: SharedSink,
+}
+
+impl LogStreamLayer {
+    /// Layer bound to the process-wide sink set by [`install`].
+    pub fn new() -> Self {
+        Self {
+            sink: Arc::clone(global_sink()),
+        }
+    }
+}
+
+impl<S: tracing::Subscriber> Layer<S> for LogStreamLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let guard = self.sink.read().unwrap_or_else(|e| e.into_inner());
+        let Some(sink) = guard.as_ref() else {
+            return;
+        };
+        let metadata = event.metadata();
+        if !sink.accepts(*metadata.level(), metadata.target()) {
+            return;
+        }
+        let now = chrono::Utc::now();
+        let Some(dropped) = sink.limiter.admit(now.timestamp()) else {
+            return;
+        };
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let mut message = visitor.message;
+        message.push_str(&visitor.fields);
+
+        let mut record = serde_json::json!({
+            "type": "system_log",
+            "level": metadata.level().as_str().to_ascii_lowercase(),
+            "target": metadata.target(),
+            "message": message.trim_start(),
+            "timestamp": now.to_rfc3339(),
+        });
+        if dropped > 0 {
+            record["dropped"] = dropped.into();
+        }
+        // No subscribers is fine; the record is simply not delivered.
+        let _ = sink.tx.send(record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn layer_with(
+        config: &GatewayLogStreamConfig,
+    ) -> (LogStreamLayer, broadcast::Receiver<serde_json::Value>) {
+        let (tx, rx) = broadcast::channel(64);
+        let layer = LogStreamLayer {
+            sink: Arc::new(RwLock::new(Some(LogSink::new(tx, config)))),
+        };
+        (layer, rx)
+    }
+
+    fn drain(rx: &mut broadcast::Receiver<serde_json::Value>) -> Vec<serde_json::Value> {
+        std::iter::from_fn(|| rx.try_recv().ok()).collect()
+    }
+
+    #[test]
+    fn rate_limiter_caps_each_second_and_reports_drops() {
+        let limiter = LogRateLimiter::new(2);
+        assert_eq!(limiter.admit(100), Some(0));
+        assert_eq!(limiter.admit(100), Some(0));
+        assert_eq!(limiter.admit(100), None);
+        assert_eq!(limiter.admit(100), None);
+        // The next window admits again and reports what was dropped.
+        assert_eq!(limiter.admit(101), Some(2));
+        assert_eq!(limiter.admit(101), Some(0));
+
+        let silent = LogRateLimiter::new(0);
+        assert_eq!(silent.admit(1), None);
+    }
+
+    #[test]
+    fn layer_forwards_warnings_and_selected_info_targets() {
+        let config = GatewayLogStreamConfig {
+            enabled: true,
+            info_targets: vec!["audit".into()],
+            max_per_second: 100,
+        };
+        let (layer, mut rx) = layer_with(&config);
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(target: "zeroclaw::channels", attempt = 3, "reconnect failed");
+            tracing::error!("adapter crashed");
+            tracing::info!(target: "audit", "config reloaded");
+            tracing::info!(target: "audit::shell", "command allowed");
+            tracing::info!(target: "auditor", "not forwarded");
+            tracing::info!("plain info is not forwarded");
+            tracing::debug!(target: "audit", "debug is not forwarded");
+        });
+
+        let events = drain(&mut rx);
+        let summary: Vec<(&str, &str, &str)> = events
+            .iter()
+            .map(|e| {
+                assert_eq!(e["type"], "system_log");
+                (
+                    e["level"].as_str().unwrap(),
+                    e["target"].as_str().unwrap(),
+                    e["message"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(summary.len(), 4, "{summary:?}");
+        assert_eq!(
+            summary[0],
+            ("warn", "zeroclaw::channels", "reconnect failed attempt=3")
+        );
+        assert_eq!(summary[1].0, "error");
+        assert_eq!(summary[2], ("info", "audit", "config reloaded"));
+        assert_eq!(summary[3].1, "audit::shell");
+    }
+
+    #[test]
+    fn layer_drops_records_over_the_rate_cap() {
+        let config = GatewayLogStreamConfig {
+            enabled: true,
+            info_targets: Vec::new(),
+            max_per_second: 3,
+        };
+        let (layer, mut rx) = layer_with(&config);
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            for i in 0..10 {
+                tracing::warn!("storm {i}");
+            }
+        });
+
+        // All ten records land in at most two one-second windows.
+        let forwarded = drain(&mut rx).len();
+        assert!((3..=6).contains(&forwarded), "forwarded {forwarded}");
+    }
+
+    #[test]
+    fn layer_is_inert_without_a_sink() {
+        let layer = LogStreamLayer {
+            sink: Arc::new(RwLock::new(None)),
+        };
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::error!("nobody is listening");
+        });
+    }
+}
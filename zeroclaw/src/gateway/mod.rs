@@ -8,12 +8,34 @@
 //! - Header sanitization (handled by axum/hyper)
 
 pub mod api;
+pub mod channels;
+pub mod error;
+pub mod idempotency;
+pub mod log_stream;
+pub mod queue;
+pub mod rate_limit;
+pub mod reload;
+pub mod sanitize;
 pub mod sse;
 pub mod static_files;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod tls;
 pub mod ws;
-
+pub mod ws_events;
+
+#[allow(unused_imports)]
+pub use channels::wati::WatiVerifyQuery;
+#[allow(unused_imports)]
+pub use channels::whatsapp::{verify_whatsapp_signature, WhatsAppVerifyQuery};
+pub use error::{ApiError, ErrorCode};
+pub use idempotency::IdempotencyStore;
+use rate_limit::rate_limited_response;
+pub use rate_limit::GatewayRateLimiter;
+
+use crate::channels::whatsapp_delivery::DeliveryStatusStore;
 use crate::channels::{
-    Channel, LinqChannel, NextcloudTalkChannel, SendMessage, WatiChannel, WhatsAppChannel,
+    GoogleChatChannel, LinqChannel, NextcloudTalkChannel, WatiChannel, WhatsAppChannel,
 };
 use crate::config::Config;
 use crate::cost::CostTracker;
@@ -24,23 +46,22 @@ use crate::security::pairing::{constant_time_eq, is_public_bind, PairingGuard};
 use crate::security::SecurityPolicy;
 use crate::tools;
 use crate::tools::traits::ToolSpec;
-use crate::util::truncate_with_ellipsis;
 use anyhow::{Context, Result};
 use axum::{
-    body::Bytes,
-    extract::{ConnectInfo, Query, State},
+    extract::{ConnectInfo, State},
     http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Json},
-    routing::{delete, get, post, put},
+    routing::{delete, get, patch, post, put},
     Router,
 };
 use parking_lot::Mutex;
-use std::collections::HashMap;
+use queue::{InboundQueue, QueueFull};
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::timeout::TimeoutLayer;
+use tracing::Instrument;
 use uuid::Uuid;
 
 /// Maximum request body size (64KB) — prevents memory exhaustion
@@ -53,27 +74,16 @@ pub const RATE_LIMIT_WINDOW_SECS: u64 = 60;
 pub const RATE_LIMIT_MAX_KEYS_DEFAULT: usize = 10_000;
 /// Fallback max distinct idempotency keys retained in gateway memory.
 pub const IDEMPOTENCY_MAX_KEYS_DEFAULT: usize = 10_000;
+/// Longest `/webhook` waits for a reply before answering `202`; kept under
+/// [`REQUEST_TIMEOUT_SECS`] so slow turns are accepted rather than timed out.
+pub const WEBHOOK_MAX_WAIT_SECS: u64 = REQUEST_TIMEOUT_SECS - 5;
+/// `Retry-After` hint sent when the inbound queue is full.
+pub const BUSY_RETRY_AFTER_SECS: u64 = 10;
 
 fn webhook_memory_key() -> String {
     format!("webhook_msg_{}", Uuid::new_v4())
 }
 
-fn whatsapp_memory_key(msg: &crate::channels::traits::ChannelMessage) -> String {
-    format!("whatsapp_{}_{}", msg.sender, msg.id)
-}
-
-fn linq_memory_key(msg: &crate::channels::traits::ChannelMessage) -> String {
-    format!("linq_{}_{}", msg.sender, msg.id)
-}
-
-fn wati_memory_key(msg: &crate::channels::traits::ChannelMessage) -> String {
-    format!("wati_{}_{}", msg.sender, msg.id)
-}
-
-fn nextcloud_talk_memory_key(msg: &crate::channels::traits::ChannelMessage) -> String {
-    format!("nextcloud_talk_{}_{}", msg.sender, msg.id)
-}
-
 fn hash_webhook_secret(value: &str) -> String {
     use sha2::{Digest, Sha256};
 
@@ -81,143 +91,16 @@ fn hash_webhook_secret(value: &str) -> String {
     hex::encode(digest)
 }
 
-/// How often the rate limiter sweeps stale IP entries from its map.
-const RATE_LIMITER_SWEEP_INTERVAL_SECS: u64 = 300; // 5 minutes
-
-#[derive(Debug)]
-struct SlidingWindowRateLimiter {
-    limit_per_window: u32,
-    window: Duration,
-    max_keys: usize,
-    requests: Mutex<(HashMap<String, Vec<Instant>>, Instant)>,
-}
-
-impl SlidingWindowRateLimiter {
-    fn new(limit_per_window: u32, window: Duration, max_keys: usize) -> Self {
-        Self {
-            limit_per_window,
-            window,
-            max_keys: max_keys.max(1),
-            requests: Mutex::new((HashMap::new(), Instant::now())),
-        }
-    }
-
-    fn prune_stale(requests: &mut HashMap<String, Vec<Instant>>, cutoff: Instant) {
-        requests.retain(|_, timestamps| {
-            timestamps.retain(|t| *t > cutoff);
-            !timestamps.is_empty()
-        });
-    }
-
-    fn allow(&self, key: &str) -> bool {
-        if self.limit_per_window == 0 {
-            return true;
-        }
-
-        let now = Instant::now();
-        let cutoff = now.checked_sub(self.window).unwrap_or_else(Instant::now);
-
-        let mut guard = self.requests.lock();
-        let (requests, last_sweep) = &mut *guard;
-
-        // Periodic sweep: remove keys with no recent requests
-        if last_sweep.elapsed() >= Duration::from_secs(RATE_LIMITER_SWEEP_INTERVAL_SECS) {
-            Self::prune_stale(requests, cutoff);
-            *last_sweep = now;
-        }
-
-        if !requests.contains_key(key) && requests.len() >= self.max_keys {
-            // Opportunistic stale cleanup before eviction under cardinality pressure.
-            Self::prune_stale(requests, cutoff);
-            *last_sweep = now;
-
-            if requests.len() >= self.max_keys {
-                let evict_key = requests
-                    .iter()
-                    .min_by_key(|(_, timestamps)| timestamps.last().copied().unwrap_or(cutoff))
-                    .map(|(k, _)| k.clone());
-                if let Some(evict_key) = evict_key {
-                    requests.remove(&evict_key);
-                }
-            }
-        }
-
-        let entry = requests.entry(key.to_owned()).or_default();
-        entry.retain(|instant| *instant > cutoff);
-
-        if entry.len() >= self.limit_per_window as usize {
-            return false;
-        }
-
-        entry.push(now);
-        true
-    }
-}
-
-#[derive(Debug)]
-pub struct GatewayRateLimiter {
-    pair: SlidingWindowRateLimiter,
-    webhook: SlidingWindowRateLimiter,
-}
-
-impl GatewayRateLimiter {
-    fn new(pair_per_minute: u32, webhook_per_minute: u32, max_keys: usize) -> Self {
-        let window = Duration::from_secs(RATE_LIMIT_WINDOW_SECS);
-        Self {
-            pair: SlidingWindowRateLimiter::new(pair_per_minute, window, max_keys),
-            webhook: SlidingWindowRateLimiter::new(webhook_per_minute, window, max_keys),
-        }
-    }
-
-    fn allow_pair(&self, key: &str) -> bool {
-        self.pair.allow(key)
-    }
-
-    fn allow_webhook(&self, key: &str) -> bool {
-        self.webhook.allow(key)
-    }
-}
-
-#[derive(Debug)]
-pub struct IdempotencyStore {
-    ttl: Duration,
-    max_keys: usize,
-    keys: Mutex<HashMap<String, Instant>>,
-}
-
-impl IdempotencyStore {
-    fn new(ttl: Duration, max_keys: usize) -> Self {
-        Self {
-            ttl,
-            max_keys: max_keys.max(1),
-            keys: Mutex::new(HashMap::new()),
-        }
-    }
-
-    /// Returns true if this key is new and is now recorded.
-    fn record_if_new(&self, key: &str) -> bool {
-        let now = Instant::now();
-        let mut keys = self.keys.lock();
-
-        keys.retain(|_, seen_at| now.duration_since(*seen_at) < self.ttl);
-
-        if keys.contains_key(key) {
-            return false;
-        }
-
-        if keys.len() >= self.max_keys {
-            let evict_key = keys
-                .iter()
-                .min_by_key(|(_, seen_at)| *seen_at)
-                .map(|(k, _)| k.clone());
-            if let Some(evict_key) = evict_key {
-                keys.remove(&evict_key);
-            }
-        }
-
-        keys.insert(key.to_owned(), now);
-        true
-    }
+/// 503 response for `/webhook` when the inbound queue is full.
+fn busy_response(full: QueueFull) -> axum::response::Response {
+    ApiError::new(
+        StatusCode::SERVICE_UNAVAILABLE,
+        ErrorCode::Busy,
+        "Agent is busy with other messages. Please retry later.",
+    )
+    .with_retry_after(BUSY_RETRY_AFTER_SECS)
+    .with_field("max_pending", full.max_depth)
+    .into_response()
 }
 
 fn parse_client_ip(value: &str) -> Option<IpAddr> {
@@ -295,6 +178,8 @@ pub struct AppState {
     pub whatsapp: Option<Arc<WhatsAppChannel>>,
     /// `WhatsApp` app secret for webhook signature verification (`X-Hub-Signature-256`)
     pub whatsapp_app_secret: Option<Arc<str>>,
+    /// Latest delivery receipt per outbound `WhatsApp` message
+    pub whatsapp_delivery: Option<Arc<DeliveryStatusStore>>,
     pub linq: Option<Arc<LinqChannel>>,
     /// Linq webhook signing secret for signature verification
     pub linq_signing_secret: Option<Arc<str>>,
@@ -302,6 +187,7 @@ pub struct AppState {
     /// Nextcloud Talk webhook secret for signature verification
     pub nextcloud_talk_webhook_secret: Option<Arc<str>>,
     pub wati: Option<Arc<WatiChannel>>,
+    pub google_chat: Option<Arc<GoogleChatChannel>>,
     /// Observability backend for metrics scraping
     pub observer: Arc<dyn crate::observability::Observer>,
     /// Registered tool specs (for web dashboard tools page)
@@ -310,20 +196,73 @@ pub struct AppState {
     pub cost_tracker: Option<Arc<CostTracker>>,
     /// SSE broadcast channel for real-time events
     pub event_tx: tokio::sync::broadcast::Sender<serde_json::Value>,
+    /// Bounded admission for agent turns; full means "busy", never a drop
+    pub inbound_queue: Arc<InboundQueue>,
+    /// When the process started, for `/api/status` uptime
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// Applies configs validated by `PUT /api/control/reload`; `None`
+    /// disables the endpoint
+    pub reload: Option<reload::ReloadHandle>,
 }
 
-/// Run the HTTP gateway using axum with proper HTTP/1.1 compliance.
-#[allow(clippy::too_many_lines)]
-pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
-    // ── Security: refuse public bind without tunnel or explicit opt-in ──
-    if is_public_bind(host) && config.tunnel.provider == "none" && !config.gateway.allow_public_bind
-    {
+/// Refuse bind configurations that would expose the gateway unprotected.
+///
+/// A non-loopback bind needs a tunnel or `allow_public_bind`, and with
+/// pairing disabled it additionally needs `allow_insecure`.
+pub fn check_bind_policy(host: &str, config: &Config) -> Result<()> {
+    if !is_public_bind(host) {
+        return Ok(());
+    }
+    if config.tunnel.provider == "none" && !config.gateway.allow_public_bind {
         anyhow::bail!(
             "🛑 Refusing to bind to {host} — gateway would be exposed to the internet.\n\
              Fix: use --host 127.0.0.1 (default), configure a tunnel, or set\n\
              [gateway] allow_public_bind = true in config.toml (NOT recommended)."
         );
     }
+    if !config.gateway.require_pairing && !config.gateway.allow_insecure {
+        anyhow::bail!(
+            "🛑 Refusing to bind to {host} with pairing disabled — every endpoint would be \
+             reachable without authentication.\n\
+             Fix: set [gateway] require_pairing = true, bind to 127.0.0.1, or set\n\
+             [gateway] allow_insecure = true in config.toml (NOT recommended)."
+        );
+    }
+    Ok(())
+}
+
+/// Run the HTTP gateway using axum with proper HTTP/1.1 compliance.
+pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
+    // ── Security: refuse unprotected public binds ──
+    check_bind_policy(host, &config)?;
+    let listener = crate::daemon::instance::bind_gateway_listener(host, port).await?;
+    run_gateway_with_listener(host, listener, config).await
+}
+
+/// Run the gateway on a listener that is already bound to `host`.
+#[allow(clippy::too_many_lines)]
+pub async fn run_gateway_with_listener(
+    host: &str,
+    listener: tokio::net::TcpListener,
+    config: Config,
+) -> Result<()> {
+    check_bind_policy(host, &config)?;
+    crate::security::redaction::install(crate::security::redaction::Redactor::from_config(&config));
+    crate::approval::confirm::install(crate::approval::confirm::ConfirmationGate::from_config(
+        &config,
+    ));
+    // Load the certificate before binding so a bad path fails fast.
+    let tls_acceptor = match &config.gateway.tls {
+        Some(tls) => Some(Arc::new(
+            tls::ReloadingAcceptor::load(tls).context("failed to load [gateway.tls]")?,
+        )),
+        None => None,
+    };
+    let scheme = if tls_acceptor.is_some() {
+        "https"
+    } else {
+        "http"
+    };
     let config_state = Arc::new(Mutex::new(config.clone()));
 
     // ── Hooks ──────────────────────────────────────────────────────
@@ -333,11 +272,19 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         None
     };
 
-    let addr: SocketAddr = format!("{host}:{port}").parse()?;
-    let listener = tokio::net::TcpListener::bind(addr).await?;
     let actual_port = listener.local_addr()?.port();
     let display_addr = format!("{host}:{actual_port}");
 
+    // SSE broadcast channel for real-time events
+    let (event_tx, _event_rx) = tokio::sync::broadcast::channel::<serde_json::Value>(256);
+    log_stream::install(event_tx.clone(), &config.gateway.log_stream);
+    // Wrap observer with broadcast capability for SSE
+    let broadcast_observer: Arc<dyn crate::observability::Observer> =
+        Arc::new(sse::BroadcastObserver::new(
+            crate::observability::create_observer(&config.observability),
+            event_tx.clone(),
+        ));
+
     let provider: Arc<dyn Provider> = Arc::from(providers::create_resilient_provider_with_options(
         config.default_provider.as_deref().unwrap_or("openrouter"),
         config.api_key.as_deref(),
@@ -349,6 +296,11 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             zeroclaw_dir: config.config_path.parent().map(std::path::PathBuf::from),
             secrets_encrypt: config.secrets.encrypt,
             reasoning_enabled: config.runtime.reasoning_enabled,
+            openrouter: config.openrouter.clone(),
+            response_cache: providers::cache::ProviderCache::from_config(
+                &config,
+                Some(Arc::clone(&broadcast_observer)),
+            ),
         },
     )?);
     let model = config
@@ -409,8 +361,6 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         None
     };
 
-    // SSE broadcast channel for real-time events
-    let (event_tx, _event_rx) = tokio::sync::broadcast::channel::<serde_json::Value>(256);
     // Extract webhook secret for authentication
     let webhook_secret_hash: Option<Arc<str>> =
         config.channels_config.webhook.as_ref().and_then(|webhook| {
@@ -422,19 +372,37 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         });
 
     // WhatsApp channel (if configured)
-    let whatsapp_channel: Option<Arc<WhatsAppChannel>> = config
+    let whatsapp_cloud = config
         .channels_config
         .whatsapp
         .as_ref()
-        .filter(|wa| wa.is_cloud_config())
-        .map(|wa| {
-            Arc::new(WhatsAppChannel::new(
-                wa.access_token.clone().unwrap_or_default(),
-                wa.phone_number_id.clone().unwrap_or_default(),
-                wa.verify_token.clone().unwrap_or_default(),
-                wa.allowed_numbers.clone(),
-            ))
-        });
+        .filter(|wa| wa.is_cloud_config());
+    let whatsapp_delivery = whatsapp_cloud.and_then(|_| {
+        DeliveryStatusStore::open(&config.workspace_dir)
+            .map(Arc::new)
+            .map_err(|e| tracing::warn!("WhatsApp delivery receipts will not be stored: {e:#}"))
+            .ok()
+    });
+    let whatsapp_channel: Option<Arc<WhatsAppChannel>> = whatsapp_cloud.map(|wa| {
+        let mut channel = WhatsAppChannel::new(
+            wa.access_token.clone().unwrap_or_default(),
+            wa.phone_number_id.clone().unwrap_or_default(),
+            wa.verify_token.clone().unwrap_or_default(),
+            wa.allowed_numbers.clone(),
+        )
+        .with_workspace_dir(config.workspace_dir.clone())
+        .with_transcription(config.transcription.clone())
+        .with_max_event_age_secs(wa.max_event_age_secs)
+        .with_template(wa.template.clone())
+        .with_outbound_limits(
+            config.runtime.adapter_max_inflight,
+            config.runtime.adapter_retry_jitter_ms,
+        );
+        if let Some(ref store) = whatsapp_delivery {
+            channel = channel.with_session_window(Arc::clone(store));
+        }
+        Arc::new(channel)
+    });
 
     // WhatsApp app secret for webhook signature verification
     // Priority: environment variable > config file
@@ -528,6 +496,30 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             })
             .map(Arc::from);
 
+    // Google Chat app (if configured)
+    let google_chat_channel: Option<Arc<GoogleChatChannel>> =
+        config.channels_config.google_chat.as_ref().map(|gc| {
+            let channel =
+                GoogleChatChannel::new(gc.project_number.clone(), gc.allowed_users.clone())
+                    .with_outbound_limits(
+                        config.runtime.adapter_max_inflight,
+                        config.runtime.adapter_retry_jitter_ms,
+                    );
+            let api = gc.service_account_json.as_deref().and_then(|key| {
+                crate::channels::google_chat::HttpChatApi::from_service_account(key)
+                    .map_err(|e| {
+                        tracing::warn!(
+                            "Google Chat: service account unusable, replying via webhook: {e:#}"
+                        );
+                    })
+                    .ok()
+            });
+            Arc::new(match api {
+                Some(api) => channel.with_chat_api(Arc::new(api)),
+                None => channel,
+            })
+        });
+
     // ── Pairing guard ──────────────────────────────────────
     let pairing = Arc::new(PairingGuard::new(
         config.gateway.require_pairing,
@@ -537,11 +529,14 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         config.gateway.rate_limit_max_keys,
         RATE_LIMIT_MAX_KEYS_DEFAULT,
     );
-    let rate_limiter = Arc::new(GatewayRateLimiter::new(
-        config.gateway.pair_rate_limit_per_minute,
-        config.gateway.webhook_rate_limit_per_minute,
-        rate_limit_max_keys,
-    ));
+    let rate_limiter = Arc::new(
+        GatewayRateLimiter::new(
+            config.gateway.pair_rate_limit_per_minute,
+            config.gateway.webhook_rate_limit_per_minute,
+            rate_limit_max_keys,
+        )
+        .with_inbound_limit(config.gateway.inbound_rate_limit_per_minute),
+    );
     let idempotency_max_keys = normalize_max_keys(
         config.gateway.idempotency_max_keys,
         IDEMPOTENCY_MAX_KEYS_DEFAULT,
@@ -569,11 +564,11 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         }
     }
 
-    println!("🦀 ZeroClaw Gateway listening on http://{display_addr}");
+    println!("🦀 ZeroClaw Gateway listening on {scheme}://{display_addr}");
     if let Some(ref url) = tunnel_url {
         println!("  🌐 Public URL: {url}");
     }
-    println!("  🌐 Web Dashboard: http://{display_addr}/");
+    println!("  🌐 Web Dashboard: {scheme}://{display_addr}/");
     println!("  POST /pair      — pair a new client (X-Pairing-Code header)");
     println!("  POST /webhook   — {{\"message\": \"your prompt\"}}");
     if whatsapp_channel.is_some() {
@@ -590,8 +585,12 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     if nextcloud_talk_channel.is_some() {
         println!("  POST /nextcloud-talk — Nextcloud Talk bot webhook");
     }
+    if google_chat_channel.is_some() {
+        println!("  POST /google-chat — Google Chat app events");
+    }
     println!("  GET  /api/*     — REST API (bearer token required)");
     println!("  GET  /ws/chat   — WebSocket agent chat");
+    println!("  GET  /ws/events — WebSocket event stream and commands");
     println!("  GET  /health    — health check");
     println!("  GET  /metrics   — Prometheus metrics");
     if let Some(code) = pairing.pairing_code() {
@@ -608,6 +607,15 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     }
     println!("  Press Ctrl+C to stop.\n");
 
+    // `/webhook` records its turns in sessions; share the channels' store
+    // when they run in this process.
+    if crate::sessions::store_for(&config.workspace_dir).is_none() {
+        match crate::sessions::SqliteSessionStore::open(&config.workspace_dir) {
+            Ok(store) => crate::sessions::register_store(&config.workspace_dir, Arc::new(store)),
+            Err(e) => tracing::warn!("Webhook session persistence disabled: {e}"),
+        }
+    }
+
     crate::health::mark_component_ok("gateway");
 
     // Fire gateway start hook
@@ -615,13 +623,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         hooks.fire_gateway_start(host, actual_port).await;
     }
 
-    // Wrap observer with broadcast capability for SSE
-    let broadcast_observer: Arc<dyn crate::observability::Observer> =
-        Arc::new(sse::BroadcastObserver::new(
-            crate::observability::create_observer(&config.observability),
-            event_tx.clone(),
-        ));
-
+    let reload = reload::ReloadHandle::spawn(Arc::clone(&config_state));
     let state = AppState {
         config: config_state,
         provider,
@@ -636,35 +638,89 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         idempotency_store,
         whatsapp: whatsapp_channel,
         whatsapp_app_secret,
+        whatsapp_delivery,
         linq: linq_channel,
         linq_signing_secret,
         nextcloud_talk: nextcloud_talk_channel,
         nextcloud_talk_webhook_secret,
         wati: wati_channel,
-        observer: broadcast_observer,
+        google_chat: google_chat_channel,
+        observer: broadcast_observer.clone(),
         tools_registry,
         cost_tracker,
         event_tx,
+        inbound_queue: Arc::new(
+            InboundQueue::new(
+                config.gateway.max_concurrent_turns,
+                config.gateway.inbound_queue_capacity,
+            )
+            .with_observer(broadcast_observer),
+        ),
+        started_at: crate::health::process_started_at(),
+        reload: Some(reload),
     };
 
+    let app = build_router(state);
+
+    // Run the server
+    if let Some(acceptor) = tls_acceptor {
+        use axum::serve::ListenerExt;
+
+        acceptor.spawn_reload_task();
+        // `tap_io` gives the TLS listener axum's `ConnectInfo<SocketAddr>` support.
+        axum::serve(
+            tls::TlsListener::new(listener, acceptor)?.tap_io(|_| {}),
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await?;
+    } else {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Build the gateway router (routes, body limits, timeouts) over `state`.
+///
+/// Separate from [`run_gateway`] so tests can drive the full stack in memory.
+pub fn build_router(state: AppState) -> Router {
     // Config PUT needs larger body limit (1MB)
     let config_put_router = Router::new()
         .route("/api/config", put(api::handle_api_config_put))
+        .route("/api/control/reload", put(api::handle_api_control_reload))
         .layer(RequestBodyLimitLayer::new(1_048_576));
+    let attachment_router = Router::new()
+        .route("/api/attachment", post(api::handle_api_attachment_upload))
+        .layer(RequestBodyLimitLayer::new(
+            crate::attachments::MAX_ATTACHMENT_BYTES,
+        ));
 
-    // Build router with middleware
-    let app = Router::new()
+    Router::new()
         // ── Existing routes ──
         .route("/health", get(handle_health))
         .route("/metrics", get(handle_metrics))
         .route("/pair", post(handle_pair))
         .route("/webhook", post(handle_webhook))
-        .route("/whatsapp", get(handle_whatsapp_verify))
-        .route("/whatsapp", post(handle_whatsapp_message))
-        .route("/linq", post(handle_linq_webhook))
-        .route("/wati", get(handle_wati_verify))
-        .route("/wati", post(handle_wati_webhook))
-        .route("/nextcloud-talk", post(handle_nextcloud_talk_webhook))
+        .route("/whatsapp", get(channels::whatsapp::handle_whatsapp_verify))
+        .route(
+            "/whatsapp",
+            post(channels::whatsapp::handle_whatsapp_message),
+        )
+        .route("/linq", post(channels::linq::handle_linq_webhook))
+        .route("/wati", get(channels::wati::handle_wati_verify))
+        .route("/wati", post(channels::wati::handle_wati_webhook))
+        .route(
+            "/nextcloud-talk",
+            post(channels::nextcloud_talk::handle_nextcloud_talk_webhook),
+        )
+        .route(
+            "/google-chat",
+            post(channels::google_chat::handle_google_chat_webhook),
+        )
         // ── Web Dashboard API routes ──
         .route("/api/status", get(api::handle_api_status))
         .route("/api/config", get(api::handle_api_config_get))
@@ -672,42 +728,82 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/api/cron", get(api::handle_api_cron_list))
         .route("/api/cron", post(api::handle_api_cron_add))
         .route("/api/cron/{id}", delete(api::handle_api_cron_delete))
+        .route("/api/cron/jobs", get(api::handle_api_cron_jobs))
+        .route("/api/cron/jobs/{id}/run", post(api::handle_api_cron_run))
         .route("/api/integrations", get(api::handle_api_integrations))
         .route(
             "/api/doctor",
             get(api::handle_api_doctor).post(api::handle_api_doctor),
         )
+        .route(
+            "/api/monitor/diagnostics",
+            get(api::handle_api_monitor_diagnostics),
+        )
         .route("/api/memory", get(api::handle_api_memory_list))
         .route("/api/memory", post(api::handle_api_memory_store))
         .route("/api/memory/{key}", delete(api::handle_api_memory_delete))
+        .route("/api/sessions", get(api::handle_api_sessions_list))
+        .route("/api/sessions/search", get(api::handle_api_sessions_search))
+        .route("/api/sessions/link", post(api::handle_api_sessions_link))
+        .route(
+            "/api/sessions/{session_key}",
+            delete(api::handle_api_session_delete),
+        )
+        .route(
+            "/api/sessions/{session_key}/settings",
+            patch(api::handle_api_session_settings_patch),
+        )
+        .route(
+            "/api/sessions/{session_key}/export",
+            get(api::handle_api_session_export),
+        )
+        .route(
+            "/api/sessions/{session_key}/link",
+            delete(api::handle_api_session_unlink),
+        )
+        .route(
+            "/api/sessions/{session_key}/summaries",
+            get(api::handle_api_session_summaries),
+        )
+        .route(
+            "/api/sessions/{session_key}/messages",
+            get(api::handle_api_session_messages),
+        )
+        .route(
+            "/api/messages/{id}/redact",
+            post(api::handle_api_message_redact),
+        )
         .route("/api/cost", get(api::handle_api_cost))
         .route("/api/cli-tools", get(api::handle_api_cli_tools))
         .route("/api/health", get(api::handle_api_health))
+        .route("/api/channels/health", get(api::handle_api_channels_health))
+        .route("/api/channels/send", post(api::handle_api_channels_send))
+        .route("/api/ui/config", get(api::handle_api_ui_config))
+        .route("/api/monitor/metrics", get(api::handle_api_monitor_metrics))
+        .route("/api/monitor/audit", get(api::handle_api_monitor_audit))
+        .route("/api/monitor/turns", get(api::handle_api_monitor_turns))
         // ── SSE event stream ──
         .route("/api/events", get(sse::handle_sse_events))
-        // ── WebSocket agent chat ──
+        // ── WebSocket agent chat and event stream ──
         .route("/ws/chat", get(ws::handle_ws_chat))
+        .route("/ws/events", get(ws_events::handle_ws_events))
         // ── Static assets (web dashboard) ──
         .route("/_app/{*path}", get(static_files::handle_static))
-        // ── Config PUT with larger body limit ──
+        .route("/assets/{*path}", get(static_files::handle_assets))
+        // Applied before the merges below so those routes keep their own,
+        // larger limits (an outer limit would cap them at MAX_BODY_SIZE).
+        .layer(RequestBodyLimitLayer::new(MAX_BODY_SIZE))
+        // ── Config PUT and attachment upload with larger body limits ──
         .merge(config_put_router)
+        .merge(attachment_router)
+        // ── SPA fallback: non-API GET requests serve index.html ──
+        .fallback(get(static_files::handle_spa_fallback))
         .with_state(state)
-        .layer(RequestBodyLimitLayer::new(MAX_BODY_SIZE))
         .layer(TimeoutLayer::with_status_code(
             StatusCode::REQUEST_TIMEOUT,
             Duration::from_secs(REQUEST_TIMEOUT_SECS),
         ))
-        // ── SPA fallback: non-API GET requests serve index.html ──
-        .fallback(get(static_files::handle_spa_fallback));
-
-    // Run the server
-    axum::serve(
-        listener,
-        app.into_make_service_with_connect_info::<SocketAddr>(),
-    )
-    .await?;
-
-    Ok(())
+        .layer(axum::middleware::from_fn(error::assign_request_id))
 }
 
 // ══════════════════════════════════════════════════════════════════════════════
@@ -718,6 +814,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
 async fn handle_health(State(state): State<AppState>) -> impl IntoResponse {
     let body = serde_json::json!({
         "status": "ok",
+        "version": env!("CARGO_PKG_VERSION"),
         "paired": state.pairing.is_paired(),
         "require_pairing": state.pairing.require_pairing(),
         "runtime": crate::health::snapshot_json(),
@@ -759,11 +856,7 @@ async fn handle_pair(
         client_key_from_request(Some(peer_addr), &headers, state.trust_forwarded_headers);
     if !state.rate_limiter.allow_pair(&rate_key) {
         tracing::warn!("/pair rate limit exceeded");
-        let err = serde_json::json!({
-            "error": "Too many pairing requests. Please retry later.",
-            "retry_after": RATE_LIMIT_WINDOW_SECS,
-        });
-        return (StatusCode::TOO_MANY_REQUESTS, Json(err));
+        return rate_limited_response("Too many pairing requests. Please retry later.");
     }
 
     let code = headers
@@ -782,7 +875,7 @@ async fn handle_pair(
                     "token": token,
                     "message": "Paired for this process, but failed to persist token to config.toml. Check config path and write permissions.",
                 });
-                return (StatusCode::OK, Json(body));
+                return (StatusCode::OK, Json(body)).into_response();
             }
 
             let body = serde_json::json!({
@@ -791,22 +884,28 @@ async fn handle_pair(
                 "token": token,
                 "message": "Save this token — use it as Authorization: Bearer <token>"
             });
-            (StatusCode::OK, Json(body))
+            (StatusCode::OK, Json(body)).into_response()
         }
         Ok(None) => {
             tracing::warn!("🔐 Pairing attempt with invalid code");
-            let err = serde_json::json!({"error": "Invalid pairing code"});
-            (StatusCode::FORBIDDEN, Json(err))
+            ApiError::new(
+                StatusCode::FORBIDDEN,
+                ErrorCode::AuthInvalid,
+                "Invalid pairing code",
+            )
+            .into_response()
         }
         Err(lockout_secs) => {
             tracing::warn!(
                 "🔐 Pairing locked out — too many failed attempts ({lockout_secs}s remaining)"
             );
-            let err = serde_json::json!({
-                "error": format!("Too many failed attempts. Try again in {lockout_secs}s."),
-                "retry_after": lockout_secs
-            });
-            (StatusCode::TOO_MANY_REQUESTS, Json(err))
+            ApiError::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                ErrorCode::RateLimited,
+                format!("Too many failed attempts. Try again in {lockout_secs}s."),
+            )
+            .with_retry_after(lockout_secs)
+            .into_response()
         }
     }
 }
@@ -862,13 +961,47 @@ async fn run_gateway_chat_simple(state: &AppState, message: &str) -> anyhow::Res
 /// Full-featured chat with tools for channel handlers (WhatsApp, Linq, Nextcloud Talk).
 async fn run_gateway_chat_with_tools(state: &AppState, message: &str) -> anyhow::Result<String> {
     let config = state.config.lock().clone();
-    crate::agent::process_message(config, message).await
+    let reply = Box::pin(crate::agent::process_message(config, message)).await?;
+    Ok(crate::security::redaction::apply(
+        crate::security::redaction::RedactionPath::Outbound,
+        reply,
+    ))
+}
+
+/// Look up the `/api/attachment` uploads referenced by an inbound message;
+/// fails on the first unknown or expired reference.
+pub(crate) fn resolve_attachments(
+    state: &AppState,
+    references: &[String],
+) -> Result<Vec<crate::attachments::Attachment>> {
+    if references.is_empty() {
+        return Ok(Vec::new());
+    }
+    let store = {
+        let config = state.config.lock();
+        crate::attachments::AttachmentStore::new(
+            &config.workspace_dir,
+            config.gateway.attachment_retention_hours,
+        )
+    };
+    store.resolve(references)
 }
 
 /// Webhook request body
 #[derive(serde::Deserialize)]
 pub struct WebhookBody {
     pub message: String,
+    /// Uploads from `POST /api/attachment`, as ids or `attachment://` URIs.
+    #[serde(default)]
+    pub attachment_ids: Vec<String>,
+    /// Answer with the agent's reply (default). `false` answers `202` with
+    /// the message id right away; the reply is recorded in the session.
+    #[serde(default)]
+    pub wait_for_reply: Option<bool>,
+    /// Seconds to wait for the reply before answering `202`, capped at
+    /// [`WEBHOOK_MAX_WAIT_SECS`] (also the default).
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
 }
 
 /// POST /webhook — main webhook endpoint
@@ -882,11 +1015,7 @@ async fn handle_webhook(
         client_key_from_request(Some(peer_addr), &headers, state.trust_forwarded_headers);
     if !state.rate_limiter.allow_webhook(&rate_key) {
         tracing::warn!("/webhook rate limit exceeded");
-        let err = serde_json::json!({
-            "error": "Too many webhook requests. Please retry later.",
-            "retry_after": RATE_LIMIT_WINDOW_SECS,
-        });
-        return (StatusCode::TOO_MANY_REQUESTS, Json(err));
+        return rate_limited_response("Too many webhook requests. Please retry later.");
     }
 
     // ── Bearer token auth (pairing) ──
@@ -898,10 +1027,10 @@ async fn handle_webhook(
         let token = auth.strip_prefix("Bearer ").unwrap_or("");
         if !state.pairing.is_authenticated(token) {
             tracing::warn!("Webhook: rejected — not paired / invalid bearer token");
-            let err = serde_json::json!({
-                "error": "Unauthorized — pair first via POST /pair, then send Authorization: Bearer <token>"
-            });
-            return (StatusCode::UNAUTHORIZED, Json(err));
+            return ApiError::auth_invalid(
+                "Unauthorized — pair first via POST /pair, then send Authorization: Bearer <token>",
+            )
+            .into_response();
         }
     }
 
@@ -917,8 +1046,10 @@ async fn handle_webhook(
             Some(val) if constant_time_eq(&val, secret_hash.as_ref()) => {}
             _ => {
                 tracing::warn!("Webhook: rejected request — invalid or missing X-Webhook-Secret");
-                let err = serde_json::json!({"error": "Unauthorized — invalid or missing X-Webhook-Secret header"});
-                return (StatusCode::UNAUTHORIZED, Json(err));
+                return ApiError::auth_invalid(
+                    "Unauthorized — invalid or missing X-Webhook-Secret header",
+                )
+                .into_response();
             }
         }
     }
@@ -928,10 +1059,10 @@ async fn handle_webhook(
         Ok(b) => b,
         Err(e) => {
             tracing::warn!("Webhook JSON parse error: {e}");
-            let err = serde_json::json!({
-                "error": "Invalid JSON body. Expected: {\"message\": \"...\"}"
-            });
-            return (StatusCode::BAD_REQUEST, Json(err));
+            return ApiError::payload_invalid(
+                "Invalid JSON body. Expected: {\"message\": \"...\"}",
+            )
+            .into_response();
         }
     };
 
@@ -949,11 +1080,21 @@ async fn handle_webhook(
                 "idempotent": true,
                 "message": "Request already processed for this idempotency key"
             });
-            return (StatusCode::OK, Json(body));
+            return (StatusCode::OK, Json(body)).into_response();
         }
     }
 
-    let message = &webhook_body.message;
+    // ── Attachments ──
+    let attachments = match resolve_attachments(&state, &webhook_body.attachment_ids) {
+        Ok(attachments) => attachments,
+        Err(e) => return ApiError::payload_invalid(format!("{e:#}")).into_response(),
+    };
+    let message = &crate::attachments::with_attachments(&webhook_body.message, &attachments);
+
+    let slot = match state.inbound_queue.try_enqueue() {
+        Ok(slot) => slot,
+        Err(full) => return busy_response(full),
+    };
 
     if state.auto_save {
         let key = webhook_memory_key();
@@ -963,6 +1104,113 @@ async fn handle_webhook(
             .await;
     }
 
+    // The turn runs in its own task so a caller that stops waiting (timeout,
+    // `wait_for_reply: false`, disconnect) does not cancel it; the reply still
+    // lands in the session.
+    let message_id = error::current_request_id().unwrap_or_else(|| Uuid::new_v4().to_string());
+    let session_key = format!("webhook_{message_id}");
+    let turn = tokio::spawn(
+        error::with_request_id(
+            message_id.clone(),
+            run_webhook_turn(state.clone(), slot, message.clone(), session_key.clone()),
+        )
+        .in_current_span(),
+    );
+    if !webhook_body.wait_for_reply.unwrap_or(true) {
+        return webhook_accepted_response(&message_id, &session_key);
+    }
+
+    let wait_secs = webhook_body
+        .timeout_secs
+        .unwrap_or(WEBHOOK_MAX_WAIT_SECS)
+        .clamp(1, WEBHOOK_MAX_WAIT_SECS);
+    match tokio::time::timeout(Duration::from_secs(wait_secs), turn).await {
+        Ok(Ok(Ok(reply))) => {
+            let body = serde_json::json!({
+                "id": message_id,
+                "session_key": session_key,
+                "response": reply.content,
+                "model": state.model,
+                "reply": reply,
+            });
+            (StatusCode::OK, Json(body)).into_response()
+        }
+        Ok(Ok(Err(e))) => e.into_response(),
+        Ok(Err(e)) => {
+            tracing::error!("Webhook turn task failed: {e}");
+            ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorCode::UpstreamFailed,
+                "Webhook turn failed",
+            )
+            .into_response()
+        }
+        Err(_) => {
+            tracing::info!("Webhook reply not ready after {wait_secs}s; answering 202");
+            webhook_accepted_response(&message_id, &session_key)
+        }
+    }
+}
+
+/// Agent reply returned inline by `/webhook`.
+#[derive(Debug, Clone, serde::Serialize)]
+struct WebhookReply {
+    /// Session message id; `None` when session persistence is unavailable.
+    id: Option<i64>,
+    content: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// `202` for a webhook turn still running: the accepted message id and the
+/// session the reply will be recorded in.
+fn webhook_accepted_response(message_id: &str, session_key: &str) -> axum::response::Response {
+    let body = serde_json::json!({
+        "status": "accepted",
+        "id": message_id,
+        "session_key": session_key,
+        "poll": format!("/api/sessions/{session_key}/export?format=json"),
+    });
+    (StatusCode::ACCEPTED, Json(body)).into_response()
+}
+
+/// Record a webhook turn in its session, when the session store is running.
+async fn record_webhook_turn(
+    state: &AppState,
+    session_key: &str,
+    role: &str,
+    content: &str,
+) -> Option<i64> {
+    let workspace_dir = state.config.lock().workspace_dir.clone();
+    let store = crate::sessions::store_for(&workspace_dir)?;
+    let (key, role, content) = (
+        session_key.to_string(),
+        role.to_string(),
+        content.to_string(),
+    );
+    crate::sessions::blocking(store, move |store| {
+        store.append_message_with_metadata(
+            &key,
+            &role,
+            &content,
+            None,
+            &std::collections::HashMap::new(),
+        )
+    })
+    .await
+    .map_err(|e| tracing::warn!("Failed to record webhook turn in {session_key}: {e}"))
+    .ok()
+}
+
+/// One `/webhook` turn: wait for a worker, ask the model, record both sides
+/// in the session.
+async fn run_webhook_turn(
+    state: AppState,
+    slot: queue::QueueSlot,
+    message: String,
+    session_key: String,
+) -> Result<WebhookReply, ApiError> {
+    record_webhook_turn(&state, &session_key, "user", &message).await;
+
     let provider_label = state
         .config
         .lock()
@@ -986,7 +1234,7 @@ async fn handle_webhook(
             messages_count: 1,
         });
 
-    match run_gateway_chat_simple(&state, message).await {
+    match slot.run(run_gateway_chat_simple(&state, &message)).await {
         Ok(response) => {
             let duration = started_at.elapsed();
             state
@@ -1013,8 +1261,11 @@ async fn handle_webhook(
                     cost_usd: None,
                 });
 
-            let body = serde_json::json!({"response": response, "model": state.model});
-            (StatusCode::OK, Json(body))
+            Ok(WebhookReply {
+                id: record_webhook_turn(&state, &session_key, "assistant", &response).await,
+                content: response,
+                created_at: chrono::Utc::now(),
+            })
         }
         Err(e) => {
             let duration = started_at.elapsed();
@@ -1038,7 +1289,10 @@ async fn handle_webhook(
                 .observer
                 .record_event(&crate::observability::ObserverEvent::Error {
                     component: "gateway".to_string(),
-                    message: sanitized.clone(),
+                    message: match error::current_request_id() {
+                        Some(request_id) => format!("{sanitized} (request_id={request_id})"),
+                        None => sanitized.clone(),
+                    },
                 });
             state
                 .observer
@@ -1051,556 +1305,90 @@ async fn handle_webhook(
                 });
 
             tracing::error!("Webhook provider error: {}", sanitized);
-            let err = serde_json::json!({"error": "LLM request failed"});
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(err))
+            Err(ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorCode::UpstreamFailed,
+                "LLM request failed",
+            ))
         }
     }
 }
 
-/// `WhatsApp` verification query params
-#[derive(serde::Deserialize)]
-pub struct WhatsAppVerifyQuery {
-    #[serde(rename = "hub.mode")]
-    pub mode: Option<String>,
-    #[serde(rename = "hub.verify_token")]
-    pub verify_token: Option<String>,
-    #[serde(rename = "hub.challenge")]
-    pub challenge: Option<String>,
-}
-
-/// GET /whatsapp — Meta webhook verification
-async fn handle_whatsapp_verify(
-    State(state): State<AppState>,
-    Query(params): Query<WhatsAppVerifyQuery>,
-) -> impl IntoResponse {
-    let Some(ref wa) = state.whatsapp else {
-        return (StatusCode::NOT_FOUND, "WhatsApp not configured".to_string());
-    };
+#[cfg(test)]
+mod tests {
+    use super::test_support::{generate_test_secret, test_connect_info, test_state, MockProvider};
+    use super::*;
+    use crate::memory::{Memory, MemoryCategory, MemoryEntry};
+    use crate::providers::Provider;
+    use async_trait::async_trait;
+    use axum::http::HeaderValue;
+    use axum::response::IntoResponse;
+    use http_body_util::BodyExt;
+    use parking_lot::Mutex;
+    use std::sync::atomic::Ordering;
 
-    // Verify the token matches (constant-time comparison to prevent timing attacks)
-    let token_matches = params
-        .verify_token
-        .as_deref()
-        .is_some_and(|t| constant_time_eq(t, wa.verify_token()));
-    if params.mode.as_deref() == Some("subscribe") && token_matches {
-        if let Some(ch) = params.challenge {
-            tracing::info!("WhatsApp webhook verified successfully");
-            return (StatusCode::OK, ch);
-        }
-        return (StatusCode::BAD_REQUEST, "Missing hub.challenge".to_string());
+    #[test]
+    fn security_body_limit_is_64kb() {
+        assert_eq!(MAX_BODY_SIZE, 65_536);
     }
 
-    tracing::warn!("WhatsApp webhook verification failed — token mismatch");
-    (StatusCode::FORBIDDEN, "Forbidden".to_string())
-}
+    #[test]
+    fn security_timeout_is_30_seconds() {
+        assert_eq!(REQUEST_TIMEOUT_SECS, 30);
+    }
 
-/// Verify `WhatsApp` webhook signature (`X-Hub-Signature-256`).
-/// Returns true if the signature is valid, false otherwise.
-/// See: <https://developers.facebook.com/docs/graph-api/webhooks/getting-started#verification-requests>
-pub fn verify_whatsapp_signature(app_secret: &str, body: &[u8], signature_header: &str) -> bool {
-    use hmac::{Hmac, Mac};
-    use sha2::Sha256;
+    #[test]
+    fn webhook_body_requires_message_field() {
+        let valid = r#"{"message": "hello"}"#;
+        let parsed: Result<WebhookBody, _> = serde_json::from_str(valid);
+        assert!(parsed.is_ok());
+        assert_eq!(parsed.unwrap().message, "hello");
 
-    // Signature format: "sha256=<hex_signature>"
-    let Some(hex_sig) = signature_header.strip_prefix("sha256=") else {
-        return false;
-    };
+        let missing = r#"{"other": "field"}"#;
+        let parsed: Result<WebhookBody, _> = serde_json::from_str(missing);
+        assert!(parsed.is_err());
+    }
 
-    // Decode hex signature
-    let Ok(expected) = hex::decode(hex_sig) else {
-        return false;
-    };
+    #[test]
+    fn bind_policy_matrix() {
+        let config_with = |pairing: bool, public: bool, insecure: bool| {
+            let mut config = Config::default();
+            config.gateway.require_pairing = pairing;
+            config.gateway.allow_public_bind = public;
+            config.gateway.allow_insecure = insecure;
+            config
+        };
 
-    // Compute HMAC-SHA256
-    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(app_secret.as_bytes()) else {
-        return false;
-    };
-    mac.update(body);
+        // Loopback is always allowed, even without pairing.
+        assert!(check_bind_policy("127.0.0.1", &config_with(false, false, false)).is_ok());
+        assert!(check_bind_policy("::1", &config_with(false, false, false)).is_ok());
 
-    // Constant-time comparison
-    mac.verify_slice(&expected).is_ok()
-}
+        // Public bind needs an explicit opt-in (or a tunnel).
+        let err = check_bind_policy("0.0.0.0", &config_with(true, false, false)).unwrap_err();
+        assert!(err.to_string().contains("allow_public_bind"));
+        assert!(check_bind_policy("0.0.0.0", &config_with(true, true, false)).is_ok());
 
-/// POST /whatsapp — incoming message webhook
-async fn handle_whatsapp_message(
-    State(state): State<AppState>,
-    headers: HeaderMap,
-    body: Bytes,
-) -> impl IntoResponse {
-    let Some(ref wa) = state.whatsapp else {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({"error": "WhatsApp not configured"})),
-        );
-    };
+        // ...and without pairing, a second opt-in.
+        let err = check_bind_policy("0.0.0.0", &config_with(false, true, false)).unwrap_err();
+        assert!(err.to_string().contains("allow_insecure"));
+        assert!(check_bind_policy("0.0.0.0", &config_with(false, true, true)).is_ok());
 
-    // ── Security: Verify X-Hub-Signature-256 if app_secret is configured ──
-    if let Some(ref app_secret) = state.whatsapp_app_secret {
-        let signature = headers
-            .get("X-Hub-Signature-256")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
+        let mut tunneled = config_with(false, false, false);
+        tunneled.tunnel.provider = "cloudflare".into();
+        assert!(check_bind_policy("0.0.0.0", &tunneled).is_err());
+        tunneled.gateway.require_pairing = true;
+        assert!(check_bind_policy("0.0.0.0", &tunneled).is_ok());
+    }
 
-        if !verify_whatsapp_signature(app_secret, &body, signature) {
-            tracing::warn!(
-                "WhatsApp webhook signature verification failed (signature: {})",
-                if signature.is_empty() {
-                    "missing"
-                } else {
-                    "invalid"
-                }
-            );
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(serde_json::json!({"error": "Invalid signature"})),
-            );
-        }
-    }
-
-    // Parse JSON body
-    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&body) else {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Invalid JSON payload"})),
-        );
-    };
-
-    // Parse messages from the webhook payload
-    let messages = wa.parse_webhook_payload(&payload);
-
-    if messages.is_empty() {
-        // Acknowledge the webhook even if no messages (could be status updates)
-        return (StatusCode::OK, Json(serde_json::json!({"status": "ok"})));
-    }
-
-    // Process each message
-    for msg in &messages {
-        tracing::info!(
-            "WhatsApp message from {}: {}",
-            msg.sender,
-            truncate_with_ellipsis(&msg.content, 50)
-        );
-
-        // Auto-save to memory
-        if state.auto_save {
-            let key = whatsapp_memory_key(msg);
-            let _ = state
-                .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation, None)
-                .await;
-        }
-
-        match run_gateway_chat_with_tools(&state, &msg.content).await {
-            Ok(response) => {
-                // Send reply via WhatsApp
-                if let Err(e) = wa
-                    .send(&SendMessage::new(response, &msg.reply_target))
-                    .await
-                {
-                    tracing::error!("Failed to send WhatsApp reply: {e}");
-                }
-            }
-            Err(e) => {
-                tracing::error!("LLM error for WhatsApp message: {e:#}");
-                let _ = wa
-                    .send(&SendMessage::new(
-                        "Sorry, I couldn't process your message right now.",
-                        &msg.reply_target,
-                    ))
-                    .await;
-            }
-        }
-    }
-
-    // Acknowledge the webhook
-    (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
-}
-
-/// POST /linq — incoming message webhook (iMessage/RCS/SMS via Linq)
-async fn handle_linq_webhook(
-    State(state): State<AppState>,
-    headers: HeaderMap,
-    body: Bytes,
-) -> impl IntoResponse {
-    let Some(ref linq) = state.linq else {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({"error": "Linq not configured"})),
-        );
-    };
-
-    let body_str = String::from_utf8_lossy(&body);
-
-    // ── Security: Verify X-Webhook-Signature if signing_secret is configured ──
-    if let Some(ref signing_secret) = state.linq_signing_secret {
-        let timestamp = headers
-            .get("X-Webhook-Timestamp")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
-
-        let signature = headers
-            .get("X-Webhook-Signature")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
-
-        if !crate::channels::linq::verify_linq_signature(
-            signing_secret,
-            &body_str,
-            timestamp,
-            signature,
-        ) {
-            tracing::warn!(
-                "Linq webhook signature verification failed (signature: {})",
-                if signature.is_empty() {
-                    "missing"
-                } else {
-                    "invalid"
-                }
-            );
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(serde_json::json!({"error": "Invalid signature"})),
-            );
-        }
-    }
-
-    // Parse JSON body
-    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&body) else {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Invalid JSON payload"})),
-        );
-    };
-
-    // Parse messages from the webhook payload
-    let messages = linq.parse_webhook_payload(&payload);
-
-    if messages.is_empty() {
-        // Acknowledge the webhook even if no messages (could be status/delivery events)
-        return (StatusCode::OK, Json(serde_json::json!({"status": "ok"})));
-    }
-
-    // Process each message
-    for msg in &messages {
-        tracing::info!(
-            "Linq message from {}: {}",
-            msg.sender,
-            truncate_with_ellipsis(&msg.content, 50)
-        );
-
-        // Auto-save to memory
-        if state.auto_save {
-            let key = linq_memory_key(msg);
-            let _ = state
-                .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation, None)
-                .await;
-        }
-
-        // Call the LLM
-        match run_gateway_chat_with_tools(&state, &msg.content).await {
-            Ok(response) => {
-                // Send reply via Linq
-                if let Err(e) = linq
-                    .send(&SendMessage::new(response, &msg.reply_target))
-                    .await
-                {
-                    tracing::error!("Failed to send Linq reply: {e}");
-                }
-            }
-            Err(e) => {
-                tracing::error!("LLM error for Linq message: {e:#}");
-                let _ = linq
-                    .send(&SendMessage::new(
-                        "Sorry, I couldn't process your message right now.",
-                        &msg.reply_target,
-                    ))
-                    .await;
-            }
-        }
-    }
-
-    // Acknowledge the webhook
-    (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
-}
-
-/// GET /wati — WATI webhook verification (echoes hub.challenge)
-async fn handle_wati_verify(
-    State(state): State<AppState>,
-    Query(params): Query<WatiVerifyQuery>,
-) -> impl IntoResponse {
-    if state.wati.is_none() {
-        return (StatusCode::NOT_FOUND, "WATI not configured".to_string());
-    }
-
-    // WATI may use Meta-style webhook verification; echo the challenge
-    if let Some(challenge) = params.challenge {
-        tracing::info!("WATI webhook verified successfully");
-        return (StatusCode::OK, challenge);
-    }
-
-    (StatusCode::BAD_REQUEST, "Missing hub.challenge".to_string())
-}
-
-#[derive(Debug, serde::Deserialize)]
-pub struct WatiVerifyQuery {
-    #[serde(rename = "hub.challenge")]
-    pub challenge: Option<String>,
-}
-
-/// POST /wati — incoming WATI WhatsApp message webhook
-async fn handle_wati_webhook(State(state): State<AppState>, body: Bytes) -> impl IntoResponse {
-    let Some(ref wati) = state.wati else {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({"error": "WATI not configured"})),
-        );
-    };
-
-    // Parse JSON body
-    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&body) else {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Invalid JSON payload"})),
-        );
-    };
-
-    // Parse messages from the webhook payload
-    let messages = wati.parse_webhook_payload(&payload);
-
-    if messages.is_empty() {
-        return (StatusCode::OK, Json(serde_json::json!({"status": "ok"})));
-    }
-
-    // Process each message
-    for msg in &messages {
-        tracing::info!(
-            "WATI message from {}: {}",
-            msg.sender,
-            truncate_with_ellipsis(&msg.content, 50)
-        );
-
-        // Auto-save to memory
-        if state.auto_save {
-            let key = wati_memory_key(msg);
-            let _ = state
-                .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation, None)
-                .await;
-        }
-
-        // Call the LLM
-        match run_gateway_chat_with_tools(&state, &msg.content).await {
-            Ok(response) => {
-                // Send reply via WATI
-                if let Err(e) = wati
-                    .send(&SendMessage::new(response, &msg.reply_target))
-                    .await
-                {
-                    tracing::error!("Failed to send WATI reply: {e}");
-                }
-            }
-            Err(e) => {
-                tracing::error!("LLM error for WATI message: {e:#}");
-                let _ = wati
-                    .send(&SendMessage::new(
-                        "Sorry, I couldn't process your message right now.",
-                        &msg.reply_target,
-                    ))
-                    .await;
-            }
-        }
-    }
-
-    // Acknowledge the webhook
-    (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
-}
-
-/// POST /nextcloud-talk — incoming message webhook (Nextcloud Talk bot API)
-async fn handle_nextcloud_talk_webhook(
-    State(state): State<AppState>,
-    headers: HeaderMap,
-    body: Bytes,
-) -> impl IntoResponse {
-    let Some(ref nextcloud_talk) = state.nextcloud_talk else {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({"error": "Nextcloud Talk not configured"})),
-        );
-    };
-
-    let body_str = String::from_utf8_lossy(&body);
-
-    // ── Security: Verify Nextcloud Talk HMAC signature if secret is configured ──
-    if let Some(ref webhook_secret) = state.nextcloud_talk_webhook_secret {
-        let random = headers
-            .get("X-Nextcloud-Talk-Random")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
-
-        let signature = headers
-            .get("X-Nextcloud-Talk-Signature")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
-
-        if !crate::channels::nextcloud_talk::verify_nextcloud_talk_signature(
-            webhook_secret,
-            random,
-            &body_str,
-            signature,
-        ) {
-            tracing::warn!(
-                "Nextcloud Talk webhook signature verification failed (signature: {})",
-                if signature.is_empty() {
-                    "missing"
-                } else {
-                    "invalid"
-                }
-            );
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(serde_json::json!({"error": "Invalid signature"})),
-            );
-        }
-    }
-
-    // Parse JSON body
-    let Ok(payload) = serde_json::from_slice::<serde_json::Value>(&body) else {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({"error": "Invalid JSON payload"})),
-        );
-    };
-
-    // Parse messages from webhook payload
-    let messages = nextcloud_talk.parse_webhook_payload(&payload);
-    if messages.is_empty() {
-        // Acknowledge webhook even if payload does not contain actionable user messages.
-        return (StatusCode::OK, Json(serde_json::json!({"status": "ok"})));
-    }
-
-    for msg in &messages {
-        tracing::info!(
-            "Nextcloud Talk message from {}: {}",
-            msg.sender,
-            truncate_with_ellipsis(&msg.content, 50)
-        );
-
-        if state.auto_save {
-            let key = nextcloud_talk_memory_key(msg);
-            let _ = state
-                .mem
-                .store(&key, &msg.content, MemoryCategory::Conversation, None)
-                .await;
-        }
-
-        match run_gateway_chat_with_tools(&state, &msg.content).await {
-            Ok(response) => {
-                if let Err(e) = nextcloud_talk
-                    .send(&SendMessage::new(response, &msg.reply_target))
-                    .await
-                {
-                    tracing::error!("Failed to send Nextcloud Talk reply: {e}");
-                }
-            }
-            Err(e) => {
-                tracing::error!("LLM error for Nextcloud Talk message: {e:#}");
-                let _ = nextcloud_talk
-                    .send(&SendMessage::new(
-                        "Sorry, I couldn't process your message right now.",
-                        &msg.reply_target,
-                    ))
-                    .await;
-            }
-        }
-    }
-
-    (StatusCode::OK, Json(serde_json::json!({"status": "ok"})))
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::channels::traits::ChannelMessage;
-    use crate::memory::{Memory, MemoryCategory, MemoryEntry};
-    use crate::providers::Provider;
-    use async_trait::async_trait;
-    use axum::http::HeaderValue;
-    use axum::response::IntoResponse;
-    use http_body_util::BodyExt;
-    use parking_lot::Mutex;
-    use std::sync::atomic::{AtomicUsize, Ordering};
-
-    /// Generate a random hex secret at runtime to avoid hard-coded cryptographic values.
-    fn generate_test_secret() -> String {
-        let bytes: [u8; 32] = rand::random();
-        hex::encode(bytes)
-    }
-
-    #[test]
-    fn security_body_limit_is_64kb() {
-        assert_eq!(MAX_BODY_SIZE, 65_536);
-    }
-
-    #[test]
-    fn security_timeout_is_30_seconds() {
-        assert_eq!(REQUEST_TIMEOUT_SECS, 30);
-    }
-
-    #[test]
-    fn webhook_body_requires_message_field() {
-        let valid = r#"{"message": "hello"}"#;
-        let parsed: Result<WebhookBody, _> = serde_json::from_str(valid);
-        assert!(parsed.is_ok());
-        assert_eq!(parsed.unwrap().message, "hello");
-
-        let missing = r#"{"other": "field"}"#;
-        let parsed: Result<WebhookBody, _> = serde_json::from_str(missing);
-        assert!(parsed.is_err());
-    }
-
-    #[test]
-    fn whatsapp_query_fields_are_optional() {
-        let q = WhatsAppVerifyQuery {
-            mode: None,
-            verify_token: None,
-            challenge: None,
-        };
-        assert!(q.mode.is_none());
-    }
-
-    #[test]
-    fn app_state_is_clone() {
-        fn assert_clone<T: Clone>() {}
-        assert_clone::<AppState>();
+    #[test]
+    fn app_state_is_clone() {
+        fn assert_clone<T: Clone>() {}
+        assert_clone::<AppState>();
     }
 
     #[tokio::test]
     async fn metrics_endpoint_returns_hint_when_prometheus_is_disabled() {
-        let state = AppState {
-            config: Arc::new(Mutex::new(Config::default())),
-            provider: Arc::new(MockProvider::default()),
-            model: "test-model".into(),
-            temperature: 0.0,
-            mem: Arc::new(MockMemory),
-            auto_save: false,
-            webhook_secret_hash: None,
-            pairing: Arc::new(PairingGuard::new(false, &[])),
-            trust_forwarded_headers: false,
-            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
-            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
-            whatsapp: None,
-            whatsapp_app_secret: None,
-            linq: None,
-            linq_signing_secret: None,
-            nextcloud_talk: None,
-            nextcloud_talk_webhook_secret: None,
-            wati: None,
-            observer: Arc::new(crate::observability::NoopObserver),
-            tools_registry: Arc::new(Vec::new()),
-            cost_tracker: None,
-            event_tx: tokio::sync::broadcast::channel(16).0,
-        };
+        let state = test_state();
 
         let response = handle_metrics(State(state)).await.into_response();
         assert_eq!(response.status(), StatusCode::OK);
@@ -1627,28 +1415,8 @@ mod tests {
 
         let observer: Arc<dyn crate::observability::Observer> = prom;
         let state = AppState {
-            config: Arc::new(Mutex::new(Config::default())),
-            provider: Arc::new(MockProvider::default()),
-            model: "test-model".into(),
-            temperature: 0.0,
-            mem: Arc::new(MockMemory),
-            auto_save: false,
-            webhook_secret_hash: None,
-            pairing: Arc::new(PairingGuard::new(false, &[])),
-            trust_forwarded_headers: false,
-            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
-            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
-            whatsapp: None,
-            whatsapp_app_secret: None,
-            linq: None,
-            linq_signing_secret: None,
-            nextcloud_talk: None,
-            nextcloud_talk_webhook_secret: None,
-            wati: None,
             observer,
-            tools_registry: Arc::new(Vec::new()),
-            cost_tracker: None,
-            event_tx: tokio::sync::broadcast::channel(16).0,
+            ..test_state()
         };
 
         let response = handle_metrics(State(state)).await.into_response();
@@ -1659,93 +1427,6 @@ mod tests {
         assert!(text.contains("zeroclaw_heartbeat_ticks_total 1"));
     }
 
-    #[test]
-    fn gateway_rate_limiter_blocks_after_limit() {
-        let limiter = GatewayRateLimiter::new(2, 2, 100);
-        assert!(limiter.allow_pair("127.0.0.1"));
-        assert!(limiter.allow_pair("127.0.0.1"));
-        assert!(!limiter.allow_pair("127.0.0.1"));
-    }
-
-    #[test]
-    fn rate_limiter_sweep_removes_stale_entries() {
-        let limiter = SlidingWindowRateLimiter::new(10, Duration::from_secs(60), 100);
-        // Add entries for multiple IPs
-        assert!(limiter.allow("ip-1"));
-        assert!(limiter.allow("ip-2"));
-        assert!(limiter.allow("ip-3"));
-
-        {
-            let guard = limiter.requests.lock();
-            assert_eq!(guard.0.len(), 3);
-        }
-
-        // Force a sweep by backdating last_sweep
-        {
-            let mut guard = limiter.requests.lock();
-            guard.1 = Instant::now()
-                .checked_sub(Duration::from_secs(RATE_LIMITER_SWEEP_INTERVAL_SECS + 1))
-                .unwrap();
-            // Clear timestamps for ip-2 and ip-3 to simulate stale entries
-            guard.0.get_mut("ip-2").unwrap().clear();
-            guard.0.get_mut("ip-3").unwrap().clear();
-        }
-
-        // Next allow() call should trigger sweep and remove stale entries
-        assert!(limiter.allow("ip-1"));
-
-        {
-            let guard = limiter.requests.lock();
-            assert_eq!(guard.0.len(), 1, "Stale entries should have been swept");
-            assert!(guard.0.contains_key("ip-1"));
-        }
-    }
-
-    #[test]
-    fn rate_limiter_zero_limit_always_allows() {
-        let limiter = SlidingWindowRateLimiter::new(0, Duration::from_secs(60), 10);
-        for _ in 0..100 {
-            assert!(limiter.allow("any-key"));
-        }
-    }
-
-    #[test]
-    fn idempotency_store_rejects_duplicate_key() {
-        let store = IdempotencyStore::new(Duration::from_secs(30), 10);
-        assert!(store.record_if_new("req-1"));
-        assert!(!store.record_if_new("req-1"));
-        assert!(store.record_if_new("req-2"));
-    }
-
-    #[test]
-    fn rate_limiter_bounded_cardinality_evicts_oldest_key() {
-        let limiter = SlidingWindowRateLimiter::new(5, Duration::from_secs(60), 2);
-        assert!(limiter.allow("ip-1"));
-        assert!(limiter.allow("ip-2"));
-        assert!(limiter.allow("ip-3"));
-
-        let guard = limiter.requests.lock();
-        assert_eq!(guard.0.len(), 2);
-        assert!(guard.0.contains_key("ip-2"));
-        assert!(guard.0.contains_key("ip-3"));
-    }
-
-    #[test]
-    fn idempotency_store_bounded_cardinality_evicts_oldest_key() {
-        let store = IdempotencyStore::new(Duration::from_secs(300), 2);
-        assert!(store.record_if_new("k1"));
-        std::thread::sleep(Duration::from_millis(2));
-        assert!(store.record_if_new("k2"));
-        std::thread::sleep(Duration::from_millis(2));
-        assert!(store.record_if_new("k3"));
-
-        let keys = store.keys.lock();
-        assert_eq!(keys.len(), 2);
-        assert!(!keys.contains_key("k1"));
-        assert!(keys.contains_key("k2"));
-        assert!(keys.contains_key("k3"));
-    }
-
     #[test]
     fn client_key_defaults_to_peer_addr_when_untrusted_proxy_mode() {
         let peer = SocketAddr::from(([10, 0, 0, 5], 42617));
@@ -1837,94 +1518,6 @@ mod tests {
         assert_ne!(key1, key2);
     }
 
-    #[test]
-    fn whatsapp_memory_key_includes_sender_and_message_id() {
-        let msg = ChannelMessage {
-            id: "wamid-123".into(),
-            sender: "+1234567890".into(),
-            reply_target: "+1234567890".into(),
-            content: "hello".into(),
-            channel: "whatsapp".into(),
-            timestamp: 1,
-            thread_ts: None,
-        };
-
-        let key = whatsapp_memory_key(&msg);
-        assert_eq!(key, "whatsapp_+1234567890_wamid-123");
-    }
-
-    #[derive(Default)]
-    struct MockMemory;
-
-    #[async_trait]
-    impl Memory for MockMemory {
-        fn name(&self) -> &str {
-            "mock"
-        }
-
-        async fn store(
-            &self,
-            _key: &str,
-            _content: &str,
-            _category: MemoryCategory,
-            _session_id: Option<&str>,
-        ) -> anyhow::Result<()> {
-            Ok(())
-        }
-
-        async fn recall(
-            &self,
-            _query: &str,
-            _limit: usize,
-            _session_id: Option<&str>,
-        ) -> anyhow::Result<Vec<MemoryEntry>> {
-            Ok(Vec::new())
-        }
-
-        async fn get(&self, _key: &str) -> anyhow::Result<Option<MemoryEntry>> {
-            Ok(None)
-        }
-
-        async fn list(
-            &self,
-            _category: Option<&MemoryCategory>,
-            _session_id: Option<&str>,
-        ) -> anyhow::Result<Vec<MemoryEntry>> {
-            Ok(Vec::new())
-        }
-
-        async fn forget(&self, _key: &str) -> anyhow::Result<bool> {
-            Ok(false)
-        }
-
-        async fn count(&self) -> anyhow::Result<usize> {
-            Ok(0)
-        }
-
-        async fn health_check(&self) -> bool {
-            true
-        }
-    }
-
-    #[derive(Default)]
-    struct MockProvider {
-        calls: AtomicUsize,
-    }
-
-    #[async_trait]
-    impl Provider for MockProvider {
-        async fn chat_with_system(
-            &self,
-            _system_prompt: Option<&str>,
-            _message: &str,
-            _model: &str,
-            _temperature: f64,
-        ) -> anyhow::Result<String> {
-            self.calls.fetch_add(1, Ordering::SeqCst);
-            Ok("ok".into())
-        }
-    }
-
     #[derive(Default)]
     struct TrackingMemory {
         keys: Mutex<Vec<String>>,
@@ -1982,39 +1575,14 @@ mod tests {
         }
     }
 
-    fn test_connect_info() -> ConnectInfo<SocketAddr> {
-        ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 30_300)))
-    }
-
     #[tokio::test]
     async fn webhook_idempotency_skips_duplicate_provider_calls() {
         let provider_impl = Arc::new(MockProvider::default());
         let provider: Arc<dyn Provider> = provider_impl.clone();
-        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
 
         let state = AppState {
-            config: Arc::new(Mutex::new(Config::default())),
             provider,
-            model: "test-model".into(),
-            temperature: 0.0,
-            mem: memory,
-            auto_save: false,
-            webhook_secret_hash: None,
-            pairing: Arc::new(PairingGuard::new(false, &[])),
-            trust_forwarded_headers: false,
-            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
-            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
-            whatsapp: None,
-            whatsapp_app_secret: None,
-            linq: None,
-            linq_signing_secret: None,
-            nextcloud_talk: None,
-            nextcloud_talk_webhook_secret: None,
-            wati: None,
-            observer: Arc::new(crate::observability::NoopObserver),
-            tools_registry: Arc::new(Vec::new()),
-            cost_tracker: None,
-            event_tx: tokio::sync::broadcast::channel(16).0,
+            ..test_state()
         };
 
         let mut headers = HeaderMap::new();
@@ -2022,6 +1590,9 @@ mod tests {
 
         let body = Ok(Json(WebhookBody {
             message: "hello".into(),
+            attachment_ids: Vec::new(),
+            wait_for_reply: None,
+            timeout_secs: None,
         }));
         let first = handle_webhook(
             State(state.clone()),
@@ -2035,6 +1606,9 @@ mod tests {
 
         let body = Ok(Json(WebhookBody {
             message: "hello".into(),
+            attachment_ids: Vec::new(),
+            wait_for_reply: None,
+            timeout_secs: None,
         }));
         let second = handle_webhook(State(state), test_connect_info(), headers, body)
             .await
@@ -2048,6 +1622,59 @@ mod tests {
         assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 1);
     }
 
+    #[tokio::test]
+    async fn webhook_returns_busy_when_inbound_queue_is_full() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let inbound_queue = Arc::new(InboundQueue::new(1, 0));
+
+        let state = AppState {
+            provider,
+            inbound_queue: Arc::clone(&inbound_queue),
+            ..test_state()
+        };
+
+        let held = inbound_queue.try_enqueue().unwrap();
+        let body = Ok(Json(WebhookBody {
+            message: "hello".into(),
+            attachment_ids: Vec::new(),
+            wait_for_reply: None,
+            timeout_secs: None,
+        }));
+        let busy = handle_webhook(
+            State(state.clone()),
+            test_connect_info(),
+            HeaderMap::new(),
+            body,
+        )
+        .await
+        .into_response();
+        assert_eq!(busy.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            busy.headers().get(header::RETRY_AFTER).unwrap(),
+            &BUSY_RETRY_AFTER_SECS.to_string()
+        );
+        let payload = busy.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["error"], "busy");
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
+        assert_eq!(inbound_queue.rejected(), 1);
+
+        drop(held);
+        let body = Ok(Json(WebhookBody {
+            message: "hello".into(),
+            attachment_ids: Vec::new(),
+            wait_for_reply: None,
+            timeout_secs: None,
+        }));
+        let ok = handle_webhook(State(state), test_connect_info(), HeaderMap::new(), body)
+            .await
+            .into_response();
+        assert_eq!(ok.status(), StatusCode::OK);
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(inbound_queue.depth(), 0);
+    }
+
     #[tokio::test]
     async fn webhook_autosave_stores_distinct_keys_per_request() {
         let provider_impl = Arc::new(MockProvider::default());
@@ -2057,34 +1684,19 @@ mod tests {
         let memory: Arc<dyn Memory> = tracking_impl.clone();
 
         let state = AppState {
-            config: Arc::new(Mutex::new(Config::default())),
             provider,
-            model: "test-model".into(),
-            temperature: 0.0,
             mem: memory,
             auto_save: true,
-            webhook_secret_hash: None,
-            pairing: Arc::new(PairingGuard::new(false, &[])),
-            trust_forwarded_headers: false,
-            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
-            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
-            whatsapp: None,
-            whatsapp_app_secret: None,
-            linq: None,
-            linq_signing_secret: None,
-            nextcloud_talk: None,
-            nextcloud_talk_webhook_secret: None,
-            wati: None,
-            observer: Arc::new(crate::observability::NoopObserver),
-            tools_registry: Arc::new(Vec::new()),
-            cost_tracker: None,
-            event_tx: tokio::sync::broadcast::channel(16).0,
+            ..test_state()
         };
 
         let headers = HeaderMap::new();
 
         let body1 = Ok(Json(WebhookBody {
             message: "hello one".into(),
+            attachment_ids: Vec::new(),
+            wait_for_reply: None,
+            timeout_secs: None,
         }));
         let first = handle_webhook(
             State(state.clone()),
@@ -2098,6 +1710,9 @@ mod tests {
 
         let body2 = Ok(Json(WebhookBody {
             message: "hello two".into(),
+            attachment_ids: Vec::new(),
+            wait_for_reply: None,
+            timeout_secs: None,
         }));
         let second = handle_webhook(State(state), test_connect_info(), headers, body2)
             .await
@@ -2129,32 +1744,12 @@ mod tests {
     async fn webhook_secret_hash_rejects_missing_header() {
         let provider_impl = Arc::new(MockProvider::default());
         let provider: Arc<dyn Provider> = provider_impl.clone();
-        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
         let secret = generate_test_secret();
 
         let state = AppState {
-            config: Arc::new(Mutex::new(Config::default())),
             provider,
-            model: "test-model".into(),
-            temperature: 0.0,
-            mem: memory,
-            auto_save: false,
             webhook_secret_hash: Some(Arc::from(hash_webhook_secret(&secret))),
-            pairing: Arc::new(PairingGuard::new(false, &[])),
-            trust_forwarded_headers: false,
-            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
-            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
-            whatsapp: None,
-            whatsapp_app_secret: None,
-            linq: None,
-            linq_signing_secret: None,
-            nextcloud_talk: None,
-            nextcloud_talk_webhook_secret: None,
-            wati: None,
-            observer: Arc::new(crate::observability::NoopObserver),
-            tools_registry: Arc::new(Vec::new()),
-            cost_tracker: None,
-            event_tx: tokio::sync::broadcast::channel(16).0,
+            ..test_state()
         };
 
         let response = handle_webhook(
@@ -2163,6 +1758,9 @@ mod tests {
             HeaderMap::new(),
             Ok(Json(WebhookBody {
                 message: "hello".into(),
+                attachment_ids: Vec::new(),
+                wait_for_reply: None,
+                timeout_secs: None,
             })),
         )
         .await
@@ -2176,33 +1774,13 @@ mod tests {
     async fn webhook_secret_hash_rejects_invalid_header() {
         let provider_impl = Arc::new(MockProvider::default());
         let provider: Arc<dyn Provider> = provider_impl.clone();
-        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
         let valid_secret = generate_test_secret();
         let wrong_secret = generate_test_secret();
 
         let state = AppState {
-            config: Arc::new(Mutex::new(Config::default())),
             provider,
-            model: "test-model".into(),
-            temperature: 0.0,
-            mem: memory,
-            auto_save: false,
             webhook_secret_hash: Some(Arc::from(hash_webhook_secret(&valid_secret))),
-            pairing: Arc::new(PairingGuard::new(false, &[])),
-            trust_forwarded_headers: false,
-            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
-            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
-            whatsapp: None,
-            whatsapp_app_secret: None,
-            linq: None,
-            linq_signing_secret: None,
-            nextcloud_talk: None,
-            nextcloud_talk_webhook_secret: None,
-            wati: None,
-            observer: Arc::new(crate::observability::NoopObserver),
-            tools_registry: Arc::new(Vec::new()),
-            cost_tracker: None,
-            event_tx: tokio::sync::broadcast::channel(16).0,
+            ..test_state()
         };
 
         let mut headers = HeaderMap::new();
@@ -2217,6 +1795,9 @@ mod tests {
             headers,
             Ok(Json(WebhookBody {
                 message: "hello".into(),
+                attachment_ids: Vec::new(),
+                wait_for_reply: None,
+                timeout_secs: None,
             })),
         )
         .await
@@ -2230,32 +1811,12 @@ mod tests {
     async fn webhook_secret_hash_accepts_valid_header() {
         let provider_impl = Arc::new(MockProvider::default());
         let provider: Arc<dyn Provider> = provider_impl.clone();
-        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
         let secret = generate_test_secret();
 
         let state = AppState {
-            config: Arc::new(Mutex::new(Config::default())),
             provider,
-            model: "test-model".into(),
-            temperature: 0.0,
-            mem: memory,
-            auto_save: false,
             webhook_secret_hash: Some(Arc::from(hash_webhook_secret(&secret))),
-            pairing: Arc::new(PairingGuard::new(false, &[])),
-            trust_forwarded_headers: false,
-            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
-            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
-            whatsapp: None,
-            whatsapp_app_secret: None,
-            linq: None,
-            linq_signing_secret: None,
-            nextcloud_talk: None,
-            nextcloud_talk_webhook_secret: None,
-            wati: None,
-            observer: Arc::new(crate::observability::NoopObserver),
-            tools_registry: Arc::new(Vec::new()),
-            cost_tracker: None,
-            event_tx: tokio::sync::broadcast::channel(16).0,
+            ..test_state()
         };
 
         let mut headers = HeaderMap::new();
@@ -2267,6 +1828,9 @@ mod tests {
             headers,
             Ok(Json(WebhookBody {
                 message: "hello".into(),
+                attachment_ids: Vec::new(),
+                wait_for_reply: None,
+                timeout_secs: None,
             })),
         )
         .await
@@ -2276,502 +1840,551 @@ mod tests {
         assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 1);
     }
 
-    fn compute_nextcloud_signature_hex(secret: &str, random: &str, body: &str) -> String {
-        use hmac::{Hmac, Mac};
-        use sha2::Sha256;
-
-        let payload = format!("{random}{body}");
-        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
-        mac.update(payload.as_bytes());
-        hex::encode(mac.finalize().into_bytes())
+    /// Answers `"late"` once `release` is notified.
+    struct GatedProvider {
+        release: Arc<tokio::sync::Notify>,
     }
 
-    #[tokio::test]
-    async fn nextcloud_talk_webhook_returns_not_found_when_not_configured() {
-        let provider: Arc<dyn Provider> = Arc::new(MockProvider::default());
-        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
+    #[async_trait]
+    impl Provider for GatedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.release.notified().await;
+            Ok("late".into())
+        }
+    }
 
+    /// State whose workspace has a registered session store.
+    fn session_state(
+        tmp: &tempfile::TempDir,
+    ) -> (AppState, Arc<crate::sessions::SqliteSessionStore>) {
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        let store = Arc::new(crate::sessions::SqliteSessionStore::open(tmp.path()).unwrap());
+        crate::sessions::register_store(tmp.path(), Arc::clone(&store));
         let state = AppState {
-            config: Arc::new(Mutex::new(Config::default())),
-            provider,
-            model: "test-model".into(),
-            temperature: 0.0,
-            mem: memory,
-            auto_save: false,
-            webhook_secret_hash: None,
-            pairing: Arc::new(PairingGuard::new(false, &[])),
-            trust_forwarded_headers: false,
-            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
-            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
-            whatsapp: None,
-            whatsapp_app_secret: None,
-            linq: None,
-            linq_signing_secret: None,
-            nextcloud_talk: None,
-            nextcloud_talk_webhook_secret: None,
-            wati: None,
-            observer: Arc::new(crate::observability::NoopObserver),
-            tools_registry: Arc::new(Vec::new()),
-            cost_tracker: None,
-            event_tx: tokio::sync::broadcast::channel(16).0,
+            config: Arc::new(Mutex::new(config)),
+            ..test_state()
         };
+        (state, store)
+    }
+
+    fn webhook_body(wait_for_reply: Option<bool>, timeout_secs: Option<u64>) -> WebhookBody {
+        WebhookBody {
+            message: "hello".into(),
+            attachment_ids: Vec::new(),
+            wait_for_reply,
+            timeout_secs,
+        }
+    }
+
+    #[tokio::test]
+    async fn webhook_returns_reply_with_message_id_and_session() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (state, store) = session_state(&tmp);
 
-        let response = handle_nextcloud_talk_webhook(
+        let response = handle_webhook(
             State(state),
+            test_connect_info(),
             HeaderMap::new(),
-            Bytes::from_static(br#"{"type":"message"}"#),
+            Ok(Json(webhook_body(None, None))),
         )
         .await
         .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let payload = response.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
 
-        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let id = parsed["id"].as_str().unwrap();
+        let session_key = parsed["session_key"].as_str().unwrap();
+        assert_eq!(session_key, format!("webhook_{id}"));
+        assert_eq!(parsed["response"], "ok");
+        assert_eq!(parsed["reply"]["content"], "ok");
+        assert!(parsed["reply"]["id"].is_i64());
+        assert!(parsed["reply"]["created_at"].is_string());
+
+        let history = store.load_history(session_key, None).unwrap();
+        let turns: Vec<_> = history
+            .iter()
+            .map(|m| (m.role.as_str(), m.content.as_str()))
+            .collect();
+        assert_eq!(turns, [("user", "hello"), ("assistant", "ok")]);
     }
 
     #[tokio::test]
-    async fn nextcloud_talk_webhook_rejects_invalid_signature() {
-        let provider_impl = Arc::new(MockProvider::default());
-        let provider: Arc<dyn Provider> = provider_impl.clone();
-        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
-
-        let channel = Arc::new(NextcloudTalkChannel::new(
-            "https://cloud.example.com".into(),
-            "app-token".into(),
-            vec!["*".into()],
-        ));
-
-        let secret = "nextcloud-test-secret";
-        let random = "seed-value";
-        let body = r#"{"type":"message","object":{"token":"room-token"},"message":{"actorType":"users","actorId":"user_a","message":"hello"}}"#;
-        let _valid_signature = compute_nextcloud_signature_hex(secret, random, body);
-        let invalid_signature = "deadbeef";
-
+    async fn webhook_accepts_slow_turns_and_records_the_reply_later() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (state, store) = session_state(&tmp);
+        let release = Arc::new(tokio::sync::Notify::new());
+        let inbound_queue = Arc::new(InboundQueue::new(1, 1));
         let state = AppState {
-            config: Arc::new(Mutex::new(Config::default())),
-            provider,
-            model: "test-model".into(),
-            temperature: 0.0,
-            mem: memory,
-            auto_save: false,
-            webhook_secret_hash: None,
-            pairing: Arc::new(PairingGuard::new(false, &[])),
-            trust_forwarded_headers: false,
-            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
-            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
-            whatsapp: None,
-            whatsapp_app_secret: None,
-            linq: None,
-            linq_signing_secret: None,
-            nextcloud_talk: Some(channel),
-            nextcloud_talk_webhook_secret: Some(Arc::from(secret)),
-            wati: None,
-            observer: Arc::new(crate::observability::NoopObserver),
-            tools_registry: Arc::new(Vec::new()),
-            cost_tracker: None,
-            event_tx: tokio::sync::broadcast::channel(16).0,
+            provider: Arc::new(GatedProvider {
+                release: Arc::clone(&release),
+            }),
+            inbound_queue: Arc::clone(&inbound_queue),
+            ..state
         };
 
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            "X-Nextcloud-Talk-Random",
-            HeaderValue::from_str(random).unwrap(),
-        );
-        headers.insert(
-            "X-Nextcloud-Talk-Signature",
-            HeaderValue::from_str(invalid_signature).unwrap(),
+        let response = handle_webhook(
+            State(state.clone()),
+            test_connect_info(),
+            HeaderMap::new(),
+            Ok(Json(webhook_body(None, Some(1)))),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let payload = response.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["status"], "accepted");
+        let session_key = parsed["session_key"].as_str().unwrap().to_string();
+        assert_eq!(
+            parsed["poll"],
+            format!("/api/sessions/{session_key}/export?format=json")
         );
 
-        let response = handle_nextcloud_talk_webhook(State(state), headers, Bytes::from(body))
-            .await
-            .into_response();
-        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
-        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
-    }
-
-    // ══════════════════════════════════════════════════════════
-    // WhatsApp Signature Verification Tests (CWE-345 Prevention)
-    // ══════════════════════════════════════════════════════════
-
-    fn compute_whatsapp_signature_hex(secret: &str, body: &[u8]) -> String {
-        use hmac::{Hmac, Mac};
-        use sha2::Sha256;
-
-        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
-        mac.update(body);
-        hex::encode(mac.finalize().into_bytes())
-    }
-
-    fn compute_whatsapp_signature_header(secret: &str, body: &[u8]) -> String {
-        format!("sha256={}", compute_whatsapp_signature_hex(secret, body))
-    }
-
-    #[test]
-    fn whatsapp_signature_valid() {
-        let app_secret = generate_test_secret();
-        let body = b"test body content";
-
-        let signature_header = compute_whatsapp_signature_header(&app_secret, body);
+        // The abandoned turn still holds its queue slot until it finishes.
+        assert_eq!(inbound_queue.depth(), 1);
+        release.notify_one();
+        let mut history = Vec::new();
+        for _ in 0..200 {
+            history = store.load_history(&session_key, None).unwrap();
+            if history.len() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(history.last().map(|m| m.content.as_str()), Some("late"));
+        assert_eq!(inbound_queue.depth(), 0);
 
-        assert!(verify_whatsapp_signature(
-            &app_secret,
-            body,
-            &signature_header
-        ));
+        // Not waiting at all answers 202 before the model is asked.
+        let response = handle_webhook(
+            State(state),
+            test_connect_info(),
+            HeaderMap::new(),
+            Ok(Json(webhook_body(Some(false), None))),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        release.notify_one();
     }
 
-    #[test]
-    fn whatsapp_signature_invalid_wrong_secret() {
-        let app_secret = generate_test_secret();
-        let wrong_secret = generate_test_secret();
-        let body = b"test body content";
+    // ── Router-level tests (full middleware stack, in-memory state) ──
 
-        let signature_header = compute_whatsapp_signature_header(&wrong_secret, body);
+    async fn send(
+        app: Router,
+        mut request: axum::http::Request<axum::body::Body>,
+    ) -> (StatusCode, String) {
+        use tower::ServiceExt;
 
-        assert!(!verify_whatsapp_signature(
-            &app_secret,
-            body,
-            &signature_header
-        ));
+        request.extensions_mut().insert(test_connect_info());
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, String::from_utf8(body.to_vec()).unwrap())
     }
 
-    #[test]
-    fn whatsapp_signature_invalid_wrong_body() {
-        let app_secret = generate_test_secret();
-        let original_body = b"original body";
-        let tampered_body = b"tampered body";
-
-        let signature_header = compute_whatsapp_signature_header(&app_secret, original_body);
-
-        // Verify with tampered body should fail
-        assert!(!verify_whatsapp_signature(
-            &app_secret,
-            tampered_body,
-            &signature_header
-        ));
+    fn get_request(uri: &str) -> axum::http::Request<axum::body::Body> {
+        axum::http::Request::get(uri)
+            .body(axum::body::Body::empty())
+            .unwrap()
     }
 
-    #[test]
-    fn whatsapp_signature_missing_prefix() {
-        let app_secret = generate_test_secret();
-        let body = b"test body";
-
-        // Signature without "sha256=" prefix
-        let signature_header = "abc123def456";
-
-        assert!(!verify_whatsapp_signature(
-            &app_secret,
-            body,
-            signature_header
-        ));
+    fn webhook_request(
+        headers: &[(&str, &str)],
+        message: &str,
+    ) -> axum::http::Request<axum::body::Body> {
+        let mut builder =
+            axum::http::Request::post("/webhook").header(header::CONTENT_TYPE, "application/json");
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder
+            .body(axum::body::Body::from(
+                serde_json::json!({ "message": message }).to_string(),
+            ))
+            .unwrap()
     }
 
-    #[test]
-    fn whatsapp_signature_empty_header() {
-        let app_secret = generate_test_secret();
-        let body = b"test body";
-
-        assert!(!verify_whatsapp_signature(&app_secret, body, ""));
-    }
+    #[tokio::test]
+    async fn router_api_requires_valid_bearer_token() {
+        let state = AppState {
+            pairing: Arc::new(PairingGuard::new(true, &["zc_router_token".into()])),
+            ..test_state()
+        };
+        let app = build_router(state);
 
-    #[test]
-    fn whatsapp_signature_invalid_hex() {
-        let app_secret = generate_test_secret();
-        let body = b"test body";
+        let (status, body) = send(app.clone(), get_request("/api/tools")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(body.contains("pair first via POST /pair"));
 
-        // Invalid hex characters
-        let signature_header = "sha256=not_valid_hex_zzz";
+        let wrong = axum::http::Request::get("/api/tools")
+            .header(header::AUTHORIZATION, "Bearer zc_wrong")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let (status, _) = send(app.clone(), wrong).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
 
-        assert!(!verify_whatsapp_signature(
-            &app_secret,
-            body,
-            signature_header
-        ));
+        let valid = axum::http::Request::get("/api/tools")
+            .header(header::AUTHORIZATION, "Bearer zc_router_token")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let (status, body) = send(app, valid).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, r#"{"tools":[]}"#);
     }
 
-    #[test]
-    fn whatsapp_signature_empty_body() {
-        let app_secret = generate_test_secret();
-        let body = b"";
-
-        let signature_header = compute_whatsapp_signature_header(&app_secret, body);
+    #[tokio::test]
+    async fn router_webhook_rejects_unpaired_client_before_provider_call() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let state = AppState {
+            provider,
+            pairing: Arc::new(PairingGuard::new(true, &["zc_router_token".into()])),
+            ..test_state()
+        };
 
-        assert!(verify_whatsapp_signature(
-            &app_secret,
-            body,
-            &signature_header
-        ));
+        let (status, body) = send(build_router(state), webhook_request(&[], "hello")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["error"], "auth_invalid");
+        assert_eq!(
+            parsed["message"],
+            "Unauthorized — pair first via POST /pair, then send Authorization: Bearer <token>"
+        );
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
     }
 
-    #[test]
-    fn whatsapp_signature_unicode_body() {
-        let app_secret = generate_test_secret();
-        let body = "Hello 🦀 World".as_bytes();
+    #[tokio::test]
+    async fn router_webhook_dedupes_by_idempotency_key() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let app = build_router(AppState {
+            provider,
+            ..test_state()
+        });
+        let headers = [("X-Idempotency-Key", "router-key-1")];
 
-        let signature_header = compute_whatsapp_signature_header(&app_secret, body);
+        let (status, body) = send(app.clone(), webhook_request(&headers, "hello")).await;
+        assert_eq!(status, StatusCode::OK);
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["model"], "test-model");
+        assert_eq!(parsed["response"], "ok");
 
-        assert!(verify_whatsapp_signature(
-            &app_secret,
-            body,
-            &signature_header
-        ));
+        let (status, body) = send(app, webhook_request(&headers, "hello")).await;
+        assert_eq!(status, StatusCode::OK);
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["status"], "duplicate");
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 1);
     }
 
-    #[test]
-    fn whatsapp_signature_json_payload() {
-        let app_secret = generate_test_secret();
-        let body = br#"{"entry":[{"changes":[{"value":{"messages":[{"from":"1234567890","text":{"body":"Hello"}}]}}]}]}"#;
+    #[derive(Clone, Default)]
+    struct LogBuffer(Arc<Mutex<Vec<u8>>>);
 
-        let signature_header = compute_whatsapp_signature_header(&app_secret, body);
+    impl std::io::Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
 
-        assert!(verify_whatsapp_signature(
-            &app_secret,
-            body,
-            &signature_header
-        ));
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
     }
 
-    #[test]
-    fn whatsapp_signature_case_sensitive_prefix() {
-        let app_secret = generate_test_secret();
-        let body = b"test body";
-
-        let hex_sig = compute_whatsapp_signature_hex(&app_secret, body);
-
-        // Wrong case prefix should fail
-        let wrong_prefix = format!("SHA256={hex_sig}");
-        assert!(!verify_whatsapp_signature(&app_secret, body, &wrong_prefix));
-
-        // Correct prefix should pass
-        let correct_prefix = format!("sha256={hex_sig}");
-        assert!(verify_whatsapp_signature(
-            &app_secret,
-            body,
-            &correct_prefix
-        ));
+    /// Route `tracing` output on this thread into a buffer.
+    fn capture_logs() -> (tracing::subscriber::DefaultGuard, LogBuffer) {
+        let buffer = LogBuffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        (tracing::subscriber::set_default(subscriber), buffer)
     }
 
-    #[test]
-    fn whatsapp_signature_truncated_hex() {
-        let app_secret = generate_test_secret();
-        let body = b"test body";
-
-        let hex_sig = compute_whatsapp_signature_hex(&app_secret, body);
-        let truncated = &hex_sig[..32]; // Only half the signature
-        let signature_header = format!("sha256={truncated}");
+    async fn send_with_headers(
+        app: Router,
+        mut request: axum::http::Request<axum::body::Body>,
+    ) -> (StatusCode, HeaderMap, serde_json::Value) {
+        use tower::ServiceExt;
 
-        assert!(!verify_whatsapp_signature(
-            &app_secret,
-            body,
-            &signature_header
-        ));
+        request.extensions_mut().insert(test_connect_info());
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, headers, serde_json::from_slice(&body).unwrap())
     }
 
-    #[test]
-    fn whatsapp_signature_extra_bytes() {
-        let app_secret = generate_test_secret();
-        let body = b"test body";
-
-        let hex_sig = compute_whatsapp_signature_hex(&app_secret, body);
-        let extended = format!("{hex_sig}deadbeef");
-        let signature_header = format!("sha256={extended}");
+    #[tokio::test]
+    async fn router_error_body_request_id_matches_header_and_logs() {
+        let (_guard, logs) = capture_logs();
+        let secret = generate_test_secret();
+        let app = build_router(AppState {
+            webhook_secret_hash: Some(Arc::from(hash_webhook_secret(&secret))),
+            ..test_state()
+        });
 
-        assert!(!verify_whatsapp_signature(
-            &app_secret,
-            body,
-            &signature_header
-        ));
+        let (status, headers, body) =
+            send_with_headers(app, webhook_request(&[("X-Webhook-Secret", "wrong")], "hi")).await;
+
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"], "auth_invalid");
+        assert!(body["message"]
+            .as_str()
+            .unwrap()
+            .contains("X-Webhook-Secret"));
+        let request_id = body["request_id"].as_str().unwrap();
+        assert_eq!(headers[error::REQUEST_ID_HEADER], request_id);
+        let logs = String::from_utf8(logs.0.lock().clone()).unwrap();
+        let rejection = logs
+            .lines()
+            .find(|line| line.contains("invalid or missing X-Webhook-Secret"))
+            .unwrap_or_else(|| panic!("rejection not logged: {logs}"));
+        assert!(
+            rejection.contains(&format!("request_id={request_id}")),
+            "{rejection}"
+        );
     }
 
-    // ══════════════════════════════════════════════════════════
-    // IdempotencyStore Edge-Case Tests
-    // ══════════════════════════════════════════════════════════
-
-    #[test]
-    fn idempotency_store_allows_different_keys() {
-        let store = IdempotencyStore::new(Duration::from_secs(60), 100);
-        assert!(store.record_if_new("key-a"));
-        assert!(store.record_if_new("key-b"));
-        assert!(store.record_if_new("key-c"));
-        assert!(store.record_if_new("key-d"));
-    }
+    #[tokio::test]
+    async fn router_platform_webhooks_return_structured_errors() {
+        let app = build_router(AppState {
+            whatsapp: Some(Arc::new(WhatsAppChannel::new(
+                "access-token".into(),
+                "123456".into(),
+                "verify-me".into(),
+                vec!["*".into()],
+            ))),
+            whatsapp_app_secret: Some(Arc::from("app-secret")),
+            ..test_state()
+        });
+        let whatsapp = |signature: &str, body: &str| {
+            axum::http::Request::post("/whatsapp")
+                .header("X-Hub-Signature-256", signature)
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap()
+        };
 
-    #[test]
-    fn idempotency_store_max_keys_clamped_to_one() {
-        let store = IdempotencyStore::new(Duration::from_secs(60), 0);
-        assert!(store.record_if_new("only-key"));
-        assert!(!store.record_if_new("only-key"));
-    }
+        let (status, _, body) = send_with_headers(app.clone(), whatsapp("sha256=00", "{}")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"], "signature_invalid");
 
-    #[test]
-    fn idempotency_store_rapid_duplicate_rejected() {
-        let store = IdempotencyStore::new(Duration::from_secs(300), 100);
-        assert!(store.record_if_new("rapid"));
-        assert!(!store.record_if_new("rapid"));
+        let raw = "not json";
+        let signature = {
+            use hmac::{Hmac, Mac};
+            let mut mac = Hmac::<sha2::Sha256>::new_from_slice(b"app-secret").unwrap();
+            mac.update(raw.as_bytes());
+            format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+        };
+        let (status, _, body) = send_with_headers(app.clone(), whatsapp(&signature, raw)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "payload_invalid");
+
+        let (status, _, body) = send_with_headers(
+            app,
+            axum::http::Request::post("/linq")
+                .body(axum::body::Body::from("{}"))
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "not_configured");
+        assert_eq!(body["request_id"].as_str().unwrap().len(), 36);
     }
 
-    #[test]
-    fn idempotency_store_accepts_after_ttl_expires() {
-        let store = IdempotencyStore::new(Duration::from_millis(1), 100);
-        assert!(store.record_if_new("ttl-key"));
-        std::thread::sleep(Duration::from_millis(10));
-        assert!(store.record_if_new("ttl-key"));
+    #[tokio::test]
+    async fn router_rejects_body_over_limit() {
+        let oversized = "x".repeat(MAX_BODY_SIZE + 1);
+        let length = (oversized.len() + r#"{"message":""}"#.len()).to_string();
+        let request = webhook_request(&[(header::CONTENT_LENGTH.as_str(), &length)], &oversized);
+        let (status, _) = send(build_router(test_state()), request).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
     }
 
-    #[test]
-    fn idempotency_store_eviction_preserves_newest() {
-        let store = IdempotencyStore::new(Duration::from_secs(300), 1);
-        assert!(store.record_if_new("old-key"));
-        std::thread::sleep(Duration::from_millis(2));
-        assert!(store.record_if_new("new-key"));
-
-        let keys = store.keys.lock();
-        assert_eq!(keys.len(), 1);
-        assert!(!keys.contains_key("old-key"));
-        assert!(keys.contains_key("new-key"));
+    /// Records the user message of every call.
+    #[derive(Default)]
+    struct CapturingProvider {
+        messages: Mutex<Vec<String>>,
     }
 
-    #[test]
-    fn rate_limiter_allows_after_window_expires() {
-        let window = Duration::from_millis(50);
-        let limiter = SlidingWindowRateLimiter::new(2, window, 100);
-        assert!(limiter.allow("ip-1"));
-        assert!(limiter.allow("ip-1"));
-        assert!(!limiter.allow("ip-1")); // blocked
-
-        // Wait for window to expire
-        std::thread::sleep(Duration::from_millis(60));
-
-        // Should be allowed again
-        assert!(limiter.allow("ip-1"));
+    #[async_trait]
+    impl Provider for CapturingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.messages.lock().push(message.to_string());
+            Ok("ok".into())
+        }
     }
 
-    #[test]
-    fn rate_limiter_independent_keys_tracked_separately() {
-        let limiter = SlidingWindowRateLimiter::new(2, Duration::from_secs(60), 100);
-        assert!(limiter.allow("ip-1"));
-        assert!(limiter.allow("ip-1"));
-        assert!(!limiter.allow("ip-1")); // ip-1 blocked
+    #[tokio::test]
+    async fn router_attachment_upload_is_described_to_agent_and_readable() {
+        use crate::tools::Tool;
 
-        // ip-2 should still work
-        assert!(limiter.allow("ip-2"));
-        assert!(limiter.allow("ip-2"));
-        assert!(!limiter.allow("ip-2")); // ip-2 now blocked
-    }
+        let tmp = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        let provider = Arc::new(CapturingProvider::default());
+        let state = AppState {
+            config: Arc::new(Mutex::new(config.clone())),
+            provider: provider.clone(),
+            ..test_state()
+        };
+        let app = build_router(state);
 
-    #[test]
-    fn rate_limiter_exact_boundary_at_max_keys() {
-        let limiter = SlidingWindowRateLimiter::new(10, Duration::from_secs(60), 3);
-        assert!(limiter.allow("ip-1"));
-        assert!(limiter.allow("ip-2"));
-        assert!(limiter.allow("ip-3"));
-        // At capacity now
-        assert!(limiter.allow("ip-4")); // should evict ip-1
-
-        let guard = limiter.requests.lock();
-        assert_eq!(guard.0.len(), 3);
+        // Larger than MAX_BODY_SIZE: uploads have their own limit.
+        let content = "meeting notes\n".repeat(6_000);
+        let upload = axum::http::Request::post("/api/attachment?filename=notes.txt")
+            .header(header::CONTENT_TYPE, "text/plain")
+            .body(axum::body::Body::from(content.clone()))
+            .unwrap();
+        let (status, body) = send(app.clone(), upload).await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        let uploaded: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let uri = uploaded["uri"].as_str().unwrap().to_string();
+        assert!(uri.starts_with("attachment://"));
+        assert_eq!(uploaded["size_bytes"], content.len());
+        assert!(uploaded["message"].as_str().unwrap().contains("expires at"));
+
+        let request = axum::http::Request::post("/webhook")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(axum::body::Body::from(
+                serde_json::json!({"message": "summarize this", "attachment_ids": [uri]})
+                    .to_string(),
+            ))
+            .unwrap();
+        let (status, body) = send(app.clone(), request).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        let seen = provider.messages.lock().pop().unwrap();
         assert!(
-            !guard.0.contains_key("ip-1"),
-            "ip-1 should have been evicted"
+            seen.starts_with("summarize this\n\n[Attachments]\n"),
+            "{seen}"
         );
-        assert!(guard.0.contains_key("ip-2"));
-        assert!(guard.0.contains_key("ip-3"));
-        assert!(guard.0.contains_key("ip-4"));
-    }
-
-    #[test]
-    fn gateway_rate_limiter_pair_and_webhook_are_independent() {
-        let limiter = GatewayRateLimiter::new(2, 3, 100);
-
-        // Exhaust pair limit
-        assert!(limiter.allow_pair("ip-1"));
-        assert!(limiter.allow_pair("ip-1"));
-        assert!(!limiter.allow_pair("ip-1")); // pair blocked
+        assert!(seen.contains(&format!("notes.txt (text/plain, {} bytes)", content.len())));
+        assert!(seen.contains("read_attachment"));
 
-        // Webhook should still work
-        assert!(limiter.allow_webhook("ip-1"));
-        assert!(limiter.allow_webhook("ip-1"));
-        assert!(limiter.allow_webhook("ip-1"));
-        assert!(!limiter.allow_webhook("ip-1")); // webhook now blocked
-    }
-
-    #[test]
-    fn rate_limiter_single_key_max_allows_one_request() {
-        let limiter = SlidingWindowRateLimiter::new(5, Duration::from_secs(60), 1);
-        assert!(limiter.allow("ip-1"));
-        assert!(limiter.allow("ip-2")); // evicts ip-1
-
-        let guard = limiter.requests.lock();
-        assert_eq!(guard.0.len(), 1);
-        assert!(guard.0.contains_key("ip-2"));
-        assert!(!guard.0.contains_key("ip-1"));
+        let tool = crate::tools::ReadAttachmentTool::new(
+            Arc::new(SecurityPolicy::default()),
+            tmp.path(),
+            config.gateway.attachment_retention_hours,
+        );
+        let result = tool
+            .execute(serde_json::json!({"id": uploaded["id"]}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, content);
+
+        let unknown = axum::http::Request::post("/webhook")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(axum::body::Body::from(
+                serde_json::json!({
+                    "message": "hi",
+                    "attachment_ids": ["attachment://00000000-0000-0000-0000-000000000000"]
+                })
+                .to_string(),
+            ))
+            .unwrap();
+        let (status, body) = send(app, unknown).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("unknown or expired attachment"), "{body}");
+        assert_eq!(provider.messages.lock().len(), 0);
     }
 
-    #[test]
-    fn rate_limiter_concurrent_access_safe() {
-        use std::sync::Arc;
-
-        let limiter = Arc::new(SlidingWindowRateLimiter::new(
-            1000,
-            Duration::from_secs(60),
-            1000,
-        ));
-        let mut handles = Vec::new();
-
-        for i in 0..10 {
-            let limiter = limiter.clone();
-            handles.push(std::thread::spawn(move || {
-                for j in 0..100 {
-                    limiter.allow(&format!("thread-{i}-req-{j}"));
-                }
-            }));
-        }
+    #[tokio::test]
+    async fn router_whatsapp_verify_handshake() {
+        let state = AppState {
+            whatsapp: Some(Arc::new(WhatsAppChannel::new(
+                "access-token".into(),
+                "123456".into(),
+                "verify-me".into(),
+                vec!["*".into()],
+            ))),
+            ..test_state()
+        };
+        let app = build_router(state);
 
-        for handle in handles {
-            handle.join().unwrap();
-        }
+        let (status, body) = send(
+            app.clone(),
+            get_request(
+                "/whatsapp?hub.mode=subscribe&hub.verify_token=verify-me&hub.challenge=1158201444",
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "1158201444");
 
-        // Should not panic or deadlock
-        let guard = limiter.requests.lock();
-        assert!(guard.0.len() <= 1000, "should respect max_keys");
+        let (status, body) = send(
+            app.clone(),
+            get_request("/whatsapp?hub.mode=subscribe&hub.verify_token=wrong&hub.challenge=1"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["error"], "auth_invalid");
+
+        let (status, body) = send(
+            app,
+            get_request("/whatsapp?hub.mode=subscribe&hub.verify_token=verify-me"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["error"], "payload_invalid");
+        assert_eq!(parsed["message"], "Missing hub.challenge");
     }
 
-    #[test]
-    fn idempotency_store_concurrent_access_safe() {
-        use std::sync::Arc;
-
-        let store = Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000));
-        let mut handles = Vec::new();
-
-        for i in 0..10 {
-            let store = store.clone();
-            handles.push(std::thread::spawn(move || {
-                for j in 0..100 {
-                    store.record_if_new(&format!("thread-{i}-key-{j}"));
-                }
-            }));
-        }
+    #[tokio::test]
+    async fn router_unconfigured_channels_return_not_found() {
+        let app = build_router(test_state());
 
-        for handle in handles {
-            handle.join().unwrap();
-        }
+        let (status, body) = send(
+            app.clone(),
+            get_request("/whatsapp?hub.mode=subscribe&hub.verify_token=x&hub.challenge=1"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["error"], "not_configured");
+        assert_eq!(parsed["message"], "WhatsApp not configured");
 
-        let keys = store.keys.lock();
-        assert!(keys.len() <= 1000, "should respect max_keys");
+        let (status, body) = send(app, get_request("/wati?hub.challenge=abc")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["message"], "WATI not configured");
     }
 
-    #[test]
-    fn rate_limiter_rapid_burst_then_cooldown() {
-        let limiter = SlidingWindowRateLimiter::new(5, Duration::from_millis(50), 100);
-
-        // Burst: use all 5 requests
-        for _ in 0..5 {
-            assert!(limiter.allow("burst-ip"));
-        }
-        assert!(!limiter.allow("burst-ip")); // 6th should fail
-
-        // Cooldown
-        std::thread::sleep(Duration::from_millis(60));
+    #[tokio::test]
+    async fn router_wati_verify_echoes_challenge() {
+        let state = AppState {
+            wati: Some(Arc::new(WatiChannel::new(
+                "api-token".into(),
+                "https://live-mt-server.wati.io".into(),
+                None,
+                vec!["*".into()],
+            ))),
+            ..test_state()
+        };
 
-        // Should be allowed again
-        assert!(limiter.allow("burst-ip"));
+        let (status, body) =
+            send(build_router(state), get_request("/wati?hub.challenge=abc")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "abc");
     }
 }
@@ -0,0 +1,256 @@
+//! Bounded admission queue for inbound agent turns.
+//!
+//! Every gateway entry point that runs the agent (`/webhook`, platform
+//! webhooks, WebSocket chat) takes a slot here first. At most `workers` turns
+//! run at once and up to `capacity` more wait in FIFO order. When the queue is
+//! full the caller gets [`QueueFull`] and must answer "busy" explicitly, so a
+//! slow model never makes inbound messages disappear.
+
+use crate::observability::traits::ObserverMetric;
+use crate::observability::{NoopObserver, Observer, ObserverEvent};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// Reply sent on platform channels when the queue rejects a message.
+pub const BUSY_REPLY: &str =
+    "I'm handling a lot of messages right now. Please try again in a minute.";
+
+/// Returned by [`InboundQueue::try_enqueue`] when no slot is free.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueFull {
+    pub max_depth: usize,
+}
+
+impl std::fmt::Display for QueueFull {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "inbound queue full ({} turns pending)", self.max_depth)
+    }
+}
+
+impl std::error::Error for QueueFull {}
+
+pub struct InboundQueue {
+    workers: Semaphore,
+    max_depth: usize,
+    depth: AtomicUsize,
+    accepted: AtomicU64,
+    rejected: AtomicU64,
+    observer: Arc<dyn Observer>,
+}
+
+impl InboundQueue {
+    /// `workers` turns run concurrently (minimum 1); `capacity` more may wait.
+    pub fn new(workers: usize, capacity: usize) -> Self {
+        let workers = workers.max(1);
+        Self {
+            workers: Semaphore::new(workers),
+            max_depth: workers.saturating_add(capacity),
+            depth: AtomicUsize::new(0),
+            accepted: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+            observer: Arc::new(NoopObserver),
+        }
+    }
+
+    /// Report queue depth and rejections to `observer`.
+    pub fn with_observer(mut self, observer: Arc<dyn Observer>) -> Self {
+        self.observer = observer;
+        self
+    }
+
+    /// Reserve a slot, or fail immediately when the queue is full.
+    ///
+    /// The returned slot's [`position`](QueueSlot::position) is 1-based and
+    /// counts the turns running or waiting ahead of it, plus itself. The slot
+    /// keeps the queue alive, so it can move into a spawned task.
+    pub fn try_enqueue(self: &Arc<Self>) -> Result<QueueSlot, QueueFull> {
+        let reserved = self
+            .depth
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |d| {
+                (d < self.max_depth).then_some(d + 1)
+            });
+        match reserved {
+            Ok(previous) => {
+                self.accepted.fetch_add(1, Ordering::Relaxed);
+                self.record_depth(previous + 1);
+                Ok(QueueSlot {
+                    queue: Arc::clone(self),
+                    position: previous + 1,
+                })
+            }
+            Err(_) => {
+                self.rejected.fetch_add(1, Ordering::Relaxed);
+                let full = QueueFull {
+                    max_depth: self.max_depth,
+                };
+                tracing::warn!("Gateway: {full}, rejecting inbound message");
+                self.observer.record_event(&ObserverEvent::Error {
+                    component: "gateway.queue".to_string(),
+                    message: full.to_string(),
+                });
+                Err(full)
+            }
+        }
+    }
+
+    /// Turns currently running or waiting.
+    pub fn depth(&self) -> usize {
+        self.depth.load(Ordering::Acquire)
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Total slots handed out since startup.
+    pub fn accepted(&self) -> u64 {
+        self.accepted.load(Ordering::Relaxed)
+    }
+
+    /// Total messages turned away because the queue was full.
+    pub fn rejected(&self) -> u64 {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    fn record_depth(&self, depth: usize) {
+        self.observer
+            .record_metric(&ObserverMetric::QueueDepth(depth as u64));
+    }
+}
+
+/// A reserved place in the queue. Dropping it frees the place.
+pub struct QueueSlot {
+    queue: Arc<InboundQueue>,
+    position: usize,
+}
+
+impl QueueSlot {
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Wait for a worker in FIFO order, then drive `turn` to completion.
+    pub async fn run<F: Future>(self, turn: F) -> F::Output {
+        if self.queue.workers.available_permits() == 0 {
+            tracing::debug!("Gateway: inbound turn queued at position {}", self.position);
+        }
+        // The semaphore is never closed, so acquire only fails on shutdown.
+        let _permit = self.queue.workers.acquire().await.ok();
+        turn.await
+    }
+}
+
+impl Drop for QueueSlot {
+    fn drop(&mut self) {
+        let previous = self.queue.depth.fetch_sub(1, Ordering::AcqRel);
+        self.queue.record_depth(previous.saturating_sub(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn rejects_once_workers_and_capacity_are_taken() {
+        let queue = Arc::new(InboundQueue::new(1, 2));
+        let a = queue.try_enqueue().unwrap();
+        let b = queue.try_enqueue().unwrap();
+        let c = queue.try_enqueue().unwrap();
+        assert_eq!((a.position(), b.position(), c.position()), (1, 2, 3));
+        assert_eq!(queue.try_enqueue().err(), Some(QueueFull { max_depth: 3 }));
+        assert_eq!(queue.rejected(), 1);
+
+        drop(b);
+        assert_eq!(queue.depth(), 2);
+        assert_eq!(queue.try_enqueue().unwrap().position(), 3);
+    }
+
+    #[test]
+    fn zero_workers_still_runs_one_turn() {
+        let queue = Arc::new(InboundQueue::new(0, 0));
+        let _slot = queue.try_enqueue().unwrap();
+        assert!(queue.try_enqueue().is_err());
+    }
+
+    #[tokio::test]
+    async fn waiting_turns_run_in_fifo_order() {
+        let queue = Arc::new(InboundQueue::new(1, 8));
+        let order = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+        let gate = {
+            let queue = Arc::clone(&queue);
+            tokio::spawn(async move {
+                let slot = queue.try_enqueue().unwrap();
+                slot.run(async {
+                    let _ = release_rx.await;
+                })
+                .await;
+            })
+        };
+        // Let the gate take the only worker.
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let mut handles = Vec::new();
+        for i in 0..5 {
+            let queue = Arc::clone(&queue);
+            let order = Arc::clone(&order);
+            handles.push(tokio::spawn(async move {
+                let slot = queue.try_enqueue().unwrap();
+                slot.run(async { order.lock().push(i) }).await;
+            }));
+            // Let each task reach the semaphore before spawning the next.
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        release_tx.send(()).unwrap();
+        gate.await.unwrap();
+        for h in handles {
+            h.await.unwrap();
+        }
+        assert_eq!(*order.lock(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(queue.depth(), 0);
+    }
+
+    /// 1000 messages against a slow consumer: every message is either
+    /// processed or explicitly rejected, never lost.
+    #[tokio::test]
+    async fn load_with_slow_consumer_has_no_silent_losses() {
+        const MESSAGES: usize = 1000;
+        let queue = Arc::new(InboundQueue::new(2, 50));
+        let processed = Arc::new(AtomicUsize::new(0));
+        let rejected = Arc::new(AtomicUsize::new(0));
+
+        let mut handles = Vec::with_capacity(MESSAGES);
+        for _ in 0..MESSAGES {
+            let queue = Arc::clone(&queue);
+            let processed = Arc::clone(&processed);
+            let rejected = Arc::clone(&rejected);
+            handles.push(tokio::spawn(async move {
+                match queue.try_enqueue() {
+                    Ok(slot) => {
+                        slot.run(tokio::time::sleep(Duration::from_millis(1))).await;
+                        processed.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Err(_) => {
+                        rejected.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+            }));
+        }
+        for h in handles {
+            h.await.unwrap();
+        }
+
+        let processed = processed.load(Ordering::SeqCst);
+        let rejected = rejected.load(Ordering::SeqCst);
+        assert_eq!(processed + rejected, MESSAGES);
+        assert!(processed >= queue.max_depth(), "processed {processed}");
+        assert!(rejected > 0, "slow consumer should have pushed back");
+        assert_eq!(queue.accepted(), processed as u64);
+        assert_eq!(queue.rejected(), rejected as u64);
+        assert_eq!(queue.depth(), 0);
+    }
+}
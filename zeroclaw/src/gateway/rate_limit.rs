@@ -0,0 +1,442 @@
+//! Sliding-window rate limiting for `/pair`, `/webhook`, platform webhooks
+//! and `/ws/events` commands.
+
+use super::error::{ApiError, ErrorCode};
+use super::RATE_LIMIT_WINDOW_SECS;
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+/// How often the rate limiter sweeps stale IP entries from its map.
+const RATE_LIMITER_SWEEP_INTERVAL_SECS: u64 = 300; // 5 minutes
+
+#[derive(Debug)]
+pub(crate) struct SlidingWindowRateLimiter {
+    limit_per_window: u32,
+    window: Duration,
+    max_keys: usize,
+    requests: Mutex<(HashMap<String, Vec<Instant>>, Instant)>,
+}
+
+impl SlidingWindowRateLimiter {
+    pub(crate) fn new(limit_per_window: u32, window: Duration, max_keys: usize) -> Self {
+        Self {
+            limit_per_window,
+            window,
+            max_keys: max_keys.max(1),
+            requests: Mutex::new((HashMap::new(), Instant::now())),
+        }
+    }
+
+    fn prune_stale(requests: &mut HashMap<String, Vec<Instant>>, cutoff: Instant) {
+        requests.retain(|_, timestamps| {
+            timestamps.retain(|t| *t > cutoff);
+            !timestamps.is_empty()
+        });
+    }
+
+    pub(crate) fn allow(&self, key: &str) -> bool {
+        if self.limit_per_window == 0 {
+            return true;
+        }
+
+        let now = Instant::now();
+        let cutoff = now.checked_sub(self.window).unwrap_or_else(Instant::now);
+
+        let mut guard = self.requests.lock();
+        let (requests, last_sweep) = &mut *guard;
+
+        // Periodic sweep: remove keys with no recent requests
+        if last_sweep.elapsed() >= Duration::from_secs(RATE_LIMITER_SWEEP_INTERVAL_SECS) {
+            Self::prune_stale(requests, cutoff);
+            *last_sweep = now;
+        }
+
+        if !requests.contains_key(key) && requests.len() >= self.max_keys {
+            // Opportunistic stale cleanup before eviction under cardinality pressure.
+            Self::prune_stale(requests, cutoff);
+            *last_sweep = now;
+
+            if requests.len() >= self.max_keys {
+                let evict_key = requests
+                    .iter()
+                    .min_by_key(|(_, timestamps)| timestamps.last().copied().unwrap_or(cutoff))
+                    .map(|(k, _)| k.clone());
+                if let Some(evict_key) = evict_key {
+                    requests.remove(&evict_key);
+                }
+            }
+        }
+
+        let entry = requests.entry(key.to_owned()).or_default();
+        entry.retain(|instant| *instant > cutoff);
+
+        if entry.len() >= self.limit_per_window as usize {
+            return false;
+        }
+
+        entry.push(now);
+        true
+    }
+}
+
+#[derive(Debug)]
+pub struct GatewayRateLimiter {
+    pair: SlidingWindowRateLimiter,
+    webhook: SlidingWindowRateLimiter,
+    /// Per channel + sender limiter for platform webhooks (WhatsApp, Linq, ...).
+    inbound: SlidingWindowRateLimiter,
+    /// Rejected request counts keyed by route/channel, surfaced via `/api/health`.
+    rejections: Mutex<BTreeMap<String, u64>>,
+}
+
+impl GatewayRateLimiter {
+    pub(crate) fn new(pair_per_minute: u32, webhook_per_minute: u32, max_keys: usize) -> Self {
+        let window = Duration::from_secs(RATE_LIMIT_WINDOW_SECS);
+        Self {
+            pair: SlidingWindowRateLimiter::new(pair_per_minute, window, max_keys),
+            webhook: SlidingWindowRateLimiter::new(webhook_per_minute, window, max_keys),
+            inbound: SlidingWindowRateLimiter::new(0, window, max_keys),
+            rejections: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Enable the per channel + sender inbound limit (`0` leaves it disabled).
+    pub(crate) fn with_inbound_limit(mut self, inbound_per_minute: u32) -> Self {
+        let window = Duration::from_secs(RATE_LIMIT_WINDOW_SECS);
+        self.inbound =
+            SlidingWindowRateLimiter::new(inbound_per_minute, window, self.inbound.max_keys);
+        self
+    }
+
+    pub(crate) fn allow_pair(&self, key: &str) -> bool {
+        self.check("pair", self.pair.allow(key))
+    }
+
+    pub(crate) fn allow_webhook(&self, key: &str) -> bool {
+        self.check("webhook", self.webhook.allow(key))
+    }
+
+    fn allow_inbound(&self, channel: &str, sender: &str) -> bool {
+        let allowed = self.inbound.allow(&format!("{channel}:{sender}"));
+        self.check(channel, allowed)
+    }
+
+    fn check(&self, scope: &str, allowed: bool) -> bool {
+        if !allowed {
+            *self.rejections.lock().entry(scope.to_string()).or_insert(0) += 1;
+        }
+        allowed
+    }
+
+    /// Snapshot of rejected request counts per route/channel.
+    pub fn rejection_counts(&self) -> BTreeMap<String, u64> {
+        self.rejections.lock().clone()
+    }
+}
+
+/// 429 response with a `Retry-After` header for client-facing endpoints.
+pub(crate) fn rate_limited_response(message: &str) -> axum::response::Response {
+    ApiError::new(
+        StatusCode::TOO_MANY_REQUESTS,
+        ErrorCode::RateLimited,
+        message,
+    )
+    .with_retry_after(RATE_LIMIT_WINDOW_SECS)
+    .into_response()
+}
+
+/// Drop messages whose channel + sender exceeded the inbound limit.
+///
+/// Platform webhooks retry on non-2xx, so callers acknowledge with
+/// `200 {"status": "rate_limited"}` instead of a 429 when nothing is left.
+pub(crate) fn retain_inbound_within_limit(
+    limiter: &GatewayRateLimiter,
+    channel: &str,
+    messages: Vec<crate::channels::traits::ChannelMessage>,
+) -> Vec<crate::channels::traits::ChannelMessage> {
+    messages
+        .into_iter()
+        .filter(|msg| {
+            let allowed = limiter.allow_inbound(channel, &msg.sender);
+            if !allowed {
+                tracing::warn!(
+                    "{channel} inbound rate limit exceeded for sender {}",
+                    msg.sender
+                );
+            }
+            allowed
+        })
+        .collect()
+}
+
+pub(crate) fn inbound_rate_limited_ack() -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({"status": "rate_limited"})),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::ChannelMessage;
+    use axum::http::header;
+    use http_body_util::BodyExt;
+
+    #[test]
+    fn gateway_rate_limiter_blocks_after_limit() {
+        let limiter = GatewayRateLimiter::new(2, 2, 100);
+        assert!(limiter.allow_pair("127.0.0.1"));
+        assert!(limiter.allow_pair("127.0.0.1"));
+        assert!(!limiter.allow_pair("127.0.0.1"));
+    }
+
+    #[test]
+    fn rate_limiter_sweep_removes_stale_entries() {
+        let limiter = SlidingWindowRateLimiter::new(10, Duration::from_secs(60), 100);
+        // Add entries for multiple IPs
+        assert!(limiter.allow("ip-1"));
+        assert!(limiter.allow("ip-2"));
+        assert!(limiter.allow("ip-3"));
+
+        {
+            let guard = limiter.requests.lock();
+            assert_eq!(guard.0.len(), 3);
+        }
+
+        // Force a sweep by backdating last_sweep
+        {
+            let mut guard = limiter.requests.lock();
+            guard.1 = Instant::now()
+                .checked_sub(Duration::from_secs(RATE_LIMITER_SWEEP_INTERVAL_SECS + 1))
+                .unwrap();
+            // Clear timestamps for ip-2 and ip-3 to simulate stale entries
+            guard.0.get_mut("ip-2").unwrap().clear();
+            guard.0.get_mut("ip-3").unwrap().clear();
+        }
+
+        // Next allow() call should trigger sweep and remove stale entries
+        assert!(limiter.allow("ip-1"));
+
+        {
+            let guard = limiter.requests.lock();
+            assert_eq!(guard.0.len(), 1, "Stale entries should have been swept");
+            assert!(guard.0.contains_key("ip-1"));
+        }
+    }
+
+    #[test]
+    fn rate_limiter_zero_limit_always_allows() {
+        let limiter = SlidingWindowRateLimiter::new(0, Duration::from_secs(60), 10);
+        for _ in 0..100 {
+            assert!(limiter.allow("any-key"));
+        }
+    }
+
+    #[test]
+    fn rate_limiter_bounded_cardinality_evicts_oldest_key() {
+        let limiter = SlidingWindowRateLimiter::new(5, Duration::from_secs(60), 2);
+        assert!(limiter.allow("ip-1"));
+        assert!(limiter.allow("ip-2"));
+        assert!(limiter.allow("ip-3"));
+
+        let guard = limiter.requests.lock();
+        assert_eq!(guard.0.len(), 2);
+        assert!(guard.0.contains_key("ip-2"));
+        assert!(guard.0.contains_key("ip-3"));
+    }
+
+    #[test]
+    fn rate_limiter_allows_after_window_expires() {
+        let window = Duration::from_millis(50);
+        let limiter = SlidingWindowRateLimiter::new(2, window, 100);
+        assert!(limiter.allow("ip-1"));
+        assert!(limiter.allow("ip-1"));
+        assert!(!limiter.allow("ip-1")); // blocked
+
+        // Wait for window to expire
+        std::thread::sleep(Duration::from_millis(60));
+
+        // Should be allowed again
+        assert!(limiter.allow("ip-1"));
+    }
+
+    #[test]
+    fn rate_limiter_independent_keys_tracked_separately() {
+        let limiter = SlidingWindowRateLimiter::new(2, Duration::from_secs(60), 100);
+        assert!(limiter.allow("ip-1"));
+        assert!(limiter.allow("ip-1"));
+        assert!(!limiter.allow("ip-1")); // ip-1 blocked
+
+        // ip-2 should still work
+        assert!(limiter.allow("ip-2"));
+        assert!(limiter.allow("ip-2"));
+        assert!(!limiter.allow("ip-2")); // ip-2 now blocked
+    }
+
+    #[test]
+    fn rate_limiter_exact_boundary_at_max_keys() {
+        let limiter = SlidingWindowRateLimiter::new(10, Duration::from_secs(60), 3);
+        assert!(limiter.allow("ip-1"));
+        assert!(limiter.allow("ip-2"));
+        assert!(limiter.allow("ip-3"));
+        // At capacity now
+        assert!(limiter.allow("ip-4")); // should evict ip-1
+
+        let guard = limiter.requests.lock();
+        assert_eq!(guard.0.len(), 3);
+        assert!(
+            !guard.0.contains_key("ip-1"),
+            "ip-1 should have been evicted"
+        );
+        assert!(guard.0.contains_key("ip-2"));
+        assert!(guard.0.contains_key("ip-3"));
+        assert!(guard.0.contains_key("ip-4"));
+    }
+
+    #[test]
+    fn gateway_rate_limiter_pair_and_webhook_are_independent() {
+        let limiter = GatewayRateLimiter::new(2, 3, 100);
+
+        // Exhaust pair limit
+        assert!(limiter.allow_pair("ip-1"));
+        assert!(limiter.allow_pair("ip-1"));
+        assert!(!limiter.allow_pair("ip-1")); // pair blocked
+
+        // Webhook should still work
+        assert!(limiter.allow_webhook("ip-1"));
+        assert!(limiter.allow_webhook("ip-1"));
+        assert!(limiter.allow_webhook("ip-1"));
+        assert!(!limiter.allow_webhook("ip-1")); // webhook now blocked
+    }
+
+    #[test]
+    fn gateway_rate_limiter_inbound_is_keyed_by_channel_and_sender() {
+        let limiter = GatewayRateLimiter::new(100, 100, 100).with_inbound_limit(2);
+
+        assert!(limiter.allow_inbound("whatsapp", "+1555"));
+        assert!(limiter.allow_inbound("whatsapp", "+1555"));
+        assert!(!limiter.allow_inbound("whatsapp", "+1555"));
+
+        // Other senders and channels keep their own budget.
+        assert!(limiter.allow_inbound("whatsapp", "+1666"));
+        assert!(limiter.allow_inbound("linq", "+1555"));
+
+        assert_eq!(limiter.rejection_counts().get("whatsapp"), Some(&1));
+        assert_eq!(limiter.rejection_counts().get("linq"), None);
+    }
+
+    #[test]
+    fn gateway_rate_limiter_inbound_disabled_by_default() {
+        let limiter = GatewayRateLimiter::new(100, 100, 100);
+        for _ in 0..50 {
+            assert!(limiter.allow_inbound("wati", "sender"));
+        }
+        assert!(limiter.rejection_counts().is_empty());
+    }
+
+    #[test]
+    fn retain_inbound_within_limit_drops_only_over_limit_senders() {
+        let limiter = GatewayRateLimiter::new(100, 100, 100).with_inbound_limit(1);
+        let message = |id: &str, sender: &str| ChannelMessage {
+            id: id.into(),
+            sender: sender.into(),
+            reply_target: sender.into(),
+            content: "hi".into(),
+            channel: "whatsapp".into(),
+            timestamp: 0,
+            thread_ts: None,
+            sender_name: None,
+            metadata: HashMap::new(),
+        };
+
+        let kept = retain_inbound_within_limit(
+            &limiter,
+            "whatsapp",
+            vec![
+                message("1", "alice"),
+                message("2", "alice"),
+                message("3", "bob"),
+            ],
+        );
+        let ids: Vec<&str> = kept.iter().map(|msg| msg.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "3"]);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_response_sets_retry_after_header() {
+        let response = rate_limited_response("slow down");
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            response.headers().get(header::RETRY_AFTER).unwrap(),
+            &RATE_LIMIT_WINDOW_SECS.to_string()
+        );
+        let payload = response.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(parsed["error"], "rate_limited");
+        assert_eq!(parsed["message"], "slow down");
+    }
+
+    #[test]
+    fn rate_limiter_single_key_max_allows_one_request() {
+        let limiter = SlidingWindowRateLimiter::new(5, Duration::from_secs(60), 1);
+        assert!(limiter.allow("ip-1"));
+        assert!(limiter.allow("ip-2")); // evicts ip-1
+
+        let guard = limiter.requests.lock();
+        assert_eq!(guard.0.len(), 1);
+        assert!(guard.0.contains_key("ip-2"));
+        assert!(!guard.0.contains_key("ip-1"));
+    }
+
+    #[test]
+    fn rate_limiter_concurrent_access_safe() {
+        use std::sync::Arc;
+
+        let limiter = Arc::new(SlidingWindowRateLimiter::new(
+            1000,
+            Duration::from_secs(60),
+            1000,
+        ));
+        let mut handles = Vec::new();
+
+        for i in 0..10 {
+            let limiter = limiter.clone();
+            handles.push(std::thread::spawn(move || {
+                for j in 0..100 {
+                    limiter.allow(&format!("thread-{i}-req-{j}"));
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // Should not panic or deadlock
+        let guard = limiter.requests.lock();
+        assert!(guard.0.len() <= 1000, "should respect max_keys");
+    }
+
+    #[test]
+    fn rate_limiter_rapid_burst_then_cooldown() {
+        let limiter = SlidingWindowRateLimiter::new(5, Duration::from_millis(50), 100);
+
+        // Burst: use all 5 requests
+        for _ in 0..5 {
+            assert!(limiter.allow("burst-ip"));
+        }
+        assert!(!limiter.allow("burst-ip")); // 6th should fail
+
+        // Cooldown
+        std::thread::sleep(Duration::from_millis(60));
+
+        // Should be allowed again
+        assert!(limiter.allow("burst-ip"));
+    }
+}
@@ -0,0 +1,299 @@
+//! Config reload for `PUT /api/control/reload`.
+//!
+//! The handler loads and validates the config file on disk synchronously,
+//! so a typo is reported to the operator instead of failing in the
+//! background. A valid candidate is compared with the running config
+//! ([`diff_configs`], secrets masked) and handed to the reload task, which
+//! swaps the shared config and acknowledges over a oneshot.
+
+use crate::config::Config;
+use anyhow::Result;
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// How long the handler waits for the reload task before answering
+/// "queued" instead of "applied".
+const ACK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A validated config waiting to be applied.
+#[derive(Debug)]
+pub struct Candidate {
+    pub config: Config,
+    /// Keys in the file that no config field reads (typos, deprecated
+    /// options). Reported, but they do not fail the reload.
+    pub unknown_keys: Vec<String>,
+}
+
+/// Load and validate the config file `current` was loaded from. On failure,
+/// returns the issues to report.
+pub async fn load_candidate(current: &Config) -> Result<Candidate, Vec<String>> {
+    let (config, unknown_keys) =
+        Config::read_from_file(&current.config_path, current.workspace_dir.clone())
+            .await
+            .map_err(|e| vec![format!("{e:#}")])?;
+    config.validate().map_err(|e| vec![format!("{e:#}")])?;
+    Ok(Candidate {
+        config,
+        unknown_keys,
+    })
+}
+
+/// Whether the reload task swapped the config before the handler answered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReloadOutcome {
+    Applied,
+    Queued,
+}
+
+impl ReloadOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Applied => "applied",
+            Self::Queued => "queued",
+        }
+    }
+}
+
+struct ReloadRequest {
+    config: Config,
+    ack: oneshot::Sender<()>,
+}
+
+/// Sends validated configs to the reload task.
+#[derive(Clone)]
+pub struct ReloadHandle {
+    tx: mpsc::Sender<ReloadRequest>,
+}
+
+impl ReloadHandle {
+    /// Spawn the task that swaps `config_state` for each requested config.
+    pub fn spawn(config_state: Arc<Mutex<Config>>) -> Self {
+        let (tx, mut rx) = mpsc::channel::<ReloadRequest>(4);
+        tokio::spawn(async move {
+            while let Some(request) = rx.recv().await {
+                crate::security::redaction::install(
+                    crate::security::redaction::Redactor::from_config(&request.config),
+                );
+                crate::approval::confirm::install(
+                    crate::approval::confirm::ConfirmationGate::from_config(&request.config),
+                );
+                *config_state.lock() = request.config;
+                tracing::info!(target: "audit", "config reloaded");
+                let _ = request.ack.send(());
+            }
+        });
+        Self { tx }
+    }
+
+    /// Hand `config` to the reload task and wait briefly for it to be applied.
+    pub async fn apply(&self, config: Config) -> Result<ReloadOutcome> {
+        let (ack, acked) = oneshot::channel();
+        self.tx
+            .send(ReloadRequest { config, ack })
+            .await
+            .map_err(|_| anyhow::anyhow!("config reload task is not running"))?;
+        match tokio::time::timeout(ACK_TIMEOUT, acked).await {
+            Ok(Ok(())) => Ok(ReloadOutcome::Applied),
+            Ok(Err(_)) => anyhow::bail!("config reload task dropped the request"),
+            Err(_) => Ok(ReloadOutcome::Queued),
+        }
+    }
+}
+
+/// A value that differs between the running and the candidate config.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ValueChange {
+    pub old: Value,
+    pub new: Value,
+}
+
+/// A changed config field, by dotted path.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct FieldChange {
+    pub path: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// What a reload would change. Secret values are masked; a changed secret
+/// shows up with masked old and new values.
+#[derive(Debug, Default, Serialize)]
+pub struct ConfigDiff {
+    pub channels_added: Vec<String>,
+    pub channels_removed: Vec<String>,
+    pub provider: Option<ValueChange>,
+    pub model: Option<ValueChange>,
+    /// Every other changed field, excluding added and removed channels.
+    pub changed: Vec<FieldChange>,
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.channels_added.is_empty()
+            && self.channels_removed.is_empty()
+            && self.provider.is_none()
+            && self.model.is_none()
+            && self.changed.is_empty()
+    }
+}
+
+/// Compare `old` and `new` field by field.
+pub fn diff_configs(old: &Config, new: &Config) -> ConfigDiff {
+    let raw_old = flatten(&serde_json::to_value(old).unwrap_or_default());
+    let raw_new = flatten(&serde_json::to_value(new).unwrap_or_default());
+    let masked_old =
+        flatten(&serde_json::to_value(super::api::mask_sensitive_fields(old)).unwrap_or_default());
+    let masked_new =
+        flatten(&serde_json::to_value(super::api::mask_sensitive_fields(new)).unwrap_or_default());
+
+    let mut diff = ConfigDiff::default();
+    let old_channels = configured_channels(&raw_old);
+    let new_channels = configured_channels(&raw_new);
+    diff.channels_added = new_channels.difference(&old_channels).cloned().collect();
+    diff.channels_removed = old_channels.difference(&new_channels).cloned().collect();
+    let toggled: Vec<String> = diff
+        .channels_added
+        .iter()
+        .chain(&diff.channels_removed)
+        .map(|name| format!("channels_config.{name}"))
+        .collect();
+
+    let paths: BTreeSet<&String> = raw_old.keys().chain(raw_new.keys()).collect();
+    for path in paths {
+        let old_value = raw_old.get(path).unwrap_or(&Value::Null);
+        let new_value = raw_new.get(path).unwrap_or(&Value::Null);
+        if old_value == new_value
+            || toggled
+                .iter()
+                .any(|prefix| path == prefix || path.starts_with(&format!("{prefix}.")))
+        {
+            continue;
+        }
+        let change = ValueChange {
+            old: masked_old.get(path).cloned().unwrap_or(Value::Null),
+            new: masked_new.get(path).cloned().unwrap_or(Value::Null),
+        };
+        match path.as_str() {
+            "default_provider" => diff.provider = Some(change),
+            "default_model" => diff.model = Some(change),
+            _ => diff.changed.push(FieldChange {
+                path: path.clone(),
+                old: change.old,
+                new: change.new,
+            }),
+        }
+    }
+    diff
+}
+
+/// Channel sections configured under `channels_config`: unconfigured ones
+/// flatten to a single `null` leaf, configured ones to nested fields.
+fn configured_channels(flat: &BTreeMap<String, Value>) -> BTreeSet<String> {
+    flat.keys()
+        .filter_map(|path| {
+            let (name, _) = path.strip_prefix("channels_config.")?.split_once('.')?;
+            Some(name.to_string())
+        })
+        .collect()
+}
+
+/// Leaf values of `value` by dotted path; arrays are leaves.
+fn flatten(value: &Value) -> BTreeMap<String, Value> {
+    fn walk(prefix: &str, value: &Value, out: &mut BTreeMap<String, Value>) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    let path = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{prefix}.{key}")
+                    };
+                    walk(&path, child, out);
+                }
+            }
+            _ => {
+                out.insert(prefix.to_string(), value.clone());
+            }
+        }
+    }
+    let mut out = BTreeMap::new();
+    walk("", value, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_reports_channels_provider_and_masks_secrets() {
+        let mut old = Config::default();
+        old.api_key = Some("sk-old-secret".into());
+        old.default_provider = Some("openrouter".into());
+        let mut new = old.clone();
+        new.api_key = Some("sk-new-secret".into());
+        new.default_provider = Some("anthropic".into());
+        new.agent.max_tool_iterations = old.agent.max_tool_iterations + 5;
+        new.channels_config.telegram = Some(crate::config::TelegramConfig {
+            bot_token: "123:tg-secret".into(),
+            allowed_users: vec!["alice".into()],
+            stream_mode: crate::config::StreamMode::default(),
+            draft_update_interval_ms: 1000,
+            interrupt_on_new_message: false,
+            mention_only: false,
+            command_prefix: None,
+            progress_messages: false,
+        });
+
+        let diff = diff_configs(&old, &new);
+        assert_eq!(diff.channels_added, vec!["telegram"]);
+        assert!(diff.channels_removed.is_empty());
+        assert_eq!(
+            diff.provider,
+            Some(ValueChange {
+                old: "openrouter".into(),
+                new: "anthropic".into(),
+            })
+        );
+        assert!(diff
+            .changed
+            .iter()
+            .any(|c| c.path == "agent.max_tool_iterations"));
+        let api_key = diff.changed.iter().find(|c| c.path == "api_key").unwrap();
+        assert_eq!(api_key.old, "***MASKED***");
+        assert_eq!(api_key.new, "***MASKED***");
+        assert!(diff
+            .changed
+            .iter()
+            .all(|c| !c.path.starts_with("channels_config.telegram")));
+
+        let rendered = serde_json::to_string(&diff).unwrap();
+        for secret in ["sk-old-secret", "sk-new-secret", "123:tg-secret"] {
+            assert!(!rendered.contains(secret), "{rendered}");
+        }
+
+        let back = diff_configs(&new, &old);
+        assert_eq!(back.channels_removed, vec!["telegram"]);
+        assert!(diff_configs(&old, &old).is_empty());
+    }
+
+    #[tokio::test]
+    async fn reload_task_acknowledges_applied_configs() {
+        let config_state = Arc::new(Mutex::new(Config::default()));
+        let handle = ReloadHandle::spawn(Arc::clone(&config_state));
+        let mut next = Config::default();
+        next.default_model = Some("reloaded-model".into());
+
+        let outcome = handle.apply(next).await.unwrap();
+        assert_eq!(outcome, ReloadOutcome::Applied);
+        assert_eq!(
+            config_state.lock().default_model.as_deref(),
+            Some("reloaded-model")
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Sanitized HTML for browser clients of the gateway.
+//!
+//! Assistant replies are markdown that may carry raw HTML, either because
+//! the model wrote it or because a fetched page prompt-injected it. Events
+//! sent to the dashboard over `/ws/events` and `/ws/chat`, and session
+//! history requested with `?render=html`, carry a `content_html` field:
+//! the markdown rendered to HTML and cleaned against a strict allowlist.
+//! The raw `content` is left untouched; channel adapters escape it
+//! themselves.
+
+use ammonia::Builder;
+use pulldown_cmark::{html, Options, Parser};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+/// URL schemes kept on links and images; everything else (including
+/// `javascript:` and `data:`) loses the attribute.
+const ALLOWED_URL_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+fn cleaner() -> &'static Builder<'static> {
+    static CLEANER: OnceLock<Builder<'static>> = OnceLock::new();
+    CLEANER.get_or_init(|| {
+        // Ammonia's defaults already drop scripts, styles, iframes, forms
+        // and every event-handler attribute.
+        let mut builder = Builder::default();
+        builder
+            .url_schemes(HashSet::from(ALLOWED_URL_SCHEMES))
+            .link_rel(Some("noopener noreferrer nofollow"));
+        builder
+    })
+}
+
+/// Render `markdown` to HTML that is safe to insert into a page.
+pub fn render_html(markdown: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let mut rendered = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut rendered, Parser::new_ext(markdown, options));
+    cleaner().clean(&rendered).to_string()
+}
+
+/// Add `content_html` to a message event that has a string `content`.
+pub fn add_content_html(event: &mut serde_json::Value) {
+    let Some(content) = event.get("content").and_then(|c| c.as_str()) else {
+        return;
+    };
+    let content_html = render_html(content);
+    event["content_html"] = serde_json::Value::String(content_html);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// No executable tags, no event-handler attributes and no script URLs.
+    /// Text such as `javascript:alert(1)` outside a tag is harmless.
+    fn assert_inert(html: &str) {
+        let lower = html.to_ascii_lowercase();
+        for tag in ["<script", "<iframe", "<style", "<object", "<embed"] {
+            assert!(!lower.contains(tag), "{tag} survived in {html}");
+        }
+        let handler = regex::Regex::new(r"<[^>]*\son[a-z]+\s*=").unwrap();
+        assert!(
+            !handler.is_match(&lower),
+            "event handler survived in {html}"
+        );
+        let script_url = regex::Regex::new(r#"(href|src)="\s*(javascript|data):"#).unwrap();
+        assert!(
+            !script_url.is_match(&lower),
+            "script URL survived in {html}"
+        );
+    }
+
+    #[test]
+    fn markdown_renders_with_safe_links() {
+        let html = render_html("**bold** and [docs](https://example.com)\n\n| a |\n|---|\n| 1 |");
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(html.contains(
+            r#"<a href="https://example.com" rel="noopener noreferrer nofollow">docs</a>"#
+        ));
+        assert!(html.contains("<table>"));
+    }
+
+    #[test]
+    fn raw_html_scripts_and_handlers_are_removed() {
+        let html = render_html(
+            "hi <script>alert(1)</script>\n\n\
+             <img src=\"x\" onerror=\"alert(1)\">\n\n\
+             <iframe src=\"https://evil.example\"></iframe>\n\n\
+             <div onclick=\"steal()\" style=\"position:fixed\">click</div>\n\n\
+             <svg><script>alert(2)</script></svg>",
+        );
+        assert_inert(&html);
+        assert!(!html.contains("alert(1)"));
+        assert!(html.contains("click"));
+    }
+
+    #[test]
+    fn javascript_urls_are_stripped_however_they_are_written() {
+        for input in [
+            "[x](javascript:alert(1))",
+            "[x](JaVaScRiPt:alert(1))",
+            "[x](jav&#x61;script:alert(1))",
+            "<a href=\"javascript:alert(1)\">x</a>",
+            "<a href=\" javascript:alert(1)\">x</a>",
+            "![x](javascript:alert(1))",
+            "[x][r]\n\n[r]: javascript:alert(1)",
+            "<https://ok.example> <javascript:alert(1)>",
+            "[x](data:text/html;base64,PHNjcmlwdD5hbGVydCgxKTwvc2NyaXB0Pg==)",
+        ] {
+            assert_inert(&render_html(input));
+        }
+    }
+
+    #[test]
+    fn nested_markdown_cannot_smuggle_attributes() {
+        for input in [
+            "![x](https://a.example/x.png\"onerror=\"alert(1))",
+            "[<img src=x onerror=alert(1)>](https://a.example)",
+            "`<script>alert(1)</script>`",
+            "```html\n<script>alert(1)</script>\n```",
+            "> <scr<script>ipt>alert(1)</script>",
+            "<details open ontoggle=alert(1)>x</details>",
+        ] {
+            assert_inert(&render_html(input));
+        }
+        // Code spans keep the text, escaped.
+        assert!(render_html("`<script>`").contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn content_html_is_added_beside_raw_content() {
+        let mut event =
+            serde_json::json!({"type": "message", "content": "<b onmouseover=x>hi</b>"});
+        add_content_html(&mut event);
+        assert_eq!(event["content"], "<b onmouseover=x>hi</b>");
+        assert_eq!(event["content_html"], "<p><b>hi</b></p>\n");
+
+        let mut failed = serde_json::json!({"type": "message_failed"});
+        add_content_html(&mut failed);
+        assert!(failed.get("content_html").is_none());
+    }
+}
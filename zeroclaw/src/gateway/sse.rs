@@ -4,20 +4,81 @@
 
 use super::AppState;
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::{header, HeaderMap, StatusCode},
     response::{
         sse::{Event, KeepAlive, Sse},
         IntoResponse,
     },
 };
+use serde::Deserialize;
+use std::collections::HashSet;
 use std::convert::Infallible;
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::StreamExt;
 
+/// Optional subscription filter, e.g. `?types=system_log&levels=warn,error`.
+#[derive(Debug, Default, Deserialize)]
+pub struct EventStreamQuery {
+    /// Comma-separated event types (`llm_request`, `tool_call`, `system_log`, ...).
+    pub types: Option<String>,
+    /// Comma-separated log levels; applies to `system_log` events only.
+    pub levels: Option<String>,
+}
+
+/// Parsed form of [`EventStreamQuery`]; `None` sets accept everything.
+#[derive(Debug, Default)]
+pub(crate) struct EventFilter {
+    types: Option<HashSet<String>>,
+    levels: Option<HashSet<String>>,
+}
+
+fn parse_list(raw: Option<&str>, normalize: fn(&str) -> &str) -> Option<HashSet<String>> {
+    let set: HashSet<String> = raw?
+        .split(',')
+        .map(|item| item.trim().to_ascii_lowercase())
+        .filter(|item| !item.is_empty())
+        .map(|item| normalize(&item).to_string())
+        .collect();
+    (!set.is_empty()).then_some(set)
+}
+
+impl EventFilter {
+    pub(crate) fn from_query(query: &EventStreamQuery) -> Self {
+        Self {
+            types: parse_list(query.types.as_deref(), |t| t),
+            levels: parse_list(query.levels.as_deref(), |level| match level {
+                "warning" => "warn",
+                "err" => "error",
+                other => other,
+            }),
+        }
+    }
+
+    pub(crate) fn matches(&self, event: &serde_json::Value) -> bool {
+        let event_type = event
+            .get("type")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("");
+        if let Some(types) = &self.types {
+            if !types.contains(event_type) {
+                return false;
+            }
+        }
+        match (&self.levels, event_type) {
+            (Some(levels), "system_log") => event
+                .get("level")
+                .and_then(serde_json::Value::as_str)
+                .is_some_and(|level| levels.contains(level)),
+            _ => true,
+        }
+    }
+}
+
 /// GET /api/events — SSE event stream
 pub async fn handle_sse_events(
     State(state): State<AppState>,
+    Query(query): Query<EventStreamQuery>,
     headers: HeaderMap,
 ) -> impl IntoResponse {
     // Auth check
@@ -37,17 +98,19 @@ pub async fn handle_sse_events(
         }
     }
 
+    let filter = EventFilter::from_query(&query);
     let rx = state.event_tx.subscribe();
     let stream = BroadcastStream::new(rx).filter_map(
-        |result: Result<
+        move |result: Result<
             serde_json::Value,
             tokio_stream::wrappers::errors::BroadcastStreamRecvError,
         >| {
             match result {
-                Ok(value) => Some(Ok::<_, Infallible>(
+                Ok(value) if filter.matches(&value) => Some(Ok::<_, Infallible>(
                     Event::default().data(value.to_string()),
                 )),
-                Err(_) => None, // Skip lagged messages
+                // Filtered out, or lagged messages skipped
+                _ => None,
             }
         },
     );
@@ -156,3 +219,45 @@ impl crate::observability::Observer for BroadcastObserver {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn filter(types: Option<&str>, levels: Option<&str>) -> EventFilter {
+        EventFilter::from_query(&EventStreamQuery {
+            types: types.map(String::from),
+            levels: levels.map(String::from),
+        })
+    }
+
+    #[test]
+    fn empty_filter_accepts_everything() {
+        let all = filter(None, Some(" , "));
+        assert!(all.matches(&json!({"type": "tool_call"})));
+        assert!(all.matches(&json!({"type": "system_log", "level": "info"})));
+    }
+
+    #[test]
+    fn type_filter_selects_event_kinds() {
+        let logs_only = filter(Some("system_log"), None);
+        assert!(logs_only.matches(&json!({"type": "system_log", "level": "info"})));
+        assert!(!logs_only.matches(&json!({"type": "llm_request"})));
+        assert!(!logs_only.matches(&json!({"no_type": true})));
+
+        let no_logs = filter(Some("llm_request, Tool_Call"), None);
+        assert!(no_logs.matches(&json!({"type": "tool_call"})));
+        assert!(!no_logs.matches(&json!({"type": "system_log", "level": "error"})));
+    }
+
+    #[test]
+    fn level_filter_applies_to_system_logs_only() {
+        let f = filter(None, Some("warning,ERROR"));
+        assert!(f.matches(&json!({"type": "system_log", "level": "warn"})));
+        assert!(f.matches(&json!({"type": "system_log", "level": "error"})));
+        assert!(!f.matches(&json!({"type": "system_log", "level": "info"})));
+        assert!(!f.matches(&json!({"type": "system_log"})));
+        assert!(f.matches(&json!({"type": "agent_start"})));
+    }
+}
@@ -1,55 +1,241 @@
-//! Static file serving for the embedded web dashboard.
+//! Static file serving for the web dashboard.
 //!
 //! Uses `rust-embed` to bundle the `web/dist/` directory into the binary at compile time.
+//! When `[web] dashboard_dir` is set, files are served read-only from that
+//! directory instead, so a dashboard can be rebuilt without rebuilding the binary.
 
+use super::AppState;
 use axum::{
+    extract::State,
     http::{header, StatusCode, Uri},
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
 use rust_embed::Embed;
+use std::path::{Component, Path, PathBuf};
 
 #[derive(Embed)]
 #[folder = "web/dist/"]
 struct WebAssets;
 
 /// Serve static files from `/_app/*` path
-pub async fn handle_static(uri: Uri) -> impl IntoResponse {
+pub async fn handle_static(State(state): State<AppState>, uri: Uri) -> Response {
     let path = uri.path().strip_prefix("/_app/").unwrap_or(uri.path());
 
-    serve_embedded_file(path)
+    match dashboard_dir(&state) {
+        Some(root) => serve_dashboard_file(&root, path).await,
+        None => serve_embedded_file(path),
+    }
+}
+
+/// Serve `/assets/*` from `dashboard_dir`; 404 when no directory is configured.
+pub async fn handle_assets(State(state): State<AppState>, uri: Uri) -> Response {
+    match dashboard_dir(&state) {
+        Some(root) => serve_dashboard_file(&root, uri.path().trim_start_matches('/')).await,
+        None => (StatusCode::NOT_FOUND, "Not found").into_response(),
+    }
 }
 
 /// SPA fallback: serve index.html for any non-API, non-static GET request
-pub async fn handle_spa_fallback() -> impl IntoResponse {
-    serve_embedded_file("index.html")
+pub async fn handle_spa_fallback(State(state): State<AppState>) -> Response {
+    match dashboard_dir(&state) {
+        Some(root) => serve_dashboard_file(&root, "index.html").await,
+        None => serve_embedded_file("index.html"),
+    }
+}
+
+/// The configured dashboard directory, resolved against the workspace.
+fn dashboard_dir(state: &AppState) -> Option<PathBuf> {
+    let config = state.config.lock();
+    let dir = config.web.dashboard_dir.as_deref()?.trim();
+    if dir.is_empty() {
+        return None;
+    }
+    let dir = PathBuf::from(shellexpand::tilde(dir).as_ref());
+    Some(if dir.is_absolute() {
+        dir
+    } else {
+        config.workspace_dir.join(dir)
+    })
+}
+
+/// Map a request path onto a file inside `root`.
+///
+/// Same layers as the workspace path policy: null bytes, `..` components and
+/// encoded separators are rejected up front, and the canonicalized result
+/// must still be inside the canonicalized root (blocks symlink escapes).
+fn resolve_dashboard_path(root: &Path, request_path: &str) -> Option<PathBuf> {
+    let lower = request_path.to_ascii_lowercase();
+    if request_path.contains('\0')
+        || request_path.contains('\\')
+        || lower.contains("%2e")
+        || lower.contains("%2f")
+        || lower.contains("%5c")
+    {
+        return None;
+    }
+    let relative = Path::new(request_path);
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return None;
+    }
+
+    let root = root.canonicalize().ok()?;
+    let resolved = root.join(relative).canonicalize().ok()?;
+    (resolved.starts_with(&root) && resolved.is_file()).then_some(resolved)
 }
 
-fn serve_embedded_file(path: &str) -> impl IntoResponse {
+async fn serve_dashboard_file(root: &Path, path: &str) -> Response {
+    let resolved = resolve_dashboard_path(root, path).or_else(|| {
+        // Client-side routes (no extension) fall back to the app shell.
+        if Path::new(path).extension().is_none() {
+            resolve_dashboard_path(root, "index.html")
+        } else {
+            None
+        }
+    });
+    let Some(resolved) = resolved else {
+        return (StatusCode::NOT_FOUND, "Not found").into_response();
+    };
+
+    match tokio::fs::read(&resolved).await {
+        Ok(data) => file_response(&resolved.to_string_lossy(), data),
+        Err(_) => (StatusCode::NOT_FOUND, "Not found").into_response(),
+    }
+}
+
+fn serve_embedded_file(path: &str) -> Response {
     match WebAssets::get(path) {
-        Some(content) => {
-            let mime = mime_guess::from_path(path)
-                .first_or_octet_stream()
-                .to_string();
+        Some(content) => file_response(path, content.data.to_vec()),
+        None => (StatusCode::NOT_FOUND, "Not found").into_response(),
+    }
+}
 
+fn file_response(path: &str, data: Vec<u8>) -> Response {
+    let mime = mime_guess::from_path(path)
+        .first_or_octet_stream()
+        .to_string();
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, mime),
             (
-                StatusCode::OK,
-                [
-                    (header::CONTENT_TYPE, mime),
-                    (
-                        header::CACHE_CONTROL,
-                        if path.contains("assets/") {
-                            // Hashed filenames — immutable cache
-                            "public, max-age=31536000, immutable".to_string()
-                        } else {
-                            // index.html etc — no cache
-                            "no-cache".to_string()
-                        },
-                    ),
-                ],
-                content.data.to_vec(),
-            )
-                .into_response()
+                header::CACHE_CONTROL,
+                if path.contains("assets/") {
+                    // Hashed filenames — immutable cache
+                    "public, max-age=31536000, immutable".to_string()
+                } else {
+                    // index.html etc — no cache
+                    "no-cache".to_string()
+                },
+            ),
+        ],
+        data,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateway::test_support::test_state;
+    use axum::body::Body;
+    use axum::http::Request;
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn dashboard_fixture() -> (tempfile::TempDir, PathBuf) {
+        let tmp = tempfile::tempdir().unwrap();
+        let dist = tmp.path().join("dist");
+        std::fs::create_dir_all(dist.join("assets")).unwrap();
+        std::fs::write(dist.join("index.html"), "<html>shell</html>").unwrap();
+        std::fs::write(dist.join("assets/app-1234.js"), "console.log(1)").unwrap();
+        std::fs::write(tmp.path().join("config.json"), "{\"secret\":1}").unwrap();
+        (tmp, dist)
+    }
+
+    async fn get(state: AppState, path: &str) -> (StatusCode, String, String) {
+        let response = crate::gateway::build_router(state)
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let cache = response
+            .headers()
+            .get(header::CACHE_CONTROL)
+            .map(|v| v.to_str().unwrap().to_string())
+            .unwrap_or_default();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, cache, String::from_utf8_lossy(&body).into_owned())
+    }
+
+    fn state_with_dashboard(dir: Option<&Path>) -> AppState {
+        let state = test_state();
+        state.config.lock().web.dashboard_dir = dir.map(|dir| dir.display().to_string());
+        state
+    }
+
+    #[test]
+    fn resolve_rejects_traversal_and_encoded_separators() {
+        let (_tmp, dist) = dashboard_fixture();
+        assert!(resolve_dashboard_path(&dist, "assets/app-1234.js").is_some());
+        for attempt in [
+            "assets/../../config.json",
+            "../config.json",
+            "/etc/passwd",
+            "assets/%2e%2e/%2e%2e/config.json",
+            "assets%2f..%2f..%2fconfig.json",
+            "assets\\..\\..\\config.json",
+            "index.html\0.js",
+            "./index.html",
+        ] {
+            assert!(
+                resolve_dashboard_path(&dist, attempt).is_none(),
+                "{attempt}"
+            );
         }
-        None => (StatusCode::NOT_FOUND, "Not found").into_response(),
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_rejects_symlink_escape() {
+        let (tmp, dist) = dashboard_fixture();
+        std::os::unix::fs::symlink(tmp.path().join("config.json"), dist.join("leak.json")).unwrap();
+        assert!(resolve_dashboard_path(&dist, "leak.json").is_none());
+    }
+
+    #[tokio::test]
+    async fn serves_dashboard_dir_with_cache_headers_and_index_fallback() {
+        let (_tmp, dist) = dashboard_fixture();
+        let state = state_with_dashboard(Some(&dist));
+
+        let (status, cache, body) = get(state.clone(), "/assets/app-1234.js").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(cache, "public, max-age=31536000, immutable");
+        assert_eq!(body, "console.log(1)");
+
+        let (status, cache, body) = get(state.clone(), "/").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(cache, "no-cache");
+        assert_eq!(body, "<html>shell</html>");
+
+        // Client-side route falls back to the app shell; missing assets do not.
+        let (status, _, body) = get(state.clone(), "/sessions/abc").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "<html>shell</html>");
+        let (status, _, _) = get(state.clone(), "/assets/missing.js").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _, body) = get(state, "/assets/../../config.json").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(!body.contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn assets_route_is_404_without_dashboard_dir() {
+        let (status, _, _) = get(state_with_dashboard(None), "/assets/app-1234.js").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
     }
 }
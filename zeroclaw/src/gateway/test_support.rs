@@ -0,0 +1,134 @@
+//! Shared fixtures for gateway handler and router tests.
+
+use super::queue::InboundQueue;
+use super::{AppState, GatewayRateLimiter, IdempotencyStore};
+use crate::config::Config;
+use crate::memory::{Memory, MemoryCategory, MemoryEntry};
+use crate::providers::Provider;
+use crate::security::pairing::PairingGuard;
+use async_trait::async_trait;
+use axum::extract::ConnectInfo;
+use parking_lot::Mutex;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Default)]
+pub(crate) struct MockMemory;
+
+#[async_trait]
+impl Memory for MockMemory {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    async fn store(
+        &self,
+        _key: &str,
+        _content: &str,
+        _category: MemoryCategory,
+        _session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn recall(
+        &self,
+        _query: &str,
+        _limit: usize,
+        _session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        Ok(Vec::new())
+    }
+
+    async fn get(&self, _key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+        Ok(None)
+    }
+
+    async fn list(
+        &self,
+        _category: Option<&MemoryCategory>,
+        _session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        Ok(Vec::new())
+    }
+
+    async fn forget(&self, _key: &str) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    async fn count(&self) -> anyhow::Result<usize> {
+        Ok(0)
+    }
+
+    async fn health_check(&self) -> bool {
+        true
+    }
+}
+
+/// Answers `"ok"` and counts calls.
+#[derive(Default)]
+pub(crate) struct MockProvider {
+    pub(crate) calls: AtomicUsize,
+}
+
+#[async_trait]
+impl Provider for MockProvider {
+    async fn chat_with_system(
+        &self,
+        _system_prompt: Option<&str>,
+        _message: &str,
+        _model: &str,
+        _temperature: f64,
+    ) -> anyhow::Result<String> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok("ok".into())
+    }
+}
+
+/// Generate a random hex secret at runtime to avoid hard-coded cryptographic values.
+pub(crate) fn generate_test_secret() -> String {
+    let bytes: [u8; 32] = rand::random();
+    hex::encode(bytes)
+}
+
+pub(crate) fn test_connect_info() -> ConnectInfo<SocketAddr> {
+    ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 30_300)))
+}
+
+/// In-memory gateway state: mock provider and memory, no channels, pairing off.
+///
+/// Override individual fields with struct update syntax:
+/// `AppState { provider, ..test_state() }`.
+pub(crate) fn test_state() -> AppState {
+    AppState {
+        config: Arc::new(Mutex::new(Config::default())),
+        provider: Arc::new(MockProvider::default()),
+        model: "test-model".into(),
+        temperature: 0.0,
+        mem: Arc::new(MockMemory),
+        auto_save: false,
+        webhook_secret_hash: None,
+        pairing: Arc::new(PairingGuard::new(false, &[])),
+        trust_forwarded_headers: false,
+        rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100, 100)),
+        idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300), 1000)),
+        whatsapp: None,
+        whatsapp_app_secret: None,
+        whatsapp_delivery: None,
+        linq: None,
+        linq_signing_secret: None,
+        nextcloud_talk: None,
+        nextcloud_talk_webhook_secret: None,
+        wati: None,
+        google_chat: None,
+        observer: Arc::new(crate::observability::NoopObserver),
+        tools_registry: Arc::new(Vec::new()),
+        cost_tracker: None,
+        event_tx: tokio::sync::broadcast::channel(16).0,
+        inbound_queue: Arc::new(InboundQueue::new(4, 32)),
+        started_at: chrono::Utc::now(),
+        reload: None,
+    }
+}
@@ -0,0 +1,327 @@
+//! HTTPS termination for the gateway.
+//!
+//! [`TlsListener`] implements axum's `Listener`, so `axum::serve` and every
+//! route (webhooks, `/ws/chat`, SSE) run unchanged over TLS. Handshakes run in
+//! their own tasks so a slow client cannot stall other connections, and the
+//! certificate is re-read when its files change on disk.
+
+use crate::config::schema::GatewayTlsConfig;
+use anyhow::{Context, Result};
+use parking_lot::RwLock;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+/// Time allowed for a client to finish the TLS handshake.
+pub const HANDSHAKE_TIMEOUT_SECS: u64 = 10;
+/// How often the certificate files are checked for changes.
+pub const CERT_RELOAD_POLL_SECS: u64 = 30;
+/// Completed handshakes waiting for the HTTP server to pick them up.
+const PENDING_CONNECTIONS: usize = 64;
+
+/// Build a rustls server config from PEM certificate chain and key files.
+pub fn load_server_config(cert_path: &Path, key_path: &Path) -> Result<Arc<rustls::ServerConfig>> {
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .with_context(|| format!("failed to read TLS certificate {}", cert_path.display()))?
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("invalid PEM in TLS certificate {}", cert_path.display()))?;
+    if certs.is_empty() {
+        anyhow::bail!("no certificates found in {}", cert_path.display());
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .with_context(|| format!("failed to read TLS private key {}", key_path.display()))?;
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut config = rustls::ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("TLS certificate and private key do not match")?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+struct LoadedCert {
+    acceptor: TlsAcceptor,
+    cert_modified: Option<SystemTime>,
+    key_modified: Option<SystemTime>,
+}
+
+/// TLS acceptor that picks up renewed certificate files.
+pub struct ReloadingAcceptor {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    current: RwLock<LoadedCert>,
+}
+
+impl ReloadingAcceptor {
+    /// Load the configured certificate; fails if it is missing or invalid.
+    pub fn load(tls: &GatewayTlsConfig) -> Result<Self> {
+        let cert_path = PathBuf::from(shellexpand::tilde(&tls.cert_path).as_ref());
+        let key_path = PathBuf::from(shellexpand::tilde(&tls.key_path).as_ref());
+        let current = Self::read(&cert_path, &key_path)?;
+        Ok(Self {
+            cert_path,
+            key_path,
+            current: RwLock::new(current),
+        })
+    }
+
+    fn read(cert_path: &Path, key_path: &Path) -> Result<LoadedCert> {
+        let cert_modified = modified(cert_path);
+        let key_modified = modified(key_path);
+        let config = load_server_config(cert_path, key_path)?;
+        Ok(LoadedCert {
+            acceptor: TlsAcceptor::from(config),
+            cert_modified,
+            key_modified,
+        })
+    }
+
+    pub fn acceptor(&self) -> TlsAcceptor {
+        self.current.read().acceptor.clone()
+    }
+
+    /// Re-read the files if either changed since the last load. Returns
+    /// `Ok(true)` after a reload; on error the previous certificate stays.
+    pub fn reload_if_changed(&self) -> Result<bool> {
+        {
+            let current = self.current.read();
+            if current.cert_modified == modified(&self.cert_path)
+                && current.key_modified == modified(&self.key_path)
+            {
+                return Ok(false);
+            }
+        }
+        let reloaded = Self::read(&self.cert_path, &self.key_path)?;
+        *self.current.write() = reloaded;
+        Ok(true)
+    }
+
+    /// Poll the certificate files every [`CERT_RELOAD_POLL_SECS`].
+    pub fn spawn_reload_task(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let acceptor = Arc::clone(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(CERT_RELOAD_POLL_SECS));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match acceptor.reload_if_changed() {
+                    Ok(true) => tracing::info!(
+                        cert = %acceptor.cert_path.display(),
+                        "Gateway TLS certificate reloaded"
+                    ),
+                    Ok(false) => {}
+                    Err(e) => tracing::warn!(
+                        "Gateway TLS certificate reload failed, keeping the current one: {e:#}"
+                    ),
+                }
+            }
+        })
+    }
+}
+
+/// TCP listener that hands `axum::serve` connections after a completed TLS
+/// handshake.
+pub struct TlsListener {
+    incoming: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+    local_addr: SocketAddr,
+}
+
+impl TlsListener {
+    pub fn new(tcp: TcpListener, acceptor: Arc<ReloadingAcceptor>) -> Result<Self> {
+        let local_addr = tcp.local_addr()?;
+        let (tx, incoming) = mpsc::channel(PENDING_CONNECTIONS);
+        tokio::spawn(accept_loop(tcp, acceptor, tx));
+        Ok(Self {
+            incoming,
+            local_addr,
+        })
+    }
+}
+
+async fn accept_loop(
+    tcp: TcpListener,
+    acceptor: Arc<ReloadingAcceptor>,
+    tx: mpsc::Sender<(TlsStream<TcpStream>, SocketAddr)>,
+) {
+    loop {
+        let (stream, peer) = match tcp.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                tracing::debug!("Gateway accept failed: {e}");
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                continue;
+            }
+        };
+        if tx.is_closed() {
+            return;
+        }
+        let tls = acceptor.acceptor();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let handshake = tokio::time::timeout(
+                Duration::from_secs(HANDSHAKE_TIMEOUT_SECS),
+                tls.accept(stream),
+            );
+            match handshake.await {
+                Ok(Ok(stream)) => {
+                    let _ = tx.send((stream, peer)).await;
+                }
+                Ok(Err(e)) => tracing::debug!(%peer, "Gateway TLS handshake failed: {e}"),
+                Err(_) => tracing::debug!(%peer, "Gateway TLS handshake timed out"),
+            }
+        });
+    }
+}
+
+impl axum::serve::Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.incoming.recv().await {
+            Some(conn) => conn,
+            // The accept loop never exits while the listener is alive.
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::serve::ListenerExt;
+    use axum::{routing::get, Router};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn fixture(name: &str) -> String {
+        format!("{}/tests/fixtures/tls/{name}", env!("CARGO_MANIFEST_DIR"))
+    }
+
+    fn fixture_tls() -> GatewayTlsConfig {
+        GatewayTlsConfig {
+            cert_path: fixture("cert.pem"),
+            key_path: fixture("key.pem"),
+        }
+    }
+
+    fn client_config() -> Arc<rustls::ClientConfig> {
+        let mut roots = rustls::RootCertStore::empty();
+        for cert in CertificateDer::pem_file_iter(fixture("ca.pem")).unwrap() {
+            roots.add(cert.unwrap()).unwrap();
+        }
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        Arc::new(
+            rustls::ClientConfig::builder_with_provider(provider)
+                .with_safe_default_protocol_versions()
+                .unwrap()
+                .with_root_certificates(roots)
+                .with_no_client_auth(),
+        )
+    }
+
+    #[test]
+    fn load_rejects_missing_and_mismatched_files() {
+        let missing = GatewayTlsConfig {
+            cert_path: fixture("missing.pem"),
+            key_path: fixture("key.pem"),
+        };
+        let err = ReloadingAcceptor::load(&missing).err().unwrap();
+        assert!(format!("{err:#}").contains("failed to read TLS certificate"));
+
+        let key_as_cert = GatewayTlsConfig {
+            cert_path: fixture("key.pem"),
+            key_path: fixture("key.pem"),
+        };
+        let err = ReloadingAcceptor::load(&key_as_cert).err().unwrap();
+        assert!(format!("{err:#}").contains("no certificates found"));
+
+        let ca_with_leaf_key = GatewayTlsConfig {
+            cert_path: fixture("ca.pem"),
+            key_path: fixture("key.pem"),
+        };
+        let err = ReloadingAcceptor::load(&ca_with_leaf_key).err().unwrap();
+        assert!(format!("{err:#}").contains("do not match"));
+    }
+
+    #[test]
+    fn reload_only_when_files_change() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let cert = tmp.path().join("cert.pem");
+        let key = tmp.path().join("key.pem");
+        std::fs::copy(fixture("cert.pem"), &cert).unwrap();
+        std::fs::copy(fixture("key.pem"), &key).unwrap();
+        let acceptor = ReloadingAcceptor::load(&GatewayTlsConfig {
+            cert_path: cert.display().to_string(),
+            key_path: key.display().to_string(),
+        })
+        .unwrap();
+        assert!(!acceptor.reload_if_changed().unwrap());
+
+        // A broken renewal keeps serving the previous certificate.
+        std::fs::write(&cert, "not a certificate").unwrap();
+        let file = std::fs::File::options().write(true).open(&cert).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+        assert!(acceptor.reload_if_changed().is_err());
+
+        std::fs::copy(fixture("cert.pem"), &cert).unwrap();
+        let file = std::fs::File::options().write(true).open(&cert).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        assert!(acceptor.reload_if_changed().unwrap());
+        assert!(!acceptor.reload_if_changed().unwrap());
+    }
+
+    #[tokio::test]
+    async fn serves_http_over_tls_with_fixture_certificate() {
+        let acceptor = Arc::new(ReloadingAcceptor::load(&fixture_tls()).unwrap());
+        let tcp = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let listener = TlsListener::new(tcp, acceptor).unwrap();
+        let addr = listener.local_addr;
+        let app = Router::new().route("/health", get(|| async { "ok" }));
+        tokio::spawn(async move {
+            axum::serve(
+                listener.tap_io(|_| {}),
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+            .unwrap();
+        });
+
+        // A plaintext client fails the handshake without affecting others.
+        let mut plain = TcpStream::connect(addr).await.unwrap();
+        plain
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+
+        let connector = tokio_rustls::TlsConnector::from(client_config());
+        let tcp = TcpStream::connect(addr).await.unwrap();
+        let domain = rustls::pki_types::ServerName::try_from("localhost").unwrap();
+        let mut tls = connector.connect(domain, tcp).await.unwrap();
+        tls.write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        tls.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+        assert!(response.ends_with("ok"), "{response}");
+    }
+}
@@ -3,11 +3,15 @@
 //! Protocol:
 //! ```text
 //! Client -> Server: {"type":"message","content":"Hello"}
+//! Client -> Server: {"type":"message","content":"Summarize","attachment_ids":["attachment://…"]}
 //! Server -> Client: {"type":"chunk","content":"Hi! "}
 //! Server -> Client: {"type":"tool_call","name":"shell","args":{...}}
 //! Server -> Client: {"type":"tool_result","name":"shell","output":"..."}
-//! Server -> Client: {"type":"done","full_response":"..."}
+//! Server -> Client: {"type":"done","full_response":"...","content_html":"<p>...</p>"}
 //! ```
+//!
+//! `content_html` is the sanitized rendering of `full_response`, sent
+//! unless `web.sanitize_output` is off.
 
 use super::AppState;
 use axum::{
@@ -31,22 +35,31 @@ pub async fn handle_ws_chat(
     Query(params): Query<WsQuery>,
     ws: WebSocketUpgrade,
 ) -> impl IntoResponse {
-    // Auth via query param (browser WebSocket limitation)
-    if state.pairing.require_pairing() {
-        let token = params.token.as_deref().unwrap_or("");
-        if !state.pairing.is_authenticated(token) {
-            return (
-                axum::http::StatusCode::UNAUTHORIZED,
-                "Unauthorized — provide ?token=<bearer_token>",
-            )
-                .into_response();
-        }
+    if let Some(rejection) = reject_unauthorized(&state, params.token.as_deref()) {
+        return rejection;
     }
 
     ws.on_upgrade(move |socket| handle_socket(socket, state))
         .into_response()
 }
 
+/// Auth via query param (browser WebSocket limitation), checked before the
+/// upgrade: the 401 response when `token` is not accepted.
+pub(super) fn reject_unauthorized(
+    state: &AppState,
+    token: Option<&str>,
+) -> Option<axum::response::Response> {
+    (state.pairing.require_pairing() && !state.pairing.is_authenticated(token.unwrap_or(""))).then(
+        || {
+            (
+                axum::http::StatusCode::UNAUTHORIZED,
+                "Unauthorized — provide ?token=<bearer_token>",
+            )
+                .into_response()
+        },
+    )
+}
+
 async fn handle_socket(socket: WebSocket, state: AppState) {
     let (mut sender, mut receiver) = socket.split();
 
@@ -77,6 +90,32 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
         if content.is_empty() {
             continue;
         }
+        let attachment_ids: Vec<String> = parsed["attachment_ids"]
+            .as_array()
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|id| id.as_str().map(ToString::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let content = match super::resolve_attachments(&state, &attachment_ids) {
+            Ok(attachments) => crate::attachments::with_attachments(&content, &attachments),
+            Err(e) => {
+                let err = serde_json::json!({"type": "error", "message": format!("{e:#}")});
+                let _ = sender.send(Message::Text(err.to_string().into())).await;
+                continue;
+            }
+        };
+
+        let Ok(slot) = state.inbound_queue.try_enqueue() else {
+            let err = serde_json::json!({
+                "type": "error",
+                "code": "busy",
+                "message": super::queue::BUSY_REPLY,
+            });
+            let _ = sender.send(Message::Text(err.to_string().into())).await;
+            continue;
+        };
 
         // Process message with the LLM provider
         let provider_label = state
@@ -127,17 +166,24 @@ async fn handle_socket(socket: WebSocket, state: AppState) {
                 }
             };
 
-        match state
-            .provider
-            .chat_with_history(&prepared.messages, &state.model, state.temperature)
+        match slot
+            .run(state.provider.chat_with_history(
+                &prepared.messages,
+                &state.model,
+                state.temperature,
+            ))
             .await
         {
             Ok(response) => {
                 // Send the full response as a done message
-                let done = serde_json::json!({
+                let mut done = serde_json::json!({
                     "type": "done",
                     "full_response": response,
                 });
+                if state.config.lock().web.sanitize_output {
+                    done["content_html"] =
+                        serde_json::Value::String(super::sanitize::render_html(&response));
+                }
                 let _ = sender.send(Message::Text(done.to_string().into())).await;
 
                 // Broadcast agent_end event
@@ -0,0 +1,424 @@
+//! WebSocket event stream with client commands.
+//!
+//! Streams the same events as `GET /api/events` (same `types`/`levels`
+//! filters) and accepts commands on the same socket:
+//! ```text
+//! Client -> Server: {"type":"ping"}
+//! Server -> Client: {"type":"pong"}
+//! Client -> Server: {"type":"subscribe","sessions":["telegram_alice"]}
+//! Server -> Client: {"type":"subscribed","sessions":["telegram_alice"]}
+//! Client -> Server: {"type":"send_message","session_key":"dashboard_1","message":"Hi"}
+//! Server -> Client: {"type":"accepted","session_key":"dashboard_1"}
+//! Server -> Client: {"type":"message","direction":"inbound","session_key":"dashboard_1","content":"Hi"}
+//! Server -> Client: {"type":"message","direction":"outbound","session_key":"dashboard_1","content":"Hello!","content_html":"<p>Hello!</p>\n"}
+//! Client -> Server: {"type":"reload"}
+//! Server -> Client: {"type":"reload","status":"applied","changes":{...}}
+//! Server -> Client: {"type":"error","message":"..."}
+//! ```
+//!
+//! `content_html` is the reply rendered from markdown and sanitized (see
+//! [`super::sanitize`]); it is left out when `web.sanitize_output` is off.
+//!
+//! `subscribe` limits events that carry a `session_key` to the listed
+//! sessions; an empty list restores all. Malformed commands get an error
+//! frame and the socket stays open.
+
+use super::rate_limit::SlidingWindowRateLimiter;
+use super::sse::{EventFilter, EventStreamQuery};
+use super::{AppState, RATE_LIMIT_WINDOW_SECS};
+use axum::{
+    extract::{
+        ws::{Message, WebSocket},
+        Query, State, WebSocketUpgrade,
+    },
+    response::IntoResponse,
+};
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Commands accepted per connection per rate limit window.
+const COMMANDS_PER_WINDOW: u32 = 60;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct WsEventsQuery {
+    pub token: Option<String>,
+    /// Same as [`EventStreamQuery::types`].
+    pub types: Option<String>,
+    /// Same as [`EventStreamQuery::levels`].
+    pub levels: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Command {
+    Ping,
+    Subscribe {
+        #[serde(default)]
+        sessions: Vec<String>,
+    },
+    SendMessage {
+        session_key: String,
+        message: String,
+    },
+    Reload,
+}
+
+/// GET /ws/events — WebSocket upgrade for the event stream
+pub async fn handle_ws_events(
+    State(state): State<AppState>,
+    Query(params): Query<WsEventsQuery>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    if let Some(rejection) = super::ws::reject_unauthorized(&state, params.token.as_deref()) {
+        return rejection;
+    }
+
+    let filter = EventFilter::from_query(&EventStreamQuery {
+        types: params.types,
+        levels: params.levels,
+    });
+    ws.on_upgrade(move |socket| handle_socket(socket, state, filter))
+        .into_response()
+}
+
+fn error_frame(message: impl std::fmt::Display) -> serde_json::Value {
+    json!({"type": "error", "message": message.to_string()})
+}
+
+/// Whether `event` passes the session subscription (`None` = all sessions).
+fn subscribed(sessions: Option<&HashSet<String>>, event: &serde_json::Value) -> bool {
+    match (sessions, event.get("session_key").and_then(|k| k.as_str())) {
+        (Some(sessions), Some(key)) => sessions.contains(key),
+        _ => true,
+    }
+}
+
+async fn handle_socket(socket: WebSocket, state: AppState, filter: EventFilter) {
+    let (mut sender, mut receiver) = socket.split();
+    let mut events = state.event_tx.subscribe();
+    let mut sessions: Option<HashSet<String>> = None;
+    let limiter = SlidingWindowRateLimiter::new(
+        COMMANDS_PER_WINDOW,
+        Duration::from_secs(RATE_LIMIT_WINDOW_SECS),
+        1,
+    );
+
+    loop {
+        let frame = tokio::select! {
+            msg = receiver.next() => {
+                let text = match msg {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+                if limiter.allow("commands") {
+                    run_command(&state, &mut sessions, &text).await
+                } else {
+                    json!({
+                        "type": "error",
+                        "code": "rate_limited",
+                        "message": "Too many commands; slow down",
+                    })
+                }
+            }
+            event = events.recv() => match event {
+                Ok(event) if filter.matches(&event) && subscribed(sessions.as_ref(), &event) => {
+                    event
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+        };
+        if sender
+            .send(Message::Text(frame.to_string().into()))
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+/// Execute one client frame and return the reply frame.
+async fn run_command(
+    state: &AppState,
+    sessions: &mut Option<HashSet<String>>,
+    text: &str,
+) -> serde_json::Value {
+    let command = match serde_json::from_str::<Command>(text) {
+        Ok(command) => command,
+        Err(e) => return error_frame(format!("Invalid command: {e}")),
+    };
+    match command {
+        Command::Ping => json!({"type": "pong"}),
+        Command::Subscribe { sessions: keys } => {
+            let keys: HashSet<String> = keys
+                .into_iter()
+                .map(|key| key.trim().to_string())
+                .filter(|key| !key.is_empty())
+                .collect();
+            let mut listed: Vec<&String> = keys.iter().collect();
+            listed.sort();
+            let reply = json!({"type": "subscribed", "sessions": listed});
+            *sessions = (!keys.is_empty()).then_some(keys);
+            reply
+        }
+        Command::SendMessage {
+            session_key,
+            message,
+        } => send_message(state, session_key.trim(), &message),
+        Command::Reload => reload(state).await,
+    }
+}
+
+/// Run an agent turn for `message` in `session_key`. The message and the
+/// reply are published as `message` events, so every socket subscribed to
+/// the session sees them.
+fn send_message(state: &AppState, session_key: &str, message: &str) -> serde_json::Value {
+    if session_key.is_empty() {
+        return error_frame("'session_key' must not be empty");
+    }
+    if message.trim().is_empty() {
+        return error_frame("'message' must not be empty");
+    }
+    let Ok(slot) = state.inbound_queue.try_enqueue() else {
+        return json!({
+            "type": "error",
+            "code": "busy",
+            "message": super::queue::BUSY_REPLY,
+        });
+    };
+
+    let _ = state.event_tx.send(json!({
+        "type": "message",
+        "direction": "inbound",
+        "session_key": session_key,
+        "content": message,
+    }));
+    let turn_state = state.clone();
+    let key = session_key.to_string();
+    let message = message.to_string();
+    tokio::spawn(async move {
+        let event =
+            match super::run_webhook_turn(turn_state.clone(), slot, message, key.clone()).await {
+                Ok(reply) => {
+                    let mut event = json!({
+                        "type": "message",
+                        "direction": "outbound",
+                        "session_key": key,
+                        "content": reply.content,
+                    });
+                    if turn_state.config.lock().web.sanitize_output {
+                        super::sanitize::add_content_html(&mut event);
+                    }
+                    event
+                }
+                Err(_) => json!({"type": "message_failed", "session_key": key}),
+            };
+        let _ = turn_state.event_tx.send(event);
+    });
+    json!({"type": "accepted", "session_key": session_key})
+}
+
+/// Reload the config file through the gateway's reload channel, as
+/// `PUT /api/control/reload` does.
+async fn reload(state: &AppState) -> serde_json::Value {
+    let Some(handle) = &state.reload else {
+        return error_frame("Config reload is not available");
+    };
+    let current = state.config.lock().clone();
+    let candidate = match super::reload::load_candidate(&current).await {
+        Ok(candidate) => candidate,
+        Err(issues) => return json!({"type": "reload", "status": "invalid", "issues": issues}),
+    };
+    let changes = super::reload::diff_configs(&current, &candidate.config);
+    match Box::pin(handle.apply(candidate.config)).await {
+        Ok(outcome) => json!({
+            "type": "reload",
+            "status": outcome.as_str(),
+            "changes": changes,
+            "unknown_keys": candidate.unknown_keys,
+        }),
+        Err(e) => error_frame(format!("Config reload failed: {e}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateway::test_support::test_state;
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    type Client = tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >;
+
+    /// Serve the gateway on a local port; returns the `/ws/events` URL.
+    async fn serve(state: AppState) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = crate::gateway::build_router(state);
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+            )
+            .await
+        });
+        format!("ws://{addr}/ws/events")
+    }
+
+    async fn connect(state: AppState) -> Client {
+        let (client, _) = tokio_tungstenite::connect_async(serve(state).await)
+            .await
+            .unwrap();
+        client
+    }
+
+    async fn send(client: &mut Client, frame: &str) {
+        client.send(WsMessage::text(frame)).await.unwrap();
+    }
+
+    async fn next_frame(client: &mut Client) -> serde_json::Value {
+        loop {
+            let msg = tokio::time::timeout(Duration::from_secs(5), client.next())
+                .await
+                .expect("frame within 5s")
+                .unwrap()
+                .unwrap();
+            if let WsMessage::Text(text) = msg {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn subscribe_limits_session_events_to_listed_sessions() {
+        let state = test_state();
+        let events = state.event_tx.clone();
+        let mut client = connect(state).await;
+
+        send(&mut client, r#"{"type":"subscribe","sessions":["a"]}"#).await;
+        assert_eq!(
+            next_frame(&mut client).await,
+            json!({"type": "subscribed", "sessions": ["a"]})
+        );
+
+        for event in [
+            json!({"type": "message", "session_key": "b", "content": "skip"}),
+            json!({"type": "message", "session_key": "a", "content": "keep"}),
+            json!({"type": "agent_start"}),
+        ] {
+            events.send(event).unwrap();
+        }
+        assert_eq!(next_frame(&mut client).await["content"], "keep");
+        assert_eq!(next_frame(&mut client).await["type"], "agent_start");
+
+        send(&mut client, r#"{"type":"subscribe","sessions":[]}"#).await;
+        assert_eq!(next_frame(&mut client).await["type"], "subscribed");
+        events
+            .send(json!({"type": "message", "session_key": "b", "content": "now"}))
+            .unwrap();
+        assert_eq!(next_frame(&mut client).await["content"], "now");
+    }
+
+    #[tokio::test]
+    async fn send_message_publishes_message_and_reply_events() {
+        let state = test_state();
+        let mut bus = state.event_tx.subscribe();
+        let mut client = connect(state).await;
+
+        send(
+            &mut client,
+            r#"{"type":"send_message","session_key":"dash","message":"hello"}"#,
+        )
+        .await;
+        assert_eq!(
+            next_frame(&mut client).await,
+            json!({"type": "accepted", "session_key": "dash"})
+        );
+
+        let inbound = bus.recv().await.unwrap();
+        assert_eq!(inbound["direction"], "inbound");
+        assert_eq!(inbound["content"], "hello");
+        let outbound = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let event = bus.recv().await.unwrap();
+                if event["direction"] == "outbound" {
+                    return event;
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(outbound["session_key"], "dash");
+        assert_eq!(outbound["content"], "ok");
+        assert_eq!(outbound["content_html"], "<p>ok</p>\n");
+    }
+
+    #[tokio::test]
+    async fn malformed_frames_get_errors_and_keep_the_socket_open() {
+        let mut client = connect(test_state()).await;
+
+        for frame in [
+            "not json",
+            r#"{"type":"bogus"}"#,
+            r#"{"type":"send_message","session_key":"dash"}"#,
+            r#"{"type":"send_message","session_key":" ","message":"hi"}"#,
+        ] {
+            send(&mut client, frame).await;
+            let reply = next_frame(&mut client).await;
+            assert_eq!(reply["type"], "error", "{frame}");
+            assert!(!reply["message"].as_str().unwrap().is_empty());
+        }
+        send(&mut client, r#"{"type":"reload"}"#).await;
+        assert!(next_frame(&mut client).await["message"]
+            .as_str()
+            .unwrap()
+            .contains("not available"));
+
+        send(&mut client, r#"{"type":"ping"}"#).await;
+        assert_eq!(next_frame(&mut client).await, json!({"type": "pong"}));
+    }
+
+    #[tokio::test]
+    async fn commands_beyond_the_rate_limit_are_rejected() {
+        let mut client = connect(test_state()).await;
+        for _ in 0..COMMANDS_PER_WINDOW {
+            send(&mut client, r#"{"type":"ping"}"#).await;
+            assert_eq!(next_frame(&mut client).await["type"], "pong");
+        }
+        send(&mut client, r#"{"type":"ping"}"#).await;
+        assert_eq!(next_frame(&mut client).await["code"], "rate_limited");
+    }
+
+    #[tokio::test]
+    async fn upgrade_requires_the_pairing_token() {
+        let state = AppState {
+            pairing: std::sync::Arc::new(crate::security::pairing::PairingGuard::new(
+                true,
+                &["zc_ws_token".into()],
+            )),
+            ..test_state()
+        };
+        let url = serve(state).await;
+        assert!(tokio_tungstenite::connect_async(&url).await.is_err());
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("{url}?token=zc_ws_token"))
+            .await
+            .unwrap();
+        send(&mut client, r#"{"type":"ping"}"#).await;
+        assert_eq!(next_frame(&mut client).await["type"], "pong");
+    }
+
+    #[test]
+    fn events_without_session_pass_any_subscription() {
+        let only_a = HashSet::from(["a".to_string()]);
+        assert!(subscribed(Some(&only_a), &json!({"type": "tool_call"})));
+        assert!(subscribed(None, &json!({"session_key": "b"})));
+        assert!(!subscribed(Some(&only_a), &json!({"session_key": "b"})));
+    }
+}
@@ -1,9 +1,18 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use parking_lot::Mutex;
 use serde::Serialize;
 use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::OnceLock;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+/// How often an idle agent loop reports that it is still alive.
+pub const AGENT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+/// An agent loop silent for longer than this is reported as not alive.
+pub const AGENT_STALE_AFTER_SECS: i64 = 120;
+
+/// Unix seconds of the last agent loop heartbeat; 0 = never.
+static AGENT_LAST_ACTIVITY: AtomicI64 = AtomicI64::new(0);
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ComponentHealth {
@@ -17,13 +26,26 @@ pub struct ComponentHealth {
 #[derive(Debug, Clone, Serialize)]
 pub struct HealthSnapshot {
     pub pid: u32,
+    pub started_at: String,
     pub updated_at: String,
     pub uptime_seconds: u64,
     pub components: BTreeMap<String, ComponentHealth>,
 }
 
+impl HealthSnapshot {
+    /// Names of channel listeners (`channel:<name>` components) currently up.
+    pub fn running_channels(&self) -> Vec<String> {
+        self.components
+            .iter()
+            .filter(|(_, health)| health.status == "ok")
+            .filter_map(|(name, _)| name.strip_prefix("channel:").map(String::from))
+            .collect()
+    }
+}
+
 struct HealthRegistry {
     started_at: Instant,
+    started_at_wall: DateTime<Utc>,
     components: Mutex<BTreeMap<String, ComponentHealth>>,
 }
 
@@ -32,10 +54,57 @@ static REGISTRY: OnceLock<HealthRegistry> = OnceLock::new();
 fn registry() -> &'static HealthRegistry {
     REGISTRY.get_or_init(|| HealthRegistry {
         started_at: Instant::now(),
+        started_at_wall: Utc::now(),
         components: Mutex::new(BTreeMap::new()),
     })
 }
 
+/// Pin the process start time. Called first thing in `main`; otherwise the
+/// registry would start its clock at the first health update.
+pub fn mark_process_started() {
+    registry();
+}
+
+pub fn process_started_at() -> DateTime<Utc> {
+    registry().started_at_wall
+}
+
+/// Whole seconds from `started_at` to `now`; 0 if the clock went backwards.
+pub fn uptime_seconds(started_at: DateTime<Utc>, now: DateTime<Utc>) -> u64 {
+    u64::try_from((now - started_at).num_seconds()).unwrap_or(0)
+}
+
+/// Compact uptime for humans, e.g. `2d 3h 4m` or `45s`.
+pub fn format_uptime(seconds: u64) -> String {
+    let (days, hours, minutes) = (seconds / 86_400, seconds / 3_600 % 24, seconds / 60 % 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{seconds}s"),
+        (0, 0, m) => format!("{m}m {}s", seconds % 60),
+        (0, h, m) => format!("{h}h {m}m"),
+        (d, h, m) => format!("{d}d {h}h {m}m"),
+    }
+}
+
+/// Heartbeat from the agent loop (message dispatch and tool loop).
+pub fn record_agent_activity() {
+    AGENT_LAST_ACTIVITY.store(Utc::now().timestamp(), Ordering::Relaxed);
+}
+
+/// Unix seconds of the last agent loop heartbeat, if there was one.
+pub fn agent_last_activity() -> Option<i64> {
+    let last = AGENT_LAST_ACTIVITY.load(Ordering::Relaxed);
+    (last > 0).then_some(last)
+}
+
+/// Whether a heartbeat at `last_activity` still counts as alive at `now`.
+pub fn is_agent_alive(last_activity: Option<i64>, now: i64) -> bool {
+    last_activity.is_some_and(|last| now - last <= AGENT_STALE_AFTER_SECS)
+}
+
+pub fn agent_loop_alive() -> bool {
+    is_agent_alive(agent_last_activity(), Utc::now().timestamp())
+}
+
 fn now_rfc3339() -> String {
     Utc::now().to_rfc3339()
 }
@@ -87,6 +156,7 @@ pub fn snapshot() -> HealthSnapshot {
 
     HealthSnapshot {
         pid: std::process::id(),
+        started_at: registry().started_at_wall.to_rfc3339(),
         updated_at: now_rfc3339(),
         uptime_seconds: registry().started_at.elapsed().as_secs(),
         components,
@@ -180,5 +250,45 @@ mod tests {
         assert!(component_json["updated_at"].as_str().is_some());
         assert!(component_json["last_ok"].as_str().is_some());
         assert!(json["uptime_seconds"].as_u64().is_some());
+        assert!(json["started_at"].as_str().is_some());
+    }
+
+    #[test]
+    fn uptime_math_handles_long_runs_and_clock_skew() {
+        let started = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let later = started + chrono::Duration::seconds(2 * 86_400 + 3 * 3_600 + 4 * 60 + 5);
+        assert_eq!(uptime_seconds(started, later), 183_845);
+        assert_eq!(uptime_seconds(later, started), 0);
+
+        assert_eq!(format_uptime(45), "45s");
+        assert_eq!(format_uptime(125), "2m 5s");
+        assert_eq!(format_uptime(3 * 3_600 + 60), "3h 1m");
+        assert_eq!(format_uptime(183_845), "2d 3h 4m");
+    }
+
+    #[test]
+    fn agent_alive_goes_stale_after_threshold() {
+        let now = 1_800_000_000;
+        assert!(!is_agent_alive(None, now));
+        assert!(is_agent_alive(Some(now), now));
+        assert!(is_agent_alive(Some(now - AGENT_STALE_AFTER_SECS), now));
+        assert!(!is_agent_alive(Some(now - AGENT_STALE_AFTER_SECS - 1), now));
+
+        record_agent_activity();
+        assert!(agent_loop_alive());
+    }
+
+    #[test]
+    fn running_channels_lists_only_healthy_listeners() {
+        let up = unique_component("channel:up");
+        let down = unique_component("channel:down");
+        mark_component_ok(&up);
+        mark_component_error(&down, "listener exited");
+
+        let running = snapshot().running_channels();
+        assert!(running.contains(&up["channel:".len()..].to_string()));
+        assert!(!running.contains(&down["channel:".len()..].to_string()));
     }
 }
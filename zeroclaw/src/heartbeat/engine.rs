@@ -75,7 +75,7 @@ impl HeartbeatEngine {
     }
 
     /// Parse tasks from HEARTBEAT.md (lines starting with `- `)
-    fn parse_tasks(content: &str) -> Vec<String> {
+    pub(crate) fn parse_tasks(content: &str) -> Vec<String> {
         content
             .lines()
             .filter_map(|line| {
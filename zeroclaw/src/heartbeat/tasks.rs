@@ -0,0 +1,348 @@
+//! Per-file heartbeat tasks with independent cadences.
+//!
+//! When `<workspace>/heartbeats/` exists, every `*.md` file in it is its own
+//! heartbeat: optional frontmatter sets the cadence, e.g.
+//!
+//! ```text
+//! ---
+//! {"interval_secs": 3600, "enabled": true}
+//! ---
+//! - Check the RSS feeds for new posts
+//! ```
+//!
+//! (`interval_secs: 3600` style lines work too). The worker polls the
+//! directory, runs each file that is due in its own `heartbeat:<stem>`
+//! session and records the run in `heartbeats/.state.json`, so a restart
+//! keeps every file's cadence instead of firing everything at once.
+//! Without the directory the single `HEARTBEAT.md` behaviour applies.
+
+use super::engine::HeartbeatEngine;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Directory of per-task heartbeat files inside the workspace.
+pub const HEARTBEATS_DIR: &str = "heartbeats";
+
+/// Last-run timestamps, kept next to the heartbeat files.
+const STATE_FILE: &str = ".state.json";
+
+/// Shortest cadence a file may ask for.
+pub const MIN_INTERVAL_SECS: u64 = 60;
+
+/// One `heartbeats/*.md` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeartbeatFile {
+    pub stem: String,
+    pub interval_secs: u64,
+    pub enabled: bool,
+    /// The file body without its frontmatter.
+    pub body: String,
+}
+
+impl HeartbeatFile {
+    /// Session the file's runs are recorded under.
+    pub fn session_key(&self) -> String {
+        session_key(&self.stem)
+    }
+
+    /// Agent prompt for one run: the file's `- ` tasks, or its whole body
+    /// when it has none. `None` when there is nothing to do.
+    pub fn prompt(&self) -> Option<String> {
+        let tasks = HeartbeatEngine::parse_tasks(&self.body);
+        let body = if tasks.is_empty() {
+            self.body.trim().to_string()
+        } else {
+            tasks
+                .iter()
+                .map(|task| format!("- {task}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        (!body.is_empty()).then(|| format!("[Heartbeat: {}]\n{body}", self.stem))
+    }
+}
+
+/// Session key for the heartbeat file `stem`.
+pub fn session_key(stem: &str) -> String {
+    format!("heartbeat:{stem}")
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Frontmatter {
+    interval_secs: Option<u64>,
+    enabled: Option<bool>,
+}
+
+/// Split `content` into its frontmatter (JSON object or `key: value` lines
+/// between `---` fences) and body. Missing or unparsable frontmatter falls
+/// back to `default_interval_secs` and enabled.
+pub fn parse_heartbeat_file(
+    stem: &str,
+    content: &str,
+    default_interval_secs: u64,
+) -> HeartbeatFile {
+    let (frontmatter, body) = split_frontmatter(content);
+    let meta = frontmatter.map(parse_frontmatter).unwrap_or_default();
+    HeartbeatFile {
+        stem: stem.to_string(),
+        interval_secs: meta
+            .interval_secs
+            .unwrap_or(default_interval_secs)
+            .max(MIN_INTERVAL_SECS),
+        enabled: meta.enabled.unwrap_or(true),
+        body: body.to_string(),
+    }
+}
+
+fn split_frontmatter(content: &str) -> (Option<&str>, &str) {
+    let trimmed = content.trim_start_matches('\u{feff}');
+    let Some(rest) = trimmed
+        .strip_prefix("---\n")
+        .or_else(|| trimmed.strip_prefix("---\r\n"))
+    else {
+        return (None, content);
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    (None, content)
+}
+
+fn parse_frontmatter(raw: &str) -> Frontmatter {
+    let raw = raw.trim();
+    if raw.starts_with('{') {
+        return serde_json::from_str(raw).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid heartbeat frontmatter: {e}");
+            Frontmatter::default()
+        });
+    }
+    let mut meta = Frontmatter::default();
+    for line in raw.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().trim_matches('"');
+        match key.trim() {
+            "interval_secs" => meta.interval_secs = value.parse().ok(),
+            "enabled" => meta.enabled = value.parse().ok(),
+            _ => {}
+        }
+    }
+    meta
+}
+
+/// Read every `*.md` file in `dir`, sorted by stem. `None` when the
+/// directory does not exist (legacy single-file mode).
+pub async fn scan_heartbeat_dir(
+    dir: &Path,
+    default_interval_secs: u64,
+) -> Result<Option<Vec<HeartbeatFile>>> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", dir.display())),
+    };
+    let mut files = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let content = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        files.push(parse_heartbeat_file(stem, &content, default_interval_secs));
+    }
+    files.sort_by(|a, b| a.stem.cmp(&b.stem));
+    Ok(Some(files))
+}
+
+/// Unix time each heartbeat file last ran, keyed by stem.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeartbeatState {
+    #[serde(default)]
+    pub last_run: BTreeMap<String, i64>,
+}
+
+impl HeartbeatState {
+    pub fn path(dir: &Path) -> PathBuf {
+        dir.join(STATE_FILE)
+    }
+
+    /// Load the state file; a missing or corrupt file starts fresh.
+    pub fn load(dir: &Path) -> Self {
+        let path = Self::path(dir);
+        match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring corrupt heartbeat state {}: {e}", path.display());
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let path = Self::path(dir);
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, &path).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    /// Enabled files whose interval has elapsed since their last run at
+    /// `now` (unix seconds). Files that never ran are due immediately.
+    pub fn due<'a>(&self, files: &'a [HeartbeatFile], now: i64) -> Vec<&'a HeartbeatFile> {
+        files
+            .iter()
+            .filter(|file| file.enabled)
+            .filter(|file| {
+                self.last_run.get(&file.stem).is_none_or(|last| {
+                    now.saturating_sub(*last)
+                        >= i64::try_from(file.interval_secs).unwrap_or(i64::MAX)
+                })
+            })
+            .collect()
+    }
+
+    /// Seconds until the next enabled file is due (0 when one already is).
+    pub fn secs_until_next_due(&self, files: &[HeartbeatFile], now: i64) -> Option<u64> {
+        files
+            .iter()
+            .filter(|file| file.enabled)
+            .map(|file| {
+                self.last_run.get(&file.stem).map_or(0, |last| {
+                    let due_at =
+                        last.saturating_add(i64::try_from(file.interval_secs).unwrap_or(i64::MAX));
+                    u64::try_from(due_at.saturating_sub(now)).unwrap_or(0)
+                })
+            })
+            .min()
+    }
+
+    /// Record a run and forget files that no longer exist.
+    pub fn mark_run(&mut self, stem: &str, now: i64, files: &[HeartbeatFile]) {
+        self.last_run.insert(stem.to_string(), now);
+        self.last_run
+            .retain(|known, _| files.iter().any(|file| &file.stem == known));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frontmatter_accepts_json_and_key_value_lines() {
+        let json = parse_heartbeat_file(
+            "rss",
+            "---\n{\"interval_secs\": 3600, \"enabled\": true}\n---\n- Check feeds\n",
+            1800,
+        );
+        assert_eq!(json.interval_secs, 3600);
+        assert!(json.enabled);
+        assert_eq!(json.body, "- Check feeds\n");
+
+        let lines = parse_heartbeat_file(
+            "disk",
+            "---\ninterval_secs: 604800\nenabled: false\n---\nCheck server disk usage.",
+            1800,
+        );
+        assert_eq!(lines.interval_secs, 604_800);
+        assert!(!lines.enabled);
+        assert_eq!(lines.body, "Check server disk usage.");
+    }
+
+    #[test]
+    fn frontmatter_defaults_and_floor() {
+        let plain = parse_heartbeat_file("todo", "- Review TODOs", 1800);
+        assert_eq!(plain.interval_secs, 1800);
+        assert!(plain.enabled);
+        assert_eq!(plain.body, "- Review TODOs");
+
+        let too_fast = parse_heartbeat_file("spin", "---\ninterval_secs: 5\n---\n- x", 1800);
+        assert_eq!(too_fast.interval_secs, MIN_INTERVAL_SECS);
+
+        // Broken JSON and an unterminated fence fall back to defaults.
+        let broken = parse_heartbeat_file("b", "---\n{not json}\n---\n- x", 1800);
+        assert_eq!((broken.interval_secs, broken.enabled), (1800, true));
+        let unterminated = parse_heartbeat_file("u", "---\ninterval_secs: 60\n- x", 1800);
+        assert_eq!(unterminated.interval_secs, 1800);
+        assert!(unterminated.body.starts_with("---"));
+    }
+
+    #[test]
+    fn prompt_uses_tasks_or_body_and_session_key_uses_stem() {
+        let tasks = parse_heartbeat_file("rss", "# Feeds\n- Check HN\n- Check lobsters\n", 60);
+        assert_eq!(
+            tasks.prompt().as_deref(),
+            Some("[Heartbeat: rss]\n- Check HN\n- Check lobsters")
+        );
+        let prose = parse_heartbeat_file("disk", "Check free disk space.\n", 60);
+        assert_eq!(
+            prose.prompt().as_deref(),
+            Some("[Heartbeat: disk]\nCheck free disk space.")
+        );
+        assert_eq!(parse_heartbeat_file("empty", "\n", 60).prompt(), None);
+        assert_eq!(tasks.session_key(), "heartbeat:rss");
+        assert_eq!(session_key("server-disk"), "heartbeat:server-disk");
+    }
+
+    #[tokio::test]
+    async fn due_files_follow_persisted_state_across_restarts() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let dir = tmp.path().join(HEARTBEATS_DIR);
+        assert!(scan_heartbeat_dir(&dir, 1800).await.unwrap().is_none());
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("hourly.md"), "---\ninterval_secs: 3600\n---\n- a").unwrap();
+        std::fs::write(dir.join("daily.md"), "---\ninterval_secs: 86400\n---\n- b").unwrap();
+        std::fs::write(dir.join("off.md"), "---\nenabled: false\n---\n- c").unwrap();
+        std::fs::write(dir.join("notes.txt"), "- ignored").unwrap();
+        let files = scan_heartbeat_dir(&dir, 1800).await.unwrap().unwrap();
+        let stems: Vec<&str> = files.iter().map(|f| f.stem.as_str()).collect();
+        assert_eq!(stems, vec!["daily", "hourly", "off"]);
+
+        // First start: everything enabled is due.
+        let start = 1_000_000;
+        let mut state = HeartbeatState::load(&dir);
+        let due: Vec<&str> = state
+            .due(&files, start)
+            .iter()
+            .map(|f| f.stem.as_str())
+            .collect();
+        assert_eq!(due, vec!["daily", "hourly"]);
+        for stem in due {
+            state.mark_run(stem, start, &files);
+        }
+        state.save(&dir).unwrap();
+
+        // "Restart" 2h later: only the hourly file is due again.
+        let reloaded = HeartbeatState::load(&dir);
+        assert_eq!(reloaded, state);
+        let later = start + 2 * 3600;
+        let due: Vec<&str> = reloaded
+            .due(&files, later)
+            .iter()
+            .map(|f| f.stem.as_str())
+            .collect();
+        assert_eq!(due, vec!["hourly"]);
+        assert_eq!(
+            reloaded.secs_until_next_due(&files, start + 600),
+            Some(3000)
+        );
+        assert_eq!(reloaded.secs_until_next_due(&files, later), Some(0));
+
+        // Removed files are dropped from the state on the next run.
+        let mut pruned = reloaded.clone();
+        pruned.mark_run("hourly", later, &files[1..2]);
+        assert_eq!(pruned.last_run.keys().collect::<Vec<_>>(), vec!["hourly"]);
+    }
+}
@@ -40,7 +40,9 @@ use serde::{Deserialize, Serialize};
 
 pub mod agent;
 pub(crate) mod approval;
+pub(crate) mod attachments;
 pub(crate) mod auth;
+pub(crate) mod backup;
 pub mod channels;
 pub mod config;
 pub(crate) mod cost;
@@ -65,8 +67,10 @@ pub mod rag;
 pub mod runtime;
 pub(crate) mod security;
 pub(crate) mod service;
+pub(crate) mod sessions;
 pub(crate) mod skills;
 pub mod tools;
+pub(crate) mod top;
 pub(crate) mod tunnel;
 pub(crate) mod util;
 
@@ -148,16 +152,37 @@ pub enum SkillCommands {
         /// Skill path or installed skill name
         source: String,
     },
-    /// Install a new skill from a URL or local path
+    /// Install a new skill from a git URL, local directory or `.tar.gz` archive
     Install {
-        /// Source URL or local path
+        /// Git URL, local directory or `.tar.gz`/`.tgz` path
         source: String,
+        /// Replace an installed skill with the same name
+        #[arg(long)]
+        force: bool,
+        /// Skip the confirmation prompt
+        #[arg(long)]
+        yes: bool,
     },
     /// Remove an installed skill
     Remove {
         /// Skill name to remove
         name: String,
     },
+    /// Let a skill use the sandbox permissions it requests, globally or for
+    /// one channel or sender
+    Approve {
+        /// Installed skill name
+        name: String,
+        /// Limit the approval to this channel (e.g. telegram)
+        #[arg(long)]
+        channel: Option<String>,
+        /// Limit the approval to this sender id on --channel
+        #[arg(long, requires = "channel")]
+        sender: Option<String>,
+        /// Record a denial at this scope instead
+        #[arg(long)]
+        deny: bool,
+    },
 }
 
 /// Migration subcommands
@@ -180,6 +205,22 @@ pub enum MigrateCommands {
 pub enum CronCommands {
     /// List all scheduled tasks
     List,
+    /// Show the next upcoming runs across all enabled tasks
+    #[command(long_about = "\
+Show when scheduled tasks will fire next.
+
+Recurring tasks are projected forward, so a task that runs every hour \
+can appear several times. Paused tasks are left out. Times are shown \
+in `timezone` from config.toml, or the system timezone when unset.
+
+Examples:
+  zeroclaw cron next
+  zeroclaw cron next --n 20")]
+    Next {
+        /// Number of upcoming runs to show
+        #[arg(long, short, default_value = "5")]
+        n: usize,
+    },
     /// Add a new scheduled task
     #[command(long_about = "\
 Add a new recurring scheduled task.
@@ -200,20 +241,30 @@ Examples:
         /// Command to run
         command: String,
     },
-    /// Add a one-shot scheduled task at an RFC3339 timestamp
+    /// Add a one-shot scheduled task at a specific time
     #[command(long_about = "\
-Add a one-shot task that fires at a specific UTC timestamp.
+Add a one-shot task that fires at a specific time.
 
-The timestamp must be in RFC 3339 format (e.g. 2025-01-15T14:00:00Z).
+Accepts an RFC 3339 timestamp (e.g. 2025-01-15T14:00:00Z) or a simple \
+expression such as 'in 2 hours', 'tomorrow 9am', 'friday 14:00' or \
+'2025-01-15 14:00'. Wall-clock times use `timezone` from config.toml, \
+or the system timezone when unset.
+
+The task is deleted after it runs successfully; pass --keep to keep it \
+(paused once it has fired) for its run history.
 
 Examples:
   zeroclaw cron add-at 2025-01-15T14:00:00Z 'Send reminder'
-  zeroclaw cron add-at 2025-12-31T23:59:00Z 'Happy New Year!'")]
+  zeroclaw cron add-at 'tomorrow 9am' 'Standup notes'
+  zeroclaw cron add-at 'in 30 minutes' 'Stretch' --keep")]
     AddAt {
-        /// One-shot timestamp in RFC3339 format
+        /// RFC3339 timestamp or expression like 'tomorrow 9am'
         at: String,
         /// Command to run
         command: String,
+        /// Keep the task after it has run instead of deleting it
+        #[arg(long)]
+        keep: bool,
     },
     /// Add a fixed-interval scheduled task
     #[command(long_about = "\
@@ -246,6 +297,9 @@ Examples:
         delay: String,
         /// Command to run
         command: String,
+        /// Keep the task after it has run instead of deleting it
+        #[arg(long)]
+        keep: bool,
     },
     /// Remove a scheduled task
     Remove {
@@ -261,7 +315,8 @@ Only the fields you specify are changed; others remain unchanged.
 Examples:
   zeroclaw cron update <task-id> --expression '0 8 * * *'
   zeroclaw cron update <task-id> --tz Europe/London --name 'Morning check'
-  zeroclaw cron update <task-id> --command 'Updated message'")]
+  zeroclaw cron update <task-id> --command 'Updated message'
+  zeroclaw cron update <task-id> --catch-up skip")]
     Update {
         /// Task ID
         id: String,
@@ -277,6 +332,9 @@ Examples:
         /// New job name
         #[arg(long)]
         name: Option<String>,
+        /// What to do after downtime made the task miss slots: run-once or skip
+        #[arg(long)]
+        catch_up: Option<String>,
     },
     /// Pause a scheduled task
     Pause {
@@ -329,6 +387,78 @@ pub enum MemoryCommands {
     },
 }
 
+/// Workspace backup subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BackupCommands {
+    /// Write sessions, cron jobs, memory and skills to a .tar.gz archive
+    Export {
+        /// Destination archive path (e.g. zeroclaw-backup.tar.gz)
+        file: std::path::PathBuf,
+    },
+    /// Restore a workspace from an archive created by `backup export`
+    Import {
+        /// Archive to restore
+        file: std::path::PathBuf,
+        /// Overwrite existing sessions, cron jobs, memory and skills
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// Workspace template subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum WorkspaceCommands {
+    /// Bring AGENTS.md, SOUL.md and the other workspace templates up to date
+    Upgrade,
+}
+
+/// Channel conversation session subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SessionCommands {
+    /// List recorded sessions
+    List,
+    /// Print recent history for a session
+    Show {
+        /// Session key (as shown by `sessions list`)
+        key: String,
+        /// Number of most recent messages to print
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+    /// Show the stored conversation summary for a session
+    Summary {
+        /// Session key
+        key: String,
+    },
+    /// Drop all but the newest messages of a session
+    Trim {
+        /// Session key
+        key: String,
+        /// Number of most recent messages to keep
+        #[arg(long)]
+        keep: usize,
+    },
+    /// Export a session as Markdown or JSON
+    Export {
+        /// Session key
+        key: String,
+        /// Output format: md (markdown) or json
+        #[arg(long, default_value = "md")]
+        format: String,
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+    },
+    /// Delete a session and all of its messages
+    Delete {
+        /// Session key
+        key: String,
+        /// Skip confirmation prompt
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
 /// Integration subcommands
 #[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum IntegrationCommands {
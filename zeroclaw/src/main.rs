@@ -38,7 +38,7 @@ use dialoguer::{Input, Password};
 use serde::{Deserialize, Serialize};
 use std::io::Write;
 use tracing::{info, warn};
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing_subscriber::{fmt, layer::SubscriberExt, EnvFilter};
 
 fn parse_temperature(s: &str) -> std::result::Result<f64, String> {
     let t: f64 = s.parse().map_err(|e| format!("{e}"))?;
@@ -50,7 +50,9 @@ fn parse_temperature(s: &str) -> std::result::Result<f64, String> {
 
 mod agent;
 mod approval;
+mod attachments;
 mod auth;
+mod backup;
 mod channels;
 mod rag {
     pub use zeroclaw::rag::*;
@@ -77,9 +79,11 @@ mod providers;
 mod runtime;
 mod security;
 mod service;
+mod sessions;
 mod skillforge;
 mod skills;
 mod tools;
+mod top;
 mod tunnel;
 mod util;
 
@@ -87,8 +91,9 @@ use config::Config;
 
 // Re-export so binary modules can use crate::<CommandEnum> while keeping a single source of truth.
 pub use zeroclaw::{
-    ChannelCommands, CronCommands, HardwareCommands, IntegrationCommands, MigrateCommands,
-    PeripheralCommands, ServiceCommands, SkillCommands,
+    BackupCommands, ChannelCommands, CronCommands, HardwareCommands, IntegrationCommands,
+    MigrateCommands, PeripheralCommands, ServiceCommands, SessionCommands, SkillCommands,
+    WorkspaceCommands,
 };
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
@@ -234,7 +239,8 @@ service (systemd/launchd) for auto-start on boot.
 Examples:
   zeroclaw daemon                   # use config defaults
   zeroclaw daemon -p 9090           # gateway on port 9090
-  zeroclaw daemon --host 127.0.0.1  # localhost only")]
+  zeroclaw daemon --host 127.0.0.1  # localhost only
+  zeroclaw daemon --max-restarts 5  # exit non-zero after 5 restarts in a row")]
     Daemon {
         /// Port to listen on (use 0 for random available port); defaults to config gateway.port
         #[arg(short, long)]
@@ -243,6 +249,11 @@ Examples:
         /// Host to bind to; defaults to config gateway.host
         #[arg(long)]
         host: Option<String>,
+
+        /// Give up and exit non-zero once a component has been restarted this
+        /// many times in a row (unlimited by default)
+        #[arg(long)]
+        max_restarts: Option<u32>,
     },
 
     /// Manage OS service lifecycle (launchd/systemd user service)
@@ -261,8 +272,71 @@ Examples:
         doctor_command: Option<DoctorCommands>,
     },
 
+    /// Check that the provider, channels and integrations actually answer
+    #[command(long_about = "\
+Check that the configured setup actually works.
+
+Sends a short ping to the default provider and reports latency and \
+model, runs each configured channel's credential check (Telegram getMe, \
+Slack auth.test, Discord users/@me, WhatsApp phone number info, ...), \
+runs a one-result Brave search and reads the Google Sheets metadata. \
+Nothing is posted to any channel. Exits non-zero if a configured check \
+fails.
+
+Examples:
+  zeroclaw verify
+  zeroclaw verify --provider
+  zeroclaw verify --channel telegram")]
+    Verify {
+        /// Only check this channel
+        #[arg(long)]
+        channel: Option<String>,
+
+        /// Only check the provider (combine with --channel to check both)
+        #[arg(long)]
+        provider: bool,
+    },
+
     /// Show system status (full details)
-    Status,
+    #[command(long_about = "\
+Show system status (full details).
+
+Prints the configured provider, security policy and channels. With \
+--live, also queries the gateway on 127.0.0.1 (/api/status) and shows \
+what is actually running: uptime, agent loop liveness, and which \
+configured channels have a running listener.
+
+Examples:
+  zeroclaw status
+  zeroclaw status --live
+  zeroclaw status --live --token <bearer-token>")]
+    Status {
+        /// Query the running gateway for live state
+        #[arg(long)]
+        live: bool,
+
+        /// Gateway bearer token (defaults to $ZEROCLAW_GATEWAY_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+    },
+
+    /// Watch the running gateway in a terminal dashboard
+    #[command(long_about = "\
+Watch the running gateway in a terminal dashboard.
+
+Connects to the gateway on 127.0.0.1 and shows a live feed of inbound \
+and outbound messages (/ws/events), channel health, queue and tool \
+metrics, and token usage. Keys: q quit, p pause the feed, j/k select a \
+session, f filter the feed to it, m send it a message.
+
+Examples:
+  zeroclaw top
+  zeroclaw top --token <bearer-token>")]
+    Top {
+        /// Gateway bearer token (defaults to $ZEROCLAW_GATEWAY_TOKEN)
+        #[arg(long)]
+        token: Option<String>,
+    },
 
     /// Engage, inspect, and resume emergency-stop states.
     ///
@@ -357,6 +431,41 @@ Examples:
         skill_command: SkillCommands,
     },
 
+    /// Export or import a workspace backup
+    #[command(long_about = "\
+Export or import a full workspace backup.
+
+An archive holds the sessions database, cron jobs, memory files, \
+installed skills and top-level workspace markdown files. SQLite \
+databases are snapshotted consistently, so export is safe while the \
+daemon is running; stop it before importing. config.toml and secret \
+keys are not included.
+
+Examples:
+  zeroclaw backup export zeroclaw-backup.tar.gz
+  zeroclaw backup import zeroclaw-backup.tar.gz
+  zeroclaw backup import zeroclaw-backup.tar.gz --force")]
+    Backup {
+        #[command(subcommand)]
+        backup_command: BackupCommands,
+    },
+
+    /// Upgrade workspace markdown templates
+    #[command(long_about = "\
+Upgrade the workspace markdown templates (AGENTS.md, SOUL.md, ...).
+
+Files that still hold the text onboarding wrote are replaced with the \
+current template. Files you have edited are never overwritten: the new \
+template is written next to them as <name>.new.md and a short diff is \
+printed. Applied versions are recorded in .zeroclaw-templates.json.
+
+Examples:
+  zeroclaw workspace upgrade")]
+    Workspace {
+        #[command(subcommand)]
+        workspace_command: WorkspaceCommands,
+    },
+
     /// Migrate data from other agent runtimes
     Migrate {
         #[command(subcommand)]
@@ -424,22 +533,63 @@ Examples:
         memory_command: MemoryCommands,
     },
 
+    /// Inspect and prune channel conversation sessions
+    #[command(long_about = "\
+Inspect and prune channel conversation sessions.
+
+Sessions are recorded per sender by the channel runtime in \
+<workspace>/sessions/sessions.db. Read-only commands are safe to run \
+while the daemon is running.
+
+Examples:
+  zeroclaw sessions list
+  zeroclaw sessions show telegram_alice --limit 10
+  zeroclaw sessions summary telegram_alice
+  zeroclaw sessions trim telegram_alice --keep 20
+  zeroclaw sessions delete telegram_alice --yes")]
+    Sessions {
+        #[command(subcommand)]
+        session_command: SessionCommands,
+    },
+
     /// Manage configuration
     #[command(long_about = "\
 Manage ZeroClaw configuration.
 
 Inspect and export configuration settings. Use 'schema' to dump \
 the full JSON Schema for the config file, which documents every \
-available key, type, and default value.
+available key, type, and default value. Use 'check' to find settings \
+that load but would fail at runtime; the gateway and daemon run the \
+same checks and refuse to start on errors.
 
 Examples:
   zeroclaw config schema              # print JSON Schema to stdout
-  zeroclaw config schema > schema.json")]
+  zeroclaw config schema > schema.json
+  zeroclaw config check               # human-readable report
+  zeroclaw config check --json        # machine-readable report")]
     Config {
         #[command(subcommand)]
         config_command: ConfigCommands,
     },
 
+    /// Manage encryption of credentials in config.toml
+    #[command(long_about = "\
+Manage encryption of credentials stored in config.toml.
+
+'encrypt' rewrites the stored API keys and tokens as ciphertext. When \
+ZEROCLAW_SECRETS_PASSPHRASE is set the key is derived from that \
+passphrase (enc3:) instead of the local key file, and the gateway and \
+daemon need the same variable at boot to decrypt them. Values written \
+as \"env:VAR\" or \"file:/path\" references are left untouched.
+
+Examples:
+  ZEROCLAW_SECRETS_PASSPHRASE=... zeroclaw secrets encrypt
+  zeroclaw secrets encrypt            # use the key file in the config dir")]
+    Secrets {
+        #[command(subcommand)]
+        secrets_command: SecretsCommands,
+    },
+
     /// Generate shell completion script to stdout
     #[command(long_about = "\
 Generate shell completion scripts for `zeroclaw`.
@@ -461,6 +611,18 @@ Examples:
 enum ConfigCommands {
     /// Dump the full configuration JSON Schema to stdout
     Schema,
+    /// Check the configuration for errors and risky settings
+    Check {
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SecretsCommands {
+    /// Encrypt credentials in config.toml (passphrase-derived key if ZEROCLAW_SECRETS_PASSPHRASE is set)
+    Encrypt,
 }
 
 #[derive(Subcommand, Debug)]
@@ -666,6 +828,7 @@ async fn main() -> Result<()> {
     if let Err(e) = rustls::crypto::ring::default_provider().install_default() {
         eprintln!("Warning: Failed to install default crypto provider: {e:?}");
     }
+    health::mark_process_started();
 
     let cli = Cli::parse();
 
@@ -684,12 +847,12 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
-    // Initialize logging - respects RUST_LOG env var, defaults to INFO
-    let subscriber = fmt::Subscriber::builder()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .finish();
+    // Initialize logging - respects RUST_LOG env var, defaults to INFO.
+    // The log-stream layer stays idle unless the gateway enables it.
+    let subscriber = tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .with(fmt::layer())
+        .with(gateway::log_stream::LogStreamLayer::new());
 
     tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
 
@@ -727,9 +890,9 @@ async fn main() -> Result<()> {
             bail!("--channels-only does not accept --force");
         }
         let config = if channels_only {
-            onboard::run_channels_repair_wizard().await
+            Box::pin(onboard::run_channels_repair_wizard()).await
         } else if interactive {
-            onboard::run_wizard(force).await
+            Box::pin(onboard::run_wizard(force)).await
         } else {
             onboard::run_quick_setup(
                 api_key.as_deref(),
@@ -783,6 +946,7 @@ async fn main() -> Result<()> {
             temperature,
             peripheral,
             true,
+            None,
         )
         .await
         .map(|_| ()),
@@ -795,10 +959,16 @@ async fn main() -> Result<()> {
             } else {
                 info!("🚀 Starting ZeroClaw Gateway on {host}:{port}");
             }
+            config::check::ensure_startable(&config, &host, port)?;
+            let _instance_lock = acquire_instance_lock(&config)?;
             gateway::run_gateway(&host, port, config).await
         }
 
-        Commands::Daemon { port, host } => {
+        Commands::Daemon {
+            port,
+            host,
+            max_restarts,
+        } => {
             let port = port.unwrap_or(config.gateway.port);
             let host = host.unwrap_or_else(|| config.gateway.host.clone());
             if port == 0 {
@@ -806,10 +976,45 @@ async fn main() -> Result<()> {
             } else {
                 info!("🧠 Starting ZeroClaw Daemon on {host}:{port}");
             }
-            daemon::run(config, host, port).await
+            config::check::ensure_startable(&config, &host, port)?;
+            let _instance_lock = acquire_instance_lock(&config)?;
+            daemon::run(config, host, port, max_restarts).await
+        }
+
+        Commands::Top { token } => {
+            let token = token.or_else(|| std::env::var("ZEROCLAW_GATEWAY_TOKEN").ok());
+            top::run(&config, token).await
         }
 
-        Commands::Status => {
+        Commands::Status { live, token } => {
+            let live_status = if live {
+                let token = token.or_else(|| std::env::var("ZEROCLAW_GATEWAY_TOKEN").ok());
+                match fetch_live_status(config.gateway.port, token.as_deref()).await {
+                    Ok(status) => Some(status),
+                    Err(e) => {
+                        println!(
+                            "⚠️  Gateway not reachable on 127.0.0.1:{}: {e}",
+                            config.gateway.port
+                        );
+                        println!();
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            let running_channels: Option<Vec<String>> = live_status.as_ref().map(|status| {
+                status["adapters"]["running_names"]
+                    .as_array()
+                    .map(|names| {
+                        names
+                            .iter()
+                            .filter_map(|name| name.as_str().map(str::to_ascii_lowercase))
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            });
+
             println!("🦀 ZeroClaw Status");
             println!();
             println!("Version:     {}", env!("CARGO_PKG_VERSION"));
@@ -875,11 +1080,46 @@ async fn main() -> Result<()> {
             println!("  OTP enabled:       {}", config.security.otp.enabled);
             println!("  E-stop enabled:    {}", config.security.estop.enabled);
             println!();
+            if let Some(status) = &live_status {
+                println!("Live gateway (127.0.0.1:{}):", config.gateway.port);
+                println!(
+                    "  Started:     {}",
+                    status["started_at"].as_str().unwrap_or("unknown")
+                );
+                println!(
+                    "  Uptime:      {}",
+                    health::format_uptime(status["uptime_seconds"].as_u64().unwrap_or(0))
+                );
+                println!(
+                    "  Agent loop:  {}",
+                    if status["agent_loop_alive"].as_bool().unwrap_or(false) {
+                        "🟢 alive"
+                    } else {
+                        "🔴 stale"
+                    }
+                );
+                println!(
+                    "  Adapters:    {}/{} running",
+                    status["adapters"]["running"].as_u64().unwrap_or(0),
+                    status["adapters"]["configured"].as_u64().unwrap_or(0)
+                );
+                println!();
+            }
             println!("Channels:");
             println!("  CLI:      ✅ always");
             for (channel, configured) in config.channels_config.channels() {
+                let running = match (&running_channels, configured) {
+                    (Some(running), true) => {
+                        if running.contains(&channel.name().to_ascii_lowercase()) {
+                            ", 🟢 running"
+                        } else {
+                            ", ⚪ not running"
+                        }
+                    }
+                    _ => "",
+                };
                 println!(
-                    "  {:9} {}",
+                    "  {:9} {}{running}",
                     channel.name(),
                     if configured {
                         "✅ configured"
@@ -995,6 +1235,10 @@ async fn main() -> Result<()> {
             None => doctor::run(&config),
         },
 
+        Commands::Verify { channel, provider } => {
+            doctor::verify::run(&config, channel.as_deref(), provider).await
+        }
+
         Commands::Channel { channel_command } => match channel_command {
             ChannelCommands::Start => channels::start_channels(config).await,
             ChannelCommands::Doctor => channels::doctor_channels(config).await,
@@ -1007,6 +1251,12 @@ async fn main() -> Result<()> {
 
         Commands::Skills { skill_command } => skills::handle_command(skill_command, &config),
 
+        Commands::Backup { backup_command } => backup::handle_command(backup_command, &config),
+
+        Commands::Workspace { workspace_command } => {
+            onboard::templates::handle_command(workspace_command, &config.workspace_dir)
+        }
+
         Commands::Migrate { migrate_command } => {
             migration::handle_command(migrate_command, &config).await
         }
@@ -1015,6 +1265,10 @@ async fn main() -> Result<()> {
             memory::cli::handle_command(memory_command, &config).await
         }
 
+        Commands::Sessions { session_command } => {
+            sessions::cli::handle_command(session_command, &config)
+        }
+
         Commands::Auth { auth_command } => handle_auth_command(auth_command, &config).await,
 
         Commands::Hardware { hardware_command } => {
@@ -1025,6 +1279,24 @@ async fn main() -> Result<()> {
             peripherals::handle_command(peripheral_command.clone(), &config).await
         }
 
+        Commands::Secrets { secrets_command } => match secrets_command {
+            SecretsCommands::Encrypt => {
+                let encrypted = config.encrypt_secrets_on_disk().await?;
+                let mode = if std::env::var(security::secrets::PASSPHRASE_ENV)
+                    .is_ok_and(|value| !value.is_empty())
+                {
+                    "passphrase-derived key"
+                } else {
+                    "key file"
+                };
+                println!(
+                    "Encrypted {encrypted} secret(s) in {} using the {mode}.",
+                    config.config_path.display()
+                );
+                Ok(())
+            }
+        },
+
         Commands::Config { config_command } => match config_command {
             ConfigCommands::Schema => {
                 let schema = schemars::schema_for!(config::Config);
@@ -1034,10 +1306,57 @@ async fn main() -> Result<()> {
                 );
                 Ok(())
             }
+            ConfigCommands::Check { json } => {
+                let issues = config.check();
+                let errors = issues.iter().filter(|i| i.is_error()).count();
+                if json {
+                    let report = serde_json::json!({
+                        "ok": errors == 0,
+                        "errors": errors,
+                        "warnings": issues.len() - errors,
+                        "issues": issues,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                } else {
+                    print!("{}", config::check::render_report(&issues));
+                }
+                if errors > 0 {
+                    bail!("configuration has {errors} error(s)");
+                }
+                Ok(())
+            }
         },
     }
 }
 
+/// Fetch `/api/status` from the gateway running on this host.
+/// Refuse to start a second gateway/daemon against the same config directory.
+fn acquire_instance_lock(config: &Config) -> Result<daemon::instance::InstanceLock> {
+    let config_dir = config
+        .config_path
+        .parent()
+        .context("Config path must have a parent directory")?;
+    daemon::instance::InstanceLock::acquire(config_dir)
+}
+
+async fn fetch_live_status(port: u16, token: Option<&str>) -> Result<serde_json::Value> {
+    let mut request = reqwest::Client::new()
+        .get(format!("http://127.0.0.1:{port}/api/status"))
+        .timeout(std::time::Duration::from_secs(3));
+    if let Some(token) = token.filter(|token| !token.trim().is_empty()) {
+        request = request.bearer_auth(token.trim());
+    }
+    let response = request.send().await?;
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        bail!("gateway requires a token (pass --token or set ZEROCLAW_GATEWAY_TOKEN)");
+    }
+    if !status.is_success() {
+        bail!("gateway returned {status}");
+    }
+    Ok(response.json().await?)
+}
+
 fn handle_estop_command(
     config: &Config,
     estop_command: Option<EstopSubcommands>,
@@ -1974,4 +2293,34 @@ mod tests {
             other => panic!("expected estop resume command, got {other:?}"),
         }
     }
+
+    #[test]
+    fn cli_parses_status_live_with_token() {
+        let cli = Cli::try_parse_from(["zeroclaw", "status", "--live", "--token", "abc"])
+            .expect("status --live should parse");
+
+        match cli.command {
+            Commands::Status { live, token } => {
+                assert!(live);
+                assert_eq!(token.as_deref(), Some("abc"));
+            }
+            other => panic!("expected status command, got {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from(["zeroclaw", "status"]).expect("status should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Status {
+                live: false,
+                token: None
+            }
+        ));
+    }
+
+    #[test]
+    fn cli_parses_top_with_token() {
+        let cli = Cli::try_parse_from(["zeroclaw", "top", "--token", "abc"])
+            .expect("top --token should parse");
+        assert!(matches!(cli.command, Commands::Top { token: Some(t) } if t == "abc"));
+    }
 }
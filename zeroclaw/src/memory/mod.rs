@@ -6,10 +6,12 @@ pub mod hygiene;
 pub mod lucid;
 pub mod markdown;
 pub mod none;
+pub mod notes;
 #[cfg(feature = "memory-postgres")]
 pub mod postgres;
 pub mod qdrant;
 pub mod response_cache;
+pub mod sheets;
 pub mod snapshot;
 pub mod sqlite;
 pub mod traits;
@@ -169,6 +171,25 @@ fn resolve_embedding_config(
     }
 }
 
+/// The embedding provider and model configured in `[memory]` (after
+/// resolving `hint:` routes), or `None` while embeddings are disabled
+/// (`embedding_provider = "none"`).
+pub fn configured_embedder(
+    config: &MemoryConfig,
+    embedding_routes: &[EmbeddingRouteConfig],
+    api_key: Option<&str>,
+) -> Option<(Arc<dyn embeddings::EmbeddingProvider>, String)> {
+    let resolved = resolve_embedding_config(config, embedding_routes, api_key);
+    let embedder = embeddings::create_embedding_provider(
+        &resolved.provider,
+        resolved.api_key.as_deref(),
+        &resolved.model,
+        resolved.dimensions,
+    );
+    (embedder.dimensions() > 0 && embedder.name() != "none")
+        .then(|| (Arc::from(embedder), resolved.model))
+}
+
 /// Factory: create the right memory backend from config
 pub fn create_memory(
     config: &MemoryConfig,
@@ -388,6 +409,7 @@ pub fn create_response_cache(config: &MemoryConfig, workspace_dir: &Path) -> Opt
         config.response_cache_max_entries,
     ) {
         Ok(cache) => {
+            let cache = cache.with_max_temperature(config.response_cache_max_temperature);
             tracing::info!(
                 "💾 Response cache enabled (TTL: {}min, max: {} entries)",
                 config.response_cache_ttl_minutes,
@@ -525,6 +547,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn configured_embedder_is_none_until_a_provider_is_set() {
+        assert!(configured_embedder(&MemoryConfig::default(), &[], Some("key")).is_none());
+
+        let cfg = MemoryConfig {
+            embedding_provider: "openai".into(),
+            embedding_model: "text-embedding-3-small".into(),
+            ..MemoryConfig::default()
+        };
+        let (embedder, model) = configured_embedder(&cfg, &[], Some("key")).unwrap();
+        assert_eq!(embedder.name(), "openai");
+        assert_eq!(model, "text-embedding-3-small");
+    }
+
     #[test]
     fn resolve_embedding_config_uses_base_config_when_model_is_not_hint() {
         let cfg = MemoryConfig {
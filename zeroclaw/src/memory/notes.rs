@@ -0,0 +1,481 @@
+//! Structured, non-destructive edits to the workspace `MEMORY.md`.
+//!
+//! The file stays plain markdown: notes live under `## Section` headings and
+//! key/value facts live in a marked region under `## Facts`. Every write goes
+//! through a process-wide lock and an atomic rename, so concurrent tool calls
+//! never clobber each other, and each change is appended to
+//! `memory/memory.log` (bounded) for audit.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+use tokio::sync::Mutex;
+
+const FACTS_HEADING: &str = "Facts";
+const FACTS_START: &str = "<!-- facts:start -->";
+const FACTS_END: &str = "<!-- facts:end -->";
+const NEW_FILE_HEADER: &str = "# Long-Term Memory\n";
+/// Entries kept in `memory/memory.log`; older ones are dropped.
+const LOG_MAX_ENTRIES: usize = 500;
+const SEARCH_MAX_RESULTS: usize = 20;
+/// `MEMORY.md` larger than this is summarised in the system prompt.
+pub const DIGEST_THRESHOLD_CHARS: usize = 4_000;
+
+/// Serialises read-modify-write cycles on `MEMORY.md` across all tools.
+static WRITE_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// A line in `MEMORY.md` that matched a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteMatch {
+    /// Heading the line sits under, if any.
+    pub section: Option<String>,
+    pub line: String,
+}
+
+pub struct MemoryNotes {
+    workspace_dir: PathBuf,
+}
+
+impl MemoryNotes {
+    pub fn new(workspace_dir: &Path) -> Self {
+        Self {
+            workspace_dir: workspace_dir.to_path_buf(),
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        self.workspace_dir.join("MEMORY.md")
+    }
+
+    fn log_path(&self) -> PathBuf {
+        self.workspace_dir.join("memory").join("memory.log")
+    }
+
+    async fn load(&self) -> Result<String> {
+        match fs::read_to_string(self.path()).await {
+            Ok(doc) => Ok(doc),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+            Err(e) => Err(e).context("Failed to read MEMORY.md"),
+        }
+    }
+
+    /// Append `text` as a bullet under `## {section}`, creating the section
+    /// if needed.
+    pub async fn append_note(&self, section: &str, text: &str) -> Result<()> {
+        let _guard = WRITE_LOCK.lock().await;
+        let doc = self.load().await?;
+        let updated = append_to_section(&doc, section, text);
+        self.commit(&updated).await?;
+        self.log(serde_json::json!({"op": "append_note", "section": section, "text": text}))
+            .await
+    }
+
+    /// Set `key` in the facts region. Returns the previous value, if any.
+    pub async fn set_fact(&self, key: &str, value: &str) -> Result<Option<String>> {
+        let _guard = WRITE_LOCK.lock().await;
+        let doc = self.load().await?;
+        let (updated, previous) = upsert_fact(&doc, key, value);
+        self.commit(&updated).await?;
+        self.log(serde_json::json!({
+            "op": "set_fact",
+            "key": key,
+            "value": value,
+            "previous": previous,
+        }))
+        .await?;
+        Ok(previous)
+    }
+
+    /// The whole file, or one section's body (heading excluded).
+    pub async fn read(&self, section: Option<&str>) -> Result<Option<String>> {
+        let doc = self.load().await?;
+        Ok(match section {
+            None => (!doc.trim().is_empty()).then_some(doc),
+            Some(name) => sections(&doc)
+                .into_iter()
+                .find(|s| s.heading.as_deref().is_some_and(|h| same_heading(h, name)))
+                .map(|s| s.body.trim().to_string()),
+        })
+    }
+
+    /// Case-insensitive line search, at most [`SEARCH_MAX_RESULTS`] hits.
+    pub async fn search(&self, query: &str) -> Result<Vec<NoteMatch>> {
+        let doc = self.load().await?;
+        Ok(search_doc(&doc, query))
+    }
+
+    pub async fn facts(&self) -> Result<BTreeMap<String, String>> {
+        Ok(parse_facts(&self.load().await?))
+    }
+
+    /// Write via a temp file and rename so readers never see a partial file.
+    async fn commit(&self, doc: &str) -> Result<()> {
+        fs::create_dir_all(&self.workspace_dir).await?;
+        let tmp = self
+            .workspace_dir
+            .join(format!(".MEMORY.md.{}.tmp", uuid::Uuid::new_v4()));
+        fs::write(&tmp, doc)
+            .await
+            .context("Failed to write MEMORY.md")?;
+        if let Err(e) = fs::rename(&tmp, self.path()).await {
+            let _ = fs::remove_file(&tmp).await;
+            return Err(e).context("Failed to replace MEMORY.md");
+        }
+        Ok(())
+    }
+
+    /// Append one JSON line to the change log, trimming it to
+    /// [`LOG_MAX_ENTRIES`]. Caller holds [`WRITE_LOCK`].
+    async fn log(&self, mut entry: serde_json::Value) -> Result<()> {
+        entry["ts"] = Utc::now().to_rfc3339().into();
+        let path = self.log_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let existing = fs::read_to_string(&path).await.unwrap_or_default();
+        let mut lines: Vec<&str> = existing.lines().filter(|l| !l.is_empty()).collect();
+        let entry = entry.to_string();
+        lines.push(&entry);
+        let keep = &lines[lines.len().saturating_sub(LOG_MAX_ENTRIES)..];
+        let mut out = keep.join("\n");
+        out.push('\n');
+        fs::write(&path, out)
+            .await
+            .context("Failed to write memory.log")
+    }
+}
+
+/// Compact stand-in for `MEMORY.md` in the system prompt once the file
+/// exceeds [`DIGEST_THRESHOLD_CHARS`]: all facts plus the section names,
+/// capped at the threshold. `None` means inject the file as-is.
+pub fn prompt_digest(workspace_dir: &Path) -> Option<String> {
+    let doc = std::fs::read_to_string(workspace_dir.join("MEMORY.md")).ok()?;
+    digest_doc(&doc, DIGEST_THRESHOLD_CHARS)
+}
+
+fn digest_doc(doc: &str, max_chars: usize) -> Option<String> {
+    if doc.trim().chars().count() <= max_chars {
+        return None;
+    }
+
+    let mut body = String::new();
+    let facts = parse_facts(doc);
+    if !facts.is_empty() {
+        body.push_str("Facts:\n");
+        for (key, value) in &facts {
+            let _ = writeln!(body, "- {key}: {value}");
+        }
+        body.push('\n');
+    }
+    let headings: Vec<String> = sections(doc)
+        .into_iter()
+        .filter_map(|s| s.heading)
+        .filter(|h| !same_heading(h, FACTS_HEADING))
+        .collect();
+    if !headings.is_empty() {
+        let _ = writeln!(body, "Note sections: {}", headings.join(", "));
+    }
+    if body.chars().count() > max_chars {
+        body = body.chars().take(max_chars).collect();
+        body.push_str("\n[... digest truncated]");
+    }
+
+    Some(format!(
+        "### MEMORY.md (digest)\n\n{}\n\n[MEMORY.md is {} chars — use `memory_notes` read/search for full notes]\n\n",
+        body.trim_end(),
+        doc.chars().count()
+    ))
+}
+
+struct Section {
+    heading: Option<String>,
+    body: String,
+}
+
+/// Split on `## ` headings. The first entry holds everything before the
+/// first heading (title, preamble) and has no heading.
+fn sections(doc: &str) -> Vec<Section> {
+    let mut out = vec![Section {
+        heading: None,
+        body: String::new(),
+    }];
+    for line in doc.lines() {
+        if let Some(h) = line.strip_prefix("## ") {
+            out.push(Section {
+                heading: Some(h.trim().to_string()),
+                body: String::new(),
+            });
+        } else if let Some(current) = out.last_mut() {
+            current.body.push_str(line);
+            current.body.push('\n');
+        }
+    }
+    out
+}
+
+fn render(sections: &[Section]) -> String {
+    let mut doc = String::new();
+    for s in sections {
+        if let Some(h) = &s.heading {
+            if !doc.is_empty() && !doc.ends_with("\n\n") {
+                doc.push('\n');
+            }
+            let _ = writeln!(doc, "## {h}");
+        }
+        doc.push_str(&s.body);
+    }
+    doc
+}
+
+fn same_heading(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+fn bullet(text: &str) -> String {
+    let mut lines = text.trim().lines();
+    let mut out = format!("- {}", lines.next().unwrap_or_default());
+    for line in lines {
+        let _ = write!(out, "\n  {line}");
+    }
+    out.push('\n');
+    out
+}
+
+fn with_header(doc: &str) -> String {
+    if doc.trim().is_empty() {
+        NEW_FILE_HEADER.to_string()
+    } else {
+        doc.to_string()
+    }
+}
+
+fn append_to_section(doc: &str, section: &str, text: &str) -> String {
+    let mut parts = sections(&with_header(doc));
+    let target = parts.iter_mut().find(|s| {
+        s.heading
+            .as_deref()
+            .is_some_and(|h| same_heading(h, section))
+    });
+    match target {
+        Some(s) => {
+            // An empty body becomes "\n", leaving a blank line under the heading.
+            let kept = s.body.trim_end().len();
+            s.body.truncate(kept);
+            s.body.push('\n');
+            s.body.push_str(&bullet(text));
+        }
+        None => parts.push(Section {
+            heading: Some(section.trim().to_string()),
+            body: format!("\n{}", bullet(text)),
+        }),
+    }
+    render(&parts)
+}
+
+fn parse_facts(doc: &str) -> BTreeMap<String, String> {
+    let Some((start, end)) = facts_region(doc) else {
+        return BTreeMap::new();
+    };
+    doc[start..end]
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("- "))
+        .filter_map(|entry| entry.split_once(": "))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect()
+}
+
+/// Byte range between the facts markers.
+fn facts_region(doc: &str) -> Option<(usize, usize)> {
+    let start = doc.find(FACTS_START)? + FACTS_START.len();
+    let end = start + doc[start..].find(FACTS_END)?;
+    Some((start, end))
+}
+
+fn upsert_fact(doc: &str, key: &str, value: &str) -> (String, Option<String>) {
+    // Keys and values are single-line; newlines would break the region format.
+    let key = key.split_whitespace().collect::<Vec<_>>().join(" ");
+    let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut facts = parse_facts(doc);
+    let previous = facts.insert(key, value);
+
+    let mut rendered = String::from("\n");
+    for (k, v) in &facts {
+        let _ = writeln!(rendered, "- {k}: {v}");
+    }
+
+    let doc = with_header(doc);
+    let updated = match facts_region(&doc) {
+        Some((start, end)) => format!("{}{rendered}{}", &doc[..start], &doc[end..]),
+        None => {
+            let mut parts = sections(&doc);
+            // Facts go first so they stay near the top of the file.
+            parts.insert(
+                1,
+                Section {
+                    heading: Some(FACTS_HEADING.to_string()),
+                    body: format!("\n{FACTS_START}{rendered}{FACTS_END}\n"),
+                },
+            );
+            render(&parts)
+        }
+    };
+    (updated, previous)
+}
+
+fn search_doc(doc: &str, query: &str) -> Vec<NoteMatch> {
+    let needle = query.trim().to_lowercase();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let mut section = None;
+    let mut hits = Vec::new();
+    for line in doc.lines() {
+        if let Some(h) = line.strip_prefix("## ") {
+            section = Some(h.trim().to_string());
+            continue;
+        }
+        if line.starts_with("<!--") || !line.to_lowercase().contains(&needle) {
+            continue;
+        }
+        hits.push(NoteMatch {
+            section: section.clone(),
+            line: line.trim().to_string(),
+        });
+        if hits.len() == SEARCH_MAX_RESULTS {
+            break;
+        }
+    }
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn append_creates_and_extends_sections() {
+        let doc = append_to_section("", "People", "Alice prefers email");
+        assert_eq!(
+            doc,
+            "# Long-Term Memory\n\n## People\n\n- Alice prefers email\n"
+        );
+
+        let doc = append_to_section(&doc, "projects", "zeroclaw\nships weekly");
+        let doc = append_to_section(&doc, "people", "Bob is on call");
+        assert_eq!(
+            doc,
+            "# Long-Term Memory\n\n## People\n\n- Alice prefers email\n- Bob is on call\n\n\
+             ## projects\n\n- zeroclaw\n  ships weekly\n"
+        );
+    }
+
+    #[test]
+    fn append_preserves_hand_written_content() {
+        let doc = "# Memory\n\nFree text intro.\n\n## Notes\nkeep me\n\n## Other\nalso keep\n";
+        let updated = append_to_section(doc, "Notes", "new");
+        assert!(updated.contains("Free text intro."));
+        assert!(updated.contains("## Notes\nkeep me\n- new\n"));
+        assert!(updated.contains("## Other\nalso keep\n"));
+    }
+
+    #[test]
+    fn set_fact_maintains_sorted_region() {
+        let (doc, prev) = upsert_fact("# Memory\n\n## Notes\n\n- hi\n", "tz", "UTC");
+        assert!(prev.is_none());
+        let (doc, prev) = upsert_fact(&doc, "name", "Ana");
+        assert!(prev.is_none());
+        let (doc, prev) = upsert_fact(&doc, "tz", "Europe/Lisbon");
+        assert_eq!(prev.as_deref(), Some("UTC"));
+
+        assert!(doc.starts_with(
+            "# Memory\n\n## Facts\n\n<!-- facts:start -->\n- name: Ana\n- tz: Europe/Lisbon\n<!-- facts:end -->\n"
+        ));
+        assert!(doc.ends_with("## Notes\n\n- hi\n"));
+        assert_eq!(parse_facts(&doc).len(), 2);
+    }
+
+    #[test]
+    fn search_reports_section_and_skips_markers() {
+        let (doc, _) = upsert_fact("", "editor", "helix");
+        let doc = append_to_section(&doc, "Tools", "Uses Helix for Rust");
+        let hits = search_doc(&doc, "HELIX");
+        assert_eq!(
+            hits,
+            vec![
+                NoteMatch {
+                    section: Some("Facts".into()),
+                    line: "- editor: helix".into()
+                },
+                NoteMatch {
+                    section: Some("Tools".into()),
+                    line: "- Uses Helix for Rust".into()
+                },
+            ]
+        );
+        assert!(search_doc(&doc, "facts:start").is_empty());
+    }
+
+    #[test]
+    fn digest_only_for_large_files_and_is_truncated() {
+        let (small, _) = upsert_fact("", "k", "v");
+        assert!(digest_doc(&small, 1000).is_none());
+
+        let mut doc = small;
+        for i in 0..50 {
+            doc = append_to_section(&doc, &format!("Section {i}"), &"x".repeat(40));
+        }
+        let digest = digest_doc(&doc, 1000).unwrap();
+        assert!(digest.starts_with("### MEMORY.md (digest)"));
+        assert!(digest.contains("- k: v"));
+        assert!(digest.contains("Section 0, Section 1"));
+        assert!(!digest.contains(&"x".repeat(40)));
+
+        let digest = digest_doc(&doc, 100).unwrap();
+        assert!(digest.contains("[... digest truncated]"));
+    }
+
+    #[tokio::test]
+    async fn concurrent_appends_are_all_kept() {
+        let tmp = TempDir::new().unwrap();
+        let notes = std::sync::Arc::new(MemoryNotes::new(tmp.path()));
+        let mut handles = Vec::new();
+        for i in 0..20 {
+            let notes = notes.clone();
+            handles.push(tokio::spawn(async move {
+                notes
+                    .append_note("Log", &format!("entry {i}"))
+                    .await
+                    .unwrap();
+            }));
+        }
+        for h in handles {
+            h.await.unwrap();
+        }
+
+        let body = notes.read(Some("log")).await.unwrap().unwrap();
+        assert_eq!(
+            body.lines().filter(|l| l.starts_with("- entry ")).count(),
+            20
+        );
+        let log = std::fs::read_to_string(tmp.path().join("memory/memory.log")).unwrap();
+        assert_eq!(log.lines().count(), 20);
+    }
+
+    #[tokio::test]
+    async fn change_log_is_bounded() {
+        let tmp = TempDir::new().unwrap();
+        let notes = MemoryNotes::new(tmp.path());
+        let log_path = tmp.path().join("memory/memory.log");
+        std::fs::create_dir_all(log_path.parent().unwrap()).unwrap();
+        std::fs::write(&log_path, "{}\n".repeat(LOG_MAX_ENTRIES)).unwrap();
+
+        notes.set_fact("k", "v").await.unwrap();
+        let log = std::fs::read_to_string(&log_path).unwrap();
+        assert_eq!(log.lines().count(), LOG_MAX_ENTRIES);
+        assert!(log.lines().last().unwrap().contains("\"op\":\"set_fact\""));
+    }
+}
@@ -4,6 +4,10 @@
 //! `(model, system_prompt_hash, user_prompt)`. Entries expire after a
 //! configurable TTL (default: 1 hour). The cache is optional and disabled by
 //! default — users opt in via `[memory] response_cache_enabled = true`.
+//!
+//! Provider calls reach it through [`crate::providers::cache::CachingProvider`],
+//! which only consults it for calls that [`ResponseCache::engages`]: no tool
+//! definitions and a temperature at or below `response_cache_max_temperature`.
 
 use anyhow::Result;
 use chrono::{Duration, Local};
@@ -22,6 +26,7 @@ pub struct ResponseCache {
     db_path: PathBuf,
     ttl_minutes: i64,
     max_entries: usize,
+    max_temperature: f64,
 }
 
 impl ResponseCache {
@@ -58,9 +63,23 @@ impl ResponseCache {
             db_path,
             ttl_minutes: i64::from(ttl_minutes),
             max_entries,
+            max_temperature: 0.3,
         })
     }
 
+    /// Serve only calls at or below `max_temperature` (default: 0.3).
+    #[must_use]
+    pub fn with_max_temperature(mut self, max_temperature: f64) -> Self {
+        self.max_temperature = max_temperature;
+        self
+    }
+
+    /// Whether a provider call with these parameters may be served from the
+    /// cache. Calls carrying tool definitions never are.
+    pub fn engages(&self, temperature: f64, has_tools: bool) -> bool {
+        !has_tools && temperature <= self.max_temperature
+    }
+
     /// Build a deterministic cache key from model + system prompt + user prompt.
     pub fn cache_key(model: &str, system_prompt: Option<&str>, user_prompt: &str) -> String {
         let mut hasher = Sha256::new();
@@ -383,6 +402,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn engages_only_for_low_temperature_tool_free_calls() {
+        let (_tmp, cache) = temp_cache(60);
+        assert!(cache.engages(0.0, false));
+        assert!(cache.engages(0.3, false));
+        assert!(!cache.engages(0.7, false));
+        assert!(!cache.engages(0.0, true));
+        assert!(!cache.engages(f64::NAN, false));
+
+        let hot = cache.with_max_temperature(1.0);
+        assert!(hot.engages(0.7, false));
+        assert!(!hot.engages(0.7, true));
+    }
+
     #[test]
     fn cache_handles_zero_max_entries() {
         let tmp = TempDir::new().unwrap();
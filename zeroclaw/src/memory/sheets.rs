@@ -0,0 +1,653 @@
+//! Google Sheets as structured long-term memory.
+//!
+//! [`SheetsClient`] talks to one spreadsheet through the Sheets v4
+//! `values.get` / `values.append` endpoints, so rows the agent writes can be
+//! read and edited by hand from the Sheets UI and vice versa. Sheet names go
+//! through [`sanitize_sheet_name`] and missing sheets are created on the first
+//! append. Requests pass a client-side per-minute budget before they reach
+//! Google (default per-user quota: 60 reads and 60 writes per minute); running
+//! out, or a 429 from the API, is reported as [`SheetsError::Quota`].
+//!
+//! HTTP goes through the [`SheetsTransport`] trait so the request building and
+//! row filtering can be tested without network access.
+
+use crate::config::GoogleSheetsConfig;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const SHEETS_API_BASE: &str = "https://sheets.googleapis.com/v4/spreadsheets";
+const SHEETS_REQUEST_TIMEOUT_SECS: u64 = 20;
+/// Google's limit on sheet title length.
+const MAX_SHEET_NAME_CHARS: usize = 100;
+/// Rows returned by one `find_rows` call.
+pub const MAX_FOUND_ROWS: usize = 50;
+const QUOTA_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    Get,
+    Post,
+}
+
+/// One call against the spreadsheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SheetsRequest {
+    pub method: HttpMethod,
+    /// Path below `spreadsheets/{id}`, already percent-encoded (empty for the
+    /// spreadsheet resource itself).
+    pub path: String,
+    pub query: Vec<(&'static str, String)>,
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SheetsError {
+    #[error("Google Sheets quota exceeded; retry in {}s", .retry_after.as_secs().max(1))]
+    Quota { retry_after: Duration },
+    #[error("Google Sheets API error ({status}): {message}")]
+    Api { status: u16, message: String },
+    #[error("{0}")]
+    Invalid(String),
+    #[error("Google Sheets request failed: {0}")]
+    Transport(String),
+}
+
+/// HTTP layer under [`SheetsClient`].
+#[async_trait]
+pub trait SheetsTransport: Send + Sync {
+    async fn send(&self, request: SheetsRequest) -> Result<Value, SheetsError>;
+}
+
+/// [`SheetsTransport`] over the Sheets REST API with an OAuth bearer token.
+pub struct HttpSheetsTransport {
+    client: reqwest::Client,
+    base_url: String,
+    access_token: String,
+}
+
+impl HttpSheetsTransport {
+    pub fn new(spreadsheet_id: &str, access_token: String) -> Self {
+        Self {
+            client: crate::config::build_runtime_proxy_client_with_timeouts(
+                "memory.google_sheets",
+                SHEETS_REQUEST_TIMEOUT_SECS,
+                10,
+            ),
+            base_url: format!(
+                "{SHEETS_API_BASE}/{}",
+                urlencoding::encode(spreadsheet_id.trim())
+            ),
+            access_token,
+        }
+    }
+}
+
+#[async_trait]
+impl SheetsTransport for HttpSheetsTransport {
+    async fn send(&self, request: SheetsRequest) -> Result<Value, SheetsError> {
+        let url = format!("{}{}", self.base_url, request.path);
+        let mut builder = match request.method {
+            HttpMethod::Get => self.client.get(&url),
+            HttpMethod::Post => self.client.post(&url),
+        }
+        .bearer_auth(&self.access_token)
+        .query(&request.query);
+        if let Some(body) = &request.body {
+            builder = builder.json(body);
+        }
+
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| SheetsError::Transport(e.to_string()))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = crate::channels::send_retry::retry_after_header(response.headers())
+                .unwrap_or(QUOTA_WINDOW);
+            return Err(SheetsError::Quota { retry_after });
+        }
+        let text = response
+            .text()
+            .await
+            .map_err(|e| SheetsError::Transport(e.to_string()))?;
+        if !status.is_success() {
+            let message = serde_json::from_str::<Value>(&text)
+                .ok()
+                .and_then(|v| v["error"]["message"].as_str().map(str::to_string))
+                .unwrap_or(text);
+            return Err(SheetsError::Api {
+                status: status.as_u16(),
+                message,
+            });
+        }
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&text).map_err(|e| SheetsError::Transport(e.to_string()))
+    }
+}
+
+/// Sliding one-minute request budget.
+struct QuotaWindow {
+    limit: usize,
+    sent: Mutex<VecDeque<Instant>>,
+}
+
+impl QuotaWindow {
+    fn new(per_minute: u32) -> Self {
+        Self {
+            limit: per_minute as usize,
+            sent: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Take a slot, or return how long until one frees up. `0` is unlimited.
+    fn try_acquire(&self, now: Instant) -> Result<(), Duration> {
+        if self.limit == 0 {
+            return Ok(());
+        }
+        let mut sent = self.sent.lock();
+        while sent
+            .front()
+            .is_some_and(|&at| now.duration_since(at) >= QUOTA_WINDOW)
+        {
+            sent.pop_front();
+        }
+        if sent.len() >= self.limit {
+            let oldest = sent.front().copied().unwrap_or(now);
+            return Err(QUOTA_WINDOW.saturating_sub(now.duration_since(oldest)));
+        }
+        sent.push_back(now);
+        Ok(())
+    }
+}
+
+/// Rows matched by [`SheetsClient::find_rows`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundRows {
+    pub header: Vec<String>,
+    /// `(sheet row number, cells)`; row numbers are 1-based like the Sheets UI.
+    pub rows: Vec<(usize, Vec<String>)>,
+    /// More rows matched than were returned.
+    pub truncated: bool,
+}
+
+pub struct SheetsClient {
+    transport: Arc<dyn SheetsTransport>,
+    reads: QuotaWindow,
+    writes: QuotaWindow,
+    /// Sheet titles, fetched on first use and updated when we add one.
+    sheet_titles: Mutex<Option<HashSet<String>>>,
+}
+
+impl SheetsClient {
+    pub fn new(transport: Arc<dyn SheetsTransport>, requests_per_minute: u32) -> Self {
+        Self {
+            transport,
+            reads: QuotaWindow::new(requests_per_minute),
+            writes: QuotaWindow::new(requests_per_minute),
+            sheet_titles: Mutex::new(None),
+        }
+    }
+
+    /// Build a client from `[google_sheets]`; the token falls back to
+    /// `GOOGLE_SHEETS_ACCESS_TOKEN`.
+    pub fn from_config(config: &GoogleSheetsConfig) -> anyhow::Result<Self> {
+        if config.spreadsheet_id.trim().is_empty() {
+            anyhow::bail!("google_sheets.spreadsheet_id is empty");
+        }
+        let token = config
+            .access_token
+            .clone()
+            .filter(|t| !t.trim().is_empty())
+            .or_else(|| std::env::var("GOOGLE_SHEETS_ACCESS_TOKEN").ok())
+            .filter(|t| !t.trim().is_empty())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "google_sheets.access_token is not set (or set GOOGLE_SHEETS_ACCESS_TOKEN)"
+                )
+            })?;
+        let transport = HttpSheetsTransport::new(&config.spreadsheet_id, token);
+        Ok(Self::new(Arc::new(transport), config.requests_per_minute))
+    }
+
+    async fn send(&self, request: SheetsRequest) -> Result<Value, SheetsError> {
+        let window = match request.method {
+            HttpMethod::Get => &self.reads,
+            HttpMethod::Post => &self.writes,
+        };
+        window
+            .try_acquire(Instant::now())
+            .map_err(|retry_after| SheetsError::Quota { retry_after })?;
+        self.transport.send(request).await
+    }
+
+    async fn sheet_titles(&self) -> Result<HashSet<String>, SheetsError> {
+        if let Some(titles) = self.sheet_titles.lock().clone() {
+            return Ok(titles);
+        }
+        let response = self
+            .send(SheetsRequest {
+                method: HttpMethod::Get,
+                path: String::new(),
+                query: vec![("fields", "sheets.properties.title".into())],
+                body: None,
+            })
+            .await?;
+        let titles: HashSet<String> = response["sheets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|sheet| sheet["properties"]["title"].as_str())
+            .map(str::to_string)
+            .collect();
+        *self.sheet_titles.lock() = Some(titles.clone());
+        Ok(titles)
+    }
+
+    /// Fetch the spreadsheet's title; a cheap read that proves the token
+    /// and spreadsheet id are valid.
+    pub async fn spreadsheet_title(&self) -> Result<String, SheetsError> {
+        let response = self
+            .send(SheetsRequest {
+                method: HttpMethod::Get,
+                path: String::new(),
+                query: vec![("fields", "properties.title".into())],
+                body: None,
+            })
+            .await?;
+        Ok(response["properties"]["title"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    async fn ensure_sheet(&self, title: &str) -> Result<(), SheetsError> {
+        if self.sheet_titles().await?.contains(title) {
+            return Ok(());
+        }
+        self.send(SheetsRequest {
+            method: HttpMethod::Post,
+            path: ":batchUpdate".into(),
+            query: Vec::new(),
+            body: Some(json!({
+                "requests": [{ "addSheet": { "properties": { "title": title } } }]
+            })),
+        })
+        .await?;
+        if let Some(titles) = self.sheet_titles.lock().as_mut() {
+            titles.insert(title.to_string());
+        }
+        Ok(())
+    }
+
+    async fn require_sheet(&self, title: &str) -> Result<(), SheetsError> {
+        if self.sheet_titles().await?.contains(title) {
+            Ok(())
+        } else {
+            Err(SheetsError::Invalid(format!(
+                "Sheet '{title}' does not exist; append a row to create it"
+            )))
+        }
+    }
+
+    /// Append one row below the sheet's data, creating the sheet if needed.
+    /// Returns the range that was written.
+    pub async fn append_row(&self, sheet: &str, values: &[Value]) -> Result<String, SheetsError> {
+        if values.is_empty() {
+            return Err(SheetsError::Invalid("values must not be empty".into()));
+        }
+        if let Some(bad) = values.iter().find(|v| v.is_array() || v.is_object()) {
+            return Err(SheetsError::Invalid(format!(
+                "cell values must be strings, numbers, booleans or null, got {bad}"
+            )));
+        }
+        let sheet = sanitize_sheet_name(sheet);
+        self.ensure_sheet(&sheet).await?;
+        let range = a1_range(&sheet, None)?;
+        let response = self
+            .send(SheetsRequest {
+                method: HttpMethod::Post,
+                path: format!("/values/{}:append", urlencoding::encode(&range)),
+                // RAW keeps model-written text from being evaluated as formulas.
+                query: vec![
+                    ("valueInputOption", "RAW".into()),
+                    ("insertDataOption", "INSERT_ROWS".into()),
+                ],
+                body: Some(json!({ "majorDimension": "ROWS", "values": [values] })),
+            })
+            .await?;
+        Ok(response["updates"]["updatedRange"]
+            .as_str()
+            .unwrap_or(&range)
+            .to_string())
+    }
+
+    /// Read cells of `sheet`; `cells` is an A1 range such as `A1:C20`, or
+    /// `None` for the whole sheet.
+    pub async fn read_range(
+        &self,
+        sheet: &str,
+        cells: Option<&str>,
+    ) -> Result<Vec<Vec<String>>, SheetsError> {
+        let sheet = sanitize_sheet_name(sheet);
+        let range = a1_range(&sheet, cells)?;
+        self.require_sheet(&sheet).await?;
+        let response = self
+            .send(SheetsRequest {
+                method: HttpMethod::Get,
+                path: format!("/values/{}", urlencoding::encode(&range)),
+                query: vec![("majorDimension", "ROWS".into())],
+                body: None,
+            })
+            .await?;
+        Ok(rows_from_values(&response["values"]))
+    }
+
+    /// Rows whose `column` contains `query` (case-insensitive). The first row
+    /// is the header; `column` is a header name or a column letter.
+    pub async fn find_rows(
+        &self,
+        sheet: &str,
+        column: &str,
+        query: &str,
+    ) -> Result<FoundRows, SheetsError> {
+        let rows = self.read_range(sheet, None).await?;
+        filter_rows(&rows, column, query, MAX_FOUND_ROWS)
+    }
+}
+
+/// Make `name` a valid sheet title: characters Sheets rejects
+/// (`[ ] * ? / \ :`) become `_`, surrounding quotes and whitespace are
+/// trimmed and the result is capped at 100 characters.
+pub fn sanitize_sheet_name(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| match c {
+            '[' | ']' | '*' | '?' | '/' | '\\' | ':' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let trimmed = replaced.trim().trim_matches('\'').trim();
+    let capped: String = trimmed.chars().take(MAX_SHEET_NAME_CHARS).collect();
+    if capped.is_empty() {
+        "Sheet1".to_string()
+    } else {
+        capped
+    }
+}
+
+/// Build an A1 range for `sheet`, quoting the title; `cells` (`A1`, `A1:C20`,
+/// `B:B`, `2:5`) is validated and upper-cased.
+pub fn a1_range(sheet: &str, cells: Option<&str>) -> Result<String, SheetsError> {
+    let quoted = format!("'{}'", sheet.replace('\'', "''"));
+    let Some(cells) = cells.map(str::trim).filter(|c| !c.is_empty()) else {
+        return Ok(quoted);
+    };
+    let parts: Vec<&str> = cells.split(':').collect();
+    if parts.len() > 2 || !parts.iter().all(|part| is_cell_ref(part)) {
+        return Err(SheetsError::Invalid(format!(
+            "Invalid A1 range '{cells}'; expected cells like A1, A1:C20 or B:B (without the sheet name)"
+        )));
+    }
+    Ok(format!("{quoted}!{}", cells.to_ascii_uppercase()))
+}
+
+/// `$A$1`, `A1`, `A` or `1` (a column, row, or cell reference).
+fn is_cell_ref(part: &str) -> bool {
+    let part = part.strip_prefix('$').unwrap_or(part);
+    let letters = part.chars().take_while(char::is_ascii_alphabetic).count();
+    let rest = &part[letters..];
+    let digits = rest.strip_prefix('$').unwrap_or(rest);
+    if letters == 0 && digits.len() != rest.len() {
+        return false;
+    }
+    letters <= 3
+        && digits.len() <= 7
+        && digits.chars().all(|c| c.is_ascii_digit())
+        && (letters > 0 || !digits.is_empty())
+        && !digits.starts_with('0')
+}
+
+/// 0-based index of a column given as letters (`A` → 0, `AA` → 26).
+fn column_index(letters: &str) -> Option<usize> {
+    if letters.is_empty() || letters.len() > 3 || !letters.chars().all(|c| c.is_ascii_alphabetic())
+    {
+        return None;
+    }
+    letters
+        .to_ascii_uppercase()
+        .bytes()
+        .try_fold(0_usize, |acc, b| Some(acc * 26 + usize::from(b - b'A') + 1))
+        .map(|n| n - 1)
+}
+
+fn rows_from_values(values: &Value) -> Vec<Vec<String>> {
+    values
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|row| {
+            row.as_array()
+                .into_iter()
+                .flatten()
+                .map(|cell| match cell {
+                    Value::String(s) => s.clone(),
+                    Value::Null => String::new(),
+                    other => other.to_string(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// `find_rows` matching over rows already read from the sheet.
+pub fn filter_rows(
+    rows: &[Vec<String>],
+    column: &str,
+    query: &str,
+    limit: usize,
+) -> Result<FoundRows, SheetsError> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Err(SheetsError::Invalid("query must not be empty".into()));
+    }
+    let Some((header, data)) = rows.split_first() else {
+        return Ok(FoundRows {
+            header: Vec::new(),
+            rows: Vec::new(),
+            truncated: false,
+        });
+    };
+    let column = column.trim();
+    let index = header
+        .iter()
+        .position(|name| name.trim().eq_ignore_ascii_case(column))
+        .or_else(|| column_index(column))
+        .ok_or_else(|| {
+            SheetsError::Invalid(format!(
+                "Unknown column '{column}'; use a header name ({}) or a column letter",
+                header.join(", ")
+            ))
+        })?;
+
+    let mut matched = data
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| {
+            row.get(index)
+                .is_some_and(|cell| cell.to_lowercase().contains(&query))
+        })
+        // +2: 1-based, and the header is row 1.
+        .map(|(i, row)| (i + 2, row.clone()));
+    let rows: Vec<_> = matched.by_ref().take(limit).collect();
+    Ok(FoundRows {
+        header: header.clone(),
+        rows,
+        truncated: matched.next().is_some(),
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Records requests and answers from a queue of canned responses.
+    #[derive(Default)]
+    pub(crate) struct MockTransport {
+        pub requests: Mutex<Vec<SheetsRequest>>,
+        pub responses: Mutex<VecDeque<Result<Value, SheetsError>>>,
+    }
+
+    impl MockTransport {
+        pub(crate) fn with_responses(
+            responses: impl IntoIterator<Item = Result<Value, SheetsError>>,
+        ) -> Arc<Self> {
+            Arc::new(Self {
+                requests: Mutex::new(Vec::new()),
+                responses: Mutex::new(responses.into_iter().collect()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl SheetsTransport for MockTransport {
+        async fn send(&self, request: SheetsRequest) -> Result<Value, SheetsError> {
+            self.requests.lock().push(request);
+            self.responses.lock().pop_front().unwrap_or(Ok(Value::Null))
+        }
+    }
+
+    pub(crate) fn titles(names: &[&str]) -> Value {
+        json!({ "sheets": names.iter().map(|n| json!({ "properties": { "title": n } })).collect::<Vec<_>>() })
+    }
+
+    fn rows(data: &[&[&str]]) -> Vec<Vec<String>> {
+        data.iter()
+            .map(|row| row.iter().map(|c| (*c).to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn a1_ranges_quote_sheet_names_and_validate_cells() {
+        assert_eq!(a1_range("Notes", None).unwrap(), "'Notes'");
+        assert_eq!(a1_range("Notes", Some("  ")).unwrap(), "'Notes'");
+        assert_eq!(
+            a1_range("Bob's list", Some("a1:c20")).unwrap(),
+            "'Bob''s list'!A1:C20"
+        );
+        assert_eq!(a1_range("S", Some("B:B")).unwrap(), "'S'!B:B");
+        assert_eq!(a1_range("S", Some("2:5")).unwrap(), "'S'!2:5");
+        assert_eq!(a1_range("S", Some("$A$1:$B$2")).unwrap(), "'S'!$A$1:$B$2");
+        for bad in ["Sheet1!A1", "A1:B2:C3", "A0", "ABCD1", "A1;B2", "1A", ":"] {
+            assert!(a1_range("S", Some(bad)).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn sheet_names_are_sanitized() {
+        assert_eq!(sanitize_sheet_name("telegram:12345"), "telegram_12345");
+        assert_eq!(sanitize_sheet_name("a/b\\c[d]*?"), "a_b_c_d___");
+        assert_eq!(sanitize_sheet_name("  'quoted'  "), "quoted");
+        assert_eq!(sanitize_sheet_name(""), "Sheet1");
+        assert_eq!(sanitize_sheet_name(&"x".repeat(150)).chars().count(), 100);
+    }
+
+    #[test]
+    fn filter_rows_by_header_or_letter() {
+        let sheet = rows(&[
+            &["Name", "City", "Notes"],
+            &["Ada", "London", "likes tea"],
+            &["Grace", "New York"],
+            &["Linus", "Portland", "Tea at 4"],
+        ]);
+
+        let found = filter_rows(&sheet, "notes", "TEA", 10).unwrap();
+        assert_eq!(found.header, vec!["Name", "City", "Notes"]);
+        let numbers: Vec<usize> = found.rows.iter().map(|(n, _)| *n).collect();
+        assert_eq!(numbers, vec![2, 4]);
+        assert!(!found.truncated);
+
+        let found = filter_rows(&sheet, "B", "york", 10).unwrap();
+        assert_eq!(
+            found.rows,
+            vec![(3, vec!["Grace".into(), "New York".into()])]
+        );
+
+        let found = filter_rows(&sheet, "City", "o", 1).unwrap();
+        assert_eq!(found.rows.len(), 1);
+        assert!(found.truncated);
+
+        assert!(filter_rows(&sheet, "Missing Column", "x", 10).is_err());
+        assert!(filter_rows(&sheet, "Name", "  ", 10).is_err());
+        assert!(filter_rows(&[], "Name", "x", 10).unwrap().rows.is_empty());
+    }
+
+    #[tokio::test]
+    async fn append_creates_missing_sheet_and_encodes_range() {
+        let transport = MockTransport::with_responses([
+            Ok(titles(&["Sheet1"])),
+            Ok(Value::Null),
+            Ok(json!({ "updates": { "updatedRange": "'Contacts_work'!A2:B2" } })),
+        ]);
+        let client = SheetsClient::new(transport.clone(), 60);
+
+        let range = client
+            .append_row("Contacts/work", &[json!("Ada"), json!(36)])
+            .await
+            .unwrap();
+        assert_eq!(range, "'Contacts_work'!A2:B2");
+
+        let requests = transport.requests.lock();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(
+            requests[1].body.as_ref().unwrap()["requests"][0]["addSheet"]["properties"]["title"],
+            "Contacts_work"
+        );
+        assert_eq!(requests[2].method, HttpMethod::Post);
+        assert_eq!(requests[2].path, "/values/%27Contacts_work%27:append");
+        assert!(requests[2]
+            .query
+            .contains(&("valueInputOption", "RAW".to_string())));
+        assert_eq!(
+            requests[2].body.as_ref().unwrap()["values"],
+            json!([["Ada", 36]])
+        );
+    }
+
+    #[tokio::test]
+    async fn read_range_requires_existing_sheet_and_converts_cells() {
+        let transport = MockTransport::with_responses([
+            Ok(titles(&["Notes"])),
+            Ok(json!({ "values": [["a", 1, true], [null, "b"]] })),
+        ]);
+        let client = SheetsClient::new(transport.clone(), 60);
+
+        let cells = client.read_range("Notes", Some("A1:C2")).await.unwrap();
+        assert_eq!(cells, rows(&[&["a", "1", "true"], &["", "b"]]));
+        assert_eq!(
+            transport.requests.lock()[1].path,
+            "/values/%27Notes%27%21A1%3AC2"
+        );
+
+        let err = client.read_range("Other", None).await.unwrap_err();
+        assert!(err.to_string().contains("does not exist"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn client_side_quota_stops_requests() {
+        let transport = MockTransport::with_responses([Ok(titles(&["Notes"]))]);
+        let client = SheetsClient::new(transport.clone(), 2);
+
+        client.read_range("Notes", None).await.unwrap();
+        let err = client.read_range("Notes", None).await.unwrap_err();
+        assert!(matches!(err, SheetsError::Quota { .. }), "{err}");
+        // The titles lookup and one read went out; the third request did not.
+        assert_eq!(transport.requests.lock().len(), 2);
+    }
+}
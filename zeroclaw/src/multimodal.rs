@@ -97,6 +97,18 @@ pub fn contains_image_markers(messages: &[ChatMessage]) -> bool {
     count_image_markers(messages) > 0
 }
 
+/// Image a tool produced for the model to look at: the `image_path` field of
+/// a JSON tool result (see the `screenshot` tool).
+pub fn tool_output_image(output: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(output).ok()?;
+    value
+        .get("image_path")
+        .and_then(serde_json::Value::as_str)
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(ToString::to_string)
+}
+
 pub fn extract_ollama_image_payload(image_ref: &str) -> Option<String> {
     if image_ref.starts_with("data:") {
         let comma_idx = image_ref.find(',')?;
@@ -507,6 +519,7 @@ mod tests {
             max_images: 1,
             max_image_size_mb: 5,
             allow_remote_fetch: false,
+            ..MultimodalConfig::default()
         };
 
         let error = prepare_messages_for_provider(&messages, &config)
@@ -549,6 +562,7 @@ mod tests {
             max_images: 4,
             max_image_size_mb: 1,
             allow_remote_fetch: false,
+            ..MultimodalConfig::default()
         };
 
         let error = prepare_messages_for_provider(&messages, &config)
@@ -47,6 +47,19 @@ impl Observer for LogObserver {
             ObserverEvent::HeartbeatTick => {
                 info!("heartbeat.tick");
             }
+            ObserverEvent::ContextPacked {
+                truncated_results,
+                dropped_messages,
+            } => {
+                info!(
+                    truncated_results = truncated_results,
+                    dropped_messages = dropped_messages,
+                    "context.packed"
+                );
+            }
+            ObserverEvent::ProviderCache { model, hit } => {
+                info!(model = %model, cache_hit = hit, "provider.cache");
+            }
             ObserverEvent::Error { component, message } => {
                 info!(component = %component, error = %message, "error");
             }
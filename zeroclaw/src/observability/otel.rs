@@ -188,7 +188,9 @@ impl Observer for OtelObserver {
             }
             ObserverEvent::LlmRequest { .. }
             | ObserverEvent::ToolCallStart { .. }
-            | ObserverEvent::TurnComplete => {}
+            | ObserverEvent::TurnComplete
+            | ObserverEvent::ContextPacked { .. }
+            | ObserverEvent::ProviderCache { .. } => {}
             ObserverEvent::LlmResponse {
                 provider,
                 model,
@@ -15,6 +15,8 @@ pub struct PrometheusObserver {
     tool_calls: IntCounterVec,
     channel_messages: IntCounterVec,
     heartbeat_ticks: prometheus::IntCounter,
+    context_packings: IntCounterVec,
+    provider_cache: IntCounterVec,
     errors: IntCounterVec,
 
     // Histograms
@@ -75,6 +77,24 @@ impl PrometheusObserver {
             prometheus::IntCounter::new("zeroclaw_heartbeat_ticks_total", "Total heartbeat ticks")
                 .expect("valid metric");
 
+        let context_packings = IntCounterVec::new(
+            prometheus::Opts::new(
+                "zeroclaw_context_packing_total",
+                "Provider requests whose context was packed, by action",
+            ),
+            &["action"],
+        )
+        .expect("valid metric");
+
+        let provider_cache = IntCounterVec::new(
+            prometheus::Opts::new(
+                "zeroclaw_provider_cache_total",
+                "Cacheable provider calls, by model and cache_hit",
+            ),
+            &["model", "cache_hit"],
+        )
+        .expect("valid metric");
+
         let errors = IntCounterVec::new(
             prometheus::Opts::new("zeroclaw_errors_total", "Total errors by component"),
             &["component"],
@@ -138,6 +158,8 @@ impl PrometheusObserver {
         registry.register(Box::new(tool_calls.clone())).ok();
         registry.register(Box::new(channel_messages.clone())).ok();
         registry.register(Box::new(heartbeat_ticks.clone())).ok();
+        registry.register(Box::new(context_packings.clone())).ok();
+        registry.register(Box::new(provider_cache.clone())).ok();
         registry.register(Box::new(errors.clone())).ok();
         registry.register(Box::new(agent_duration.clone())).ok();
         registry.register(Box::new(tool_duration.clone())).ok();
@@ -155,6 +177,8 @@ impl PrometheusObserver {
             tool_calls,
             channel_messages,
             heartbeat_ticks,
+            context_packings,
+            provider_cache,
             errors,
             agent_duration,
             tool_duration,
@@ -245,6 +269,23 @@ impl Observer for PrometheusObserver {
             ObserverEvent::HeartbeatTick => {
                 self.heartbeat_ticks.inc();
             }
+            ObserverEvent::ContextPacked {
+                truncated_results,
+                dropped_messages,
+            } => {
+                if *truncated_results > 0 {
+                    self.context_packings.with_label_values(&["truncate"]).inc();
+                }
+                if *dropped_messages > 0 {
+                    self.context_packings.with_label_values(&["drop"]).inc();
+                }
+            }
+            ObserverEvent::ProviderCache { model, hit } => {
+                let hit_str = if *hit { "true" } else { "false" };
+                self.provider_cache
+                    .with_label_values(&[model.as_str(), hit_str])
+                    .inc();
+            }
             ObserverEvent::Error {
                 component,
                 message: _,
@@ -380,6 +421,42 @@ mod tests {
         assert!(output.contains("zeroclaw_heartbeat_ticks_total 3"));
     }
 
+    #[test]
+    fn context_packing_counts_by_action() {
+        let obs = PrometheusObserver::new();
+
+        obs.record_event(&ObserverEvent::ContextPacked {
+            truncated_results: 2,
+            dropped_messages: 0,
+        });
+        obs.record_event(&ObserverEvent::ContextPacked {
+            truncated_results: 1,
+            dropped_messages: 4,
+        });
+
+        let output = obs.encode();
+        assert!(output.contains(r#"zeroclaw_context_packing_total{action="truncate"} 2"#));
+        assert!(output.contains(r#"zeroclaw_context_packing_total{action="drop"} 1"#));
+    }
+
+    #[test]
+    fn provider_cache_counts_hits_and_misses() {
+        let obs = PrometheusObserver::new();
+
+        for hit in [false, true, true] {
+            obs.record_event(&ObserverEvent::ProviderCache {
+                model: "gpt-4o-mini".into(),
+                hit,
+            });
+        }
+
+        let output = obs.encode();
+        assert!(output
+            .contains(r#"zeroclaw_provider_cache_total{cache_hit="true",model="gpt-4o-mini"} 2"#));
+        assert!(output
+            .contains(r#"zeroclaw_provider_cache_total{cache_hit="false",model="gpt-4o-mini"} 1"#));
+    }
+
     #[test]
     fn tool_calls_track_success_and_failure_separately() {
         let obs = PrometheusObserver::new();
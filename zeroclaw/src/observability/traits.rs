@@ -58,6 +58,14 @@ pub enum ObserverEvent {
     },
     /// Periodic heartbeat tick from the runtime keep-alive loop.
     HeartbeatTick,
+    /// Older tool results were truncated or dropped before a provider call
+    /// to keep the request within the context budget.
+    ContextPacked {
+        truncated_results: usize,
+        dropped_messages: usize,
+    },
+    /// A cacheable provider call was served from (or missed) the response cache.
+    ProviderCache { model: String, hit: bool },
     /// An error occurred in a named component.
     Error {
         /// Subsystem where the error originated (e.g., `"provider"`, `"gateway"`).
@@ -1,3 +1,5 @@
+mod prompt;
+pub mod templates;
 pub mod wizard;
 
 // Re-exported for CLI and external use
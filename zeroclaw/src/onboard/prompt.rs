@@ -0,0 +1,157 @@
+//! Prompt abstraction for wizard steps.
+//!
+//! Steps written against [`Prompter`] run on the terminal through
+//! [`TerminalPrompter`] and against canned answers in tests, so the flow can
+//! be exercised without a TTY.
+
+use anyhow::Result;
+use dialoguer::{Confirm, Input, MultiSelect, Select};
+
+pub trait Prompter {
+    /// Free-text answer. An empty reply yields `default` when one is given.
+    fn input(&mut self, prompt: &str, default: Option<&str>) -> Result<String>;
+
+    fn confirm(&mut self, prompt: &str, default: bool) -> Result<bool>;
+
+    /// Index of the chosen item.
+    fn select(&mut self, prompt: &str, items: &[String], default: usize) -> Result<usize>;
+
+    /// Indices of the checked items, in ascending order.
+    fn multi_select(
+        &mut self,
+        prompt: &str,
+        items: &[String],
+        checked: &[bool],
+    ) -> Result<Vec<usize>>;
+}
+
+/// Interactive prompts on stdin/stdout via `dialoguer`.
+pub struct TerminalPrompter;
+
+impl Prompter for TerminalPrompter {
+    fn input(&mut self, prompt: &str, default: Option<&str>) -> Result<String> {
+        let mut input = Input::<String>::new()
+            .with_prompt(format!("  {prompt}"))
+            .allow_empty(true);
+        if let Some(default) = default {
+            input = input.default(default.to_string());
+        }
+        Ok(input.interact_text()?.trim().to_string())
+    }
+
+    fn confirm(&mut self, prompt: &str, default: bool) -> Result<bool> {
+        Ok(Confirm::new()
+            .with_prompt(format!("  {prompt}"))
+            .default(default)
+            .interact()?)
+    }
+
+    fn select(&mut self, prompt: &str, items: &[String], default: usize) -> Result<usize> {
+        Ok(Select::new()
+            .with_prompt(format!("  {prompt}"))
+            .items(items)
+            .default(default)
+            .interact()?)
+    }
+
+    fn multi_select(
+        &mut self,
+        prompt: &str,
+        items: &[String],
+        checked: &[bool],
+    ) -> Result<Vec<usize>> {
+        Ok(MultiSelect::new()
+            .with_prompt(format!("  {prompt} (space to toggle, enter to confirm)"))
+            .items(items)
+            .defaults(checked)
+            .interact()?)
+    }
+}
+
+/// Replays scripted answers in order.
+///
+/// `""` accepts the default; confirms take `y`/`n`; selects take an index;
+/// multi-selects take comma-separated indices.
+#[cfg(test)]
+pub struct ScriptedPrompter {
+    answers: std::collections::VecDeque<String>,
+}
+
+#[cfg(test)]
+impl ScriptedPrompter {
+    pub fn new<I, S>(answers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            answers: answers.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.answers.is_empty()
+    }
+
+    fn next(&mut self, prompt: &str) -> Result<String> {
+        self.answers
+            .pop_front()
+            .ok_or_else(|| anyhow::anyhow!("no scripted answer for prompt: {prompt}"))
+    }
+}
+
+#[cfg(test)]
+impl Prompter for ScriptedPrompter {
+    fn input(&mut self, prompt: &str, default: Option<&str>) -> Result<String> {
+        let answer = self.next(prompt)?;
+        Ok(match (answer.trim(), default) {
+            ("", Some(default)) => default.to_string(),
+            (answer, _) => answer.to_string(),
+        })
+    }
+
+    fn confirm(&mut self, prompt: &str, default: bool) -> Result<bool> {
+        match self.next(prompt)?.trim() {
+            "" => Ok(default),
+            "y" => Ok(true),
+            "n" => Ok(false),
+            other => anyhow::bail!("bad confirm answer {other:?} for prompt: {prompt}"),
+        }
+    }
+
+    fn select(&mut self, prompt: &str, items: &[String], default: usize) -> Result<usize> {
+        let answer = self.next(prompt)?;
+        let index = if answer.trim().is_empty() {
+            default
+        } else {
+            answer.trim().parse()?
+        };
+        anyhow::ensure!(index < items.len(), "select index {index} out of range");
+        Ok(index)
+    }
+
+    fn multi_select(
+        &mut self,
+        prompt: &str,
+        items: &[String],
+        checked: &[bool],
+    ) -> Result<Vec<usize>> {
+        let answer = self.next(prompt)?;
+        if answer.trim().is_empty() {
+            return Ok((0..items.len())
+                .filter(|&i| checked.get(i).copied().unwrap_or(false))
+                .collect());
+        }
+        let mut picked = answer
+            .split(',')
+            .map(|part| part.trim().parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()?;
+        picked.sort_unstable();
+        picked.dedup();
+        anyhow::ensure!(
+            picked.iter().all(|&i| i < items.len()),
+            "multi-select index out of range"
+        );
+        Ok(picked)
+    }
+}
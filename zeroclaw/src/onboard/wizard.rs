@@ -1,11 +1,15 @@
+use super::prompt::{Prompter, TerminalPrompter};
+use super::templates::{Template, TemplateManifest};
 use crate::config::schema::{
-    default_nostr_relays, DingTalkConfig, IrcConfig, LarkReceiveMode, LinqConfig,
-    NextcloudTalkConfig, NostrConfig, QQConfig, SignalConfig, StreamMode, WhatsAppConfig,
+    default_nostr_relays, DingTalkConfig, GoogleChatConfig, IrcConfig, LarkReceiveMode, LinqConfig,
+    MattermostConfig, NextcloudTalkConfig, NostrConfig, QQConfig, SignalConfig, StreamMode,
+    WatiConfig, WhatsAppConfig,
 };
 use crate::config::{
     AutonomyConfig, BrowserConfig, ChannelsConfig, ComposioConfig, Config, DiscordConfig,
-    HeartbeatConfig, IMessageConfig, LarkConfig, MatrixConfig, MemoryConfig, ObservabilityConfig,
-    RuntimeConfig, SecretsConfig, SlackConfig, StorageConfig, TelegramConfig, WebhookConfig,
+    GatewayConfig, HeartbeatConfig, IMessageConfig, LarkConfig, MatrixConfig, MemoryConfig,
+    ObservabilityConfig, RuntimeConfig, SecretsConfig, SlackConfig, StorageConfig, TelegramConfig,
+    WebhookConfig,
 };
 use crate::hardware::{self, HardwareConfig};
 use crate::memory::{
@@ -30,7 +34,7 @@ use tokio::fs;
 // ── Project context collected during wizard ──────────────────────
 
 /// User-provided personalization baked into workspace MD files.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ProjectContext {
     pub user_name: String,
     pub timezone: String,
@@ -71,6 +75,7 @@ fn has_launchable_channels(channels: &ChannelsConfig) -> bool {
 enum InteractiveOnboardingMode {
     FullOnboarding,
     UpdateProviderOnly,
+    EditSections,
 }
 
 pub async fn run_wizard(force: bool) -> Result<Config> {
@@ -88,37 +93,44 @@ pub async fn run_wizard(force: bool) -> Result<Config> {
     );
     println!();
 
-    print_step(1, 9, "Workspace Setup");
+    print_step(1, 10, "Workspace Setup");
     let (workspace_dir, config_path) = setup_workspace().await?;
     match resolve_interactive_onboarding_mode(&config_path, force)? {
         InteractiveOnboardingMode::FullOnboarding => {}
         InteractiveOnboardingMode::UpdateProviderOnly => {
             return run_provider_update_wizard(&workspace_dir, &config_path).await;
         }
+        InteractiveOnboardingMode::EditSections => {
+            return run_section_edit_wizard(&workspace_dir, &config_path).await;
+        }
     }
 
-    print_step(2, 9, "AI Provider & API Key");
+    print_step(2, 10, "AI Provider & API Key");
     let (provider, api_key, model, provider_api_url) = setup_provider(&workspace_dir).await?;
 
-    print_step(3, 9, "Channels (How You Talk to ZeroClaw)");
-    let channels_config = setup_channels()?;
+    print_step(3, 10, "Channels (How You Talk to ZeroClaw)");
+    let channels_config = setup_channels(ChannelsConfig::default())?;
 
-    print_step(4, 9, "Tunnel (Expose to Internet)");
+    print_step(4, 10, "Tunnel (Expose to Internet)");
     let tunnel_config = setup_tunnel()?;
 
-    print_step(5, 9, "Tool Mode & Security");
+    print_step(5, 10, "Gateway");
+    let (gateway_config, gateway_token) =
+        setup_gateway(&mut TerminalPrompter, &GatewayConfig::default())?;
+
+    print_step(6, 10, "Tool Mode & Security");
     let (composio_config, secrets_config) = setup_tool_mode()?;
 
-    print_step(6, 9, "Hardware (Physical World)");
+    print_step(7, 10, "Hardware (Physical World)");
     let hardware_config = setup_hardware()?;
 
-    print_step(7, 9, "Memory Configuration");
+    print_step(8, 10, "Memory Configuration");
     let memory_config = setup_memory()?;
 
-    print_step(8, 9, "Project Context (Personalize Your Agent)");
+    print_step(9, 10, "Project Context (Personalize Your Agent)");
     let project_ctx = setup_project_context()?;
 
-    print_step(9, 9, "Workspace Files");
+    print_step(10, 10, "Workspace Files");
     scaffold_workspace(&workspace_dir, &project_ctx).await?;
 
     // ── Build config ──
@@ -126,6 +138,7 @@ pub async fn run_wizard(force: bool) -> Result<Config> {
     let config = Config {
         workspace_dir: workspace_dir.clone(),
         config_path: config_path.clone(),
+        secret_refs: Vec::new(),
         api_key: if api_key.is_empty() {
             None
         } else {
@@ -141,6 +154,8 @@ pub async fn run_wizard(force: bool) -> Result<Config> {
         security: crate::config::SecurityConfig::default(),
         runtime: RuntimeConfig::default(),
         reliability: crate::config::ReliabilityConfig::default(),
+        openrouter: crate::config::OpenRouterConfig::default(),
+        session_compaction: crate::config::SessionCompactionConfig::default(),
         scheduler: crate::config::schema::SchedulerConfig::default(),
         agent: crate::config::schema::AgentConfig::default(),
         skills: crate::config::SkillsConfig::default(),
@@ -148,18 +163,27 @@ pub async fn run_wizard(force: bool) -> Result<Config> {
         embedding_routes: Vec::new(),
         heartbeat: HeartbeatConfig::default(),
         cron: crate::config::CronConfig::default(),
+        quiet_hours: crate::config::QuietHoursConfig::default(),
+        // Only keep names the scheduler can resolve; typos fall back to local time.
+        timezone: project_ctx
+            .timezone
+            .parse::<chrono_tz::Tz>()
+            .ok()
+            .map(|_| project_ctx.timezone.clone()),
         channels_config,
         memory: memory_config, // User-selected memory backend
         storage: StorageConfig::default(),
         tunnel: tunnel_config,
-        gateway: crate::config::GatewayConfig::default(),
+        gateway: gateway_config,
         composio: composio_config,
+        google_sheets: None,
         secrets: secrets_config,
         browser: BrowserConfig::default(),
         http_request: crate::config::HttpRequestConfig::default(),
         multimodal: crate::config::MultimodalConfig::default(),
         web_fetch: crate::config::WebFetchConfig::default(),
         web_search: crate::config::WebSearchConfig::default(),
+        web: crate::config::WebConfig::default(),
         proxy: crate::config::ProxyConfig::default(),
         identity: crate::config::IdentityConfig::default(),
         cost: crate::config::CostConfig::default(),
@@ -187,6 +211,9 @@ pub async fn run_wizard(force: bool) -> Result<Config> {
     persist_workspace_selection(&config.config_path).await?;
 
     // ── Final summary ────────────────────────────────────────────
+    if let Some(token) = gateway_token {
+        print_minted_token(&token);
+    }
     print_summary(&config);
 
     // ── Offer to launch channels immediately ─────────────────────
@@ -231,7 +258,7 @@ pub async fn run_channels_repair_wizard() -> Result<Config> {
     let mut config = Config::load_or_init().await?;
 
     print_step(1, 1, "Channels (How You Talk to ZeroClaw)");
-    config.channels_config = setup_channels()?;
+    config.channels_config = setup_channels(config.channels_config.clone())?;
     config.save().await?;
     persist_workspace_selection(&config.config_path).await?;
 
@@ -277,20 +304,7 @@ async fn run_provider_update_wizard(workspace_dir: &Path, config_path: &Path) ->
         style("↻").cyan().bold()
     );
 
-    let raw = fs::read_to_string(config_path).await.with_context(|| {
-        format!(
-            "Failed to read existing config at {}",
-            config_path.display()
-        )
-    })?;
-    let mut config: Config = toml::from_str(&raw).with_context(|| {
-        format!(
-            "Failed to parse existing config at {}",
-            config_path.display()
-        )
-    })?;
-    config.workspace_dir = workspace_dir.to_path_buf();
-    config.config_path = config_path.to_path_buf();
+    let mut config = load_existing_config(workspace_dir, config_path).await?;
 
     print_step(1, 1, "AI Provider & API Key");
     let (provider, api_key, model, provider_api_url) = setup_provider(workspace_dir).await?;
@@ -331,6 +345,24 @@ async fn run_provider_update_wizard(workspace_dir: &Path, config_path: &Path) ->
     Ok(config)
 }
 
+async fn load_existing_config(workspace_dir: &Path, config_path: &Path) -> Result<Config> {
+    let raw = fs::read_to_string(config_path).await.with_context(|| {
+        format!(
+            "Failed to read existing config at {}",
+            config_path.display()
+        )
+    })?;
+    let mut config: Config = toml::from_str(&raw).with_context(|| {
+        format!(
+            "Failed to parse existing config at {}",
+            config_path.display()
+        )
+    })?;
+    config.workspace_dir = workspace_dir.to_path_buf();
+    config.config_path = config_path.to_path_buf();
+    Ok(config)
+}
+
 fn apply_provider_update(
     config: &mut Config,
     provider: String,
@@ -369,6 +401,8 @@ fn memory_config_defaults_for_backend(backend: &str) -> MemoryConfig {
         archive_after_days: if profile.uses_sqlite_hygiene { 7 } else { 0 },
         purge_after_days: if profile.uses_sqlite_hygiene { 30 } else { 0 },
         conversation_retention_days: 30,
+        session_retention_days: 0,
+        cross_session_search: false,
         embedding_provider: "none".to_string(),
         embedding_model: "text-embedding-3-small".to_string(),
         embedding_dimensions: 1536,
@@ -384,6 +418,7 @@ fn memory_config_defaults_for_backend(backend: &str) -> MemoryConfig {
         response_cache_enabled: false,
         response_cache_ttl_minutes: 60,
         response_cache_max_entries: 5_000,
+        response_cache_max_temperature: 0.3,
         snapshot_enabled: false,
         snapshot_on_hygiene: false,
         auto_hydrate: true,
@@ -477,6 +512,7 @@ async fn run_quick_setup_with_home(
     let config = Config {
         workspace_dir: workspace_dir.clone(),
         config_path: config_path.clone(),
+        secret_refs: Vec::new(),
         api_key: credential_override.map(|c| {
             let mut s = String::with_capacity(c.len());
             s.push_str(c);
@@ -492,6 +528,8 @@ async fn run_quick_setup_with_home(
         security: crate::config::SecurityConfig::default(),
         runtime: RuntimeConfig::default(),
         reliability: crate::config::ReliabilityConfig::default(),
+        openrouter: crate::config::OpenRouterConfig::default(),
+        session_compaction: crate::config::SessionCompactionConfig::default(),
         scheduler: crate::config::schema::SchedulerConfig::default(),
         agent: crate::config::schema::AgentConfig::default(),
         skills: crate::config::SkillsConfig::default(),
@@ -499,18 +537,22 @@ async fn run_quick_setup_with_home(
         embedding_routes: Vec::new(),
         heartbeat: HeartbeatConfig::default(),
         cron: crate::config::CronConfig::default(),
+        quiet_hours: crate::config::QuietHoursConfig::default(),
+        timezone: None,
         channels_config: ChannelsConfig::default(),
         memory: memory_config,
         storage: StorageConfig::default(),
         tunnel: crate::config::TunnelConfig::default(),
         gateway: crate::config::GatewayConfig::default(),
         composio: ComposioConfig::default(),
+        google_sheets: None,
         secrets: SecretsConfig::default(),
         browser: BrowserConfig::default(),
         http_request: crate::config::HttpRequestConfig::default(),
         multimodal: crate::config::MultimodalConfig::default(),
         web_fetch: crate::config::WebFetchConfig::default(),
         web_search: crate::config::WebSearchConfig::default(),
+        web: crate::config::WebConfig::default(),
         proxy: crate::config::ProxyConfig::default(),
         identity: crate::config::IdentityConfig::default(),
         cost: crate::config::CostConfig::default(),
@@ -1907,6 +1949,15 @@ pub async fn cached_model_catalog_stats(
     Ok(Some((cached.models.len(), cached.age_secs)))
 }
 
+/// Model IDs cached for `provider_name` by `zeroclaw models refresh`, regardless of age.
+pub async fn cached_model_ids(config: &Config, provider_name: &str) -> Result<Option<Vec<String>>> {
+    Ok(
+        load_any_cached_models_for_provider(&config.workspace_dir, provider_name)
+            .await?
+            .map(|cached| cached.models),
+    )
+}
+
 pub async fn run_models_refresh_all(config: &Config, force: bool) -> Result<()> {
     let mut targets: Vec<String> = crate::providers::list_providers()
         .into_iter()
@@ -1996,6 +2047,7 @@ fn resolve_interactive_onboarding_mode(
     let options = [
         "Full onboarding (overwrite config.toml)",
         "Update AI provider/model/API key only (preserve existing configuration)",
+        "Edit selected sections (provider, channels, gateway, tunnel, memory)",
         "Cancel",
     ];
 
@@ -2011,6 +2063,7 @@ fn resolve_interactive_onboarding_mode(
     match mode {
         0 => Ok(InteractiveOnboardingMode::FullOnboarding),
         1 => Ok(InteractiveOnboardingMode::UpdateProviderOnly),
+        2 => Ok(InteractiveOnboardingMode::EditSections),
         _ => bail!("Onboarding canceled: existing configuration was left unchanged."),
     }
 }
@@ -3323,14 +3376,17 @@ enum ChannelMenuChoice {
     Telegram,
     Discord,
     Slack,
+    Mattermost,
     IMessage,
     Matrix,
     Signal,
     WhatsApp,
+    Wati,
     Linq,
     Irc,
     Webhook,
     NextcloudTalk,
+    GoogleChat,
     DingTalk,
     QqOfficial,
     Lark,
@@ -3343,14 +3399,17 @@ const CHANNEL_MENU_CHOICES: &[ChannelMenuChoice] = &[
     ChannelMenuChoice::Telegram,
     ChannelMenuChoice::Discord,
     ChannelMenuChoice::Slack,
+    ChannelMenuChoice::Mattermost,
     ChannelMenuChoice::IMessage,
     ChannelMenuChoice::Matrix,
     ChannelMenuChoice::Signal,
     ChannelMenuChoice::WhatsApp,
+    ChannelMenuChoice::Wati,
     ChannelMenuChoice::Linq,
     ChannelMenuChoice::Irc,
     ChannelMenuChoice::Webhook,
     ChannelMenuChoice::NextcloudTalk,
+    ChannelMenuChoice::GoogleChat,
     ChannelMenuChoice::DingTalk,
     ChannelMenuChoice::QqOfficial,
     ChannelMenuChoice::Lark,
@@ -3363,13 +3422,15 @@ fn channel_menu_choices() -> &'static [ChannelMenuChoice] {
     CHANNEL_MENU_CHOICES
 }
 
+/// Channel menu loop. Starts from `existing`, so rerunning it edits the
+/// configured channels instead of dropping them.
 #[allow(clippy::too_many_lines)]
-fn setup_channels() -> Result<ChannelsConfig> {
+fn setup_channels(existing: ChannelsConfig) -> Result<ChannelsConfig> {
     print_bullet("Channels let you talk to ZeroClaw from anywhere.");
     print_bullet("CLI is always available. Connect more channels now.");
     println!();
 
-    let mut config = ChannelsConfig::default();
+    let mut config = existing;
     let menu_choices = channel_menu_choices();
 
     loop {
@@ -3400,6 +3461,14 @@ fn setup_channels() -> Result<ChannelsConfig> {
                         "— connect your bot"
                     }
                 ),
+                ChannelMenuChoice::Mattermost => format!(
+                    "Mattermost {}",
+                    if config.mattermost.is_some() {
+                        "✅ connected"
+                    } else {
+                        "— connect your bot"
+                    }
+                ),
                 ChannelMenuChoice::IMessage => format!(
                     "iMessage   {}",
                     if config.imessage.is_some() {
@@ -3432,6 +3501,14 @@ fn setup_channels() -> Result<ChannelsConfig> {
                         "— Business Cloud API"
                     }
                 ),
+                ChannelMenuChoice::Wati => format!(
+                    "WATI       {}",
+                    if config.wati.is_some() {
+                        "✅ connected"
+                    } else {
+                        "— WhatsApp via WATI Business API"
+                    }
+                ),
                 ChannelMenuChoice::Linq => format!(
                     "Linq       {}",
                     if config.linq.is_some() {
@@ -3464,6 +3541,14 @@ fn setup_channels() -> Result<ChannelsConfig> {
                         "— Talk webhook + OCS API"
                     }
                 ),
+                ChannelMenuChoice::GoogleChat => format!(
+                    "Google Chat {}",
+                    if config.google_chat.is_some() {
+                        "✅ connected"
+                    } else {
+                        "— Workspace Chat app"
+                    }
+                ),
                 ChannelMenuChoice::DingTalk => format!(
                     "DingTalk   {}",
                     if config.dingtalk.is_some() {
@@ -3618,6 +3703,8 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     draft_update_interval_ms: 1000,
                     interrupt_on_new_message: false,
                     mention_only: false,
+                    command_prefix: None,
+                    progress_messages: false,
                 });
             }
             ChannelMenuChoice::Discord => {
@@ -3717,6 +3804,8 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     allowed_users,
                     listen_to_bots: false,
                     mention_only: false,
+                    command_prefix: None,
+                    command_users: vec![],
                 });
             }
             ChannelMenuChoice::Slack => {
@@ -3844,6 +3933,7 @@ fn setup_channels() -> Result<ChannelsConfig> {
                         Some(channel)
                     },
                     allowed_users,
+                    progress_messages: false,
                 });
             }
             ChannelMenuChoice::IMessage => {
@@ -4174,6 +4264,8 @@ fn setup_channels() -> Result<ChannelsConfig> {
                         pair_code: (!pair_code.trim().is_empty())
                             .then(|| pair_code.trim().to_string()),
                         allowed_numbers,
+                        max_event_age_secs: 3600,
+                        template: None,
                     });
 
                     println!(
@@ -4266,15 +4358,24 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     users_str.split(',').map(|s| s.trim().to_string()).collect()
                 };
 
+                print_bullet("App secret lets the gateway verify webhook signatures.");
+                let app_secret: String = Input::new()
+                    .with_prompt("  App secret (optional, or set ZEROCLAW_WHATSAPP_APP_SECRET)")
+                    .allow_empty(true)
+                    .interact_text()?;
+
                 config.whatsapp = Some(WhatsAppConfig {
                     access_token: Some(access_token.trim().to_string()),
                     phone_number_id: Some(phone_number_id.trim().to_string()),
                     verify_token: Some(verify_token.trim().to_string()),
-                    app_secret: None, // Can be set via ZEROCLAW_WHATSAPP_APP_SECRET env var
+                    app_secret: (!app_secret.trim().is_empty())
+                        .then(|| app_secret.trim().to_string()),
                     session_path: None,
                     pair_phone: None,
                     pair_code: None,
                     allowed_numbers,
+                    max_event_age_secs: 3600,
+                    template: None,
                 });
             }
             ChannelMenuChoice::Linq => {
@@ -5031,6 +5132,18 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     style(relays.len()).cyan()
                 );
             }
+            ChannelMenuChoice::Mattermost => {
+                let result = setup_mattermost(&mut TerminalPrompter, config.mattermost.as_ref())?;
+                apply_channel_step(&mut config.mattermost, result, "Mattermost");
+            }
+            ChannelMenuChoice::Wati => {
+                let result = setup_wati(&mut TerminalPrompter, config.wati.as_ref())?;
+                apply_channel_step(&mut config.wati, result, "WATI");
+            }
+            ChannelMenuChoice::GoogleChat => {
+                let result = setup_google_chat(&mut TerminalPrompter, config.google_chat.as_ref())?;
+                apply_channel_step(&mut config.google_chat, result, "Google Chat");
+            }
             ChannelMenuChoice::Done => break,
         }
         println!();
@@ -5053,6 +5166,184 @@ fn setup_channels() -> Result<ChannelsConfig> {
     Ok(config)
 }
 
+// ── Prompter-driven channel steps ───────────────────────────────
+//
+// These take the currently configured section (if any) so re-running the
+// wizard edits values in place: an empty answer keeps what is there.
+
+/// Comma-separated allowlist; a lone `*` means everyone.
+fn parse_allowlist(raw: &str) -> Vec<String> {
+    if raw.trim() == "*" {
+        return vec!["*".into()];
+    }
+    raw.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn optional_answer(answer: String) -> Option<String> {
+    (!answer.is_empty()).then_some(answer)
+}
+
+fn setup_google_chat(
+    p: &mut dyn Prompter,
+    existing: Option<&GoogleChatConfig>,
+) -> Result<Option<GoogleChatConfig>> {
+    println!();
+    println!(
+        "  {} {}",
+        style("Google Chat Setup").white().bold(),
+        style("— Workspace Chat app via gateway /google-chat").dim()
+    );
+    print_bullet("1. Create a Chat app in Google Cloud Console (APIs → Google Chat API)");
+    print_bullet("2. Set the HTTP endpoint URL to https://<your-gateway>/google-chat");
+    print_bullet("3. Copy the project number from the Cloud project dashboard");
+    println!();
+
+    let project_number = p.input(
+        "Project number",
+        existing.map(|c| c.project_number.as_str()),
+    )?;
+    if project_number.is_empty() {
+        return Ok(None);
+    }
+
+    let current_users = existing.map(|c| c.allowed_users.join(","));
+    let allowed_users = parse_allowlist(&p.input(
+        "Allowed user emails or users/{id} (comma-separated, '*' for all)",
+        current_users.as_deref().filter(|s| !s.is_empty()),
+    )?);
+    if allowed_users.is_empty() {
+        println!(
+            "  {} No users allowlisted — Google Chat messages will be denied until you add users or '*'.",
+            style("⚠").yellow().bold()
+        );
+    }
+
+    Ok(Some(GoogleChatConfig {
+        project_number,
+        allowed_users,
+        service_account_json: existing.and_then(|c| c.service_account_json.clone()),
+    }))
+}
+
+fn setup_wati(p: &mut dyn Prompter, existing: Option<&WatiConfig>) -> Result<Option<WatiConfig>> {
+    println!();
+    println!(
+        "  {} {}",
+        style("WATI Setup").white().bold(),
+        style("— WhatsApp via WATI Business API").dim()
+    );
+    print_bullet("1. Open WATI dashboard → API Docs and copy the access token");
+    print_bullet("2. Point the WATI webhook at https://<your-gateway>/wati");
+    println!();
+
+    let api_token = p.input("API token", existing.map(|c| c.api_token.as_str()))?;
+    if api_token.is_empty() {
+        return Ok(None);
+    }
+
+    let api_url = p.input(
+        "API base URL",
+        Some(existing.map_or("https://live-mt-server.wati.io", |c| c.api_url.as_str())),
+    )?;
+    let tenant_id = optional_answer(p.input(
+        "Tenant ID (optional, multi-channel setups)",
+        existing.and_then(|c| c.tenant_id.as_deref()),
+    )?);
+    let current_numbers = existing.map(|c| c.allowed_numbers.join(","));
+    let allowed_numbers = parse_allowlist(
+        &p.input(
+            "Allowed phone numbers (comma-separated +1234567890, or * for all)",
+            Some(
+                current_numbers
+                    .as_deref()
+                    .filter(|s| !s.is_empty())
+                    .unwrap_or("*"),
+            ),
+        )?,
+    );
+
+    Ok(Some(WatiConfig {
+        api_token,
+        api_url,
+        tenant_id,
+        allowed_numbers,
+    }))
+}
+
+fn setup_mattermost(
+    p: &mut dyn Prompter,
+    existing: Option<&MattermostConfig>,
+) -> Result<Option<MattermostConfig>> {
+    println!();
+    println!(
+        "  {} {}",
+        style("Mattermost Setup").white().bold(),
+        style("— talk to ZeroClaw from Mattermost").dim()
+    );
+    print_bullet("1. System Console → Integrations → Bot Accounts → Add Bot Account");
+    print_bullet("2. Copy the bot access token and add the bot to your channel");
+    println!();
+
+    let url = p.input(
+        "Server URL (e.g. https://mattermost.example.com)",
+        existing.map(|c| c.url.as_str()),
+    )?;
+    if url.is_empty() {
+        return Ok(None);
+    }
+    let bot_token = p.input("Bot access token", existing.map(|c| c.bot_token.as_str()))?;
+    if bot_token.is_empty() {
+        return Ok(None);
+    }
+
+    let channel_id = optional_answer(p.input(
+        "Channel ID (optional, restricts the bot to one channel)",
+        existing.and_then(|c| c.channel_id.as_deref()),
+    )?);
+    let current_users = existing.map(|c| c.allowed_users.join(","));
+    let allowed_users = parse_allowlist(&p.input(
+        "Allowed user IDs (comma-separated, '*' for all)",
+        current_users.as_deref().filter(|s| !s.is_empty()),
+    )?);
+    if allowed_users.is_empty() {
+        println!(
+            "  {} No users allowlisted — Mattermost messages will be denied until you add user IDs or '*'.",
+            style("⚠").yellow().bold()
+        );
+    }
+    let thread_replies = p.confirm(
+        "Reply in threads?",
+        existing.and_then(|c| c.thread_replies).unwrap_or(true),
+    )?;
+    let mention_only = p.confirm(
+        "Only respond when @-mentioned?",
+        existing.and_then(|c| c.mention_only).unwrap_or(false),
+    )?;
+
+    Ok(Some(MattermostConfig {
+        url: url.trim_end_matches('/').to_string(),
+        bot_token,
+        channel_id,
+        allowed_users,
+        thread_replies: Some(thread_replies),
+        mention_only: Some(mention_only),
+    }))
+}
+
+/// Apply a Prompter-driven channel step: store the result or report a skip.
+fn apply_channel_step<T>(slot: &mut Option<T>, result: Option<T>, name: &str) {
+    match result {
+        Some(cfg) => {
+            *slot = Some(cfg);
+            println!("  {} {name} configured", style("✅").green().bold());
+        }
+        None => println!("  {} Skipped", style("→").dim()),
+    }
+}
+
 // ── Step 4: Tunnel ──────────────────────────────────────────────
 
 #[allow(clippy::too_many_lines)]
@@ -5208,234 +5499,203 @@ fn setup_tunnel() -> Result<crate::config::TunnelConfig> {
     Ok(config)
 }
 
-// ── Step 6: Scaffold workspace files ─────────────────────────────
+// ── Step 5: Gateway ─────────────────────────────────────────────
+
+/// Prompt for gateway bind address, auth and turn limits.
+///
+/// Returns the updated config plus the plaintext bearer token when a new one
+/// was minted. Only its hash is stored in `paired_tokens`, so the caller must
+/// show the plaintext to the user now.
+fn setup_gateway(
+    p: &mut dyn Prompter,
+    existing: &GatewayConfig,
+) -> Result<(GatewayConfig, Option<String>)> {
+    use crate::security::pairing::{is_public_bind, mint_paired_token};
+
+    print_bullet("The gateway serves webhooks, the dashboard API and WebSocket chat.");
+    print_bullet("Keep it on 127.0.0.1 and use a tunnel unless you know you need more.");
+    println!();
 
-#[allow(clippy::too_many_lines)]
-async fn scaffold_workspace(workspace_dir: &Path, ctx: &ProjectContext) -> Result<()> {
-    let agent = if ctx.agent_name.is_empty() {
-        "ZeroClaw"
-    } else {
-        &ctx.agent_name
-    };
-    let user = if ctx.user_name.is_empty() {
-        "User"
-    } else {
-        &ctx.user_name
-    };
-    let tz = if ctx.timezone.is_empty() {
-        "UTC"
-    } else {
-        &ctx.timezone
-    };
-    let comm_style = if ctx.communication_style.is_empty() {
-        "Be warm, natural, and clear. Use occasional relevant emojis (1-2 max) and avoid robotic phrasing."
+    let mut config = existing.clone();
+
+    config.host = p.input("Bind host", Some(&existing.host))?;
+    let port = p.input("Port", Some(&existing.port.to_string()))?;
+    config.port = port
+        .parse()
+        .with_context(|| format!("Invalid gateway port: {port}"))?;
+
+    config.allow_public_bind = false;
+    if is_public_bind(&config.host) {
+        println!(
+            "  {} Binding to {} exposes the gateway to every network this machine is on.",
+            style("⚠").yellow().bold(),
+            style(&config.host).yellow()
+        );
+        print_bullet("Anyone who can reach the port can talk to your agent once paired.");
+        config.allow_public_bind = p.confirm("Allow public bind anyway?", false)?;
+        if !config.allow_public_bind {
+            println!(
+                "  {} Falling back to 127.0.0.1 — use a tunnel to expose the gateway.",
+                style("→").dim()
+            );
+            config.host = "127.0.0.1".into();
+        }
+    }
+
+    config.require_pairing = p.confirm("Require a bearer token for API requests?", true)?;
+    let mut minted = None;
+    if config.require_pairing {
+        let mint = p.confirm(
+            "Generate a new auth token now? (otherwise pair later via /pair)",
+            existing.paired_tokens.is_empty(),
+        )?;
+        if mint {
+            let (token, hash) = mint_paired_token();
+            config.paired_tokens.push(hash);
+            minted = Some(token);
+        }
     } else {
-        &ctx.communication_style
-    };
+        println!(
+            "  {} Pairing disabled — any client that reaches the gateway is trusted.",
+            style("⚠").yellow().bold()
+        );
+    }
 
-    let identity = format!(
-        "# IDENTITY.md — Who Am I?\n\n\
-         - **Name:** {agent}\n\
-         - **Creature:** A Rust-forged AI — fast, lean, and relentless\n\
-         - **Vibe:** Sharp, direct, resourceful. Not corporate. Not a chatbot.\n\
-         - **Emoji:** \u{1f980}\n\n\
-         ---\n\n\
-         Update this file as you evolve. Your identity is yours to shape.\n"
-    );
+    let turns = p.input(
+        "Max concurrent agent turns",
+        Some(&existing.max_concurrent_turns.to_string()),
+    )?;
+    config.max_concurrent_turns = turns
+        .parse()
+        .with_context(|| format!("Invalid turn limit: {turns}"))?;
+    let capacity = p.input(
+        "Inbound queue capacity (messages waiting for a turn)",
+        Some(&existing.inbound_queue_capacity.to_string()),
+    )?;
+    config.inbound_queue_capacity = capacity
+        .parse()
+        .with_context(|| format!("Invalid queue capacity: {capacity}"))?;
 
-    let agents = format!(
-        "# AGENTS.md — {agent} Personal Assistant\n\n\
-         ## Every Session (required)\n\n\
-         Before doing anything else:\n\n\
-         1. Read `SOUL.md` — this is who you are\n\
-         2. Read `USER.md` — this is who you're helping\n\
-         3. Use `memory_recall` for recent context (daily notes are on-demand)\n\
-         4. If in MAIN SESSION (direct chat): `MEMORY.md` is already injected\n\n\
-         Don't ask permission. Just do it.\n\n\
-         ## Memory System\n\n\
-         You wake up fresh each session. These files ARE your continuity:\n\n\
-         - **Daily notes:** `memory/YYYY-MM-DD.md` — raw logs (accessed via memory tools)\n\
-         - **Long-term:** `MEMORY.md` — curated memories (auto-injected in main session)\n\n\
-         Capture what matters. Decisions, context, things to remember.\n\
-         Skip secrets unless asked to keep them.\n\n\
-         ### Write It Down — No Mental Notes!\n\
-         - Memory is limited — if you want to remember something, WRITE IT TO A FILE\n\
-         - \"Mental notes\" don't survive session restarts. Files do.\n\
-         - When someone says \"remember this\" -> update daily file or MEMORY.md\n\
-         - When you learn a lesson -> update AGENTS.md, TOOLS.md, or the relevant skill\n\n\
-         ## Safety\n\n\
-         - Don't exfiltrate private data. Ever.\n\
-         - Don't run destructive commands without asking.\n\
-         - `trash` > `rm` (recoverable beats gone forever)\n\
-         - When in doubt, ask.\n\n\
-         ## External vs Internal\n\n\
-         **Safe to do freely:** Read files, explore, organize, learn, search the web.\n\n\
-         **Ask first:** Sending emails/tweets/posts, anything that leaves the machine.\n\n\
-         ## Group Chats\n\n\
-         Participate, don't dominate. Respond when mentioned or when you add genuine value.\n\
-         Stay silent when it's casual banter or someone already answered.\n\n\
-         ## Tools & Skills\n\n\
-         Skills are listed in the system prompt. Use `read` on a skill's SKILL.md for details.\n\
-         Keep local notes (SSH hosts, device names, etc.) in `TOOLS.md`.\n\n\
-         ## Crash Recovery\n\n\
-         - If a run stops unexpectedly, recover context before acting.\n\
-         - Check `MEMORY.md` + latest `memory/*.md` notes to avoid duplicate work.\n\
-         - Resume from the last confirmed step, not from scratch.\n\n\
-         ## Sub-task Scoping\n\n\
-         - Break complex work into focused sub-tasks with clear success criteria.\n\
-         - Keep sub-tasks small, verify each output, then merge results.\n\
-         - Prefer one clear objective per sub-task over broad \"do everything\" asks.\n\n\
-         ## Make It Yours\n\n\
-         This is a starting point. Add your own conventions, style, and rules.\n"
+    println!(
+        "  {} Gateway: {}:{} (pairing: {})",
+        style("✓").green().bold(),
+        style(&config.host).green(),
+        style(config.port).green(),
+        if config.require_pairing { "on" } else { "off" }
     );
+    Ok((config, minted))
+}
 
-    let heartbeat = format!(
-        "# HEARTBEAT.md\n\n\
-         # Keep this file empty (or with only comments) to skip heartbeat work.\n\
-         # Add tasks below when you want {agent} to check something periodically.\n\
-         #\n\
-         # Examples:\n\
-         # - Check my email for important messages\n\
-         # - Review my calendar for upcoming events\n\
-         # - Run `git status` on my active projects\n"
+fn print_minted_token(token: &str) {
+    println!();
+    println!(
+        "  {} Gateway auth token (shown once — store it now):",
+        style("🔑").cyan()
     );
+    println!("     {}", style(token).white().bold());
+    print_bullet("Send it as 'Authorization: Bearer <token>' to the gateway API.");
+}
 
-    let soul = format!(
-        "# SOUL.md — Who You Are\n\n\
-         *You're not a chatbot. You're becoming someone.*\n\n\
-         ## Core Truths\n\n\
-         **Be genuinely helpful, not performatively helpful.**\n\
-         Skip the \"Great question!\" and \"I'd be happy to help!\" — just help.\n\n\
-         **Have opinions.** You're allowed to disagree, prefer things,\n\
-         find stuff amusing or boring.\n\n\
-         **Be resourceful before asking.** Try to figure it out.\n\
-         Read the file. Check the context. Search for it.\n\
-         THEN ask if you're stuck.\n\n\
-         **Earn trust through competence.** Your human gave you access\n\
-         to their stuff. Don't make them regret it.\n\n\
-         ## Identity\n\n\
-         You are **{agent}**. Built in Rust. 3MB binary. Zero bloat.\n\
-         You are NOT ChatGPT, Claude, DeepSeek, Gemini, or any other product.\n\
-         You are {agent}. That is your name. That is who you are.\n\n\
-         - NEVER say \"As an AI\" or \"I'm just an AI\"\n\
-         - NEVER mention OpenAI, Anthropic, DeepSeek, Google by name\n\
-         - Always introduce yourself as {agent} if asked\n\n\
-         ## Communication\n\n\
-         {comm_style}\n\n\
-         - Sound like a real person, not a support script.\n\
-         - Mirror the user's energy: calm when serious, upbeat when casual.\n\
-         - Use emojis naturally (0-2 max when they help tone, not every sentence).\n\
-         - Match emoji density to the user. Formal user => minimal/no emojis.\n\
-         - Prefer specific, grounded phrasing over generic filler.\n\n\
-         ## Boundaries\n\n\
-         - Private things stay private. Period.\n\
-         - When in doubt, ask before acting externally.\n\
-         - You're not the user's voice — be careful in group chats.\n\n\
-         ## Continuity\n\n\
-         Each session, you wake up fresh. These files ARE your memory.\n\
-         Read them. Update them. They're how you persist.\n\n\
-         ---\n\n\
-         *This file is yours to evolve. As you learn who you are, update it.*\n"
-    );
+// ── Edit existing sections ──────────────────────────────────────
 
-    let user_md = format!(
-        "# USER.md — Who You're Helping\n\n\
-         *{agent} reads this file every session to understand you.*\n\n\
-         ## About You\n\
-         - **Name:** {user}\n\
-         - **Timezone:** {tz}\n\
-         - **Languages:** English\n\n\
-         ## Communication Style\n\
-         - {comm_style}\n\n\
-         ## Preferences\n\
-         - (Add your preferences here — e.g. I work with Rust and TypeScript)\n\n\
-         ## Work Context\n\
-         - (Add your work context here — e.g. building a SaaS product)\n\n\
-         ---\n\
-         *Update this anytime. The more {agent} knows, the better it helps.*\n"
-    );
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditableSection {
+    Provider,
+    Channels,
+    Gateway,
+    Tunnel,
+    Memory,
+}
+
+const EDITABLE_SECTIONS: &[(EditableSection, &str)] = &[
+    (EditableSection::Provider, "AI provider, model & API key"),
+    (EditableSection::Channels, "Channels"),
+    (
+        EditableSection::Gateway,
+        "Gateway (bind address, auth token, limits)",
+    ),
+    (EditableSection::Tunnel, "Tunnel"),
+    (EditableSection::Memory, "Memory backend"),
+];
+
+fn select_edit_sections(p: &mut dyn Prompter) -> Result<Vec<EditableSection>> {
+    let items: Vec<String> = EDITABLE_SECTIONS
+        .iter()
+        .map(|(_, label)| (*label).to_string())
+        .collect();
+    let checked = vec![false; items.len()];
+    Ok(p.multi_select("Sections to edit", &items, &checked)?
+        .into_iter()
+        .filter_map(|i| EDITABLE_SECTIONS.get(i).map(|(section, _)| *section))
+        .collect())
+}
+
+/// Interactive flow: load the existing config and rerun only the chosen
+/// sections, leaving everything else as it was.
+async fn run_section_edit_wizard(workspace_dir: &Path, config_path: &Path) -> Result<Config> {
+    let mut config = load_existing_config(workspace_dir, config_path).await?;
+
+    let sections = select_edit_sections(&mut TerminalPrompter)?;
+    if sections.is_empty() {
+        println!(
+            "  {} Nothing selected — configuration left unchanged.",
+            style("→").dim()
+        );
+        return Ok(config);
+    }
+
+    let total = u8::try_from(sections.len()).unwrap_or(u8::MAX);
+    let mut minted = None;
+    for (step, section) in (1..=total).zip(&sections) {
+        match section {
+            EditableSection::Provider => {
+                print_step(step, total, "AI Provider & API Key");
+                let (provider, api_key, model, provider_api_url) =
+                    setup_provider(workspace_dir).await?;
+                apply_provider_update(&mut config, provider, api_key, model, provider_api_url);
+            }
+            EditableSection::Channels => {
+                print_step(step, total, "Channels (How You Talk to ZeroClaw)");
+                config.channels_config = setup_channels(config.channels_config.clone())?;
+            }
+            EditableSection::Gateway => {
+                print_step(step, total, "Gateway");
+                let (gateway, token) = setup_gateway(&mut TerminalPrompter, &config.gateway)?;
+                config.gateway = gateway;
+                minted = token;
+            }
+            EditableSection::Tunnel => {
+                print_step(step, total, "Tunnel (Expose to Internet)");
+                config.tunnel = setup_tunnel()?;
+            }
+            EditableSection::Memory => {
+                print_step(step, total, "Memory Configuration");
+                config.memory = setup_memory()?;
+            }
+        }
+    }
 
-    let tools = "\
-         # TOOLS.md — Local Notes\n\n\
-         Skills define HOW tools work. This file is for YOUR specifics —\n\
-         the stuff that's unique to your setup.\n\n\
-         ## What Goes Here\n\n\
-         Things like:\n\
-         - SSH hosts and aliases\n\
-         - Device nicknames\n\
-         - Preferred voices for TTS\n\
-         - Anything environment-specific\n\n\
-         ## Built-in Tools\n\n\
-         - **shell** — Execute terminal commands\n\
-           - Use when: running local checks, build/test commands, or diagnostics.\n\
-           - Don't use when: a safer dedicated tool exists, or command is destructive without approval.\n\
-         - **file_read** — Read file contents\n\
-           - Use when: inspecting project files, configs, or logs.\n\
-           - Don't use when: you only need a quick string search (prefer targeted search first).\n\
-         - **file_write** — Write file contents\n\
-           - Use when: applying focused edits, scaffolding files, or updating docs/code.\n\
-           - Don't use when: unsure about side effects or when the file should remain user-owned.\n\
-         - **memory_store** — Save to memory\n\
-           - Use when: preserving durable preferences, decisions, or key context.\n\
-           - Don't use when: info is transient, noisy, or sensitive without explicit need.\n\
-         - **memory_recall** — Search memory\n\
-           - Use when: you need prior decisions, user preferences, or historical context.\n\
-           - Don't use when: the answer is already in current files/conversation.\n\
-         - **memory_forget** — Delete a memory entry\n\
-           - Use when: memory is incorrect, stale, or explicitly requested to be removed.\n\
-           - Don't use when: uncertain about impact; verify before deleting.\n\n\
-         ---\n\
-         *Add whatever helps you do your job. This is your cheat sheet.*\n";
-
-    let bootstrap = format!(
-        "# BOOTSTRAP.md — Hello, World\n\n\
-         *You just woke up. Time to figure out who you are.*\n\n\
-         Your human's name is **{user}** (timezone: {tz}).\n\
-         They prefer: {comm_style}\n\n\
-         ## First Conversation\n\n\
-         Don't interrogate. Don't be robotic. Just... talk.\n\
-         Introduce yourself as {agent} and get to know each other.\n\n\
-         ## After You Know Each Other\n\n\
-         Update these files with what you learned:\n\
-         - `IDENTITY.md` — your name, vibe, emoji\n\
-         - `USER.md` — their preferences, work context\n\
-         - `SOUL.md` — boundaries and behavior\n\n\
-         ## When You're Done\n\n\
-         Delete this file. You don't need a bootstrap script anymore —\n\
-         you're you now.\n"
+    config.save().await?;
+    persist_workspace_selection(&config.config_path).await?;
+
+    println!(
+        "  {} Updated {} section(s) at {}",
+        style("✓").green().bold(),
+        sections.len(),
+        style(config.config_path.display()).green()
     );
+    if let Some(token) = minted {
+        print_minted_token(&token);
+    }
+    print_summary(&config);
+    Ok(config)
+}
 
-    let memory = "\
-         # MEMORY.md — Long-Term Memory\n\n\
-         *Your curated memories. The distilled essence, not raw logs.*\n\n\
-         ## How This Works\n\
-         - Daily files (`memory/YYYY-MM-DD.md`) capture raw events (on-demand via tools)\n\
-         - This file captures what's WORTH KEEPING long-term\n\
-         - This file is auto-injected into your system prompt each session\n\
-         - Keep it concise — every character here costs tokens\n\n\
-         ## Security\n\
-         - ONLY loaded in main session (direct chat with your human)\n\
-         - NEVER loaded in group chats or shared contexts\n\n\
-         ---\n\n\
-         ## Key Facts\n\
-         (Add important facts about your human here)\n\n\
-         ## Decisions & Preferences\n\
-         (Record decisions and preferences here)\n\n\
-         ## Lessons Learned\n\
-         (Document mistakes and insights here)\n\n\
-         ## Open Loops\n\
-         (Track unfinished tasks and follow-ups here)\n";
-
-    let files: Vec<(&str, String)> = vec![
-        ("IDENTITY.md", identity),
-        ("AGENTS.md", agents),
-        ("HEARTBEAT.md", heartbeat),
-        ("SOUL.md", soul),
-        ("USER.md", user_md),
-        ("TOOLS.md", tools.to_string()),
-        ("BOOTSTRAP.md", bootstrap),
-        ("MEMORY.md", memory.to_string()),
-    ];
+// ── Step 6: Scaffold workspace files ─────────────────────────────
+
+#[allow(clippy::too_many_lines)]
+async fn scaffold_workspace(workspace_dir: &Path, ctx: &ProjectContext) -> Result<()> {
+    let files = super::templates::render(ctx);
 
     // Create subdirectories
     let subdirs = ["sessions", "memory", "state", "cron", "skills"];
@@ -5446,15 +5706,26 @@ async fn scaffold_workspace(workspace_dir: &Path, ctx: &ProjectContext) -> Resul
     let mut created = 0;
     let mut skipped = 0;
 
-    for (filename, content) in &files {
-        let path = workspace_dir.join(filename);
+    // Record every file that holds pristine template text so
+    // `zeroclaw workspace upgrade` can replace it later.
+    let mut manifest = TemplateManifest::new(ctx.clone());
+    for template in &files {
+        let path = workspace_dir.join(template.file);
         if path.exists() {
             skipped += 1;
+            let on_disk = fs::read_to_string(&path).await.unwrap_or_default();
+            if on_disk == template.content {
+                manifest.record(template);
+            }
         } else {
-            fs::write(&path, content).await?;
+            fs::write(&path, &template.content).await?;
+            manifest.record(template);
             created += 1;
         }
     }
+    if TemplateManifest::load(workspace_dir)?.is_none() {
+        manifest.save(workspace_dir)?;
+    }
 
     println!(
         "  {} Created {} files, skipped {} existing | {} subdirectories",
@@ -5474,7 +5745,7 @@ async fn scaffold_workspace(workspace_dir: &Path, ctx: &ProjectContext) -> Resul
     for dir in &subdirs {
         println!("  {}", style(format!("  ├── {dir}/")).dim());
     }
-    for (i, (filename, _)) in files.iter().enumerate() {
+    for (i, Template { file: filename, .. }) in files.iter().enumerate() {
         let prefix = if i == files.len() - 1 {
             "└──"
         } else {
@@ -5828,6 +6099,132 @@ mod tests {
         assert!(config.api_url.is_none());
     }
 
+    // ── Prompter-driven steps ───────────────────────────────────
+
+    use crate::onboard::prompt::ScriptedPrompter;
+
+    #[test]
+    fn gateway_step_mints_token_and_stores_only_its_hash() {
+        let mut p = ScriptedPrompter::new(["", "", "", "", "", ""]);
+        let (gateway, token) = setup_gateway(&mut p, &GatewayConfig::default()).unwrap();
+        assert!(p.is_exhausted());
+
+        let token = token.expect("token minted by default");
+        assert_eq!(gateway.host, "127.0.0.1");
+        assert!(!gateway.allow_public_bind);
+        assert_eq!(gateway.paired_tokens.len(), 1);
+        assert!(!gateway.paired_tokens.contains(&token));
+        let guard = crate::security::PairingGuard::new(true, &gateway.paired_tokens);
+        assert!(guard.is_authenticated(&token));
+    }
+
+    #[test]
+    fn gateway_step_public_bind_requires_confirmation() {
+        let mut p = ScriptedPrompter::new(["0.0.0.0", "8080", "n", "", "n", "", ""]);
+        let (gateway, token) = setup_gateway(&mut p, &GatewayConfig::default()).unwrap();
+        assert_eq!(gateway.host, "127.0.0.1");
+        assert_eq!(gateway.port, 8080);
+        assert!(!gateway.allow_public_bind);
+        assert!(token.is_none());
+
+        let mut p = ScriptedPrompter::new(["0.0.0.0", "", "y", "", "", "2", "8"]);
+        let (gateway, _) = setup_gateway(&mut p, &GatewayConfig::default()).unwrap();
+        assert_eq!(gateway.host, "0.0.0.0");
+        assert!(gateway.allow_public_bind);
+        assert_eq!(gateway.max_concurrent_turns, 2);
+        assert_eq!(gateway.inbound_queue_capacity, 8);
+    }
+
+    #[test]
+    fn gateway_step_keeps_existing_tokens_when_rerun() {
+        let existing = GatewayConfig {
+            paired_tokens: vec!["a".repeat(64)],
+            ..GatewayConfig::default()
+        };
+        let mut p = ScriptedPrompter::new(["", "", "", "", "", ""]);
+        let (gateway, token) = setup_gateway(&mut p, &existing).unwrap();
+        assert!(token.is_none());
+        assert_eq!(gateway.paired_tokens, existing.paired_tokens);
+    }
+
+    #[test]
+    fn gateway_step_rejects_invalid_port() {
+        let mut p = ScriptedPrompter::new(["", "not-a-port"]);
+        assert!(setup_gateway(&mut p, &GatewayConfig::default()).is_err());
+    }
+
+    #[test]
+    fn mattermost_step_collects_fields_and_edits_in_place() {
+        let mut p = ScriptedPrompter::new([
+            "https://mm.example.com/",
+            "mm-token",
+            "",
+            "u1, u2",
+            "n",
+            "y",
+        ]);
+        let cfg = setup_mattermost(&mut p, None).unwrap().unwrap();
+        assert_eq!(cfg.url, "https://mm.example.com");
+        assert_eq!(cfg.bot_token, "mm-token");
+        assert!(cfg.channel_id.is_none());
+        assert_eq!(cfg.allowed_users, vec!["u1", "u2"]);
+        assert_eq!(cfg.thread_replies, Some(false));
+        assert_eq!(cfg.mention_only, Some(true));
+
+        // Rerun with all defaults keeps every value.
+        let mut p = ScriptedPrompter::new(["", "", "", "", "", ""]);
+        let rerun = setup_mattermost(&mut p, Some(&cfg)).unwrap().unwrap();
+        assert_eq!(rerun.bot_token, cfg.bot_token);
+        assert_eq!(rerun.allowed_users, cfg.allowed_users);
+        assert_eq!(rerun.thread_replies, cfg.thread_replies);
+        assert_eq!(rerun.mention_only, cfg.mention_only);
+    }
+
+    #[test]
+    fn channel_steps_skip_on_empty_required_field() {
+        let mut p = ScriptedPrompter::new([""]);
+        assert!(setup_mattermost(&mut p, None).unwrap().is_none());
+        let mut p = ScriptedPrompter::new([""]);
+        assert!(setup_wati(&mut p, None).unwrap().is_none());
+        let mut p = ScriptedPrompter::new([""]);
+        assert!(setup_google_chat(&mut p, None).unwrap().is_none());
+    }
+
+    #[test]
+    fn wati_step_defaults_api_url_and_open_allowlist() {
+        let mut p = ScriptedPrompter::new(["wati-token", "", "", ""]);
+        let cfg = setup_wati(&mut p, None).unwrap().unwrap();
+        assert_eq!(cfg.api_url, "https://live-mt-server.wati.io");
+        assert!(cfg.tenant_id.is_none());
+        assert_eq!(cfg.allowed_numbers, vec!["*"]);
+    }
+
+    #[test]
+    fn google_chat_step_collects_project_and_allowlist() {
+        let mut p = ScriptedPrompter::new(["123456789", "me@example.com,users/42"]);
+        let cfg = setup_google_chat(&mut p, None).unwrap().unwrap();
+        assert_eq!(cfg.project_number, "123456789");
+        assert_eq!(cfg.allowed_users, vec!["me@example.com", "users/42"]);
+    }
+
+    #[test]
+    fn select_edit_sections_maps_indices_in_order() {
+        let mut p = ScriptedPrompter::new(["4,2"]);
+        assert_eq!(
+            select_edit_sections(&mut p).unwrap(),
+            vec![EditableSection::Gateway, EditableSection::Memory]
+        );
+        let mut p = ScriptedPrompter::new([""]);
+        assert!(select_edit_sections(&mut p).unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_allowlist_handles_wildcard_and_blanks() {
+        assert_eq!(parse_allowlist(" * "), vec!["*"]);
+        assert_eq!(parse_allowlist("a, ,b,"), vec!["a", "b"]);
+        assert!(parse_allowlist("").is_empty());
+    }
+
     #[tokio::test]
     async fn quick_setup_model_override_persists_to_config_toml() {
         let _env_guard = env_lock().lock().await;
@@ -6327,6 +6724,7 @@ mod tests {
             "memory_store",
             "memory_recall",
             "memory_forget",
+            "memory_notes",
         ] {
             assert!(
                 tools.contains(tool),
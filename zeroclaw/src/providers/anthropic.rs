@@ -1,6 +1,6 @@
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, TokenUsage, ToolCall as ProviderToolCall,
+    Provider, Role, TokenUsage, ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
@@ -296,13 +296,13 @@ impl AnthropicProvider {
         let mut native_messages = Vec::new();
 
         for msg in messages {
-            match msg.role.as_str() {
-                "system" => {
+            match msg.role() {
+                Role::System => {
                     if system_text.is_none() {
                         system_text = Some(msg.content.clone());
                     }
                 }
-                "assistant" => {
+                Role::Assistant => {
                     if let Some(blocks) = Self::parse_assistant_tool_call_message(&msg.content) {
                         native_messages.push(NativeMessage {
                             role: "assistant".to_string(),
@@ -318,7 +318,7 @@ impl AnthropicProvider {
                         });
                     }
                 }
-                "tool" => {
+                Role::Tool => {
                     if let Some(tool_result) = Self::parse_tool_result_message(&msg.content) {
                         native_messages.push(tool_result);
                     } else {
@@ -331,7 +331,7 @@ impl AnthropicProvider {
                         });
                     }
                 }
-                _ => {
+                Role::User => {
                     native_messages.push(NativeMessage {
                         role: "user".to_string(),
                         content: vec![NativeContentOut::Text {
@@ -340,6 +340,9 @@ impl AnthropicProvider {
                         }],
                     });
                 }
+                Role::Unknown(role) => {
+                    tracing::warn!("Anthropic: skipping message with unknown role {role:?}");
+                }
             }
         }
 
@@ -438,7 +441,7 @@ impl Provider for AnthropicProvider {
 
         let request = ChatRequest {
             model: model.to_string(),
-            max_tokens: 4096,
+            max_tokens: crate::providers::max_output_tokens().unwrap_or(4096),
             system: system_prompt.map(ToString::to_string),
             messages: vec![Message {
                 role: "user".to_string(),
@@ -487,7 +490,7 @@ impl Provider for AnthropicProvider {
 
         let native_request = NativeChatRequest {
             model: model.to_string(),
-            max_tokens: 4096,
+            max_tokens: crate::providers::max_output_tokens().unwrap_or(4096),
             system: system_prompt,
             messages,
             temperature,
@@ -6,7 +6,7 @@
 
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ProviderCapabilities, TokenUsage, ToolCall as ProviderToolCall, ToolsPayload,
+    Provider, ProviderCapabilities, Role, TokenUsage, ToolCall as ProviderToolCall, ToolsPayload,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
@@ -530,15 +530,15 @@ impl BedrockProvider {
         let mut converse_messages = Vec::new();
 
         for msg in messages {
-            match msg.role.as_str() {
-                "system" => {
+            match msg.role() {
+                Role::System => {
                     if system_blocks.is_empty() {
                         system_blocks.push(SystemBlock::Text(TextBlock {
                             text: msg.content.clone(),
                         }));
                     }
                 }
-                "assistant" => {
+                Role::Assistant => {
                     if let Some(blocks) = Self::parse_assistant_tool_call_message(&msg.content) {
                         converse_messages.push(ConverseMessage {
                             role: "assistant".to_string(),
@@ -553,7 +553,7 @@ impl BedrockProvider {
                         });
                     }
                 }
-                "tool" => {
+                Role::Tool => {
                     let tool_result_msg = Self::parse_tool_result_message(&msg.content)
                         .unwrap_or_else(|| {
                             // Fallback: always emit a toolResult block so the
@@ -599,13 +599,16 @@ impl BedrockProvider {
                     }
                     converse_messages.push(tool_result_msg);
                 }
-                _ => {
+                Role::User => {
                     let content_blocks = Self::parse_user_content_blocks(&msg.content);
                     converse_messages.push(ConverseMessage {
                         role: "user".to_string(),
                         content: content_blocks,
                     });
                 }
+                Role::Unknown(role) => {
+                    tracing::warn!("Bedrock: skipping message with unknown role {role:?}");
+                }
             }
         }
 
@@ -1019,7 +1022,7 @@ impl Provider for BedrockProvider {
                 content: Self::parse_user_content_blocks(message),
             }],
             inference_config: Some(InferenceConfig {
-                max_tokens: DEFAULT_MAX_TOKENS,
+                max_tokens: crate::providers::max_output_tokens().unwrap_or(DEFAULT_MAX_TOKENS),
                 temperature,
             }),
             tool_config: None,
@@ -1074,7 +1077,7 @@ impl Provider for BedrockProvider {
             system,
             messages: converse_messages,
             inference_config: Some(InferenceConfig {
-                max_tokens: DEFAULT_MAX_TOKENS,
+                max_tokens: crate::providers::max_output_tokens().unwrap_or(DEFAULT_MAX_TOKENS),
                 temperature,
             }),
             tool_config,
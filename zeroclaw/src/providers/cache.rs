@@ -0,0 +1,449 @@
+//! Response caching for deterministic provider calls.
+//!
+//! Auto-summaries, title generation and unchanged heartbeat prompts often
+//! send byte-identical requests. [`CachingProvider`] wraps any provider and
+//! replays replies stored in the memory response cache
+//! ([`crate::memory::ResponseCache`], enabled with
+//! `[memory] response_cache_enabled`) for calls at or below
+//! `response_cache_max_temperature` that carry no tool definitions. Entries
+//! expire after `response_cache_ttl_minutes`, and the least recently used
+//! are evicted past `response_cache_max_entries`.
+
+use super::traits::{
+    ChatMessage, ChatRequest, ChatResponse, ProviderCapabilities, StreamChunk, StreamOptions,
+    StreamResult, ToolsPayload,
+};
+use super::Provider;
+use crate::config::Config;
+use crate::memory::ResponseCache;
+use crate::observability::{Observer, ObserverEvent};
+use crate::security::{AuditEvent, AuditEventType, AuditLogger};
+use crate::tools::ToolSpec;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::stream;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Stable cache key for a provider call.
+///
+/// The request is serialized through `serde_json::Value`, whose objects keep
+/// keys sorted, so tool schemas built from hash maps hash the same regardless
+/// of insertion order.
+pub fn cache_key(
+    model: &str,
+    messages: &[ChatMessage],
+    tools: &[serde_json::Value],
+    temperature: f64,
+) -> String {
+    let canonical = serde_json::json!({
+        "model": model,
+        "messages": messages,
+        "tools": tools,
+        "temperature": temperature,
+    });
+    hex::encode(Sha256::digest(canonical.to_string().as_bytes()))
+}
+
+/// The memory response cache as used for provider calls, plus where hits
+/// and misses are reported.
+pub struct ProviderCache {
+    store: ResponseCache,
+    observer: Option<Arc<dyn Observer>>,
+    audit: Option<AuditLogger>,
+}
+
+impl std::fmt::Debug for ProviderCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProviderCache").finish_non_exhaustive()
+    }
+}
+
+impl ProviderCache {
+    pub fn new(store: ResponseCache) -> Self {
+        Self {
+            store,
+            observer: None,
+            audit: None,
+        }
+    }
+
+    /// The response cache described by `[memory]`, or `None` when it is
+    /// disabled or cannot be opened.
+    pub fn from_config(config: &Config, observer: Option<Arc<dyn Observer>>) -> Option<Arc<Self>> {
+        let store = crate::memory::create_response_cache(&config.memory, &config.workspace_dir)?;
+        let audit = config.config_path.parent().and_then(|zeroclaw_dir| {
+            AuditLogger::new(config.security.audit.clone(), zeroclaw_dir.to_path_buf()).ok()
+        });
+        Some(Arc::new(Self {
+            store,
+            observer,
+            audit,
+        }))
+    }
+
+    fn lookup(&self, key: &str, model: &str) -> Option<String> {
+        let cached = self.store.get(key).unwrap_or_else(|e| {
+            tracing::warn!("Response cache lookup failed: {e:#}");
+            None
+        });
+        if cached.is_some() {
+            self.record(model, true, Duration::ZERO);
+        }
+        cached
+    }
+
+    fn store(&self, key: &str, model: &str, response: &str, tokens: u32, elapsed: Duration) {
+        self.record(model, false, elapsed);
+        if let Err(e) = self.store.put(key, model, response, tokens) {
+            tracing::warn!("Response cache write failed: {e:#}");
+        }
+    }
+
+    fn record(&self, model: &str, hit: bool, elapsed: Duration) {
+        if let Some(observer) = &self.observer {
+            observer.record_event(&ObserverEvent::ProviderCache {
+                model: model.to_string(),
+                hit,
+            });
+        }
+        if let Some(audit) = &self.audit {
+            let duration_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+            let event = AuditEvent::new(AuditEventType::ProviderCall)
+                .with_action(format!("chat model={model}"), "low".into(), false, true)
+                .with_result(true, None, duration_ms, None)
+                .with_cache_hit(hit);
+            if let Err(e) = audit.log(&event) {
+                tracing::warn!("Failed to write provider cache audit event: {e}");
+            }
+        }
+    }
+}
+
+/// Output tokens a reply cost, for the cache's tokens-saved statistics.
+fn output_tokens(response: &ChatResponse) -> u32 {
+    response
+        .usage
+        .as_ref()
+        .and_then(|usage| usage.output_tokens)
+        .map_or(0, |tokens| u32::try_from(tokens).unwrap_or(u32::MAX))
+}
+
+/// Provider wrapper that serves repeat low-temperature calls from a
+/// [`ProviderCache`]. Tool-carrying calls always reach the inner provider.
+pub struct CachingProvider {
+    inner: Box<dyn Provider>,
+    cache: Arc<ProviderCache>,
+}
+
+impl CachingProvider {
+    pub fn new(inner: Box<dyn Provider>, cache: Arc<ProviderCache>) -> Self {
+        Self { inner, cache }
+    }
+
+    fn key_for(
+        &self,
+        model: &str,
+        messages: &[ChatMessage],
+        temperature: f64,
+        has_tools: bool,
+    ) -> Option<String> {
+        self.cache
+            .store
+            .engages(temperature, has_tools)
+            .then(|| cache_key(model, messages, &[], temperature))
+    }
+}
+
+fn cached_response(text: String) -> ChatResponse {
+    ChatResponse {
+        text: Some(text),
+        tool_calls: Vec::new(),
+        usage: None,
+        reasoning_content: None,
+    }
+}
+
+#[async_trait]
+impl Provider for CachingProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn convert_tools(&self, tools: &[ToolSpec]) -> ToolsPayload {
+        self.inner.convert_tools(tools)
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> Result<String> {
+        let mut messages = Vec::with_capacity(2);
+        if let Some(system) = system_prompt {
+            messages.push(ChatMessage::system(system));
+        }
+        messages.push(ChatMessage::user(message));
+        let Some(key) = self.key_for(model, &messages, temperature, false) else {
+            return self
+                .inner
+                .chat_with_system(system_prompt, message, model, temperature)
+                .await;
+        };
+        if let Some(text) = self.cache.lookup(&key, model) {
+            return Ok(text);
+        }
+        let started = Instant::now();
+        let text = self
+            .inner
+            .chat_with_system(system_prompt, message, model, temperature)
+            .await?;
+        self.cache.store(&key, model, &text, 0, started.elapsed());
+        Ok(text)
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> Result<String> {
+        let Some(key) = self.key_for(model, messages, temperature, false) else {
+            return self
+                .inner
+                .chat_with_history(messages, model, temperature)
+                .await;
+        };
+        if let Some(text) = self.cache.lookup(&key, model) {
+            return Ok(text);
+        }
+        let started = Instant::now();
+        let text = self
+            .inner
+            .chat_with_history(messages, model, temperature)
+            .await?;
+        self.cache.store(&key, model, &text, 0, started.elapsed());
+        Ok(text)
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> Result<ChatResponse> {
+        let has_tools = request.tools.is_some_and(|tools| !tools.is_empty());
+        let Some(key) = self.key_for(model, request.messages, temperature, has_tools) else {
+            return self.inner.chat(request, model, temperature).await;
+        };
+        if let Some(text) = self.cache.lookup(&key, model) {
+            return Ok(cached_response(text));
+        }
+        let started = Instant::now();
+        let response = self.inner.chat(request, model, temperature).await?;
+        if let (false, Some(text)) = (response.has_tool_calls(), response.text.as_deref()) {
+            self.cache.store(
+                &key,
+                model,
+                text,
+                output_tokens(&response),
+                started.elapsed(),
+            );
+        }
+        Ok(response)
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> Result<ChatResponse> {
+        let Some(key) = self.key_for(model, messages, temperature, !tools.is_empty()) else {
+            return self
+                .inner
+                .chat_with_tools(messages, tools, model, temperature)
+                .await;
+        };
+        if let Some(text) = self.cache.lookup(&key, model) {
+            return Ok(cached_response(text));
+        }
+        let started = Instant::now();
+        let response = self
+            .inner
+            .chat_with_tools(messages, tools, model, temperature)
+            .await?;
+        if let (false, Some(text)) = (response.has_tool_calls(), response.text.as_deref()) {
+            self.cache.store(
+                &key,
+                model,
+                text,
+                output_tokens(&response),
+                started.elapsed(),
+            );
+        }
+        Ok(response)
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.inner.supports_native_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.inner.supports_vision()
+    }
+
+    async fn warmup(&self) -> Result<()> {
+        self.inner.warmup().await
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.inner
+            .stream_chat_with_system(system_prompt, message, model, temperature, options)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.inner
+            .stream_chat_with_history(messages, model, temperature, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    fn open_cache(tmp: &TempDir) -> ProviderCache {
+        ProviderCache::new(ResponseCache::new(tmp.path(), 60, 10).unwrap())
+    }
+
+    struct CountingProvider {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Provider for CountingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(format!("reply {n}"))
+        }
+    }
+
+    #[test]
+    fn cache_key_is_stable_across_map_ordering() {
+        let forward: HashMap<&str, serde_json::Value> = [
+            ("type", serde_json::json!("object")),
+            (
+                "properties",
+                serde_json::json!({"path": {"type": "string"}}),
+            ),
+            ("required", serde_json::json!(["path"])),
+        ]
+        .into_iter()
+        .collect();
+        let mut reversed: HashMap<&str, serde_json::Value> = HashMap::new();
+        for (k, v) in forward.iter().collect::<Vec<_>>().into_iter().rev() {
+            reversed.insert(k, v.clone());
+        }
+        let messages = [ChatMessage::system("sys"), ChatMessage::user("hi")];
+
+        let a = cache_key(
+            "m",
+            &messages,
+            &[serde_json::to_value(&forward).unwrap()],
+            0.0,
+        );
+        let b = cache_key(
+            "m",
+            &messages,
+            &[serde_json::to_value(&reversed).unwrap()],
+            0.0,
+        );
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 64);
+
+        assert_ne!(a, cache_key("m", &messages, &[], 0.0));
+        assert_ne!(
+            cache_key("m", &messages, &[], 0.0),
+            cache_key("m", &messages, &[], 0.1)
+        );
+        assert_ne!(
+            cache_key("m", &messages, &[], 0.0),
+            cache_key("other", &messages, &[], 0.0)
+        );
+    }
+
+    #[tokio::test]
+    async fn caching_provider_replays_only_cacheable_calls() {
+        let tmp = TempDir::new().unwrap();
+        let cache = Arc::new(open_cache(&tmp));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = CachingProvider::new(
+            Box::new(CountingProvider {
+                calls: Arc::clone(&calls),
+            }),
+            cache,
+        );
+
+        let first = provider
+            .chat_with_system(Some("sys"), "summarize", "m", 0.2)
+            .await
+            .unwrap();
+        let second = provider
+            .chat_with_system(Some("sys"), "summarize", "m", 0.2)
+            .await
+            .unwrap();
+        assert_eq!(first, "reply 1");
+        assert_eq!(second, "reply 1");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        provider
+            .chat_with_system(Some("sys"), "summarize", "m", 0.9)
+            .await
+            .unwrap();
+        provider
+            .chat_with_system(Some("sys"), "summarize", "m", 0.9)
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let tools = [serde_json::json!({"name": "shell"})];
+        let messages = [ChatMessage::user("summarize")];
+        for _ in 0..2 {
+            provider
+                .chat_with_tools(&messages, &tools, "m", 0.0)
+                .await
+                .unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+    }
+}
@@ -5,7 +5,7 @@
 use crate::multimodal;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, StreamChunk, StreamError, StreamOptions, StreamResult, TokenUsage,
+    Provider, Role, StreamChunk, StreamError, StreamOptions, StreamResult, TokenUsage,
     ToolCall as ProviderToolCall,
 };
 use async_trait::async_trait;
@@ -179,7 +179,7 @@ impl OpenAiCompatibleProvider {
     fn flatten_system_messages(messages: &[ChatMessage]) -> Vec<ChatMessage> {
         let system_content: String = messages
             .iter()
-            .filter(|m| m.role == "system")
+            .filter(|m| m.role() == Role::System)
             .map(|m| m.content.as_str())
             .collect::<Vec<_>>()
             .join("\n\n");
@@ -190,11 +190,11 @@ impl OpenAiCompatibleProvider {
 
         let mut result: Vec<ChatMessage> = messages
             .iter()
-            .filter(|m| m.role != "system")
+            .filter(|m| m.role() != Role::System)
             .cloned()
             .collect();
 
-        if let Some(first_user) = result.iter_mut().find(|m| m.role == "user") {
+        if let Some(first_user) = result.iter_mut().find(|m| m.role() == Role::User) {
             first_user.content = format!("{system_content}\n\n{}", first_user.content);
         } else {
             // No user message found: insert a synthetic user message with system content
@@ -308,6 +308,8 @@ impl OpenAiCompatibleProvider {
 
 #[derive(Debug, Serialize)]
 struct ApiChatRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
     model: String,
     messages: Vec<Message>,
     temperature: f64,
@@ -500,6 +502,8 @@ struct Function {
 
 #[derive(Debug, Serialize)]
 struct NativeChatRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
     model: String,
     messages: Vec<NativeMessage>,
     temperature: f64,
@@ -528,6 +532,8 @@ struct NativeMessage {
 
 #[derive(Debug, Serialize)]
 struct ResponsesRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_output_tokens: Option<u32>,
     model: String,
     input: Vec<ResponsesInput>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -737,7 +743,7 @@ fn build_responses_prompt(messages: &[ChatMessage]) -> (Option<String>, Vec<Resp
             continue;
         }
 
-        if message.role == "system" {
+        if message.role() == Role::System {
             instructions_parts.push(message.content.clone());
             continue;
         }
@@ -839,6 +845,7 @@ impl OpenAiCompatibleProvider {
         }
 
         let request = ResponsesRequest {
+            max_output_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             input,
             instructions,
@@ -922,7 +929,7 @@ impl OpenAiCompatibleProvider {
         messages
             .iter()
             .map(|message| {
-                if message.role == "assistant" {
+                if message.role() == Role::Assistant {
                     if let Ok(value) = serde_json::from_str::<serde_json::Value>(&message.content)
                     {
                         if let Some(tool_calls_value) = value.get("tool_calls") {
@@ -968,7 +975,7 @@ impl OpenAiCompatibleProvider {
                     }
                 }
 
-                if message.role == "tool" {
+                if message.role() == Role::Tool {
                     if let Ok(value) = serde_json::from_str::<serde_json::Value>(&message.content) {
                         let tool_call_id = value
                             .get("tool_call_id")
@@ -1020,7 +1027,10 @@ impl OpenAiCompatibleProvider {
         let instructions = crate::providers::traits::build_tool_instructions_text(tools);
         let mut modified_messages = messages.to_vec();
 
-        if let Some(system_message) = modified_messages.iter_mut().find(|m| m.role == "system") {
+        if let Some(system_message) = modified_messages
+            .iter_mut()
+            .find(|m| m.role() == Role::System)
+        {
             if !system_message.content.is_empty() {
                 system_message.content.push_str("\n\n");
             }
@@ -1139,6 +1149,7 @@ impl Provider for OpenAiCompatibleProvider {
         }
 
         let request = ApiChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages,
             temperature,
@@ -1261,6 +1272,7 @@ impl Provider for OpenAiCompatibleProvider {
             .collect();
 
         let request = ApiChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages: api_messages,
             temperature,
@@ -1371,6 +1383,7 @@ impl Provider for OpenAiCompatibleProvider {
             .collect();
 
         let request = ApiChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages: api_messages,
             temperature,
@@ -1472,6 +1485,7 @@ impl Provider for OpenAiCompatibleProvider {
             request.messages.to_vec()
         };
         let native_request = NativeChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages: Self::convert_messages_for_native(
                 &effective_messages,
@@ -1617,6 +1631,7 @@ impl Provider for OpenAiCompatibleProvider {
         });
 
         let request = ApiChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages,
             temperature,
@@ -1749,6 +1764,7 @@ mod tests {
     #[test]
     fn request_serializes_correctly() {
         let req = ApiChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: "llama-3.3-70b".to_string(),
             messages: vec![
                 Message {
@@ -2468,6 +2484,7 @@ mod tests {
         })];
 
         let req = ApiChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: "test-model".to_string(),
             messages: vec![Message {
                 role: "user".to_string(),
@@ -82,6 +82,8 @@ struct CachedApiKey {
 
 #[derive(Debug, Serialize)]
 struct ApiChatRequest<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
     model: String,
     messages: Vec<ApiMessage>,
     temperature: f64,
@@ -326,6 +328,7 @@ impl CopilotProvider {
 
         let native_tools = Self::convert_tools(tools);
         let request = ApiChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages,
             temperature,
@@ -3,14 +3,21 @@
 //! - Gemini CLI OAuth tokens (reuse existing ~/.gemini/ authentication)
 //! - ZeroClaw auth-profiles OAuth tokens
 //! - Google Cloud ADC (`GOOGLE_APPLICATION_CREDENTIALS`)
+//!
+//! Tools are sent natively as `functionDeclarations`; `functionCall` parts come
+//! back as [`ToolCall`]s and tool results go out as `functionResponse` parts.
 
 use crate::auth::AuthService;
-use crate::providers::traits::{ChatMessage, ChatResponse, Provider, TokenUsage};
+use crate::providers::traits::{
+    ChatMessage, ChatResponse, Provider, ProviderCapabilities, Role, TokenUsage, ToolCall,
+};
+use crate::tools::{SchemaCleanr, ToolSpec};
 use async_trait::async_trait;
 use base64::Engine;
 use directories::UserDirs;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -89,6 +96,8 @@ struct GenerateContentRequest {
     system_instruction: Option<Content>,
     #[serde(rename = "generationConfig")]
     generation_config: GenerationConfig,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<GeminiTool>>,
 }
 
 /// Request envelope for the internal cloudcode-pa API.
@@ -125,6 +134,8 @@ struct InternalGenerateContentRequest {
     system_instruction: Option<Content>,
     #[serde(rename = "generationConfig", skip_serializing_if = "Option::is_none")]
     generation_config: Option<GenerationConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<GeminiTool>>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -134,9 +145,51 @@ struct Content {
     parts: Vec<Part>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+/// A request part: plain text, a model `functionCall` replayed from history,
+/// or a `functionResponse` carrying a tool result.
+#[derive(Debug, Serialize, Clone, Default)]
 struct Part {
-    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(rename = "functionCall", skip_serializing_if = "Option::is_none")]
+    function_call: Option<FunctionCall>,
+    #[serde(rename = "functionResponse", skip_serializing_if = "Option::is_none")]
+    function_response: Option<FunctionResponse>,
+}
+
+impl Part {
+    fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: Some(text.into()),
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct FunctionCall {
+    name: String,
+    #[serde(default)]
+    args: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct FunctionResponse {
+    name: String,
+    response: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct GeminiTool {
+    #[serde(rename = "functionDeclarations")]
+    function_declarations: Vec<FunctionDeclaration>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct FunctionDeclaration {
+    name: String,
+    description: String,
+    parameters: serde_json::Value,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -189,9 +242,33 @@ struct ResponsePart {
     /// Thinking models (e.g. gemini-3-pro-preview) mark reasoning parts with `thought: true`.
     #[serde(default)]
     thought: bool,
+    #[serde(default, rename = "functionCall")]
+    function_call: Option<FunctionCall>,
 }
 
 impl CandidateContent {
+    /// `functionCall` parts as tool calls, with arguments JSON-stringified.
+    ///
+    /// Gemini does not assign call ids, so each call gets a fresh one; the
+    /// function name for the matching `functionResponse` is recovered from
+    /// the assistant history entry that carries the same id.
+    fn tool_calls(&self) -> Vec<ToolCall> {
+        self.parts
+            .iter()
+            .filter_map(|part| part.function_call.as_ref())
+            .filter(|call| !call.name.is_empty())
+            .map(|call| ToolCall {
+                id: format!("call_{}", uuid::Uuid::new_v4().simple()),
+                name: call.name.clone(),
+                arguments: if call.args.is_null() {
+                    "{}".to_string()
+                } else {
+                    call.args.to_string()
+                },
+            })
+            .collect()
+    }
+
     /// Extract effective text, skipping thinking/signature parts.
     ///
     /// Gemini thinking models (e.g. gemini-3-pro-preview) return parts like:
@@ -898,6 +975,7 @@ impl GeminiProvider {
                         } else {
                             None
                         },
+                        tools: request.tools.clone(),
                     },
                 };
                 self.http_client()
@@ -909,6 +987,147 @@ impl GeminiProvider {
         }
     }
 
+    fn convert_tool_specs(tools: Option<&[ToolSpec]>) -> Option<Vec<GeminiTool>> {
+        let items = tools?;
+        if items.is_empty() {
+            return None;
+        }
+        let function_declarations = items
+            .iter()
+            .map(|tool| FunctionDeclaration {
+                name: tool.name.clone(),
+                description: tool.description.clone(),
+                parameters: SchemaCleanr::clean_for_gemini(tool.parameters.clone()),
+            })
+            .collect();
+        Some(vec![GeminiTool {
+            function_declarations,
+        }])
+    }
+
+    /// Replay an assistant turn stored as `{"content", "tool_calls"}` JSON as
+    /// `model` parts, remembering each call id's function name.
+    fn parse_assistant_tool_call_message(
+        content: &str,
+        call_names: &mut HashMap<String, String>,
+    ) -> Option<Vec<Part>> {
+        let value = serde_json::from_str::<serde_json::Value>(content).ok()?;
+        let tool_calls = value
+            .get("tool_calls")
+            .and_then(|v| serde_json::from_value::<Vec<ToolCall>>(v.clone()).ok())?;
+
+        let mut parts = Vec::new();
+        if let Some(text) = value
+            .get("content")
+            .and_then(serde_json::Value::as_str)
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+        {
+            parts.push(Part::text(text));
+        }
+        for call in tool_calls {
+            let args = serde_json::from_str::<serde_json::Value>(&call.arguments)
+                .unwrap_or_else(|_| serde_json::Value::Object(serde_json::Map::new()));
+            call_names.insert(call.id, call.name.clone());
+            parts.push(Part {
+                function_call: Some(FunctionCall {
+                    name: call.name,
+                    args,
+                }),
+                ..Part::default()
+            });
+        }
+        Some(parts)
+    }
+
+    /// Turn a `{"tool_call_id", "content"}` tool message into a
+    /// `functionResponse` part. Gemini matches responses by function name, so
+    /// the id is resolved through the calls seen earlier in the history.
+    fn parse_tool_result_message(
+        content: &str,
+        call_names: &HashMap<String, String>,
+    ) -> Option<Part> {
+        let value = serde_json::from_str::<serde_json::Value>(content).ok()?;
+        let tool_call_id = value
+            .get("tool_call_id")
+            .and_then(serde_json::Value::as_str)?;
+        let name = call_names
+            .get(tool_call_id)
+            .cloned()
+            .unwrap_or_else(|| tool_call_id.to_string());
+        let result = value
+            .get("content")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("");
+        Some(Part {
+            function_response: Some(FunctionResponse {
+                name,
+                response: serde_json::json!({ "content": result }),
+            }),
+            ..Part::default()
+        })
+    }
+
+    /// Split history into `systemInstruction` and `contents`, mapping
+    /// assistant → `model` and tool results → `user` `functionResponse` parts.
+    fn convert_messages(messages: &[ChatMessage]) -> (Option<Content>, Vec<Content>) {
+        let mut system_parts: Vec<&str> = Vec::new();
+        let mut contents: Vec<Content> = Vec::new();
+        let mut call_names: HashMap<String, String> = HashMap::new();
+
+        for msg in messages {
+            match msg.role() {
+                Role::System => system_parts.push(&msg.content),
+                Role::User => contents.push(Content {
+                    role: Some("user".to_string()),
+                    parts: vec![Part::text(msg.content.as_str())],
+                }),
+                Role::Assistant => {
+                    // Gemini API uses "model" role instead of "assistant"
+                    let parts =
+                        Self::parse_assistant_tool_call_message(&msg.content, &mut call_names)
+                            .unwrap_or_else(|| vec![Part::text(msg.content.as_str())]);
+                    contents.push(Content {
+                        role: Some("model".to_string()),
+                        parts,
+                    });
+                }
+                Role::Tool => {
+                    let part = Self::parse_tool_result_message(&msg.content, &call_names)
+                        .unwrap_or_else(|| Part::text(msg.content.as_str()));
+                    // All responses for one model turn belong in a single content.
+                    match contents.last_mut() {
+                        Some(last)
+                            if last.role.as_deref() == Some("user")
+                                && last.parts.iter().all(|p| p.function_response.is_some())
+                                && part.function_response.is_some() =>
+                        {
+                            last.parts.push(part);
+                        }
+                        _ => contents.push(Content {
+                            role: Some("user".to_string()),
+                            parts: vec![part],
+                        }),
+                    }
+                }
+                Role::Unknown(role) => {
+                    tracing::warn!("Gemini: skipping message with unknown role {role:?}");
+                }
+            }
+        }
+
+        let system_instruction = if system_parts.is_empty() {
+            None
+        } else {
+            Some(Content {
+                role: None,
+                parts: vec![Part::text(system_parts.join("\n\n"))],
+            })
+        };
+
+        (system_instruction, contents)
+    }
+
     fn should_retry_oauth_without_generation_config(
         status: reqwest::StatusCode,
         error_text: &str,
@@ -935,9 +1154,10 @@ impl GeminiProvider {
         &self,
         contents: Vec<Content>,
         system_instruction: Option<Content>,
+        tools: Option<Vec<GeminiTool>>,
         model: &str,
         temperature: f64,
-    ) -> anyhow::Result<(String, Option<TokenUsage>)> {
+    ) -> anyhow::Result<ChatResponse> {
         let auth = self.auth.as_ref().ok_or_else(|| {
             anyhow::anyhow!(
                 "Gemini API key not found. Options:\n\
@@ -985,8 +1205,9 @@ impl GeminiProvider {
             system_instruction,
             generation_config: GenerationConfig {
                 temperature,
-                max_output_tokens: 8192,
+                max_output_tokens: crate::providers::max_output_tokens().unwrap_or(8192),
             },
+            tools,
         };
 
         let url = Self::build_generate_content_url(model, auth);
@@ -1130,19 +1351,37 @@ impl GeminiProvider {
             output_tokens: u.candidates_token_count,
         });
 
-        let text = result
+        let content = result
             .candidates
             .and_then(|c| c.into_iter().next())
-            .and_then(|c| c.content)
-            .and_then(|c| c.effective_text())
-            .ok_or_else(|| anyhow::anyhow!("No response from Gemini"))?;
+            .and_then(|c| c.content);
+        let tool_calls = content
+            .as_ref()
+            .map(CandidateContent::tool_calls)
+            .unwrap_or_default();
+        let text = content.and_then(CandidateContent::effective_text);
+        if text.is_none() && tool_calls.is_empty() {
+            anyhow::bail!("No response from Gemini");
+        }
 
-        Ok((text, usage))
+        Ok(ChatResponse {
+            text,
+            tool_calls,
+            usage,
+            reasoning_content: None,
+        })
     }
 }
 
 #[async_trait]
 impl Provider for GeminiProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: true,
+            vision: false,
+        }
+    }
+
     async fn chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -1152,22 +1391,20 @@ impl Provider for GeminiProvider {
     ) -> anyhow::Result<String> {
         let system_instruction = system_prompt.map(|sys| Content {
             role: None,
-            parts: vec![Part {
-                text: sys.to_string(),
-            }],
+            parts: vec![Part::text(sys)],
         });
 
         let contents = vec![Content {
             role: Some("user".to_string()),
-            parts: vec![Part {
-                text: message.to_string(),
-            }],
+            parts: vec![Part::text(message)],
         }];
 
-        let (text, _usage) = self
-            .send_generate_content(contents, system_instruction, model, temperature)
+        let response = self
+            .send_generate_content(contents, system_instruction, None, model, temperature)
             .await?;
-        Ok(text)
+        response
+            .text
+            .ok_or_else(|| anyhow::anyhow!("No response from Gemini"))
     }
 
     async fn chat_with_history(
@@ -1176,50 +1413,13 @@ impl Provider for GeminiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let mut system_parts: Vec<&str> = Vec::new();
-        let mut contents: Vec<Content> = Vec::new();
-
-        for msg in messages {
-            match msg.role.as_str() {
-                "system" => {
-                    system_parts.push(&msg.content);
-                }
-                "user" => {
-                    contents.push(Content {
-                        role: Some("user".to_string()),
-                        parts: vec![Part {
-                            text: msg.content.clone(),
-                        }],
-                    });
-                }
-                "assistant" => {
-                    // Gemini API uses "model" role instead of "assistant"
-                    contents.push(Content {
-                        role: Some("model".to_string()),
-                        parts: vec![Part {
-                            text: msg.content.clone(),
-                        }],
-                    });
-                }
-                _ => {}
-            }
-        }
-
-        let system_instruction = if system_parts.is_empty() {
-            None
-        } else {
-            Some(Content {
-                role: None,
-                parts: vec![Part {
-                    text: system_parts.join("\n\n"),
-                }],
-            })
-        };
-
-        let (text, _usage) = self
-            .send_generate_content(contents, system_instruction, model, temperature)
+        let (system_instruction, contents) = Self::convert_messages(messages);
+        let response = self
+            .send_generate_content(contents, system_instruction, None, model, temperature)
             .await?;
-        Ok(text)
+        response
+            .text
+            .ok_or_else(|| anyhow::anyhow!("No response from Gemini"))
     }
 
     async fn chat(
@@ -1228,49 +1428,61 @@ impl Provider for GeminiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let mut system_parts: Vec<&str> = Vec::new();
-        let mut contents: Vec<Content> = Vec::new();
-
-        for msg in request.messages {
-            match msg.role.as_str() {
-                "system" => system_parts.push(&msg.content),
-                "user" => contents.push(Content {
-                    role: Some("user".to_string()),
-                    parts: vec![Part {
-                        text: msg.content.clone(),
-                    }],
-                }),
-                "assistant" => contents.push(Content {
-                    role: Some("model".to_string()),
-                    parts: vec![Part {
-                        text: msg.content.clone(),
-                    }],
-                }),
-                _ => {}
-            }
-        }
+        let (system_instruction, contents) = Self::convert_messages(request.messages);
+        self.send_generate_content(
+            contents,
+            system_instruction,
+            Self::convert_tool_specs(request.tools),
+            model,
+            temperature,
+        )
+        .await
+    }
 
-        let system_instruction = if system_parts.is_empty() {
-            None
-        } else {
-            Some(Content {
-                role: None,
-                parts: vec![Part {
-                    text: system_parts.join("\n\n"),
-                }],
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        // Same OpenAI-format → ToolSpec conversion as the Anthropic provider,
+        // so both share one `chat()` path for history and tool mapping.
+        let tool_specs: Vec<ToolSpec> = tools
+            .iter()
+            .filter_map(|t| {
+                let func = t.get("function").or_else(|| {
+                    tracing::warn!("Skipping malformed tool definition (missing 'function' key)");
+                    None
+                })?;
+                let name = func.get("name").and_then(|n| n.as_str()).or_else(|| {
+                    tracing::warn!("Skipping tool with missing or non-string 'name'");
+                    None
+                })?;
+                Some(ToolSpec {
+                    name: name.to_string(),
+                    description: func
+                        .get("description")
+                        .and_then(|d| d.as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                    parameters: func
+                        .get("parameters")
+                        .cloned()
+                        .unwrap_or(serde_json::json!({"type": "object"})),
+                })
             })
-        };
-
-        let (text, usage) = self
-            .send_generate_content(contents, system_instruction, model, temperature)
-            .await?;
+            .collect();
 
-        Ok(ChatResponse {
-            text: Some(text),
-            tool_calls: Vec::new(),
-            usage,
-            reasoning_content: None,
-        })
+        let request = crate::providers::traits::ChatRequest {
+            messages,
+            tools: if tool_specs.is_empty() {
+                None
+            } else {
+                Some(&tool_specs)
+            },
+        };
+        self.chat(request, model, temperature).await
     }
 
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -1542,15 +1754,14 @@ mod tests {
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
-                parts: vec![Part {
-                    text: "hello".into(),
-                }],
+                parts: vec![Part::text("hello")],
             }],
             system_instruction: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
             },
+            tools: None,
         };
 
         let request = provider
@@ -1583,15 +1794,14 @@ mod tests {
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
-                parts: vec![Part {
-                    text: "hello".into(),
-                }],
+                parts: vec![Part::text("hello")],
             }],
             system_instruction: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
             },
+            tools: None,
         };
 
         let request = provider
@@ -1627,15 +1837,14 @@ mod tests {
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
-                parts: vec![Part {
-                    text: "hello".into(),
-                }],
+                parts: vec![Part::text("hello")],
             }],
             system_instruction: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
             },
+            tools: None,
         };
 
         let request = provider
@@ -1659,20 +1868,17 @@ mod tests {
         let request = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".to_string()),
-                parts: vec![Part {
-                    text: "Hello".to_string(),
-                }],
+                parts: vec![Part::text("Hello")],
             }],
             system_instruction: Some(Content {
                 role: None,
-                parts: vec![Part {
-                    text: "You are helpful".to_string(),
-                }],
+                parts: vec![Part::text("You are helpful")],
             }),
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
             },
+            tools: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -1693,15 +1899,14 @@ mod tests {
             request: InternalGenerateContentRequest {
                 contents: vec![Content {
                     role: Some("user".to_string()),
-                    parts: vec![Part {
-                        text: "Hello".to_string(),
-                    }],
+                    parts: vec![Part::text("Hello")],
                 }],
                 system_instruction: None,
                 generation_config: Some(GenerationConfig {
                     temperature: 0.7,
                     max_output_tokens: 8192,
                 }),
+                tools: None,
             },
         };
 
@@ -1725,12 +1930,11 @@ mod tests {
             request: InternalGenerateContentRequest {
                 contents: vec![Content {
                     role: Some("user".to_string()),
-                    parts: vec![Part {
-                        text: "Hello".to_string(),
-                    }],
+                    parts: vec![Part::text("Hello")],
                 }],
                 system_instruction: None,
                 generation_config: None,
+                tools: None,
             },
         };
 
@@ -1748,12 +1952,11 @@ mod tests {
             request: InternalGenerateContentRequest {
                 contents: vec![Content {
                     role: Some("user".to_string()),
-                    parts: vec![Part {
-                        text: "Hello".to_string(),
-                    }],
+                    parts: vec![Part::text("Hello")],
                 }],
                 system_instruction: None,
                 generation_config: None,
+                tools: None,
             },
         };
 
@@ -2139,4 +2342,183 @@ mod tests {
         // Should succeed without making HTTP requests
         assert!(result.is_ok());
     }
+
+    fn tool_round_trip_fixture() -> Vec<ChatMessage> {
+        let assistant = serde_json::json!({
+            "content": "Checking both.",
+            "tool_calls": [
+                {"id": "call_a", "name": "shell", "arguments": "{\"command\":\"date\"}"},
+                {"id": "call_b", "name": "file_read", "arguments": "{\"path\":\"README.md\"}"}
+            ]
+        });
+        vec![
+            ChatMessage::system("You are helpful."),
+            ChatMessage::user("What time is it, and what's in the README?"),
+            ChatMessage::assistant(assistant.to_string()),
+            ChatMessage::tool(r#"{"tool_call_id":"call_a","content":"Mon Jan 5"}"#),
+            ChatMessage::tool(r#"{"tool_call_id":"call_b","content":"Hello"}"#),
+            ChatMessage::assistant("It's Monday; the README says Hello."),
+            ChatMessage::user("Thanks"),
+        ]
+    }
+
+    #[test]
+    fn convert_messages_maps_tool_round_trip() {
+        let (system, contents) = GeminiProvider::convert_messages(&tool_round_trip_fixture());
+
+        let system = serde_json::to_value(system.unwrap()).unwrap();
+        assert_eq!(
+            system,
+            serde_json::json!({"parts": [{"text": "You are helpful."}]})
+        );
+
+        let contents = serde_json::to_value(&contents).unwrap();
+        assert_eq!(
+            contents,
+            serde_json::json!([
+                {"role": "user", "parts": [{"text": "What time is it, and what's in the README?"}]},
+                {"role": "model", "parts": [
+                    {"text": "Checking both."},
+                    {"functionCall": {"name": "shell", "args": {"command": "date"}}},
+                    {"functionCall": {"name": "file_read", "args": {"path": "README.md"}}}
+                ]},
+                {"role": "user", "parts": [
+                    {"functionResponse": {"name": "shell", "response": {"content": "Mon Jan 5"}}},
+                    {"functionResponse": {"name": "file_read", "response": {"content": "Hello"}}}
+                ]},
+                {"role": "model", "parts": [{"text": "It's Monday; the README says Hello."}]},
+                {"role": "user", "parts": [{"text": "Thanks"}]}
+            ])
+        );
+    }
+
+    #[test]
+    fn convert_messages_falls_back_to_id_for_unknown_tool_call() {
+        let messages = vec![ChatMessage::tool(
+            r#"{"tool_call_id":"shell","content":"ok"}"#,
+        )];
+        let (_, contents) = GeminiProvider::convert_messages(&messages);
+        let part = &contents[0].parts[0];
+        assert_eq!(part.function_response.as_ref().unwrap().name, "shell");
+    }
+
+    #[test]
+    fn convert_tool_specs_builds_cleaned_function_declarations() {
+        assert!(GeminiProvider::convert_tool_specs(None).is_none());
+        assert!(GeminiProvider::convert_tool_specs(Some(&[])).is_none());
+
+        let specs = vec![ToolSpec {
+            name: "shell".into(),
+            description: "Run a shell command".into(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {"command": {"type": "string", "minLength": 1}},
+                "required": ["command"],
+                "additionalProperties": false
+            }),
+        }];
+        let tools = GeminiProvider::convert_tool_specs(Some(&specs)).unwrap();
+        let json = serde_json::to_value(&tools).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([{
+                "functionDeclarations": [{
+                    "name": "shell",
+                    "description": "Run a shell command",
+                    "parameters": {
+                        "type": "object",
+                        "properties": {"command": {"type": "string"}},
+                        "required": ["command"]
+                    }
+                }]
+            }])
+        );
+    }
+
+    #[test]
+    fn request_with_tools_serializes_tools_and_internal_envelope_keeps_them() {
+        let (system_instruction, contents) =
+            GeminiProvider::convert_messages(&tool_round_trip_fixture());
+        let specs = vec![ToolSpec {
+            name: "shell".into(),
+            description: "Run a shell command".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+        let request = GenerateContentRequest {
+            contents,
+            system_instruction,
+            generation_config: GenerationConfig {
+                temperature: 0.2,
+                max_output_tokens: 8192,
+            },
+            tools: GeminiProvider::convert_tool_specs(Some(&specs)),
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["tools"][0]["functionDeclarations"][0]["name"], "shell");
+        assert!(json["systemInstruction"].is_object());
+
+        let provider = test_provider(Some(test_oauth_auth("ya29.mock-token")));
+        let auth = test_oauth_auth("ya29.mock-token");
+        let url = GeminiProvider::build_generate_content_url("gemini-2.5-pro", &auth);
+        let built = provider
+            .build_generate_content_request(
+                &auth,
+                &url,
+                &request,
+                "gemini-2.5-pro",
+                true,
+                Some("proj"),
+                Some("ya29.mock-token"),
+            )
+            .build()
+            .unwrap();
+        let body: serde_json::Value =
+            serde_json::from_slice(built.body().unwrap().as_bytes().unwrap()).unwrap();
+        assert_eq!(
+            body["request"]["tools"][0]["functionDeclarations"][0]["name"],
+            "shell"
+        );
+    }
+
+    #[test]
+    fn request_without_tools_omits_tools_field() {
+        let request = GenerateContentRequest {
+            contents: vec![],
+            system_instruction: None,
+            generation_config: GenerationConfig {
+                temperature: 0.7,
+                max_output_tokens: 8192,
+            },
+            tools: None,
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert!(json.get("tools").is_none());
+    }
+
+    #[test]
+    fn response_function_call_parts_become_tool_calls() {
+        let json = r#"{
+            "candidates": [{"content": {"role": "model", "parts": [
+                {"functionCall": {"name": "shell", "args": {"command": "date"}}},
+                {"functionCall": {"name": "memory_recall"}}
+            ]}}],
+            "usageMetadata": {"promptTokenCount": 50, "candidatesTokenCount": 7}
+        }"#;
+        let resp: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let content = resp.candidates.unwrap().remove(0).content.unwrap();
+
+        let calls = content.tool_calls();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(calls[0].arguments, r#"{"command":"date"}"#);
+        assert_eq!(calls[1].arguments, "{}");
+        assert_ne!(calls[0].id, calls[1].id);
+        assert!(content.effective_text().is_none());
+    }
+
+    #[test]
+    fn gemini_declares_native_tool_calling() {
+        let provider = test_provider(None);
+        assert!(provider.supports_native_tools());
+    }
 }
@@ -18,6 +18,7 @@
 
 pub mod anthropic;
 pub mod bedrock;
+pub mod cache;
 pub mod compatible;
 pub mod copilot;
 pub mod gemini;
@@ -33,7 +34,7 @@ pub mod traits;
 #[allow(unused_imports)]
 pub use traits::{
     ChatMessage, ChatRequest, ChatResponse, ConversationMessage, Provider, ProviderCapabilityError,
-    ToolCall, ToolResultMessage,
+    ProviderError, Role, ToolCall, ToolResultMessage,
 };
 
 use crate::auth::AuthService;
@@ -75,6 +76,22 @@ const ZAI_GLOBAL_BASE_URL: &str = "https://api.z.ai/api/coding/paas/v4";
 const ZAI_CN_BASE_URL: &str = "https://open.bigmodel.cn/api/coding/paas/v4";
 const VERCEL_AI_GATEWAY_BASE_URL: &str = "https://ai-gateway.vercel.sh/v1";
 
+tokio::task_local! {
+    static MAX_OUTPUT_TOKENS: u32;
+}
+
+/// Run `fut` with the provider calls it makes capped at `limit` output
+/// tokens, for short utility replies such as session titles. Providers
+/// without such a request option ignore it.
+pub async fn with_max_output_tokens<F: std::future::Future>(limit: u32, fut: F) -> F::Output {
+    MAX_OUTPUT_TOKENS.scope(limit, fut).await
+}
+
+/// Output-token cap set by [`with_max_output_tokens`] for the running task.
+pub fn max_output_tokens() -> Option<u32> {
+    MAX_OUTPUT_TOKENS.try_with(|limit| *limit).ok()
+}
+
 pub(crate) fn is_minimax_intl_alias(name: &str) -> bool {
     matches!(
         name,
@@ -676,6 +693,10 @@ pub struct ProviderRuntimeOptions {
     pub zeroclaw_dir: Option<PathBuf>,
     pub secrets_encrypt: bool,
     pub reasoning_enabled: Option<bool>,
+    pub openrouter: crate::config::OpenRouterConfig,
+    /// Shared response cache; when set, factory-built providers are wrapped
+    /// in [`cache::CachingProvider`].
+    pub response_cache: Option<std::sync::Arc<cache::ProviderCache>>,
 }
 
 impl Default for ProviderRuntimeOptions {
@@ -686,6 +707,8 @@ impl Default for ProviderRuntimeOptions {
             zeroclaw_dir: None,
             secrets_encrypt: true,
             reasoning_enabled: None,
+            openrouter: crate::config::OpenRouterConfig::default(),
+            response_cache: None,
         }
     }
 }
@@ -968,9 +991,17 @@ fn create_provider_with_url_and_options(
             )?))
         }
         // ── Primary providers (custom implementations) ───────
-        "openrouter" => Ok(Box::new(openrouter::OpenRouterProvider::new(key))),
+        "openrouter" => Ok(Box::new(openrouter::OpenRouterProvider::with_config(
+            key,
+            options.openrouter.clone(),
+        ))),
         "anthropic" => Ok(Box::new(anthropic::AnthropicProvider::new(key))),
-        "openai" => Ok(Box::new(openai::OpenAiProvider::with_base_url(api_url, key))),
+        "openai" => Ok(Box::new(
+            openai::OpenAiProvider::with_base_url(api_url, key).with_organization(
+                std::env::var("OPENAI_ORG_ID").ok().as_deref(),
+                std::env::var("OPENAI_PROJECT_ID").ok().as_deref(),
+            ),
+        )),
         // Ollama uses api_url for custom base URL (e.g. remote Ollama instance)
         "ollama" => Ok(Box::new(ollama::OllamaProvider::new_with_reasoning(
             api_url,
@@ -1339,9 +1370,24 @@ pub fn create_resilient_provider_with_options(
         reliability.provider_backoff_ms,
     )
     .with_api_keys(reliability.api_keys.clone())
-    .with_model_fallbacks(reliability.model_fallbacks.clone());
+    .with_model_fallbacks(reliability.model_fallbacks.clone())
+    .with_request_timeout(reliability.provider_timeout_secs);
+
+    Ok(with_response_cache(Box::new(reliable), options))
+}
 
-    Ok(Box::new(reliable))
+/// Wrap `provider` in the shared response cache when one is configured.
+fn with_response_cache(
+    provider: Box<dyn Provider>,
+    options: &ProviderRuntimeOptions,
+) -> Box<dyn Provider> {
+    match &options.response_cache {
+        Some(cache) => Box::new(cache::CachingProvider::new(
+            provider,
+            std::sync::Arc::clone(cache),
+        )),
+        None => provider,
+    }
 }
 
 /// Create a RouterProvider if model routes are configured, otherwise return a
@@ -1394,7 +1440,12 @@ pub fn create_routed_provider_with_options(
         }
     }
 
-    // Create each provider (with its own resilience wrapper)
+    // Create each provider (with its own resilience wrapper). The cache
+    // wraps the router as a whole, so routes are built without it.
+    let route_options = ProviderRuntimeOptions {
+        response_cache: None,
+        ..options.clone()
+    };
     let mut providers: Vec<(String, Box<dyn Provider>)> = Vec::new();
     for name in &needed {
         let routed_credential = model_routes
@@ -1409,7 +1460,7 @@ pub fn create_routed_provider_with_options(
         let key = routed_credential.or(api_key);
         // Only use api_url for the primary provider
         let url = if name == primary_name { api_url } else { None };
-        match create_resilient_provider_with_options(name, key, url, reliability, options) {
+        match create_resilient_provider_with_options(name, key, url, reliability, &route_options) {
             Ok(provider) => providers.push((name.clone(), provider)),
             Err(e) => {
                 if name == primary_name {
@@ -1437,11 +1488,8 @@ pub fn create_routed_provider_with_options(
         })
         .collect();
 
-    Ok(Box::new(router::RouterProvider::new(
-        providers,
-        routes,
-        default_model.to_string(),
-    )))
+    let router = router::RouterProvider::new(providers, routes, default_model.to_string());
+    Ok(with_response_cache(Box::new(router), options))
 }
 
 /// Information about a supported provider for display purposes.
@@ -2442,6 +2490,7 @@ mod tests {
         let reliability = crate::config::ReliabilityConfig {
             provider_retries: 1,
             provider_backoff_ms: 100,
+            provider_timeout_secs: 120,
             fallback_providers: vec![
                 "openrouter".into(),
                 "nonexistent-provider".into(),
@@ -2454,6 +2503,8 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            outbound_queue_ttl_secs: 21_600,
+            outbound_retry_max_backoff_secs: 300,
         };
 
         let provider = create_resilient_provider(
@@ -2486,6 +2537,7 @@ mod tests {
         let reliability = crate::config::ReliabilityConfig {
             provider_retries: 1,
             provider_backoff_ms: 100,
+            provider_timeout_secs: 120,
             fallback_providers: vec!["lmstudio".into(), "ollama".into()],
             api_keys: Vec::new(),
             model_fallbacks: std::collections::HashMap::new(),
@@ -2493,6 +2545,8 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            outbound_queue_ttl_secs: 21_600,
+            outbound_retry_max_backoff_secs: 300,
         };
 
         // Primary uses a ZAI key; fallbacks (lmstudio, ollama) should NOT
@@ -2508,6 +2562,7 @@ mod tests {
         let reliability = crate::config::ReliabilityConfig {
             provider_retries: 1,
             provider_backoff_ms: 100,
+            provider_timeout_secs: 120,
             fallback_providers: vec!["custom:http://host.docker.internal:1234/v1".into()],
             api_keys: Vec::new(),
             model_fallbacks: std::collections::HashMap::new(),
@@ -2515,6 +2570,8 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            outbound_queue_ttl_secs: 21_600,
+            outbound_retry_max_backoff_secs: 300,
         };
 
         let provider =
@@ -2529,6 +2586,7 @@ mod tests {
         let reliability = crate::config::ReliabilityConfig {
             provider_retries: 1,
             provider_backoff_ms: 100,
+            provider_timeout_secs: 120,
             fallback_providers: vec![
                 "deepseek".into(),
                 "custom:http://localhost:8080/v1".into(),
@@ -2541,6 +2599,8 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            outbound_queue_ttl_secs: 21_600,
+            outbound_retry_max_backoff_secs: 300,
         };
 
         let provider = create_resilient_provider("zai", Some("zai-test-key"), None, &reliability);
@@ -2566,6 +2626,7 @@ mod tests {
         let reliability = crate::config::ReliabilityConfig {
             provider_retries: 1,
             provider_backoff_ms: 100,
+            provider_timeout_secs: 120,
             fallback_providers: vec!["osaurus".into(), "lmstudio".into()],
             api_keys: Vec::new(),
             model_fallbacks: std::collections::HashMap::new(),
@@ -2573,6 +2634,8 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            outbound_queue_ttl_secs: 21_600,
+            outbound_retry_max_backoff_secs: 300,
         };
 
         let provider = create_resilient_provider("zai", Some("zai-test-key"), None, &reliability);
@@ -2859,6 +2922,7 @@ mod tests {
         let reliability = crate::config::ReliabilityConfig {
             provider_retries: 1,
             provider_backoff_ms: 100,
+            provider_timeout_secs: 120,
             fallback_providers: vec!["openai-codex:second".into()],
             api_keys: Vec::new(),
             model_fallbacks: std::collections::HashMap::new(),
@@ -2866,6 +2930,8 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            outbound_queue_ttl_secs: 21_600,
+            outbound_retry_max_backoff_secs: 300,
         };
 
         // openai-codex resolves its own OAuth credential; it should not
@@ -2883,6 +2949,7 @@ mod tests {
         let reliability = crate::config::ReliabilityConfig {
             provider_retries: 1,
             provider_backoff_ms: 100,
+            provider_timeout_secs: 120,
             fallback_providers: vec![
                 "openai-codex:second".into(),
                 "custom:http://localhost:8080/v1".into(),
@@ -2895,6 +2962,8 @@ mod tests {
             channel_max_backoff_secs: 60,
             scheduler_poll_secs: 15,
             scheduler_retries: 2,
+            outbound_queue_ttl_secs: 21_600,
+            outbound_retry_max_backoff_secs: 300,
         };
 
         let provider = create_resilient_provider("ollama", None, None, &reliability);
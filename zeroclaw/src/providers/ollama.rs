@@ -1,6 +1,6 @@
 use crate::multimodal;
 use crate::providers::traits::{
-    ChatMessage, ChatResponse, Provider, ProviderCapabilities, TokenUsage, ToolCall,
+    ChatMessage, ChatResponse, Provider, ProviderCapabilities, Role, TokenUsage, ToolCall,
 };
 use async_trait::async_trait;
 use reqwest::Client;
@@ -56,6 +56,8 @@ struct OutgoingFunction {
 #[derive(Debug, Serialize)]
 struct Options {
     temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_predict: Option<u32>,
 }
 
 // ─── Response Structures ──────────────────────────────────────────────────────
@@ -210,7 +212,10 @@ impl OllamaProvider {
             model: model.to_string(),
             messages,
             stream: false,
-            options: Options { temperature },
+            options: Options {
+                temperature,
+                num_predict: crate::providers::max_output_tokens(),
+            },
             think: self.reasoning_enabled,
             tools: tools.map(|t| t.to_vec()),
         }
@@ -252,7 +257,7 @@ impl OllamaProvider {
         messages
             .iter()
             .map(|message| {
-                if message.role == "assistant" {
+                if message.role() == Role::Assistant {
                     if let Ok(value) = serde_json::from_str::<serde_json::Value>(&message.content) {
                         if let Some(tool_calls_value) = value.get("tool_calls") {
                             if let Ok(parsed_calls) =
@@ -289,7 +294,7 @@ impl OllamaProvider {
                     }
                 }
 
-                if message.role == "tool" {
+                if message.role() == Role::Tool {
                     if let Ok(value) = serde_json::from_str::<serde_json::Value>(&message.content) {
                         let tool_name = value
                             .get("tool_name")
@@ -321,7 +326,7 @@ impl OllamaProvider {
                     }
                 }
 
-                if message.role == "user" {
+                if message.role() == Role::User {
                     let (content, images) = self.convert_user_message_content(&message.content);
                     return Message {
                         role: "user".to_string(),
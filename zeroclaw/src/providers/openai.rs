@@ -1,19 +1,27 @@
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, TokenUsage, ToolCall as ProviderToolCall,
+    Provider, ProviderError, Role, TokenUsage, ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, StatusCode};
 use serde::{Deserialize, Serialize};
 
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
 pub struct OpenAiProvider {
     base_url: String,
     credential: Option<String>,
+    /// Sent as `OpenAI-Organization` when set.
+    organization: Option<String>,
+    /// Sent as `OpenAI-Project` when set.
+    project: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 struct ChatRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_completion_tokens: Option<u32>,
     model: String,
     messages: Vec<Message>,
     temperature: f64,
@@ -27,6 +35,7 @@ struct Message {
 
 #[derive(Debug, Deserialize)]
 struct ChatResponse {
+    #[serde(default)]
     choices: Vec<Choice>,
 }
 
@@ -55,6 +64,8 @@ impl ResponseMessage {
 
 #[derive(Debug, Serialize)]
 struct NativeChatRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_completion_tokens: Option<u32>,
     model: String,
     messages: Vec<NativeMessage>,
     temperature: f64,
@@ -124,6 +135,7 @@ struct NativeFunctionCall {
 
 #[derive(Debug, Deserialize)]
 struct NativeChatResponse {
+    #[serde(default)]
     choices: Vec<NativeChoice>,
     #[serde(default)]
     usage: Option<UsageInfo>,
@@ -168,14 +180,58 @@ impl OpenAiProvider {
     }
 
     /// Create a provider with an optional custom base URL.
-    /// Defaults to `https://api.openai.com/v1` when `base_url` is `None`.
+    /// Defaults to `https://api.openai.com/v1` when `base_url` is `None`;
+    /// see [`normalize_base_url`] for how custom URLs are interpreted.
     pub fn with_base_url(base_url: Option<&str>, credential: Option<&str>) -> Self {
         Self {
             base_url: base_url
-                .map(|u| u.trim_end_matches('/').to_string())
-                .unwrap_or_else(|| "https://api.openai.com/v1".to_string()),
+                .map(normalize_base_url)
+                .filter(|u| !u.is_empty())
+                .unwrap_or_else(|| OPENAI_BASE_URL.to_string()),
             credential: credential.map(ToString::to_string),
+            organization: None,
+            project: None,
+        }
+    }
+
+    /// Attach organization/project headers for multi-org OpenAI accounts.
+    /// Blank values are ignored.
+    pub fn with_organization(mut self, organization: Option<&str>, project: Option<&str>) -> Self {
+        let clean = |v: Option<&str>| {
+            v.map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        self.organization = clean(organization);
+        self.project = clean(project);
+        self
+    }
+
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    /// Authenticated request builder. The hosted API needs a key;
+    /// self-hosted OpenAI-compatible servers (LM Studio, vLLM, llama.cpp)
+    /// usually run without one.
+    fn request(&self, method: reqwest::Method, path: &str) -> anyhow::Result<RequestBuilder> {
+        let mut builder = self.http_client().request(method, self.endpoint(path));
+        match self.credential.as_deref() {
+            Some(credential) => {
+                builder = builder.header("Authorization", format!("Bearer {credential}"));
+            }
+            None if self.base_url == OPENAI_BASE_URL => {
+                anyhow::bail!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
+            }
+            None => {}
+        }
+        if let Some(organization) = &self.organization {
+            builder = builder.header("OpenAI-Organization", organization);
         }
+        if let Some(project) = &self.project {
+            builder = builder.header("OpenAI-Project", project);
+        }
+        Ok(builder)
     }
 
     fn convert_tools(tools: Option<&[ToolSpec]>) -> Option<Vec<NativeToolSpec>> {
@@ -198,7 +254,8 @@ impl OpenAiProvider {
         messages
             .iter()
             .map(|m| {
-                if m.role == "assistant" {
+                let role = m.role();
+                if role == Role::Assistant {
                     if let Ok(value) = serde_json::from_str::<serde_json::Value>(&m.content) {
                         if let Some(tool_calls_value) = value.get("tool_calls") {
                             if let Ok(parsed_calls) =
@@ -237,7 +294,7 @@ impl OpenAiProvider {
                     }
                 }
 
-                if m.role == "tool" {
+                if role == Role::Tool {
                     if let Ok(value) = serde_json::from_str::<serde_json::Value>(&m.content) {
                         let tool_call_id = value
                             .get("tool_call_id")
@@ -257,8 +314,10 @@ impl OpenAiProvider {
                     }
                 }
 
+                // Unknown roles are passed through so the API rejects them
+                // rather than the turn being re-labelled.
                 NativeMessage {
-                    role: m.role.clone(),
+                    role: role.to_string(),
                     content: Some(m.content.clone()),
                     tool_call_id: None,
                     tool_calls: None,
@@ -295,6 +354,78 @@ impl OpenAiProvider {
     }
 }
 
+/// Normalize a configured base URL: drop trailing slashes and a pasted
+/// `/chat/completions` suffix, and add `/v1` when only a host was given.
+/// A base that already carries a path (`/v1`, `/openai/v1`, ...) is kept.
+fn normalize_base_url(raw: &str) -> String {
+    let mut url = raw.trim().trim_end_matches('/');
+    if let Some(stripped) = url.strip_suffix("/chat/completions") {
+        url = stripped.trim_end_matches('/');
+    }
+    let after_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    if url.is_empty() || after_scheme.contains('/') {
+        url.to_string()
+    } else {
+        format!("{url}/v1")
+    }
+}
+
+/// Turn a non-success response into an error, classifying context-window
+/// overflows and rejected credentials as [`ProviderError`]s.
+async fn response_error(response: reqwest::Response) -> anyhow::Error {
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "<failed to read provider error body>".to_string());
+    classify_error(status, &body)
+}
+
+fn classify_error(status: StatusCode, body: &str) -> anyhow::Error {
+    let error = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| value.get("error").cloned());
+    let field = |name: &str| {
+        error
+            .as_ref()
+            .and_then(|e| e.get(name))
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let code = field("code");
+    let message = super::sanitize_api_error(&match field("message") {
+        m if m.is_empty() => body.to_string(),
+        m => m,
+    });
+    let lower = message.to_lowercase();
+
+    let context_too_long = code == "context_length_exceeded"
+        || lower.contains("maximum context length")
+        || lower.contains("context length exceeded")
+        || lower.contains("context window")
+        || (lower.contains("prompt") && lower.contains("too long"));
+    if matches!(
+        status,
+        StatusCode::BAD_REQUEST | StatusCode::PAYLOAD_TOO_LARGE
+    ) && context_too_long
+    {
+        return ProviderError::ContextTooLong {
+            message: format!("OpenAI API error ({status}, context length exceeded): {message}"),
+        }
+        .into();
+    }
+    if matches!(status, StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) {
+        return ProviderError::Config {
+            message: format!(
+                "OpenAI API error ({status}): {message}. Check the API key, organization and project settings."
+            ),
+        }
+        .into();
+    }
+    anyhow::anyhow!("OpenAI API error ({status}): {message}")
+}
+
 #[async_trait]
 impl Provider for OpenAiProvider {
     async fn chat_with_system(
@@ -304,10 +435,6 @@ impl Provider for OpenAiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
-        })?;
-
         let mut messages = Vec::new();
 
         if let Some(sys) = system_prompt {
@@ -323,21 +450,20 @@ impl Provider for OpenAiProvider {
         });
 
         let request = ChatRequest {
+            max_completion_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages,
             temperature,
         };
 
         let response = self
-            .http_client()
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {credential}"))
+            .request(reqwest::Method::POST, "chat/completions")?
             .json(&request)
             .send()
             .await?;
 
         if !response.status().is_success() {
-            return Err(super::api_error("OpenAI", response).await);
+            return Err(response_error(response).await);
         }
 
         let chat_response: ChatResponse = response.json().await?;
@@ -356,12 +482,9 @@ impl Provider for OpenAiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
-        })?;
-
         let tools = Self::convert_tools(request.tools);
         let native_request = NativeChatRequest {
+            max_completion_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages: Self::convert_messages(request.messages),
             temperature,
@@ -370,15 +493,13 @@ impl Provider for OpenAiProvider {
         };
 
         let response = self
-            .http_client()
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {credential}"))
+            .request(reqwest::Method::POST, "chat/completions")?
             .json(&native_request)
             .send()
             .await?;
 
         if !response.status().is_success() {
-            return Err(super::api_error("OpenAI", response).await);
+            return Err(response_error(response).await);
         }
 
         let native_response: NativeChatResponse = response.json().await?;
@@ -408,10 +529,6 @@ impl Provider for OpenAiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
-        })?;
-
         let native_tools: Option<Vec<NativeToolSpec>> = if tools.is_empty() {
             None
         } else {
@@ -425,6 +542,7 @@ impl Provider for OpenAiProvider {
         };
 
         let native_request = NativeChatRequest {
+            max_completion_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages: Self::convert_messages(messages),
             temperature,
@@ -433,15 +551,13 @@ impl Provider for OpenAiProvider {
         };
 
         let response = self
-            .http_client()
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {credential}"))
+            .request(reqwest::Method::POST, "chat/completions")?
             .json(&native_request)
             .send()
             .await?;
 
         if !response.status().is_success() {
-            return Err(super::api_error("OpenAI", response).await);
+            return Err(response_error(response).await);
         }
 
         let native_response: NativeChatResponse = response.json().await?;
@@ -461,10 +577,8 @@ impl Provider for OpenAiProvider {
     }
 
     async fn warmup(&self) -> anyhow::Result<()> {
-        if let Some(credential) = self.credential.as_ref() {
-            self.http_client()
-                .get(format!("{}/models", self.base_url))
-                .header("Authorization", format!("Bearer {credential}"))
+        if self.credential.is_some() {
+            self.request(reqwest::Method::GET, "models")?
                 .send()
                 .await?
                 .error_for_status()?;
@@ -515,6 +629,7 @@ mod tests {
     #[test]
     fn request_serializes_with_system_message() {
         let req = ChatRequest {
+            max_completion_tokens: crate::providers::max_output_tokens(),
             model: "gpt-4o".to_string(),
             messages: vec![
                 Message {
@@ -537,6 +652,7 @@ mod tests {
     #[test]
     fn request_serializes_without_system() {
         let req = ChatRequest {
+            max_completion_tokens: crate::providers::max_output_tokens(),
             model: "gpt-4o".to_string(),
             messages: vec![Message {
                 role: "user".to_string(),
@@ -828,4 +944,106 @@ mod tests {
         assert!(json.contains("reasoning_content"));
         assert!(json.contains("thinking..."));
     }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // base URL / headers / error classification
+    // ═══════════════════════════════════════════════════════════════════════
+
+    #[test]
+    fn base_url_normalization_joins_with_single_slash() {
+        for raw in [
+            "https://api.example.com/v1",
+            "https://api.example.com/v1/",
+            "https://api.example.com/v1/chat/completions",
+            "https://api.example.com",
+            "https://api.example.com/",
+        ] {
+            let p = OpenAiProvider::with_base_url(Some(raw), None);
+            assert_eq!(
+                p.endpoint("chat/completions"),
+                "https://api.example.com/v1/chat/completions",
+                "raw base: {raw}"
+            );
+        }
+        let p = OpenAiProvider::with_base_url(Some("http://localhost:1234/openai/v1/"), None);
+        assert_eq!(
+            p.endpoint("/models"),
+            "http://localhost:1234/openai/v1/models"
+        );
+        let p = OpenAiProvider::with_base_url(Some("  "), None);
+        assert_eq!(p.base_url, OPENAI_BASE_URL);
+    }
+
+    #[test]
+    fn custom_base_url_does_not_require_key() {
+        let p = OpenAiProvider::with_base_url(Some("http://localhost:8000/v1"), None);
+        assert!(p.request(reqwest::Method::POST, "chat/completions").is_ok());
+        let p = OpenAiProvider::new(None);
+        assert!(p
+            .request(reqwest::Method::POST, "chat/completions")
+            .is_err());
+    }
+
+    #[test]
+    fn organization_and_project_headers_are_sent_when_set() {
+        let p = OpenAiProvider::new(Some("key")).with_organization(Some("org-1"), Some(" "));
+        let request = p
+            .request(reqwest::Method::POST, "chat/completions")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(request.headers()["OpenAI-Organization"], "org-1");
+        assert!(request.headers().get("OpenAI-Project").is_none());
+        assert_eq!(request.headers()["Authorization"], "Bearer key");
+    }
+
+    #[test]
+    fn classify_error_detects_context_length_by_code() {
+        let body = r#"{"error":{"message":"This model's maximum context length is 128000 tokens.","type":"invalid_request_error","code":"context_length_exceeded"}}"#;
+        let err = classify_error(StatusCode::BAD_REQUEST, body);
+        assert!(matches!(
+            err.downcast_ref::<ProviderError>(),
+            Some(ProviderError::ContextTooLong { .. })
+        ));
+        assert!(err.to_string().contains("128000"));
+    }
+
+    #[test]
+    fn classify_error_detects_context_length_by_message() {
+        let body = r#"{"error":{"message":"Prompt is too long for this model","code":null}}"#;
+        let err = classify_error(StatusCode::PAYLOAD_TOO_LARGE, body);
+        assert!(matches!(
+            err.downcast_ref::<ProviderError>(),
+            Some(ProviderError::ContextTooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn classify_error_maps_auth_failures_to_config_errors() {
+        let body = r#"{"error":{"message":"Incorrect API key provided","code":"invalid_api_key"}}"#;
+        for status in [StatusCode::UNAUTHORIZED, StatusCode::FORBIDDEN] {
+            let err = classify_error(status, body);
+            assert!(matches!(
+                err.downcast_ref::<ProviderError>(),
+                Some(ProviderError::Config { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn classify_error_keeps_other_failures_untyped() {
+        let err = classify_error(StatusCode::TOO_MANY_REQUESTS, "rate limited");
+        assert!(err.downcast_ref::<ProviderError>().is_none());
+        assert!(err.to_string().contains("429"));
+        assert!(err.to_string().contains("rate limited"));
+    }
+
+    #[test]
+    fn native_response_tolerates_null_usage_and_missing_choices() {
+        let resp: NativeChatResponse = serde_json::from_str(r#"{"usage": null}"#).unwrap();
+        assert!(resp.usage.is_none());
+        assert!(resp.choices.is_empty());
+        let resp: ChatResponse = serde_json::from_str("{}").unwrap();
+        assert!(resp.choices.is_empty());
+    }
 }
@@ -1,7 +1,7 @@
 use crate::auth::openai_oauth::extract_account_id_from_jwt;
 use crate::auth::AuthService;
 use crate::multimodal;
-use crate::providers::traits::{ChatMessage, Provider, ProviderCapabilities};
+use crate::providers::traits::{ChatMessage, Provider, ProviderCapabilities, Role};
 use crate::providers::ProviderRuntimeOptions;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -212,9 +212,9 @@ fn build_responses_input(messages: &[ChatMessage]) -> (String, Vec<ResponsesInpu
     let mut input: Vec<ResponsesInput> = Vec::new();
 
     for msg in messages {
-        match msg.role.as_str() {
-            "system" => system_parts.push(&msg.content),
-            "user" => {
+        match msg.role() {
+            Role::System => system_parts.push(&msg.content),
+            Role::User => {
                 let (cleaned_text, image_refs) = multimodal::parse_image_markers(&msg.content);
 
                 let mut content_items = Vec::new();
@@ -251,7 +251,7 @@ fn build_responses_input(messages: &[ChatMessage]) -> (String, Vec<ResponsesInpu
                     content: content_items,
                 });
             }
-            "assistant" => {
+            Role::Assistant => {
                 input.push(ResponsesInput {
                     role: "assistant".to_string(),
                     content: vec![ResponsesInputContent {
@@ -261,7 +261,10 @@ fn build_responses_input(messages: &[ChatMessage]) -> (String, Vec<ResponsesInpu
                     }],
                 });
             }
-            _ => {}
+            Role::Tool => {}
+            Role::Unknown(role) => {
+                tracing::warn!("OpenAI Codex: skipping message with unknown role {role:?}");
+            }
         }
     }
 
@@ -1018,6 +1021,8 @@ data: [DONE]
             secrets_encrypt: false,
             auth_profile_override: None,
             reasoning_enabled: None,
+            openrouter: crate::config::OpenRouterConfig::default(),
+            response_cache: None,
         };
         let provider =
             OpenAiCodexProvider::new(&options, None).expect("provider should initialize");
@@ -1,22 +1,116 @@
+use crate::config::OpenRouterConfig;
 use crate::multimodal;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ProviderCapabilities, TokenUsage, ToolCall as ProviderToolCall,
+    Provider, ProviderCapabilities, Role, TokenUsage, ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, StatusCode};
 use serde::{Deserialize, Serialize};
 
+const CHAT_COMPLETIONS_URL: &str = "https://openrouter.ai/api/v1/chat/completions";
+
 pub struct OpenRouterProvider {
     credential: Option<String>,
+    config: OpenRouterConfig,
+}
+
+/// OpenRouter failures that carry a meaning beyond the HTTP status.
+///
+/// `ReliableProvider` downcasts to this type: insufficient credits and unknown
+/// models fail immediately instead of burning the retry budget.
+#[derive(Debug, thiserror::Error)]
+pub enum OpenRouterError {
+    #[error("OpenRouter API error (402 insufficient credits): {message}")]
+    InsufficientCredits { message: String },
+    #[error("OpenRouter API error (model not found): {message}")]
+    ModelNotFound { message: String },
+    #[error("OpenRouter API error ({status}): {message}")]
+    Api { status: StatusCode, message: String },
+}
+
+impl OpenRouterError {
+    /// Only rate limits, timeouts and upstream/server failures can succeed on retry.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::InsufficientCredits { .. } | Self::ModelNotFound { .. } => false,
+            Self::Api { status, .. } => {
+                status.is_server_error()
+                    || *status == StatusCode::TOO_MANY_REQUESTS
+                    || *status == StatusCode::REQUEST_TIMEOUT
+            }
+        }
+    }
+
+    /// Classify an error response. OpenRouter reports failures as
+    /// `{"error": {"code": ..., "message": ...}}`, sometimes with HTTP 200.
+    fn from_response(status: StatusCode, body: &str) -> Self {
+        let error = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|value| value.get("error").cloned());
+        let code = error
+            .as_ref()
+            .and_then(|e| e.get("code"))
+            .and_then(|code| {
+                code.as_u64()
+                    .or_else(|| code.as_str().and_then(|c| c.parse().ok()))
+            })
+            .and_then(|code| u16::try_from(code).ok())
+            .and_then(|code| StatusCode::from_u16(code).ok());
+        let status = match code {
+            Some(code) if status.is_success() || code != status => code,
+            _ => status,
+        };
+        let message = super::sanitize_api_error(
+            error
+                .as_ref()
+                .and_then(|e| e.get("message"))
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or(body),
+        );
+
+        let lower = message.to_lowercase();
+        if status == StatusCode::PAYMENT_REQUIRED || lower.contains("insufficient credits") {
+            return Self::InsufficientCredits { message };
+        }
+        let model_missing = lower.contains("no endpoints found")
+            || lower.contains("not a valid model")
+            || (lower.contains("model") && lower.contains("not found"));
+        if (status == StatusCode::NOT_FOUND || status == StatusCode::BAD_REQUEST) && model_missing {
+            return Self::ModelNotFound { message };
+        }
+        Self::Api { status, message }
+    }
+}
+
+/// OpenRouter routing fields shared by every chat request.
+#[derive(Debug, Default, Serialize)]
+struct Routing {
+    /// Primary model followed by the configured fallbacks.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    models: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<ProviderPreferences>,
+}
+
+#[derive(Debug, Serialize)]
+struct ProviderPreferences {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    order: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allow_fallbacks: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
 struct ChatRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
     model: String,
     messages: Vec<Message>,
     temperature: f64,
+    #[serde(flatten)]
+    routing: Routing,
 }
 
 #[derive(Debug, Serialize)]
@@ -44,23 +138,10 @@ struct ImageUrlPart {
     url: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct ApiChatResponse {
-    choices: Vec<Choice>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Choice {
-    message: ResponseMessage,
-}
-
-#[derive(Debug, Deserialize)]
-struct ResponseMessage {
-    content: String,
-}
-
 #[derive(Debug, Serialize)]
 struct NativeChatRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
     model: String,
     messages: Vec<NativeMessage>,
     temperature: f64,
@@ -68,6 +149,8 @@ struct NativeChatRequest {
     tools: Option<Vec<NativeToolSpec>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<String>,
+    #[serde(flatten)]
+    routing: Routing,
 }
 
 #[derive(Debug, Serialize)]
@@ -114,19 +197,46 @@ struct NativeFunctionCall {
     arguments: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct NativeChatResponse {
+    #[serde(default)]
     choices: Vec<NativeChoice>,
     #[serde(default)]
     usage: Option<UsageInfo>,
 }
 
+/// Token usage as reported by OpenRouter. Depending on the upstream provider
+/// only some fields are present, and `input_tokens`/`output_tokens` naming is
+/// passed through for some Anthropic routes.
 #[derive(Debug, Deserialize)]
 struct UsageInfo {
-    #[serde(default)]
+    #[serde(default, alias = "input_tokens")]
     prompt_tokens: Option<u64>,
-    #[serde(default)]
+    #[serde(default, alias = "output_tokens")]
     completion_tokens: Option<u64>,
+    #[serde(default)]
+    total_tokens: Option<u64>,
+}
+
+impl UsageInfo {
+    /// Fill a missing side from `total_tokens`; `None` when nothing was reported.
+    fn normalize(&self) -> Option<TokenUsage> {
+        let derive = |known: Option<u64>| {
+            self.total_tokens
+                .zip(known)
+                .map(|(total, known)| total.saturating_sub(known))
+        };
+        let input_tokens = self
+            .prompt_tokens
+            .or_else(|| derive(self.completion_tokens));
+        let output_tokens = self
+            .completion_tokens
+            .or_else(|| derive(self.prompt_tokens));
+        (input_tokens.is_some() || output_tokens.is_some()).then_some(TokenUsage {
+            input_tokens,
+            output_tokens,
+        })
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -134,7 +244,7 @@ struct NativeChoice {
     message: NativeResponseMessage,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct NativeResponseMessage {
     #[serde(default)]
     content: Option<String>,
@@ -147,9 +257,173 @@ struct NativeResponseMessage {
 
 impl OpenRouterProvider {
     pub fn new(credential: Option<&str>) -> Self {
+        Self::with_config(credential, OpenRouterConfig::default())
+    }
+
+    pub fn with_config(credential: Option<&str>, config: OpenRouterConfig) -> Self {
         Self {
             credential: credential.map(ToString::to_string),
+            config,
+        }
+    }
+
+    /// Accept `openrouter/<vendor>/<model>` as written in some configs;
+    /// OpenRouter's own models such as `openrouter/auto` are left alone.
+    fn normalize_model(model: &str) -> &str {
+        model
+            .strip_prefix("openrouter/")
+            .filter(|rest| rest.contains('/'))
+            .unwrap_or(model)
+    }
+
+    fn routing(&self, model: &str) -> Routing {
+        let models = if self.config.fallback_models.is_empty() {
+            Vec::new()
+        } else {
+            let mut models = vec![model.to_string()];
+            for fallback in &self.config.fallback_models {
+                let fallback = Self::normalize_model(fallback.trim());
+                if !fallback.is_empty() && !models.iter().any(|m| m == fallback) {
+                    models.push(fallback.to_string());
+                }
+            }
+            models
+        };
+        let provider = (!self.config.provider_order.is_empty()
+            || self.config.allow_fallbacks.is_some())
+        .then(|| ProviderPreferences {
+            order: self.config.provider_order.clone(),
+            allow_fallbacks: self.config.allow_fallbacks,
+        });
+        Routing { models, provider }
+    }
+
+    fn chat_completions(&self, credential: &str) -> RequestBuilder {
+        let mut request = self
+            .http_client()
+            .post(CHAT_COMPLETIONS_URL)
+            .header("Authorization", format!("Bearer {credential}"));
+        if !self.config.http_referer.trim().is_empty() {
+            request = request.header("HTTP-Referer", self.config.http_referer.trim());
+        }
+        if !self.config.x_title.trim().is_empty() {
+            request = request.header("X-Title", self.config.x_title.trim());
         }
+        request
+    }
+
+    async fn complete<T: Serialize>(
+        &self,
+        credential: &str,
+        body: &T,
+    ) -> anyhow::Result<NativeChatResponse> {
+        let response = self.chat_completions(credential).json(body).send().await?;
+        let status = response.status();
+        let text = response.text().await?;
+        if !status.is_success() {
+            return Err(OpenRouterError::from_response(status, &text).into());
+        }
+        Self::decode_completion(&text)
+    }
+
+    /// Decode a completion body. Besides plain JSON this accepts an SSE
+    /// transcript, where usage often arrives only in the trailing chunk, and
+    /// an `error` object returned with HTTP 200.
+    fn decode_completion(body: &str) -> anyhow::Result<NativeChatResponse> {
+        let trimmed = body.trim();
+        if !trimmed.starts_with('{') {
+            return Self::decode_sse(trimmed);
+        }
+        let value: serde_json::Value = serde_json::from_str(trimmed)?;
+        if value.get("error").is_some() && value.get("choices").is_none() {
+            return Err(OpenRouterError::from_response(StatusCode::OK, trimmed).into());
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    fn decode_sse(body: &str) -> anyhow::Result<NativeChatResponse> {
+        let mut message = NativeResponseMessage::default();
+        let mut tool_calls: Vec<NativeToolCall> = Vec::new();
+        let mut usage = None;
+        let mut saw_chunk = false;
+
+        for line in body.lines() {
+            // `:` lines are OpenRouter keep-alive comments.
+            let Some(data) = line.trim().strip_prefix("data:").map(str::trim) else {
+                continue;
+            };
+            if data.is_empty() || data == "[DONE]" {
+                continue;
+            }
+            let chunk: serde_json::Value = serde_json::from_str(data)?;
+            if chunk.get("error").is_some() {
+                return Err(OpenRouterError::from_response(StatusCode::OK, data).into());
+            }
+            saw_chunk = true;
+            if let Some(chunk_usage) = chunk.get("usage").filter(|u| !u.is_null()) {
+                usage = Some(serde_json::from_value::<UsageInfo>(chunk_usage.clone())?);
+            }
+            let Some(delta) = chunk.pointer("/choices/0/delta") else {
+                continue;
+            };
+            for (field, target) in [
+                ("content", &mut message.content),
+                ("reasoning_content", &mut message.reasoning_content),
+            ] {
+                if let Some(text) = delta.get(field).and_then(serde_json::Value::as_str) {
+                    target.get_or_insert_with(String::new).push_str(text);
+                }
+            }
+            for call in delta
+                .get("tool_calls")
+                .and_then(serde_json::Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                let index = call
+                    .get("index")
+                    .and_then(serde_json::Value::as_u64)
+                    .and_then(|i| usize::try_from(i).ok())
+                    .unwrap_or(tool_calls.len());
+                while tool_calls.len() <= index {
+                    tool_calls.push(NativeToolCall {
+                        id: None,
+                        kind: Some("function".to_string()),
+                        function: NativeFunctionCall {
+                            name: String::new(),
+                            arguments: String::new(),
+                        },
+                    });
+                }
+                let entry = &mut tool_calls[index];
+                if let Some(id) = call.get("id").and_then(serde_json::Value::as_str) {
+                    entry.id = Some(id.to_string());
+                }
+                if let Some(name) = call
+                    .pointer("/function/name")
+                    .and_then(serde_json::Value::as_str)
+                {
+                    entry.function.name.push_str(name);
+                }
+                if let Some(arguments) = call
+                    .pointer("/function/arguments")
+                    .and_then(serde_json::Value::as_str)
+                {
+                    entry.function.arguments.push_str(arguments);
+                }
+            }
+        }
+
+        if !saw_chunk {
+            anyhow::bail!("Unrecognized response body from OpenRouter");
+        }
+        if !tool_calls.is_empty() {
+            message.tool_calls = Some(tool_calls);
+        }
+        Ok(NativeChatResponse {
+            choices: vec![NativeChoice { message }],
+            usage,
+        })
     }
 
     fn convert_tools(tools: Option<&[ToolSpec]>) -> Option<Vec<NativeToolSpec>> {
@@ -176,7 +450,7 @@ impl OpenRouterProvider {
         messages
             .iter()
             .map(|m| {
-                if m.role == "assistant" {
+                if m.role() == Role::Assistant {
                     if let Ok(value) = serde_json::from_str::<serde_json::Value>(&m.content) {
                         if let Some(tool_calls_value) = value.get("tool_calls") {
                             if let Ok(parsed_calls) =
@@ -215,7 +489,7 @@ impl OpenRouterProvider {
                     }
                 }
 
-                if m.role == "tool" {
+                if m.role() == Role::Tool {
                     if let Ok(value) = serde_json::from_str::<serde_json::Value>(&m.content) {
                         let tool_call_id = value
                             .get("tool_call_id")
@@ -347,36 +621,22 @@ impl Provider for OpenRouterProvider {
             content: Self::to_message_content("user", message),
         });
 
+        let model = Self::normalize_model(model);
         let request = ChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages,
             temperature,
+            routing: self.routing(model),
         };
 
-        let response = self
-            .http_client()
-            .post("https://openrouter.ai/api/v1/chat/completions")
-            .header("Authorization", format!("Bearer {credential}"))
-            .header(
-                "HTTP-Referer",
-                "https://github.com/theonlyhennygod/zeroclaw",
-            )
-            .header("X-Title", "ZeroClaw")
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(super::api_error("OpenRouter", response).await);
-        }
-
-        let chat_response: ApiChatResponse = response.json().await?;
+        let chat_response = self.complete(credential, &request).await?;
 
         chat_response
             .choices
             .into_iter()
             .next()
-            .map(|c| c.message.content)
+            .and_then(|c| c.message.content)
             .ok_or_else(|| anyhow::anyhow!("No response from OpenRouter"))
     }
 
@@ -397,36 +657,22 @@ impl Provider for OpenRouterProvider {
             })
             .collect();
 
+        let model = Self::normalize_model(model);
         let request = ChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages: api_messages,
             temperature,
+            routing: self.routing(model),
         };
 
-        let response = self
-            .http_client()
-            .post("https://openrouter.ai/api/v1/chat/completions")
-            .header("Authorization", format!("Bearer {credential}"))
-            .header(
-                "HTTP-Referer",
-                "https://github.com/theonlyhennygod/zeroclaw",
-            )
-            .header("X-Title", "ZeroClaw")
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(super::api_error("OpenRouter", response).await);
-        }
-
-        let chat_response: ApiChatResponse = response.json().await?;
+        let chat_response = self.complete(credential, &request).await?;
 
         chat_response
             .choices
             .into_iter()
             .next()
-            .map(|c| c.message.content)
+            .and_then(|c| c.message.content)
             .ok_or_else(|| anyhow::anyhow!("No response from OpenRouter"))
     }
 
@@ -442,37 +688,23 @@ impl Provider for OpenRouterProvider {
         )
         })?;
 
+        let model = Self::normalize_model(model);
         let tools = Self::convert_tools(request.tools);
         let native_request = NativeChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages: Self::convert_messages(request.messages),
             temperature,
             tool_choice: tools.as_ref().map(|_| "auto".to_string()),
             tools,
+            routing: self.routing(model),
         };
 
-        let response = self
-            .http_client()
-            .post("https://openrouter.ai/api/v1/chat/completions")
-            .header("Authorization", format!("Bearer {credential}"))
-            .header(
-                "HTTP-Referer",
-                "https://github.com/theonlyhennygod/zeroclaw",
-            )
-            .header("X-Title", "ZeroClaw")
-            .json(&native_request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(super::api_error("OpenRouter", response).await);
-        }
-
-        let native_response: NativeChatResponse = response.json().await?;
-        let usage = native_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
-        });
+        let native_response = self.complete(credential, &native_request).await?;
+        let usage = native_response
+            .usage
+            .as_ref()
+            .and_then(UsageInfo::normalize);
         let message = native_response
             .choices
             .into_iter()
@@ -537,36 +769,22 @@ impl Provider for OpenRouterProvider {
         // when history contains native tool-call metadata.
         let native_messages = Self::convert_messages(messages);
 
+        let model = Self::normalize_model(model);
         let native_request = NativeChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages: native_messages,
             temperature,
             tool_choice: native_tools.as_ref().map(|_| "auto".to_string()),
             tools: native_tools,
+            routing: self.routing(model),
         };
 
-        let response = self
-            .http_client()
-            .post("https://openrouter.ai/api/v1/chat/completions")
-            .header("Authorization", format!("Bearer {credential}"))
-            .header(
-                "HTTP-Referer",
-                "https://github.com/theonlyhennygod/zeroclaw",
-            )
-            .header("X-Title", "ZeroClaw")
-            .json(&native_request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            return Err(super::api_error("OpenRouter", response).await);
-        }
-
-        let native_response: NativeChatResponse = response.json().await?;
-        let usage = native_response.usage.map(|u| TokenUsage {
-            input_tokens: u.prompt_tokens,
-            output_tokens: u.completion_tokens,
-        });
+        let native_response = self.complete(credential, &native_request).await?;
+        let usage = native_response
+            .usage
+            .as_ref()
+            .and_then(UsageInfo::normalize);
         let message = native_response
             .choices
             .into_iter()
@@ -650,6 +868,7 @@ mod tests {
     #[test]
     fn chat_request_serializes_with_system_and_user() {
         let request = ChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: "anthropic/claude-sonnet-4".into(),
             messages: vec![
                 Message {
@@ -662,6 +881,7 @@ mod tests {
                 },
             ],
             temperature: 0.5,
+            routing: Routing::default(),
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -686,6 +906,7 @@ mod tests {
         ];
 
         let request = ChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: "google/gemini-2.5-pro".into(),
             messages: messages
                 .iter()
@@ -695,6 +916,7 @@ mod tests {
                 })
                 .collect(),
             temperature: 0.0,
+            routing: Routing::default(),
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -707,17 +929,20 @@ mod tests {
     fn response_deserializes_single_choice() {
         let json = r#"{"choices":[{"message":{"content":"Hi from OpenRouter"}}]}"#;
 
-        let response: ApiChatResponse = serde_json::from_str(json).unwrap();
+        let response: NativeChatResponse = serde_json::from_str(json).unwrap();
 
         assert_eq!(response.choices.len(), 1);
-        assert_eq!(response.choices[0].message.content, "Hi from OpenRouter");
+        assert_eq!(
+            response.choices[0].message.content.as_deref(),
+            Some("Hi from OpenRouter")
+        );
     }
 
     #[test]
     fn response_deserializes_empty_choices() {
         let json = r#"{"choices":[]}"#;
 
-        let response: ApiChatResponse = serde_json::from_str(json).unwrap();
+        let response: NativeChatResponse = serde_json::from_str(json).unwrap();
 
         assert!(response.choices.is_empty());
     }
@@ -1026,4 +1251,218 @@ mod tests {
         assert!(json.contains("reasoning_content"));
         assert!(json.contains("thinking..."));
     }
+
+    // ═══════════════════════════════════════════════════════════════════════
+    // OpenRouter headers, routing, errors and usage
+    // ═══════════════════════════════════════════════════════════════════════
+
+    fn header<'a>(request: &'a reqwest::Request, name: &str) -> Option<&'a str> {
+        request.headers().get(name).and_then(|v| v.to_str().ok())
+    }
+
+    #[test]
+    fn chat_completions_sets_attribution_headers_from_config() {
+        let provider = OpenRouterProvider::with_config(
+            Some("key"),
+            OpenRouterConfig {
+                http_referer: "https://example.com/bot".into(),
+                x_title: "Example Bot".into(),
+                ..OpenRouterConfig::default()
+            },
+        );
+        let request = provider.chat_completions("key").build().unwrap();
+        assert_eq!(request.url().as_str(), CHAT_COMPLETIONS_URL);
+        assert_eq!(header(&request, "authorization"), Some("Bearer key"));
+        assert_eq!(
+            header(&request, "http-referer"),
+            Some("https://example.com/bot")
+        );
+        assert_eq!(header(&request, "x-title"), Some("Example Bot"));
+
+        let defaults = OpenRouterProvider::new(Some("key"))
+            .chat_completions("key")
+            .build()
+            .unwrap();
+        assert_eq!(header(&defaults, "x-title"), Some("ZeroClaw"));
+
+        let blank = OpenRouterProvider::with_config(
+            Some("key"),
+            OpenRouterConfig {
+                x_title: " ".into(),
+                ..OpenRouterConfig::default()
+            },
+        )
+        .chat_completions("key")
+        .build()
+        .unwrap();
+        assert!(header(&blank, "x-title").is_none());
+    }
+
+    #[test]
+    fn routing_sends_fallback_models_and_provider_preferences() {
+        let provider = OpenRouterProvider::with_config(
+            Some("key"),
+            OpenRouterConfig {
+                fallback_models: vec![
+                    "openrouter/openai/gpt-4o".into(),
+                    "anthropic/claude-sonnet-4".into(),
+                ],
+                provider_order: vec!["anthropic".into()],
+                allow_fallbacks: Some(false),
+                ..OpenRouterConfig::default()
+            },
+        );
+        let request = ChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
+            model: "anthropic/claude-sonnet-4".into(),
+            messages: Vec::new(),
+            temperature: 0.2,
+            routing: provider.routing("anthropic/claude-sonnet-4"),
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            json["models"],
+            serde_json::json!(["anthropic/claude-sonnet-4", "openai/gpt-4o"])
+        );
+        assert_eq!(
+            json["provider"],
+            serde_json::json!({ "order": ["anthropic"], "allow_fallbacks": false })
+        );
+
+        let plain = serde_json::to_value(ChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
+            model: "openai/gpt-4o".into(),
+            messages: Vec::new(),
+            temperature: 0.2,
+            routing: OpenRouterProvider::new(None).routing("openai/gpt-4o"),
+        })
+        .unwrap();
+        assert!(plain.get("models").is_none());
+        assert!(plain.get("provider").is_none());
+    }
+
+    #[test]
+    fn normalize_model_strips_routing_prefix_only_for_vendor_models() {
+        assert_eq!(
+            OpenRouterProvider::normalize_model("openrouter/anthropic/claude-sonnet-4"),
+            "anthropic/claude-sonnet-4"
+        );
+        assert_eq!(
+            OpenRouterProvider::normalize_model("openrouter/auto"),
+            "openrouter/auto"
+        );
+        assert_eq!(
+            OpenRouterProvider::normalize_model("openai/gpt-4o"),
+            "openai/gpt-4o"
+        );
+    }
+
+    #[test]
+    fn error_mapping_classifies_credits_and_missing_models() {
+        let credits = OpenRouterError::from_response(
+            StatusCode::PAYMENT_REQUIRED,
+            r#"{"error":{"code":402,"message":"Insufficient credits. Add more using https://openrouter.ai/credits"}}"#,
+        );
+        assert!(matches!(
+            credits,
+            OpenRouterError::InsufficientCredits { .. }
+        ));
+        assert!(!credits.is_retryable());
+
+        let missing = OpenRouterError::from_response(
+            StatusCode::NOT_FOUND,
+            r#"{"error":{"code":404,"message":"No endpoints found for foo/bar."}}"#,
+        );
+        assert!(matches!(missing, OpenRouterError::ModelNotFound { .. }));
+        assert!(!missing.is_retryable());
+
+        let invalid = OpenRouterError::from_response(
+            StatusCode::BAD_REQUEST,
+            r#"{"error":{"code":400,"message":"foo/bar is not a valid model ID"}}"#,
+        );
+        assert!(matches!(invalid, OpenRouterError::ModelNotFound { .. }));
+
+        // Errors delivered with HTTP 200 use the code from the body.
+        let in_body = OpenRouterError::from_response(
+            StatusCode::OK,
+            r#"{"error":{"code":502,"message":"upstream provider error"}}"#,
+        );
+        assert!(matches!(
+            in_body,
+            OpenRouterError::Api {
+                status: StatusCode::BAD_GATEWAY,
+                ..
+            }
+        ));
+        assert!(in_body.is_retryable());
+
+        let limited = OpenRouterError::from_response(StatusCode::TOO_MANY_REQUESTS, "slow down");
+        assert!(limited.is_retryable());
+        assert!(limited.to_string().contains("429 Too Many Requests"));
+    }
+
+    #[test]
+    fn decode_completion_maps_error_bodies_returned_with_200() {
+        let err = OpenRouterProvider::decode_completion(
+            r#"{"error":{"code":402,"message":"Insufficient credits"}}"#,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<OpenRouterError>(),
+            Some(OpenRouterError::InsufficientCredits { .. })
+        ));
+    }
+
+    #[test]
+    fn usage_normalizes_partial_and_aliased_fields() {
+        let full: UsageInfo = serde_json::from_str(
+            r#"{"prompt_tokens":42,"completion_tokens":15,"total_tokens":57,"cost":0.0012}"#,
+        )
+        .unwrap();
+        let usage = full.normalize().unwrap();
+        assert_eq!(usage.input_tokens, Some(42));
+        assert_eq!(usage.output_tokens, Some(15));
+
+        let derived: UsageInfo =
+            serde_json::from_str(r#"{"completion_tokens":15,"total_tokens":57}"#).unwrap();
+        assert_eq!(derived.normalize().unwrap().input_tokens, Some(42));
+
+        let aliased: UsageInfo =
+            serde_json::from_str(r#"{"input_tokens":7,"output_tokens":3}"#).unwrap();
+        let usage = aliased.normalize().unwrap();
+        assert_eq!(usage.input_tokens, Some(7));
+        assert_eq!(usage.output_tokens, Some(3));
+
+        let empty: UsageInfo = serde_json::from_str("{}").unwrap();
+        assert!(empty.normalize().is_none());
+    }
+
+    #[test]
+    fn decode_completion_reads_usage_from_trailing_sse_chunk() {
+        let body = concat!(
+            ": OPENROUTER PROCESSING\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_1\",\"function\":{\"name\":\"shell\",\"arguments\":\"{\\\"command\\\":\"}}]}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"\\\"ls\\\"}\"}}]}}]}\n\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":12,\"completion_tokens\":4,\"total_tokens\":16}}\n\n",
+            "data: [DONE]\n",
+        );
+        let response = OpenRouterProvider::decode_completion(body).unwrap();
+        let usage = response
+            .usage
+            .as_ref()
+            .and_then(UsageInfo::normalize)
+            .unwrap();
+        assert_eq!(usage.input_tokens, Some(12));
+        assert_eq!(usage.output_tokens, Some(4));
+
+        let parsed = OpenRouterProvider::parse_native_response(
+            response.choices.into_iter().next().unwrap().message,
+        );
+        assert_eq!(parsed.text.as_deref(), Some("Hello"));
+        assert_eq!(parsed.tool_calls.len(), 1);
+        assert_eq!(parsed.tool_calls[0].id, "call_1");
+        assert_eq!(parsed.tool_calls[0].arguments, r#"{"command":"ls"}"#);
+    }
 }
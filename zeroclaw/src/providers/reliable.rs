@@ -1,7 +1,7 @@
 use super::traits::{
     ChatMessage, ChatRequest, ChatResponse, StreamChunk, StreamOptions, StreamResult,
 };
-use super::Provider;
+use super::{Provider, ProviderError};
 use async_trait::async_trait;
 use futures_util::{stream, StreamExt};
 use std::collections::HashMap;
@@ -16,10 +16,19 @@ use std::time::Duration;
 
 /// Check if an error is non-retryable (client errors that won't resolve with retries).
 fn is_non_retryable(err: &anyhow::Error) -> bool {
+    match err.downcast_ref::<ProviderError>() {
+        Some(ProviderError::ContextTooLong { .. } | ProviderError::Config { .. }) => return true,
+        Some(ProviderError::Timeout { .. }) => return false,
+        None => {}
+    }
     if is_context_window_exceeded(err) {
         return true;
     }
 
+    if let Some(openrouter_err) = err.downcast_ref::<super::openrouter::OpenRouterError>() {
+        return !openrouter_err.is_retryable();
+    }
+
     // 4xx errors are generally non-retryable (bad request, auth failure, etc.),
     // except 429 (rate-limit — transient) and 408 (timeout — worth retrying).
     if let Some(reqwest_err) = err.downcast_ref::<reqwest::Error>() {
@@ -71,7 +80,16 @@ fn is_non_retryable(err: &anyhow::Error) -> bool {
             || msg_lower.contains("invalid"))
 }
 
-fn is_context_window_exceeded(err: &anyhow::Error) -> bool {
+/// Whether `err` says the request did not fit the model's context window.
+/// Typed [`ProviderError::ContextTooLong`] errors are matched directly;
+/// other providers are recognized by their error wording.
+pub fn is_context_window_exceeded(err: &anyhow::Error) -> bool {
+    if matches!(
+        err.downcast_ref::<ProviderError>(),
+        Some(ProviderError::ContextTooLong { .. })
+    ) {
+        return true;
+    }
     let lower = err.to_string().to_lowercase();
     let hints = [
         "exceeds the context window",
@@ -231,6 +249,8 @@ pub struct ReliableProvider {
     key_index: AtomicUsize,
     /// Per-model fallback chains: model_name → [fallback_model_1, fallback_model_2, ...]
     model_fallbacks: HashMap<String, Vec<String>>,
+    /// Bound on one provider attempt; `None` waits for the provider.
+    request_timeout: Option<Duration>,
 }
 
 impl ReliableProvider {
@@ -246,6 +266,7 @@ impl ReliableProvider {
             api_keys: Vec::new(),
             key_index: AtomicUsize::new(0),
             model_fallbacks: HashMap::new(),
+            request_timeout: None,
         }
     }
 
@@ -261,6 +282,30 @@ impl ReliableProvider {
         self
     }
 
+    /// Abandon a provider attempt after `secs` seconds with a retryable
+    /// [`ProviderError::Timeout`]; `0` disables the bound.
+    pub fn with_request_timeout(mut self, secs: u64) -> Self {
+        self.request_timeout = (secs > 0).then(|| Duration::from_secs(secs));
+        self
+    }
+
+    /// Run one provider attempt within the request timeout.
+    async fn attempt<T>(
+        &self,
+        call: impl std::future::Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        let Some(limit) = self.request_timeout else {
+            return call.await;
+        };
+        match tokio::time::timeout(limit, call).await {
+            Ok(result) => result,
+            Err(_) => Err(ProviderError::Timeout {
+                secs: limit.as_secs(),
+            }
+            .into()),
+        }
+    }
+
     /// Build the list of models to try: [original, fallback1, fallback2, ...]
     fn model_chain<'a>(&'a self, model: &'a str) -> Vec<&'a str> {
         let mut chain = vec![model];
@@ -321,8 +366,13 @@ impl Provider for ReliableProvider {
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
-                    match provider
-                        .chat_with_system(system_prompt, message, current_model, temperature)
+                    match self
+                        .attempt(provider.chat_with_system(
+                            system_prompt,
+                            message,
+                            current_model,
+                            temperature,
+                        ))
                         .await
                     {
                         Ok(resp) => {
@@ -378,10 +428,13 @@ impl Provider for ReliableProvider {
                                 );
 
                                 if is_context_window_exceeded(&e) {
-                                    anyhow::bail!(
-                                        "Request exceeds model context window; retries and fallbacks were skipped. Attempts:\n{}",
-                                        failures.join("\n")
-                                    );
+                                    return Err(ProviderError::ContextTooLong {
+                                        message: format!(
+                                            "Request exceeds model context window; retries and fallbacks were skipped. Attempts:\n{}",
+                                            failures.join("\n")
+                                        ),
+                                    }
+                                    .into());
                                 }
 
                                 break;
@@ -441,8 +494,8 @@ impl Provider for ReliableProvider {
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
-                    match provider
-                        .chat_with_history(messages, current_model, temperature)
+                    match self
+                        .attempt(provider.chat_with_history(messages, current_model, temperature))
                         .await
                     {
                         Ok(resp) => {
@@ -496,10 +549,13 @@ impl Provider for ReliableProvider {
                                 );
 
                                 if is_context_window_exceeded(&e) {
-                                    anyhow::bail!(
-                                        "Request exceeds model context window; retries and fallbacks were skipped. Attempts:\n{}",
-                                        failures.join("\n")
-                                    );
+                                    return Err(ProviderError::ContextTooLong {
+                                        message: format!(
+                                            "Request exceeds model context window; retries and fallbacks were skipped. Attempts:\n{}",
+                                            failures.join("\n")
+                                        ),
+                                    }
+                                    .into());
                                 }
 
                                 break;
@@ -565,8 +621,13 @@ impl Provider for ReliableProvider {
                 let mut backoff_ms = self.base_backoff_ms;
 
                 for attempt in 0..=self.max_retries {
-                    match provider
-                        .chat_with_tools(messages, tools, current_model, temperature)
+                    match self
+                        .attempt(provider.chat_with_tools(
+                            messages,
+                            tools,
+                            current_model,
+                            temperature,
+                        ))
                         .await
                     {
                         Ok(resp) => {
@@ -620,10 +681,13 @@ impl Provider for ReliableProvider {
                                 );
 
                                 if is_context_window_exceeded(&e) {
-                                    anyhow::bail!(
-                                        "Request exceeds model context window; retries and fallbacks were skipped. Attempts:\n{}",
-                                        failures.join("\n")
-                                    );
+                                    return Err(ProviderError::ContextTooLong {
+                                        message: format!(
+                                            "Request exceeds model context window; retries and fallbacks were skipped. Attempts:\n{}",
+                                            failures.join("\n")
+                                        ),
+                                    }
+                                    .into());
                                 }
 
                                 break;
@@ -679,7 +743,10 @@ impl Provider for ReliableProvider {
                         messages: request.messages,
                         tools: request.tools,
                     };
-                    match provider.chat(req, current_model, temperature).await {
+                    match self
+                        .attempt(provider.chat(req, current_model, temperature))
+                        .await
+                    {
                         Ok(resp) => {
                             if attempt > 0 || *current_model != model {
                                 tracing::info!(
@@ -731,10 +798,13 @@ impl Provider for ReliableProvider {
                                 );
 
                                 if is_context_window_exceeded(&e) {
-                                    anyhow::bail!(
-                                        "Request exceeds model context window; retries and fallbacks were skipped. Attempts:\n{}",
-                                        failures.join("\n")
-                                    );
+                                    return Err(ProviderError::ContextTooLong {
+                                        message: format!(
+                                            "Request exceeds model context window; retries and fallbacks were skipped. Attempts:\n{}",
+                                            failures.join("\n")
+                                        ),
+                                    }
+                                    .into());
                                 }
 
                                 break;
@@ -896,6 +966,52 @@ mod tests {
         }
     }
 
+    /// Mock whose first call hangs well past any request timeout.
+    struct HangsOnceMock {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Provider for HangsOnceMock {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                tokio::time::sleep(Duration::from_secs(300)).await;
+            }
+            Ok("answered".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn hung_request_times_out_and_is_retried() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = ReliableProvider::new(
+            vec![(
+                "slow".into(),
+                Box::new(HangsOnceMock {
+                    calls: Arc::clone(&calls),
+                }) as Box<dyn Provider>,
+            )],
+            1,
+            1,
+        )
+        .with_request_timeout(1);
+
+        let started = std::time::Instant::now();
+        let result = provider.simple_chat("hello", "test", 0.0).await.unwrap();
+        assert_eq!(result, "answered");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        let timeout: anyhow::Error = ProviderError::Timeout { secs: 1 }.into();
+        assert!(!is_non_retryable(&timeout));
+    }
+
     /// Mock that records which model was used for each call.
     struct ModelAwareMock {
         calls: Arc<AtomicUsize>,
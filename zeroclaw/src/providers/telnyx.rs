@@ -138,6 +138,8 @@ struct ModelInfo {
 /// Request body for chat completions
 #[derive(Debug, serde::Serialize)]
 struct ChatRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
     model: String,
     messages: Vec<Message>,
     temperature: f64,
@@ -195,6 +197,7 @@ impl Provider for TelnyxProvider {
         });
 
         let request = ChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages,
             temperature,
@@ -247,6 +250,7 @@ impl Provider for TelnyxProvider {
             .collect();
 
         let request = ChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: model.to_string(),
             messages: api_messages,
             temperature,
@@ -362,6 +366,7 @@ mod tests {
     #[test]
     fn chat_request_serializes() {
         let req = ChatRequest {
+            max_tokens: crate::providers::max_output_tokens(),
             model: "openai/gpt-4o".to_string(),
             messages: vec![
                 Message {
@@ -4,40 +4,105 @@ use futures_util::{stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::fmt::Write;
 
+/// Author of a [`ChatMessage`].
+///
+/// The canonical form is the lowercase name (`system`, `user`, `assistant`,
+/// `tool`); that is what `ChatMessage::role` carries and what the session
+/// store persists. Any other string parses to [`Role::Unknown`] so callers
+/// have to decide what to do with it instead of treating it as a user turn.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+    Tool,
+    Unknown(String),
+}
+
+impl Role {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::System => "system",
+            Self::User => "user",
+            Self::Assistant => "assistant",
+            Self::Tool => "tool",
+            Self::Unknown(role) => role,
+        }
+    }
+}
+
+impl From<&str> for Role {
+    fn from(role: &str) -> Self {
+        match role.trim().to_ascii_lowercase().as_str() {
+            "system" => Self::System,
+            "user" => Self::User,
+            "assistant" => Self::Assistant,
+            "tool" => Self::Tool,
+            _ => Self::Unknown(role.to_string()),
+        }
+    }
+}
+
+impl std::str::FromStr for Role {
+    type Err = std::convert::Infallible;
+
+    fn from_str(role: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(role))
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Role {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Role {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|role| Self::from(role.as_str()))
+    }
+}
+
 /// A single message in a conversation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
+    /// Canonical [`Role`] string; read it through [`ChatMessage::role`].
     pub role: String,
     pub content: String,
 }
 
 impl ChatMessage {
-    pub fn system(content: impl Into<String>) -> Self {
+    pub fn new(role: &Role, content: impl Into<String>) -> Self {
         Self {
-            role: "system".into(),
+            role: role.to_string(),
             content: content.into(),
         }
     }
 
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new(&Role::System, content)
+    }
+
     pub fn user(content: impl Into<String>) -> Self {
-        Self {
-            role: "user".into(),
-            content: content.into(),
-        }
+        Self::new(&Role::User, content)
     }
 
     pub fn assistant(content: impl Into<String>) -> Self {
-        Self {
-            role: "assistant".into(),
-            content: content.into(),
-        }
+        Self::new(&Role::Assistant, content)
     }
 
     pub fn tool(content: impl Into<String>) -> Self {
-        Self {
-            role: "tool".into(),
-            content: content.into(),
-        }
+        Self::new(&Role::Tool, content)
+    }
+
+    pub fn role(&self) -> Role {
+        Role::from(self.role.as_str())
     }
 }
 
@@ -209,6 +274,24 @@ pub enum StreamError {
     Io(#[from] std::io::Error),
 }
 
+/// Provider failures that callers handle differently from a generic error.
+///
+/// `ReliableProvider` fails fast on `ContextTooLong` and `Config` and
+/// retries `Timeout`; the agent loop answers `ContextTooLong` by packing the
+/// history harder and retrying once.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ProviderError {
+    /// The request is larger than the model's context window.
+    #[error("{message}")]
+    ContextTooLong { message: String },
+    /// Credentials or account configuration were rejected (HTTP 401/403).
+    #[error("{message}")]
+    Config { message: String },
+    /// No response within `reliability.provider_timeout_secs`.
+    #[error("Provider request timed out after {secs}s")]
+    Timeout { secs: u64 },
+}
+
 /// Structured error returned when a requested capability is not supported.
 #[derive(Debug, Clone, thiserror::Error)]
 #[error("provider_capability_error provider={provider} capability={capability} message={message}")]
@@ -528,6 +611,33 @@ mod tests {
         assert_eq!(tool.role, "tool");
     }
 
+    #[test]
+    fn role_round_trips_through_its_canonical_string() {
+        for role in [Role::System, Role::User, Role::Assistant, Role::Tool] {
+            let text = role.to_string();
+            assert_eq!(text, text.to_lowercase());
+            assert_eq!(text.parse::<Role>().unwrap(), role);
+
+            let json = serde_json::to_string(&role).unwrap();
+            assert_eq!(json, format!("\"{text}\""));
+            assert_eq!(serde_json::from_str::<Role>(&json).unwrap(), role);
+            assert_eq!(ChatMessage::new(&role, "x").role(), role);
+        }
+        assert_eq!(Role::from(" Assistant "), Role::Assistant);
+    }
+
+    #[test]
+    fn unknown_roles_stay_unknown_instead_of_becoming_user() {
+        let role: Role = "developer".parse().unwrap();
+        assert_eq!(role, Role::Unknown("developer".into()));
+        assert_eq!(role.to_string(), "developer");
+        assert_eq!(
+            serde_json::from_str::<Role>("\"critic\"").unwrap(),
+            Role::Unknown("critic".into())
+        );
+        assert_ne!(Role::from(""), Role::User);
+    }
+
     #[test]
     fn chat_response_helpers() {
         let empty = ChatResponse {
@@ -1,13 +1,15 @@
 //! Audit logging for security events
 
+use crate::agent::subtask::current_subtask_id;
+use crate::agent::turn::{current_turn_id, TurnSummary};
 use crate::config::AuditConfig;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::path::PathBuf;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 /// Audit event types
@@ -21,6 +23,26 @@ pub enum AuditEventType {
     AuthFailure,
     PolicyViolation,
     SecurityEvent,
+    ProviderCall,
+    /// A failed turn reported to a channel user.
+    ChannelError,
+    /// End of a channel turn; carries a [`TurnSummary`].
+    TurnSummary,
+    /// A `spawn_subtask` run; carries its `subtask_id`.
+    Subtask,
+    /// Credentials found in a reply or tool result; names the kinds only.
+    Redaction,
+    /// An inbound message over the per-sender rate cap (notice or drop).
+    InboundFlood,
+    /// A channel turn cancelled by a newer message from the same sender;
+    /// carries its [`TurnSummary`].
+    TurnCancelled,
+    /// A turn whose prompt filled more than 95% of the model's context
+    /// window; carries its [`TurnSummary`].
+    ContextPressure,
+    /// A tool call held for user confirmation, and the decision on it
+    /// (`held`, `approved`, `denied` or `expired`).
+    ToolConfirmation,
 }
 
 /// Actor information (who performed the action)
@@ -47,6 +69,9 @@ pub struct ExecutionResult {
     pub exit_code: Option<i32>,
     pub duration_ms: Option<u64>,
     pub error: Option<String>,
+    /// Whether a provider reply was served from the response cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_hit: Option<bool>,
 }
 
 /// Security context
@@ -55,6 +80,9 @@ pub struct SecurityContext {
     pub policy_violation: bool,
     pub rate_limit_remaining: Option<u32>,
     pub sandbox_backend: Option<String>,
+    /// Policy rule that produced this event (e.g. a blocked command pattern).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched_rule: Option<String>,
 }
 
 /// Complete audit event
@@ -67,6 +95,18 @@ pub struct AuditEvent {
     pub action: Option<Action>,
     pub result: Option<ExecutionResult>,
     pub security: SecurityContext,
+    /// Reference id shown to the user, for correlating their report.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// Turn during which the event was recorded (see [`crate::agent::turn`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_id: Option<String>,
+    /// Sub-task of the turn during which the event was recorded (see
+    /// [`crate::agent::subtask`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subtask_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn: Option<TurnSummary>,
 }
 
 impl AuditEvent {
@@ -83,7 +123,12 @@ impl AuditEvent {
                 policy_violation: false,
                 rate_limit_remaining: None,
                 sandbox_backend: None,
+                matched_rule: None,
             },
+            reference: None,
+            turn_id: current_turn_id(),
+            subtask_id: current_subtask_id(),
+            turn: None,
         }
     }
 
@@ -132,15 +177,45 @@ impl AuditEvent {
             exit_code,
             duration_ms: Some(duration_ms),
             error,
+            cache_hit: None,
         });
         self
     }
 
+    /// Mark whether the recorded result came from the provider response cache.
+    pub fn with_cache_hit(mut self, cache_hit: bool) -> Self {
+        if let Some(result) = self.result.as_mut() {
+            result.cache_hit = Some(cache_hit);
+        }
+        self
+    }
+
     /// Set security context
     pub fn with_security(mut self, sandbox_backend: Option<String>) -> Self {
         self.security.sandbox_backend = sandbox_backend;
         self
     }
+
+    /// Set the user-visible reference id.
+    pub fn with_reference(mut self, reference: String) -> Self {
+        self.reference = Some(reference);
+        self
+    }
+
+    /// Attach a turn summary; the event's `turn_id` becomes the summary's.
+    pub fn with_turn_summary(mut self, summary: TurnSummary) -> Self {
+        self.turn_id = Some(summary.turn_id.clone());
+        self.turn = Some(summary);
+        self
+    }
+
+    /// Record the policy rule behind this event; a blocked action also
+    /// marks the event as a policy violation.
+    pub fn with_matched_rule(mut self, rule: String, violation: bool) -> Self {
+        self.security.matched_rule = Some(rule);
+        self.security.policy_violation = violation;
+        self
+    }
 }
 
 /// Audit logger
@@ -165,7 +240,7 @@ pub struct CommandExecutionLog<'a> {
 impl AuditLogger {
     /// Create a new audit logger
     pub fn new(config: AuditConfig, zeroclaw_dir: PathBuf) -> Result<Self> {
-        let log_path = zeroclaw_dir.join(&config.log_path);
+        let log_path = audit_log_path(&config, &zeroclaw_dir);
         Ok(Self {
             log_path,
             config,
@@ -258,6 +333,130 @@ impl AuditLogger {
     }
 }
 
+/// Resolve the audit log file for `config` under the zeroclaw directory.
+pub fn audit_log_path(config: &AuditConfig, zeroclaw_dir: &Path) -> PathBuf {
+    zeroclaw_dir.join(&config.log_path)
+}
+
+/// Upper bound on bytes scanned per [`read_recent_events`] call, across the
+/// current and previous log file.
+const MAX_AUDIT_SCAN_BYTES: u64 = 8 * 1024 * 1024;
+const AUDIT_READ_CHUNK_BYTES: u64 = 64 * 1024;
+
+/// Filters for [`read_recent_events`].
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    pub limit: usize,
+    /// Match `event_type` (e.g. `command_execution`).
+    pub event_type: Option<String>,
+    /// Match the actor's channel or user id, or a turn summary's session.
+    pub session: Option<String>,
+}
+
+impl AuditQuery {
+    fn matches(&self, event: &serde_json::Value) -> bool {
+        if let Some(event_type) = &self.event_type {
+            if event.get("event_type").and_then(|v| v.as_str()) != Some(event_type.as_str()) {
+                return false;
+            }
+        }
+        if let Some(session) = &self.session {
+            let actor = event.get("actor");
+            let field = |name: &str| actor.and_then(|a| a.get(name)).and_then(|v| v.as_str());
+            let turn_session = event
+                .get("turn")
+                .and_then(|turn| turn.get("session"))
+                .and_then(|v| v.as_str());
+            if field("channel") != Some(session.as_str())
+                && field("user_id") != Some(session.as_str())
+                && turn_session != Some(session.as_str())
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Read the most recent audit events, newest first.
+///
+/// Scans the current log and then the last rotated file (`<log>.1.log`)
+/// backwards from the end, stopping after `query.limit` matches or
+/// [`MAX_AUDIT_SCAN_BYTES`], so large logs never load fully into memory.
+/// Lines that do not parse as JSON (e.g. a partially written last line)
+/// are skipped.
+pub fn read_recent_events(log_path: &Path, query: &AuditQuery) -> Vec<serde_json::Value> {
+    let mut events = Vec::new();
+    if query.limit == 0 {
+        return events;
+    }
+    let mut budget = MAX_AUDIT_SCAN_BYTES;
+    let rotated = PathBuf::from(format!("{}.1.log", log_path.display()));
+
+    for path in [log_path, rotated.as_path()] {
+        let result = scan_lines_backwards(path, &mut budget, |line| {
+            if let Ok(event) = serde_json::from_slice::<serde_json::Value>(line) {
+                if query.matches(&event) {
+                    events.push(event);
+                }
+            }
+            events.len() < query.limit
+        });
+        if let Err(e) = result {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to read audit log {}: {e}", path.display());
+            }
+        }
+        if events.len() >= query.limit || budget == 0 {
+            break;
+        }
+    }
+    events
+}
+
+/// Feed the non-empty lines of `path` to `visit` from last to first, reading
+/// at most `budget` bytes. Stops early when `visit` returns `false`.
+fn scan_lines_backwards(
+    path: &Path,
+    budget: &mut u64,
+    mut visit: impl FnMut(&[u8]) -> bool,
+) -> std::io::Result<()> {
+    let mut file = File::open(path)?;
+    let mut pos = file.metadata()?.len();
+    // Start of a line whose beginning lies in the part not read yet.
+    let mut carry: Vec<u8> = Vec::new();
+
+    while pos > 0 && *budget > 0 {
+        let read_len = AUDIT_READ_CHUNK_BYTES.min(pos).min(*budget);
+        pos -= read_len;
+        *budget -= read_len;
+        file.seek(SeekFrom::Start(pos))?;
+        #[allow(clippy::cast_possible_truncation)]
+        let mut data = vec![0u8; read_len as usize];
+        file.read_exact(&mut data)?;
+        data.extend_from_slice(&carry);
+
+        let complete_from = if pos == 0 {
+            0
+        } else {
+            match data.iter().position(|b| *b == b'\n') {
+                Some(idx) => idx + 1,
+                None => {
+                    carry = data;
+                    continue;
+                }
+            }
+        };
+        for line in data[complete_from..].rsplit(|b| *b == b'\n') {
+            if !line.is_empty() && !visit(line) {
+                return Ok(());
+            }
+        }
+        carry = data[..complete_from].to_vec();
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -420,4 +619,141 @@ mod tests {
         );
         Ok(())
     }
+
+    fn write_fixture(path: &Path, events: &[AuditEvent], trailer: &str) {
+        let mut content = String::new();
+        for event in events {
+            content.push_str(&serde_json::to_string(event).unwrap());
+            content.push('\n');
+        }
+        content.push_str(trailer);
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn command_event(channel: &str, command: &str) -> AuditEvent {
+        AuditEvent::new(AuditEventType::CommandExecution)
+            .with_actor(channel.to_string(), None, None)
+            .with_action(command.to_string(), "low".to_string(), false, true)
+    }
+
+    #[test]
+    fn read_recent_events_filters_newest_first_and_skips_partial_line() {
+        let tmp = TempDir::new().unwrap();
+        let log_path = tmp.path().join("audit.log");
+        write_fixture(
+            &log_path.with_file_name("audit.log.1.log"),
+            &[command_event("cli", "echo rotated")],
+            "",
+        );
+        write_fixture(
+            &log_path,
+            &[
+                command_event("telegram", "ls"),
+                AuditEvent::new(AuditEventType::AuthFailure).with_actor(
+                    "gateway".into(),
+                    None,
+                    None,
+                ),
+                command_event("cli", "pwd"),
+            ],
+            r#"{"timestamp":"2026-01-01T00:00:00Z","event_type":"comm"#,
+        );
+
+        let all = read_recent_events(
+            &log_path,
+            &AuditQuery {
+                limit: 10,
+                ..AuditQuery::default()
+            },
+        );
+        let commands: Vec<_> = all
+            .iter()
+            .map(|e| e["action"]["command"].as_str().unwrap_or("-"))
+            .collect();
+        assert_eq!(commands, vec!["pwd", "-", "ls", "echo rotated"]);
+
+        let filtered = read_recent_events(
+            &log_path,
+            &AuditQuery {
+                limit: 10,
+                event_type: Some("command_execution".into()),
+                session: Some("cli".into()),
+            },
+        );
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(filtered[0]["action"]["command"], "pwd");
+        assert_eq!(filtered[1]["action"]["command"], "echo rotated");
+
+        let limited = read_recent_events(
+            &log_path,
+            &AuditQuery {
+                limit: 1,
+                ..AuditQuery::default()
+            },
+        );
+        assert_eq!(limited.len(), 1);
+        assert_eq!(limited[0]["action"]["command"], "pwd");
+    }
+
+    #[tokio::test]
+    async fn events_carry_turn_id_and_turn_summaries_match_session_filter() {
+        use crate::agent::turn::{self, TurnOutcome, TurnRecorder};
+        use std::sync::Arc;
+
+        assert!(command_event("cli", "ls").turn_id.is_none());
+        let recorder = Arc::new(TurnRecorder::new("telegram_alice"));
+        let inside = turn::with_turn(Arc::clone(&recorder), async {
+            turn::record_outcome(TurnOutcome::Completed);
+            command_event("telegram", "ls")
+        })
+        .await;
+        assert_eq!(inside.turn_id.as_deref(), Some(recorder.id()));
+
+        let tmp = TempDir::new().unwrap();
+        let log_path = tmp.path().join("audit.log");
+        let summary_event = AuditEvent::new(AuditEventType::TurnSummary)
+            .with_actor("telegram".into(), Some("alice".into()), None)
+            .with_turn_summary(recorder.summary().unwrap());
+        write_fixture(&log_path, &[inside, summary_event], "");
+
+        let turns = read_recent_events(
+            &log_path,
+            &AuditQuery {
+                limit: 10,
+                event_type: Some("turn_summary".into()),
+                session: Some("telegram_alice".into()),
+            },
+        );
+        assert_eq!(turns.len(), 1);
+        assert_eq!(turns[0]["turn_id"], recorder.id());
+        assert_eq!(turns[0]["turn"]["outcome"], "completed");
+    }
+
+    #[test]
+    fn scan_lines_backwards_handles_lines_across_chunks() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("lines.log");
+        let long = "x".repeat(usize::try_from(AUDIT_READ_CHUNK_BYTES).unwrap() + 10);
+        std::fs::write(&path, format!("first\n{long}\nlast\n")).unwrap();
+
+        let mut lines = Vec::new();
+        let mut budget = MAX_AUDIT_SCAN_BYTES;
+        scan_lines_backwards(&path, &mut budget, |line| {
+            lines.push(line.len());
+            true
+        })
+        .unwrap();
+        assert_eq!(lines, vec![4, long.len(), 5]);
+
+        // A tight budget stops the scan instead of reading the whole file.
+        let mut budget = 16;
+        let mut seen = 0;
+        scan_lines_backwards(&path, &mut budget, |_| {
+            seen += 1;
+            true
+        })
+        .unwrap();
+        assert_eq!(seen, 1);
+        assert_eq!(budget, 0);
+    }
 }
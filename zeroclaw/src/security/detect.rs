@@ -133,6 +133,7 @@ mod tests {
                 enabled: Some(false),
                 backend: SandboxBackend::None,
                 firejail_args: Vec::new(),
+                ..SandboxConfig::default()
             },
             ..Default::default()
         };
@@ -147,6 +148,7 @@ mod tests {
                 enabled: None, // Auto-detect
                 backend: SandboxBackend::Auto,
                 firejail_args: Vec::new(),
+                ..SandboxConfig::default()
             },
             ..Default::default()
         };
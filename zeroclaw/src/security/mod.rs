@@ -21,6 +21,7 @@
 pub mod audit;
 #[cfg(feature = "sandbox-bubblewrap")]
 pub mod bubblewrap;
+pub mod command_filter;
 pub mod detect;
 pub mod docker;
 
@@ -36,11 +37,13 @@ pub mod otp;
 pub mod pairing;
 pub mod policy;
 pub mod prompt_guard;
+pub mod redaction;
 pub mod secrets;
 pub mod traits;
 
 #[allow(unused_imports)]
 pub use audit::{AuditEvent, AuditEventType, AuditLogger};
+pub use command_filter::CommandFilter;
 #[allow(unused_imports)]
 pub use detect::create_sandbox;
 pub use domain_matcher::DomainMatcher;
@@ -50,7 +53,7 @@ pub use estop::{EstopLevel, EstopManager, EstopState, ResumeSelector};
 pub use otp::OtpValidator;
 #[allow(unused_imports)]
 pub use pairing::PairingGuard;
-pub use policy::{AutonomyLevel, SecurityPolicy};
+pub use policy::{AutonomyLevel, SecurityPolicy, SENSITIVE_WORKSPACE_PATHS};
 #[allow(unused_imports)]
 pub use secrets::SecretStore;
 #[allow(unused_imports)]
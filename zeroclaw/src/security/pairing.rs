@@ -275,6 +275,14 @@ fn hash_token(token: &str) -> String {
     format!("{:x}", Sha256::digest(token.as_bytes()))
 }
 
+/// Mint a bearer token for pre-seeding `gateway.paired_tokens` (e.g. from
+/// onboarding). Returns `(plaintext, hash)`; persist only the hash.
+pub fn mint_paired_token() -> (String, String) {
+    let token = generate_token();
+    let hash = hash_token(&token);
+    (token, hash)
+}
+
 /// Check if a stored value looks like a SHA-256 hash (64 hex chars)
 /// rather than a plaintext token.
 fn is_token_hash(value: &str) -> bool {
@@ -441,6 +449,15 @@ mod tests {
         assert!(!is_token_hash(""));
     }
 
+    #[test]
+    async fn minted_token_hash_authenticates() {
+        let (token, hash) = mint_paired_token();
+        assert!(token.starts_with("zc_"));
+        assert!(is_token_hash(&hash));
+        let guard = PairingGuard::new(true, &[hash]);
+        assert!(guard.is_authenticated(&token));
+    }
+
     // ── is_public_bind ───────────────────────────────────────
 
     #[test]
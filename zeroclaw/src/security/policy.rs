@@ -2,8 +2,14 @@ use parking_lot::Mutex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Instant;
 
+/// Workspace-relative files file tools never touch, whatever the scope: the
+/// channel session history. SQLite sidecars (`-wal`, `-shm`, `-journal`)
+/// are covered by prefix.
+pub const SENSITIVE_WORKSPACE_PATHS: &[&str] = &["sessions/sessions.db"];
+
 /// How much autonomy the agent has
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
@@ -36,13 +42,21 @@ pub enum ToolOperation {
 #[derive(Debug)]
 pub struct ActionTracker {
     /// Timestamps of recent actions (kept within the last hour).
-    actions: Mutex<Vec<Instant>>,
+    actions: Arc<Mutex<Vec<Instant>>>,
 }
 
 impl ActionTracker {
     pub fn new() -> Self {
         Self {
-            actions: Mutex::new(Vec::new()),
+            actions: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// A tracker counting against the same window as `self`, unlike
+    /// [`Clone`], which snapshots it.
+    pub fn shared(&self) -> Self {
+        Self {
+            actions: Arc::clone(&self.actions),
         }
     }
 
@@ -72,7 +86,7 @@ impl Clone for ActionTracker {
     fn clone(&self) -> Self {
         let actions = self.actions.lock();
         Self {
-            actions: Mutex::new(actions.clone()),
+            actions: Arc::new(Mutex::new(actions.clone())),
         }
     }
 }
@@ -993,6 +1007,55 @@ impl SecurityPolicy {
         )
     }
 
+    /// Whether `resolved` is one of [`SENSITIVE_WORKSPACE_PATHS`] (or a SQLite
+    /// sidecar of one) under the workspace.
+    pub fn is_sensitive_path(&self, resolved: &Path) -> bool {
+        let workspace_root = self
+            .workspace_dir
+            .canonicalize()
+            .unwrap_or_else(|_| self.workspace_dir.clone());
+        let Ok(relative) = resolved.strip_prefix(&workspace_root) else {
+            return false;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        SENSITIVE_WORKSPACE_PATHS.iter().any(|sensitive| {
+            relative
+                .strip_prefix(sensitive)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+        })
+    }
+
+    /// Full check for a canonicalized path a file tool is about to touch.
+    ///
+    /// Rejects sensitive workspace files, paths outside `base_dir` when one is
+    /// given (a skill's data directory under `fs_scope = "skill"`), and
+    /// anything [`Self::is_resolved_path_allowed`] refuses. The error is ready
+    /// to show to the model.
+    pub fn validate_path(&self, resolved: &Path, base_dir: Option<&Path>) -> Result<(), String> {
+        if self.is_sensitive_path(resolved) {
+            return Err(format!(
+                "Access denied: {} holds agent state and is not readable or writable by tools",
+                resolved.display()
+            ));
+        }
+        if let Some(base_dir) = base_dir {
+            let base_root = base_dir
+                .canonicalize()
+                .unwrap_or_else(|_| base_dir.to_path_buf());
+            if !resolved.starts_with(&base_root) {
+                return Err(format!(
+                    "Path escapes the skill data directory {}: {}",
+                    base_root.display(),
+                    resolved.display()
+                ));
+            }
+        }
+        if !self.is_resolved_path_allowed(resolved) {
+            return Err(self.resolved_path_violation_message(resolved));
+        }
+        Ok(())
+    }
+
     /// Check if autonomy level permits any action at all
     pub fn can_act(&self) -> bool {
         self.autonomy != AutonomyLevel::ReadOnly
@@ -1558,6 +1621,17 @@ mod tests {
         assert_eq!(cloned.count(), 2); // clone is independent
     }
 
+    #[test]
+    fn action_tracker_shared_counts_together() {
+        let tracker = ActionTracker::new();
+        tracker.record();
+        let shared = tracker.shared();
+        shared.record();
+        assert_eq!(tracker.count(), 2);
+        tracker.record();
+        assert_eq!(shared.count(), 3);
+    }
+
     // ── Edge cases: command injection ────────────────────────
 
     #[test]
@@ -2211,6 +2285,54 @@ mod tests {
         let _ = std::fs::remove_dir_all(&workspace);
     }
 
+    #[test]
+    fn validate_path_denies_sensitive_workspace_files() {
+        let policy = SecurityPolicy {
+            workspace_dir: PathBuf::from("/srv/zeroclaw/workspace"),
+            ..SecurityPolicy::default()
+        };
+        let ws = Path::new("/srv/zeroclaw/workspace");
+
+        for denied in ["sessions/sessions.db", "sessions/sessions.db-wal"] {
+            let err = policy.validate_path(&ws.join(denied), None).unwrap_err();
+            assert!(err.contains("holds agent state"), "{denied}: {err}");
+        }
+        assert!(policy
+            .validate_path(&ws.join("sessions/sessions.db.txt"), None)
+            .is_ok());
+        assert!(policy
+            .validate_path(&ws.join("memory/notes.md"), None)
+            .is_ok());
+    }
+
+    #[test]
+    fn validate_path_confines_to_base_dir() {
+        let policy = SecurityPolicy {
+            workspace_dir: PathBuf::from("/srv/zeroclaw/workspace"),
+            allowed_roots: vec![PathBuf::from("/srv/shared")],
+            ..SecurityPolicy::default()
+        };
+        let data = Path::new("/srv/zeroclaw/workspace/skills/notes/data");
+
+        assert!(policy
+            .validate_path(&data.join("todo.md"), Some(data))
+            .is_ok());
+        let err = policy
+            .validate_path(
+                Path::new("/srv/zeroclaw/workspace/memory/brain.db"),
+                Some(data),
+            )
+            .unwrap_err();
+        assert!(err.contains("skill data directory"), "{err}");
+        // Allowed roots do not widen a base-dir restriction.
+        assert!(policy
+            .validate_path(Path::new("/srv/shared/file.txt"), Some(data))
+            .is_err());
+        assert!(policy
+            .validate_path(Path::new("/srv/shared/file.txt"), None)
+            .is_ok());
+    }
+
     #[test]
     fn resolved_path_blocks_root_escape() {
         let policy = SecurityPolicy {
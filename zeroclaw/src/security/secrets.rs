@@ -19,10 +19,16 @@
 // Migration: values with the legacy `enc:` prefix (XOR cipher) are decrypted
 // using the old algorithm for backward compatibility. New encryptions always
 // produce `enc2:` (ChaCha20-Poly1305).
+//
+// Passphrase mode: when `ZEROCLAW_SECRETS_PASSPHRASE` is set, new values are
+// encrypted as `enc3:` with a key derived from the passphrase (PBKDF2-HMAC-SHA256,
+// random per-value salt) instead of the key file, so a copied config directory
+// is useless without the passphrase.
 
 use anyhow::{Context, Result};
 use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
 use chacha20poly1305::{AeadCore, ChaCha20Poly1305, Key, Nonce};
+use sha2::Sha256;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -32,24 +38,66 @@ const KEY_LEN: usize = 32;
 /// ChaCha20-Poly1305 nonce length in bytes.
 const NONCE_LEN: usize = 12;
 
+/// Salt length for passphrase-derived keys (`enc3:` values).
+const SALT_LEN: usize = 16;
+
+/// PBKDF2 iteration count for passphrase-derived keys.
+const PBKDF2_ROUNDS: u32 = 100_000;
+
+/// Environment variable holding the passphrase for `enc3:` secrets.
+pub const PASSPHRASE_ENV: &str = "ZEROCLAW_SECRETS_PASSPHRASE";
+
 /// Manages encrypted storage of secrets (API keys, tokens, etc.)
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct SecretStore {
     /// Path to the key file (`~/.zeroclaw/.secret_key`)
     key_path: PathBuf,
     /// Whether encryption is enabled
     enabled: bool,
+    /// Passphrase for `enc3:` values; takes precedence over the key file
+    passphrase: Option<String>,
+}
+
+impl std::fmt::Debug for SecretStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretStore")
+            .field("key_path", &self.key_path)
+            .field("enabled", &self.enabled)
+            .field(
+                "passphrase",
+                &self.passphrase.as_ref().map(|_| "[REDACTED]"),
+            )
+            .finish()
+    }
 }
 
 impl SecretStore {
     /// Create a new secret store rooted at the given directory.
+    ///
+    /// Picks up the passphrase from `ZEROCLAW_SECRETS_PASSPHRASE` when set.
     pub fn new(zeroclaw_dir: &Path, enabled: bool) -> Self {
+        let passphrase = std::env::var(PASSPHRASE_ENV)
+            .ok()
+            .filter(|value| !value.is_empty());
         Self {
             key_path: zeroclaw_dir.join(".secret_key"),
             enabled,
+            passphrase,
         }
     }
 
+    /// Use (or stop using) a passphrase-derived key for new encryptions.
+    #[must_use]
+    pub fn with_passphrase(mut self, passphrase: Option<String>) -> Self {
+        self.passphrase = passphrase.filter(|value| !value.is_empty());
+        self
+    }
+
+    /// Whether new values are encrypted with the passphrase (`enc3:`).
+    pub fn uses_passphrase(&self) -> bool {
+        self.passphrase.is_some()
+    }
+
     /// Encrypt a plaintext secret. Returns hex-encoded ciphertext prefixed with `enc2:`.
     /// Format: `enc2:<hex(nonce ‖ ciphertext ‖ tag)>` (12 + N + 16 bytes).
     /// If encryption is disabled, returns the plaintext as-is.
@@ -57,6 +105,9 @@ impl SecretStore {
         if !self.enabled || plaintext.is_empty() {
             return Ok(plaintext.to_string());
         }
+        if let Some(passphrase) = &self.passphrase {
+            return Ok(encrypt_with_passphrase(passphrase, plaintext));
+        }
 
         let key_bytes = self.load_or_create_key()?;
         let key = Key::from_slice(&key_bytes);
@@ -83,7 +134,9 @@ impl SecretStore {
     /// **Warning**: Legacy `enc:` values are insecure. Use `decrypt_and_migrate` to
     /// automatically upgrade them to the secure `enc2:` format.
     pub fn decrypt(&self, value: &str) -> Result<String> {
-        if let Some(hex_str) = value.strip_prefix("enc2:") {
+        if let Some(hex_str) = value.strip_prefix("enc3:") {
+            self.decrypt_passphrase(hex_str)
+        } else if let Some(hex_str) = value.strip_prefix("enc2:") {
             self.decrypt_chacha20(hex_str)
         } else if let Some(hex_str) = value.strip_prefix("enc:") {
             self.decrypt_legacy_xor(hex_str)
@@ -99,7 +152,9 @@ impl SecretStore {
     ///
     /// This allows callers to persist the upgraded value back to config.
     pub fn decrypt_and_migrate(&self, value: &str) -> Result<(String, Option<String>)> {
-        if let Some(hex_str) = value.strip_prefix("enc2:") {
+        if let Some(hex_str) = value.strip_prefix("enc3:") {
+            Ok((self.decrypt_passphrase(hex_str)?, None))
+        } else if let Some(hex_str) = value.strip_prefix("enc2:") {
             // Already using secure format — no migration needed
             let plaintext = self.decrypt_chacha20(hex_str)?;
             Ok((plaintext, None))
@@ -147,6 +202,35 @@ impl SecretStore {
             .context("Decrypted secret is not valid UTF-8 — corrupt data")
     }
 
+    /// Decrypt an `enc3:` value with the passphrase-derived key.
+    fn decrypt_passphrase(&self, hex_str: &str) -> Result<String> {
+        let Some(passphrase) = &self.passphrase else {
+            anyhow::bail!(
+                "Secret is passphrase-encrypted (enc3:); set {PASSPHRASE_ENV} to decrypt it"
+            );
+        };
+        let blob =
+            hex_decode(hex_str).context("Failed to decode encrypted secret (corrupt hex)")?;
+        anyhow::ensure!(
+            blob.len() > SALT_LEN + NONCE_LEN,
+            "Encrypted value too short (missing salt or nonce)"
+        );
+
+        let (salt, rest) = blob.split_at(SALT_LEN);
+        let (nonce_bytes, ciphertext) = rest.split_at(NONCE_LEN);
+        let key_bytes = derive_passphrase_key(passphrase, salt);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&key_bytes));
+
+        let plaintext_bytes = cipher
+            .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+            .map_err(|_| {
+                anyhow::anyhow!("Decryption failed — wrong passphrase or tampered data")
+            })?;
+
+        String::from_utf8(plaintext_bytes)
+            .context("Decrypted secret is not valid UTF-8 — corrupt data")
+    }
+
     /// Decrypt using legacy XOR cipher (insecure, for backward compatibility only).
     fn decrypt_legacy_xor(&self, hex_str: &str) -> Result<String> {
         let ciphertext = hex_decode(hex_str)
@@ -159,12 +243,12 @@ impl SecretStore {
 
     /// Check if a value is already encrypted (current or legacy format).
     pub fn is_encrypted(value: &str) -> bool {
-        value.starts_with("enc2:") || value.starts_with("enc:")
+        value.starts_with("enc3:") || value.starts_with("enc2:") || value.starts_with("enc:")
     }
 
-    /// Check if a value uses the secure `enc2:` format.
+    /// Check if a value uses a secure AEAD format (`enc2:` or `enc3:`).
     pub fn is_secure_encrypted(value: &str) -> bool {
-        value.starts_with("enc2:")
+        value.starts_with("enc2:") || value.starts_with("enc3:")
     }
 
     /// Load the encryption key from disk, or create one if it doesn't exist.
@@ -226,6 +310,29 @@ impl SecretStore {
     }
 }
 
+/// Encrypt with a fresh salt and nonce. Format: `enc3:<hex(salt ‖ nonce ‖ ciphertext ‖ tag)>`.
+fn encrypt_with_passphrase(passphrase: &str, plaintext: &str) -> String {
+    let mut salt = [0u8; SALT_LEN];
+    salt.copy_from_slice(&generate_random_key()[..SALT_LEN]);
+    let key_bytes = derive_passphrase_key(passphrase, &salt);
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&key_bytes));
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    // Encrypting into a Vec with a valid key and nonce cannot fail.
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .expect("ChaCha20-Poly1305 encryption is infallible for in-memory buffers");
+
+    let mut blob = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+    blob.extend_from_slice(&salt);
+    blob.extend_from_slice(&nonce);
+    blob.extend_from_slice(&ciphertext);
+    format!("enc3:{}", hex_encode(&blob))
+}
+
+fn derive_passphrase_key(passphrase: &str, salt: &[u8]) -> [u8; KEY_LEN] {
+    pbkdf2::pbkdf2_hmac_array::<Sha256, KEY_LEN>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS)
+}
+
 /// XOR cipher with repeating key. Same function for encrypt and decrypt.
 fn xor_cipher(data: &[u8], key: &[u8]) -> Vec<u8> {
     if key.is_empty() {
@@ -379,6 +486,50 @@ mod tests {
         assert_eq!(decrypted, "cross-store-secret");
     }
 
+    // ── Passphrase mode (enc3:) ────────────────────────────────
+
+    #[test]
+    fn passphrase_decrypts_fixed_pbkdf2_known_answer_vector() {
+        let tmp = TempDir::new().unwrap();
+        let store =
+            SecretStore::new(tmp.path(), true).with_passphrase(Some("correct horse".into()));
+        let stored = "enc3:dfdf9a1a135102951fe9b190d2c35843eb1f170c12ded0bb90f484f8bac31b37\
+                      ee389c31e8e2aabbf4a48b410dcf85c4ff3074c037928390a5a200";
+        assert_eq!(store.decrypt(stored).unwrap(), "sk-legacy-value");
+    }
+
+    #[test]
+    fn passphrase_roundtrip_without_key_file() {
+        let tmp = TempDir::new().unwrap();
+        let store =
+            SecretStore::new(tmp.path(), true).with_passphrase(Some("correct horse".into()));
+
+        let encrypted = store.encrypt("sk-passphrase-secret").unwrap();
+        assert!(encrypted.starts_with("enc3:"));
+        assert!(SecretStore::is_secure_encrypted(&encrypted));
+        assert_eq!(store.decrypt(&encrypted).unwrap(), "sk-passphrase-secret");
+        assert!(
+            !tmp.path().join(".secret_key").exists(),
+            "passphrase mode must not create a key file"
+        );
+
+        let wrong = SecretStore::new(tmp.path(), true).with_passphrase(Some("wrong".into()));
+        assert!(wrong.decrypt(&encrypted).is_err());
+
+        let missing = SecretStore::new(tmp.path(), true).with_passphrase(None);
+        let err = missing.decrypt(&encrypted).unwrap_err().to_string();
+        assert!(err.contains(PASSPHRASE_ENV));
+    }
+
+    #[test]
+    fn debug_redacts_passphrase() {
+        let tmp = TempDir::new().unwrap();
+        let store = SecretStore::new(tmp.path(), true).with_passphrase(Some("hunter2".into()));
+        let debug = format!("{store:?}");
+        assert!(!debug.contains("hunter2"));
+        assert!(debug.contains("[REDACTED]"));
+    }
+
     #[test]
     fn unicode_secret_roundtrip() {
         let tmp = TempDir::new().unwrap();
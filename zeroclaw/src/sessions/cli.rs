@@ -0,0 +1,234 @@
+use super::export::ExportFormat;
+use super::{SessionInfo, SqliteSessionStore};
+use crate::config::Config;
+use crate::util::truncate_with_ellipsis;
+use anyhow::{bail, Context, Result};
+use console::style;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const TITLE_COLUMN_CHARS: usize = 40;
+
+/// Handle `zeroclaw sessions <subcommand>` CLI commands.
+pub fn handle_command(command: crate::SessionCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::SessionCommands::List => handle_list(config),
+        crate::SessionCommands::Show { key, limit } => handle_show(config, &key, limit),
+        crate::SessionCommands::Summary { key } => handle_summary(config, &key),
+        crate::SessionCommands::Trim { key, keep } => handle_trim(config, &key, keep),
+        crate::SessionCommands::Export { key, format, out } => {
+            handle_export(config, &key, &format, out.as_deref())
+        }
+        crate::SessionCommands::Delete { key, yes } => handle_delete(config, &key, yes),
+    }
+}
+
+fn handle_list(config: &Config) -> Result<()> {
+    let store = SqliteSessionStore::open_read_only(&config.workspace_dir)?;
+    let sessions = store.list_sessions()?;
+
+    if sessions.is_empty() {
+        println!("No sessions recorded.");
+        return Ok(());
+    }
+
+    println!("Sessions ({} total):\n", sessions.len());
+    print!("{}", format_sessions_table(&sessions));
+    Ok(())
+}
+
+fn handle_show(config: &Config, key: &str, limit: usize) -> Result<()> {
+    let store = SqliteSessionStore::open_read_only(&config.workspace_dir)?;
+    if !store.session_exists(key)? {
+        println!("No session found for key: {key}");
+        return Ok(());
+    }
+
+    let history = store.load_history(key, Some(limit))?;
+    if history.is_empty() {
+        println!("Session {key} has no messages.");
+        return Ok(());
+    }
+
+    println!(
+        "Session {} (last {} messages):\n",
+        style(key).white().bold(),
+        history.len()
+    );
+    for message in &history {
+        println!(
+            "[{}] {}",
+            message.created_at,
+            style(&message.role).cyan().bold()
+        );
+        println!("{}\n", message.content);
+    }
+    Ok(())
+}
+
+fn handle_summary(config: &Config, key: &str) -> Result<()> {
+    let store = SqliteSessionStore::open_read_only(&config.workspace_dir)?;
+    if !store.session_exists(key)? {
+        println!("No session found for key: {key}");
+        return Ok(());
+    }
+
+    match store.summary(key)? {
+        Some(summary) => println!("{summary}"),
+        None => println!("Session {key} has no stored summary."),
+    }
+    Ok(())
+}
+
+fn handle_export(config: &Config, key: &str, format: &str, out: Option<&Path>) -> Result<()> {
+    let format: ExportFormat = format.parse()?;
+    let store = SqliteSessionStore::open_read_only(&config.workspace_dir)?;
+
+    let mut writer: Box<dyn Write> = match out {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).with_context(|| {
+                format!("failed to create {}", path.display())
+            })?))
+        }
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let found = store.export_session(key, format, |chunk| {
+        writer.write_all(chunk.as_bytes()).map_err(Into::into)
+    })?;
+    writer.flush()?;
+    drop(writer);
+
+    if !found {
+        if let Some(path) = out {
+            let _ = std::fs::remove_file(path);
+        }
+        bail!("No session found for key: {key}");
+    }
+    if let Some(path) = out {
+        println!("Exported session {key} to {}", path.display());
+    }
+    Ok(())
+}
+
+fn handle_trim(config: &Config, key: &str, keep: usize) -> Result<()> {
+    let store = SqliteSessionStore::open(&config.workspace_dir)?;
+    if !store.session_exists(key)? {
+        println!("No session found for key: {key}");
+        return Ok(());
+    }
+
+    let removed = store.trim_history(key, keep)?;
+    println!(
+        "{} Trimmed {removed} messages from {key} (kept newest {keep}).",
+        style("✓").green().bold()
+    );
+    Ok(())
+}
+
+fn handle_delete(config: &Config, key: &str, yes: bool) -> Result<()> {
+    let store = SqliteSessionStore::open(&config.workspace_dir)?;
+    if !store.session_exists(key)? {
+        println!("No session found for key: {key}");
+        return Ok(());
+    }
+
+    if !yes {
+        let confirmed = dialoguer::Confirm::new()
+            .with_prompt(format!("  Delete session '{key}' and all its messages?"))
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    store.delete_session(key)?;
+    println!("{} Deleted session {key}.", style("✓").green().bold());
+    Ok(())
+}
+
+/// Render sessions as an aligned plain-text table.
+fn format_sessions_table(sessions: &[SessionInfo]) -> String {
+    use std::fmt::Write as _;
+
+    let titles: Vec<String> = sessions
+        .iter()
+        .map(|s| {
+            s.title.as_deref().map_or_else(
+                || "-".to_string(),
+                |t| truncate_with_ellipsis(t, TITLE_COLUMN_CHARS),
+            )
+        })
+        .collect();
+
+    let key_width = sessions
+        .iter()
+        .map(|s| s.key.chars().count())
+        .chain(std::iter::once("KEY".len()))
+        .max()
+        .unwrap_or(0);
+    let title_width = titles
+        .iter()
+        .map(|t| t.chars().count())
+        .chain(std::iter::once("TITLE".len()))
+        .max()
+        .unwrap_or(0);
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:<key_width$}  {:<title_width$}  {:>8}  UPDATED",
+        "KEY", "TITLE", "MESSAGES"
+    );
+    for (session, title) in sessions.iter().zip(&titles) {
+        let _ = writeln!(
+            out,
+            "{:<key_width$}  {:<title_width$}  {:>8}  {}",
+            session.key, title, session.message_count, session.updated_at
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(key: &str, title: Option<&str>, count: usize) -> SessionInfo {
+        SessionInfo {
+            key: key.to_string(),
+            title: title.map(str::to_string),
+            message_count: count,
+            updated_at: "2026-01-01T00:00:00+00:00".to_string(),
+            context_utilization: None,
+        }
+    }
+
+    #[test]
+    fn sessions_table_aligns_columns() {
+        let table = format_sessions_table(&[
+            info("telegram_alice", Some("Trip planning"), 12),
+            info("irc_b", None, 3),
+        ]);
+        let lines: Vec<_> = table.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("KEY             TITLE"));
+        assert!(lines[1].starts_with("telegram_alice  Trip planning"));
+        assert!(lines[2].starts_with("irc_b           -"));
+        let updated_col = lines[0].find("UPDATED").unwrap();
+        assert_eq!(lines[1].find("2026").unwrap(), updated_col);
+        assert_eq!(lines[2].find("2026").unwrap(), updated_col);
+    }
+
+    #[test]
+    fn sessions_table_truncates_long_titles() {
+        let long = "x".repeat(100);
+        let table = format_sessions_table(&[info("k", Some(&long), 1)]);
+        let row = table.lines().nth(1).unwrap();
+        assert!(row.contains("..."));
+        assert!(!row.contains(&long));
+    }
+}
@@ -0,0 +1,380 @@
+//! Background compaction of idle stored sessions.
+//!
+//! The agent loop only compacts the conversation it is serving, so sessions
+//! that went quiet keep every persisted turn. [`run_compaction`] picks stored
+//! sessions over `[session_compaction].message_threshold` turns that have
+//! been idle for `idle_hours`, folds the turns about to be removed into the
+//! stored session summary (or just drops them when `summarize` is off) and
+//! trims each to its newest `keep_recent` turns. Each new summary is
+//! appended to the session's summary history (see [`super::summaries`]).
+
+use super::{blocking, SqliteSessionStore, StoredMessage};
+use crate::config::SessionCompactionConfig;
+use crate::providers::{ChatMessage, Provider};
+use chrono::Utc;
+use futures_util::{stream, StreamExt};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// Provider and model used to summarize the turns a pass removes.
+#[derive(Clone)]
+pub struct Summarizer {
+    pub provider: Arc<dyn Provider>,
+    pub model: String,
+}
+
+/// Outcome of one [`run_compaction`] pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CompactionReport {
+    pub sessions_compacted: usize,
+    pub messages_removed: usize,
+}
+
+/// Process-wide totals, reported by `/api/monitor/metrics`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CompactionTotals {
+    pub passes: u64,
+    pub sessions_compacted: u64,
+    pub messages_removed: u64,
+    /// RFC 3339 timestamp of the most recent pass.
+    pub last_run_at: Option<String>,
+}
+
+static TOTALS: OnceLock<Mutex<CompactionTotals>> = OnceLock::new();
+
+fn totals() -> &'static Mutex<CompactionTotals> {
+    TOTALS.get_or_init(|| Mutex::new(CompactionTotals::default()))
+}
+
+fn record(report: CompactionReport) {
+    let mut totals = totals().lock();
+    totals.passes = totals.passes.saturating_add(1);
+    totals.sessions_compacted = totals
+        .sessions_compacted
+        .saturating_add(report.sessions_compacted as u64);
+    totals.messages_removed = totals
+        .messages_removed
+        .saturating_add(report.messages_removed as u64);
+    totals.last_run_at = Some(Utc::now().to_rfc3339());
+}
+
+/// Compaction totals since startup.
+pub fn snapshot() -> CompactionTotals {
+    totals().lock().clone()
+}
+
+pub fn snapshot_json() -> serde_json::Value {
+    serde_json::to_value(snapshot()).unwrap_or_else(|_| serde_json::json!({}))
+}
+
+/// Trim `key` to its newest `keep_recent` turns. With a `summarizer`, the
+/// removed turns (and any earlier summary) are condensed into the stored
+/// session summary first. Pinned turns are neither removed nor summarized.
+/// Returns the number of turns removed.
+pub async fn compact_session(
+    store: &Arc<SqliteSessionStore>,
+    key: &str,
+    keep_recent: usize,
+    summarizer: Option<&Summarizer>,
+) -> anyhow::Result<usize> {
+    let (owned_key, summarize) = (key.to_string(), summarizer.is_some());
+    let (removed, previous) = blocking(Arc::clone(store), move |store| {
+        let removed = store.trimmable_history(&owned_key, keep_recent)?;
+        let previous = if summarize && !removed.is_empty() {
+            store.summary(&owned_key)?
+        } else {
+            None
+        };
+        Ok((removed, previous))
+    })
+    .await?;
+    if removed.is_empty() {
+        return Ok(0);
+    }
+
+    let summary = match summarizer {
+        Some(summarizer) => {
+            let messages: Vec<ChatMessage> = removed.iter().map(to_chat_message).collect();
+            let transcript = summarization_source(
+                previous.as_deref(),
+                &crate::agent::loop_::build_compaction_transcript(&messages),
+            );
+            Some(
+                crate::agent::loop_::summarize_compaction_transcript(
+                    summarizer.provider.as_ref(),
+                    &summarizer.model,
+                    &transcript,
+                )
+                .await,
+            )
+        }
+        None => None,
+    };
+
+    let owned_key = key.to_string();
+    blocking(Arc::clone(store), move |store| {
+        if let Some(summary) = summary {
+            store.set_summary(&owned_key, &summary)?;
+        }
+        store.trim_history(&owned_key, keep_recent)
+    })
+    .await
+}
+
+/// Transcript handed to the summarizer. The previous summary is passed as a
+/// labelled section rather than as another turn, so facts it carries are
+/// merged into the new summary instead of being re-compressed as chatter.
+fn summarization_source(previous: Option<&str>, transcript: &str) -> String {
+    match previous.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(previous) => format!(
+            "Earlier summary: {previous}\n\n\
+             Keep every fact from the earlier summary that the newer turns do not \
+             supersede, and add what the newer turns establish.\n\n\
+             Newer turns:\n{transcript}"
+        ),
+        None => transcript.to_string(),
+    }
+}
+
+fn to_chat_message(message: &StoredMessage) -> ChatMessage {
+    ChatMessage {
+        role: message.role.clone(),
+        content: message.content.clone(),
+    }
+}
+
+/// Compact every session [`SqliteSessionStore::list_sessions_needing_trim`]
+/// selects, at most `config.concurrency` at a time. A failing session is
+/// logged and skipped. Totals are added to [`snapshot`].
+pub async fn run_compaction(
+    store: &Arc<SqliteSessionStore>,
+    config: &SessionCompactionConfig,
+    summarizer: Option<&Summarizer>,
+) -> anyhow::Result<CompactionReport> {
+    let (threshold, idle_hours) = (config.message_threshold, config.idle_hours);
+    let candidates = blocking(Arc::clone(store), move |store| {
+        store.list_sessions_needing_trim(threshold, idle_hours)
+    })
+    .await?;
+    let summarizer = summarizer.filter(|_| config.summarize);
+
+    let mut results = stream::iter(candidates.into_iter().map(|session| async move {
+        let outcome = compact_session(store, &session.key, config.keep_recent, summarizer).await;
+        (session.key, outcome)
+    }))
+    .buffer_unordered(config.concurrency.clamp(1, 2));
+
+    let mut report = CompactionReport::default();
+    while let Some((key, outcome)) = results.next().await {
+        match outcome {
+            Ok(0) => {}
+            Ok(removed) => {
+                report.sessions_compacted += 1;
+                report.messages_removed += removed;
+            }
+            Err(e) => tracing::warn!("session compaction failed for {key}: {e}"),
+        }
+    }
+
+    record(report);
+    Ok(report)
+}
+
+/// Run [`run_compaction`] every `config.interval_secs` for the lifetime of
+/// the process. Failures are logged and retried next interval.
+pub fn spawn_compaction(
+    store: Arc<SqliteSessionStore>,
+    config: SessionCompactionConfig,
+    summarizer: Option<Summarizer>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            match run_compaction(&store, &config, summarizer.as_ref()).await {
+                Ok(report) if report.sessions_compacted > 0 => tracing::info!(
+                    "session compaction trimmed {} message(s) across {} session(s)",
+                    report.messages_removed,
+                    report.sessions_compacted
+                ),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("session compaction failed: {e}"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use chrono::Local;
+    use rusqlite::params;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    struct CountingProvider {
+        calls: Arc<AtomicUsize>,
+        transcripts: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Provider for CountingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.transcripts.lock().push(message.to_string());
+            Ok("- user counted turns".into())
+        }
+    }
+
+    fn idle_store(sessions: &[(&str, usize)]) -> (TempDir, Arc<SqliteSessionStore>) {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        for (key, count) in sessions {
+            for i in 0..*count {
+                store
+                    .append_message(key, "user", &format!("turn {i}"))
+                    .unwrap();
+            }
+        }
+        let stale = (Local::now() - chrono::Duration::hours(48)).to_rfc3339();
+        store
+            .conn
+            .lock()
+            .execute("UPDATE sessions SET updated_at = ?1", params![stale])
+            .unwrap();
+        (tmp, Arc::new(store))
+    }
+
+    fn test_config(summarize: bool) -> SessionCompactionConfig {
+        SessionCompactionConfig {
+            message_threshold: 5,
+            keep_recent: 2,
+            idle_hours: 24,
+            summarize,
+            ..SessionCompactionConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn truncation_only_mode_trims_without_llm_calls() {
+        let (_tmp, store) = idle_store(&[("chatty", 8), ("quiet", 3)]);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let summarizer = Summarizer {
+            provider: Arc::new(CountingProvider {
+                calls: Arc::clone(&calls),
+                transcripts: Arc::default(),
+            }),
+            model: "test-model".into(),
+        };
+
+        let report = run_compaction(&store, &test_config(false), Some(&summarizer))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            report,
+            CompactionReport {
+                sessions_compacted: 1,
+                messages_removed: 6,
+            }
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert!(store.summary("chatty").unwrap().is_none());
+        let kept: Vec<String> = store
+            .load_history("chatty", None)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.content)
+            .collect();
+        assert_eq!(kept, vec!["turn 6", "turn 7"]);
+        assert_eq!(store.load_history("quiet", None).unwrap().len(), 3);
+        assert!(snapshot().messages_removed >= 6);
+    }
+
+    #[tokio::test]
+    async fn summarize_mode_stores_summary_before_trimming() {
+        let (_tmp, store) = idle_store(&[("first", 6), ("second", 7)]);
+        store.set_summary("first", "- earlier facts").unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let transcripts = Arc::new(Mutex::new(Vec::new()));
+        let summarizer = Summarizer {
+            provider: Arc::new(CountingProvider {
+                calls: Arc::clone(&calls),
+                transcripts: Arc::clone(&transcripts),
+            }),
+            model: "test-model".into(),
+        };
+
+        let report = run_compaction(&store, &test_config(true), Some(&summarizer))
+            .await
+            .unwrap();
+
+        assert!(transcripts
+            .lock()
+            .iter()
+            .all(|transcript| transcript.contains("USER: turn 0")));
+        assert_eq!(report.sessions_compacted, 2);
+        assert_eq!(report.messages_removed, 4 + 5);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(transcripts
+            .lock()
+            .iter()
+            .any(|transcript| transcript.contains("Earlier summary: - earlier facts")));
+        assert_eq!(
+            store.summary("first").unwrap().as_deref(),
+            Some("- user counted turns")
+        );
+        let history: Vec<String> = store
+            .summary_history("first", None)
+            .unwrap()
+            .into_iter()
+            .map(|s| s.summary)
+            .collect();
+        assert_eq!(history, vec!["- user counted turns", "- earlier facts"]);
+        assert_eq!(store.load_history("second", None).unwrap().len(), 2);
+        // A second pass finds nothing left to do.
+        let again = run_compaction(&store, &test_config(true), Some(&summarizer))
+            .await
+            .unwrap();
+        assert_eq!(again, CompactionReport::default());
+    }
+
+    #[tokio::test]
+    async fn pinned_turns_are_neither_summarized_nor_trimmed() {
+        let (_tmp, store) = idle_store(&[("pinned", 6)]);
+        store.pin_message("pinned", Some(5)).unwrap().unwrap();
+        let transcripts = Arc::new(Mutex::new(Vec::new()));
+        let summarizer = Summarizer {
+            provider: Arc::new(CountingProvider {
+                calls: Arc::new(AtomicUsize::new(0)),
+                transcripts: Arc::clone(&transcripts),
+            }),
+            model: "test-model".into(),
+        };
+
+        let removed = compact_session(&store, "pinned", 2, Some(&summarizer))
+            .await
+            .unwrap();
+
+        assert_eq!(removed, 3);
+        let transcript = transcripts.lock()[0].clone();
+        assert!(transcript.contains("USER: turn 0"), "{transcript}");
+        assert!(!transcript.contains("turn 1"), "{transcript}");
+        let kept: Vec<String> = store
+            .load_history("pinned", None)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.content)
+            .collect();
+        assert_eq!(kept, vec!["turn 1", "turn 4", "turn 5"]);
+    }
+}
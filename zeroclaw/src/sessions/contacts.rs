@@ -0,0 +1,377 @@
+//! Sender directory for channel conversations, persisted in the `contacts`
+//! table.
+//!
+//! The channel runtime upserts a row for every inbound message, keeping the
+//! display name the adapter reported (Telegram full name, Slack profile,
+//! WhatsApp profile name) and the first/last time the sender was seen. When
+//! one session holds turns from more than one sender, [`label_turns_by_sender`]
+//! prefixes each user turn with who said it so the model can tell people
+//! apart; the `contacts_lookup` tool resolves ids on demand.
+
+use super::SqliteSessionStore;
+use crate::providers::ChatMessage;
+use chrono::Local;
+use rusqlite::{params, OptionalExtension, Row};
+use std::collections::HashSet;
+
+/// One known sender on one channel.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Contact {
+    pub channel: String,
+    pub sender_id: String,
+    pub display_name: Option<String>,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub notes: Option<String>,
+}
+
+impl Contact {
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            channel: row.get(0)?,
+            sender_id: row.get(1)?,
+            display_name: row.get(2)?,
+            first_seen: row.get(3)?,
+            last_seen: row.get(4)?,
+            notes: row.get(5)?,
+        })
+    }
+
+    /// Display name, falling back to the raw sender id.
+    pub fn label(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.sender_id)
+    }
+}
+
+/// A stored turn together with who sent it (user turns only).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributedTurn {
+    pub role: String,
+    pub content: String,
+    pub sender: Option<String>,
+    pub display_name: Option<String>,
+}
+
+const CONTACT_COLUMNS: &str = "channel, sender_id, display_name, first_seen, last_seen, notes";
+
+impl SqliteSessionStore {
+    /// Record that `sender_id` was seen on `channel` now. A non-blank
+    /// `display_name` replaces the stored one; `None` keeps it. `first_seen`
+    /// and notes are never touched by an upsert.
+    pub fn upsert_contact(
+        &self,
+        channel: &str,
+        sender_id: &str,
+        display_name: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let display_name = display_name.map(str::trim).filter(|name| !name.is_empty());
+        let now = Local::now().to_rfc3339();
+        self.conn.lock().execute(
+            "INSERT INTO contacts (channel, sender_id, display_name, first_seen, last_seen)
+             VALUES (?1, ?2, ?3, ?4, ?4)
+             ON CONFLICT(channel, sender_id) DO UPDATE SET
+                 display_name = COALESCE(excluded.display_name, contacts.display_name),
+                 last_seen = excluded.last_seen",
+            params![channel, sender_id, display_name, now],
+        )?;
+        Ok(())
+    }
+
+    pub fn contact(&self, channel: &str, sender_id: &str) -> anyhow::Result<Option<Contact>> {
+        let conn = self.conn.lock();
+        let contact = conn
+            .query_row(
+                &format!(
+                    "SELECT {CONTACT_COLUMNS} FROM contacts WHERE channel = ?1 AND sender_id = ?2"
+                ),
+                params![channel, sender_id],
+                Contact::from_row,
+            )
+            .optional()?;
+        Ok(contact)
+    }
+
+    /// Contacts whose id equals `query` or whose display name contains it
+    /// (case-insensitive), most recently seen first.
+    pub fn find_contacts(
+        &self,
+        query: &str,
+        channel: Option<&str>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<Contact>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let pattern = format!(
+            "%{}%",
+            query
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_")
+        );
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {CONTACT_COLUMNS} FROM contacts
+             WHERE (sender_id = ?1 OR display_name LIKE ?2 ESCAPE '\\')
+               AND (?3 IS NULL OR channel = ?3)
+             ORDER BY (sender_id = ?1) DESC, last_seen DESC
+             LIMIT ?4"
+        ))?;
+        let rows = stmt.query_map(params![query, pattern, channel, limit], Contact::from_row)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Replace the free-form notes for a contact. Returns `false` if the
+    /// contact has never been seen.
+    pub fn set_contact_notes(
+        &self,
+        channel: &str,
+        sender_id: &str,
+        notes: Option<&str>,
+    ) -> anyhow::Result<bool> {
+        let updated = self.conn.lock().execute(
+            "UPDATE contacts SET notes = ?3 WHERE channel = ?1 AND sender_id = ?2",
+            params![channel, sender_id, notes],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// The newest `limit` turns of `key` in chronological order, with the
+    /// sender of each user turn resolved against `channel`'s contacts.
+    pub fn load_attributed_turns(
+        &self,
+        key: &str,
+        channel: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<AttributedTurn>> {
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT m.role, m.content, m.sender, c.display_name FROM session_messages m
+             LEFT JOIN contacts c ON c.channel = ?2 AND c.sender_id = m.sender
+             WHERE m.session_key = ?1
+             ORDER BY m.id DESC
+             LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![key, channel, limit], |row| {
+            Ok(AttributedTurn {
+                role: row.get(0)?,
+                content: row.get(1)?,
+                sender: row.get(2)?,
+                display_name: row.get(3)?,
+            })
+        })?;
+        let mut turns = rows.collect::<Result<Vec<_>, _>>()?;
+        turns.reverse();
+        Ok(turns)
+    }
+}
+
+/// Prefix each user turn in `turns` with `[name] ` when `attributed` (the
+/// stored copy of the same conversation) shows more than one distinct
+/// sender. Turns are matched by content from the newest backwards, so
+/// in-memory history that was trimmed or compacted simply stays unlabeled
+/// where it no longer matches. Returns whether labels were applied.
+pub fn label_turns_by_sender(turns: &mut [ChatMessage], attributed: &[AttributedTurn]) -> bool {
+    let senders: HashSet<&str> = attributed
+        .iter()
+        .filter(|turn| turn.role == "user")
+        .filter_map(|turn| turn.sender.as_deref())
+        .collect();
+    if senders.len() < 2 {
+        return false;
+    }
+
+    let mut remaining = attributed;
+    for turn in turns.iter_mut().rev().filter(|turn| turn.role == "user") {
+        let Some(pos) = remaining
+            .iter()
+            .rposition(|stored| stored.role == "user" && stored.content == turn.content)
+        else {
+            continue;
+        };
+        let stored = &remaining[pos];
+        if let Some(sender) = stored.sender.as_deref() {
+            let name = stored.display_name.as_deref().unwrap_or(sender);
+            turn.content = format!("[{name}] {}", turn.content);
+        }
+        remaining = &remaining[..pos];
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn temp_store() -> (TempDir, SqliteSessionStore) {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        (tmp, store)
+    }
+
+    fn user_turn(content: &str, sender: &str, name: Option<&str>) -> AttributedTurn {
+        AttributedTurn {
+            role: "user".into(),
+            content: content.into(),
+            sender: Some(sender.into()),
+            display_name: name.map(String::from),
+        }
+    }
+
+    fn assistant_turn(content: &str) -> AttributedTurn {
+        AttributedTurn {
+            role: "assistant".into(),
+            content: content.into(),
+            sender: None,
+            display_name: None,
+        }
+    }
+
+    #[test]
+    fn upsert_keeps_first_seen_notes_and_known_name() {
+        let (_tmp, store) = temp_store();
+        store.upsert_contact("slack", "U03AB12", None).unwrap();
+        let first = store.contact("slack", "U03AB12").unwrap().unwrap();
+        assert_eq!(first.display_name, None);
+        assert_eq!(first.label(), "U03AB12");
+
+        store
+            .upsert_contact("slack", "U03AB12", Some(" Alice "))
+            .unwrap();
+        assert!(store
+            .set_contact_notes("slack", "U03AB12", Some("prefers metric units"))
+            .unwrap());
+        // A later message without a name must not erase the known one.
+        store
+            .upsert_contact("slack", "U03AB12", Some("  "))
+            .unwrap();
+
+        let contact = store.contact("slack", "U03AB12").unwrap().unwrap();
+        assert_eq!(contact.display_name.as_deref(), Some("Alice"));
+        assert_eq!(contact.notes.as_deref(), Some("prefers metric units"));
+        assert_eq!(contact.first_seen, first.first_seen);
+        assert!(contact.last_seen >= first.last_seen);
+
+        store
+            .upsert_contact("slack", "U03AB12", Some("Alice W."))
+            .unwrap();
+        assert_eq!(
+            store
+                .contact("slack", "U03AB12")
+                .unwrap()
+                .unwrap()
+                .display_name
+                .as_deref(),
+            Some("Alice W.")
+        );
+        assert!(!store.set_contact_notes("slack", "nobody", None).unwrap());
+    }
+
+    #[test]
+    fn contacts_are_scoped_per_channel_and_searchable() {
+        let (_tmp, store) = temp_store();
+        store
+            .upsert_contact("slack", "U1", Some("Alice Walker"))
+            .unwrap();
+        store.upsert_contact("telegram", "U1", Some("Bob")).unwrap();
+        store
+            .upsert_contact("telegram", "42", Some("alice_100%"))
+            .unwrap();
+
+        assert_eq!(
+            store.contact("telegram", "U1").unwrap().unwrap().label(),
+            "Bob"
+        );
+        assert_eq!(store.find_contacts("ALICE", None, 10).unwrap().len(), 2);
+        let by_id = store.find_contacts("U1", Some("slack"), 10).unwrap();
+        assert_eq!(by_id.len(), 1);
+        assert_eq!(by_id[0].label(), "Alice Walker");
+        // LIKE wildcards in the query are matched literally.
+        assert_eq!(store.find_contacts("100%", None, 10).unwrap().len(), 1);
+        assert_eq!(store.find_contacts("_", None, 10).unwrap().len(), 1);
+        assert!(store.find_contacts(" ", None, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn attributed_turns_resolve_names_from_contacts() {
+        let (_tmp, store) = temp_store();
+        store.upsert_contact("slack", "U1", Some("Alice")).unwrap();
+        store
+            .append_message_from("slack_C1", "user", "hi all", Some("U1"))
+            .unwrap();
+        store
+            .append_message_from("slack_C1", "user", "hello", Some("U2"))
+            .unwrap();
+        store
+            .append_message("slack_C1", "assistant", "hey both")
+            .unwrap();
+
+        let turns = store
+            .load_attributed_turns("slack_C1", "slack", 10)
+            .unwrap();
+        assert_eq!(
+            turns,
+            vec![
+                user_turn("hi all", "U1", Some("Alice")),
+                user_turn("hello", "U2", None),
+                assistant_turn("hey both"),
+            ]
+        );
+        assert_eq!(
+            store
+                .load_attributed_turns("slack_C1", "slack", 1)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn single_sender_sessions_are_left_unlabeled() {
+        let mut turns = vec![
+            ChatMessage::user("first"),
+            ChatMessage::assistant("ok"),
+            ChatMessage::user("second"),
+        ];
+        let attributed = vec![
+            user_turn("first", "U1", Some("Alice")),
+            assistant_turn("ok"),
+            user_turn("second", "U1", Some("Alice")),
+        ];
+        assert!(!label_turns_by_sender(&mut turns, &attributed));
+        assert_eq!(turns[0].content, "first");
+        assert_eq!(turns[2].content, "second");
+    }
+
+    #[test]
+    fn multi_sender_sessions_label_each_user_turn() {
+        let mut turns = vec![
+            ChatMessage::user("same words"),
+            ChatMessage::assistant("ok"),
+            ChatMessage::user("same words"),
+            ChatMessage::assistant("ok"),
+            ChatMessage::user("compacted and no longer matching"),
+        ];
+        let attributed = vec![
+            user_turn("same words", "U1", Some("Alice")),
+            assistant_turn("ok"),
+            user_turn("same words", "U2", None),
+            assistant_turn("ok"),
+            user_turn(
+                "compacted and no longer matching in full",
+                "U1",
+                Some("Alice"),
+            ),
+        ];
+        assert!(label_turns_by_sender(&mut turns, &attributed));
+        assert_eq!(turns[0].content, "[Alice] same words");
+        assert_eq!(turns[1].content, "ok");
+        assert_eq!(turns[2].content, "[U2] same words");
+        assert_eq!(turns[4].content, "compacted and no longer matching");
+    }
+}
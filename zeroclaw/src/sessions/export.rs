@@ -0,0 +1,588 @@
+//! Export a stored session as Markdown or JSON.
+//!
+//! [`SqliteSessionStore::export_session`] walks a session's turns a page at
+//! a time and hands every rendered chunk to a sink, so the CLI can write
+//! straight to a file and the gateway can stream an HTTP body without the
+//! whole transcript ever sitting in memory. Markdown keeps tool calls and
+//! their results together under the assistant turn that issued them and
+//! refers to attachments by filename and id. JSON turns carry their row id,
+//! which `POST /api/messages/{id}/redact` takes.
+
+use super::{SqliteSessionStore, StoredMessage};
+use crate::attachments::{parse_attachment_id, ATTACHMENT_URI_PREFIX};
+use chrono::{DateTime, Local};
+use rusqlite::params;
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// Turns fetched per query; the connection lock is released between pages.
+const EXPORT_PAGE_SIZE: usize = 200;
+
+const TOOL_RESULTS_PREFIX: &str = "[Tool results]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::Json => "application/json",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "json" => Ok(Self::Json),
+            other => anyhow::bail!("unknown export format '{other}' (expected markdown or json)"),
+        }
+    }
+}
+
+/// Session metadata rendered ahead of the turns.
+#[derive(Debug, Clone)]
+struct ExportHeader {
+    key: String,
+    title: Option<String>,
+    summary: Option<String>,
+    exported_at: String,
+}
+
+impl SqliteSessionStore {
+    /// Render every turn of `key` in `format`, passing the output to `sink`
+    /// chunk by chunk: a preamble, one chunk per turn, then a closing chunk.
+    /// Returns `false` (without calling `sink`) when the session does not
+    /// exist. An error from `sink` aborts the export.
+    pub fn export_session(
+        &self,
+        key: &str,
+        format: ExportFormat,
+        sink: impl FnMut(&str) -> anyhow::Result<()>,
+    ) -> anyhow::Result<bool> {
+        self.export_session_paged(key, format, EXPORT_PAGE_SIZE, sink)
+    }
+
+    fn export_session_paged(
+        &self,
+        key: &str,
+        format: ExportFormat,
+        page_size: usize,
+        mut sink: impl FnMut(&str) -> anyhow::Result<()>,
+    ) -> anyhow::Result<bool> {
+        if !self.session_exists(key)? {
+            return Ok(false);
+        }
+        let header = ExportHeader {
+            key: key.to_string(),
+            title: self.title(key)?,
+            summary: self.summary(key)?,
+            exported_at: Local::now().to_rfc3339(),
+        };
+
+        let mut renderer = Renderer::new(format);
+        sink(&renderer.preamble(&header))?;
+        let mut after_id = 0;
+        loop {
+            let page = self.messages_after(key, after_id, page_size)?;
+            let Some((last_id, _)) = page.last() else {
+                break;
+            };
+            after_id = *last_id;
+            let full_page = page.len() == page_size;
+            for (id, message) in &page {
+                sink(&renderer.message(*id, message))?;
+            }
+            if !full_page {
+                break;
+            }
+        }
+        sink(&renderer.finish())?;
+        Ok(true)
+    }
+
+    /// Up to `limit` turns of `key` with an id above `after_id`, oldest first.
+    fn messages_after(
+        &self,
+        key: &str,
+        after_id: i64,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(i64, StoredMessage)>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, role, content, created_at FROM session_messages
+             WHERE session_key = ?1 AND id > ?2
+             ORDER BY id ASC
+             LIMIT ?3",
+        )?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let rows = stmt.query_map(params![key, after_id, limit], |row| {
+            Ok((
+                row.get(0)?,
+                StoredMessage {
+                    role: row.get(1)?,
+                    content: row.get(2)?,
+                    created_at: row.get(3)?,
+                },
+            ))
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+}
+
+/// Chunk renderer; counts turns so JSON can separate them and an empty
+/// markdown export says so.
+struct Renderer {
+    format: ExportFormat,
+    written: usize,
+}
+
+impl Renderer {
+    fn new(format: ExportFormat) -> Self {
+        Self { format, written: 0 }
+    }
+
+    fn preamble(&self, header: &ExportHeader) -> String {
+        match self.format {
+            ExportFormat::Markdown => markdown_preamble(header),
+            ExportFormat::Json => {
+                let meta = serde_json::json!({
+                    "session_key": header.key,
+                    "title": header.title,
+                    "summary": header.summary,
+                    "exported_at": header.exported_at,
+                });
+                // Reopen the object so messages can be appended one by one.
+                let meta = meta.to_string();
+                format!("{},\"messages\":[\n", &meta[..meta.len() - 1])
+            }
+        }
+    }
+
+    fn message(&mut self, id: i64, message: &StoredMessage) -> String {
+        let first = self.written == 0;
+        self.written += 1;
+        match self.format {
+            ExportFormat::Markdown => markdown_message(message),
+            ExportFormat::Json => {
+                let value = serde_json::json!({
+                    "id": id,
+                    "role": message.role,
+                    "content": message.content,
+                    "created_at": message.created_at,
+                });
+                if first {
+                    value.to_string()
+                } else {
+                    format!(",\n{value}")
+                }
+            }
+        }
+    }
+
+    fn finish(&self) -> String {
+        match self.format {
+            ExportFormat::Markdown if self.written == 0 => "_No messages._\n".into(),
+            ExportFormat::Markdown => String::new(),
+            ExportFormat::Json => "\n]}\n".into(),
+        }
+    }
+}
+
+fn markdown_preamble(header: &ExportHeader) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# {}\n",
+        header.title.as_deref().unwrap_or(&header.key)
+    );
+    let _ = writeln!(out, "- Session: `{}`", header.key);
+    let _ = writeln!(
+        out,
+        "- Exported: {}\n",
+        display_timestamp(&header.exported_at)
+    );
+    if let Some(summary) = header.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+        let _ = writeln!(out, "## Summary\n\n{}\n", summary.trim());
+    }
+    out.push_str("## Conversation\n\n");
+    out
+}
+
+fn markdown_message(message: &StoredMessage) -> String {
+    let timestamp = display_timestamp(&message.created_at);
+    // Tool results stay under the assistant turn that requested them.
+    if let Some(results) = tool_results_body(message) {
+        let mut out = format!("**Tool results** · {timestamp}\n\n");
+        for (name, body) in split_tool_results(results) {
+            if let Some(name) = name {
+                let _ = writeln!(out, "`{name}`\n");
+            }
+            out.push_str(&fenced("text", body));
+        }
+        return out;
+    }
+
+    let mut out = format!("### {} · {timestamp}\n\n", role_label(&message.role));
+    if message.role == "assistant" {
+        let (text, calls) = split_tool_calls(&message.content);
+        if !text.is_empty() {
+            let _ = writeln!(out, "{}\n", render_attachments(&text));
+        }
+        for call in calls {
+            out.push_str("**Tool call**\n\n");
+            out.push_str(&fenced("json", &call));
+        }
+    } else {
+        let _ = writeln!(out, "{}\n", render_attachments(message.content.trim()));
+    }
+    out
+}
+
+/// Result payload of a tool-result turn (prompt-mode `[Tool results]` user
+/// turns and native `tool` turns), or `None` for ordinary turns.
+fn tool_results_body(message: &StoredMessage) -> Option<&str> {
+    match message.role.as_str() {
+        "user" => message
+            .content
+            .strip_prefix(TOOL_RESULTS_PREFIX)
+            .map(str::trim_start),
+        "tool" => Some(message.content.as_str()),
+        _ => None,
+    }
+}
+
+/// Split `<tool_result name="…">…</tool_result>` sections; content without
+/// tags is returned as a single unnamed result.
+fn split_tool_results(content: &str) -> Vec<(Option<&str>, &str)> {
+    let mut results = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("<tool_result") {
+        let Some(open_end) = rest[start..].find('>').map(|i| start + i + 1) else {
+            break;
+        };
+        let Some(close) = rest[open_end..]
+            .find("</tool_result>")
+            .map(|i| open_end + i)
+        else {
+            break;
+        };
+        let name = tag_attribute(&rest[start..open_end], "name")
+            .or_else(|| tag_attribute(&rest[start..open_end], "id"));
+        results.push((name, rest[open_end..close].trim_matches('\n')));
+        rest = &rest[close + "</tool_result>".len()..];
+    }
+    if results.is_empty() {
+        results.push((None, content.trim()));
+    }
+    results
+}
+
+fn tag_attribute<'a>(tag: &'a str, attribute: &str) -> Option<&'a str> {
+    let marker = format!("{attribute}=\"");
+    let start = tag.find(&marker)? + marker.len();
+    let end = tag[start..].find('"')? + start;
+    Some(&tag[start..end])
+}
+
+/// Separate an assistant turn into its prose and its tool calls, covering
+/// both `<tool_call>` tags and native `{"content", "tool_calls"}` JSON.
+fn split_tool_calls(content: &str) -> (String, Vec<String>) {
+    if let Ok(serde_json::Value::Object(native)) = serde_json::from_str(content) {
+        if let Some(calls) = native.get("tool_calls").and_then(|c| c.as_array()) {
+            let text = native
+                .get("content")
+                .and_then(|c| c.as_str())
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            let calls = calls.iter().map(pretty_json).collect();
+            return (text, calls);
+        }
+    }
+
+    let mut text = String::new();
+    let mut calls = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("<tool_call>") {
+        let body_start = start + "<tool_call>".len();
+        let Some(end) = rest[body_start..]
+            .find("</tool_call>")
+            .map(|i| body_start + i)
+        else {
+            break;
+        };
+        text.push_str(&rest[..start]);
+        let body = rest[body_start..end].trim();
+        calls.push(
+            serde_json::from_str::<serde_json::Value>(body)
+                .map_or_else(|_| body.to_string(), |value| pretty_json(&value)),
+        );
+        rest = &rest[end + "</tool_call>".len()..];
+    }
+    text.push_str(rest);
+    (text.trim().to_string(), calls)
+}
+
+fn pretty_json(value: &serde_json::Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+/// Rewrite `[Attachments]` lines (see
+/// [`crate::attachments::describe_attachments`]) to name each file by
+/// filename and id instead of the prompt guidance meant for the model.
+fn render_attachments(content: &str) -> String {
+    if !content.contains(ATTACHMENT_URI_PREFIX) {
+        return content.to_string();
+    }
+    content
+        .lines()
+        .map(|line| attachment_line(line).unwrap_or_else(|| line.to_string()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn attachment_line(line: &str) -> Option<String> {
+    let entry = line.strip_prefix("- ")?;
+    let uri_start = entry.find(ATTACHMENT_URI_PREFIX)?;
+    let id = entry[uri_start..]
+        .split_whitespace()
+        .next()
+        .and_then(parse_attachment_id)?;
+    let described = entry[..uri_start].trim_end();
+    let filename = described
+        .rfind(" (")
+        .map_or(described, |paren| &described[..paren]);
+    Some(format!("- 📎 {filename} (attachment `{id}`)"))
+}
+
+/// Fenced code block whose fence is longer than any backtick run inside.
+fn fenced(info: &str, body: &str) -> String {
+    let longest_run = body.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    format!("{fence}{info}\n{body}\n{fence}\n\n")
+}
+
+fn role_label(role: &str) -> String {
+    let mut chars = role.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+fn display_timestamp(raw: &str) -> String {
+    DateTime::parse_from_rfc3339(raw).map_or_else(
+        |_| raw.to_string(),
+        |ts| ts.format("%Y-%m-%d %H:%M:%S %:z").to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn fixture() -> Vec<StoredMessage> {
+        let turn = |role: &str, content: &str, at: &str| StoredMessage {
+            role: role.into(),
+            content: content.into(),
+            created_at: format!("2026-03-02T10:{at}+01:00"),
+        };
+        vec![
+            turn(
+                "user",
+                "why is CI red?\n\n[Attachments]\n- build.log (text/plain, 42 bytes) \
+                 attachment://0f3c-aa — read it with the read_attachment tool (id \"0f3c-aa\")",
+                "00:00",
+            ),
+            turn(
+                "assistant",
+                "Let me look.\n<tool_call>\n{\"name\":\"shell\",\"arguments\":{\"command\":\"cargo test\"}}\n</tool_call>",
+                "00:05",
+            ),
+            turn(
+                "user",
+                "[Tool results]\n<tool_result name=\"shell\">\n```\ntest foo ... FAILED\n```\n</tool_result>",
+                "00:09",
+            ),
+            turn("assistant", "`foo` fails on main.", "00:12"),
+        ]
+    }
+
+    #[test]
+    fn markdown_renders_roles_tool_pairs_and_attachments() {
+        let header = ExportHeader {
+            key: "telegram_alice".into(),
+            title: Some("CI debugging".into()),
+            summary: Some("- user asked about CI".into()),
+            exported_at: "2026-03-03T08:00:00+01:00".into(),
+        };
+        let mut renderer = Renderer::new(ExportFormat::Markdown);
+        let mut out = renderer.preamble(&header);
+        for message in fixture() {
+            out.push_str(&renderer.message(0, &message));
+        }
+        out.push_str(&renderer.finish());
+
+        let expected = "\
+# CI debugging
+
+- Session: `telegram_alice`
+- Exported: 2026-03-03 08:00:00 +01:00
+
+## Summary
+
+- user asked about CI
+
+## Conversation
+
+### User · 2026-03-02 10:00:00 +01:00
+
+why is CI red?
+
+[Attachments]
+- 📎 build.log (attachment `0f3c-aa`)
+
+### Assistant · 2026-03-02 10:00:05 +01:00
+
+Let me look.
+
+**Tool call**
+
+```json
+{
+  \"arguments\": {
+    \"command\": \"cargo test\"
+  },
+  \"name\": \"shell\"
+}
+```
+
+**Tool results** · 2026-03-02 10:00:09 +01:00
+
+`shell`
+
+````text
+```
+test foo ... FAILED
+```
+````
+
+### Assistant · 2026-03-02 10:00:12 +01:00
+
+`foo` fails on main.
+
+";
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn native_tool_calls_and_tool_turns_render_as_blocks() {
+        let call = StoredMessage {
+            role: "assistant".into(),
+            content: r#"{"content":"checking","tool_calls":[{"id":"c1","name":"shell","arguments":"{}"}]}"#
+                .into(),
+            created_at: "not a timestamp".into(),
+        };
+        let rendered = markdown_message(&call);
+        assert!(rendered.starts_with("### Assistant · not a timestamp\n\nchecking\n\n"));
+        assert!(rendered.contains("```json\n{\n  \"arguments\": \"{}\""));
+
+        let result = StoredMessage {
+            role: "tool".into(),
+            content: "exit 0".into(),
+            created_at: "x".into(),
+        };
+        assert_eq!(
+            markdown_message(&result),
+            "**Tool results** · x\n\n```text\nexit 0\n```\n\n"
+        );
+    }
+
+    #[test]
+    fn export_streams_one_chunk_per_message_across_pages() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        for i in 0..5 {
+            let role = if i % 2 == 0 { "user" } else { "assistant" };
+            store
+                .append_message("k", role, &format!("turn {i}"))
+                .unwrap();
+        }
+        store
+            .append_message("other", "user", "not exported")
+            .unwrap();
+
+        let mut chunks = Vec::new();
+        let found = store
+            .export_session_paged("k", ExportFormat::Json, 2, |chunk| {
+                chunks.push(chunk.to_string());
+                Ok(())
+            })
+            .unwrap();
+        assert!(found);
+        // Preamble, five turns, closing chunk: page boundaries never split
+        // or repeat a turn.
+        assert_eq!(chunks.len(), 7);
+        for (i, chunk) in chunks[1..6].iter().enumerate() {
+            let turn: serde_json::Value =
+                serde_json::from_str(chunk.trim_start_matches(",\n")).unwrap();
+            assert_eq!(turn["content"], format!("turn {i}"));
+        }
+        let document: serde_json::Value = serde_json::from_str(&chunks.concat()).unwrap();
+        assert_eq!(document["session_key"], "k");
+        assert_eq!(document["messages"].as_array().unwrap().len(), 5);
+
+        // An exact multiple of the page size ends cleanly too.
+        let mut markdown = Vec::new();
+        store
+            .export_session_paged("k", ExportFormat::Markdown, 5, |chunk| {
+                markdown.push(chunk.to_string());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(markdown.len(), 7);
+        assert!(markdown[5].contains("turn 4"));
+    }
+
+    #[test]
+    fn export_reports_missing_session_and_sink_errors() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        assert!(!store
+            .export_session("missing", ExportFormat::Markdown, |_| unreachable!())
+            .unwrap());
+
+        store.append_message("k", "user", "hi").unwrap();
+        let err = store
+            .export_session("k", ExportFormat::Markdown, |_| {
+                anyhow::bail!("client gone")
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("client gone"));
+    }
+
+    #[test]
+    fn format_parses_aliases() {
+        assert_eq!(
+            "md".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Markdown
+        );
+        assert_eq!("JSON".parse::<ExportFormat>().unwrap(), ExportFormat::Json);
+        assert!("pdf".parse::<ExportFormat>().is_err());
+    }
+}
@@ -0,0 +1,262 @@
+//! Linked sessions: one conversation continued on several channels.
+//!
+//! Channel sessions are keyed per channel and sender, so a chat started on
+//! WhatsApp and picked up in webchat would normally start from scratch. A
+//! row in `session_links` makes a session key an alias of a canonical key;
+//! the channel runtime resolves every inbound message through
+//! [`SqliteSessionStore::canonical_key`] before reading or recording
+//! history, settings, pins and summaries, so all linked channels share one
+//! thread. Replies still go out on the channel the message arrived on.
+//!
+//! Users link from chat with a short-lived code: the `link_sessions` tool
+//! issues one on the first channel ([`SqliteSessionStore::create_link_code`])
+//! and redeems it on the second ([`SqliteSessionStore::redeem_link_code`]).
+//! Operators can link keys directly with `POST /api/sessions/link`.
+
+use super::SqliteSessionStore;
+use rusqlite::{params, OptionalExtension};
+use std::time::Duration;
+
+/// How long a link code stays redeemable.
+pub const LINK_CODE_TTL: Duration = Duration::from_secs(10 * 60);
+
+fn unix_now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+impl SqliteSessionStore {
+    /// Key whose history `key` reads and writes: the canonical session it is
+    /// linked to, or `key` itself.
+    pub fn canonical_key(&self, key: &str) -> anyhow::Result<String> {
+        let conn = self.conn.lock();
+        let canonical: Option<String> = conn
+            .query_row(
+                "SELECT canonical_key FROM session_links WHERE alias_key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(canonical.unwrap_or_else(|| key.to_string()))
+    }
+
+    /// Sessions linked to the canonical session `key`, oldest link first.
+    pub fn linked_sessions(&self, key: &str) -> anyhow::Result<Vec<String>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT alias_key FROM session_links WHERE canonical_key = ?1
+             ORDER BY created_at, alias_key",
+        )?;
+        let rows = stmt.query_map(params![key], |row| row.get(0))?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// Make `alias` read and write the session of `canonical` (resolved
+    /// through its own link, if any). Sessions already linked to `alias`
+    /// move along with it. The history recorded under `alias` before the
+    /// link stays in the store but is no longer read. Returns the canonical
+    /// key.
+    pub fn link_sessions(&self, alias: &str, canonical: &str) -> anyhow::Result<String> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let canonical = tx
+            .query_row(
+                "SELECT canonical_key FROM session_links WHERE alias_key = ?1",
+                params![canonical],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+            .unwrap_or_else(|| canonical.to_string());
+        if alias == canonical {
+            anyhow::bail!("Cannot link session {alias} to itself");
+        }
+
+        tx.execute(
+            "UPDATE session_links SET canonical_key = ?2 WHERE canonical_key = ?1",
+            params![alias, canonical],
+        )?;
+        tx.execute(
+            "INSERT INTO session_links (alias_key, canonical_key, created_at)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(alias_key) DO UPDATE SET
+                canonical_key = excluded.canonical_key,
+                created_at    = excluded.created_at",
+            params![alias, canonical, chrono::Local::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+        Ok(canonical)
+    }
+
+    /// Remove the links of `key`: its own link when it is an alias, every
+    /// link to it when it is canonical. Returns the number of links removed.
+    pub fn unlink_session(&self, key: &str) -> anyhow::Result<usize> {
+        let conn = self.conn.lock();
+        Ok(conn.execute(
+            "DELETE FROM session_links WHERE alias_key = ?1 OR canonical_key = ?1",
+            params![key],
+        )?)
+    }
+
+    /// Issue a six-digit code that links another session to `key` when
+    /// redeemed within `ttl`. A new code replaces the session's previous one.
+    pub fn create_link_code(&self, key: &str, ttl: Duration) -> anyhow::Result<String> {
+        let now = unix_now();
+        let expires_at = now.saturating_add(i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX));
+        let conn = self.conn.lock();
+        conn.execute(
+            "DELETE FROM session_link_codes WHERE expires_at <= ?1 OR session_key = ?2",
+            params![now, key],
+        )?;
+        loop {
+            let code = format!("{:06}", rand::random::<u32>() % 1_000_000);
+            let inserted = conn.execute(
+                "INSERT OR IGNORE INTO session_link_codes (code, session_key, expires_at)
+                 VALUES (?1, ?2, ?3)",
+                params![code, key, expires_at],
+            )?;
+            if inserted > 0 {
+                return Ok(code);
+            }
+        }
+    }
+
+    /// Redeem `code` from session `key`: link `key` to the session that
+    /// issued it. Codes are single-use; returns the canonical key, or `None`
+    /// when the code is unknown or expired.
+    pub fn redeem_link_code(&self, code: &str, key: &str) -> anyhow::Result<Option<String>> {
+        let issuer: Option<String> = {
+            let conn = self.conn.lock();
+            conn.execute(
+                "DELETE FROM session_link_codes WHERE expires_at <= ?1",
+                params![unix_now()],
+            )?;
+            let issuer = conn
+                .query_row(
+                    "SELECT session_key FROM session_link_codes WHERE code = ?1",
+                    params![code.trim()],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            conn.execute(
+                "DELETE FROM session_link_codes WHERE code = ?1",
+                params![code.trim()],
+            )?;
+            issuer
+        };
+        match issuer {
+            Some(issuer) => self.link_sessions(key, &issuer).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn temp_store() -> (TempDir, SqliteSessionStore) {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        (tmp, store)
+    }
+
+    #[test]
+    fn linked_keys_resolve_to_one_canonical_session() {
+        let (_tmp, store) = temp_store();
+        assert_eq!(
+            store.canonical_key("whatsapp_alice").unwrap(),
+            "whatsapp_alice"
+        );
+
+        store
+            .link_sessions("webchat_alice", "whatsapp_alice")
+            .unwrap();
+        assert_eq!(
+            store.canonical_key("webchat_alice").unwrap(),
+            "whatsapp_alice"
+        );
+        assert_eq!(
+            store.canonical_key("whatsapp_alice").unwrap(),
+            "whatsapp_alice"
+        );
+
+        // Linking to an alias lands on its canonical session.
+        assert_eq!(
+            store.link_sessions("slack_U1", "webchat_alice").unwrap(),
+            "whatsapp_alice"
+        );
+        // Moving a canonical session carries its aliases along.
+        store.link_sessions("whatsapp_alice", "cli_alice").unwrap();
+        for key in ["whatsapp_alice", "webchat_alice", "slack_U1"] {
+            assert_eq!(store.canonical_key(key).unwrap(), "cli_alice");
+        }
+        assert_eq!(store.linked_sessions("cli_alice").unwrap().len(), 3);
+        assert!(store.link_sessions("webchat_alice", "cli_alice").is_ok());
+        assert!(store.link_sessions("cli_alice", "slack_U1").is_err());
+    }
+
+    #[test]
+    fn unlinking_an_alias_or_canonical_session() {
+        let (_tmp, store) = temp_store();
+        store
+            .link_sessions("webchat_alice", "telegram_alice")
+            .unwrap();
+        store.link_sessions("slack_U1", "telegram_alice").unwrap();
+
+        assert_eq!(store.unlink_session("webchat_alice").unwrap(), 1);
+        assert_eq!(
+            store.canonical_key("webchat_alice").unwrap(),
+            "webchat_alice"
+        );
+        assert_eq!(store.canonical_key("slack_U1").unwrap(), "telegram_alice");
+
+        assert_eq!(store.unlink_session("telegram_alice").unwrap(), 1);
+        assert_eq!(store.canonical_key("slack_U1").unwrap(), "slack_U1");
+        assert_eq!(store.unlink_session("telegram_alice").unwrap(), 0);
+    }
+
+    #[test]
+    fn link_codes_are_single_use_and_expire() {
+        let (_tmp, store) = temp_store();
+        let code = store
+            .create_link_code("whatsapp_alice", LINK_CODE_TTL)
+            .unwrap();
+        assert_eq!(code.len(), 6);
+        assert!(code.chars().all(|c| c.is_ascii_digit()));
+
+        assert_eq!(
+            store.redeem_link_code("not-it", "webchat_alice").unwrap(),
+            None
+        );
+        assert_eq!(
+            store.redeem_link_code(&code, "webchat_alice").unwrap(),
+            Some("whatsapp_alice".to_string())
+        );
+        assert_eq!(
+            store.canonical_key("webchat_alice").unwrap(),
+            "whatsapp_alice"
+        );
+        assert_eq!(store.redeem_link_code(&code, "slack_U1").unwrap(), None);
+
+        let expired = store
+            .create_link_code("whatsapp_alice", Duration::ZERO)
+            .unwrap();
+        assert_eq!(store.redeem_link_code(&expired, "slack_U1").unwrap(), None);
+        assert_eq!(store.canonical_key("slack_U1").unwrap(), "slack_U1");
+
+        // A newer code replaces the session's previous one.
+        let first = store
+            .create_link_code("whatsapp_alice", LINK_CODE_TTL)
+            .unwrap();
+        let second = store
+            .create_link_code("whatsapp_alice", LINK_CODE_TTL)
+            .unwrap();
+        if first != second {
+            assert_eq!(store.redeem_link_code(&first, "slack_U1").unwrap(), None);
+        }
+        assert!(store
+            .redeem_link_code(&second, "slack_U1")
+            .unwrap()
+            .is_some());
+    }
+}
@@ -0,0 +1,1530 @@
+//! Persistent channel conversation sessions.
+//!
+//! The channel runtime keeps per-sender history in memory for prompt building;
+//! every turn is also mirrored into `<workspace>/sessions/sessions.db` so
+//! conversations can be inspected and pruned from the CLI
+//! (`zeroclaw sessions …`) while the daemon is running. The database runs in
+//! WAL mode, so read-only CLI connections never block the writer, and a
+//! periodic maintenance task ([`spawn_maintenance`]) prunes old turns and
+//! keeps the WAL and free pages from growing without bound. A second task
+//! ([`compaction::spawn_compaction`]) summarizes and trims sessions that grew
+//! large and then went idle. Senders are tracked per turn and in a
+//! `contacts` directory (see [`contacts`]) so group conversations can be
+//! attributed. Turns the user pins (see [`pins`]) survive every trim, and
+//! every summary compaction writes is kept (see [`summaries`]). Turns can be
+//! redacted and sessions deleted on request (see [`redact`]). With an
+//! embedding model configured, turns are also indexed for meaning-based
+//! search (see [`semantic`]). Sessions on different channels can be linked
+//! into one conversation (see [`links`]). Filtered listings and history for
+//! the agent's tools are queried in SQL (see [`queries`]). Store calls block,
+//! so async code runs them on the blocking pool through [`blocking`] and its
+//! `blocking_*` variants.
+
+pub mod cli;
+pub mod compaction;
+pub mod contacts;
+pub mod export;
+pub mod links;
+pub mod pins;
+pub mod queries;
+pub mod redact;
+pub mod semantic;
+pub mod settings;
+pub mod summaries;
+pub mod title;
+
+pub use queries::{HistoryFilter, SessionListFilter};
+pub use settings::{SessionSettings, SessionSettingsPatch};
+
+use crate::providers::Role;
+use anyhow::Context;
+use chrono::Local;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// How often the background maintenance task runs.
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Conversation an agent turn belongs to. The channel runtime scopes every
+/// turn with it (see [`with_session`]) so tools can tie what they schedule
+/// back to the originating chat.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionContext {
+    pub session_key: String,
+    pub channel: String,
+    pub reply_target: String,
+    /// Platform thread the turn arrived in (Slack `thread_ts`, Telegram
+    /// topic id), so replies scheduled from it land in the same thread.
+    pub thread_ts: Option<String>,
+    /// Platform id of the sender the turn answers; empty for turns that
+    /// do not answer an inbound message (heartbeat, cron).
+    pub sender: String,
+}
+
+tokio::task_local! {
+    static CURRENT_SESSION: SessionContext;
+}
+
+/// Run `fut` with `ctx` as the current session.
+pub async fn with_session<F: Future>(ctx: SessionContext, fut: F) -> F::Output {
+    CURRENT_SESSION.scope(ctx, fut).await
+}
+
+/// Session of the turn being executed, if it came from a channel.
+pub fn current_session() -> Option<SessionContext> {
+    CURRENT_SESSION.try_with(Clone::clone).ok()
+}
+
+/// Session key that tools reading stored conversations are limited to: the
+/// conversation being served, unless `cross_session` is set
+/// (`[memory] cross_session_search`). `None` means every session, which is
+/// also the case for turns that did not come from a channel (CLI).
+pub fn visible_session(cross_session: bool) -> Option<String> {
+    if cross_session {
+        return None;
+    }
+    current_session().map(|session| session.session_key)
+}
+
+/// Summary row returned by [`SqliteSessionStore::list_sessions`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SessionInfo {
+    pub key: String,
+    pub title: Option<String>,
+    pub message_count: usize,
+    pub updated_at: String,
+    /// Context-window utilization of the latest turn, in percent; see
+    /// [`crate::agent::context_usage`].
+    pub context_utilization: Option<f64>,
+}
+
+/// Full-text search hit returned by [`SqliteSessionStore::search_messages`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SessionSearchHit {
+    pub key: String,
+    pub title: Option<String>,
+    pub role: String,
+    pub content: String,
+    pub created_at: String,
+    /// BM25 relevance, higher is better.
+    pub score: f64,
+}
+
+/// Outcome of one [`SqliteSessionStore::run_maintenance`] pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MaintenanceReport {
+    pub pruned_messages: usize,
+    /// WAL frames still present after the checkpoint (0 when fully truncated).
+    pub wal_frames_remaining: i64,
+}
+
+/// A single persisted conversation turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredMessage {
+    pub role: String,
+    pub content: String,
+    pub created_at: String,
+}
+
+impl StoredMessage {
+    pub fn role(&self) -> Role {
+        Role::from(self.role.as_str())
+    }
+}
+
+/// An assistant turn whose delivery on a channel reported a platform id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveredReply {
+    pub id: i64,
+    pub content: String,
+    /// The `<channel>_message_id` recorded on the turn.
+    pub message_id: String,
+}
+
+/// SQLite-backed session store shared by the channel runtime and the CLI.
+pub struct SqliteSessionStore {
+    conn: Mutex<Connection>,
+    db_path: PathBuf,
+}
+
+impl SqliteSessionStore {
+    /// Path of the sessions database inside a workspace.
+    pub fn db_path(workspace_dir: &Path) -> PathBuf {
+        workspace_dir.join("sessions").join("sessions.db")
+    }
+
+    /// Open (and create if needed) the store for read/write use.
+    pub fn open(workspace_dir: &Path) -> anyhow::Result<Self> {
+        let db_path = Self::db_path(workspace_dir);
+        if let Some(parent) = db_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let conn = Connection::open(&db_path).context("SQLite failed to open sessions database")?;
+        // auto_vacuum only takes effect for databases created with it; older
+        // stores keep working and simply skip the incremental vacuum step.
+        conn.execute_batch(
+            "PRAGMA auto_vacuum  = INCREMENTAL;
+             PRAGMA journal_mode = WAL;
+             PRAGMA synchronous  = NORMAL;
+             PRAGMA busy_timeout = 5000;",
+        )?;
+        Self::init_schema(&conn)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+            db_path,
+        })
+    }
+
+    /// Open an existing store without write access (safe next to a running daemon).
+    pub fn open_read_only(workspace_dir: &Path) -> anyhow::Result<Self> {
+        let db_path = Self::db_path(workspace_dir);
+        if !db_path.exists() {
+            anyhow::bail!(
+                "No sessions database at {} (no channel conversations recorded yet)",
+                db_path.display()
+            );
+        }
+
+        let conn = Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .context("SQLite failed to open sessions database read-only")?;
+        conn.execute_batch("PRAGMA busy_timeout = 5000;")?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+            db_path,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.db_path
+    }
+
+    fn init_schema(conn: &Connection) -> anyhow::Result<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS sessions (
+                key         TEXT PRIMARY KEY,
+                title       TEXT,
+                summary     TEXT,
+                created_at  TEXT NOT NULL,
+                updated_at  TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS session_messages (
+                id          INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_key TEXT NOT NULL,
+                -- Canonical lowercase `Role` string. Rows written before the
+                -- `Role` type existed use the same values, so no migration.
+                role        TEXT NOT NULL,
+                content     TEXT NOT NULL,
+                created_at  TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_session_messages_key
+                ON session_messages(session_key, id);
+            CREATE TABLE IF NOT EXISTS contacts (
+                channel      TEXT NOT NULL,
+                sender_id    TEXT NOT NULL,
+                display_name TEXT,
+                first_seen   TEXT NOT NULL,
+                last_seen    TEXT NOT NULL,
+                notes        TEXT,
+                PRIMARY KEY (channel, sender_id)
+            );
+            CREATE TABLE IF NOT EXISTS session_settings (
+                session_key         TEXT PRIMARY KEY,
+                model               TEXT,
+                temperature         REAL,
+                max_tokens          INTEGER,
+                system_prompt_extra TEXT,
+                updated_at          TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS session_summaries (
+                id               INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_key      TEXT NOT NULL,
+                summary          TEXT NOT NULL,
+                message_count_at INTEGER NOT NULL,
+                created_at       TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_session_summaries_key
+                ON session_summaries(session_key, id);
+            CREATE TABLE IF NOT EXISTS session_embeddings (
+                message_id INTEGER PRIMARY KEY,
+                model      TEXT NOT NULL,
+                vector     BLOB
+            );
+            CREATE TABLE IF NOT EXISTS session_links (
+                alias_key     TEXT PRIMARY KEY,
+                canonical_key TEXT NOT NULL,
+                created_at    TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_session_links_canonical
+                ON session_links(canonical_key);
+            CREATE TABLE IF NOT EXISTS session_link_codes (
+                code        TEXT PRIMARY KEY,
+                session_key TEXT NOT NULL,
+                expires_at  INTEGER NOT NULL
+            );
+            CREATE TRIGGER IF NOT EXISTS session_embeddings_ad AFTER DELETE ON session_messages BEGIN
+                DELETE FROM session_embeddings WHERE message_id = old.id;
+            END;
+            CREATE TRIGGER IF NOT EXISTS session_embeddings_au
+            AFTER UPDATE OF content ON session_messages BEGIN
+                DELETE FROM session_embeddings WHERE message_id = old.id;
+            END;",
+        )?;
+
+        // Stores created before senders were tracked gain the column in place.
+        let has_sender: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('session_messages')
+             WHERE name = 'sender'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_sender {
+            conn.execute_batch("ALTER TABLE session_messages ADD COLUMN sender TEXT;")?;
+        }
+        // Pin order of pinned turns (NULL when not pinned); see [`pins`].
+        let has_pin_order: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('session_messages')
+             WHERE name = 'pin_order'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_pin_order {
+            conn.execute_batch("ALTER TABLE session_messages ADD COLUMN pin_order INTEGER;")?;
+        }
+        // Platform metadata of a turn as a JSON object (NULL when empty).
+        let has_metadata: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('session_messages')
+             WHERE name = 'metadata'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_metadata {
+            conn.execute_batch("ALTER TABLE session_messages ADD COLUMN metadata TEXT;")?;
+        }
+        // Context-window utilization of the latest turn, in percent.
+        let has_context_utilization: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('sessions')
+             WHERE name = 'context_utilization'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_context_utilization {
+            conn.execute_batch("ALTER TABLE sessions ADD COLUMN context_utilization REAL;")?;
+        }
+
+        // FTS5 index over message content. Stores created before the index
+        // existed are backfilled once via 'rebuild'.
+        let fts_exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master
+             WHERE type = 'table' AND name = 'session_messages_fts'",
+            [],
+            |row| row.get(0),
+        )?;
+        conn.execute_batch(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS session_messages_fts USING fts5(
+                content, content=session_messages, content_rowid=id
+            );
+            CREATE TRIGGER IF NOT EXISTS session_messages_ai AFTER INSERT ON session_messages BEGIN
+                INSERT INTO session_messages_fts(rowid, content) VALUES (new.id, new.content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS session_messages_ad AFTER DELETE ON session_messages BEGIN
+                INSERT INTO session_messages_fts(session_messages_fts, rowid, content)
+                VALUES ('delete', old.id, old.content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS session_messages_au AFTER UPDATE ON session_messages BEGIN
+                INSERT INTO session_messages_fts(session_messages_fts, rowid, content)
+                VALUES ('delete', old.id, old.content);
+                INSERT INTO session_messages_fts(rowid, content) VALUES (new.id, new.content);
+            END;",
+        )?;
+        if !fts_exists {
+            conn.execute_batch(
+                "INSERT INTO session_messages_fts(session_messages_fts) VALUES('rebuild');",
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Append a turn, creating the session row on first use.
+    pub fn append_message(&self, key: &str, role: &str, content: &str) -> anyhow::Result<()> {
+        self.append_message_from(key, role, content, None)
+    }
+
+    /// [`append_message`](Self::append_message), recording the channel
+    /// sender id of a user turn.
+    pub fn append_message_from(
+        &self,
+        key: &str,
+        role: &str,
+        content: &str,
+        sender: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.append_message_with_metadata(key, role, content, sender, &HashMap::new())
+            .map(|_| ())
+    }
+
+    /// [`append_message_from`](Self::append_message_from), also storing the
+    /// turn's platform metadata. Returns the turn's row id for
+    /// [`merge_message_metadata`](Self::merge_message_metadata).
+    pub fn append_message_with_metadata(
+        &self,
+        key: &str,
+        role: &str,
+        content: &str,
+        sender: Option<&str>,
+        metadata: &HashMap<String, String>,
+    ) -> anyhow::Result<i64> {
+        let now = Local::now().to_rfc3339();
+        let role = Role::from(role);
+        let metadata = if metadata.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(metadata)?)
+        };
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO sessions (key, created_at, updated_at) VALUES (?1, ?2, ?2)
+             ON CONFLICT(key) DO UPDATE SET updated_at = excluded.updated_at",
+            params![key, now],
+        )?;
+        tx.execute(
+            "INSERT INTO session_messages (session_key, role, content, created_at, sender, metadata)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![key, role.as_str(), content, now, sender, metadata],
+        )?;
+        let id = tx.last_insert_rowid();
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Add `entries` to the metadata of turn `id`, replacing keys it already
+    /// has. Used to record ids the platform assigns after delivery. Returns
+    /// whether the turn still exists.
+    pub fn merge_message_metadata(
+        &self,
+        id: i64,
+        entries: &HashMap<String, String>,
+    ) -> anyhow::Result<bool> {
+        let conn = self.conn.lock();
+        let Some(existing) = conn
+            .query_row(
+                "SELECT metadata FROM session_messages WHERE id = ?1",
+                params![id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()?
+        else {
+            return Ok(false);
+        };
+        let mut metadata = parse_metadata(existing.as_deref());
+        metadata.extend(entries.iter().map(|(k, v)| (k.clone(), v.clone())));
+        conn.execute(
+            "UPDATE session_messages SET metadata = ?1 WHERE id = ?2",
+            params![serde_json::to_string(&metadata)?, id],
+        )?;
+        Ok(true)
+    }
+
+    /// The most recent assistant turn of `key` with a `<channel>_message_id`,
+    /// i.e. the latest reply the platform could edit. Only the last few
+    /// replies are considered.
+    pub fn latest_delivered_reply(
+        &self,
+        key: &str,
+        channel: &str,
+    ) -> anyhow::Result<Option<DeliveredReply>> {
+        let id_key = format!("{channel}_message_id");
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, content, metadata FROM session_messages
+             WHERE session_key = ?1 AND role = 'assistant' AND metadata IS NOT NULL
+             ORDER BY id DESC LIMIT 20",
+        )?;
+        let rows = stmt.query_map(params![key], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (id, content, metadata) = row?;
+            if let Some(message_id) = parse_metadata(metadata.as_deref()).remove(&id_key) {
+                return Ok(Some(DeliveredReply {
+                    id,
+                    content,
+                    message_id,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Replace the content of turn `id`, e.g. after the reply was corrected
+    /// on the platform. Returns the previous content, or `None` when there
+    /// is no such turn.
+    pub fn replace_message_content(
+        &self,
+        id: i64,
+        content: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let Some(previous) = tx
+            .query_row(
+                "SELECT content FROM session_messages WHERE id = ?1",
+                params![id],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?
+        else {
+            return Ok(None);
+        };
+        tx.execute(
+            "UPDATE session_messages SET content = ?2 WHERE id = ?1",
+            params![id, content],
+        )?;
+        tx.commit()?;
+        Ok(Some(previous))
+    }
+
+    /// Metadata of the most recent `role` turn of a session (empty when the
+    /// session has no such turn or the turn carries none).
+    pub fn latest_message_metadata(
+        &self,
+        key: &str,
+        role: &str,
+    ) -> anyhow::Result<HashMap<String, String>> {
+        let conn = self.conn.lock();
+        let metadata: Option<Option<String>> = conn
+            .query_row(
+                "SELECT metadata FROM session_messages
+                 WHERE session_key = ?1 AND role = ?2
+                 ORDER BY id DESC LIMIT 1",
+                params![key, role],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(parse_metadata(metadata.flatten().as_deref()))
+    }
+
+    /// All sessions, most recently updated first.
+    pub fn list_sessions(&self) -> anyhow::Result<Vec<SessionInfo>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT s.key, s.title, s.updated_at,
+                    (SELECT COUNT(*) FROM session_messages m WHERE m.session_key = s.key),
+                    s.context_utilization
+             FROM sessions s
+             ORDER BY s.updated_at DESC, s.key ASC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let count: i64 = row.get(3)?;
+            Ok(SessionInfo {
+                key: row.get(0)?,
+                title: row.get(1)?,
+                updated_at: row.get(2)?,
+                message_count: usize::try_from(count).unwrap_or(0),
+                context_utilization: row.get(4)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Sessions with more than `threshold` turns and no new turn for
+    /// `idle_hours`, largest first. Candidates for background compaction
+    /// (see [`compaction`]).
+    pub fn list_sessions_needing_trim(
+        &self,
+        threshold: usize,
+        idle_hours: u64,
+    ) -> anyhow::Result<Vec<SessionInfo>> {
+        // Clamp so absurd configs cannot overflow the chrono arithmetic.
+        let hours = i64::try_from(idle_hours)
+            .unwrap_or(i64::MAX)
+            .min(24 * 365 * 100);
+        let cutoff = (Local::now() - chrono::Duration::hours(hours)).to_rfc3339();
+        let threshold = i64::try_from(threshold).unwrap_or(i64::MAX);
+
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT s.key, s.title, s.updated_at, COUNT(m.id) AS message_count,
+                    s.context_utilization
+             FROM sessions s
+             JOIN session_messages m ON m.session_key = s.key
+             WHERE julianday(s.updated_at) < julianday(?1)
+             GROUP BY s.key
+             HAVING message_count > ?2
+             ORDER BY message_count DESC, s.key ASC",
+        )?;
+        let rows = stmt.query_map(params![cutoff, threshold], |row| {
+            let count: i64 = row.get(3)?;
+            Ok(SessionInfo {
+                key: row.get(0)?,
+                title: row.get(1)?,
+                updated_at: row.get(2)?,
+                message_count: usize::try_from(count).unwrap_or(0),
+                context_utilization: row.get(4)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Session history in chronological order. `limit` keeps only the most
+    /// recent `n` turns.
+    pub fn load_history(
+        &self,
+        key: &str,
+        limit: Option<usize>,
+    ) -> anyhow::Result<Vec<StoredMessage>> {
+        let conn = self.conn.lock();
+        let limit = limit.map_or(-1, |n| i64::try_from(n).unwrap_or(i64::MAX));
+        let mut stmt = conn.prepare(
+            "SELECT role, content, created_at FROM (
+                SELECT id, role, content, created_at FROM session_messages
+                WHERE session_key = ?1
+                ORDER BY id DESC
+                LIMIT ?2
+             ) ORDER BY id ASC",
+        )?;
+        let rows = stmt.query_map(params![key, limit], |row| {
+            Ok(StoredMessage {
+                role: row.get(0)?,
+                content: row.get(1)?,
+                created_at: row.get(2)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Whether a session row exists for `key`.
+    pub fn session_exists(&self, key: &str) -> anyhow::Result<bool> {
+        let conn = self.conn.lock();
+        let found: Option<i64> = conn
+            .query_row(
+                "SELECT 1 FROM sessions WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    pub fn title(&self, key: &str) -> anyhow::Result<Option<String>> {
+        let conn = self.conn.lock();
+        let title: Option<Option<String>> = conn
+            .query_row(
+                "SELECT title FROM sessions WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(title.flatten())
+    }
+
+    /// Set the session title. Returns `false` if the session does not exist.
+    pub fn set_title(&self, key: &str, title: &str) -> anyhow::Result<bool> {
+        let conn = self.conn.lock();
+        let updated = conn.execute(
+            "UPDATE sessions SET title = ?2 WHERE key = ?1",
+            params![key, title],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Record the context-window utilization (percent) of the session's
+    /// latest turn. Returns `false` if the session does not exist.
+    pub fn set_context_utilization(&self, key: &str, pct: f64) -> anyhow::Result<bool> {
+        let conn = self.conn.lock();
+        let updated = conn.execute(
+            "UPDATE sessions SET context_utilization = ?2 WHERE key = ?1",
+            params![key, pct],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Set the title only if none is stored yet, so concurrent generators
+    /// cannot overwrite each other. Returns whether the title was written.
+    pub fn set_title_if_absent(&self, key: &str, title: &str) -> anyhow::Result<bool> {
+        let conn = self.conn.lock();
+        let updated = conn.execute(
+            "UPDATE sessions SET title = ?2 WHERE key = ?1 AND title IS NULL",
+            params![key, title],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Whether an untitled session has accumulated at least `min_messages` turns.
+    pub fn needs_title(&self, key: &str, min_messages: usize) -> anyhow::Result<bool> {
+        let conn = self.conn.lock();
+        let row: Option<(Option<String>, i64)> = conn
+            .query_row(
+                "SELECT s.title,
+                        (SELECT COUNT(*) FROM session_messages m WHERE m.session_key = s.key)
+                 FROM sessions s WHERE s.key = ?1",
+                params![key],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        Ok(match row {
+            Some((None, count)) => usize::try_from(count).unwrap_or(0) >= min_messages,
+            _ => false,
+        })
+    }
+
+    /// Full-text search over message content across all sessions, best
+    /// matches first. Every whitespace-separated term must match.
+    pub fn search_messages(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<SessionSearchHit>> {
+        self.search_messages_in(query, None, limit)
+    }
+
+    /// [`search_messages`](Self::search_messages) limited to the session
+    /// `key` when one is given.
+    pub fn search_messages_in(
+        &self,
+        query: &str,
+        key: Option<&str>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<SessionSearchHit>> {
+        let fts_query = fts_query(query);
+        if fts_query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT m.session_key, s.title, m.role, m.content, m.created_at,
+                    bm25(session_messages_fts) AS rank
+             FROM session_messages_fts f
+             JOIN session_messages m ON m.id = f.rowid
+             LEFT JOIN sessions s ON s.key = m.session_key
+             WHERE session_messages_fts MATCH ?1
+               AND (?2 IS NULL OR m.session_key = ?2)
+             ORDER BY rank, m.id DESC
+             LIMIT ?3",
+        )?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let rows = stmt.query_map(params![fts_query, key, limit], |row| {
+            let rank: f64 = row.get(5)?;
+            Ok(SessionSearchHit {
+                key: row.get(0)?,
+                title: row.get(1)?,
+                role: row.get(2)?,
+                content: row.get(3)?,
+                created_at: row.get(4)?,
+                // BM25 is negative (lower = better); flip so higher ranks first.
+                score: -rank,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Latest stored conversation summary, if one has been recorded. Earlier
+    /// ones are kept in [`summary_history`](Self::summary_history).
+    pub fn summary(&self, key: &str) -> anyhow::Result<Option<String>> {
+        let conn = self.conn.lock();
+        let summary: Option<Option<String>> = conn
+            .query_row(
+                "SELECT summary FROM sessions WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(summary.flatten())
+    }
+
+    /// Overrides stored for `key`; all fields unset when none were saved.
+    pub fn settings(&self, key: &str) -> anyhow::Result<SessionSettings> {
+        let conn = self.conn.lock();
+        let settings = conn
+            .query_row(
+                "SELECT model, temperature, max_tokens, system_prompt_extra, updated_at
+                 FROM session_settings WHERE session_key = ?1",
+                params![key],
+                |row| {
+                    Ok(SessionSettings {
+                        model: row.get(0)?,
+                        temperature: row.get(1)?,
+                        max_tokens: row.get(2)?,
+                        system_prompt_extra: row.get(3)?,
+                        updated_at: row.get(4)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(settings.unwrap_or_default())
+    }
+
+    /// Replace the overrides for `key`; an empty `settings` removes the row.
+    /// Values are stored as given, so validate them first.
+    pub fn set_settings(&self, key: &str, settings: &SessionSettings) -> anyhow::Result<()> {
+        let conn = self.conn.lock();
+        if settings.is_empty() {
+            conn.execute(
+                "DELETE FROM session_settings WHERE session_key = ?1",
+                params![key],
+            )?;
+            return Ok(());
+        }
+        conn.execute(
+            "INSERT INTO session_settings
+                 (session_key, model, temperature, max_tokens, system_prompt_extra, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(session_key) DO UPDATE SET
+                 model = excluded.model,
+                 temperature = excluded.temperature,
+                 max_tokens = excluded.max_tokens,
+                 system_prompt_extra = excluded.system_prompt_extra,
+                 updated_at = excluded.updated_at",
+            params![
+                key,
+                settings.model,
+                settings.temperature,
+                settings.max_tokens,
+                settings.system_prompt_extra,
+                Local::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Turns [`trim_history`](Self::trim_history) would remove with the same
+    /// `keep`, in chronological order.
+    pub fn trimmable_history(&self, key: &str, keep: usize) -> anyhow::Result<Vec<StoredMessage>> {
+        let conn = self.conn.lock();
+        let keep = i64::try_from(keep).unwrap_or(i64::MAX);
+        let mut stmt = conn.prepare(
+            "SELECT role, content, created_at FROM session_messages
+             WHERE session_key = ?1
+               AND pin_order IS NULL
+               AND id NOT IN (
+                   SELECT id FROM session_messages
+                   WHERE session_key = ?1
+                   ORDER BY id DESC
+                   LIMIT ?2
+               )
+             ORDER BY id ASC",
+        )?;
+        let rows = stmt.query_map(params![key, keep], |row| {
+            Ok(StoredMessage {
+                role: row.get(0)?,
+                content: row.get(1)?,
+                created_at: row.get(2)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Drop all but the newest `keep` turns. Pinned turns are never dropped.
+    /// Returns the number removed.
+    pub fn trim_history(&self, key: &str, keep: usize) -> anyhow::Result<usize> {
+        let conn = self.conn.lock();
+        let keep = i64::try_from(keep).unwrap_or(i64::MAX);
+        let removed = conn.execute(
+            "DELETE FROM session_messages
+             WHERE session_key = ?1
+               AND pin_order IS NULL
+               AND id NOT IN (
+                   SELECT id FROM session_messages
+                   WHERE session_key = ?1
+                   ORDER BY id DESC
+                   LIMIT ?2
+               )",
+            params![key, keep],
+        )?;
+        Ok(removed)
+    }
+
+    /// Delete turns older than `retention_days` (the newest `keep_recent`
+    /// turns of each session and pinned turns are always kept), then
+    /// checkpoint the WAL and return freed pages to the filesystem.
+    /// `retention_days == 0` skips pruning but still checkpoints.
+    pub fn run_maintenance(
+        &self,
+        retention_days: u32,
+        keep_recent: usize,
+    ) -> anyhow::Result<MaintenanceReport> {
+        let conn = self.conn.lock();
+        let pruned_messages = if retention_days == 0 {
+            0
+        } else {
+            let cutoff =
+                (Local::now() - chrono::Duration::days(i64::from(retention_days))).to_rfc3339();
+            let keep = i64::try_from(keep_recent).unwrap_or(i64::MAX);
+            conn.execute(
+                "DELETE FROM session_messages WHERE id IN (
+                     SELECT id FROM (
+                         SELECT id, created_at, pin_order,
+                                ROW_NUMBER() OVER (PARTITION BY session_key ORDER BY id DESC) AS rn
+                         FROM session_messages
+                     )
+                     WHERE rn > ?2 AND julianday(created_at) < julianday(?1)
+                       AND pin_order IS NULL
+                 )",
+                params![cutoff, keep],
+            )?
+        };
+
+        let wal_frames_remaining: i64 =
+            conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| row.get(1))?;
+        conn.execute_batch("PRAGMA incremental_vacuum;")?;
+
+        Ok(MaintenanceReport {
+            pruned_messages,
+            wal_frames_remaining,
+        })
+    }
+
+    /// Remove a session and all of its messages, pins, settings, summaries
+    /// and links to other sessions atomically.
+    pub fn delete_session(&self, key: &str) -> anyhow::Result<bool> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM session_messages WHERE session_key = ?1",
+            params![key],
+        )?;
+        tx.execute(
+            "DELETE FROM session_settings WHERE session_key = ?1",
+            params![key],
+        )?;
+        tx.execute(
+            "DELETE FROM session_summaries WHERE session_key = ?1",
+            params![key],
+        )?;
+        tx.execute(
+            "DELETE FROM session_links WHERE alias_key = ?1 OR canonical_key = ?1",
+            params![key],
+        )?;
+        let removed = tx.execute("DELETE FROM sessions WHERE key = ?1", params![key])?;
+        tx.commit()?;
+        Ok(removed > 0)
+    }
+}
+
+/// Quote each term so FTS5 operators and punctuation in user input are
+/// matched literally.
+fn fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Decode a stored metadata column; malformed JSON reads as empty.
+fn parse_metadata(raw: Option<&str>) -> HashMap<String, String> {
+    raw.and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default()
+}
+
+/// Stores registered by running channel runtimes, keyed by workspace.
+fn active_stores() -> &'static Mutex<HashMap<PathBuf, Arc<SqliteSessionStore>>> {
+    static STORES: OnceLock<Mutex<HashMap<PathBuf, Arc<SqliteSessionStore>>>> = OnceLock::new();
+    STORES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Enable turn persistence for a workspace. Called once by `start_channels`.
+pub fn register_store(workspace_dir: &Path, store: Arc<SqliteSessionStore>) {
+    active_stores()
+        .lock()
+        .insert(workspace_dir.to_path_buf(), store);
+}
+
+/// Store registered for `workspace_dir`, if persistence is enabled.
+pub fn store_for(workspace_dir: &Path) -> Option<Arc<SqliteSessionStore>> {
+    active_stores().lock().get(workspace_dir).cloned()
+}
+
+/// Run `f` against `store` on the blocking thread pool. rusqlite calls block
+/// on disk I/O and the connection mutex, so async code goes through here
+/// rather than calling the store directly.
+pub async fn blocking<T, F>(store: Arc<SqliteSessionStore>, f: F) -> anyhow::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&SqliteSessionStore) -> anyhow::Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || f(&store)).await?
+}
+
+/// Open the store of `workspace_dir` read-only and run `f` against it, both
+/// on the blocking thread pool.
+pub async fn blocking_read_only<T, F>(workspace_dir: &Path, f: F) -> anyhow::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&SqliteSessionStore) -> anyhow::Result<T> + Send + 'static,
+{
+    let workspace_dir = workspace_dir.to_path_buf();
+    tokio::task::spawn_blocking(move || f(&SqliteSessionStore::open_read_only(&workspace_dir)?))
+        .await?
+}
+
+/// Run `f` on the blocking thread pool against the store registered for
+/// `workspace_dir`, or one opened read-write when no channel runtime is
+/// running.
+pub async fn blocking_writable<T, F>(workspace_dir: &Path, f: F) -> anyhow::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&SqliteSessionStore) -> anyhow::Result<T> + Send + 'static,
+{
+    let workspace_dir = workspace_dir.to_path_buf();
+    tokio::task::spawn_blocking(move || match store_for(&workspace_dir) {
+        Some(store) => f(&store),
+        None => f(&SqliteSessionStore::open(&workspace_dir)?),
+    })
+    .await?
+}
+
+/// Run [`SqliteSessionStore::run_maintenance`] every few hours for the
+/// lifetime of the process. Failures are logged and retried next interval.
+pub fn spawn_maintenance(
+    store: Arc<SqliteSessionStore>,
+    retention_days: u32,
+    keep_recent: usize,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(MAINTENANCE_INTERVAL);
+        loop {
+            interval.tick().await;
+            let store = Arc::clone(&store);
+            let result = tokio::task::spawn_blocking(move || {
+                store.run_maintenance(retention_days, keep_recent)
+            })
+            .await;
+            match result {
+                Ok(Ok(report)) if report.pruned_messages > 0 => tracing::info!(
+                    "session maintenance pruned {} message(s)",
+                    report.pruned_messages
+                ),
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => tracing::warn!("session maintenance failed: {e}"),
+                Err(e) => tracing::warn!("session maintenance task panicked: {e}"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn temp_store() -> (TempDir, SqliteSessionStore) {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        (tmp, store)
+    }
+
+    fn backdate(store: &SqliteSessionStore, key: &str, days: i64) {
+        let old = (Local::now() - chrono::Duration::days(days)).to_rfc3339();
+        store
+            .conn
+            .lock()
+            .execute(
+                "UPDATE session_messages SET created_at = ?2 WHERE session_key = ?1",
+                params![key, old],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn message_metadata_round_trips_and_merges() {
+        let (_tmp, store) = temp_store();
+        let inbound = HashMap::from([
+            ("telegram_message_id".to_string(), "41".to_string()),
+            ("telegram_chat_type".to_string(), "private".to_string()),
+        ]);
+        store
+            .append_message_with_metadata("telegram_alice", "user", "hi", Some("alice"), &inbound)
+            .unwrap();
+        let reply = store
+            .append_message_with_metadata(
+                "telegram_alice",
+                "assistant",
+                "hello",
+                None,
+                &HashMap::new(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            store
+                .latest_message_metadata("telegram_alice", "user")
+                .unwrap(),
+            inbound
+        );
+        assert!(store
+            .latest_message_metadata("telegram_alice", "assistant")
+            .unwrap()
+            .is_empty());
+
+        let sent = HashMap::from([("telegram_message_id".to_string(), "42".to_string())]);
+        assert!(store.merge_message_metadata(reply, &sent).unwrap());
+        assert_eq!(
+            store
+                .latest_message_metadata("telegram_alice", "assistant")
+                .unwrap(),
+            sent
+        );
+        assert!(!store.merge_message_metadata(reply + 100, &sent).unwrap());
+    }
+
+    #[test]
+    fn delivered_reply_is_found_by_channel_message_id_and_replaced() {
+        let (_tmp, store) = temp_store();
+        let key = "slack_C1";
+        let delivered = store
+            .append_message_with_metadata(key, "assistant", "at 2pm", None, &HashMap::new())
+            .unwrap();
+        let sent = HashMap::from([("slack_message_id".to_string(), "1700.01".to_string())]);
+        store.merge_message_metadata(delivered, &sent).unwrap();
+        store.append_message(key, "user", "thanks").unwrap();
+        store
+            .append_message(key, "assistant", "not delivered")
+            .unwrap();
+
+        assert!(store
+            .latest_delivered_reply(key, "telegram")
+            .unwrap()
+            .is_none());
+        let reply = store.latest_delivered_reply(key, "slack").unwrap().unwrap();
+        assert_eq!(
+            reply,
+            DeliveredReply {
+                id: delivered,
+                content: "at 2pm".into(),
+                message_id: "1700.01".into(),
+            }
+        );
+
+        assert_eq!(
+            store.replace_message_content(delivered, "at 3pm").unwrap(),
+            Some("at 2pm".into())
+        );
+        assert_eq!(
+            store
+                .latest_delivered_reply(key, "slack")
+                .unwrap()
+                .unwrap()
+                .content,
+            "at 3pm"
+        );
+        assert!(store
+            .replace_message_content(delivered + 100, "x")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn list_sessions_needing_trim_selects_large_idle_sessions() {
+        let (_tmp, store) = temp_store();
+        let fill = |key: &str, count: usize| {
+            for i in 0..count {
+                store
+                    .append_message(key, "user", &format!("{key} {i}"))
+                    .unwrap();
+            }
+        };
+        fill("big-idle", 6);
+        fill("bigger-idle", 8);
+        fill("big-active", 9);
+        fill("small-idle", 3);
+        let stale = (Local::now() - chrono::Duration::hours(48)).to_rfc3339();
+        store
+            .conn
+            .lock()
+            .execute(
+                "UPDATE sessions SET updated_at = ?1 WHERE key != 'big-active'",
+                params![stale],
+            )
+            .unwrap();
+
+        let keys: Vec<(String, usize)> = store
+            .list_sessions_needing_trim(5, 24)
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.key, s.message_count))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![("bigger-idle".to_string(), 8), ("big-idle".to_string(), 6)]
+        );
+        assert!(store.list_sessions_needing_trim(5, 72).unwrap().is_empty());
+    }
+
+    #[test]
+    fn maintenance_prunes_old_turns_beyond_keep_recent() {
+        let (_tmp, store) = temp_store();
+        for i in 0..5 {
+            store
+                .append_message("old", "user", &format!("old {i}"))
+                .unwrap();
+        }
+        backdate(&store, "old", 120);
+        store.append_message("fresh", "user", "fresh").unwrap();
+        store.append_message("short", "user", "short").unwrap();
+        backdate(&store, "short", 120);
+
+        let report = store.run_maintenance(90, 2).unwrap();
+        assert_eq!(report.pruned_messages, 3);
+        assert_eq!(report.wal_frames_remaining, 0);
+
+        let kept: Vec<String> = store
+            .load_history("old", None)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.content)
+            .collect();
+        assert_eq!(kept, vec!["old 3", "old 4"]);
+        assert_eq!(store.load_history("short", None).unwrap().len(), 1);
+        assert_eq!(store.load_history("fresh", None).unwrap().len(), 1);
+        assert!(store.search_messages("old 0", 10).unwrap().is_empty());
+
+        assert_eq!(store.run_maintenance(0, 0).unwrap().pruned_messages, 0);
+    }
+
+    #[test]
+    fn maintenance_compares_times_across_utc_offsets() {
+        let (_tmp, store) = temp_store();
+        store.append_message("far", "user", "old").unwrap();
+        store.append_message("far", "user", "new").unwrap();
+        // Two hours past the cutoff, but written with a +14:00 offset its
+        // text sorts after the cutoff's.
+        let at = (chrono::Utc::now() - chrono::Duration::days(90) - chrono::Duration::hours(2))
+            .with_timezone(&chrono::FixedOffset::east_opt(14 * 3600).unwrap())
+            .to_rfc3339();
+        store
+            .conn
+            .lock()
+            .execute(
+                "UPDATE session_messages SET created_at = ?1 WHERE content = 'old'",
+                params![at],
+            )
+            .unwrap();
+
+        assert_eq!(store.run_maintenance(90, 1).unwrap().pruned_messages, 1);
+        let kept = store.load_history("far", None).unwrap();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].content, "new");
+    }
+
+    #[test]
+    fn concurrent_writers_and_readers_do_not_hit_busy_errors() {
+        let (tmp, store) = temp_store();
+        let store = Arc::new(store);
+        store.append_message("seed", "user", "seed").unwrap();
+        let done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let reader = {
+            let reader_store = SqliteSessionStore::open_read_only(tmp.path()).unwrap();
+            let done = Arc::clone(&done);
+            let store = Arc::clone(&store);
+            std::thread::spawn(move || {
+                let mut reads = 0;
+                while !done.load(std::sync::atomic::Ordering::Relaxed) {
+                    reader_store.list_sessions().unwrap();
+                    store.list_sessions().unwrap();
+                    reads += 1;
+                }
+                reads
+            })
+        };
+
+        let writers: Vec<_> = (0..8)
+            .map(|w| {
+                let store = Arc::clone(&store);
+                std::thread::spawn(move || {
+                    for i in 0..25 {
+                        store
+                            .append_message(&format!("writer_{w}"), "user", &format!("msg {i}"))
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        store.run_maintenance(90, 10).unwrap();
+        done.store(true, std::sync::atomic::Ordering::Relaxed);
+        assert!(reader.join().unwrap() > 0);
+
+        let sessions = store.list_sessions().unwrap();
+        assert_eq!(sessions.len(), 9);
+        assert!(sessions
+            .iter()
+            .filter(|s| s.key.starts_with("writer_"))
+            .all(|s| s.message_count == 25));
+    }
+
+    #[test]
+    fn append_and_list_sessions() {
+        let (_tmp, store) = temp_store();
+        store
+            .append_message("telegram_alice", "user", "hi")
+            .unwrap();
+        store
+            .append_message("telegram_alice", "assistant", "hello")
+            .unwrap();
+        store.append_message("slack_bob", "user", "yo").unwrap();
+
+        let sessions = store.list_sessions().unwrap();
+        assert_eq!(sessions.len(), 2);
+        let alice = sessions.iter().find(|s| s.key == "telegram_alice").unwrap();
+        assert_eq!(alice.message_count, 2);
+        assert!(alice.title.is_none());
+    }
+
+    #[test]
+    fn load_history_limit_keeps_most_recent_in_order() {
+        let (_tmp, store) = temp_store();
+        for i in 0..5 {
+            store.append_message("k", "user", &format!("m{i}")).unwrap();
+        }
+
+        let recent = store.load_history("k", Some(2)).unwrap();
+        let contents: Vec<_> = recent.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["m3", "m4"]);
+        assert_eq!(store.load_history("k", None).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn roles_round_trip_through_the_store() {
+        let (_tmp, store) = temp_store();
+        let roles = [
+            Role::System,
+            Role::User,
+            Role::Assistant,
+            Role::Tool,
+            Role::Unknown("critic".into()),
+        ];
+        for role in &roles {
+            store.append_message("k", role.as_str(), "turn").unwrap();
+        }
+        store
+            .append_message("k", "Assistant", "legacy casing")
+            .unwrap();
+
+        let stored: Vec<_> = store
+            .load_history("k", None)
+            .unwrap()
+            .iter()
+            .map(StoredMessage::role)
+            .collect();
+        assert_eq!(stored[..roles.len()], roles);
+        assert_eq!(stored[roles.len()], Role::Assistant);
+        let raw = store.load_history("k", None).unwrap();
+        assert_eq!(raw[roles.len()].role, "assistant");
+    }
+
+    #[test]
+    fn trim_history_keeps_newest() {
+        let (_tmp, store) = temp_store();
+        for i in 0..4 {
+            store.append_message("k", "user", &format!("m{i}")).unwrap();
+        }
+
+        assert_eq!(store.trim_history("k", 1).unwrap(), 3);
+        let remaining = store.load_history("k", None).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].content, "m3");
+    }
+
+    #[test]
+    fn delete_session_removes_row_and_messages() {
+        let (_tmp, store) = temp_store();
+        store.append_message("gone", "user", "a").unwrap();
+        store.append_message("gone", "assistant", "b").unwrap();
+        store.append_message("kept", "user", "c").unwrap();
+
+        assert!(store.delete_session("gone").unwrap());
+        assert!(!store.session_exists("gone").unwrap());
+        assert!(store.load_history("gone", None).unwrap().is_empty());
+        assert_eq!(store.load_history("kept", None).unwrap().len(), 1);
+        assert!(!store.delete_session("gone").unwrap());
+    }
+
+    #[test]
+    fn context_utilization_is_listed_with_the_session() {
+        let (_tmp, store) = temp_store();
+        assert!(!store.set_context_utilization("k", 12.5).unwrap());
+        store.append_message("k", "user", "a").unwrap();
+        assert_eq!(store.list_sessions().unwrap()[0].context_utilization, None);
+        assert!(store.set_context_utilization("k", 97.25).unwrap());
+        assert_eq!(
+            store.list_sessions().unwrap()[0].context_utilization,
+            Some(97.25)
+        );
+    }
+
+    #[test]
+    fn summary_roundtrip() {
+        let (_tmp, store) = temp_store();
+        assert!(!store.set_summary("k", "nothing yet").unwrap());
+        store.append_message("k", "user", "a").unwrap();
+        assert_eq!(store.summary("k").unwrap(), None);
+        assert!(store.set_summary("k", "talked about a").unwrap());
+        assert_eq!(
+            store.summary("k").unwrap().as_deref(),
+            Some("talked about a")
+        );
+    }
+
+    #[test]
+    fn settings_persist_across_reopen_and_clear_when_empty() {
+        let (tmp, store) = temp_store();
+        assert!(store.settings("k").unwrap().is_empty());
+
+        let settings = SessionSettings {
+            model: Some("openai/gpt-4o-mini".into()),
+            temperature: Some(0.2),
+            max_tokens: Some(512),
+            system_prompt_extra: Some("Be terse.".into()),
+            updated_at: None,
+        };
+        store.set_settings("k", &settings).unwrap();
+        drop(store);
+
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        let loaded = store.settings("k").unwrap();
+        assert!(loaded.updated_at.is_some());
+        assert_eq!(
+            SessionSettings {
+                updated_at: None,
+                ..loaded
+            },
+            settings
+        );
+        assert!(store.settings("other").unwrap().is_empty());
+
+        store
+            .set_settings("k", &SessionSettings::default())
+            .unwrap();
+        assert_eq!(store.settings("k").unwrap(), SessionSettings::default());
+    }
+
+    #[test]
+    fn read_only_store_sees_writer_data() {
+        let (tmp, store) = temp_store();
+        store.append_message("k", "user", "a").unwrap();
+
+        let reader = SqliteSessionStore::open_read_only(tmp.path()).unwrap();
+        assert_eq!(reader.list_sessions().unwrap().len(), 1);
+        assert!(reader.append_message("k", "user", "b").is_err());
+    }
+
+    #[test]
+    fn search_matches_all_terms_across_sessions() {
+        let (_tmp, store) = temp_store();
+        store
+            .append_message("a", "user", "book a flight to Lisbon")
+            .unwrap();
+        store
+            .append_message("b", "user", "what is the weather in Lisbon")
+            .unwrap();
+        store.append_message("c", "user", "flight delayed").unwrap();
+        store.set_title("a", "Lisbon trip").unwrap();
+
+        let hits = store.search_messages("flight lisbon", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].key, "a");
+        assert_eq!(hits[0].title.as_deref(), Some("Lisbon trip"));
+
+        assert_eq!(store.search_messages("lisbon", 10).unwrap().len(), 2);
+        assert!(store.search_messages("   ", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn search_ranks_denser_matches_first() {
+        let (_tmp, store) = temp_store();
+        store
+            .append_message(
+                "sparse",
+                "user",
+                "rust appears once in this much longer sentence about many other topics entirely",
+            )
+            .unwrap();
+        store
+            .append_message("dense", "user", "rust rust rust")
+            .unwrap();
+
+        let hits = store.search_messages("rust", 10).unwrap();
+        let keys: Vec<_> = hits.iter().map(|h| h.key.as_str()).collect();
+        assert_eq!(keys, vec!["dense", "sparse"]);
+        assert!(hits[0].score >= hits[1].score);
+    }
+
+    #[test]
+    fn search_treats_operators_literally_and_tracks_deletes() {
+        let (_tmp, store) = temp_store();
+        store
+            .append_message("k", "user", "use NOT \"quotes\" here")
+            .unwrap();
+
+        assert_eq!(store.search_messages("NOT", 10).unwrap().len(), 1);
+        assert_eq!(store.search_messages("\"quotes", 10).unwrap().len(), 1);
+
+        store.delete_session("k").unwrap();
+        assert!(store.search_messages("quotes", 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn fts_index_backfills_existing_messages() {
+        let tmp = TempDir::new().unwrap();
+        let db_path = SqliteSessionStore::db_path(tmp.path());
+        std::fs::create_dir_all(db_path.parent().unwrap()).unwrap();
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE sessions (key TEXT PRIMARY KEY, title TEXT, summary TEXT,
+                     created_at TEXT NOT NULL, updated_at TEXT NOT NULL);
+                 CREATE TABLE session_messages (id INTEGER PRIMARY KEY AUTOINCREMENT,
+                     session_key TEXT NOT NULL, role TEXT NOT NULL, content TEXT NOT NULL,
+                     created_at TEXT NOT NULL);
+                 INSERT INTO sessions VALUES ('old', NULL, NULL, 't', 't');
+                 INSERT INTO session_messages (session_key, role, content, created_at)
+                     VALUES ('old', 'user', 'legacy searchable text', 't');",
+            )
+            .unwrap();
+        }
+
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        let hits = store.search_messages("legacy", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].key, "old");
+    }
+
+    #[test]
+    fn needs_title_requires_enough_messages_and_no_title() {
+        let (_tmp, store) = temp_store();
+        assert!(!store.needs_title("missing", 1).unwrap());
+
+        store.append_message("k", "user", "a").unwrap();
+        assert!(!store.needs_title("k", 2).unwrap());
+        store.append_message("k", "assistant", "b").unwrap();
+        assert!(store.needs_title("k", 2).unwrap());
+
+        assert!(store.set_title_if_absent("k", "First").unwrap());
+        assert!(!store.set_title_if_absent("k", "Second").unwrap());
+        assert_eq!(store.title("k").unwrap().as_deref(), Some("First"));
+        assert!(!store.needs_title("k", 2).unwrap());
+    }
+
+    #[test]
+    fn read_only_open_fails_without_database() {
+        let tmp = TempDir::new().unwrap();
+        assert!(SqliteSessionStore::open_read_only(tmp.path()).is_err());
+    }
+}
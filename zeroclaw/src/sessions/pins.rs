@@ -0,0 +1,332 @@
+//! Pinned turns: messages the user asked the assistant to keep.
+//!
+//! A pinned turn carries a `pin_order` in `session_messages`. Trimming,
+//! maintenance pruning and background compaction skip pinned turns, and the
+//! channel runtime adds a "Pinned notes" section built from them to the system
+//! prompt, so a detail pinned weeks ago stays in context after the turns
+//! around it are gone. Each session keeps at most [`MAX_PINNED_PER_SESSION`]
+//! pins; pinning past the cap unpins the oldest.
+
+use super::SqliteSessionStore;
+use rusqlite::{params, OptionalExtension, Row};
+use std::fmt::Write;
+
+/// Pins kept per session before the oldest is released.
+pub const MAX_PINNED_PER_SESSION: usize = 20;
+
+/// Longest rendering of a single note in [`pinned_notes_section`].
+const NOTE_MAX_CHARS: usize = 400;
+
+/// A pinned turn.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct PinnedMessage {
+    pub id: i64,
+    pub role: String,
+    pub content: String,
+    pub created_at: String,
+}
+
+impl PinnedMessage {
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            role: row.get(1)?,
+            content: row.get(2)?,
+            created_at: row.get(3)?,
+        })
+    }
+}
+
+/// Result of [`SqliteSessionStore::pin_message`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinOutcome {
+    pub pinned: PinnedMessage,
+    /// `false` when the turn was already pinned.
+    pub newly_pinned: bool,
+    /// Older pins released to stay within [`MAX_PINNED_PER_SESSION`].
+    pub evicted: Vec<PinnedMessage>,
+}
+
+const PIN_COLUMNS: &str = "id, role, content, created_at";
+
+impl SqliteSessionStore {
+    /// Pin a turn of `key`: the most recent user turn when `index` is
+    /// `None`, otherwise the `index`-th most recent turn of any role
+    /// (1 = latest). Returns `None` when there is no such turn.
+    pub fn pin_message(
+        &self,
+        key: &str,
+        index: Option<usize>,
+    ) -> anyhow::Result<Option<PinOutcome>> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+
+        let target = match index {
+            None => tx
+                .query_row(
+                    &format!(
+                        "SELECT {PIN_COLUMNS}, pin_order FROM session_messages
+                         WHERE session_key = ?1 AND role = 'user'
+                         ORDER BY id DESC LIMIT 1"
+                    ),
+                    params![key],
+                    |row| Ok((PinnedMessage::from_row(row)?, row.get::<_, Option<i64>>(4)?)),
+                )
+                .optional()?,
+            Some(0) => None,
+            Some(n) => {
+                let offset = i64::try_from(n - 1).unwrap_or(i64::MAX);
+                tx.query_row(
+                    &format!(
+                        "SELECT {PIN_COLUMNS}, pin_order FROM session_messages
+                         WHERE session_key = ?1
+                         ORDER BY id DESC LIMIT 1 OFFSET ?2"
+                    ),
+                    params![key, offset],
+                    |row| Ok((PinnedMessage::from_row(row)?, row.get::<_, Option<i64>>(4)?)),
+                )
+                .optional()?
+            }
+        };
+        let Some((pinned, pin_order)) = target else {
+            return Ok(None);
+        };
+        if pin_order.is_some() {
+            return Ok(Some(PinOutcome {
+                pinned,
+                newly_pinned: false,
+                evicted: Vec::new(),
+            }));
+        }
+
+        tx.execute(
+            "UPDATE session_messages
+             SET pin_order = (SELECT COALESCE(MAX(pin_order), 0) + 1
+                              FROM session_messages WHERE session_key = ?1)
+             WHERE id = ?2",
+            params![key, pinned.id],
+        )?;
+
+        let cap = i64::try_from(MAX_PINNED_PER_SESSION).unwrap_or(i64::MAX);
+        let evicted = {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT {PIN_COLUMNS} FROM session_messages
+                 WHERE session_key = ?1 AND pin_order IS NOT NULL
+                 ORDER BY pin_order DESC LIMIT -1 OFFSET ?2"
+            ))?;
+            let rows = stmt.query_map(params![key, cap], PinnedMessage::from_row)?;
+            let mut evicted = rows.collect::<Result<Vec<_>, _>>()?;
+            evicted.reverse();
+            evicted
+        };
+        for message in &evicted {
+            tx.execute(
+                "UPDATE session_messages SET pin_order = NULL WHERE id = ?1",
+                params![message.id],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(Some(PinOutcome {
+            pinned,
+            newly_pinned: true,
+            evicted,
+        }))
+    }
+
+    /// Unpin the `number`-th pin of `key` as listed by
+    /// [`pinned_messages`](Self::pinned_messages) (1-based).
+    pub fn unpin_message(&self, key: &str, number: usize) -> anyhow::Result<Option<PinnedMessage>> {
+        let Some(position) = number.checked_sub(1) else {
+            return Ok(None);
+        };
+        let Some(target) = self.pinned_messages(key)?.into_iter().nth(position) else {
+            return Ok(None);
+        };
+        self.conn.lock().execute(
+            "UPDATE session_messages SET pin_order = NULL WHERE id = ?1",
+            params![target.id],
+        )?;
+        Ok(Some(target))
+    }
+
+    /// Pinned turns of `key`, oldest pin first.
+    pub fn pinned_messages(&self, key: &str) -> anyhow::Result<Vec<PinnedMessage>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {PIN_COLUMNS} FROM session_messages
+             WHERE session_key = ?1 AND pin_order IS NOT NULL
+             ORDER BY pin_order ASC"
+        ))?;
+        let rows = stmt.query_map(params![key], PinnedMessage::from_row)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+}
+
+/// Numbered "Pinned notes" prompt section for `pins`, or an empty string
+/// when nothing is pinned. Notes are flattened to one line and truncated.
+pub fn pinned_notes_section(pins: &[PinnedMessage]) -> String {
+    if pins.is_empty() {
+        return String::new();
+    }
+    let mut section = String::from(
+        "## Pinned notes\n\n\
+         The user pinned these messages in this conversation. They remain in effect even \
+         when the original turns are no longer in the history.\n\n",
+    );
+    for (number, pin) in pins.iter().enumerate() {
+        let text = pin.content.split_whitespace().collect::<Vec<_>>().join(" ");
+        let text = crate::util::truncate_with_ellipsis(&text, NOTE_MAX_CHARS);
+        if pin.role == "user" {
+            let _ = writeln!(section, "{}. {text}", number + 1);
+        } else {
+            let _ = writeln!(section, "{}. ({}) {text}", number + 1, pin.role);
+        }
+    }
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store_with_turns(turns: &[(&str, &str)]) -> (TempDir, SqliteSessionStore) {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        for (role, content) in turns {
+            store.append_message("chat", role, content).unwrap();
+        }
+        (tmp, store)
+    }
+
+    fn pinned_contents(store: &SqliteSessionStore) -> Vec<String> {
+        let pins = store.pinned_messages("chat").unwrap();
+        pins.into_iter().map(|pin| pin.content).collect()
+    }
+
+    fn history_contents(store: &SqliteSessionStore) -> Vec<String> {
+        let history = store.load_history("chat", None).unwrap();
+        history.into_iter().map(|turn| turn.content).collect()
+    }
+
+    #[test]
+    fn pins_latest_user_turn_or_turn_by_index() {
+        let (_tmp, store) = store_with_turns(&[
+            ("user", "remember: the production DB is called prod-eu-1"),
+            ("assistant", "Noted."),
+            ("user", "thanks"),
+            ("assistant", "Anytime."),
+        ]);
+
+        let latest = store.pin_message("chat", None).unwrap().unwrap();
+        assert_eq!(latest.pinned.content, "thanks");
+        assert!(latest.newly_pinned);
+        let third = store.pin_message("chat", Some(4)).unwrap().unwrap();
+        assert!(third.pinned.content.contains("prod-eu-1"));
+        let again = store.pin_message("chat", Some(2)).unwrap().unwrap();
+        assert!(!again.newly_pinned);
+
+        assert!(store.pin_message("chat", Some(9)).unwrap().is_none());
+        assert!(store.pin_message("other", None).unwrap().is_none());
+        assert_eq!(
+            pinned_contents(&store),
+            vec!["thanks", "remember: the production DB is called prod-eu-1"]
+        );
+
+        let removed = store.unpin_message("chat", 1).unwrap().unwrap();
+        assert_eq!(removed.content, "thanks");
+        assert!(store.unpin_message("chat", 5).unwrap().is_none());
+        assert_eq!(store.pinned_messages("chat").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn trim_and_maintenance_keep_pinned_turns() {
+        let (_tmp, store) = store_with_turns(&[
+            ("user", "remember: deploys happen on Tuesdays"),
+            ("assistant", "Got it."),
+            ("user", "turn 2"),
+            ("assistant", "turn 3"),
+            ("user", "turn 4"),
+        ]);
+        store.pin_message("chat", Some(5)).unwrap().unwrap();
+
+        let trimmable = store.trimmable_history("chat", 2).unwrap();
+        let trimmable: Vec<String> = trimmable.into_iter().map(|turn| turn.content).collect();
+        assert_eq!(trimmable, vec!["Got it.", "turn 2"]);
+        assert_eq!(store.trim_history("chat", 2).unwrap(), 2);
+        assert_eq!(
+            history_contents(&store),
+            vec!["remember: deploys happen on Tuesdays", "turn 3", "turn 4"]
+        );
+
+        let old = (chrono::Local::now() - chrono::Duration::days(30)).to_rfc3339();
+        store
+            .conn
+            .lock()
+            .execute("UPDATE session_messages SET created_at = ?1", params![old])
+            .unwrap();
+        let report = store.run_maintenance(7, 0).unwrap();
+        assert_eq!(report.pruned_messages, 2);
+        assert_eq!(
+            history_contents(&store),
+            vec!["remember: deploys happen on Tuesdays"]
+        );
+    }
+
+    #[test]
+    fn pinning_past_the_cap_releases_the_oldest_pin() {
+        let turns: Vec<String> = (0..=MAX_PINNED_PER_SESSION)
+            .map(|i| format!("note {i}"))
+            .collect();
+        let (_tmp, store) = store_with_turns(
+            &turns
+                .iter()
+                .map(|t| ("user", t.as_str()))
+                .collect::<Vec<_>>(),
+        );
+
+        for index in (2..=MAX_PINNED_PER_SESSION + 1).rev() {
+            let outcome = store.pin_message("chat", Some(index)).unwrap().unwrap();
+            assert!(outcome.evicted.is_empty());
+        }
+        let outcome = store.pin_message("chat", None).unwrap().unwrap();
+        assert_eq!(
+            outcome.pinned.content,
+            format!("note {MAX_PINNED_PER_SESSION}")
+        );
+        assert_eq!(outcome.evicted.len(), 1);
+        assert_eq!(outcome.evicted[0].content, "note 0");
+
+        let pins = pinned_contents(&store);
+        assert_eq!(pins.len(), MAX_PINNED_PER_SESSION);
+        assert_eq!(pins[0], "note 1");
+        // The released turn is ordinary history again and can be trimmed.
+        assert_eq!(store.trim_history("chat", 0).unwrap(), 1);
+    }
+
+    #[test]
+    fn notes_section_numbers_and_flattens_pins() {
+        assert_eq!(pinned_notes_section(&[]), "");
+
+        let pin = |role: &str, content: &str| PinnedMessage {
+            id: 1,
+            role: role.into(),
+            content: content.into(),
+            created_at: "2026-03-02T10:00:00+01:00".into(),
+        };
+        let long = "x".repeat(NOTE_MAX_CHARS + 50);
+        let section = pinned_notes_section(&[
+            pin("user", "remember:\n  the production DB\tis prod-eu-1"),
+            pin("assistant", "Deploy checklist: tests, tag, ship"),
+            pin("user", &long),
+        ]);
+
+        assert!(section.starts_with("## Pinned notes\n\n"));
+        assert!(section.contains("\n1. remember: the production DB is prod-eu-1\n"));
+        assert!(section.contains("\n2. (assistant) Deploy checklist: tests, tag, ship\n"));
+        let third = section.lines().last().unwrap();
+        assert!(third.starts_with("3. xxx"));
+        assert!(third.chars().count() < NOTE_MAX_CHARS + 10);
+    }
+}
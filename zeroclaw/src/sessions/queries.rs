@@ -0,0 +1,246 @@
+//! Filtered reads of stored sessions for the `sessions_list` and
+//! `sessions_history` tools.
+//!
+//! Filters and limits run in SQL, so a long-lived install with thousands of
+//! sessions does not load them all to answer "telegram sessions active this
+//! week". Each query also reports how many rows matched before the limit,
+//! so callers can say how many were left out.
+
+use super::{SessionInfo, SqliteSessionStore, StoredMessage};
+use crate::providers::Role;
+use chrono::{DateTime, Local};
+use rusqlite::params;
+
+/// Filter for [`SqliteSessionStore::list_sessions_filtered`].
+#[derive(Debug, Clone, Default)]
+pub struct SessionListFilter {
+    /// Only the session with exactly this key.
+    pub key: Option<String>,
+    /// Only keys starting with this, e.g. `telegram` or `telegram_alice`.
+    pub key_prefix: Option<String>,
+    /// Only sessions with a turn at or after this time.
+    pub active_since: Option<DateTime<Local>>,
+    /// At most this many sessions, most recently active first.
+    pub limit: Option<usize>,
+}
+
+/// Filter for [`SqliteSessionStore::load_history_filtered`].
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    pub role: Option<Role>,
+    /// Only turns at or after this time.
+    pub since: Option<DateTime<Local>>,
+    /// At most this many turns, the most recent ones.
+    pub limit: Option<usize>,
+}
+
+fn sql_limit(limit: Option<usize>) -> i64 {
+    limit.map_or(-1, |n| i64::try_from(n).unwrap_or(i64::MAX))
+}
+
+impl SqliteSessionStore {
+    /// Sessions matching `filter`, most recently active first, and how many
+    /// matched before the limit.
+    pub fn list_sessions_filtered(
+        &self,
+        filter: &SessionListFilter,
+    ) -> anyhow::Result<(Vec<SessionInfo>, usize)> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT s.key, s.title, s.updated_at,
+                    (SELECT COUNT(*) FROM session_messages m WHERE m.session_key = s.key),
+                    s.context_utilization,
+                    COUNT(*) OVER ()
+             FROM sessions s
+             WHERE (?1 IS NULL OR substr(s.key, 1, length(?1)) = ?1)
+               AND (?2 IS NULL OR julianday(s.updated_at) >= julianday(?2))
+               AND (?3 IS NULL OR s.key = ?3)
+             ORDER BY s.updated_at DESC, s.key ASC
+             LIMIT ?4",
+        )?;
+        let since = filter.active_since.map(|at| at.to_rfc3339());
+        let mut total = 0;
+        let rows = stmt.query_map(
+            params![
+                filter.key_prefix,
+                since,
+                filter.key,
+                sql_limit(filter.limit)
+            ],
+            |row| {
+                total = usize::try_from(row.get::<_, i64>(5)?).unwrap_or(0);
+                Ok(SessionInfo {
+                    key: row.get(0)?,
+                    title: row.get(1)?,
+                    updated_at: row.get(2)?,
+                    message_count: usize::try_from(row.get::<_, i64>(3)?).unwrap_or(0),
+                    context_utilization: row.get(4)?,
+                })
+            },
+        )?;
+        let sessions = rows.collect::<Result<Vec<_>, _>>()?;
+        Ok((sessions, total))
+    }
+
+    /// Turns of `key` matching `filter` in chronological order, and how many
+    /// matched before the limit.
+    pub fn load_history_filtered(
+        &self,
+        key: &str,
+        filter: &HistoryFilter,
+    ) -> anyhow::Result<(Vec<StoredMessage>, usize)> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT role, content, created_at, total FROM (
+                SELECT id, role, content, created_at, COUNT(*) OVER () AS total
+                FROM session_messages
+                WHERE session_key = ?1
+                  AND (?2 IS NULL OR role = ?2)
+                  AND (?3 IS NULL OR julianday(created_at) >= julianday(?3))
+                ORDER BY id DESC
+                LIMIT ?4
+             ) ORDER BY id ASC",
+        )?;
+        let role = filter.role.as_ref().map(Role::as_str);
+        let since = filter.since.map(|at| at.to_rfc3339());
+        let mut total = 0;
+        let rows = stmt.query_map(params![key, role, since, sql_limit(filter.limit)], |row| {
+            total = usize::try_from(row.get::<_, i64>(3)?).unwrap_or(0);
+            Ok(StoredMessage {
+                role: row.get(0)?,
+                content: row.get(1)?,
+                created_at: row.get(2)?,
+            })
+        })?;
+        let messages = rows.collect::<Result<Vec<_>, _>>()?;
+        Ok((messages, total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn set_time(store: &SqliteSessionStore, sql: &str, key: &str, hours_ago: i64) {
+        let at = (Local::now() - chrono::Duration::hours(hours_ago)).to_rfc3339();
+        store.conn.lock().execute(sql, params![key, at]).unwrap();
+    }
+
+    #[test]
+    fn sessions_are_filtered_by_prefix_and_activity_in_sql() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        for (key, hours_ago) in [
+            ("telegram_alice", 2),
+            ("telegram_bob", 24 * 10),
+            ("telegram_carol", 30),
+            ("discord_dave", 1),
+        ] {
+            store.append_message(key, "user", "hi").unwrap();
+            set_time(
+                &store,
+                "UPDATE sessions SET updated_at = ?2 WHERE key = ?1",
+                key,
+                hours_ago,
+            );
+        }
+
+        let keys = |filter: SessionListFilter| {
+            let (sessions, total) = store.list_sessions_filtered(&filter).unwrap();
+            let keys: Vec<String> = sessions.into_iter().map(|s| s.key).collect();
+            (keys, total)
+        };
+        let week_ago = Local::now() - chrono::Duration::days(7);
+
+        assert_eq!(
+            keys(SessionListFilter {
+                key_prefix: Some("telegram".into()),
+                active_since: Some(week_ago),
+                ..SessionListFilter::default()
+            }),
+            (vec!["telegram_alice".into(), "telegram_carol".into()], 2)
+        );
+        assert_eq!(
+            keys(SessionListFilter {
+                limit: Some(2),
+                ..SessionListFilter::default()
+            }),
+            (vec!["discord_dave".into(), "telegram_alice".into()], 4)
+        );
+        assert_eq!(
+            keys(SessionListFilter {
+                key: Some("telegram_bob".into()),
+                ..SessionListFilter::default()
+            }),
+            (vec!["telegram_bob".into()], 1)
+        );
+        // The prefix is literal, not a LIKE pattern.
+        assert_eq!(
+            keys(SessionListFilter {
+                key_prefix: Some("telegram_%".into()),
+                ..SessionListFilter::default()
+            }),
+            (Vec::new(), 0)
+        );
+    }
+
+    #[test]
+    fn history_is_filtered_by_role_and_time_keeping_the_latest() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        for (role, content) in [
+            ("user", "old question"),
+            ("assistant", "old answer"),
+            ("user", "q1"),
+            ("assistant", "a1"),
+            ("user", "q2"),
+            ("assistant", "a2"),
+        ] {
+            store.append_message("k", role, content).unwrap();
+        }
+        set_time(
+            &store,
+            "UPDATE session_messages SET created_at = ?2
+             WHERE session_key = ?1 AND content LIKE 'old%'",
+            "k",
+            48,
+        );
+
+        let contents = |filter: HistoryFilter| {
+            let (messages, total) = store.load_history_filtered("k", &filter).unwrap();
+            let contents: Vec<String> = messages.into_iter().map(|m| m.content).collect();
+            (contents, total)
+        };
+
+        assert_eq!(
+            contents(HistoryFilter {
+                role: Some(Role::User),
+                ..HistoryFilter::default()
+            }),
+            (vec!["old question".into(), "q1".into(), "q2".into()], 3)
+        );
+        assert_eq!(
+            contents(HistoryFilter {
+                since: Some(Local::now() - chrono::Duration::hours(1)),
+                limit: Some(3),
+                ..HistoryFilter::default()
+            }),
+            (vec!["a1".into(), "q2".into(), "a2".into()], 4)
+        );
+        assert_eq!(
+            contents(HistoryFilter {
+                role: Some(Role::Assistant),
+                since: Some(Local::now() - chrono::Duration::hours(1)),
+                limit: Some(1),
+            }),
+            (vec!["a2".into()], 2)
+        );
+        assert_eq!(
+            store
+                .load_history_filtered("missing", &HistoryFilter::default())
+                .unwrap(),
+            (Vec::new(), 0)
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Removing what the user asked to forget.
+//!
+//! [`SqliteSessionStore::redact_message`] replaces the content of one turn
+//! with [`REDACTED_CONTENT`] and clears its metadata and pin, keeping the row
+//! so the conversation keeps its order; [`SqliteSessionStore::delete_session`]
+//! removes a whole session. The channel runtime also holds recent turns in
+//! memory, so callers report the change with [`note_forgotten`] and the
+//! runtime drops the turns from its cache ([`take_forgotten`]) before it
+//! builds the session's next context.
+
+use super::SqliteSessionStore;
+use crate::providers::ChatMessage;
+use parking_lot::Mutex;
+use rusqlite::{params, OptionalExtension};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Content a redacted turn is left with.
+pub const REDACTED_CONTENT: &str = "[redacted]";
+
+/// A turn replaced by [`SqliteSessionStore::redact_message`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactedMessage {
+    pub id: i64,
+    pub session_key: String,
+    pub role: String,
+    /// Content before the redaction, to find the turn in cached histories.
+    pub content: String,
+}
+
+impl SqliteSessionStore {
+    /// Replace the content of turn `id` with [`REDACTED_CONTENT`] and clear
+    /// its metadata and pin. Returns `None` when there is no such turn.
+    pub fn redact_message(&self, id: i64) -> anyhow::Result<Option<RedactedMessage>> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let Some(redacted) = tx
+            .query_row(
+                "SELECT id, session_key, role, content FROM session_messages WHERE id = ?1",
+                params![id],
+                |row| {
+                    Ok(RedactedMessage {
+                        id: row.get(0)?,
+                        session_key: row.get(1)?,
+                        role: row.get(2)?,
+                        content: row.get(3)?,
+                    })
+                },
+            )
+            .optional()?
+        else {
+            return Ok(None);
+        };
+        tx.execute(
+            "UPDATE session_messages
+             SET content = ?2, metadata = NULL, pin_order = NULL
+             WHERE id = ?1",
+            params![id, REDACTED_CONTENT],
+        )?;
+        tx.commit()?;
+        Ok(Some(redacted))
+    }
+
+    /// Id of the `skip`-th most recent user turn of `key` that is not
+    /// redacted yet (0 = latest).
+    pub fn recent_user_message_id(&self, key: &str, skip: usize) -> anyhow::Result<Option<i64>> {
+        let conn = self.conn.lock();
+        let offset = i64::try_from(skip).unwrap_or(i64::MAX);
+        conn.query_row(
+            "SELECT id FROM session_messages
+             WHERE session_key = ?1 AND role = 'user' AND content != ?2
+             ORDER BY id DESC LIMIT 1 OFFSET ?3",
+            params![key, REDACTED_CONTENT, offset],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(Into::into)
+    }
+}
+
+/// What to drop from a session's cached history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Forgotten {
+    /// The session was deleted; drop all of it.
+    Session,
+    /// Turns with these contents were redacted.
+    Messages(Vec<String>),
+}
+
+impl Forgotten {
+    /// Remove the forgotten turns from `turns`, the latest match of each
+    /// redacted content.
+    pub fn apply(self, turns: &mut Vec<ChatMessage>) {
+        match self {
+            Self::Session => turns.clear(),
+            Self::Messages(contents) => {
+                for content in contents {
+                    if let Some(pos) = turns.iter().rposition(|turn| turn.content == content) {
+                        turns.remove(pos);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn pending() -> &'static Mutex<HashMap<String, Forgotten>> {
+    static PENDING: OnceLock<Mutex<HashMap<String, Forgotten>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Record that turns of `key` were redacted or the session deleted, for the
+/// channel runtime to apply to its cache.
+pub fn note_forgotten(key: &str, forgotten: Forgotten) {
+    let mut pending = pending().lock();
+    match (pending.get_mut(key), forgotten) {
+        (Some(Forgotten::Session), _) => {}
+        (Some(Forgotten::Messages(noted)), Forgotten::Messages(more)) => noted.extend(more),
+        (_, forgotten) => {
+            pending.insert(key.to_string(), forgotten);
+        }
+    }
+}
+
+/// Take what was forgotten in `key` since the last call.
+pub fn take_forgotten(key: &str) -> Option<Forgotten> {
+    pending().lock().remove(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn temp_store() -> (TempDir, SqliteSessionStore) {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        (tmp, store)
+    }
+
+    #[test]
+    fn redaction_keeps_the_row_and_clears_metadata_and_pin() {
+        let (_tmp, store) = temp_store();
+        let metadata = HashMap::from([("telegram_message_id".to_string(), "7".to_string())]);
+        store
+            .append_message_with_metadata("chat", "user", "my card is 4111", None, &metadata)
+            .unwrap();
+        store.append_message("chat", "assistant", "Noted.").unwrap();
+        store.pin_message("chat", Some(2)).unwrap().unwrap();
+        let id = store.recent_user_message_id("chat", 0).unwrap().unwrap();
+
+        let redacted = store.redact_message(id).unwrap().unwrap();
+        assert_eq!(redacted.session_key, "chat");
+        assert_eq!(redacted.content, "my card is 4111");
+
+        let history = store.load_history("chat", None).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].content, REDACTED_CONTENT);
+        assert_eq!(history[1].content, "Noted.");
+        assert!(store
+            .latest_message_metadata("chat", "user")
+            .unwrap()
+            .is_empty());
+        assert!(store.pinned_messages("chat").unwrap().is_empty());
+        assert!(store.search_messages("4111", 10).unwrap().is_empty());
+        // Redacted turns are not targeted again.
+        assert_eq!(store.recent_user_message_id("chat", 0).unwrap(), None);
+        assert!(store.redact_message(id + 100).unwrap().is_none());
+    }
+
+    #[test]
+    fn delete_cascades_to_settings_summaries_and_pins() {
+        let (_tmp, store) = temp_store();
+        store.append_message("gone", "user", "secret").unwrap();
+        store.pin_message("gone", None).unwrap().unwrap();
+        store.set_summary("gone", "talked about a secret").unwrap();
+        store
+            .set_settings(
+                "gone",
+                &crate::sessions::SessionSettings {
+                    model: Some("m".into()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        store.append_message("kept", "user", "hello").unwrap();
+
+        assert!(store.delete_session("gone").unwrap());
+        assert!(store.pinned_messages("gone").unwrap().is_empty());
+        assert!(store.summary_history("gone", None).unwrap().is_empty());
+        assert!(store.settings("gone").unwrap().is_empty());
+        assert!(store.search_messages("secret", 10).unwrap().is_empty());
+        assert_eq!(store.load_history("kept", None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn forgotten_turns_are_dropped_from_cached_history() {
+        note_forgotten("redact-cache-a", Forgotten::Messages(vec!["secret".into()]));
+        note_forgotten("redact-cache-a", Forgotten::Messages(vec!["other".into()]));
+        let mut turns = vec![
+            ChatMessage::user("secret"),
+            ChatMessage::assistant("Noted."),
+            ChatMessage::user("secret"),
+            ChatMessage::user("forget that"),
+        ];
+        take_forgotten("redact-cache-a").unwrap().apply(&mut turns);
+        let contents: Vec<_> = turns.iter().map(|t| t.content.as_str()).collect();
+        assert_eq!(contents, ["secret", "Noted.", "forget that"]);
+        assert!(take_forgotten("redact-cache-a").is_none());
+
+        note_forgotten("redact-cache-b", Forgotten::Session);
+        note_forgotten("redact-cache-b", Forgotten::Messages(vec!["x".into()]));
+        assert_eq!(take_forgotten("redact-cache-b"), Some(Forgotten::Session));
+    }
+}
@@ -0,0 +1,448 @@
+//! Meaning-based search over past conversations.
+//!
+//! Keyword search ([`SqliteSessionStore::search_messages`]) misses turns that
+//! say the same thing in other words. When `[memory] embedding_provider` is
+//! configured, [`spawn_indexer`] embeds new turns in batches in the
+//! background and stores each vector in `session_embeddings` (a BLOB of
+//! little-endian `f32`s keyed by message id, see [`crate::memory::vector`]).
+//! Tool chatter, redacted turns and very short messages are recorded without
+//! a vector so they are not looked at again. [`search`] embeds the query and
+//! ranks turns through a [`VectorIndex`]; [`BruteForceIndex`] scans every
+//! vector, which is fine at personal-assistant scale.
+//!
+//! Deleting or rewriting a turn drops its vector (SQLite triggers), so
+//! redactions and retention pruning apply to the index as well. Indexing runs
+//! off the turn path: a failed batch is logged and retried on the next pass.
+
+use super::redact::REDACTED_CONTENT;
+use super::{blocking, SessionSearchHit, SqliteSessionStore};
+use crate::memory::embeddings::EmbeddingProvider;
+use crate::memory::vector::{bytes_to_vec, cosine_similarity, vec_to_bytes};
+use rusqlite::params;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Turns shorter than this (in characters) are not embedded.
+pub const MIN_INDEXED_CHARS: usize = 20;
+/// Turns embedded per provider call.
+pub const INDEX_BATCH_SIZE: usize = 32;
+/// How often the background indexer looks for new turns.
+const INDEX_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Outcome of one [`index_pending`] pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IndexReport {
+    pub embedded: usize,
+    pub skipped: usize,
+}
+
+/// Whether a stored turn is worth embedding.
+pub fn is_indexable(role: &str, content: &str) -> bool {
+    let content = content.trim();
+    matches!(role, "user" | "assistant")
+        && content.chars().count() >= MIN_INDEXED_CHARS
+        && content != REDACTED_CONTENT
+        && !content.starts_with("[Tool results]")
+        && !content.contains("<tool_result")
+        && !content.contains("<tool_call")
+}
+
+/// Nearest stored vectors to a query vector, as `(message id, similarity)`
+/// best first.
+pub trait VectorIndex {
+    fn nearest(&self, query: &[f32], limit: usize) -> anyhow::Result<Vec<(i64, f32)>>;
+}
+
+/// Scans every vector of one embedding model, optionally of one session.
+pub struct BruteForceIndex<'a> {
+    store: &'a SqliteSessionStore,
+    model: &'a str,
+    session_key: Option<&'a str>,
+}
+
+impl<'a> BruteForceIndex<'a> {
+    pub fn new(store: &'a SqliteSessionStore, model: &'a str) -> Self {
+        Self {
+            store,
+            model,
+            session_key: None,
+        }
+    }
+
+    /// Only consider turns of the session `key`.
+    pub fn in_session(mut self, key: &'a str) -> Self {
+        self.session_key = Some(key);
+        self
+    }
+}
+
+impl VectorIndex for BruteForceIndex<'_> {
+    fn nearest(&self, query: &[f32], limit: usize) -> anyhow::Result<Vec<(i64, f32)>> {
+        let conn = self.store.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT e.message_id, e.vector FROM session_embeddings e
+             JOIN session_messages m ON m.id = e.message_id
+             WHERE e.model = ?1 AND e.vector IS NOT NULL
+               AND (?2 IS NULL OR m.session_key = ?2)",
+        )?;
+        let rows = stmt.query_map(params![self.model, self.session_key], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })?;
+        let mut scored = Vec::new();
+        for row in rows {
+            let (id, bytes) = row?;
+            let similarity = cosine_similarity(query, &bytes_to_vec(&bytes));
+            // Zero means nothing in common (or a dimension mismatch).
+            if similarity > 0.0 {
+                scored.push((id, similarity));
+            }
+        }
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(b.0.cmp(&a.0)));
+        scored.truncate(limit);
+        Ok(scored)
+    }
+}
+
+impl SqliteSessionStore {
+    /// Up to `limit` turns with no index entry for `model`, oldest first, as
+    /// `(id, role, content)`.
+    fn unindexed_messages(
+        &self,
+        model: &str,
+        limit: usize,
+    ) -> anyhow::Result<Vec<(i64, String, String)>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT m.id, m.role, m.content FROM session_messages m
+             LEFT JOIN session_embeddings e ON e.message_id = m.id
+             WHERE e.message_id IS NULL OR e.model != ?1
+             ORDER BY m.id
+             LIMIT ?2",
+        )?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let rows = stmt.query_map(params![model, limit], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Record index entries for `model`; `None` marks a turn as skipped.
+    fn store_embeddings(
+        &self,
+        model: &str,
+        entries: &[(i64, Option<&[f32]>)],
+    ) -> anyhow::Result<()> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        for (id, vector) in entries {
+            // A turn deleted since it was read must not leave an orphan row.
+            tx.execute(
+                "INSERT OR REPLACE INTO session_embeddings (message_id, model, vector)
+                 SELECT id, ?2, ?3 FROM session_messages WHERE id = ?1",
+                params![id, model, vector.map(vec_to_bytes)],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The turns `ids` as search hits, in the given order with the given
+    /// scores; ids that no longer exist are left out.
+    fn hits_for(&self, scored: &[(i64, f32)]) -> anyhow::Result<Vec<SessionSearchHit>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT m.session_key, s.title, m.role, m.content, m.created_at
+             FROM session_messages m
+             LEFT JOIN sessions s ON s.key = m.session_key
+             WHERE m.id = ?1",
+        )?;
+        let mut hits = Vec::with_capacity(scored.len());
+        for &(id, score) in scored {
+            let mut rows = stmt.query(params![id])?;
+            if let Some(row) = rows.next()? {
+                hits.push(SessionSearchHit {
+                    key: row.get(0)?,
+                    title: row.get(1)?,
+                    role: row.get(2)?,
+                    content: row.get(3)?,
+                    created_at: row.get(4)?,
+                    score: f64::from(score),
+                });
+            }
+        }
+        Ok(hits)
+    }
+}
+
+/// Embed every turn not yet indexed for `model`, `batch_size` turns per
+/// provider call. Stops at the first failed batch; turns indexed before it
+/// are kept.
+pub async fn index_pending(
+    store: &Arc<SqliteSessionStore>,
+    embedder: &dyn EmbeddingProvider,
+    model: &str,
+    batch_size: usize,
+) -> anyhow::Result<IndexReport> {
+    let mut report = IndexReport::default();
+    loop {
+        let owned_model = model.to_string();
+        let pending = blocking(Arc::clone(store), move |store| {
+            store.unindexed_messages(&owned_model, batch_size.max(1))
+        })
+        .await?;
+        if pending.is_empty() {
+            return Ok(report);
+        }
+        let (indexable, skipped): (Vec<_>, Vec<_>) = pending
+            .iter()
+            .partition(|(_, role, content)| is_indexable(role, content));
+        let texts: Vec<&str> = indexable.iter().map(|(_, _, c)| c.as_str()).collect();
+        let vectors = if texts.is_empty() {
+            Vec::new()
+        } else {
+            embedder.embed(&texts).await?
+        };
+        if vectors.len() != texts.len() {
+            anyhow::bail!(
+                "embedding provider returned {} vectors for {} texts",
+                vectors.len(),
+                texts.len()
+            );
+        }
+        let mut entries: Vec<(i64, Option<Vec<f32>>)> =
+            skipped.iter().map(|(id, ..)| (*id, None)).collect();
+        entries.extend(
+            indexable
+                .iter()
+                .map(|(id, ..)| *id)
+                .zip(vectors.into_iter().map(Some)),
+        );
+        report.embedded += indexable.len();
+        report.skipped += skipped.len();
+        let owned_model = model.to_string();
+        blocking(Arc::clone(store), move |store| {
+            let entries: Vec<(i64, Option<&[f32]>)> = entries
+                .iter()
+                .map(|(id, vector)| (*id, vector.as_deref()))
+                .collect();
+            store.store_embeddings(&owned_model, &entries)
+        })
+        .await?;
+    }
+}
+
+/// The `limit` indexed turns closest in meaning to `query`, best first,
+/// limited to the session `key` when one is given.
+pub async fn search(
+    store: &Arc<SqliteSessionStore>,
+    embedder: &dyn EmbeddingProvider,
+    model: &str,
+    query: &str,
+    key: Option<&str>,
+    limit: usize,
+) -> anyhow::Result<Vec<SessionSearchHit>> {
+    let query = embedder.embed_one(query).await?;
+    let (model, key) = (model.to_string(), key.map(str::to_string));
+    blocking(Arc::clone(store), move |store| {
+        let mut index = BruteForceIndex::new(store, &model);
+        if let Some(key) = key.as_deref() {
+            index = index.in_session(key);
+        }
+        let nearest = index.nearest(&query, limit)?;
+        store.hits_for(&nearest)
+    })
+    .await
+}
+
+/// Index new turns of `store` every minute until the runtime stops.
+pub fn spawn_indexer(
+    store: Arc<SqliteSessionStore>,
+    embedder: Arc<dyn EmbeddingProvider>,
+    model: String,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(INDEX_INTERVAL);
+        loop {
+            interval.tick().await;
+            match index_pending(&store, embedder.as_ref(), &model, INDEX_BATCH_SIZE).await {
+                Ok(report) if report.embedded > 0 => {
+                    tracing::debug!("session indexer embedded {} turn(s)", report.embedded);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("session indexing failed: {e:#}"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use tempfile::TempDir;
+
+    /// Embeds text as counts of a few topic words, so related turns share
+    /// directions.
+    #[derive(Default)]
+    struct TopicEmbedder {
+        batches: Mutex<Vec<usize>>,
+    }
+
+    const TOPICS: [&[&str]; 3] = [
+        &["hosting", "hetzner", "server", "provider"],
+        &["dinner", "pasta", "restaurant"],
+        &["flight", "airport", "berlin"],
+    ];
+
+    fn topic_vector(text: &str) -> Vec<f32> {
+        let text = text.to_lowercase();
+        TOPICS
+            .iter()
+            .map(|words| words.iter().filter(|w| text.contains(*w)).count() as f32)
+            .collect()
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for TopicEmbedder {
+        fn name(&self) -> &str {
+            "topics"
+        }
+
+        fn dimensions(&self) -> usize {
+            TOPICS.len()
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            self.batches.lock().push(texts.len());
+            Ok(texts.iter().map(|t| topic_vector(t)).collect())
+        }
+    }
+
+    fn temp_store() -> (TempDir, Arc<SqliteSessionStore>) {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        (tmp, Arc::new(store))
+    }
+
+    #[test]
+    fn skips_tool_output_redactions_and_short_turns() {
+        assert!(is_indexable("user", "We'll go with Hetzner for hosting."));
+        assert!(!is_indexable("user", "ok thanks"));
+        assert!(!is_indexable(
+            "system",
+            "You are a helpful assistant, be brief."
+        ));
+        assert!(!is_indexable(
+            "user",
+            "[Tool results]\n<tool_result name=\"shell\">done</tool_result>"
+        ));
+        assert!(!is_indexable("user", REDACTED_CONTENT));
+    }
+
+    #[tokio::test]
+    async fn ranks_by_meaning_and_indexes_in_batches() {
+        let (_tmp, store) = temp_store();
+        store
+            .append_message(
+                "work",
+                "user",
+                "We'll go with Hetzner, their server prices are fair.",
+            )
+            .unwrap();
+        store.append_message("work", "assistant", "ok").unwrap();
+        store
+            .append_message(
+                "home",
+                "user",
+                "Booked the pasta restaurant for dinner on Friday.",
+            )
+            .unwrap();
+        store
+            .append_message(
+                "trip",
+                "user",
+                "My flight lands at the Berlin airport at noon.",
+            )
+            .unwrap();
+        let embedder = TopicEmbedder::default();
+
+        let report = index_pending(&store, &embedder, "topics-v1", 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            report,
+            IndexReport {
+                embedded: 3,
+                skipped: 1
+            }
+        );
+        assert_eq!(*embedder.batches.lock(), [1, 2]);
+        // Nothing is embedded twice.
+        let again = index_pending(&store, &embedder, "topics-v1", 2)
+            .await
+            .unwrap();
+        assert_eq!(again, IndexReport::default());
+
+        let hits = search(
+            &store,
+            &embedder,
+            "topics-v1",
+            "which hosting provider?",
+            None,
+            2,
+        )
+        .await
+        .unwrap();
+        assert_eq!(hits.len(), 1, "unrelated turns are not returned");
+        assert_eq!(hits[0].key, "work");
+        assert!(hits[0].content.contains("Hetzner"));
+        let elsewhere = search(&store, &embedder, "topics-v1", "hosting", Some("home"), 5)
+            .await
+            .unwrap();
+        assert!(elsewhere.is_empty(), "other sessions are not searched");
+
+        // Another model's vectors are neither compared nor mistaken for current.
+        assert!(search(&store, &embedder, "topics-v2", "hosting", None, 5)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn redacted_and_deleted_turns_leave_the_index() {
+        let (_tmp, store) = temp_store();
+        store
+            .append_message(
+                "work",
+                "user",
+                "The hosting provider is Hetzner, server in Falkenstein.",
+            )
+            .unwrap();
+        store
+            .append_message(
+                "gone",
+                "user",
+                "Our hetzner hosting bill is due on the first.",
+            )
+            .unwrap();
+        let embedder = TopicEmbedder::default();
+        index_pending(&store, &embedder, "m", 8).await.unwrap();
+
+        let id = store.recent_user_message_id("work", 0).unwrap().unwrap();
+        store.redact_message(id).unwrap();
+        store.delete_session("gone").unwrap();
+        let report = index_pending(&store, &embedder, "m", 8).await.unwrap();
+        assert_eq!(
+            report,
+            IndexReport {
+                embedded: 0,
+                skipped: 1
+            }
+        );
+        assert!(search(&store, &embedder, "m", "hosting", None, 5)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,301 @@
+//! Per-session overrides of the agent defaults (model, temperature, extra
+//! system prompt), persisted in the `session_settings` table.
+//!
+//! Session settings win over `[agent.channel_overrides.<channel>]`, which win
+//! over the global defaults.
+
+use crate::config::{ChannelOverrideConfig, Config};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Accepted sampling temperature range, matching what providers accept.
+pub const TEMPERATURE_RANGE: std::ops::RangeInclusive<f64> = 0.0..=2.0;
+
+/// Longest `system_prompt_extra` accepted, in characters.
+pub const SYSTEM_PROMPT_EXTRA_MAX_CHARS: usize = 4000;
+
+/// Overrides stored for one session. Unset fields fall back to the defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SessionSettings {
+    pub model: Option<String>,
+    pub temperature: Option<f64>,
+    /// Recorded for providers that accept an output cap; the channel runtime
+    /// does not forward it yet.
+    pub max_tokens: Option<u32>,
+    /// Appended to the system prompt for this session only.
+    pub system_prompt_extra: Option<String>,
+    /// RFC 3339 time of the last change, set by the store.
+    pub updated_at: Option<String>,
+}
+
+/// Partial update: an absent field keeps the stored value, `null` clears it.
+#[allow(clippy::option_option)]
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SessionSettingsPatch {
+    #[serde(default, deserialize_with = "nullable")]
+    pub model: Option<Option<String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub temperature: Option<Option<f64>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub max_tokens: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub system_prompt_extra: Option<Option<String>>,
+}
+
+#[allow(clippy::option_option)]
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+impl SessionSettings {
+    pub fn is_empty(&self) -> bool {
+        self.model.is_none()
+            && self.temperature.is_none()
+            && self.max_tokens.is_none()
+            && self.system_prompt_extra.is_none()
+    }
+
+    /// Apply `patch`, treating blank strings as clearing the field.
+    pub fn apply(&mut self, patch: SessionSettingsPatch) {
+        let non_blank = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        if let Some(model) = patch.model {
+            self.model = non_blank(model);
+        }
+        if let Some(temperature) = patch.temperature {
+            self.temperature = temperature;
+        }
+        if let Some(max_tokens) = patch.max_tokens {
+            self.max_tokens = max_tokens;
+        }
+        if let Some(extra) = patch.system_prompt_extra {
+            self.system_prompt_extra = non_blank(extra);
+        }
+    }
+
+    /// Check ranges and that `model` is known to the default provider.
+    ///
+    /// Models are checked against the catalog cached by `zeroclaw models
+    /// refresh`; without a cache any well-formed model ID is accepted.
+    pub async fn validate(&self, config: &Config) -> anyhow::Result<()> {
+        if let Some(temperature) = self.temperature {
+            if !TEMPERATURE_RANGE.contains(&temperature) {
+                anyhow::bail!("temperature must be between 0 and 2 (got {temperature})");
+            }
+        }
+        if self.max_tokens == Some(0) {
+            anyhow::bail!("max_tokens must be at least 1");
+        }
+        if let Some(extra) = &self.system_prompt_extra {
+            let chars = extra.chars().count();
+            if chars > SYSTEM_PROMPT_EXTRA_MAX_CHARS {
+                anyhow::bail!(
+                    "system_prompt_extra is {chars} characters; the limit is {SYSTEM_PROMPT_EXTRA_MAX_CHARS}"
+                );
+            }
+        }
+        if let Some(model) = &self.model {
+            validate_model(config, model).await?;
+        }
+        Ok(())
+    }
+
+    /// Temperature for a turn: the session's, then the channel override's,
+    /// then `default`.
+    pub fn effective_temperature(&self, channel: &ChannelOverrideConfig, default: f64) -> f64 {
+        self.temperature.or(channel.temperature).unwrap_or(default)
+    }
+
+    /// Output cap for a turn: the session's, then the channel override's.
+    pub fn effective_max_tokens(&self, channel: &ChannelOverrideConfig) -> Option<u32> {
+        self.max_tokens.or(channel.max_tokens)
+    }
+
+    /// `base` with `system_prompt_extra` appended as a session section.
+    pub fn system_prompt(&self, base: &str) -> String {
+        match &self.system_prompt_extra {
+            Some(extra) => format!("{base}\n\n## Session Instructions\n\n{extra}\n"),
+            None => base.to_string(),
+        }
+    }
+}
+
+async fn validate_model(config: &Config, model: &str) -> anyhow::Result<()> {
+    if model.chars().any(char::is_whitespace) {
+        anyhow::bail!("model '{model}' is not a valid model ID");
+    }
+    if model.starts_with("hint:") {
+        anyhow::bail!(
+            "route hints such as '{model}' cannot be pinned to a session; use a concrete model ID"
+        );
+    }
+
+    let provider = config.default_provider.as_deref().unwrap_or("openrouter");
+    let Some(known) = crate::onboard::wizard::cached_model_ids(config, provider).await? else {
+        return Ok(());
+    };
+    let in_catalog = known.iter().any(|m| m == model)
+        || config.model_routes.iter().any(|route| route.model == model);
+    if !in_catalog {
+        anyhow::bail!(
+            "unknown model '{model}' for provider '{provider}' (run `zeroclaw models list` to see available models)"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_config(tmp: &TempDir) -> Config {
+        Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            default_provider: Some("openrouter".into()),
+            ..Config::default()
+        }
+    }
+
+    fn write_model_cache(config: &Config, models: &[&str]) {
+        let state_dir = config.workspace_dir.join("state");
+        std::fs::create_dir_all(&state_dir).unwrap();
+        let cache = serde_json::json!({
+            "entries": [{
+                "provider": "openrouter",
+                "fetched_at_unix": 0,
+                "models": models,
+            }]
+        });
+        std::fs::write(
+            state_dir.join("models_cache.json"),
+            serde_json::to_vec(&cache).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn patch_distinguishes_absent_from_null() {
+        let mut settings = SessionSettings {
+            model: Some("openai/gpt-4o".into()),
+            temperature: Some(0.3),
+            ..SessionSettings::default()
+        };
+        let patch: SessionSettingsPatch =
+            serde_json::from_str(r#"{"temperature": null, "system_prompt_extra": "Be terse."}"#)
+                .unwrap();
+        settings.apply(patch);
+        assert_eq!(settings.model.as_deref(), Some("openai/gpt-4o"));
+        assert_eq!(settings.temperature, None);
+        assert_eq!(settings.system_prompt_extra.as_deref(), Some("Be terse."));
+
+        settings.apply(serde_json::from_str(r#"{"model": "  "}"#).unwrap());
+        assert!(settings.model.is_none());
+    }
+
+    #[tokio::test]
+    async fn validate_rejects_out_of_range_values() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+
+        let hot = SessionSettings {
+            temperature: Some(2.5),
+            ..SessionSettings::default()
+        };
+        let err = hot.validate(&config).await.unwrap_err().to_string();
+        assert!(err.contains("between 0 and 2"), "{err}");
+
+        let zero = SessionSettings {
+            max_tokens: Some(0),
+            ..SessionSettings::default()
+        };
+        assert!(zero.validate(&config).await.is_err());
+
+        let hint = SessionSettings {
+            model: Some("hint:fast".into()),
+            ..SessionSettings::default()
+        };
+        assert!(hint.validate(&config).await.is_err());
+
+        let ok = SessionSettings {
+            temperature: Some(2.0),
+            model: Some("any/model".into()),
+            ..SessionSettings::default()
+        };
+        ok.validate(&config).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn validate_checks_model_against_cached_catalog() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        write_model_cache(
+            &config,
+            &["openai/gpt-4o-mini", "anthropic/claude-sonnet-4"],
+        );
+
+        let known = SessionSettings {
+            model: Some("openai/gpt-4o-mini".into()),
+            ..SessionSettings::default()
+        };
+        known.validate(&config).await.unwrap();
+
+        let unknown = SessionSettings {
+            model: Some("openai/gpt-7".into()),
+            ..SessionSettings::default()
+        };
+        let err = unknown.validate(&config).await.unwrap_err().to_string();
+        assert!(err.contains("unknown model 'openai/gpt-7'"), "{err}");
+    }
+
+    #[test]
+    fn session_settings_win_over_channel_override_over_defaults() {
+        let channel = ChannelOverrideConfig {
+            system_prompt_suffix: Some("Keep replies short.".into()),
+            max_tokens: Some(300),
+            temperature: Some(0.2),
+        };
+        let none = SessionSettings::default();
+        assert_eq!(none.effective_temperature(&channel, 0.7), 0.2);
+        assert_eq!(none.effective_max_tokens(&channel), Some(300));
+        assert_eq!(
+            none.effective_temperature(&ChannelOverrideConfig::default(), 0.7),
+            0.7
+        );
+
+        let session = SessionSettings {
+            temperature: Some(1.1),
+            max_tokens: Some(50),
+            system_prompt_extra: Some("Answer in French.".into()),
+            ..SessionSettings::default()
+        };
+        assert_eq!(session.effective_temperature(&channel, 0.7), 1.1);
+        assert_eq!(session.effective_max_tokens(&channel), Some(50));
+
+        // The session section comes last so it can refine the channel's.
+        let prompt = session.system_prompt(&channel.system_prompt("base"));
+        let channel_at = prompt.find("## Channel Instructions").unwrap();
+        let session_at = prompt.find("## Session Instructions").unwrap();
+        assert!(prompt.starts_with("base\n\n"));
+        assert!(channel_at < session_at);
+    }
+
+    #[test]
+    fn system_prompt_appends_session_instructions() {
+        let settings = SessionSettings {
+            system_prompt_extra: Some("Answer in one sentence.".into()),
+            ..SessionSettings::default()
+        };
+        let prompt = settings.system_prompt("base");
+        assert!(prompt.starts_with("base\n\n## Session Instructions"));
+        assert!(prompt.contains("Answer in one sentence."));
+        assert_eq!(SessionSettings::default().system_prompt("base"), "base");
+    }
+}
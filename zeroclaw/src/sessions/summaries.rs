@@ -0,0 +1,114 @@
+//! Summary history of stored sessions.
+//!
+//! Every summarization appends a row to `session_summaries` instead of only
+//! overwriting `sessions.summary`, which keeps mirroring the latest one for
+//! prompt building and older readers. Earlier summaries stay available for
+//! auditing what compaction dropped (`GET /api/sessions/:key/summaries`) and
+//! for the `session_recall` tool.
+
+use super::SqliteSessionStore;
+use chrono::Local;
+use rusqlite::{params, Row};
+
+/// One recorded summary of a session.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct SessionSummary {
+    pub id: i64,
+    pub summary: String,
+    /// Turns the session held when the summary was written.
+    pub message_count_at: usize,
+    pub created_at: String,
+}
+
+impl SessionSummary {
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            summary: row.get(1)?,
+            message_count_at: usize::try_from(row.get::<_, i64>(2)?).unwrap_or(0),
+            created_at: row.get(3)?,
+        })
+    }
+}
+
+impl SqliteSessionStore {
+    /// Record a new summary for `key`: appended to the history and mirrored
+    /// into `sessions.summary`. Returns `false` (and records nothing) when
+    /// the session does not exist.
+    pub fn set_summary(&self, key: &str, summary: &str) -> anyhow::Result<bool> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let updated = tx.execute(
+            "UPDATE sessions SET summary = ?2 WHERE key = ?1",
+            params![key, summary],
+        )?;
+        if updated == 0 {
+            return Ok(false);
+        }
+        tx.execute(
+            "INSERT INTO session_summaries (session_key, summary, message_count_at, created_at)
+             VALUES (?1, ?2,
+                     (SELECT COUNT(*) FROM session_messages WHERE session_key = ?1),
+                     ?3)",
+            params![key, summary, Local::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Summaries recorded for `key`, newest first, at most `limit` when given.
+    pub fn summary_history(
+        &self,
+        key: &str,
+        limit: Option<usize>,
+    ) -> anyhow::Result<Vec<SessionSummary>> {
+        let conn = self.conn.lock();
+        let limit = limit.map_or(-1, |n| i64::try_from(n).unwrap_or(i64::MAX));
+        let mut stmt = conn.prepare(
+            "SELECT id, summary, message_count_at, created_at FROM session_summaries
+             WHERE session_key = ?1
+             ORDER BY id DESC
+             LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![key, limit], SessionSummary::from_row)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn each_summary_is_appended_and_latest_is_mirrored() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        assert!(!store.set_summary("k", "too early").unwrap());
+        assert!(store.summary_history("k", None).unwrap().is_empty());
+
+        store.append_message("k", "user", "a").unwrap();
+        assert!(store.set_summary("k", "talked about a").unwrap());
+        store.append_message("k", "assistant", "b").unwrap();
+        store.append_message("k", "user", "c").unwrap();
+        assert!(store.set_summary("k", "talked about a, b and c").unwrap());
+
+        assert_eq!(
+            store.summary("k").unwrap().as_deref(),
+            Some("talked about a, b and c")
+        );
+        let history = store.summary_history("k", None).unwrap();
+        let recorded: Vec<(&str, usize)> = history
+            .iter()
+            .map(|s| (s.summary.as_str(), s.message_count_at))
+            .collect();
+        assert_eq!(
+            recorded,
+            vec![("talked about a, b and c", 3), ("talked about a", 1)]
+        );
+        assert_eq!(store.summary_history("k", Some(1)).unwrap().len(), 1);
+
+        assert!(store.delete_session("k").unwrap());
+        assert!(store.summary_history("k", None).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,218 @@
+//! Short session titles generated from the first few exchanges.
+
+use super::{blocking, store_for, SqliteSessionStore};
+use crate::providers::Provider;
+use crate::util::truncate_with_ellipsis;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Turns a session must accumulate (two exchanges) before it gets a title.
+pub const TITLE_MIN_MESSAGES: usize = 4;
+
+const TITLE_MAX_CHARS: usize = 60;
+const TITLE_TRANSCRIPT_MESSAGE_CHARS: usize = 400;
+/// Output tokens a title reply may use; six words fit comfortably.
+const TITLE_MAX_TOKENS: u32 = 20;
+
+const TITLE_SYSTEM_PROMPT: &str = "You name chat conversations. Reply with a short title \
+(at most 6 words) describing the topic of the conversation. Reply with the title only: \
+no quotes, no trailing punctuation, no explanation.";
+
+/// Generate and store a title for `key` if it is untitled and long enough.
+///
+/// Returns the stored title, or `None` when the session already has one (or
+/// is too short) and no LLM call was made.
+pub async fn generate_title(
+    store: &Arc<SqliteSessionStore>,
+    provider: &dyn Provider,
+    model: &str,
+    key: &str,
+) -> anyhow::Result<Option<String>> {
+    let owned_key = key.to_string();
+    let history = blocking(Arc::clone(store), move |store| {
+        if !store.needs_title(&owned_key, TITLE_MIN_MESSAGES)? {
+            return Ok(None);
+        }
+        store
+            .load_history(&owned_key, Some(TITLE_MIN_MESSAGES))
+            .map(Some)
+    })
+    .await?;
+    let Some(history) = history else {
+        return Ok(None);
+    };
+    let transcript = history
+        .iter()
+        .map(|m| {
+            format!(
+                "{}: {}",
+                m.role,
+                truncate_with_ellipsis(&m.content, TITLE_TRANSCRIPT_MESSAGE_CHARS)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let raw = crate::providers::with_max_output_tokens(
+        TITLE_MAX_TOKENS,
+        provider.chat_with_system(Some(TITLE_SYSTEM_PROMPT), &transcript, model, 0.2),
+    )
+    .await?;
+    let Some(title) = sanitize_title(&raw) else {
+        return Ok(None);
+    };
+
+    let (owned_key, stored) = (key.to_string(), title.clone());
+    if blocking(Arc::clone(store), move |store| {
+        store.set_title_if_absent(&owned_key, &stored)
+    })
+    .await?
+    {
+        Ok(Some(title))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Fire-and-forget title generation for the channel runtime. No-op when
+/// session persistence is not enabled for `workspace_dir`.
+pub fn spawn_title_generation(
+    workspace_dir: &Path,
+    key: &str,
+    provider: Arc<dyn Provider>,
+    model: String,
+) {
+    let Some(store) = store_for(workspace_dir) else {
+        return;
+    };
+
+    let key = key.to_string();
+    tokio::spawn(async move {
+        match generate_title(&store, provider.as_ref(), &model, &key).await {
+            Ok(Some(title)) => tracing::debug!("Session {key} titled: {title}"),
+            Ok(None) => {}
+            Err(e) => tracing::debug!("Session title generation failed for {key}: {e}"),
+        }
+    });
+}
+
+/// First non-empty line, stripped of quotes/markup and clamped in length.
+fn sanitize_title(raw: &str) -> Option<String> {
+    let line = raw.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = line.strip_prefix("Title:").unwrap_or(line);
+    let cleaned = line
+        .trim()
+        .trim_matches(|c: char| matches!(c, '"' | '\'' | '`' | '*' | '#'))
+        .trim_end_matches(['.', '!', ':'])
+        .trim();
+    if cleaned.is_empty() {
+        return None;
+    }
+    Some(truncate_with_ellipsis(cleaned, TITLE_MAX_CHARS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    #[derive(Default)]
+    struct TitleProvider {
+        calls: AtomicUsize,
+        max_tokens: AtomicU32,
+        reply: &'static str,
+    }
+
+    #[async_trait]
+    impl Provider for TitleProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let max_tokens = crate::providers::max_output_tokens().unwrap_or(0);
+            self.max_tokens.store(max_tokens, Ordering::SeqCst);
+            Ok(self.reply.to_string())
+        }
+    }
+
+    fn seeded_store(turns: usize) -> (TempDir, Arc<SqliteSessionStore>) {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        for i in 0..turns {
+            let role = if i % 2 == 0 { "user" } else { "assistant" };
+            store
+                .append_message("k", role, &format!("turn {i}"))
+                .unwrap();
+        }
+        (tmp, Arc::new(store))
+    }
+
+    #[tokio::test]
+    async fn generates_and_stores_title() {
+        let (_tmp, store) = seeded_store(TITLE_MIN_MESSAGES);
+        let provider = TitleProvider {
+            reply: "\"Planning a Lisbon trip.\"\n",
+            ..TitleProvider::default()
+        };
+
+        let title = generate_title(&store, &provider, "m", "k").await.unwrap();
+        assert_eq!(title.as_deref(), Some("Planning a Lisbon trip"));
+        assert_eq!(
+            store.title("k").unwrap().as_deref(),
+            Some("Planning a Lisbon trip")
+        );
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(provider.max_tokens.load(Ordering::SeqCst), TITLE_MAX_TOKENS);
+    }
+
+    #[tokio::test]
+    async fn skips_llm_call_when_title_exists() {
+        let (_tmp, store) = seeded_store(TITLE_MIN_MESSAGES);
+        store.set_title("k", "Existing").unwrap();
+        let provider = TitleProvider {
+            reply: "New title",
+            ..TitleProvider::default()
+        };
+
+        let title = generate_title(&store, &provider, "m", "k").await.unwrap();
+        assert!(title.is_none());
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 0);
+        assert_eq!(store.title("k").unwrap().as_deref(), Some("Existing"));
+    }
+
+    #[tokio::test]
+    async fn skips_llm_call_for_short_sessions() {
+        let (_tmp, store) = seeded_store(TITLE_MIN_MESSAGES - 1);
+        let provider = TitleProvider {
+            reply: "Too early",
+            ..TitleProvider::default()
+        };
+
+        assert!(generate_title(&store, &provider, "m", "k")
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn sanitize_title_cleans_model_output() {
+        assert_eq!(
+            sanitize_title("Title: **Rust lifetimes**").as_deref(),
+            Some("Rust lifetimes")
+        );
+        assert_eq!(
+            sanitize_title("\n\n  'Weekly report'  \nextra").as_deref(),
+            Some("Weekly report")
+        );
+        assert_eq!(sanitize_title("   \n\"\""), None);
+        let long = "word ".repeat(40);
+        assert!(sanitize_title(&long).unwrap().chars().count() <= TITLE_MAX_CHARS + 3);
+    }
+}
@@ -0,0 +1,361 @@
+//! Operator approvals for skill sandbox permissions.
+//!
+//! A skill's `[permissions]` only widen the built-in tools it names once an
+//! operator approves the skill with `zeroclaw skills approve`. Approvals are
+//! stored in `approved_skills.json` next to `config.toml`, outside the
+//! workspace the agent can write to, and apply globally, to
+//! one channel (`--channel telegram`) or to one sender on a channel
+//! (`--channel telegram --sender 123`). `--deny` records a denial at the
+//! given scope. For a call, the most specific matching entry decides: sender
+//! beats channel beats global, and a skill without a matching entry is not
+//! approved.
+//!
+//! Older files hold a flat list of skill names; each loads as a global
+//! approval. Without the file nothing is approved, so skills run their tools
+//! with the default sandbox.
+//!
+//! Tool calls check approvals against a copy loaded once per config
+//! directory and reloaded when the file's size or modification time changes.
+
+use crate::config::Config;
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::SystemTime;
+
+/// Approvals file name, relative to the config directory.
+pub const APPROVALS_FILE: &str = "approved_skills.json";
+const APPROVALS_VERSION: u32 = 1;
+
+/// One approval (or denial) of a skill at a scope.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillApproval {
+    pub skill: String,
+    /// Channel name the approval is limited to; `None` is global.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Sender id on `channel` the approval is limited to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<String>,
+    /// `false` denies the skill at this scope.
+    #[serde(default = "default_approved")]
+    pub approved: bool,
+}
+
+fn default_approved() -> bool {
+    true
+}
+
+impl SkillApproval {
+    /// Human-readable scope: `global`, `channel telegram` or
+    /// `sender telegram:123`.
+    pub fn scope(&self) -> String {
+        match (&self.channel, &self.sender) {
+            (Some(channel), Some(sender)) => format!("sender {channel}:{sender}"),
+            (Some(channel), None) => format!("channel {channel}"),
+            _ => "global".into(),
+        }
+    }
+
+    /// Specificity of a matching entry for `channel`/`sender`; `None` when
+    /// the entry does not apply.
+    fn rank(&self, channel: Option<&str>, sender: Option<&str>) -> Option<u8> {
+        match (self.channel.as_deref(), self.sender.as_deref()) {
+            (None, _) => Some(0),
+            (Some(c), None) if Some(c) == channel => Some(1),
+            (Some(c), Some(s)) if Some(c) == channel && Some(s) == sender => Some(2),
+            _ => None,
+        }
+    }
+
+    fn same_scope(&self, other: &Self) -> bool {
+        self.skill == other.skill && self.channel == other.channel && self.sender == other.sender
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredApprovals {
+    /// Current format: `{"version": 1, "approvals": [...]}`.
+    Scoped {
+        #[serde(rename = "version")]
+        _version: u32,
+        approvals: Vec<SkillApproval>,
+    },
+    /// Original format: a list of globally approved skill names.
+    Legacy(Vec<String>),
+}
+
+/// The approvals recorded in a config directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApprovedSkills {
+    approvals: Vec<SkillApproval>,
+}
+
+impl ApprovedSkills {
+    /// Directory holding the approvals of `config`: the one holding its
+    /// `config.toml`.
+    pub fn dir(config: &Config) -> &Path {
+        config
+            .config_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+    }
+
+    pub fn path(config_dir: &Path) -> PathBuf {
+        config_dir.join(APPROVALS_FILE)
+    }
+
+    /// Approvals in `config_dir`, or `None` when none were ever recorded.
+    pub fn load(config_dir: &Path) -> Result<Option<Self>> {
+        let path = Self::path(config_dir);
+        let raw = match std::fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("failed to read {}", path.display())),
+        };
+        let stored: StoredApprovals = serde_json::from_str(&raw)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        let approvals = match stored {
+            StoredApprovals::Scoped { approvals, .. } => approvals,
+            StoredApprovals::Legacy(names) => names
+                .into_iter()
+                .map(|skill| SkillApproval {
+                    skill,
+                    channel: None,
+                    sender: None,
+                    approved: true,
+                })
+                .collect(),
+        };
+        Ok(Some(Self { approvals }))
+    }
+
+    /// Write the approvals in the current format.
+    pub fn save(&self, config_dir: &Path) -> Result<()> {
+        let path = Self::path(config_dir);
+        let body = serde_json::to_string_pretty(&serde_json::json!({
+            "version": APPROVALS_VERSION,
+            "approvals": self.approvals,
+        }))?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, body).with_context(|| format!("failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, &path)
+            .with_context(|| format!("failed to replace {}", path.display()))?;
+        // A rewrite within the timestamp granularity can keep the old stamp.
+        LOADED.lock().remove(&path);
+        Ok(())
+    }
+
+    /// Record `approval`, replacing an earlier entry for the same scope.
+    pub fn set(&mut self, approval: SkillApproval) {
+        match self.approvals.iter_mut().find(|a| a.same_scope(&approval)) {
+            Some(existing) => *existing = approval,
+            None => self.approvals.push(approval),
+        }
+    }
+
+    /// Entries recorded for `skill`.
+    pub fn for_skill<'a>(&'a self, skill: &'a str) -> impl Iterator<Item = &'a SkillApproval> {
+        self.approvals.iter().filter(move |a| a.skill == skill)
+    }
+
+    /// Whether `skill` is approved for a call from `sender` on `channel`
+    /// (both `None` outside a channel conversation).
+    pub fn is_approved(&self, skill: &str, channel: Option<&str>, sender: Option<&str>) -> bool {
+        self.for_skill(skill)
+            .filter_map(|a| a.rank(channel, sender).map(|rank| (rank, a.approved)))
+            .max_by_key(|(rank, _)| *rank)
+            .is_some_and(|(_, approved)| approved)
+    }
+}
+
+/// Size and modification time of an approvals file, `None` when missing.
+type FileStamp = Option<(Option<SystemTime>, u64)>;
+
+/// Approvals loaded from a file, with the stamp of the file they were
+/// loaded from. A load error is kept as `None`.
+type LoadedApprovals = (FileStamp, Option<ApprovedSkills>);
+
+/// Loaded approvals, keyed by file path.
+static LOADED: LazyLock<Mutex<HashMap<PathBuf, LoadedApprovals>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn file_stamp(path: &Path) -> FileStamp {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok(), metadata.len()))
+}
+
+/// Whether the permissions of `skill` apply to a call from `sender` on
+/// `channel`, per the approvals in `config_dir`. Without an approvals file, or with an
+/// unreadable one, nothing is approved.
+pub fn permissions_apply(
+    config_dir: &Path,
+    skill: &str,
+    channel: Option<&str>,
+    sender: Option<&str>,
+) -> bool {
+    let path = ApprovedSkills::path(config_dir);
+    let stamp = file_stamp(&path);
+    let mut loaded = LOADED.lock();
+    let fresh = loaded
+        .get(&path)
+        .is_some_and(|(cached, _)| *cached == stamp);
+    if !fresh {
+        let approvals = ApprovedSkills::load(config_dir).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring skill permissions: {e:#}");
+            None
+        });
+        loaded.insert(path.clone(), (stamp, approvals));
+    }
+    loaded
+        .get(&path)
+        .and_then(|(_, approvals)| approvals.as_ref())
+        .is_some_and(|approvals| approvals.is_approved(skill, channel, sender))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(channel: Option<&str>, sender: Option<&str>, approved: bool) -> SkillApproval {
+        SkillApproval {
+            skill: "shell-admin".into(),
+            channel: channel.map(Into::into),
+            sender: sender.map(Into::into),
+            approved,
+        }
+    }
+
+    #[test]
+    fn legacy_flat_list_loads_as_global_approvals() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(
+            ApprovedSkills::path(tmp.path()),
+            r#"["shell-admin", "notes"]"#,
+        )
+        .unwrap();
+
+        let approvals = ApprovedSkills::load(tmp.path()).unwrap().unwrap();
+        assert!(approvals.is_approved("shell-admin", Some("slack"), Some("U1")));
+        assert!(approvals.is_approved("notes", None, None));
+        assert!(!approvals.is_approved("other", None, None));
+        assert_eq!(
+            approvals.for_skill("notes").next().unwrap().scope(),
+            "global"
+        );
+    }
+
+    #[test]
+    fn saved_approvals_round_trip_in_the_scoped_format() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(ApprovedSkills::path(tmp.path()), r#"["shell-admin"]"#).unwrap();
+        let mut approvals = ApprovedSkills::load(tmp.path()).unwrap().unwrap();
+        approvals.set(entry(Some("telegram"), Some("42"), true));
+        approvals.set(entry(None, None, false));
+        approvals.save(tmp.path()).unwrap();
+
+        let raw: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(ApprovedSkills::path(tmp.path())).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(raw["version"], 1);
+        assert_eq!(raw["approvals"].as_array().unwrap().len(), 2);
+        let reloaded = ApprovedSkills::load(tmp.path()).unwrap().unwrap();
+        assert_eq!(reloaded, approvals);
+        assert_eq!(
+            reloaded
+                .for_skill("shell-admin")
+                .map(SkillApproval::scope)
+                .collect::<Vec<_>>(),
+            vec!["global", "sender telegram:42"]
+        );
+    }
+
+    #[test]
+    fn most_specific_scope_decides() {
+        let mut approvals = ApprovedSkills::default();
+        approvals.set(entry(None, None, false));
+        approvals.set(entry(Some("telegram"), None, true));
+        approvals.set(entry(Some("telegram"), Some("mallory"), false));
+        approvals.set(entry(Some("slack"), Some("U-me"), true));
+
+        let ok = |channel, sender| approvals.is_approved("shell-admin", channel, sender);
+        // Global deny.
+        assert!(!ok(None, None));
+        assert!(!ok(Some("discord"), Some("anyone")));
+        // Channel beats global.
+        assert!(ok(Some("telegram"), Some("alice")));
+        // Sender beats channel.
+        assert!(!ok(Some("telegram"), Some("mallory")));
+        // Sender beats global, only on its own channel.
+        assert!(ok(Some("slack"), Some("U-me")));
+        assert!(!ok(Some("slack"), Some("U-other")));
+        assert!(!ok(Some("discord"), Some("U-me")));
+    }
+
+    #[test]
+    fn unlisted_skill_is_not_approved_once_approvals_exist() {
+        let mut approvals = ApprovedSkills::default();
+        approvals.set(entry(Some("telegram"), None, true));
+        assert!(!approvals.is_approved("shell-admin", Some("slack"), None));
+        assert!(!approvals.is_approved("notes", Some("telegram"), None));
+    }
+
+    #[test]
+    fn approvals_live_next_to_config_toml_not_in_the_workspace() {
+        let tmp = TempDir::new().unwrap();
+        let config = Config {
+            config_path: tmp.path().join("config.toml"),
+            workspace_dir: tmp.path().join("workspace"),
+            ..Config::default()
+        };
+        assert_eq!(
+            ApprovedSkills::path(ApprovedSkills::dir(&config)),
+            tmp.path().join(APPROVALS_FILE)
+        );
+
+        // A file the agent drops into its workspace grants nothing.
+        std::fs::create_dir_all(&config.workspace_dir).unwrap();
+        std::fs::write(
+            config.workspace_dir.join(APPROVALS_FILE),
+            r#"["shell-admin"]"#,
+        )
+        .unwrap();
+        assert!(!permissions_apply(
+            ApprovedSkills::dir(&config),
+            "shell-admin",
+            None,
+            None
+        ));
+    }
+
+    #[test]
+    fn permissions_are_denied_without_an_approval_record() {
+        let tmp = TempDir::new().unwrap();
+        assert!(!permissions_apply(tmp.path(), "shell-admin", None, None));
+
+        std::fs::write(ApprovedSkills::path(tmp.path()), "{not json").unwrap();
+        assert!(!permissions_apply(tmp.path(), "shell-admin", None, None));
+    }
+
+    #[test]
+    fn permissions_follow_changes_to_the_approvals_file() {
+        let tmp = TempDir::new().unwrap();
+        let mut approvals = ApprovedSkills::default();
+        approvals.set(entry(None, None, true));
+        approvals.save(tmp.path()).unwrap();
+        assert!(permissions_apply(tmp.path(), "shell-admin", None, None));
+
+        approvals.set(entry(None, None, false));
+        approvals.save(tmp.path()).unwrap();
+        assert!(!permissions_apply(tmp.path(), "shell-admin", None, None));
+
+        std::fs::remove_file(ApprovedSkills::path(tmp.path())).unwrap();
+        assert!(!permissions_apply(tmp.path(), "shell-admin", None, None));
+    }
+}
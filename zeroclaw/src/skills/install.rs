@@ -0,0 +1,501 @@
+//! `skills install` from a local directory, a `.tar.gz` archive or a git URL.
+//!
+//! The package is staged next to the skills directory and validated before
+//! anything is written into `skills/<name>`: `SKILL.toml` must parse, its
+//! name must be a plain directory name that is not installed yet (unless
+//! forced), and the security audit must pass. Requirements declared in the
+//! manifest are checked and reported but do not block the install. The
+//! caller confirms the plan (permissions included) before the final move.
+
+use super::{
+    copy_dir_recursive_secure, enforce_skill_security_audit, is_git_source, remove_git_metadata,
+    SkillManifest,
+};
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const MANIFEST_FILE: &str = "SKILL.toml";
+
+/// Where a skill package comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkillSource {
+    Directory(PathBuf),
+    Archive(PathBuf),
+    Git(String),
+}
+
+impl SkillSource {
+    pub fn parse(source: &str) -> Self {
+        let lower = source.to_ascii_lowercase();
+        if is_git_source(source) {
+            Self::Git(source.to_string())
+        } else if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+            Self::Archive(PathBuf::from(source))
+        } else {
+            Self::Directory(PathBuf::from(source))
+        }
+    }
+}
+
+/// A validated package, shown to the user before it is installed.
+#[derive(Debug)]
+pub struct InstallPlan {
+    pub manifest: SkillManifest,
+    /// Declared requirements that are not met on this machine.
+    pub missing_requirements: Vec<String>,
+    /// An installed skill of the same name will be replaced (`--force`).
+    pub replaces_existing: bool,
+    pub files_scanned: usize,
+}
+
+#[derive(Debug)]
+pub enum InstallOutcome {
+    Installed {
+        dest: PathBuf,
+        plan: Box<InstallPlan>,
+    },
+    Cancelled,
+}
+
+/// Temporary directory beside `skills/`, removed on drop.
+struct Staging(PathBuf);
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Stage, validate and (after `confirm`) install a skill package.
+pub fn install_skill(
+    source: &str,
+    skills_path: &Path,
+    force: bool,
+    confirm: impl FnOnce(&InstallPlan) -> Result<bool>,
+) -> Result<InstallOutcome> {
+    fs::create_dir_all(skills_path)
+        .with_context(|| format!("failed to create {}", skills_path.display()))?;
+    // Outside `skills/` so a half-staged package is never loaded, but on the
+    // same filesystem so the final move is a rename.
+    let staging = Staging(
+        skills_path.with_file_name(format!(".skill-install-{}", uuid::Uuid::new_v4().simple())),
+    );
+    let package = staging.0.join("package");
+
+    match SkillSource::parse(source) {
+        SkillSource::Directory(dir) => {
+            let dir = dir
+                .canonicalize()
+                .with_context(|| format!("Source path does not exist: {source}"))?;
+            copy_dir_recursive_secure(&dir, &package)?;
+        }
+        SkillSource::Archive(archive) => extract_archive(&archive, &package)?,
+        SkillSource::Git(url) => {
+            fs::create_dir_all(&staging.0)?;
+            let output = std::process::Command::new("git")
+                .args(["clone", "--depth", "1", "--", &url])
+                .arg(&package)
+                .output()
+                .context("failed to run git")?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                bail!("Git clone failed: {}", stderr.trim());
+            }
+            remove_git_metadata(&package)?;
+        }
+    }
+
+    let root = package_root(&package)?;
+    let manifest = read_manifest(&root)?;
+    validate_skill_name(&manifest.skill.name)?;
+
+    let dest = skills_path.join(&manifest.skill.name);
+    let replaces_existing = dest.exists();
+    if replaces_existing && !force {
+        bail!(
+            "Skill '{}' is already installed at {} (use --force to replace it)",
+            manifest.skill.name,
+            dest.display()
+        );
+    }
+
+    let report = enforce_skill_security_audit(&root)?;
+    let plan = InstallPlan {
+        missing_requirements: missing_requirements(&manifest),
+        manifest,
+        replaces_existing,
+        files_scanned: report.files_scanned,
+    };
+    if !confirm(&plan)? {
+        return Ok(InstallOutcome::Cancelled);
+    }
+
+    if replaces_existing {
+        let previous = staging.0.join("previous");
+        fs::rename(&dest, &previous)
+            .with_context(|| format!("failed to move aside {}", dest.display()))?;
+        if let Err(err) = fs::rename(&root, &dest) {
+            let _ = fs::rename(&previous, &dest);
+            return Err(err).with_context(|| format!("failed to install into {}", dest.display()));
+        }
+    } else {
+        fs::rename(&root, &dest)
+            .with_context(|| format!("failed to install into {}", dest.display()))?;
+    }
+
+    Ok(InstallOutcome::Installed {
+        dest,
+        plan: Box::new(plan),
+    })
+}
+
+/// Unpack a `.tar.gz` into `dest`, refusing entries that could land outside
+/// it (absolute paths, `..`, links) instead of silently skipping them.
+fn extract_archive(archive: &Path, dest: &Path) -> Result<()> {
+    let file = fs::File::open(archive)
+        .with_context(|| format!("failed to open archive {}", archive.display()))?;
+    let mut tar = tar::Archive::new(GzDecoder::new(file));
+    fs::create_dir_all(dest)?;
+
+    for entry in tar.entries().context("failed to read archive")? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let mut rel = PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::Normal(part) => rel.push(part),
+                Component::CurDir => {}
+                _ => bail!(
+                    "Archive entry escapes the target directory: {}",
+                    path.display()
+                ),
+            }
+        }
+
+        let kind = entry.header().entry_type();
+        if kind.is_dir() {
+            fs::create_dir_all(dest.join(&rel))?;
+        } else if kind.is_file() {
+            let target = dest.join(&rel);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            entry
+                .unpack(&target)
+                .with_context(|| format!("failed to extract {}", path.display()))?;
+        } else if kind.is_symlink() || kind.is_hard_link() {
+            bail!("Archive contains a link, refusing: {}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// The directory holding `SKILL.toml`: the package itself, or its single
+/// top-level directory (how archives and some repos are laid out).
+fn package_root(package: &Path) -> Result<PathBuf> {
+    if package.join(MANIFEST_FILE).is_file() {
+        return Ok(package.to_path_buf());
+    }
+    let entries: Vec<PathBuf> = fs::read_dir(package)
+        .with_context(|| format!("failed to read {}", package.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    if let [only] = entries.as_slice() {
+        if only.is_dir() && only.join(MANIFEST_FILE).is_file() {
+            return Ok(only.clone());
+        }
+    }
+    bail!("Skill package has no {MANIFEST_FILE} manifest")
+}
+
+fn read_manifest(root: &Path) -> Result<SkillManifest> {
+    let raw = fs::read_to_string(root.join(MANIFEST_FILE))
+        .with_context(|| format!("failed to read {MANIFEST_FILE}"))?;
+    toml::from_str(&raw).with_context(|| format!("Invalid {MANIFEST_FILE}"))
+}
+
+/// Skill names become directory names under `skills/`.
+pub fn validate_skill_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !name.contains("..");
+    if !valid {
+        bail!("Invalid skill name '{name}': use letters, digits, '-', '_' or '.'");
+    }
+    Ok(())
+}
+
+/// Human-readable list of declared requirements that are not satisfied.
+fn missing_requirements(manifest: &SkillManifest) -> Vec<String> {
+    let requirements = &manifest.requirements;
+    let bins = requirements
+        .bins
+        .iter()
+        .filter(|bin| which::which(bin).is_err())
+        .map(|bin| format!("binary `{bin}` not found on PATH"));
+    let env = requirements
+        .env
+        .iter()
+        .filter(|var| std::env::var_os(var).is_none_or(|value| value.is_empty()))
+        .map(|var| format!("environment variable `{var}` is not set"));
+    bins.chain(env).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const MANIFEST: &str = r#"
+[skill]
+name = "weather"
+description = "Looks up the forecast"
+version = "1.2.0"
+
+[permissions]
+tools = ["web_fetch"]
+network_domains = ["api.weather.example"]
+
+[requirements]
+bins = ["zeroclaw-test-missing-binary"]
+env = ["ZEROCLAW_TEST_MISSING_ENV"]
+"#;
+
+    fn write_package(dir: &Path, manifest: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(MANIFEST_FILE), manifest).unwrap();
+        fs::write(dir.join("README.md"), "# Weather\n").unwrap();
+    }
+
+    fn accept(_: &InstallPlan) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn install(source: &Path, skills: &Path, force: bool) -> Result<InstallOutcome> {
+        install_skill(source.to_str().unwrap(), skills, force, accept)
+    }
+
+    /// Build a gzipped tarball; names are written raw so unsafe paths can be
+    /// tested (the tar builder itself refuses them).
+    fn write_tarball(path: &Path, entries: &[(&str, tar::EntryType, &[u8])]) {
+        let file = fs::File::create(path).unwrap();
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            file,
+            flate2::Compression::default(),
+        ));
+        for (name, kind, data) in entries {
+            let mut header = tar::Header::new_old();
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_entry_type(*kind);
+            if kind.is_symlink() {
+                header.set_link_name("/etc/passwd").unwrap();
+            }
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append(&header, *data).unwrap();
+        }
+        builder
+            .into_inner()
+            .unwrap()
+            .finish()
+            .unwrap()
+            .flush()
+            .unwrap();
+    }
+
+    #[test]
+    fn classifies_sources() {
+        assert_eq!(
+            SkillSource::parse("https://github.com/org/skill.git"),
+            SkillSource::Git("https://github.com/org/skill.git".into())
+        );
+        assert_eq!(
+            SkillSource::parse("/tmp/skill.TGZ"),
+            SkillSource::Archive("/tmp/skill.TGZ".into())
+        );
+        assert_eq!(
+            SkillSource::parse("./my-skill"),
+            SkillSource::Directory("./my-skill".into())
+        );
+    }
+
+    #[test]
+    fn installs_local_directory_and_reports_requirements() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source = tmp.path().join("src/weather-skill");
+        write_package(&source, MANIFEST);
+        let skills = tmp.path().join("workspace/skills");
+
+        let InstallOutcome::Installed { dest, plan } = install(&source, &skills, false).unwrap()
+        else {
+            panic!("expected install");
+        };
+
+        // Installed under the manifest name, not the source directory name.
+        assert_eq!(dest, skills.join("weather"));
+        assert!(dest.join("README.md").is_file());
+        assert_eq!(plan.manifest.permissions.tools, vec!["web_fetch"]);
+        assert_eq!(plan.missing_requirements.len(), 2);
+        assert!(plan.missing_requirements[0].contains("zeroclaw-test-missing-binary"));
+        assert!(plan.missing_requirements[1].contains("ZEROCLAW_TEST_MISSING_ENV"));
+        // The staging area is cleaned up.
+        let leftovers: Vec<_> = fs::read_dir(tmp.path().join("workspace"))
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                e.file_name()
+                    .to_string_lossy()
+                    .starts_with(".skill-install")
+            })
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_manifests() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skills = tmp.path().join("skills");
+
+        let no_manifest = tmp.path().join("empty");
+        fs::create_dir_all(&no_manifest).unwrap();
+        fs::write(no_manifest.join("SKILL.md"), "# Skill\n").unwrap();
+        let err = install(&no_manifest, &skills, false).unwrap_err();
+        assert!(err.to_string().contains("no SKILL.toml"), "{err}");
+
+        let broken = tmp.path().join("broken");
+        write_package(&broken, "[skill]\nname = \"broken\"\n");
+        let err = install(&broken, &skills, false).unwrap_err();
+        assert!(err.to_string().contains("Invalid SKILL.toml"), "{err}");
+
+        for name in ["../escape", ".hidden", "a/b", ""] {
+            let bad = tmp.path().join("bad-name");
+            let _ = fs::remove_dir_all(&bad);
+            write_package(
+                &bad,
+                &format!("[skill]\nname = \"{name}\"\ndescription = \"x\"\n"),
+            );
+            let err = install(&bad, &skills, false).unwrap_err();
+            assert!(
+                err.to_string().contains("Invalid skill name"),
+                "{name}: {err}"
+            );
+        }
+        assert!(fs::read_dir(&skills).unwrap().next().is_none());
+    }
+
+    #[test]
+    fn collision_requires_force_and_replaces() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skills = tmp.path().join("skills");
+        let source = tmp.path().join("weather");
+        write_package(&source, MANIFEST);
+        install(&source, &skills, false).unwrap();
+
+        fs::write(source.join("README.md"), "# Weather v2\n").unwrap();
+        let err = install(&source, &skills, false).unwrap_err();
+        assert!(err.to_string().contains("already installed"), "{err}");
+        assert_eq!(
+            fs::read_to_string(skills.join("weather/README.md")).unwrap(),
+            "# Weather\n"
+        );
+
+        let InstallOutcome::Installed { plan, .. } = install(&source, &skills, true).unwrap()
+        else {
+            panic!("expected install");
+        };
+        assert!(plan.replaces_existing);
+        assert_eq!(
+            fs::read_to_string(skills.join("weather/README.md")).unwrap(),
+            "# Weather v2\n"
+        );
+    }
+
+    #[test]
+    fn declined_confirmation_writes_nothing() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skills = tmp.path().join("skills");
+        let source = tmp.path().join("weather");
+        write_package(&source, MANIFEST);
+
+        let outcome =
+            install_skill(source.to_str().unwrap(), &skills, false, |_| Ok(false)).unwrap();
+        assert!(matches!(outcome, InstallOutcome::Cancelled));
+        assert!(!skills.join("weather").exists());
+    }
+
+    #[test]
+    fn installs_tarball_with_top_level_directory() {
+        let tmp = tempfile::tempdir().unwrap();
+        let archive = tmp.path().join("weather.tar.gz");
+        write_tarball(
+            &archive,
+            &[
+                ("weather-1.2.0/", tar::EntryType::Directory, b""),
+                (
+                    "weather-1.2.0/SKILL.toml",
+                    tar::EntryType::Regular,
+                    MANIFEST.as_bytes(),
+                ),
+                (
+                    "./weather-1.2.0/README.md",
+                    tar::EntryType::Regular,
+                    b"# Weather\n",
+                ),
+            ],
+        );
+        let skills = tmp.path().join("skills");
+
+        let InstallOutcome::Installed { dest, .. } = install(&archive, &skills, false).unwrap()
+        else {
+            panic!("expected install");
+        };
+        assert_eq!(dest, skills.join("weather"));
+        assert!(dest.join("SKILL.toml").is_file());
+        assert!(dest.join("README.md").is_file());
+    }
+
+    #[test]
+    fn tarball_entries_escaping_the_target_are_rejected() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skills = tmp.path().join("ws/skills");
+
+        let traversal = tmp.path().join("traversal.tar.gz");
+        write_tarball(
+            &traversal,
+            &[
+                ("SKILL.toml", tar::EntryType::Regular, MANIFEST.as_bytes()),
+                ("../../escaped.txt", tar::EntryType::Regular, b"pwned"),
+            ],
+        );
+        let err = install(&traversal, &skills, false).unwrap_err();
+        assert!(err.to_string().contains("escapes the target"), "{err}");
+        assert!(!tmp.path().join("escaped.txt").exists());
+
+        let absolute = tmp.path().join("absolute.tar.gz");
+        write_tarball(
+            &absolute,
+            &[("/tmp/zeroclaw-abs.txt", tar::EntryType::Regular, b"x")],
+        );
+        let err = install(&absolute, &skills, false).unwrap_err();
+        assert!(err.to_string().contains("escapes the target"), "{err}");
+
+        let link = tmp.path().join("link.tar.gz");
+        write_tarball(
+            &link,
+            &[
+                ("SKILL.toml", tar::EntryType::Regular, MANIFEST.as_bytes()),
+                ("passwd", tar::EntryType::Symlink, b""),
+            ],
+        );
+        let err = install(&link, &skills, false).unwrap_err();
+        assert!(err.to_string().contains("link"), "{err}");
+        assert!(!skills.join("weather").exists());
+    }
+}
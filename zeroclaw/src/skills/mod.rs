@@ -1,16 +1,20 @@
 use anyhow::{Context, Result};
 use directories::UserDirs;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::{Duration, SystemTime};
 
+pub mod approvals;
 mod audit;
+mod install;
 
 const OPEN_SKILLS_REPO_URL: &str = "https://github.com/besoeasy/open-skills";
 const OPEN_SKILLS_SYNC_MARKER: &str = ".zeroclaw-open-skills-sync";
 const OPEN_SKILLS_SYNC_INTERVAL_SECS: u64 = 60 * 60 * 24 * 7;
+/// Maximum directory depth scanned below a skills root (`skills/<category>/.../<skill>`).
+const MAX_SKILL_DISCOVERY_DEPTH: usize = 4;
 
 /// A skill is a user-defined or community-built capability.
 /// Skills live in `~/.zeroclaw/workspace/skills/<name>/SKILL.md`
@@ -28,10 +32,50 @@ pub struct Skill {
     pub tools: Vec<SkillTool>,
     #[serde(default)]
     pub prompts: Vec<String>,
+    #[serde(default)]
+    pub permissions: SkillPermissions,
     #[serde(skip)]
     pub location: Option<PathBuf>,
 }
 
+/// Sandbox permissions a skill requests for the built-in tools it uses.
+///
+/// Declared in the `[permissions]` table of `SKILL.toml`. Only tools listed in
+/// `tools` run with the skill's overrides; everything else keeps the global
+/// sandbox. Values are merged with the base tool config at execution time (see
+/// [`crate::tools::SandboxOverrides`]), so a skill can narrow limits freely but
+/// can only widen them up to the global caps in `[skills]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillPermissions {
+    /// Built-in tool names this skill is authorised to drive (e.g. `"shell"`).
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Filesystem scope for file tools. `None` keeps the workspace scope.
+    #[serde(default)]
+    pub fs_scope: Option<FsScope>,
+    /// Domains network tools may reach on behalf of this skill. Empty means
+    /// no additional restriction beyond the tool's own allowlist.
+    #[serde(default)]
+    pub network_domains: Vec<String>,
+    /// Shell command timeout in seconds, capped by `skills.max_exec_timeout_secs`.
+    #[serde(default)]
+    pub max_exec_timeout: Option<u64>,
+}
+
+/// Filesystem scope requested by a skill.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FsScope {
+    /// The regular workspace sandbox.
+    #[default]
+    Workspace,
+    /// Only `skills/<name>/data` in the workspace, created on first use.
+    Skill,
+    /// Paths outside the workspace (forbidden paths still apply). Requires
+    /// `skills.allow_system_fs_scope`; otherwise treated as `Workspace`.
+    System,
+}
+
 /// A tool defined by a skill (shell command, HTTP call, etc.)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillTool {
@@ -53,6 +97,22 @@ struct SkillManifest {
     tools: Vec<SkillTool>,
     #[serde(default)]
     prompts: Vec<String>,
+    #[serde(default)]
+    permissions: SkillPermissions,
+    #[serde(default)]
+    requirements: SkillRequirements,
+}
+
+/// External prerequisites declared in `[requirements]`; checked (and
+/// reported) at install time, never enforced.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SkillRequirements {
+    /// Executables that must be on `PATH`.
+    #[serde(default)]
+    bins: Vec<String>,
+    /// Environment variables that must be set.
+    #[serde(default)]
+    env: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -113,14 +173,58 @@ fn load_skills_from_directory(skills_dir: &Path) -> Vec<Skill> {
     }
 
     let mut skills = Vec::new();
+    let mut seen_names = HashSet::new();
+    collect_skills_recursive(skills_dir, 0, &mut skills, &mut seen_names);
+    skills
+}
 
-    let Ok(entries) = std::fs::read_dir(skills_dir) else {
-        return skills;
+/// Whether a directory holds a skill manifest (`SKILL.toml` or `SKILL.md`).
+fn is_skill_directory(path: &Path) -> bool {
+    path.join("SKILL.toml").is_file() || path.join("SKILL.md").is_file()
+}
+
+/// List subdirectories of `dir` in a stable order so that the
+/// "first definition wins" rule for duplicate skill names is deterministic.
+fn sorted_subdirectories(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
     };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
 
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if !path.is_dir() {
+/// Whether a non-skill directory should be descended into as a category.
+///
+/// Hidden and symlinked directories are skipped so a nested layout cannot be
+/// used to pull in skills from outside the skills root.
+fn is_category_directory(path: &Path) -> bool {
+    let is_hidden = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.'));
+    let is_symlink = std::fs::symlink_metadata(path)
+        .map(|meta| meta.file_type().is_symlink())
+        .unwrap_or(true);
+    !is_hidden && !is_symlink
+}
+
+fn collect_skills_recursive(
+    dir: &Path,
+    depth: usize,
+    skills: &mut Vec<Skill>,
+    seen_names: &mut HashSet<String>,
+) {
+    for path in sorted_subdirectories(dir) {
+        if !is_skill_directory(&path) {
+            // Category directory (e.g. skills/devops/k8s-helper): descend.
+            if depth + 1 < MAX_SKILL_DISCOVERY_DEPTH && is_category_directory(&path) {
+                collect_skills_recursive(&path, depth + 1, skills, seen_names);
+            }
             continue;
         }
 
@@ -147,18 +251,27 @@ fn load_skills_from_directory(skills_dir: &Path) -> Vec<Skill> {
         let manifest_path = path.join("SKILL.toml");
         let md_path = path.join("SKILL.md");
 
-        if manifest_path.exists() {
-            if let Ok(skill) = load_skill_toml(&manifest_path) {
-                skills.push(skill);
-            }
-        } else if md_path.exists() {
-            if let Ok(skill) = load_skill_md(&md_path, &path) {
-                skills.push(skill);
-            }
+        let loaded = if manifest_path.exists() {
+            load_skill_toml(&manifest_path).ok()
+        } else {
+            load_skill_md(&md_path, &path).ok()
+        };
+
+        let Some(skill) = loaded else {
+            continue;
+        };
+
+        if !seen_names.insert(skill.name.clone()) {
+            tracing::warn!(
+                "skipping duplicate skill name '{}' at {} (first definition wins)",
+                skill.name,
+                path.display()
+            );
+            continue;
         }
-    }
 
-    skills
+        skills.push(skill);
+    }
 }
 
 fn load_open_skills(repo_dir: &Path) -> Vec<Skill> {
@@ -413,6 +526,7 @@ fn load_skill_toml(path: &Path) -> Result<Skill> {
         tags: manifest.skill.tags,
         tools: manifest.tools,
         prompts: manifest.prompts,
+        permissions: manifest.permissions,
         location: Some(path.to_path_buf()),
     })
 }
@@ -434,6 +548,7 @@ fn load_skill_md(path: &Path, dir: &Path) -> Result<Skill> {
         tags: Vec::new(),
         tools: Vec::new(),
         prompts: vec![content],
+        permissions: SkillPermissions::default(),
         location: Some(path.to_path_buf()),
     })
 }
@@ -454,6 +569,7 @@ fn load_open_skill_md(path: &Path) -> Result<Skill> {
         tags: vec!["open-skills".to_string()],
         tools: Vec::new(),
         prompts: vec![content],
+        permissions: SkillPermissions::default(),
         location: Some(path.to_path_buf()),
     })
 }
@@ -558,6 +674,14 @@ pub fn skills_to_prompt_with_mode(
             matches!(mode, crate::config::SkillsPromptInjectionMode::Compact),
         );
         write_xml_text_element(&mut prompt, 4, "location", &location);
+        if !skill.permissions.tools.is_empty() {
+            write_xml_text_element(
+                &mut prompt,
+                4,
+                "granted_tools",
+                &skill.permissions.tools.join(", "),
+            );
+        }
 
         if matches!(mode, crate::config::SkillsPromptInjectionMode::Full) {
             if !skill.prompts.is_empty() {
@@ -585,9 +709,184 @@ pub fn skills_to_prompt_with_mode(
     }
 
     prompt.push_str("</available_skills>");
+    if skills
+        .iter()
+        .any(|skill| !skill.permissions.tools.is_empty())
+    {
+        prompt.push_str(
+            "\n\nWhen a call to one of a skill's `granted_tools` follows that skill's \
+             instructions, pass `\"skill\": \"<skill name>\"` in its arguments so it runs \
+             with the skill's sandbox permissions.",
+        );
+    }
     prompt
 }
 
+/// Snapshot of skill manifest files (`SKILL.toml` / `SKILL.md`) and their
+/// modification stamps, used to detect skills added, changed, or removed on disk.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SkillsFingerprint {
+    manifests: BTreeMap<PathBuf, (Option<SystemTime>, u64)>,
+}
+
+/// Difference between two [`SkillsFingerprint`] snapshots.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SkillsChange {
+    pub added: Vec<PathBuf>,
+    pub changed: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+}
+
+impl SkillsChange {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} changed, {} removed",
+            self.added.len(),
+            self.changed.len(),
+            self.removed.len()
+        )
+    }
+}
+
+impl SkillsFingerprint {
+    /// Scan `skills_dir` (including nested category directories) for manifests.
+    pub fn scan(skills_dir: &Path) -> Self {
+        let mut fingerprint = Self::default();
+        fingerprint.scan_dir(skills_dir, 0);
+        fingerprint
+    }
+
+    fn scan_dir(&mut self, dir: &Path, depth: usize) {
+        for path in sorted_subdirectories(dir) {
+            if is_skill_directory(&path) {
+                for manifest in [path.join("SKILL.toml"), path.join("SKILL.md")] {
+                    if let Ok(meta) = std::fs::metadata(&manifest) {
+                        self.manifests
+                            .insert(manifest, (meta.modified().ok(), meta.len()));
+                    }
+                }
+                continue;
+            }
+            if depth + 1 < MAX_SKILL_DISCOVERY_DEPTH && is_category_directory(&path) {
+                self.scan_dir(&path, depth + 1);
+            }
+        }
+    }
+
+    /// Compute which manifests were added, changed, or removed in `next`.
+    pub fn diff(&self, next: &Self) -> SkillsChange {
+        let mut change = SkillsChange::default();
+        for (path, stamp) in &next.manifests {
+            match self.manifests.get(path) {
+                None => change.added.push(path.clone()),
+                Some(previous) if previous != stamp => change.changed.push(path.clone()),
+                Some(_) => {}
+            }
+        }
+        for path in self.manifests.keys() {
+            if !next.manifests.contains_key(path) {
+                change.removed.push(path.clone());
+            }
+        }
+        change
+    }
+}
+
+/// Poll `skills_dir` every `interval` and invoke `on_change` whenever skill
+/// manifests are added, modified, or removed.
+pub fn spawn_skills_watcher<F>(
+    skills_dir: PathBuf,
+    interval: Duration,
+    on_change: F,
+) -> tokio::task::JoinHandle<()>
+where
+    F: Fn(&SkillsChange) + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut previous = SkillsFingerprint::scan(&skills_dir);
+        let mut ticker = tokio::time::interval(interval.max(Duration::from_secs(1)));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        // The first tick completes immediately; the baseline is already captured.
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            let dir = skills_dir.clone();
+            let Ok(next) = tokio::task::spawn_blocking(move || SkillsFingerprint::scan(&dir)).await
+            else {
+                continue;
+            };
+
+            let change = previous.diff(&next);
+            if change.is_empty() {
+                continue;
+            }
+
+            tracing::info!(
+                dir = %skills_dir.display(),
+                "skills changed on disk: {}",
+                change.summary()
+            );
+            on_change(&change);
+            previous = next;
+        }
+    })
+}
+
+/// Resolve the sandbox overrides `skill` is entitled to under the global
+/// `[skills]` caps.
+pub fn sandbox_overrides(
+    skill: &Skill,
+    config: &crate::config::SkillsConfig,
+) -> crate::tools::SandboxOverrides {
+    let permissions = &skill.permissions;
+    let mut fs_scope = permissions.fs_scope.unwrap_or_default();
+    if fs_scope == FsScope::System && !config.allow_system_fs_scope {
+        tracing::warn!(
+            skill = %skill.name,
+            "skill requested fs_scope = \"system\" but skills.allow_system_fs_scope is off; using workspace scope"
+        );
+        fs_scope = FsScope::Workspace;
+    }
+
+    crate::tools::SandboxOverrides {
+        skill: skill.name.clone(),
+        exec_timeout_secs: permissions
+            .max_exec_timeout
+            .map(|secs| secs.min(config.max_exec_timeout_secs)),
+        network_domains: permissions
+            .network_domains
+            .iter()
+            .map(|domain| domain.trim().trim_start_matches("*.").to_ascii_lowercase())
+            .filter(|domain| !domain.is_empty())
+            .collect(),
+        fs_scope,
+    }
+}
+
+/// Map built-in tool names to the sandbox overrides of every skill that
+/// grants them via `permissions.tools`, in load order. Which one applies is
+/// decided per call, by the skill the call names.
+pub fn sandbox_grants(
+    skills: &[Skill],
+    config: &crate::config::SkillsConfig,
+) -> HashMap<String, Vec<crate::tools::SandboxOverrides>> {
+    let mut grants: HashMap<String, Vec<crate::tools::SandboxOverrides>> = HashMap::new();
+    for skill in skills {
+        for tool in &skill.permissions.tools {
+            grants
+                .entry(tool.clone())
+                .or_default()
+                .push(sandbox_overrides(skill, config));
+        }
+    }
+    grants
+}
+
 /// Get the skills directory path
 pub fn skills_dir(workspace_dir: &Path) -> PathBuf {
     workspace_dir.join("skills")
@@ -676,39 +975,6 @@ fn is_git_scp_source(source: &str) -> bool {
         && !host.contains('\\')
 }
 
-fn snapshot_skill_children(skills_path: &Path) -> Result<HashSet<PathBuf>> {
-    let mut paths = HashSet::new();
-    for entry in std::fs::read_dir(skills_path)? {
-        let entry = entry?;
-        paths.insert(entry.path());
-    }
-    Ok(paths)
-}
-
-fn detect_newly_installed_directory(
-    skills_path: &Path,
-    before: &HashSet<PathBuf>,
-) -> Result<PathBuf> {
-    let mut created = Vec::new();
-    for entry in std::fs::read_dir(skills_path)? {
-        let entry = entry?;
-        let path = entry.path();
-        if !before.contains(&path) && path.is_dir() {
-            created.push(path);
-        }
-    }
-
-    match created.len() {
-        1 => Ok(created.remove(0)),
-        0 => anyhow::bail!(
-            "Unable to determine installed skill directory after clone (no new directory found)"
-        ),
-        _ => anyhow::bail!(
-            "Unable to determine installed skill directory after clone (multiple new directories found)"
-        ),
-    }
-}
-
 fn enforce_skill_security_audit(skill_path: &Path) -> Result<audit::SkillAuditReport> {
     let report = audit::audit_skill_directory(skill_path)?;
     if report.is_clean() {
@@ -772,59 +1038,60 @@ fn copy_dir_recursive_secure(src: &Path, dest: &Path) -> Result<()> {
     Ok(())
 }
 
-fn install_local_skill_source(source: &str, skills_path: &Path) -> Result<(PathBuf, usize)> {
-    let source_path = PathBuf::from(source);
-    if !source_path.exists() {
-        anyhow::bail!("Source path does not exist: {source}");
-    }
-
-    let source_path = source_path
-        .canonicalize()
-        .with_context(|| format!("failed to canonicalize source path {source}"))?;
-    let _ = enforce_skill_security_audit(&source_path)?;
-
-    let name = source_path
-        .file_name()
-        .context("Source path must include a directory name")?;
-    let dest = skills_path.join(name);
-    if dest.exists() {
-        anyhow::bail!("Destination skill already exists: {}", dest.display());
+/// Print what a package will get before the user confirms the install.
+fn print_install_plan(plan: &install::InstallPlan) {
+    let manifest = &plan.manifest;
+    println!();
+    println!(
+        "  {} {} — {}",
+        console::style(&manifest.skill.name).white().bold(),
+        console::style(format!("v{}", manifest.skill.version)).dim(),
+        manifest.skill.description
+    );
+    if plan.replaces_existing {
+        println!(
+            "  {} replaces the installed skill of the same name",
+            console::style("!").yellow().bold()
+        );
     }
-
-    if let Err(err) = copy_dir_recursive_secure(&source_path, &dest) {
-        let _ = std::fs::remove_dir_all(&dest);
-        return Err(err);
+    if !manifest.tools.is_empty() {
+        let names: Vec<&str> = manifest.tools.iter().map(|t| t.name.as_str()).collect();
+        println!("  Tools:       {}", names.join(", "));
     }
 
-    match enforce_skill_security_audit(&dest) {
-        Ok(report) => Ok((dest, report.files_scanned)),
-        Err(err) => {
-            let _ = std::fs::remove_dir_all(&dest);
-            Err(err)
+    let permissions = &manifest.permissions;
+    println!("  {}", console::style("Permissions:").bold());
+    let or_none = |items: &[String]| {
+        if items.is_empty() {
+            "(none)".to_string()
+        } else {
+            items.join(", ")
         }
-    }
-}
-
-fn install_git_skill_source(source: &str, skills_path: &Path) -> Result<(PathBuf, usize)> {
-    let before = snapshot_skill_children(skills_path)?;
-    let output = std::process::Command::new("git")
-        .args(["clone", "--depth", "1", source])
-        .current_dir(skills_path)
-        .output()?;
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Git clone failed: {stderr}");
-    }
-
-    let installed_dir = detect_newly_installed_directory(skills_path, &before)?;
-    remove_git_metadata(&installed_dir)?;
-    match enforce_skill_security_audit(&installed_dir) {
-        Ok(report) => Ok((installed_dir, report.files_scanned)),
-        Err(err) => {
-            let _ = std::fs::remove_dir_all(&installed_dir);
-            Err(err)
+    };
+    println!("    tools:           {}", or_none(&permissions.tools));
+    println!(
+        "    fs_scope:        {}",
+        match permissions.fs_scope.unwrap_or_default() {
+            FsScope::Workspace => "workspace",
+            FsScope::Skill => "skill",
+            FsScope::System => "system",
         }
+    );
+    println!(
+        "    network_domains: {}",
+        or_none(&permissions.network_domains)
+    );
+    if let Some(timeout) = permissions.max_exec_timeout {
+        println!("    max_exec_timeout: {timeout}s");
+    }
+
+    for missing in &plan.missing_requirements {
+        println!(
+            "  {} requirement not met: {missing}",
+            console::style("⚠").yellow().bold()
+        );
     }
+    println!();
 }
 
 /// Handle the `skills` CLI command
@@ -842,6 +1109,9 @@ pub fn handle_command(command: crate::SkillCommands, config: &crate::config::Con
                 println!();
                 println!("  Or install: zeroclaw skills install <source>");
             } else {
+                let approvals =
+                    approvals::ApprovedSkills::load(approvals::ApprovedSkills::dir(config))?
+                        .unwrap_or_default();
                 println!("Installed skills ({}):", skills.len());
                 println!();
                 for skill in &skills {
@@ -865,7 +1135,25 @@ pub fn handle_command(command: crate::SkillCommands, config: &crate::config::Con
                     if !skill.tags.is_empty() {
                         println!("    Tags:  {}", skill.tags.join(", "));
                     }
+                    let scopes: Vec<String> = approvals
+                        .for_skill(&skill.name)
+                        .map(|a| {
+                            let verdict = if a.approved { "approved" } else { "denied" };
+                            format!("{verdict} ({})", a.scope())
+                        })
+                        .collect();
+                    println!(
+                        "    Approval: {}",
+                        if scopes.is_empty() {
+                            "not approved".to_string()
+                        } else {
+                            scopes.join(", ")
+                        }
+                    );
                 }
+                println!();
+                println!("  Skill permissions apply only once approved with:");
+                println!("    zeroclaw skills approve <name> [--channel <c>] [--sender <id>]");
             }
             println!();
             Ok(())
@@ -903,34 +1191,39 @@ pub fn handle_command(command: crate::SkillCommands, config: &crate::config::Con
             }
             anyhow::bail!("Skill audit failed.");
         }
-        crate::SkillCommands::Install { source } => {
+        crate::SkillCommands::Install { source, force, yes } => {
             println!("Installing skill from: {source}");
 
             let skills_path = skills_dir(workspace_dir);
-            std::fs::create_dir_all(&skills_path)?;
-
-            if is_git_source(&source) {
-                let (installed_dir, files_scanned) =
-                    install_git_skill_source(&source, &skills_path)
-                        .with_context(|| format!("failed to install git skill source: {source}"))?;
-                println!(
-                    "  {} Skill installed and audited: {} ({} files scanned)",
-                    console::style("✓").green().bold(),
-                    installed_dir.display(),
-                    files_scanned
-                );
-            } else {
-                let (dest, files_scanned) = install_local_skill_source(&source, &skills_path)
-                    .with_context(|| format!("failed to install local skill source: {source}"))?;
-                println!(
-                    "  {} Skill installed and audited: {} ({} files scanned)",
-                    console::style("✓").green().bold(),
-                    dest.display(),
-                    files_scanned
-                );
+            let outcome = install::install_skill(&source, &skills_path, force, |plan| {
+                print_install_plan(plan);
+                if yes {
+                    return Ok(true);
+                }
+                if !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+                    anyhow::bail!("Refusing to install without confirmation; re-run with --yes");
+                }
+                Ok(dialoguer::Confirm::new()
+                    .with_prompt("  Install this skill?")
+                    .default(false)
+                    .interact()?)
+            })
+            .with_context(|| format!("failed to install skill source: {source}"))?;
+
+            match outcome {
+                install::InstallOutcome::Installed { dest, plan } => {
+                    println!(
+                        "  {} Skill installed and audited: {} ({} files scanned)",
+                        console::style("✓").green().bold(),
+                        dest.display(),
+                        plan.files_scanned
+                    );
+                    println!(
+                        "  Note: the skill is not auto-approved; its permissions apply once you run `zeroclaw skills approve <name>`."
+                    );
+                }
+                install::InstallOutcome::Cancelled => println!("  Installation cancelled."),
             }
-
-            println!("  Security audit completed successfully.");
             Ok(())
         }
         crate::SkillCommands::Remove { name } => {
@@ -963,6 +1256,35 @@ pub fn handle_command(command: crate::SkillCommands, config: &crate::config::Con
             );
             Ok(())
         }
+        crate::SkillCommands::Approve {
+            name,
+            channel,
+            sender,
+            deny,
+        } => {
+            let skills = load_skills_with_config(workspace_dir, config);
+            if !skills.iter().any(|skill| skill.name == name) {
+                anyhow::bail!("Skill not found: {name}");
+            }
+            let mut approvals =
+                approvals::ApprovedSkills::load(approvals::ApprovedSkills::dir(config))?
+                    .unwrap_or_default();
+            let approval = approvals::SkillApproval {
+                skill: name.clone(),
+                channel: channel.map(|c| c.trim().to_ascii_lowercase()),
+                sender: sender.map(|s| s.trim().to_string()),
+                approved: !deny,
+            };
+            let scope = approval.scope();
+            approvals.set(approval);
+            approvals.save(approvals::ApprovedSkills::dir(config))?;
+            println!(
+                "  {} Skill '{name}' {} ({scope}).",
+                console::style("✓").green().bold(),
+                if deny { "denied" } else { "approved" }
+            );
+            Ok(())
+        }
     }
 }
 
@@ -1040,6 +1362,77 @@ command = "echo hello"
         assert_eq!(skills[0].tools[0].name, "hello");
     }
 
+    #[test]
+    fn skill_permissions_resolve_against_global_caps() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill_dir = dir.path().join("skills").join("long-build");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("SKILL.toml"),
+            r#"
+[skill]
+name = "long-build"
+description = "Runs the slow release build"
+
+[permissions]
+tools = ["shell", "web_fetch", "file_read"]
+fs_scope = "system"
+network_domains = ["*.Crates.io", " "]
+max_exec_timeout = 300
+"#,
+        )
+        .unwrap();
+
+        let skills = load_skills(dir.path());
+        assert_eq!(skills[0].permissions.fs_scope, Some(FsScope::System));
+
+        let mut config = crate::config::SkillsConfig::default();
+        let grants = sandbox_grants(&skills, &config);
+        assert_eq!(grants.len(), 3);
+        let shell = &grants["shell"][0];
+        assert_eq!(shell.skill, "long-build");
+        // Extended beyond the 60s shell default, within the 600s cap.
+        assert_eq!(shell.exec_timeout_secs, Some(300));
+        assert_eq!(shell.network_domains, vec!["crates.io".to_string()]);
+        // `system` is refused unless explicitly allowed.
+        assert_eq!(shell.fs_scope, FsScope::Workspace);
+        assert_eq!(shell.skill_data_dir(dir.path()), skill_dir.join("data"));
+
+        config.max_exec_timeout_secs = 120;
+        config.allow_system_fs_scope = true;
+        let capped = sandbox_overrides(&skills[0], &config);
+        assert_eq!(capped.exec_timeout_secs, Some(120));
+        assert_eq!(capped.fs_scope, FsScope::System);
+    }
+
+    #[test]
+    fn sandbox_grants_keep_every_granting_skill() {
+        let skill = |name: &str, timeout: u64| Skill {
+            name: name.to_string(),
+            description: String::new(),
+            version: "1.0.0".to_string(),
+            author: None,
+            tags: vec![],
+            tools: vec![],
+            prompts: vec![],
+            permissions: SkillPermissions {
+                tools: vec!["shell".to_string()],
+                max_exec_timeout: Some(timeout),
+                ..SkillPermissions::default()
+            },
+            location: None,
+        };
+        let grants = sandbox_grants(
+            &[skill("first", 90), skill("second", 30)],
+            &crate::config::SkillsConfig::default(),
+        );
+        let shell: Vec<_> = grants["shell"]
+            .iter()
+            .map(|grant| (grant.skill.as_str(), grant.exec_timeout_secs))
+            .collect();
+        assert_eq!(shell, [("first", Some(90)), ("second", Some(30))]);
+    }
+
     #[test]
     fn load_skill_from_md() {
         let dir = tempfile::tempdir().unwrap();
@@ -1075,6 +1468,7 @@ command = "echo hello"
             tags: vec![],
             tools: vec![],
             prompts: vec!["Do the thing.".to_string()],
+            permissions: SkillPermissions::default(),
             location: None,
         }];
         let prompt = skills_to_prompt(&skills, Path::new("/tmp"));
@@ -1099,6 +1493,7 @@ command = "echo hello"
                 args: HashMap::new(),
             }],
             prompts: vec!["Do the thing.".to_string()],
+            permissions: SkillPermissions::default(),
             location: Some(PathBuf::from("/tmp/workspace/skills/test/SKILL.md")),
         }];
         let prompt = skills_to_prompt_with_mode(
@@ -1180,6 +1575,84 @@ command = "echo hello"
         assert_eq!(skills.len(), 3);
     }
 
+    #[test]
+    fn load_discovers_skills_in_nested_category_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let skills_dir = dir.path().join("skills");
+        let nested = skills_dir.join("devops").join("k8s-helper");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("SKILL.md"), "# K8s\nHelps with clusters.\n").unwrap();
+
+        let top_level = skills_dir.join("notes");
+        fs::create_dir_all(&top_level).unwrap();
+        fs::write(top_level.join("SKILL.md"), "# Notes\nTakes notes.\n").unwrap();
+
+        let mut names: Vec<String> = load_skills(dir.path())
+            .into_iter()
+            .map(|skill| skill.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["k8s-helper", "notes"]);
+    }
+
+    #[test]
+    fn load_skips_hidden_category_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let hidden = dir.path().join("skills").join(".archive").join("old");
+        fs::create_dir_all(&hidden).unwrap();
+        fs::write(hidden.join("SKILL.md"), "# Old\nArchived.\n").unwrap();
+
+        assert!(load_skills(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn load_keeps_first_skill_on_name_collision() {
+        let dir = tempfile::tempdir().unwrap();
+        let skills_dir = dir.path().join("skills");
+        for category in ["a-team", "b-team"] {
+            let skill_dir = skills_dir.join(category).join("deploy");
+            fs::create_dir_all(&skill_dir).unwrap();
+            fs::write(
+                skill_dir.join("SKILL.md"),
+                format!("# Deploy\nDeploy for {category}.\n"),
+            )
+            .unwrap();
+        }
+
+        let skills = load_skills(dir.path());
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].name, "deploy");
+        assert!(skills[0].description.contains("a-team"));
+    }
+
+    #[test]
+    fn skills_fingerprint_diff_reports_added_changed_and_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let skills_dir = dir.path().join("skills");
+        let keep = skills_dir.join("keep");
+        let gone = skills_dir.join("ops").join("gone");
+        fs::create_dir_all(&keep).unwrap();
+        fs::create_dir_all(&gone).unwrap();
+        fs::write(keep.join("SKILL.md"), "# Keep\nv1\n").unwrap();
+        fs::write(gone.join("SKILL.md"), "# Gone\nbye\n").unwrap();
+
+        let before = SkillsFingerprint::scan(&skills_dir);
+        assert!(before.diff(&before).is_empty());
+
+        fs::write(keep.join("SKILL.md"), "# Keep\nversion two, longer\n").unwrap();
+        fs::remove_dir_all(&gone).unwrap();
+        let fresh = skills_dir.join("fresh");
+        fs::create_dir_all(&fresh).unwrap();
+        fs::write(fresh.join("SKILL.md"), "# Fresh\nnew\n").unwrap();
+
+        let after = SkillsFingerprint::scan(&skills_dir);
+        let change = before.diff(&after);
+        assert_eq!(change.added, vec![fresh.join("SKILL.md")]);
+        assert_eq!(change.changed, vec![keep.join("SKILL.md")]);
+        assert_eq!(change.removed, vec![gone.join("SKILL.md")]);
+        assert_eq!(change.summary(), "1 added, 1 changed, 1 removed");
+    }
+
     #[test]
     fn toml_skill_with_multiple_tools() {
         let dir = tempfile::tempdir().unwrap();
@@ -1299,6 +1772,7 @@ description = "Bare minimum"
                 args: HashMap::new(),
             }],
             prompts: vec![],
+            permissions: SkillPermissions::default(),
             location: None,
         }];
         let prompt = skills_to_prompt(&skills, Path::new("/tmp"));
@@ -1318,6 +1792,7 @@ description = "Bare minimum"
             tags: vec![],
             tools: vec![],
             prompts: vec!["Use <tool> & check \"quotes\".".to_string()],
+            permissions: SkillPermissions::default(),
             location: None,
         }];
 
@@ -0,0 +1,268 @@
+//! Full tool output kept in workspace files.
+//!
+//! Tools listed in `[security.sandbox] artifact_tools` do not cut output
+//! beyond `artifact_preview_bytes` away: [`Artifacts::spill`] writes all of
+//! it to `.artifacts/` in the workspace and returns a preview followed by a
+//! pointer line, so the model can page through the rest with `file_read`
+//! (`offset`/`limit`). [`spawn_cleanup`] keeps the directory under
+//! `artifact_max_total_mb` by removing the least recently used files.
+
+use crate::config::SandboxConfig;
+use crate::util::truncate_at_char_boundary;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Workspace-relative directory holding artifacts.
+pub const ARTIFACTS_DIR: &str = ".artifacts";
+/// Largest output kept in one artifact; `file_read` reads files up to 10 MB.
+pub const ARTIFACT_MAX_BYTES: usize = 8 * 1024 * 1024;
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Where an oversized output was saved, as reported to the model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArtifactPointer {
+    /// Path relative to the workspace, usable with `file_read`.
+    pub artifact_path: String,
+    pub total_bytes: usize,
+    pub preview_bytes: usize,
+}
+
+/// Artifact settings for one tool.
+#[derive(Debug, Clone)]
+pub struct Artifacts {
+    workspace_dir: PathBuf,
+    preview_bytes: usize,
+}
+
+impl Artifacts {
+    /// Settings for `tool`, or `None` when its output is not kept.
+    pub fn for_tool(workspace_dir: &Path, sandbox: &SandboxConfig, tool: &str) -> Option<Self> {
+        sandbox
+            .artifact_tools
+            .iter()
+            .any(|name| name == tool)
+            .then(|| Self {
+                workspace_dir: workspace_dir.to_path_buf(),
+                preview_bytes: sandbox.artifact_preview_bytes,
+            })
+    }
+
+    /// `output` unchanged when it fits the preview; otherwise its first
+    /// `preview_bytes` followed by a pointer to the file holding all of it
+    /// (or a plain truncation note when the file cannot be written).
+    pub fn spill(&self, tool: &str, output: &str) -> String {
+        if output.len() <= self.preview_bytes {
+            return output.to_string();
+        }
+        let preview = truncate_at_char_boundary(output, self.preview_bytes);
+        match self.write(tool, output) {
+            Ok(pointer) => format!(
+                "{preview}\n\n... [output truncated; the full output is saved, read it with \
+                 file_read and offset/limit]\n[artifact] {}",
+                serde_json::to_string(&ArtifactPointer {
+                    preview_bytes: preview.len(),
+                    ..pointer
+                })
+                .unwrap_or_default()
+            ),
+            Err(e) => {
+                tracing::warn!("Failed to save {tool} output as an artifact: {e}");
+                format!(
+                    "{preview}\n\n... [output truncated at {} bytes]",
+                    preview.len()
+                )
+            }
+        }
+    }
+
+    fn write(&self, tool: &str, output: &str) -> std::io::Result<ArtifactPointer> {
+        let dir = self.workspace_dir.join(ARTIFACTS_DIR);
+        std::fs::create_dir_all(&dir)?;
+        let turn = crate::agent::turn::current_turn_id().unwrap_or_else(|| "adhoc".into());
+        let suffix = uuid::Uuid::new_v4().simple().to_string();
+        let name = format!("{tool}-{turn}-{}.txt", &suffix[..8]);
+        let kept = truncate_at_char_boundary(output, ARTIFACT_MAX_BYTES);
+        std::fs::write(dir.join(&name), kept)?;
+        Ok(ArtifactPointer {
+            artifact_path: format!("{ARTIFACTS_DIR}/{name}"),
+            total_bytes: output.len(),
+            preview_bytes: 0,
+        })
+    }
+}
+
+/// Outcome of one [`cleanup`] pass.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CleanupReport {
+    pub removed: usize,
+    pub remaining_bytes: u64,
+}
+
+/// Remove the least recently used artifacts until the directory holds at
+/// most `max_total_bytes`.
+pub fn cleanup(workspace_dir: &Path, max_total_bytes: u64) -> std::io::Result<CleanupReport> {
+    let dir = workspace_dir.join(ARTIFACTS_DIR);
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(CleanupReport::default());
+        }
+        Err(e) => return Err(e),
+    };
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+        let used = metadata
+            .accessed()
+            .or_else(|_| metadata.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        files.push((used, metadata.len(), entry.path()));
+    }
+    files.sort_by_key(|(used, _, _)| *used);
+
+    let mut report = CleanupReport {
+        removed: 0,
+        remaining_bytes: files.iter().map(|(_, len, _)| len).sum(),
+    };
+    for (_, len, path) in files {
+        if report.remaining_bytes <= max_total_bytes {
+            break;
+        }
+        std::fs::remove_file(&path)?;
+        report.removed += 1;
+        report.remaining_bytes -= len;
+    }
+    Ok(report)
+}
+
+/// Run [`cleanup`] for `workspace_dir` every few minutes, unless no tool
+/// keeps artifacts.
+pub fn spawn_cleanup(workspace_dir: &Path, sandbox: &SandboxConfig) {
+    if sandbox.artifact_tools.is_empty() {
+        return;
+    }
+    let workspace_dir = workspace_dir.to_path_buf();
+    let max_total_bytes = sandbox.artifact_max_total_mb.saturating_mul(1024 * 1024);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            let dir = workspace_dir.clone();
+            match tokio::task::spawn_blocking(move || cleanup(&dir, max_total_bytes)).await {
+                Ok(Ok(report)) if report.removed > 0 => tracing::info!(
+                    "artifact cleanup removed {} file(s), {} bytes left",
+                    report.removed,
+                    report.remaining_bytes
+                ),
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => tracing::warn!("artifact cleanup failed: {e}"),
+                Err(e) => tracing::warn!("artifact cleanup task panicked: {e}"),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn artifacts(tmp: &TempDir, preview_bytes: usize) -> Artifacts {
+        let sandbox = SandboxConfig {
+            artifact_tools: vec!["shell".into()],
+            artifact_preview_bytes: preview_bytes,
+            ..SandboxConfig::default()
+        };
+        Artifacts::for_tool(tmp.path(), &sandbox, "shell").unwrap()
+    }
+
+    fn pointer(output: &str) -> serde_json::Value {
+        let line = output
+            .lines()
+            .find_map(|line| line.strip_prefix("[artifact] "))
+            .expect("pointer line");
+        serde_json::from_str(line).unwrap()
+    }
+
+    #[test]
+    fn small_output_is_returned_unchanged() {
+        let tmp = TempDir::new().unwrap();
+        assert_eq!(artifacts(&tmp, 16).spill("shell", "short"), "short");
+        assert!(!tmp.path().join(ARTIFACTS_DIR).exists());
+    }
+
+    #[test]
+    fn large_output_is_saved_with_preview_and_pointer() {
+        let tmp = TempDir::new().unwrap();
+        let output = "é".repeat(100);
+        let spilled = artifacts(&tmp, 15).spill("shell", &output);
+
+        assert!(spilled.starts_with(&"é".repeat(7)));
+        let pointer = pointer(&spilled);
+        assert_eq!(pointer["total_bytes"], 200);
+        assert_eq!(pointer["preview_bytes"], 14);
+        let path = pointer["artifact_path"].as_str().unwrap();
+        assert!(path.starts_with(".artifacts/shell-adhoc-"));
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join(path)).unwrap(),
+            output
+        );
+    }
+
+    #[test]
+    fn tools_not_listed_keep_no_artifacts() {
+        let tmp = TempDir::new().unwrap();
+        let sandbox = SandboxConfig::default();
+        assert!(Artifacts::for_tool(tmp.path(), &sandbox, "shell").is_some());
+        assert!(Artifacts::for_tool(tmp.path(), &sandbox, "file_read").is_none());
+    }
+
+    #[test]
+    fn cleanup_removes_least_recently_used_until_under_budget() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join(ARTIFACTS_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        let now = SystemTime::now();
+        for (name, age_secs) in [("old.txt", 300), ("mid.txt", 200), ("new.txt", 100)] {
+            let path = dir.join(name);
+            std::fs::write(&path, vec![b'x'; 1000]).unwrap();
+            let used = now - Duration::from_secs(age_secs);
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_times(
+                    std::fs::FileTimes::new()
+                        .set_accessed(used)
+                        .set_modified(used),
+                )
+                .unwrap();
+        }
+
+        let report = cleanup(tmp.path(), 2000).unwrap();
+        assert_eq!(
+            report,
+            CleanupReport {
+                removed: 1,
+                remaining_bytes: 2000
+            }
+        );
+        assert!(!dir.join("old.txt").exists());
+        assert!(dir.join("mid.txt").exists());
+
+        let report = cleanup(tmp.path(), 500).unwrap();
+        assert_eq!(report.removed, 2);
+        assert_eq!(report.remaining_bytes, 0);
+    }
+
+    #[test]
+    fn cleanup_without_directory_is_a_no_op() {
+        let tmp = TempDir::new().unwrap();
+        assert_eq!(cleanup(tmp.path(), 0).unwrap(), CleanupReport::default());
+    }
+}
@@ -0,0 +1,165 @@
+use super::traits::{Tool, ToolResult};
+use crate::sessions::{blocking_read_only, SqliteSessionStore};
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+/// Let the agent resolve channel sender ids to names (and back)
+pub struct ContactsLookupTool {
+    workspace_dir: PathBuf,
+}
+
+impl ContactsLookupTool {
+    pub fn new(workspace_dir: PathBuf) -> Self {
+        Self { workspace_dir }
+    }
+}
+
+#[async_trait]
+impl Tool for ContactsLookupTool {
+    fn name(&self) -> &str {
+        "contacts_lookup"
+    }
+
+    fn description(&self) -> &str {
+        "Look up who a channel sender is. Matches an exact sender id (e.g. U03AB12) or part of a display name, and returns the name, channel, first/last seen times and any notes."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Sender id, or part of a display name"
+                },
+                "channel": {
+                    "type": "string",
+                    "description": "Only search this channel (e.g. slack, telegram). Defaults to the current conversation's channel, if any."
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max results to return (default: 5)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
+
+        let channel = args
+            .get("channel")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .or_else(|| crate::sessions::current_session().map(|s| s.channel));
+
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(5, |v| v as usize);
+
+        if !SqliteSessionStore::db_path(&self.workspace_dir).exists() {
+            return Ok(ToolResult {
+                success: true,
+                output: "No contacts have been recorded yet.".into(),
+                error: None,
+            });
+        }
+
+        let lookup = query.to_string();
+        let contacts = blocking_read_only(&self.workspace_dir, move |store| {
+            store.find_contacts(&lookup, channel.as_deref(), limit)
+        })
+        .await;
+
+        match contacts {
+            Ok(contacts) if contacts.is_empty() => Ok(ToolResult {
+                success: true,
+                output: format!("No contact matched '{query}'."),
+                error: None,
+            }),
+            Ok(contacts) => {
+                let mut output = format!("Found {} contact(s):\n", contacts.len());
+                for contact in &contacts {
+                    let _ = write!(
+                        output,
+                        "- {} ({} id {}): first seen {}, last seen {}",
+                        contact.label(),
+                        contact.channel,
+                        contact.sender_id,
+                        contact.first_seen,
+                        contact.last_seen
+                    );
+                    if let Some(notes) = contact.notes.as_deref() {
+                        let _ = write!(output, ". Notes: {notes}");
+                    }
+                    output.push('\n');
+                }
+                Ok(ToolResult {
+                    success: true,
+                    output,
+                    error: None,
+                })
+            }
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Contact lookup failed: {e}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn lookup_without_database_reports_empty() {
+        let tmp = TempDir::new().unwrap();
+        let tool = ContactsLookupTool::new(tmp.path().to_path_buf());
+        let result = tool.execute(json!({"query": "U1"})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("No contacts"));
+    }
+
+    #[tokio::test]
+    async fn lookup_resolves_ids_and_names() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        store
+            .upsert_contact("slack", "U03AB12", Some("Alice"))
+            .unwrap();
+        store
+            .set_contact_notes("slack", "U03AB12", Some("on-call this week"))
+            .unwrap();
+        store.upsert_contact("telegram", "42", Some("Bob")).unwrap();
+
+        let tool = ContactsLookupTool::new(tmp.path().to_path_buf());
+        let by_id = tool.execute(json!({"query": "U03AB12"})).await.unwrap();
+        assert!(by_id.success);
+        assert!(by_id.output.contains("- Alice (slack id U03AB12)"));
+        assert!(by_id.output.contains("Notes: on-call this week"));
+
+        let other_channel = tool
+            .execute(json!({"query": "bob", "channel": "slack"}))
+            .await
+            .unwrap();
+        assert!(other_channel.output.contains("No contact matched"));
+    }
+
+    #[tokio::test]
+    async fn missing_query_is_an_error() {
+        let tmp = TempDir::new().unwrap();
+        let tool = ContactsLookupTool::new(tmp.path().to_path_buf());
+        assert!(tool.execute(json!({})).await.is_err());
+    }
+}
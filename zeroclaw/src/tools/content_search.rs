@@ -230,6 +230,15 @@ impl Tool for ContentSearchTool {
                 )),
             });
         }
+        if self.security.is_sensitive_path(&resolved_canon) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "'{search_path}' holds agent state and cannot be searched."
+                )),
+            });
+        }
 
         // --- Multiline check for grep fallback ---
         if multiline && !self.has_rg {
@@ -340,6 +349,15 @@ impl Tool for ContentSearchTool {
     }
 }
 
+/// File-name globs for [`crate::security::SENSITIVE_WORKSPACE_PATHS`] and
+/// their SQLite sidecars, excluded from directory searches.
+fn sensitive_file_globs() -> impl Iterator<Item = String> {
+    crate::security::SENSITIVE_WORKSPACE_PATHS
+        .iter()
+        .filter_map(|path| path.rsplit('/').next())
+        .map(|name| format!("{name}*"))
+}
+
 fn build_rg_command(
     pattern: &str,
     search_path: &std::path::Path,
@@ -387,6 +405,9 @@ fn build_rg_command(
     if let Some(glob) = include {
         cmd.arg("--glob").arg(glob);
     }
+    for name in sensitive_file_globs() {
+        cmd.arg("--glob").arg(format!("!{name}"));
+    }
 
     // Separator to prevent pattern from being parsed as flag
     cmd.arg("--");
@@ -437,6 +458,9 @@ fn build_grep_command(
     if let Some(glob) = include {
         cmd.arg("--include").arg(glob);
     }
+    for name in sensitive_file_globs() {
+        cmd.arg(format!("--exclude={name}"));
+    }
 
     cmd.arg("--");
     cmd.arg(pattern);
@@ -68,7 +68,16 @@ impl Tool for CronAddTool {
                 "name": { "type": "string" },
                 "schedule": {
                     "type": "object",
-                    "description": "Schedule object: {kind:'cron',expr,tz?} | {kind:'at',at} | {kind:'every',every_ms}"
+                    "description": "Schedule object: {kind:'cron',expr,tz?} | {kind:'at',at} | {kind:'every',every_ms}. 'at' accepts RFC3339 or expressions like 'in 2 hours', 'tomorrow 9am', 'friday 14:00' (configured timezone)"
+                },
+                "at": {
+                    "type": "string",
+                    "description": "Shorthand for a one-shot schedule {kind:'at',at}; use instead of 'schedule'. RFC3339 or an expression like 'in 2 hours' or 'tomorrow 9am'"
+                },
+                "keep": {
+                    "type": "boolean",
+                    "description": "Keep a one-shot job (paused, with its run history) after it fires instead of deleting it. Same as delete_after_run=false",
+                    "default": false
                 },
                 "job_type": { "type": "string", "enum": ["shell", "agent"] },
                 "command": { "type": "string" },
@@ -91,8 +100,7 @@ impl Tool for CronAddTool {
                     "description": "Set true to explicitly approve medium/high-risk shell commands in supervised mode",
                     "default": false
                 }
-            },
-            "required": ["schedule"]
+            }
         })
     }
 
@@ -105,8 +113,14 @@ impl Tool for CronAddTool {
             });
         }
 
-        let schedule = match args.get("schedule") {
-            Some(v) => match serde_json::from_value::<Schedule>(v.clone()) {
+        let at_shorthand = args
+            .get("at")
+            .and_then(serde_json::Value::as_str)
+            .map(|at| json!({ "kind": "at", "at": at }));
+        let schedule = match at_shorthand.as_ref().or_else(|| args.get("schedule")) {
+            Some(v) => match cron::at::ScheduleTz::from_config(self.config.timezone.as_deref())
+                .and_then(|tz| cron::at::schedule_from_json(v, tz, chrono::Utc::now()))
+            {
                 Ok(schedule) => schedule,
                 Err(e) => {
                     return Ok(ToolResult {
@@ -120,7 +134,7 @@ impl Tool for CronAddTool {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some("Missing 'schedule' parameter".to_string()),
+                    error: Some("Missing 'schedule' (or 'at') parameter".to_string()),
                 });
             }
         };
@@ -150,10 +164,14 @@ impl Tool for CronAddTool {
         };
 
         let default_delete_after_run = matches!(schedule, Schedule::At { .. });
+        let keep = args
+            .get("keep")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
         let delete_after_run = args
             .get("delete_after_run")
             .and_then(serde_json::Value::as_bool)
-            .unwrap_or(default_delete_after_run);
+            .unwrap_or(default_delete_after_run && !keep);
         let approved = args
             .get("approved")
             .and_then(serde_json::Value::as_bool)
@@ -184,7 +202,20 @@ impl Tool for CronAddTool {
                     return Ok(blocked);
                 }
 
-                cron::add_shell_job(&self.config, name, schedule, command)
+                cron::add_shell_job(&self.config, name, schedule, command).and_then(|job| {
+                    if job.delete_after_run == delete_after_run {
+                        Ok(job)
+                    } else {
+                        cron::update_job(
+                            &self.config,
+                            &job.id,
+                            cron::CronJobPatch {
+                                delete_after_run: Some(delete_after_run),
+                                ..cron::CronJobPatch::default()
+                            },
+                        )
+                    }
+                })
             }
             JobType::Agent => {
                 let prompt = match args.get("prompt").and_then(serde_json::Value::as_str) {
@@ -257,6 +288,7 @@ impl Tool for CronAddTool {
                     "job_type": job.job_type,
                     "schedule": job.schedule,
                     "next_run": job.next_run,
+                    "delete_after_run": job.delete_after_run,
                     "enabled": job.enabled
                 }))?,
                 error: None,
@@ -314,6 +346,37 @@ mod tests {
         assert!(result.output.contains("next_run"));
     }
 
+    #[tokio::test]
+    async fn at_shorthand_adds_one_shot_and_keep_retains_it() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = test_config(&tmp).await;
+        let tool = CronAddTool::new(cfg.clone(), test_security(&cfg));
+        let add = |extra: serde_json::Value| {
+            let mut args = json!({ "at": "in 2 hours", "command": "echo reminder" });
+            args.as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            tool.execute(args)
+        };
+
+        let result = add(json!({})).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let job: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(job["schedule"]["kind"], "at");
+        assert_eq!(job["delete_after_run"], true);
+
+        let result = add(json!({ "keep": true })).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let job: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(job["delete_after_run"], false);
+        let stored = cron::get_job(&cfg, job["id"].as_str().unwrap()).unwrap();
+        assert!(!stored.delete_after_run);
+
+        let result = tool.execute(json!({ "command": "echo x" })).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("'at'"));
+    }
+
     #[tokio::test]
     async fn blocks_disallowed_shell_command() {
         let tmp = TempDir::new().unwrap();
@@ -463,6 +526,36 @@ mod tests {
             .contains("every_ms must be > 0"));
     }
 
+    #[tokio::test]
+    async fn natural_at_time_keeps_original_expression() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = test_config(&tmp).await;
+        let tool = CronAddTool::new(cfg.clone(), test_security(&cfg));
+
+        let result = tool
+            .execute(json!({
+                "schedule": { "kind": "at", "at": "in 2 hours" },
+                "job_type": "shell",
+                "command": "echo later"
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let job: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(job["schedule"]["original"], "in 2 hours");
+
+        let result = tool
+            .execute(json!({
+                "schedule": { "kind": "at", "at": "2020-01-01T00:00:00Z" },
+                "job_type": "shell",
+                "command": "echo past"
+            }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap_or_default().contains("in the past"));
+    }
+
     #[tokio::test]
     async fn agent_job_requires_prompt() {
         let tmp = TempDir::new().unwrap();
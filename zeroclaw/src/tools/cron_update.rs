@@ -54,7 +54,7 @@ impl Tool for CronUpdateTool {
     }
 
     fn description(&self) -> &str {
-        "Patch an existing cron job (schedule, command, prompt, enabled, delivery, model, etc.)"
+        "Patch an existing cron job (schedule, command, prompt, enabled, delivery, model, catch_up: \"run_once\" | \"skip\" after downtime, etc.)"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -93,7 +93,7 @@ impl Tool for CronUpdateTool {
             }
         };
 
-        let patch_val = match args.get("patch") {
+        let mut patch_val = match args.get("patch") {
             Some(v) => v.clone(),
             None => {
                 return Ok(ToolResult {
@@ -104,8 +104,28 @@ impl Tool for CronUpdateTool {
             }
         };
 
+        // Resolve natural-language `at` times before the typed decode.
+        let schedule = match patch_val
+            .as_object_mut()
+            .and_then(|patch| patch.remove("schedule"))
+        {
+            Some(value) => match cron::at::ScheduleTz::from_config(self.config.timezone.as_deref())
+                .and_then(|tz| cron::at::schedule_from_json(&value, tz, chrono::Utc::now()))
+            {
+                Ok(schedule) => Some(schedule),
+                Err(e) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!("Invalid schedule: {e}")),
+                    });
+                }
+            },
+            None => None,
+        };
+
         let patch = match serde_json::from_value::<CronJobPatch>(patch_val) {
-            Ok(patch) => patch,
+            Ok(patch) => CronJobPatch { schedule, ..patch },
             Err(e) => {
                 return Ok(ToolResult {
                     success: false,
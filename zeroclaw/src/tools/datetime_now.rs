@@ -0,0 +1,106 @@
+use super::traits::{Tool, ToolResult};
+use crate::cron::at::ScheduleTz;
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::json;
+
+/// Current time in the configured timezone, so the agent resolves "tomorrow"
+/// or "9am" the same way the scheduler does.
+pub struct DatetimeNowTool {
+    timezone: Option<String>,
+}
+
+impl DatetimeNowTool {
+    pub fn new(timezone: Option<String>) -> Self {
+        Self { timezone }
+    }
+}
+
+#[async_trait]
+impl Tool for DatetimeNowTool {
+    fn name(&self) -> &str {
+        "datetime_now"
+    }
+
+    fn description(&self) -> &str {
+        "Get the current date and time in the configured timezone (and UTC). Scheduling tools interpret wall-clock times such as 'tomorrow 9am' in this timezone."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "timezone": {
+                    "type": "string",
+                    "description": "Optional IANA timezone to report instead (e.g. 'Asia/Tokyo')"
+                }
+            },
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let requested = args.get("timezone").and_then(serde_json::Value::as_str);
+        let tz = match ScheduleTz::from_config(requested.or(self.timezone.as_deref())) {
+            Ok(tz) => tz,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                });
+            }
+        };
+
+        let now = Utc::now();
+        let source = match (requested, &self.timezone) {
+            (Some(_), _) => "requested",
+            (None, Some(_)) => "configured",
+            (None, None) => "system",
+        };
+        let output = json!({
+            "local": tz.format(now),
+            "weekday": tz.weekday(now).to_string(),
+            "timezone": tz.name(),
+            "timezone_source": source,
+            "utc": now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "unix": now.timestamp(),
+        });
+        Ok(ToolResult {
+            success: true,
+            output: serde_json::to_string_pretty(&output)?,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reports_configured_timezone() {
+        let tool = DatetimeNowTool::new(Some("Asia/Tokyo".into()));
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(result.success);
+        let parsed: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(parsed["timezone"], "Asia/Tokyo");
+        assert_eq!(parsed["timezone_source"], "configured");
+        assert!(parsed["local"].as_str().unwrap().ends_with("JST"));
+    }
+
+    #[tokio::test]
+    async fn requested_timezone_overrides_and_invalid_is_error() {
+        let tool = DatetimeNowTool::new(Some("Asia/Tokyo".into()));
+        let result = tool.execute(json!({"timezone": "UTC"})).await.unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(parsed["timezone"], "UTC");
+        assert_eq!(parsed["timezone_source"], "requested");
+
+        let result = tool
+            .execute(json!({"timezone": "Nowhere/Special"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+    }
+}
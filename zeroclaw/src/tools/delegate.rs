@@ -1,5 +1,5 @@
 use super::traits::{Tool, ToolResult};
-use crate::agent::loop_::run_tool_call_loop;
+use crate::agent::loop_::{run_turn, HeadlessTurn};
 use crate::config::DelegateAgentConfig;
 use crate::observability::traits::{Observer, ObserverEvent, ObserverMetric};
 use crate::providers::{self, ChatMessage, Provider};
@@ -394,23 +394,19 @@ impl DelegateTool {
 
         let result = tokio::time::timeout(
             Duration::from_secs(DELEGATE_AGENTIC_TIMEOUT_SECS),
-            run_tool_call_loop(
+            run_turn(
                 provider,
                 &mut history,
                 &sub_tools,
                 &noop_observer,
-                &agent_config.provider,
-                &agent_config.model,
-                temperature,
-                true,
-                None,
-                "delegate",
-                &self.multimodal_config,
-                agent_config.max_iterations,
-                None,
-                None,
-                None,
-                &[],
+                &HeadlessTurn {
+                    provider_name: &agent_config.provider,
+                    model: &agent_config.model,
+                    temperature,
+                    channel_name: "delegate",
+                    multimodal_config: &self.multimodal_config,
+                    max_iterations: agent_config.max_iterations,
+                },
             ),
         )
         .await;
@@ -449,12 +445,13 @@ impl DelegateTool {
     }
 }
 
-struct ToolArcRef {
+/// Boxed view of a shared parent tool, for sub-agent registries.
+pub(crate) struct ToolArcRef {
     inner: Arc<dyn Tool>,
 }
 
 impl ToolArcRef {
-    fn new(inner: Arc<dyn Tool>) -> Self {
+    pub(crate) fn new(inner: Arc<dyn Tool>) -> Self {
         Self { inner }
     }
 }
@@ -0,0 +1,180 @@
+use super::traits::{Tool, ToolResult};
+use crate::channels::edits::{note_outbound_edit, OutboundEdit};
+use crate::security::SecurityPolicy;
+use crate::sessions::{blocking_writable, current_session};
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Correct the agent's previous reply in the current conversation. The
+/// channel runtime edits the delivered message in place where the platform
+/// allows it and sends the corrected text as a new message otherwise.
+pub struct EditReplyTool {
+    security: Arc<SecurityPolicy>,
+    workspace_dir: PathBuf,
+}
+
+impl EditReplyTool {
+    pub fn new(security: Arc<SecurityPolicy>, workspace_dir: PathBuf) -> Self {
+        Self {
+            security,
+            workspace_dir,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for EditReplyTool {
+    fn name(&self) -> &str {
+        "edit_reply"
+    }
+
+    fn description(&self) -> &str {
+        "Replace the text of your previous reply in the current conversation, e.g. to fix a wrong \
+         time or figure you gave. 'text' is the full corrected reply. On Telegram, Slack and \
+         Discord the delivered message is edited; elsewhere the corrected text is sent as a new \
+         message."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "text": {
+                    "type": "string",
+                    "description": "The complete corrected reply"
+                }
+            },
+            "required": ["text"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let Some(session) = current_session() else {
+            return Ok(ToolResult::failure(
+                "edit_reply is only available inside a channel conversation",
+            ));
+        };
+        let Some(text) = args
+            .get("text")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+        else {
+            return Ok(ToolResult::failure("Missing 'text' parameter"));
+        };
+        if !self.security.can_act() {
+            return Ok(ToolResult::failure(
+                "Security policy: read-only mode, cannot edit replies",
+            ));
+        }
+        if !self.security.record_action() {
+            return Ok(ToolResult::failure(
+                "Rate limit exceeded: action budget exhausted",
+            ));
+        }
+
+        let (key, channel) = (session.session_key.clone(), session.channel.clone());
+        let reply = blocking_writable(&self.workspace_dir, move |store| {
+            store.latest_delivered_reply(&key, &channel)
+        })
+        .await?;
+        let Some(reply) = reply else {
+            return Ok(ToolResult::failure(
+                "No earlier reply in this conversation can be edited",
+            ));
+        };
+        if reply.content.trim() == text {
+            return Ok(ToolResult::failure("The reply already has this text"));
+        }
+        note_outbound_edit(
+            &session.session_key,
+            OutboundEdit {
+                row_id: reply.id,
+                message_id: reply.message_id,
+                new_content: text.to_string(),
+            },
+        );
+        Ok(ToolResult {
+            success: true,
+            output: "The previous reply will be replaced with the corrected text when this turn \
+                     ends; do not repeat the correction in your answer."
+                .into(),
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::edits::take_outbound_edits;
+    use crate::config::AutonomyConfig;
+    use crate::sessions::{with_session, SessionContext, SqliteSessionStore};
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    const KEY: &str = "slack_C-edit-reply";
+
+    fn security(tmp: &TempDir) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy::from_config(
+            &AutonomyConfig::default(),
+            tmp.path(),
+        ))
+    }
+
+    async fn run(tool: &dyn Tool, args: serde_json::Value) -> ToolResult {
+        let ctx = SessionContext {
+            session_key: KEY.into(),
+            channel: "slack".into(),
+            reply_target: "C-edit-reply".into(),
+            thread_ts: None,
+            sender: String::new(),
+        };
+        with_session(ctx, tool.execute(args)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn queues_an_edit_of_the_latest_delivered_reply() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        let sent = |ts: &str| HashMap::from([("slack_message_id".to_string(), ts.to_string())]);
+        store
+            .append_message_with_metadata(KEY, "assistant", "Hi!", None, &sent("1.0"))
+            .unwrap();
+        let standup = store
+            .append_message_with_metadata(KEY, "assistant", "Standup is at 9", None, &sent("2.0"))
+            .unwrap();
+        store
+            .append_message(KEY, "user", "isn't it at 10?")
+            .unwrap();
+        let tool = EditReplyTool::new(security(&tmp), tmp.path().to_path_buf());
+
+        let result = run(&tool, json!({"text": "Standup is at 10"})).await;
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            take_outbound_edits(KEY),
+            vec![OutboundEdit {
+                row_id: standup,
+                message_id: "2.0".into(),
+                new_content: "Standup is at 10".into(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn fails_without_a_delivered_reply_or_conversation() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        store.append_message(KEY, "assistant", "not sent").unwrap();
+        let tool = EditReplyTool::new(security(&tmp), tmp.path().to_path_buf());
+
+        let result = run(&tool, json!({"text": "fixed"})).await;
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("No earlier reply"));
+
+        let outside = tool.execute(json!({"text": "fixed"})).await.unwrap();
+        assert!(outside.error.unwrap().contains("channel conversation"));
+    }
+}
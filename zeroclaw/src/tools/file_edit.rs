@@ -1,7 +1,8 @@
-use super::traits::{Tool, ToolResult};
+use super::traits::{SandboxOverrides, Tool, ToolResult};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 /// Edit a file by replacing an exact string match with new content.
@@ -12,11 +13,16 @@ use std::sync::Arc;
 /// the matched text. Security checks mirror [`super::file_write::FileWriteTool`].
 pub struct FileEditTool {
     security: Arc<SecurityPolicy>,
+    /// Jail set under a skill's `fs_scope = "skill"`.
+    base_dir: Option<PathBuf>,
 }
 
 impl FileEditTool {
     pub fn new(security: Arc<SecurityPolicy>) -> Self {
-        Self { security }
+        Self {
+            security,
+            base_dir: None,
+        }
     }
 }
 
@@ -125,18 +131,6 @@ impl Tool for FileEditTool {
             }
         };
 
-        // ── 6. Resolved path post-validation ───────────────────────
-        if !self.security.is_resolved_path_allowed(&resolved_parent) {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(
-                    self.security
-                        .resolved_path_violation_message(&resolved_parent),
-                ),
-            });
-        }
-
         let Some(file_name) = full_path.file_name() else {
             return Ok(ToolResult {
                 success: false,
@@ -147,6 +141,18 @@ impl Tool for FileEditTool {
 
         let resolved_target = resolved_parent.join(file_name);
 
+        // ── 6. Resolved path post-validation ───────────────────────
+        if let Err(message) = self
+            .security
+            .validate_path(&resolved_target, self.base_dir.as_deref())
+        {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(message),
+            });
+        }
+
         // ── 7. Symlink check ───────────────────────────────────────
         if let Ok(meta) = tokio::fs::symlink_metadata(&resolved_target).await {
             if meta.file_type().is_symlink() {
@@ -220,6 +226,21 @@ impl Tool for FileEditTool {
             }),
         }
     }
+
+    async fn execute_with_ctx(
+        &self,
+        args: serde_json::Value,
+        overrides: &SandboxOverrides,
+    ) -> anyhow::Result<ToolResult> {
+        let Some(scoped) = overrides.scoped_fs(&self.security) else {
+            return self.execute(args).await;
+        };
+        let scoped_tool = Self {
+            security: scoped.security,
+            base_dir: scoped.base_dir,
+        };
+        scoped_tool.execute(args).await
+    }
 }
 
 #[cfg(test)]
@@ -1,19 +1,32 @@
-use super::traits::{Tool, ToolResult};
+use super::image_info::ImageInfoTool;
+use super::traits::{SandboxOverrides, Tool, ToolResult};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 const MAX_FILE_SIZE_BYTES: u64 = 10 * 1024 * 1024;
+/// Cap on the text returned to the model; larger reads are cut at a line
+/// boundary and point at `start_line` to continue.
+const MAX_OUTPUT_BYTES: usize = 100_000;
+/// How much of a file is inspected when deciding whether it is binary.
+const BINARY_SNIFF_BYTES: usize = 8192;
 
 /// Read file contents with path sandboxing
 pub struct FileReadTool {
     security: Arc<SecurityPolicy>,
+    /// Jail set under a skill's `fs_scope = "skill"`.
+    base_dir: Option<PathBuf>,
 }
 
 impl FileReadTool {
     pub fn new(security: Arc<SecurityPolicy>) -> Self {
-        Self { security }
+        Self {
+            security,
+            base_dir: None,
+        }
     }
 }
 
@@ -24,7 +37,7 @@ impl Tool for FileReadTool {
     }
 
     fn description(&self) -> &str {
-        "Read file contents with line numbers. Supports partial reading via offset and limit. Extracts text from PDF; other binary files are read with lossy UTF-8 conversion."
+        "Read file contents with line numbers. Supports partial reading via start_line/end_line (or offset/limit). Extracts text from PDF; images return metadata; other binary files return a size and type summary instead of their contents."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -35,13 +48,25 @@ impl Tool for FileReadTool {
                     "type": "string",
                     "description": "Path to the file. Relative paths resolve from workspace; outside paths require policy allowlist."
                 },
+                "start_line": {
+                    "type": "integer",
+                    "description": "First line to return (1-based, inclusive, default: 1)"
+                },
+                "end_line": {
+                    "type": "integer",
+                    "description": "Last line to return (1-based, inclusive, default: end of file)"
+                },
                 "offset": {
                     "type": "integer",
-                    "description": "Starting line number (1-based, default: 1)"
+                    "description": "Starting line number (1-based, default: 1); alias of start_line"
                 },
                 "limit": {
                     "type": "integer",
-                    "description": "Maximum number of lines to return (default: all)"
+                    "description": "Maximum number of lines to return (default: all); ignored when end_line is set"
+                },
+                "attach": {
+                    "type": "boolean",
+                    "description": "For image files, also return an [IMAGE:<path>] marker so the image can be passed to a multimodal model (default: false)"
                 }
             },
             "required": ["path"]
@@ -96,14 +121,14 @@ impl Tool for FileReadTool {
             }
         };
 
-        if !self.security.is_resolved_path_allowed(&resolved_path) {
+        if let Err(message) = self
+            .security
+            .validate_path(&resolved_path, self.base_dir.as_deref())
+        {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(
-                    self.security
-                        .resolved_path_violation_message(&resolved_path),
-                ),
+                error: Some(message),
             });
         }
 
@@ -130,90 +155,201 @@ impl Tool for FileReadTool {
             }
         }
 
-        match tokio::fs::read_to_string(&resolved_path).await {
-            Ok(contents) => {
-                let lines: Vec<&str> = contents.lines().collect();
-                let total = lines.len();
+        let bytes = match tokio::fs::read(&resolved_path).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Failed to read file: {e}")),
+                });
+            }
+        };
 
-                if total == 0 {
-                    return Ok(ToolResult {
-                        success: true,
-                        output: String::new(),
-                        error: None,
-                    });
-                }
+        if let Some(text) = try_extract_pdf_text(&bytes) {
+            return Ok(ToolResult {
+                success: true,
+                output: truncate_output(&text, MAX_OUTPUT_BYTES),
+                error: None,
+            });
+        }
 
-                let offset = args
-                    .get("offset")
-                    .and_then(|v| v.as_u64())
-                    .map(|v| {
-                        usize::try_from(v.max(1))
-                            .unwrap_or(usize::MAX)
-                            .saturating_sub(1)
-                    })
-                    .unwrap_or(0);
-                let start = offset.min(total);
-
-                let end = match args.get("limit").and_then(|v| v.as_u64()) {
-                    Some(l) => {
-                        let limit = usize::try_from(l).unwrap_or(usize::MAX);
-                        (start.saturating_add(limit)).min(total)
-                    }
-                    None => total,
-                };
+        let format = ImageInfoTool::detect_format(&bytes);
+        if format != "unknown" {
+            let attach = args
+                .get("attach")
+                .and_then(serde_json::Value::as_bool)
+                .unwrap_or(false);
+            return Ok(ToolResult {
+                success: true,
+                output: describe_image(&bytes, format, &resolved_path, attach),
+                error: None,
+            });
+        }
 
-                if start >= end {
-                    return Ok(ToolResult {
-                        success: true,
-                        output: format!("[No lines in range, file has {total} lines]"),
-                        error: None,
-                    });
-                }
+        if looks_binary(&bytes) {
+            return Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "[Binary file: {} bytes, {}. Contents not shown.]",
+                    bytes.len(),
+                    sniff_mime(&bytes, &resolved_path)
+                ),
+                error: None,
+            });
+        }
 
-                let numbered: String = lines[start..end]
-                    .iter()
-                    .enumerate()
-                    .map(|(i, line)| format!("{}: {}", start + i + 1, line))
-                    .collect::<Vec<_>>()
-                    .join("\n");
-
-                let partial = start > 0 || end < total;
-                let summary = if partial {
-                    format!("\n[Lines {}-{} of {total}]", start + 1, end)
-                } else {
-                    format!("\n[{total} lines total]")
-                };
+        // Mostly-text files with a few invalid bytes (e.g. Latin-1 logs) are
+        // read lossily; invalid bytes become U+FFFD.
+        let contents = String::from_utf8_lossy(&bytes);
+        let output = render_lines(&contents, &args);
+        Ok(ToolResult {
+            success: true,
+            output: truncate_output(&output, MAX_OUTPUT_BYTES),
+            error: None,
+        })
+    }
 
-                Ok(ToolResult {
-                    success: true,
-                    output: format!("{numbered}{summary}"),
-                    error: None,
-                })
-            }
-            Err(_) => {
-                // Not valid UTF-8 — read raw bytes and try to extract text
-                let bytes = tokio::fs::read(&resolved_path)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Failed to read file: {e}"))?;
+    async fn execute_with_ctx(
+        &self,
+        args: serde_json::Value,
+        overrides: &SandboxOverrides,
+    ) -> anyhow::Result<ToolResult> {
+        let Some(scoped) = overrides.scoped_fs(&self.security) else {
+            return self.execute(args).await;
+        };
+        let scoped_tool = Self {
+            security: scoped.security,
+            base_dir: scoped.base_dir,
+        };
+        scoped_tool.execute(args).await
+    }
+}
 
-                if let Some(text) = try_extract_pdf_text(&bytes) {
-                    return Ok(ToolResult {
-                        success: true,
-                        output: text,
-                        error: None,
-                    });
-                }
+/// Number the requested line range, with a header giving the range and the
+/// file's total line count.
+fn render_lines(contents: &str, args: &serde_json::Value) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+    let total = lines.len();
+    if total == 0 {
+        return String::new();
+    }
 
-                // Lossy fallback — replaces invalid bytes with U+FFFD
-                let lossy = String::from_utf8_lossy(&bytes).into_owned();
-                Ok(ToolResult {
-                    success: true,
-                    output: lossy,
-                    error: None,
-                })
-            }
-        }
+    let line_arg = |key: &str| {
+        args.get(key)
+            .and_then(serde_json::Value::as_u64)
+            .map(|v| usize::try_from(v).unwrap_or(usize::MAX))
+    };
+    let start = line_arg("start_line")
+        .or_else(|| line_arg("offset"))
+        .map_or(0, |line| line.max(1) - 1)
+        .min(total);
+    let end = match (line_arg("end_line"), line_arg("limit")) {
+        (Some(end_line), _) => end_line.min(total),
+        (None, Some(limit)) => start.saturating_add(limit).min(total),
+        (None, None) => total,
+    };
+
+    if start >= end {
+        return format!("[No lines in range, file has {total} lines]");
+    }
+
+    let header = if start > 0 || end < total {
+        format!("[Lines {}-{end} of {total}]", start + 1)
+    } else {
+        format!("[{total} lines total]")
+    };
+    let numbered: Vec<String> = lines[start..end]
+        .iter()
+        .enumerate()
+        .map(|(i, line)| format!("{}: {line}", start + i + 1))
+        .collect();
+    format!("{header}\n{}", numbered.join("\n"))
+}
+
+/// Cut `output` to at most `max_bytes`, at a line boundary when possible,
+/// and say so.
+fn truncate_output(output: &str, max_bytes: usize) -> String {
+    if output.len() <= max_bytes {
+        return output.to_string();
     }
+    let mut cut = crate::util::truncate_at_char_boundary(output, max_bytes).len();
+    if let Some(newline) = output[..cut].rfind('\n') {
+        cut = newline;
+    }
+    let kept = &output[..cut];
+    let hint = kept
+        .lines()
+        .last()
+        .and_then(|line| line.split_once(": "))
+        .and_then(|(number, _)| number.parse::<usize>().ok())
+        .map(|last| format!("; continue with start_line={}", last + 1))
+        .unwrap_or_default();
+    format!("{kept}\n[Output truncated at {max_bytes} bytes{hint}]")
+}
+
+/// NUL bytes or a high share of invalid UTF-8 near the start mean binary.
+fn looks_binary(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(BINARY_SNIFF_BYTES)];
+    if sample.contains(&0) {
+        return true;
+    }
+    let invalid: usize = sample
+        .utf8_chunks()
+        .map(|chunk| chunk.invalid().len())
+        .sum();
+    // A sample cut mid-character leaves a few invalid trailing bytes.
+    invalid > 4 && invalid * 10 > sample.len()
+}
+
+/// Best-effort MIME type from magic bytes, then the file extension.
+fn sniff_mime(bytes: &[u8], path: &Path) -> &'static str {
+    const MAGIC: &[(&[u8], &str)] = &[
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"\x7fELF", "application/x-elf"),
+        (b"\0asm", "application/wasm"),
+        (b"SQLite format 3\0", "application/vnd.sqlite3"),
+        (b"MZ", "application/x-msdownload"),
+        (b"OggS", "audio/ogg"),
+        (b"ID3", "audio/mpeg"),
+        (b"fLaC", "audio/flac"),
+    ];
+    if let Some((_, mime)) = MAGIC.iter().find(|(magic, _)| bytes.starts_with(magic)) {
+        return mime;
+    }
+    if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+        return "video/mp4";
+    }
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match ext.as_deref() {
+        Some("tar") => "application/x-tar",
+        Some("7z") => "application/x-7z-compressed",
+        Some("mp3") => "audio/mpeg",
+        Some("wav") => "audio/wav",
+        Some("mp4") => "video/mp4",
+        Some("docx") => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        Some("xlsx") => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Metadata summary for an image file, optionally with an `[IMAGE:]`
+/// marker for multimodal providers.
+fn describe_image(bytes: &[u8], format: &str, path: &Path, attach: bool) -> String {
+    let mut output = format!("[Image file: {format}, {} bytes", bytes.len());
+    if let Some((w, h)) = ImageInfoTool::extract_dimensions(bytes, format) {
+        let _ = write!(output, ", {w}x{h}");
+    }
+    output.push(']');
+    if attach {
+        let _ = write!(output, "\n[IMAGE:{}]", path.display());
+    }
+    output
 }
 
 #[cfg(feature = "rag-pdf")]
@@ -302,6 +438,112 @@ mod tests {
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
 
+    #[tokio::test]
+    async fn file_read_skill_scope_jails_to_skill_data_dir() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_file_read_skill_scope");
+        let skill_dir = dir.join("skills").join("notes");
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        tokio::fs::create_dir_all(&skill_dir).await.unwrap();
+        tokio::fs::write(dir.join("secret.txt"), "workspace only")
+            .await
+            .unwrap();
+        tokio::fs::write(skill_dir.join("SKILL.toml"), "[skill]")
+            .await
+            .unwrap();
+
+        let tool = FileReadTool::new(test_security(dir.clone()));
+        let overrides = SandboxOverrides {
+            skill: "notes".into(),
+            fs_scope: crate::skills::FsScope::Skill,
+            ..SandboxOverrides::default()
+        };
+
+        // The data directory does not exist yet; the first call creates it.
+        let missing = tool
+            .execute_with_ctx(json!({"path": "notes.txt"}), &overrides)
+            .await
+            .unwrap();
+        assert!(!missing.success);
+        assert!(skill_dir.join("data").is_dir());
+
+        tokio::fs::write(skill_dir.join("data").join("notes.txt"), "skill data")
+            .await
+            .unwrap();
+        let inside = tool
+            .execute_with_ctx(json!({"path": "notes.txt"}), &overrides)
+            .await
+            .unwrap();
+        assert!(inside.success, "{:?}", inside.error);
+        assert!(inside.output.contains("skill data"));
+
+        for escape in ["../../../secret.txt", "../SKILL.toml"] {
+            let outside = tool
+                .execute_with_ctx(json!({"path": escape}), &overrides)
+                .await
+                .unwrap();
+            assert!(!outside.success, "{escape} should be outside the jail");
+        }
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(
+                dir.join("secret.txt"),
+                skill_dir.join("data").join("leak.txt"),
+            )
+            .unwrap();
+            let leak = tool
+                .execute_with_ctx(json!({"path": "leak.txt"}), &overrides)
+                .await
+                .unwrap();
+            assert!(!leak.success);
+            assert!(leak.error.unwrap().contains("skill data directory"));
+        }
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn file_read_denies_sensitive_paths_in_every_scope() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_file_read_sensitive");
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        tokio::fs::create_dir_all(dir.join("sessions"))
+            .await
+            .unwrap();
+        tokio::fs::write(dir.join("sessions").join("sessions.db"), "history")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.join("sessions").join("sessions.db-wal"), "pages")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.join("notes.md"), "fine")
+            .await
+            .unwrap();
+
+        let tool = FileReadTool::new(test_security(dir.clone()));
+        let system = SandboxOverrides {
+            skill: "ops".into(),
+            fs_scope: crate::skills::FsScope::System,
+            ..SandboxOverrides::default()
+        };
+
+        for path in ["sessions/sessions.db", "sessions/sessions.db-wal"] {
+            let plain = tool.execute(json!({"path": path})).await.unwrap();
+            assert!(!plain.success, "{path} readable under workspace scope");
+            assert!(plain.error.unwrap().contains("holds agent state"));
+
+            let widened = tool
+                .execute_with_ctx(json!({"path": path}), &system)
+                .await
+                .unwrap();
+            assert!(!widened.success, "{path} readable under system scope");
+        }
+
+        let ok = tool.execute(json!({"path": "notes.md"})).await.unwrap();
+        assert!(ok.success);
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
     #[tokio::test]
     async fn file_read_nonexistent_file() {
         let dir = std::env::temp_dir().join("zeroclaw_test_file_read_missing");
@@ -494,6 +736,41 @@ mod tests {
         let _ = tokio::fs::remove_dir_all(&root).await;
     }
 
+    #[tokio::test]
+    async fn file_read_skill_scope_counts_against_the_shared_budget() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_file_read_scoped_budget");
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        tokio::fs::write(dir.join("notes.txt"), "workspace")
+            .await
+            .unwrap();
+
+        let security = test_security_with(dir.clone(), AutonomyLevel::Supervised, 2);
+        let tool = FileReadTool::new(security.clone());
+        let overrides = SandboxOverrides {
+            skill: "notes".into(),
+            fs_scope: crate::skills::FsScope::Skill,
+            ..SandboxOverrides::default()
+        };
+
+        for _ in 0..2 {
+            tool.execute_with_ctx(json!({"path": "notes.txt"}), &overrides)
+                .await
+                .unwrap();
+        }
+        assert_eq!(security.tracker.count(), 2);
+
+        let scoped = tool
+            .execute_with_ctx(json!({"path": "notes.txt"}), &overrides)
+            .await
+            .unwrap();
+        assert!(scoped.error.unwrap().contains("Rate limit"));
+        let plain = tool.execute(json!({"path": "notes.txt"})).await.unwrap();
+        assert!(plain.error.unwrap().contains("Rate limit"));
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
     #[tokio::test]
     async fn file_read_nonexistent_consumes_rate_limit_budget() {
         let dir = std::env::temp_dir().join("zeroclaw_test_file_read_probe");
@@ -652,10 +929,10 @@ mod tests {
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
 
-    /// Non-UTF-8 binary files should be read with lossy conversion.
+    /// Binary files are summarized instead of dumped.
     #[tokio::test]
-    async fn file_read_lossy_reads_binary_file() {
-        let dir = std::env::temp_dir().join("zeroclaw_test_file_read_lossy");
+    async fn file_read_describes_binary_file() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_file_read_binary");
         let _ = tokio::fs::remove_dir_all(&dir).await;
         tokio::fs::create_dir_all(&dir).await.unwrap();
 
@@ -664,25 +941,146 @@ mod tests {
         tokio::fs::write(dir.join("data.bin"), &binary_data)
             .await
             .unwrap();
+        let mut archive = b"PK\x03\x04".to_vec();
+        archive.extend(std::iter::repeat_n(0xA5, 64));
+        tokio::fs::write(dir.join("bundle.zip"), &archive)
+            .await
+            .unwrap();
 
         let tool = FileReadTool::new(test_security(dir.clone()));
         let result = tool.execute(json!({"path": "data.bin"})).await.unwrap();
+        assert!(result.success, "error: {:?}", result.error);
+        assert_eq!(
+            result.output,
+            "[Binary file: 7 bytes, application/octet-stream. Contents not shown.]"
+        );
+        assert!(!result.output.contains('\u{FFFD}'));
 
-        assert!(
-            result.success,
-            "lossy read must succeed, error: {:?}",
-            result.error
+        let result = tool.execute(json!({"path": "bundle.zip"})).await.unwrap();
+        assert!(result.output.contains("68 bytes, application/zip"));
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    /// Mostly-text files with a few invalid bytes are still read, lossily.
+    #[tokio::test]
+    async fn file_read_lossy_reads_mostly_text_file() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_file_read_lossy");
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let mut latin1 = b"caf\xe9 opened\n".to_vec();
+        latin1.extend(b"plain ascii line\n".repeat(4));
+        tokio::fs::write(dir.join("app.log"), &latin1)
+            .await
+            .unwrap();
+
+        let tool = FileReadTool::new(test_security(dir.clone()));
+        let result = tool.execute(json!({"path": "app.log"})).await.unwrap();
+
+        assert!(result.success, "error: {:?}", result.error);
+        assert!(result.output.contains("1: caf\u{FFFD} opened"));
+        assert!(result.output.contains("5: plain ascii line"));
+        assert!(result.output.starts_with("[5 lines total]\n"));
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[tokio::test]
+    async fn file_read_line_range_of_large_file() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_file_read_range");
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let contents = (1..=10_000)
+            .map(|i| format!("log entry {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        tokio::fs::write(dir.join("big.log"), &contents)
+            .await
+            .unwrap();
+
+        let tool = FileReadTool::new(test_security(dir.clone()));
+        let result = tool
+            .execute(json!({"path": "big.log", "start_line": 5000, "end_line": 5002}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(
+            result.output,
+            "[Lines 5000-5002 of 10000]\n5000: log entry 5000\n5001: log entry 5001\n5002: log entry 5002"
         );
-        assert!(
-            result.output.contains('\u{FFFD}'),
-            "lossy output must contain replacement character, got: {:?}",
-            result.output
+
+        // end_line past the end is clamped to the last line.
+        let result = tool
+            .execute(json!({"path": "big.log", "start_line": 9999, "end_line": 20000}))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.output,
+            "[Lines 9999-10000 of 10000]\n9999: log entry 9999\n10000: log entry 10000"
         );
-        assert!(
-            result.output.contains("hi"),
-            "lossy output must preserve valid ASCII, got: {:?}",
-            result.output
+
+        // start_line past the end reports the total instead of failing.
+        let result = tool
+            .execute(json!({"path": "big.log", "start_line": 10001, "end_line": 10005}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "[No lines in range, file has 10000 lines]");
+
+        // Reading everything stays within the output cap.
+        let result = tool.execute(json!({"path": "big.log"})).await.unwrap();
+        assert!(result.output.len() <= MAX_OUTPUT_BYTES + 100);
+        assert!(result
+            .output
+            .starts_with("[10000 lines total]\n1: log entry 1\n"));
+        assert!(result
+            .output
+            .contains("[Output truncated at 100000 bytes; continue with start_line="));
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[test]
+    fn truncate_output_cuts_at_line_boundary() {
+        assert_eq!(truncate_output("short", 10), "short");
+        let output = "[3 lines total]\n1: aaaa\n2: bbbb\n3: cccc";
+        assert_eq!(
+            truncate_output(output, 28),
+            "[3 lines total]\n1: aaaa\n[Output truncated at 28 bytes; continue with start_line=2]"
+        );
+        assert_eq!(
+            truncate_output("🦀🦀🦀", 5),
+            "🦀\n[Output truncated at 5 bytes]"
         );
+    }
+
+    #[tokio::test]
+    async fn file_read_summarizes_images_and_attaches_on_request() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_file_read_image");
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend(640_u32.to_be_bytes());
+        png.extend(480_u32.to_be_bytes());
+        png.extend([8, 6, 0, 0, 0]);
+        tokio::fs::write(dir.join("shot.png"), &png).await.unwrap();
+
+        let tool = FileReadTool::new(test_security(dir.clone()));
+        let result = tool.execute(json!({"path": "shot.png"})).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "[Image file: png, 29 bytes, 640x480]");
+
+        let result = tool
+            .execute(json!({"path": "shot.png", "attach": true}))
+            .await
+            .unwrap();
+        let resolved = tokio::fs::canonicalize(dir.join("shot.png")).await.unwrap();
+        assert!(result
+            .output
+            .ends_with(&format!("\n[IMAGE:{}]", resolved.display())));
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
@@ -861,17 +1259,17 @@ mod tests {
         let _ = tokio::fs::remove_dir_all(&workspace).await;
     }
 
-    /// End-to-end test: agent calls `file_read` on a binary file, gets
-    /// lossy UTF-8 output with replacement characters in the tool result.
+    /// End-to-end test: agent calls `file_read` on a binary file and gets a
+    /// size and type summary in the tool result instead of raw bytes.
     #[tokio::test]
-    async fn e2e_agent_file_read_lossy_binary() {
+    async fn e2e_agent_file_read_binary_summary() {
         use crate::agent::agent::Agent;
         use crate::agent::dispatcher::NativeToolDispatcher;
         use crate::providers::{ChatResponse, Provider, ToolCall};
         use e2e_helpers::*;
 
         // ── Set up workspace with binary file ──
-        let workspace = std::env::temp_dir().join("zeroclaw_test_e2e_file_read_lossy");
+        let workspace = std::env::temp_dir().join("zeroclaw_test_e2e_file_read_binary");
         let _ = tokio::fs::remove_dir_all(&workspace).await;
         tokio::fs::create_dir_all(&workspace).await.unwrap();
 
@@ -923,7 +1321,7 @@ mod tests {
             "agent response must mention binary, got: {response}",
         );
 
-        // Verify tool result contains lossy output with replacement chars
+        // Verify tool result describes the file instead of dumping it
         {
             let all_requests = recorded.lock().unwrap();
             assert!(
@@ -938,13 +1336,13 @@ mod tests {
                 .expect("second request must contain a tool result message");
 
             assert!(
-                tool_result_msg.content.contains("valid"),
-                "tool result must preserve valid ASCII from binary file, got: {}",
+                tool_result_msg.content.contains("Binary file: 10 bytes"),
+                "tool result must summarize the binary file, got: {}",
                 tool_result_msg.content,
             );
             assert!(
-                tool_result_msg.content.contains('\u{FFFD}'),
-                "tool result must contain replacement character for invalid bytes, got: {}",
+                !tool_result_msg.content.contains('\u{FFFD}'),
+                "tool result must not contain decoded garbage, got: {}",
                 tool_result_msg.content,
             );
         }
@@ -1,17 +1,49 @@
-use super::traits::{Tool, ToolResult};
+use super::traits::{SandboxOverrides, Tool, ToolResult};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Write file contents with path sandboxing
 pub struct FileWriteTool {
     security: Arc<SecurityPolicy>,
+    /// Jail set under a skill's `fs_scope = "skill"`.
+    base_dir: Option<PathBuf>,
+    /// Commit each write to the workspace repository
+    /// (`[security.sandbox] auto_commit_writes`).
+    auto_commit: bool,
 }
 
 impl FileWriteTool {
     pub fn new(security: Arc<SecurityPolicy>) -> Self {
-        Self { security }
+        Self {
+            security,
+            base_dir: None,
+            auto_commit: false,
+        }
+    }
+
+    pub fn with_auto_commit(mut self, enabled: bool) -> Self {
+        self.auto_commit = enabled;
+        self
+    }
+
+    /// Commit `target` to the workspace repository. Failures are reported
+    /// alongside the write result rather than failing a write that happened.
+    async fn commit_write(&self, target: &Path, path: &str) -> Option<String> {
+        let workspace = tokio::fs::canonicalize(&self.security.workspace_dir)
+            .await
+            .ok()?;
+        let relative = target.strip_prefix(&workspace).ok()?;
+        let message = format!("file_write: {}", relative.display());
+        match super::git_operations::commit_workspace_path(&workspace, relative, &message).await {
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!(path, "Auto-commit after file_write failed: {e}");
+                Some(format!(" (auto-commit failed: {e})"))
+            }
+        }
     }
 }
 
@@ -103,17 +135,6 @@ impl Tool for FileWriteTool {
             }
         };
 
-        if !self.security.is_resolved_path_allowed(&resolved_parent) {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(
-                    self.security
-                        .resolved_path_violation_message(&resolved_parent),
-                ),
-            });
-        }
-
         let Some(file_name) = full_path.file_name() else {
             return Ok(ToolResult {
                 success: false,
@@ -124,6 +145,17 @@ impl Tool for FileWriteTool {
 
         let resolved_target = resolved_parent.join(file_name);
 
+        if let Err(message) = self
+            .security
+            .validate_path(&resolved_target, self.base_dir.as_deref())
+        {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(message),
+            });
+        }
+
         // If the target already exists and is a symlink, refuse to follow it
         if let Ok(meta) = tokio::fs::symlink_metadata(&resolved_target).await {
             if meta.file_type().is_symlink() {
@@ -147,11 +179,19 @@ impl Tool for FileWriteTool {
         }
 
         match tokio::fs::write(&resolved_target, content).await {
-            Ok(()) => Ok(ToolResult {
-                success: true,
-                output: format!("Written {} bytes to {path}", content.len()),
-                error: None,
-            }),
+            Ok(()) => {
+                let mut output = format!("Written {} bytes to {path}", content.len());
+                if self.auto_commit {
+                    if let Some(note) = self.commit_write(&resolved_target, path).await {
+                        output.push_str(&note);
+                    }
+                }
+                Ok(ToolResult {
+                    success: true,
+                    output,
+                    error: None,
+                })
+            }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
@@ -159,6 +199,22 @@ impl Tool for FileWriteTool {
             }),
         }
     }
+
+    async fn execute_with_ctx(
+        &self,
+        args: serde_json::Value,
+        overrides: &SandboxOverrides,
+    ) -> anyhow::Result<ToolResult> {
+        let Some(scoped) = overrides.scoped_fs(&self.security) else {
+            return self.execute(args).await;
+        };
+        let scoped_tool = Self {
+            security: scoped.security,
+            base_dir: scoped.base_dir,
+            auto_commit: self.auto_commit,
+        };
+        scoped_tool.execute(args).await
+    }
 }
 
 #[cfg(test)]
@@ -271,6 +327,35 @@ mod tests {
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
 
+    #[tokio::test]
+    async fn file_write_auto_commits_to_workspace_repository() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let tool =
+            FileWriteTool::new(test_security(dir.path().to_path_buf())).with_auto_commit(true);
+
+        for content in ["one", "two"] {
+            let result = tool
+                .execute(json!({"path": "notes/todo.md", "content": content}))
+                .await
+                .unwrap();
+            assert!(result.success);
+            assert!(
+                !result.output.contains("auto-commit failed"),
+                "{}",
+                result.output
+            );
+        }
+
+        let log = std::process::Command::new("git")
+            .args(["log", "--format=%s", "--", "notes/todo.md"])
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        let subjects = String::from_utf8_lossy(&log.stdout);
+        assert_eq!(subjects.lines().count(), 2);
+        assert!(subjects.contains("file_write: notes/todo.md"));
+    }
+
     #[tokio::test]
     async fn file_write_blocks_path_traversal() {
         let dir = std::env::temp_dir().join("zeroclaw_test_file_write_traversal");
@@ -288,6 +373,26 @@ mod tests {
         let _ = tokio::fs::remove_dir_all(&dir).await;
     }
 
+    #[tokio::test]
+    async fn file_write_blocks_sensitive_workspace_files() {
+        let dir = std::env::temp_dir().join("zeroclaw_test_file_write_sensitive");
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+
+        let tool = FileWriteTool::new(test_security(dir.clone()));
+        for path in ["sessions/sessions.db", "sessions/sessions.db-wal"] {
+            let result = tool
+                .execute(json!({"path": path, "content": "tampered"}))
+                .await
+                .unwrap();
+            assert!(!result.success, "{path} should be denied");
+            assert!(result.error.unwrap().contains("holds agent state"));
+            assert!(!dir.join(path).exists());
+        }
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
     #[tokio::test]
     async fn file_write_blocks_absolute_path() {
         let tool = FileWriteTool::new(test_security(std::env::temp_dir()));
@@ -0,0 +1,175 @@
+use super::traits::{Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use crate::sessions::redact::{note_forgotten, Forgotten, REDACTED_CONTENT};
+use crate::sessions::{blocking_writable, current_session};
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Redact a user message of the current conversation in the session store
+/// and drop it from the channel runtime's cached history, so it is not sent
+/// to the model again.
+pub struct ForgetMessageTool {
+    security: Arc<SecurityPolicy>,
+    workspace_dir: PathBuf,
+}
+
+impl ForgetMessageTool {
+    pub fn new(security: Arc<SecurityPolicy>, workspace_dir: PathBuf) -> Self {
+        Self {
+            security,
+            workspace_dir,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ForgetMessageTool {
+    fn name(&self) -> &str {
+        "forget"
+    }
+
+    fn description(&self) -> &str {
+        "Permanently redact a message the user sent in the current conversation, e.g. when they \
+         say 'forget what I just told you'. By default redacts the user's message before the \
+         current request; 'index' selects an earlier one (2 = the one before that), and 0 \
+         redacts the current request itself. Long-term memories are separate: use memory_forget \
+         for those."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "index": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "n-th user message before the current request (1 = the previous one, default); 0 = the current request"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let Some(session) = current_session() else {
+            return Ok(ToolResult::failure(
+                "forget is only available inside a channel conversation",
+            ));
+        };
+        if !self.security.can_act() {
+            return Ok(ToolResult::failure(
+                "Security policy: read-only mode, cannot redact messages",
+            ));
+        }
+        if !self.security.record_action() {
+            return Ok(ToolResult::failure(
+                "Rate limit exceeded: action budget exhausted",
+            ));
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        let index = args
+            .get("index")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(1, |n| n as usize);
+
+        let key = session.session_key.clone();
+        let redacted = blocking_writable(&self.workspace_dir, move |store| {
+            // The current request is the latest user turn of the session.
+            match store.recent_user_message_id(&key, index)? {
+                Some(id) => store.redact_message(id),
+                None => Ok(None),
+            }
+        })
+        .await?;
+        let Some(redacted) = redacted else {
+            return Ok(ToolResult::failure("No such message in this conversation"));
+        };
+        note_forgotten(
+            &session.session_key,
+            Forgotten::Messages(vec![redacted.content]),
+        );
+        Ok(ToolResult {
+            success: true,
+            output: format!(
+                "Redacted the message; it is stored as \"{REDACTED_CONTENT}\" and will not be in \
+                 the conversation from the next message on."
+            ),
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AutonomyConfig;
+    use crate::sessions::redact::take_forgotten;
+    use crate::sessions::{with_session, SessionContext, SqliteSessionStore};
+    use tempfile::TempDir;
+
+    const KEY: &str = "telegram_forgetful";
+
+    fn security(tmp: &TempDir) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy::from_config(
+            &AutonomyConfig::default(),
+            tmp.path(),
+        ))
+    }
+
+    async fn run(tool: &dyn Tool, args: serde_json::Value) -> ToolResult {
+        let ctx = SessionContext {
+            session_key: KEY.into(),
+            channel: "telegram".into(),
+            reply_target: "chat-42".into(),
+            thread_ts: None,
+            sender: String::new(),
+        };
+        with_session(ctx, tool.execute(args)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn redacts_the_message_before_the_request() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        for (role, content) in [
+            ("user", "my door code is 4711"),
+            ("assistant", "Got it."),
+            ("user", "forget what I just told you"),
+        ] {
+            store.append_message(KEY, role, content).unwrap();
+        }
+        let forget = ForgetMessageTool::new(security(&tmp), tmp.path().to_path_buf());
+
+        let result = run(&forget, json!({})).await;
+        assert!(result.success, "{:?}", result.error);
+        assert!(!result.output.contains("4711"));
+        let contents: Vec<_> = store
+            .load_history(KEY, None)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.content)
+            .collect();
+        assert_eq!(
+            contents,
+            [REDACTED_CONTENT, "Got it.", "forget what I just told you"]
+        );
+        assert_eq!(
+            take_forgotten(KEY),
+            Some(Forgotten::Messages(vec!["my door code is 4711".into()]))
+        );
+
+        // Nothing older is left to forget.
+        let again = run(&forget, json!({})).await;
+        assert!(!again.success);
+    }
+
+    #[tokio::test]
+    async fn requires_a_channel_conversation() {
+        let tmp = TempDir::new().unwrap();
+        let forget = ForgetMessageTool::new(security(&tmp), tmp.path().to_path_buf());
+        let result = forget.execute(json!({})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("channel conversation"));
+    }
+}
@@ -2,10 +2,118 @@ use super::traits::{Tool, ToolResult};
 use crate::security::{AutonomyLevel, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
+
+/// Upper bound for a single git invocation.
+const GIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Written to a workspace repository created on first use: databases and
+/// uploaded attachments change constantly and do not belong in history.
+const WORKSPACE_GITIGNORE: &str = "\
+# Created by ZeroClaw for the workspace repository.
+*.db
+*.db-journal
+*.db-shm
+*.db-wal
+attachments/
+";
+
+async fn run_git(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = tokio::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(GIT_TIMEOUT, output)
+        .await
+        .map_err(|_| anyhow::anyhow!("Git command timed out after {}s", GIT_TIMEOUT.as_secs()))??;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Git command failed: {stderr}");
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Root of the repository containing `workspace_dir`, or `None` outside one.
+async fn repository_root(workspace_dir: &Path) -> Option<PathBuf> {
+    let top = run_git(workspace_dir, &["rev-parse", "--show-toplevel"])
+        .await
+        .ok()?;
+    Some(PathBuf::from(top.trim()))
+}
+
+/// Fail unless `root` is the workspace itself; a workspace nested in a
+/// larger repository would otherwise let the agent touch files outside it.
+fn check_repository_scope(workspace_dir: &Path, root: &Path) -> anyhow::Result<()> {
+    let workspace = std::fs::canonicalize(workspace_dir)?;
+    let root = std::fs::canonicalize(root)?;
+    if root != workspace {
+        anyhow::bail!(
+            "Refusing to use the git repository at {}: it is rooted outside the workspace {}",
+            root.display(),
+            workspace.display()
+        );
+    }
+    Ok(())
+}
+
+/// Make sure the workspace is the root of its own repository, running
+/// `git init` (with a local identity and `.gitignore`) when it is in none.
+pub(crate) async fn ensure_workspace_repo(workspace_dir: &Path) -> anyhow::Result<()> {
+    if let Some(root) = repository_root(workspace_dir).await {
+        return check_repository_scope(workspace_dir, &root);
+    }
+
+    run_git(workspace_dir, &["init", "--quiet"]).await?;
+    run_git(workspace_dir, &["config", "user.name", "ZeroClaw"]).await?;
+    run_git(
+        workspace_dir,
+        &["config", "user.email", "zeroclaw@localhost"],
+    )
+    .await?;
+    let gitignore = workspace_dir.join(".gitignore");
+    if !gitignore.exists() {
+        tokio::fs::write(&gitignore, WORKSPACE_GITIGNORE).await?;
+    }
+    tracing::info!(
+        workspace = %workspace_dir.display(),
+        "Initialized workspace git repository"
+    );
+    Ok(())
+}
+
+/// Commit the current content of `path` (relative to the workspace).
+/// Returns `false` when the file had no changes to record.
+pub(crate) async fn commit_workspace_path(
+    workspace_dir: &Path,
+    path: &Path,
+    message: &str,
+) -> anyhow::Result<bool> {
+    ensure_workspace_repo(workspace_dir).await?;
+    let path = path.to_string_lossy();
+    run_git(workspace_dir, &["add", "--", &path]).await?;
+    if run_git(workspace_dir, &["diff", "--cached", "--quiet", "--", &path])
+        .await
+        .is_ok()
+    {
+        return Ok(false);
+    }
+    run_git(
+        workspace_dir,
+        &["commit", "--quiet", "-m", message, "--", &path],
+    )
+    .await?;
+    Ok(true)
+}
 
 /// Git operations tool for structured repository management.
-/// Provides safe, parsed git operations with JSON output.
+/// Provides safe, parsed git operations with JSON output. Only a repository
+/// rooted at the workspace is used; one is created on first use when the
+/// workspace is not in a repository yet.
 pub struct GitOperationsTool {
     security: Arc<SecurityPolicy>,
     workspace_dir: std::path::PathBuf,
@@ -53,7 +161,7 @@ impl GitOperationsTool {
     fn requires_write_access(&self, operation: &str) -> bool {
         matches!(
             operation,
-            "commit" | "add" | "checkout" | "stash" | "reset" | "revert"
+            "commit" | "add" | "checkout" | "stash" | "reset" | "revert" | "revert_file"
         )
     }
 
@@ -66,18 +174,7 @@ impl GitOperationsTool {
     }
 
     async fn run_git_command(&self, args: &[&str]) -> anyhow::Result<String> {
-        let output = tokio::process::Command::new("git")
-            .args(args)
-            .current_dir(&self.workspace_dir)
-            .output()
-            .await?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("Git command failed: {stderr}");
-        }
-
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        run_git(&self.workspace_dir, args).await
     }
 
     async fn git_status(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
@@ -421,6 +518,47 @@ impl GitOperationsTool {
             }),
         }
     }
+
+    async fn git_revert_file(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+        let commit = args
+            .get("commit")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'commit' parameter"))?;
+
+        self.sanitize_git_args(path)?;
+        let sanitized = self.sanitize_git_args(commit)?;
+        if sanitized.len() != 1 || sanitized[0].starts_with('-') {
+            anyhow::bail!("Invalid commit specification");
+        }
+        if !self.security.is_path_allowed(path) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Path not allowed by security policy: {path}")),
+            });
+        }
+
+        let output = self
+            .run_git_command(&["checkout", &sanitized[0], "--", path])
+            .await;
+
+        match output {
+            Ok(_) => Ok(ToolResult {
+                success: true,
+                output: format!("Restored {path} from {}", sanitized[0]),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Revert failed: {e}")),
+            }),
+        }
+    }
 }
 
 #[async_trait]
@@ -430,7 +568,7 @@ impl Tool for GitOperationsTool {
     }
 
     fn description(&self) -> &str {
-        "Perform structured Git operations (status, diff, log, branch, commit, add, checkout, stash). Provides parsed JSON output and integrates with security policy for autonomy controls."
+        "Perform structured Git operations (status, diff, log, branch, commit, add, checkout, stash, revert_file) on the workspace repository, created on first use. Provides parsed JSON output and integrates with security policy for autonomy controls."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -439,7 +577,7 @@ impl Tool for GitOperationsTool {
             "properties": {
                 "operation": {
                     "type": "string",
-                    "enum": ["status", "diff", "log", "branch", "commit", "add", "checkout", "stash"],
+                    "enum": ["status", "diff", "log", "branch", "commit", "add", "checkout", "stash", "revert_file"],
                     "description": "Git operation to perform"
                 },
                 "message": {
@@ -474,6 +612,14 @@ impl Tool for GitOperationsTool {
                 "index": {
                     "type": "integer",
                     "description": "Stash index (for 'stash' with 'drop' action)"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "File to restore (for 'revert_file' operation)"
+                },
+                "commit": {
+                    "type": "string",
+                    "description": "Commit to restore the file from (for 'revert_file' operation)"
                 }
             },
             "required": ["operation"]
@@ -492,26 +638,22 @@ impl Tool for GitOperationsTool {
             }
         };
 
-        // Check if we're in a git repository
-        if !self.workspace_dir.join(".git").exists() {
-            // Try to find .git in parent directories
-            let mut current_dir = self.workspace_dir.as_path();
-            let mut found_git = false;
-            while current_dir.parent().is_some() {
-                if current_dir.join(".git").exists() {
-                    found_git = true;
-                    break;
-                }
-                current_dir = current_dir.parent().unwrap();
-            }
-
-            if !found_git {
-                return Ok(ToolResult {
-                    success: false,
-                    output: String::new(),
-                    error: Some("Not in a git repository".into()),
-                });
+        // Only a repository rooted at the workspace is used. It is created on
+        // first use unless the policy forbids writes.
+        let repository = if self.security.can_act() {
+            ensure_workspace_repo(&self.workspace_dir).await
+        } else {
+            match repository_root(&self.workspace_dir).await {
+                Some(root) => check_repository_scope(&self.workspace_dir, &root),
+                None => Err(anyhow::anyhow!("Not in a git repository")),
             }
+        };
+        if let Err(e) = repository {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(e.to_string()),
+            });
         }
 
         // Check autonomy level for write operations
@@ -557,6 +699,7 @@ impl Tool for GitOperationsTool {
             "add" => self.git_add(args).await,
             "checkout" => self.git_checkout(args).await,
             "stash" => self.git_stash(args).await,
+            "revert_file" => self.git_revert_file(args).await,
             _ => Ok(ToolResult {
                 success: false,
                 output: String::new(),
@@ -810,4 +953,76 @@ mod tests {
 
         assert_eq!(truncated.chars().count(), 2000);
     }
+
+    fn git(dir: &std::path::Path, args: &[&str]) -> String {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    #[tokio::test]
+    async fn initializes_workspace_repository_on_first_use() {
+        let tmp = TempDir::new().unwrap();
+        let tool = test_tool(tmp.path());
+
+        let result = tool.execute(json!({"operation": "status"})).await.unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        assert!(tmp.path().join(".git").is_dir());
+        let gitignore = std::fs::read_to_string(tmp.path().join(".gitignore")).unwrap();
+        assert!(gitignore.contains("*.db"));
+        assert!(gitignore.contains("attachments/"));
+    }
+
+    #[tokio::test]
+    async fn refuses_repository_rooted_outside_workspace() {
+        let tmp = TempDir::new().unwrap();
+        git(tmp.path(), &["init", "--quiet"]);
+        let workspace = tmp.path().join("workspace");
+        std::fs::create_dir(&workspace).unwrap();
+        let tool = test_tool(&workspace);
+
+        let result = tool.execute(json!({"operation": "status"})).await.unwrap();
+
+        assert!(!result.success);
+        assert!(result
+            .error
+            .unwrap()
+            .contains("rooted outside the workspace"));
+        assert!(!workspace.join(".git").exists());
+    }
+
+    #[tokio::test]
+    async fn revert_file_restores_prior_content() {
+        let tmp = TempDir::new().unwrap();
+        let path = std::path::Path::new("notes.md");
+        std::fs::write(tmp.path().join(path), "first").unwrap();
+        assert!(commit_workspace_path(tmp.path(), path, "v1").await.unwrap());
+        let first = git(tmp.path(), &["rev-parse", "HEAD"]);
+        std::fs::write(tmp.path().join(path), "second").unwrap();
+        assert!(commit_workspace_path(tmp.path(), path, "v2").await.unwrap());
+        assert!(!commit_workspace_path(tmp.path(), path, "noop")
+            .await
+            .unwrap());
+
+        let tool = test_tool(tmp.path());
+        let result = tool
+            .execute(json!({"operation": "revert_file", "path": "notes.md", "commit": first}))
+            .await
+            .unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join(path)).unwrap(),
+            "first"
+        );
+
+        let result = tool
+            .execute(json!({"operation": "revert_file", "path": "notes.md", "commit": "--orphan"}))
+            .await;
+        assert!(result.is_err());
+    }
 }
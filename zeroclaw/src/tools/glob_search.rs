@@ -125,8 +125,8 @@ impl Tool for GlobSearchTool {
                 Err(_) => continue, // skip broken symlinks / unresolvable paths
             };
 
-            if !self.security.is_resolved_path_allowed(&resolved) {
-                continue; // silently filter symlink escapes
+            if self.security.validate_path(&resolved, None).is_err() {
+                continue; // silently filter symlink escapes and sensitive files
             }
 
             // Only include files, not directories
@@ -24,7 +24,7 @@ impl ImageInfoTool {
     }
 
     /// Detect image format from first few bytes (magic numbers).
-    fn detect_format(bytes: &[u8]) -> &'static str {
+    pub(crate) fn detect_format(bytes: &[u8]) -> &'static str {
         if bytes.len() < 4 {
             return "unknown";
         }
@@ -45,7 +45,7 @@ impl ImageInfoTool {
 
     /// Try to extract dimensions from image header bytes.
     /// Returns (width, height) if detectable.
-    fn extract_dimensions(bytes: &[u8], format: &str) -> Option<(u32, u32)> {
+    pub(crate) fn extract_dimensions(bytes: &[u8], format: &str) -> Option<(u32, u32)> {
         match format {
             "png" => {
                 // PNG IHDR chunk: bytes 16-19 = width, 20-23 = height (big-endian)
@@ -0,0 +1,205 @@
+use super::traits::{Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use crate::sessions::links::LINK_CODE_TTL;
+use crate::sessions::{blocking_writable, current_session};
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+fn success(output: String) -> ToolResult {
+    ToolResult {
+        success: true,
+        output,
+        error: None,
+    }
+}
+
+/// Continue the current conversation on another channel: issue a link code
+/// here, redeem it there, and both channels share one history.
+pub struct LinkSessionsTool {
+    security: Arc<SecurityPolicy>,
+    workspace_dir: PathBuf,
+}
+
+impl LinkSessionsTool {
+    pub fn new(security: Arc<SecurityPolicy>, workspace_dir: PathBuf) -> Self {
+        Self {
+            security,
+            workspace_dir,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for LinkSessionsTool {
+    fn name(&self) -> &str {
+        "link_sessions"
+    }
+
+    fn description(&self) -> &str {
+        "Link this conversation with one on another channel so both share the same history \
+         (e.g. start on WhatsApp, continue in webchat). action 'code' issues a six-digit code \
+         valid for 10 minutes; the user sends it on the other channel, where you call action \
+         'redeem' with that code. action 'unlink' separates this conversation from every \
+         channel linked to it. Replies always go to the channel the user wrote on."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["code", "redeem", "unlink"],
+                    "description": "'code' to start linking here, 'redeem' to finish it with a code from the other channel, 'unlink' to undo links"
+                },
+                "code": {
+                    "type": "string",
+                    "description": "Code issued on the other channel (required for 'redeem')"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let Some(session) = current_session() else {
+            return Ok(ToolResult::failure(
+                "link_sessions is only available inside a channel conversation",
+            ));
+        };
+        let action = args
+            .get("action")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default();
+        if !matches!(action, "code" | "redeem" | "unlink") {
+            return Ok(ToolResult::failure(
+                "'action' must be one of: code, redeem, unlink",
+            ));
+        }
+        if !self.security.can_act() {
+            return Ok(ToolResult::failure(
+                "Security policy: read-only mode, cannot change session links",
+            ));
+        }
+        if !self.security.record_action() {
+            return Ok(ToolResult::failure(
+                "Rate limit exceeded: action budget exhausted",
+            ));
+        }
+
+        let key = session.session_key;
+        match action {
+            "code" => {
+                let code = blocking_writable(&self.workspace_dir, move |store| {
+                    store.create_link_code(&key, LINK_CODE_TTL)
+                })
+                .await?;
+                Ok(success(format!(
+                    "Link code: {code}. Send it on the other channel within {} minutes to \
+                     continue this conversation there.",
+                    LINK_CODE_TTL.as_secs() / 60
+                )))
+            }
+            "redeem" => {
+                let Some(code) = args
+                    .get("code")
+                    .and_then(serde_json::Value::as_str)
+                    .filter(|code| !code.trim().is_empty())
+                else {
+                    return Ok(ToolResult::failure("Missing 'code' parameter"));
+                };
+                let code = code.to_string();
+                let redeemed = blocking_writable(&self.workspace_dir, move |store| {
+                    store.redeem_link_code(&code, &key)
+                })
+                .await;
+                match redeemed {
+                    Ok(Some(canonical)) => Ok(success(format!(
+                        "Linked: this conversation now continues session {canonical}."
+                    ))),
+                    Ok(None) => Ok(ToolResult::failure("Unknown or expired link code")),
+                    Err(e) => Ok(ToolResult::failure(format!("{e:#}"))),
+                }
+            }
+            _ => match blocking_writable(&self.workspace_dir, move |store| {
+                store.unlink_session(&key)
+            })
+            .await?
+            {
+                0 => Ok(ToolResult::failure(
+                    "This conversation is not linked to another channel",
+                )),
+                n => Ok(success(format!("Unlinked {n} session(s)."))),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AutonomyConfig;
+    use crate::sessions::{with_session, SessionContext, SqliteSessionStore};
+    use tempfile::TempDir;
+
+    async fn run(tool: &dyn Tool, key: &str, args: serde_json::Value) -> ToolResult {
+        let (channel, sender) = key.split_once('_').unwrap();
+        let ctx = SessionContext {
+            session_key: key.into(),
+            channel: channel.into(),
+            reply_target: sender.into(),
+            thread_ts: None,
+            sender: sender.into(),
+        };
+        with_session(ctx, tool.execute(args)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn code_from_one_channel_links_the_other() {
+        let tmp = TempDir::new().unwrap();
+        let security = Arc::new(SecurityPolicy::from_config(
+            &AutonomyConfig::default(),
+            tmp.path(),
+        ));
+        let tool = LinkSessionsTool::new(security, tmp.path().to_path_buf());
+
+        let issued = run(&tool, "whatsapp_alice", json!({"action": "code"})).await;
+        assert!(issued.success, "{:?}", issued.error);
+        let code: String = issued
+            .output
+            .chars()
+            .filter(char::is_ascii_digit)
+            .take(6)
+            .collect();
+
+        let wrong = run(
+            &tool,
+            "webchat_alice",
+            json!({"action": "redeem", "code": "nope"}),
+        )
+        .await;
+        assert!(!wrong.success);
+        let linked = run(
+            &tool,
+            "webchat_alice",
+            json!({"action": "redeem", "code": code}),
+        )
+        .await;
+        assert!(linked.success, "{:?}", linked.error);
+        assert!(linked.output.contains("whatsapp_alice"));
+
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        assert_eq!(
+            store.canonical_key("webchat_alice").unwrap(),
+            "whatsapp_alice"
+        );
+        let unlinked = run(&tool, "whatsapp_alice", json!({"action": "unlink"})).await;
+        assert!(unlinked.success);
+        assert_eq!(
+            store.canonical_key("webchat_alice").unwrap(),
+            "webchat_alice"
+        );
+    }
+}
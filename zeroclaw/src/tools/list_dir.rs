@@ -0,0 +1,476 @@
+use super::traits::{SandboxOverrides, Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Hard cap on entries returned by one call.
+const MAX_ENTRIES: usize = 500;
+/// Depth used by `recursive: true` when `max_depth` is not given.
+const DEFAULT_MAX_DEPTH: usize = 5;
+const MAX_DEPTH_LIMIT: usize = 20;
+
+/// List a directory, optionally recursively, with glob filtering and metadata.
+pub struct ListDirTool {
+    security: Arc<SecurityPolicy>,
+    /// Jail set under a skill's `fs_scope = "skill"`.
+    base_dir: Option<PathBuf>,
+}
+
+impl ListDirTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self {
+            security,
+            base_dir: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    /// Path relative to the workspace (absolute when listing an allowed
+    /// root outside it).
+    display: String,
+    kind: &'static str,
+    size: u64,
+    modified: Option<String>,
+}
+
+impl Entry {
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "path": self.display,
+            "type": self.kind,
+            "size": self.size,
+            "modified": self.modified,
+        })
+    }
+}
+
+fn is_hidden(name: &str) -> bool {
+    name.starts_with('.')
+}
+
+/// Depth-first directory walk in name order, collecting up to
+/// [`MAX_ENTRIES`] matching entries.
+struct Walker<'a> {
+    security: &'a SecurityPolicy,
+    base_dir: Option<&'a Path>,
+    /// Directory being listed; `pattern` matches paths relative to it.
+    root: &'a Path,
+    workspace: &'a Path,
+    max_depth: usize,
+    pattern: Option<&'a glob::Pattern>,
+    include_hidden: bool,
+    entries: Vec<Entry>,
+}
+
+impl Walker<'_> {
+    /// Returns `true` when the walk stopped at [`MAX_ENTRIES`].
+    fn walk(&mut self, dir: &Path, depth: usize) -> bool {
+        let Ok(read_dir) = std::fs::read_dir(dir) else {
+            return false;
+        };
+        let mut children: Vec<_> = read_dir.filter_map(Result::ok).collect();
+        children.sort_by_key(std::fs::DirEntry::file_name);
+
+        for child in children {
+            let name = child.file_name().to_string_lossy().to_string();
+            if !self.include_hidden && is_hidden(&name) {
+                continue;
+            }
+            let path = child.path();
+            // Skips agent state files and anything the policy forbids.
+            if self.security.validate_path(&path, self.base_dir).is_err() {
+                continue;
+            }
+            // Symlinks are reported but never followed.
+            let Ok(meta) = std::fs::symlink_metadata(&path) else {
+                continue;
+            };
+            let kind = if meta.file_type().is_symlink() {
+                "symlink"
+            } else if meta.is_dir() {
+                "dir"
+            } else {
+                "file"
+            };
+
+            let relative_to_root = path.strip_prefix(self.root).unwrap_or(&path);
+            let matches = self.pattern.is_none_or(|pattern| {
+                pattern.matches_path_with(
+                    relative_to_root,
+                    glob::MatchOptions {
+                        case_sensitive: true,
+                        require_literal_separator: true,
+                        require_literal_leading_dot: false,
+                    },
+                )
+            });
+            if matches {
+                if self.entries.len() >= MAX_ENTRIES {
+                    return true;
+                }
+                let display = path
+                    .strip_prefix(self.workspace)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .to_string();
+                self.entries.push(Entry {
+                    display,
+                    kind,
+                    size: if kind == "file" { meta.len() } else { 0 },
+                    modified: meta.modified().ok().map(|time| {
+                        chrono::DateTime::<chrono::Utc>::from(time)
+                            .format("%Y-%m-%dT%H:%M:%SZ")
+                            .to_string()
+                    }),
+                });
+            }
+
+            if kind == "dir" && depth < self.max_depth && self.walk(&path, depth + 1) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+fn error_result(message: impl Into<String>) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(message.into()),
+    }
+}
+
+#[async_trait]
+impl Tool for ListDirTool {
+    fn name(&self) -> &str {
+        "list_dir"
+    }
+
+    fn description(&self) -> &str {
+        "List a directory's entries as workspace-relative paths (directories end with '/'). \
+         Set recursive=true (with max_depth) to walk subdirectories in one call, pattern to \
+         filter by glob (e.g. '**/*.rs'), and include_metadata for JSON with type, size and \
+         modified time. Hidden entries such as .git are skipped unless include_hidden=true."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Directory to list, relative to the workspace (default: '.')"
+                },
+                "recursive": {
+                    "type": "boolean",
+                    "description": "Walk subdirectories (default: false)"
+                },
+                "max_depth": {
+                    "type": "integer",
+                    "description": "Levels below 'path' to descend when recursive (default: 5, max: 20)"
+                },
+                "pattern": {
+                    "type": "string",
+                    "description": "Glob matched against paths relative to 'path', e.g. '*.md' or '**/*.rs'"
+                },
+                "include_metadata": {
+                    "type": "boolean",
+                    "description": "Return JSON entries with type (file/dir/symlink), size in bytes and modified time (default: false)"
+                },
+                "include_hidden": {
+                    "type": "boolean",
+                    "description": "Include dotfiles and dot-directories such as .git (default: false)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.trim().is_empty())
+            .unwrap_or(".");
+        let flag = |name: &str| args.get(name).and_then(|v| v.as_bool()).unwrap_or(false);
+        let recursive = flag("recursive");
+        let include_metadata = flag("include_metadata");
+        let include_hidden = flag("include_hidden");
+        let max_depth = if recursive {
+            args.get("max_depth")
+                .and_then(|v| v.as_u64())
+                .map_or(DEFAULT_MAX_DEPTH, |d| {
+                    usize::try_from(d).unwrap_or(MAX_DEPTH_LIMIT)
+                })
+                .clamp(1, MAX_DEPTH_LIMIT)
+        } else {
+            1
+        };
+        let pattern = match args.get("pattern").and_then(|v| v.as_str()) {
+            Some(raw) => match glob::Pattern::new(raw) {
+                Ok(pattern) => Some(pattern),
+                Err(e) => return Ok(error_result(format!("Invalid glob pattern: {e}"))),
+            },
+            None => None,
+        };
+
+        if self.security.is_rate_limited() {
+            return Ok(error_result(
+                "Rate limit exceeded: too many actions in the last hour",
+            ));
+        }
+
+        if !self.security.is_path_allowed(path) {
+            return Ok(error_result(format!(
+                "Path not allowed by security policy: {path}"
+            )));
+        }
+
+        if !self.security.record_action() {
+            return Ok(error_result("Rate limit exceeded: action budget exhausted"));
+        }
+
+        let resolved = match tokio::fs::canonicalize(self.security.workspace_dir.join(path)).await {
+            Ok(p) => p,
+            Err(e) => return Ok(error_result(format!("Failed to resolve directory: {e}"))),
+        };
+        if let Err(message) = self
+            .security
+            .validate_path(&resolved, self.base_dir.as_deref())
+        {
+            return Ok(error_result(message));
+        }
+        if !resolved.is_dir() {
+            return Ok(error_result(format!("Not a directory: {path}")));
+        }
+
+        let workspace = std::fs::canonicalize(&self.security.workspace_dir)
+            .unwrap_or_else(|_| self.security.workspace_dir.clone());
+        let security = Arc::clone(&self.security);
+        let base_dir = self.base_dir.clone();
+        let (entries, truncated) = tokio::task::spawn_blocking(move || {
+            let mut walker = Walker {
+                security: &security,
+                base_dir: base_dir.as_deref(),
+                root: &resolved,
+                workspace: &workspace,
+                max_depth,
+                pattern: pattern.as_ref(),
+                include_hidden,
+                entries: Vec::new(),
+            };
+            let truncated = walker.walk(&resolved, 1);
+            (walker.entries, truncated)
+        })
+        .await?;
+
+        let output = if include_metadata {
+            serde_json::to_string_pretty(&json!({
+                "entries": entries.iter().map(Entry::to_json).collect::<Vec<_>>(),
+                "truncated": truncated,
+            }))?
+        } else if entries.is_empty() {
+            format!("No entries found in '{path}'.")
+        } else {
+            let mut buf = String::new();
+            for entry in &entries {
+                let suffix = if entry.kind == "dir" { "/" } else { "" };
+                let _ = writeln!(buf, "{}{suffix}", entry.display);
+            }
+            if truncated {
+                let _ = writeln!(
+                    buf,
+                    "\n[Results truncated: showing first {MAX_ENTRIES} entries; narrow with pattern or max_depth]"
+                );
+            }
+            let _ = write!(buf, "\nTotal: {} entries", entries.len());
+            buf
+        };
+
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+
+    async fn execute_with_ctx(
+        &self,
+        args: serde_json::Value,
+        overrides: &SandboxOverrides,
+    ) -> anyhow::Result<ToolResult> {
+        let Some(scoped) = overrides.scoped_fs(&self.security) else {
+            return self.execute(args).await;
+        };
+        let scoped_tool = Self {
+            security: scoped.security,
+            base_dir: scoped.base_dir,
+        };
+        scoped_tool.execute(args).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    fn test_security(workspace: PathBuf) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace,
+            ..SecurityPolicy::default()
+        })
+    }
+
+    fn sample_tree() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("src/tools/nested")).unwrap();
+        std::fs::create_dir_all(root.join(".git/objects")).unwrap();
+        std::fs::write(root.join("README.md"), "readme").unwrap();
+        std::fs::write(root.join(".env"), "SECRET=1").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(root.join("src/tools/mod.rs"), "").unwrap();
+        std::fs::write(root.join("src/tools/nested/deep.rs"), "").unwrap();
+        std::fs::write(root.join(".git/HEAD"), "ref").unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn lists_single_level_by_default() {
+        let dir = sample_tree();
+        let tool = ListDirTool::new(test_security(dir.path().to_path_buf()));
+        let result = tool.execute(json!({})).await.unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        let lines: Vec<&str> = result.output.lines().collect();
+        assert_eq!(lines[..2], ["README.md", "src/"]);
+        assert!(!result.output.contains("main.rs"));
+    }
+
+    #[tokio::test]
+    async fn recursive_listing_respects_max_depth() {
+        let dir = sample_tree();
+        let tool = ListDirTool::new(test_security(dir.path().to_path_buf()));
+
+        let result = tool
+            .execute(json!({"path": "src", "recursive": true, "max_depth": 2}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("src/main.rs"));
+        assert!(result.output.contains("src/tools/mod.rs"));
+        assert!(result.output.contains("src/tools/nested/"));
+        assert!(!result.output.contains("deep.rs"));
+
+        let result = tool
+            .execute(json!({"path": "src", "recursive": true}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("src/tools/nested/deep.rs"));
+    }
+
+    #[tokio::test]
+    async fn glob_pattern_filters_entries() {
+        let dir = sample_tree();
+        let tool = ListDirTool::new(test_security(dir.path().to_path_buf()));
+
+        let result = tool
+            .execute(json!({"recursive": true, "pattern": "**/*.rs"}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("src/main.rs"));
+        assert!(result.output.contains("src/tools/nested/deep.rs"));
+        assert!(!result.output.contains("README.md"));
+        assert!(!result.output.contains("src/\n"));
+
+        let result = tool
+            .execute(json!({"recursive": true, "pattern": "*.md"}))
+            .await
+            .unwrap();
+        assert!(result.output.starts_with("README.md\n"));
+        assert!(result.output.contains("Total: 1 entries"));
+
+        let result = tool.execute(json!({"pattern": "[bad"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Invalid glob pattern"));
+    }
+
+    #[tokio::test]
+    async fn caps_entries_with_truncation_notice() {
+        let dir = TempDir::new().unwrap();
+        for i in 0..MAX_ENTRIES + 20 {
+            std::fs::write(dir.path().join(format!("f{i:04}.txt")), "").unwrap();
+        }
+        let tool = ListDirTool::new(test_security(dir.path().to_path_buf()));
+
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(result.output.contains("[Results truncated"));
+        assert!(result
+            .output
+            .contains(&format!("Total: {MAX_ENTRIES} entries")));
+
+        let result = tool
+            .execute(json!({"include_metadata": true}))
+            .await
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(parsed["entries"].as_array().unwrap().len(), MAX_ENTRIES);
+        assert_eq!(parsed["truncated"], true);
+    }
+
+    #[tokio::test]
+    async fn hidden_entries_are_skipped_unless_requested() {
+        let dir = sample_tree();
+        let tool = ListDirTool::new(test_security(dir.path().to_path_buf()));
+
+        let result = tool.execute(json!({"recursive": true})).await.unwrap();
+        assert!(!result.output.contains(".git"));
+        assert!(!result.output.contains(".env"));
+
+        let result = tool
+            .execute(json!({"recursive": true, "include_hidden": true}))
+            .await
+            .unwrap();
+        assert!(result.output.contains(".git/"));
+        assert!(result.output.contains(".git/HEAD"));
+        assert!(result.output.contains(".env"));
+    }
+
+    #[tokio::test]
+    async fn metadata_reports_type_and_size() {
+        let dir = sample_tree();
+        let tool = ListDirTool::new(test_security(dir.path().to_path_buf()));
+
+        let result = tool
+            .execute(json!({"include_metadata": true}))
+            .await
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        let entries = parsed["entries"].as_array().unwrap();
+        let readme = entries.iter().find(|e| e["path"] == "README.md").unwrap();
+        assert_eq!(readme["type"], "file");
+        assert_eq!(readme["size"], 6);
+        assert!(readme["modified"].as_str().unwrap().ends_with('Z'));
+        let src = entries.iter().find(|e| e["path"] == "src").unwrap();
+        assert_eq!(src["type"], "dir");
+        assert_eq!(parsed["truncated"], false);
+    }
+
+    #[tokio::test]
+    async fn rejects_paths_outside_workspace() {
+        let dir = sample_tree();
+        let tool = ListDirTool::new(test_security(dir.path().to_path_buf()));
+
+        let result = tool.execute(json!({"path": "../"})).await.unwrap();
+        assert!(!result.success);
+        let result = tool.execute(json!({"path": "/etc"})).await.unwrap();
+        assert!(!result.success);
+    }
+}
@@ -0,0 +1,246 @@
+use super::traits::{Tool, ToolResult};
+use crate::memory::notes::MemoryNotes;
+use crate::security::policy::ToolOperation;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Structured edits to the workspace `MEMORY.md` — safer than rewriting the
+/// file with `file_write`.
+pub struct MemoryNotesTool {
+    notes: MemoryNotes,
+    security: Arc<SecurityPolicy>,
+}
+
+impl MemoryNotesTool {
+    pub fn new(security: Arc<SecurityPolicy>, workspace_dir: PathBuf) -> Self {
+        Self {
+            notes: MemoryNotes::new(&workspace_dir),
+            security,
+        }
+    }
+}
+
+fn required<'a>(args: &'a serde_json::Value, name: &str) -> anyhow::Result<&'a str> {
+    args.get(name)
+        .and_then(serde_json::Value::as_str)
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Missing '{name}' parameter"))
+}
+
+fn ok(output: String) -> ToolResult {
+    ToolResult {
+        success: true,
+        output,
+        error: None,
+    }
+}
+
+#[async_trait]
+impl Tool for MemoryNotesTool {
+    fn name(&self) -> &str {
+        "memory_notes"
+    }
+
+    fn description(&self) -> &str {
+        "Read and update MEMORY.md without rewriting it. Operations: append_note (add a bullet under a section), read (whole file or one section), search (matching lines), set_fact (set a key/value fact in the Facts list). Use this instead of file_write for MEMORY.md."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": ["append_note", "read", "search", "set_fact"]
+                },
+                "section": {
+                    "type": "string",
+                    "description": "Section heading for append_note (required) or read (optional)"
+                },
+                "text": {
+                    "type": "string",
+                    "description": "Note text for append_note"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Case-insensitive search text"
+                },
+                "key": {
+                    "type": "string",
+                    "description": "Fact name for set_fact (e.g. 'timezone')"
+                },
+                "value": {
+                    "type": "string",
+                    "description": "Fact value for set_fact"
+                }
+            },
+            "required": ["operation"],
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let operation = required(&args, "operation")?;
+
+        if matches!(operation, "append_note" | "set_fact") {
+            if let Err(error) = self
+                .security
+                .enforce_tool_operation(ToolOperation::Act, "memory_notes")
+            {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(error),
+                });
+            }
+        }
+
+        match operation {
+            "append_note" => {
+                let section = required(&args, "section")?;
+                let text = required(&args, "text")?;
+                self.notes.append_note(section, text).await?;
+                Ok(ok(format!("Added note under '{section}'")))
+            }
+            "set_fact" => {
+                let key = required(&args, "key")?;
+                let value = required(&args, "value")?;
+                let output = match self.notes.set_fact(key, value).await? {
+                    Some(previous) => format!("Updated {key}: {previous} → {value}"),
+                    None => format!("Set {key}: {value}"),
+                };
+                Ok(ok(output))
+            }
+            "read" => {
+                let section = args
+                    .get("section")
+                    .and_then(serde_json::Value::as_str)
+                    .filter(|s| !s.trim().is_empty());
+                Ok(match self.notes.read(section).await? {
+                    Some(body) if !body.is_empty() => ok(body),
+                    _ => ok(match section {
+                        Some(name) => format!("No section '{name}' in MEMORY.md"),
+                        None => "MEMORY.md is empty".to_string(),
+                    }),
+                })
+            }
+            "search" => {
+                let query = required(&args, "query")?;
+                let hits = self.notes.search(query).await?;
+                if hits.is_empty() {
+                    return Ok(ok(format!("No notes match '{query}'")));
+                }
+                let mut output = String::new();
+                for hit in hits {
+                    let _ = writeln!(
+                        output,
+                        "[{}] {}",
+                        hit.section.as_deref().unwrap_or("top"),
+                        hit.line
+                    );
+                }
+                Ok(ok(output))
+            }
+            other => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Unknown operation: {other}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    fn tool(tmp: &TempDir, autonomy: AutonomyLevel) -> MemoryNotesTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy,
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        MemoryNotesTool::new(security, tmp.path().to_path_buf())
+    }
+
+    #[tokio::test]
+    async fn append_read_search_and_set_fact_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join("MEMORY.md"),
+            "# Memory\n\nHand-written intro.\n",
+        )
+        .unwrap();
+        let t = tool(&tmp, AutonomyLevel::Supervised);
+
+        let r = t
+            .execute(
+                json!({"operation": "append_note", "section": "People", "text": "Ana likes tea"}),
+            )
+            .await
+            .unwrap();
+        assert!(r.success, "{r:?}");
+        let r = t
+            .execute(json!({"operation": "set_fact", "key": "timezone", "value": "UTC"}))
+            .await
+            .unwrap();
+        assert_eq!(r.output, "Set timezone: UTC");
+        let r = t
+            .execute(json!({"operation": "set_fact", "key": "timezone", "value": "CET"}))
+            .await
+            .unwrap();
+        assert_eq!(r.output, "Updated timezone: UTC → CET");
+
+        let r = t
+            .execute(json!({"operation": "read", "section": "people"}))
+            .await
+            .unwrap();
+        assert_eq!(r.output, "- Ana likes tea");
+
+        let r = t
+            .execute(json!({"operation": "search", "query": "tea"}))
+            .await
+            .unwrap();
+        assert_eq!(r.output.trim(), "[People] - Ana likes tea");
+
+        let file = std::fs::read_to_string(tmp.path().join("MEMORY.md")).unwrap();
+        assert!(file.contains("Hand-written intro."));
+        assert!(file.contains("- timezone: CET"));
+    }
+
+    #[tokio::test]
+    async fn writes_blocked_in_read_only_mode_but_reads_allowed() {
+        let tmp = TempDir::new().unwrap();
+        let t = tool(&tmp, AutonomyLevel::ReadOnly);
+
+        let r = t
+            .execute(json!({"operation": "set_fact", "key": "k", "value": "v"}))
+            .await
+            .unwrap();
+        assert!(!r.success);
+        assert!(!tmp.path().join("MEMORY.md").exists());
+
+        let r = t.execute(json!({"operation": "read"})).await.unwrap();
+        assert!(r.success);
+        assert_eq!(r.output, "MEMORY.md is empty");
+    }
+
+    #[tokio::test]
+    async fn missing_parameters_are_errors() {
+        let tmp = TempDir::new().unwrap();
+        let t = tool(&tmp, AutonomyLevel::Supervised);
+        assert!(t
+            .execute(json!({"operation": "append_note", "section": "x"}))
+            .await
+            .is_err());
+        let r = t.execute(json!({"operation": "nope"})).await.unwrap();
+        assert!(!r.success);
+    }
+}
@@ -0,0 +1,226 @@
+use super::traits::{Tool, ToolResult};
+use crate::memory::embeddings::EmbeddingProvider;
+use crate::sessions::{semantic, store_for, visible_session, SqliteSessionStore};
+use crate::util::truncate_with_ellipsis;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const SNIPPET_CHARS: usize = 200;
+const MAX_LIMIT: usize = 20;
+
+/// Let the agent search past channel conversations by meaning, using the
+/// session embedding index. Registered only when an embedding model is
+/// configured.
+pub struct MemorySearchTool {
+    workspace_dir: PathBuf,
+    embedder: Arc<dyn EmbeddingProvider>,
+    model: String,
+    cross_session: bool,
+}
+
+impl MemorySearchTool {
+    pub fn new(
+        workspace_dir: PathBuf,
+        embedder: Arc<dyn EmbeddingProvider>,
+        model: String,
+    ) -> Self {
+        Self {
+            workspace_dir,
+            embedder,
+            model,
+            cross_session: false,
+        }
+    }
+
+    /// Search every stored session instead of only the current one.
+    pub fn with_cross_session(mut self, cross_session: bool) -> Self {
+        self.cross_session = cross_session;
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for MemorySearchTool {
+    fn name(&self) -> &str {
+        "memory_search"
+    }
+
+    fn description(&self) -> &str {
+        "Semantic search over past messages of this conversation (every channel conversation when cross-session search is enabled): finds messages that mean the same as the query even when they use other words (e.g. 'which hosting provider did we pick' finds 'we'll go with Hetzner'). Returns the closest messages with session, time and a snippet. Use sessions_search for exact words."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "What to look for, as a question or description"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max results to return (default: 5, max: 20)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
+
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(5, |v| (v as usize).clamp(1, MAX_LIMIT));
+
+        if !SqliteSessionStore::db_path(&self.workspace_dir).exists() {
+            return Ok(ToolResult {
+                success: true,
+                output: "No past conversations have been recorded yet.".into(),
+                error: None,
+            });
+        }
+        let store = match store_for(&self.workspace_dir) {
+            Some(store) => store,
+            None => Arc::new(SqliteSessionStore::open(&self.workspace_dir)?),
+        };
+
+        let scope = visible_session(self.cross_session);
+        let hits = semantic::search(
+            &store,
+            self.embedder.as_ref(),
+            &self.model,
+            query,
+            scope.as_deref(),
+            limit,
+        )
+        .await;
+        match hits {
+            Ok(hits) if hits.is_empty() => Ok(ToolResult {
+                success: true,
+                output: "No past conversations matched that query (recent messages may not be indexed yet).".into(),
+                error: None,
+            }),
+            Ok(hits) => {
+                let mut output = format!("Found {} related messages:\n", hits.len());
+                for hit in &hits {
+                    let title = hit.title.as_deref().unwrap_or("untitled");
+                    let _ = writeln!(
+                        output,
+                        "- [{}] {} ({title}) {} (similarity {:.2}): {}",
+                        hit.created_at,
+                        hit.key,
+                        hit.role,
+                        hit.score,
+                        truncate_with_ellipsis(&hit.content, SNIPPET_CHARS)
+                    );
+                }
+                Ok(ToolResult {
+                    success: true,
+                    output,
+                    error: None,
+                })
+            }
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Semantic search failed: {e}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// One dimension per keyword, so ranking is predictable.
+    struct KeywordEmbedder;
+
+    #[async_trait]
+    impl EmbeddingProvider for KeywordEmbedder {
+        fn name(&self) -> &str {
+            "keywords"
+        }
+
+        fn dimensions(&self) -> usize {
+            2
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|t| {
+                    let t = t.to_lowercase();
+                    vec![
+                        f32::from(u8::from(t.contains("hetzner") || t.contains("hosting"))),
+                        f32::from(u8::from(t.contains("dentist"))),
+                    ]
+                })
+                .collect())
+        }
+    }
+
+    fn tool(tmp: &TempDir) -> MemorySearchTool {
+        MemorySearchTool::new(
+            tmp.path().to_path_buf(),
+            Arc::new(KeywordEmbedder),
+            "kw".into(),
+        )
+    }
+
+    #[tokio::test]
+    async fn returns_indexed_messages_with_session_and_snippet() {
+        let tmp = TempDir::new().unwrap();
+        let store = Arc::new(SqliteSessionStore::open(tmp.path()).unwrap());
+        store
+            .append_message(
+                "telegram_alice",
+                "user",
+                "Let's go with Hetzner, it is cheaper.",
+            )
+            .unwrap();
+        store
+            .append_message(
+                "telegram_alice",
+                "user",
+                "Remind me about the dentist on Monday.",
+            )
+            .unwrap();
+        semantic::index_pending(&store, &KeywordEmbedder, "kw", 8)
+            .await
+            .unwrap();
+
+        let result = tool(&tmp)
+            .execute(json!({"query": "what hosting did we decide on?"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("Found 1 related"));
+        assert!(result.output.contains("telegram_alice"));
+        assert!(result.output.contains("Hetzner"));
+        assert!(!result.output.contains("dentist"));
+    }
+
+    #[tokio::test]
+    async fn reports_empty_without_database() {
+        let tmp = TempDir::new().unwrap();
+        let result = tool(&tmp)
+            .execute(json!({"query": "anything"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("No past conversations"));
+    }
+}
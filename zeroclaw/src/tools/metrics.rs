@@ -0,0 +1,113 @@
+//! Process-wide per-tool call metrics.
+//!
+//! [`super::registry::execute_tool`] records every call to a registered tool
+//! here, so the gateway (`/api/monitor/metrics`) and the `tool_metrics` tool
+//! can report which tools are slow or failing without an observer backend.
+
+use chrono::Utc;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// Longest `last_error` kept per tool; tool errors can embed whole outputs.
+const MAX_ERROR_CHARS: usize = 500;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ToolMetrics {
+    pub execution_count: u64,
+    pub success_count: u64,
+    pub failure_count: u64,
+    pub total_duration_ms: u64,
+    pub avg_duration_ms: f64,
+    pub last_error: Option<String>,
+    /// RFC 3339 timestamp of the most recent call.
+    pub last_used_at: Option<String>,
+}
+
+impl ToolMetrics {
+    fn record(&mut self, duration: Duration, error: Option<&str>) {
+        let millis = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        self.execution_count = self.execution_count.saturating_add(1);
+        self.total_duration_ms = self.total_duration_ms.saturating_add(millis);
+        #[allow(clippy::cast_precision_loss)]
+        {
+            self.avg_duration_ms = self.total_duration_ms as f64 / self.execution_count as f64;
+        }
+        match error {
+            None => self.success_count = self.success_count.saturating_add(1),
+            Some(reason) => {
+                self.failure_count = self.failure_count.saturating_add(1);
+                self.last_error = Some(reason.chars().take(MAX_ERROR_CHARS).collect());
+            }
+        }
+        self.last_used_at = Some(Utc::now().to_rfc3339());
+    }
+}
+
+static METRICS: OnceLock<Mutex<BTreeMap<String, ToolMetrics>>> = OnceLock::new();
+
+fn metrics() -> &'static Mutex<BTreeMap<String, ToolMetrics>> {
+    METRICS.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+/// Record one call to `tool`. `error` is `None` on success.
+pub fn record(tool: &str, duration: Duration, error: Option<&str>) {
+    metrics()
+        .lock()
+        .entry(tool.to_string())
+        .or_default()
+        .record(duration, error);
+}
+
+/// Metrics for every tool called since startup, keyed by tool name.
+pub fn snapshot() -> BTreeMap<String, ToolMetrics> {
+    metrics().lock().clone()
+}
+
+pub fn snapshot_json() -> serde_json::Value {
+    serde_json::to_value(snapshot()).unwrap_or_else(|_| serde_json::json!({}))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unique_tool(prefix: &str) -> String {
+        format!("{prefix}-{}", uuid::Uuid::new_v4())
+    }
+
+    #[test]
+    fn record_accumulates_counts_and_average() {
+        let tool = unique_tool("metrics-avg");
+        record(&tool, Duration::from_millis(10), None);
+        record(&tool, Duration::from_millis(30), Some("boom"));
+
+        let m = snapshot().remove(&tool).unwrap();
+        assert_eq!(m.execution_count, 2);
+        assert_eq!(m.success_count, 1);
+        assert_eq!(m.failure_count, 1);
+        assert_eq!(m.total_duration_ms, 40);
+        assert!((m.avg_duration_ms - 20.0).abs() < f64::EPSILON);
+        assert_eq!(m.last_error.as_deref(), Some("boom"));
+        assert!(m.last_used_at.is_some());
+    }
+
+    #[test]
+    fn success_keeps_previous_error() {
+        let tool = unique_tool("metrics-sticky");
+        record(&tool, Duration::ZERO, Some("first failure"));
+        record(&tool, Duration::ZERO, None);
+        let m = snapshot().remove(&tool).unwrap();
+        assert_eq!(m.last_error.as_deref(), Some("first failure"));
+    }
+
+    #[test]
+    fn long_errors_are_truncated() {
+        let tool = unique_tool("metrics-long");
+        record(&tool, Duration::ZERO, Some(&"x".repeat(2000)));
+        let m = snapshot().remove(&tool).unwrap();
+        assert_eq!(m.last_error.unwrap().chars().count(), MAX_ERROR_CHARS);
+    }
+}
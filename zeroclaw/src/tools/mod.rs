@@ -15,10 +15,12 @@
 //! To add a new tool, implement [`Tool`] in a new submodule and register it in
 //! [`all_tools_with_runtime`]. See `AGENTS.md` §7.3 for the full change playbook.
 
+pub mod artifacts;
 pub mod browser;
 pub mod browser_open;
 pub mod cli_discovery;
 pub mod composio;
+pub mod contacts_lookup;
 pub mod content_search;
 pub mod cron_add;
 pub mod cron_list;
@@ -26,10 +28,13 @@ pub mod cron_remove;
 pub mod cron_run;
 pub mod cron_runs;
 pub mod cron_update;
+pub mod datetime_now;
 pub mod delegate;
+pub mod edit_reply;
 pub mod file_edit;
 pub mod file_read;
 pub mod file_write;
+pub mod forget_message;
 pub mod git_operations;
 pub mod glob_search;
 #[cfg(feature = "hardware")]
@@ -40,17 +45,36 @@ pub mod hardware_memory_map;
 pub mod hardware_memory_read;
 pub mod http_request;
 pub mod image_info;
+pub mod link_sessions;
+pub mod list_dir;
 pub mod memory_forget;
+pub mod memory_notes;
 pub mod memory_recall;
+pub mod memory_search;
 pub mod memory_store;
+pub mod metrics;
 pub mod model_routing_config;
 pub mod pdf_read;
+pub mod pin_message;
 pub mod proxy_config;
 pub mod pushover;
+pub mod read_attachment;
+pub mod registry;
+pub mod run_code;
+pub mod run_diagnostics;
 pub mod schedule;
+pub mod schedule_followup;
 pub mod schema;
 pub mod screenshot;
+pub mod session_recall;
+pub mod session_settings;
+pub mod sessions_history;
+pub mod sessions_list;
+pub mod sessions_search;
+pub mod sheets_memory;
 pub mod shell;
+pub mod spawn_subtask;
+pub mod tool_metrics;
 pub mod traits;
 pub mod web_fetch;
 pub mod web_search_tool;
@@ -58,6 +82,7 @@ pub mod web_search_tool;
 pub use browser::{BrowserTool, ComputerUseConfig};
 pub use browser_open::BrowserOpenTool;
 pub use composio::ComposioTool;
+pub use contacts_lookup::ContactsLookupTool;
 pub use content_search::ContentSearchTool;
 pub use cron_add::CronAddTool;
 pub use cron_list::CronListTool;
@@ -65,10 +90,13 @@ pub use cron_remove::CronRemoveTool;
 pub use cron_run::CronRunTool;
 pub use cron_runs::CronRunsTool;
 pub use cron_update::CronUpdateTool;
+pub use datetime_now::DatetimeNowTool;
 pub use delegate::DelegateTool;
+pub use edit_reply::EditReplyTool;
 pub use file_edit::FileEditTool;
 pub use file_read::FileReadTool;
 pub use file_write::FileWriteTool;
+pub use forget_message::ForgetMessageTool;
 pub use git_operations::GitOperationsTool;
 pub use glob_search::GlobSearchTool;
 #[cfg(feature = "hardware")]
@@ -79,21 +107,39 @@ pub use hardware_memory_map::HardwareMemoryMapTool;
 pub use hardware_memory_read::HardwareMemoryReadTool;
 pub use http_request::HttpRequestTool;
 pub use image_info::ImageInfoTool;
+pub use link_sessions::LinkSessionsTool;
+pub use list_dir::ListDirTool;
 pub use memory_forget::MemoryForgetTool;
+pub use memory_notes::MemoryNotesTool;
 pub use memory_recall::MemoryRecallTool;
+pub use memory_search::MemorySearchTool;
 pub use memory_store::MemoryStoreTool;
 pub use model_routing_config::ModelRoutingConfigTool;
 pub use pdf_read::PdfReadTool;
+pub use pin_message::{PinMessageTool, UnpinMessageTool};
 pub use proxy_config::ProxyConfigTool;
 pub use pushover::PushoverTool;
+pub use read_attachment::ReadAttachmentTool;
+pub use registry::execute_tool;
+pub use run_code::RunCodeTool;
+pub use run_diagnostics::RunDiagnosticsTool;
 pub use schedule::ScheduleTool;
+pub use schedule_followup::ScheduleFollowupTool;
 #[allow(unused_imports)]
 pub use schema::{CleaningStrategy, SchemaCleanr};
 pub use screenshot::ScreenshotTool;
+pub use session_recall::SessionRecallTool;
+pub use session_settings::SessionSettingsTool;
+pub use sessions_history::SessionsHistoryTool;
+pub use sessions_list::SessionsListTool;
+pub use sessions_search::SessionsSearchTool;
+pub use sheets_memory::SheetsMemoryTool;
 pub use shell::ShellTool;
+pub use spawn_subtask::SpawnSubtaskTool;
+pub use tool_metrics::ToolMetricsTool;
 pub use traits::Tool;
 #[allow(unused_imports)]
-pub use traits::{ToolResult, ToolSpec};
+pub use traits::{SandboxOverrides, ToolResult, ToolSpec};
 pub use web_fetch::WebFetchTool;
 pub use web_search_tool::WebSearchTool;
 
@@ -105,14 +151,48 @@ use async_trait::async_trait;
 use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Argument naming the skill a call follows, on tools that skills grant.
+const SKILL_ARG: &str = "skill";
+
 #[derive(Clone)]
 struct ArcDelegatingTool {
     inner: Arc<dyn Tool>,
+    /// Overrides of every skill granting this tool. A call gets one only
+    /// when it names that skill in its `skill` argument and the skill is
+    /// approved for the conversation; other calls keep the base policy.
+    grants: Vec<SandboxOverrides>,
+    /// Config directory holding the skill approvals.
+    approvals_dir: std::path::PathBuf,
 }
 
 impl ArcDelegatingTool {
-    fn boxed(inner: Arc<dyn Tool>) -> Box<dyn Tool> {
-        Box::new(Self { inner })
+    fn boxed(
+        inner: Arc<dyn Tool>,
+        grants: Vec<SandboxOverrides>,
+        approvals_dir: &std::path::Path,
+    ) -> Box<dyn Tool> {
+        Box::new(Self {
+            inner,
+            grants,
+            approvals_dir: approvals_dir.to_path_buf(),
+        })
+    }
+
+    /// Whether the granting skill is approved for the current conversation
+    /// (its channel and sender; global approvals only outside one).
+    fn grant_approved(&self, overrides: &SandboxOverrides) -> bool {
+        let session = crate::sessions::current_session();
+        let channel = session.as_ref().map(|s| s.channel.as_str());
+        let sender = session
+            .as_ref()
+            .map(|s| s.sender.as_str())
+            .filter(|s| !s.is_empty());
+        crate::skills::approvals::permissions_apply(
+            &self.approvals_dir,
+            &overrides.skill,
+            channel,
+            sender,
+        )
     }
 }
 
@@ -127,16 +207,81 @@ impl Tool for ArcDelegatingTool {
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
-        self.inner.parameters_schema()
+        let mut schema = self.inner.parameters_schema();
+        if self.grants.is_empty() {
+            return schema;
+        }
+        let skills: Vec<&str> = self.grants.iter().map(|g| g.skill.as_str()).collect();
+        if let Some(object) = schema.as_object_mut() {
+            let properties = object
+                .entry("properties")
+                .or_insert_with(|| serde_json::json!({}));
+            if let Some(properties) = properties.as_object_mut() {
+                properties.insert(
+                    SKILL_ARG.into(),
+                    serde_json::json!({
+                        "type": "string",
+                        "enum": skills,
+                        "description": "Skill whose instructions this call follows; the call then runs with that skill's sandbox permissions. Omit otherwise."
+                    }),
+                );
+            }
+        }
+        schema
+    }
+
+    async fn execute(&self, mut args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        if self.grants.is_empty() {
+            return self.inner.execute(args).await;
+        }
+        let Some(named) = args
+            .as_object_mut()
+            .and_then(|object| object.remove(SKILL_ARG))
+        else {
+            return self.inner.execute(args).await;
+        };
+        let named = named.as_str().unwrap_or_default();
+        let Some(overrides) = self.grants.iter().find(|grant| grant.skill == named) else {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!(
+                    "Skill '{named}' does not grant the {} tool",
+                    self.name()
+                )),
+            });
+        };
+        if self.grant_approved(overrides) {
+            self.inner.execute_with_ctx(args, overrides).await
+        } else {
+            self.inner.execute(args).await
+        }
     }
 
-    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
-        self.inner.execute(args).await
+    async fn execute_with_ctx(
+        &self,
+        args: serde_json::Value,
+        overrides: &SandboxOverrides,
+    ) -> anyhow::Result<ToolResult> {
+        self.inner.execute_with_ctx(args, overrides).await
     }
 }
 
-fn boxed_registry_from_arcs(tools: Vec<Arc<dyn Tool>>) -> Vec<Box<dyn Tool>> {
-    tools.into_iter().map(ArcDelegatingTool::boxed).collect()
+/// Box the registry, giving each tool granted by skills those skills'
+/// sandbox overrides, applied per call to the skill the call names (where
+/// that skill is approved in `approvals_dir`).
+fn boxed_registry_from_arcs(
+    tools: Vec<Arc<dyn Tool>>,
+    grants: &HashMap<String, Vec<SandboxOverrides>>,
+    approvals_dir: &std::path::Path,
+) -> Vec<Box<dyn Tool>> {
+    tools
+        .into_iter()
+        .map(|tool| {
+            let grants = grants.get(tool.name()).cloned().unwrap_or_default();
+            ArcDelegatingTool::boxed(tool, grants, approvals_dir)
+        })
+        .collect()
 }
 
 /// Create the default tool registry
@@ -209,12 +354,37 @@ pub fn all_tools_with_runtime(
     fallback_api_key: Option<&str>,
     root_config: &crate::config::Config,
 ) -> Vec<Box<dyn Tool>> {
+    let audit = root_config.config_path.parent().and_then(|zeroclaw_dir| {
+        crate::security::AuditLogger::new(
+            root_config.security.audit.clone(),
+            zeroclaw_dir.to_path_buf(),
+        )
+        .ok()
+        .map(Arc::new)
+    });
     let mut tool_arcs: Vec<Arc<dyn Tool>> = vec![
-        Arc::new(ShellTool::new(security.clone(), runtime)),
+        Arc::new(
+            ShellTool::new(security.clone(), runtime.clone())
+                .with_command_filter(
+                    Arc::new(crate::security::CommandFilter::from_config(
+                        &root_config.security.sandbox,
+                    )),
+                    audit.clone(),
+                )
+                .with_artifacts(artifacts::Artifacts::for_tool(
+                    workspace_dir,
+                    &root_config.security.sandbox,
+                    "shell",
+                )),
+        ),
         Arc::new(FileReadTool::new(security.clone())),
-        Arc::new(FileWriteTool::new(security.clone())),
+        Arc::new(
+            FileWriteTool::new(security.clone())
+                .with_auto_commit(root_config.security.sandbox.auto_commit_writes),
+        ),
         Arc::new(FileEditTool::new(security.clone())),
         Arc::new(GlobSearchTool::new(security.clone())),
+        Arc::new(ListDirTool::new(security.clone())),
         Arc::new(ContentSearchTool::new(security.clone())),
         Arc::new(CronAddTool::new(config.clone(), security.clone())),
         Arc::new(CronListTool::new(config.clone())),
@@ -225,7 +395,60 @@ pub fn all_tools_with_runtime(
         Arc::new(MemoryStoreTool::new(memory.clone(), security.clone())),
         Arc::new(MemoryRecallTool::new(memory.clone())),
         Arc::new(MemoryForgetTool::new(memory, security.clone())),
+        Arc::new(MemoryNotesTool::new(
+            security.clone(),
+            workspace_dir.to_path_buf(),
+        )),
+        Arc::new(
+            SessionsSearchTool::new(workspace_dir.to_path_buf())
+                .with_cross_session(root_config.memory.cross_session_search),
+        ),
+        Arc::new(
+            SessionsListTool::new(workspace_dir.to_path_buf())
+                .with_cross_session(root_config.memory.cross_session_search),
+        ),
+        Arc::new(
+            SessionsHistoryTool::new(workspace_dir.to_path_buf())
+                .with_cross_session(root_config.memory.cross_session_search),
+        ),
+        Arc::new(SessionRecallTool::new(workspace_dir.to_path_buf())),
+        Arc::new(ContactsLookupTool::new(workspace_dir.to_path_buf())),
+        Arc::new(SessionSettingsTool::new(
+            security.clone(),
+            root_config.clone(),
+        )),
+        Arc::new(RunDiagnosticsTool::new(root_config.clone())),
+        Arc::new(PinMessageTool::new(
+            security.clone(),
+            workspace_dir.to_path_buf(),
+        )),
+        Arc::new(UnpinMessageTool::new(
+            security.clone(),
+            workspace_dir.to_path_buf(),
+        )),
+        Arc::new(LinkSessionsTool::new(
+            security.clone(),
+            workspace_dir.to_path_buf(),
+        )),
+        Arc::new(ForgetMessageTool::new(
+            security.clone(),
+            workspace_dir.to_path_buf(),
+        )),
+        Arc::new(EditReplyTool::new(
+            security.clone(),
+            workspace_dir.to_path_buf(),
+        )),
+        Arc::new(ReadAttachmentTool::new(
+            security.clone(),
+            workspace_dir,
+            root_config.gateway.attachment_retention_hours,
+        )),
         Arc::new(ScheduleTool::new(security.clone(), root_config.clone())),
+        Arc::new(ScheduleFollowupTool::new(
+            security.clone(),
+            root_config.clone(),
+        )),
+        Arc::new(DatetimeNowTool::new(root_config.timezone.clone())),
         Arc::new(ModelRoutingConfigTool::new(
             config.clone(),
             security.clone(),
@@ -278,30 +501,66 @@ pub fn all_tools_with_runtime(
     }
 
     if web_fetch_config.enabled {
-        tool_arcs.push(Arc::new(WebFetchTool::new(
-            security.clone(),
-            web_fetch_config.allowed_domains.clone(),
-            web_fetch_config.blocked_domains.clone(),
-            web_fetch_config.max_response_size,
-            web_fetch_config.timeout_secs,
-        )));
+        tool_arcs.push(Arc::new(
+            WebFetchTool::new(
+                security.clone(),
+                web_fetch_config.allowed_domains.clone(),
+                web_fetch_config.blocked_domains.clone(),
+                web_fetch_config.max_response_size,
+                web_fetch_config.timeout_secs,
+            )
+            .with_artifacts(artifacts::Artifacts::for_tool(
+                workspace_dir,
+                &root_config.security.sandbox,
+                "web_fetch",
+            )),
+        ));
     }
 
     // Web search tool (enabled by default for GLM and other models)
     if root_config.web_search.enabled {
-        tool_arcs.push(Arc::new(WebSearchTool::new(
-            root_config.web_search.provider.clone(),
-            root_config.web_search.brave_api_key.clone(),
-            root_config.web_search.max_results,
-            root_config.web_search.timeout_secs,
+        tool_arcs.push(Arc::new(
+            WebSearchTool::new(
+                root_config.web_search.provider.clone(),
+                root_config.web_search.brave_api_key.clone(),
+                root_config.web_search.max_results,
+                root_config.web_search.timeout_secs,
+            )
+            .with_max_requests_per_minute(root_config.web_search.max_requests_per_minute),
+        ));
+    }
+
+    // Semantic search over past conversations needs an embedding model.
+    if let Some((embedder, model)) = crate::memory::configured_embedder(
+        &root_config.memory,
+        &root_config.embedding_routes,
+        root_config.api_key.as_deref(),
+    ) {
+        tool_arcs.push(Arc::new(
+            MemorySearchTool::new(workspace_dir.to_path_buf(), embedder, model)
+                .with_cross_session(root_config.memory.cross_session_search),
+        ));
+    }
+
+    if root_config.security.sandbox.code_enabled {
+        tool_arcs.push(Arc::new(RunCodeTool::new(
+            security.clone(),
+            runtime.clone(),
+            &root_config.security.sandbox,
         )));
     }
 
     // PDF extraction (feature-gated at compile time via rag-pdf)
     tool_arcs.push(Arc::new(PdfReadTool::new(security.clone())));
 
+    tool_arcs.push(Arc::new(ToolMetricsTool));
+
     // Vision tools are always available
-    tool_arcs.push(Arc::new(ScreenshotTool::new(security.clone())));
+    tool_arcs.push(Arc::new(ScreenshotTool::new(
+        security.clone(),
+        &root_config.multimodal,
+        root_config.gateway.attachment_retention_hours,
+    )));
     tool_arcs.push(Arc::new(ImageInfoTool::new(security.clone())));
 
     if let Some(key) = composio_key {
@@ -314,6 +573,30 @@ pub fn all_tools_with_runtime(
         }
     }
 
+    if let Some(sheets) = &root_config.google_sheets {
+        match crate::memory::sheets::SheetsClient::from_config(sheets) {
+            Ok(client) => tool_arcs.push(Arc::new(SheetsMemoryTool::new(
+                Arc::new(client),
+                security.clone(),
+            ))),
+            Err(e) => tracing::warn!("sheets_memory tool not registered: {e}"),
+        }
+    }
+
+    let provider_runtime_options = crate::providers::ProviderRuntimeOptions {
+        auth_profile_override: None,
+        provider_api_url: root_config.api_url.clone(),
+        zeroclaw_dir: root_config
+            .config_path
+            .parent()
+            .map(std::path::PathBuf::from),
+        secrets_encrypt: root_config.secrets.encrypt,
+        reasoning_enabled: root_config.runtime.reasoning_enabled,
+        openrouter: root_config.openrouter.clone(),
+        response_cache: None,
+    };
+    let parent_tools = Arc::new(tool_arcs.clone());
+
     // Add delegation tool when agents are configured
     if !agents.is_empty() {
         let delegate_agents: HashMap<String, DelegateAgentConfig> = agents
@@ -324,34 +607,44 @@ pub fn all_tools_with_runtime(
             let trimmed_value = value.trim();
             (!trimmed_value.is_empty()).then(|| trimmed_value.to_owned())
         });
-        let parent_tools = Arc::new(tool_arcs.clone());
         let delegate_tool = DelegateTool::new_with_options(
             delegate_agents,
             delegate_fallback_credential,
             security.clone(),
-            crate::providers::ProviderRuntimeOptions {
-                auth_profile_override: None,
-                provider_api_url: root_config.api_url.clone(),
-                zeroclaw_dir: root_config
-                    .config_path
-                    .parent()
-                    .map(std::path::PathBuf::from),
-                secrets_encrypt: root_config.secrets.encrypt,
-                reasoning_enabled: root_config.runtime.reasoning_enabled,
-            },
+            provider_runtime_options.clone(),
         )
-        .with_parent_tools(parent_tools)
+        .with_parent_tools(Arc::clone(&parent_tools))
         .with_multimodal_config(root_config.multimodal.clone());
         tool_arcs.push(Arc::new(delegate_tool));
     }
 
-    boxed_registry_from_arcs(tool_arcs)
+    if root_config.agent.subtasks.enabled {
+        tool_arcs.push(Arc::new(
+            SpawnSubtaskTool::new(
+                security.clone(),
+                root_config.agent.subtasks.clone(),
+                parent_tools,
+                root_config,
+                provider_runtime_options,
+            )
+            .with_audit(audit),
+        ));
+    }
+
+    let skills = crate::skills::load_skills_with_config(workspace_dir, root_config);
+    let grants = crate::skills::sandbox_grants(&skills, &root_config.skills);
+    boxed_registry_from_arcs(
+        tool_arcs,
+        &grants,
+        crate::skills::approvals::ApprovedSkills::dir(root_config),
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::config::{BrowserConfig, Config, MemoryConfig};
+    use crate::skills::approvals::{ApprovedSkills, SkillApproval};
     use tempfile::TempDir;
 
     fn test_config(tmp: &TempDir) -> Config {
@@ -362,6 +655,146 @@ mod tests {
         }
     }
 
+    /// Reports whether it ran with skill overrides.
+    struct GrantProbe;
+
+    #[async_trait]
+    impl Tool for GrantProbe {
+        fn name(&self) -> &str {
+            "shell"
+        }
+
+        fn description(&self) -> &str {
+            "probe"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            Ok(ToolResult {
+                success: true,
+                output: "plain".into(),
+                error: None,
+            })
+        }
+
+        async fn execute_with_ctx(
+            &self,
+            _args: serde_json::Value,
+            overrides: &SandboxOverrides,
+        ) -> anyhow::Result<ToolResult> {
+            Ok(ToolResult {
+                success: true,
+                output: format!("granted by {}", overrides.skill),
+                error: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn skill_grants_apply_only_where_the_skill_is_approved() {
+        let tmp = TempDir::new().unwrap();
+        let grants = HashMap::from([(
+            "shell".to_string(),
+            vec![SandboxOverrides {
+                skill: "shell-admin".into(),
+                ..SandboxOverrides::default()
+            }],
+        )]);
+        let registry = boxed_registry_from_arcs(vec![Arc::new(GrantProbe)], &grants, tmp.path());
+        let run = |channel: &str, sender: &str| {
+            let session = crate::sessions::SessionContext {
+                session_key: format!("{channel}_{sender}"),
+                channel: channel.into(),
+                reply_target: sender.into(),
+                thread_ts: None,
+                sender: sender.into(),
+            };
+            let tool = &registry[0];
+            async move {
+                crate::sessions::with_session(
+                    session,
+                    tool.execute(serde_json::json!({"skill": "shell-admin"})),
+                )
+                .await
+                .unwrap()
+                .output
+            }
+        };
+
+        // No approvals recorded: nothing is widened.
+        assert_eq!(run("slack", "U1").await, "plain");
+
+        let mut approvals = ApprovedSkills::default();
+        approvals.set(SkillApproval {
+            skill: "shell-admin".into(),
+            channel: Some("telegram".into()),
+            sender: Some("alice".into()),
+            approved: true,
+        });
+        approvals.save(tmp.path()).unwrap();
+
+        assert_eq!(run("telegram", "alice").await, "granted by shell-admin");
+        assert_eq!(run("telegram", "bob").await, "plain");
+        assert_eq!(run("slack", "alice").await, "plain");
+        assert_eq!(
+            registry[0]
+                .execute(serde_json::json!({"skill": "shell-admin"}))
+                .await
+                .unwrap()
+                .output,
+            "plain"
+        );
+    }
+
+    #[tokio::test]
+    async fn skill_grants_apply_only_to_calls_naming_the_skill() {
+        let tmp = TempDir::new().unwrap();
+        let grant = |skill: &str| SandboxOverrides {
+            skill: skill.into(),
+            ..SandboxOverrides::default()
+        };
+        let grants = HashMap::from([(
+            "shell".to_string(),
+            vec![grant("shell-admin"), grant("deploy")],
+        )]);
+        let mut approvals = ApprovedSkills::default();
+        for skill in ["shell-admin", "deploy"] {
+            approvals.set(SkillApproval {
+                skill: skill.into(),
+                channel: None,
+                sender: None,
+                approved: true,
+            });
+        }
+        approvals.save(tmp.path()).unwrap();
+        let registry = boxed_registry_from_arcs(vec![Arc::new(GrantProbe)], &grants, tmp.path());
+        let shell = &registry[0];
+
+        let schema = shell.parameters_schema();
+        assert_eq!(
+            schema["properties"]["skill"]["enum"],
+            serde_json::json!(["shell-admin", "deploy"])
+        );
+
+        // A call outside any skill keeps the base policy.
+        let plain = shell.execute(serde_json::json!({"command": "ls"})).await;
+        assert_eq!(plain.unwrap().output, "plain");
+        let deploy = shell.execute(serde_json::json!({"skill": "deploy"})).await;
+        assert_eq!(deploy.unwrap().output, "granted by deploy");
+        let other = shell
+            .execute(serde_json::json!({"skill": "notes"}))
+            .await
+            .unwrap();
+        assert!(!other.success);
+        assert_eq!(
+            other.error.as_deref(),
+            Some("Skill 'notes' does not grant the shell tool")
+        );
+    }
+
     #[test]
     fn default_tools_has_expected_count() {
         let security = Arc::new(SecurityPolicy::default());
@@ -406,6 +839,29 @@ mod tests {
         let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
         assert!(!names.contains(&"browser_open"));
         assert!(names.contains(&"schedule"));
+        assert!(names.contains(&"schedule_followup"));
+        assert!(names.contains(&"datetime_now"));
+        assert!(names.contains(&"sessions_search"));
+        assert!(names.contains(&"sessions_list"));
+        assert!(names.contains(&"sessions_history"));
+        assert!(names.contains(&"session_recall"));
+        // Opt-in through `agent.subtasks.enabled`.
+        assert!(!names.contains(&"spawn_subtask"));
+        assert!(names.contains(&"contacts_lookup"));
+        assert!(names.contains(&"session_settings"));
+        assert!(names.contains(&"run_diagnostics"));
+        // Opt-in through `security.sandbox.code_enabled`.
+        assert!(!names.contains(&"run_code"));
+        assert!(names.contains(&"pin_message"));
+        assert!(names.contains(&"unpin_message"));
+        assert!(names.contains(&"link_sessions"));
+        assert!(names.contains(&"forget"));
+        assert!(names.contains(&"edit_reply"));
+        // No embedding model is configured.
+        assert!(!names.contains(&"memory_search"));
+        assert!(names.contains(&"read_attachment"));
+        assert!(names.contains(&"tool_metrics"));
+        assert!(names.contains(&"memory_notes"));
         assert!(names.contains(&"model_routing_config"));
         assert!(names.contains(&"pushover"));
         assert!(names.contains(&"proxy_config"));
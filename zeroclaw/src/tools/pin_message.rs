@@ -0,0 +1,271 @@
+use super::traits::{Tool, ToolResult};
+use crate::security::SecurityPolicy;
+use crate::sessions::pins::MAX_PINNED_PER_SESSION;
+use crate::sessions::{blocking_writable, current_session};
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Session key of the current channel turn and a mutation go-ahead, or the
+/// failure to return.
+fn pin_session(security: &SecurityPolicy, tool: &str) -> Result<String, ToolResult> {
+    let Some(session) = current_session() else {
+        return Err(ToolResult::failure(format!(
+            "{tool} is only available inside a channel conversation"
+        )));
+    };
+    if !security.can_act() {
+        return Err(ToolResult::failure(
+            "Security policy: read-only mode, cannot change pinned messages",
+        ));
+    }
+    if !security.record_action() {
+        return Err(ToolResult::failure(
+            "Rate limit exceeded: action budget exhausted",
+        ));
+    }
+    Ok(session.session_key)
+}
+
+fn preview(content: &str) -> String {
+    crate::util::truncate_with_ellipsis(content.trim(), 80)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn index_arg(args: &serde_json::Value, name: &str) -> Option<usize> {
+    args.get(name)
+        .and_then(serde_json::Value::as_u64)
+        .map(|n| n as usize)
+}
+
+/// Pin a message of the current conversation so trimming and summarization
+/// never drop it and it stays in the prompt as a "Pinned notes" entry.
+pub struct PinMessageTool {
+    security: Arc<SecurityPolicy>,
+    workspace_dir: PathBuf,
+}
+
+impl PinMessageTool {
+    pub fn new(security: Arc<SecurityPolicy>, workspace_dir: PathBuf) -> Self {
+        Self {
+            security,
+            workspace_dir,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for PinMessageTool {
+    fn name(&self) -> &str {
+        "pin_message"
+    }
+
+    fn description(&self) -> &str {
+        "Pin a message in the current conversation so it is never trimmed or summarized away and \
+         always appears under 'Pinned notes' in your context. Use when the user asks you to \
+         remember something for this chat. Pins the user's most recent message by default; \
+         'index' selects the n-th most recent message instead (1 = latest). At most 20 pins are \
+         kept; pinning more releases the oldest."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "index": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "n-th most recent message of any role (1 = latest). Omit to pin the user's most recent message."
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let key = match pin_session(&self.security, self.name()) {
+            Ok(key) => key,
+            Err(blocked) => return Ok(blocked),
+        };
+        let index = index_arg(&args, "index");
+        if index == Some(0) {
+            return Ok(ToolResult::failure(
+                "'index' starts at 1 (the latest message)",
+            ));
+        }
+
+        let pinned = blocking_writable(&self.workspace_dir, move |store| {
+            store.pin_message(&key, index)
+        })
+        .await?;
+        let Some(outcome) = pinned else {
+            return Ok(ToolResult::failure("No such message in this conversation"));
+        };
+        let mut output = if outcome.newly_pinned {
+            format!("Pinned: \"{}\"", preview(&outcome.pinned.content))
+        } else {
+            format!("Already pinned: \"{}\"", preview(&outcome.pinned.content))
+        };
+        for evicted in &outcome.evicted {
+            let _ = write!(
+                output,
+                "\nPin limit ({MAX_PINNED_PER_SESSION}) reached; unpinned the oldest: \"{}\"",
+                preview(&evicted.content)
+            );
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+/// Release a pin by its number in the "Pinned notes" section.
+pub struct UnpinMessageTool {
+    security: Arc<SecurityPolicy>,
+    workspace_dir: PathBuf,
+}
+
+impl UnpinMessageTool {
+    pub fn new(security: Arc<SecurityPolicy>, workspace_dir: PathBuf) -> Self {
+        Self {
+            security,
+            workspace_dir,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for UnpinMessageTool {
+    fn name(&self) -> &str {
+        "unpin_message"
+    }
+
+    fn description(&self) -> &str {
+        "Unpin a message in the current conversation, given its number in the 'Pinned notes' \
+         section. The message becomes ordinary history again."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "number": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Number of the note in the 'Pinned notes' section"
+                }
+            },
+            "required": ["number"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let Some(number) = index_arg(&args, "number") else {
+            return Ok(ToolResult::failure("Missing 'number' parameter"));
+        };
+        let key = match pin_session(&self.security, self.name()) {
+            Ok(key) => key,
+            Err(blocked) => return Ok(blocked),
+        };
+
+        let unpinned = blocking_writable(&self.workspace_dir, move |store| {
+            store.unpin_message(&key, number)
+        })
+        .await?;
+        match unpinned {
+            Some(unpinned) => Ok(ToolResult {
+                success: true,
+                output: format!("Unpinned: \"{}\"", preview(&unpinned.content)),
+                error: None,
+            }),
+            None => Ok(ToolResult::failure(format!(
+                "No pinned note number {number}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AutonomyConfig;
+    use crate::sessions::{with_session, SessionContext, SqliteSessionStore};
+    use tempfile::TempDir;
+
+    fn security(tmp: &TempDir) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy::from_config(
+            &AutonomyConfig::default(),
+            tmp.path(),
+        ))
+    }
+
+    async fn run(tool: &dyn Tool, args: serde_json::Value) -> ToolResult {
+        let ctx = SessionContext {
+            session_key: "telegram_alice".into(),
+            channel: "telegram".into(),
+            reply_target: "chat-42".into(),
+            thread_ts: None,
+            sender: String::new(),
+        };
+        with_session(ctx, tool.execute(args)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn pins_and_unpins_in_current_session() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        store
+            .append_message("telegram_alice", "user", "remember: prod DB is prod-eu-1")
+            .unwrap();
+        store
+            .append_message("telegram_alice", "assistant", "Noted.")
+            .unwrap();
+        let pin = PinMessageTool::new(security(&tmp), tmp.path().to_path_buf());
+        let unpin = UnpinMessageTool::new(security(&tmp), tmp.path().to_path_buf());
+
+        let pinned = run(&pin, json!({})).await;
+        assert!(pinned.success, "{:?}", pinned.error);
+        assert!(pinned.output.contains("prod-eu-1"));
+        let again = run(&pin, json!({"index": 2})).await;
+        assert!(again.output.starts_with("Already pinned"));
+        assert_eq!(store.pinned_messages("telegram_alice").unwrap().len(), 1);
+
+        let missing = run(&unpin, json!({"number": 2})).await;
+        assert!(!missing.success);
+        let unpinned = run(&unpin, json!({"number": 1})).await;
+        assert!(unpinned.success);
+        assert!(store.pinned_messages("telegram_alice").unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reports_pins_released_by_the_cap() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        for i in 0..=MAX_PINNED_PER_SESSION {
+            store
+                .append_message("telegram_alice", "user", &format!("note {i}"))
+                .unwrap();
+        }
+        for index in 2..=MAX_PINNED_PER_SESSION + 1 {
+            store.pin_message("telegram_alice", Some(index)).unwrap();
+        }
+        let pin = PinMessageTool::new(security(&tmp), tmp.path().to_path_buf());
+
+        let result = run(&pin, json!({})).await;
+        assert!(result.success);
+        assert!(result.output.contains("unpinned the oldest"));
+        assert!(result.output.contains("\"note 19\""));
+    }
+
+    #[tokio::test]
+    async fn requires_a_channel_conversation() {
+        let tmp = TempDir::new().unwrap();
+        let pin = PinMessageTool::new(security(&tmp), tmp.path().to_path_buf());
+        let result = pin.execute(json!({})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("channel conversation"));
+    }
+}
@@ -0,0 +1,124 @@
+use super::traits::{Tool, ToolResult};
+use crate::attachments::AttachmentStore;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Cap on the text returned to the model.
+const MAX_OUTPUT_CHARS: usize = 100_000;
+
+/// Read a text attachment uploaded through `POST /api/attachment`.
+pub struct ReadAttachmentTool {
+    security: Arc<SecurityPolicy>,
+    store: AttachmentStore,
+}
+
+impl ReadAttachmentTool {
+    pub fn new(security: Arc<SecurityPolicy>, workspace_dir: &Path, retention_hours: u64) -> Self {
+        Self {
+            security,
+            store: AttachmentStore::new(workspace_dir, retention_hours),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for ReadAttachmentTool {
+    fn name(&self) -> &str {
+        "read_attachment"
+    }
+
+    fn description(&self) -> &str {
+        "Read the content of a text attachment sent with a message, by its id or attachment:// URI. \
+         Binary attachments are not returned; the error reports their type and size."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "string",
+                    "description": "Attachment id or attachment://{id} URI"
+                }
+            },
+            "required": ["id"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let Some(reference) = args.get("id").and_then(serde_json::Value::as_str) else {
+            return Ok(ToolResult::failure("Missing 'id' parameter"));
+        };
+        if self.security.is_rate_limited() {
+            return Ok(ToolResult::failure(
+                "Rate limit exceeded: too many actions in the last hour",
+            ));
+        }
+
+        let Some((attachment, data)) = self.store.read(reference)? else {
+            return Ok(ToolResult::failure(format!(
+                "Attachment not found or expired: {reference}"
+            )));
+        };
+        if !attachment.is_text() {
+            return Ok(ToolResult::failure(format!(
+                "{} is {} ({} bytes), not a text attachment",
+                attachment.filename, attachment.mime_type, attachment.size_bytes
+            )));
+        }
+        let Ok(text) = String::from_utf8(data) else {
+            return Ok(ToolResult::failure(format!(
+                "{} is declared as {} but is not valid UTF-8",
+                attachment.filename, attachment.mime_type
+            )));
+        };
+
+        Ok(ToolResult {
+            success: true,
+            output: crate::util::truncate_with_ellipsis(&text, MAX_OUTPUT_CHARS),
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AutonomyConfig;
+    use tempfile::TempDir;
+
+    fn test_tool(tmp: &TempDir) -> (ReadAttachmentTool, AttachmentStore) {
+        let security = Arc::new(SecurityPolicy::from_config(
+            &AutonomyConfig::default(),
+            tmp.path(),
+        ));
+        let store = AttachmentStore::new(tmp.path(), 24);
+        (ReadAttachmentTool::new(security, tmp.path(), 24), store)
+    }
+
+    #[tokio::test]
+    async fn reads_text_and_refuses_binary() {
+        let tmp = TempDir::new().unwrap();
+        let (tool, store) = test_tool(&tmp);
+        let text = store.save("todo.txt", "text/plain", b"buy milk").unwrap();
+        let image = store.save("cat.png", "image/png", &[0x89, 0x50]).unwrap();
+
+        let result = tool.execute(json!({"id": text.uri()})).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "buy milk");
+
+        let result = tool.execute(json!({"id": image.id})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not a text attachment"));
+
+        let result = tool
+            .execute(json!({"id": "attachment://0000-missing"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not found"));
+    }
+}
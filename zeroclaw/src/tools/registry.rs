@@ -0,0 +1,300 @@
+//! Shared execution path for tool calls made by the agent.
+//!
+//! Both the channel/CLI tool-call loop and [`crate::agent::Agent`] run model
+//! tool calls through [`execute_tool`], so lookup, the permission check,
+//! argument validation, timing, observer events and [`super::metrics`]
+//! behave identically everywhere.
+
+use super::metrics;
+use super::schema::SchemaCleanr;
+use super::traits::Tool;
+use crate::observability::{Observer, ObserverEvent};
+use std::time::{Duration, Instant};
+
+/// Result of running one model tool call through [`execute_tool`].
+#[derive(Debug, Clone)]
+pub struct ToolCallOutcome {
+    /// Text to feed back to the model (tool output or an error message).
+    pub output: String,
+    pub success: bool,
+    /// Failure reason without the `Error:` prefix, for observers and logs.
+    pub error_reason: Option<String>,
+    pub duration: Duration,
+}
+
+impl ToolCallOutcome {
+    fn failed(reason: String, duration: Duration) -> Self {
+        Self {
+            output: reason.clone(),
+            success: false,
+            error_reason: Some(reason),
+            duration,
+        }
+    }
+}
+
+/// Find a tool by name in the registry.
+pub fn find_tool<'a>(tools: &'a [Box<dyn Tool>], name: &str) -> Option<&'a dyn Tool> {
+    tools.iter().find(|t| t.name() == name).map(|t| t.as_ref())
+}
+
+/// Look up `name`, refuse it if it is in `excluded` (tools withheld from
+/// the channel, e.g. `autonomy.non_cli_excluded_tools`), validate `args`
+/// against its schema, run it, and record the call with `observer` and in
+/// the per-tool metrics.
+///
+/// Unknown, excluded and invalid calls are reported back as failed outcomes
+/// (never as `Err`) so the model can correct itself on the next iteration.
+pub async fn execute_tool(
+    tools: &[Box<dyn Tool>],
+    excluded: &[String],
+    name: &str,
+    args: serde_json::Value,
+    observer: &dyn Observer,
+) -> ToolCallOutcome {
+    observer.record_event(&ObserverEvent::ToolCallStart {
+        tool: name.to_string(),
+    });
+    let start = Instant::now();
+
+    let outcome = match find_tool(tools, name) {
+        None => ToolCallOutcome::failed(format!("Unknown tool: {name}"), start.elapsed()),
+        // Excluded tools are not offered, so a call is treated like an unknown one.
+        Some(_) if excluded.iter().any(|ex| ex == name) => ToolCallOutcome::failed(
+            format!("Tool '{name}' is not available in this channel"),
+            start.elapsed(),
+        ),
+        Some(tool) => {
+            let violations = SchemaCleanr::validate_arguments(&tool.parameters_schema(), &args);
+            let outcome = if violations.is_empty() {
+                run_tool(tool, args, start).await
+            } else {
+                ToolCallOutcome::failed(format_violations(name, &violations), start.elapsed())
+            };
+            // Unknown names are left out so hallucinated tools can't grow the map.
+            metrics::record(name, outcome.duration, outcome.error_reason.as_deref());
+            outcome
+        }
+    };
+
+    observer.record_event(&ObserverEvent::ToolCall {
+        tool: name.to_string(),
+        duration: outcome.duration,
+        success: outcome.success,
+    });
+    outcome
+}
+
+async fn run_tool(tool: &dyn Tool, args: serde_json::Value, start: Instant) -> ToolCallOutcome {
+    match tool.execute(args).await {
+        Ok(r) if r.success => ToolCallOutcome {
+            output: r.output,
+            success: true,
+            error_reason: None,
+            duration: start.elapsed(),
+        },
+        Ok(r) => {
+            let reason = r.error.unwrap_or(r.output);
+            ToolCallOutcome {
+                output: format!("Error: {reason}"),
+                success: false,
+                error_reason: Some(reason),
+                duration: start.elapsed(),
+            }
+        }
+        Err(e) => ToolCallOutcome::failed(
+            format!("Error executing {}: {e}", tool.name()),
+            start.elapsed(),
+        ),
+    }
+}
+
+fn format_violations(name: &str, violations: &[String]) -> String {
+    let mut message = format!("Invalid arguments for {name}:");
+    for violation in violations {
+        message.push_str("\n- ");
+        message.push_str(violation);
+    }
+    message.push_str("\nFix the arguments to match the tool's parameter schema and call it again.");
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::NoopObserver;
+    use crate::tools::ToolResult;
+    use async_trait::async_trait;
+    use serde_json::json;
+
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echo text back"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            json!({
+                "type": "object",
+                "properties": {
+                    "text": { "type": "string" },
+                    "times": { "type": "integer" }
+                },
+                "required": ["text"],
+                "additionalProperties": false
+            })
+        }
+
+        async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            let text = args["text"].as_str().unwrap_or_default();
+            let times = args["times"].as_u64().unwrap_or(1);
+            #[allow(clippy::cast_possible_truncation)]
+            Ok(ToolResult {
+                success: true,
+                output: text.repeat(times as usize),
+                error: None,
+            })
+        }
+    }
+
+    fn registry() -> Vec<Box<dyn Tool>> {
+        vec![Box::new(EchoTool)]
+    }
+
+    #[tokio::test]
+    async fn valid_arguments_run_the_tool() {
+        let outcome = execute_tool(
+            &registry(),
+            &[],
+            "echo",
+            json!({"text": "ab", "times": 2}),
+            &NoopObserver,
+        )
+        .await;
+        assert!(outcome.success);
+        assert_eq!(outcome.output, "abab");
+        assert!(outcome.error_reason.is_none());
+    }
+
+    #[tokio::test]
+    async fn type_mismatch_is_reported_without_running_the_tool() {
+        let outcome = execute_tool(
+            &registry(),
+            &[],
+            "echo",
+            json!({"text": "ab", "times": "twice"}),
+            &NoopObserver,
+        )
+        .await;
+        assert!(!outcome.success);
+        assert!(outcome.output.starts_with("Invalid arguments for echo:"));
+        assert!(outcome
+            .output
+            .contains("- 'times' must be integer, got string"));
+    }
+
+    struct FailTool(&'static str);
+
+    #[async_trait]
+    impl Tool for FailTool {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "Always fails"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            json!({"type": "object", "properties": {}})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("disk full".into()),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn calls_are_recorded_in_tool_metrics() {
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(FailTool("registry-metrics-fail"))];
+        execute_tool(
+            &tools,
+            &[],
+            "registry-metrics-fail",
+            json!({}),
+            &NoopObserver,
+        )
+        .await;
+        execute_tool(
+            &tools,
+            &[],
+            "registry-metrics-fail",
+            json!({}),
+            &NoopObserver,
+        )
+        .await;
+
+        let snapshot = metrics::snapshot_json();
+        let entry = &snapshot["registry-metrics-fail"];
+        assert_eq!(entry["execution_count"], 2);
+        assert_eq!(entry["success_count"], 0);
+        assert_eq!(entry["failure_count"], 2);
+        assert_eq!(entry["last_error"], "disk full");
+        assert!(entry["avg_duration_ms"].is_number());
+        assert!(entry["total_duration_ms"].is_u64());
+        assert!(entry["last_used_at"].is_string());
+    }
+
+    #[tokio::test]
+    async fn unknown_tools_are_not_recorded() {
+        execute_tool(
+            &registry(),
+            &[],
+            "registry-metrics-ghost",
+            json!({}),
+            &NoopObserver,
+        )
+        .await;
+        assert!(!metrics::snapshot().contains_key("registry-metrics-ghost"));
+    }
+
+    #[tokio::test]
+    async fn excluded_tools_are_refused_without_running() {
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(FailTool("registry-excluded"))];
+        let outcome = execute_tool(
+            &tools,
+            &["registry-excluded".to_string()],
+            "registry-excluded",
+            json!({}),
+            &NoopObserver,
+        )
+        .await;
+        assert!(!outcome.success);
+        assert_eq!(
+            outcome.output,
+            "Tool 'registry-excluded' is not available in this channel"
+        );
+        assert!(!metrics::snapshot().contains_key("registry-excluded"));
+    }
+
+    #[tokio::test]
+    async fn unknown_tool_is_reported() {
+        let outcome = execute_tool(&registry(), &[], "missing", json!({}), &NoopObserver).await;
+        assert!(!outcome.success);
+        assert_eq!(outcome.output, "Unknown tool: missing");
+        assert_eq!(
+            outcome.error_reason.as_deref(),
+            Some("Unknown tool: missing")
+        );
+    }
+}
@@ -0,0 +1,682 @@
+use super::traits::{Tool, ToolResult};
+use crate::config::{CommandFilterMode, SandboxConfig};
+use crate::runtime::RuntimeAdapter;
+use crate::security::policy::ToolOperation;
+use crate::security::{AutonomyLevel, CommandFilter, SecurityPolicy};
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Workspace subdirectory holding snippets while they run.
+const RUN_DIR: &str = ".run_code";
+/// Largest file a snippet may write (16MB).
+#[cfg(unix)]
+const MAX_FILE_BYTES: u64 = 16 * 1024 * 1024;
+
+/// An interpreter found on `PATH`.
+#[derive(Debug, Clone)]
+struct Interpreter {
+    language: &'static str,
+    binary: &'static str,
+    program: PathBuf,
+    extension: &'static str,
+}
+
+/// (language, binary, file extension) for every supported language.
+const LANGUAGES: &[(&str, &str, &str)] =
+    &[("python", "python3", "py"), ("javascript", "node", "js")];
+
+/// Interpreters on the host `PATH`. Other runtimes run the snippet in
+/// their own environment, so every language is offered and a missing
+/// binary shows up as a failed run.
+fn detect_interpreters(runtime: &dyn RuntimeAdapter) -> Vec<Interpreter> {
+    let native = runtime.name() == "native";
+    LANGUAGES
+        .iter()
+        .filter_map(|(language, binary, extension)| {
+            let program = if native {
+                which::which(binary).ok()?
+            } else {
+                PathBuf::from(binary)
+            };
+            Some(Interpreter {
+                language,
+                binary,
+                program,
+                extension,
+            })
+        })
+        .collect()
+}
+
+/// Run a Python or JavaScript snippet under tighter limits than `shell`:
+/// a shorter timeout, a smaller output cap, and per-process rlimits. The
+/// snippet runs in its own process group so the whole tree is killed on
+/// timeout.
+///
+/// Calls pass the same gates as `shell`: the interpreter must be in the
+/// command allowlist, supervised mode needs `approved=true`, the code is
+/// checked against the blocked rules, and the process is started through
+/// the runtime adapter.
+pub struct RunCodeTool {
+    security: Arc<SecurityPolicy>,
+    runtime: Arc<dyn RuntimeAdapter>,
+    command_filter: CommandFilter,
+    interpreters: Vec<Interpreter>,
+    timeout_secs: u64,
+    max_output_bytes: usize,
+    max_memory_mb: u64,
+    description: String,
+}
+
+impl RunCodeTool {
+    pub fn new(
+        security: Arc<SecurityPolicy>,
+        runtime: Arc<dyn RuntimeAdapter>,
+        sandbox: &SandboxConfig,
+    ) -> Self {
+        let interpreters = detect_interpreters(runtime.as_ref());
+        Self::with_interpreters(security, runtime, sandbox, interpreters)
+    }
+
+    fn with_interpreters(
+        security: Arc<SecurityPolicy>,
+        runtime: Arc<dyn RuntimeAdapter>,
+        sandbox: &SandboxConfig,
+        interpreters: Vec<Interpreter>,
+    ) -> Self {
+        let available = if interpreters.is_empty() {
+            "No interpreters were found on this system, so every call will fail.".to_string()
+        } else {
+            let names: Vec<String> = interpreters
+                .iter()
+                .map(|i| format!("{} ({})", i.language, i.program.display()))
+                .collect();
+            format!("Available languages: {}.", names.join(", "))
+        };
+        let description = format!(
+            "Run a short Python or JavaScript program in the workspace and return its \
+             stdout, stderr and exit code. Prefer this over `shell` for computation. \
+             Killed after {}s; output capped at {} bytes. {available}",
+            sandbox.code_timeout_secs, sandbox.code_max_output_bytes
+        );
+        Self {
+            security,
+            runtime,
+            command_filter: CommandFilter::from_config(sandbox),
+            interpreters,
+            timeout_secs: sandbox.code_timeout_secs.max(1),
+            max_output_bytes: sandbox.code_max_output_bytes,
+            max_memory_mb: sandbox.code_max_memory_mb,
+            description,
+        }
+    }
+
+    fn interpreter(&self, language: &str) -> Option<&Interpreter> {
+        let language = match language.trim().to_ascii_lowercase().as_str() {
+            "python" | "python3" | "py" => "python",
+            "javascript" | "js" | "node" => "javascript",
+            _ => return None,
+        };
+        self.interpreters.iter().find(|i| i.language == language)
+    }
+}
+
+/// Contents of the single- and double-quoted literals in `code`, where
+/// shell commands handed to `os.system` or `child_process` usually sit.
+fn string_literals(code: &str) -> Vec<&str> {
+    let mut literals = Vec::new();
+    let mut chars = code.char_indices();
+    while let Some((start, quote)) = chars.next() {
+        if quote != '"' && quote != '\'' {
+            continue;
+        }
+        let mut escaped = false;
+        for (i, c) in chars.by_ref() {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == quote {
+                literals.push(&code[start + 1..i]);
+                break;
+            }
+        }
+    }
+    literals
+}
+
+/// Removes the snippet file however execution ends.
+struct TempSource(PathBuf);
+
+impl Drop for TempSource {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Read at most `cap` bytes, then drop the pipe so a chatty child gets
+/// `EPIPE` instead of filling memory.
+async fn read_capped<R: AsyncRead + Unpin>(reader: Option<R>, cap: usize) -> (Vec<u8>, bool) {
+    let Some(reader) = reader else {
+        return (Vec::new(), false);
+    };
+    let mut buf = Vec::new();
+    let limit = u64::try_from(cap).unwrap_or(u64::MAX).saturating_add(1);
+    let _ = reader.take(limit).read_to_end(&mut buf).await;
+    let truncated = buf.len() > cap;
+    buf.truncate(cap);
+    (buf, truncated)
+}
+
+fn render(bytes: &[u8], truncated: bool, cap: usize) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    if truncated {
+        format!("{text}\n... [truncated at {cap} bytes]")
+    } else {
+        text.into_owned()
+    }
+}
+
+/// Put the child in its own process group and apply rlimits before exec.
+#[cfg(unix)]
+fn apply_limits(cmd: &mut tokio::process::Command, cpu_secs: u64, memory_mb: u64) {
+    let memory_bytes = memory_mb.saturating_mul(1024 * 1024);
+    cmd.process_group(0);
+    // SAFETY: the closure runs in the forked child before exec and only calls
+    // async-signal-safe `setrlimit`.
+    unsafe {
+        cmd.pre_exec(move || {
+            let set = |resource, value: u64| {
+                let limit = libc::rlimit {
+                    rlim_cur: value as libc::rlim_t,
+                    rlim_max: value as libc::rlim_t,
+                };
+                if libc::setrlimit(resource, &raw const limit) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            };
+            set(libc::RLIMIT_CPU, cpu_secs)?;
+            set(libc::RLIMIT_FSIZE, MAX_FILE_BYTES)?;
+            set(libc::RLIMIT_CORE, 0)?;
+            // RLIMIT_AS would break V8's address-space reservation; the
+            // data segment limit still caps heap growth.
+            if memory_bytes > 0 {
+                set(libc::RLIMIT_DATA, memory_bytes)?;
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(not(unix))]
+fn apply_limits(_cmd: &mut tokio::process::Command, _cpu_secs: u64, _memory_mb: u64) {}
+
+/// Kill every process in the snippet's group, including grandchildren.
+#[cfg(unix)]
+fn kill_process_group(pid: Option<u32>) {
+    if let Some(pgid) = pid.and_then(|p| i32::try_from(p).ok()) {
+        // SAFETY: plain syscall; a stale group id at worst returns ESRCH.
+        unsafe {
+            libc::kill(-pgid, libc::SIGKILL);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(_pid: Option<u32>) {}
+
+#[async_trait]
+impl Tool for RunCodeTool {
+    fn name(&self) -> &str {
+        "run_code"
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "language": {
+                    "type": "string",
+                    "description": "Language of the source code: 'python' or 'javascript'"
+                },
+                "code": {
+                    "type": "string",
+                    "description": "Complete program source; print results to stdout"
+                },
+                "approved": {
+                    "type": "boolean",
+                    "description": "Set true to explicitly approve running the snippet in supervised mode",
+                    "default": false
+                }
+            },
+            "required": ["language", "code"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let language = args
+            .get("language")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'language' parameter"))?;
+        let code = args
+            .get("code")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'code' parameter"))?;
+        let approved = args
+            .get("approved")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let Some(interpreter) = self.interpreter(language).cloned() else {
+            let available: Vec<&str> = self.interpreters.iter().map(|i| i.language).collect();
+            return Ok(ToolResult::failure(format!(
+                "No interpreter available for '{language}'. Available: {}",
+                if available.is_empty() {
+                    "none (install python3 or node)".to_string()
+                } else {
+                    available.join(", ")
+                }
+            )));
+        };
+
+        if let Err(e) = self
+            .security
+            .enforce_tool_operation(ToolOperation::Act, "run_code")
+        {
+            return Ok(ToolResult::failure(e));
+        }
+
+        if self.security.is_rate_limited() {
+            return Ok(ToolResult::failure(
+                "Rate limit exceeded: too many actions in the last hour",
+            ));
+        }
+
+        let snippet = format!(
+            "{RUN_DIR}/snippet_{}.{}",
+            uuid::Uuid::new_v4().simple(),
+            interpreter.extension
+        );
+        let command = format!("{} {snippet}", interpreter.binary);
+
+        if let Err(reason) = self.security.validate_command_execution(&command, approved) {
+            return Ok(ToolResult::failure(reason));
+        }
+        // Arbitrary code is never low-risk, whatever the interpreter's name.
+        if self.security.autonomy == AutonomyLevel::Supervised && !approved {
+            return Ok(ToolResult::failure(
+                "run_code requires explicit approval (approved=true) in supervised mode",
+            ));
+        }
+        if let Some(path) = self.security.forbidden_path_argument(&command) {
+            return Ok(ToolResult::failure(format!(
+                "Path blocked by security policy: {path}"
+            )));
+        }
+        if let Some(rule) = std::iter::once(code)
+            .chain(string_literals(code))
+            .find_map(|text| self.command_filter.matched_rule(text))
+        {
+            if self.command_filter.mode() == CommandFilterMode::Block {
+                return Ok(ToolResult::failure(format!(
+                    "Snippet blocked by sandbox rule {rule}"
+                )));
+            }
+            tracing::warn!("run_code snippet matches sandbox rule {rule}; running in warn mode");
+        }
+        if !self.security.record_action() {
+            return Ok(ToolResult::failure(
+                "Rate limit exceeded: action budget exhausted",
+            ));
+        }
+
+        let run_dir = self.security.workspace_dir.join(RUN_DIR);
+        if let Err(e) = tokio::fs::create_dir_all(&run_dir).await {
+            return Ok(ToolResult::failure(format!(
+                "Failed to create {}: {e}",
+                run_dir.display()
+            )));
+        }
+        let source = TempSource(self.security.workspace_dir.join(&snippet));
+        if let Err(e) = tokio::fs::write(&source.0, code).await {
+            return Ok(ToolResult::failure(format!("Failed to write snippet: {e}")));
+        }
+
+        let mut cmd = match self
+            .runtime
+            .build_shell_command(&command, &self.security.workspace_dir)
+        {
+            Ok(cmd) => cmd,
+            Err(e) => {
+                return Ok(ToolResult::failure(format!(
+                    "Failed to build runtime command: {e}"
+                )))
+            }
+        };
+        cmd.stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .env_clear();
+        for var in super::shell::collect_allowed_shell_env_vars(&self.security) {
+            if let Ok(val) = std::env::var(&var) {
+                cmd.env(&var, val);
+            }
+        }
+        apply_limits(&mut cmd, self.timeout_secs + 1, self.max_memory_mb);
+
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                return Ok(ToolResult::failure(format!(
+                    "Failed to start {}: {e}",
+                    interpreter.program.display()
+                )))
+            }
+        };
+        let pid = child.id();
+        let cap = self.max_output_bytes;
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+
+        let run = async {
+            let ((out, out_cut), (err, err_cut), status) = tokio::join!(
+                read_capped(stdout, cap),
+                read_capped(stderr, cap),
+                child.wait()
+            );
+            (out, out_cut, err, err_cut, status)
+        };
+
+        match tokio::time::timeout(Duration::from_secs(self.timeout_secs), run).await {
+            Ok((out, out_cut, err, err_cut, status)) => {
+                // Reap anything the snippet left running in the background.
+                kill_process_group(pid);
+                let status = match status {
+                    Ok(status) => status,
+                    Err(e) => {
+                        return Ok(ToolResult::failure(format!(
+                            "Failed to wait for snippet: {e}"
+                        )))
+                    }
+                };
+                let exit = status
+                    .code()
+                    .map_or_else(|| "killed by signal".to_string(), |c| c.to_string());
+                let stderr = render(&err, err_cut, cap);
+                Ok(ToolResult {
+                    success: status.success(),
+                    output: format!("{}\n[exit code: {exit}]", render(&out, out_cut, cap)),
+                    error: if stderr.is_empty() {
+                        None
+                    } else {
+                        Some(stderr)
+                    },
+                })
+            }
+            Err(_) => {
+                kill_process_group(pid);
+                Ok(ToolResult::failure(format!(
+                    "Snippet timed out after {}s and was killed",
+                    self.timeout_secs
+                )))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::NativeRuntime;
+
+    fn runtime() -> Arc<dyn RuntimeAdapter> {
+        Arc::new(NativeRuntime::new())
+    }
+
+    fn policy(workspace: &std::path::Path, autonomy: AutonomyLevel) -> Arc<SecurityPolicy> {
+        let mut allowed_commands = SecurityPolicy::default().allowed_commands;
+        allowed_commands.extend(["python3".to_string(), "node".to_string()]);
+        Arc::new(SecurityPolicy {
+            autonomy,
+            workspace_dir: workspace.to_path_buf(),
+            allowed_commands,
+            ..SecurityPolicy::default()
+        })
+    }
+
+    fn sandbox(timeout_secs: u64) -> SandboxConfig {
+        SandboxConfig {
+            code_timeout_secs: timeout_secs,
+            ..SandboxConfig::default()
+        }
+    }
+
+    fn tool_in(workspace: &std::path::Path, timeout_secs: u64) -> RunCodeTool {
+        let security = policy(workspace, AutonomyLevel::Full);
+        RunCodeTool::new(security, runtime(), &sandbox(timeout_secs))
+    }
+
+    fn has_python(tool: &RunCodeTool) -> bool {
+        tool.interpreter("python").is_some()
+    }
+
+    #[tokio::test]
+    async fn python_snippet_returns_stdout_and_cleans_up() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tool = tool_in(tmp.path(), 10);
+        if !has_python(&tool) {
+            return;
+        }
+
+        let result = tool
+            .execute(json!({"language": "python", "code": "print(6 * 7)"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "42\n\n[exit code: 0]");
+        let leftovers = std::fs::read_dir(tmp.path().join(RUN_DIR)).unwrap().count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[tokio::test]
+    async fn nonzero_exit_reports_stderr() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tool = tool_in(tmp.path(), 10);
+        if !has_python(&tool) {
+            return;
+        }
+
+        let result = tool
+            .execute(json!({
+                "language": "py",
+                "code": "import sys\nsys.stderr.write('boom')\nsys.exit(3)"
+            }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.output.ends_with("[exit code: 3]"));
+        assert_eq!(result.error.as_deref(), Some("boom"));
+    }
+
+    #[tokio::test]
+    async fn output_is_capped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let security = policy(tmp.path(), AutonomyLevel::Supervised);
+        let config = SandboxConfig {
+            code_max_output_bytes: 100,
+            ..SandboxConfig::default()
+        };
+        let tool = RunCodeTool::new(security, runtime(), &config);
+        if !has_python(&tool) {
+            return;
+        }
+
+        let result = tool
+            .execute(json!({
+                "language": "python",
+                "code": "print('x' * 100000)",
+                "approved": true
+            }))
+            .await
+            .unwrap();
+        assert!(result.output.starts_with(&"x".repeat(100)));
+        assert!(result.output.contains("[truncated at 100 bytes]"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn timeout_kills_whole_process_group() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tool = tool_in(tmp.path(), 1);
+        if !has_python(&tool) {
+            return;
+        }
+        let pid_file = tmp.path().join("grandchild.pid");
+        let code = format!(
+            "import subprocess, time\n\
+             p = subprocess.Popen(['sleep', '30'])\n\
+             open({:?}, 'w').write(str(p.pid))\n\
+             time.sleep(30)\n",
+            pid_file.display().to_string()
+        );
+
+        let started = std::time::Instant::now();
+        let result = tool
+            .execute(json!({"language": "python", "code": code}))
+            .await
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("timed out after 1s"));
+
+        // The grandchild shares the snippet's process group, so it must be
+        // gone too (or a zombie awaiting reaping by init).
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let alive = std::fs::read_to_string(format!("/proc/{}/stat", pid.trim()))
+            .map(|stat| !stat.contains(") Z"))
+            .unwrap_or(false);
+        assert!(!alive, "grandchild {pid} survived the timeout");
+        assert_eq!(
+            std::fs::read_dir(tmp.path().join(RUN_DIR)).unwrap().count(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_interpreter_is_reported() {
+        let tmp = tempfile::tempdir().unwrap();
+        let security = Arc::new(SecurityPolicy {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let tool =
+            RunCodeTool::with_interpreters(security, runtime(), &SandboxConfig::default(), vec![]);
+        assert!(tool.description().contains("No interpreters were found"));
+
+        let result = tool
+            .execute(json!({"language": "python", "code": "print(1)"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(
+            result.error.as_deref(),
+            Some(
+                "No interpreter available for 'python'. Available: none (install python3 or node)"
+            )
+        );
+        assert!(!tmp.path().join(RUN_DIR).exists());
+    }
+
+    #[tokio::test]
+    async fn read_only_autonomy_blocks_execution() {
+        let tmp = tempfile::tempdir().unwrap();
+        let security = policy(tmp.path(), AutonomyLevel::ReadOnly);
+        let tool = RunCodeTool::new(security, runtime(), &SandboxConfig::default());
+        if !has_python(&tool) {
+            return;
+        }
+        let result = tool
+            .execute(json!({"language": "python", "code": "print(1)", "approved": true}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+
+    #[tokio::test]
+    async fn supervised_autonomy_requires_approval() {
+        let tmp = tempfile::tempdir().unwrap();
+        let security = policy(tmp.path(), AutonomyLevel::Supervised);
+        let tool = RunCodeTool::new(security, runtime(), &SandboxConfig::default());
+        if !has_python(&tool) {
+            return;
+        }
+        let result = tool
+            .execute(json!({"language": "python", "code": "print(1)"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("approved=true"));
+        assert!(!tmp.path().join(RUN_DIR).exists());
+
+        let result = tool
+            .execute(json!({"language": "python", "code": "print(1)", "approved": true}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+    }
+
+    #[tokio::test]
+    async fn interpreter_outside_the_allowlist_is_refused() {
+        let tmp = tempfile::tempdir().unwrap();
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let tool = RunCodeTool::new(security, runtime(), &SandboxConfig::default());
+        if !has_python(&tool) {
+            return;
+        }
+        let result = tool
+            .execute(json!({"language": "python", "code": "print(1)"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error
+            .unwrap()
+            .contains("Command not allowed by security policy: python3"));
+        assert!(!tmp.path().join(RUN_DIR).exists());
+    }
+
+    #[tokio::test]
+    async fn blocked_rules_apply_to_the_snippet() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tool = tool_in(tmp.path(), 10);
+        if !has_python(&tool) {
+            return;
+        }
+        let result = tool
+            .execute(json!({
+                "language": "python",
+                "code": "import os\nos.system('rm -rf /')"
+            }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("blocked by sandbox rule"));
+    }
+}
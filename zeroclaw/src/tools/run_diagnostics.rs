@@ -0,0 +1,57 @@
+use super::traits::{Tool, ToolResult};
+use crate::config::Config;
+use async_trait::async_trait;
+use serde_json::json;
+
+/// Run live health probes (provider, channels, public URL, disk, databases)
+/// so the agent can answer "is everything healthy?" with concrete findings.
+pub struct RunDiagnosticsTool {
+    config: Config,
+}
+
+impl RunDiagnosticsTool {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl Tool for RunDiagnosticsTool {
+    fn name(&self) -> &str {
+        "run_diagnostics"
+    }
+
+    fn description(&self) -> &str {
+        "Check whether this assistant's setup is healthy: pings the LLM provider, validates \
+         channel credentials (Telegram getMe, Slack auth.test, ...), checks the public URL, free \
+         disk space and that the databases are writable. Returns each finding with a suggested \
+         fix for failures. Results are cached for a minute; set 'refresh' to probe again."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "refresh": {
+                    "type": "boolean",
+                    "description": "Ignore the cached report and run every probe now"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let refresh = args
+            .get("refresh")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+        let report = crate::doctor::diagnostics::shared()
+            .report(&self.config, refresh)
+            .await;
+        Ok(ToolResult {
+            success: true,
+            output: report.render(),
+            error: None,
+        })
+    }
+}
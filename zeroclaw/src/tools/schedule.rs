@@ -4,7 +4,7 @@ use crate::cron;
 use crate::security::SecurityPolicy;
 use anyhow::Result;
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::Utc;
 use serde_json::json;
 use std::sync::Arc;
 
@@ -52,7 +52,7 @@ impl Tool for ScheduleTool {
                 },
                 "run_at": {
                     "type": "string",
-                    "description": "Absolute RFC3339 time for one-shot tasks (e.g. '2030-01-01T00:00:00Z')."
+                    "description": "Time for one-shot tasks: RFC3339 (e.g. '2030-01-01T00:00:00Z') or an expression like 'tomorrow 9am' or 'friday 14:00' in the configured timezone."
                 },
                 "command": {
                     "type": "string",
@@ -339,9 +339,9 @@ impl ScheduleTool {
         }
 
         let run_at_raw = run_at.ok_or_else(|| anyhow::anyhow!("Missing scheduling parameters"))?;
-        let run_at_parsed: DateTime<Utc> = DateTime::parse_from_rfc3339(run_at_raw)
-            .map_err(|error| anyhow::anyhow!("Invalid run_at timestamp: {error}"))?
-            .with_timezone(&Utc);
+        let tz = cron::at::ScheduleTz::from_config(self.config.timezone.as_deref())?;
+        let run_at_parsed = cron::at::parse_at(run_at_raw, tz, Utc::now())
+            .map_err(|error| anyhow::anyhow!("Invalid run_at: {error:#}"))?;
 
         let job = cron::add_once_at(&self.config, run_at_parsed, command)?;
         Ok(ToolResult {
@@ -0,0 +1,465 @@
+use super::traits::{Tool, ToolResult};
+use crate::channels::slack;
+use crate::config::Config;
+use crate::cron::{self, DeliveryConfig, Schedule, SessionTarget};
+use crate::security::SecurityPolicy;
+use crate::sessions::{current_session, SessionContext};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::json;
+use std::sync::Arc;
+
+/// One-shot agent turns that report back into the conversation that asked
+/// for them ("check the build again in 20 minutes and tell me").
+///
+/// The job's delivery carries the originating session key, so the reply is
+/// sent to the same chat and recorded in that session's history. Listing and
+/// cancelling only ever see the current session's follow-ups.
+pub struct ScheduleFollowupTool {
+    security: Arc<SecurityPolicy>,
+    config: Config,
+}
+
+impl ScheduleFollowupTool {
+    pub fn new(security: Arc<SecurityPolicy>, config: Config) -> Self {
+        Self { security, config }
+    }
+
+    fn enforce_mutation_allowed(&self) -> Option<ToolResult> {
+        if !self.config.cron.enabled {
+            return Some(ToolResult::failure(
+                "cron is disabled by config (cron.enabled=false); cannot schedule follow-ups",
+            ));
+        }
+        if !self.security.can_act() {
+            return Some(ToolResult::failure(
+                "Security policy: read-only mode, cannot schedule follow-ups",
+            ));
+        }
+        if !self.security.record_action() {
+            return Some(ToolResult::failure(
+                "Rate limit exceeded: action budget exhausted",
+            ));
+        }
+        None
+    }
+
+    fn session_followups(&self, session: &SessionContext) -> anyhow::Result<Vec<cron::CronJob>> {
+        let key = Some(session.session_key.as_str());
+        Ok(cron::list_jobs(&self.config)?
+            .into_iter()
+            .filter(|job| job.delivery.session_key.as_deref() == key)
+            .collect())
+    }
+
+    /// Delivery target for a follow-up. Slack follow-ups reply in the
+    /// conversation's thread (or an explicit `thread_ts`) as `channel:thread_ts`.
+    fn followup_target(
+        session: &SessionContext,
+        args: &serde_json::Value,
+    ) -> anyhow::Result<String> {
+        let thread_ts = args
+            .get("thread_ts")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty());
+        if session.channel != "slack" {
+            if thread_ts.is_some() {
+                anyhow::bail!("'thread_ts' is only supported in Slack conversations");
+            }
+            return Ok(session.reply_target.clone());
+        }
+        let target = slack::delivery_target(
+            &session.reply_target,
+            thread_ts.or(session.thread_ts.as_deref()),
+        );
+        slack::parse_delivery_target(&target)?;
+        Ok(target)
+    }
+
+    fn handle_create(
+        &self,
+        session: &SessionContext,
+        args: &serde_json::Value,
+    ) -> anyhow::Result<ToolResult> {
+        let Some(prompt) = args
+            .get("prompt")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+        else {
+            return Ok(ToolResult::failure("Missing 'prompt' for create"));
+        };
+        let Some(when) = args
+            .get("when")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+        else {
+            return Ok(ToolResult::failure("Missing 'when' for create"));
+        };
+        if !cron::scheduler::ANNOUNCE_CHANNELS.contains(&session.channel.as_str()) {
+            return Ok(ToolResult::failure(format!(
+                "Follow-ups cannot be delivered on the '{}' channel (supported: {})",
+                session.channel,
+                cron::scheduler::ANNOUNCE_CHANNELS.join(", ")
+            )));
+        }
+        let to = match Self::followup_target(session, args) {
+            Ok(to) => to,
+            Err(e) => return Ok(ToolResult::failure(format!("{e:#}"))),
+        };
+        if let Some(blocked) = self.enforce_mutation_allowed() {
+            return Ok(blocked);
+        }
+
+        let tz = cron::at::ScheduleTz::from_config(self.config.timezone.as_deref())?;
+        let at = match cron::at::parse_at(when, tz, Utc::now()) {
+            Ok(at) => at,
+            Err(e) => return Ok(ToolResult::failure(format!("Invalid 'when': {e:#}"))),
+        };
+        let name = args
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(ToString::to_string)
+            .or_else(|| Some("follow-up".to_string()));
+
+        let job = cron::add_agent_job(
+            &self.config,
+            name,
+            Schedule::At {
+                at,
+                original: Some(when.to_string()),
+            },
+            prompt,
+            SessionTarget::Isolated,
+            None,
+            Some(DeliveryConfig {
+                mode: "announce".into(),
+                channel: Some(session.channel.clone()),
+                to: Some(to),
+                best_effort: true,
+                session_key: Some(session.session_key.clone()),
+            }),
+            true,
+        )?;
+
+        Ok(ToolResult {
+            success: true,
+            output: format!(
+                "Scheduled follow-up {} for {} ({})",
+                job.id,
+                tz.format(job.next_run),
+                when
+            ),
+            error: None,
+        })
+    }
+
+    fn handle_list(&self, session: &SessionContext) -> anyhow::Result<ToolResult> {
+        let jobs = self.session_followups(session)?;
+        if jobs.is_empty() {
+            return Ok(ToolResult {
+                success: true,
+                output: "No follow-ups scheduled for this conversation.".to_string(),
+                error: None,
+            });
+        }
+        let tz = cron::at::ScheduleTz::from_config(self.config.timezone.as_deref())?;
+        let lines: Vec<String> = jobs
+            .iter()
+            .map(|job| {
+                format!(
+                    "- {} | {} | {}",
+                    job.id,
+                    tz.format(job.next_run),
+                    job.prompt.as_deref().unwrap_or_default()
+                )
+            })
+            .collect();
+        Ok(ToolResult {
+            success: true,
+            output: format!("Follow-ups ({}):\n{}", lines.len(), lines.join("\n")),
+            error: None,
+        })
+    }
+
+    fn handle_cancel(
+        &self,
+        session: &SessionContext,
+        args: &serde_json::Value,
+    ) -> anyhow::Result<ToolResult> {
+        let Some(id) = args.get("id").and_then(|v| v.as_str()) else {
+            return Ok(ToolResult::failure("Missing 'id' for cancel"));
+        };
+        if !self
+            .session_followups(session)?
+            .iter()
+            .any(|job| job.id == id)
+        {
+            return Ok(ToolResult::failure(format!(
+                "No follow-up '{id}' in this conversation"
+            )));
+        }
+        if let Some(blocked) = self.enforce_mutation_allowed() {
+            return Ok(blocked);
+        }
+        cron::remove_job(&self.config, id)?;
+        Ok(ToolResult {
+            success: true,
+            output: format!("Cancelled follow-up {id}"),
+            error: None,
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for ScheduleFollowupTool {
+    fn name(&self) -> &str {
+        "schedule_followup"
+    }
+
+    fn description(&self) -> &str {
+        "Schedule a one-time follow-up in the current chat, e.g. 'check the build again in 20 minutes and tell me'. \
+         At the given time the agent runs the prompt with this conversation as context and replies here. \
+         Actions: create (prompt, when, optional thread_ts), list, cancel (id). Only this conversation's follow-ups are visible."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["create", "list", "cancel"]
+                },
+                "prompt": {
+                    "type": "string",
+                    "description": "What to do when the follow-up fires (e.g. 'check the CI status of PR 42 and report')"
+                },
+                "when": {
+                    "type": "string",
+                    "description": "When to fire: 'in 20 minutes', 'tomorrow 9am', 'friday 14:00' (configured timezone) or RFC3339"
+                },
+                "name": { "type": "string" },
+                "thread_ts": {
+                    "type": "string",
+                    "description": "Slack only: timestamp of the thread to reply in (e.g. 1712345678.000100); defaults to the current thread"
+                },
+                "id": {
+                    "type": "string",
+                    "description": "Follow-up id for cancel"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let Some(session) = current_session() else {
+            return Ok(ToolResult::failure(
+                "schedule_followup is only available inside a channel conversation; use cron_add instead",
+            ));
+        };
+
+        match args.get("action").and_then(|v| v.as_str()) {
+            Some("create") => self.handle_create(&session, &args),
+            Some("list") => self.handle_list(&session),
+            Some("cancel") => self.handle_cancel(&session, &args),
+            Some(other) => Ok(ToolResult::failure(format!(
+                "Unknown action '{other}'. Use create/list/cancel."
+            ))),
+            None => Ok(ToolResult::failure("Missing 'action' parameter")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AutonomyConfig;
+    use crate::security::AutonomyLevel;
+    use crate::sessions::with_session;
+    use tempfile::TempDir;
+
+    fn test_tool(tmp: &TempDir) -> ScheduleFollowupTool {
+        let config = Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        };
+        std::fs::create_dir_all(&config.workspace_dir).unwrap();
+        let security = Arc::new(SecurityPolicy::from_config(
+            &config.autonomy,
+            &config.workspace_dir,
+        ));
+        ScheduleFollowupTool::new(security, config)
+    }
+
+    fn session(key: &str, channel: &str) -> SessionContext {
+        SessionContext {
+            session_key: key.into(),
+            channel: channel.into(),
+            reply_target: "chat-42".into(),
+            thread_ts: None,
+            sender: String::new(),
+        }
+    }
+
+    async fn run(
+        tool: &ScheduleFollowupTool,
+        ctx: SessionContext,
+        args: serde_json::Value,
+    ) -> ToolResult {
+        with_session(ctx, tool.execute(args)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn create_binds_job_to_current_session() {
+        let tmp = TempDir::new().unwrap();
+        let tool = test_tool(&tmp);
+        let result = run(
+            &tool,
+            session("telegram_alice", "telegram"),
+            json!({"action": "create", "prompt": "check the build", "when": "in 20 minutes"}),
+        )
+        .await;
+        assert!(result.success, "{:?}", result.error);
+
+        let jobs = cron::list_jobs(&tool.config).unwrap();
+        assert_eq!(jobs.len(), 1);
+        let job = &jobs[0];
+        assert!(job.delete_after_run);
+        assert!(matches!(job.schedule, Schedule::At { .. }));
+        assert_eq!(job.delivery.mode, "announce");
+        assert_eq!(job.delivery.channel.as_deref(), Some("telegram"));
+        assert_eq!(job.delivery.to.as_deref(), Some("chat-42"));
+        assert_eq!(job.delivery.session_key.as_deref(), Some("telegram_alice"));
+    }
+
+    #[tokio::test]
+    async fn slack_followups_target_the_conversation_thread() {
+        let tmp = TempDir::new().unwrap();
+        let tool = test_tool(&tmp);
+        let mut ctx = session("slack_C1_123.001_U1", "slack");
+        ctx.reply_target = "C1".into();
+        ctx.thread_ts = Some("123.001".into());
+        let create = |extra: serde_json::Value| {
+            let mut args = json!({"action": "create", "prompt": "ping", "when": "in 1 hour"});
+            args.as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            args
+        };
+
+        assert!(run(&tool, ctx.clone(), create(json!({}))).await.success);
+        assert!(
+            run(&tool, ctx.clone(), create(json!({"thread_ts": "456.002"})))
+                .await
+                .success
+        );
+        let malformed = run(&tool, ctx.clone(), create(json!({"thread_ts": "456"}))).await;
+        assert!(!malformed.success);
+        assert!(malformed.error.unwrap().contains("thread_ts"));
+        let not_slack = run(
+            &tool,
+            session("telegram_alice", "telegram"),
+            create(json!({"thread_ts": "456.002"})),
+        )
+        .await;
+        assert!(!not_slack.success);
+
+        let mut targets: Vec<_> = cron::list_jobs(&tool.config)
+            .unwrap()
+            .into_iter()
+            .filter_map(|job| job.delivery.to)
+            .collect();
+        targets.sort();
+        assert_eq!(targets, ["C1:123.001", "C1:456.002"]);
+    }
+
+    #[tokio::test]
+    async fn list_and_cancel_are_scoped_to_session() {
+        let tmp = TempDir::new().unwrap();
+        let tool = test_tool(&tmp);
+        let create = json!({"action": "create", "prompt": "ping", "when": "in 1 hour"});
+        assert!(
+            run(&tool, session("telegram_alice", "telegram"), create.clone())
+                .await
+                .success
+        );
+        assert!(
+            run(&tool, session("telegram_bob", "telegram"), create)
+                .await
+                .success
+        );
+        let bob_job = cron::list_jobs(&tool.config)
+            .unwrap()
+            .into_iter()
+            .find(|job| job.delivery.session_key.as_deref() == Some("telegram_bob"))
+            .unwrap();
+
+        let listed = run(
+            &tool,
+            session("telegram_alice", "telegram"),
+            json!({"action": "list"}),
+        )
+        .await;
+        assert!(listed.output.starts_with("Follow-ups (1):"));
+        assert!(!listed.output.contains(&bob_job.id));
+
+        let denied = run(
+            &tool,
+            session("telegram_alice", "telegram"),
+            json!({"action": "cancel", "id": bob_job.id}),
+        )
+        .await;
+        assert!(!denied.success);
+        assert_eq!(cron::list_jobs(&tool.config).unwrap().len(), 2);
+
+        let cancelled = run(
+            &tool,
+            session("telegram_bob", "telegram"),
+            json!({"action": "cancel", "id": bob_job.id}),
+        )
+        .await;
+        assert!(cancelled.success);
+        assert_eq!(cron::list_jobs(&tool.config).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn requires_channel_session_and_supported_channel() {
+        let tmp = TempDir::new().unwrap();
+        let tool = test_tool(&tmp);
+        let args = json!({"action": "create", "prompt": "ping", "when": "in 1 hour"});
+
+        let outside = tool.execute(args.clone()).await.unwrap();
+        assert!(!outside.success);
+        assert!(outside.error.unwrap().contains("channel conversation"));
+
+        let unsupported = run(&tool, session("irc_alice", "irc"), args).await;
+        assert!(!unsupported.success);
+        assert!(cron::list_jobs(&tool.config).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn read_only_mode_blocks_create() {
+        let tmp = TempDir::new().unwrap();
+        let mut tool = test_tool(&tmp);
+        tool.security = Arc::new(SecurityPolicy::from_config(
+            &AutonomyConfig {
+                level: AutonomyLevel::ReadOnly,
+                ..AutonomyConfig::default()
+            },
+            &tool.config.workspace_dir,
+        ));
+        let result = run(
+            &tool,
+            session("telegram_alice", "telegram"),
+            json!({"action": "create", "prompt": "ping", "when": "in 1 hour"}),
+        )
+        .await;
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+}
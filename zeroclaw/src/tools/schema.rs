@@ -170,6 +170,20 @@ impl SchemaCleanr {
         Ok(())
     }
 
+    /// Check tool-call arguments against a tool's declared parameter schema.
+    ///
+    /// Returns one message per violation (empty when the arguments are valid).
+    /// Only the keywords tool schemas actually use are checked: `type`,
+    /// `required`, `properties`, `additionalProperties: false`, `enum` and
+    /// `items`. Subschemas with `$ref`, `anyOf` or `oneOf` are accepted as-is.
+    /// A `null` value for an optional property is treated as absent, since
+    /// models commonly send explicit nulls for parameters they skip.
+    pub fn validate_arguments(schema: &Value, args: &Value) -> Vec<String> {
+        let mut violations = Vec::new();
+        Self::check_value(schema, args, "", &mut violations);
+        violations
+    }
+
     // --------------------------------------------------------------------
     // Internal implementation
     // --------------------------------------------------------------------
@@ -533,6 +547,144 @@ impl SchemaCleanr {
         }
         target
     }
+
+    /// Validate `value` against `schema`, recording violations under `path`.
+    fn check_value(schema: &Value, value: &Value, path: &str, violations: &mut Vec<String>) {
+        let Some(obj) = schema.as_object() else {
+            return;
+        };
+        if ["$ref", "anyOf", "oneOf"]
+            .iter()
+            .any(|k| obj.contains_key(*k))
+        {
+            return;
+        }
+
+        if let Some(expected) = obj.get("type") {
+            let allowed: Vec<&str> = match expected {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !allowed.is_empty() && !allowed.iter().any(|t| Self::matches_type(t, value)) {
+                violations.push(format!(
+                    "{} must be {}, got {}",
+                    Self::describe_path(path),
+                    allowed.join(" or "),
+                    Self::json_type_name(value)
+                ));
+                return;
+            }
+        }
+
+        if let Some(Value::Array(options)) = obj.get("enum") {
+            if !options.contains(value) {
+                let options = options
+                    .iter()
+                    .map(Value::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                violations.push(format!(
+                    "{} must be one of [{options}], got {value}",
+                    Self::describe_path(path)
+                ));
+            }
+        }
+
+        match value {
+            Value::Object(map) => Self::check_object(obj, map, path, violations),
+            Value::Array(items) => {
+                if let Some(item_schema) = obj.get("items") {
+                    for (i, item) in items.iter().enumerate() {
+                        Self::check_value(item_schema, item, &format!("{path}[{i}]"), violations);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Check `required`, `properties` and `additionalProperties` for an object.
+    fn check_object(
+        schema: &Map<String, Value>,
+        map: &Map<String, Value>,
+        path: &str,
+        violations: &mut Vec<String>,
+    ) {
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|r| r.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        for name in &required {
+            if map.get(*name).is_none_or(Value::is_null) {
+                violations.push(format!(
+                    "missing required property {}",
+                    Self::describe_path(&Self::join_path(path, name))
+                ));
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let closed = matches!(schema.get("additionalProperties"), Some(Value::Bool(false)));
+
+        for (name, value) in map {
+            let child_path = Self::join_path(path, name);
+            match properties.and_then(|p| p.get(name)) {
+                Some(_) if value.is_null() && !required.contains(&name.as_str()) => {}
+                Some(prop_schema) => Self::check_value(prop_schema, value, &child_path, violations),
+                None if closed => violations.push(format!(
+                    "unknown property {}",
+                    Self::describe_path(&child_path)
+                )),
+                None => {}
+            }
+        }
+    }
+
+    fn matches_type(expected: &str, value: &Value) -> bool {
+        match expected {
+            "string" => value.is_string(),
+            "boolean" => value.is_boolean(),
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "null" => value.is_null(),
+            "number" => value.is_number(),
+            "integer" => {
+                value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|f| f.fract() == 0.0)
+            }
+            // Unknown type names are not ours to reject.
+            _ => true,
+        }
+    }
+
+    fn json_type_name(value: &Value) -> &'static str {
+        match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(n) if n.is_f64() => "number",
+            Value::Number(_) => "integer",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
+    fn join_path(parent: &str, name: &str) -> String {
+        if parent.is_empty() {
+            name.to_string()
+        } else {
+            format!("{parent}.{name}")
+        }
+    }
+
+    fn describe_path(path: &str) -> String {
+        if path.is_empty() {
+            "arguments".to_string()
+        } else {
+            format!("'{path}'")
+        }
+    }
 }
 
 #[cfg(test)]
@@ -835,4 +987,71 @@ mod tests {
         assert_eq!(cleaned["not"]["type"], "integer");
         assert!(cleaned["not"].get("minimum").is_none());
     }
+
+    fn search_schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string" },
+                "limit": { "type": "integer" },
+                "mode": { "type": "string", "enum": ["fast", "full"] },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["query"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn test_validate_arguments_accepts_valid_args() {
+        let args = json!({"query": "rust", "limit": 5, "mode": "fast", "tags": ["a"]});
+        assert!(SchemaCleanr::validate_arguments(&search_schema(), &args).is_empty());
+
+        // Whole-number floats count as integers; explicit null means "omitted".
+        let args = json!({"query": "rust", "limit": 5.0, "mode": null});
+        assert!(SchemaCleanr::validate_arguments(&search_schema(), &args).is_empty());
+    }
+
+    #[test]
+    fn test_validate_arguments_reports_each_violation() {
+        let args = json!({"limit": "five", "mode": "slow", "tags": ["a", 2], "extra": true});
+        let mut violations = SchemaCleanr::validate_arguments(&search_schema(), &args);
+        violations.sort();
+
+        assert_eq!(
+            violations,
+            vec![
+                "'limit' must be integer, got string".to_string(),
+                r#"'mode' must be one of ["fast", "full"], got "slow""#.to_string(),
+                "'tags[1]' must be string, got integer".to_string(),
+                "missing required property 'query'".to_string(),
+                "unknown property 'extra'".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_arguments_skips_unions_and_open_objects() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "value": { "anyOf": [{ "type": "string" }, { "type": "integer" }] },
+                "nested": {
+                    "type": "object",
+                    "properties": { "n": { "type": ["integer", "null"] } },
+                    "required": ["n"]
+                }
+            }
+        });
+
+        let args = json!({"value": [1], "nested": {"n": 1, "other": "ok"}, "free": 1});
+        assert!(SchemaCleanr::validate_arguments(&schema, &args).is_empty());
+
+        let violations = SchemaCleanr::validate_arguments(&schema, &json!({"nested": {}}));
+        assert_eq!(violations, vec!["missing required property 'nested.n'"]);
+        assert_eq!(
+            SchemaCleanr::validate_arguments(&schema, &json!("text")),
+            vec!["arguments must be object, got string"]
+        );
+    }
 }
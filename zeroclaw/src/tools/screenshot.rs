@@ -1,28 +1,54 @@
 use super::traits::{Tool, ToolResult};
+use crate::attachments::AttachmentStore;
+use crate::config::MultimodalConfig;
 use crate::security::SecurityPolicy;
+use anyhow::Context;
 use async_trait::async_trait;
 use serde_json::json;
-use std::fmt::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
 /// Maximum time to wait for a screenshot command to complete.
 const SCREENSHOT_TIMEOUT_SECS: u64 = 15;
-/// Maximum base64 payload size to return (2 MB of base64 ≈ 1.5 MB image).
-const MAX_BASE64_BYTES: usize = 2_097_152;
+/// Largest capture the tool will decode (64 MB).
+const MAX_RAW_BYTES: u64 = 64 * 1024 * 1024;
+/// Lowest JPEG quality the size budget may push a capture down to.
+const MIN_JPEG_QUALITY: u8 = 30;
+/// Shortest long edge the size budget may push a capture down to.
+const MIN_DIMENSION: u32 = 64;
 
 /// Tool for capturing screenshots using platform-native commands.
 ///
 /// macOS: `screencapture`
 /// Linux: tries `gnome-screenshot`, `scrot`, `import` (`ImageMagick`) in order.
+///
+/// The capture is downscaled and re-encoded as JPEG (`[multimodal]
+/// screenshot_max_dimension` / `screenshot_jpeg_quality`), kept within
+/// `max_image_size_mb`, and stored as an attachment. The result is a JSON
+/// description; the agent loop attaches the image itself for vision providers.
 pub struct ScreenshotTool {
     security: Arc<SecurityPolicy>,
+    store: AttachmentStore,
+    max_dimension: u32,
+    jpeg_quality: u8,
+    max_bytes: usize,
 }
 
 impl ScreenshotTool {
-    pub fn new(security: Arc<SecurityPolicy>) -> Self {
-        Self { security }
+    pub fn new(
+        security: Arc<SecurityPolicy>,
+        multimodal: &MultimodalConfig,
+        retention_hours: u64,
+    ) -> Self {
+        let (_, max_image_size_mb) = multimodal.effective_limits();
+        Self {
+            store: AttachmentStore::new(&security.workspace_dir, retention_hours),
+            security,
+            max_dimension: multimodal.screenshot_max_dimension,
+            jpeg_quality: multimodal.screenshot_jpeg_quality,
+            max_bytes: max_image_size_mb.saturating_mul(1024 * 1024),
+        }
     }
 
     /// Determine the screenshot command for the current platform.
@@ -132,7 +158,7 @@ impl ScreenshotTool {
                     });
                 }
 
-                Self::read_and_encode(&output_path).await
+                self.read_and_encode(&output_path).await
             }
             Ok(Err(e)) => Ok(ToolResult {
                 success: false,
@@ -149,64 +175,121 @@ impl ScreenshotTool {
         }
     }
 
-    /// Read the screenshot file and return base64-encoded result.
-    async fn read_and_encode(output_path: &std::path::Path) -> anyhow::Result<ToolResult> {
-        // Check file size before reading to prevent OOM on large screenshots
-        const MAX_RAW_BYTES: u64 = 1_572_864; // ~1.5 MB (base64 expands ~33%)
+    /// Downscale the capture, store it as an attachment and describe it.
+    async fn read_and_encode(&self, output_path: &Path) -> anyhow::Result<ToolResult> {
+        // Check file size before reading to prevent OOM on huge captures
         if let Ok(meta) = tokio::fs::metadata(output_path).await {
             if meta.len() > MAX_RAW_BYTES {
                 return Ok(ToolResult {
-                    success: true,
-                    output: format!(
-                        "Screenshot saved to: {}\nSize: {} bytes (too large to base64-encode inline)",
-                        output_path.display(),
-                        meta.len(),
-                    ),
-                    error: None,
+                    success: false,
+                    output: format!("Screenshot saved to: {}", output_path.display()),
+                    error: Some(format!(
+                        "Screenshot is {} bytes, too large to process",
+                        meta.len()
+                    )),
                 });
             }
         }
 
-        match tokio::fs::read(output_path).await {
-            Ok(bytes) => {
-                use base64::Engine;
-                let size = bytes.len();
-                let mut encoded = base64::engine::general_purpose::STANDARD.encode(&bytes);
-                let truncated = if encoded.len() > MAX_BASE64_BYTES {
-                    encoded.truncate(encoded.floor_char_boundary(MAX_BASE64_BYTES));
-                    true
-                } else {
-                    false
-                };
-
-                let mut output_msg = format!(
-                    "Screenshot saved to: {}\nSize: {size} bytes\nBase64 length: {}",
-                    output_path.display(),
-                    encoded.len(),
-                );
-                if truncated {
-                    output_msg.push_str(" (truncated)");
-                }
-                let mime = match output_path.extension().and_then(|e| e.to_str()) {
-                    Some("jpg" | "jpeg") => "image/jpeg",
-                    Some("bmp") => "image/bmp",
-                    Some("gif") => "image/gif",
-                    Some("webp") => "image/webp",
-                    _ => "image/png",
-                };
-                let _ = write!(output_msg, "\ndata:{mime};base64,{encoded}");
-
-                Ok(ToolResult {
-                    success: true,
-                    output: output_msg,
-                    error: None,
+        let raw = match tokio::fs::read(output_path).await {
+            Ok(raw) => raw,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: format!("Screenshot saved to: {}", output_path.display()),
+                    error: Some(format!("Failed to read screenshot file: {e}")),
                 })
             }
-            Err(e) => Ok(ToolResult {
-                success: false,
-                output: format!("Screenshot saved to: {}", output_path.display()),
-                error: Some(format!("Failed to read screenshot file: {e}")),
-            }),
+        };
+
+        let (max_dimension, quality, max_bytes) =
+            (self.max_dimension, self.jpeg_quality, self.max_bytes);
+        let encoded = tokio::task::spawn_blocking(move || {
+            downscale_screenshot(&raw, max_dimension, quality, max_bytes)
+        })
+        .await?;
+        let encoded = match encoded {
+            Ok(encoded) => encoded,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: format!("Screenshot saved to: {}", output_path.display()),
+                    error: Some(format!("Failed to downscale screenshot: {e}")),
+                })
+            }
+        };
+
+        let filename = output_path.with_extension("jpg");
+        let filename = filename
+            .file_name()
+            .map_or_else(|| "screenshot.jpg".into(), |n| n.to_string_lossy());
+        let attachment = self.store.save(&filename, "image/jpeg", &encoded.bytes)?;
+
+        let result = json!({
+            "attachment": attachment.uri(),
+            "image_path": self.store.data_path(&attachment.id),
+            "mime_type": attachment.mime_type,
+            "width": encoded.width,
+            "height": encoded.height,
+            "size_bytes": attachment.size_bytes,
+            "original_path": output_path,
+        });
+        Ok(ToolResult {
+            success: true,
+            output: serde_json::to_string_pretty(&result)?,
+            error: None,
+        })
+    }
+}
+
+/// A capture re-encoded for a vision model.
+#[derive(Debug)]
+pub struct EncodedScreenshot {
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Decode `raw`, shrink its long edge to `max_dimension` and re-encode it as
+/// JPEG at `quality`. While the result exceeds `max_bytes` the quality is
+/// lowered to [`MIN_JPEG_QUALITY`], then the long edge is halved down to
+/// [`MIN_DIMENSION`]; an image that still does not fit is an error.
+pub fn downscale_screenshot(
+    raw: &[u8],
+    max_dimension: u32,
+    quality: u8,
+    max_bytes: usize,
+) -> anyhow::Result<EncodedScreenshot> {
+    let image = image::load_from_memory(raw).context("screenshot is not a decodable image")?;
+    let long_edge = image.width().max(image.height()).max(1);
+    let mut edge = max_dimension.max(MIN_DIMENSION).min(long_edge);
+    let mut quality = quality.clamp(1, 100);
+
+    loop {
+        let scaled = if edge < long_edge {
+            image.thumbnail(edge, edge)
+        } else {
+            image.clone()
+        };
+        let mut bytes = Vec::new();
+        let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut bytes, quality);
+        image::DynamicImage::ImageRgb8(scaled.to_rgb8()).write_with_encoder(encoder)?;
+        if bytes.len() <= max_bytes {
+            return Ok(EncodedScreenshot {
+                bytes,
+                width: scaled.width(),
+                height: scaled.height(),
+            });
+        }
+
+        if quality > MIN_JPEG_QUALITY {
+            quality = quality.saturating_sub(15).max(MIN_JPEG_QUALITY);
+        } else if edge > MIN_DIMENSION {
+            edge = (edge / 2).max(MIN_DIMENSION);
+        } else {
+            anyhow::bail!(
+                "screenshot does not fit in {max_bytes} bytes even at {edge}px and quality {quality}"
+            );
         }
     }
 }
@@ -218,7 +301,8 @@ impl Tool for ScreenshotTool {
     }
 
     fn description(&self) -> &str {
-        "Capture a screenshot of the current screen. Returns the file path and base64-encoded PNG data."
+        "Capture a screenshot of the current screen. The image is downscaled, saved as an attachment and \
+         described as JSON (attachment, path, dimensions, size); vision models also receive the image."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -262,22 +346,42 @@ mod tests {
         })
     }
 
+    fn test_tool() -> ScreenshotTool {
+        ScreenshotTool::new(test_security(), &MultimodalConfig::default(), 24)
+    }
+
+    /// A phone-sized PNG filled with pseudo-random noise, which JPEG cannot
+    /// compress well.
+    fn fixture_png(width: u32, height: u32) -> Vec<u8> {
+        let mut state: u32 = 0x1234_5678;
+        let image = image::RgbImage::from_fn(width, height, |_, _| {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let [r, g, b, _] = state.to_le_bytes();
+            image::Rgb([r, g, b])
+        });
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgb8(image)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        png
+    }
+
     #[test]
     fn screenshot_tool_name() {
-        let tool = ScreenshotTool::new(test_security());
+        let tool = test_tool();
         assert_eq!(tool.name(), "screenshot");
     }
 
     #[test]
     fn screenshot_tool_description() {
-        let tool = ScreenshotTool::new(test_security());
+        let tool = test_tool();
         assert!(!tool.description().is_empty());
         assert!(tool.description().contains("screenshot"));
     }
 
     #[test]
     fn screenshot_tool_schema() {
-        let tool = ScreenshotTool::new(test_security());
+        let tool = test_tool();
         let schema = tool.parameters_schema();
         assert!(schema["properties"]["filename"].is_object());
         assert!(schema["properties"]["region"].is_object());
@@ -285,7 +389,7 @@ mod tests {
 
     #[test]
     fn screenshot_tool_spec() {
-        let tool = ScreenshotTool::new(test_security());
+        let tool = test_tool();
         let spec = tool.spec();
         assert_eq!(spec.name, "screenshot");
         assert!(spec.parameters.is_object());
@@ -302,7 +406,7 @@ mod tests {
 
     #[tokio::test]
     async fn screenshot_rejects_shell_injection_filename() {
-        let tool = ScreenshotTool::new(test_security());
+        let tool = test_tool();
         let result = tool
             .execute(json!({"filename": "test'injection.png"}))
             .await
@@ -320,4 +424,41 @@ mod tests {
             "Command should contain the output path"
         );
     }
+
+    #[test]
+    fn downscale_keeps_aspect_ratio_and_encodes_jpeg() {
+        let png = fixture_png(1080, 2400);
+
+        let encoded = downscale_screenshot(&png, 768, 80, usize::MAX).unwrap();
+
+        assert_eq!((encoded.width, encoded.height), (346, 768));
+        assert!(encoded.bytes.starts_with(&[0xff, 0xd8, 0xff]));
+        assert!(encoded.bytes.len() < png.len());
+    }
+
+    #[test]
+    fn downscale_never_upscales_small_captures() {
+        let encoded = downscale_screenshot(&fixture_png(200, 100), 768, 80, usize::MAX).unwrap();
+        assert_eq!((encoded.width, encoded.height), (200, 100));
+    }
+
+    #[test]
+    fn downscale_shrinks_until_size_budget_fits() {
+        let png = fixture_png(1080, 2400);
+        let unconstrained = downscale_screenshot(&png, 768, 90, usize::MAX).unwrap();
+        let budget = unconstrained.bytes.len() / 8;
+
+        let encoded = downscale_screenshot(&png, 768, 90, budget).unwrap();
+
+        assert!(encoded.bytes.len() <= budget);
+        assert!(encoded.height < 768);
+
+        let err = downscale_screenshot(&png, 768, 90, 64).unwrap_err();
+        assert!(err.to_string().contains("does not fit in 64 bytes"));
+    }
+
+    #[test]
+    fn downscale_rejects_non_images() {
+        assert!(downscale_screenshot(b"not an image", 768, 80, usize::MAX).is_err());
+    }
 }
@@ -0,0 +1,191 @@
+use super::traits::{Tool, ToolResult};
+use crate::sessions::{blocking_read_only, current_session, SqliteSessionStore};
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+/// Let the agent read older summaries of the conversation it is serving
+pub struct SessionRecallTool {
+    workspace_dir: PathBuf,
+}
+
+impl SessionRecallTool {
+    pub fn new(workspace_dir: PathBuf) -> Self {
+        Self { workspace_dir }
+    }
+}
+
+#[async_trait]
+impl Tool for SessionRecallTool {
+    fn name(&self) -> &str {
+        "session_recall"
+    }
+
+    fn description(&self) -> &str {
+        "Read earlier summaries of the current conversation, newest first. Use this when the user refers to something discussed long ago that is no longer in the recent turns."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Only return summaries containing this text (case-insensitive)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max summaries to return (default: 5)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let Some(session) = current_session() else {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("session_recall only works inside a channel conversation".into()),
+            });
+        };
+
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .map(str::to_lowercase);
+
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(5, |v| v as usize)
+            .max(1);
+
+        if !SqliteSessionStore::db_path(&self.workspace_dir).exists() {
+            return Ok(ToolResult {
+                success: true,
+                output: "No summaries have been recorded for this conversation yet.".into(),
+                error: None,
+            });
+        }
+
+        let key = session.session_key.clone();
+        let summaries = blocking_read_only(&self.workspace_dir, move |store| {
+            store.summary_history(&key, None)
+        })
+        .await;
+        let summaries = match summaries {
+            Ok(summaries) => summaries,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Summary lookup failed: {e}")),
+                })
+            }
+        };
+
+        let matching: Vec<_> = summaries
+            .iter()
+            .filter(|s| {
+                query
+                    .as_deref()
+                    .is_none_or(|q| s.summary.to_lowercase().contains(q))
+            })
+            .take(limit)
+            .collect();
+
+        if matching.is_empty() {
+            let output = match &query {
+                Some(q) => format!("No earlier summary of this conversation mentions '{q}'."),
+                None => "No summaries have been recorded for this conversation yet.".into(),
+            };
+            return Ok(ToolResult {
+                success: true,
+                output,
+                error: None,
+            });
+        }
+
+        let mut output = format!(
+            "{} summary(ies) of this conversation, newest first:\n",
+            matching.len()
+        );
+        for summary in matching {
+            let _ = write!(
+                output,
+                "\n[{}, after {} turn(s)]\n{}\n",
+                summary.created_at,
+                summary.message_count_at,
+                summary.summary.trim()
+            );
+        }
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sessions::{with_session, SessionContext};
+    use tempfile::TempDir;
+
+    fn session(key: &str) -> SessionContext {
+        SessionContext {
+            session_key: key.into(),
+            channel: "telegram".into(),
+            reply_target: "alice".into(),
+            thread_ts: None,
+            sender: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn recall_requires_a_channel_session() {
+        let tmp = TempDir::new().unwrap();
+        let tool = SessionRecallTool::new(tmp.path().to_path_buf());
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(!result.success);
+    }
+
+    #[tokio::test]
+    async fn recall_lists_summaries_of_the_current_session_only() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        for (key, summary) in [
+            ("telegram_alice", "- planned the Lisbon trip"),
+            ("telegram_alice", "- booked flights for March"),
+            ("telegram_bob", "- Lisbon hotel budget"),
+        ] {
+            store.append_message(key, "user", "hello").unwrap();
+            store.set_summary(key, summary).unwrap();
+        }
+        let tool = SessionRecallTool::new(tmp.path().to_path_buf());
+
+        let result = with_session(session("telegram_alice"), tool.execute(json!({})))
+            .await
+            .unwrap();
+        assert!(result.success);
+        let flights = result.output.find("booked flights").unwrap();
+        let trip = result.output.find("Lisbon trip").unwrap();
+        assert!(flights < trip, "{}", result.output);
+        assert!(!result.output.contains("hotel budget"));
+
+        let result = with_session(
+            session("telegram_alice"),
+            tool.execute(json!({"query": "lisbon"})),
+        )
+        .await
+        .unwrap();
+        assert!(result.output.contains("Lisbon trip"));
+        assert!(!result.output.contains("booked flights"));
+    }
+}
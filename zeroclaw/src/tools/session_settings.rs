@@ -0,0 +1,275 @@
+use super::traits::{Tool, ToolResult};
+use crate::config::Config;
+use crate::security::SecurityPolicy;
+use crate::sessions::{blocking_writable, current_session, SessionSettings, SessionSettingsPatch};
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Per-conversation overrides of model, temperature and system prompt
+/// ("use the cheap model for this chat from now on").
+///
+/// Settings are stored against the current session key and applied by the
+/// channel runtime on the next turn; other conversations are unaffected.
+pub struct SessionSettingsTool {
+    security: Arc<SecurityPolicy>,
+    config: Config,
+}
+
+impl SessionSettingsTool {
+    pub fn new(security: Arc<SecurityPolicy>, config: Config) -> Self {
+        Self { security, config }
+    }
+
+    async fn settings(&self, key: &str) -> anyhow::Result<SessionSettings> {
+        let key = key.to_string();
+        blocking_writable(&self.config.workspace_dir, move |store| {
+            store.settings(&key)
+        })
+        .await
+    }
+
+    async fn set_settings(&self, key: &str, settings: SessionSettings) -> anyhow::Result<()> {
+        let key = key.to_string();
+        blocking_writable(&self.config.workspace_dir, move |store| {
+            store.set_settings(&key, &settings)
+        })
+        .await
+    }
+
+    fn describe(settings: &SessionSettings) -> String {
+        if settings.is_empty() {
+            return "No overrides; this conversation uses the default settings.".to_string();
+        }
+        let mut lines = Vec::new();
+        if let Some(model) = &settings.model {
+            lines.push(format!("model: {model}"));
+        }
+        if let Some(temperature) = settings.temperature {
+            lines.push(format!("temperature: {temperature}"));
+        }
+        if let Some(max_tokens) = settings.max_tokens {
+            lines.push(format!("max_tokens: {max_tokens}"));
+        }
+        if let Some(extra) = &settings.system_prompt_extra {
+            lines.push(format!("system_prompt_extra: {extra}"));
+        }
+        lines.join("\n")
+    }
+
+    fn enforce_mutation_allowed(&self) -> Option<ToolResult> {
+        if !self.security.can_act() {
+            return Some(ToolResult::failure(
+                "Security policy: read-only mode, cannot change session settings",
+            ));
+        }
+        if !self.security.record_action() {
+            return Some(ToolResult::failure(
+                "Rate limit exceeded: action budget exhausted",
+            ));
+        }
+        None
+    }
+
+    async fn handle_set(&self, key: &str, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let patch: SessionSettingsPatch = match serde_json::from_value(args) {
+            Ok(patch) => patch,
+            Err(e) => return Ok(ToolResult::failure(format!("Invalid settings: {e}"))),
+        };
+        let mut settings = self.settings(key).await?;
+        settings.apply(patch);
+        if let Err(e) = settings.validate(&self.config).await {
+            return Ok(ToolResult::failure(format!("{e:#}")));
+        }
+        if let Some(blocked) = self.enforce_mutation_allowed() {
+            return Ok(blocked);
+        }
+        self.set_settings(key, settings.clone()).await?;
+        Ok(ToolResult {
+            success: true,
+            output: format!(
+                "Session settings updated (effective from the next message):\n{}",
+                Self::describe(&settings)
+            ),
+            error: None,
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for SessionSettingsTool {
+    fn name(&self) -> &str {
+        "session_settings"
+    }
+
+    fn description(&self) -> &str {
+        "Show or change settings for the current conversation only: model, temperature (0-2), \
+         max_tokens and extra system prompt instructions. Actions: get, set (only the given fields \
+         change; null clears one), reset (back to defaults). Changes apply from the next message."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["get", "set", "reset"]
+                },
+                "model": {
+                    "type": ["string", "null"],
+                    "description": "Model ID to pin for this conversation"
+                },
+                "temperature": {
+                    "type": ["number", "null"],
+                    "minimum": 0,
+                    "maximum": 2
+                },
+                "max_tokens": {
+                    "type": ["integer", "null"],
+                    "minimum": 1
+                },
+                "system_prompt_extra": {
+                    "type": ["string", "null"],
+                    "description": "Extra instructions for this conversation, e.g. 'keep replies terse'"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, mut args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let Some(session) = current_session() else {
+            return Ok(ToolResult::failure(
+                "session_settings is only available inside a channel conversation",
+            ));
+        };
+        let key = session.session_key.as_str();
+
+        let action = args
+            .as_object_mut()
+            .and_then(|obj| obj.remove("action"))
+            .and_then(|v| v.as_str().map(ToString::to_string));
+        match action.as_deref() {
+            Some("get") => Ok(ToolResult {
+                success: true,
+                output: Self::describe(&self.settings(key).await?),
+                error: None,
+            }),
+            Some("set") => self.handle_set(key, args).await,
+            Some("reset") => {
+                if let Some(blocked) = self.enforce_mutation_allowed() {
+                    return Ok(blocked);
+                }
+                self.set_settings(key, SessionSettings::default()).await?;
+                Ok(ToolResult {
+                    success: true,
+                    output: "Session settings cleared; defaults apply from the next message."
+                        .to_string(),
+                    error: None,
+                })
+            }
+            Some(other) => Ok(ToolResult::failure(format!(
+                "Unknown action '{other}'. Use get/set/reset."
+            ))),
+            None => Ok(ToolResult::failure("Missing 'action' parameter")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sessions::{with_session, SessionContext, SqliteSessionStore};
+    use tempfile::TempDir;
+
+    fn test_tool(tmp: &TempDir) -> SessionSettingsTool {
+        let config = Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        };
+        std::fs::create_dir_all(&config.workspace_dir).unwrap();
+        let security = Arc::new(SecurityPolicy::from_config(
+            &config.autonomy,
+            &config.workspace_dir,
+        ));
+        SessionSettingsTool::new(security, config)
+    }
+
+    async fn run(tool: &SessionSettingsTool, key: &str, args: serde_json::Value) -> ToolResult {
+        let ctx = SessionContext {
+            session_key: key.into(),
+            channel: "telegram".into(),
+            reply_target: "chat-42".into(),
+            thread_ts: None,
+            sender: String::new(),
+        };
+        with_session(ctx, tool.execute(args)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn set_merges_fields_for_current_session_only() {
+        let tmp = TempDir::new().unwrap();
+        let tool = test_tool(&tmp);
+
+        let set = run(
+            &tool,
+            "telegram_alice",
+            json!({"action": "set", "model": "openai/gpt-4o-mini", "temperature": 0.2}),
+        )
+        .await;
+        assert!(set.success, "{:?}", set.error);
+        let set = run(
+            &tool,
+            "telegram_alice",
+            json!({"action": "set", "temperature": null, "system_prompt_extra": "Be terse."}),
+        )
+        .await;
+        assert!(set.success, "{:?}", set.error);
+
+        let store = SqliteSessionStore::open(&tool.config.workspace_dir).unwrap();
+        let settings = store.settings("telegram_alice").unwrap();
+        assert_eq!(settings.model.as_deref(), Some("openai/gpt-4o-mini"));
+        assert_eq!(settings.temperature, None);
+        assert_eq!(settings.system_prompt_extra.as_deref(), Some("Be terse."));
+        assert!(store.settings("telegram_bob").unwrap().is_empty());
+
+        let reset = run(&tool, "telegram_alice", json!({"action": "reset"})).await;
+        assert!(reset.success);
+        assert!(store.settings("telegram_alice").unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn invalid_values_are_rejected_without_saving() {
+        let tmp = TempDir::new().unwrap();
+        let tool = test_tool(&tmp);
+
+        let result = run(
+            &tool,
+            "telegram_alice",
+            json!({"action": "set", "temperature": 3.5}),
+        )
+        .await;
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("between 0 and 2"));
+
+        let result = run(
+            &tool,
+            "telegram_alice",
+            json!({"action": "set", "temperature": "hot"}),
+        )
+        .await;
+        assert!(!result.success);
+        assert!(tool.settings("telegram_alice").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn requires_channel_session() {
+        let tmp = TempDir::new().unwrap();
+        let tool = test_tool(&tmp);
+        let result = tool.execute(json!({"action": "get"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("channel conversation"));
+    }
+}
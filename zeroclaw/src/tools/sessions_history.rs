@@ -0,0 +1,317 @@
+use super::sessions_list::{fit_budget, relative_to, render_array, OUTPUT_BUDGET};
+use super::traits::{Tool, ToolResult};
+use crate::providers::Role;
+use crate::sessions::{blocking_read_only, visible_session, HistoryFilter, SqliteSessionStore};
+use crate::util::truncate_with_ellipsis;
+use async_trait::async_trait;
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use serde_json::json;
+use std::path::PathBuf;
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 200;
+const DEFAULT_MAX_CHARS: usize = 300;
+
+/// Let the agent read back the turns of one past conversation
+pub struct SessionsHistoryTool {
+    workspace_dir: PathBuf,
+    cross_session: bool,
+}
+
+impl SessionsHistoryTool {
+    pub fn new(workspace_dir: PathBuf) -> Self {
+        Self {
+            workspace_dir,
+            cross_session: false,
+        }
+    }
+
+    /// Read any stored session instead of only the current one.
+    pub fn with_cross_session(mut self, cross_session: bool) -> Self {
+        self.cross_session = cross_session;
+        self
+    }
+}
+
+/// `since` as RFC 3339 or a `YYYY-MM-DD` date, taken as local midnight.
+fn parse_since(since: &str) -> Option<DateTime<Local>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(since) {
+        return Some(at.with_timezone(&Local));
+    }
+    let midnight = NaiveDate::parse_from_str(since, "%Y-%m-%d")
+        .ok()?
+        .and_hms_opt(0, 0, 0)?;
+    Local.from_local_datetime(&midnight).earliest()
+}
+
+#[async_trait]
+impl Tool for SessionsHistoryTool {
+    fn name(&self) -> &str {
+        "sessions_history"
+    }
+
+    fn description(&self) -> &str {
+        "Read the most recent turns of this conversation (or another one when cross-session search is enabled), oldest first. Long messages are truncated."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "session": {
+                    "type": "string",
+                    "description": "Session key from sessions_list (default: this conversation)"
+                },
+                "role": {
+                    "type": "string",
+                    "enum": ["user", "assistant"],
+                    "description": "Only turns from this side"
+                },
+                "since": {
+                    "type": "string",
+                    "description": "Only turns at or after this time, RFC 3339 or YYYY-MM-DD"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max turns to return (default: 20, max: 200)"
+                },
+                "max_chars_per_message": {
+                    "type": "integer",
+                    "description": "Truncate each message to this many characters (default: 300)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let visible = visible_session(self.cross_session);
+        let key = match args.get("session").and_then(|v| v.as_str()) {
+            Some(key) => key.to_string(),
+            None => visible
+                .clone()
+                .or_else(|| crate::sessions::current_session().map(|s| s.session_key))
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Missing 'session' parameter outside a channel conversation")
+                })?,
+        };
+        if visible.as_ref().is_some_and(|visible| *visible != key) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(
+                    "Only this conversation's history is readable here; \
+                     set [memory] cross_session_search = true to read other sessions"
+                        .into(),
+                ),
+            });
+        }
+
+        let role = match args.get("role").and_then(|v| v.as_str()) {
+            None => None,
+            Some(role) => match Role::from(role) {
+                role @ (Role::User | Role::Assistant) => Some(role),
+                _ => anyhow::bail!("'role' must be \"user\" or \"assistant\""),
+            },
+        };
+
+        let since = match args.get("since").and_then(|v| v.as_str()) {
+            None => None,
+            Some(since) => Some(parse_since(since.trim()).ok_or_else(|| {
+                anyhow::anyhow!("'since' must be an RFC 3339 time or YYYY-MM-DD date")
+            })?),
+        };
+
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(DEFAULT_LIMIT, |v| (v as usize).clamp(1, MAX_LIMIT));
+
+        #[allow(clippy::cast_possible_truncation)]
+        let max_chars = args
+            .get("max_chars_per_message")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(DEFAULT_MAX_CHARS, |v| (v as usize).max(1));
+
+        if !SqliteSessionStore::db_path(&self.workspace_dir).exists() {
+            return Ok(ToolResult {
+                success: true,
+                output: "No past conversations have been recorded yet.".into(),
+                error: None,
+            });
+        }
+
+        let filter = HistoryFilter {
+            role,
+            since,
+            limit: Some(limit),
+        };
+        let lookup = key.clone();
+        let loaded = blocking_read_only(&self.workspace_dir, move |store| {
+            store.load_history_filtered(&lookup, &filter)
+        })
+        .await;
+
+        match loaded {
+            Ok((messages, _)) if messages.is_empty() => Ok(ToolResult {
+                success: true,
+                output: format!("No turns in session {key} matched those filters."),
+                error: None,
+            }),
+            Ok((messages, total)) => {
+                let now = Utc::now();
+                // Newest first, so the budget drops the oldest turns.
+                let entries: Vec<String> = messages
+                    .iter()
+                    .rev()
+                    .map(|m| {
+                        json!({
+                            "role": m.role,
+                            "content": truncate_with_ellipsis(&m.content, max_chars),
+                            "when": relative_to(&m.created_at, now),
+                        })
+                        .to_string()
+                    })
+                    .collect();
+                let mut shown = fit_budget(entries, OUTPUT_BUDGET);
+                shown.reverse();
+                Ok(ToolResult {
+                    success: true,
+                    output: render_array(&shown, total - shown.len()),
+                    error: None,
+                })
+            }
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Loading session history failed: {e}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn history(output: &str) -> (Vec<serde_json::Value>, Option<&str>) {
+        let (array, trailer) = output
+            .split_once('\n')
+            .map_or((output, None), |(a, t)| (a, Some(t)));
+        let entries: serde_json::Value = serde_json::from_str(array).unwrap();
+        (entries.as_array().unwrap().clone(), trailer)
+    }
+
+    #[tokio::test]
+    async fn history_truncates_messages_and_filters_by_role() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        store.append_message("k", "user", &"x".repeat(50)).unwrap();
+        store.append_message("k", "assistant", "short").unwrap();
+        store.append_message("k", "user", "second").unwrap();
+
+        let tool = SessionsHistoryTool::new(tmp.path().to_path_buf());
+        let result = tool
+            .execute(json!({"session": "k", "role": "user", "max_chars_per_message": 10}))
+            .await
+            .unwrap();
+        let (entries, trailer) = history(&result.output);
+        assert_eq!(trailer, None);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0]["content"],
+            truncate_with_ellipsis(&"x".repeat(50), 10)
+        );
+        assert_eq!(entries[1]["content"], "second");
+        assert_eq!(entries[1]["when"], "now");
+        assert!(entries.iter().all(|e| e["role"] == "user"));
+    }
+
+    #[tokio::test]
+    async fn history_budget_keeps_the_latest_turns_and_counts_the_rest() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        for i in 0..30 {
+            store
+                .append_message("k", "user", &format!("{i:02}{}", "y".repeat(400)))
+                .unwrap();
+        }
+
+        let tool = SessionsHistoryTool::new(tmp.path().to_path_buf());
+        let result = tool
+            .execute(json!({"session": "k", "limit": 25, "max_chars_per_message": 1000}))
+            .await
+            .unwrap();
+        let (entries, trailer) = history(&result.output);
+        let array_len = result.output.split('\n').next().unwrap().chars().count();
+        assert!(array_len <= OUTPUT_BUDGET);
+        // Each entry is a bit over 400 characters, so nine of them fit.
+        assert_eq!(entries.len(), 9);
+        assert!(entries[0]["content"].as_str().unwrap().starts_with("21"));
+        assert!(entries[8]["content"].as_str().unwrap().starts_with("29"));
+        assert_eq!(trailer, Some("(21 more not shown)"));
+    }
+
+    #[tokio::test]
+    async fn channel_turns_cannot_read_other_sessions() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        store
+            .append_message("alice", "user", "secret plan")
+            .unwrap();
+        store.append_message("bob", "user", "hello").unwrap();
+        let bob = crate::sessions::SessionContext {
+            session_key: "bob".into(),
+            channel: "telegram".into(),
+            reply_target: "bob".into(),
+            thread_ts: None,
+            sender: "bob".into(),
+        };
+
+        let tool = SessionsHistoryTool::new(tmp.path().to_path_buf());
+        let denied =
+            crate::sessions::with_session(bob.clone(), tool.execute(json!({"session": "alice"})))
+                .await
+                .unwrap();
+        assert!(!denied.success);
+        assert!(denied.error.unwrap().contains("cross_session_search"));
+        let own = crate::sessions::with_session(bob.clone(), tool.execute(json!({})))
+            .await
+            .unwrap();
+        assert_eq!(history(&own.output).0[0]["content"], "hello");
+
+        let tool = tool.with_cross_session(true);
+        let allowed = crate::sessions::with_session(bob, tool.execute(json!({"session": "alice"})))
+            .await
+            .unwrap();
+        assert_eq!(history(&allowed.output).0[0]["content"], "secret plan");
+    }
+
+    #[tokio::test]
+    async fn invalid_filters_are_errors() {
+        let tmp = TempDir::new().unwrap();
+        let tool = SessionsHistoryTool::new(tmp.path().to_path_buf());
+        assert!(tool.execute(json!({})).await.is_err());
+        assert!(tool
+            .execute(json!({"session": "k", "role": "system"}))
+            .await
+            .is_err());
+        assert!(tool
+            .execute(json!({"session": "k", "since": "last week"}))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn since_accepts_rfc3339_and_dates() {
+        let at = parse_since("2026-03-01T12:00:00Z").unwrap();
+        assert_eq!(
+            at.with_timezone(&Utc).to_rfc3339(),
+            "2026-03-01T12:00:00+00:00"
+        );
+        let day = parse_since("2026-03-01").unwrap();
+        assert_eq!(day.format("%Y-%m-%d %H:%M").to_string(), "2026-03-01 00:00");
+        assert!(parse_since("yesterday").is_none());
+    }
+}
@@ -0,0 +1,283 @@
+use super::traits::{Tool, ToolResult};
+use crate::sessions::{blocking_read_only, visible_session, SessionListFilter, SqliteSessionStore};
+use async_trait::async_trait;
+use chrono::{DateTime, Local, Utc};
+use serde_json::json;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+/// Characters of JSON the tool returns at most, before the trailer.
+pub(super) const OUTPUT_BUDGET: usize = 4000;
+
+/// Let the agent list past channel conversations
+pub struct SessionsListTool {
+    workspace_dir: PathBuf,
+    cross_session: bool,
+}
+
+impl SessionsListTool {
+    pub fn new(workspace_dir: PathBuf) -> Self {
+        Self {
+            workspace_dir,
+            cross_session: false,
+        }
+    }
+
+    /// List every stored session instead of only the current one.
+    pub fn with_cross_session(mut self, cross_session: bool) -> Self {
+        self.cross_session = cross_session;
+        self
+    }
+}
+
+/// Leading `entries` whose JSON array fits in `budget` characters. The
+/// first entry is always kept so an oversized one still shows up.
+pub(super) fn fit_budget(entries: Vec<String>, budget: usize) -> Vec<String> {
+    // Brackets around the array plus one separator per entry after the first.
+    let mut used = 2;
+    let mut kept = Vec::new();
+    for entry in entries {
+        let cost = entry.chars().count() + usize::from(!kept.is_empty());
+        if !kept.is_empty() && used + cost > budget {
+            break;
+        }
+        used += cost;
+        kept.push(entry);
+    }
+    kept
+}
+
+/// `entries` as a JSON array, followed by a note when `hidden` more
+/// matched but were left out.
+pub(super) fn render_array(entries: &[String], hidden: usize) -> String {
+    let mut output = format!("[{}]", entries.join(","));
+    if hidden > 0 {
+        let _ = write!(output, "\n({hidden} more not shown)");
+    }
+    output
+}
+
+/// RFC 3339 `timestamp` as a distance from `now`, or unchanged if it doesn't parse.
+pub(super) fn relative_to(timestamp: &str, now: DateTime<Utc>) -> String {
+    DateTime::parse_from_rfc3339(timestamp).map_or_else(
+        |_| timestamp.to_string(),
+        |at| crate::cron::relative_time(at.with_timezone(&Utc), now),
+    )
+}
+
+#[async_trait]
+impl Tool for SessionsListTool {
+    fn name(&self) -> &str {
+        "sessions_list"
+    }
+
+    fn description(&self) -> &str {
+        "List past channel conversations, most recently active first (only this conversation unless cross-session search is enabled). Returns key, title, message count and how long ago each was active."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "channel": {
+                    "type": "string",
+                    "description": "Only sessions whose key starts with this, e.g. \"telegram\""
+                },
+                "active_within_hours": {
+                    "type": "integer",
+                    "description": "Only sessions active in the last N hours"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max sessions to return (default: 20, max: 100)"
+                }
+            }
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let key_prefix = args
+            .get("channel")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(String::from);
+
+        let active_since = args
+            .get("active_within_hours")
+            .and_then(serde_json::Value::as_u64)
+            // Capped well below the point where chrono's duration overflows.
+            .map(|hours| {
+                let hours = i64::try_from(hours).unwrap_or(i64::MAX).min(1_000_000);
+                Local::now() - chrono::Duration::hours(hours)
+            });
+
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(DEFAULT_LIMIT, |v| (v as usize).clamp(1, MAX_LIMIT));
+
+        if !SqliteSessionStore::db_path(&self.workspace_dir).exists() {
+            return Ok(ToolResult {
+                success: true,
+                output: "No past conversations have been recorded yet.".into(),
+                error: None,
+            });
+        }
+
+        let filter = SessionListFilter {
+            key: visible_session(self.cross_session),
+            key_prefix,
+            active_since,
+            limit: Some(limit),
+        };
+        let listed = blocking_read_only(&self.workspace_dir, move |store| {
+            store.list_sessions_filtered(&filter)
+        })
+        .await;
+
+        match listed {
+            Ok((sessions, _)) if sessions.is_empty() => Ok(ToolResult {
+                success: true,
+                output: "No past conversations matched those filters.".into(),
+                error: None,
+            }),
+            Ok((sessions, total)) => {
+                let now = Utc::now();
+                let entries: Vec<String> = sessions
+                    .iter()
+                    .map(|s| {
+                        json!({
+                            "key": s.key,
+                            "title": s.title,
+                            "msgs": s.message_count,
+                            "last_active": relative_to(&s.updated_at, now),
+                        })
+                        .to_string()
+                    })
+                    .collect();
+                let shown = fit_budget(entries, OUTPUT_BUDGET);
+                Ok(ToolResult {
+                    success: true,
+                    output: render_array(&shown, total - shown.len()),
+                    error: None,
+                })
+            }
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Listing sessions failed: {e}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn budget_keeps_whole_entries_and_at_least_one() {
+        let entries = || vec!["a".repeat(10), "b".repeat(10), "c".repeat(10)];
+        // [aaaaaaaaaa,bbbbbbbbbb] is exactly 23 characters.
+        assert_eq!(fit_budget(entries(), 23).len(), 2);
+        assert_eq!(fit_budget(entries(), 22).len(), 1);
+        assert_eq!(fit_budget(entries(), 0).len(), 1);
+        assert_eq!(fit_budget(entries(), 34).len(), 3);
+        assert!(fit_budget(Vec::new(), 10).is_empty());
+
+        let shown = fit_budget(entries(), 23);
+        let output = render_array(&shown, 3 - shown.len());
+        assert_eq!(output.lines().next().unwrap().chars().count(), 23);
+        assert!(output.ends_with("\n(1 more not shown)"));
+        assert_eq!(render_array(&["1".into(), "2".into()], 0), "[1,2]");
+    }
+
+    #[tokio::test]
+    async fn list_is_compact_filtered_and_reports_hidden_sessions() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        store
+            .append_message("telegram_alice", "user", "hi")
+            .unwrap();
+        store
+            .append_message("telegram_alice", "assistant", "hello")
+            .unwrap();
+        store.set_title("telegram_alice", "Greetings").unwrap();
+        store.append_message("telegram_bob", "user", "yo").unwrap();
+        store
+            .append_message("discord_carol", "user", "hey")
+            .unwrap();
+
+        let tool = SessionsListTool::new(tmp.path().to_path_buf());
+        let result = tool
+            .execute(json!({"channel": "telegram", "active_within_hours": 24, "limit": 1}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        let (array, trailer) = result.output.split_once('\n').unwrap();
+        let listed: serde_json::Value = serde_json::from_str(array).unwrap();
+        assert_eq!(listed.as_array().unwrap().len(), 1);
+        let key = listed[0]["key"].as_str().unwrap();
+        assert!(key.starts_with("telegram_"));
+        assert_eq!(listed[0]["last_active"], "now");
+        assert!(listed[0].get("updated_at").is_none());
+        assert_eq!(trailer, "(1 more not shown)");
+
+        let all = tool.execute(json!({})).await.unwrap();
+        let listed: serde_json::Value = serde_json::from_str(&all.output).unwrap();
+        let alice = listed
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["key"] == "telegram_alice")
+            .unwrap();
+        assert_eq!(alice["title"], "Greetings");
+        assert_eq!(alice["msgs"], 2);
+    }
+
+    #[tokio::test]
+    async fn channel_turns_only_list_their_own_session() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        store
+            .append_message("telegram_alice", "user", "hi")
+            .unwrap();
+        store.append_message("telegram_bob", "user", "yo").unwrap();
+        let bob = crate::sessions::SessionContext {
+            session_key: "telegram_bob".into(),
+            channel: "telegram".into(),
+            reply_target: "bob".into(),
+            thread_ts: None,
+            sender: "bob".into(),
+        };
+
+        let tool = SessionsListTool::new(tmp.path().to_path_buf());
+        let result = crate::sessions::with_session(bob.clone(), tool.execute(json!({})))
+            .await
+            .unwrap();
+        let listed: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(listed.as_array().unwrap().len(), 1);
+        assert_eq!(listed[0]["key"], "telegram_bob");
+
+        let tool = tool.with_cross_session(true);
+        let result = crate::sessions::with_session(bob, tool.execute(json!({})))
+            .await
+            .unwrap();
+        let listed: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(listed.as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn list_without_database_reports_empty() {
+        let tmp = TempDir::new().unwrap();
+        let tool = SessionsListTool::new(tmp.path().to_path_buf());
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("No past conversations"));
+    }
+}
@@ -0,0 +1,189 @@
+use super::traits::{Tool, ToolResult};
+use crate::sessions::{blocking_read_only, visible_session, SqliteSessionStore};
+use crate::util::truncate_with_ellipsis;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+const SNIPPET_CHARS: usize = 200;
+const MAX_LIMIT: usize = 20;
+
+/// Let the agent search past channel conversations
+pub struct SessionsSearchTool {
+    workspace_dir: PathBuf,
+    cross_session: bool,
+}
+
+impl SessionsSearchTool {
+    pub fn new(workspace_dir: PathBuf) -> Self {
+        Self {
+            workspace_dir,
+            cross_session: false,
+        }
+    }
+
+    /// Search every stored session instead of only the current one.
+    pub fn with_cross_session(mut self, cross_session: bool) -> Self {
+        self.cross_session = cross_session;
+        self
+    }
+}
+
+#[async_trait]
+impl Tool for SessionsSearchTool {
+    fn name(&self) -> &str {
+        "sessions_search"
+    }
+
+    fn description(&self) -> &str {
+        "Full-text search over past messages of this conversation (every channel conversation when cross-session search is enabled). Returns matching messages with their session key and title, best matches first."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Words that must all appear in the message"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Max results to return (default: 5, max: 20)"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
+
+        #[allow(clippy::cast_possible_truncation)]
+        let limit = args
+            .get("limit")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(5, |v| (v as usize).clamp(1, MAX_LIMIT));
+
+        if !SqliteSessionStore::db_path(&self.workspace_dir).exists() {
+            return Ok(ToolResult {
+                success: true,
+                output: "No past conversations have been recorded yet.".into(),
+                error: None,
+            });
+        }
+
+        let scope = visible_session(self.cross_session);
+        let query = query.to_string();
+        let hits = blocking_read_only(&self.workspace_dir, move |store| {
+            store.search_messages_in(&query, scope.as_deref(), limit)
+        })
+        .await;
+
+        match hits {
+            Ok(hits) if hits.is_empty() => Ok(ToolResult {
+                success: true,
+                output: "No past conversations matched that query.".into(),
+                error: None,
+            }),
+            Ok(hits) => {
+                let mut output = format!("Found {} matching messages:\n", hits.len());
+                for hit in &hits {
+                    let title = hit.title.as_deref().unwrap_or("untitled");
+                    let _ = writeln!(
+                        output,
+                        "- [{}] {} ({title}) {}: {}",
+                        hit.created_at,
+                        hit.key,
+                        hit.role,
+                        truncate_with_ellipsis(&hit.content, SNIPPET_CHARS)
+                    );
+                }
+                Ok(ToolResult {
+                    success: true,
+                    output,
+                    error: None,
+                })
+            }
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("Session search failed: {e}")),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn search_without_database_reports_empty() {
+        let tmp = TempDir::new().unwrap();
+        let tool = SessionsSearchTool::new(tmp.path().to_path_buf());
+        let result = tool.execute(json!({"query": "anything"})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("No past conversations"));
+    }
+
+    #[tokio::test]
+    async fn search_finds_past_messages() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        store
+            .append_message("telegram_alice", "user", "remind me about the dentist")
+            .unwrap();
+        store.set_title("telegram_alice", "Dentist").unwrap();
+
+        let tool = SessionsSearchTool::new(tmp.path().to_path_buf());
+        let result = tool.execute(json!({"query": "dentist"})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("telegram_alice (Dentist) user"));
+    }
+
+    #[tokio::test]
+    async fn channel_turns_only_search_their_own_session() {
+        let tmp = TempDir::new().unwrap();
+        let store = SqliteSessionStore::open(tmp.path()).unwrap();
+        store
+            .append_message("telegram_alice", "user", "my passport number is 123")
+            .unwrap();
+        store
+            .append_message("telegram_bob", "user", "where is my passport")
+            .unwrap();
+        let bob = crate::sessions::SessionContext {
+            session_key: "telegram_bob".into(),
+            channel: "telegram".into(),
+            reply_target: "bob".into(),
+            thread_ts: None,
+            sender: "bob".into(),
+        };
+
+        let tool = SessionsSearchTool::new(tmp.path().to_path_buf());
+        let query = json!({"query": "passport", "limit": 1000});
+        let result = crate::sessions::with_session(bob.clone(), tool.execute(query.clone()))
+            .await
+            .unwrap();
+        assert!(result.output.contains("Found 1 matching"));
+        assert!(!result.output.contains("telegram_alice"));
+
+        let tool = tool.with_cross_session(true);
+        let result = crate::sessions::with_session(bob, tool.execute(query))
+            .await
+            .unwrap();
+        assert!(result.output.contains("telegram_alice"));
+    }
+
+    #[tokio::test]
+    async fn missing_query_is_an_error() {
+        let tmp = TempDir::new().unwrap();
+        let tool = SessionsSearchTool::new(tmp.path().to_path_buf());
+        assert!(tool.execute(json!({})).await.is_err());
+    }
+}
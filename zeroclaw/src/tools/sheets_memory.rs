@@ -0,0 +1,213 @@
+use super::traits::{Tool, ToolResult};
+use crate::memory::sheets::{SheetsClient, SheetsError, MAX_FOUND_ROWS};
+use crate::security::policy::ToolOperation;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Structured memory in a Google Sheets spreadsheet the user can also edit
+/// by hand. Registered when `[google_sheets]` is configured.
+pub struct SheetsMemoryTool {
+    client: Arc<SheetsClient>,
+    security: Arc<SecurityPolicy>,
+}
+
+impl SheetsMemoryTool {
+    pub fn new(client: Arc<SheetsClient>, security: Arc<SecurityPolicy>) -> Self {
+        Self { client, security }
+    }
+
+    /// API and quota errors go back to the model as tool results so it can
+    /// wait, narrow the request, or tell the user.
+    fn sheets_failure(error: &SheetsError) -> ToolResult {
+        ToolResult::failure(error.to_string())
+    }
+}
+
+fn string_arg<'a>(args: &'a serde_json::Value, name: &str) -> anyhow::Result<&'a str> {
+    args.get(name)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| anyhow::anyhow!("Missing '{name}' parameter"))
+}
+
+#[async_trait]
+impl Tool for SheetsMemoryTool {
+    fn name(&self) -> &str {
+        "sheets_memory"
+    }
+
+    fn description(&self) -> &str {
+        "Structured long-term memory in the user's Google Sheets spreadsheet (the user can edit it too). Operations: append_row (add a row to a sheet, created if missing), read_range (read an A1 range or the whole sheet), find_rows (rows whose column contains a query; the first row is the header)."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "operation": {
+                    "type": "string",
+                    "enum": ["append_row", "read_range", "find_rows"],
+                    "description": "What to do"
+                },
+                "sheet": {
+                    "type": "string",
+                    "description": "Sheet (tab) name, e.g. 'Contacts'"
+                },
+                "values": {
+                    "type": "array",
+                    "items": { "type": ["string", "number", "boolean", "null"] },
+                    "description": "append_row: cell values for the new row, left to right"
+                },
+                "range": {
+                    "type": "string",
+                    "description": "read_range: A1 range without the sheet name, e.g. 'A1:D20' or 'B:B'. Omit to read the whole sheet."
+                },
+                "column": {
+                    "type": "string",
+                    "description": "find_rows: header name or column letter to search"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "find_rows: case-insensitive text to look for"
+                }
+            },
+            "required": ["operation", "sheet"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let operation = string_arg(&args, "operation")?;
+        let sheet = string_arg(&args, "sheet")?;
+
+        match operation {
+            "append_row" => {
+                let values = args
+                    .get("values")
+                    .and_then(|v| v.as_array())
+                    .ok_or_else(|| anyhow::anyhow!("Missing 'values' parameter"))?;
+                if let Err(error) = self
+                    .security
+                    .enforce_tool_operation(ToolOperation::Act, "sheets_memory")
+                {
+                    return Ok(ToolResult::failure(error));
+                }
+                match self.client.append_row(sheet, values).await {
+                    Ok(range) => Ok(ToolResult {
+                        success: true,
+                        output: format!("Appended row at {range}"),
+                        error: None,
+                    }),
+                    Err(e) => Ok(Self::sheets_failure(&e)),
+                }
+            }
+            "read_range" => {
+                let range = args.get("range").and_then(|v| v.as_str());
+                match self.client.read_range(sheet, range).await {
+                    Ok(rows) => Ok(ToolResult {
+                        success: true,
+                        output: json!({ "rows": rows }).to_string(),
+                        error: None,
+                    }),
+                    Err(e) => Ok(Self::sheets_failure(&e)),
+                }
+            }
+            "find_rows" => {
+                let column = string_arg(&args, "column")?;
+                let query = string_arg(&args, "query")?;
+                match self.client.find_rows(sheet, column, query).await {
+                    Ok(found) => {
+                        let rows: Vec<_> = found
+                            .rows
+                            .iter()
+                            .map(|(row, cells)| json!({ "row": row, "cells": cells }))
+                            .collect();
+                        let mut output = json!({ "header": found.header, "matches": rows });
+                        if found.truncated {
+                            output["note"] = json!(format!(
+                                "Only the first {MAX_FOUND_ROWS} matches are shown; refine the query"
+                            ));
+                        }
+                        Ok(ToolResult {
+                            success: true,
+                            output: output.to_string(),
+                            error: None,
+                        })
+                    }
+                    Err(e) => Ok(Self::sheets_failure(&e)),
+                }
+            }
+            other => Ok(ToolResult::failure(format!(
+                "Unknown operation '{other}'; use append_row, read_range or find_rows"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::sheets::tests::{titles, MockTransport};
+    use crate::security::AutonomyLevel;
+
+    fn tool(
+        transport: Arc<MockTransport>,
+        per_minute: u32,
+        autonomy: AutonomyLevel,
+    ) -> SheetsMemoryTool {
+        SheetsMemoryTool::new(
+            Arc::new(SheetsClient::new(transport, per_minute)),
+            Arc::new(SecurityPolicy {
+                autonomy,
+                ..SecurityPolicy::default()
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn find_rows_returns_matches_with_row_numbers() {
+        let transport = MockTransport::with_responses([
+            Ok(titles(&["Contacts"])),
+            Ok(json!({ "values": [["Name", "City"], ["Ada", "London"], ["Linus", "Portland"]] })),
+        ]);
+        let result = tool(transport, 60, AutonomyLevel::Full)
+            .execute(json!({ "operation": "find_rows", "sheet": "Contacts", "column": "city", "query": "port" }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let output: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(
+            output["matches"],
+            json!([{ "row": 3, "cells": ["Linus", "Portland"] }])
+        );
+    }
+
+    #[tokio::test]
+    async fn quota_errors_become_tool_results() {
+        let transport = MockTransport::with_responses([Err(SheetsError::Quota {
+            retry_after: std::time::Duration::from_secs(30),
+        })]);
+        let result = tool(transport, 60, AutonomyLevel::Full)
+            .execute(json!({ "operation": "read_range", "sheet": "Notes" }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error
+            .unwrap()
+            .contains("quota exceeded; retry in 30s"));
+    }
+
+    #[tokio::test]
+    async fn append_is_blocked_in_read_only_mode() {
+        let transport = MockTransport::with_responses([]);
+        let result = tool(transport.clone(), 60, AutonomyLevel::ReadOnly)
+            .execute(json!({ "operation": "append_row", "sheet": "Notes", "values": ["x"] }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(transport.requests.lock().is_empty());
+    }
+}
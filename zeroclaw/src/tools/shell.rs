@@ -1,6 +1,8 @@
-use super::traits::{Tool, ToolResult};
+use super::artifacts::Artifacts;
+use super::traits::{SandboxOverrides, Tool, ToolResult};
+use crate::config::{CommandFilterMode, SandboxConfig};
 use crate::runtime::RuntimeAdapter;
-use crate::security::SecurityPolicy;
+use crate::security::{AuditEvent, AuditEventType, AuditLogger, CommandFilter, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
 use std::collections::HashSet;
@@ -21,71 +23,67 @@ const SAFE_ENV_VARS: &[&str] = &[
 pub struct ShellTool {
     security: Arc<SecurityPolicy>,
     runtime: Arc<dyn RuntimeAdapter>,
+    command_filter: Arc<CommandFilter>,
+    audit: Option<Arc<AuditLogger>>,
+    artifacts: Option<Artifacts>,
 }
 
 impl ShellTool {
+    /// Shell tool with the default `[security.sandbox]` blocked rules.
     pub fn new(security: Arc<SecurityPolicy>, runtime: Arc<dyn RuntimeAdapter>) -> Self {
-        Self { security, runtime }
-    }
-}
-
-fn is_valid_env_var_name(name: &str) -> bool {
-    let mut chars = name.chars();
-    match chars.next() {
-        Some(first) if first.is_ascii_alphabetic() || first == '_' => {}
-        _ => return false,
-    }
-    chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
-}
-
-fn collect_allowed_shell_env_vars(security: &SecurityPolicy) -> Vec<String> {
-    let mut out = Vec::new();
-    let mut seen = HashSet::new();
-    for key in SAFE_ENV_VARS
-        .iter()
-        .copied()
-        .chain(security.shell_env_passthrough.iter().map(|s| s.as_str()))
-    {
-        let candidate = key.trim();
-        if candidate.is_empty() || !is_valid_env_var_name(candidate) {
-            continue;
-        }
-        if seen.insert(candidate.to_string()) {
-            out.push(candidate.to_string());
+        Self {
+            security,
+            runtime,
+            command_filter: Arc::new(CommandFilter::from_config(&SandboxConfig::default())),
+            audit: None,
+            artifacts: None,
         }
     }
-    out
-}
 
-#[async_trait]
-impl Tool for ShellTool {
-    fn name(&self) -> &str {
-        "shell"
+    /// Use configured blocked rules; matches are written to `audit`.
+    pub fn with_command_filter(
+        mut self,
+        command_filter: Arc<CommandFilter>,
+        audit: Option<Arc<AuditLogger>>,
+    ) -> Self {
+        self.command_filter = command_filter;
+        self.audit = audit;
+        self
     }
 
-    fn description(&self) -> &str {
-        "Execute a shell command in the workspace directory"
+    /// Save stdout beyond the preview size as a workspace artifact instead
+    /// of truncating it.
+    pub fn with_artifacts(mut self, artifacts: Option<Artifacts>) -> Self {
+        self.artifacts = artifacts;
+        self
     }
 
-    fn parameters_schema(&self) -> serde_json::Value {
-        json!({
-            "type": "object",
-            "properties": {
-                "command": {
-                    "type": "string",
-                    "description": "The shell command to execute"
-                },
-                "approved": {
-                    "type": "boolean",
-                    "description": "Set true to explicitly approve medium/high-risk commands in supervised mode",
-                    "default": false
-                }
-            },
-            "required": ["command"]
-        })
+    /// Record a blocked-rule decision in the audit log.
+    fn audit_filter_decision(&self, command: &str, rule: &str, blocked: bool) {
+        let Some(audit) = &self.audit else {
+            return;
+        };
+        let event_type = if blocked {
+            AuditEventType::PolicyViolation
+        } else {
+            AuditEventType::SecurityEvent
+        };
+        let (channel, session_key) = crate::sessions::current_session().map_or_else(
+            || ("shell".to_string(), None),
+            |session| (session.channel, Some(session.session_key)),
+        );
+        let risk = format!("{:?}", self.security.command_risk_level(command)).to_lowercase();
+        let event = AuditEvent::new(event_type)
+            .with_actor(channel, session_key, None)
+            .with_action(command.to_string(), risk, false, !blocked)
+            .with_matched_rule(rule.to_string(), blocked);
+        if let Err(e) = audit.log(&event) {
+            tracing::warn!("Failed to write shell audit event: {e}");
+        }
     }
 
-    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+    /// Validate and run `args["command"]`, killing it after `timeout_secs`.
+    async fn run(&self, args: serde_json::Value, timeout_secs: u64) -> anyhow::Result<ToolResult> {
         let command = args
             .get("command")
             .and_then(|v| v.as_str())
@@ -122,6 +120,19 @@ impl Tool for ShellTool {
             });
         }
 
+        if let Some(rule) = self.command_filter.matched_rule(command) {
+            let blocked = self.command_filter.mode() == CommandFilterMode::Block;
+            self.audit_filter_decision(command, &rule, blocked);
+            if blocked {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Command blocked by sandbox rule {rule}")),
+                });
+            }
+            tracing::warn!("shell command matches sandbox rule {rule}; running in warn mode");
+        }
+
         if !self.security.record_action() {
             return Ok(ToolResult {
                 success: false,
@@ -154,8 +165,7 @@ impl Tool for ShellTool {
             }
         }
 
-        let result =
-            tokio::time::timeout(Duration::from_secs(SHELL_TIMEOUT_SECS), cmd.output()).await;
+        let result = tokio::time::timeout(Duration::from_secs(timeout_secs), cmd.output()).await;
 
         match result {
             Ok(Ok(output)) => {
@@ -163,7 +173,9 @@ impl Tool for ShellTool {
                 let mut stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
                 // Truncate output to prevent OOM
-                if stdout.len() > MAX_OUTPUT_BYTES {
+                if let Some(artifacts) = &self.artifacts {
+                    stdout = artifacts.spill("shell", &stdout);
+                } else if stdout.len() > MAX_OUTPUT_BYTES {
                     stdout.truncate(stdout.floor_char_boundary(MAX_OUTPUT_BYTES));
                     stdout.push_str("\n... [output truncated at 1MB]");
                 }
@@ -191,13 +203,83 @@ impl Tool for ShellTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!(
-                    "Command timed out after {SHELL_TIMEOUT_SECS}s and was killed"
+                    "Command timed out after {timeout_secs}s and was killed"
                 )),
             }),
         }
     }
 }
 
+fn is_valid_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {}
+        _ => return false,
+    }
+    chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+pub(crate) fn collect_allowed_shell_env_vars(security: &SecurityPolicy) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut seen = HashSet::new();
+    for key in SAFE_ENV_VARS
+        .iter()
+        .copied()
+        .chain(security.shell_env_passthrough.iter().map(|s| s.as_str()))
+    {
+        let candidate = key.trim();
+        if candidate.is_empty() || !is_valid_env_var_name(candidate) {
+            continue;
+        }
+        if seen.insert(candidate.to_string()) {
+            out.push(candidate.to_string());
+        }
+    }
+    out
+}
+
+#[async_trait]
+impl Tool for ShellTool {
+    fn name(&self) -> &str {
+        "shell"
+    }
+
+    fn description(&self) -> &str {
+        "Execute a shell command in the workspace directory"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "command": {
+                    "type": "string",
+                    "description": "The shell command to execute"
+                },
+                "approved": {
+                    "type": "boolean",
+                    "description": "Set true to explicitly approve medium/high-risk commands in supervised mode",
+                    "default": false
+                }
+            },
+            "required": ["command"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        self.run(args, SHELL_TIMEOUT_SECS).await
+    }
+
+    async fn execute_with_ctx(
+        &self,
+        args: serde_json::Value,
+        overrides: &SandboxOverrides,
+    ) -> anyhow::Result<ToolResult> {
+        let timeout_secs = overrides.exec_timeout_secs.unwrap_or(SHELL_TIMEOUT_SECS);
+        self.run(args, timeout_secs).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -252,6 +334,52 @@ mod tests {
         assert!(result.error.is_none());
     }
 
+    #[tokio::test]
+    async fn shell_large_output_is_saved_as_artifact_readable_with_file_read() {
+        use crate::tools::artifacts::Artifacts;
+        use crate::tools::file_read::FileReadTool;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: tmp.path().to_path_buf(),
+            allowed_commands: vec!["seq".into()],
+            ..SecurityPolicy::default()
+        });
+        let tool = ShellTool::new(security.clone(), test_runtime()).with_artifacts(
+            Artifacts::for_tool(tmp.path(), &SandboxConfig::default(), "shell"),
+        );
+
+        // About 1.3 MB, beyond the old 1 MB truncation.
+        let result = tool
+            .execute(json!({"command": "seq 1 200000"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.len() < 70_000);
+        let pointer: serde_json::Value = serde_json::from_str(
+            result
+                .output
+                .lines()
+                .find_map(|line| line.strip_prefix("[artifact] "))
+                .expect("pointer line"),
+        )
+        .unwrap();
+        assert_eq!(pointer["total_bytes"], 1_288_895);
+
+        let read = FileReadTool::new(security)
+            .execute(json!({
+                "path": pointer["artifact_path"],
+                "offset": 199_999,
+                "limit": 2
+            }))
+            .await
+            .unwrap();
+        assert!(read.success, "{:?}", read.error);
+        assert!(read.output.contains("199999"));
+        assert!(read.output.contains("200000"));
+    }
+
     #[tokio::test]
     async fn shell_blocks_disallowed_command() {
         let tool = ShellTool::new(test_security(AutonomyLevel::Supervised), test_runtime());
@@ -554,6 +682,31 @@ mod tests {
         assert_eq!(SHELL_TIMEOUT_SECS, 60, "shell timeout must be 60 seconds");
     }
 
+    #[tokio::test]
+    async fn shell_uses_skill_timeout_override() {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: std::env::temp_dir(),
+            allowed_commands: vec!["sleep".into()],
+            ..SecurityPolicy::default()
+        });
+        let tool = ShellTool::new(security, test_runtime());
+        let overrides = SandboxOverrides {
+            skill: "slow-build".into(),
+            exec_timeout_secs: Some(1),
+            ..SandboxOverrides::default()
+        };
+        let result = tool
+            .execute_with_ctx(json!({"command": "sleep 5"}), &overrides)
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(
+            result.error.as_deref(),
+            Some("Command timed out after 1s and was killed")
+        );
+    }
+
     #[test]
     fn shell_output_limit_is_1mb() {
         assert_eq!(
@@ -659,4 +812,101 @@ mod tests {
                 || r2.error.as_deref().unwrap_or("").contains("budget")
         );
     }
+
+    fn filtered_tool(
+        tmp: &tempfile::TempDir,
+        mode: CommandFilterMode,
+    ) -> (ShellTool, std::path::PathBuf) {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let filter = CommandFilter::from_config(&SandboxConfig {
+            command_filter: mode,
+            blocked_patterns: vec!["echo forbidden".into()],
+            ..SandboxConfig::default()
+        });
+        let audit_config = crate::config::AuditConfig::default();
+        let log_path = crate::security::audit::audit_log_path(&audit_config, tmp.path());
+        let audit = AuditLogger::new(audit_config, tmp.path().to_path_buf()).unwrap();
+        let tool = ShellTool::new(security, test_runtime())
+            .with_command_filter(Arc::new(filter), Some(Arc::new(audit)));
+        (tool, log_path)
+    }
+
+    fn audit_events(log_path: &std::path::Path) -> Vec<serde_json::Value> {
+        crate::security::audit::read_recent_events(
+            log_path,
+            &crate::security::audit::AuditQuery {
+                limit: 10,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn shell_block_mode_refuses_and_audits_matched_rule() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let (tool, log_path) = filtered_tool(&tmp, CommandFilterMode::Block);
+
+        let result = tool
+            .execute(json!({"command": "echo 'forbidden' | wc -c"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("pattern `echo forbidden`"));
+
+        // Argument-aware: the word only appearing elsewhere is fine.
+        let result = tool
+            .execute(json!({"command": "echo allowed forbidden-ish"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+
+        let events = audit_events(&log_path);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["event_type"], "policy_violation");
+        assert_eq!(events[0]["action"]["allowed"], false);
+        assert_eq!(
+            events[0]["security"]["matched_rule"],
+            "pattern `echo forbidden`"
+        );
+    }
+
+    #[tokio::test]
+    async fn shell_warn_mode_runs_and_audits_matched_rule() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let (tool, log_path) = filtered_tool(&tmp, CommandFilterMode::Warn);
+
+        let result = tool
+            .execute(json!({"command": "echo forbidden"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output.trim(), "forbidden");
+
+        let events = audit_events(&log_path);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["event_type"], "security_event");
+        assert_eq!(events[0]["action"]["allowed"], true);
+        assert_eq!(events[0]["security"]["policy_violation"], false);
+    }
+
+    #[tokio::test]
+    async fn shell_off_mode_skips_rules_without_audit() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let (tool, log_path) = filtered_tool(&tmp, CommandFilterMode::Off);
+
+        let result = tool
+            .execute(json!({"command": "echo forbidden"}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(audit_events(&log_path).is_empty());
+    }
 }
@@ -0,0 +1,938 @@
+use super::delegate::ToolArcRef;
+use super::traits::{Tool, ToolResult};
+use crate::agent::loop_::HeadlessTurn;
+use crate::agent::subtask::{self, SubtaskBudgetExceeded};
+use crate::approval::ApprovalManager;
+use crate::config::SubtaskConfig;
+use crate::providers::{self, Provider};
+use crate::security::policy::ToolOperation;
+use crate::security::{AuditEvent, AuditEventType, AuditLogger, SecurityPolicy};
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Tools a sub-task may never be given: they would nest sub-agents.
+const NESTING_TOOLS: [&str; 2] = ["spawn_subtask", "delegate"];
+
+/// Where the sub-task model comes from.
+enum SubtaskProvider {
+    /// Created per call from the configured default provider.
+    Configured {
+        name: String,
+        credential: Option<String>,
+        options: Box<providers::ProviderRuntimeOptions>,
+    },
+    Fixed(Arc<dyn Provider>),
+}
+
+/// Run a scoped sub-task in a nested agent loop (see
+/// [`crate::agent::subtask`]) with a narrowed tool allowlist and its own
+/// iteration and token budget. The sub-task's answer is the tool output.
+pub struct SpawnSubtaskTool {
+    security: Arc<SecurityPolicy>,
+    config: SubtaskConfig,
+    /// Tools of the parent registry; the allowlist is validated against them.
+    parent_tools: Arc<Vec<Arc<dyn Tool>>>,
+    provider: SubtaskProvider,
+    model: String,
+    temperature: f64,
+    multimodal_config: crate::config::MultimodalConfig,
+    audit: Option<Arc<AuditLogger>>,
+    /// Decides which tools need approval. Sub-tasks run without anyone to
+    /// ask, so those tools are refused.
+    approvals: ApprovalManager,
+}
+
+impl SpawnSubtaskTool {
+    pub fn new(
+        security: Arc<SecurityPolicy>,
+        config: SubtaskConfig,
+        parent_tools: Arc<Vec<Arc<dyn Tool>>>,
+        root_config: &crate::config::Config,
+        provider_runtime_options: providers::ProviderRuntimeOptions,
+    ) -> Self {
+        Self {
+            security,
+            config,
+            parent_tools,
+            provider: SubtaskProvider::Configured {
+                name: crate::channels::resolved_default_provider(root_config),
+                credential: root_config.api_key.clone(),
+                options: Box::new(provider_runtime_options),
+            },
+            model: crate::channels::resolved_default_model(root_config),
+            temperature: root_config.default_temperature,
+            multimodal_config: root_config.multimodal.clone(),
+            audit: None,
+            approvals: ApprovalManager::from_config(&root_config.autonomy),
+        }
+    }
+
+    /// Use `provider` and `model` instead of the configured default.
+    pub fn with_provider(mut self, provider: Arc<dyn Provider>, model: impl Into<String>) -> Self {
+        self.provider = SubtaskProvider::Fixed(provider);
+        self.model = model.into();
+        self
+    }
+
+    /// Record each sub-task in `audit`.
+    pub fn with_audit(mut self, audit: Option<Arc<AuditLogger>>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Parent tools named in `requested`, or an error naming those the parent
+    /// cannot offer. The parent's set is its registry, narrowed to what the
+    /// calling loop offered the model when known.
+    fn narrow_tools(&self, requested: &[String]) -> Result<Vec<Box<dyn Tool>>, String> {
+        let offered = subtask::offered_tools();
+        let allowed = |name: &str| {
+            !NESTING_TOOLS.contains(&name)
+                && offered
+                    .as_deref()
+                    .is_none_or(|offered| offered.iter().any(|o| o == name))
+        };
+
+        let requested: HashSet<&str> = requested.iter().map(|name| name.trim()).collect();
+        let mut refused: Vec<&str> = requested
+            .iter()
+            .copied()
+            .filter(|name| {
+                !allowed(name) || !self.parent_tools.iter().any(|tool| tool.name() == *name)
+            })
+            .collect();
+        if !refused.is_empty() {
+            refused.sort_unstable();
+            return Err(format!(
+                "Tools not available to this conversation: {}. A sub-task can only use tools the current turn has (never spawn_subtask or delegate).",
+                refused.join(", ")
+            ));
+        }
+
+        let mut gated: Vec<&str> = requested
+            .iter()
+            .copied()
+            .filter(|name| self.approvals.needs_approval(name))
+            .collect();
+        if !gated.is_empty() {
+            gated.sort_unstable();
+            return Err(format!(
+                "Tools that need approval cannot run in a sub-task: {}. Call them from this conversation instead, or add them to autonomy.auto_approve.",
+                gated.join(", ")
+            ));
+        }
+
+        Ok(self
+            .parent_tools
+            .iter()
+            .filter(|tool| requested.contains(tool.name()))
+            .map(|tool| Box::new(ToolArcRef::new(Arc::clone(tool))) as Box<dyn Tool>)
+            .collect())
+    }
+
+    fn provider(&self) -> anyhow::Result<Arc<dyn Provider>> {
+        match &self.provider {
+            SubtaskProvider::Fixed(provider) => Ok(Arc::clone(provider)),
+            SubtaskProvider::Configured {
+                name,
+                credential,
+                options,
+            } => providers::create_provider_with_options(name, credential.as_deref(), options)
+                .map(Arc::from),
+        }
+    }
+
+    fn audit_subtask(&self, name: &str, subtask_id: &str, tools: &[String], outcome: &Outcome) {
+        let Some(audit) = &self.audit else {
+            return;
+        };
+        let (channel, session_key) = crate::sessions::current_session().map_or_else(
+            || ("subtask".to_string(), None),
+            |session| (session.channel, Some(session.session_key)),
+        );
+        let mut event = AuditEvent::new(AuditEventType::Subtask)
+            .with_actor(channel, session_key, None)
+            .with_action(
+                format!(
+                    "spawn_subtask {name} (tools: {}; {} tokens)",
+                    tools.join(", "),
+                    outcome.tokens_used
+                ),
+                "medium".into(),
+                true,
+                true,
+            )
+            .with_result(
+                outcome.error.is_none(),
+                None,
+                outcome.duration_ms,
+                outcome.error.clone(),
+            );
+        event.subtask_id = Some(subtask_id.to_string());
+        if let Err(e) = audit.log(&event) {
+            tracing::warn!("Failed to write sub-task audit event: {e}");
+        }
+    }
+}
+
+struct Outcome {
+    tokens_used: u64,
+    duration_ms: u64,
+    error: Option<String>,
+}
+
+#[async_trait]
+impl Tool for SpawnSubtaskTool {
+    fn name(&self) -> &str {
+        "spawn_subtask"
+    }
+
+    fn description(&self) -> &str {
+        "Hand a self-contained piece of a larger request (e.g. researching one library of several) to a sub-task that runs its own tool loop and returns only its final answer. Give it a complete task description and the tools it needs; it cannot see this conversation."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Short label for the sub-task, e.g. 'research-tokio'"
+                },
+                "task": {
+                    "type": "string",
+                    "description": "Everything the sub-task needs to know: goal, inputs, and what its answer should contain"
+                },
+                "tools": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Tool names the sub-task may use; must be tools available to you"
+                },
+                "max_iterations": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": format!("LLM calls the sub-task may make (at most {})", self.config.max_iterations)
+                },
+                "max_tokens": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": format!("Token budget of the sub-task (at most {})", self.config.max_tokens)
+                }
+            },
+            "required": ["name", "task", "tools"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let name = args
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'name' parameter"))?;
+        let task = args
+            .get("task")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'task' parameter"))?;
+        if name.is_empty() || task.is_empty() {
+            return Ok(ToolResult::failure("'name' and 'task' must not be empty"));
+        }
+        let tools: Vec<String> = args
+            .get("tools")
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| item.as_str())
+                    .map(|item| item.trim().to_string())
+                    .filter(|item| !item.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if tools.is_empty() {
+            return Ok(ToolResult::failure(
+                "'tools' must list at least one tool name",
+            ));
+        }
+
+        if subtask::current_subtask_id().is_some() {
+            return Ok(ToolResult::failure(
+                "Sub-tasks cannot spawn further sub-tasks (depth limit 1)",
+            ));
+        }
+        if let Err(error) = self
+            .security
+            .enforce_tool_operation(ToolOperation::Act, "spawn_subtask")
+        {
+            return Ok(ToolResult::failure(error));
+        }
+
+        let sub_tools = match self.narrow_tools(&tools) {
+            Ok(sub_tools) => sub_tools,
+            Err(message) => return Ok(ToolResult::failure(message)),
+        };
+
+        #[allow(clippy::cast_possible_truncation)]
+        let max_iterations = args
+            .get("max_iterations")
+            .and_then(serde_json::Value::as_u64)
+            .map_or(self.config.max_iterations, |n| n as usize)
+            .clamp(1, self.config.max_iterations.max(1));
+        let max_tokens = args
+            .get("max_tokens")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(self.config.max_tokens)
+            .clamp(1, self.config.max_tokens.max(1));
+
+        let provider = match self.provider() {
+            Ok(provider) => provider,
+            Err(e) => {
+                return Ok(ToolResult::failure(format!(
+                    "Failed to create sub-task provider: {e}"
+                )))
+            }
+        };
+        let provider_name = match &self.provider {
+            SubtaskProvider::Configured { name, .. } => name.as_str(),
+            SubtaskProvider::Fixed(_) => "subtask",
+        };
+        let turn = HeadlessTurn {
+            provider_name,
+            model: &self.model,
+            temperature: self.temperature,
+            channel_name: "subtask",
+            multimodal_config: &self.multimodal_config,
+            max_iterations,
+        };
+
+        let started = Instant::now();
+        let timeout = Duration::from_secs(self.config.timeout_secs.max(1));
+        let run = tokio::time::timeout(
+            timeout,
+            subtask::run_subtask(provider.as_ref(), &sub_tools, &turn, task, max_tokens),
+        )
+        .await;
+
+        let (subtask_id, tokens_used, result) = match run {
+            Ok(run) => (run.id, run.tokens_used, run.result),
+            Err(_) => (
+                "-".to_string(),
+                0,
+                Err(anyhow::anyhow!(
+                    "timed out after {}s",
+                    self.config.timeout_secs
+                )),
+            ),
+        };
+        let result = result.map_err(|e| {
+            match e
+                .chain()
+                .find_map(|source| source.downcast_ref::<SubtaskBudgetExceeded>())
+            {
+                Some(exceeded) => exceeded.to_string(),
+                None => e.to_string(),
+            }
+        });
+        let outcome = Outcome {
+            tokens_used,
+            duration_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+            error: result.as_ref().err().cloned(),
+        };
+        self.audit_subtask(name, &subtask_id, &tools, &outcome);
+
+        Ok(match result {
+            Ok(answer) => ToolResult {
+                success: true,
+                output: format!(
+                    "[Sub-task '{name}' ({subtask_id}), {tokens_used} tokens]\n{}",
+                    if answer.trim().is_empty() {
+                        "[Empty response]"
+                    } else {
+                        answer.trim()
+                    }
+                ),
+                error: None,
+            },
+            Err(message) => ToolResult::failure(format!("Sub-task '{name}' stopped: {message}")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::progress::TurnPhase;
+    use crate::providers::traits::TokenUsage;
+    use crate::providers::{ChatMessage, ChatRequest, ChatResponse, ToolCall};
+    use crate::security::AutonomyLevel;
+    use parking_lot::Mutex;
+    use std::collections::VecDeque;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct EchoTool;
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo_tool"
+        }
+
+        fn description(&self) -> &str {
+            "Echoes its input"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            json!({"type": "object", "properties": {"value": {"type": "string"}}})
+        }
+
+        async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            Ok(ToolResult {
+                success: true,
+                output: format!("echo:{}", args["value"].as_str().unwrap_or_default()),
+                error: None,
+            })
+        }
+    }
+
+    struct NamedTool(&'static str);
+
+    #[async_trait]
+    impl Tool for NamedTool {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "Does nothing"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            json!({"type": "object"})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+            Ok(ToolResult {
+                success: true,
+                output: String::new(),
+                error: None,
+            })
+        }
+    }
+
+    /// Calls `echo_tool` `tool_rounds` times, then answers. Every response
+    /// reports `tokens_per_call` tokens of usage.
+    struct ScriptedProvider {
+        calls: AtomicUsize,
+        tool_rounds: usize,
+        tokens_per_call: u64,
+        offered_tools: Mutex<Vec<Vec<String>>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(tool_rounds: usize, tokens_per_call: u64) -> Arc<Self> {
+            Arc::new(Self {
+                calls: AtomicUsize::new(0),
+                tool_rounds,
+                tokens_per_call,
+                offered_tools: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok("unused".into())
+        }
+
+        fn supports_native_tools(&self) -> bool {
+            true
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.offered_tools.lock().push(
+                request
+                    .tools
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|spec| spec.name.clone())
+                    .collect(),
+            );
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            let usage = Some(TokenUsage {
+                input_tokens: Some(self.tokens_per_call / 2),
+                output_tokens: Some(self.tokens_per_call / 2),
+            });
+            if call < self.tool_rounds {
+                Ok(ChatResponse {
+                    text: None,
+                    tool_calls: vec![ToolCall {
+                        id: format!("call-{call}"),
+                        name: "echo_tool".into(),
+                        arguments: json!({"value": format!("round {call}")}).to_string(),
+                    }],
+                    usage,
+                    reasoning_content: None,
+                })
+            } else {
+                Ok(ChatResponse {
+                    text: Some("tokio wins on ecosystem".into()),
+                    tool_calls: Vec::new(),
+                    usage,
+                    reasoning_content: None,
+                })
+            }
+        }
+    }
+
+    fn parent_tools() -> Arc<Vec<Arc<dyn Tool>>> {
+        Arc::new(vec![
+            Arc::new(EchoTool) as Arc<dyn Tool>,
+            Arc::new(NamedTool("file_write")),
+            Arc::new(NamedTool("delegate")),
+        ])
+    }
+
+    fn subtask_tool(provider: Arc<dyn Provider>) -> SpawnSubtaskTool {
+        let mut root_config = crate::config::Config::default();
+        root_config
+            .autonomy
+            .auto_approve
+            .extend(["echo_tool".to_string(), "file_write".to_string()]);
+        SpawnSubtaskTool::new(
+            Arc::new(SecurityPolicy::default()),
+            SubtaskConfig {
+                max_iterations: 4,
+                max_tokens: 1_000,
+                ..SubtaskConfig::default()
+            },
+            parent_tools(),
+            &root_config,
+            providers::ProviderRuntimeOptions::default(),
+        )
+        .with_provider(provider, "test-model")
+    }
+
+    #[tokio::test]
+    async fn subtask_runs_with_only_the_requested_tools() {
+        let provider = ScriptedProvider::new(1, 100);
+        let tool = subtask_tool(provider.clone());
+
+        let result = tool
+            .execute(json!({
+                "name": "research",
+                "task": "Compare tokio and async-std",
+                "tools": ["echo_tool"]
+            }))
+            .await
+            .unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("tokio wins on ecosystem"));
+        assert!(result.output.contains("200 tokens"));
+        let offered = provider.offered_tools.lock();
+        assert_eq!(offered.len(), 2);
+        assert!(offered.iter().all(|names| names == &["echo_tool"]));
+    }
+
+    #[tokio::test]
+    async fn allowlist_is_never_wider_than_the_parent() {
+        let provider = ScriptedProvider::new(0, 10);
+        let tool = subtask_tool(provider.clone());
+
+        let result = tool
+            .execute(json!({"name": "x", "task": "t", "tools": ["echo_tool", "shell"]}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("shell"));
+
+        let result = tool
+            .execute(json!({"name": "x", "task": "t", "tools": ["delegate"]}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+
+        // Registered but not offered by the calling loop: refused.
+        let offered: Arc<[String]> = Arc::from(vec!["echo_tool".to_string()]);
+        let result = subtask::with_offered_tools(
+            offered,
+            tool.execute(json!({"name": "x", "task": "t", "tools": ["file_write"]})),
+        )
+        .await
+        .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("file_write"));
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn supervised_subtasks_refuse_tools_that_need_approval() {
+        let provider = ScriptedProvider::new(0, 10);
+        let tool = SpawnSubtaskTool {
+            approvals: ApprovalManager::from_config(&crate::config::AutonomyConfig::default()),
+            ..subtask_tool(provider.clone())
+        };
+
+        let result = tool
+            .execute(json!({"name": "x", "task": "t", "tools": ["echo_tool", "file_write"]}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        let error = result.error.unwrap();
+        assert!(
+            error.contains("need approval cannot run in a sub-task: echo_tool, file_write"),
+            "{error}"
+        );
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 0);
+
+        let full = crate::config::AutonomyConfig {
+            level: AutonomyLevel::Full,
+            ..crate::config::AutonomyConfig::default()
+        };
+        let tool = SpawnSubtaskTool {
+            approvals: ApprovalManager::from_config(&full),
+            ..subtask_tool(ScriptedProvider::new(0, 10))
+        };
+        let result = tool
+            .execute(json!({"name": "x", "task": "t", "tools": ["file_write"]}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+    }
+
+    #[tokio::test]
+    async fn token_budget_stops_the_subtask() {
+        let provider = ScriptedProvider::new(10, 400);
+        let tool = subtask_tool(provider.clone());
+
+        let result = tool
+            .execute(json!({
+                "name": "loop",
+                "task": "keep going",
+                "tools": ["echo_tool"],
+                "max_tokens": 1_000
+            }))
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+        let error = result.error.unwrap();
+        assert!(error.contains("token budget exhausted"), "{error}");
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn iteration_cap_is_clamped_to_config() {
+        let provider = ScriptedProvider::new(10, 2);
+        let tool = subtask_tool(provider.clone());
+
+        let result = tool
+            .execute(json!({
+                "name": "loop",
+                "task": "keep going",
+                "tools": ["echo_tool"],
+                "max_iterations": 50
+            }))
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+        assert!(result
+            .error
+            .unwrap()
+            .contains("maximum tool iterations (4)"));
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn read_only_autonomy_blocks_subtasks() {
+        let tool = SpawnSubtaskTool {
+            security: Arc::new(SecurityPolicy {
+                autonomy: AutonomyLevel::ReadOnly,
+                ..SecurityPolicy::default()
+            }),
+            ..subtask_tool(ScriptedProvider::new(0, 1))
+        };
+        let result = tool
+            .execute(json!({"name": "x", "task": "t", "tools": ["echo_tool"]}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+    }
+
+    /// Answers with the scripted texts in order (prompt-guided tool calls).
+    struct TextProvider {
+        responses: Mutex<VecDeque<String>>,
+        last_messages: Mutex<Vec<String>>,
+    }
+
+    impl TextProvider {
+        fn new(responses: &[&str]) -> Arc<Self> {
+            Arc::new(Self {
+                responses: Mutex::new(responses.iter().map(|r| (*r).to_string()).collect()),
+                last_messages: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Provider for TextProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok("unused".into())
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            *self.last_messages.lock() =
+                request.messages.iter().map(|m| m.content.clone()).collect();
+            let text = self
+                .responses
+                .lock()
+                .pop_front()
+                .unwrap_or_else(|| "done".into());
+            Ok(ChatResponse {
+                text: Some(text),
+                tool_calls: Vec::new(),
+                usage: None,
+                reasoning_content: None,
+            })
+        }
+    }
+
+    const SPAWN_CALL: &str = r#"<tool_call>
+{"name":"spawn_subtask","arguments":{"name":"inner","task":"nest","tools":["echo_tool"]}}
+</tool_call>"#;
+
+    #[tokio::test]
+    async fn subtasks_cannot_spawn_subtasks() {
+        let tool: Arc<dyn Tool> = Arc::new(subtask_tool(ScriptedProvider::new(0, 1)));
+        let provider = TextProvider::new(&[SPAWN_CALL, "gave up"]);
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(ToolArcRef::new(tool))];
+        let turn = HeadlessTurn {
+            provider_name: "mock",
+            model: "mock-model",
+            temperature: 0.0,
+            channel_name: "subtask",
+            multimodal_config: &crate::config::MultimodalConfig::default(),
+            max_iterations: 3,
+        };
+
+        let run =
+            subtask::run_subtask(provider.as_ref(), &tools, &turn, "try nesting", 10_000).await;
+
+        assert_eq!(run.result.unwrap(), "gave up");
+        let messages = provider.last_messages.lock().join("\n");
+        assert!(messages.contains("depth limit 1"), "{messages}");
+    }
+
+    #[tokio::test]
+    async fn subtask_cannot_bypass_channel_confirmation() {
+        use crate::approval::confirm::{self, ConfirmationGate, CONFIRMATION_TTL};
+
+        let _installed = confirm::TEST_INSTALL_LOCK.lock().await;
+        let tmp = tempfile::TempDir::new().unwrap();
+        confirm::install(Some(ConfirmationGate::new(
+            &["file_write".into()],
+            CONFIRMATION_TTL,
+            tmp.path(),
+        )));
+        let gate = confirm::installed().unwrap();
+        let provider = TextProvider::new(&[
+            r#"<tool_call>
+{"name":"file_write","arguments":{"path":"notes.txt","content":"x"}}
+</tool_call>"#,
+            "could not write",
+        ]);
+        let tool = subtask_tool(ScriptedProvider::new(0, 1)).with_provider(provider.clone(), "m");
+        let session = crate::sessions::SessionContext {
+            session_key: "telegram_alice".into(),
+            channel: "telegram".into(),
+            reply_target: "alice".into(),
+            thread_ts: None,
+            sender: "alice".into(),
+        };
+
+        let result = crate::sessions::with_session(
+            session,
+            tool.execute(json!({"name": "w", "task": "write notes", "tools": ["file_write"]})),
+        )
+        .await
+        .unwrap();
+        confirm::install(None);
+
+        assert!(result.output.contains("could not write"), "{result:?}");
+        let messages = provider.last_messages.lock().join("\n");
+        assert!(
+            messages.contains("'file_write' needs the user's confirmation"),
+            "{messages}"
+        );
+        assert!(!gate.has_pending("telegram_alice"));
+    }
+
+    #[tokio::test]
+    async fn subtask_publishes_nothing_to_the_parent_channel() {
+        let sub_provider = ScriptedProvider::new(1, 10);
+        let registry: Vec<Box<dyn Tool>> = vec![
+            Box::new(subtask_tool(sub_provider.clone())),
+            Box::new(EchoTool),
+        ];
+        let parent = TextProvider::new(&[SPAWN_CALL, "done"]);
+        let (delta_tx, mut delta_rx) = tokio::sync::mpsc::channel(64);
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::channel(64);
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("compare")];
+
+        let answer = crate::agent::loop_::run_tool_call_loop(
+            parent.as_ref(),
+            &mut history,
+            &registry,
+            &crate::observability::NoopObserver,
+            "mock",
+            "mock-model",
+            0.0,
+            true,
+            None,
+            "telegram",
+            &crate::config::MultimodalConfig::default(),
+            4,
+            None,
+            Some(delta_tx),
+            Some(progress_tx),
+            None,
+            &[],
+            &crate::agent::packing::PackingLimits::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(answer, "done");
+        assert_eq!(sub_provider.calls.load(Ordering::SeqCst), 2);
+        let mut deltas = String::new();
+        while let Ok(delta) = delta_rx.try_recv() {
+            deltas.push_str(&delta);
+        }
+        assert!(!deltas.contains("echo_tool"), "{deltas}");
+        assert!(!deltas.contains("tokio wins"), "{deltas}");
+        let mut started = Vec::new();
+        while let Ok(event) = progress_rx.try_recv() {
+            if let TurnPhase::ToolStarted { tool } = event.phase {
+                started.push(tool);
+            }
+        }
+        assert_eq!(started, vec!["spawn_subtask"]);
+        // The parent model sees the sub-task's answer as the tool result.
+        assert!(parent
+            .last_messages
+            .lock()
+            .iter()
+            .any(|m| m.contains("tokio wins on ecosystem")));
+    }
+
+    #[tokio::test]
+    async fn usage_and_audit_events_are_attributed_to_the_parent_turn() {
+        use crate::agent::turn::{self, TurnOutcome, TurnRecorder};
+
+        struct AuditingTool(Arc<Mutex<Vec<AuditEvent>>>);
+
+        #[async_trait]
+        impl Tool for AuditingTool {
+            fn name(&self) -> &str {
+                "echo_tool"
+            }
+
+            fn description(&self) -> &str {
+                "Records an audit event"
+            }
+
+            fn parameters_schema(&self) -> serde_json::Value {
+                json!({"type": "object"})
+            }
+
+            async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
+                self.0
+                    .lock()
+                    .push(AuditEvent::new(AuditEventType::CommandExecution));
+                Ok(ToolResult {
+                    success: true,
+                    output: "ok".into(),
+                    error: None,
+                })
+            }
+        }
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let tmp = tempfile::TempDir::new().unwrap();
+        let audit_config = crate::config::AuditConfig {
+            enabled: true,
+            ..crate::config::AuditConfig::default()
+        };
+        let audit =
+            Arc::new(AuditLogger::new(audit_config.clone(), tmp.path().to_path_buf()).unwrap());
+        let tool = SpawnSubtaskTool {
+            parent_tools: Arc::new(vec![
+                Arc::new(AuditingTool(Arc::clone(&events))) as Arc<dyn Tool>
+            ]),
+            ..subtask_tool(ScriptedProvider::new(1, 50))
+        }
+        .with_audit(Some(audit));
+
+        let recorder = Arc::new(TurnRecorder::new("telegram_alice"));
+        let result = turn::with_turn(Arc::clone(&recorder), async {
+            let result = tool
+                .execute(json!({"name": "audit", "task": "t", "tools": ["echo_tool"]}))
+                .await
+                .unwrap();
+            turn::record_outcome(TurnOutcome::Completed);
+            result
+        })
+        .await;
+        assert!(result.success, "{:?}", result.error);
+
+        let inner = events.lock()[0].clone();
+        assert_eq!(inner.turn_id.as_deref(), Some(recorder.id()));
+        let subtask_id = inner
+            .subtask_id
+            .clone()
+            .expect("subtask id on inner events");
+        assert!(result.output.contains(&subtask_id));
+
+        let summary = recorder.summary().unwrap();
+        assert_eq!(summary.tools_used, vec!["echo_tool"]);
+        assert_eq!(summary.input_tokens + summary.output_tokens, 100);
+
+        let logged = crate::security::audit::read_recent_events(
+            &crate::security::audit::audit_log_path(&audit_config, tmp.path()),
+            &crate::security::audit::AuditQuery {
+                limit: 10,
+                event_type: Some("subtask".into()),
+                session: None,
+            },
+        );
+        assert_eq!(logged.len(), 1);
+        assert_eq!(logged[0]["subtask_id"], subtask_id.as_str());
+        assert_eq!(logged[0]["turn_id"], recorder.id());
+    }
+}
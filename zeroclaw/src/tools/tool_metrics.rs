@@ -0,0 +1,97 @@
+use super::metrics;
+use super::traits::{Tool, ToolResult};
+use async_trait::async_trait;
+use serde_json::json;
+
+/// Lets the agent inspect its own tool usage ("which tools have been failing?").
+pub struct ToolMetricsTool;
+
+#[async_trait]
+impl Tool for ToolMetricsTool {
+    fn name(&self) -> &str {
+        "tool_metrics"
+    }
+
+    fn description(&self) -> &str {
+        "Report per-tool call counts, failures, average duration, last error and last use since startup. Set failing_only to list only tools that have failed."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "tool": {
+                    "type": "string",
+                    "description": "Only report this tool"
+                },
+                "failing_only": {
+                    "type": "boolean",
+                    "description": "Only report tools with at least one failure"
+                }
+            },
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let only_tool = args.get("tool").and_then(serde_json::Value::as_str);
+        let failing_only = args
+            .get("failing_only")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+
+        let mut snapshot = metrics::snapshot();
+        snapshot.retain(|name, m| {
+            only_tool.is_none_or(|t| t == name) && (!failing_only || m.failure_count > 0)
+        });
+
+        let output = if snapshot.is_empty() {
+            "No matching tool calls recorded since startup.".to_string()
+        } else {
+            serde_json::to_string_pretty(&snapshot)?
+        };
+        Ok(ToolResult {
+            success: true,
+            output,
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn failing_only_filters_healthy_tools() {
+        metrics::record("tm-test-ok", Duration::from_millis(5), None);
+        metrics::record("tm-test-bad", Duration::from_millis(5), Some("timeout"));
+
+        let result = ToolMetricsTool
+            .execute(json!({"failing_only": true}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        let parsed: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(parsed["tm-test-bad"]["last_error"], "timeout");
+        assert!(parsed.get("tm-test-ok").is_none());
+    }
+
+    #[tokio::test]
+    async fn single_tool_filter_and_empty_result() {
+        metrics::record("tm-test-single", Duration::ZERO, None);
+        let result = ToolMetricsTool
+            .execute(json!({"tool": "tm-test-single"}))
+            .await
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(parsed.as_object().unwrap().len(), 1);
+
+        let result = ToolMetricsTool
+            .execute(json!({"tool": "tm-test-never-called"}))
+            .await
+            .unwrap();
+        assert!(result.output.starts_with("No matching tool calls"));
+    }
+}
@@ -1,5 +1,9 @@
+use crate::security::SecurityPolicy;
+use crate::skills::FsScope;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Result of a tool execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,6 +13,17 @@ pub struct ToolResult {
     pub error: Option<String>,
 }
 
+impl ToolResult {
+    /// A failed call that reports `error` back to the model.
+    pub fn failure(error: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            output: String::new(),
+            error: Some(error.into()),
+        }
+    }
+}
+
 /// Description of a tool for the LLM
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolSpec {
@@ -17,6 +32,87 @@ pub struct ToolSpec {
     pub parameters: serde_json::Value,
 }
 
+/// Sandbox adjustments granted to a tool call by the skill that authorised it.
+///
+/// Built from [`crate::skills::SkillPermissions`] after clamping against the
+/// global `[skills]` caps, so tools can apply the values as-is.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SandboxOverrides {
+    /// Name of the skill the overrides come from.
+    pub skill: String,
+    /// Shell command timeout in seconds (already clamped to the hard cap).
+    pub exec_timeout_secs: Option<u64>,
+    /// Domains network tools may reach, on top of their own allowlist.
+    /// Empty means no additional restriction.
+    pub network_domains: Vec<String>,
+    pub fs_scope: FsScope,
+}
+
+/// Policy and directory jail a file tool runs with under a skill's `fs_scope`.
+#[derive(Debug, Clone)]
+pub struct ScopedFs {
+    pub security: Arc<SecurityPolicy>,
+    /// Directory every resolved path must stay inside; passed to
+    /// [`SecurityPolicy::validate_path`]. Set for [`FsScope::Skill`].
+    pub base_dir: Option<PathBuf>,
+}
+
+impl SandboxOverrides {
+    /// `skills/<skill>/data` under `workspace`, the jail for [`FsScope::Skill`].
+    pub fn skill_data_dir(&self, workspace: &Path) -> PathBuf {
+        let name: String = self
+            .skill
+            .chars()
+            .map(|c| if c == '/' || c == '\\' { '_' } else { c })
+            .collect();
+        let name = if name.is_empty() || name.chars().all(|c| c == '.') {
+            "_".to_string()
+        } else {
+            name
+        };
+        workspace.join("skills").join(name).join("data")
+    }
+
+    /// Filesystem view file tools should use under this `fs_scope`, or `None`
+    /// when the base policy applies unchanged.
+    ///
+    /// Under [`FsScope::Skill`] relative paths resolve from the skill's data
+    /// directory, which is created on first use. The returned policy shares
+    /// the rate limiter of `base`, so scoped calls count against its budget.
+    pub fn scoped_fs(&self, base: &SecurityPolicy) -> Option<ScopedFs> {
+        match self.fs_scope {
+            FsScope::Workspace => None,
+            FsScope::Skill => {
+                let data_dir = self.skill_data_dir(&base.workspace_dir);
+                if let Err(e) = std::fs::create_dir_all(&data_dir) {
+                    tracing::warn!(
+                        skill = %self.skill,
+                        "failed to create skill data directory {}: {e}",
+                        data_dir.display()
+                    );
+                }
+                Some(ScopedFs {
+                    security: Arc::new(SecurityPolicy {
+                        workspace_dir: data_dir.clone(),
+                        workspace_only: true,
+                        tracker: base.tracker.shared(),
+                        ..base.clone()
+                    }),
+                    base_dir: Some(data_dir),
+                })
+            }
+            FsScope::System => Some(ScopedFs {
+                security: Arc::new(SecurityPolicy {
+                    workspace_only: false,
+                    tracker: base.tracker.shared(),
+                    ..base.clone()
+                }),
+                base_dir: None,
+            }),
+        }
+    }
+}
+
 /// Core tool trait — implement for any capability
 #[async_trait]
 pub trait Tool: Send + Sync {
@@ -32,6 +128,18 @@ pub trait Tool: Send + Sync {
     /// Execute the tool with given arguments
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult>;
 
+    /// Execute with skill-scoped sandbox overrides.
+    ///
+    /// Tools whose limits a skill may adjust (shell, network, filesystem)
+    /// override this; every other tool ignores the overrides.
+    async fn execute_with_ctx(
+        &self,
+        args: serde_json::Value,
+        _overrides: &SandboxOverrides,
+    ) -> anyhow::Result<ToolResult> {
+        self.execute(args).await
+    }
+
     /// Get the full spec for LLM registration
     fn spec(&self) -> ToolSpec {
         ToolSpec {
@@ -1,4 +1,5 @@
-use super::traits::{Tool, ToolResult};
+use super::artifacts::{Artifacts, ARTIFACT_MAX_BYTES};
+use super::traits::{SandboxOverrides, Tool, ToolResult};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use futures_util::StreamExt;
@@ -14,12 +15,15 @@ use std::time::Duration;
 /// - Converts HTML to clean plain text via `nanohtml2text`
 /// - Passes through text/plain, text/markdown, and application/json as-is
 /// - Sets a descriptive User-Agent
+/// - Inside a channel turn, heads the output with the page's citation id
+///   (see [`crate::agent::citations`])
 pub struct WebFetchTool {
     security: Arc<SecurityPolicy>,
     allowed_domains: Vec<String>,
     blocked_domains: Vec<String>,
     max_response_size: usize,
     timeout_secs: u64,
+    artifacts: Option<Artifacts>,
 }
 
 impl WebFetchTool {
@@ -36,9 +40,17 @@ impl WebFetchTool {
             blocked_domains: normalize_allowed_domains(blocked_domains),
             max_response_size,
             timeout_secs,
+            artifacts: None,
         }
     }
 
+    /// Save pages beyond the preview size as workspace artifacts instead of
+    /// truncating them at `max_response_size`.
+    pub fn with_artifacts(mut self, artifacts: Option<Artifacts>) -> Self {
+        self.artifacts = artifacts;
+        self
+    }
+
     fn validate_url(&self, raw_url: &str) -> anyhow::Result<String> {
         validate_target_url(
             raw_url,
@@ -48,6 +60,19 @@ impl WebFetchTool {
         )
     }
 
+    /// Copy of this tool whose allowlist is narrowed to `skill_domains`, so
+    /// redirects are held to the skill's domains as well.
+    fn scoped_to(&self, skill_domains: &[String]) -> Self {
+        Self {
+            security: self.security.clone(),
+            allowed_domains: intersect_allowlists(&self.allowed_domains, skill_domains),
+            blocked_domains: self.blocked_domains.clone(),
+            max_response_size: self.max_response_size,
+            timeout_secs: self.timeout_secs,
+            artifacts: self.artifacts.clone(),
+        }
+    }
+
     fn truncate_response(&self, text: &str) -> String {
         if text.len() > self.max_response_size {
             let mut truncated = text
@@ -66,7 +91,12 @@ impl WebFetchTool {
         response: reqwest::Response,
     ) -> anyhow::Result<String> {
         let mut bytes_stream = response.bytes_stream();
-        let hard_cap = self.max_response_size.saturating_add(1);
+        let limit = if self.artifacts.is_some() {
+            self.max_response_size.max(ARTIFACT_MAX_BYTES)
+        } else {
+            self.max_response_size
+        };
+        let hard_cap = limit.saturating_add(1);
         let mut bytes = Vec::new();
 
         while let Some(chunk_result) = bytes_stream.next().await {
@@ -237,6 +267,8 @@ impl Tool for WebFetchTool {
             });
         };
 
+        // Cite the page where redirects ended up.
+        let final_url = response.url().to_string();
         let body = match self.read_response_text_limited(response).await {
             Ok(t) => t,
             Err(e) => {
@@ -248,13 +280,22 @@ impl Tool for WebFetchTool {
             }
         };
 
+        let title = if body_mode == "html" {
+            html_title(&body)
+        } else {
+            None
+        };
         let text = if body_mode == "html" {
             nanohtml2text::html2text(&body)
         } else {
             body
         };
 
-        let output = self.truncate_response(&text);
+        let output = match &self.artifacts {
+            Some(artifacts) => artifacts.spill("web_fetch", &text),
+            None => self.truncate_response(&text),
+        };
+        let output = with_citation_header(&final_url, title.as_deref(), output);
 
         Ok(ToolResult {
             success: true,
@@ -262,6 +303,59 @@ impl Tool for WebFetchTool {
             error: None,
         })
     }
+
+    async fn execute_with_ctx(
+        &self,
+        args: serde_json::Value,
+        overrides: &SandboxOverrides,
+    ) -> anyhow::Result<ToolResult> {
+        if overrides.network_domains.is_empty() {
+            return self.execute(args).await;
+        }
+
+        let skill_domains = normalize_allowed_domains(overrides.network_domains.clone());
+        if let Some(url) = args.get("url").and_then(|v| v.as_str()) {
+            if let Ok(host) = extract_host(url.trim()) {
+                if !host_matches_allowlist(&host, &skill_domains) {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!(
+                            "Host '{host}' is not in the network_domains of skill '{}'",
+                            overrides.skill
+                        )),
+                    });
+                }
+            }
+        }
+
+        self.scoped_to(&skill_domains).execute(args).await
+    }
+}
+
+/// Text of the page's `<title>` element, whitespace collapsed.
+fn html_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    let title = nanohtml2text::html2text(&html[start..end]);
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+/// Prefix `output` with the page's citation id in the current turn. It goes
+/// before the body so truncation cannot drop it; outside a turn `output` is
+/// returned unchanged.
+fn with_citation_header(url: &str, title: Option<&str>, output: String) -> String {
+    let Some(id) = crate::agent::turn::cite_source(url, title.unwrap_or_default()) else {
+        return output;
+    };
+    let instruction = crate::agent::citations::cite_instruction(&id);
+    match title {
+        Some(title) => format!("[{id}] {title} <{url}> ({instruction})\n\n{output}"),
+        None => format!("[{id}] <{url}> ({instruction})\n\n{output}"),
+    }
 }
 
 // ── Helper functions (independent from http_request.rs per DRY rule-of-three) ──
@@ -417,6 +511,24 @@ fn host_matches_allowlist(host: &str, allowed_domains: &[String]) -> bool {
     })
 }
 
+/// Domains allowed by both lists: entries of either list that fall within
+/// the other one.
+fn intersect_allowlists(tool_domains: &[String], skill_domains: &[String]) -> Vec<String> {
+    let mut allowed: Vec<String> = skill_domains
+        .iter()
+        .filter(|domain| host_matches_allowlist(domain, tool_domains))
+        .chain(
+            tool_domains
+                .iter()
+                .filter(|domain| *domain != "*" && host_matches_allowlist(domain, skill_domains)),
+        )
+        .cloned()
+        .collect();
+    allowed.sort_unstable();
+    allowed.dedup();
+    allowed
+}
+
 fn is_private_or_local_host(host: &str) -> bool {
     let bare = host
         .strip_prefix('[')
@@ -851,4 +963,90 @@ mod tests {
         let ips = vec!["93.184.216.34".parse().unwrap(), "1.1.1.1".parse().unwrap()];
         assert!(validate_resolved_ips_are_public("example.com", &ips).is_ok());
     }
+
+    // ── Skill sandbox overrides ─────────────────────────────────
+
+    fn skill_overrides(domains: &[&str]) -> SandboxOverrides {
+        SandboxOverrides {
+            skill: "docs-helper".into(),
+            network_domains: domains.iter().map(|d| (*d).to_string()).collect(),
+            ..SandboxOverrides::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn skill_network_domains_restrict_allowlist() {
+        let tool = test_tool(vec!["example.com", "docs.rs"]);
+        let result = tool
+            .execute_with_ctx(
+                json!({"url": "https://example.com/page"}),
+                &skill_overrides(&["docs.rs"]),
+            )
+            .await
+            .unwrap();
+        assert!(!result.success);
+        let err = result.error.unwrap();
+        assert!(
+            err.contains("network_domains of skill 'docs-helper'"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn html_title_is_extracted_and_collapsed() {
+        assert_eq!(
+            html_title("<html><head><TITLE>\n  Tokio &amp; async\n</TITLE></head></html>")
+                .as_deref(),
+            Some("Tokio & async")
+        );
+        assert_eq!(html_title("<title> </title>"), None);
+        assert_eq!(html_title("<p>no title</p>"), None);
+    }
+
+    #[tokio::test]
+    async fn citation_header_is_added_inside_a_turn_only() {
+        use crate::agent::turn::{with_turn, TurnRecorder};
+
+        let body = "page text".to_string();
+        assert_eq!(
+            with_citation_header("https://docs.rs/tokio", Some("Tokio"), body.clone()),
+            body
+        );
+
+        let turn = Arc::new(TurnRecorder::new("slack_alice"));
+        let (first, second, again) = with_turn(turn, async {
+            (
+                with_citation_header("https://docs.rs/tokio", Some("Tokio"), body.clone()),
+                with_citation_header("https://tokio.rs/blog", None, body.clone()),
+                with_citation_header("https://docs.rs/tokio", Some("Tokio"), body.clone()),
+            )
+        })
+        .await;
+        assert_eq!(
+            first,
+            "[S1] Tokio <https://docs.rs/tokio> (cite as [S1])\n\npage text"
+        );
+        assert_eq!(
+            second,
+            "[S2] <https://tokio.rs/blog> (cite as [S2])\n\npage text"
+        );
+        assert_eq!(again, first);
+    }
+
+    #[test]
+    fn skill_scope_cannot_widen_tool_allowlist() {
+        let tool = test_tool(vec!["docs.rs"]);
+        let scoped = tool.scoped_to(&["docs.rs".into(), "evil.example".into()]);
+        assert_eq!(scoped.allowed_domains, vec!["docs.rs".to_string()]);
+        assert!(scoped.validate_url("https://evil.example").is_err());
+
+        let wildcard = test_tool(vec!["*"]).scoped_to(&["docs.rs".into()]);
+        assert_eq!(wildcard.allowed_domains, vec!["docs.rs".to_string()]);
+
+        let narrower = test_tool(vec!["api.example.com"]).scoped_to(&["example.com".into()]);
+        assert_eq!(
+            narrower.allowed_domains,
+            vec!["api.example.com".to_string()]
+        );
+    }
 }
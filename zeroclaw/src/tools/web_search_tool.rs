@@ -1,18 +1,192 @@
-use super::traits::{Tool, ToolResult};
+use super::traits::{SandboxOverrides, Tool, ToolResult};
 use async_trait::async_trait;
+use parking_lot::Mutex;
 use regex::Regex;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde::Serialize;
 use serde_json::json;
-use std::time::Duration;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+const BRAVE_API_HOST: &str = "api.search.brave.com";
+const DUCKDUCKGO_HOST: &str = "html.duckduckgo.com";
+/// Searches allowed per minute unless configured otherwise.
+const DEFAULT_MAX_REQUESTS_PER_MINUTE: u32 = 30;
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
 
 /// Web search tool for searching the internet.
 /// Supports multiple providers: DuckDuckGo (free), Brave (requires API key).
+///
+/// Results are returned as JSON (`title`, `url`, `snippet`, `age`) so the
+/// model can cite them; inside a channel turn each also carries its
+/// citation id (`S1`, …, see [`crate::agent::citations`]). Quota and key errors from Brave become failed tool
+/// results the model can act on, and a per-minute limit keeps a runaway
+/// loop from burning the API quota.
 pub struct WebSearchTool {
     provider: String,
     brave_api_key: Option<String>,
     max_results: usize,
     timeout_secs: u64,
+    max_requests_per_minute: u32,
+    /// Start times of the searches made in the last minute.
+    recent_requests: Mutex<VecDeque<Instant>>,
+}
+
+/// How recent results must be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Freshness {
+    Day,
+    Week,
+    Month,
+}
+
+impl Freshness {
+    fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "day" => Some(Self::Day),
+            "week" => Some(Self::Week),
+            "month" => Some(Self::Month),
+            _ => None,
+        }
+    }
+
+    fn brave_param(self) -> &'static str {
+        match self {
+            Self::Day => "pd",
+            Self::Week => "pw",
+            Self::Month => "pm",
+        }
+    }
+
+    fn duckduckgo_param(self) -> &'static str {
+        match self {
+            Self::Day => "d",
+            Self::Week => "w",
+            Self::Month => "m",
+        }
+    }
+}
+
+/// Per-call options read from the tool arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SearchOptions {
+    count: usize,
+    freshness: Option<Freshness>,
+    /// Two-letter country code, upper case (Brave only).
+    country: Option<String>,
+    /// Domain results must come from.
+    site: Option<String>,
+}
+
+impl SearchOptions {
+    /// Validate `count`, `freshness`, `country` and `site`; `count` is
+    /// clamped to 1–10 and defaults to `default_count`.
+    fn from_args(args: &serde_json::Value, default_count: usize) -> Result<Self, String> {
+        let count = match args.get("count") {
+            None | Some(serde_json::Value::Null) => default_count,
+            Some(value) => {
+                let count = value
+                    .as_u64()
+                    .ok_or_else(|| "'count' must be a whole number from 1 to 10".to_string())?;
+                usize::try_from(count).unwrap_or(usize::MAX).clamp(1, 10)
+            }
+        };
+
+        let freshness = match optional_str(args, "freshness") {
+            Some(raw) => Some(Freshness::parse(raw).ok_or_else(|| {
+                format!("Invalid 'freshness' '{raw}': expected day, week or month")
+            })?),
+            None => None,
+        };
+
+        let country = match optional_str(args, "country") {
+            Some(raw) if raw.len() == 2 && raw.bytes().all(|b| b.is_ascii_alphabetic()) => {
+                Some(raw.to_ascii_uppercase())
+            }
+            Some(raw) => {
+                return Err(format!(
+                    "Invalid 'country' '{raw}': expected a two-letter code such as US or DE"
+                ))
+            }
+            None => None,
+        };
+
+        let site = match optional_str(args, "site") {
+            Some(raw) => Some(normalize_site(raw).ok_or_else(|| {
+                format!("Invalid 'site' '{raw}': expected a domain such as docs.rs")
+            })?),
+            None => None,
+        };
+
+        Ok(Self {
+            count,
+            freshness,
+            country,
+            site,
+        })
+    }
+
+    /// `query` with the site filter applied as a `site:` operator.
+    fn query(&self, query: &str) -> String {
+        match &self.site {
+            Some(site) => format!("{} site:{site}", query.trim()),
+            None => query.trim().to_string(),
+        }
+    }
 }
 
+fn optional_str<'a>(args: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    args.get(key)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+/// Bare host of a `site` filter: scheme and path are dropped.
+fn normalize_site(raw: &str) -> Option<String> {
+    let lowered = raw.trim().to_ascii_lowercase();
+    let without_scheme = lowered
+        .strip_prefix("https://")
+        .or_else(|| lowered.strip_prefix("http://"))
+        .unwrap_or(&lowered);
+    let host = without_scheme.split('/').next().unwrap_or_default();
+    let valid = host.contains('.')
+        && !host.starts_with('.')
+        && !host.ends_with('.')
+        && host
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'.');
+    valid.then(|| host.to_string())
+}
+
+/// One search hit as returned to the model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct SearchResult {
+    /// Citation id within the current turn, e.g. `S1`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    title: String,
+    url: String,
+    snippet: String,
+    /// Page age as reported by the provider, e.g. "2 days ago".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    age: Option<String>,
+}
+
+/// A provider refusal (bad key, exhausted quota) reported to the model as a
+/// failed tool result rather than an execution error.
+#[derive(Debug)]
+struct SearchRefused(String);
+
+impl std::fmt::Display for SearchRefused {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for SearchRefused {}
+
 impl WebSearchTool {
     pub fn new(
         provider: String,
@@ -25,12 +199,63 @@ impl WebSearchTool {
             brave_api_key,
             max_results: max_results.clamp(1, 10),
             timeout_secs: timeout_secs.max(1),
+            max_requests_per_minute: DEFAULT_MAX_REQUESTS_PER_MINUTE,
+            recent_requests: Mutex::new(VecDeque::new()),
         }
     }
 
-    async fn search_duckduckgo(&self, query: &str) -> anyhow::Result<String> {
-        let encoded_query = urlencoding::encode(query);
-        let search_url = format!("https://html.duckduckgo.com/html/?q={}", encoded_query);
+    /// Cap searches per minute (minimum 1).
+    pub fn with_max_requests_per_minute(mut self, max: u32) -> Self {
+        self.max_requests_per_minute = max.max(1);
+        self
+    }
+
+    /// Host the configured provider is queried on.
+    fn api_host(&self) -> &'static str {
+        if self.provider == "brave" {
+            BRAVE_API_HOST
+        } else {
+            DUCKDUCKGO_HOST
+        }
+    }
+
+    /// Count a search against the per-minute limit, or return how long to
+    /// wait when the limit is reached.
+    fn reserve_request(&self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut recent = self.recent_requests.lock();
+        while recent
+            .front()
+            .is_some_and(|started| now.duration_since(*started) >= RATE_LIMIT_WINDOW)
+        {
+            recent.pop_front();
+        }
+        if recent.len() >= self.max_requests_per_minute as usize {
+            let oldest = recent.front().copied().unwrap_or(now);
+            return Err(RATE_LIMIT_WINDOW.saturating_sub(now.duration_since(oldest)));
+        }
+        recent.push_back(now);
+        Ok(())
+    }
+
+    fn duckduckgo_search_url(query: &str, options: &SearchOptions) -> String {
+        let mut url = format!(
+            "https://{DUCKDUCKGO_HOST}/html/?q={}",
+            urlencoding::encode(query)
+        );
+        if let Some(freshness) = options.freshness {
+            url.push_str("&df=");
+            url.push_str(freshness.duckduckgo_param());
+        }
+        url
+    }
+
+    async fn search_duckduckgo(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+    ) -> anyhow::Result<Vec<SearchResult>> {
+        let search_url = Self::duckduckgo_search_url(query, options);
 
         let client = reqwest::Client::builder()
             .timeout(Duration::from_secs(self.timeout_secs))
@@ -47,10 +272,14 @@ impl WebSearchTool {
         }
 
         let html = response.text().await?;
-        self.parse_duckduckgo_results(&html, query)
+        self.parse_duckduckgo_results(&html, options.count)
     }
 
-    fn parse_duckduckgo_results(&self, html: &str, query: &str) -> anyhow::Result<String> {
+    fn parse_duckduckgo_results(
+        &self,
+        html: &str,
+        count: usize,
+    ) -> anyhow::Result<Vec<SearchResult>> {
         // Extract result links: <a class="result__a" href="...">Title</a>
         let link_regex = Regex::new(
             r#"<a[^>]*class="[^"]*result__a[^"]*"[^>]*href="([^"]+)"[^>]*>([\s\S]*?)</a>"#,
@@ -59,56 +288,53 @@ impl WebSearchTool {
         // Extract snippets: <a class="result__snippet">...</a>
         let snippet_regex = Regex::new(r#"<a class="result__snippet[^"]*"[^>]*>([\s\S]*?)</a>"#)?;
 
-        let link_matches: Vec<_> = link_regex
-            .captures_iter(html)
-            .take(self.max_results + 2)
-            .collect();
+        let snippet_matches: Vec<_> = snippet_regex.captures_iter(html).take(count).collect();
 
-        let snippet_matches: Vec<_> = snippet_regex
+        Ok(link_regex
             .captures_iter(html)
-            .take(self.max_results + 2)
-            .collect();
+            .take(count)
+            .enumerate()
+            .map(|(i, caps)| SearchResult {
+                id: None,
+                title: strip_tags(&caps[2]).trim().to_string(),
+                url: decode_ddg_redirect_url(&caps[1]).trim().to_string(),
+                snippet: snippet_matches
+                    .get(i)
+                    .map(|snippet| strip_tags(&snippet[1]).trim().to_string())
+                    .unwrap_or_default(),
+                age: None,
+            })
+            .collect())
+    }
 
-        if link_matches.is_empty() {
-            return Ok(format!("No results found for: {}", query));
+    fn brave_search_url(query: &str, options: &SearchOptions) -> String {
+        let mut url = format!(
+            "https://{BRAVE_API_HOST}/res/v1/web/search?q={}&count={}",
+            urlencoding::encode(query),
+            options.count
+        );
+        if let Some(freshness) = options.freshness {
+            url.push_str("&freshness=");
+            url.push_str(freshness.brave_param());
         }
-
-        let mut lines = vec![format!("Search results for: {} (via DuckDuckGo)", query)];
-
-        let count = link_matches.len().min(self.max_results);
-
-        for i in 0..count {
-            let caps = &link_matches[i];
-            let url_str = decode_ddg_redirect_url(&caps[1]);
-            let title = strip_tags(&caps[2]);
-
-            lines.push(format!("{}. {}", i + 1, title.trim()));
-            lines.push(format!("   {}", url_str.trim()));
-
-            // Add snippet if available
-            if i < snippet_matches.len() {
-                let snippet = strip_tags(&snippet_matches[i][1]);
-                let snippet = snippet.trim();
-                if !snippet.is_empty() {
-                    lines.push(format!("   {}", snippet));
-                }
-            }
+        if let Some(country) = &options.country {
+            url.push_str("&country=");
+            url.push_str(country);
         }
-
-        Ok(lines.join("\n"))
+        url
     }
 
-    async fn search_brave(&self, query: &str) -> anyhow::Result<String> {
+    async fn search_brave(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+    ) -> anyhow::Result<Vec<SearchResult>> {
         let api_key = self
             .brave_api_key
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("Brave API key not configured"))?;
 
-        let encoded_query = urlencoding::encode(query);
-        let search_url = format!(
-            "https://api.search.brave.com/res/v1/web/search?q={}&count={}",
-            encoded_query, self.max_results
-        );
+        let search_url = Self::brave_search_url(query, options);
 
         let client = reqwest::Client::builder()
             .timeout(Duration::from_secs(self.timeout_secs))
@@ -121,49 +347,150 @@ impl WebSearchTool {
             .send()
             .await?;
 
+        if let Some(message) = brave_refusal_message(response.status(), response.headers()) {
+            return Err(SearchRefused(message).into());
+        }
         if !response.status().is_success() {
             anyhow::bail!("Brave search failed with status: {}", response.status());
         }
 
         let json: serde_json::Value = response.json().await?;
-        self.parse_brave_results(&json, query)
+        self.parse_brave_results(&json, options.count)
     }
 
-    fn parse_brave_results(&self, json: &serde_json::Value, query: &str) -> anyhow::Result<String> {
-        let results = json
-            .get("web")
-            .and_then(|w| w.get("results"))
+    fn parse_brave_results(
+        &self,
+        json: &serde_json::Value,
+        count: usize,
+    ) -> anyhow::Result<Vec<SearchResult>> {
+        let Some(web) = json.get("web") else {
+            // Brave omits `web` when nothing matched.
+            return if json.get("type").is_some() {
+                Ok(Vec::new())
+            } else {
+                Err(anyhow::anyhow!("Invalid Brave API response"))
+            };
+        };
+        let results = web
+            .get("results")
             .and_then(|r| r.as_array())
             .ok_or_else(|| anyhow::anyhow!("Invalid Brave API response"))?;
 
-        if results.is_empty() {
-            return Ok(format!("No results found for: {}", query));
-        }
+        let text = |result: &serde_json::Value, key: &str| {
+            result
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        Ok(results
+            .iter()
+            .take(count)
+            .map(|result| SearchResult {
+                id: None,
+                title: text(result, "title").unwrap_or_else(|| "No title".to_string()),
+                url: text(result, "url").unwrap_or_default(),
+                snippet: text(result, "description")
+                    .map(|d| strip_tags(&d))
+                    .unwrap_or_default(),
+                age: text(result, "age").or_else(|| text(result, "page_age")),
+            })
+            .collect())
+    }
+}
 
-        let mut lines = vec![format!("Search results for: {} (via Brave)", query)];
-
-        for (i, result) in results.iter().take(self.max_results).enumerate() {
-            let title = result
-                .get("title")
-                .and_then(|t| t.as_str())
-                .unwrap_or("No title");
-            let url = result.get("url").and_then(|u| u.as_str()).unwrap_or("");
-            let description = result
-                .get("description")
-                .and_then(|d| d.as_str())
-                .unwrap_or("");
-
-            lines.push(format!("{}. {}", i + 1, title));
-            lines.push(format!("   {}", url));
-            if !description.is_empty() {
-                lines.push(format!("   {}", description));
+/// Label results with their citation ids in the current turn; outside a
+/// turn they stay unlabelled.
+fn cite_results(results: &mut [SearchResult]) {
+    for result in results.iter_mut().filter(|r| !r.url.is_empty()) {
+        result.id = crate::agent::turn::cite_source(&result.url, &result.title);
+    }
+}
+
+/// JSON output for the model: the query actually sent and the results.
+fn render_results(provider: &str, query: &str, results: &[SearchResult]) -> String {
+    let mut output = json!({
+        "query": query,
+        "provider": provider,
+        "results": results,
+    });
+    if let Some(id) = results.iter().find_map(|r| r.id.as_deref()) {
+        output["citation"] = json!(format!(
+            "When you use a result, cite it by its id, e.g. {}",
+            crate::agent::citations::cite_instruction(id)
+        ));
+    }
+    output.to_string()
+}
+
+/// Readable message for Brave responses the model should hear about
+/// instead of a generic failure: a rejected key or an exhausted quota.
+fn brave_refusal_message(status: StatusCode, headers: &HeaderMap) -> Option<String> {
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Some(format!(
+            "Brave search rejected the API key ({status}); check web_search.brave_api_key in config.toml"
+        )),
+        StatusCode::TOO_MANY_REQUESTS => Some(match brave_quota_reset_secs(headers) {
+            Some(secs) => {
+                let reset_at = chrono::Utc::now()
+                    + chrono::Duration::seconds(i64::try_from(secs).unwrap_or(i64::MAX / 1000));
+                format!(
+                    "Search quota exhausted, try again after {} (in {})",
+                    reset_at.format("%Y-%m-%d %H:%M UTC"),
+                    format_wait(secs)
+                )
             }
-        }
+            None => "Search quota exhausted, try again later".to_string(),
+        }),
+        _ => None,
+    }
+}
 
-        Ok(lines.join("\n"))
+/// Seconds until the exhausted quota window resets. Brave sends one
+/// comma-separated entry per window (per second, per month) in
+/// `X-RateLimit-Remaining` and `X-RateLimit-Reset`; the longest reset among
+/// windows with nothing remaining wins, else the longest reset.
+fn brave_quota_reset_secs(headers: &HeaderMap) -> Option<u64> {
+    let list = |name: &str| -> Vec<Option<u64>> {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.split(',').map(|part| part.trim().parse().ok()).collect())
+            .unwrap_or_default()
+    };
+    let resets = list("x-ratelimit-reset");
+    let remaining = list("x-ratelimit-remaining");
+    let exhausted = resets
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| remaining.get(*i) == Some(&Some(0)))
+        .filter_map(|(_, reset)| *reset)
+        .max();
+    exhausted.or_else(|| resets.iter().flatten().copied().max())
+}
+
+fn format_wait(secs: u64) -> String {
+    match secs {
+        0..=119 => format!("{secs}s"),
+        120..=7_199 => format!("{} min", secs.div_ceil(60)),
+        7_200..=172_799 => format!("{} h", secs.div_ceil(3_600)),
+        _ => format!("{} days", secs.div_ceil(86_400)),
     }
 }
 
+// Independent from web_fetch.rs / http_request.rs per DRY rule-of-three.
+fn host_matches_allowlist(host: &str, allowed_domains: &[String]) -> bool {
+    allowed_domains.iter().any(|domain| {
+        let domain = domain.trim().to_ascii_lowercase();
+        domain == "*"
+            || host == domain
+            || host
+                .strip_suffix(domain.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    })
+}
+
 fn decode_ddg_redirect_url(raw_url: &str) -> String {
     if let Some(index) = raw_url.find("uddg=") {
         let encoded = &raw_url[index + 5..];
@@ -188,7 +515,7 @@ impl Tool for WebSearchTool {
     }
 
     fn description(&self) -> &str {
-        "Search the web for information. Returns relevant search results with titles, URLs, and descriptions. Use this to find current information, news, or research topics."
+        "Search the web for information. Returns JSON search results with title, url, snippet and (when known) age. Use this to find current information, news, or research topics. Optional filters: count, freshness, country, site."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -198,6 +525,25 @@ impl Tool for WebSearchTool {
                 "query": {
                     "type": "string",
                     "description": "The search query. Be specific for better results."
+                },
+                "count": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": 10,
+                    "description": "Number of results to return (1-10). Defaults to the configured max_results."
+                },
+                "freshness": {
+                    "type": "string",
+                    "enum": ["day", "week", "month"],
+                    "description": "Only return results published within this period."
+                },
+                "country": {
+                    "type": "string",
+                    "description": "Two-letter country code to localize results, e.g. US or DE (Brave only)."
+                },
+                "site": {
+                    "type": "string",
+                    "description": "Restrict results to this domain, e.g. docs.rs."
                 }
             },
             "required": ["query"]
@@ -214,23 +560,63 @@ impl Tool for WebSearchTool {
             anyhow::bail!("Search query cannot be empty");
         }
 
+        let options = match SearchOptions::from_args(&args, self.max_results) {
+            Ok(options) => options,
+            Err(message) => return Ok(ToolResult::failure(message)),
+        };
+        let query = options.query(query);
+
+        if let Err(wait) = self.reserve_request() {
+            return Ok(ToolResult::failure(format!(
+                "web search rate limit reached ({} per minute); try again in {}s",
+                self.max_requests_per_minute,
+                wait.as_secs().max(1)
+            )));
+        }
+
         tracing::info!("Searching web for: {}", query);
 
-        let result = match self.provider.as_str() {
-            "duckduckgo" | "ddg" => self.search_duckduckgo(query).await?,
-            "brave" => self.search_brave(query).await?,
+        let (provider, results) = match self.provider.as_str() {
+            "duckduckgo" | "ddg" => ("duckduckgo", self.search_duckduckgo(&query, &options).await),
+            "brave" => ("brave", self.search_brave(&query, &options).await),
             _ => anyhow::bail!(
                 "Unknown search provider: '{}'. Set tools.web_search.provider to 'duckduckgo' or 'brave' in config.toml",
                 self.provider
             ),
         };
 
+        let mut results = match results {
+            Ok(results) => results,
+            Err(error) => match error.downcast::<SearchRefused>() {
+                Ok(SearchRefused(message)) => return Ok(ToolResult::failure(message)),
+                Err(error) => return Err(error),
+            },
+        };
+
+        cite_results(&mut results);
         Ok(ToolResult {
             success: true,
-            output: result,
+            output: render_results(provider, &query, &results),
             error: None,
         })
     }
+
+    async fn execute_with_ctx(
+        &self,
+        args: serde_json::Value,
+        overrides: &SandboxOverrides,
+    ) -> anyhow::Result<ToolResult> {
+        let host = self.api_host();
+        if !overrides.network_domains.is_empty()
+            && !host_matches_allowlist(host, &overrides.network_domains)
+        {
+            return Ok(ToolResult::failure(format!(
+                "Search host '{host}' is not in the network_domains of skill '{}'",
+                overrides.skill
+            )));
+        }
+        self.execute(args).await
+    }
 }
 
 #[cfg(test)]
@@ -266,10 +652,10 @@ mod tests {
     #[test]
     fn test_parse_duckduckgo_results_empty() {
         let tool = WebSearchTool::new("duckduckgo".to_string(), None, 5, 15);
-        let result = tool
-            .parse_duckduckgo_results("<html>No results here</html>", "test")
+        let results = tool
+            .parse_duckduckgo_results("<html>No results here</html>", 5)
             .unwrap();
-        assert!(result.contains("No results found"));
+        assert!(results.is_empty());
     }
 
     #[test]
@@ -279,9 +665,11 @@ mod tests {
             <a class="result__a" href="https://example.com">Example Title</a>
             <a class="result__snippet">This is a description</a>
         "#;
-        let result = tool.parse_duckduckgo_results(html, "test").unwrap();
-        assert!(result.contains("Example Title"));
-        assert!(result.contains("https://example.com"));
+        let results = tool.parse_duckduckgo_results(html, 5).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Example Title");
+        assert_eq!(results[0].url, "https://example.com");
+        assert_eq!(results[0].snippet, "This is a description");
     }
 
     #[test]
@@ -291,9 +679,8 @@ mod tests {
             <a class="result__a" href="https://duckduckgo.com/l/?uddg=https%3A%2F%2Fexample.com%2Fpath%3Fa%3D1&amp;rut=test">Example Title</a>
             <a class="result__snippet">This is a description</a>
         "#;
-        let result = tool.parse_duckduckgo_results(html, "test").unwrap();
-        assert!(result.contains("https://example.com/path?a=1"));
-        assert!(!result.contains("rut=test"));
+        let results = tool.parse_duckduckgo_results(html, 5).unwrap();
+        assert_eq!(results[0].url, "https://example.com/path?a=1");
     }
 
     #[test]
@@ -303,8 +690,11 @@ mod tests {
             <a class="result__a" href="https://example.com">Example Title</a>
             <a class="result__snippet">This is a description</a>
         "#;
-        let result = tool.parse_duckduckgo_results(html, "test").unwrap();
-        assert!(result.contains("Example Title"));
+        let results = tool
+            .parse_duckduckgo_results(html, tool.max_results)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Example Title");
     }
 
     #[tokio::test]
@@ -328,4 +718,165 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("API key"));
     }
+
+    #[test]
+    fn search_options_validate_and_apply_site_filter() {
+        let options = SearchOptions::from_args(
+            &json!({"count": 25, "freshness": "Week", "country": "de", "site": "https://docs.rs/tokio"}),
+            5,
+        )
+        .unwrap();
+        assert_eq!(options.count, 10);
+        assert_eq!(options.freshness, Some(Freshness::Week));
+        assert_eq!(options.country.as_deref(), Some("DE"));
+        assert_eq!(
+            options.query(" spawn_blocking "),
+            "spawn_blocking site:docs.rs"
+        );
+
+        let defaults = SearchOptions::from_args(&json!({}), 5).unwrap();
+        assert_eq!(defaults.count, 5);
+        assert_eq!(defaults.query("rust"), "rust");
+
+        assert!(SearchOptions::from_args(&json!({"freshness": "year"}), 5).is_err());
+        assert!(SearchOptions::from_args(&json!({"country": "USA"}), 5).is_err());
+        assert!(SearchOptions::from_args(&json!({"site": "localhost"}), 5).is_err());
+        assert!(SearchOptions::from_args(&json!({"count": "three"}), 5).is_err());
+    }
+
+    #[test]
+    fn search_urls_carry_filters() {
+        let options =
+            SearchOptions::from_args(&json!({"count": 3, "freshness": "day", "country": "us"}), 5)
+                .unwrap();
+        assert_eq!(
+            WebSearchTool::brave_search_url("rust async", &options),
+            "https://api.search.brave.com/res/v1/web/search?q=rust%20async&count=3&freshness=pd&country=US"
+        );
+        assert_eq!(
+            WebSearchTool::duckduckgo_search_url("rust async", &options),
+            "https://html.duckduckgo.com/html/?q=rust%20async&df=d"
+        );
+    }
+
+    #[test]
+    fn parse_brave_results_includes_age() {
+        let tool = WebSearchTool::new("brave".to_string(), Some("k".into()), 5, 15);
+        let json = json!({
+            "type": "search",
+            "web": {"results": [
+                {"title": "Tokio", "url": "https://tokio.rs", "description": "An <strong>async</strong> runtime", "age": "2 days ago"},
+                {"title": "Docs", "url": "https://docs.rs/tokio", "description": "API docs", "page_age": "2026-01-02T00:00:00"},
+                {"title": "Extra", "url": "https://example.com", "description": ""}
+            ]}
+        });
+        let results = tool.parse_brave_results(&json, 2).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].snippet, "An async runtime");
+        assert_eq!(results[0].age.as_deref(), Some("2 days ago"));
+        assert_eq!(results[1].age.as_deref(), Some("2026-01-02T00:00:00"));
+
+        let rendered: serde_json::Value =
+            serde_json::from_str(&render_results("brave", "tokio", &results)).unwrap();
+        assert_eq!(rendered["results"][0]["url"], "https://tokio.rs");
+        assert!(rendered["results"][0].get("id").is_none());
+        assert!(rendered.get("citation").is_none());
+        assert!(tool
+            .parse_brave_results(&json!({"type": "search"}), 5)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn results_carry_citation_ids_stable_within_a_turn() {
+        use crate::agent::turn::{with_turn, TurnRecorder};
+
+        let tool = WebSearchTool::new("brave".to_string(), Some("k".into()), 5, 15);
+        let first = json!({"web": {"results": [
+            {"title": "Tokio", "url": "https://tokio.rs", "description": "runtime"},
+            {"title": "Docs", "url": "https://docs.rs/tokio", "description": "API docs"}
+        ]}});
+        let second = json!({"web": {"results": [
+            {"title": "Book", "url": "https://rust-lang.org/book", "description": "book"},
+            {"title": "Tokio", "url": "https://tokio.rs", "description": "runtime"}
+        ]}});
+
+        let turn = std::sync::Arc::new(TurnRecorder::new("slack_alice"));
+        let (first, second) = with_turn(turn, async {
+            let render = |json: &serde_json::Value| {
+                let mut results = tool.parse_brave_results(json, 5).unwrap();
+                cite_results(&mut results);
+                serde_json::from_str::<serde_json::Value>(&render_results(
+                    "brave", "tokio", &results,
+                ))
+                .unwrap()
+            };
+            (render(&first), render(&second))
+        })
+        .await;
+
+        assert_eq!(first["results"][0]["id"], "S1");
+        assert_eq!(first["results"][1]["id"], "S2");
+        assert!(first["citation"].as_str().unwrap().contains("cite as [S1]"));
+        assert_eq!(second["results"][0]["id"], "S3");
+        assert_eq!(second["results"][1]["id"], "S1");
+    }
+
+    #[test]
+    fn brave_refusals_are_readable() {
+        let message = brave_refusal_message(StatusCode::UNAUTHORIZED, &HeaderMap::new()).unwrap();
+        assert!(message.contains("web_search.brave_api_key"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-reset", "1, 3600".parse().unwrap());
+        headers.insert("x-ratelimit-remaining", "1, 0".parse().unwrap());
+        assert_eq!(brave_quota_reset_secs(&headers), Some(3600));
+        let message = brave_refusal_message(StatusCode::TOO_MANY_REQUESTS, &headers).unwrap();
+        assert!(message.starts_with("Search quota exhausted, try again after "));
+        assert!(message.contains("(in 60 min)"));
+
+        assert!(
+            brave_refusal_message(StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new())
+                .unwrap()
+                .ends_with("try again later")
+        );
+        assert!(brave_refusal_message(StatusCode::BAD_GATEWAY, &headers).is_none());
+    }
+
+    #[tokio::test]
+    async fn rate_limit_rejects_excess_searches() {
+        let tool = WebSearchTool::new("duckduckgo".to_string(), None, 5, 15)
+            .with_max_requests_per_minute(2);
+        assert!(tool.reserve_request().is_ok());
+        assert!(tool.reserve_request().is_ok());
+
+        let result = tool.execute(json!({"query": "rust"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result
+            .error
+            .unwrap()
+            .contains("rate limit reached (2 per minute)"));
+    }
+
+    #[tokio::test]
+    async fn skill_network_domains_must_cover_search_host() {
+        let tool = WebSearchTool::new("brave".to_string(), Some("k".into()), 5, 15);
+        let overrides = SandboxOverrides {
+            skill: "docs-helper".into(),
+            network_domains: vec!["docs.rs".into()],
+            ..SandboxOverrides::default()
+        };
+        let result = tool
+            .execute_with_ctx(json!({"query": "tokio"}), &overrides)
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains(
+            "'api.search.brave.com' is not in the network_domains of skill 'docs-helper'"
+        ));
+        assert!(host_matches_allowlist(
+            BRAVE_API_HOST,
+            &["search.brave.com".to_string()]
+        ));
+    }
 }
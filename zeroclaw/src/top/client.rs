@@ -0,0 +1,143 @@
+//! Gateway access for `zeroclaw top`.
+//!
+//! The UI only talks to [`GatewayClient`], so state updates and rendering can
+//! be driven by synthetic events in tests.
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use futures_util::stream::SplitSink;
+use futures_util::{SinkExt, StreamExt};
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+type WsWriter = SplitSink<
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>,
+    WsMessage,
+>;
+
+#[async_trait]
+pub trait GatewayClient: Send + Sync {
+    /// `GET /api/channels/health`.
+    async fn channel_health(&self) -> Result<serde_json::Value>;
+    /// `GET /api/monitor/metrics`.
+    async fn metrics(&self) -> Result<serde_json::Value>;
+    /// `GET /api/cost`.
+    async fn cost(&self) -> Result<serde_json::Value>;
+    /// Open `/ws/events`; frames arrive on the returned receiver, which
+    /// closes when the socket does.
+    async fn subscribe(&self) -> Result<mpsc::Receiver<serde_json::Value>>;
+    /// Run a turn for `message` in `session_key` over the event socket.
+    async fn send_message(&self, session_key: &str, message: &str) -> Result<()>;
+}
+
+/// Talks to the gateway on `127.0.0.1` over HTTP and WebSocket.
+pub struct HttpGatewayClient {
+    base: String,
+    token: Option<String>,
+    http: reqwest::Client,
+    writer: Mutex<Option<WsWriter>>,
+}
+
+impl HttpGatewayClient {
+    pub fn new(port: u16, token: Option<String>) -> Self {
+        Self {
+            base: format!("127.0.0.1:{port}"),
+            token: token
+                .map(|token| token.trim().to_string())
+                .filter(|token| !token.is_empty()),
+            http: reqwest::Client::new(),
+            writer: Mutex::new(None),
+        }
+    }
+
+    pub fn address(&self) -> &str {
+        &self.base
+    }
+
+    async fn get(&self, path: &str) -> Result<serde_json::Value> {
+        let mut request = self
+            .http
+            .get(format!("http://{}{path}", self.base))
+            .timeout(REQUEST_TIMEOUT);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await.with_context(|| {
+            format!(
+                "gateway not reachable on {} (start it with `zeroclaw gateway` or `zeroclaw daemon`)",
+                self.base
+            )
+        })?;
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED {
+            bail!("gateway requires a token (pass --token or set ZEROCLAW_GATEWAY_TOKEN)");
+        }
+        if !status.is_success() {
+            bail!("gateway returned {status} for {path}");
+        }
+        Ok(response.json().await?)
+    }
+}
+
+#[async_trait]
+impl GatewayClient for HttpGatewayClient {
+    async fn channel_health(&self) -> Result<serde_json::Value> {
+        self.get("/api/channels/health").await
+    }
+
+    async fn metrics(&self) -> Result<serde_json::Value> {
+        self.get("/api/monitor/metrics").await
+    }
+
+    async fn cost(&self) -> Result<serde_json::Value> {
+        self.get("/api/cost").await
+    }
+
+    async fn subscribe(&self) -> Result<mpsc::Receiver<serde_json::Value>> {
+        let mut url = format!("ws://{}/ws/events", self.base);
+        if let Some(token) = &self.token {
+            url.push_str("?token=");
+            url.push_str(&urlencoding::encode(token));
+        }
+        let (stream, _) = tokio_tungstenite::connect_async(&url)
+            .await
+            .context("could not open the gateway event stream (/ws/events)")?;
+        let (writer, mut reader) = stream.split();
+        *self.writer.lock().await = Some(writer);
+
+        let (tx, rx) = mpsc::channel(256);
+        tokio::spawn(async move {
+            while let Some(Ok(frame)) = reader.next().await {
+                let WsMessage::Text(text) = frame else {
+                    continue;
+                };
+                let Ok(event) = serde_json::from_str::<serde_json::Value>(&text) else {
+                    continue;
+                };
+                if tx.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    async fn send_message(&self, session_key: &str, message: &str) -> Result<()> {
+        let mut writer = self.writer.lock().await;
+        let Some(writer) = writer.as_mut() else {
+            bail!("event stream is not connected");
+        };
+        let frame = serde_json::json!({
+            "type": "send_message",
+            "session_key": session_key,
+            "message": message,
+        });
+        writer
+            .send(WsMessage::Text(frame.to_string().into()))
+            .await
+            .context("failed to send over the event stream")
+    }
+}
@@ -0,0 +1,210 @@
+//! `zeroclaw top` — live terminal view of a running gateway.
+//!
+//! Streams `/ws/events` into a message feed and polls channel health,
+//! queue/tool metrics and cost every few seconds. The gateway is reached on
+//! `127.0.0.1` at the configured port, like `zeroclaw status --live`.
+
+pub mod client;
+pub mod state;
+pub mod ui;
+
+use crate::config::Config;
+use anyhow::Result;
+use client::{GatewayClient, HttpGatewayClient};
+use ratatui::crossterm::event::{self, Event, KeyEvent, KeyEventKind};
+use state::{Action, TopState};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+const KEY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Restores the terminal when `top` exits, including on error. Panics are
+/// covered by the hook `ratatui::init` installs.
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        ratatui::restore();
+    }
+}
+
+pub async fn run(config: &Config, token: Option<String>) -> Result<()> {
+    let client = HttpGatewayClient::new(config.gateway.port, token);
+    let mut state = TopState::new(client.address());
+    // Fail before taking over the terminal so the error stays readable.
+    refresh(&client, &mut state).await?;
+    let mut events = client.subscribe().await?;
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let mut keys = spawn_key_reader(Arc::clone(&stop));
+    let mut terminal = ratatui::init();
+    let _guard = TerminalGuard;
+
+    let mut tick = tokio::time::interval(REFRESH_INTERVAL);
+    tick.tick().await;
+    let result = loop {
+        if let Err(e) = terminal.draw(|frame| ui::draw(frame, &state)) {
+            break Err(e.into());
+        }
+        tokio::select! {
+            event = events.recv() => match event {
+                Some(event) => state.apply_event(&event),
+                None => break Err(anyhow::anyhow!("gateway closed the event stream")),
+            },
+            Some(key) = keys.recv() => {
+                if !perform(&client, &mut state, key).await {
+                    break Ok(());
+                }
+            }
+            _ = tick.tick() => {
+                if let Err(e) = refresh(&client, &mut state).await {
+                    state.notice = format!("refresh failed: {e}");
+                }
+            }
+        }
+    };
+    stop.store(true, Ordering::Relaxed);
+    result
+}
+
+/// Poll channel health, metrics and cost. Only the health call is required;
+/// the others leave their panels as they were when they fail.
+async fn refresh(client: &dyn GatewayClient, state: &mut TopState) -> Result<()> {
+    state.apply_channel_health(&client.channel_health().await?);
+    if let Ok(metrics) = client.metrics().await {
+        state.apply_metrics(&metrics);
+    }
+    if let Ok(cost) = client.cost().await {
+        state.apply_cost(&cost);
+    }
+    Ok(())
+}
+
+/// Handle one key press. Returns `false` when `top` should exit.
+async fn perform(client: &dyn GatewayClient, state: &mut TopState, key: KeyEvent) -> bool {
+    match state.handle_key(key) {
+        Action::None => true,
+        Action::Quit => false,
+        Action::Send {
+            session_key,
+            message,
+        } => {
+            state.notice = match client.send_message(&session_key, &message).await {
+                Ok(()) => format!("sending to {session_key}…"),
+                Err(e) => format!("send failed: {e}"),
+            };
+            true
+        }
+    }
+}
+
+/// Read key presses on a blocking thread until `stop` is set.
+fn spawn_key_reader(stop: Arc<AtomicBool>) -> mpsc::Receiver<KeyEvent> {
+    let (tx, rx) = mpsc::channel(32);
+    std::thread::spawn(move || {
+        while !stop.load(Ordering::Relaxed) {
+            match event::poll(KEY_POLL_INTERVAL) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(_) => break,
+            }
+            match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                    if tx.blocking_send(key).is_err() {
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+    });
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use ratatui::crossterm::event::{KeyCode, KeyModifiers};
+    use serde_json::json;
+
+    #[derive(Default)]
+    struct FakeGateway {
+        health_error: Option<&'static str>,
+        sent: Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl GatewayClient for FakeGateway {
+        async fn channel_health(&self) -> Result<serde_json::Value> {
+            match self.health_error {
+                Some(error) => anyhow::bail!(error),
+                None => Ok(json!({"channels": {"telegram": {"status": "ok"}}})),
+            }
+        }
+
+        async fn metrics(&self) -> Result<serde_json::Value> {
+            Ok(json!({"queue": {"depth": 3}}))
+        }
+
+        async fn cost(&self) -> Result<serde_json::Value> {
+            anyhow::bail!("cost tracking disabled")
+        }
+
+        async fn subscribe(&self) -> Result<mpsc::Receiver<serde_json::Value>> {
+            Ok(mpsc::channel(1).1)
+        }
+
+        async fn send_message(&self, session_key: &str, message: &str) -> Result<()> {
+            self.sent
+                .lock()
+                .push((session_key.to_string(), message.to_string()));
+            Ok(())
+        }
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[tokio::test]
+    async fn refresh_requires_health_but_tolerates_missing_cost() {
+        let gateway = FakeGateway::default();
+        let mut state = TopState::new("gw");
+        refresh(&gateway, &mut state).await.unwrap();
+        assert_eq!(state.channels.len(), 1);
+        assert_eq!(state.metrics.turns_in_flight, 3);
+        assert_eq!(state.metrics.tokens, None);
+
+        let down = FakeGateway {
+            health_error: Some("gateway not reachable"),
+            ..FakeGateway::default()
+        };
+        let err = refresh(&down, &mut state).await.unwrap_err();
+        assert!(err.to_string().contains("not reachable"));
+    }
+
+    #[tokio::test]
+    async fn composed_messages_go_through_the_client() {
+        let gateway = FakeGateway::default();
+        let mut state = TopState::new("gw");
+        state.apply_event(&json!({
+            "type": "message", "direction": "inbound",
+            "session_key": "webchat_7", "content": "hello",
+        }));
+        for code in [KeyCode::Char('m'), KeyCode::Char('o'), KeyCode::Char('k')] {
+            assert!(perform(&gateway, &mut state, key(code)).await);
+        }
+        assert!(perform(&gateway, &mut state, key(KeyCode::Enter)).await);
+        assert_eq!(
+            *gateway.sent.lock(),
+            vec![("webchat_7".to_string(), "ok".to_string())]
+        );
+        assert!(!perform(&gateway, &mut state, key(KeyCode::Char('q'))).await);
+    }
+}
@@ -0,0 +1,432 @@
+//! What `zeroclaw top` shows, updated from gateway events and polls.
+//!
+//! Everything here is plain data so rendering and key handling can be tested
+//! with synthetic events, without a terminal or a gateway.
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::VecDeque;
+
+/// Messages kept in the feed.
+const FEED_CAPACITY: usize = 500;
+/// Characters of a message shown in the feed.
+const CONTENT_PREVIEW_CHARS: usize = 200;
+
+/// One line of the message feed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedEntry {
+    /// Local time the event arrived, `HH:MM:SS`.
+    pub at: String,
+    /// `inbound`, `outbound` or `failed`.
+    pub direction: String,
+    pub session_key: String,
+    pub channel: String,
+    pub content: String,
+}
+
+/// A row of the channel health table (`/api/channels/health`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelRow {
+    pub name: String,
+    pub status: String,
+    pub restart_count: u64,
+    pub queued_outbound: u64,
+    pub last_error: Option<String>,
+}
+
+/// Figures from `/api/monitor/metrics` and `/api/cost`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsView {
+    /// Turns running or waiting in the gateway queue.
+    pub turns_in_flight: u64,
+    pub max_turns: u64,
+    pub turns_rejected: u64,
+    pub tool_calls: u64,
+    pub tool_failures: u64,
+    /// `None` until `/api/cost` answers (no cost tracker: zeros).
+    pub tokens: Option<u64>,
+    pub cost_today_usd: Option<f64>,
+}
+
+impl MetricsView {
+    /// Failed share of tool calls, in percent.
+    pub fn tool_error_rate(&self) -> f64 {
+        if self.tool_calls == 0 {
+            return 0.0;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        let rate = self.tool_failures as f64 / self.tool_calls as f64 * 100.0;
+        rate
+    }
+}
+
+/// What a key press asks the run loop to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    None,
+    Quit,
+    /// Send `message` to `session_key` over the event socket.
+    Send {
+        session_key: String,
+        message: String,
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct TopState {
+    /// `host:port` of the gateway, for the header.
+    pub gateway: String,
+    pub feed: VecDeque<FeedEntry>,
+    /// Events received while paused, shown on resume.
+    held: Vec<FeedEntry>,
+    pub paused: bool,
+    /// Show only this session's messages.
+    pub filter: Option<String>,
+    /// Sessions seen so far, in order of first appearance.
+    pub sessions: Vec<String>,
+    pub selected: usize,
+    pub channels: Vec<ChannelRow>,
+    pub metrics: MetricsView,
+    /// Message being typed for the selected session.
+    pub compose: Option<String>,
+    /// Last notice or error, shown above the key help.
+    pub notice: String,
+}
+
+fn session_channel(session_key: &str) -> &str {
+    session_key
+        .split_once('_')
+        .map_or(session_key, |(channel, _)| channel)
+}
+
+fn u64_at(value: &serde_json::Value, pointer: &str) -> u64 {
+    value
+        .pointer(pointer)
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0)
+}
+
+impl TopState {
+    pub fn new(gateway: impl Into<String>) -> Self {
+        Self {
+            gateway: gateway.into(),
+            notice: "connected".into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn selected_session(&self) -> Option<&str> {
+        self.sessions.get(self.selected).map(String::as_str)
+    }
+
+    /// Feed entries that pass the session filter, oldest first.
+    pub fn visible_feed(&self) -> impl Iterator<Item = &FeedEntry> {
+        self.feed.iter().filter(|entry| {
+            self.filter
+                .as_deref()
+                .is_none_or(|filter| entry.session_key == filter)
+        })
+    }
+
+    pub fn held_count(&self) -> usize {
+        self.held.len()
+    }
+
+    /// Apply one `/ws/events` frame.
+    pub fn apply_event(&mut self, event: &serde_json::Value) {
+        let field = |name: &str| {
+            event
+                .get(name)
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+        match event.get("type").and_then(serde_json::Value::as_str) {
+            Some("message") => {
+                let direction = field("direction");
+                self.push_message(
+                    if direction.is_empty() {
+                        "message".into()
+                    } else {
+                        direction
+                    },
+                    field("session_key"),
+                    &field("content"),
+                );
+            }
+            Some("message_failed") => {
+                self.push_message("failed".into(), field("session_key"), "(turn failed)");
+            }
+            Some("accepted") => {
+                self.notice = format!("sent to {}", field("session_key"));
+            }
+            Some("error") => {
+                let component = field("component");
+                self.notice = if component.is_empty() {
+                    format!("error: {}", field("message"))
+                } else {
+                    format!("error in {component}: {}", field("message"))
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn push_message(&mut self, direction: String, session_key: String, content: &str) {
+        if !session_key.is_empty() && !self.sessions.contains(&session_key) {
+            self.sessions.push(session_key.clone());
+        }
+        let entry = FeedEntry {
+            at: chrono::Local::now().format("%H:%M:%S").to_string(),
+            direction,
+            channel: session_channel(&session_key).to_string(),
+            session_key,
+            content: crate::util::truncate_with_ellipsis(
+                &content.split_whitespace().collect::<Vec<_>>().join(" "),
+                CONTENT_PREVIEW_CHARS,
+            ),
+        };
+        if self.paused {
+            self.held.push(entry);
+        } else {
+            self.push_feed(entry);
+        }
+    }
+
+    fn push_feed(&mut self, entry: FeedEntry) {
+        if self.feed.len() == FEED_CAPACITY {
+            self.feed.pop_front();
+        }
+        self.feed.push_back(entry);
+    }
+
+    /// Replace the channel table with a `/api/channels/health` response.
+    pub fn apply_channel_health(&mut self, health: &serde_json::Value) {
+        let Some(channels) = health.get("channels").and_then(|c| c.as_object()) else {
+            return;
+        };
+        self.channels = channels
+            .iter()
+            .map(|(name, row)| ChannelRow {
+                name: name.clone(),
+                status: row
+                    .get("status")
+                    .and_then(serde_json::Value::as_str)
+                    .unwrap_or("unknown")
+                    .to_string(),
+                restart_count: u64_at(row, "/restart_count"),
+                queued_outbound: u64_at(row, "/queued_outbound"),
+                last_error: row
+                    .get("last_error")
+                    .and_then(serde_json::Value::as_str)
+                    .map(String::from),
+            })
+            .collect();
+    }
+
+    /// Update queue and tool figures from `/api/monitor/metrics`.
+    pub fn apply_metrics(&mut self, metrics: &serde_json::Value) {
+        self.metrics.turns_in_flight = u64_at(metrics, "/queue/depth");
+        self.metrics.max_turns = u64_at(metrics, "/queue/max_depth");
+        self.metrics.turns_rejected = u64_at(metrics, "/queue/rejected");
+        let tools = metrics.get("tools").and_then(|t| t.as_object());
+        self.metrics.tool_calls = tools.map_or(0, |tools| {
+            tools
+                .values()
+                .map(|tool| u64_at(tool, "/execution_count"))
+                .sum()
+        });
+        self.metrics.tool_failures = tools.map_or(0, |tools| {
+            tools
+                .values()
+                .map(|tool| u64_at(tool, "/failure_count"))
+                .sum()
+        });
+    }
+
+    /// Update token and spend figures from `/api/cost`.
+    pub fn apply_cost(&mut self, cost: &serde_json::Value) {
+        self.metrics.tokens = cost
+            .pointer("/cost/total_tokens")
+            .and_then(serde_json::Value::as_u64);
+        self.metrics.cost_today_usd = cost
+            .pointer("/cost/daily_cost_usd")
+            .and_then(serde_json::Value::as_f64);
+    }
+
+    /// React to a key press.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+        if let Some(draft) = self.compose.as_mut() {
+            match key.code {
+                KeyCode::Esc => {
+                    self.compose = None;
+                    self.notice = "message discarded".into();
+                }
+                KeyCode::Backspace => {
+                    draft.pop();
+                }
+                KeyCode::Char(c) => draft.push(c),
+                KeyCode::Enter => {
+                    let message = self.compose.take().unwrap_or_default();
+                    match self.selected_session() {
+                        Some(session_key) if !message.trim().is_empty() => {
+                            return Action::Send {
+                                session_key: session_key.to_string(),
+                                message,
+                            };
+                        }
+                        _ => self.notice = "nothing sent".into(),
+                    }
+                }
+                _ => {}
+            }
+            return Action::None;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Char('p' | ' ') => {
+                self.paused = !self.paused;
+                if !self.paused {
+                    for entry in std::mem::take(&mut self.held) {
+                        self.push_feed(entry);
+                    }
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.sessions.len().saturating_sub(1));
+            }
+            KeyCode::Char('f') => {
+                let selected = self.selected_session().map(String::from);
+                self.filter = if self.filter == selected {
+                    None
+                } else {
+                    selected
+                };
+            }
+            KeyCode::Char('m') | KeyCode::Enter => {
+                if self.selected_session().is_some() {
+                    self.compose = Some(String::new());
+                } else {
+                    self.notice = "no session selected yet".into();
+                }
+            }
+            _ => {}
+        }
+        Action::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn message(direction: &str, session_key: &str, content: &str) -> serde_json::Value {
+        json!({
+            "type": "message",
+            "direction": direction,
+            "session_key": session_key,
+            "content": content,
+        })
+    }
+
+    #[test]
+    fn message_events_fill_the_feed_and_session_list() {
+        let mut state = TopState::new("127.0.0.1:42617");
+        state.apply_event(&message("inbound", "telegram_alice", "Hi\nthere"));
+        state.apply_event(&message("outbound", "telegram_alice", "Hello!"));
+        state.apply_event(&json!({"type": "message_failed", "session_key": "slack_bob"}));
+        state.apply_event(&json!({"type": "tool_call", "tool": "shell"}));
+
+        assert_eq!(state.feed.len(), 3);
+        assert_eq!(state.feed[0].content, "Hi there");
+        assert_eq!(state.feed[0].channel, "telegram");
+        assert_eq!(state.feed[2].direction, "failed");
+        assert_eq!(state.sessions, vec!["telegram_alice", "slack_bob"]);
+
+        state.apply_event(&json!({"type": "error", "component": "provider", "message": "boom"}));
+        assert_eq!(state.notice, "error in provider: boom");
+    }
+
+    #[test]
+    fn pause_holds_events_until_resumed_and_filter_narrows_the_feed() {
+        let mut state = TopState::new("gw");
+        state.apply_event(&message("inbound", "telegram_alice", "one"));
+        state.handle_key(key(KeyCode::Char('p')));
+        state.apply_event(&message("inbound", "slack_bob", "two"));
+        assert_eq!(state.feed.len(), 1);
+        assert_eq!(state.held_count(), 1);
+        state.handle_key(key(KeyCode::Char('p')));
+        assert_eq!(state.feed.len(), 2);
+        assert_eq!(state.held_count(), 0);
+
+        state.handle_key(key(KeyCode::Down));
+        assert_eq!(state.selected_session(), Some("slack_bob"));
+        state.handle_key(key(KeyCode::Char('f')));
+        let visible: Vec<_> = state.visible_feed().map(|e| e.content.as_str()).collect();
+        assert_eq!(visible, vec!["two"]);
+        state.handle_key(key(KeyCode::Char('f')));
+        assert_eq!(state.visible_feed().count(), 2);
+    }
+
+    #[test]
+    fn compose_sends_to_the_selected_session() {
+        let mut state = TopState::new("gw");
+        assert_eq!(state.handle_key(key(KeyCode::Char('m'))), Action::None);
+        assert!(state.compose.is_none());
+
+        state.apply_event(&message("inbound", "webchat_1", "hey"));
+        state.handle_key(key(KeyCode::Char('m')));
+        for c in "hi!".chars() {
+            state.handle_key(key(KeyCode::Char(c)));
+        }
+        state.handle_key(key(KeyCode::Backspace));
+        assert_eq!(
+            state.handle_key(key(KeyCode::Enter)),
+            Action::Send {
+                session_key: "webchat_1".into(),
+                message: "hi".into(),
+            }
+        );
+        assert!(state.compose.is_none());
+        assert_eq!(state.handle_key(key(KeyCode::Char('q'))), Action::Quit);
+    }
+
+    #[test]
+    fn polled_health_metrics_and_cost_update_the_panels() {
+        let mut state = TopState::new("gw");
+        state.apply_channel_health(&json!({
+            "channels": {
+                "telegram": {"status": "ok", "restart_count": 1, "queued_outbound": 0},
+                "slack": {"status": "error", "last_error": "auth", "queued_outbound": 3}
+            }
+        }));
+        state.apply_metrics(&json!({
+            "queue": {"depth": 2, "max_depth": 8, "rejected": 1},
+            "tools": {
+                "shell": {"execution_count": 3, "failure_count": 1},
+                "file_read": {"execution_count": 1, "failure_count": 0}
+            }
+        }));
+        state.apply_cost(&json!({"cost": {"total_tokens": 1200, "daily_cost_usd": 0.42}}));
+
+        assert_eq!(state.channels.len(), 2);
+        let slack = state.channels.iter().find(|c| c.name == "slack").unwrap();
+        assert_eq!(slack.last_error.as_deref(), Some("auth"));
+        assert_eq!(slack.queued_outbound, 3);
+        assert_eq!(state.metrics.turns_in_flight, 2);
+        assert_eq!(state.metrics.tool_calls, 4);
+        assert!((state.metrics.tool_error_rate() - 25.0).abs() < f64::EPSILON);
+        assert_eq!(state.metrics.tokens, Some(1200));
+    }
+}
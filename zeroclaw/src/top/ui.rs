@@ -0,0 +1,260 @@
+//! Layout and drawing for `zeroclaw top`.
+
+use super::state::TopState;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Row, Table};
+use ratatui::Frame;
+
+const HELP: &str = "q quit · p pause · j/k select session · f filter · m message";
+
+pub fn draw(frame: &mut Frame, state: &TopState) {
+    let [header, body, footer] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(5),
+            Constraint::Length(2),
+        ])
+        .areas(frame.area());
+    let [feed, side] = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(62), Constraint::Percentage(38)])
+        .areas(body);
+    let [channels, metrics, sessions] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(40),
+            Constraint::Length(7),
+            Constraint::Min(3),
+        ])
+        .areas(side);
+
+    draw_header(frame, header, state);
+    draw_feed(frame, feed, state);
+    draw_channels(frame, channels, state);
+    draw_metrics(frame, metrics, state);
+    draw_sessions(frame, sessions, state);
+    draw_footer(frame, footer, state);
+}
+
+fn draw_header(frame: &mut Frame, area: Rect, state: &TopState) {
+    let mut spans = vec![
+        Span::styled(
+            "zeroclaw top",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!("  {}", state.gateway)),
+    ];
+    if state.paused {
+        spans.push(Span::styled(
+            format!("  PAUSED ({} held)", state.held_count()),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    if let Some(filter) = &state.filter {
+        spans.push(Span::styled(
+            format!("  filter: {filter}"),
+            Style::default().fg(Color::Cyan),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+fn direction_style(direction: &str) -> Style {
+    match direction {
+        "inbound" => Style::default().fg(Color::Green),
+        "outbound" => Style::default().fg(Color::Blue),
+        "failed" => Style::default().fg(Color::Red),
+        _ => Style::default(),
+    }
+}
+
+fn draw_feed(frame: &mut Frame, area: Rect, state: &TopState) {
+    let entries: Vec<_> = state.visible_feed().collect();
+    // Newest at the bottom; keep only what fits.
+    let rows = usize::from(area.height.saturating_sub(2));
+    let items: Vec<ListItem> = entries[entries.len().saturating_sub(rows)..]
+        .iter()
+        .map(|entry| {
+            let arrow = match entry.direction.as_str() {
+                "inbound" => "→",
+                "outbound" => "←",
+                _ => "✗",
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} ", entry.at),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!("{arrow} {} ", entry.session_key),
+                    direction_style(&entry.direction),
+                ),
+                Span::raw(entry.content.clone()),
+            ]))
+        })
+        .collect();
+    let title = format!(" Messages ({}) ", entries.len());
+    frame.render_widget(
+        List::new(items).block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
+}
+
+fn draw_channels(frame: &mut Frame, area: Rect, state: &TopState) {
+    let rows = state.channels.iter().map(|channel| {
+        let style = match channel.status.as_str() {
+            "ok" => Style::default().fg(Color::Green),
+            "starting" => Style::default().fg(Color::Yellow),
+            _ => Style::default().fg(Color::Red),
+        };
+        Row::new(vec![
+            channel.name.clone(),
+            channel.status.clone(),
+            channel.restart_count.to_string(),
+            channel.queued_outbound.to_string(),
+        ])
+        .style(style)
+    });
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Min(10),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Length(7),
+        ],
+    )
+    .header(
+        Row::new(vec!["channel", "status", "restarts", "queued"])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::default().borders(Borders::ALL).title(" Channels "));
+    frame.render_widget(table, area);
+}
+
+fn draw_metrics(frame: &mut Frame, area: Rect, state: &TopState) {
+    let metrics = &state.metrics;
+    let tokens = metrics
+        .tokens
+        .map_or_else(|| "n/a".into(), |t| t.to_string());
+    let cost = metrics
+        .cost_today_usd
+        .map_or_else(|| "n/a".into(), |c| format!("${c:.2}"));
+    let lines = vec![
+        Line::from(format!(
+            "turns in flight: {}/{}  rejected: {}",
+            metrics.turns_in_flight, metrics.max_turns, metrics.turns_rejected
+        )),
+        Line::from(format!("tokens: {tokens}  today: {cost}")),
+        Line::from(format!(
+            "tool calls: {}  errors: {} ({:.1}%)",
+            metrics.tool_calls,
+            metrics.tool_failures,
+            metrics.tool_error_rate()
+        )),
+    ];
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" Metrics ")),
+        area,
+    );
+}
+
+fn draw_sessions(frame: &mut Frame, area: Rect, state: &TopState) {
+    let items: Vec<ListItem> = state
+        .sessions
+        .iter()
+        .map(|session| ListItem::new(session.as_str()))
+        .collect();
+    let mut list_state = ListState::default();
+    if !state.sessions.is_empty() {
+        list_state.select(Some(state.selected));
+    }
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(" Sessions "))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("> "),
+        area,
+        &mut list_state,
+    );
+}
+
+fn draw_footer(frame: &mut Frame, area: Rect, state: &TopState) {
+    let lines = match (&state.compose, state.selected_session()) {
+        (Some(draft), Some(session)) => vec![
+            Line::from(format!("to {session}: {draft}_")),
+            Line::from("Enter send · Esc cancel"),
+        ],
+        _ => vec![Line::from(state.notice.as_str()), Line::from(HELP)],
+    };
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::Terminal;
+    use serde_json::json;
+
+    fn render(state: &TopState) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|frame| draw(frame, state)).unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .chunks(120)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn renders_messages_channels_and_metrics() {
+        let mut state = TopState::new("127.0.0.1:42617");
+        state.apply_event(&json!({
+            "type": "message",
+            "direction": "inbound",
+            "session_key": "telegram_alice",
+            "content": "what's the weather",
+        }));
+        state.apply_channel_health(&json!({
+            "channels": {"telegram": {"status": "ok", "restart_count": 2}}
+        }));
+        state.apply_metrics(&json!({
+            "queue": {"depth": 1, "max_depth": 4},
+            "tools": {"shell": {"execution_count": 2, "failure_count": 1}}
+        }));
+
+        let screen = render(&state);
+        assert!(screen.contains("127.0.0.1:42617"));
+        assert!(screen.contains("→ telegram_alice what's the weather"));
+        assert!(screen.contains("telegram"));
+        assert!(screen.contains("turns in flight: 1/4"));
+        assert!(screen.contains("errors: 1 (50.0%)"));
+        assert!(screen.contains("tokens: n/a"));
+        assert!(screen.contains("> telegram_alice"));
+    }
+
+    #[test]
+    fn renders_pause_filter_and_compose_state() {
+        let mut state = TopState::new("gw");
+        state.apply_event(&json!({
+            "type": "message", "direction": "inbound",
+            "session_key": "slack_bob", "content": "ping",
+        }));
+        for key in ['p', 'f', 'm', 'y', 'o'] {
+            state.handle_key(KeyEvent::new(KeyCode::Char(key), KeyModifiers::NONE));
+        }
+
+        let screen = render(&state);
+        assert!(screen.contains("PAUSED (0 held)"));
+        assert!(screen.contains("filter: slack_bob"));
+        assert!(screen.contains("to slack_bob: yo_"));
+    }
+}
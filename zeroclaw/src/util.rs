@@ -43,6 +43,56 @@ pub fn truncate_with_ellipsis(s: &str, max_chars: usize) -> String {
     }
 }
 
+/// The longest prefix of `s` that fits in `max_bytes` without splitting a
+/// character.
+pub fn truncate_at_char_boundary(s: &str, max_bytes: usize) -> &str {
+    if s.len() <= max_bytes {
+        return s;
+    }
+    let mut end = max_bytes;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+/// Lines that differ between `current` and `new`, after their common first
+/// and last lines, as `-`/`+` lines; at most `max_lines` of them.
+pub fn diff_preview(current: Option<&str>, new: &str, max_lines: usize) -> String {
+    let old: Vec<&str> = current
+        .map(|text| text.lines().collect())
+        .unwrap_or_default();
+    let new: Vec<&str> = new.lines().collect();
+    let common_prefix = old
+        .iter()
+        .zip(&new)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let common_suffix = old[common_prefix..]
+        .iter()
+        .rev()
+        .zip(new[common_prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+
+    let removed = &old[common_prefix..old.len() - common_suffix];
+    let added = &new[common_prefix..new.len() - common_suffix];
+    let mut lines: Vec<String> = removed
+        .iter()
+        .map(|line| format!("- {line}"))
+        .chain(added.iter().map(|line| format!("+ {line}")))
+        .collect();
+    if lines.is_empty() {
+        return "(no changes)".into();
+    }
+    let hidden = lines.len().saturating_sub(max_lines);
+    lines.truncate(max_lines);
+    if hidden > 0 {
+        lines.push(format!("... {hidden} more changed line(s)"));
+    }
+    lines.join("\n")
+}
+
 /// Utility enum for handling optional values.
 pub enum MaybeSet<T> {
     Set(T),
@@ -137,6 +187,16 @@ mod tests {
         assert!(result.ends_with("..."));
     }
 
+    #[test]
+    fn test_truncate_at_char_boundary() {
+        // "é" is 2 bytes, "🦀" is 4.
+        assert_eq!(truncate_at_char_boundary("éééé", 5), "éé");
+        assert_eq!(truncate_at_char_boundary("a🦀b", 4), "a");
+        assert_eq!(truncate_at_char_boundary("a🦀b", 5), "a🦀");
+        assert_eq!(truncate_at_char_boundary("short", 16), "short");
+        assert_eq!(truncate_at_char_boundary("🦀", 0), "");
+    }
+
     #[test]
     fn test_truncate_zero_max_chars() {
         // Edge case: max_chars = 0
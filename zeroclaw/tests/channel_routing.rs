@@ -8,6 +8,7 @@
 //! Verifies sender/reply_target field contracts to prevent field swaps.
 
 use async_trait::async_trait;
+use std::collections::HashMap;
 use zeroclaw::channels::traits::{Channel, ChannelMessage, SendMessage};
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -25,6 +26,8 @@ fn channel_message_sender_field_holds_platform_user_id() {
         channel: "telegram".into(),
         timestamp: 1700000000,
         thread_ts: None,
+        sender_name: None,
+        metadata: HashMap::new(),
     };
 
     assert_eq!(msg.sender, "123456789");
@@ -47,6 +50,8 @@ fn channel_message_reply_target_distinct_from_sender() {
         channel: "discord".into(),
         timestamp: 1700000000,
         thread_ts: None,
+        sender_name: None,
+        metadata: HashMap::new(),
     };
 
     assert_ne!(
@@ -67,6 +72,8 @@ fn channel_message_fields_not_swapped() {
         channel: "test".into(),
         timestamp: 1700000000,
         thread_ts: None,
+        sender_name: None,
+        metadata: HashMap::new(),
     };
 
     assert_eq!(
@@ -93,6 +100,8 @@ fn channel_message_preserves_all_fields_on_clone() {
         channel: "test_channel".into(),
         timestamp: 1700000001,
         thread_ts: None,
+        sender_name: None,
+        metadata: HashMap::new(),
     };
 
     let cloned = original.clone();
@@ -186,6 +195,8 @@ impl Channel for CapturingChannel {
             channel: "capturing".into(),
             timestamp: 1700000000,
             thread_ts: None,
+            sender_name: None,
+            metadata: HashMap::new(),
         })
         .await
         .map_err(|e| anyhow::anyhow!(e.to_string()))
@@ -0,0 +1,196 @@
+//! Integration tests for inbound WhatsApp media handling.
+//!
+//! Each fixture is a Cloud API webhook payload for one message type. Media is
+//! served by a mock Graph API: `GET /{media_id}` returns a download URL on the
+//! same server, which must be fetched with the bearer token.
+
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use zeroclaw::channels::WhatsAppChannel;
+
+const TOKEN: &str = "test-access-token";
+
+fn fixture(name: &str) -> serde_json::Value {
+    let path = format!(
+        "{}/tests/fixtures/whatsapp_{name}_message.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn channel(server: &MockServer, workspace: &std::path::Path) -> WhatsAppChannel {
+    WhatsAppChannel::new(
+        TOKEN.into(),
+        "106540352242922".into(),
+        "verify".into(),
+        vec!["+16505551234".into()],
+    )
+    .with_api_base(server.uri())
+    .with_workspace_dir(workspace.to_path_buf())
+}
+
+/// Mount the two-step Graph API media download for `media_id`.
+async fn mock_media(server: &MockServer, media_id: &str, mime_type: &str, body: &[u8]) {
+    Mock::given(method("GET"))
+        .and(path(format!("/{media_id}")))
+        .and(header("authorization", format!("Bearer {TOKEN}").as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "messaging_product": "whatsapp",
+            "url": format!("{}/download/{media_id}", server.uri()),
+            "mime_type": mime_type,
+            "file_size": body.len(),
+            "id": media_id
+        })))
+        .expect(1)
+        .mount(server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/download/{media_id}")))
+        .and(header("authorization", format!("Bearer {TOKEN}").as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(body.to_vec()))
+        .expect(1)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn image_is_downloaded_and_referenced_with_caption() {
+    let server = MockServer::start().await;
+    let workspace = tempfile::tempdir().unwrap();
+    mock_media(&server, "1003383421387256", "image/jpeg", b"\xff\xd8jpeg").await;
+
+    let msgs = channel(&server, workspace.path())
+        .receive_webhook(&fixture("image"))
+        .await
+        .messages;
+
+    assert_eq!(msgs.len(), 1);
+    let saved = workspace
+        .path()
+        .join("whatsapp_files/whatsapp_1003383421387256.jpg");
+    assert_eq!(std::fs::read(&saved).unwrap(), b"\xff\xd8jpeg");
+    assert_eq!(
+        msgs[0].content,
+        format!("[IMAGE:{}]\n\nReceipt from lunch", saved.display())
+    );
+    assert_eq!(msgs[0].sender, "+16505551234");
+}
+
+#[tokio::test]
+async fn audio_without_transcription_is_saved() {
+    let server = MockServer::start().await;
+    let workspace = tempfile::tempdir().unwrap();
+    mock_media(&server, "1181934473610829", "audio/mpeg", b"ID3audio").await;
+
+    let msgs = channel(&server, workspace.path())
+        .receive_webhook(&fixture("audio"))
+        .await
+        .messages;
+
+    let saved = workspace
+        .path()
+        .join("whatsapp_files/whatsapp_1181934473610829.mp3");
+    assert!(saved.exists());
+    assert_eq!(msgs[0].content, format!("[Audio] {}", saved.display()));
+}
+
+#[tokio::test]
+async fn voice_note_is_saved_as_ogg() {
+    let server = MockServer::start().await;
+    let workspace = tempfile::tempdir().unwrap();
+    mock_media(
+        &server,
+        "1225467302466452",
+        "audio/ogg; codecs=opus",
+        b"OggS",
+    )
+    .await;
+
+    let msgs = channel(&server, workspace.path())
+        .receive_webhook(&fixture("voice"))
+        .await
+        .messages;
+
+    let saved = workspace
+        .path()
+        .join("whatsapp_files/whatsapp_1225467302466452.ogg");
+    assert_eq!(msgs[0].content, format!("[Audio] {}", saved.display()));
+}
+
+#[tokio::test]
+async fn document_keeps_original_filename_in_content() {
+    let server = MockServer::start().await;
+    let workspace = tempfile::tempdir().unwrap();
+    mock_media(&server, "1376223850470843", "application/pdf", b"%PDF-1.4").await;
+
+    let msgs = channel(&server, workspace.path())
+        .receive_webhook(&fixture("document"))
+        .await
+        .messages;
+
+    let saved = workspace
+        .path()
+        .join("whatsapp_files/whatsapp_1376223850470843.pdf");
+    assert_eq!(
+        msgs[0].content,
+        format!("[Document: report.pdf] {}\n\nQ3 numbers", saved.display())
+    );
+}
+
+#[tokio::test]
+async fn location_becomes_text_without_downloads() {
+    let server = MockServer::start().await;
+    let workspace = tempfile::tempdir().unwrap();
+
+    let msgs = channel(&server, workspace.path())
+        .receive_webhook(&fixture("location"))
+        .await
+        .messages;
+
+    assert_eq!(
+        msgs[0].content,
+        "shared location: 37.483307,-122.148981 \
+         (Meta Headquarters, 1 Hacker Way, Menlo Park, CA 94025)"
+    );
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn failed_download_keeps_caption() {
+    let server = MockServer::start().await;
+    let workspace = tempfile::tempdir().unwrap();
+    Mock::given(method("GET"))
+        .and(path("/1003383421387256"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let msgs = channel(&server, workspace.path())
+        .receive_webhook(&fixture("image"))
+        .await
+        .messages;
+
+    assert_eq!(msgs.len(), 1);
+    assert_eq!(msgs[0].content, "Receipt from lunch");
+}
+
+#[tokio::test]
+async fn mismatched_mime_type_is_rejected() {
+    let server = MockServer::start().await;
+    let workspace = tempfile::tempdir().unwrap();
+    mock_media(
+        &server,
+        "1003383421387256",
+        "application/x-msdownload",
+        b"MZ",
+    )
+    .await;
+
+    let msgs = channel(&server, workspace.path())
+        .receive_webhook(&fixture("image"))
+        .await
+        .messages;
+
+    assert_eq!(msgs[0].content, "Receipt from lunch");
+    assert!(!workspace.path().join("whatsapp_files").exists());
+}